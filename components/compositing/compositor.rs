@@ -43,9 +43,10 @@ use webrender_api::units::{
 use webrender_api::{
     self, BuiltDisplayList, DirtyRect, DisplayListPayload, DocumentId, Epoch as WebRenderEpoch,
     ExternalScrollId, FontInstanceFlags, FontInstanceKey, FontInstanceOptions, FontKey,
-    HitTestFlags, PipelineId as WebRenderPipelineId, PropertyBinding, ReferenceFrameKind,
-    RenderReasons, SampledScrollOffset, ScrollLocation, SpaceAndClipInfo, SpatialId,
-    SpatialTreeItemKey, TransformStyle,
+    HitTestFlags, ImageData, ImageDescriptor, ImageDescriptorFlags, ImageFormat, ImageKey,
+    PipelineId as WebRenderPipelineId, PropertyBinding, ReferenceFrameKind, RenderReasons,
+    SampledScrollOffset, ScrollLocation, SpaceAndClipInfo, SpatialId, SpatialTreeItemKey,
+    TransformStyle,
 };
 
 use crate::InitialCompositorState;
@@ -170,21 +171,6 @@ bitflags! {
     }
 }
 
-/// The paint status of a particular pipeline in the Servo renderer. This is used to trigger metrics
-/// in script (via the constellation) when display lists are received.
-///
-/// See <https://w3c.github.io/paint-timing/#first-contentful-paint>.
-#[derive(PartialEq)]
-pub(crate) enum PaintMetricState {
-    /// The renderer is still waiting to process a display list which triggers this metric.
-    Waiting,
-    /// The renderer has processed the display list which will trigger this event, marked the Servo
-    /// instance ready to paint, and is waiting for the given epoch to actually be rendered.
-    Seen(WebRenderEpoch, bool /* first_reflow */),
-    /// The metric has been sent to the constellation and no more work needs to be done.
-    Sent,
-}
-
 pub(crate) struct PipelineDetails {
     /// The pipeline associated with this PipelineDetails object.
     pub pipeline: Option<CompositionPipeline>,
@@ -205,11 +191,8 @@ pub(crate) struct PipelineDetails {
     /// nodes in the compositor before forwarding new offsets to WebRender.
     pub scroll_tree: ScrollTree,
 
-    /// The paint metric status of the first paint.
-    pub first_paint_metric: PaintMetricState,
-
-    /// The paint metric status of the first contentful paint.
-    pub first_contentful_paint_metric: PaintMetricState,
+    /// The epochs of display lists whose presentation script is waiting for.
+    pub pending_presentation_feedback: Vec<WebRenderEpoch>,
 
     /// The CSS pixel to device pixel scale of the viewport of this pipeline, including
     /// page zoom, but not including any pinch zoom amount. This is used to detect
@@ -241,8 +224,7 @@ impl PipelineDetails {
             animation_callbacks_running: false,
             throttled: false,
             scroll_tree: ScrollTree::default(),
-            first_paint_metric: PaintMetricState::Waiting,
-            first_contentful_paint_metric: PaintMetricState::Waiting,
+            pending_presentation_feedback: Vec::new(),
             exited: PipelineExitSource::empty(),
         }
     }
@@ -659,18 +641,6 @@ impl IOCompositor {
                 details.viewport_scale =
                     Some(display_list_info.viewport_details.hidpi_scale_factor);
 
-                let epoch = display_list_info.epoch;
-                let first_reflow = display_list_info.first_reflow;
-                if details.first_paint_metric == PaintMetricState::Waiting {
-                    details.first_paint_metric = PaintMetricState::Seen(epoch, first_reflow);
-                }
-                if details.first_contentful_paint_metric == PaintMetricState::Waiting &&
-                    display_list_info.is_contentful
-                {
-                    details.first_contentful_paint_metric =
-                        PaintMetricState::Seen(epoch, first_reflow);
-                }
-
                 let mut transaction = Transaction::new();
 
                 let is_root_pipeline =
@@ -704,6 +674,10 @@ impl IOCompositor {
                     warn!("Sending Image Keys to Constellation failed with({error:?}).");
                 }
             },
+
+            CompositorMsg::CaptureViewTransitionSnapshot(webview_id, pipeline_id, sender) => {
+                let _ = sender.send(self.capture_view_transition_snapshot(webview_id, pipeline_id));
+            },
             CompositorMsg::UpdateImages(updates) => {
                 let mut txn = Transaction::new();
                 for update in updates {
@@ -770,6 +744,15 @@ impl IOCompositor {
                     webview.set_viewport_description(viewport_description);
                 }
             },
+
+            CompositorMsg::RequestPresentationFeedback(webview_id, pipeline_id, epoch) => {
+                if let Some(webview_renderer) = self.webview_renderers.get_mut(webview_id) {
+                    webview_renderer
+                        .ensure_pipeline_details(pipeline_id)
+                        .pending_presentation_feedback
+                        .push(epoch.into());
+                }
+            },
         }
     }
 
@@ -796,6 +779,9 @@ impl IOCompositor {
             CompositorMsg::GenerateImageKey(sender) => {
                 let _ = sender.send(self.global.borrow().webrender_api.generate_image_key());
             },
+            CompositorMsg::CaptureViewTransitionSnapshot(_, _, sender) => {
+                let _ = sender.send(None);
+            },
             CompositorMsg::GenerateFontKeys(
                 number_of_font_keys,
                 number_of_font_instance_keys,
@@ -985,6 +971,7 @@ impl IOCompositor {
         hide_others: bool,
     ) -> Result<(), UnknownWebView> {
         debug!("{webview_id}: Showing webview; hide_others={hide_others}");
+        let previously_painted = self.painted_webview_ids();
         let painting_order_changed = if hide_others {
             let result = self
                 .webview_renderers
@@ -999,14 +986,17 @@ impl IOCompositor {
         };
         if painting_order_changed {
             self.send_root_pipeline_display_list();
+            self.update_webview_throttling(previously_painted);
         }
         Ok(())
     }
 
     pub fn hide_webview(&mut self, webview_id: WebViewId) -> Result<(), UnknownWebView> {
         debug!("{webview_id}: Hiding webview");
+        let previously_painted = self.painted_webview_ids();
         if self.webview_renderers.hide(webview_id)? {
             self.send_root_pipeline_display_list();
+            self.update_webview_throttling(previously_painted);
         }
         Ok(())
     }
@@ -1017,6 +1007,7 @@ impl IOCompositor {
         hide_others: bool,
     ) -> Result<(), UnknownWebView> {
         debug!("{webview_id}: Raising webview to top; hide_others={hide_others}");
+        let previously_painted = self.painted_webview_ids();
         let painting_order_changed = if hide_others {
             let result = self
                 .webview_renderers
@@ -1031,10 +1022,39 @@ impl IOCompositor {
         };
         if painting_order_changed {
             self.send_root_pipeline_display_list();
+            self.update_webview_throttling(previously_painted);
         }
         Ok(())
     }
 
+    fn painted_webview_ids(&self) -> Vec<WebViewId> {
+        self.webview_renderers
+            .painting_order()
+            .map(|(&webview_id, _)| webview_id)
+            .collect()
+    }
+
+    /// Tell the constellation which webviews are no longer painted and which ones are painted
+    /// again, so that background webviews use less resources.
+    fn update_webview_throttling(&self, previously_painted: Vec<WebViewId>) {
+        let painted = self.painted_webview_ids();
+        let hidden = previously_painted
+            .iter()
+            .filter(|webview_id| !painted.contains(webview_id))
+            .map(|webview_id| (*webview_id, true));
+        let shown = painted
+            .iter()
+            .filter(|webview_id| !previously_painted.contains(webview_id))
+            .map(|webview_id| (*webview_id, false));
+        for (webview_id, hidden) in hidden.chain(shown) {
+            if let Err(error) = self.global.borrow().constellation_sender.send(
+                EmbedderToConstellationMessage::SetWebViewHidden(webview_id, hidden),
+            ) {
+                warn!("Sending event to constellation failed ({:?}).", error);
+            }
+        }
+    }
+
     pub fn move_resize_webview(&mut self, webview_id: WebViewId, rect: DeviceRect) {
         if self.global.borrow().shutdown_state() != ShutdownState::NotShuttingDown {
             return;
@@ -1257,6 +1277,53 @@ impl IOCompositor {
             }))
     }
 
+    /// Render the scene and store the part of it covered by the given `WebView` as a new
+    /// WebRender image, so that script can paint the old state of the page during a view
+    /// transition. Only the root pipeline of a `WebView` can be captured this way.
+    fn capture_view_transition_snapshot(
+        &mut self,
+        webview_id: WebViewId,
+        pipeline_id: PipelineId,
+    ) -> Option<ImageKey> {
+        let webview_rect = {
+            let webview_renderer = self.webview_renderers.get(webview_id)?;
+            if webview_renderer.root_pipeline_id != Some(pipeline_id) {
+                return None;
+            }
+            webview_renderer.rect.to_i32()
+        };
+
+        if let Err(error) = self.render_inner() {
+            warn!("Unable to render view transition snapshot: {error:?}");
+            return None;
+        }
+
+        // We need to convert to the bottom-left origin coordinate system used by OpenGL.
+        let size = self.rendering_context.size2d().to_i32();
+        let rect = DeviceIntRect::from_origin_and_size(
+            Point2D::new(webview_rect.min.x, size.height - webview_rect.max.y),
+            webview_rect.size(),
+        );
+        let image = self.rendering_context.read_to_image(rect)?;
+
+        let image_key = self.global.borrow().webrender_api.generate_image_key();
+        let descriptor = ImageDescriptor::new(
+            image.width() as i32,
+            image.height() as i32,
+            ImageFormat::RGBA8,
+            ImageDescriptorFlags::empty(),
+        );
+        let mut transaction = Transaction::new();
+        transaction.add_image(
+            image_key,
+            descriptor,
+            ImageData::new(image.into_raw()),
+            None,
+        );
+        self.global.borrow_mut().send_transaction(transaction);
+        Some(image_key)
+    }
+
     #[servo_tracing::instrument(skip_all)]
     fn render_inner(&mut self) -> Result<(), UnableToComposite> {
         if let Err(err) = self.rendering_context.make_current() {
@@ -1301,10 +1368,10 @@ impl IOCompositor {
     /// Send all pending paint metrics messages after a composite operation, which may advance
     /// the epoch for pipelines in the WebRender scene.
     ///
-    /// If there are pending paint metrics, we check if any of the painted epochs is one
-    /// of the ones that the paint metrics recorder is expecting. In that case, we get the
-    /// current time, inform the constellation about it and remove the pending metric from
-    /// the list.
+    /// If script is waiting for the presentation of the display list of a pipeline whose
+    /// epoch has now been painted, we get the current time and inform script about it via
+    /// the constellation. This is what script uses to report Paint Timing and the other
+    /// rendering-related performance entries.
     fn send_pending_paint_metrics_messages_after_composite(&mut self) {
         let paint_time = CrossProcessInstant::now();
         let document_id = self.webrender_document();
@@ -1318,44 +1385,20 @@ impl IOCompositor {
                     continue;
                 };
 
-                match pipeline.first_paint_metric {
-                    // We need to check whether the current epoch is later, because
-                    // CrossProcessCompositorMessage::SendInitialTransaction sends an
-                    // empty display list to WebRender which can happen before we receive
-                    // the first "real" display list.
-                    PaintMetricState::Seen(epoch, first_reflow) if epoch <= current_epoch => {
-                        assert!(epoch <= current_epoch);
-                        if let Err(error) = self.global.borrow().constellation_sender.send(
-                            EmbedderToConstellationMessage::PaintMetric(
-                                *pipeline_id,
-                                PaintMetricEvent::FirstPaint(paint_time, first_reflow),
-                            ),
-                        ) {
-                            warn!(
-                                "Sending paint metric event to constellation failed ({error:?})."
-                            );
-                        }
-                        pipeline.first_paint_metric = PaintMetricState::Sent;
-                    },
-                    _ => {},
-                }
-
-                match pipeline.first_contentful_paint_metric {
-                    PaintMetricState::Seen(epoch, first_reflow) if epoch <= current_epoch => {
-                        if let Err(error) = self.global.borrow().constellation_sender.send(
-                            EmbedderToConstellationMessage::PaintMetric(
-                                *pipeline_id,
-                                PaintMetricEvent::FirstContentfulPaint(paint_time, first_reflow),
-                            ),
-                        ) {
-                            warn!(
-                                "Sending paint metric event to constellation failed ({error:?})."
-                            );
-                        }
-                        pipeline.first_contentful_paint_metric = PaintMetricState::Sent;
-                    },
-                    _ => {},
-                }
+                pipeline.pending_presentation_feedback.retain(|epoch| {
+                    if *epoch > current_epoch {
+                        return true;
+                    }
+                    if let Err(error) = self.global.borrow().constellation_sender.send(
+                        EmbedderToConstellationMessage::PaintMetric(
+                            *pipeline_id,
+                            PaintMetricEvent::FramePresented(Epoch(epoch.0), paint_time),
+                        ),
+                    ) {
+                        warn!("Sending paint metric event to constellation failed ({error:?}).");
+                    }
+                    false
+                });
             }
         }
     }
@@ -1488,9 +1531,9 @@ impl IOCompositor {
         let mut flags = webrender.get_debug_flags();
         let flag = match option {
             WebRenderDebugOption::Profiler => {
-                webrender::DebugFlags::PROFILER_DBG |
-                    webrender::DebugFlags::GPU_TIME_QUERIES |
-                    webrender::DebugFlags::GPU_SAMPLE_QUERIES
+                webrender::DebugFlags::PROFILER_DBG
+                    | webrender::DebugFlags::GPU_TIME_QUERIES
+                    | webrender::DebugFlags::GPU_SAMPLE_QUERIES
             },
             WebRenderDebugOption::TextureCacheDebug => webrender::DebugFlags::TEXTURE_CACHE_DBG,
             WebRenderDebugOption::RenderTargetDebug => webrender::DebugFlags::RENDER_TARGET_DBG,
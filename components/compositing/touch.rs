@@ -3,6 +3,7 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 use embedder_traits::{TouchId, TouchSequenceId};
 use euclid::{Point2D, Scale, Vector2D};
@@ -22,11 +23,18 @@ const FLING_SCALING_FACTOR: f32 = 0.95;
 const FLING_MIN_SCREEN_PX: f32 = 3.0;
 /// Maximum velocity when flinging.
 const FLING_MAX_SCREEN_PX: f32 = 4000.0;
+/// Maximum time between two taps for them to be treated as a double tap.
+const DOUBLE_TAP_MAX_INTERVAL: Duration = Duration::from_millis(300);
+/// Maximum distance between two taps for them to be treated as a double tap.
+const DOUBLE_TAP_MAX_SCREEN_PX: f32 = 40.0;
 
 pub struct TouchHandler {
     pub current_sequence_id: TouchSequenceId,
     // todo: VecDeque + modulo arithmetic would be more efficient.
     touch_sequence_map: HashMap<TouchSequenceId, TouchSequenceInfo>,
+    /// The time and location of the last tap that resulted in a click, used to
+    /// detect double taps.
+    last_tap: Option<(Instant, DevicePoint)>,
 }
 
 /// Whether the default move action is allowed or not.
@@ -209,9 +217,27 @@ impl TouchHandler {
             // so that we always have one element in the map, which simplifies creating
             // a new touch sequence on touch_down.
             touch_sequence_map: HashMap::from([(TouchSequenceId::new(), finished_info)]),
+            last_tap: None,
         }
     }
 
+    /// Record a tap at the given point. Returns true if this tap completes a double tap
+    /// together with the previous tap, in which case the tap history is reset so that
+    /// a third tap starts a new gesture.
+    pub(crate) fn on_tap(&mut self, point: DevicePoint) -> bool {
+        let now = Instant::now();
+        let is_double_tap = self.last_tap.is_some_and(|(time, last_point)| {
+            now.duration_since(time) <= DOUBLE_TAP_MAX_INTERVAL &&
+                (point - last_point).length() <= DOUBLE_TAP_MAX_SCREEN_PX
+        });
+        self.last_tap = if is_double_tap {
+            None
+        } else {
+            Some((now, point))
+        };
+        is_double_tap
+    }
+
     pub(crate) fn set_handling_touch_move(&mut self, sequence_id: TouchSequenceId, flag: bool) {
         if let Some(sequence) = self.touch_sequence_map.get_mut(&sequence_id) {
             sequence.handling_touch_move = flag;
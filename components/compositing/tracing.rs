@@ -51,7 +51,9 @@ mod from_constellation {
                 Self::RemoveFonts(..) => target!("RemoveFonts"),
                 Self::CollectMemoryReport(..) => target!("CollectMemoryReport"),
                 Self::Viewport(..) => target!("Viewport"),
+                Self::RequestPresentationFeedback(..) => target!("RequestPresentationFeedback"),
                 Self::GenerateImageKeysForPipeline(..) => target!("GenerateImageKeysForPipeline"),
+                Self::CaptureViewTransitionSnapshot(..) => target!("CaptureViewTransitionSnapshot"),
             }
         }
     }
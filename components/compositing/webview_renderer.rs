@@ -30,6 +30,9 @@ use webrender_api::{ExternalScrollId, HitTestFlags, ScrollLocation};
 use crate::compositor::{PipelineDetails, ServoRenderer};
 use crate::touch::{TouchHandler, TouchMoveAction, TouchMoveAllowed, TouchSequenceState};
 
+/// The pinch zoom magnification applied when double tapping on an unzoomed page.
+const DOUBLE_TAP_ZOOM_FACTOR: f32 = 2.0;
+
 #[derive(Clone, Copy)]
 struct ScrollEvent {
     /// Scroll by this offset, or to Start or End
@@ -359,7 +362,7 @@ impl WebViewRenderer {
             InputEvent::MouseLeave(_) => {
                 self.global.borrow_mut().last_mouse_move_position = None;
             },
-            InputEvent::MouseButton(_) | InputEvent::Wheel(_) => {},
+            InputEvent::DragDrop(_) | InputEvent::MouseButton(_) | InputEvent::Wheel(_) => {},
             _ => unreachable!("Unexpected input event type: {event:?}"),
         }
 
@@ -671,7 +674,11 @@ impl WebViewRenderer {
                                 // PreventDefault from touch_down may have been processed after
                                 // touch_up already occurred.
                                 if !info.prevent_click {
-                                    self.simulate_mouse_click(point);
+                                    if self.touch_handler.on_tap(point) {
+                                        self.on_double_tap(point);
+                                    } else {
+                                        self.simulate_mouse_click(point);
+                                    }
                                 }
                                 self.touch_handler.remove_touch_sequence(sequence_id);
                             },
@@ -704,6 +711,32 @@ impl WebViewRenderer {
         }
     }
 
+    /// The default action of a double tap: zoom in around the tapped point, or return to
+    /// the unzoomed view if the page is already pinch zoomed.
+    fn on_double_tap(&mut self, point: DevicePoint) {
+        let current_zoom = self.pinch_zoom_level().get();
+        let magnification = if current_zoom > 1.0 {
+            1.0 / current_zoom
+        } else {
+            DOUBLE_TAP_ZOOM_FACTOR
+        };
+
+        // Keep the tapped point in place by scrolling by the distance it moves when
+        // magnified around the origin. This mirrors the scroll applied when pinching.
+        let scroll_delta = point.to_vector() * (magnification - 1.0);
+        self.pending_scroll_zoom_events
+            .push(ScrollZoomEvent::PinchZoom(magnification));
+        self.pending_scroll_zoom_events
+            .push(ScrollZoomEvent::Scroll(ScrollEvent {
+                scroll_location: ScrollLocation::Delta(LayoutVector2D::from_untyped(
+                    scroll_delta.to_untyped(),
+                )),
+                // Make sure this hits the base layer.
+                cursor: Point2D::new(-1, -1),
+                event_count: 1,
+            }));
+    }
+
     /// <http://w3c.github.io/touch-events/#mouse-events>
     fn simulate_mouse_click(&mut self, point: DevicePoint) {
         let button = MouseButton::Left;
@@ -67,6 +67,10 @@ pub struct Opts {
     /// Directory for a default config directory
     pub config_dir: Option<PathBuf>,
 
+    /// The maximum size, in bytes, of the HTTP cache persisted in the config directory.
+    /// `None` keeps the HTTP cache in memory only.
+    pub http_disk_cache_size: Option<u64>,
+
     /// Path to PEM encoded SSL CA certificate store.
     pub certificate_path: Option<String>,
 
@@ -195,6 +199,7 @@ impl Default for Opts {
             sandbox: false,
             debug: Default::default(),
             config_dir: None,
+            http_disk_cache_size: None,
             shaders_dir: None,
             certificate_path: None,
             ignore_certificate_errors: false,
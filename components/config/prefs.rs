@@ -26,6 +26,18 @@ pub fn set(preferences: Preferences) {
     stylo_config::set_bool("layout.flexbox.enabled", preferences.layout_flexbox_enabled);
     stylo_config::set_bool("layout.columns.enabled", preferences.layout_columns_enabled);
     stylo_config::set_bool("layout.grid.enabled", preferences.layout_grid_enabled);
+    stylo_config::set_bool(
+        "layout.css.grid-template-masonry-value.enabled",
+        preferences.layout_grid_masonry_enabled,
+    );
+    stylo_config::set_bool(
+        "layout.css.at-scope.enabled",
+        preferences.layout_css_at_scope_enabled,
+    );
+    stylo_config::set_bool(
+        "layout.css.has-selector.enabled",
+        preferences.layout_css_has_selector_enabled,
+    );
     stylo_config::set_bool(
         "layout.css.transition-behavior.enabled",
         preferences.layout_css_transition_behavior_enabled,
@@ -38,6 +50,11 @@ pub fn set(preferences: Preferences) {
         "layout.container-queries.enabled",
         preferences.layout_container_queries_enabled,
     );
+    // `style()` container queries on custom properties are part of container queries.
+    stylo_config::set_bool(
+        "layout.css.style-queries.enabled",
+        preferences.layout_container_queries_enabled,
+    );
 
     *PREFERENCES.write().unwrap() = preferences;
 }
@@ -86,30 +103,51 @@ pub struct Preferences {
     pub dom_canvas_backend: String,
     pub dom_clipboardevent_enabled: bool,
     pub dom_composition_event_enabled: bool,
+    pub dom_compression_stream_enabled: bool,
     pub dom_cookiestore_enabled: bool,
+    pub dom_credential_management_enabled: bool,
     pub dom_crypto_subtle_enabled: bool,
     pub dom_customelements_enabled: bool,
+    pub dom_deprecation_reporting_enabled: bool,
     pub dom_document_dblclick_timeout: i64,
     pub dom_document_dblclick_dist: i64,
+    pub dom_drag_and_drop_enabled: bool,
+    pub dom_element_timing_enabled: bool,
+    pub dom_event_timing_enabled: bool,
     pub dom_fontface_enabled: bool,
     pub dom_fullscreen_test: bool,
     pub dom_gamepad_enabled: bool,
+    pub dom_geolocation_enabled: bool,
     pub dom_indexeddb_enabled: bool,
     pub dom_intersection_observer_enabled: bool,
+    pub dom_intervention_reporting_enabled: bool,
+    pub dom_largest_contentful_paint_enabled: bool,
+    pub dom_layout_instability_enabled: bool,
+    pub dom_long_animation_frames_enabled: bool,
+    pub dom_long_tasks_enabled: bool,
+    pub dom_mathml_enabled: bool,
     pub dom_microdata_testing_enabled: bool,
     pub dom_mouse_event_which_enabled: bool,
     pub dom_mutation_observer_enabled: bool,
+    pub dom_navigation_api_enabled: bool,
     pub dom_navigator_sendbeacon_enabled: bool,
     pub dom_notification_enabled: bool,
     pub dom_offscreen_canvas_enabled: bool,
+    pub dom_page_lifecycle_enabled: bool,
     pub dom_permissions_enabled: bool,
     pub dom_permissions_testing_allowed_in_nonsecure_contexts: bool,
+    pub dom_popover_enabled: bool,
+    pub dom_request_idle_callback_enabled: bool,
     pub dom_resize_observer_enabled: bool,
+    pub dom_sanitizer_enabled: bool,
+    pub dom_scheduler_enabled: bool,
     pub dom_script_asynch: bool,
+    pub dom_server_timing_enabled: bool,
     pub dom_serviceworker_enabled: bool,
     pub dom_serviceworker_timeout_seconds: i64,
     pub dom_servo_helpers_enabled: bool,
     pub dom_servoparser_async_html_tokenizer_enabled: bool,
+    pub dom_speculation_rules_enabled: bool,
     pub dom_svg_enabled: bool,
     pub dom_testable_crash_enabled: bool,
     pub dom_testbinding_enabled: bool,
@@ -126,7 +164,10 @@ pub struct Preferences {
     pub dom_testperf_enabled: bool,
     // https://testutils.spec.whatwg.org#availability
     pub dom_testutils_enabled: bool,
+    pub dom_text_encoder_stream_enabled: bool,
     pub dom_trusted_types_enabled: bool,
+    pub dom_view_transitions_enabled: bool,
+    pub dom_webauthn_enabled: bool,
     pub dom_xpath_enabled: bool,
     /// Enable WebGL2 APIs.
     pub dom_webgl2_enabled: bool,
@@ -209,7 +250,10 @@ pub struct Preferences {
     pub layout_animations_test_enabled: bool,
     pub layout_columns_enabled: bool,
     pub layout_grid_enabled: bool,
+    pub layout_grid_masonry_enabled: bool,
     pub layout_container_queries_enabled: bool,
+    pub layout_css_at_scope_enabled: bool,
+    pub layout_css_has_selector_enabled: bool,
     pub layout_css_transition_behavior_enabled: bool,
     pub layout_flexbox_enabled: bool,
     pub layout_threads: i64,
@@ -219,12 +263,40 @@ pub struct Preferences {
     pub media_glvideo_enabled: bool,
     /// Enable a non-standard event handler for verifying behavior of media elements during tests.
     pub media_testing_enabled: bool,
+    /// The URL of the DNS-over-HTTPS provider used to resolve host names, such as
+    /// `https://dns.example/dns-query`. The system resolver is used if this is empty.
+    pub network_doh_provider_url: String,
     pub network_enforce_tls_enabled: bool,
     pub network_enforce_tls_localhost: bool,
     pub network_enforce_tls_onion: bool,
+    /// How long, in milliseconds, to wait for a connection to the first resolved address of a
+    /// host before also trying an address of the other IP family, as described in
+    /// <https://www.rfc-editor.org/rfc/rfc8305>. Addresses are tried one after the other if
+    /// this is 0.
+    pub network_happy_eyeballs_delay_ms: i64,
     pub network_http_cache_disabled: bool,
+    /// The proxy used for `http` URLs, such as `http://proxy.example:3128` or
+    /// `socks5://127.0.0.1:1080`. URLs are fetched directly if this is empty.
+    pub network_http_proxy_uri: String,
+    /// The proxy used for `https` URLs, in the same form as `network_http_proxy_uri`.
+    pub network_https_proxy_uri: String,
     pub network_local_directory_listing_enabled: bool,
+    /// The maximum number of requests that may be in flight to a single host at once.
+    pub network_max_connections_per_host: i64,
     pub network_mime_sniff: bool,
+    /// How long, in seconds, an idle connection is kept open for reuse.
+    pub network_pool_idle_timeout_s: i64,
+    /// The maximum number of idle connections kept open to a single host.
+    pub network_pool_max_idle_per_host: i64,
+    /// A comma-separated list of hosts that are reached without a proxy. Each entry is a
+    /// domain, which also matches its subdomains, an IP address or CIDR range, `<local>` for
+    /// host names without a dot, or `*` for all hosts.
+    pub network_proxy_bypass_list: String,
+    /// Whether documents are kept alive in the back/forward cache when they stop being active,
+    /// so that traversing back to them restores them instead of loading them again.
+    pub session_history_bfcache_enabled: bool,
+    /// How long, in seconds, an inactive document is kept in the back/forward cache.
+    pub session_history_bfcache_timeout_s: i64,
     pub session_history_max_length: i64,
     /// The background color of shell's viewport. This will be used by OpenGL's `glClearColor`.
     pub shell_background_color_rgba: [f64; 4],
@@ -266,30 +338,51 @@ impl Preferences {
             dom_canvas_backend: String::new(),
             dom_clipboardevent_enabled: true,
             dom_composition_event_enabled: false,
+            dom_compression_stream_enabled: false,
             dom_cookiestore_enabled: false,
+            dom_credential_management_enabled: false,
             dom_crypto_subtle_enabled: true,
             dom_customelements_enabled: true,
+            dom_deprecation_reporting_enabled: false,
             dom_document_dblclick_dist: 1,
             dom_document_dblclick_timeout: 300,
+            dom_drag_and_drop_enabled: false,
+            dom_element_timing_enabled: false,
+            dom_event_timing_enabled: false,
             dom_fontface_enabled: false,
             dom_fullscreen_test: false,
             dom_gamepad_enabled: true,
+            dom_geolocation_enabled: false,
             dom_indexeddb_enabled: false,
             dom_intersection_observer_enabled: false,
+            dom_intervention_reporting_enabled: false,
+            dom_largest_contentful_paint_enabled: false,
+            dom_layout_instability_enabled: false,
+            dom_long_animation_frames_enabled: false,
+            dom_long_tasks_enabled: false,
+            dom_mathml_enabled: false,
             dom_microdata_testing_enabled: false,
             dom_mouse_event_which_enabled: false,
             dom_mutation_observer_enabled: true,
+            dom_navigation_api_enabled: false,
             dom_navigator_sendbeacon_enabled: false,
             dom_notification_enabled: false,
             dom_offscreen_canvas_enabled: false,
+            dom_page_lifecycle_enabled: false,
             dom_permissions_enabled: false,
             dom_permissions_testing_allowed_in_nonsecure_contexts: false,
+            dom_popover_enabled: false,
+            dom_request_idle_callback_enabled: false,
             dom_resize_observer_enabled: false,
+            dom_sanitizer_enabled: false,
+            dom_scheduler_enabled: false,
             dom_script_asynch: true,
+            dom_server_timing_enabled: false,
             dom_serviceworker_enabled: false,
             dom_serviceworker_timeout_seconds: 60,
             dom_servo_helpers_enabled: false,
             dom_servoparser_async_html_tokenizer_enabled: false,
+            dom_speculation_rules_enabled: false,
             dom_svg_enabled: false,
             dom_testable_crash_enabled: false,
             dom_testbinding_enabled: false,
@@ -305,7 +398,10 @@ impl Preferences {
             dom_testing_html_input_element_select_files_enabled: false,
             dom_testperf_enabled: false,
             dom_testutils_enabled: false,
+            dom_text_encoder_stream_enabled: false,
             dom_trusted_types_enabled: false,
+            dom_view_transitions_enabled: false,
+            dom_webauthn_enabled: false,
             dom_webgl2_enabled: false,
             dom_webgpu_enabled: false,
             dom_webgpu_wgpu_backend: String::new(),
@@ -387,21 +483,34 @@ impl Preferences {
             layout_animations_test_enabled: false,
             layout_columns_enabled: false,
             layout_container_queries_enabled: false,
+            layout_css_at_scope_enabled: false,
+            layout_css_has_selector_enabled: false,
             layout_css_transition_behavior_enabled: true,
             layout_flexbox_enabled: true,
             layout_grid_enabled: false,
+            layout_grid_masonry_enabled: false,
             // TODO(mrobinson): This should likely be based on the number of processors.
             layout_threads: 3,
             layout_unimplemented: false,
             layout_writing_mode_enabled: false,
             media_glvideo_enabled: false,
             media_testing_enabled: false,
+            network_doh_provider_url: String::new(),
             network_enforce_tls_enabled: false,
             network_enforce_tls_localhost: false,
             network_enforce_tls_onion: false,
+            network_happy_eyeballs_delay_ms: 250,
             network_http_cache_disabled: false,
+            network_http_proxy_uri: String::new(),
+            network_https_proxy_uri: String::new(),
             network_local_directory_listing_enabled: true,
+            network_max_connections_per_host: 6,
             network_mime_sniff: false,
+            network_pool_idle_timeout_s: 90,
+            network_pool_max_idle_per_host: 6,
+            network_proxy_bypass_list: String::new(),
+            session_history_bfcache_enabled: true,
+            session_history_bfcache_timeout_s: 600,
             session_history_max_length: 20,
             shell_background_color_rgba: [1.0, 1.0, 1.0, 1.0],
             threadpools_async_runtime_workers_max: 6,
@@ -0,0 +1,49 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use base::id::PipelineId;
+
+/// The pipelines of the session history of a `WebView` whose documents are kept alive while
+/// they are not active, so that traversing back to them restores them instead of loading
+/// them again. Their script threads freeze them by suspending their timers and media when
+/// they become inactive.
+///
+/// Documents that cannot be restored are discarded by their script thread when they are
+/// unloaded, see <https://html.spec.whatwg.org/multipage/#concept-document-salvageable>.
+///
+/// <https://html.spec.whatwg.org/multipage/#note-bfcache>
+#[derive(Debug, Default)]
+pub(crate) struct BackForwardCache {
+    /// The cached pipelines, along with the time at which they were put in the cache.
+    entries: HashMap<PipelineId, Instant>,
+}
+
+impl BackForwardCache {
+    /// Put the pipeline of a document that stopped being active in the cache.
+    pub(crate) fn insert(&mut self, pipeline_id: PipelineId) {
+        self.entries.insert(pipeline_id, Instant::now());
+    }
+
+    /// Take a pipeline out of the cache, because its document is active again or because it
+    /// was closed.
+    pub(crate) fn remove(&mut self, pipeline_id: PipelineId) {
+        self.entries.remove(&pipeline_id);
+    }
+
+    /// Forget about the pipelines for which `keep` returns false.
+    pub(crate) fn retain(&mut self, mut keep: impl FnMut(PipelineId) -> bool) {
+        self.entries.retain(|pipeline_id, _| keep(*pipeline_id));
+    }
+
+    /// The pipelines that have been in the cache for longer than `timeout`.
+    pub(crate) fn expired(&self, timeout: Duration) -> impl Iterator<Item = PipelineId> + '_ {
+        self.entries
+            .iter()
+            .filter(move |(_, cached_at)| cached_at.elapsed() > timeout)
+            .map(|(pipeline_id, _)| *pipeline_id)
+    }
+}
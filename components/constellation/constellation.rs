@@ -9,7 +9,13 @@
 //!
 //! * The set of all `EventLoop` objects. Each event loop is
 //!   the constellation's view of a script thread. The constellation
-//!   interacts with a script thread by message-passing.
+//!   interacts with a script thread by message-passing. Event loops
+//!   are looked up by site, so documents of different sites are not
+//!   given the same event loop, and navigations that are redirected to
+//!   another site are restarted in an event loop of that site. This is
+//!   not site isolation: content processes are not locked to a site,
+//!   and nothing stops a compromised content process from requesting
+//!   the cookies, storage or network resources of other sites.
 //!
 //! * The set of all `Pipeline` objects.  Each pipeline gives the
 //!   constellation's view of a `Window`, with its script thread and
@@ -86,6 +92,7 @@
 
 use std::borrow::ToOwned;
 use std::cell::OnceCell;
+use std::cmp::Ordering;
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::marker::PhantomData;
@@ -93,6 +100,7 @@ use std::mem::replace;
 use std::rc::{Rc, Weak};
 use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
+use std::time::Duration;
 use std::{process, thread};
 
 use background_hang_monitor::HangMonitorRegister;
@@ -117,10 +125,11 @@ use compositing_traits::{
 use constellation_traits::{
     AuxiliaryWebViewCreationRequest, AuxiliaryWebViewCreationResponse, DocumentState,
     EmbedderToConstellationMessage, IFrameLoadInfo, IFrameLoadInfoWithData, IFrameSandboxState,
-    IFrameSizeMsg, Job, LoadData, LoadOrigin, LogEntry, MessagePortMsg, NavigationHistoryBehavior,
-    PaintMetricEvent, PortMessageTask, PortTransferInfo, SWManagerMsg, SWManagerSenders,
-    ScriptToConstellationChan, ScriptToConstellationMessage, ServiceWorkerManagerFactory,
-    ServiceWorkerMsg, StructuredSerializedData, TraversalDirection, WindowSizeType,
+    IFrameSizeMsg, Job, LoadData, LoadOrigin, LogEntry, MessagePortMsg, NavigableHistoryEntry,
+    NavigationHistoryBehavior, PaintMetricEvent, PortMessageTask, PortTransferInfo, SWManagerMsg,
+    SWManagerSenders, SandboxingFlagSet, ScriptToConstellationChan, ScriptToConstellationMessage,
+    ServiceWorkerManagerFactory, ServiceWorkerMsg, StructuredSerializedData, TraversalDirection,
+    WindowSizeType,
 };
 use crossbeam_channel::{Receiver, Select, Sender, unbounded};
 use devtools_traits::{
@@ -146,7 +155,8 @@ use keyboard_types::{Key, KeyState, Modifiers, NamedKey};
 use layout_api::{LayoutFactory, ScriptThreadFactory};
 use log::{debug, error, info, trace, warn};
 use media::WindowGLContext;
-use net_traits::pub_domains::reg_host;
+use net_traits::policy_container::OpenerPolicyValue;
+use net_traits::pub_domains::{Site, origin_site, reg_site};
 use net_traits::request::Referrer;
 use net_traits::storage_thread::{StorageThreadMsg, StorageType};
 use net_traits::{
@@ -156,13 +166,13 @@ use net_traits::{
 use profile_traits::mem::ProfilerMsg;
 use profile_traits::{mem, time};
 use script_traits::{
-    ConstellationInputEvent, DiscardBrowsingContext, DocumentActivity, ProgressiveWebMetricType,
-    ScriptThreadMessage, UpdatePipelineIdReason,
+    ConstellationInputEvent, DiscardBrowsingContext, DocumentActivity, ScriptThreadMessage,
+    UpdatePipelineIdReason,
 };
 use serde::{Deserialize, Serialize};
 use servo_config::{opts, pref};
 use servo_rand::{Rng, ServoRng, SliceRandom, random};
-use servo_url::{Host, ImmutableOrigin, ServoUrl};
+use servo_url::{ImmutableOrigin, ServoUrl};
 use style_traits::CSSPixel;
 #[cfg(feature = "webgpu")]
 use webgpu::swapchain::WGPUImageMap;
@@ -177,7 +187,7 @@ use crate::browsingcontext::{
     AllBrowsingContextsIterator, BrowsingContext, FullyActiveBrowsingContextsIterator,
     NewBrowsingContextInfo,
 };
-use crate::constellation_webview::ConstellationWebView;
+use crate::constellation_webview::{ConstellationWebView, Prerender};
 use crate::event_loop::EventLoop;
 use crate::pipeline::{InitialPipelineState, Pipeline};
 use crate::process_manager::ProcessManager;
@@ -242,16 +252,18 @@ struct BrowsingContextGroup {
 
     /// The set of all event loops in this BrowsingContextGroup.
     /// We store the event loops in a map
-    /// indexed by registered domain name (as a `Host`) to event loops.
-    /// It is important that scripts with the same eTLD+1,
+    /// indexed by site (scheme and eTLD+1) to event loops.
+    /// It is important that scripts with the same site,
     /// who are part of the same browsing-context group
     /// share an event loop, since they can use `document.domain`
     /// to become same-origin, at which point they can share DOM objects.
-    event_loops: HashMap<Host, Weak<EventLoop>>,
+    /// Documents of different sites never can, so they are not given
+    /// the same event loop.
+    event_loops: HashMap<Site, Weak<EventLoop>>,
 
     /// The set of all WebGPU channels in this BrowsingContextGroup.
     #[cfg(feature = "webgpu")]
-    webgpus: HashMap<Host, WebGPU>,
+    webgpus: HashMap<Site, WebGPU>,
 }
 
 /// The `Constellation` itself. In the servo browser, there is one
@@ -766,7 +778,7 @@ where
 
     fn get_event_loop(
         &mut self,
-        host: &Host,
+        site: &Site,
         webview_id: &WebViewId,
         opener: &Option<BrowsingContextId>,
     ) -> Result<Weak<EventLoop>, &'static str> {
@@ -800,7 +812,7 @@ where
         };
         bc_group
             .event_loops
-            .get(host)
+            .get(site)
             .ok_or("Trying to get an event-loop from an unknown browsing context group")
             .cloned()
     }
@@ -808,7 +820,7 @@ where
     fn set_event_loop(
         &mut self,
         event_loop: Weak<EventLoop>,
-        host: Host,
+        site: Site,
         webview_id: WebViewId,
         opener: Option<BrowsingContextId>,
     ) {
@@ -847,17 +859,63 @@ where
         if let Some(bc_group) = self.browsing_context_group_set.get_mut(&bc_group_id) {
             if bc_group
                 .event_loops
-                .insert(host.clone(), event_loop)
+                .insert(site.clone(), event_loop)
                 .is_some()
             {
                 warn!(
                     "Double-setting an event-loop for {:?} at {:?}",
-                    host, relevant_top_level
+                    site, relevant_top_level
                 );
             }
         }
     }
 
+    /// Whether a pipeline of the top-level browsing context `webview_id` that runs in
+    /// `event_loop`, or in a new event loop if it is `None`, is in a content process that no
+    /// document of another browsing context group can use.
+    ///
+    /// Browsing context groups are only switched because of the opener policy once a
+    /// document's response has arrived, after its pipeline was created, so top-level documents
+    /// are only cross-origin isolated when this holds.
+    fn is_in_isolated_content_process(
+        &self,
+        webview_id: WebViewId,
+        opener: Option<BrowsingContextId>,
+        event_loop: Option<&Rc<EventLoop>>,
+    ) -> bool {
+        // In single-process mode, all documents share the same process, and auxiliary
+        // browsing contexts share the browsing context group of their opener.
+        if !opts::get().multiprocess || opener.is_some() {
+            return false;
+        }
+        let Some((bc_group_id, bc_group)) =
+            self.browsing_context_group_set
+                .iter()
+                .find(|(_, bc_group)| {
+                    bc_group
+                        .top_level_browsing_context_set
+                        .contains(&webview_id)
+                })
+        else {
+            return false;
+        };
+        if bc_group.top_level_browsing_context_set.len() != 1 {
+            return false;
+        }
+        let Some(event_loop) = event_loop else {
+            return true;
+        };
+        // A browsing context group that was switched because of the opener policy keeps using
+        // the event loop of its previous group.
+        !self
+            .browsing_context_group_set
+            .iter()
+            .filter(|(id, _)| *id != bc_group_id)
+            .flat_map(|(_, other_bc_group)| other_bc_group.event_loops.values())
+            .filter_map(Weak::upgrade)
+            .any(|other_event_loop| Rc::ptr_eq(&other_event_loop, event_loop))
+    }
+
     /// Helper function for creating a pipeline
     #[allow(clippy::too_many_arguments)]
     fn new_pipeline(
@@ -872,7 +930,7 @@ where
         // here, because it will be send on an ipc channel,
         // and ipc channels take onership of their data.
         // https://github.com/servo/ipc-channel/issues/138
-        load_data: LoadData,
+        mut load_data: LoadData,
         sandbox: IFrameSandboxState,
         is_private: bool,
         throttled: bool,
@@ -881,6 +939,10 @@ where
             return;
         }
 
+        // The ancestors of the navigable decide whether the new document may be embedded in
+        // them, so they are not taken from script, which may be compromised.
+        load_data.ancestor_origins = self.ancestor_origins(parent_pipeline_id);
+
         let Some(theme) = self
             .webviews
             .get(webview_id)
@@ -895,7 +957,7 @@ where
             pipeline_id, browsing_context_id
         );
 
-        let (event_loop, host) = match sandbox {
+        let (event_loop, site) = match sandbox {
             IFrameSandboxState::IFrameSandboxed => (None, None),
             IFrameSandboxState::IFrameUnsandboxed => {
                 // If this is an about:blank or about:srcdoc load, it must share the creator's
@@ -904,18 +966,18 @@ where
                 if load_data.url.as_str() != "about:blank" &&
                     load_data.url.as_str() != "about:srcdoc"
                 {
-                    match reg_host(&load_data.url) {
+                    match reg_site(&load_data.url) {
                         None => (None, None),
-                        Some(host) => match self.get_event_loop(&host, &webview_id, &opener) {
+                        Some(site) => match self.get_event_loop(&site, &webview_id, &opener) {
                             Err(err) => {
                                 warn!("{}", err);
-                                (None, Some(host))
+                                (None, Some(site))
                             },
                             Ok(event_loop) => {
                                 if let Some(event_loop) = event_loop.upgrade() {
                                     (Some(event_loop), None)
                                 } else {
-                                    (None, Some(host))
+                                    (None, Some(site))
                                 }
                             },
                         },
@@ -935,6 +997,9 @@ where
             },
         };
 
+        load_data.in_isolated_content_process =
+            self.is_in_isolated_content_process(webview_id, opener, event_loop.as_ref());
+
         let resource_threads = if is_private {
             self.private_resource_threads.clone()
         } else {
@@ -995,11 +1060,11 @@ where
             self.script_join_handles.insert(webview_id, join_handle);
         }
 
-        if let Some(host) = host {
-            debug!("{}: Adding new host entry {}", webview_id, host,);
+        if let Some(site) = site {
+            debug!("{}: Adding new site entry {}", webview_id, site);
             self.set_event_loop(
                 Rc::downgrade(&pipeline.pipeline.event_loop),
-                host,
+                site,
                 webview_id,
                 opener,
             );
@@ -1008,7 +1073,11 @@ where
         if let Some((lifeline_receiver, process)) = pipeline.lifeline {
             let crossbeam_receiver =
                 route_ipc_receiver_to_new_crossbeam_receiver_preserving_errors(lifeline_receiver);
-            self.process_manager.add(crossbeam_receiver, process);
+            self.process_manager.add(
+                crossbeam_receiver,
+                process,
+                Some(Rc::downgrade(&pipeline.pipeline.event_loop)),
+            );
         }
 
         assert!(!self.pipelines.contains_key(&pipeline_id));
@@ -1242,7 +1311,7 @@ where
             Request::FromSWManager(message) => {
                 self.handle_request_from_swmanager(message);
             },
-            Request::RemoveProcess(index) => self.process_manager.remove(index),
+            Request::RemoveProcess(index) => self.handle_remove_process(index),
         }
     }
 
@@ -1342,6 +1411,11 @@ where
                 self.public_resource_threads.clear_cache();
                 self.private_resource_threads.clear_cache();
             },
+            // The preload list is shared by the public and private resource threads.
+            EmbedderToConstellationMessage::UpdateHstsPreloadList(preload_list) => {
+                self.public_resource_threads
+                    .update_hsts_preload_list(preload_list);
+            },
             // Load a new page from a typed url
             // If there is already a pending page (self.pending_changes), it will not be overridden;
             // However, if the id is not encompassed by another change, it will be.
@@ -1467,6 +1541,12 @@ where
             EmbedderToConstellationMessage::SetWebViewThrottled(webview_id, throttled) => {
                 self.set_webview_throttled(webview_id, throttled);
             },
+            EmbedderToConstellationMessage::SetWebViewHidden(webview_id, hidden) => {
+                self.set_webview_hidden(webview_id, hidden);
+            },
+            EmbedderToConstellationMessage::SetWebViewFrozen(webview_id, frozen) => {
+                self.set_webview_frozen(webview_id, frozen);
+            },
             EmbedderToConstellationMessage::SetScrollStates(pipeline_id, scroll_states) => {
                 self.handle_set_scroll_states(pipeline_id, scroll_states)
             },
@@ -1662,19 +1742,53 @@ where
             ScriptToConstellationMessage::PipelineExited => {
                 self.handle_pipeline_exited(source_pipeline_id);
             },
+            ScriptToConstellationMessage::PersistFormState(form_state) => {
+                if let Some(pipeline) = self.pipelines.get_mut(&source_pipeline_id) {
+                    pipeline.load_data.persisted_form_state = form_state;
+                }
+            },
+            ScriptToConstellationMessage::PersistScrollState(scroll_state) => {
+                if let Some(pipeline) = self.pipelines.get_mut(&source_pipeline_id) {
+                    pipeline.load_data.persisted_scroll_state = Some(scroll_state);
+                }
+            },
+            ScriptToConstellationMessage::Prerender(load_data) => {
+                self.handle_prerender_msg(webview_id, source_pipeline_id, load_data);
+            },
             ScriptToConstellationMessage::DiscardDocument => {
                 self.handle_discard_document(webview_id, source_pipeline_id);
             },
             ScriptToConstellationMessage::DiscardTopLevelBrowsingContext => {
                 self.handle_close_top_level_browsing_context(webview_id);
             },
+            // Content processes may be compromised, so they can only create and navigate the
+            // iframes of their own documents, and open popups from them.
             ScriptToConstellationMessage::ScriptLoadedURLInIFrame(load_info) => {
+                if load_info.info.parent_pipeline_id != source_pipeline_id {
+                    return warn!(
+                        "{}: Iframe load claiming to come from {}",
+                        source_pipeline_id, load_info.info.parent_pipeline_id
+                    );
+                }
                 self.handle_script_loaded_url_in_iframe_msg(load_info);
             },
             ScriptToConstellationMessage::ScriptNewIFrame(load_info) => {
+                if load_info.info.parent_pipeline_id != source_pipeline_id {
+                    return warn!(
+                        "{}: New iframe claiming to come from {}",
+                        source_pipeline_id, load_info.info.parent_pipeline_id
+                    );
+                }
                 self.handle_script_new_iframe(load_info);
             },
             ScriptToConstellationMessage::CreateAuxiliaryWebView(load_info) => {
+                if load_info.opener_pipeline_id != source_pipeline_id {
+                    let _ = load_info.response_sender.send(None);
+                    return warn!(
+                        "{}: Auxiliary claiming to be opened by {}",
+                        source_pipeline_id, load_info.opener_pipeline_id
+                    );
+                }
                 self.handle_script_new_auxiliary(load_info);
             },
             ScriptToConstellationMessage::ChangeRunningAnimationsState(animation_state) => {
@@ -1692,6 +1806,21 @@ where
             ScriptToConstellationMessage::AbortLoadUrl => {
                 self.handle_abort_load_url_msg(source_pipeline_id);
             },
+            ScriptToConstellationMessage::NavigationResponseBlocked(reason) => {
+                self.handle_navigation_response_blocked_msg(source_pipeline_id, reason);
+            },
+            ScriptToConstellationMessage::NavigationRedirectedToOtherSite {
+                url,
+                referrer,
+                referrer_policy,
+            } => {
+                self.handle_navigation_redirected_to_other_site_msg(
+                    source_pipeline_id,
+                    url,
+                    referrer,
+                    referrer_policy,
+                );
+            },
             // A page loaded has completed all parsing, script, and reflow messages have been sent.
             ScriptToConstellationMessage::LoadComplete => {
                 self.handle_load_complete_msg(webview_id, source_pipeline_id)
@@ -1715,6 +1844,9 @@ where
             ScriptToConstellationMessage::JointSessionHistoryLength(response_sender) => {
                 self.handle_joint_session_history_length(webview_id, response_sender);
             },
+            ScriptToConstellationMessage::TraverseNavigableHistory(delta) => {
+                self.handle_traverse_navigable_history(webview_id, source_pipeline_id, delta);
+            },
             // Notification that the new document is ready to become active
             ScriptToConstellationMessage::ActivateDocument => {
                 self.handle_activate_document_msg(source_pipeline_id);
@@ -1728,13 +1860,24 @@ where
                     warn!("constellation got set final url message for dead pipeline");
                 }
             },
+            ScriptToConstellationMessage::SetCrossOriginOpenerPolicy(opener_policy) => {
+                self.handle_set_cross_origin_opener_policy(source_pipeline_id, opener_policy);
+            },
             ScriptToConstellationMessage::PostMessage {
                 target: browsing_context_id,
-                source: source_pipeline_id,
+                source,
                 target_origin: origin,
                 source_origin,
                 data,
             } => {
+                // Content processes may be compromised, so they can only post messages from
+                // their own documents.
+                if source != source_pipeline_id {
+                    return warn!(
+                        "{}: PostMessage claiming to come from {}",
+                        source_pipeline_id, source
+                    );
+                }
                 self.handle_post_message_msg(
                     browsing_context_id,
                     source_pipeline_id,
@@ -1854,6 +1997,10 @@ where
                 );
             },
             ScriptToConstellationMessage::MediaSessionEvent(pipeline_id, event) => {
+                if let MediaSessionEvent::PlaybackStateChange(ref state) = event {
+                    self.handle_media_playback_state_change(webview_id, pipeline_id, state);
+                }
+
                 // Unlikely at this point, but we may receive events coming from
                 // different media sessions, so we set the active media session based
                 // on Playing events.
@@ -1957,8 +2104,8 @@ where
             Some(pipeline) => pipeline,
             None => return warn!("{}: ScriptMsg from closed pipeline", source_pipeline_id),
         };
-        let host = match reg_host(&source_pipeline.url) {
-            Some(host) => host,
+        let site = match reg_site(&source_pipeline.url) {
+            Some(site) => site,
             None => return warn!("Invalid host url"),
         };
         let browsing_context_group = if let Some(bcg) = self
@@ -1969,7 +2116,7 @@ where
         } else {
             return warn!("Browsing context group not found");
         };
-        let webgpu_chan = match browsing_context_group.webgpus.entry(host) {
+        let webgpu_chan = match browsing_context_group.webgpus.entry(site) {
             Entry::Vacant(v) => start_webgpu_thread(
                 self.compositor_proxy.cross_process_compositor_api.clone(),
                 self.webrender_wgpu.webrender_external_images.clone(),
@@ -2418,7 +2565,7 @@ where
                             route_ipc_receiver_to_new_crossbeam_receiver_preserving_errors(
                                 receiver,
                             );
-                        self.process_manager.add(crossbeam_receiver, process);
+                        self.process_manager.add(crossbeam_receiver, process, None);
                     } else {
                         return warn!("Failed to spawn process for SW manager.");
                     }
@@ -2569,6 +2716,8 @@ where
             ipc::channel().expect("Failed to create IPC channel!");
         let (storage_ipc_sender, storage_ipc_receiver) =
             ipc::channel().expect("Failed to create IPC channel!");
+        let (private_storage_ipc_sender, private_storage_ipc_receiver) =
+            ipc::channel().expect("Failed to create IPC channel!");
         let mut webgl_threads_receiver = None;
 
         debug!("Exiting core resource threads.");
@@ -2594,6 +2743,12 @@ where
         {
             warn!("Exit storage thread failed ({})", e);
         }
+        if let Err(e) = self
+            .private_resource_threads
+            .send(StorageThreadMsg::Exit(private_storage_ipc_sender))
+        {
+            warn!("Exit private storage thread failed ({})", e);
+        }
 
         #[cfg(feature = "bluetooth")]
         {
@@ -2675,6 +2830,9 @@ where
         if let Err(e) = storage_ipc_receiver.recv() {
             warn!("Exit storage thread failed ({:?})", e);
         }
+        if let Err(e) = private_storage_ipc_receiver.recv() {
+            warn!("Exit private storage thread failed ({:?})", e);
+        }
         if self.webgl_threads.is_some() {
             if let Err(e) = webgl_threads_receiver
                 .expect("webgl_threads_receiver to be Some")
@@ -2710,6 +2868,74 @@ where
         ));
     }
 
+    /// The origin of the document of a pipeline, as far as the constellation knows it.
+    /// Documents at `about:blank` and `about:srcdoc` have the origin of their creator. This
+    /// must match the logic in the script thread when determining the proper origin.
+    fn pipeline_origin(pipeline: &Pipeline) -> ImmutableOrigin {
+        match &pipeline.load_data.load_origin {
+            LoadOrigin::Script(origin)
+                if pipeline.url.as_str() == "about:blank" ||
+                    pipeline.url.as_str() == "about:srcdoc" =>
+            {
+                origin.clone()
+            },
+            _ => pipeline.url.origin(),
+        }
+    }
+
+    /// The origins of the ancestors of a navigable whose parent document is in the given
+    /// pipeline, starting with that parent.
+    fn ancestor_origins(&self, parent_pipeline_id: Option<PipelineId>) -> Vec<ImmutableOrigin> {
+        let mut ancestor_origins = vec![];
+        let mut next_pipeline_id = parent_pipeline_id;
+        while let Some(pipeline) = next_pipeline_id.and_then(|id| self.pipelines.get(&id)) {
+            ancestor_origins.push(Self::pipeline_origin(pipeline));
+            next_pipeline_id = pipeline.parent_pipeline_id;
+        }
+        ancestor_origins
+    }
+
+    /// Handle the exit of a content process. If the process crashed or was killed, every
+    /// webview with a pipeline in it displays a crash page, and later loads of the sites of
+    /// the process spawn a fresh one.
+    #[servo_tracing::instrument(skip_all)]
+    fn handle_remove_process(&mut self, index: usize) {
+        let (pid, event_loop) = self.process_manager.remove(index);
+        let Some(event_loop) = event_loop else {
+            return;
+        };
+        if self.shutting_down {
+            return;
+        }
+
+        let weak_event_loop = Rc::downgrade(&event_loop);
+        for bc_group in self.browsing_context_group_set.values_mut() {
+            bc_group
+                .event_loops
+                .retain(|_, other| !other.ptr_eq(&weak_event_loop));
+        }
+
+        let dead_pipelines: Vec<(PipelineId, WebViewId)> = self
+            .pipelines
+            .values()
+            .filter(|pipeline| Rc::ptr_eq(&pipeline.event_loop, &event_loop))
+            .map(|pipeline| (pipeline.id, pipeline.webview_id))
+            .collect();
+        let webview_ids: HashSet<WebViewId> = dead_pipelines
+            .iter()
+            .map(|(_, webview_id)| *webview_id)
+            .collect();
+        let reason = format!("Content process {pid} exited unexpectedly");
+        for webview_id in webview_ids {
+            self.handle_panic(Some(webview_id), reason.clone(), None);
+        }
+
+        // The script threads of these pipelines will never report that they exited.
+        for (pipeline_id, _) in dead_pipelines {
+            self.handle_pipeline_exited(pipeline_id);
+        }
+    }
+
     #[servo_tracing::instrument(skip_all)]
     fn handle_send_error(&mut self, pipeline_id: PipelineId, err: IpcError) {
         // Treat send error the same as receiving a panic message
@@ -3049,6 +3275,114 @@ where
         });
     }
 
+    /// Record the cross-origin opener policy of a newly loaded top-level document and, if it
+    /// does not match the policy of the document it replaces, move its top-level browsing
+    /// context to a new browsing context group.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/#check-browsing-context-group-switch-coop-value>
+    ///
+    /// Note that the event loop of the new document was already chosen when its pipeline was
+    /// created, before its response was received, so the switch only takes effect for
+    /// documents that are loaded afterwards.
+    #[servo_tracing::instrument(skip_all)]
+    fn handle_set_cross_origin_opener_policy(
+        &mut self,
+        pipeline_id: PipelineId,
+        opener_policy: OpenerPolicyValue,
+    ) {
+        let Some(pipeline) = self.pipelines.get_mut(&pipeline_id) else {
+            return warn!("{pipeline_id}: Cross-origin opener policy for closed pipeline");
+        };
+        pipeline.opener_policy = opener_policy;
+        let webview_id = pipeline.webview_id;
+        let browsing_context_id = pipeline.browsing_context_id;
+        let origin = pipeline.url.origin();
+        let opener = pipeline.opener;
+        let site = reg_site(&pipeline.url);
+        let event_loop = Rc::downgrade(&pipeline.event_loop);
+        if browsing_context_id != BrowsingContextId::from(webview_id) {
+            return;
+        }
+
+        let Some(browsing_context) = self.browsing_contexts.get(&browsing_context_id) else {
+            return;
+        };
+        let bc_group_id = browsing_context.bc_group_id;
+
+        // The policy is compared with the one of the document that is being replaced. The
+        // initial `about:blank` document of an auxiliary browsing context has the policy of its
+        // opener.
+        let previous_pipeline = self
+            .pipelines
+            .get(&browsing_context.pipeline_id)
+            .filter(|previous| previous.id != pipeline_id);
+        let previous_pipeline = match previous_pipeline {
+            Some(previous) if previous.url.as_str() == "about:blank" => opener
+                .and_then(|opener| self.browsing_contexts.get(&opener))
+                .and_then(|opener| self.pipelines.get(&opener.pipeline_id))
+                .or(Some(previous)),
+            previous => previous,
+        };
+        let Some(previous_pipeline) = previous_pipeline else {
+            return;
+        };
+
+        // <https://html.spec.whatwg.org/multipage/#matching-coop>
+        let previous_policy = previous_pipeline.opener_policy;
+        let policies_match = (previous_policy == OpenerPolicyValue::UnsafeNone &&
+            opener_policy == OpenerPolicyValue::UnsafeNone) ||
+            (previous_policy == opener_policy && previous_pipeline.url.origin() == origin);
+        if policies_match {
+            return;
+        }
+
+        // If this top-level browsing context is alone in its group, there is nothing to
+        // isolate it from.
+        let Some(bc_group) = self.browsing_context_group_set.get_mut(&bc_group_id) else {
+            return warn!("{bc_group_id}: Browsing context group not found!");
+        };
+        if bc_group.top_level_browsing_context_set.len() <= 1 {
+            return;
+        }
+        bc_group.top_level_browsing_context_set.remove(&webview_id);
+
+        debug!("{webview_id}: Switching browsing context group because of opener policy");
+
+        // https://html.spec.whatwg.org/multipage/#creating-a-new-browsing-context-group
+        let mut new_bc_group: BrowsingContextGroup = Default::default();
+        let new_bc_group_id = self.next_browsing_context_group_id();
+        new_bc_group
+            .top_level_browsing_context_set
+            .insert(webview_id);
+        if let Some(site) = site {
+            new_bc_group.event_loops.insert(site, event_loop);
+        }
+        self.browsing_context_group_set
+            .insert(new_bc_group_id, new_bc_group);
+
+        for browsing_context in self.browsing_contexts.values_mut() {
+            if browsing_context.top_level_id == webview_id {
+                browsing_context.bc_group_id = new_bc_group_id;
+            }
+        }
+
+        // The new document no longer has an opener, so that documents loaded in this top-level
+        // browsing context afterwards are not placed in the browsing context group of the opener.
+        if opener.is_none() {
+            return;
+        }
+        let Some(pipeline) = self.pipelines.get_mut(&pipeline_id) else {
+            return;
+        };
+        pipeline.opener = None;
+        let result = pipeline
+            .event_loop
+            .send(ScriptThreadMessage::DisownOpener(pipeline_id));
+        if let Err(error) = result {
+            self.handle_send_error(pipeline_id, error);
+        }
+    }
+
     #[servo_tracing::instrument(skip_all)]
     fn handle_close_top_level_browsing_context(&mut self, webview_id: WebViewId) {
         debug!("{webview_id}: Closing");
@@ -3058,6 +3392,7 @@ where
         if self.webviews.focused_webview().map(|(id, _)| id) == Some(webview_id) {
             self.embedder_proxy.send(EmbedderMsg::WebViewBlurred);
         }
+        self.discard_prerender(webview_id);
         self.webviews.remove(webview_id);
         self.compositor_proxy
             .send(CompositorMsg::RemoveWebView(webview_id));
@@ -3333,12 +3668,50 @@ where
     #[servo_tracing::instrument(skip_all)]
     fn handle_script_new_auxiliary(&mut self, load_info: AuxiliaryWebViewCreationRequest) {
         let AuxiliaryWebViewCreationRequest {
-            load_data,
+            mut load_data,
             opener_webview_id,
             opener_pipeline_id,
+            features,
             response_sender,
         } = load_info;
 
+        // https://html.spec.whatwg.org/multipage/#the-rules-for-choosing-a-navigable
+        // > If sourceDocument's active sandboxing flag set has the sandboxed auxiliary
+        // > navigation browsing context flag set, then the user agent may report to a
+        // > developer console that a popup has been blocked.
+        let Some(opener_sandboxing_flags) = self
+            .pipelines
+            .get(&opener_pipeline_id)
+            .map(|pipeline| pipeline.load_data.sandboxing_flag_set)
+        else {
+            let _ = response_sender.send(None);
+            return warn!(
+                "{}: Auxiliary requested by closed pipeline",
+                opener_pipeline_id
+            );
+        };
+        if opener_sandboxing_flags.contains(SandboxingFlagSet::SANDBOXED_AUXILIARY_NAVIGATION) {
+            warn!(
+                "{}: Blocked a popup opened by a sandboxed document",
+                opener_pipeline_id
+            );
+            let _ = response_sender.send(None);
+            return;
+        }
+
+        // https://html.spec.whatwg.org/multipage/#creating-a-new-auxiliary-browsing-context
+        // > If sourceDocument's active sandboxing flag set has the sandbox propagates to
+        // > auxiliary browsing contexts flag set, then set newBrowsingContext's popup
+        // > sandboxing flag set to sourceDocument's active sandboxing flag set.
+        load_data.sandboxing_flag_set = if opener_sandboxing_flags
+            .contains(SandboxingFlagSet::SANDBOX_PROPAGATES_TO_AUXILIARY_BROWSING_CONTEXTS)
+        {
+            opener_sandboxing_flags
+        } else {
+            SandboxingFlagSet::empty()
+        };
+        let sandboxing_flag_set = load_data.sandboxing_flag_set;
+
         let (webview_id_sender, webview_id_receiver) = match ipc::channel() {
             Ok(result) => result,
             Err(error) => {
@@ -3349,6 +3722,7 @@ where
         };
         self.embedder_proxy.send(EmbedderMsg::AllowOpeningWebView(
             opener_webview_id,
+            features,
             webview_id_sender,
         ));
         let (new_webview_id, viewport_details) = match webview_id_receiver.recv() {
@@ -3394,6 +3768,7 @@ where
         let _ = response_sender.send(Some(AuxiliaryWebViewCreationResponse {
             new_webview_id,
             new_pipeline_id,
+            sandboxing_flag_set,
         }));
 
         assert!(!self.pipelines.contains_key(&new_pipeline_id));
@@ -3504,6 +3879,12 @@ where
         load_data: LoadData,
         history_handling: NavigationHistoryBehavior,
     ) {
+        if !self.is_allowed_by_sandboxing_to_navigate(source_id, &load_data) {
+            return warn!(
+                "{}: Navigation to {} blocked by the sandbox of its source document",
+                source_id, load_data.url
+            );
+        }
         match self.pending_approval_navigations.entry(source_id) {
             Entry::Occupied(_) => {
                 return warn!(
@@ -3524,12 +3905,74 @@ where
             ));
     }
 
+    /// Whether the document that initiated a navigation of the document of `target_id`, if
+    /// any, is allowed by its sandboxing flags to navigate the browsing context of the latter.
+    /// <https://html.spec.whatwg.org/multipage/#allowed-by-sandboxing-to-navigate>
+    fn is_allowed_by_sandboxing_to_navigate(
+        &self,
+        target_id: PipelineId,
+        load_data: &LoadData,
+    ) -> bool {
+        let Some(source_snapshot_params) = load_data.source_snapshot_params else {
+            return true;
+        };
+        let Some(source_bc) = self
+            .pipelines
+            .get(&source_snapshot_params.pipeline_id)
+            .map(|pipeline| pipeline.browsing_context_id)
+        else {
+            return false;
+        };
+        let Some(target_pipeline) = self.pipelines.get(&target_id) else {
+            return false;
+        };
+        let target_bc = target_pipeline.browsing_context_id;
+        let sandboxing_flags = source_snapshot_params.sandboxing_flags;
+
+        // A browsing context can always navigate itself and its descendants.
+        if source_bc == target_bc ||
+            self.ancestor_pipelines_of_browsing_context_iter(target_bc)
+                .any(|pipeline| pipeline.browsing_context_id == source_bc)
+        {
+            return true;
+        }
+
+        // Other child browsing contexts can only be navigated by unsandboxed documents.
+        let target_is_top_level = self
+            .browsing_contexts
+            .get(&target_bc)
+            .is_some_and(|browsing_context| browsing_context.is_top_level());
+        if !target_is_top_level {
+            return !sandboxing_flags.contains(SandboxingFlagSet::SANDBOXED_NAVIGATION);
+        }
+
+        // Navigating the top-level ancestor is governed by `allow-top-navigation`, or by
+        // `allow-top-navigation-by-user-activation` when the source had transient activation.
+        if self
+            .ancestor_pipelines_of_browsing_context_iter(source_bc)
+            .any(|pipeline| pipeline.browsing_context_id == target_bc)
+        {
+            let flag = if source_snapshot_params.has_transient_activation {
+                SandboxingFlagSet::SANDBOXED_TOP_LEVEL_NAVIGATION_WITH_USER_ACTIVATION
+            } else {
+                SandboxingFlagSet::SANDBOXED_TOP_LEVEL_NAVIGATION_WITHOUT_USER_ACTIVATION
+            };
+            return !sandboxing_flags.contains(flag);
+        }
+
+        // Other top-level browsing contexts can only be navigated by unsandboxed documents,
+        // and by the one permitted sandboxed navigator of an auxiliary browsing context, which
+        // is its opener.
+        !sandboxing_flags.contains(SandboxingFlagSet::SANDBOXED_NAVIGATION) ||
+            target_pipeline.opener == Some(source_bc)
+    }
+
     #[servo_tracing::instrument(skip_all)]
     fn load_url(
         &mut self,
         webview_id: WebViewId,
         source_id: PipelineId,
-        load_data: LoadData,
+        mut load_data: LoadData,
         history_handling: NavigationHistoryBehavior,
     ) -> Option<PipelineId> {
         debug!(
@@ -3631,14 +4074,36 @@ where
                 // Being here means either there are no pending changes, or none of the pending
                 // changes would be overridden by changing the subframe associated with source_id.
 
-                // Create the new pipeline
-
                 let replace = if history_handling == NavigationHistoryBehavior::Replace {
                     Some(NeedsToReload::No(pipeline_id))
                 } else {
                     None
                 };
 
+                // Activate the prerendered pipeline if this navigation was prerendered, and
+                // otherwise discard the prerender that turned out to be useless.
+                if let Some(prerender) = self.take_matching_prerender(webview_id, &load_data) {
+                    let new_pipeline_id = prerender.pipeline_id;
+                    self.activate_prerender(
+                        webview_id,
+                        browsing_context_id,
+                        prerender,
+                        replace,
+                        viewport_details,
+                        is_throttled,
+                    );
+                    return Some(new_pipeline_id);
+                }
+                self.discard_prerender(webview_id);
+
+                // The new document of a top-level browsing context keeps being sandboxed by
+                // its popup sandboxing flags.
+                if let Some(pipeline) = self.pipelines.get(&pipeline_id) {
+                    load_data.sandboxing_flag_set = pipeline.load_data.sandboxing_flag_set;
+                }
+
+                // Create the new pipeline
+
                 let new_pipeline_id = PipelineId::new();
                 let sandbox = IFrameSandboxState::IFrameUnsandboxed;
                 self.new_pipeline(
@@ -3667,61 +4132,375 @@ where
         }
     }
 
+    /// Start prerendering a likely next navigation of the top-level browsing context of the
+    /// given pipeline, in a hidden pipeline which that navigation can activate instantly.
+    /// <https://wicg.github.io/nav-speculation/prerendering.html#start-user-agent-initiated-prerendering>
     #[servo_tracing::instrument(skip_all)]
-    fn handle_abort_load_url_msg(&mut self, new_pipeline_id: PipelineId) {
-        let pending_index = self
-            .pending_changes
-            .iter()
-            .rposition(|change| change.new_pipeline_id == new_pipeline_id);
-
-        // If it is found, remove it from the pending changes.
-        if let Some(pending_index) = pending_index {
-            self.pending_changes.remove(pending_index);
-            self.close_pipeline(
-                new_pipeline_id,
-                DiscardBrowsingContext::No,
-                ExitPipelineMode::Normal,
+    fn handle_prerender_msg(
+        &mut self,
+        webview_id: WebViewId,
+        source_id: PipelineId,
+        mut load_data: LoadData,
+    ) {
+        let (browsing_context_id, opener) = match self.pipelines.get(&source_id) {
+            Some(pipeline) => {
+                load_data.sandboxing_flag_set = pipeline.load_data.sandboxing_flag_set;
+                (pipeline.browsing_context_id, pipeline.opener)
+            },
+            None => return warn!("{}: Prerendered after closure", source_id),
+        };
+        let Some(browsing_context) = self.browsing_contexts.get(&browsing_context_id) else {
+            return warn!(
+                "{}: Prerendered in closed {}",
+                source_id, browsing_context_id
             );
-        }
-    }
+        };
 
-    #[servo_tracing::instrument(skip_all)]
-    fn handle_load_complete_msg(&mut self, webview_id: WebViewId, pipeline_id: PipelineId) {
-        if let Some(pipeline) = self.pipelines.get_mut(&pipeline_id) {
-            debug!("{}: Marking as loaded", pipeline_id);
-            pipeline.completely_loaded = true;
+        // Only the active document of a top-level browsing context can prerender its next
+        // navigation.
+        if browsing_context.parent_pipeline_id.is_some() ||
+            browsing_context.pipeline_id != source_id
+        {
+            return;
         }
+        let viewport_details = browsing_context.viewport_details;
+        let is_private = browsing_context.is_private;
 
-        // Notify the embedder that the TopLevelBrowsingContext current document
-        // has finished loading.
-        // We need to make sure the pipeline that has finished loading is the current
-        // pipeline and that no pending pipeline will replace the current one.
-        let pipeline_is_top_level_pipeline = self
-            .browsing_contexts
-            .get(&BrowsingContextId::from(webview_id))
-            .map(|ctx| ctx.pipeline_id == pipeline_id)
-            .unwrap_or(false);
-        if pipeline_is_top_level_pipeline {
-            // Is there any pending pipeline that will replace the current top level pipeline
-            let current_top_level_pipeline_will_be_replaced = self
-                .pending_changes
-                .iter()
-                .any(|change| change.browsing_context_id == webview_id);
-
-            if !current_top_level_pipeline_will_be_replaced {
-                // Notify embedder and compositor top level document finished loading.
-                self.compositor_proxy
-                    .send(CompositorMsg::LoadComplete(webview_id));
-            }
-        } else {
-            self.handle_subframe_loaded(pipeline_id);
+        // Only one navigation is prerendered at a time, the latest one replacing the others.
+        let Some(webview) = self.webviews.get(webview_id) else {
+            return;
+        };
+        if webview
+            .prerender
+            .as_ref()
+            .is_some_and(|prerender| prerender.url == load_data.url)
+        {
+            return;
         }
-    }
+        self.discard_prerender(webview_id);
 
-    #[servo_tracing::instrument(skip_all)]
-    fn handle_navigated_to_fragment(
-        &mut self,
-        pipeline_id: PipelineId,
+        // The prerendered pipeline is throttled, as it is not displayed until it is activated.
+        let pipeline_id = PipelineId::new();
+        let url = load_data.url.clone();
+        load_data.prerendering = true;
+        self.new_pipeline(
+            pipeline_id,
+            browsing_context_id,
+            webview_id,
+            None,
+            opener,
+            viewport_details,
+            load_data,
+            IFrameSandboxState::IFrameUnsandboxed,
+            is_private,
+            true,
+        );
+        if let Some(webview) = self.webviews.get_mut(webview_id) {
+            webview.prerender = Some(Prerender {
+                pipeline_id,
+                url,
+                ready: false,
+            });
+        }
+    }
+
+    /// Take the prerender of the given webview if it prerendered the given navigation.
+    fn take_matching_prerender(
+        &mut self,
+        webview_id: WebViewId,
+        load_data: &LoadData,
+    ) -> Option<Prerender> {
+        let webview = self.webviews.get_mut(webview_id)?;
+        let prerender = webview.prerender.as_ref()?;
+        let prerendered_load_data = &self.pipelines.get(&prerender.pipeline_id)?.load_data;
+        if prerender.url != load_data.url ||
+            prerendered_load_data.method != load_data.method ||
+            load_data.data.is_some()
+        {
+            return None;
+        }
+        webview.prerender.take()
+    }
+
+    /// Make the prerendered pipeline of a navigation the active pipeline of the top-level
+    /// browsing context, as soon as its document is ready.
+    /// <https://wicg.github.io/nav-speculation/prerendering.html#prerendering-traversable-activate>
+    fn activate_prerender(
+        &mut self,
+        webview_id: WebViewId,
+        browsing_context_id: BrowsingContextId,
+        prerender: Prerender,
+        replace: Option<NeedsToReload>,
+        viewport_details: ViewportDetails,
+        is_throttled: bool,
+    ) {
+        let Some(pipeline) = self.pipelines.get_mut(&prerender.pipeline_id) else {
+            return warn!(
+                "{}: Activated prerender after closure",
+                prerender.pipeline_id
+            );
+        };
+        debug!("{}: Activating prerender of {}", pipeline.id, prerender.url);
+
+        // Reloading this session history entry later must not prerender it again.
+        pipeline.load_data.prerendering = false;
+        if !is_throttled {
+            pipeline.set_throttled(false);
+        }
+        let _ = pipeline
+            .event_loop
+            .send(ScriptThreadMessage::ActivatePrerenderedDocument(
+                prerender.pipeline_id,
+            ));
+
+        self.add_pending_change(SessionHistoryChange {
+            webview_id,
+            browsing_context_id,
+            new_pipeline_id: prerender.pipeline_id,
+            replace,
+            new_browsing_context_info: None,
+            viewport_details,
+        });
+        if !prerender.ready {
+            return;
+        }
+        self.handle_activate_document_msg(prerender.pipeline_id);
+
+        // The embedder has not been told that the document finished loading while it was
+        // hidden.
+        if self
+            .pipelines
+            .get(&prerender.pipeline_id)
+            .is_some_and(|pipeline| pipeline.completely_loaded)
+        {
+            self.handle_load_complete_msg(webview_id, prerender.pipeline_id);
+        }
+    }
+
+    /// Close the pipeline of the navigation that the given webview prerenders, if any.
+    fn discard_prerender(&mut self, webview_id: WebViewId) {
+        let Some(prerender) = self
+            .webviews
+            .get_mut(webview_id)
+            .and_then(|webview| webview.prerender.take())
+        else {
+            return;
+        };
+        self.close_pipeline(
+            prerender.pipeline_id,
+            DiscardBrowsingContext::No,
+            ExitPipelineMode::Normal,
+        );
+    }
+
+    #[servo_tracing::instrument(skip_all)]
+    fn handle_abort_load_url_msg(&mut self, new_pipeline_id: PipelineId) {
+        let pending_index = self
+            .pending_changes
+            .iter()
+            .rposition(|change| change.new_pipeline_id == new_pipeline_id);
+
+        // If it is found, remove it from the pending changes.
+        if let Some(pending_index) = pending_index {
+            self.pending_changes.remove(pending_index);
+            self.close_pipeline(
+                new_pipeline_id,
+                DiscardBrowsingContext::No,
+                ExitPipelineMode::Normal,
+            );
+        }
+    }
+
+    /// Cancel the pending load of a pipeline whose response may not be embedded in the
+    /// ancestors of its navigable, and load an error page in its place.
+    #[servo_tracing::instrument(skip_all)]
+    fn handle_navigation_response_blocked_msg(
+        &mut self,
+        blocked_pipeline_id: PipelineId,
+        reason: String,
+    ) {
+        let Some(pipeline) = self.pipelines.get(&blocked_pipeline_id) else {
+            return warn!("{blocked_pipeline_id}: Blocked navigation response for closed pipeline");
+        };
+        let load_data = LoadData {
+            navigation_error: Some(reason),
+            ..pipeline.load_data.clone()
+        };
+        self.replace_pending_pipeline(
+            blocked_pipeline_id,
+            load_data,
+            IFrameSandboxState::IFrameSandboxed,
+        );
+    }
+
+    /// Cancel the pending load of a pipeline whose navigation was redirected to another site,
+    /// and restart it from the redirect target in a pipeline of the event loop of that site,
+    /// so that documents of different sites never share an event loop.
+    #[servo_tracing::instrument(skip_all)]
+    fn handle_navigation_redirected_to_other_site_msg(
+        &mut self,
+        redirected_pipeline_id: PipelineId,
+        url: ServoUrl,
+        referrer: Referrer,
+        referrer_policy: ReferrerPolicy,
+    ) {
+        let Some(pipeline) = self.pipelines.get(&redirected_pipeline_id) else {
+            return warn!("{redirected_pipeline_id}: Redirected navigation for closed pipeline");
+        };
+        let webview_id = pipeline.webview_id;
+        let opener = pipeline.opener;
+        let event_loop = pipeline.event_loop.clone();
+        let site = reg_site(&pipeline.load_data.url);
+        let load_data = LoadData {
+            url,
+            referrer,
+            referrer_policy,
+            ..pipeline.load_data.clone()
+        };
+
+        // Pipelines that were not given the event loop of their site, such as those of
+        // sandboxed iframes, keep getting an event loop of their own.
+        let is_in_event_loop_of_site = site
+            .and_then(|site| self.get_event_loop(&site, &webview_id, &opener).ok())
+            .and_then(|site_event_loop| site_event_loop.upgrade())
+            .is_some_and(|site_event_loop| Rc::ptr_eq(&site_event_loop, &event_loop));
+        let sandbox = if is_in_event_loop_of_site {
+            IFrameSandboxState::IFrameUnsandboxed
+        } else {
+            IFrameSandboxState::IFrameSandboxed
+        };
+        self.replace_pending_pipeline(redirected_pipeline_id, load_data, sandbox);
+    }
+
+    /// Close the pipeline of a pending load and create a new one in its place for the same
+    /// session history change, loading `load_data`.
+    fn replace_pending_pipeline(
+        &mut self,
+        old_pipeline_id: PipelineId,
+        load_data: LoadData,
+        sandbox: IFrameSandboxState,
+    ) {
+        let Some(pending_index) = self
+            .pending_changes
+            .iter()
+            .rposition(|change| change.new_pipeline_id == old_pipeline_id)
+        else {
+            return warn!(
+                "{old_pipeline_id}: Replacing the pipeline of a load that is not pending"
+            );
+        };
+        let Some(pipeline) = self.pipelines.get(&old_pipeline_id) else {
+            return warn!("{old_pipeline_id}: Replacing closed pipeline");
+        };
+        let opener = pipeline.opener;
+
+        let mut change = self.pending_changes.remove(pending_index);
+        let (parent_pipeline_id, is_private, throttled) = match change.new_browsing_context_info {
+            Some(ref info) => (info.parent_pipeline_id, info.is_private, info.throttled),
+            None => match self.browsing_contexts.get(&change.browsing_context_id) {
+                Some(browsing_context) => (
+                    browsing_context.parent_pipeline_id,
+                    browsing_context.is_private,
+                    browsing_context.throttled,
+                ),
+                None => {
+                    return warn!(
+                        "{}: Replacing a pending pipeline in closed browsing context",
+                        change.browsing_context_id
+                    );
+                },
+            },
+        };
+
+        self.close_pipeline(
+            old_pipeline_id,
+            DiscardBrowsingContext::No,
+            ExitPipelineMode::Normal,
+        );
+
+        let browsing_context_id = change.browsing_context_id;
+        let new_pipeline_id = PipelineId::new();
+        self.new_pipeline(
+            new_pipeline_id,
+            browsing_context_id,
+            change.webview_id,
+            parent_pipeline_id,
+            opener,
+            change.viewport_details,
+            load_data,
+            sandbox,
+            is_private,
+            throttled,
+        );
+        change.new_pipeline_id = new_pipeline_id;
+        self.add_pending_change(change);
+
+        // The container of the navigable waits for the new pipeline to load instead.
+        let Some(parent_pipeline_id) = parent_pipeline_id else {
+            return;
+        };
+        let msg = ScriptThreadMessage::ReplacePendingPipelineId(
+            parent_pipeline_id,
+            browsing_context_id,
+            old_pipeline_id,
+            new_pipeline_id,
+        );
+        let result = match self.pipelines.get(&parent_pipeline_id) {
+            None => {
+                return warn!(
+                    "{parent_pipeline_id}: Replacing a pending pipeline in closed parent"
+                );
+            },
+            Some(pipeline) => pipeline.event_loop.send(msg),
+        };
+        if let Err(e) = result {
+            self.handle_send_error(parent_pipeline_id, e);
+        }
+    }
+
+    #[servo_tracing::instrument(skip_all)]
+    fn handle_load_complete_msg(&mut self, webview_id: WebViewId, pipeline_id: PipelineId) {
+        if let Some(pipeline) = self.pipelines.get_mut(&pipeline_id) {
+            debug!("{}: Marking as loaded", pipeline_id);
+            pipeline.completely_loaded = true;
+        }
+
+        // Notify the embedder that the TopLevelBrowsingContext current document
+        // has finished loading.
+        // We need to make sure the pipeline that has finished loading is the current
+        // pipeline and that no pending pipeline will replace the current one.
+        let pipeline_is_top_level_pipeline = self
+            .browsing_contexts
+            .get(&BrowsingContextId::from(webview_id))
+            .map(|ctx| ctx.pipeline_id == pipeline_id)
+            .unwrap_or(false);
+        // A prerendered document is hidden until it is activated.
+        let pipeline_is_prerendered = self
+            .webviews
+            .get(webview_id)
+            .and_then(|webview| webview.prerender.as_ref())
+            .is_some_and(|prerender| prerender.pipeline_id == pipeline_id);
+        if pipeline_is_top_level_pipeline {
+            // Is there any pending pipeline that will replace the current top level pipeline
+            let current_top_level_pipeline_will_be_replaced = self
+                .pending_changes
+                .iter()
+                .any(|change| change.browsing_context_id == webview_id);
+
+            if !current_top_level_pipeline_will_be_replaced {
+                // Notify embedder and compositor top level document finished loading.
+                self.compositor_proxy
+                    .send(CompositorMsg::LoadComplete(webview_id));
+            }
+        } else if !pipeline_is_prerendered {
+            self.handle_subframe_loaded(pipeline_id);
+        }
+    }
+
+    #[servo_tracing::instrument(skip_all)]
+    fn handle_navigated_to_fragment(
+        &mut self,
+        pipeline_id: PipelineId,
         new_url: ServoUrl,
         history_handling: NavigationHistoryBehavior,
     ) {
@@ -3747,6 +4526,7 @@ where
                 self.get_joint_session_history(webview_id).push_diff(diff);
 
                 self.notify_history_changed(webview_id);
+                self.update_navigable_histories(webview_id);
             },
         }
     }
@@ -3890,6 +4670,7 @@ where
         self.notify_history_changed(webview_id);
 
         self.trim_history(webview_id);
+        self.update_navigable_histories(webview_id);
         self.update_webview_in_compositor(webview_id);
     }
 
@@ -3997,6 +4778,11 @@ where
             self.notify_focus_state(new_pipeline_id);
         }
 
+        if let Some(webview) = self.webviews.get_mut(top_level_id) {
+            webview.back_forward_cache.remove(new_pipeline_id);
+            webview.back_forward_cache.insert(old_pipeline_id);
+        }
+
         self.update_activity(old_pipeline_id);
         self.update_activity(new_pipeline_id);
 
@@ -4027,21 +4813,26 @@ where
         history_state_id: Option<HistoryStateId>,
         url: ServoUrl,
     ) {
-        let result = match self.pipelines.get_mut(&pipeline_id) {
+        let webview_id = match self.pipelines.get_mut(&pipeline_id) {
             None => {
                 return warn!("{}: History state updated after closure", pipeline_id);
             },
             Some(pipeline) => {
-                let msg = ScriptThreadMessage::UpdateHistoryState(
-                    pipeline_id,
-                    history_state_id,
-                    url.clone(),
-                );
                 pipeline.history_state_id = history_state_id;
-                pipeline.url = url;
-                pipeline.event_loop.send(msg)
+                pipeline.url = url.clone();
+                pipeline.webview_id
             },
         };
+
+        // The document fires the `navigate` event of the traversal when it applies it, so it
+        // needs the session history that results from the traversal first.
+        self.update_navigable_history(webview_id, pipeline_id);
+
+        let msg = ScriptThreadMessage::UpdateHistoryState(pipeline_id, history_state_id, url);
+        let result = match self.pipelines.get(&pipeline_id) {
+            None => return warn!("{}: History state updated after closure", pipeline_id),
+            Some(pipeline) => pipeline.event_loop.send(msg),
+        };
         if let Err(e) = result {
             self.handle_send_error(pipeline_id, e);
         }
@@ -4061,6 +4852,193 @@ where
         let _ = response_sender.send(length as u32);
     }
 
+    /// Reconstruct the session history entries of the browsing context of the given pipeline
+    /// from the joint session history, returning each entry along with the number of joint
+    /// session history entries to traverse to reach it, as well as the index of the current
+    /// entry.
+    fn navigable_history(
+        &self,
+        webview_id: WebViewId,
+        pipeline_id: PipelineId,
+    ) -> Option<(Vec<(isize, NavigableHistoryEntry)>, usize)> {
+        let session_history = &self.webviews.get(webview_id)?.session_history;
+        let pipeline = self.pipelines.get(&pipeline_id)?;
+        let current_entry = NavigableHistoryEntry {
+            pipeline_id,
+            url: pipeline.url.clone(),
+            history_state_id: pipeline.history_state_id,
+        };
+
+        let mut entries = self.walk_navigable_history(
+            session_history.past.iter().rev(),
+            pipeline.browsing_context_id,
+            current_entry.clone(),
+            false,
+        );
+        entries.reverse();
+        let current_index = entries.len();
+        entries.push((0, current_entry.clone()));
+        entries.extend(self.walk_navigable_history(
+            session_history.future.iter().rev(),
+            pipeline.browsing_context_id,
+            current_entry,
+            true,
+        ));
+        Some((entries, current_index))
+    }
+
+    /// Walk the joint session history away from the current entry in one direction, keeping
+    /// track of the entry of the given browsing context at each step that changes it.
+    fn walk_navigable_history<'a>(
+        &self,
+        diffs: impl Iterator<Item = &'a SessionHistoryDiff>,
+        browsing_context_id: BrowsingContextId,
+        mut entry: NavigableHistoryEntry,
+        forward: bool,
+    ) -> Vec<(isize, NavigableHistoryEntry)> {
+        let mut entries = Vec::new();
+        for (step, diff) in diffs.enumerate() {
+            match diff {
+                SessionHistoryDiff::BrowsingContext {
+                    browsing_context_id: changed_browsing_context_id,
+                    old_reloader,
+                    new_reloader,
+                } if *changed_browsing_context_id == browsing_context_id => {
+                    let reloader = if forward { new_reloader } else { old_reloader };
+                    let Some(reloaded_entry) = self.navigable_history_entry_for_reloader(reloader)
+                    else {
+                        continue;
+                    };
+                    entry = reloaded_entry;
+                },
+                SessionHistoryDiff::Pipeline {
+                    pipeline_reloader,
+                    old_history_state_id,
+                    old_url,
+                    new_history_state_id,
+                    new_url,
+                } if pipeline_reloader.pipeline_id() == entry.pipeline_id => {
+                    if forward {
+                        entry.url = new_url.clone();
+                        entry.history_state_id = Some(*new_history_state_id);
+                    } else {
+                        entry.url = old_url.clone();
+                        entry.history_state_id = *old_history_state_id;
+                    }
+                },
+                SessionHistoryDiff::Hash {
+                    pipeline_reloader,
+                    old_url,
+                    new_url,
+                } if pipeline_reloader.pipeline_id() == entry.pipeline_id => {
+                    entry.url = if forward { new_url } else { old_url }.clone();
+                },
+                _ => continue,
+            }
+            let distance = step as isize + 1;
+            let distance = if forward { distance } else { -distance };
+            entries.push((distance, entry.clone()));
+        }
+        entries
+    }
+
+    /// The session history entry of a pipeline as it was when it was last the active
+    /// pipeline of its browsing context.
+    fn navigable_history_entry_for_reloader(
+        &self,
+        reloader: &NeedsToReload,
+    ) -> Option<NavigableHistoryEntry> {
+        match *reloader {
+            NeedsToReload::No(pipeline_id) => {
+                let pipeline = self.pipelines.get(&pipeline_id)?;
+                Some(NavigableHistoryEntry {
+                    pipeline_id,
+                    url: pipeline.url.clone(),
+                    history_state_id: pipeline.history_state_id,
+                })
+            },
+            NeedsToReload::Yes(pipeline_id, ref load_data) => Some(NavigableHistoryEntry {
+                pipeline_id,
+                url: load_data.url.clone(),
+                history_state_id: None,
+            }),
+        }
+    }
+
+    /// Send the session history entries of the browsing context of the given pipeline that
+    /// are contiguous with its current entry and same origin with it to its script thread.
+    /// Entries of other origins are never sent to script, so that a document cannot learn
+    /// where its navigable was before or after it.
+    fn update_navigable_history(&mut self, webview_id: WebViewId, pipeline_id: PipelineId) {
+        let Some((entries, current_index)) = self.navigable_history(webview_id, pipeline_id) else {
+            return;
+        };
+        let origin = entries[current_index].1.url.origin();
+        let is_same_origin =
+            |(_, entry): &(isize, NavigableHistoryEntry)| entry.url.origin() == origin;
+        let start = entries[..current_index]
+            .iter()
+            .rposition(|entry| !is_same_origin(entry))
+            .map_or(0, |index| index + 1);
+        let end = entries[current_index + 1..]
+            .iter()
+            .position(|entry| !is_same_origin(entry))
+            .map_or(entries.len(), |index| current_index + 1 + index);
+        let entries = entries[start..end]
+            .iter()
+            .map(|(_, entry)| entry.clone())
+            .collect();
+
+        let msg = ScriptThreadMessage::UpdateNavigableHistory(
+            pipeline_id,
+            entries,
+            current_index - start,
+        );
+        let result = match self.pipelines.get(&pipeline_id) {
+            None => return warn!("{}: Navigable history updated after closure", pipeline_id),
+            Some(pipeline) => pipeline.event_loop.send(msg),
+        };
+        if let Err(e) = result {
+            self.handle_send_error(pipeline_id, e);
+        }
+    }
+
+    /// Send the session history of their browsing context to the active documents of all
+    /// fully active browsing contexts of the given webview, after it changed.
+    fn update_navigable_histories(&mut self, webview_id: WebViewId) {
+        let pipeline_ids: Vec<_> = self
+            .fully_active_browsing_contexts_iter(webview_id)
+            .map(|browsing_context| browsing_context.pipeline_id)
+            .collect();
+        for pipeline_id in pipeline_ids {
+            self.update_navigable_history(webview_id, pipeline_id);
+        }
+    }
+
+    #[servo_tracing::instrument(skip_all)]
+    fn handle_traverse_navigable_history(
+        &mut self,
+        webview_id: WebViewId,
+        pipeline_id: PipelineId,
+        delta: isize,
+    ) {
+        let Some((entries, current_index)) = self.navigable_history(webview_id, pipeline_id) else {
+            return warn!("{}: Traversal for closed pipeline", pipeline_id);
+        };
+        let Some((distance, _)) = current_index
+            .checked_add_signed(delta)
+            .and_then(|index| entries.get(index))
+        else {
+            return warn!("{}: Cannot traverse {} entries", pipeline_id, delta);
+        };
+        let direction = match distance.cmp(&0) {
+            Ordering::Greater => TraversalDirection::Forward(distance.unsigned_abs()),
+            Ordering::Less => TraversalDirection::Back(distance.unsigned_abs()),
+            Ordering::Equal => return,
+        };
+        self.handle_traverse_history_msg(webview_id, direction);
+    }
+
     #[servo_tracing::instrument(skip_all)]
     fn handle_push_history_state_msg(
         &mut self,
@@ -4093,6 +5071,7 @@ where
         };
         self.get_joint_session_history(webview_id).push_diff(diff);
         self.notify_history_changed(webview_id);
+        self.update_navigable_histories(webview_id);
     }
 
     #[servo_tracing::instrument(skip_all)]
@@ -4118,6 +5097,7 @@ where
 
         let session_history = self.get_joint_session_history(webview_id);
         session_history.replace_history_state(pipeline_id, history_state_id, url);
+        self.update_navigable_histories(webview_id);
     }
 
     #[servo_tracing::instrument(skip_all)]
@@ -4129,6 +5109,24 @@ where
                 return warn!("{}: Got reload event after closure", browsing_context_id);
             },
         };
+        // A crash page is reloaded by loading the document that crashed again, in a fresh
+        // pipeline, rather than by asking the crash page to reload itself.
+        if let Some(pipeline) = self.pipelines.get(&pipeline_id) {
+            if pipeline.load_data.crash.is_some() {
+                let load_data = LoadData {
+                    crash: None,
+                    ..pipeline.load_data.clone()
+                };
+                self.load_url(
+                    webview_id,
+                    pipeline_id,
+                    load_data,
+                    NavigationHistoryBehavior::Replace,
+                );
+                return;
+            }
+        }
+
         let msg = ScriptThreadMessage::Reload(pipeline_id);
         let result = match self.pipelines.get(&pipeline_id) {
             None => return warn!("{}: Got reload event after closure", pipeline_id),
@@ -4158,7 +5156,21 @@ where
             Some(browsing_context) => browsing_context.pipeline_id,
         };
         let source_browsing_context = match self.pipelines.get(&source_pipeline) {
-            Some(pipeline) => pipeline.webview_id,
+            Some(pipeline) => {
+                // The origin of the message must be of the site of the source document, so
+                // that a compromised content process can't impersonate another site.
+                if let (Some(site), Some(source_site)) =
+                    (reg_site(&pipeline.url), origin_site(&source_origin))
+                {
+                    if site != source_site {
+                        return warn!(
+                            "{}: PostMessage from {} claiming to come from {}",
+                            source_pipeline, site, source_site
+                        );
+                    }
+                }
+                pipeline.webview_id
+            },
             None => return warn!("{}: PostMessage from closed pipeline", source_pipeline),
         };
         let msg = ScriptThreadMessage::PostMessage {
@@ -4550,16 +5562,78 @@ where
 
     #[servo_tracing::instrument(skip_all)]
     fn set_webview_throttled(&mut self, webview_id: WebViewId, throttled: bool) {
-        let browsing_context_id = BrowsingContextId::from(webview_id);
-        let pipeline_id = match self.browsing_contexts.get(&browsing_context_id) {
-            Some(browsing_context) => browsing_context.pipeline_id,
-            None => {
-                return warn!("{browsing_context_id}: Tried to SetWebViewThrottled after closure");
+        let Some(webview) = self.webviews.get_mut(webview_id) else {
+            return warn!("{webview_id}: Tried to SetWebViewThrottled after closure");
+        };
+        webview.throttled = throttled;
+        self.update_webview_throttling(webview_id);
+    }
+
+    #[servo_tracing::instrument(skip_all)]
+    fn set_webview_hidden(&mut self, webview_id: WebViewId, hidden: bool) {
+        let Some(webview) = self.webviews.get_mut(webview_id) else {
+            return warn!("{webview_id}: Tried to SetWebViewHidden after closure");
+        };
+        webview.hidden = hidden;
+        self.update_webview_throttling(webview_id);
+    }
+
+    #[servo_tracing::instrument(skip_all)]
+    fn set_webview_frozen(&mut self, webview_id: WebViewId, frozen: bool) {
+        if !self.webviews.contains_key(webview_id) {
+            return warn!("{webview_id}: Tried to SetWebViewFrozen after closure");
+        }
+        for browsing_context in self.fully_active_browsing_contexts_iter(webview_id) {
+            if let Some(pipeline) = self.pipelines.get(&browsing_context.pipeline_id) {
+                pipeline.set_frozen(frozen);
+            }
+        }
+    }
+
+    /// Keep track of the pipelines playing media, as hidden webviews playing media are
+    /// exempted from throttling.
+    fn handle_media_playback_state_change(
+        &mut self,
+        webview_id: WebViewId,
+        pipeline_id: PipelineId,
+        state: &MediaSessionPlaybackState,
+    ) {
+        let Some(webview) = self.webviews.get_mut(webview_id) else {
+            return;
+        };
+        let changed = match state {
+            MediaSessionPlaybackState::Playing => {
+                webview.pipelines_playing_media.insert(pipeline_id)
             },
+            _ => webview.pipelines_playing_media.remove(&pipeline_id),
         };
-        match self.pipelines.get(&pipeline_id) {
-            None => warn!("{pipeline_id}: Tried to SetWebViewThrottled after closure"),
-            Some(pipeline) => pipeline.set_throttled(throttled),
+        if changed && webview.hidden {
+            self.update_webview_throttling(webview_id);
+        }
+    }
+
+    /// Throttle every browsing context of a webview if the embedder asked for it, or if the
+    /// webview is hidden and not playing media, and stop throttling them otherwise.
+    fn update_webview_throttling(&mut self, webview_id: WebViewId) {
+        let Some(webview) = self.webviews.get(webview_id) else {
+            return;
+        };
+        let throttled =
+            webview.throttled || (webview.hidden && webview.pipelines_playing_media.is_empty());
+
+        let browsing_context_ids: Vec<BrowsingContextId> = self
+            .all_descendant_browsing_contexts_iter(BrowsingContextId::from(webview_id))
+            .map(|browsing_context| browsing_context.id)
+            .collect();
+        for browsing_context_id in browsing_context_ids {
+            let Some(browsing_context) = self.browsing_contexts.get_mut(&browsing_context_id)
+            else {
+                continue;
+            };
+            browsing_context.throttled = throttled;
+            if let Some(pipeline) = self.pipelines.get(&browsing_context.pipeline_id) {
+                pipeline.set_throttled(throttled);
+            }
         }
     }
 
@@ -4765,6 +5839,9 @@ where
                     let diffs_to_close = self
                         .get_joint_session_history(change.webview_id)
                         .push_diff(diff);
+                    if let Some(webview) = self.webviews.get_mut(change.webview_id) {
+                        webview.back_forward_cache.insert(old_pipeline_id);
+                    }
 
                     for diff in diffs_to_close {
                         match diff {
@@ -4831,6 +5908,7 @@ where
         self.notify_focus_state(change.new_pipeline_id);
 
         self.notify_history_changed(change.webview_id);
+        self.update_navigable_histories(change.webview_id);
         self.update_webview_in_compositor(change.webview_id);
     }
 
@@ -4882,7 +5960,7 @@ where
 
     #[servo_tracing::instrument(skip_all)]
     fn trim_history(&mut self, webview_id: WebViewId) {
-        let pipelines_to_evict = {
+        let mut pipelines_to_evict = {
             let session_history = self.get_joint_session_history(webview_id);
 
             let history_length = pref!(session_history_max_length) as usize;
@@ -4914,6 +5992,23 @@ where
             pipelines_to_evict
         };
 
+        // Documents are also evicted once they have been in the back/forward cache for too
+        // long, as they keep using resources while they are not active.
+        if let Some(webview) = self.webviews.get_mut(webview_id) {
+            let pipelines = &self.pipelines;
+            webview
+                .back_forward_cache
+                .retain(|pipeline_id| pipelines.contains_key(&pipeline_id));
+            let timeout =
+                Duration::from_secs(pref!(session_history_bfcache_timeout_s).max(0) as u64);
+            let expired_pipelines: Vec<_> = webview
+                .back_forward_cache
+                .expired(timeout)
+                .filter(|pipeline_id| !pipelines_to_evict.contains(pipeline_id))
+                .collect();
+            pipelines_to_evict.extend(expired_pipelines);
+        }
+
         let mut dead_pipelines = vec![];
         for evicted_id in pipelines_to_evict {
             let load_data = match self.pipelines.get(&evicted_id) {
@@ -4982,6 +6077,15 @@ where
                 }
             }
             self.change_session_history(change);
+        } else if let Some(prerender) = self
+            .pipelines
+            .get(&pipeline_id)
+            .and_then(|pipeline| self.webviews.get_mut(pipeline.webview_id))
+            .and_then(|webview| webview.prerender.as_mut())
+            .filter(|prerender| prerender.pipeline_id == pipeline_id)
+        {
+            // A prerendered document stays hidden until a navigation activates it.
+            prerender.ready = true;
         }
     }
 
@@ -5427,6 +6531,21 @@ where
             None => return warn!("fn close_pipeline: {pipeline_id}: Closing twice"),
         };
 
+        let webview_id = pipeline.webview_id;
+        let mut stopped_playing_media = false;
+        if let Some(webview) = self.webviews.get_mut(webview_id) {
+            webview.back_forward_cache.remove(pipeline_id);
+            if webview
+                .prerender
+                .as_ref()
+                .is_some_and(|prerender| prerender.pipeline_id == pipeline_id)
+            {
+                webview.prerender = None;
+            }
+            stopped_playing_media =
+                webview.pipelines_playing_media.remove(&pipeline_id) && webview.hidden;
+        }
+
         // Remove this pipeline from pending changes if it hasn't loaded yet.
         let pending_index = self
             .pending_changes
@@ -5439,6 +6558,10 @@ where
         // Inform script, compositor that this pipeline has exited.
         pipeline.send_exit_message_to_script(dbc);
 
+        if stopped_playing_media {
+            self.update_webview_throttling(webview_id);
+        }
+
         debug!("{}: Closed", pipeline_id);
     }
 
@@ -5584,24 +6707,12 @@ where
             warn!("Discarding paint metric event for unknown pipeline");
             return;
         };
-        let (metric_type, metric_value, first_reflow) = match event {
-            PaintMetricEvent::FirstPaint(metric_value, first_reflow) => (
-                ProgressiveWebMetricType::FirstPaint,
-                metric_value,
-                first_reflow,
-            ),
-            PaintMetricEvent::FirstContentfulPaint(metric_value, first_reflow) => (
-                ProgressiveWebMetricType::FirstContentfulPaint,
-                metric_value,
-                first_reflow,
-            ),
+        let message = match event {
+            PaintMetricEvent::FramePresented(epoch, presentation_time) => {
+                ScriptThreadMessage::FramePresented(pipeline_id, epoch, presentation_time)
+            },
         };
-        if let Err(error) = pipeline.event_loop.send(ScriptThreadMessage::PaintMetric(
-            pipeline_id,
-            metric_type,
-            metric_value,
-            first_reflow,
-        )) {
+        if let Err(error) = pipeline.event_loop.send(message) {
             warn!("Could not sent paint metric event to pipeline: {pipeline_id:?}: {error:?}");
         }
     }
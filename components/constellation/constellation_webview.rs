@@ -2,9 +2,13 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
-use base::id::BrowsingContextId;
+use std::collections::HashSet;
+
+use base::id::{BrowsingContextId, PipelineId};
 use embedder_traits::Theme;
+use servo_url::ServoUrl;
 
+use crate::back_forward_cache::BackForwardCache;
 use crate::session_history::JointSessionHistory;
 
 /// The `Constellation`'s view of a `WebView` in the embedding layer. This tracks all of the
@@ -18,6 +22,25 @@ pub(crate) struct ConstellationWebView {
     /// The joint session history for this webview.
     pub session_history: JointSessionHistory,
 
+    /// The documents of the session history of this webview that are kept alive while they
+    /// are not active.
+    pub back_forward_cache: BackForwardCache,
+
+    /// The likely next navigation of the top-level browsing context of this webview that is
+    /// being prerendered, if any.
+    pub prerender: Option<Prerender>,
+
+    /// Whether the embedder asked for this webview to be throttled.
+    pub throttled: bool,
+
+    /// Whether this webview is not painted by the renderer. Hidden webviews are throttled
+    /// unless they are playing media.
+    pub hidden: bool,
+
+    /// The pipelines of this webview that are currently playing media. A hidden webview
+    /// playing media is not throttled, so that its playback is not disrupted.
+    pub pipelines_playing_media: HashSet<PipelineId>,
+
     /// The [`Theme`] that this [`ConstellationWebView`] uses. This is communicated to all
     /// `ScriptThread`s so that they know how to render the contents of a particular `WebView.
     theme: Theme,
@@ -28,6 +51,11 @@ impl ConstellationWebView {
         Self {
             focused_browsing_context_id,
             session_history: JointSessionHistory::new(),
+            back_forward_cache: BackForwardCache::default(),
+            prerender: None,
+            throttled: false,
+            hidden: false,
+            pipelines_playing_media: HashSet::new(),
             theme: Theme::Light,
         }
     }
@@ -43,3 +71,16 @@ impl ConstellationWebView {
         self.theme
     }
 }
+
+/// A hidden pipeline that loads a likely next navigation of the top-level browsing context
+/// of a webview, which becomes its active pipeline instantly if that navigation happens.
+/// <https://wicg.github.io/nav-speculation/prerendering.html#prerendering-traversable>
+#[derive(Debug)]
+pub(crate) struct Prerender {
+    /// The pipeline of the prerendered document.
+    pub pipeline_id: PipelineId,
+    /// The URL that is prerendered.
+    pub url: ServoUrl,
+    /// Whether the prerendered document is ready to be activated.
+    pub ready: bool,
+}
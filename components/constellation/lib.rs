@@ -7,6 +7,7 @@
 #[macro_use]
 mod tracing;
 
+mod back_forward_cache;
 mod broadcastchannel;
 mod browsingcontext;
 mod constellation;
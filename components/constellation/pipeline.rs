@@ -36,6 +36,7 @@ use log::{debug, error, warn};
 use media::WindowGLContext;
 use net::image_cache::ImageCacheImpl;
 use net_traits::image_cache::ImageCache;
+use net_traits::policy_container::OpenerPolicyValue;
 use net_traits::{CoreResourceThread, ResourceThreads};
 use profile::system_reporter;
 use profile_traits::mem::{ProfilerMsg, Reporter};
@@ -105,6 +106,10 @@ pub struct Pipeline {
     pub layout_epoch: Epoch,
 
     pub focus_sequence: FocusSequenceNumber,
+
+    /// The cross-origin opener policy of this pipeline's document, if it is a top-level document.
+    /// <https://html.spec.whatwg.org/multipage/#concept-document-coop>
+    pub opener_policy: OpenerPolicyValue,
 }
 
 /// Initial setup data needed to construct a pipeline.
@@ -381,6 +386,7 @@ impl Pipeline {
             title: String::new(),
             layout_epoch: Epoch(0),
             focus_sequence: FocusSequenceNumber::default(),
+            opener_policy: OpenerPolicyValue::default(),
         };
 
         pipeline.set_throttled(throttled);
@@ -413,6 +419,14 @@ impl Pipeline {
         }
     }
 
+    /// Freeze or resume the document of this pipeline.
+    pub fn set_frozen(&self, frozen: bool) {
+        let msg = ScriptThreadMessage::SetFrozen(self.id, frozen);
+        if let Err(e) = self.event_loop.send(msg) {
+            warn!("Sending SetFrozen to script failed ({}).", e);
+        }
+    }
+
     /// The compositor's view of a pipeline.
     pub fn to_sendable(&self) -> CompositionPipeline {
         CompositionPipeline {
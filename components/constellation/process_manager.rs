@@ -3,18 +3,21 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 use std::process::Child;
+use std::rc::{Rc, Weak};
 
 use crossbeam_channel::{Receiver, Select};
 use log::{debug, warn};
 use profile_traits::mem::{ProfilerChan, ProfilerMsg};
 
+use crate::event_loop::EventLoop;
+
 pub enum Process {
     Unsandboxed(Child),
     Sandboxed(u32),
 }
 
 impl Process {
-    fn pid(&self) -> u32 {
+    pub(crate) fn pid(&self) -> u32 {
         match self {
             Self::Unsandboxed(child) => child.id(),
             Self::Sandboxed(pid) => *pid,
@@ -36,8 +39,16 @@ impl Process {
 
 type ProcessReceiver = Receiver<Result<(), ipc_channel::Error>>;
 
+/// A content process, along with the event loop that runs in it, if it is not the process
+/// of a service worker manager.
+struct ManagedProcess {
+    process: Process,
+    receiver: ProcessReceiver,
+    event_loop: Option<Weak<EventLoop>>,
+}
+
 pub(crate) struct ProcessManager {
-    processes: Vec<(Process, ProcessReceiver)>,
+    processes: Vec<ManagedProcess>,
     mem_profiler_chan: ProfilerChan,
 }
 
@@ -49,24 +60,39 @@ impl ProcessManager {
         }
     }
 
-    pub fn add(&mut self, receiver: ProcessReceiver, process: Process) {
+    pub fn add(
+        &mut self,
+        receiver: ProcessReceiver,
+        process: Process,
+        event_loop: Option<Weak<EventLoop>>,
+    ) {
         debug!("Adding process pid={}", process.pid());
-        self.processes.push((process, receiver));
+        self.processes.push(ManagedProcess {
+            process,
+            receiver,
+            event_loop,
+        });
     }
 
     pub fn register<'a>(&'a self, select: &mut Select<'a>) {
-        for (_, receiver) in &self.processes {
-            select.recv(receiver);
+        for managed_process in &self.processes {
+            select.recv(&managed_process.receiver);
         }
     }
 
     pub fn receiver_at(&self, index: usize) -> &ProcessReceiver {
-        let (_, receiver) = &self.processes[index];
-        receiver
+        &self.processes[index].receiver
     }
 
-    pub fn remove(&mut self, index: usize) {
-        let (mut process, _) = self.processes.swap_remove(index);
+    /// Remove a process whose lifeline was closed, returning its pid and, if the process
+    /// crashed, the event loop that ran in it. An event loop is only dropped once none of its
+    /// pipelines remain, so if it is still alive the process exited unexpectedly.
+    pub fn remove(&mut self, index: usize) -> (u32, Option<Rc<EventLoop>>) {
+        let ManagedProcess {
+            mut process,
+            event_loop,
+            ..
+        } = self.processes.swap_remove(index);
         debug!("Removing process pid={}", process.pid());
         // Unregister this process system memory profiler
         self.mem_profiler_chan
@@ -75,5 +101,114 @@ impl ProcessManager {
                 process.pid()
             )));
         process.wait();
+        let crashed_event_loop = event_loop.and_then(|event_loop| event_loop.upgrade());
+        (process.pid(), crashed_event_loop)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(unix)]
+    use std::process::Command;
+    use std::rc::Rc;
+    #[cfg(unix)]
+    use std::time::Duration;
+
+    #[cfg(unix)]
+    use crossbeam_channel::Select;
+    use ipc_channel::ipc;
+    #[cfg(unix)]
+    use ipc_channel::router::ROUTER;
+    use profile_traits::mem::ProfilerChan;
+
+    use super::{Process, ProcessManager};
+    use crate::event_loop::EventLoop;
+
+    #[test]
+    fn test_removed_process_returns_its_event_loop() {
+        let (profiler_sender, _profiler_receiver) = ipc::channel().unwrap();
+        let mut process_manager = ProcessManager::new(ProfilerChan(profiler_sender));
+        let (script_sender, _script_receiver) = ipc::channel().unwrap();
+        let crashed_event_loop = EventLoop::new(script_sender);
+        let (script_sender, _script_receiver) = ipc::channel().unwrap();
+        let exited_event_loop = EventLoop::new(script_sender);
+
+        let (_lifeline_sender, lifeline) = crossbeam_channel::unbounded();
+        process_manager.add(
+            lifeline,
+            Process::Sandboxed(1),
+            Some(Rc::downgrade(&crashed_event_loop)),
+        );
+        let (_lifeline_sender, lifeline) = crossbeam_channel::unbounded();
+        process_manager.add(
+            lifeline,
+            Process::Sandboxed(2),
+            Some(Rc::downgrade(&exited_event_loop)),
+        );
+        let (_lifeline_sender, lifeline) = crossbeam_channel::unbounded();
+        process_manager.add(lifeline, Process::Sandboxed(3), None);
+
+        // The event loop of a process that crashed is still in use by its pipelines.
+        let (pid, event_loop) = process_manager.remove(0);
+        assert_eq!(pid, 1);
+        assert!(event_loop.is_some_and(|event_loop| Rc::ptr_eq(&event_loop, &crashed_event_loop)));
+
+        // Removing a process moves the last one into its place.
+        let (pid, event_loop) = process_manager.remove(0);
+        assert_eq!(pid, 3);
+        assert!(event_loop.is_none());
+
+        // The event loop of a process that exited normally was dropped first.
+        drop(exited_event_loop);
+        let (pid, event_loop) = process_manager.remove(0);
+        assert_eq!(pid, 2);
+        assert!(event_loop.is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_crashed_content_process_is_detected() {
+        let (profiler_sender, _profiler_receiver) = ipc::channel().unwrap();
+        let mut process_manager = ProcessManager::new(ProfilerChan(profiler_sender));
+        let (script_sender, _script_receiver) = ipc::channel().unwrap();
+        let event_loop = EventLoop::new(script_sender);
+
+        // A content process holds the sending end of its lifeline, which is closed when the
+        // process exits. The constellation routes the receiving end like this.
+        let (lifeline_sender, lifeline_receiver) = ipc::channel::<()>().unwrap();
+        let (crossbeam_sender, lifeline) = crossbeam_channel::unbounded();
+        ROUTER.add_typed_route(
+            lifeline_receiver,
+            Box::new(move |message| {
+                let _ = crossbeam_sender.send(message);
+            }),
+        );
+        let child = Command::new("sh").args(["-c", "exit 1"]).spawn().unwrap();
+        let pid = child.id();
+        process_manager.add(
+            lifeline,
+            Process::Unsandboxed(child),
+            Some(Rc::downgrade(&event_loop)),
+        );
+        drop(lifeline_sender);
+
+        let mut select = Select::new();
+        process_manager.register(&mut select);
+        let operation = select
+            .select_timeout(Duration::from_secs(10))
+            .expect("The exit of the content process was not detected");
+        let index = operation.index();
+        assert!(
+            operation
+                .recv(process_manager.receiver_at(index))
+                .unwrap()
+                .is_err()
+        );
+        drop(select);
+
+        // The pipelines of the event loop are still alive, so the exit is reported as a crash.
+        let (removed_pid, crashed_event_loop) = process_manager.remove(index);
+        assert_eq!(removed_pid, pid);
+        assert!(crashed_event_loop.is_some_and(|crashed| Rc::ptr_eq(&crashed, &event_loop)));
     }
 }
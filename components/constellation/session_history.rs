@@ -158,6 +158,13 @@ impl NeedsToReload {
             NeedsToReload::Yes(..) => None,
         }
     }
+
+    /// Returns the id of the pipeline, whether or not it is still alive.
+    pub fn pipeline_id(&self) -> PipelineId {
+        match *self {
+            NeedsToReload::No(pipeline_id) | NeedsToReload::Yes(pipeline_id, _) => pipeline_id,
+        }
+    }
 }
 
 // Custom `PartialEq` that only compares the `PipelineId`s of the same variants while ignoring `LoadData`
@@ -54,6 +54,7 @@ mod from_compositor {
                 Self::AllowNavigationResponse(..) => target!("AllowNavigationResponse"),
                 Self::LoadUrl(..) => target!("LoadUrl"),
                 Self::ClearCache => target!("ClearCache"),
+                Self::UpdateHstsPreloadList(..) => target!("UpdateHstsPreloadList"),
                 Self::TraverseHistory(..) => target!("TraverseHistory"),
                 Self::ChangeViewportDetails(..) => target!("ChangeViewportDetails"),
                 Self::ThemeChange(..) => target!("ThemeChange"),
@@ -72,6 +73,8 @@ mod from_compositor {
                 Self::ExitFullScreen(_) => target!("ExitFullScreen"),
                 Self::MediaSessionAction(_) => target!("MediaSessionAction"),
                 Self::SetWebViewThrottled(_, _) => target!("SetWebViewThrottled"),
+                Self::SetWebViewHidden(_, _) => target!("SetWebViewHidden"),
+                Self::SetWebViewFrozen(_, _) => target!("SetWebViewFrozen"),
                 Self::SetScrollStates(..) => target!("SetScrollStates"),
                 Self::PaintMetric(..) => target!("PaintMetric"),
                 Self::EvaluateJavaScript(..) => target!("EvaluateJavaScript"),
@@ -90,6 +93,7 @@ mod from_compositor {
                 };
             }
             match self {
+                InputEvent::DragDrop(..) => target_variant!("DragDrop"),
                 InputEvent::EditingAction(..) => target_variant!("EditingAction"),
                 InputEvent::Gamepad(..) => target_variant!("Gamepad"),
                 InputEvent::Ime(..) => target_variant!("Ime"),
@@ -149,12 +153,17 @@ mod from_script {
                 Self::LoadComplete => target!("LoadComplete"),
                 Self::LoadUrl(..) => target!("LoadUrl"),
                 Self::AbortLoadUrl => target!("AbortLoadUrl"),
+                Self::NavigationResponseBlocked(..) => target!("NavigationResponseBlocked"),
+                Self::NavigationRedirectedToOtherSite { .. } => {
+                    target!("NavigationRedirectedToOtherSite")
+                },
                 Self::PostMessage { .. } => target!("PostMessage"),
                 Self::NavigatedToFragment(..) => target!("NavigatedToFragment"),
                 Self::TraverseHistory(..) => target!("TraverseHistory"),
                 Self::PushHistoryState(..) => target!("PushHistoryState"),
                 Self::ReplaceHistoryState(..) => target!("ReplaceHistoryState"),
                 Self::JointSessionHistoryLength(..) => target!("JointSessionHistoryLength"),
+                Self::TraverseNavigableHistory(..) => target!("TraverseNavigableHistory"),
                 Self::RemoveIFrame(..) => target!("RemoveIFrame"),
                 Self::SetThrottledComplete(..) => target!("SetThrottledComplete"),
                 Self::ScriptLoadedURLInIFrame(..) => target!("ScriptLoadedURLInIFrame"),
@@ -164,8 +173,12 @@ mod from_script {
                 Self::SetDocumentState(..) => target!("SetDocumentState"),
                 Self::SetLayoutEpoch(..) => target!("SetLayoutEpoch"),
                 Self::SetFinalUrl(..) => target!("SetFinalUrl"),
+                Self::SetCrossOriginOpenerPolicy(..) => target!("SetCrossOriginOpenerPolicy"),
                 Self::TouchEventProcessed(..) => target!("TouchEventProcessed"),
                 Self::LogEntry(..) => target!("LogEntry"),
+                Self::PersistFormState(..) => target!("PersistFormState"),
+                Self::PersistScrollState(..) => target!("PersistScrollState"),
+                Self::Prerender(..) => target!("Prerender"),
                 Self::DiscardDocument => target!("DiscardDocument"),
                 Self::DiscardTopLevelBrowsingContext => target!("DiscardTopLevelBrowsingContext"),
                 Self::PipelineExited => target!("PipelineExited"),
@@ -210,6 +223,17 @@ mod from_script {
                 Self::Keyboard(..) => target_variant!("Keyboard"),
                 Self::ClearClipboard(..) => target_variant!("ClearClipboard"),
                 Self::GetClipboardText(..) => target_variant!("GetClipboardText"),
+                Self::GetGeolocationPosition(..) => target_variant!("GetGeolocationPosition"),
+                Self::MakeCredential(..) => target_variant!("MakeCredential"),
+                Self::GetAssertion(..) => target_variant!("GetAssertion"),
+                Self::IsUserVerifyingPlatformAuthenticatorAvailable(..) => {
+                    target_variant!("IsUserVerifyingPlatformAuthenticatorAvailable")
+                },
+                Self::SelectPasswordCredential(..) => target_variant!("SelectPasswordCredential"),
+                Self::SavePasswordCredential(..) => target_variant!("SavePasswordCredential"),
+                Self::GetClipboardContents(..) => target_variant!("GetClipboardContents"),
+                Self::StartDrag(..) => target_variant!("StartDrag"),
+                Self::EndDrag(..) => target_variant!("EndDrag"),
                 Self::SetClipboardText(..) => target_variant!("SetClipboardText"),
                 Self::SetCursor(..) => target_variant!("SetCursor"),
                 Self::NewFavicon(..) => target_variant!("NewFavicon"),
@@ -237,6 +261,7 @@ mod from_script {
                 Self::PromptPermission(..) => target_variant!("PromptPermission"),
                 Self::ShowIME(..) => target_variant!("ShowIME"),
                 Self::HideIME(..) => target_variant!("HideIME"),
+                Self::UpdateIMECaretRect(..) => target_variant!("UpdateIMECaretRect"),
                 Self::ReportProfile(..) => target_variant!("ReportProfile"),
                 Self::MediaSessionEvent(..) => target_variant!("MediaSessionEvent"),
                 Self::OnDevtoolsStarted(..) => target_variant!("OnDevtoolsStarted"),
@@ -245,6 +270,7 @@ mod from_script {
                 Self::StopGamepadHapticEffect(..) => target_variant!("StopGamepadHapticEffect"),
                 Self::ShutdownComplete => target_variant!("ShutdownComplete"),
                 Self::ShowNotification(..) => target_variant!("ShowNotification"),
+                Self::RequestPrint(..) => target_variant!("RequestPrint"),
                 Self::ShowFormControl(..) => target_variant!("ShowFormControl"),
                 Self::FinishJavaScriptEvaluation(..) => {
                     target_variant!("FinishJavaScriptEvaluation")
@@ -11,6 +11,7 @@ use chrono::{Local, LocalResult, TimeZone};
 use devtools_traits::{HttpRequest as DevtoolsHttpRequest, HttpResponse as DevtoolsHttpResponse};
 use headers::{ContentLength, ContentType, Cookie, HeaderMapExt};
 use http::{HeaderMap, Method, header};
+use net_traits::ServerTimingMetric;
 use net_traits::request::Destination as RequestDestination;
 use serde::Serialize;
 use serde_json::{Map, Value};
@@ -42,6 +43,7 @@ pub struct NetworkEventActor {
     pub total_time: Duration,
     pub security_state: String,
     pub event_timing: Option<Timings>,
+    pub server_timings: Vec<ServerTimingMetric>,
     pub watcher_name: String,
 }
 
@@ -182,6 +184,7 @@ struct GetEventTimingsReply {
     from: String,
     timings: Timings,
     total_time: u64,
+    server_timings: Vec<ServerTimingMetric>,
 }
 
 #[derive(Serialize)]
@@ -320,6 +323,7 @@ impl Actor for NetworkEventActor {
                     from: self.name(),
                     timings: timings_obj,
                     total_time: total,
+                    server_timings: self.server_timings.clone(),
                 };
                 request.reply_final(&msg)?
             },
@@ -366,6 +370,7 @@ impl NetworkEventActor {
             total_time: Duration::ZERO,
             security_state: "insecure".to_owned(),
             event_timing: None,
+            server_timings: vec![],
             watcher_name,
         }
     }
@@ -392,6 +397,7 @@ impl NetworkEventActor {
         self.response_content = Self::response_content(&response);
         self.response_body = response.body.clone();
         self.response_headers_raw = response.headers.clone();
+        self.server_timings = response.server_timing;
     }
 
     pub fn event_actor(&self) -> EventActor {
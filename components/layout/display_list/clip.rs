@@ -4,15 +4,27 @@
 
 use app_units::Au;
 use base::id::ScrollTreeNodeId;
+use kurbo::{BezPath, PathEl};
+use style::values::computed::LengthPercentage;
 use style::values::computed::basic_shape::{BasicShape, ClipPath};
 use style::values::computed::length_percentage::NonNegativeLengthPercentage;
 use style::values::computed::position::Position;
-use style::values::generics::basic_shape::{GenericShapeRadius, ShapeBox, ShapeGeometryBox};
+use style::values::generics::basic_shape::{
+    GenericPathOrShapeFunction, GenericPolygon, GenericShapeRadius, Path, ShapeBox,
+    ShapeGeometryBox,
+};
 use style::values::generics::position::GenericPositionOrAuto;
-use webrender_api::BorderRadius;
-use webrender_api::units::{LayoutRect, LayoutSideOffsets, LayoutSize};
+use style_traits::ToCss;
+use webrender_api::units::{LayoutPoint, LayoutRect, LayoutSideOffsets, LayoutSize};
+use webrender_api::{BorderRadius, FillRule};
 
-use super::{BuilderForBoxFragment, compute_margin_box_radius, normalize_radii};
+use super::{BuilderForBoxFragment, ToWebRender, compute_margin_box_radius, normalize_radii};
+
+/// The maximum number of points of a polygon clip supported by WebRender.
+const MAX_CLIP_POLYGON_POINTS: usize = 32;
+
+/// The maximum distance between a `path()` and the polygon it is flattened to, in pixels.
+const CLIP_PATH_FLATTENING_TOLERANCE: f64 = 0.25;
 
 /// An identifier for a clip used during StackingContextTree construction. This is a simple index in
 /// a [`ClipStore`]s vector of clips.
@@ -34,6 +46,16 @@ pub(crate) struct Clip {
     pub rect: LayoutRect,
     pub parent_scroll_node_id: ScrollTreeNodeId,
     pub parent_clip_id: ClipId,
+    /// A polygon that further restricts this clip, in which case [`Self::rect`] is its bounding
+    /// rectangle and [`Self::radii`] are zero.
+    pub polygon: Option<ClipPolygon>,
+}
+
+/// The polygon of a `clip-path: polygon()` or `clip-path: path()` clip.
+#[derive(Clone)]
+pub(crate) struct ClipPolygon {
+    pub points: Vec<LayoutPoint>,
+    pub fill_rule: FillRule,
 }
 
 /// A simple vector of [`Clip`] that is built during `StackingContextTree` construction.
@@ -61,10 +83,40 @@ impl StackingContextTreeClipStore {
             rect,
             parent_scroll_node_id,
             parent_clip_id,
+            polygon: None,
         });
         id
     }
 
+    /// Add a clip to the given polygon. Returns `None` if WebRender can't clip to it.
+    fn add_polygon(
+        &mut self,
+        polygon: ClipPolygon,
+        parent_scroll_node_id: ScrollTreeNodeId,
+        parent_clip_id: ClipId,
+    ) -> Option<ClipId> {
+        // TODO: Polygons with more points need a mask image rasterized by Servo.
+        if polygon.points.len() > MAX_CLIP_POLYGON_POINTS {
+            return None;
+        }
+        let rect = LayoutRect::from_points(&polygon.points);
+        let id = ClipId(self.0.len());
+        self.0.push(Clip {
+            id,
+            radii: BorderRadius::zero(),
+            rect,
+            parent_scroll_node_id,
+            parent_clip_id,
+            polygon: Some(polygon),
+        });
+        Some(id)
+    }
+
+    /// Whether any of the clips is a polygon, which WebRender needs a mask image for.
+    pub(crate) fn has_polygons(&self) -> bool {
+        self.0.iter().any(|clip| clip.polygon.is_some())
+    }
+
     pub(super) fn add_for_clip_path(
         &mut self,
         clip_path: ClipPath,
@@ -72,11 +124,20 @@ impl StackingContextTreeClipStore {
         parent_clip_chain_id: &ClipId,
         fragment_builder: BuilderForBoxFragment,
     ) -> Option<ClipId> {
+        // > For elements with associated CSS layout box, the used value for fill-box is
+        // > content-box and for stroke-box and view-box is border-box.
+        // <https://drafts.fxtf.org/css-masking/#typedef-geometry-box>
+        let used_shape_box = |geometry_box: &ShapeGeometryBox| match geometry_box {
+            ShapeGeometryBox::ShapeBox(shape_box) => *shape_box,
+            ShapeGeometryBox::FillBox => ShapeBox::ContentBox,
+            ShapeGeometryBox::ElementDependent |
+            ShapeGeometryBox::StrokeBox |
+            ShapeGeometryBox::ViewBox => ShapeBox::BorderBox,
+        };
         let geometry_box = match clip_path {
-            ClipPath::Shape(_, ShapeGeometryBox::ShapeBox(shape_box)) => shape_box,
-            ClipPath::Shape(_, ShapeGeometryBox::ElementDependent) => ShapeBox::BorderBox,
-            ClipPath::Box(ShapeGeometryBox::ShapeBox(shape_box)) => shape_box,
-            ClipPath::Box(ShapeGeometryBox::ElementDependent) => ShapeBox::BorderBox,
+            ClipPath::Shape(_, ref geometry_box) | ClipPath::Box(ref geometry_box) => {
+                used_shape_box(geometry_box)
+            },
             _ => return None,
         };
         let layout_rect = match geometry_box {
@@ -94,7 +155,19 @@ impl StackingContextTreeClipStore {
                         parent_scroll_node_id,
                         parent_clip_chain_id,
                     ),
-                BasicShape::Polygon(_) | BasicShape::PathOrShape(_) => None,
+                BasicShape::Polygon(polygon) => self.add_polygon(
+                    polygon_for_clip_path(&polygon, layout_rect),
+                    *parent_scroll_node_id,
+                    *parent_clip_chain_id,
+                ),
+                BasicShape::PathOrShape(GenericPathOrShapeFunction::Path(path)) => self
+                    .add_polygon(
+                        polygon_for_path(&path, layout_rect)?,
+                        *parent_scroll_node_id,
+                        *parent_clip_chain_id,
+                    ),
+                // TODO: The shape() function is not supported yet.
+                BasicShape::PathOrShape(GenericPathOrShapeFunction::Shape(_)) => None,
             }
         } else {
             Some(self.add(
@@ -253,6 +326,52 @@ impl StackingContextTreeClipStore {
     }
 }
 
+/// The points of a `polygon()`, resolved against the given reference box.
+fn polygon_for_clip_path(
+    polygon: &GenericPolygon<LengthPercentage>,
+    reference_box: LayoutRect,
+) -> ClipPolygon {
+    let width = Au::from_f32_px(reference_box.width());
+    let height = Au::from_f32_px(reference_box.height());
+    let points = polygon
+        .coordinates
+        .iter()
+        .map(|coordinate| {
+            reference_box.min +
+                LayoutSize::new(
+                    coordinate.0.to_used_value(width).to_f32_px(),
+                    coordinate.1.to_used_value(height).to_f32_px(),
+                )
+                .to_vector()
+        })
+        .collect();
+    ClipPolygon {
+        points,
+        fill_rule: polygon.fill.to_webrender(),
+    }
+}
+
+/// The polygon that a `path()` is flattened to, placed in the given reference box. Returns
+/// `None` if the path can't be parsed.
+///
+/// TODO: Paths with several subpaths are flattened to a single polygon, which joins them.
+fn polygon_for_path(path: &Path, reference_box: LayoutRect) -> Option<ClipPolygon> {
+    // The path data serializes as a quoted SVG path string.
+    let path_data = path.path.to_css_string();
+    let bezier_path = BezPath::from_svg(path_data.trim_matches('"')).ok()?;
+
+    let mut points = Vec::new();
+    bezier_path.flatten(CLIP_PATH_FLATTENING_TOLERANCE, |element| match element {
+        PathEl::MoveTo(point) | PathEl::LineTo(point) => points
+            .push(reference_box.min + LayoutSize::new(point.x as f32, point.y as f32).to_vector()),
+        _ => {},
+    });
+    Some(ClipPolygon {
+        points,
+        fill_rule: path.fill.to_webrender(),
+    })
+}
+
 fn compute_shape_radius(
     center: f32,
     radius: &GenericShapeRadius<NonNegativeLengthPercentage>,
@@ -9,9 +9,11 @@ use style::computed_values::mix_blend_mode::T as ComputedMixBlendMode;
 use style::computed_values::text_decoration_style::T as ComputedTextDecorationStyle;
 use style::computed_values::transform_style::T as ComputedTransformStyle;
 use style::values::computed::Filter as ComputedFilter;
+use style::values::generics::basic_shape::FillRule as ComputedFillRule;
 use style::values::specified::border::BorderImageRepeatKeyword;
 use webrender_api::{
-    FilterOp, ImageRendering, LineStyle, MixBlendMode, RepeatMode, Shadow, TransformStyle, units,
+    FillRule, FilterOp, ImageRendering, LineStyle, MixBlendMode, RepeatMode, Shadow,
+    TransformStyle, units,
 };
 
 use crate::geom::{PhysicalPoint, PhysicalRect, PhysicalSides, PhysicalSize};
@@ -162,3 +164,14 @@ impl ToWebRender for ComputedImageRendering {
         }
     }
 }
+
+impl ToWebRender for ComputedFillRule {
+    type Type = FillRule;
+
+    fn to_webrender(&self) -> Self::Type {
+        match self {
+            ComputedFillRule::Nonzero => FillRule::Nonzero,
+            ComputedFillRule::Evenodd => FillRule::Evenodd,
+        }
+    }
+}
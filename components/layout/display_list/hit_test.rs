@@ -8,15 +8,15 @@ use app_units::Au;
 use base::id::ScrollTreeNodeId;
 use embedder_traits::Cursor;
 use euclid::{Box2D, Point2D, Point3D, Vector2D};
-use kurbo::{Ellipse, Shape};
+use kurbo::{BezPath, Ellipse, Point, Shape};
 use layout_api::{ElementsFromPointFlags, ElementsFromPointResult};
 use style::computed_values::backface_visibility::T as BackfaceVisibility;
 use style::computed_values::pointer_events::T as PointerEvents;
 use style::computed_values::visibility::T as Visibility;
 use style::properties::ComputedValues;
 use style::values::computed::ui::CursorKind;
-use webrender_api::BorderRadius;
 use webrender_api::units::{LayoutPoint, LayoutRect, LayoutSize, LayoutTransform, RectExt};
+use webrender_api::{BorderRadius, FillRule};
 
 use crate::display_list::clip::{Clip, ClipId};
 use crate::display_list::stacking_context::StackingContextSection;
@@ -128,7 +128,29 @@ impl<'a> HitTest<'a> {
 
 impl Clip {
     fn contains(&self, point: LayoutPoint) -> bool {
-        rounded_rect_contains_point(self.rect, &self.radii, point)
+        let Some(polygon) = &self.polygon else {
+            return rounded_rect_contains_point(self.rect, &self.radii, point);
+        };
+        if !self.rect.contains(point) {
+            return false;
+        }
+
+        let mut path = BezPath::new();
+        for (index, polygon_point) in polygon.points.iter().enumerate() {
+            let polygon_point = Point::new(polygon_point.x.into(), polygon_point.y.into());
+            if index == 0 {
+                path.move_to(polygon_point);
+            } else {
+                path.line_to(polygon_point);
+            }
+        }
+        path.close_path();
+
+        let winding = path.winding(Point::new(point.x.into(), point.y.into()));
+        match polygon.fill_rule {
+            FillRule::Nonzero => winding != 0,
+            FillRule::Evenodd => winding % 2 != 0,
+        }
     }
 }
 
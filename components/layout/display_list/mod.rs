@@ -3,6 +3,7 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 use std::cell::{OnceCell, RefCell};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use app_units::{AU_PER_PX, Au};
@@ -11,23 +12,26 @@ use clip::{Clip, ClipId};
 use compositing_traits::display_list::{CompositorDisplayListInfo, SpatialTreeNodeInfo};
 use euclid::{Point2D, Scale, SideOffsets2D, Size2D, UnknownUnit, Vector2D};
 use fonts::GlyphStore;
+use fxhash::FxHashSet;
 use gradient::WebRenderGradient;
-use layout_api::ReflowRequest;
+use layout_api::{ContentfulPaint, ReflowRequest};
 use net_traits::image_cache::Image as CachedImage;
 use range::Range as ServoRange;
 use servo_arc::Arc as ServoArc;
 use servo_config::opts::DebugOptions;
 use servo_geometry::MaxRect;
 use style::Zero;
+use style::animation::AnimationSetKey;
 use style::color::{AbsoluteColor, ColorSpace};
 use style::computed_values::border_image_outset::T as BorderImageOutset;
 use style::computed_values::text_decoration_style::{
     T as ComputedTextDecorationStyle, T as TextDecorationStyle,
 };
 use style::dom::OpaqueNode;
-use style::properties::ComputedValues;
 use style::properties::longhands::visibility::computed_value::T as Visibility;
 use style::properties::style_structs::Border;
+use style::properties::{ComputedValues, PropertyDeclarationBlock};
+use style::shared_lock::{Locked, SharedRwLock};
 use style::values::computed::{
     BorderImageSideWidth, BorderImageWidth, BorderStyle, LengthPercentage,
     NonNegativeLengthOrNumber, NumberOrPercentage, OutlineStyle,
@@ -39,8 +43,8 @@ use style_traits::{CSSPixel as StyloCSSPixel, DevicePixel as StyloDevicePixel};
 use webrender_api::units::{DeviceIntSize, DevicePixel, LayoutPixel, LayoutRect, LayoutSize};
 use webrender_api::{
     self as wr, BorderDetails, BorderRadius, BoxShadowClipMode, BuiltDisplayList, ClipChainId,
-    ClipMode, CommonItemProperties, ComplexClipRegion, NinePatchBorder, NinePatchBorderSource,
-    PrimitiveFlags, PropertyBinding, SpatialId, SpatialTreeItemKey, units,
+    ClipMode, CommonItemProperties, ComplexClipRegion, ImageKey, ImageMask, NinePatchBorder,
+    NinePatchBorderSource, PrimitiveFlags, PropertyBinding, SpatialId, SpatialTreeItemKey, units,
 };
 use wr::units::LayoutVector2D;
 
@@ -56,7 +60,7 @@ use crate::geom::{
     LengthPercentageOrAuto, PhysicalPoint, PhysicalRect, PhysicalSides, PhysicalSize,
 };
 use crate::replaced::NaturalSizes;
-use crate::style_ext::{BorderStyleColor, ComputedValuesExt};
+use crate::style_ext::{BorderStyleColor, ComputedValuesExt, is_animated_effect_property};
 
 mod background;
 mod clip;
@@ -115,6 +119,37 @@ pub(crate) struct DisplayListBuilder<'a> {
 
     /// The device pixel ratio used for this `Document`'s display list.
     device_pixel_ratio: Scale<f32, StyloCSSPixel, StyloDevicePixel>,
+
+    /// The area of the images and text painted in this display list for each element, along
+    /// with whether any of it is an image. This is returned to script in order to report
+    /// Largest Contentful Paint and Element Timing entries.
+    contentful_paints: HashMap<OpaqueNode, (bool, PhysicalRect<Au>)>,
+
+    /// The border box of the first box fragment painted in this display list for each element,
+    /// which is used to detect elements that moved between two display lists.
+    box_rects: HashMap<OpaqueNode, PhysicalRect<Au>>,
+
+    /// Whether this display list paints any text, image or iframe, which makes it count
+    /// for First Contentful Paint.
+    /// See <https://w3c.github.io/paint-timing/#first-contentful-paint>.
+    is_contentful: bool,
+
+    /// An opaque image to use as the mask of polygon clips, if there are any.
+    clip_mask_image_key: Option<ImageKey>,
+
+    /// The elements and pseudo-elements with a running animation or transition of their
+    /// transform or opacity, which are promoted to their own WebRender stacking contexts.
+    animated_effect_keys: FxHashSet<AnimationSetKey>,
+}
+
+/// Information about the elements painted in a display list, gathered while building it.
+pub(crate) struct PaintedElements {
+    /// The images and text painted for each element.
+    pub contentful_paints: Vec<ContentfulPaint>,
+    /// The border box of the first box fragment painted for each element.
+    pub box_rects: HashMap<OpaqueNode, PhysicalRect<Au>>,
+    /// Whether any text, image or iframe was painted.
+    pub is_contentful: bool,
 }
 
 struct InspectorHighlight {
@@ -142,6 +177,36 @@ struct HighlightTraversalState {
     maybe_box_fragment: Option<ArcRefCell<BoxFragment>>,
 }
 
+/// Collect the keys of the elements and pseudo-elements whose running animations or
+/// transitions currently affect one of the properties in [`is_animated_effect_property`].
+fn animated_effect_keys(reflow_request: &ReflowRequest) -> FxHashSet<AnimationSetKey> {
+    // The declaration blocks are created just for this check, so they do not need to
+    // share a lock with the document.
+    let shared_lock = SharedRwLock::new();
+    let guard = shared_lock.read();
+    let animations = &reflow_request.animations;
+    let now = reflow_request.animation_timeline_value;
+    let affects_effects = |block: Option<ServoArc<Locked<PropertyDeclarationBlock>>>| {
+        block.is_some_and(|block| {
+            block
+                .read_with(&guard)
+                .declarations()
+                .iter()
+                .any(|declaration| is_animated_effect_property(declaration.id()))
+        })
+    };
+
+    // Looking up the declarations takes the lock of the animation sets again, so release it
+    // before doing so.
+    let keys: Vec<AnimationSetKey> = animations.sets.read().keys().cloned().collect();
+    keys.into_iter()
+        .filter(|key| {
+            affects_effects(animations.get_animation_declarations(key, now, &shared_lock)) ||
+                affects_effects(animations.get_transition_declarations(key, now, &shared_lock))
+        })
+        .collect()
+}
+
 impl InspectorHighlight {
     fn for_node(node: OpaqueNode) -> Self {
         Self {
@@ -159,7 +224,8 @@ impl DisplayListBuilder<'_> {
         image_resolver: Arc<ImageResolver>,
         device_pixel_ratio: Scale<f32, StyloCSSPixel, StyloDevicePixel>,
         debug: &DebugOptions,
-    ) -> BuiltDisplayList {
+        clip_mask_image_key: Option<ImageKey>,
+    ) -> (BuiltDisplayList, PaintedElements) {
         // Build the rest of the display list which inclues all of the WebRender primitives.
         let compositor_info = &mut stacking_context_tree.compositor_info;
         let pipeline_id = compositor_info.pipeline_id;
@@ -191,6 +257,11 @@ impl DisplayListBuilder<'_> {
             clip_map: Default::default(),
             image_resolver,
             device_pixel_ratio,
+            contentful_paints: Default::default(),
+            box_rects: Default::default(),
+            is_contentful: false,
+            clip_mask_image_key,
+            animated_effect_keys: animated_effect_keys(reflow_request),
         };
 
         builder.add_all_spatial_nodes();
@@ -208,9 +279,24 @@ impl DisplayListBuilder<'_> {
         stacking_context_tree
             .root_stacking_context
             .build_display_list(&mut builder);
+        builder.paint_view_transition_snapshot(reflow_request);
         builder.paint_dom_inspector_highlight();
 
-        webrender_display_list_builder.end().1
+        let contentful_paints = builder
+            .contentful_paints
+            .into_iter()
+            .map(|(node, (is_image, rect))| ContentfulPaint {
+                node: node.into(),
+                is_image,
+                rect: rect.to_untyped(),
+            })
+            .collect();
+        let painted_elements = PaintedElements {
+            contentful_paints,
+            box_rects: builder.box_rects,
+            is_contentful: builder.is_contentful,
+        };
+        (webrender_display_list_builder.end().1, painted_elements)
     }
 
     fn wr(&mut self) -> &mut wr::DisplayListBuilder {
@@ -222,7 +308,37 @@ impl DisplayListBuilder<'_> {
     }
 
     fn mark_is_contentful(&mut self) {
-        self.compositor_info.is_contentful = true;
+        self.is_contentful = true;
+    }
+
+    /// Record that the given area of the element of a fragment was painted with an image
+    /// or text.
+    fn record_contentful_paint(
+        &mut self,
+        tag: Option<Tag>,
+        is_image: bool,
+        rect: PhysicalRect<Au>,
+    ) {
+        // The content of pseudo-elements is not attributed to any element.
+        let Some(tag) = tag.filter(|tag| tag.pseudo.is_none()) else {
+            return;
+        };
+        self.contentful_paints
+            .entry(tag.node)
+            .and_modify(|(painted_image, painted_rect)| {
+                *painted_image |= is_image;
+                *painted_rect = painted_rect.union(&rect);
+            })
+            .or_insert((is_image, rect));
+    }
+
+    /// Record the border box of a box fragment of an element, unless one was already
+    /// recorded for it.
+    fn record_box_rect(&mut self, tag: Option<Tag>, rect: PhysicalRect<Au>) {
+        let Some(tag) = tag.filter(|tag| tag.pseudo.is_none()) else {
+            return;
+        };
+        self.box_rects.entry(tag.node).or_insert(rect);
     }
 
     fn spatial_id(&self, id: ScrollTreeNodeId) -> SpatialId {
@@ -358,7 +474,21 @@ impl DisplayListBuilder<'_> {
         );
 
         let spatial_id = self.spatial_id(clip.parent_scroll_node_id);
-        let new_clip_id = if clip.radii.is_zero() {
+        let new_clip_id = if let Some(polygon) = &clip.polygon {
+            // WebRender clips polygons as part of an image mask, so an opaque image is used.
+            match self.clip_mask_image_key {
+                Some(image) => self.wr().define_clip_image_mask(
+                    spatial_id,
+                    ImageMask {
+                        image,
+                        rect: clip.rect,
+                    },
+                    &polygon.points,
+                    polygon.fill_rule,
+                ),
+                None => self.wr().define_clip_rect(spatial_id, clip.rect),
+            }
+        } else if clip.radii.is_zero() {
             self.wr().define_clip_rect(spatial_id, clip.rect)
         } else {
             self.wr().define_clip_rounded_rect(
@@ -405,6 +535,7 @@ impl DisplayListBuilder<'_> {
             rect,
             parent_scroll_node_id: self.current_scroll_node_id,
             parent_clip_id: self.current_clip_id,
+            polygon: None,
         }))
     }
 
@@ -425,6 +556,52 @@ impl DisplayListBuilder<'_> {
     }
 
     /// Draw highlights around the node that is currently hovered in the devtools.
+    /// Paint the old state of the document over the viewport while a view transition
+    /// cross-fades from it to the new state.
+    /// <https://drafts.csswg.org/css-view-transitions-1/#view-transition-image-pair-pseudo>
+    fn paint_view_transition_snapshot(&mut self, reflow_request: &ReflowRequest) {
+        let Some(snapshot) = reflow_request.view_transition_snapshot else {
+            return;
+        };
+
+        let spatial_id = self.spatial_id(self.compositor_info.root_reference_frame_id);
+        self.wr().push_stacking_context(
+            units::LayoutPoint::zero(), // origin
+            spatial_id,
+            PrimitiveFlags::default(),
+            None, // clip_chain_id
+            wr::TransformStyle::Flat,
+            wr::MixBlendMode::Normal,
+            &[wr::FilterOp::Opacity(
+                snapshot.opacity.into(),
+                snapshot.opacity,
+            )],
+            &[], // filter_datas
+            &[], // filter_primitives
+            wr::RasterSpace::Screen,
+            wr::StackingContextFlags::empty(),
+            None, // snapshot
+        );
+
+        let viewport_rect =
+            LayoutRect::from_size(self.compositor_info.viewport_details.layout_size());
+        let properties = wr::CommonItemProperties {
+            clip_rect: viewport_rect,
+            spatial_id,
+            clip_chain_id: ClipChainId::INVALID,
+            flags: PrimitiveFlags::default(),
+        };
+        self.wr().push_image(
+            &properties,
+            viewport_rect,
+            wr::ImageRendering::Auto,
+            wr::AlphaType::PremultipliedAlpha,
+            snapshot.image_key,
+            wr::ColorF::WHITE,
+        );
+        self.wr().pop_stacking_context();
+    }
+
     fn paint_dom_inspector_highlight(&mut self) {
         let Some(highlight) = self
             .inspector_highlight
@@ -602,12 +779,20 @@ impl Fragment {
             Fragment::Box(box_fragment) | Fragment::Float(box_fragment) => {
                 let box_fragment = &*box_fragment.borrow();
                 match box_fragment.style.get_inherited_box().visibility {
-                    Visibility::Visible => BuilderForBoxFragment::new(
-                        box_fragment,
-                        containing_block,
-                        is_collapsed_table_borders,
-                    )
-                    .build(builder, section),
+                    Visibility::Visible => {
+                        builder.record_box_rect(
+                            self.tag(),
+                            box_fragment
+                                .border_rect()
+                                .translate(containing_block.origin.to_vector()),
+                        );
+                        BuilderForBoxFragment::new(
+                            box_fragment,
+                            containing_block,
+                            is_collapsed_table_borders,
+                        )
+                        .build(builder, section)
+                    },
                     Visibility::Hidden => (),
                     Visibility::Collapse => (),
                 }
@@ -619,15 +804,15 @@ impl Fragment {
                     Visibility::Visible => {
                         builder.mark_is_contentful();
 
+                        let image_rect = image.rect.translate(containing_block.origin.to_vector());
+                        builder.record_contentful_paint(self.tag(), true, image_rect);
+
                         let image_rendering = image
                             .style
                             .get_inherited_box()
                             .image_rendering
                             .to_webrender();
-                        let rect = image
-                            .rect
-                            .translate(containing_block.origin.to_vector())
-                            .to_webrender();
+                        let rect = image_rect.to_webrender();
                         let clip = image
                             .clip
                             .translate(containing_block.origin.to_vector())
@@ -721,6 +906,7 @@ impl Fragment {
         if glyphs.is_empty() {
             return;
         }
+        builder.record_contentful_paint(self.tag(), false, rect);
 
         let parent_style = fragment.inline_styles.style.borrow();
         let color = parent_style.clone_color();
@@ -20,6 +20,7 @@ use euclid::default::{Point2D, Rect, Size2D};
 use log::warn;
 use servo_config::opts::DebugOptions;
 use style::Zero;
+use style::animation::AnimationSetKey;
 use style::color::AbsoluteColor;
 use style::computed_values::float::T as ComputedFloat;
 use style::computed_values::mix_blend_mode::T as ComputedMixBlendMode;
@@ -56,8 +57,9 @@ pub(crate) struct ContainingBlock {
     scroll_node_id: ScrollTreeNodeId,
 
     /// The size of the parent scroll frame of this containing block, used for resolving
-    /// sticky margins. If this is None, then this is a direct descendant of a reference
-    /// frame and sticky positioning isn't taken into account.
+    /// sticky margins. If this is None, then there is no scroll frame that this containing
+    /// block scrolls with (as for descendants of the root reference frame) and sticky
+    /// positioning isn't taken into account.
     scroll_frame_size: Option<LayoutSize>,
 
     /// The [`ClipId`] to use for the children of this containing block.
@@ -143,7 +145,6 @@ impl StackingContextTree {
         fragment_tree: &FragmentTree,
         viewport_details: ViewportDetails,
         pipeline_id: wr::PipelineId,
-        first_reflow: bool,
         debug: &DebugOptions,
     ) -> Self {
         let scrollable_overflow = fragment_tree.scrollable_overflow();
@@ -160,7 +161,6 @@ impl StackingContextTree {
             // This epoch is set when the WebRender display list is built. For now use a dummy value.
             wr::Epoch(0),
             fragment_tree.viewport_scroll_sensitivity,
-            first_reflow,
         );
 
         let root_scroll_node_id = compositor_info.root_scroll_node_id;
@@ -542,10 +542,16 @@ impl StackingContext {
         // actually need to create a stacking context, just avoid creating one.
         let style = &fragment.style;
         let effects = style.get_effects();
+        let has_animated_effects = fragment.base.tag.is_some_and(|tag| {
+            builder
+                .animated_effect_keys
+                .contains(&AnimationSetKey::new(tag.node, tag.pseudo))
+        });
         if effects.filter.0.is_empty() &&
             effects.opacity == 1.0 &&
             effects.mix_blend_mode == ComputedMixBlendMode::Normal &&
             !style.has_effective_transform_or_perspective(FragmentFlags::empty()) &&
+            !style.is_layerization_candidate(FragmentFlags::empty(), has_animated_effects) &&
             style.clone_clip_path() == ClipPath::None
         {
             return false;
@@ -1040,12 +1046,14 @@ impl BoxFragment {
             self.style
                 .establishes_containing_block_for_all_descendants(self.base.flags)
         );
+        // The reference frame still scrolls with the nearest scroll frame of its containing
+        // block, so sticky descendants keep being constrained by that scroll frame.
         let adjusted_containing_block = ContainingBlock::new(
             containing_block
                 .rect
                 .translate(-reference_frame_data.origin.to_vector()),
             new_spatial_id,
-            None,
+            containing_block.scroll_frame_size,
             containing_block.clip_id,
         );
         let new_containing_block_info =
@@ -1525,7 +1533,8 @@ impl BoxFragment {
         let scroll_frame_size_for_resolve = match scroll_frame_size {
             Some(size) => size,
             None => {
-                // This is a direct descendant of a reference frame.
+                // This box doesn't scroll with any scroll frame, e.g. it's a descendant of
+                // a `position: fixed` box.
                 &stacking_context_tree
                     .compositor_info
                     .viewport_details
@@ -1565,18 +1574,28 @@ impl BoxFragment {
             .to_webrender();
 
         // Position:sticky elements are always restricted based on the size and position of their
-        // containing block.
+        // containing block. It's the margin box of the element which must stay within it.
+        // <https://drafts.csswg.org/css-position-3/#stickypos-insets>
+        let margin_rect = self
+            .margin_rect()
+            .translate(containing_block_rect.origin.to_vector())
+            .to_webrender();
         let containing_block_rect = containing_block_rect.to_webrender();
 
         // This is the minimum negative offset and then the maximum positive offset. We just
         // specify every edge, but if the corresponding margin is None, that offset has no effect.
-        let vertical_offset_bounds = wr::StickyOffsetBounds::new(
-            containing_block_rect.min.y - frame_rect.min.y,
-            containing_block_rect.max.y - frame_rect.max.y,
+        // If the margin box is already overflowing the containing block, the element can't move
+        // in that direction at all.
+        let offset_bounds = |min_space: f32, max_space: f32| {
+            wr::StickyOffsetBounds::new(min_space.min(0.0), max_space.max(0.0))
+        };
+        let vertical_offset_bounds = offset_bounds(
+            containing_block_rect.min.y - margin_rect.min.y,
+            containing_block_rect.max.y - margin_rect.max.y,
         );
-        let horizontal_offset_bounds = wr::StickyOffsetBounds::new(
-            containing_block_rect.min.x - frame_rect.min.x,
-            containing_block_rect.max.x - frame_rect.max.x,
+        let horizontal_offset_bounds = offset_bounds(
+            containing_block_rect.min.x - margin_rect.min.x,
+            containing_block_rect.max.x - margin_rect.max.x,
         );
 
         let margins = SideOffsets2D::new(
@@ -1602,9 +1621,12 @@ impl BoxFragment {
         &self,
         containing_block_rect: &PhysicalRect<Au>,
     ) -> Option<ReferenceFrameData> {
+        // Boxes whose transform is hinted to change get a reference frame even if their
+        // transform is currently the identity, so that animating it only updates that frame.
         if !self
             .style
-            .has_effective_transform_or_perspective(self.base.flags)
+            .has_effective_transform_or_perspective(self.base.flags) &&
+            !self.style.will_change_transform(self.base.flags)
         {
             return None;
         }
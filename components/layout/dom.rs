@@ -4,15 +4,19 @@
 
 use std::any::Any;
 use std::marker::PhantomData;
+use std::sync::{Arc, Weak};
 
+use app_units::Au;
 use atomic_refcell::{AtomicRef, AtomicRefCell, AtomicRefMut};
 use base::id::{BrowsingContextId, PipelineId};
+use euclid::default::Size2D as UntypedSize2D;
 use html5ever::{local_name, ns};
 use layout_api::wrapper_traits::{
     LayoutDataTrait, LayoutNode, ThreadSafeLayoutElement, ThreadSafeLayoutNode,
 };
 use layout_api::{
-    GenericLayoutDataTrait, LayoutDamage, LayoutElementType, LayoutNodeType as ScriptLayoutNodeType,
+    GenericLayoutDataTrait, LayoutDamage, LayoutElementType,
+    LayoutNodeType as ScriptLayoutNodeType, TrustedNodeAddress,
 };
 use malloc_size_of_derive::MallocSizeOf;
 use net_traits::image_cache::Image;
@@ -44,6 +48,33 @@ pub struct PseudoLayoutData {
 pub struct InnerDOMLayoutData {
     pub(super) self_box: ArcRefCell<Option<LayoutBox>>,
     pub(super) pseudo_boxes: SmallVec<[PseudoLayoutData; 2]>,
+    /// The size of the content box of this element in the last layout, if it is a query
+    /// container.
+    pub(super) query_container_size: Option<PhysicalSize<Au>>,
+    /// Set while this element is in the list of query containers kept by layout.
+    #[ignore_malloc_size_of = "Zero-sized"]
+    pub(super) query_container_handle: Option<Arc<QueryContainerHandle>>,
+}
+
+/// Shared by the layout data of a query container and the list of query containers kept by
+/// layout, so that the list can tell whether the node of a container is still alive.
+pub(crate) struct QueryContainerHandle;
+
+/// An element that is a query container. Layout keeps a list of them, so that their sizes
+/// can be recorded after every layout without walking the whole DOM.
+pub(crate) struct QueryContainer {
+    node: TrustedNodeAddress,
+    /// Dropped along with the layout data of the node, when it is removed from the document.
+    handle: Weak<QueryContainerHandle>,
+}
+
+impl QueryContainer {
+    /// The node of this query container, or `None` if it is not in the document anymore.
+    #[allow(unsafe_code)]
+    pub(crate) fn node<'dom>(&self) -> Option<ServoLayoutNode<'dom>> {
+        self.handle.upgrade()?;
+        Some(unsafe { ServoLayoutNode::new(&self.node) })
+    }
 }
 
 impl InnerDOMLayoutData {
@@ -174,6 +205,13 @@ impl GenericLayoutDataTrait for DOMLayoutData {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn query_container_size(&self) -> Option<UntypedSize2D<Au>> {
+        self.0
+            .borrow()
+            .query_container_size
+            .map(|size| size.to_untyped())
+    }
 }
 
 pub struct BoxSlot<'dom> {
@@ -249,6 +287,15 @@ pub(crate) trait NodeExt<'dom> {
     fn fragments_for_pseudo(&self, pseudo_element: Option<PseudoElement>) -> Vec<Fragment>;
     fn clear_fragment_layout_cache(&self);
 
+    /// Start tracking this node as a query container, returning the entry to add to the list
+    /// of query containers, or `None` if it is already in that list.
+    fn track_as_query_container(&self) -> Option<QueryContainer>;
+
+    /// Update the size of this node as a query container from the fragments of the last
+    /// layout, returning whether it changed. Nodes that are no longer query containers
+    /// stop being tracked.
+    fn update_query_container_size(&self) -> bool;
+
     fn repair_style(&self, context: &SharedStyleContext);
     fn take_restyle_damage(&self) -> LayoutDamage;
 }
@@ -407,6 +454,53 @@ impl<'dom> NodeExt<'dom> for ServoLayoutNode<'dom> {
             .unwrap_or_default()
     }
 
+    fn track_as_query_container(&self) -> Option<QueryContainer> {
+        let mut layout_data = self.layout_data_mut();
+        if layout_data.query_container_handle.is_some() {
+            return None;
+        }
+        let handle = Arc::new(QueryContainerHandle);
+        let query_container = QueryContainer {
+            node: self.to_trusted_node_address(),
+            handle: Arc::downgrade(&handle),
+        };
+        layout_data.query_container_handle = Some(handle);
+        Some(query_container)
+    }
+
+    fn update_query_container_size(&self) -> bool {
+        let is_query_container = self.style_data().is_some_and(|style_data| {
+            style_data
+                .element_data
+                .borrow()
+                .styles
+                .get_primary()
+                .is_some_and(|style| !style.clone_container_type().is_normal())
+        });
+        let size = if is_query_container {
+            self.fragments_for_pseudo(None)
+                .into_iter()
+                .find_map(|fragment| match fragment {
+                    Fragment::Box(box_fragment) | Fragment::Float(box_fragment) => {
+                        Some(box_fragment.borrow().content_rect.size)
+                    },
+                    _ => None,
+                })
+        } else {
+            None
+        };
+
+        let mut layout_data = self.layout_data_mut();
+        if !is_query_container {
+            layout_data.query_container_handle = None;
+        }
+        if layout_data.query_container_size == size {
+            return false;
+        }
+        layout_data.query_container_size = size;
+        true
+    }
+
     fn repair_style(&self, context: &SharedStyleContext) {
         let data = self.layout_data_mut();
         if let Some(layout_object) = &*data.self_box.borrow() {
@@ -25,7 +25,9 @@ use crate::flow::inline::SharedInlineStyles;
 use crate::fragment_tree::{BaseFragmentInfo, FragmentFlags, Tag};
 use crate::quotes::quotes_for_lang;
 use crate::replaced::ReplacedContents;
-use crate::style_ext::{Display, DisplayGeneratingBox, DisplayInside, DisplayOutside};
+use crate::style_ext::{
+    ComputedValuesExt, Display, DisplayGeneratingBox, DisplayInside, DisplayOutside,
+};
 
 /// A data structure used to pass and store related layout information together to
 /// avoid having to repeat the same arguments in argument lists.
@@ -286,12 +288,37 @@ fn traverse_element<'dom>(
                 NonReplacedContents::OfElement.into()
             };
             let display = display.used_value_for_contents(&contents);
+            traverse_backdrop_pseudo_element(&info, context, handler);
             let box_slot = element.element_box_slot();
             handler.handle_element(&info, display, contents, box_slot);
         },
     }
 }
 
+/// Elements in the top layer generate a `::backdrop` box, which is rendered immediately below
+/// them. It is handled right before its originating element so that it precedes it in paint
+/// order among the other top layer boxes.
+/// <https://drafts.csswg.org/css-position-4/#backdrop>
+fn traverse_backdrop_pseudo_element<'dom>(
+    info: &NodeAndStyleInfo<'dom>,
+    context: &LayoutContext,
+    handler: &mut impl TraversalHandler<'dom>,
+) {
+    if !info.style.is_in_top_layer() {
+        return;
+    }
+    let Some(backdrop_info) = info.pseudo(context, PseudoElement::Backdrop) else {
+        return;
+    };
+    let Display::GeneratingBox(display) = Display::from(backdrop_info.style.get_box().display)
+    else {
+        return;
+    };
+    let box_slot = info.node.pseudo_element_box_slot(PseudoElement::Backdrop);
+    let contents = NonReplacedContents::OfPseudoElement(Vec::new()).into();
+    handler.handle_element(&backdrop_info, display, contents, box_slot);
+}
+
 fn traverse_eager_pseudo_element<'dom>(
     pseudo_element_type: PseudoElement,
     node_info: &NodeAndStyleInfo<'dom>,
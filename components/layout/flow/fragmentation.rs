@@ -0,0 +1,365 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Breaking the in-flow contents of a box into fragmentainers, such as columns or pages.
+//!
+//! The contents are laid out as if they were in a single fragmentainer of infinite block size,
+//! and are then distributed into fragmentainers between the block-level boxes and line boxes
+//! that are direct children of the fragmented box. These are never broken themselves.
+//!
+//! TODO: `break-before`, `break-after` and `break-inside` are not honored, because they are
+//! not supported by the style system of Servo yet. Likewise, `orphans` and `widows` always
+//! have their initial values.
+//!
+//! <https://drafts.csswg.org/css-break/>
+
+use std::ops::Range;
+
+use app_units::Au;
+use style::Zero;
+
+use crate::ContainingBlock;
+use crate::fragment_tree::Fragment;
+use crate::geom::ToLogicalWithContainingBlock;
+
+/// The minimum number of line boxes of a block container that must be left in a fragmentainer
+/// before a break, which is the initial value of `orphans`.
+/// <https://drafts.csswg.org/css-break/#widows-orphans>
+const ORPHANS: usize = 2;
+
+/// The minimum number of line boxes of a block container that must be left in a fragmentainer
+/// after a break, which is the initial value of `widows`.
+/// <https://drafts.csswg.org/css-break/#widows-orphans>
+const WIDOWS: usize = 2;
+
+/// A direct child of a fragmented box, as seen by the fragmentation algorithm.
+pub(super) struct FragmentationChild {
+    /// The block range occupied by the child, or `None` if it doesn't take space.
+    range: Option<Range<Au>>,
+    /// Whether breaking right before this child should be avoided, if possible.
+    /// <https://drafts.csswg.org/css-break/#unforced-breaks>
+    avoid_break_before: bool,
+}
+
+impl FragmentationChild {
+    /// Describes the given fragments, which are the direct children of a fragmented box, for
+    /// the fragmentation algorithm. Breaks that would leave fewer than [`ORPHANS`] or
+    /// [`WIDOWS`] line boxes on either side of them are avoided.
+    pub(super) fn for_fragments(
+        fragments: &[Fragment],
+        containing_block: &ContainingBlock,
+    ) -> Vec<Self> {
+        Self::for_ranges(
+            fragments
+                .iter()
+                .map(|fragment| {
+                    (
+                        fragment_block_range(fragment, containing_block),
+                        is_line_box(fragment),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Like [`Self::for_fragments`], but for the block range of each child, along with whether
+    /// it is a line box.
+    fn for_ranges(ranges: Vec<(Option<Range<Au>>, bool)>) -> Vec<Self> {
+        let mut children: Vec<_> = ranges
+            .iter()
+            .map(|(range, _)| Self {
+                range: range.clone(),
+                avoid_break_before: false,
+            })
+            .collect();
+
+        let mut index = 0;
+        while index < ranges.len() {
+            let line_count = ranges[index..]
+                .iter()
+                .take_while(|(_, is_line_box)| *is_line_box)
+                .count();
+            for line in 1..line_count {
+                children[index + line].avoid_break_before =
+                    line < ORPHANS || line_count - line < WIDOWS;
+            }
+            index += line_count.max(1);
+        }
+        children
+    }
+}
+
+/// Whether the given fragment, which is a direct child of a fragmented box, is a line box.
+fn is_line_box(fragment: &Fragment) -> bool {
+    match fragment {
+        Fragment::Positioning(positioning_fragment) => {
+            positioning_fragment.borrow().base.is_anonymous()
+        },
+        _ => false,
+    }
+}
+
+/// The block range occupied by the given fragment, which is a direct child of a fragmented
+/// box, or `None` if it doesn't take space.
+fn fragment_block_range(
+    fragment: &Fragment,
+    containing_block: &ContainingBlock,
+) -> Option<Range<Au>> {
+    let rect = match fragment {
+        Fragment::Box(box_fragment) | Fragment::Float(box_fragment) => {
+            box_fragment.borrow().border_rect()
+        },
+        Fragment::Positioning(positioning_fragment) => positioning_fragment.borrow().rect,
+        Fragment::Text(text_fragment) => text_fragment.borrow().rect,
+        Fragment::Image(image_fragment) => image_fragment.borrow().rect,
+        Fragment::IFrame(iframe_fragment) => iframe_fragment.borrow().rect,
+        Fragment::AbsoluteOrFixedPositioned(_) => return None,
+    };
+    let rect = rect.to_logical(containing_block);
+    Some(rect.start_corner.block..rect.max_block_position())
+}
+
+/// The result of breaking the children of a fragmented box into fragmentainers.
+pub(super) struct FragmentainerBreaks {
+    /// For each child, the index of its fragmentainer and the block offset at which that
+    /// fragmentainer starts.
+    pub positions: Vec<(usize, Au)>,
+    /// The number of fragmentainers used.
+    pub count: usize,
+    /// The block size of the tallest fragmentainer.
+    pub block_size: Au,
+    /// The smallest increase of the fragmentainer block size which would have avoided one of
+    /// the breaks, if any.
+    smallest_overflow: Option<Au>,
+}
+
+impl FragmentainerBreaks {
+    /// Breaks the given children into fragmentainers of the given block size. When a child
+    /// doesn't fit, the break is moved before the closest earlier child where breaking isn't
+    /// avoided, if there is one in the same fragmentainer. Children without a range stay with
+    /// the child before them.
+    pub(super) fn new(children: &[FragmentationChild], fragmentainer_block_size: Au) -> Self {
+        let mut positions = Vec::with_capacity(children.len());
+        let mut fragmentainer_index = 0;
+        let mut fragmentainer_start = Au::zero();
+        let mut first_child_in_fragmentainer = 0;
+        let mut smallest_overflow: Option<Au> = None;
+
+        // Every break strictly increases `fragmentainer_start`, so this terminates.
+        let mut index = 0;
+        while index < children.len() {
+            if let Some(range) = &children[index].range {
+                let overflow = range.end - fragmentainer_start - fragmentainer_block_size;
+                if overflow > Au::zero() && range.start > fragmentainer_start {
+                    smallest_overflow =
+                        Some(smallest_overflow.map_or(overflow, |smallest| smallest.min(overflow)));
+                    let can_break_before = |candidate: &usize| {
+                        let child = &children[*candidate];
+                        !child.avoid_break_before &&
+                            child
+                                .range
+                                .as_ref()
+                                .is_some_and(|range| range.start > fragmentainer_start)
+                    };
+                    let break_index = (first_child_in_fragmentainer + 1..=index)
+                        .rev()
+                        .find(can_break_before)
+                        .unwrap_or(index);
+
+                    positions.truncate(break_index);
+                    fragmentainer_index += 1;
+                    fragmentainer_start = children[break_index]
+                        .range
+                        .as_ref()
+                        .map_or(range.start, |range| range.start);
+                    first_child_in_fragmentainer = break_index;
+                    index = break_index;
+                    continue;
+                }
+            }
+            positions.push((fragmentainer_index, fragmentainer_start));
+            index += 1;
+        }
+
+        let block_size = children
+            .iter()
+            .zip(&positions)
+            .filter_map(|(child, (_, start))| Some(child.range.as_ref()?.end - *start))
+            .max()
+            .unwrap_or_default();
+
+        Self {
+            positions,
+            count: fragmentainer_index + 1,
+            block_size,
+            smallest_overflow,
+        }
+    }
+
+    /// Breaks the given children into at most `fragmentainer_count` fragmentainers, as
+    /// balanced as possible, and not exceeding `available_block_size` unless the contents
+    /// can't fit otherwise.
+    ///
+    /// <https://drafts.csswg.org/css-multicol/#cf>
+    pub(super) fn balanced(
+        children: &[FragmentationChild],
+        fragmentainer_count: usize,
+        available_block_size: Option<Au>,
+    ) -> Self {
+        let ranges = || children.iter().filter_map(|child| child.range.as_ref());
+        let total_block_size = ranges().map(|range| range.end).max().unwrap_or_default();
+        let tallest_child = ranges()
+            .map(|range| range.end - range.start)
+            .max()
+            .unwrap_or_default();
+
+        let mut block_size = (total_block_size / fragmentainer_count as i32).max(tallest_child);
+        if let Some(available_block_size) = available_block_size {
+            block_size = block_size.min(available_block_size);
+        }
+
+        // The block size grows in every iteration until the contents fit into a single
+        // fragmentainer, so this terminates.
+        loop {
+            let breaks = Self::new(children, block_size);
+            let can_grow = available_block_size
+                .is_none_or(|available_block_size| block_size < available_block_size);
+            match breaks.smallest_overflow {
+                Some(overflow) if breaks.count > fragmentainer_count && can_grow => {
+                    block_size += overflow;
+                    if let Some(available_block_size) = available_block_size {
+                        block_size = block_size.min(available_block_size);
+                    }
+                },
+                _ => return breaks,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(start: i32, end: i32) -> (Option<Range<Au>>, bool) {
+        (Some(Au::from_px(start)..Au::from_px(end)), false)
+    }
+
+    fn line(start: i32, end: i32) -> (Option<Range<Au>>, bool) {
+        (Some(Au::from_px(start)..Au::from_px(end)), true)
+    }
+
+    fn fragmentainers(breaks: &FragmentainerBreaks) -> Vec<(usize, i32)> {
+        breaks
+            .positions
+            .iter()
+            .map(|(index, start)| (*index, start.to_px()))
+            .collect()
+    }
+
+    #[test]
+    fn test_break_between_blocks() {
+        let children = FragmentationChild::for_ranges(vec![
+            block(0, 20),
+            block(20, 40),
+            (None, false),
+            block(40, 60),
+        ]);
+        let breaks = FragmentainerBreaks::new(&children, Au::from_px(45));
+        assert_eq!(fragmentainers(&breaks), [(0, 0), (0, 0), (0, 0), (1, 40)]);
+        assert_eq!(breaks.count, 2);
+        assert_eq!(breaks.block_size, Au::from_px(40));
+    }
+
+    #[test]
+    fn test_child_taller_than_fragmentainer() {
+        let children = FragmentationChild::for_ranges(vec![block(0, 100), block(100, 110)]);
+        let breaks = FragmentainerBreaks::new(&children, Au::from_px(50));
+        assert_eq!(fragmentainers(&breaks), [(0, 0), (1, 100)]);
+        assert_eq!(breaks.block_size, Au::from_px(100));
+    }
+
+    #[test]
+    fn test_orphans() {
+        // Breaking after the first line would leave a single line before the break, so the
+        // break moves before the first line.
+        let children = FragmentationChild::for_ranges(vec![
+            block(0, 50),
+            line(50, 60),
+            line(60, 70),
+            line(70, 80),
+        ]);
+        let breaks = FragmentainerBreaks::new(&children, Au::from_px(65));
+        assert_eq!(fragmentainers(&breaks), [(0, 0), (1, 50), (1, 50), (1, 50)]);
+    }
+
+    #[test]
+    fn test_widows() {
+        // Breaking before the last line would leave a single line after the break, so the
+        // break moves before the line above it.
+        let children = FragmentationChild::for_ranges(vec![
+            line(0, 10),
+            line(10, 20),
+            line(20, 30),
+            line(30, 40),
+        ]);
+        let breaks = FragmentainerBreaks::new(&children, Au::from_px(35));
+        assert_eq!(fragmentainers(&breaks), [(0, 0), (0, 0), (1, 20), (1, 20)]);
+    }
+
+    #[test]
+    fn test_too_few_lines_to_avoid_break() {
+        // With three lines, any break leaves a single line on one side. The break is then
+        // made where the contents overflow.
+        let children =
+            FragmentationChild::for_ranges(vec![line(0, 10), line(10, 20), line(20, 30)]);
+        let breaks = FragmentainerBreaks::new(&children, Au::from_px(15));
+        assert_eq!(fragmentainers(&breaks), [(0, 0), (1, 10), (2, 20)]);
+    }
+
+    #[test]
+    fn test_balanced() {
+        let children = FragmentationChild::for_ranges(vec![
+            block(0, 40),
+            block(40, 60),
+            block(60, 80),
+            block(80, 120),
+            block(120, 150),
+            block(150, 180),
+        ]);
+        let breaks = FragmentainerBreaks::balanced(&children, 3, None);
+        assert_eq!(
+            fragmentainers(&breaks),
+            [(0, 0), (0, 0), (1, 60), (1, 60), (2, 120), (2, 120)]
+        );
+        assert_eq!(breaks.block_size, Au::from_px(60));
+    }
+
+    #[test]
+    fn test_balanced_grows_until_contents_fit() {
+        // The average block size of 30px would need a third fragmentainer.
+        let children =
+            FragmentationChild::for_ranges(vec![block(0, 20), block(20, 40), block(40, 60)]);
+        let breaks = FragmentainerBreaks::balanced(&children, 2, None);
+        assert_eq!(fragmentainers(&breaks), [(0, 0), (0, 0), (1, 40)]);
+        assert_eq!(breaks.count, 2);
+        assert_eq!(breaks.block_size, Au::from_px(40));
+    }
+
+    #[test]
+    fn test_balanced_with_available_block_size() {
+        // The contents overflow into extra fragmentainers rather than exceeding the
+        // available block size.
+        let children = FragmentationChild::for_ranges(vec![
+            block(0, 20),
+            block(20, 40),
+            block(40, 60),
+            block(60, 80),
+        ]);
+        let breaks = FragmentainerBreaks::balanced(&children, 2, Some(Au::from_px(30)));
+        assert_eq!(fragmentainers(&breaks), [(0, 0), (1, 20), (2, 40), (3, 60)]);
+        assert_eq!(breaks.count, 4);
+        assert_eq!(breaks.block_size, Au::from_px(20));
+    }
+}
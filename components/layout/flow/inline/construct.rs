@@ -5,8 +5,10 @@
 use std::borrow::Cow;
 use std::char::{ToLowercase, ToUppercase};
 
+use fonts_traits::ByteIndex;
 use icu_segmenter::WordSegmenter;
 use itertools::izip;
+use range::Range as ServoRange;
 use style::computed_values::white_space_collapse::T as WhiteSpaceCollapse;
 use style::values::specified::text::TextTransformCase;
 use unicode_bidi::Level;
@@ -400,7 +402,14 @@ impl InlineFormattingContextBuilder {
             return;
         }
 
-        let selection_range = info.get_selection_range();
+        let selection_range = info.get_selection_range().map(|selection| {
+            self.selection_range_in_collapsed_text(
+                &text,
+                &new_text,
+                selection,
+                white_space_collapse,
+            )
+        });
         if let Some(last_character) = new_text.chars().next_back() {
             self.on_word_boundary = last_character.is_whitespace();
             self.last_inline_box_ended_with_collapsible_white_space =
@@ -413,7 +422,23 @@ impl InlineFormattingContextBuilder {
 
         if let Some(inline_item) = self.inline_items.last() {
             if let InlineItem::TextRun(text_run) = &mut *inline_item.borrow_mut() {
-                text_run.borrow_mut().text_range.end = new_range.end;
+                let mut text_run = text_run.borrow_mut();
+                if let Some(selection) = selection_range {
+                    // The selection of the merged text is relative to the start of the
+                    // existing text run. Selections are contiguous, so the result covers
+                    // both the existing and the new selected text.
+                    let offset = ByteIndex((new_range.start - text_run.text_range.start) as isize);
+                    let selection = ServoRange::new(selection.begin() + offset, selection.length());
+                    text_run.selection_range = Some(match text_run.selection_range {
+                        Some(existing) => {
+                            let begin = existing.begin().min(selection.begin());
+                            let end = existing.end().max(selection.end());
+                            ServoRange::new(begin, end - begin)
+                        },
+                        None => selection,
+                    });
+                }
+                text_run.text_range.end = new_range.end;
                 return;
             }
         }
@@ -429,6 +454,37 @@ impl InlineFormattingContextBuilder {
             ))));
     }
 
+    /// Map a selection in the original text of a node to the text that remains after
+    /// white space collapsing. Offsets are clamped to the length of the collapsed text, as
+    /// text transforms can change its length as well.
+    fn selection_range_in_collapsed_text(
+        &self,
+        text: &str,
+        new_text: &str,
+        selection: ServoRange<ByteIndex>,
+        white_space_collapse: WhiteSpaceCollapse,
+    ) -> ServoRange<ByteIndex> {
+        let map_offset = |offset: ByteIndex| {
+            let offset = offset.0.max(0) as usize;
+            let collapsed_offset = if text.len() == new_text.len() {
+                offset
+            } else {
+                let prefix = text.get(..offset).unwrap_or(text);
+                WhitespaceCollapse::new(
+                    prefix.chars(),
+                    white_space_collapse,
+                    self.last_inline_box_ended_with_collapsible_white_space,
+                )
+                .map(char::len_utf8)
+                .sum()
+            };
+            ByteIndex(collapsed_offset.min(new_text.len()) as isize)
+        };
+        let begin = map_offset(selection.begin());
+        let end = map_offset(selection.end());
+        ServoRange::new(begin, end - begin)
+    }
+
     pub(crate) fn enter_display_contents(&mut self, shared_inline_styles: SharedInlineStyles) {
         self.shared_inline_styles_stack.push(shared_inline_styles);
     }
@@ -43,12 +43,16 @@ use crate::sizing::{
     self, ComputeInlineContentSizes, ContentSizes, InlineContentSizesResult, LazySize, Size,
     SizeConstraint, Sizes,
 };
-use crate::style_ext::{AspectRatio, ContentBoxSizesAndPBM, LayoutStyle, PaddingBorderMargin};
+use crate::style_ext::{
+    AspectRatio, ComputedValuesExt, ContentBoxSizesAndPBM, LayoutStyle, PaddingBorderMargin,
+};
 use crate::{ConstraintSpace, ContainingBlock, ContainingBlockSize, IndefiniteContainingBlock};
 
 mod construct;
 pub mod float;
+mod fragmentation;
 pub mod inline;
+mod multicol;
 mod root;
 
 pub(crate) use construct::BlockContainerBuilder;
@@ -427,6 +431,10 @@ impl BlockFormattingContext {
         positioning_context: &mut PositioningContext,
         containing_block: &ContainingBlock,
     ) -> CacheableLayoutResult {
+        if containing_block.style.get_column().is_multicol() {
+            return self.layout_multicol(layout_context, positioning_context, containing_block);
+        }
+
         let mut sequential_layout_state = if self.contains_floats || !layout_context.use_rayon {
             Some(SequentialLayoutState::new(containing_block.size.inline))
         } else {
@@ -1069,6 +1077,12 @@ fn layout_in_flow_non_replaced_block_level_same_formatting_context(
     );
     let mut content_block_size: Au = flow_layout.content_block_size;
 
+    // Size containment makes the box behave as if it had no contents when sizing it in the
+    // block axis.
+    if style.has_block_axis_size_containment() {
+        content_block_size = Au::zero();
+    }
+
     // Update margins.
     let mut block_margins_collapsed_with_children = CollapsedBlockMargins::from_margin(&margin);
     let mut collapsible_margins_in_children = flow_layout.collapsible_margins_in_children;
@@ -1732,6 +1746,20 @@ fn solve_containing_block_padding_and_border_for_in_flow_box<'a>(
         is_table,
     );
 
+    // A non-replaced box with a preferred aspect ratio and an automatic block size gets a
+    // definite block size transferred from its inline size through the ratio.
+    // <https://drafts.csswg.org/css-sizing-4/#aspect-ratio-size-transfers>
+    let tentative_block_size = match (tentative_block_size, preferred_aspect_ratio) {
+        (SizeConstraint::MinMax(min_size, max_size), Some(ratio)) if !is_replaced => {
+            SizeConstraint::Definite(
+                ratio
+                    .compute_dependent_size(Direction::Block, inline_size)
+                    .clamp_between_extremums(min_size, max_size),
+            )
+        },
+        _ => tentative_block_size,
+    };
+
     let containing_block_for_children = ContainingBlock {
         size: ContainingBlockSize {
             inline: inline_size,
@@ -0,0 +1,362 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Multi-column layout.
+//!
+//! The in-flow contents of a multi-column container are laid out as a single column which is
+//! then broken into columns by the fragmentation code in [`super::fragmentation`].
+//!
+//! <https://drafts.csswg.org/css-multicol/>
+
+use app_units::Au;
+use style::Zero;
+use style::computed_values::column_span::T as ColumnSpan;
+use style::properties::ComputedValues;
+use style::values::generics::column::ColumnCount;
+use style::values::generics::length::{LengthPercentageOrAuto, LengthPercentageOrNormal};
+
+use super::float::SequentialLayoutState;
+use super::fragmentation::{FragmentainerBreaks, FragmentationChild};
+use super::{
+    BlockContainer, BlockFormattingContext, BlockLevelBox, CollapsibleWithParentStartMargin,
+    layout_block_level_children,
+};
+use crate::cell::ArcRefCell;
+use crate::context::LayoutContext;
+use crate::formatting_contexts::Baselines;
+use crate::fragment_tree::{CollapsedBlockMargins, Fragment};
+use crate::geom::{LogicalSides1D, LogicalVec2, ToLogical};
+use crate::layout_box_base::CacheableLayoutResult;
+use crate::positioned::PositioningContext;
+use crate::sizing::SizeConstraint;
+use crate::{ContainingBlock, ContainingBlockSize};
+
+/// The used number and inline size of the columns of a multi-column container, as well as
+/// the gap between them.
+struct ColumnGeometry {
+    count: usize,
+    inline_size: Au,
+    gap: Au,
+}
+
+impl ColumnGeometry {
+    /// <https://drafts.csswg.org/css-multicol/#pseudo-algorithm>
+    fn new(style: &ComputedValues, available_inline_size: Au) -> Self {
+        let gap = match style.clone_column_gap() {
+            LengthPercentageOrNormal::LengthPercentage(length_percentage) => {
+                length_percentage.to_used_value(available_inline_size)
+            },
+            // > The normal value is treated as 1em in multi-column containers.
+            LengthPercentageOrNormal::Normal => {
+                Au::from(style.get_font().font_size.computed_size())
+            },
+        };
+
+        let column_style = style.get_column();
+        let column_width = match column_style.column_width {
+            LengthPercentageOrAuto::LengthPercentage(width) => Some(Au::from(width.0)),
+            LengthPercentageOrAuto::Auto => None,
+        };
+        let column_count = match column_style.column_count {
+            ColumnCount::Integer(count) => Some(count.0 as usize),
+            ColumnCount::Auto => None,
+        };
+
+        Self::from_used_values(column_width, column_count, gap, available_inline_size)
+    }
+
+    /// Determines the used column count and width from the used `column-width`,
+    /// `column-count` and `column-gap` of a multi-column container.
+    fn from_used_values(
+        column_width: Option<Au>,
+        column_count: Option<usize>,
+        gap: Au,
+        available_inline_size: Au,
+    ) -> Self {
+        let count = match column_width {
+            Some(column_width) => {
+                let fitting_count = (available_inline_size + gap).0 / (column_width + gap).0.max(1);
+                let fitting_count = (fitting_count.max(1)) as usize;
+                column_count.map_or(fitting_count, |count| count.min(fitting_count))
+            },
+            None => column_count.unwrap_or(1),
+        };
+        let inline_size =
+            Au::zero().max((available_inline_size - gap * (count as i32 - 1)) / count as i32);
+
+        Self {
+            count,
+            inline_size,
+            gap,
+        }
+    }
+
+    /// The physical offset in the inline axis of the column with the given index, relative to
+    /// the start of the multi-column container. Columns are ordered in the inline base
+    /// direction of the container.
+    fn inline_offset(&self, index: usize, containing_block: &ContainingBlock) -> Au {
+        let offset = (self.inline_size + self.gap) * index as i32;
+        if containing_block.style.writing_mode.is_bidi_ltr() {
+            offset
+        } else {
+            containing_block.size.inline - self.inline_size - offset
+        }
+    }
+}
+
+/// A run of the in-flow contents of a multi-column container, which is either flowed into
+/// columns or spans all of them.
+enum ColumnSegment<'a> {
+    Columns(ColumnContents<'a>),
+    Spanner(&'a ArcRefCell<BlockLevelBox>),
+}
+
+enum ColumnContents<'a> {
+    BlockLevelBoxes(&'a [ArcRefCell<BlockLevelBox>]),
+    Container(&'a BlockContainer),
+}
+
+/// Splits the contents of a multi-column container into the runs that are flowed into columns
+/// and the spanners that separate them.
+///
+/// TODO: Spanners that are not children of the multi-column container are flowed into the
+/// columns.
+fn column_segments(contents: &BlockContainer) -> Vec<ColumnSegment<'_>> {
+    let BlockContainer::BlockLevelBoxes(child_boxes) = contents else {
+        return vec![ColumnSegment::Columns(ColumnContents::Container(contents))];
+    };
+
+    let is_spanner = |child_box: &ArcRefCell<BlockLevelBox>| match &*child_box.borrow() {
+        BlockLevelBox::Independent(independent) => {
+            independent.style().get_column().column_span == ColumnSpan::All
+        },
+        _ => false,
+    };
+
+    let mut segments = Vec::new();
+    let mut run_start = 0;
+    for (index, child_box) in child_boxes.iter().enumerate() {
+        if !is_spanner(child_box) {
+            continue;
+        }
+        if run_start < index {
+            segments.push(ColumnSegment::Columns(ColumnContents::BlockLevelBoxes(
+                &child_boxes[run_start..index],
+            )));
+        }
+        segments.push(ColumnSegment::Spanner(child_box));
+        run_start = index + 1;
+    }
+    if run_start < child_boxes.len() {
+        segments.push(ColumnSegment::Columns(ColumnContents::BlockLevelBoxes(
+            &child_boxes[run_start..],
+        )));
+    }
+    segments
+}
+
+/// Offsets the given fragment, which is a direct child of a multi-column container.
+fn offset_fragment(
+    fragment: &mut Fragment,
+    offset: LogicalVec2<Au>,
+    containing_block: &ContainingBlock,
+) {
+    let offset = offset.to_physical_size(containing_block.style.writing_mode);
+    match fragment {
+        Fragment::Positioning(positioning_fragment) => {
+            positioning_fragment.borrow_mut().rect.origin += offset
+        },
+        Fragment::AbsoluteOrFixedPositioned(hoisted_fragment) => {
+            hoisted_fragment.borrow_mut().static_position_rect.origin += offset
+        },
+        _ => fragment.mutate_content_rect(|rect| rect.origin += offset),
+    }
+}
+
+impl BlockFormattingContext {
+    /// Lays out the contents of a multi-column container.
+    ///
+    /// TODO: Column rules are not painted, because `column-rule-*` are not supported by the
+    /// style system of Servo yet. The intrinsic inline sizes of multi-column containers also
+    /// ignore the columns.
+    pub(super) fn layout_multicol(
+        &self,
+        layout_context: &LayoutContext,
+        positioning_context: &mut PositioningContext,
+        containing_block: &ContainingBlock,
+    ) -> CacheableLayoutResult {
+        let columns = ColumnGeometry::new(containing_block.style, containing_block.size.inline);
+        let containing_block_for_columns = ContainingBlock {
+            size: ContainingBlockSize {
+                inline: columns.inline_size,
+                block: SizeConstraint::default(),
+            },
+            style: containing_block.style,
+        };
+        let available_block_size = containing_block.size.block.to_definite();
+        let ignore_block_margins_for_stretch = LogicalSides1D::new(false, false);
+        let writing_mode = containing_block.style.writing_mode;
+
+        let mut fragments = Vec::new();
+        let mut baselines = Baselines::default();
+        let mut depends_on_block_constraints = false;
+        let mut current_block_position = Au::zero();
+        for segment in column_segments(&self.contents) {
+            let segment_block_size = match segment {
+                ColumnSegment::Columns(contents) => {
+                    let mut sequential_layout_state =
+                        if self.contains_floats || !layout_context.use_rayon {
+                            Some(SequentialLayoutState::new(columns.inline_size))
+                        } else {
+                            None
+                        };
+                    let collapsible_with_parent_start_margin =
+                        CollapsibleWithParentStartMargin(false);
+                    let flow_layout = match contents {
+                        ColumnContents::BlockLevelBoxes(child_boxes) => {
+                            layout_block_level_children(
+                                layout_context,
+                                positioning_context,
+                                child_boxes,
+                                &containing_block_for_columns,
+                                sequential_layout_state.as_mut(),
+                                collapsible_with_parent_start_margin,
+                                ignore_block_margins_for_stretch,
+                            )
+                        },
+                        ColumnContents::Container(container) => container.layout(
+                            layout_context,
+                            positioning_context,
+                            &containing_block_for_columns,
+                            sequential_layout_state.as_mut(),
+                            collapsible_with_parent_start_margin,
+                            ignore_block_margins_for_stretch,
+                        ),
+                    };
+                    depends_on_block_constraints |= flow_layout.depends_on_block_constraints;
+
+                    let children = FragmentationChild::for_fragments(
+                        &flow_layout.fragments,
+                        &containing_block_for_columns,
+                    );
+                    let breaks = FragmentainerBreaks::balanced(
+                        &children,
+                        columns.count,
+                        available_block_size,
+                    );
+                    for (mut fragment, (column_index, column_start)) in
+                        flow_layout.fragments.into_iter().zip(breaks.positions)
+                    {
+                        let offset = LogicalVec2 {
+                            inline: columns.inline_offset(column_index, containing_block),
+                            block: current_block_position - column_start,
+                        };
+                        offset_fragment(&mut fragment, offset, containing_block);
+                        fragments.push(fragment);
+                    }
+
+                    if baselines.first.is_none() {
+                        baselines.first = flow_layout
+                            .baselines
+                            .first
+                            .map(|baseline| baseline + current_block_position);
+                    }
+                    breaks.block_size
+                },
+                ColumnSegment::Spanner(spanner) => {
+                    let mut fragment = spanner.borrow().layout(
+                        layout_context,
+                        positioning_context,
+                        containing_block,
+                        None, /* sequential_layout_state */
+                        None, /* collapsible_with_parent_start_margin */
+                        ignore_block_margins_for_stretch,
+                    );
+                    let Fragment::Box(box_fragment) = &fragment else {
+                        unreachable!("A column spanner should produce a box fragment.");
+                    };
+                    let (margin, border_block_size) = {
+                        let box_fragment = box_fragment.borrow();
+                        (
+                            box_fragment.margin.to_logical(writing_mode),
+                            box_fragment
+                                .border_rect()
+                                .size
+                                .to_logical(writing_mode)
+                                .block,
+                        )
+                    };
+                    let offset = LogicalVec2 {
+                        inline: Au::zero(),
+                        block: current_block_position + margin.block_start,
+                    };
+                    offset_fragment(&mut fragment, offset, containing_block);
+                    fragments.push(fragment);
+                    margin.block_start + border_block_size + margin.block_end
+                },
+            };
+            current_block_position += segment_block_size;
+        }
+
+        CacheableLayoutResult {
+            fragments,
+            content_block_size: current_block_position,
+            content_inline_size_for_table: None,
+            baselines,
+            depends_on_block_constraints,
+            specific_layout_info: None,
+            collapsible_margins_in_children: CollapsedBlockMargins::zero(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geometry(
+        column_width: Option<i32>,
+        column_count: Option<usize>,
+        gap: i32,
+        available_inline_size: i32,
+    ) -> (usize, Au) {
+        let geometry = ColumnGeometry::from_used_values(
+            column_width.map(Au::from_px),
+            column_count,
+            Au::from_px(gap),
+            Au::from_px(available_inline_size),
+        );
+        (geometry.count, geometry.inline_size)
+    }
+
+    #[test]
+    fn test_column_count() {
+        assert_eq!(geometry(None, Some(2), 0, 200), (2, Au::from_px(100)));
+        assert_eq!(geometry(None, Some(3), 20, 340), (3, Au::from_px(100)));
+        assert_eq!(geometry(None, None, 20, 340), (1, Au::from_px(340)));
+    }
+
+    #[test]
+    fn test_column_width() {
+        // As many columns as fit, which are then stretched to fill the container.
+        assert_eq!(geometry(Some(90), None, 10, 320), (3, Au::from_px(100)));
+        assert_eq!(geometry(Some(90), None, 10, 309), (3, Au::from_px(289) / 3));
+        assert_eq!(geometry(Some(90), None, 10, 288), (2, Au::from_px(139)));
+
+        // There is always at least one column, even if it is narrower than `column-width`.
+        assert_eq!(geometry(Some(500), None, 10, 320), (1, Au::from_px(320)));
+    }
+
+    #[test]
+    fn test_column_width_and_count() {
+        // `column-count` is the maximum number of columns.
+        assert_eq!(geometry(Some(90), Some(2), 10, 320), (2, Au::from_px(155)));
+        assert_eq!(geometry(Some(90), Some(5), 10, 320), (3, Au::from_px(100)));
+    }
+
+    #[test]
+    fn test_gap_wider_than_container() {
+        assert_eq!(geometry(None, Some(3), 200, 300), (3, Au::zero()));
+    }
+}
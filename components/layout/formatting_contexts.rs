@@ -6,6 +6,7 @@ use app_units::Au;
 use malloc_size_of_derive::MallocSizeOf;
 use script::layout_dom::{ServoLayoutElement, ServoLayoutNode};
 use servo_arc::Arc;
+use style::Zero;
 use style::context::SharedStyleContext;
 use style::logical_geometry::Direction;
 use style::properties::ComputedValues;
@@ -24,7 +25,7 @@ use crate::replaced::ReplacedContents;
 use crate::sizing::{
     self, ComputeInlineContentSizes, ContentSizes, InlineContentSizesResult, LazySize,
 };
-use crate::style_ext::{AspectRatio, DisplayInside, LayoutStyle};
+use crate::style_ext::{AspectRatio, ComputedValuesExt, DisplayInside, LayoutStyle};
 use crate::table::Table;
 use crate::taffy::TaffyContainer;
 use crate::{
@@ -269,14 +270,16 @@ impl IndependentFormattingContext {
         preferred_aspect_ratio: Option<AspectRatio>,
         lazy_block_size: &LazySize,
     ) -> CacheableLayoutResult {
-        match &self.contents {
-            IndependentFormattingContextContents::Replaced(replaced) => replaced.layout(
-                layout_context,
-                containing_block_for_children,
-                preferred_aspect_ratio,
-                &self.base,
-                lazy_block_size,
-            ),
+        let mut result = match &self.contents {
+            IndependentFormattingContextContents::Replaced(replaced) => {
+                return replaced.layout(
+                    layout_context,
+                    containing_block_for_children,
+                    preferred_aspect_ratio,
+                    &self.base,
+                    lazy_block_size,
+                );
+            },
             IndependentFormattingContextContents::Flow(bfc) => bfc.layout(
                 layout_context,
                 positioning_context,
@@ -300,7 +303,38 @@ impl IndependentFormattingContext {
                 containing_block_for_children,
                 containing_block,
             ),
+        };
+
+        // Size containment makes the box behave as if it had no contents when sizing it in the
+        // block axis.
+        if self.style().has_block_axis_size_containment() {
+            result.content_block_size = Au::zero();
+        }
+
+        // The automatic block size of a non-replaced box with a preferred aspect ratio is
+        // transferred from its inline size through the ratio.
+        // <https://drafts.csswg.org/css-sizing-4/#aspect-ratio-size-transfers>
+        if let Some(ratio) = preferred_aspect_ratio {
+            let inline_size = containing_block_for_children.size.inline;
+            let transferred_block_size =
+                ratio.compute_dependent_size(Direction::Block, inline_size);
+
+            // > In order to avoid unintentional overflow, the automatic minimum size in the
+            // > ratio-dependent axis of a box with a preferred aspect ratio that is neither a
+            // > replaced element nor a scroll container is its min-content size capped by its
+            // > maximum size.
+            // <https://drafts.csswg.org/css-sizing-4/#aspect-ratio-minimum>
+            let style = self.style();
+            let has_automatic_minimum_size =
+                style.min_box_size(style.writing_mode).block.is_initial() &&
+                    !style.establishes_scroll_container(self.base_fragment_info().flags);
+            result.content_block_size = if has_automatic_minimum_size {
+                transferred_block_size.max(result.content_block_size)
+            } else {
+                transferred_block_size
+            };
         }
+        result
     }
 
     #[servo_tracing::instrument(name = "IndependentFormattingContext::layout", skip_all)]
@@ -374,8 +408,11 @@ impl IndependentFormattingContext {
             IndependentFormattingContextContents::Replaced(replaced) => {
                 replaced.preferred_aspect_ratio(self.style(), padding_border_sums)
             },
-            // TODO: support preferred aspect ratios on non-replaced boxes.
-            _ => None,
+            // Tables size themselves from their contents, so they ignore `aspect-ratio`.
+            IndependentFormattingContextContents::Table(_) => None,
+            _ => self
+                .style()
+                .preferred_aspect_ratio(None, padding_border_sums),
         }
     }
 }
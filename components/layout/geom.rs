@@ -545,24 +545,42 @@ impl LogicalRect<Au> {
         &self,
         containing_block: Option<&ContainingBlock<'_>>,
     ) -> PhysicalRect<Au> {
-        let mode = containing_block.map_or_else(WritingMode::horizontal_tb, |containing_block| {
-            containing_block.style.writing_mode
-        });
+        match containing_block {
+            Some(containing_block) => self.as_physical_in_writing_mode(
+                containing_block.style.writing_mode,
+                containing_block.size.inline,
+            ),
+            None => self.as_physical_in_writing_mode(WritingMode::horizontal_tb(), Au::zero()),
+        }
+    }
+
+    /// Convert this rectangle to a physical one, relative to the start corner of a containing
+    /// block with the given writing mode and inline size.
+    fn as_physical_in_writing_mode(
+        &self,
+        mode: WritingMode,
+        containing_block_inline_size: Au,
+    ) -> PhysicalRect<Au> {
         let (x, y, width, height) = if mode.is_vertical() {
-            // TODO: Bottom-to-top writing modes are not supported.
+            // TODO: Right-to-left block flow directions are not supported yet, because they
+            // need the final block size of the containing block.
+            let y = if mode.is_inline_tb() {
+                self.start_corner.inline
+            } else {
+                containing_block_inline_size - self.max_inline_position()
+            };
             (
                 self.start_corner.block,
-                self.start_corner.inline,
+                y,
                 self.size.block,
                 self.size.inline,
             )
         } else {
             let y = self.start_corner.block;
-            let x = match containing_block {
-                Some(containing_block) if !mode.is_bidi_ltr() => {
-                    containing_block.size.inline - self.max_inline_position()
-                },
-                _ => self.start_corner.inline,
+            let x = if mode.is_bidi_ltr() {
+                self.start_corner.inline
+            } else {
+                containing_block_inline_size - self.max_inline_position()
             };
             (x, y, self.size.inline, self.size.block)
         };
@@ -629,56 +647,194 @@ pub(crate) trait ToLogicalWithContainingBlock<LogicalType> {
 
 impl ToLogicalWithContainingBlock<LogicalVec2<Au>> for PhysicalPoint<Au> {
     fn to_logical(&self, containing_block: &ContainingBlock) -> LogicalVec2<Au> {
-        let writing_mode = containing_block.style.writing_mode;
-        // TODO: Bottom-to-top and right-to-left vertical writing modes are not supported yet.
-        if writing_mode.is_vertical() {
-            LogicalVec2 {
-                inline: self.y,
-                block: self.x,
-            }
-        } else {
-            LogicalVec2 {
-                inline: if writing_mode.is_bidi_ltr() {
-                    self.x
-                } else {
-                    containing_block.size.inline - self.x
-                },
-                block: self.y,
-            }
-        }
+        physical_point_to_logical(
+            *self,
+            containing_block.style.writing_mode,
+            containing_block.size.inline,
+        )
     }
 }
 
 impl ToLogicalWithContainingBlock<LogicalRect<Au>> for PhysicalRect<Au> {
     fn to_logical(&self, containing_block: &ContainingBlock) -> LogicalRect<Au> {
-        let inline_start;
-        let block_start;
-        let inline;
-        let block;
-
-        let writing_mode = containing_block.style.writing_mode;
-        if writing_mode.is_vertical() {
-            // TODO: Bottom-to-top and right-to-left vertical writing modes are not supported yet.
-            inline = self.size.height;
-            block = self.size.width;
-            block_start = self.origin.x;
-            inline_start = self.origin.y;
-        } else {
-            inline = self.size.width;
-            block = self.size.height;
-            block_start = self.origin.y;
-            if writing_mode.is_bidi_ltr() {
-                inline_start = self.origin.x;
+        physical_rect_to_logical(
+            *self,
+            containing_block.style.writing_mode,
+            containing_block.size.inline,
+        )
+    }
+}
+
+fn physical_point_to_logical(
+    point: PhysicalPoint<Au>,
+    writing_mode: WritingMode,
+    containing_block_inline_size: Au,
+) -> LogicalVec2<Au> {
+    // TODO: Right-to-left block flow directions are not supported yet.
+    if writing_mode.is_vertical() {
+        LogicalVec2 {
+            inline: if writing_mode.is_inline_tb() {
+                point.y
             } else {
-                inline_start = containing_block.size.inline - (self.origin.x + self.size.width);
-            }
+                containing_block_inline_size - point.y
+            },
+            block: point.x,
+        }
+    } else {
+        LogicalVec2 {
+            inline: if writing_mode.is_bidi_ltr() {
+                point.x
+            } else {
+                containing_block_inline_size - point.x
+            },
+            block: point.y,
         }
+    }
+}
+
+fn physical_rect_to_logical(
+    rect: PhysicalRect<Au>,
+    writing_mode: WritingMode,
+    containing_block_inline_size: Au,
+) -> LogicalRect<Au> {
+    let inline_start;
+    let block_start;
+    let inline;
+    let block;
+
+    if writing_mode.is_vertical() {
+        // TODO: Right-to-left block flow directions are not supported yet.
+        inline = rect.size.height;
+        block = rect.size.width;
+        block_start = rect.origin.x;
+        if writing_mode.is_inline_tb() {
+            inline_start = rect.origin.y;
+        } else {
+            inline_start = containing_block_inline_size - (rect.origin.y + rect.size.height);
+        }
+    } else {
+        inline = rect.size.width;
+        block = rect.size.height;
+        block_start = rect.origin.y;
+        if writing_mode.is_bidi_ltr() {
+            inline_start = rect.origin.x;
+        } else {
+            inline_start = containing_block_inline_size - (rect.origin.x + rect.size.width);
+        }
+    }
+    LogicalRect {
+        start_corner: LogicalVec2 {
+            inline: inline_start,
+            block: block_start,
+        },
+        size: LogicalVec2 { inline, block },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every combination of `writing-mode` and `direction`, along with whether the inline axis
+    /// runs from the physical end to the physical start, i.e. right to left or bottom to top.
+    fn writing_modes() -> [(WritingMode, bool); 10] {
+        let vertical_rl = WritingMode::VERTICAL;
+        let vertical_lr =
+            WritingMode::VERTICAL | WritingMode::VERTICAL_LR | WritingMode::LINE_INVERTED;
+        let sideways_rl = WritingMode::VERTICAL | WritingMode::VERTICAL_SIDEWAYS;
+        let sideways_lr =
+            WritingMode::VERTICAL | WritingMode::VERTICAL_LR | WritingMode::VERTICAL_SIDEWAYS;
+        let rtl = WritingMode::RTL | WritingMode::INLINE_REVERSED;
+        [
+            (WritingMode::horizontal_tb(), false),
+            (WritingMode::RTL, true),
+            (vertical_rl, false),
+            (vertical_rl | rtl, true),
+            (vertical_lr, false),
+            (vertical_lr | rtl, true),
+            (sideways_rl, false),
+            (sideways_rl | rtl, true),
+            (sideways_lr | WritingMode::INLINE_REVERSED, true),
+            (sideways_lr | WritingMode::RTL, false),
+        ]
+    }
+
+    fn logical_rect() -> LogicalRect<Au> {
         LogicalRect {
             start_corner: LogicalVec2 {
-                inline: inline_start,
-                block: block_start,
+                inline: Au::from_px(10),
+                block: Au::from_px(5),
             },
-            size: LogicalVec2 { inline, block },
+            size: LogicalVec2 {
+                inline: Au::from_px(20),
+                block: Au::from_px(30),
+            },
+        }
+    }
+
+    #[test]
+    fn test_logical_rect_to_physical() {
+        let containing_block_inline_size = Au::from_px(100);
+        for (index, (mode, inline_reversed)) in writing_modes().into_iter().enumerate() {
+            let physical =
+                logical_rect().as_physical_in_writing_mode(mode, containing_block_inline_size);
+            let inline_start = if inline_reversed { 70 } else { 10 };
+            let expected = if mode.is_vertical() {
+                PhysicalRect::new(
+                    PhysicalPoint::new(Au::from_px(5), Au::from_px(inline_start)),
+                    PhysicalSize::new(Au::from_px(30), Au::from_px(20)),
+                )
+            } else {
+                PhysicalRect::new(
+                    PhysicalPoint::new(Au::from_px(inline_start), Au::from_px(5)),
+                    PhysicalSize::new(Au::from_px(20), Au::from_px(30)),
+                )
+            };
+            assert_eq!(physical, expected, "writing mode {index}");
+        }
+    }
+
+    #[test]
+    fn test_rect_round_trip() {
+        let containing_block_inline_size = Au::from_px(100);
+        let physical = PhysicalRect::new(
+            PhysicalPoint::new(Au::from_px(1), Au::from_px(2)),
+            PhysicalSize::new(Au::from_px(3), Au::from_px(4)),
+        );
+        for (index, (mode, _)) in writing_modes().into_iter().enumerate() {
+            let logical = physical_rect_to_logical(physical, mode, containing_block_inline_size);
+            assert_eq!(
+                logical.as_physical_in_writing_mode(mode, containing_block_inline_size),
+                physical,
+                "writing mode {index}"
+            );
+
+            let logical = logical_rect();
+            let round_trip = physical_rect_to_logical(
+                logical.as_physical_in_writing_mode(mode, containing_block_inline_size),
+                mode,
+                containing_block_inline_size,
+            );
+            assert!(
+                round_trip.start_corner == logical.start_corner,
+                "writing mode {index}"
+            );
+            assert!(round_trip.size == logical.size, "writing mode {index}");
+        }
+    }
+
+    #[test]
+    fn test_point_to_logical_matches_rect() {
+        let containing_block_inline_size = Au::from_px(100);
+        let physical = PhysicalRect::new(
+            PhysicalPoint::new(Au::from_px(1), Au::from_px(2)),
+            PhysicalSize::new(Au::zero(), Au::zero()),
+        );
+        for (index, (mode, _)) in writing_modes().into_iter().enumerate() {
+            let point =
+                physical_point_to_logical(physical.origin, mode, containing_block_inline_size);
+            let rect = physical_rect_to_logical(physical, mode, containing_block_inline_size);
+            assert!(point == rect.start_corner, "writing mode {index}");
         }
     }
 }
@@ -8,13 +8,16 @@ use app_units::Au;
 use atomic_refcell::AtomicRefCell;
 use malloc_size_of_derive::MallocSizeOf;
 use servo_arc::Arc;
+use style::Zero;
 use style::properties::ComputedValues;
 
 use crate::context::LayoutContext;
 use crate::formatting_contexts::Baselines;
 use crate::fragment_tree::{BaseFragmentInfo, CollapsedBlockMargins, Fragment, SpecificLayoutInfo};
 use crate::positioned::PositioningContext;
-use crate::sizing::{ComputeInlineContentSizes, InlineContentSizesResult, SizeConstraint};
+use crate::sizing::{
+    ComputeInlineContentSizes, ContentSizes, InlineContentSizesResult, SizeConstraint,
+};
 use crate::{ConstraintSpace, ContainingBlockSize};
 
 /// A box tree node that handles containing information about style and the original DOM
@@ -52,6 +55,16 @@ impl LayoutBoxBase {
         constraint_space: &ConstraintSpace,
         layout_box: &impl ComputeInlineContentSizes,
     ) -> InlineContentSizesResult {
+        // Query containers have size containment in the inline axis, so their contents do
+        // not contribute to their intrinsic inline size. Containment in the block axis is
+        // handled when laying them out.
+        if !self.style.clone_container_type().is_normal() {
+            return InlineContentSizesResult {
+                sizes: ContentSizes::zero(),
+                depends_on_block_constraints: false,
+            };
+        }
+
         let mut cache = self.cached_inline_content_size.borrow_mut();
         if let Some(cached_inline_content_size) = cache.as_ref() {
             let (previous_cb_block_size, result) = **cached_inline_content_size;
@@ -15,8 +15,8 @@ use app_units::Au;
 use base::Epoch;
 use base::id::{PipelineId, WebViewId};
 use bitflags::bitflags;
-use compositing_traits::CrossProcessCompositorApi;
 use compositing_traits::display_list::ScrollType;
+use compositing_traits::{CrossProcessCompositorApi, SerializableImageData};
 use embedder_traits::{Theme, ViewportDetails};
 use euclid::default::{Point2D as UntypedPoint2D, Rect as UntypedRect};
 use euclid::{Point2D, Scale, Size2D};
@@ -24,11 +24,12 @@ use fnv::FnvHashMap;
 use fonts::{FontContext, FontContextWebFontMethods};
 use fonts_traits::StylesheetWebFontLoadFinishedCallback;
 use fxhash::FxHashMap;
-use ipc_channel::ipc::IpcSender;
+use ipc_channel::ipc::{IpcSender, IpcSharedMemory};
+use layout_api::wrapper_traits::LayoutNode;
 use layout_api::{
-    IFrameSizes, Layout, LayoutConfig, LayoutDamage, LayoutFactory, OffsetParentResponse, QueryMsg,
-    ReflowGoal, ReflowPhasesRun, ReflowRequest, ReflowRequestRestyle, ReflowResult,
-    TrustedNodeAddress,
+    IFrameSizes, Layout, LayoutConfig, LayoutDamage, LayoutFactory, LayoutShift,
+    OffsetParentResponse, QueryMsg, ReflowGoal, ReflowPhasesRun, ReflowRequest,
+    ReflowRequestRestyle, ReflowResult, TrustedNodeAddress, ViewTransitionSnapshot,
 };
 use log::{debug, error, warn};
 use malloc_size_of::{MallocConditionalSizeOf, MallocSizeOf, MallocSizeOfOps};
@@ -75,11 +76,15 @@ use style::{Zero, driver};
 use style_traits::{CSSPixel, SpeculativePainter};
 use stylo_atoms::Atom;
 use url::Url;
-use webrender_api::ExternalScrollId;
 use webrender_api::units::{DevicePixel, LayoutVector2D};
+use webrender_api::{
+    ExternalScrollId, ImageDescriptor, ImageDescriptorFlags, ImageFormat, ImageKey,
+};
 
 use crate::context::{CachedImageOrError, ImageResolver, LayoutContext};
-use crate::display_list::{DisplayListBuilder, HitTest, StackingContextTree};
+use crate::display_list::{DisplayListBuilder, HitTest, PaintedElements, StackingContextTree};
+use crate::dom::{NodeExt, QueryContainer};
+use crate::geom::PhysicalRect;
 use crate::query::{
     get_the_text_steps, process_client_rect_request, process_content_box_request,
     process_content_boxes_request, process_node_scroll_area_request, process_offset_parent_query,
@@ -88,6 +93,10 @@ use crate::query::{
 use crate::traversal::{RecalcStyle, compute_damage_and_repair_style};
 use crate::{BoxTree, FragmentTree};
 
+/// The maximum number of times layout is run again in a single reflow because the size of a
+/// query container changed.
+const MAX_CONTAINER_QUERY_PASSES: usize = 4;
+
 // This mutex is necessary due to syncronisation issues between two different types of thread-local storage
 // which manifest themselves when the layout thread tries to layout iframes in parallel with the main page
 //
@@ -105,6 +114,12 @@ static SERVO_CSS: &[u8] = include_bytes!("./stylesheets/servo.css");
 /// A CSS file to style the presentational hints.
 static PRESENTATIONAL_HINTS_CSS: &[u8] = include_bytes!("./stylesheets/presentational-hints.css");
 
+/// A CSS file to style popovers.
+static POPOVER_CSS: &[u8] = include_bytes!("./stylesheets/popover.css");
+
+/// A CSS file to style MathML elements.
+static MATHML_CSS: &[u8] = include_bytes!("./stylesheets/mathml.css");
+
 /// A CSS file to style the quirks mode.
 static QUIRKS_MODE_CSS: &[u8] = include_bytes!("./stylesheets/quirks-mode.css");
 
@@ -159,6 +174,10 @@ pub struct LayoutThread {
     /// The box tree.
     box_tree: RefCell<Option<Arc<BoxTree>>>,
 
+    /// The elements that were query containers when they were last styled. Their sizes are
+    /// recorded after every layout, as container queries are evaluated against them.
+    query_containers: RefCell<Vec<QueryContainer>>,
+
     /// The fragment tree.
     fragment_tree: RefCell<Option<Rc<FragmentTree>>>,
 
@@ -187,6 +206,19 @@ pub struct LayoutThread {
     ///
     /// If this changed, then we need to create a new display list.
     previously_highlighted_dom_node: Cell<Option<OpaqueNode>>,
+
+    /// Tracks the view transition snapshot painted during the last reflow.
+    ///
+    /// If this changed, then we need to create a new display list.
+    previous_view_transition_snapshot: Cell<Option<ViewTransitionSnapshot>>,
+
+    /// The border box of each element painted in the last display list, used to detect the
+    /// elements that moved when a new display list is built.
+    previous_box_rects: RefCell<HashMap<OpaqueNode, PhysicalRect<Au>>>,
+
+    /// An opaque image used as the mask of polygon clips, created the first time that a
+    /// display list needs one.
+    clip_mask_image_key: Cell<Option<ImageKey>>,
 }
 
 pub struct LayoutFactoryImpl();
@@ -203,7 +235,10 @@ impl Drop for LayoutThread {
             .font_context
             .collect_unused_webrender_resources(true /* all */);
         self.compositor_api
-            .remove_unused_font_resources(keys, instance_keys)
+            .remove_unused_font_resources(keys, instance_keys);
+        if let Some(clip_mask_image_key) = self.clip_mask_image_key.get() {
+            self.compositor_api.delete_image(clip_mask_image_key);
+        }
     }
 }
 
@@ -408,29 +443,28 @@ impl Layout for LayoutThread {
 
     fn exit_now(&mut self) {}
 
-    fn collect_reports(&self, reports: &mut Vec<Report>, ops: &mut MallocSizeOfOps) {
+    fn collect_reports(&self, prefix: &str, reports: &mut Vec<Report>, ops: &mut MallocSizeOfOps) {
         // TODO: Measure more than just display list, stylist, and font context.
-        let formatted_url = &format!("url({})", self.url);
         reports.push(Report {
-            path: path![formatted_url, "layout-thread", "display-list"],
+            path: path![prefix, "layout-thread", "display-list"],
             kind: ReportKind::ExplicitJemallocHeapSize,
             size: 0,
         });
 
         reports.push(Report {
-            path: path![formatted_url, "layout-thread", "stylist"],
+            path: path![prefix, "layout-thread", "stylist"],
             kind: ReportKind::ExplicitJemallocHeapSize,
             size: self.stylist.size_of(ops),
         });
 
         reports.push(Report {
-            path: path![formatted_url, "layout-thread", "font-context"],
+            path: path![prefix, "layout-thread", "font-context"],
             kind: ReportKind::ExplicitJemallocHeapSize,
             size: self.font_context.conditional_size_of(ops),
         });
 
         reports.push(Report {
-            path: path![formatted_url, "layout-thread", "box-tree"],
+            path: path![prefix, "layout-thread", "box-tree"],
             kind: ReportKind::ExplicitJemallocHeapSize,
             size: self
                 .box_tree
@@ -440,7 +474,7 @@ impl Layout for LayoutThread {
         });
 
         reports.push(Report {
-            path: path![formatted_url, "layout-thread", "fragment-tree"],
+            path: path![prefix, "layout-thread", "fragment-tree"],
             kind: ReportKind::ExplicitJemallocHeapSize,
             size: self
                 .fragment_tree
@@ -553,6 +587,7 @@ impl LayoutThread {
             need_new_display_list: Cell::new(false),
             need_new_stacking_context_tree: Cell::new(false),
             box_tree: Default::default(),
+            query_containers: Default::default(),
             fragment_tree: Default::default(),
             stacking_context_tree: Default::default(),
             // Epoch starts at 1 because of the initial display list for epoch 0 that we send to WR
@@ -562,6 +597,9 @@ impl LayoutThread {
             resolved_images_cache: Default::default(),
             debug: opts::get().debug.clone(),
             previously_highlighted_dom_node: Cell::new(None),
+            previous_view_transition_snapshot: Cell::new(None),
+            previous_box_rects: Default::default(),
+            clip_mask_image_key: Default::default(),
         }
     }
 
@@ -708,19 +746,51 @@ impl LayoutThread {
             animation_timeline_value: reflow_request.animation_timeline_value,
         });
 
-        let (mut reflow_phases_run, damage, iframe_sizes) = self.restyle_and_build_trees(
+        let (mut reflow_phases_run, mut damage, mut iframe_sizes) = self.restyle_and_build_trees(
             &mut reflow_request,
             document,
             root_element,
             &image_resolver,
         );
+
+        // Container queries are evaluated against the sizes of their containers from the
+        // previous layout, so when one of those sizes changes, the descendants of the container
+        // must be restyled and laid out again. This is repeated a bounded number of times to
+        // avoid cycles where a query result changes the size of its own container.
+        if pref!(layout_container_queries_enabled) {
+            for _ in 0..MAX_CONTAINER_QUERY_PASSES {
+                if !reflow_phases_run.contains(ReflowPhasesRun::RanLayout) ||
+                    !self.update_query_container_sizes()
+                {
+                    break;
+                }
+                if let Some(restyle) = reflow_request.restyle.as_mut() {
+                    restyle.dirty_root = Some(root_element.as_node().to_trusted_node_address());
+                    restyle.stylesheets_changed = false;
+                }
+                let (phases, new_damage, new_iframe_sizes) = self.restyle_and_build_trees(
+                    &mut reflow_request,
+                    document,
+                    root_element,
+                    &image_resolver,
+                );
+                if !phases.contains(ReflowPhasesRun::RanLayout) {
+                    break;
+                }
+                reflow_phases_run.insert(phases);
+                damage.insert(new_damage);
+                iframe_sizes = new_iframe_sizes;
+            }
+        }
+
         if self.calculate_overflow(damage) {
             reflow_phases_run.insert(ReflowPhasesRun::CalculatedOverflow);
         }
         if self.build_stacking_context_tree_for_reflow(&reflow_request, damage) {
             reflow_phases_run.insert(ReflowPhasesRun::BuiltStackingContextTree);
         }
-        if self.build_display_list(&reflow_request, damage, &image_resolver) {
+        let painted_elements = self.build_display_list(&reflow_request, damage, &image_resolver);
+        if painted_elements.is_some() {
             reflow_phases_run.insert(ReflowPhasesRun::BuiltDisplayList);
         }
         if self.handle_update_scroll_node_request(&reflow_request) {
@@ -730,15 +800,61 @@ impl LayoutThread {
         let pending_images = std::mem::take(&mut *image_resolver.pending_images.lock());
         let pending_rasterization_images =
             std::mem::take(&mut *image_resolver.pending_rasterization_images.lock());
+        let (contentful_paints, layout_shifts, is_contentful) = match painted_elements {
+            Some(painted_elements) => (
+                painted_elements.contentful_paints,
+                self.detect_layout_shifts(painted_elements.box_rects),
+                painted_elements.is_contentful,
+            ),
+            None => Default::default(),
+        };
 
         Some(ReflowResult {
             reflow_phases_run,
             pending_images,
             pending_rasterization_images,
+            contentful_paints,
+            layout_shifts,
+            is_contentful,
             iframe_sizes: Some(iframe_sizes),
         })
     }
 
+    /// Record the sizes of all query containers from the fragments of the last layout, marking
+    /// the descendants of those whose size changed for restyle. Containers that were removed
+    /// from the document or are not query containers anymore are dropped from the list.
+    /// Returns whether any of the sizes changed.
+    fn update_query_container_sizes(&self) -> bool {
+        let mut changed = false;
+        let mut query_containers = self.query_containers.borrow_mut();
+        query_containers.retain(|query_container| query_container.node().is_some());
+        for query_container in query_containers.iter() {
+            let Some(node) = query_container.node() else {
+                continue;
+            };
+            let Some(element) = node.as_element() else {
+                continue;
+            };
+            if !node.update_query_container_size() {
+                continue;
+            }
+            changed = true;
+
+            if let Some(mut data) = element.mutate_data() {
+                data.hint.insert(RestyleHint::RESTYLE_DESCENDANTS);
+            }
+            let mut current = Some(element);
+            while let Some(element) = current {
+                if element.has_dirty_descendants() {
+                    break;
+                }
+                unsafe { element.set_dirty_descendants() };
+                current = element.traversal_parent();
+            }
+        }
+        changed
+    }
+
     fn update_device_if_necessary(
         &mut self,
         reflow_request: &ReflowRequest,
@@ -842,7 +958,10 @@ impl LayoutThread {
             &snapshot_map,
         );
 
-        if self.previously_highlighted_dom_node.get() != reflow_request.highlighted_dom_node {
+        if self.previously_highlighted_dom_node.get() != reflow_request.highlighted_dom_node ||
+            self.previous_view_transition_snapshot.get() !=
+                reflow_request.view_transition_snapshot
+        {
             // Need to manually force layout to build a new display list regardless of whether the box tree
             // changed or not.
             self.need_new_display_list.set(true);
@@ -905,6 +1024,7 @@ impl LayoutThread {
             &layout_context.style_context,
             root_node,
             damage_from_environment,
+            &mut self.query_containers.borrow_mut(),
         );
 
         if !damage.contains(RestyleDamage::RELAYOUT) {
@@ -1029,13 +1149,8 @@ impl LayoutThread {
         // Build the StackingContextTree. This turns the `FragmentTree` into a
         // tree of fragments in CSS painting order and also creates all
         // applicable spatial and clip nodes.
-        let mut new_stacking_context_tree = StackingContextTree::new(
-            fragment_tree,
-            viewport_details,
-            self.id.into(),
-            !self.have_ever_generated_display_list.get(),
-            &self.debug,
-        );
+        let mut new_stacking_context_tree =
+            StackingContextTree::new(fragment_tree, viewport_details, self.id.into(), &self.debug);
 
         // When a new StackingContextTree is built, it contains a freshly built
         // ScrollTree. We want to preserve any existing scroll offsets in that tree,
@@ -1065,26 +1180,27 @@ impl LayoutThread {
         true
     }
 
-    /// Build the display list for the current layout and send it to the renderer. If no display
-    /// list is built, returns false.
+    /// Build the display list for the current layout and send it to the renderer, returning
+    /// information about the elements painted in it. If no display list is built, returns
+    /// `None`.
     #[servo_tracing::instrument(name = "Display List Construction", skip_all)]
     fn build_display_list(
         &self,
         reflow_request: &ReflowRequest,
         damage: RestyleDamage,
         image_resolver: &Arc<ImageResolver>,
-    ) -> bool {
+    ) -> Option<PaintedElements> {
         if !ReflowPhases::necessary(&reflow_request.reflow_goal)
             .contains(ReflowPhases::DisplayListConstruction)
         {
-            return false;
+            return None;
         }
         let Some(fragment_tree) = &*self.fragment_tree.borrow() else {
-            return false;
+            return None;
         };
         let mut stacking_context_tree = self.stacking_context_tree.borrow_mut();
         let Some(stacking_context_tree) = stacking_context_tree.as_mut() else {
-            return false;
+            return None;
         };
 
         // It's not enough to simply check `damage` here as not all reflow requests
@@ -1092,7 +1208,7 @@ impl LayoutThread {
         // in a previous refow, we cannot skip display list generation here the next time
         // a display list is requested.
         if !self.need_new_display_list.get() && !damage.contains(RestyleDamage::REPAINT) {
-            return false;
+            return None;
         }
 
         let mut epoch = self.epoch.get();
@@ -1100,13 +1216,19 @@ impl LayoutThread {
         self.epoch.set(epoch);
         stacking_context_tree.compositor_info.epoch = epoch.into();
 
-        let built_display_list = DisplayListBuilder::build(
+        let clip_mask_image_key = if stacking_context_tree.clip_store.has_polygons() {
+            self.clip_mask_image_key()
+        } else {
+            None
+        };
+        let (built_display_list, painted_elements) = DisplayListBuilder::build(
             reflow_request,
             stacking_context_tree,
             fragment_tree,
             image_resolver.clone(),
             self.device().device_pixel_ratio(),
             &self.debug,
+            clip_mask_image_key,
         );
         self.compositor_api.send_display_list(
             self.webview_id,
@@ -1124,7 +1246,52 @@ impl LayoutThread {
         self.need_new_display_list.set(false);
         self.previously_highlighted_dom_node
             .set(reflow_request.highlighted_dom_node);
-        true
+        self.previous_view_transition_snapshot
+            .set(reflow_request.view_transition_snapshot);
+        Some(painted_elements)
+    }
+
+    /// Get the opaque image used as the mask of polygon clips, creating it if necessary.
+    fn clip_mask_image_key(&self) -> Option<ImageKey> {
+        if let Some(clip_mask_image_key) = self.clip_mask_image_key.get() {
+            return Some(clip_mask_image_key);
+        }
+
+        let clip_mask_image_key = self.compositor_api.generate_image_key_blocking()?;
+        self.compositor_api.add_image(
+            clip_mask_image_key,
+            ImageDescriptor::new(1, 1, ImageFormat::BGRA8, ImageDescriptorFlags::IS_OPAQUE),
+            SerializableImageData::Raw(IpcSharedMemory::from_bytes(&[255; 4])),
+        );
+        self.clip_mask_image_key.set(Some(clip_mask_image_key));
+        Some(clip_mask_image_key)
+    }
+
+    /// Compare the border boxes of the elements painted in a new display list with the ones
+    /// of the previous display list, returning the elements whose box moved.
+    /// <https://wicg.github.io/layout-instability/#unstable-node>
+    fn detect_layout_shifts(
+        &self,
+        box_rects: HashMap<OpaqueNode, PhysicalRect<Au>>,
+    ) -> Vec<LayoutShift> {
+        let previous_box_rects = self.previous_box_rects.replace(box_rects);
+        let box_rects = self.previous_box_rects.borrow();
+        box_rects
+            .iter()
+            .filter_map(|(node, current_rect)| {
+                // Only elements that were painted in the previous display list can shift,
+                // and only changes to their starting point count as a shift.
+                let previous_rect = previous_box_rects.get(node)?;
+                if previous_rect.origin == current_rect.origin {
+                    return None;
+                }
+                Some(LayoutShift {
+                    node: (*node).into(),
+                    previous_rect: previous_rect.to_untyped(),
+                    current_rect: current_rect.to_untyped(),
+                })
+            })
+            .collect()
     }
 
     fn set_scroll_offset_from_script(
@@ -1257,6 +1424,22 @@ fn get_ua_stylesheets() -> Result<UserAgentStylesheets, &'static str> {
         )?,
     ];
 
+    if pref!(dom_mathml_enabled) {
+        user_or_user_agent_stylesheets.push(parse_ua_stylesheet(
+            shared_lock,
+            "mathml.css",
+            MATHML_CSS,
+        )?);
+    }
+
+    if pref!(dom_popover_enabled) {
+        user_or_user_agent_stylesheets.push(parse_ua_stylesheet(
+            shared_lock,
+            "popover.css",
+            POPOVER_CSS,
+        )?);
+    }
+
     for (contents, url) in &opts::get().user_stylesheets {
         user_or_user_agent_stylesheets.push(DocumentStyleSheet(ServoArc::new(
             Stylesheet::from_bytes(
@@ -6,6 +6,7 @@ use app_units::Au;
 use malloc_size_of_derive::MallocSizeOf;
 use style::Zero;
 use style::color::AbsoluteColor;
+use style::computed_values::_servo_top_layer::T as TopLayer;
 use style::computed_values::direction::T as Direction;
 use style::computed_values::isolation::T as ComputedIsolation;
 use style::computed_values::mix_blend_mode::T as ComputedMixBlendMode;
@@ -13,16 +14,18 @@ use style::computed_values::position::T as ComputedPosition;
 use style::computed_values::transform_style::T as ComputedTransformStyle;
 use style::computed_values::unicode_bidi::T as UnicodeBidi;
 use style::logical_geometry::{Direction as AxisDirection, PhysicalSide, WritingMode};
-use style::properties::ComputedValues;
 use style::properties::longhands::backface_visibility::computed_value::T as BackfaceVisiblity;
 use style::properties::longhands::box_sizing::computed_value::T as BoxSizing;
 use style::properties::longhands::column_span::computed_value::T as ColumnSpan;
 use style::properties::style_structs::Border;
+use style::properties::{ComputedValues, LonghandId, PropertyDeclarationId};
 use style::servo::selector_parser::PseudoElement;
 use style::values::CSSFloat;
 use style::values::computed::basic_shape::ClipPath;
 use style::values::computed::image::Image as ComputedImageLayer;
-use style::values::computed::{AlignItems, BorderStyle, Color, Inset, LengthPercentage, Margin};
+use style::values::computed::{
+    AlignItems, BorderStyle, Color, ContainerType, Inset, LengthPercentage, Margin,
+};
 use style::values::generics::box_::Perspective;
 use style::values::generics::position::{GenericAspectRatio, PreferredRatio};
 use style::values::generics::transform::{GenericRotate, GenericScale, GenericTranslate};
@@ -292,6 +295,33 @@ pub(crate) struct OverflowDirection {
     pub downward: bool,
 }
 
+/// Whether `id` is one of the properties whose running animations make an element a
+/// candidate for its own WebRender stacking context. Changes to these properties can be
+/// applied to the stacking context alone, without rebuilding what is painted inside it.
+pub(crate) fn is_animated_effect_property(id: PropertyDeclarationId) -> bool {
+    matches!(
+        id,
+        PropertyDeclarationId::Longhand(
+            LonghandId::Transform |
+                LonghandId::Translate |
+                LonghandId::Rotate |
+                LonghandId::Scale |
+                LonghandId::Opacity
+        )
+    )
+}
+
+/// See [`ComputedValuesExt::is_layerization_candidate`].
+fn is_layerization_candidate(
+    will_change: WillChangeBits,
+    is_transformable: bool,
+    has_animated_effects: bool,
+) -> bool {
+    has_animated_effects ||
+        (is_transformable && will_change.intersects(WillChangeBits::TRANSFORM)) ||
+        will_change.intersects(WillChangeBits::OPACITY)
+}
+
 pub(crate) trait ComputedValuesExt {
     fn physical_box_offsets(&self) -> PhysicalSides<LengthPercentageOrAuto<'_>>;
     fn box_offsets(&self, writing_mode: WritingMode) -> LogicalSides<LengthPercentageOrAuto<'_>>;
@@ -334,12 +364,20 @@ pub(crate) trait ComputedValuesExt {
     fn is_transformable(&self, fragment_flags: FragmentFlags) -> bool;
     fn has_transform_or_perspective_style(&self) -> bool;
     fn has_effective_transform_or_perspective(&self, fragment_flags: FragmentFlags) -> bool;
+    fn will_change_transform(&self, fragment_flags: FragmentFlags) -> bool;
+    fn is_layerization_candidate(
+        &self,
+        fragment_flags: FragmentFlags,
+        has_animated_effects: bool,
+    ) -> bool;
     fn z_index_applies(&self, fragment_flags: FragmentFlags) -> bool;
+    fn is_in_top_layer(&self) -> bool;
     fn effective_z_index(&self, fragment_flags: FragmentFlags) -> i32;
     fn effective_overflow(&self, fragment_flags: FragmentFlags) -> AxesOverflow;
     fn establishes_block_formatting_context(&self, fragment_flags: FragmentFlags) -> bool;
     fn establishes_stacking_context(&self, fragment_flags: FragmentFlags) -> bool;
     fn establishes_scroll_container(&self, fragment_flags: FragmentFlags) -> bool;
+    fn has_block_axis_size_containment(&self) -> bool;
     fn establishes_containing_block_for_absolute_descendants(
         &self,
         fragment_flags: FragmentFlags,
@@ -558,6 +596,35 @@ impl ComputedValuesExt for ComputedValues {
         self.is_transformable(fragment_flags) && self.has_transform_or_perspective_style()
     }
 
+    /// Returns true if `will-change` hints that the transform of this element is going to
+    /// change, and the `transform` property applies to this element.
+    #[inline]
+    fn will_change_transform(&self, fragment_flags: FragmentFlags) -> bool {
+        self.is_transformable(fragment_flags) &&
+            self.clone_will_change()
+                .bits
+                .intersects(WillChangeBits::TRANSFORM)
+    }
+
+    /// Whether this element is worth painting into its own WebRender stacking context
+    /// (and, for `will-change: transform`, its own reference frame) even when it has no
+    /// effects that require one, because its transform or opacity is going to be
+    /// animated. That is either hinted by `will-change` or known from
+    /// `has_animated_effects`, which callers set when the element has a running
+    /// animation or transition of one of those properties. Isolating such elements keeps
+    /// those changes from invalidating the rendering of the rest of the page.
+    fn is_layerization_candidate(
+        &self,
+        fragment_flags: FragmentFlags,
+        has_animated_effects: bool,
+    ) -> bool {
+        is_layerization_candidate(
+            self.clone_will_change().bits,
+            self.is_transformable(fragment_flags),
+            has_animated_effects,
+        )
+    }
+
     /// Whether the `z-index` property applies to this fragment.
     fn z_index_applies(&self, fragment_flags: FragmentFlags) -> bool {
         // As per CSS 2 § 9.9.1, `z-index` applies to positioned elements.
@@ -579,11 +646,23 @@ impl ComputedValuesExt for ComputedValues {
         fragment_flags.contains(FragmentFlags::IS_FLEX_OR_GRID_ITEM)
     }
 
+    /// Whether this box is rendered in the top layer, which the user agent stylesheet indicates
+    /// with the internal `-servo-top-layer` property.
+    /// <https://drafts.csswg.org/css-position-4/#top-layer>
+    fn is_in_top_layer(&self) -> bool {
+        self.get_box()._servo_top_layer == TopLayer::Top
+    }
+
     /// Get the effective z-index of this fragment. Z-indices only apply to positioned elements
     /// per CSS 2 9.9.1 (<http://www.w3.org/TR/CSS2/visuren.html#z-index>), so this value may differ
     /// from the value specified in the style.
+    ///
+    /// Boxes in the top layer are painted above everything else, which is approximated by giving
+    /// them the largest possible z-index. Among each other they are painted in tree order.
     fn effective_z_index(&self, fragment_flags: FragmentFlags) -> i32 {
-        if self.z_index_applies(fragment_flags) {
+        if self.is_in_top_layer() {
+            i32::MAX
+        } else if self.z_index_applies(fragment_flags) {
             self.get_position().z_index.integer_or(0)
         } else {
             0
@@ -691,6 +770,13 @@ impl ComputedValuesExt for ComputedValues {
         self.effective_overflow(fragment_flags).x.is_scrollable()
     }
 
+    /// Whether the contents of this box are ignored when computing its block size, as
+    /// it is a query container with `container-type: size`.
+    /// <https://drafts.csswg.org/css-contain-2/#containment-size>
+    fn has_block_axis_size_containment(&self) -> bool {
+        self.clone_container_type().contains(ContainerType::SIZE)
+    }
+
     /// Returns true if this fragment establishes a new stacking context and false otherwise.
     fn establishes_stacking_context(&self, fragment_flags: FragmentFlags) -> bool {
         // From <https://www.w3.org/TR/css-will-change/#valdef-will-change-custom-ident>:
@@ -712,6 +798,11 @@ impl ComputedValuesExt for ComputedValues {
             return true;
         }
 
+        // Boxes in the top layer are always painted in their own stacking context.
+        if self.is_in_top_layer() {
+            return true;
+        }
+
         // Fixed position and sticky position always create stacking contexts.
         // Note `will-change: position` is handled above by `STACKING_CONTEXT_UNCONDITIONAL`.
         if matches!(
@@ -1303,3 +1394,85 @@ impl TransformExt for LayoutTransform {
         post_translation.then(self).then(&pre_translation)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn animated_effect_properties() {
+        for id in [
+            LonghandId::Transform,
+            LonghandId::Translate,
+            LonghandId::Rotate,
+            LonghandId::Scale,
+            LonghandId::Opacity,
+        ] {
+            assert!(is_animated_effect_property(
+                PropertyDeclarationId::Longhand(id)
+            ));
+        }
+        for id in [
+            LonghandId::Color,
+            LonghandId::Width,
+            LonghandId::Left,
+            LonghandId::BackgroundColor,
+            LonghandId::Filter,
+        ] {
+            assert!(!is_animated_effect_property(
+                PropertyDeclarationId::Longhand(id)
+            ));
+        }
+    }
+
+    #[test]
+    fn layerization_candidates() {
+        // Nothing hints that the element is going to change.
+        assert!(!is_layerization_candidate(
+            WillChangeBits::empty(),
+            true,
+            false
+        ));
+        assert!(!is_layerization_candidate(
+            WillChangeBits::Z_INDEX,
+            true,
+            false
+        ));
+
+        // `will-change: transform` only promotes elements that `transform` applies to,
+        // whereas `will-change: opacity` promotes any element.
+        assert!(is_layerization_candidate(
+            WillChangeBits::TRANSFORM,
+            true,
+            false
+        ));
+        assert!(!is_layerization_candidate(
+            WillChangeBits::TRANSFORM,
+            false,
+            false
+        ));
+        assert!(is_layerization_candidate(
+            WillChangeBits::OPACITY,
+            true,
+            false
+        ));
+        assert!(is_layerization_candidate(
+            WillChangeBits::OPACITY,
+            false,
+            false
+        ));
+
+        // A running transform or opacity animation promotes the element without any
+        // `will-change` hint.
+        assert!(is_layerization_candidate(
+            WillChangeBits::empty(),
+            true,
+            true
+        ));
+        assert!(is_layerization_candidate(
+            WillChangeBits::empty(),
+            false,
+            true
+        ));
+    }
+}
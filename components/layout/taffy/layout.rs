@@ -5,6 +5,8 @@
 use app_units::Au;
 use atomic_refcell::{AtomicRef, AtomicRefCell};
 use style::properties::ComputedValues;
+use style::values::generics::grid::GridTemplateComponent;
+use style::values::generics::length::LengthPercentageOrNormal;
 use style::values::specified::align::AlignFlags;
 use style::values::specified::box_::DisplayInside;
 use style::{Atom, Zero};
@@ -31,6 +33,34 @@ use crate::{ConstraintSpace, ContainingBlock, ContainingBlockSize};
 
 const DUMMY_NODE_ID: taffy::NodeId = taffy::NodeId::new(u64::MAX);
 
+/// The axis in which the items of a masonry grid container are packed.
+///
+/// <https://drafts.csswg.org/css-grid-3/#masonry-layout>
+#[derive(Clone, Copy, PartialEq)]
+enum MasonryAxis {
+    Rows,
+    Columns,
+}
+
+impl MasonryAxis {
+    fn for_style(style: &ComputedValues) -> Option<Self> {
+        let position_styles = style.get_position();
+        if matches!(
+            position_styles.grid_template_rows,
+            GridTemplateComponent::Masonry
+        ) {
+            Some(Self::Rows)
+        } else if matches!(
+            position_styles.grid_template_columns,
+            GridTemplateComponent::Masonry
+        ) {
+            Some(Self::Columns)
+        } else {
+            None
+        }
+    }
+}
+
 fn resolve_content_size(constraint: AvailableSpace, content_sizes: ContentSizes) -> f32 {
     match constraint {
         AvailableSpace::Definite(limit) => {
@@ -410,13 +440,53 @@ impl TaffyContainer {
             available_space: taffy_containing_block.map(AvailableSpace::from),
         };
 
-        let output = match container_ctx.style.clone_display().inside() {
+        let mut output = match container_ctx.style.clone_display().inside() {
             DisplayInside::Grid => {
                 taffy::compute_grid_layout(&mut container_ctx, DUMMY_NODE_ID, layout_input)
             },
             _ => panic!("Servo is only configured to use Taffy for CSS Grid layout"),
         };
 
+        // Taffy lays out the masonry axis of a masonry grid container as if it had a single
+        // implicit track per row or column of items, which are then packed here.
+        match MasonryAxis::for_style(container_style) {
+            Some(MasonryAxis::Rows) => {
+                let gap = match container_style.clone_row_gap() {
+                    LengthPercentageOrNormal::LengthPercentage(length_percentage) => {
+                        length_percentage
+                            .maybe_to_used_value(content_box_size_override.size.block.to_definite())
+                            .unwrap_or_default()
+                    },
+                    LengthPercentageOrNormal::Normal => Au::zero(),
+                };
+                let content_start = pbm.padding.block_start + pbm.border.block_start;
+                let content_end = self.pack_masonry_items(
+                    MasonryAxis::Rows,
+                    content_start.to_f32_px(),
+                    gap.to_f32_px(),
+                );
+                if content_box_size_override.size.block.to_definite().is_none() {
+                    output.size.height =
+                        content_end + (pbm.padding.block_end + pbm.border.block_end).to_f32_px();
+                }
+            },
+            Some(MasonryAxis::Columns) => {
+                let gap = match container_style.clone_column_gap() {
+                    LengthPercentageOrNormal::LengthPercentage(length_percentage) => {
+                        length_percentage.to_used_value(content_box_size_override.size.inline)
+                    },
+                    LengthPercentageOrNormal::Normal => Au::zero(),
+                };
+                let content_start = pbm.padding.inline_start + pbm.border.inline_start;
+                self.pack_masonry_items(
+                    MasonryAxis::Columns,
+                    content_start.to_f32_px(),
+                    gap.to_f32_px(),
+                );
+            },
+            None => {},
+        }
+
         // Convert `taffy::Layout` into Servo `Fragment`s
         // with container_ctx.child_specific_layout_infos will also moved to the corresponding `Fragment`s
         let fragments: Vec<Fragment> = self
@@ -575,6 +645,126 @@ impl TaffyContainer {
         }
     }
 
+    /// Pack the in-flow items of a masonry grid container in its masonry axis, after they have
+    /// been placed in the tracks of its grid axis by the grid layout algorithm. Each item is
+    /// placed after the items already in its tracks and auto-placed items are moved to the
+    /// tracks where they can be placed the earliest. Returns the end of the content in the
+    /// masonry axis.
+    ///
+    /// <https://drafts.csswg.org/css-grid-3/#masonry-layout-algorithm>
+    ///
+    /// TODO: Auto-placed items keep the size they had in their original tracks, so they may not
+    /// fit tracks of a different size.
+    fn pack_masonry_items(&self, axis: MasonryAxis, content_start: f32, gap: f32) -> f32 {
+        const EPSILON: f32 = 0.01;
+
+        // The margin box of an item, as its position and size in the grid axis followed by
+        // those in the masonry axis.
+        let margin_box = |layout: &taffy::Layout| -> (f32, f32, f32, f32) {
+            let (location, size, margin) = (layout.location, layout.size, layout.margin);
+            let (x, width) = (
+                location.x - margin.left,
+                size.width + margin.left + margin.right,
+            );
+            let (y, height) = (
+                location.y - margin.top,
+                size.height + margin.top + margin.bottom,
+            );
+            match axis {
+                MasonryAxis::Rows => (x, width, y, height),
+                MasonryAxis::Columns => (y, height, x, width),
+            }
+        };
+
+        let in_flow_children: Vec<_> = self
+            .children
+            .iter()
+            .filter(|child| {
+                matches!(
+                    (***child).borrow().taffy_level_box,
+                    TaffyItemBoxInner::InFlowBox(_)
+                )
+            })
+            .collect();
+
+        // The tracks of the grid axis are the distinct positions at which the grid layout
+        // algorithm placed items in it.
+        let mut track_starts: Vec<f32> = in_flow_children
+            .iter()
+            .map(|child| margin_box(&(***child).borrow().taffy_layout).0)
+            .collect();
+        track_starts.sort_by(f32::total_cmp);
+        track_starts.dedup_by(|a, b| (*a - *b).abs() < EPSILON);
+
+        let mut running_positions = vec![content_start; track_starts.len()];
+        for child in in_flow_children {
+            let mut child = (**child).borrow_mut();
+            let (grid_start, grid_size, _, masonry_size) = margin_box(&child.taffy_layout);
+            let Some(first_track) = track_starts
+                .iter()
+                .position(|start| (start - grid_start).abs() < EPSILON)
+            else {
+                continue;
+            };
+            let track_count = track_starts[first_track..]
+                .iter()
+                .take_while(|start| **start < grid_start + grid_size - EPSILON)
+                .count()
+                .max(1);
+
+            let position_styles = child.style.get_position();
+            let (placement_start, placement_end) = match axis {
+                MasonryAxis::Rows => (
+                    &position_styles.grid_column_start,
+                    &position_styles.grid_column_end,
+                ),
+                MasonryAxis::Columns => (
+                    &position_styles.grid_row_start,
+                    &position_styles.grid_row_end,
+                ),
+            };
+            let is_auto_placed = (placement_start.is_auto() || placement_start.is_span) &&
+                (placement_end.is_auto() || placement_end.is_span);
+
+            // Items are placed after the items already in all of the tracks they span.
+            let position_in_tracks = |first: usize| {
+                running_positions[first..first + track_count]
+                    .iter()
+                    .copied()
+                    .fold(content_start, f32::max)
+            };
+            let first_track = if is_auto_placed {
+                (0..=running_positions.len() - track_count)
+                    .min_by(|a, b| position_in_tracks(*a).total_cmp(&position_in_tracks(*b)))
+                    .unwrap_or(first_track)
+            } else {
+                first_track
+            };
+            let masonry_position = position_in_tracks(first_track);
+            for running_position in &mut running_positions[first_track..first_track + track_count] {
+                *running_position = masonry_position + masonry_size + gap;
+            }
+
+            let grid_offset = track_starts[first_track] - grid_start;
+            let layout = &mut child.taffy_layout;
+            match axis {
+                MasonryAxis::Rows => {
+                    layout.location.x += grid_offset;
+                    layout.location.y = masonry_position + layout.margin.top;
+                },
+                MasonryAxis::Columns => {
+                    layout.location.y += grid_offset;
+                    layout.location.x = masonry_position + layout.margin.left;
+                },
+            }
+        }
+
+        running_positions
+            .into_iter()
+            .map(|position| position - gap)
+            .fold(content_start, f32::max)
+    }
+
     #[inline]
     pub(crate) fn layout_style(&self) -> LayoutStyle {
         LayoutStyle::Default(&self.style)
@@ -35,7 +35,7 @@ mod stylo {
     pub(crate) use style::computed_values::grid_auto_flow::T as GridAutoFlow;
     pub(crate) use style::values::computed::GridLine;
     pub(crate) use style::values::generics::grid::{
-        RepeatCount, TrackBreadth, TrackListValue, TrackSize,
+        LineNameList, RepeatCount, TrackBreadth, TrackListValue, TrackSize,
     };
     pub(crate) use style::values::specified::GenericGridTemplateComponent;
 }
@@ -10,6 +10,7 @@ use style::values::computed::{GridTemplateAreas, LengthPercentage};
 use style::values::generics::grid::{TrackListValue, TrackRepeat, TrackSize};
 use style::values::specified::position::NamedArea;
 use style::{Atom, OwnedSlice};
+use stylo_atoms::atom;
 
 use super::{convert, stylo};
 
@@ -212,6 +213,114 @@ impl taffy::GenericRepetition for RepetitionWrapper<'_> {
     }
 }
 
+/// An iterator over the tracks of an explicit grid, either specified as a track list or
+/// adopted from the parent grid by a subgrid.
+#[derive(Clone)]
+pub enum StyloTemplateTrackListIter<'a> {
+    TrackList(core::slice::Iter<'a, TrackListValue<LengthPercentage, i32>>),
+    Subgrid(core::iter::RepeatN<taffy::TrackSizingFunction>),
+}
+
+impl StyloTemplateTrackListIter<'_> {
+    /// The tracks of a subgrid spanning `track_count` tracks of its parent grid.
+    ///
+    /// TODO: A subgrid should use the sizes of the tracks it spans in its parent grid, and
+    /// contribute the sizes of its items to them, but Taffy has no support for that yet. For
+    /// now, the space of the subgrid is divided equally between its tracks, which matches the
+    /// parent grid when the spanned tracks are all the same size. Line names and gaps are not
+    /// inherited from the parent grid either.
+    fn subgrid(track_count: u16) -> Self {
+        Self::Subgrid(core::iter::repeat_n(
+            taffy::MinMax {
+                min: taffy::MinTrackSizingFunction::length(0.),
+                max: taffy::MaxTrackSizingFunction::fr(1.),
+            },
+            track_count.into(),
+        ))
+    }
+}
+
+impl<'a> Iterator for StyloTemplateTrackListIter<'a> {
+    type Item = taffy::GenericGridTemplateComponent<Atom, RepetitionWrapper<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::TrackList(tracks) => tracks.next().map(|track| match track {
+                stylo::TrackListValue::TrackSize(size) => {
+                    taffy::GenericGridTemplateComponent::Single(convert::track_size(size))
+                },
+                stylo::TrackListValue::TrackRepeat(repeat) => {
+                    taffy::GenericGridTemplateComponent::Repeat(RepetitionWrapper(repeat))
+                },
+            }),
+            Self::Subgrid(tracks) => tracks
+                .next()
+                .map(taffy::GenericGridTemplateComponent::Single),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::TrackList(tracks) => tracks.size_hint(),
+            Self::Subgrid(tracks) => tracks.size_hint(),
+        }
+    }
+}
+
+impl ExactSizeIterator for StyloTemplateTrackListIter<'_> {}
+
+/// The number of tracks of the parent grid spanned by a subgrid in one axis. This is given
+/// by its placement when it is explicit, and by the number of lines of its `<line-name-list>`
+/// otherwise.
+///
+/// <https://drafts.csswg.org/css-grid-2/#subgrid-span>
+fn subgrid_track_count(
+    line_names: &stylo::LineNameList<i32>,
+    start: &stylo::GridLine,
+    end: &stylo::GridLine,
+) -> u16 {
+    let is_numbered_line =
+        |line: &stylo::GridLine| !line.is_span && line.ident.0 == atom!("") && line.line_num != 0;
+    let is_unnamed_span = |line: &stylo::GridLine| line.is_span && line.ident.0 == atom!("");
+
+    let span = if is_numbered_line(start) &&
+        is_numbered_line(end) &&
+        start.line_num.signum() == end.line_num.signum()
+    {
+        (end.line_num - start.line_num).unsigned_abs()
+    } else if is_unnamed_span(start) {
+        start.line_num.unsigned_abs()
+    } else if is_unnamed_span(end) {
+        end.line_num.unsigned_abs()
+    } else {
+        line_names.expanded_line_names_length.saturating_sub(1) as u32
+    };
+    span.clamp(1, u16::MAX as u32) as u16
+}
+
+/// The placement of a subgrid in one axis when it is automatic, which spans the number of
+/// tracks given by its `<line-name-list>`.
+fn subgrid_placement(
+    template: &stylo::GenericGridTemplateComponent<LengthPercentage, i32>,
+    start: &stylo::GridLine,
+    end: &stylo::GridLine,
+) -> taffy::Line<taffy::GridPlacement<Atom>> {
+    match template {
+        stylo::GenericGridTemplateComponent::Subgrid(line_names)
+            if start.is_auto() && end.is_auto() =>
+        {
+            taffy::Line {
+                start: taffy::GridPlacement::Auto,
+                end: taffy::GridPlacement::Span(subgrid_track_count(line_names, start, end)),
+            }
+        },
+        _ => taffy::Line {
+            start: convert::grid_line(start),
+            end: convert::grid_line(end),
+        },
+    }
+}
+
 impl<T: Deref<Target = ComputedValues>> taffy::GridContainerStyle for TaffyStyloStyle<T> {
     type Repetition<'a>
         = RepetitionWrapper<'a>
@@ -219,12 +328,7 @@ impl<T: Deref<Target = ComputedValues>> taffy::GridContainerStyle for TaffyStylo
         Self: 'a;
 
     type TemplateTrackList<'a>
-        = core::iter::Map<
-        core::slice::Iter<'a, TrackListValue<LengthPercentage, i32>>,
-        fn(
-            &'a TrackListValue<LengthPercentage, i32>,
-        ) -> taffy::GenericGridTemplateComponent<Atom, RepetitionWrapper<'a>>,
-    >
+        = StyloTemplateTrackListIter<'a>
     where
         Self: 'a;
 
@@ -244,42 +348,40 @@ impl<T: Deref<Target = ComputedValues>> taffy::GridContainerStyle for TaffyStylo
 
     #[inline]
     fn grid_template_rows(&self) -> Option<Self::TemplateTrackList<'_>> {
-        match &self.style.get_position().grid_template_rows {
+        let position_styles = self.style.get_position();
+        match &position_styles.grid_template_rows {
             stylo::GenericGridTemplateComponent::None => None,
             stylo::GenericGridTemplateComponent::TrackList(list) => {
-                Some(list.values.iter().map(|track| match track {
-                    stylo::TrackListValue::TrackSize(size) => {
-                        taffy::GenericGridTemplateComponent::Single(convert::track_size(size))
-                    },
-                    stylo::TrackListValue::TrackRepeat(repeat) => {
-                        taffy::GenericGridTemplateComponent::Repeat(RepetitionWrapper(repeat))
-                    },
-                }))
+                Some(StyloTemplateTrackListIter::TrackList(list.values.iter()))
             },
-
-            // TODO: Implement subgrid and masonry
-            stylo::GenericGridTemplateComponent::Subgrid(_) => None,
+            stylo::GenericGridTemplateComponent::Subgrid(line_names) => {
+                Some(StyloTemplateTrackListIter::subgrid(subgrid_track_count(
+                    line_names,
+                    &position_styles.grid_row_start,
+                    &position_styles.grid_row_end,
+                )))
+            },
+            // TODO: Implement masonry
             stylo::GenericGridTemplateComponent::Masonry => None,
         }
     }
 
     #[inline]
     fn grid_template_columns(&self) -> Option<Self::TemplateTrackList<'_>> {
-        match &self.style.get_position().grid_template_columns {
+        let position_styles = self.style.get_position();
+        match &position_styles.grid_template_columns {
             stylo::GenericGridTemplateComponent::None => None,
             stylo::GenericGridTemplateComponent::TrackList(list) => {
-                Some(list.values.iter().map(|track| match track {
-                    stylo::TrackListValue::TrackSize(size) => {
-                        taffy::GenericGridTemplateComponent::Single(convert::track_size(size))
-                    },
-                    stylo::TrackListValue::TrackRepeat(repeat) => {
-                        taffy::GenericGridTemplateComponent::Repeat(RepetitionWrapper(repeat))
-                    },
-                }))
+                Some(StyloTemplateTrackListIter::TrackList(list.values.iter()))
             },
-
-            // TODO: Implement subgrid and masonry
-            stylo::GenericGridTemplateComponent::Subgrid(_) => None,
+            stylo::GenericGridTemplateComponent::Subgrid(line_names) => {
+                Some(StyloTemplateTrackListIter::subgrid(subgrid_track_count(
+                    line_names,
+                    &position_styles.grid_column_start,
+                    &position_styles.grid_column_end,
+                )))
+            },
+            // TODO: Implement masonry
             stylo::GenericGridTemplateComponent::Masonry => None,
         }
     }
@@ -382,19 +484,21 @@ impl<T: Deref<Target = ComputedValues>> taffy::GridItemStyle for TaffyStyloStyle
     #[inline]
     fn grid_row(&self) -> taffy::Line<taffy::GridPlacement<Atom>> {
         let position_styles = self.style.get_position();
-        taffy::Line {
-            start: convert::grid_line(&position_styles.grid_row_start),
-            end: convert::grid_line(&position_styles.grid_row_end),
-        }
+        subgrid_placement(
+            &position_styles.grid_template_rows,
+            &position_styles.grid_row_start,
+            &position_styles.grid_row_end,
+        )
     }
 
     #[inline]
     fn grid_column(&self) -> taffy::Line<taffy::GridPlacement<Atom>> {
         let position_styles = self.style.get_position();
-        taffy::Line {
-            start: convert::grid_line(&position_styles.grid_column_start),
-            end: convert::grid_line(&position_styles.grid_column_end),
-        }
+        subgrid_placement(
+            &position_styles.grid_template_columns,
+            &position_styles.grid_column_start,
+            &position_styles.grid_column_end,
+        )
     }
 
     #[inline]
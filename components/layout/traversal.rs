@@ -14,7 +14,7 @@ use style::traversal::{DomTraversal, PerLevelTraversalData, recalc_style_at};
 use style::values::computed::Display;
 
 use crate::context::LayoutContext;
-use crate::dom::{DOMLayoutData, NodeExt};
+use crate::dom::{DOMLayoutData, NodeExt, QueryContainer};
 use crate::dom_traversal::iter_child_nodes;
 
 pub struct RecalcStyle<'a> {
@@ -99,14 +99,16 @@ pub(crate) fn compute_damage_and_repair_style(
     context: &SharedStyleContext,
     node: ServoLayoutNode<'_>,
     damage_from_environment: RestyleDamage,
+    query_containers: &mut Vec<QueryContainer>,
 ) -> RestyleDamage {
-    compute_damage_and_repair_style_inner(context, node, damage_from_environment)
+    compute_damage_and_repair_style_inner(context, node, damage_from_environment, query_containers)
 }
 
 pub(crate) fn compute_damage_and_repair_style_inner(
     context: &SharedStyleContext,
     node: ServoLayoutNode<'_>,
     damage_from_parent: RestyleDamage,
+    query_containers: &mut Vec<QueryContainer>,
 ) -> RestyleDamage {
     let mut element_damage;
     let original_element_damage;
@@ -128,6 +130,18 @@ pub(crate) fn compute_damage_and_repair_style_inner(
         }
     }
 
+    // Newly styled query containers are added to the list kept by layout, so that their sizes
+    // can be recorded after layout without walking the whole DOM.
+    if element_data
+        .borrow()
+        .styles
+        .primary
+        .as_ref()
+        .is_some_and(|style| !style.clone_container_type().is_normal())
+    {
+        query_containers.extend(node.track_as_query_container());
+    }
+
     // If we are reconstructing this node, then all of the children should be reconstructed as well.
     // Otherwise, do not propagate down its box damage.
     let mut damage_for_children = element_damage;
@@ -138,8 +152,12 @@ pub(crate) fn compute_damage_and_repair_style_inner(
     let mut damage_from_children = RestyleDamage::empty();
     for child in iter_child_nodes(node) {
         if child.is_element() {
-            damage_from_children |=
-                compute_damage_and_repair_style_inner(context, child, damage_for_children);
+            damage_from_children |= compute_damage_and_repair_style_inner(
+                context,
+                child,
+                damage_for_children,
+                query_containers,
+            );
         }
     }
 
@@ -77,6 +77,21 @@ where
         .spawn(task);
 }
 
+/// Run `function`, which blocks, for example on disk I/O, on the blocking thread pool of the
+/// runtime, so that the calling task can wait for its result without blocking a worker.
+pub async fn run_blocking<F, R>(function: F) -> Option<R>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    ASYNC_RUNTIME_HANDLE
+        .get()
+        .expect("Runtime handle should be initialized on start-up")
+        .spawn_blocking(function)
+        .await
+        .ok()
+}
+
 /// Spawn a blocking task using the handle to the runtime.
 pub fn spawn_blocking_task<F, R>(task: F) -> F::Output
 where
@@ -0,0 +1,143 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Scheduling of HTTP network requests by [internal priority].
+//!
+//! The number of requests that may be in flight to a single host at once is limited by the
+//! `network_max_connections_per_host` preference. Requests
+//! that exceed that limit wait in a queue, and when a slot frees up it is handed to the most
+//! urgent waiting request, with ties broken in the order the requests were made.
+//!
+//! [internal priority]: https://fetch.spec.whatwg.org/#request-internal-priority
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use net_traits::request::InternalPriority;
+use servo_config::pref;
+use tokio::sync::oneshot;
+
+#[derive(Default)]
+pub struct ConnectionScheduler {
+    hosts: Mutex<HashMap<String, HostQueue>>,
+}
+
+#[derive(Default)]
+struct HostQueue {
+    /// The number of [`ConnectionPermit`]s for this host that are alive.
+    in_flight: usize,
+    /// Requests waiting for a [`ConnectionPermit`], most urgent first.
+    waiting: BinaryHeap<PendingRequest>,
+    /// Sequence number given to the next waiting request, used to keep requests of equal
+    /// priority in first-in first-out order.
+    next_sequence: u64,
+}
+
+struct PendingRequest {
+    priority: InternalPriority,
+    sequence: u64,
+    sender: oneshot::Sender<ConnectionPermit>,
+}
+
+impl Ord for PendingRequest {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+impl PartialOrd for PendingRequest {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for PendingRequest {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PendingRequest {}
+
+/// Permission to have a request in flight to a host. The slot is released, and given to the
+/// next waiting request, when the permit is dropped.
+pub struct ConnectionPermit {
+    /// The scheduler to release the slot to, or `None` if the permit was never handed out.
+    scheduler: Option<Arc<ConnectionScheduler>>,
+    host: String,
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        if let Some(scheduler) = self.scheduler.take() {
+            scheduler.release(&self.host);
+        }
+    }
+}
+
+impl ConnectionScheduler {
+    /// Wait until a request with the given priority may be sent to `host`.
+    pub async fn acquire(
+        self: &Arc<Self>,
+        host: &str,
+        priority: InternalPriority,
+    ) -> ConnectionPermit {
+        let receiver = {
+            let mut hosts = self.hosts.lock().unwrap();
+            let queue = hosts.entry(host.to_owned()).or_default();
+            let max_requests = pref!(network_max_connections_per_host).max(1) as usize;
+            if queue.in_flight < max_requests {
+                queue.in_flight += 1;
+                return ConnectionPermit {
+                    scheduler: Some(self.clone()),
+                    host: host.to_owned(),
+                };
+            }
+
+            let (sender, receiver) = oneshot::channel();
+            queue.waiting.push(PendingRequest {
+                priority,
+                sequence: queue.next_sequence,
+                sender,
+            });
+            queue.next_sequence += 1;
+            receiver
+        };
+
+        // A pending request is only ever removed from the queue to be sent a permit.
+        receiver
+            .await
+            .expect("Pending request was dropped without a permit")
+    }
+
+    /// Hand the slot of a dropped [`ConnectionPermit`] to the most urgent waiting request, or
+    /// free it if no request is waiting.
+    fn release(self: &Arc<Self>, host: &str) {
+        let mut hosts = self.hosts.lock().unwrap();
+        let Some(queue) = hosts.get_mut(host) else {
+            return;
+        };
+
+        while let Some(pending) = queue.waiting.pop() {
+            let permit = ConnectionPermit {
+                scheduler: Some(self.clone()),
+                host: host.to_owned(),
+            };
+            match pending.sender.send(permit) {
+                Ok(()) => return,
+                // The request was cancelled while waiting. Its permit must not release the
+                // slot, as the slot is being handed to the next waiting request instead.
+                Err(mut permit) => permit.scheduler = None,
+            }
+        }
+
+        queue.in_flight -= 1;
+        if queue.in_flight == 0 {
+            hosts.remove(host);
+        }
+    }
+}
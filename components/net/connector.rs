@@ -4,7 +4,9 @@
 
 use std::collections::hash_map::HashMap;
 use std::convert::TryFrom;
+use std::pin::Pin;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use futures::Future;
 use futures::task::{Context, Poll};
@@ -14,38 +16,62 @@ use hyper::body::Bytes;
 use hyper::rt::Executor;
 use hyper_rustls::HttpsConnector as HyperRustlsHttpsConnector;
 use hyper_util::client::legacy::Client;
-use hyper_util::client::legacy::connect::HttpConnector as HyperHttpConnector;
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::rt::{TokioIo, TokioTimer};
 use log::warn;
 use rustls::client::WebPkiServerVerifier;
 use rustls::{ClientConfig, RootCertStore};
 use rustls_pki_types::{CertificateDer, ServerName, UnixTime};
+use servo_config::pref;
 use tower_service::Service;
 
 use crate::async_runtime::spawn_task;
+use crate::doh::DnsResolver;
 use crate::hosts::replace_host;
+use crate::proxy::{ProxyConfig, ProxyKind, connect_tunnel, socks5_connect};
 
 pub const BUF_SIZE: usize = 32768;
 
+type HyperHttpConnector = HttpConnector<DnsResolver>;
+
 #[derive(Clone)]
 pub struct ServoHttpConnector {
     inner: HyperHttpConnector,
+    /// The proxies to connect through, read from the preferences once, when the connector
+    /// is created.
+    proxy_config: Arc<ProxyConfig>,
 }
 
 impl ServoHttpConnector {
     fn new() -> ServoHttpConnector {
-        let mut inner = HyperHttpConnector::new();
+        let mut inner = HyperHttpConnector::new_with_resolver(DnsResolver::new());
         inner.enforce_http(false);
-        inner.set_happy_eyeballs_timeout(None);
-        ServoHttpConnector { inner }
+        // Race a connection to an address of the other IP family when the first address is
+        // slow to connect, rather than waiting for it to time out.
+        // <https://www.rfc-editor.org/rfc/rfc8305#section-5>
+        let happy_eyeballs_delay = pref!(network_happy_eyeballs_delay_ms);
+        inner.set_happy_eyeballs_timeout(
+            (happy_eyeballs_delay > 0).then(|| Duration::from_millis(happy_eyeballs_delay as u64)),
+        );
+        ServoHttpConnector {
+            inner,
+            proxy_config: Arc::new(ProxyConfig::from_prefs()),
+        }
     }
 }
 
 impl Service<Destination> for ServoHttpConnector {
     type Response = <HyperHttpConnector as Service<Destination>>::Response;
-    type Error = <HyperHttpConnector as Service<Destination>>::Error;
-    type Future = <HyperHttpConnector as Service<Destination>>::Future;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
 
     fn call(&mut self, dest: Destination) -> Self::Future {
+        let scheme = dest.scheme_str().unwrap_or("http").to_owned();
+        let proxy = self
+            .proxy_config
+            .proxy_for(&scheme, dest.host().unwrap_or_default())
+            .cloned();
+
         // Perform host replacement when making the actual TCP connection.
         let mut new_dest = dest.clone();
         let mut parts = dest.into_parts();
@@ -68,7 +94,27 @@ impl Service<Destination> for ServoHttpConnector {
             }
         }
 
-        self.inner.call(new_dest)
+        if let Some(proxy) = proxy {
+            let host = new_dest.host().unwrap_or_default().to_owned();
+            let port = new_dest
+                .port_u16()
+                .unwrap_or(if scheme == "https" { 443 } else { 80 });
+            let mut inner = self.inner.clone();
+            return Box::pin(async move {
+                let proxy_uri = Destination::try_from(format!("http://{}", proxy.authority()))?;
+                let mut stream = inner.call(proxy_uri).await?.into_inner();
+                // Connections for `http` URLs go through a tunnel as well, so that the
+                // credentials of the proxy are never part of the headers of a request.
+                match proxy.kind {
+                    ProxyKind::Http => connect_tunnel(&mut stream, &proxy, &host, port).await?,
+                    ProxyKind::Socks5 => socks5_connect(&mut stream, &proxy, &host, port).await?,
+                }
+                Ok(TokioIo::new(stream))
+            });
+        }
+
+        let connecting = self.inner.call(new_dest);
+        Box::pin(async move { Ok(connecting.await?) })
     }
 
     fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
@@ -158,7 +204,7 @@ pub fn create_tls_config(
 }
 
 #[derive(Clone)]
-struct TokioExecutor {}
+pub(crate) struct TokioExecutor {}
 
 impl<F> Executor<F> for TokioExecutor
 where
@@ -278,5 +324,13 @@ pub fn create_http_client(tls_config: TlsConfig) -> Client<Connector, BoxedBody>
 
     Client::builder(TokioExecutor {})
         .http1_title_case_headers(true)
+        // Grow the HTTP/2 flow control windows to match the bandwidth-delay product, so that
+        // streams multiplexed over one connection are not throttled by the small defaults.
+        .http2_adaptive_window(true)
+        .pool_timer(TokioTimer::new())
+        .pool_idle_timeout(Duration::from_secs(
+            pref!(network_pool_idle_timeout_s).max(0) as u64,
+        ))
+        .pool_max_idle_per_host(pref!(network_pool_max_idle_per_host).max(0) as usize)
         .build(connector)
 }
@@ -9,10 +9,11 @@ use std::borrow::ToOwned;
 use std::net::{Ipv4Addr, Ipv6Addr};
 use std::time::SystemTime;
 
-use cookie::Cookie;
+use cookie::{Cookie, SameSite};
 use log::{Level, debug, log_enabled};
 use net_traits::CookieSource;
-use net_traits::pub_domains::is_pub_domain;
+use net_traits::pub_domains::{is_pub_domain, origin_site};
+use net_traits::request::{Destination, Origin, Request, RequestMode};
 use nom::branch::alt;
 use nom::bytes::complete::{tag, tag_no_case, take, take_while_m_n};
 use nom::combinator::{opt, recognize};
@@ -20,9 +21,11 @@ use nom::multi::{many0, many1, separated_list1};
 use nom::sequence::{delimited, preceded, terminated};
 use nom::{IResult, Parser};
 use serde::{Deserialize, Serialize};
-use servo_url::ServoUrl;
+use servo_url::{ImmutableOrigin, ServoUrl};
 use time::{Date, Duration, Month, OffsetDateTime, Time};
 
+use crate::http_loader::is_same_site;
+
 /// A stored cookie that wraps the definition in cookie-rs. This is used to implement
 /// various behaviours defined in the spec that rely on an associated request URL,
 /// which cookie-rs and hyper's header parsing do not support.
@@ -38,6 +41,117 @@ pub struct ServoCookie {
     pub creation_time: SystemTime,
     pub last_access: SystemTime,
     pub expiry_time: Option<SystemTime>,
+    /// The site of the top-level navigable that this cookie was set in, if it has the
+    /// `Partitioned` attribute.
+    /// <https://datatracker.ietf.org/doc/html/draft-cutler-httpbis-partitioned-cookies>
+    #[serde(default)]
+    pub partition_key: Option<String>,
+}
+
+/// The context that cookies are stored from or retrieved for, which decides whether cookies
+/// with a `SameSite` attribute are available and which partition partitioned cookies belong to.
+#[derive(Clone, Debug)]
+pub struct CookieSiteContext {
+    /// The site of the top-level navigable, or `None` if it has an opaque origin.
+    pub partition_key: Option<String>,
+    /// Whether the context is "same-site", which means that the site for cookies of the client
+    /// is same-site with every URL the request went through.
+    /// <https://www.ietf.org/archive/id/draft-ietf-httpbis-rfc6265bis-15.html#name-same-site-and-cross-site-re>
+    pub same_site: bool,
+    /// Whether this is a request navigating a top-level navigable.
+    pub top_level_navigation: bool,
+    /// Whether this is a request with a safe method.
+    pub safe_method: bool,
+}
+
+impl CookieSiteContext {
+    /// The context of a document at `url` in a top-level navigable, which can access all
+    /// cookies for `url` that are not partitioned for another site.
+    pub fn first_party(url: &ServoUrl) -> CookieSiteContext {
+        CookieSiteContext {
+            partition_key: site_for_origin(&url.origin()),
+            same_site: true,
+            top_level_navigation: false,
+            safe_method: true,
+        }
+    }
+
+    /// The context of a document at `url` using a non-HTTP API, given the origin of the
+    /// document followed by the origins of the ancestors of its navigable.
+    pub fn for_document(url: &ServoUrl, ancestor_origins: &[ImmutableOrigin]) -> CookieSiteContext {
+        let Some(site_for_cookies) = site_for_cookies(ancestor_origins) else {
+            return CookieSiteContext::first_party(url);
+        };
+        CookieSiteContext {
+            partition_key: ancestor_origins.last().and_then(site_for_origin),
+            same_site: is_same_site(&site_for_cookies, &url.origin()),
+            top_level_navigation: false,
+            safe_method: true,
+        }
+    }
+
+    /// The context of `request`, where the current URL of `request` is the one that cookies are
+    /// stored from or retrieved for.
+    pub fn for_request(request: &Request) -> CookieSiteContext {
+        let top_level_navigation =
+            request.mode == RequestMode::Navigate && request.destination == Destination::Document;
+
+        // The site for cookies of a top-level navigation is that of the document that started
+        // it, while the navigated document itself is in the top-level navigable.
+        let client_origin = match request.origin {
+            Origin::Origin(ref origin) if !top_level_navigation => Some(origin),
+            _ => None,
+        };
+        let site_for_cookies =
+            site_for_cookies(&request.ancestor_origins).or_else(|| client_origin.cloned());
+        let top_level_origin = if top_level_navigation {
+            Some(request.current_url().origin())
+        } else {
+            request.ancestor_origins.last().or(client_origin).cloned()
+        };
+
+        // A request is "same-site" if its client's site for cookies is same-site with its URL and
+        // it is not the result of a cross-site redirect. Requests without a client, such as
+        // navigations started by the user, are treated as same-site.
+        let same_site = site_for_cookies.is_none_or(|site_for_cookies| {
+            request
+                .url_list
+                .iter()
+                .all(|url| is_same_site(&site_for_cookies, &url.origin()))
+        });
+
+        CookieSiteContext {
+            partition_key: top_level_origin.as_ref().map_or_else(
+                || site_for_origin(&request.current_url().origin()),
+                site_for_origin,
+            ),
+            same_site,
+            top_level_navigation,
+            safe_method: request.method.is_safe(),
+        }
+    }
+}
+
+/// Returns the site for cookies given the origin of a document followed by the origins of the
+/// ancestors of its navigable: the origin of the top-level navigable if all of them are
+/// same-site with it, or an opaque origin otherwise. Returns `None` if there are no origins.
+///
+/// <https://html.spec.whatwg.org/multipage/#concept-document-site-for-cookies>
+fn site_for_cookies(ancestor_origins: &[ImmutableOrigin]) -> Option<ImmutableOrigin> {
+    let top_level_origin = ancestor_origins.last()?;
+    if ancestor_origins
+        .iter()
+        .all(|origin| is_same_site(origin, top_level_origin))
+    {
+        Some(top_level_origin.clone())
+    } else {
+        Some(ImmutableOrigin::new_opaque())
+    }
+}
+
+/// Serializes the site of `origin`, which is used as the partition key of partitioned cookies.
+fn site_for_origin(origin: &ImmutableOrigin) -> Option<String> {
+    origin_site(origin).map(|site| site.to_string())
 }
 
 impl ServoCookie {
@@ -213,7 +327,20 @@ impl ServoCookie {
 
         // TODO: Step 16, Ignore cookies from insecure request uris based on existing cookies
 
-        // TODO: Steps 17-19, same-site-flag
+        // Steps 17-18 depend on the context that the cookie was received in, and are
+        // implemented in `ServoCookie::apply_site_context`.
+
+        // Step 19. If the cookie's same-site-flag is "None", abort these steps and ignore the
+        // cookie entirely unless the cookie's secure-only-flag is true.
+        if cookie.same_site() == Some(SameSite::None) && !secure_only {
+            return None;
+        }
+
+        // A cookie with the "Partitioned" attribute is ignored unless its secure-only-flag
+        // is true.
+        if cookie.partitioned().unwrap_or(false) && !secure_only {
+            return None;
+        }
 
         // Step 20. If the cookie-name begins with a case-insensitive match for the string "__Secure-",
         // abort these steps and ignore the cookie entirely unless the cookie's secure-only-flag is true.
@@ -270,9 +397,59 @@ impl ServoCookie {
             creation_time: SystemTime::now(),
             last_access: SystemTime::now(),
             expiry_time,
+            partition_key: None,
         })
     }
 
+    /// Steps 17-18 from <https://www.ietf.org/archive/id/draft-ietf-httpbis-rfc6265bis-15.html#name-storage-model>,
+    /// which depend on the context that the cookie was received in. This also assigns
+    /// partitioned cookies to the partition of `context`. Returns false if the cookie must be
+    /// ignored.
+    pub fn apply_site_context(
+        &mut self,
+        context: &CookieSiteContext,
+        source: CookieSource,
+    ) -> bool {
+        // Step 17. If the cookie-attribute-list contains an attribute with an attribute-name of
+        // "SameSite", and an attribute-value of "Strict", "Lax", or "None", set the cookie's
+        // same-site-flag to the attribute-value of the last attribute in the
+        // cookie-attribute-list with an attribute-name of "SameSite". Otherwise, set the cookie's
+        // same-site-flag to "Default".
+        // NOTE: This is done by the cookie crate. Cookies with a "Default" same-site-flag are
+        // treated like "None" cookies.
+
+        // Step 18. If the cookie's same-site-flag is not "None":
+        if matches!(
+            self.cookie.same_site(),
+            Some(SameSite::Strict) | Some(SameSite::Lax)
+        ) {
+            // 1. If the cookie was received from a "non-HTTP" API, and the API was called from a
+            // navigable's active document whose "site for cookies" is not same-site with the
+            // top-level origin, then abort these steps and ignore the newly created cookie
+            // entirely.
+            // 2. If the cookie was received from a "same-site" request, skip the remaining
+            // substeps and continue processing the cookie.
+            // 3. If the cookie was received from a request which is navigating a top-level
+            // traversable, skip the remaining substeps and continue processing the cookie.
+            // 4. Abort these steps and ignore the newly created cookie entirely.
+            if !context.same_site && !(source == CookieSource::HTTP && context.top_level_navigation)
+            {
+                return false;
+            }
+        }
+
+        // Partitioned cookies are keyed on the site of the top-level navigable, and cannot be
+        // set when it has an opaque origin.
+        if self.cookie.partitioned().unwrap_or(false) {
+            let Some(partition_key) = context.partition_key.clone() else {
+                return false;
+            };
+            self.partition_key = Some(partition_key);
+        }
+
+        true
+    }
+
     pub fn touch(&mut self) {
         self.last_access = SystemTime::now();
     }
@@ -373,12 +550,34 @@ impl ServoCookie {
         if self.cookie.http_only().unwrap_or(false) && source == CookieSource::NonHTTP {
             return false;
         }
-        // TODO: Apply same site checks
-        // TOOD: Apply Partitioning checks
 
         true
     }
 
+    /// Whether the cookie can be retrieved in `context`, given its same-site-flag and
+    /// partition. This complements [`ServoCookie::appropriate_for_url`].
+    ///
+    /// <https://www.ietf.org/archive/id/draft-ietf-httpbis-rfc6265bis-15.html#name-retrieval-algorithm>
+    pub fn appropriate_for_context(&self, context: &CookieSiteContext) -> bool {
+        // Partitioned cookies are only available in the partition they were set in.
+        if self.partition_key.is_some() && self.partition_key != context.partition_key {
+            return false;
+        }
+
+        match self.cookie.same_site() {
+            // If the cookie's same-site-flag is "Strict", exclude it unless the retrieval is
+            // "same-site".
+            Some(SameSite::Strict) => context.same_site,
+            // If the cookie's same-site-flag is "Lax", exclude it unless the retrieval is
+            // "same-site", or is for a request navigating a top-level traversable with a safe
+            // method.
+            Some(SameSite::Lax) => {
+                context.same_site || (context.top_level_navigation && context.safe_method)
+            },
+            _ => true,
+        }
+    }
+
     /// <https://www.ietf.org/archive/id/draft-ietf-httpbis-rfc6265bis-20.html#name-dates>
     pub fn parse_date(string: &str) -> Option<OffsetDateTime> {
         let string_in_bytes = string.as_bytes();
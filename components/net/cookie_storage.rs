@@ -10,21 +10,36 @@ use std::collections::HashMap;
 use std::collections::hash_map::Entry;
 use std::time::SystemTime;
 
+use base::id::CookieStoreId;
 use cookie::Cookie;
+use hyper_serde::Serde;
+use ipc_channel::ipc::IpcSender;
 use itertools::Itertools;
 use log::info;
-use net_traits::CookieSource;
 use net_traits::pub_domains::reg_suffix;
+use net_traits::{CookieAsyncResponse, CookieChange, CookieData, CookieSource};
 use serde::{Deserialize, Serialize};
 use servo_url::ServoUrl;
 
-use crate::cookie::ServoCookie;
+use crate::cookie::{CookieSiteContext, ServoCookie};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct CookieStorage {
     version: u32,
     cookies_map: HashMap<String, Vec<ServoCookie>>,
     max_per_host: usize,
+    /// The `CookieStore`s observing changes to this storage.
+    #[serde(skip)]
+    listeners: HashMap<CookieStoreId, CookieListener>,
+}
+
+/// A `CookieStore` that receives the results of its asynchronous operations and the changes
+/// to the cookies visible to a URL.
+#[derive(Clone, Debug)]
+struct CookieListener {
+    sender: IpcSender<CookieAsyncResponse>,
+    url: ServoUrl,
+    context: CookieSiteContext,
 }
 
 #[derive(Debug)]
@@ -39,6 +54,65 @@ impl CookieStorage {
             version: 1,
             cookies_map: HashMap::new(),
             max_per_host: max_cookies,
+            listeners: HashMap::new(),
+        }
+    }
+
+    pub fn add_listener(
+        &mut self,
+        id: CookieStoreId,
+        sender: IpcSender<CookieAsyncResponse>,
+        url: ServoUrl,
+        context: CookieSiteContext,
+    ) {
+        self.listeners.insert(
+            id,
+            CookieListener {
+                sender,
+                url,
+                context,
+            },
+        );
+    }
+
+    pub fn remove_listener(&mut self, id: CookieStoreId) {
+        self.listeners.remove(&id);
+    }
+
+    /// The context that the operations of the `CookieStore` identified by `id` happen in, or
+    /// `None` if no such store is listening.
+    pub fn listener_context(&self, id: CookieStoreId) -> Option<CookieSiteContext> {
+        self.listeners
+            .get(&id)
+            .map(|listener| listener.context.clone())
+    }
+
+    /// Sends the result of an asynchronous operation to the `CookieStore` identified by `id`.
+    pub fn send_to_listener(&self, id: CookieStoreId, data: CookieData) {
+        if let Some(listener) = self.listeners.get(&id) {
+            let _ = listener.sender.send(CookieAsyncResponse { data });
+        }
+    }
+
+    /// Notifies every listener that can see `cookie` that it was set, or deleted if it has
+    /// expired.
+    fn notify_listeners(&self, cookie: &ServoCookie) {
+        let deleted = is_cookie_expired(cookie);
+        for listener in self.listeners.values() {
+            if !cookie.appropriate_for_url(&listener.url, CookieSource::NonHTTP) ||
+                !cookie.appropriate_for_context(&listener.context)
+            {
+                continue;
+            }
+            let mut change = CookieChange::default();
+            if deleted {
+                change.deleted.push(Serde(cookie.cookie.clone()));
+            } else {
+                change.changed.push(Serde(cookie.cookie.clone()));
+            }
+            let _ = listener.sender.send(CookieAsyncResponse {
+                data: CookieData::Change(change),
+            });
         }
     }
 
@@ -77,7 +151,8 @@ impl CookieStorage {
         let position = cookies.iter().position(|c| {
             c.cookie.domain() == cookie.cookie.domain() &&
                 c.cookie.path() == cookie.cookie.path() &&
-                c.cookie.name() == cookie.cookie.name()
+                c.cookie.name() == cookie.cookie.name() &&
+                c.partition_key == cookie.partition_key
         });
 
         if let Some(ind) = position {
@@ -103,36 +178,66 @@ impl CookieStorage {
         for cookie in cookies.iter_mut() {
             cookie.set_expiry_time_in_past();
         }
+        if !self.listeners.is_empty() {
+            let cleared = cookies.clone();
+            cleared
+                .iter()
+                .for_each(|cookie| self.notify_listeners(cookie));
+        }
     }
 
     pub fn delete_cookie_with_name(&mut self, url: &ServoUrl, name: String) {
         let domain = reg_host(url.host_str().unwrap_or(""));
         let cookies = self.cookies_map.entry(domain).or_default();
+        let mut deleted = vec![];
         for cookie in cookies.iter_mut() {
             if cookie.cookie.name() == name {
                 cookie.set_expiry_time_in_past();
+                deleted.push(cookie.clone());
             }
         }
+        deleted
+            .iter()
+            .for_each(|cookie| self.notify_listeners(cookie));
+    }
+
+    /// Stores `cookie` as if it was received by a document at `url` in a top-level navigable.
+    pub fn push(&mut self, cookie: ServoCookie, url: &ServoUrl, source: CookieSource) {
+        self.push_in_context(cookie, url, source, &CookieSiteContext::first_party(url));
     }
 
+    /// Returns whether the cookie was stored.
     // http://tools.ietf.org/html/rfc6265#section-5.3
-    pub fn push(&mut self, mut cookie: ServoCookie, url: &ServoUrl, source: CookieSource) {
+    pub fn push_in_context(
+        &mut self,
+        mut cookie: ServoCookie,
+        url: &ServoUrl,
+        source: CookieSource,
+        context: &CookieSiteContext,
+    ) -> bool {
+        if !cookie.apply_site_context(context, source) {
+            return false;
+        }
+
         // https://www.ietf.org/id/draft-ietf-httpbis-cookie-alone-01.txt Step 1
         if cookie.cookie.secure().unwrap_or(false) && !url.is_secure_scheme() {
-            return;
+            return false;
         }
 
         let old_cookie = self.remove(&cookie, url, source);
         if old_cookie.is_err() {
             // This new cookie is not allowed to overwrite an existing one.
-            return;
+            return false;
         }
 
         // Step 11
-        if let Some(old_cookie) = old_cookie.unwrap() {
+        let replaced = if let Some(old_cookie) = old_cookie.unwrap() {
             // Step 11.3
             cookie.creation_time = old_cookie.creation_time;
-        }
+            true
+        } else {
+            false
+        };
 
         // Step 12
         let domain = reg_host(cookie.cookie.domain().as_ref().unwrap_or(&""));
@@ -147,10 +252,18 @@ impl CookieStorage {
             if new_len == old_len &&
                 !evict_one_cookie(cookie.cookie.secure().unwrap_or(false), cookies)
             {
-                return;
+                return false;
             }
         }
+
+        // Storing an expired cookie that did not replace anything is not observable.
+        let notify = !self.listeners.is_empty() && (replaced || !is_cookie_expired(&cookie));
+        let stored = notify.then(|| cookie.clone());
         cookies.push(cookie);
+        if let Some(stored) = stored {
+            self.notify_listeners(&stored);
+        }
+        true
     }
 
     pub fn cookie_comparator(a: &ServoCookie, b: &ServoCookie) -> Ordering {
@@ -175,10 +288,21 @@ impl CookieStorage {
         }
     }
 
-    // http://tools.ietf.org/html/rfc6265#section-5.4
+    /// Retrieves the cookies for `url` as if it was requested by a document in a top-level
+    /// navigable that is same-site with it.
     pub fn cookies_for_url(&mut self, url: &ServoUrl, source: CookieSource) -> Option<String> {
+        self.cookies_for_url_in_context(url, source, CookieSiteContext::first_party(url))
+    }
+
+    // http://tools.ietf.org/html/rfc6265#section-5.4
+    pub fn cookies_for_url_in_context(
+        &mut self,
+        url: &ServoUrl,
+        source: CookieSource,
+        context: CookieSiteContext,
+    ) -> Option<String> {
         // Let cookie-list be the set of cookies from the cookie store
-        let cookie_list = self.cookies_data_for_url(url, source);
+        let cookie_list = self.cookies_data_for_url_in_context(url, source, context);
 
         let reducer = |acc: String, cookie: Cookie<'static>| -> String {
             // Serialize the cookie-list into a cookie-string by processing each cookie in the cookie-list in order:
@@ -209,13 +333,24 @@ impl CookieStorage {
         &'a mut self,
         url: &'a ServoUrl,
         source: CookieSource,
+    ) -> impl Iterator<Item = cookie::Cookie<'static>> + 'a {
+        self.cookies_data_for_url_in_context(url, source, CookieSiteContext::first_party(url))
+    }
+
+    pub fn cookies_data_for_url_in_context<'a>(
+        &'a mut self,
+        url: &'a ServoUrl,
+        source: CookieSource,
+        context: CookieSiteContext,
     ) -> impl Iterator<Item = cookie::Cookie<'static>> + 'a {
         let domain = reg_host(url.host_str().unwrap_or(""));
         let cookies = self.cookies_map.entry(domain).or_default();
 
         cookies
             .iter_mut()
-            .filter(move |c| c.appropriate_for_url(url, source))
+            .filter(move |c| {
+                c.appropriate_for_url(url, source) && c.appropriate_for_context(&context)
+            })
             .sorted_by(|a: &&mut ServoCookie, b: &&mut ServoCookie| {
                 // The user agent SHOULD sort the cookie-list
                 CookieStorage::cookie_comparator(a, b)
@@ -0,0 +1,487 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Persistence of the HTTP cache to disk.
+//!
+//! Each stored response body is kept in its own file, next to an index holding the metadata
+//! of all stored responses. Changes to the index are appended to a journal, which is folded
+//! back into the index when the cache is opened or when the journal grows too long. When the
+//! stored bodies grow larger than the configured size, the least recently used responses are
+//! evicted.
+//!
+//! The index is kept in memory, and all writes to disk are made by a dedicated thread, so
+//! that fetches never wait on them. Response bodies are only read once a stored response
+//! is selected for a request.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use http::HeaderMap;
+use log::warn;
+use net_traits::http_status::HttpStatus;
+use net_traits::response::HttpsState;
+use serde::{Deserialize, Serialize};
+use servo_url::ServoUrl;
+use uuid::Uuid;
+
+/// The name of the file holding the index of the disk cache.
+const INDEX_FILE_NAME: &str = "index.json";
+
+/// The name of the file that a new index is written to, before it replaces the index.
+const NEW_INDEX_FILE_NAME: &str = "index.json.new";
+
+/// The name of the file holding the changes made to the index since it was last written.
+const JOURNAL_FILE_NAME: &str = "journal.json";
+
+/// The number of changes after which the journal is folded back into the index.
+const MAX_JOURNAL_LENGTH: usize = 1000;
+
+/// The metadata of a response stored in the disk cache.
+#[derive(Clone, Deserialize, Serialize)]
+pub(crate) struct StoredResponse {
+    /// The URL of the request that the response was stored for.
+    pub url: ServoUrl,
+    /// The headers of the request that are named by the `Vary` header of the response,
+    /// used to select the response for later requests. Other request headers, which may
+    /// hold credentials, are never stored.
+    #[serde(
+        deserialize_with = "::hyper_serde::deserialize",
+        serialize_with = "::hyper_serde::serialize"
+    )]
+    pub request_headers: HeaderMap,
+    #[serde(
+        deserialize_with = "::hyper_serde::deserialize",
+        serialize_with = "::hyper_serde::serialize"
+    )]
+    pub headers: HeaderMap,
+    pub status: HttpStatus,
+    pub final_url: ServoUrl,
+    pub url_list: Vec<ServoUrl>,
+    pub location_url: Option<Result<ServoUrl, String>>,
+    pub https_state: HttpsState,
+    pub content_type: Option<String>,
+    pub charset: Option<String>,
+    /// The freshness lifetime of the response at the time it was last validated.
+    pub expires: Duration,
+    pub last_validated: SystemTime,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct DiskCacheEntry {
+    response: StoredResponse,
+    /// The name of the file holding the response body.
+    body_file: String,
+    body_size: u64,
+    last_used: SystemTime,
+}
+
+/// A change to the index, as recorded in the journal.
+#[derive(Clone, Deserialize, Serialize)]
+enum IndexChange {
+    /// A response was stored.
+    Insert(DiskCacheEntry),
+    /// The response whose body is in the given file was used, and possibly revalidated.
+    Update {
+        body_file: String,
+        response: Option<StoredResponse>,
+        last_used: SystemTime,
+    },
+    /// The response whose body is in the given file was removed.
+    Remove(String),
+}
+
+impl IndexChange {
+    fn apply(&self, entries: &mut Vec<DiskCacheEntry>) {
+        match self {
+            IndexChange::Insert(new_entry) => {
+                entries.retain(|entry| entry.body_file != new_entry.body_file);
+                entries.push(new_entry.clone());
+            },
+            IndexChange::Update {
+                body_file,
+                response,
+                last_used,
+            } => {
+                if let Some(entry) = entries
+                    .iter_mut()
+                    .find(|entry| entry.body_file == *body_file)
+                {
+                    if let Some(response) = response {
+                        entry.response = response.clone();
+                    }
+                    entry.last_used = *last_used;
+                }
+            },
+            IndexChange::Remove(body_file) => {
+                entries.retain(|entry| entry.body_file != *body_file);
+            },
+        }
+    }
+}
+
+/// Work for the thread that writes to the disk cache.
+enum DiskCacheTask {
+    /// Write the body of a response that is about to be inserted into the index.
+    WriteBody(String, Vec<u8>),
+    /// Write changes to the index.
+    Record(Vec<IndexChange>),
+    /// Remove all stored responses.
+    Clear,
+}
+
+/// A response stored in the disk cache, whose body has not been read yet.
+pub(crate) struct DiskCacheHit {
+    /// The name of the file holding the body, which identifies the response in the cache.
+    pub body_file: String,
+    pub response: StoredResponse,
+    body_path: PathBuf,
+}
+
+impl DiskCacheHit {
+    /// Read the body of the response. This blocks on disk I/O, so it should not be called
+    /// from the network runtime.
+    pub(crate) fn read_body(&self) -> Option<Vec<u8>> {
+        fs::read(&self.body_path).ok()
+    }
+}
+
+/// A directory of stored HTTP responses.
+pub(crate) struct DiskCache {
+    directory: PathBuf,
+    /// The maximum total size, in bytes, of the stored response bodies.
+    max_size: u64,
+    /// The index, which includes the changes that the writer thread has not made yet.
+    entries: Mutex<Vec<DiskCacheEntry>>,
+    /// The channel to the thread that writes to the disk cache.
+    writer: Option<Sender<DiskCacheTask>>,
+    writer_thread: Option<JoinHandle<()>>,
+}
+
+impl DiskCache {
+    /// Open the disk cache in `directory`, creating the directory if needed.
+    pub(crate) fn new(directory: PathBuf, max_size: u64) -> DiskCache {
+        if let Err(error) = fs::create_dir_all(&directory) {
+            warn!(
+                "Couldn't create HTTP cache directory {:?}: {error}",
+                directory
+            );
+        }
+        let mut entries: Vec<DiskCacheEntry> = File::open(directory.join(INDEX_FILE_NAME))
+            .ok()
+            .and_then(|file| serde_json::from_reader(file).ok())
+            .unwrap_or_default();
+
+        // Replay the changes made since the index was last written. A change that was only
+        // partially written when Servo exited ends the journal.
+        if let Ok(journal) = File::open(directory.join(JOURNAL_FILE_NAME)) {
+            BufReader::new(journal)
+                .lines()
+                .map_while(|line| serde_json::from_str::<IndexChange>(&line.ok()?).ok())
+                .for_each(|change| change.apply(&mut entries));
+        }
+        entries.retain(|entry| directory.join(&entry.body_file).is_file());
+
+        let (sender, receiver) = unbounded();
+        let writer = DiskCacheWriter {
+            directory: directory.clone(),
+            entries: entries.clone(),
+            journal: None,
+            journal_length: 0,
+        };
+        let writer_thread = thread::Builder::new()
+            .name("HttpDiskCache".to_owned())
+            .spawn(move || writer.run(receiver))
+            .map_err(|error| warn!("Couldn't start the HTTP cache writer thread: {error}"))
+            .ok();
+
+        DiskCache {
+            directory,
+            max_size,
+            entries: Mutex::new(entries),
+            writer: writer_thread.as_ref().map(|_| sender),
+            writer_thread,
+        }
+    }
+
+    /// The responses stored for `url`, without their bodies.
+    pub(crate) fn lookup(&self, url: &ServoUrl) -> Vec<DiskCacheHit> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|entry| entry.response.url == *url)
+            .map(|entry| DiskCacheHit {
+                body_file: entry.body_file.clone(),
+                response: entry.response.clone(),
+                body_path: self.directory.join(&entry.body_file),
+            })
+            .collect()
+    }
+
+    /// Note that the stored responses whose bodies are in `body_files` were used, so that
+    /// they are evicted last.
+    pub(crate) fn mark_used(&self, body_files: Vec<String>) {
+        let changes = body_files
+            .into_iter()
+            .map(|body_file| IndexChange::Update {
+                body_file,
+                response: None,
+                last_used: SystemTime::now(),
+            })
+            .collect();
+        self.record(&mut self.entries.lock().unwrap(), changes);
+    }
+
+    /// Store a response, returning the name of its body file, or `None` if the response
+    /// could not be stored.
+    pub(crate) fn store(&self, response: StoredResponse, body: &[u8]) -> Option<String> {
+        let body_size = body.len() as u64;
+        if body_size > self.max_size {
+            return None;
+        }
+        let writer = self.writer.as_ref()?;
+
+        let body_file = Uuid::new_v4().simple().to_string();
+        let _ = writer.send(DiskCacheTask::WriteBody(body_file.clone(), body.to_vec()));
+
+        let mut entries = self.entries.lock().unwrap();
+        let mut changes = vec![IndexChange::Insert(DiskCacheEntry {
+            response,
+            body_file: body_file.clone(),
+            body_size,
+            last_used: SystemTime::now(),
+        })];
+        changes.extend(
+            self.entries_to_evict(&entries, body_size)
+                .into_iter()
+                .map(IndexChange::Remove),
+        );
+        self.record(&mut entries, changes);
+        Some(body_file)
+    }
+
+    /// Replace the metadata of the stored response whose body is in `body_file`, after it
+    /// has been revalidated.
+    pub(crate) fn update(&self, body_file: &str, response: StoredResponse) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.iter().any(|entry| entry.body_file == body_file) {
+            let change = IndexChange::Update {
+                body_file: body_file.to_owned(),
+                response: Some(response),
+                last_used: SystemTime::now(),
+            };
+            self.record(&mut entries, vec![change]);
+        }
+    }
+
+    /// Remove all responses stored for `url`.
+    pub(crate) fn remove(&self, url: &ServoUrl) {
+        let mut entries = self.entries.lock().unwrap();
+        let changes = entries
+            .iter()
+            .filter(|entry| entry.response.url == *url)
+            .map(|entry| IndexChange::Remove(entry.body_file.clone()))
+            .collect();
+        self.record(&mut entries, changes);
+    }
+
+    /// Remove all stored responses.
+    pub(crate) fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+        if let Some(writer) = &self.writer {
+            let _ = writer.send(DiskCacheTask::Clear);
+        }
+    }
+
+    /// The body files of the least recently used responses that need to be removed for the
+    /// stored bodies, including a new body of `new_body_size` bytes, to fit in `max_size`.
+    fn entries_to_evict(&self, entries: &[DiskCacheEntry], new_body_size: u64) -> Vec<String> {
+        let mut total_size: u64 =
+            new_body_size + entries.iter().map(|entry| entry.body_size).sum::<u64>();
+        if total_size <= self.max_size {
+            return vec![];
+        }
+        let mut entries: Vec<_> = entries.iter().collect();
+        entries.sort_by_key(|entry| entry.last_used);
+        entries
+            .into_iter()
+            .take_while(|entry| {
+                let evict = total_size > self.max_size;
+                total_size -= entry.body_size;
+                evict
+            })
+            .map(|entry| entry.body_file.clone())
+            .collect()
+    }
+
+    /// Apply `changes` to the index, and have the writer thread write them to disk.
+    fn record(&self, entries: &mut Vec<DiskCacheEntry>, changes: Vec<IndexChange>) {
+        if changes.is_empty() {
+            return;
+        }
+        for change in &changes {
+            change.apply(entries);
+        }
+        if let Some(writer) = &self.writer {
+            let _ = writer.send(DiskCacheTask::Record(changes));
+        }
+    }
+}
+
+impl Drop for DiskCache {
+    fn drop(&mut self) {
+        // Let the writer thread finish the writes that are queued, so that they are not lost.
+        self.writer = None;
+        if let Some(writer_thread) = self.writer_thread.take() {
+            let _ = writer_thread.join();
+        }
+    }
+}
+
+/// The state of the thread that writes to the disk cache: its own copy of the index, along
+/// with the journal of changes not yet written to the index file.
+struct DiskCacheWriter {
+    directory: PathBuf,
+    entries: Vec<DiskCacheEntry>,
+    journal: Option<BufWriter<File>>,
+    journal_length: usize,
+}
+
+impl DiskCacheWriter {
+    fn run(mut self, receiver: Receiver<DiskCacheTask>) {
+        // Fold the journal read when opening the cache into the index.
+        self.write_index();
+        self.remove_unindexed_files();
+
+        for task in receiver {
+            match task {
+                DiskCacheTask::WriteBody(body_file, body) => {
+                    if let Err(error) = fs::write(self.directory.join(&body_file), body) {
+                        warn!("Couldn't write to the HTTP cache: {error}");
+                    }
+                },
+                DiskCacheTask::Record(changes) => self.record(changes),
+                DiskCacheTask::Clear => {
+                    for entry in std::mem::take(&mut self.entries) {
+                        self.remove_file(&entry.body_file);
+                    }
+                    self.write_index();
+                },
+            }
+        }
+    }
+
+    /// Apply `changes` to the index and append them to the journal, removing the body files
+    /// of removed responses.
+    fn record(&mut self, changes: Vec<IndexChange>) {
+        if self.journal_length + changes.len() > MAX_JOURNAL_LENGTH {
+            for change in changes {
+                if let IndexChange::Remove(body_file) = &change {
+                    self.remove_file(body_file);
+                }
+                change.apply(&mut self.entries);
+            }
+            self.write_index();
+            return;
+        }
+
+        if self.journal.is_none() {
+            let path = self.directory.join(JOURNAL_FILE_NAME);
+            match OpenOptions::new().create(true).append(true).open(&path) {
+                Ok(file) => self.journal = Some(BufWriter::new(file)),
+                Err(error) => warn!(
+                    "Couldn't open HTTP cache journal {}: {error}",
+                    path.display()
+                ),
+            }
+        }
+        for change in changes {
+            if let Some(journal) = self.journal.as_mut() {
+                let result = serde_json::to_writer(&mut *journal, &change)
+                    .map_err(|error| error.to_string())
+                    .and_then(|_| journal.write_all(b"\n").map_err(|error| error.to_string()));
+                if let Err(error) = result {
+                    warn!("Couldn't write to the HTTP cache journal: {error}");
+                }
+            }
+            if let IndexChange::Remove(body_file) = &change {
+                self.remove_file(body_file);
+            }
+            change.apply(&mut self.entries);
+            self.journal_length += 1;
+        }
+        if let Some(Err(error)) = self.journal.as_mut().map(|journal| journal.flush()) {
+            warn!("Couldn't write to the HTTP cache journal: {error}");
+        }
+    }
+
+    /// Write the whole index to disk and empty the journal. The index is written to a new
+    /// file that then replaces the old one, so that the index on disk is complete even if
+    /// Servo exits while writing it.
+    fn write_index(&mut self) {
+        let path = self.directory.join(INDEX_FILE_NAME);
+        let new_path = self.directory.join(NEW_INDEX_FILE_NAME);
+        let result = File::create(&new_path)
+            .map_err(|error| error.to_string())
+            .and_then(|file| {
+                let mut writer = BufWriter::new(file);
+                serde_json::to_writer(&mut writer, &self.entries)
+                    .map_err(|error| error.to_string())?;
+                let file = writer.into_inner().map_err(|error| error.to_string())?;
+                file.sync_all().map_err(|error| error.to_string())
+            })
+            .and_then(|_| fs::rename(&new_path, &path).map_err(|error| error.to_string()));
+        if let Err(error) = result {
+            let _ = fs::remove_file(&new_path);
+            warn!(
+                "Couldn't write HTTP cache index {}: {error}",
+                path.display()
+            );
+            return;
+        }
+
+        self.journal = None;
+        self.journal_length = 0;
+        let journal_path = self.directory.join(JOURNAL_FILE_NAME);
+        if let Err(error) = File::create(&journal_path) {
+            warn!(
+                "Couldn't empty HTTP cache journal {}: {error}",
+                journal_path.display()
+            );
+        }
+    }
+
+    fn remove_file(&self, body_file: &str) {
+        let _ = fs::remove_file(self.directory.join(body_file));
+    }
+
+    /// Remove body files that are not in the index, such as those left behind when Servo
+    /// exited before writing the index.
+    fn remove_unindexed_files(&self) {
+        let Ok(directory) = fs::read_dir(&self.directory) else {
+            return;
+        };
+        for file in directory.flatten() {
+            let file_name = file.file_name();
+            let Some(file_name) = file_name.to_str() else {
+                continue;
+            };
+            if file_name != INDEX_FILE_NAME &&
+                file_name != JOURNAL_FILE_NAME &&
+                file_name != NEW_INDEX_FILE_NAME &&
+                !self
+                    .entries
+                    .iter()
+                    .any(|entry| entry.body_file == file_name)
+            {
+                self.remove_file(file_name);
+            }
+        }
+    }
+}
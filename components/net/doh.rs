@@ -0,0 +1,355 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Resolution of host names with [DNS-over-HTTPS].
+//!
+//! When the `network_doh_provider_url` preference is set, host names are resolved by sending
+//! DNS queries to that provider over HTTPS, so that lookups are encrypted without any operating
+//! system configuration. Answers are cached for their time to live. If the provider cannot be
+//! reached or returns no addresses, the system resolver is used instead.
+//!
+//! [DNS-over-HTTPS]: https://www.rfc-editor.org/rfc/rfc8484
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use http::header::ACCEPT;
+use http_body_util::{BodyExt, Empty};
+use hyper::body::Bytes;
+use hyper_rustls::HttpsConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::connect::dns::{GaiResolver, Name};
+use log::{debug, warn};
+use servo_config::pref;
+use tower_service::Service;
+
+use crate::connector::{
+    CACertificates, CertificateErrorOverrideManager, TokioExecutor, create_tls_config,
+};
+
+/// The media type of DNS messages, <https://www.rfc-editor.org/rfc/rfc8484#section-6>.
+const DNS_MESSAGE_MEDIA_TYPE: &str = "application/dns-message";
+
+/// How long to wait for the provider before falling back to the system resolver.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The longest time an answer is cached for, whatever its time to live.
+const MAX_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// The DNS record type of IPv4 addresses.
+pub const RECORD_TYPE_A: u16 = 1;
+/// The DNS record type of IPv6 addresses.
+pub const RECORD_TYPE_AAAA: u16 = 28;
+
+const CLASS_IN: u16 = 1;
+
+/// The host name resolver used by the HTTP connector: DNS-over-HTTPS if a provider is
+/// configured, and the system resolver otherwise.
+#[derive(Clone)]
+pub struct DnsResolver {
+    doh: Option<Arc<DohResolver>>,
+    system: GaiResolver,
+}
+
+impl DnsResolver {
+    pub(crate) fn new() -> DnsResolver {
+        let provider = pref!(network_doh_provider_url);
+        let doh = if provider.is_empty() {
+            None
+        } else if !provider.starts_with("https://") {
+            warn!("Ignoring DNS-over-HTTPS provider {provider}, which does not use HTTPS");
+            None
+        } else {
+            Some(Arc::new(DohResolver::new(provider)))
+        };
+        DnsResolver {
+            doh,
+            system: GaiResolver::new(),
+        }
+    }
+}
+
+impl Service<Name> for DnsResolver {
+    type Response = std::vec::IntoIter<SocketAddr>;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, io::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Ok(()).into()
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        let doh = self.doh.clone();
+        let mut system = self.system.clone();
+        Box::pin(async move {
+            let host = name.as_str();
+            // Local names are never sent to the provider.
+            let is_local = host == "localhost" || host.ends_with(".localhost");
+            if let Some(doh) = doh.filter(|_| !is_local) {
+                match doh.lookup(host).await {
+                    Ok(addresses) => {
+                        return Ok(addresses
+                            .into_iter()
+                            .map(|address| SocketAddr::new(address, 0))
+                            .collect::<Vec<_>>()
+                            .into_iter());
+                    },
+                    Err(error) => warn!(
+                        "DNS-over-HTTPS lookup of {host} failed, using the system resolver: {error}"
+                    ),
+                }
+            }
+            let addresses = system.call(name).await?;
+            Ok(addresses.collect::<Vec<_>>().into_iter())
+        })
+    }
+}
+
+struct CachedLookup {
+    addresses: Vec<IpAddr>,
+    expires: Instant,
+}
+
+/// A client of a DNS-over-HTTPS provider, with a cache of its answers.
+struct DohResolver {
+    provider: String,
+    /// The client used to reach the provider, whose own host name is resolved by the system
+    /// resolver.
+    client: Client<HttpsConnector<HttpConnector>, Empty<Bytes>>,
+    cache: Mutex<HashMap<String, CachedLookup>>,
+}
+
+impl DohResolver {
+    fn new(provider: String) -> DohResolver {
+        let tls_config = create_tls_config(
+            CACertificates::Default,
+            false,
+            CertificateErrorOverrideManager::new(),
+        );
+        let mut http_connector = HttpConnector::new();
+        http_connector.enforce_http(false);
+        let connector = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_tls_config(tls_config)
+            .https_only()
+            .enable_http1()
+            .enable_http2()
+            .wrap_connector(http_connector);
+        DohResolver {
+            provider,
+            client: Client::builder(TokioExecutor {}).build(connector),
+            cache: Default::default(),
+        }
+    }
+
+    /// Look up the IPv6 and IPv4 addresses of `host`, failing if there are none.
+    async fn lookup(&self, host: &str) -> Result<Vec<IpAddr>, String> {
+        if let Some(cached) = self.cache.lock().unwrap().get(host) {
+            if cached.expires > Instant::now() {
+                return Ok(cached.addresses.clone());
+            }
+        }
+
+        let (ipv6, ipv4) = futures::join!(
+            self.query(host, RECORD_TYPE_AAAA),
+            self.query(host, RECORD_TYPE_A)
+        );
+        let mut addresses = vec![];
+        let mut ttl = MAX_CACHE_TTL;
+        let mut last_error = None;
+        for result in [ipv6, ipv4] {
+            match result {
+                Ok((answer_addresses, answer_ttl)) => {
+                    addresses.extend(answer_addresses);
+                    ttl = ttl.min(answer_ttl);
+                },
+                Err(error) => last_error = Some(error),
+            }
+        }
+        if addresses.is_empty() {
+            return Err(last_error.unwrap_or_else(|| "no addresses".to_owned()));
+        }
+
+        debug!("Resolved {host} to {addresses:?} with DNS-over-HTTPS");
+        self.cache.lock().unwrap().insert(
+            host.to_owned(),
+            CachedLookup {
+                addresses: addresses.clone(),
+                expires: Instant::now() + ttl,
+            },
+        );
+        Ok(addresses)
+    }
+
+    /// Send a query for the records of `record_type` of `host`, returning the addresses in
+    /// the answer along with their time to live.
+    /// <https://www.rfc-editor.org/rfc/rfc8484#section-4.1>
+    async fn query(&self, host: &str, record_type: u16) -> Result<(Vec<IpAddr>, Duration), String> {
+        let query =
+            encode_dns_query(host, record_type).ok_or_else(|| format!("invalid host {host}"))?;
+        let separator = if self.provider.contains('?') {
+            '&'
+        } else {
+            '?'
+        };
+        let uri = format!(
+            "{}{separator}dns={}",
+            self.provider,
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(query)
+        );
+        let request = http::Request::get(uri)
+            .header(ACCEPT, DNS_MESSAGE_MEDIA_TYPE)
+            .body(Empty::new())
+            .map_err(|error| error.to_string())?;
+
+        let body = tokio::time::timeout(QUERY_TIMEOUT, async {
+            let response = self
+                .client
+                .request(request)
+                .await
+                .map_err(|error| error.to_string())?;
+            if !response.status().is_success() {
+                return Err(format!("provider responded with {}", response.status()));
+            }
+            response
+                .into_body()
+                .collect()
+                .await
+                .map(|body| body.to_bytes())
+                .map_err(|error| error.to_string())
+        })
+        .await
+        .map_err(|_| "timed out".to_owned())??;
+
+        parse_dns_response(&body, record_type)
+    }
+}
+
+/// Encode a DNS query for the records of `record_type` of `host`, or return `None` if `host`
+/// is not a valid domain name.
+/// <https://www.rfc-editor.org/rfc/rfc1035#section-4.1>
+pub fn encode_dns_query(host: &str, record_type: u16) -> Option<Vec<u8>> {
+    let host = host.strip_suffix('.').unwrap_or(host);
+    if host.is_empty() || host.len() > 253 {
+        return None;
+    }
+
+    // The id is 0 so that identical queries can be cached by HTTP caches, and only the
+    // "recursion desired" flag is set.
+    let mut message = vec![0, 0, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+    for label in host.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return None;
+        }
+        message.push(label.len() as u8);
+        message.extend_from_slice(label.as_bytes());
+    }
+    message.push(0);
+    message.extend_from_slice(&record_type.to_be_bytes());
+    message.extend_from_slice(&CLASS_IN.to_be_bytes());
+    Some(message)
+}
+
+/// Read the addresses of the records of `record_type` in the answer section of a DNS
+/// response, along with the shortest time to live among them.
+pub fn parse_dns_response(
+    message: &[u8],
+    record_type: u16,
+) -> Result<(Vec<IpAddr>, Duration), String> {
+    let mut reader = DnsReader { message, offset: 0 };
+    let malformed = || "malformed DNS response".to_owned();
+
+    let _id = reader.read_u16().ok_or_else(malformed)?;
+    let flags = reader.read_u16().ok_or_else(malformed)?;
+    let response_code = flags & 0x000F;
+    if response_code != 0 {
+        return Err(format!("DNS response code {response_code}"));
+    }
+    let question_count = reader.read_u16().ok_or_else(malformed)?;
+    let answer_count = reader.read_u16().ok_or_else(malformed)?;
+    reader.skip(4).ok_or_else(malformed)?;
+
+    for _ in 0..question_count {
+        reader.skip_name().ok_or_else(malformed)?;
+        reader.skip(4).ok_or_else(malformed)?;
+    }
+
+    let mut addresses = vec![];
+    let mut ttl = MAX_CACHE_TTL;
+    for _ in 0..answer_count {
+        reader.skip_name().ok_or_else(malformed)?;
+        let answer_type = reader.read_u16().ok_or_else(malformed)?;
+        let class = reader.read_u16().ok_or_else(malformed)?;
+        let answer_ttl = reader.read_u32().ok_or_else(malformed)?;
+        let length = reader.read_u16().ok_or_else(malformed)? as usize;
+        let data = reader.read(length).ok_or_else(malformed)?;
+        if answer_type != record_type || class != CLASS_IN {
+            // Other records, such as the CNAMEs leading to the addresses, are skipped.
+            continue;
+        }
+
+        let address = match (record_type, data.len()) {
+            (RECORD_TYPE_A, 4) => IpAddr::from(Ipv4Addr::new(data[0], data[1], data[2], data[3])),
+            (RECORD_TYPE_AAAA, 16) => {
+                let mut octets = [0; 16];
+                octets.copy_from_slice(data);
+                IpAddr::from(Ipv6Addr::from(octets))
+            },
+            _ => return Err(malformed()),
+        };
+        addresses.push(address);
+        ttl = ttl.min(Duration::from_secs(answer_ttl.into()));
+    }
+    Ok((addresses, ttl))
+}
+
+struct DnsReader<'a> {
+    message: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> DnsReader<'a> {
+    fn read(&mut self, length: usize) -> Option<&'a [u8]> {
+        let bytes = self
+            .message
+            .get(self.offset..self.offset.checked_add(length)?)?;
+        self.offset += length;
+        Some(bytes)
+    }
+
+    fn skip(&mut self, length: usize) -> Option<()> {
+        self.read(length).map(|_| ())
+    }
+
+    fn read_u16(&mut self) -> Option<u16> {
+        self.read(2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        self.read(4)
+            .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Skip a domain name, which is a sequence of labels ending either with an empty label or
+    /// with a pointer to a name elsewhere in the message.
+    /// <https://www.rfc-editor.org/rfc/rfc1035#section-4.1.4>
+    fn skip_name(&mut self) -> Option<()> {
+        loop {
+            let length = self.read(1)?[0];
+            match length {
+                0 => return Some(()),
+                length if length & 0xC0 == 0xC0 => return self.skip(1),
+                length => self.skip(length as usize)?,
+            }
+        }
+    }
+}
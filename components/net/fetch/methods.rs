@@ -24,8 +24,9 @@ use net_traits::http_status::HttpStatus;
 use net_traits::policy_container::{PolicyContainer, RequestPolicyContainer};
 use net_traits::request::{
     BodyChunkRequest, BodyChunkResponse, CredentialsMode, Destination, Initiator,
-    InsecureRequestsPolicy, Origin, ParserMetadata, RedirectMode, Referrer, Request, RequestMode,
-    ResponseTainting, Window, is_cors_safelisted_method, is_cors_safelisted_request_header,
+    InsecureRequestsPolicy, InternalPriority, Origin, ParserMetadata, RedirectMode, Referrer,
+    Request, RequestMode, ResponseTainting, Window, is_cors_safelisted_method,
+    is_cors_safelisted_request_header,
 };
 use net_traits::response::{Response, ResponseBody, ResponseType};
 use net_traits::{
@@ -36,11 +37,16 @@ use rustls_pki_types::CertificateDer;
 use serde::{Deserialize, Serialize};
 use servo_arc::Arc as ServoArc;
 use servo_url::{Host, ImmutableOrigin, ServoUrl};
+use tokio::sync::Notify;
 use tokio::sync::mpsc::{UnboundedReceiver as TokioReceiver, UnboundedSender as TokioSender};
 
 use super::fetch_params::FetchParams;
 use crate::fetch::cors_cache::CorsCache;
 use crate::fetch::headers::determine_nosniff;
+use crate::fetch::opaque_response_blocking::{
+    OpaqueResponseSafelistCheck, opaque_response_safelist_check_body,
+    opaque_response_safelist_check_headers,
+};
 use crate::filemanager_thread::FileManager;
 use crate::http_loader::{
     HttpState, determine_requests_referrer, http_fetch, send_early_httprequest_to_devtools,
@@ -74,9 +80,14 @@ pub struct FetchContext {
     pub protocols: Arc<ProtocolRegistry>,
 }
 
+/// The control side of an ongoing fetch, shared between the resource thread and the
+/// tasks that perform the fetch. Besides cancellation, it lets the consumer of a
+/// response body pause the transfer of data from the network when it isn't keeping up.
 #[derive(Default)]
 pub struct CancellationListener {
     cancelled: AtomicBool,
+    suspended: AtomicBool,
+    state_changed: Notify,
 }
 
 impl CancellationListener {
@@ -85,7 +96,40 @@ impl CancellationListener {
     }
 
     pub(crate) fn cancel(&self) {
-        self.cancelled.store(true, Ordering::Relaxed)
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.state_changed.notify_waiters();
+    }
+
+    /// Stop reading the response body from the network until [`Self::resume`] is called.
+    pub(crate) fn suspend(&self) {
+        self.suspended.store(true, Ordering::Relaxed);
+    }
+
+    pub(crate) fn resume(&self) {
+        self.suspended.store(false, Ordering::Relaxed);
+        self.state_changed.notify_waiters();
+    }
+
+    /// Wait until the fetch is cancelled.
+    pub(crate) async fn wait_until_cancelled(&self) {
+        loop {
+            let notified = self.state_changed.notified();
+            if self.cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Wait until the response body is no longer suspended, or the fetch was cancelled.
+    pub(crate) async fn wait_until_resumed(&self) {
+        loop {
+            let notified = self.state_changed.notified();
+            if !self.suspended.load(Ordering::Relaxed) || self.cancelled() {
+                return;
+            }
+            notified.await;
+        }
     }
 }
 pub type DoneChannel = Option<(TokioSender<Data>, TokioReceiver<Data>)>;
@@ -159,7 +203,13 @@ pub async fn fetch_with_cors_cache(
     // Step 15. If request’s internal priority is null, then use request’s priority, initiator,
     // destination, and render-blocking in an implementation-defined manner to set request’s
     // internal priority to an implementation-defined object.
-    // TODO: figure out what a Priority object is.
+    if request.internal_priority.is_none() {
+        request.internal_priority = Some(InternalPriority::for_request(
+            request.priority,
+            request.initiator,
+            request.destination,
+        ));
+    }
 
     // Step 16: If request is a subresource request, then:
     if request.is_subresource_request() {
@@ -367,10 +417,13 @@ pub async fn main_fetch(
         request.referrer_policy = policy_container.get_referrer_policy();
     }
 
+    // Step 9: If request’s referrer is not "no-referrer", then set request’s referrer to the
+    // result of invoking determine request’s referrer.
+    // A `Referer` header from before a redirect must not outlive a policy that now forbids it.
+    request.headers.remove(header::REFERER);
     let referrer_url = match mem::replace(&mut request.referrer, Referrer::NoReferrer) {
         Referrer::NoReferrer => None,
         Referrer::ReferrerUrl(referrer_source) | Referrer::Client(referrer_source) => {
-            request.headers.remove(header::REFERER);
             determine_requests_referrer(
                 request.referrer_policy,
                 referrer_source,
@@ -380,7 +433,6 @@ pub async fn main_fetch(
     };
     request.referrer = referrer_url.map_or(Referrer::NoReferrer, Referrer::ReferrerUrl);
 
-    // Step 9.
     // TODO: handle FTP URLs.
 
     // Step 10.
@@ -658,6 +710,34 @@ pub async fn main_fetch(
         response
     };
 
+    // Cross-origin no-cors responses only reach the fetch target once they pass the
+    // opaque-response-safelist check.
+    // <https://whatpr.org/fetch/1442.html#main-fetch>
+    let mut response = if !response.is_network_error() &&
+        request.mode == RequestMode::NoCors &&
+        response.response_type == ResponseType::Opaque
+    {
+        let allowed = match opaque_response_safelist_check_headers(response.actual_response()) {
+            OpaqueResponseSafelistCheck::Allowed => true,
+            OpaqueResponseSafelistCheck::Blocked => false,
+            OpaqueResponseSafelistCheck::NeedsBody => {
+                wait_for_response_body(&mut response, done_chan).await;
+                opaque_response_safelist_check_body(response.actual_response())
+            },
+        };
+        if allowed {
+            response
+        } else {
+            // Make sure that no part of the body is passed on to the fetch target.
+            *done_chan = None;
+            Response::network_error(NetworkError::Internal(
+                "Blocked by opaque response blocking".into(),
+            ))
+        }
+    } else {
+        response
+    };
+
     // Step 19.
     let mut response_loaded = false;
     let mut response = if !response.is_network_error() && !request.integrity_metadata.is_empty() {
@@ -670,9 +750,7 @@ pub async fn main_fetch(
         if response.termination_reason.is_none() &&
             !is_response_integrity_valid(integrity_metadata, &response)
         {
-            Response::network_error(NetworkError::Internal(
-                "Subresource integrity validation failed".into(),
-            ))
+            Response::network_error(NetworkError::SubresourceIntegrity)
         } else {
             response
         }
@@ -720,8 +798,9 @@ pub async fn main_fetch(
     // processed before sending the response to Devtools.
     send_response_to_devtools(request, context, &response, None);
 
-    if let Ok(http_cache) = context.state.http_cache.write() {
+    if let Ok(mut http_cache) = context.state.http_cache.write() {
         http_cache.update_awaiting_consumers(request, &response);
+        http_cache.write_to_disk(request, &response);
     }
 
     // Steps 25-27.
@@ -777,6 +856,27 @@ async fn wait_for_response(
     }
 }
 
+/// Waits for the whole body of `response` to be received without passing it on to the fetch
+/// target. [`wait_for_response`] then passes the received body on at once.
+async fn wait_for_response_body(response: &mut Response, done_chan: &mut DoneChannel) {
+    if let Some(ref mut ch) = *done_chan {
+        loop {
+            match ch.1.recv().await {
+                Some(Data::Payload(_)) => {},
+                Some(Data::Done) => break,
+                Some(Data::Cancelled) => {
+                    response.aborted.store(true, Ordering::Release);
+                    break;
+                },
+                _ => {
+                    panic!("fetch worker should always send Done before terminating");
+                },
+            }
+        }
+    }
+    *done_chan = None;
+}
+
 /// Range header start and end values.
 pub enum RangeRequestBounds {
     /// The range bounds are known and set to final values.
@@ -0,0 +1,204 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Opaque Response Blocking (ORB), which keeps cross-origin `no-cors` responses that are
+//! unlikely to be images, media, scripts or stylesheets out of the content process.
+//!
+//! <https://whatpr.org/fetch/1442.html#orb-algorithm>
+
+use http::StatusCode;
+use mime::Mime;
+use net_traits::fetch::headers::extract_mime_type_as_mime;
+use net_traits::mime_classifier::{MediaType, MimeClassifier};
+use net_traits::response::{Response, ResponseBody};
+
+use crate::fetch::headers::determine_nosniff;
+
+/// The result of running the part of the opaque-response-safelist check that only looks at the
+/// response headers.
+#[derive(Debug, PartialEq)]
+pub(crate) enum OpaqueResponseSafelistCheck {
+    Allowed,
+    Blocked,
+    /// The body of the response needs to be sniffed before a decision can be made.
+    NeedsBody,
+}
+
+/// <https://whatpr.org/fetch/1442.html#opaque-safelisted-mime-type>
+fn is_opaque_safelisted_mime_type(mime_type: &Mime) -> bool {
+    MimeClassifier::get_media_type(mime_type) == Some(MediaType::JavaScript) ||
+        matches!(
+            mime_type.essence_str(),
+            "application/dash+xml" |
+                "application/vnd.apple.mpegurl" |
+                "audio/mpegurl" |
+                "audio/x-mpegurl" |
+                "image/svg+xml" |
+                "text/css" |
+                "text/vtt"
+        )
+}
+
+/// <https://whatpr.org/fetch/1442.html#opaque-blocklisted-mime-type>
+fn is_opaque_blocklisted_mime_type(mime_type: &Mime) -> bool {
+    matches!(
+        MimeClassifier::get_media_type(mime_type),
+        Some(MediaType::Html) | Some(MediaType::Json) | Some(MediaType::Xml)
+    ) && mime_type.essence_str() != "image/svg+xml"
+}
+
+/// <https://whatpr.org/fetch/1442.html#opaque-blocklisted-never-sniffed-mime-type>
+fn is_opaque_blocklisted_never_sniffed_mime_type(mime_type: &Mime) -> bool {
+    matches!(
+        mime_type.essence_str(),
+        "application/gzip" |
+            "application/msexcel" |
+            "application/mspowerpoint" |
+            "application/msword" |
+            "application/msword-template" |
+            "application/pdf" |
+            "application/vnd.ces-quickpoint" |
+            "application/vnd.ces-quicksheet" |
+            "application/vnd.ces-quickword" |
+            "application/vnd.ms-excel" |
+            "application/vnd.ms-excel.sheet.macroenabled.12" |
+            "application/vnd.ms-powerpoint" |
+            "application/vnd.ms-powerpoint.presentation.macroenabled.12" |
+            "application/vnd.ms-word" |
+            "application/vnd.ms-word.document.12" |
+            "application/vnd.ms-word.document.macroenabled.12" |
+            "application/vnd.msword" |
+            "application/vnd.openxmlformats-officedocument.presentationml.presentation" |
+            "application/vnd.openxmlformats-officedocument.presentationml.template" |
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" |
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.template" |
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document" |
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.template" |
+            "application/vnd.presentation-openxml" |
+            "application/vnd.presentation-openxmlm" |
+            "application/vnd.spreadsheet-openxml" |
+            "application/vnd.wordprocessing-openxml" |
+            "application/x-gzip" |
+            "application/x-protobuf" |
+            "application/x-protobuffer" |
+            "application/zip" |
+            "multipart/byteranges" |
+            "multipart/signed" |
+            "text/event-stream" |
+            "text/csv"
+    )
+}
+
+/// Steps 1 to 3 of the opaque-response-safelist check, which only need the headers and status
+/// of `response`, which must not be a filtered response.
+///
+/// <https://whatpr.org/fetch/1442.html#orb-algorithm>
+pub(crate) fn opaque_response_safelist_check_headers(
+    response: &Response,
+) -> OpaqueResponseSafelistCheck {
+    // Step 1. Let mimeType be the result of extracting a MIME type from response’s header list.
+    let mime_type = extract_mime_type_as_mime(&response.headers);
+
+    // Step 2. Let nosniff be the result of determining nosniff given response’s header list.
+    let nosniff = determine_nosniff(&response.headers);
+
+    // Step 3. If mimeType is not failure, then:
+    let Some(mime_type) = mime_type else {
+        return OpaqueResponseSafelistCheck::NeedsBody;
+    };
+
+    // Step 3.1. If mimeType is an opaque-safelisted MIME type, then return true.
+    if is_opaque_safelisted_mime_type(&mime_type) {
+        return OpaqueResponseSafelistCheck::Allowed;
+    }
+
+    // Step 3.2. If mimeType is an opaque-blocklisted-never-sniffed MIME type, then return false.
+    if is_opaque_blocklisted_never_sniffed_mime_type(&mime_type) {
+        return OpaqueResponseSafelistCheck::Blocked;
+    }
+
+    // Step 3.3. If response’s status is 206 and mimeType is an opaque-blocklisted MIME type,
+    // then return false.
+    let is_blocklisted = is_opaque_blocklisted_mime_type(&mime_type);
+    if response.status.code() == StatusCode::PARTIAL_CONTENT && is_blocklisted {
+        return OpaqueResponseSafelistCheck::Blocked;
+    }
+
+    // Step 3.4. If nosniff is true and mimeType is an opaque-blocklisted MIME type or its
+    // essence is "text/plain", then return false.
+    if nosniff && (is_blocklisted || mime_type.essence_str() == "text/plain") {
+        return OpaqueResponseSafelistCheck::Blocked;
+    }
+
+    // Responses that declare an image, audio or video type are allowed without sniffing their
+    // body, so that media can still be streamed. This deviates from the specification, which
+    // sniffs the body and blocks such responses when it does not turn out to be media.
+    if matches!(mime_type.type_(), mime::IMAGE | mime::AUDIO | mime::VIDEO) {
+        return OpaqueResponseSafelistCheck::Allowed;
+    }
+
+    OpaqueResponseSafelistCheck::NeedsBody
+}
+
+/// The remaining steps of the opaque-response-safelist check, run once the whole body of
+/// `response`, which must not be a filtered response, has been received. Returns true if the
+/// response is allowed.
+///
+/// <https://whatpr.org/fetch/1442.html#orb-algorithm>
+pub(crate) fn opaque_response_safelist_check_body(response: &Response) -> bool {
+    let body = response.body.lock().unwrap();
+    let ResponseBody::Done(ref bytes) = *body else {
+        // Step 13. If bytes is failure, then return false.
+        return false;
+    };
+
+    let mime_type = extract_mime_type_as_mime(&response.headers);
+    let classifier = MimeClassifier::default();
+
+    // Step 7. If the result of sniffing in a media context on bytes is an image, audio or video
+    // type, then return true.
+    if classifier.sniff_media_type(bytes).is_some() {
+        return true;
+    }
+
+    // Step 9. If nosniff is true, then return false.
+    if determine_nosniff(&response.headers) {
+        return false;
+    }
+
+    // Step 10. If response’s status is not an ok status, then return false.
+    if !response.status.is_success() {
+        return false;
+    }
+
+    // Step 11. If mimeType is failure, then return true.
+    let Some(mime_type) = mime_type else {
+        return true;
+    };
+
+    // Step 12. If mimeType’s essence starts with "audio/", "image/", or "video/", then return
+    // false.
+    if matches!(mime_type.type_(), mime::IMAGE | mime::AUDIO | mime::VIDEO) {
+        return false;
+    }
+
+    // Step 14. If response’s body parses as JavaScript and does not parse as JSON, then return
+    // true.
+    // Step 15. Return false.
+    //
+    // There is no JavaScript parser available here, so instead bodies that look like JSON,
+    // HTML or XML documents are blocked and everything else is assumed to be script.
+    !(has_json_security_prefix(bytes) ||
+        serde_json::from_slice::<serde_json::Value>(bytes).is_ok() ||
+        classifier.sniffs_as_markup_document(bytes))
+}
+
+/// Whether `bytes` starts with one of the prefixes commonly used to prevent JSON responses
+/// from being executed as script.
+fn has_json_security_prefix(bytes: &[u8]) -> bool {
+    let bytes = bytes.trim_ascii_start();
+    [&b")]}'"[..], b"{}&&", b"for(;;);", b"while(1);"]
+        .iter()
+        .any(|prefix| bytes.starts_with(prefix))
+}
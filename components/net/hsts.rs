@@ -5,7 +5,7 @@
 use std::collections::HashMap;
 use std::net::{Ipv4Addr, Ipv6Addr};
 use std::num::NonZeroU64;
-use std::sync::LazyLock;
+use std::sync::{LazyLock, RwLock};
 use std::time::Duration;
 
 use embedder_traits::resources::{self, Resource};
@@ -98,11 +98,26 @@ impl MallocSizeOf for HstsPreloadList {
     }
 }
 
-static PRELOAD_LIST_ENTRIES: LazyLock<HstsPreloadList> =
-    LazyLock::new(HstsPreloadList::from_servo_preload);
+static PRELOAD_LIST_ENTRIES: LazyLock<RwLock<HstsPreloadList>> =
+    LazyLock::new(|| RwLock::new(HstsPreloadList::from_servo_preload()));
 
 pub fn hsts_preload_size_of(ops: &mut MallocSizeOfOps) -> usize {
-    PRELOAD_LIST_ENTRIES.size_of(ops)
+    PRELOAD_LIST_ENTRIES.read().unwrap().size_of(ops)
+}
+
+/// Replace the preload list, such as with a version newer than the one built into Servo.
+/// Returns `false`, keeping the current list, if `preload_content` is not a valid FST map.
+pub fn replace_hsts_preload_list(preload_content: Vec<u8>) -> bool {
+    let Some(preload_list) = HstsPreloadList::from_preload(preload_content) else {
+        error!("New HSTS preload list is invalid, keeping the current one");
+        return false;
+    };
+    info!(
+        "Replacing HSTS preload list ({} entries)",
+        preload_list.0.len()
+    );
+    *PRELOAD_LIST_ENTRIES.write().unwrap() = preload_list;
+    true
 }
 
 impl HstsPreloadList {
@@ -148,7 +163,7 @@ impl HstsPreloadList {
 
 impl HstsList {
     pub fn is_host_secure(&self, host: &str) -> bool {
-        if PRELOAD_LIST_ENTRIES.is_host_secure(host) {
+        if PRELOAD_LIST_ENTRIES.read().unwrap().is_host_secure(host) {
             info!("{host} is in the preload list");
             return true;
         }
@@ -199,6 +214,14 @@ impl HstsList {
         entries.retain(|e| !e.is_expired());
     }
 
+    /// Remove the entries whose max-age has elapsed.
+    pub fn remove_expired(&mut self) {
+        self.entries_map.retain(|_, entries| {
+            entries.retain(|entry| !entry.is_expired());
+            !entries.is_empty()
+        });
+    }
+
     /// Step 2.9 of <https://fetch.spec.whatwg.org/#concept-main-fetch>.
     pub fn apply_hsts_rules(&self, url: &mut ServoUrl) {
         if url.scheme() != "http" && url.scheme() != "ws" {
@@ -5,16 +5,17 @@
 #![deny(missing_docs)]
 
 //! A memory cache implementing the logic specified in <http://tools.ietf.org/html/rfc7234>
-//! and <http://tools.ietf.org/html/rfc7232>.
+//! and <http://tools.ietf.org/html/rfc7232>, optionally persisted to disk.
 
 use std::collections::HashMap;
 use std::ops::Bound;
+use std::path::PathBuf;
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant, SystemTime};
 
 use headers::{
-    CacheControl, ContentRange, Expires, HeaderMapExt, LastModified, Pragma, Range, Vary,
+    CacheControl, ContentRange, Date, Expires, HeaderMapExt, LastModified, Pragma, Range, Vary,
 };
 use http::header::HeaderValue;
 use http::{HeaderMap, Method, StatusCode, header};
@@ -30,6 +31,7 @@ use servo_config::pref;
 use servo_url::ServoUrl;
 use tokio::sync::mpsc::{UnboundedSender as TokioSender, unbounded_channel as unbounded};
 
+use crate::disk_cache::{DiskCache, DiskCacheHit, StoredResponse};
 use crate::fetch::methods::{Data, DoneChannel};
 
 /// The key used to differentiate requests in the cache.
@@ -67,6 +69,9 @@ struct CachedResource {
     url_list: Vec<ServoUrl>,
     expires: Duration,
     last_validated: Instant,
+    /// The name of the file holding the body in the disk cache, if the resource is stored
+    /// on disk.
+    disk_file: Option<String>,
 }
 
 impl MallocSizeOf for CachedResource {
@@ -81,7 +86,82 @@ impl MallocSizeOf for CachedResource {
             self.status.size_of(ops) +
             self.url_list.size_of(ops) +
             self.expires.size_of(ops) +
-            self.last_validated.size_of(ops)
+            self.last_validated.size_of(ops) +
+            self.disk_file.size_of(ops)
+    }
+}
+
+impl CachedResource {
+    /// Create a resource from a response read from the disk cache.
+    fn from_disk(disk_file: String, stored: StoredResponse, body: Vec<u8>) -> CachedResource {
+        // Resources are validated relative to an `Instant`, which can't be stored, so convert
+        // the stored time of the last validation, and if that predates the `Instant` clock,
+        // shorten the freshness lifetime instead.
+        let since_validated = SystemTime::now()
+            .duration_since(stored.last_validated)
+            .unwrap_or_default();
+        let (last_validated, expires) = match Instant::now().checked_sub(since_validated) {
+            Some(last_validated) => (last_validated, stored.expires),
+            None => (
+                Instant::now(),
+                stored.expires.saturating_sub(since_validated),
+            ),
+        };
+        CachedResource {
+            request_headers: Arc::new(Mutex::new(stored.request_headers)),
+            body: Arc::new(Mutex::new(ResponseBody::Done(body))),
+            aborted: Arc::new(AtomicBool::new(false)),
+            awaiting_body: Arc::new(Mutex::new(vec![])),
+            metadata: CachedMetadata {
+                headers: Arc::new(Mutex::new(stored.headers)),
+                final_url: stored.final_url,
+                content_type: stored.content_type,
+                charset: stored.charset,
+                status: stored.status.clone(),
+            },
+            location_url: stored.location_url,
+            https_state: stored.https_state,
+            status: stored.status,
+            url_list: stored.url_list,
+            expires,
+            last_validated,
+            disk_file: Some(disk_file),
+        }
+    }
+
+    /// The metadata to store for this resource in the disk cache.
+    fn to_stored_response(&self, url: &ServoUrl) -> StoredResponse {
+        let headers = self.metadata.headers.lock().unwrap().clone();
+        // Only the request headers named by `Vary` are needed to select the response later,
+        // and the others may hold credentials, so don't write them to disk.
+        let vary = headers.typed_get::<Vary>();
+        let request_headers = self
+            .request_headers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(name, _)| {
+                vary.as_ref().is_some_and(|vary| {
+                    vary.iter_strs()
+                        .any(|vary_name| name.as_str().eq_ignore_ascii_case(vary_name))
+                })
+            })
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        StoredResponse {
+            url: url.clone(),
+            request_headers,
+            headers,
+            status: self.status.clone(),
+            final_url: self.metadata.final_url.clone(),
+            url_list: self.url_list.clone(),
+            location_url: self.location_url.clone(),
+            https_state: self.https_state,
+            content_type: self.metadata.content_type.clone(),
+            charset: self.metadata.charset.clone(),
+            expires: self.expires,
+            last_validated: SystemTime::now() - self.last_validated.elapsed(),
+        }
     }
 }
 
@@ -113,6 +193,9 @@ pub struct CachedResponse {
 pub struct HttpCache {
     /// cached responses.
     entries: HashMap<CacheKey, Vec<CachedResource>>,
+    /// Where complete responses are persisted, if anywhere.
+    #[ignore_malloc_size_of = "Stored on disk"]
+    disk_cache: Option<DiskCache>,
 }
 
 /// Determine if a response is cacheable by default <https://tools.ietf.org/html/rfc7231#section-6.1>
@@ -160,14 +243,21 @@ fn response_is_cacheable(metadata: &Metadata) -> bool {
 /// Calculating Age
 /// <https://tools.ietf.org/html/rfc7234#section-4.2.3>
 fn calculate_response_age(response: &Response) -> Duration {
-    // TODO: follow the spec more closely (Date headers, request/response lag, ...)
-    response
+    // TODO: account for the delay between sending the request and receiving the response.
+    let age_value = response
         .headers
         .get(header::AGE)
         .and_then(|age_header| age_header.to_str().ok())
         .and_then(|age_string| age_string.parse::<u64>().ok())
         .map(Duration::from_secs)
-        .unwrap_or_default()
+        .unwrap_or_default();
+    // The response is only just being stored, so the time it was received is now.
+    let apparent_age = response
+        .headers
+        .typed_get::<Date>()
+        .and_then(|date| SystemTime::now().duration_since(date.into()).ok())
+        .unwrap_or_default();
+    apparent_age.max(age_value)
 }
 
 /// Determine the expiry date from relevant headers,
@@ -271,6 +361,49 @@ fn get_expiry_adjustment_from_request_headers(request: &Request, expires: Durati
 }
 
 /// Create a CachedResponse from a request and a CachedResource.
+/// Whether a stored response with `cached_headers`, stored for a request with
+/// `original_request_headers`, can be used for `request`, given the stored `Vary` header.
+/// Calculating Secondary Keys with Vary <https://tools.ietf.org/html/rfc7234#section-4.1>
+fn vary_matches(
+    cached_headers: &HeaderMap,
+    original_request_headers: &HeaderMap,
+    request: &Request,
+) -> bool {
+    let Some(vary_value) = cached_headers.typed_get::<Vary>() else {
+        return true;
+    };
+    if vary_value.is_any() {
+        debug!("vary value is any, not caching");
+        return false;
+    }
+    // For every header name found in the Vary header of the stored response.
+    for vary_val in vary_value.iter_strs() {
+        match request.headers.get(vary_val) {
+            Some(header_data) => {
+                // If the header is present in the request.
+                if let Some(original_header_data) = original_request_headers.get(vary_val) {
+                    // Check that the value of the nominated header field,
+                    // in the original request, matches the value in the current request.
+                    if original_header_data != header_data {
+                        debug!("headers don't match, not caching");
+                        return false;
+                    }
+                }
+            },
+            None => {
+                // If a header field is absent from a request,
+                // it can only match a stored response if those headers,
+                // were also absent in the original request.
+                if original_request_headers.get(vary_val).is_some() {
+                    debug!("vary header present, not caching");
+                    return false;
+                }
+            },
+        }
+    }
+    true
+}
+
 fn create_cached_response(
     request: &Request,
     cached_resource: &CachedResource,
@@ -306,10 +439,19 @@ fn create_cached_response(
     response.aborted = cached_resource.aborted.clone();
 
     let expires = cached_resource.expires;
-    let adjusted_expires = get_expiry_adjustment_from_request_headers(request, expires);
+    let mut adjusted_expires = get_expiry_adjustment_from_request_headers(request, expires);
     let time_since_validated = Instant::now() - cached_resource.last_validated;
 
-    // TODO: take must-revalidate into account <https://tools.ietf.org/html/rfc7234#section-5.2.2.1>
+    // A stale response must not be used without validation if it has the must-revalidate
+    // directive, even if the request allows stale responses.
+    // <https://tools.ietf.org/html/rfc7234#section-5.2.2.1>
+    let must_revalidate = cached_headers
+        .typed_get::<CacheControl>()
+        .is_some_and(|directives| directives.must_revalidate());
+    if must_revalidate {
+        adjusted_expires = adjusted_expires.min(expires);
+    }
+
     // TODO: if this cache is to be considered shared, take proxy-revalidate into account
     // <https://tools.ietf.org/html/rfc7234#section-5.2.2.7>
     let has_expired = adjusted_expires <= time_since_validated;
@@ -338,6 +480,7 @@ fn create_resource_with_bytes_from_resource(
         url_list: resource.url_list.clone(),
         expires: resource.expires,
         last_validated: resource.last_validated,
+        disk_file: None,
     }
 }
 
@@ -506,6 +649,90 @@ fn handle_range_request(
 }
 
 impl HttpCache {
+    /// Create a cache that persists complete responses in `directory`, storing at most
+    /// `max_size` bytes of response bodies there.
+    pub fn with_disk_cache(directory: PathBuf, max_size: u64) -> HttpCache {
+        HttpCache {
+            entries: HashMap::new(),
+            disk_cache: Some(DiskCache::new(directory, max_size)),
+        }
+    }
+
+    /// Read the resources stored on disk for `request` into memory, so that
+    /// [`Self::construct_response`] can use them. This blocks on disk I/O; fetches use
+    /// [`Self::disk_lookup`] and [`Self::insert_from_disk`] instead.
+    pub fn load_from_disk(&mut self, request: &Request) {
+        let loaded = self
+            .disk_lookup(request)
+            .into_iter()
+            .filter_map(|hit| {
+                let body = hit.read_body()?;
+                Some((hit, body))
+            })
+            .collect();
+        self.insert_from_disk(request, loaded);
+    }
+
+    /// The responses stored on disk that can be used for `request` and are not in memory
+    /// yet. Their bodies are not read, so this does not block on disk I/O.
+    pub(crate) fn disk_lookup(&self, request: &Request) -> Vec<DiskCacheHit> {
+        let Some(disk_cache) = &self.disk_cache else {
+            return vec![];
+        };
+        if request.method != Method::GET {
+            return vec![];
+        }
+        let entry_key = CacheKey::new(request);
+        let in_memory = self.entries.get(&entry_key);
+        disk_cache
+            .lookup(&entry_key.url)
+            .into_iter()
+            .filter(|hit| {
+                !in_memory.is_some_and(|resources| {
+                    resources
+                        .iter()
+                        .any(|resource| resource.disk_file.as_ref() == Some(&hit.body_file))
+                })
+            })
+            .filter(|hit| {
+                vary_matches(
+                    &hit.response.headers,
+                    &hit.response.request_headers,
+                    request,
+                )
+            })
+            .collect()
+    }
+
+    /// Add the responses found with [`Self::disk_lookup`], along with their bodies, to the
+    /// resources in memory for `request`.
+    pub(crate) fn insert_from_disk(
+        &mut self,
+        request: &Request,
+        loaded: Vec<(DiskCacheHit, Vec<u8>)>,
+    ) {
+        let Some(disk_cache) = &self.disk_cache else {
+            return;
+        };
+        if loaded.is_empty() {
+            return;
+        }
+        let resources = self.entries.entry(CacheKey::new(request)).or_default();
+        let mut used = vec![];
+        for (hit, body) in loaded {
+            // Another fetch may have read the same response while the body was being read.
+            if resources
+                .iter()
+                .any(|resource| resource.disk_file.as_ref() == Some(&hit.body_file))
+            {
+                continue;
+            }
+            used.push(hit.body_file.clone());
+            resources.push(CachedResource::from_disk(hit.body_file, hit.response, body));
+        }
+        disk_cache.mark_used(used);
+    }
+
     /// Constructing Responses from Caches.
     /// <https://tools.ietf.org/html/rfc7234#section-4>
     pub fn construct_response(
@@ -528,50 +755,9 @@ impl HttpCache {
             .filter(|r| !r.aborted.load(Ordering::Relaxed));
         let mut candidates = vec![];
         for cached_resource in resources {
-            let mut can_be_constructed = true;
             let cached_headers = cached_resource.metadata.headers.lock().unwrap();
             let original_request_headers = cached_resource.request_headers.lock().unwrap();
-            if let Some(vary_value) = cached_headers.typed_get::<Vary>() {
-                if vary_value.is_any() {
-                    debug!("vary value is any, not caching");
-                    can_be_constructed = false
-                } else {
-                    // For every header name found in the Vary header of the stored response.
-                    // Calculating Secondary Keys with Vary <https://tools.ietf.org/html/rfc7234#section-4.1>
-                    for vary_val in vary_value.iter_strs() {
-                        match request.headers.get(vary_val) {
-                            Some(header_data) => {
-                                // If the header is present in the request.
-                                if let Some(original_header_data) =
-                                    original_request_headers.get(vary_val)
-                                {
-                                    // Check that the value of the nominated header field,
-                                    // in the original request, matches the value in the current request.
-                                    if original_header_data != header_data {
-                                        debug!("headers don't match, not caching");
-                                        can_be_constructed = false;
-                                        break;
-                                    }
-                                }
-                            },
-                            None => {
-                                // If a header field is absent from a request,
-                                // it can only match a stored response if those headers,
-                                // were also absent in the original request.
-                                can_be_constructed =
-                                    original_request_headers.get(vary_val).is_none();
-                                if !can_be_constructed {
-                                    debug!("vary header present, not caching");
-                                }
-                            },
-                        }
-                        if !can_be_constructed {
-                            break;
-                        }
-                    }
-                }
-            }
-            if can_be_constructed {
+            if vary_matches(&cached_headers, &original_request_headers, request) {
                 candidates.push(cached_resource);
             }
         }
@@ -661,6 +847,31 @@ impl HttpCache {
         }
     }
 
+    /// Persist the resources that have finished receiving `response`, if this cache is
+    /// backed by a disk cache.
+    pub fn write_to_disk(&mut self, request: &Request, response: &Response) {
+        let Some(disk_cache) = &self.disk_cache else {
+            return;
+        };
+        let entry_key = CacheKey::new(request);
+        let Some(cached_resources) = self.entries.get_mut(&entry_key) else {
+            return;
+        };
+        let body = &response.actual_response().body;
+        let finished_resources = cached_resources.iter_mut().filter(|resource| {
+            resource.disk_file.is_none() &&
+                Arc::ptr_eq(&resource.body, body) &&
+                !resource.aborted.load(Ordering::Acquire) &&
+                resource.status == StatusCode::OK
+        });
+        for resource in finished_resources {
+            let stored = resource.to_stored_response(&entry_key.url);
+            if let ResponseBody::Done(ref bytes) = *resource.body.lock().unwrap() {
+                resource.disk_file = disk_cache.store(stored, bytes);
+            }
+        }
+    }
+
     /// Freshening Stored Responses upon Validation.
     /// <https://tools.ietf.org/html/rfc7234#section-4.3.4>
     pub fn refresh(
@@ -670,6 +881,8 @@ impl HttpCache {
         done_chan: &mut DoneChannel,
     ) -> Option<Response> {
         assert_eq!(response.status, StatusCode::NOT_MODIFIED);
+        // A stored response is only revalidated once it is in memory, so this doesn't need
+        // to read from disk while the cache is locked.
         let entry_key = CacheKey::new(request);
         if let Some(cached_resources) = self.entries.get_mut(&entry_key) {
             if let Some(cached_resource) = cached_resources.iter_mut().next() {
@@ -711,10 +924,20 @@ impl HttpCache {
                 constructed_response
                     .url_list
                     .clone_from(&cached_resource.url_list);
-                cached_resource.expires = get_response_expiry(&constructed_response);
                 let mut stored_headers = cached_resource.metadata.headers.lock().unwrap();
                 stored_headers.extend(response.headers);
                 constructed_response.headers = stored_headers.clone();
+                drop(stored_headers);
+                cached_resource.expires = get_response_expiry(&constructed_response);
+                cached_resource.last_validated = Instant::now();
+                if let (Some(disk_cache), Some(disk_file)) =
+                    (&self.disk_cache, &cached_resource.disk_file)
+                {
+                    disk_cache.update(
+                        disk_file,
+                        cached_resource.to_stored_response(&entry_key.url),
+                    );
+                }
                 return Some(constructed_response);
             }
         }
@@ -726,8 +949,12 @@ impl HttpCache {
         if let Some(cached_resources) = self.entries.get_mut(&entry_key) {
             for cached_resource in cached_resources.iter_mut() {
                 cached_resource.expires = Duration::ZERO;
+                cached_resource.disk_file = None;
             }
         }
+        if let Some(disk_cache) = &self.disk_cache {
+            disk_cache.remove(url);
+        }
     }
 
     /// Invalidation.
@@ -806,6 +1033,7 @@ impl HttpCache {
             url_list: response.url_list.clone(),
             expires: expiry,
             last_validated: Instant::now(),
+            disk_file: None,
         };
         let entry = self.entries.entry(entry_key).or_default();
         entry.push(entry_resource);
@@ -817,5 +1045,8 @@ impl HttpCache {
     /// Clear the contents of this cache.
     pub fn clear(&mut self) {
         self.entries.clear();
+        if let Some(disk_cache) = &self.disk_cache {
+            disk_cache.clear();
+        }
     }
 }
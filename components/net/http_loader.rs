@@ -28,7 +28,7 @@ use http::header::{
     self, ACCEPT, ACCESS_CONTROL_REQUEST_HEADERS, AUTHORIZATION, CONTENT_ENCODING,
     CONTENT_LANGUAGE, CONTENT_LOCATION, CONTENT_TYPE, HeaderValue, RANGE,
 };
-use http::{HeaderMap, Method, Request as HyperRequest, StatusCode};
+use http::{HeaderMap, Method, Request as HyperRequest, StatusCode, Version};
 use http_body_util::combinators::BoxBody;
 use http_body_util::{BodyExt, Full};
 use hyper::Response as HyperResponse;
@@ -42,12 +42,13 @@ use ipc_channel::router::ROUTER;
 use log::{debug, error, info, log_enabled, warn};
 use malloc_size_of::{MallocSizeOf, MallocSizeOfOps};
 use net_traits::http_status::HttpStatus;
+use net_traits::policy_container::{EmbedderPolicy, EmbedderPolicyValue, RequestPolicyContainer};
 use net_traits::pub_domains::reg_suffix;
 use net_traits::request::Origin::Origin as SpecificOrigin;
 use net_traits::request::{
     BodyChunkRequest, BodyChunkResponse, CacheMode, CredentialsMode, Destination, Initiator,
-    Origin, RedirectMode, Referrer, Request, RequestBuilder, RequestMode, ResponseTainting,
-    ServiceWorkersMode, Window as RequestWindow, get_cors_unsafe_header_names,
+    InternalPriority, Origin, RedirectMode, Referrer, Request, RequestBuilder, RequestMode,
+    ResponseTainting, ServiceWorkersMode, Window as RequestWindow, get_cors_unsafe_header_names,
     is_cors_non_wildcard_request_header_name, is_cors_safelisted_method,
     is_cors_safelisted_request_header,
 };
@@ -66,9 +67,10 @@ use tokio::sync::mpsc::{
 };
 use tokio_stream::wrappers::ReceiverStream;
 
-use crate::async_runtime::spawn_task;
+use crate::async_runtime::{run_blocking, spawn_task};
+use crate::connection_scheduler::ConnectionScheduler;
 use crate::connector::{CertificateErrorOverrideManager, Connector};
-use crate::cookie::ServoCookie;
+use crate::cookie::{CookieSiteContext, ServoCookie};
 use crate::cookie_storage::CookieStorage;
 use crate::decoder::Decoder;
 use crate::fetch::cors_cache::CorsCache;
@@ -77,7 +79,12 @@ use crate::fetch::headers::{SecFetchDest, SecFetchMode, SecFetchSite, SecFetchUs
 use crate::fetch::methods::{Data, DoneChannel, FetchContext, Target, main_fetch};
 use crate::hsts::HstsList;
 use crate::http_cache::{CacheKey, HttpCache};
+use crate::preload::{PreloadedResources, Preloader};
 use crate::resource_thread::{AuthCache, AuthCacheEntry};
+use crate::server_timing::parse_server_timing;
+
+/// The `Priority` request header, <https://www.rfc-editor.org/rfc/rfc9218#name-the-priority-http-header-fi>.
+const PRIORITY: HeaderName = HeaderName::from_static("priority");
 
 /// The various states an entry of the HttpCache can be in.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -103,6 +110,10 @@ pub struct HttpState {
     pub auth_cache: RwLock<AuthCache>,
     pub history_states: RwLock<HashMap<HistoryStateId, Vec<u8>>>,
     pub client: Client<Connector, crate::connector::BoxedBody>,
+    /// Limits the requests in flight to each host, sending the most urgent waiting ones first.
+    pub connection_scheduler: StdArc<ConnectionScheduler>,
+    /// Responses to `Link: rel=preload` headers that are waiting to be used by their document.
+    pub preloaded_resources: PreloadedResources,
     pub override_manager: CertificateErrorOverrideManager,
     pub embedder_proxy: Mutex<EmbedderProxy>,
 }
@@ -186,6 +197,44 @@ fn set_default_accept_encoding(headers: &mut HeaderMap) {
     );
 }
 
+/// Set the `Priority` header of <https://www.rfc-editor.org/rfc/rfc9218>, which tells HTTP/2
+/// and HTTP/3 servers in which order to send the responses of concurrent requests.
+fn set_priority_header(
+    headers: &mut HeaderMap,
+    priority: InternalPriority,
+    destination: Destination,
+) {
+    if headers.contains_key(PRIORITY) {
+        return;
+    }
+
+    // Lower urgencies are more urgent, with 3 being the default.
+    let urgency = match priority {
+        InternalPriority::Highest => 0,
+        InternalPriority::High => 1,
+        InternalPriority::Medium => 2,
+        InternalPriority::Low => 3,
+        InternalPriority::Lowest => 4,
+    };
+    // Resources that can be used before they are complete should be received in parallel
+    // with others of the same urgency, rather than one after the other.
+    let incremental = matches!(
+        destination,
+        Destination::Document |
+            Destination::IFrame |
+            Destination::Frame |
+            Destination::Image |
+            Destination::Audio |
+            Destination::Video
+    );
+    let value = if incremental {
+        format!("u={urgency}, i")
+    } else {
+        format!("u={urgency}")
+    };
+    headers.insert(PRIORITY, HeaderValue::from_str(&value).unwrap());
+}
+
 /// <https://w3c.github.io/webappsec-referrer-policy/#referrer-policy-state-no-referrer-when-downgrade>
 fn no_referrer_when_downgrade(referrer_url: ServoUrl, current_url: ServoUrl) -> Option<ServoUrl> {
     // Step 1
@@ -224,7 +273,7 @@ fn strict_origin_when_cross_origin(
 }
 
 /// <https://html.spec.whatwg.org/multipage/#same-site>
-fn is_same_site(site_a: &ImmutableOrigin, site_b: &ImmutableOrigin) -> bool {
+pub(crate) fn is_same_site(site_a: &ImmutableOrigin, site_b: &ImmutableOrigin) -> bool {
     // First steps are for
     // https://html.spec.whatwg.org/multipage/#concept-site-same-site
     //
@@ -355,10 +404,13 @@ fn set_request_cookies(
     url: &ServoUrl,
     headers: &mut HeaderMap,
     cookie_jar: &RwLock<CookieStorage>,
+    context: CookieSiteContext,
 ) {
     let mut cookie_jar = cookie_jar.write().unwrap();
     cookie_jar.remove_expired_cookies_for_url(url);
-    if let Some(cookie_list) = cookie_jar.cookies_for_url(url, CookieSource::HTTP) {
+    if let Some(cookie_list) =
+        cookie_jar.cookies_for_url_in_context(url, CookieSource::HTTP, context)
+    {
         headers.insert(
             header::COOKIE,
             HeaderValue::from_bytes(cookie_list.as_bytes()).unwrap(),
@@ -366,12 +418,17 @@ fn set_request_cookies(
     }
 }
 
-fn set_cookie_for_url(cookie_jar: &RwLock<CookieStorage>, request: &ServoUrl, cookie_val: &str) {
+fn set_cookie_for_url(
+    cookie_jar: &RwLock<CookieStorage>,
+    request: &ServoUrl,
+    cookie_val: &str,
+    context: &CookieSiteContext,
+) {
     let mut cookie_jar = cookie_jar.write().unwrap();
     let source = CookieSource::HTTP;
 
     if let Some(cookie) = ServoCookie::from_cookie_string(cookie_val.into(), request, source) {
-        cookie_jar.push(cookie, request, source);
+        cookie_jar.push_in_context(cookie, request, source, context);
     }
 }
 
@@ -379,10 +436,11 @@ fn set_cookies_from_headers(
     url: &ServoUrl,
     headers: &HeaderMap,
     cookie_jar: &RwLock<CookieStorage>,
+    context: &CookieSiteContext,
 ) {
     for cookie in headers.get_all(header::SET_COOKIE) {
         if let Ok(cookie_str) = std::str::from_utf8(cookie.as_bytes()) {
-            set_cookie_for_url(cookie_jar, url, cookie_str);
+            set_cookie_for_url(cookie_jar, url, cookie_str, context);
         }
     }
 }
@@ -470,6 +528,10 @@ pub fn send_response_values_to_devtools(
         request.target_webview_id,
     ) {
         let browsing_context_id = webview_id.0;
+        let server_timing = headers
+            .as_ref()
+            .map(parse_server_timing)
+            .unwrap_or_default();
 
         let devtoolsresponse = DevtoolsHttpResponse {
             headers,
@@ -477,6 +539,7 @@ pub fn send_response_values_to_devtools(
             body,
             pipeline_id,
             browsing_context_id,
+            server_timing,
         };
         let net_event_response = NetworkEvent::HttpResponse(devtoolsresponse);
 
@@ -607,14 +670,20 @@ async fn obtain_response(
     pipeline_id: &Option<PipelineId>,
     request_id: Option<&str>,
     destination: Destination,
+    priority: InternalPriority,
     is_xhr: bool,
     context: &FetchContext,
     fetch_terminated: UnboundedSender<bool>,
     browsing_context_id: Option<BrowsingContextId>,
+    preloader: Option<Preloader>,
 ) -> Result<(HyperResponse<Decoder>, Option<ChromeToDevtoolsControlMsg>), NetworkError> {
     {
         let mut headers = request_headers.clone();
 
+        if url.scheme() == "https" {
+            set_priority_header(&mut headers, priority, destination);
+        }
+
         let devtools_bytes = StdArc::new(Mutex::new(vec![]));
 
         // https://url.spec.whatwg.org/#percent-encoded-bytes
@@ -732,11 +801,24 @@ async fn obtain_response(
                 )
         };
 
+        // Wait for our turn to send a request to this host. The permit is held until the
+        // response headers have been received.
+        let _connection_permit = context
+            .state
+            .connection_scheduler
+            .acquire(url.host_str().unwrap_or_default(), priority)
+            .await;
+
         context
             .timing
             .lock()
             .unwrap()
             .set_attribute(ResourceAttribute::DomainLookupStart);
+        context
+            .timing
+            .lock()
+            .unwrap()
+            .set_attribute(ResourceAttribute::DomainLookupEnd);
 
         // TODO(#21261) connect_start: set if a persistent connection is *not* used and the last non-redirected
         // fetch passes the timing allow check
@@ -764,6 +846,15 @@ async fn obtain_response(
         };
         *request.headers_mut() = headers.clone();
 
+        // Start preloading the resources listed by any 103 Early Hints responses.
+        if let Some(preloader) = preloader {
+            hyper::ext::on_informational(&mut request, move |response| {
+                if response.status().as_u16() == 103 {
+                    preloader.preload_from_headers(response.headers());
+                }
+            });
+        }
+
         let connect_end = CrossProcessInstant::now();
         context
             .timing
@@ -781,11 +872,18 @@ async fn obtain_response(
         let override_manager = context.state.override_manager.clone();
         let headers = headers.clone();
         let is_secure_scheme = url.is_secure_scheme();
+        let timing = context.timing.clone();
 
         client
             .request(request)
             .and_then(move |res| {
                 let send_end = CrossProcessInstant::now();
+                timing
+                    .lock()
+                    .unwrap()
+                    .set_attribute(ResourceAttribute::NextHopProtocol(
+                        next_hop_protocol(res.version()).to_owned(),
+                    ));
 
                 // TODO(#21271) response_start: immediately after receiving first byte of response
 
@@ -1277,7 +1375,7 @@ async fn http_network_or_cache_fetch(
     };
 
     // Step 8.3: Let includeCredentials be true if one of:
-    let include_credentials = match http_request.credentials_mode {
+    let mut include_credentials = match http_request.credentials_mode {
         // request’s credentials mode is "include"
         CredentialsMode::Include => true,
         // request’s credentials mode is "same-origin" and request’s response tainting is "basic"
@@ -1291,7 +1389,9 @@ async fn http_network_or_cache_fetch(
 
     // Step 8.4: If Cross-Origin-Embedder-Policy allows credentials with request returns false, then
     // set includeCredentials to false.
-    // TODO(#33616): Requires request's client object
+    if !cross_origin_embedder_policy_allows_credentials(http_request) {
+        include_credentials = false;
+    }
 
     // Step 8.5 Let contentLength be httpRequest’s body’s length, if httpRequest’s body is non-null;
     // otherwise null.
@@ -1437,10 +1537,12 @@ async fn http_network_or_cache_fetch(
         // Substep 1
         // TODO http://mxr.mozilla.org/servo/source/components/net/http_loader.rs#504
         // XXXManishearth http_loader has block_cookies: support content blocking here too
+        let cookie_context = CookieSiteContext::for_request(http_request);
         set_request_cookies(
             &current_url,
             &mut http_request.headers,
             &context.state.cookie_jar,
+            cookie_context,
         );
         // Substep 2
         if !http_request.headers.contains_key(header::AUTHORIZATION) {
@@ -1474,6 +1576,31 @@ async fn http_network_or_cache_fetch(
 
     // TODO(#33616) Step 8.22 If there’s a proxy-authentication entry, use it as appropriate.
 
+    // Read the responses stored on disk for this request into the cache. The bodies are read
+    // on the blocking thread pool, without holding a lock on the cache.
+    let disk_hits = context
+        .state
+        .http_cache
+        .read()
+        .map(|http_cache| http_cache.disk_lookup(http_request))
+        .unwrap_or_default();
+    if !disk_hits.is_empty() {
+        let loaded: Vec<_> = run_blocking(move || {
+            disk_hits
+                .into_iter()
+                .filter_map(|hit| {
+                    let body = hit.read_body()?;
+                    Some((hit, body))
+                })
+                .collect()
+        })
+        .await
+        .unwrap_or_default();
+        if let Ok(mut http_cache) = context.state.http_cache.write() {
+            http_cache.insert_from_disk(http_request, loaded);
+        }
+    }
+
     // If the cache is not ready to construct a response, wait.
     //
     // The cache is not ready if a previous fetch checked the cache, found nothing,
@@ -1632,6 +1759,15 @@ async fn http_network_or_cache_fetch(
 
     wait_for_cached_response(done_chan, &mut response).await;
 
+    // Use the response to a matching `Link: rel=preload` header, if the resource was preloaded.
+    if response.is_none() {
+        response = context
+            .state
+            .preloaded_resources
+            .take_matching(http_request)
+            .await;
+    }
+
     // TODO(#33616): Step 9. If aborted, then return the appropriate network error for fetchParams.
 
     // Step 10. If response is null, then:
@@ -1698,7 +1834,7 @@ async fn http_network_or_cache_fetch(
     // FIXME: The spec doesn't tell us to do this *here*, but if we don't do it then
     // tests fail. Where should we do it instead? See also #33615
     if http_request.response_tainting != ResponseTainting::CorsTainting &&
-        cross_origin_resource_policy_check(http_request, &response) ==
+        cross_origin_resource_policy_check(http_request, &response, include_credentials) ==
             CrossOriginResourcePolicy::Blocked
     {
         return Response::network_error(NetworkError::Internal(
@@ -1840,58 +1976,183 @@ enum CrossOriginResourcePolicy {
     Blocked,
 }
 
-// TODO(#33615): Judging from the name, this appears to be https://fetch.spec.whatwg.org/#cross-origin-resource-policy-check,
-//       but the steps aren't even close to the spec. Perhaps this needs to be rewritten?
+/// <https://fetch.spec.whatwg.org/#cross-origin-embedder-policy-allows-credentials>
+fn cross_origin_embedder_policy_allows_credentials(request: &Request) -> bool {
+    // Step 1. If request’s mode is not "no-cors", then return true.
+    if request.mode != RequestMode::NoCors {
+        return true;
+    }
+
+    // Step 2. If request’s client is null, then return true.
+    // Step 3. If request’s client’s policy container’s embedder policy’s value is not
+    // "credentialless", then return true.
+    let RequestPolicyContainer::PolicyContainer(ref policy_container) = request.policy_container
+    else {
+        return true;
+    };
+    if policy_container.get_embedder_policy().value != EmbedderPolicyValue::Credentialless {
+        return true;
+    }
+
+    // Step 4. If request’s origin is same origin with request’s current URL’s origin and request
+    // does not have a redirect-tainted origin, then return true.
+    if let Origin::Origin(ref origin) = request.origin {
+        if *origin == request.current_url().origin() &&
+            !request_has_redirect_tainted_origin(request)
+        {
+            return true;
+        }
+    }
+
+    // Step 5. Return false.
+    false
+}
+
+/// <https://fetch.spec.whatwg.org/#cross-origin-resource-policy-check>
 fn cross_origin_resource_policy_check(
     request: &Request,
     response: &Response,
+    request_includes_credentials: bool,
 ) -> CrossOriginResourcePolicy {
-    // Step 1
-    if request.mode != RequestMode::NoCors {
+    // This check only applies to no-cors requests and to navigations of nested navigables.
+    let for_navigation = matches!(
+        request.destination,
+        Destination::IFrame | Destination::Frame
+    );
+    if request.mode != RequestMode::NoCors && !for_navigation {
         return CrossOriginResourcePolicy::Allowed;
     }
 
-    // Step 2
-    let current_url_origin = request.current_url().origin();
-    let same_origin = if let Origin::Origin(ref origin) = request.origin {
-        *origin == request.current_url().origin()
-    } else {
-        false
+    // Step 2. Let embedderPolicy be settingsObject’s policy container’s embedder policy.
+    let embedder_policy = match request.policy_container {
+        RequestPolicyContainer::PolicyContainer(ref policy_container) => {
+            policy_container.get_embedder_policy()
+        },
+        RequestPolicyContainer::Client => EmbedderPolicy::default(),
+    };
+
+    let internal_check = |embedder_policy_value| {
+        cross_origin_resource_policy_internal_check(
+            &request.origin,
+            embedder_policy_value,
+            response,
+            request_includes_credentials,
+            for_navigation,
+        )
     };
 
-    if same_origin {
+    // Step 3. If the cross-origin resource policy internal check with origin, "unsafe-none",
+    // response, and forNavigation returns blocked, then return blocked.
+    if internal_check(EmbedderPolicyValue::UnsafeNone) == CrossOriginResourcePolicy::Blocked {
+        return CrossOriginResourcePolicy::Blocked;
+    }
+
+    // Step 4. If the cross-origin resource policy internal check with origin, embedderPolicy’s
+    // report only value, response, and forNavigation returns blocked, then queue a
+    // cross-origin embedder policy CORP violation report with response, settingsObject,
+    // destination, and true.
+    // TODO: Embedder policy violation reports are not supported yet.
+
+    // Step 5. If the cross-origin resource policy internal check with origin, embedderPolicy’s
+    // value, response, and forNavigation returns allowed, then return allowed.
+    if internal_check(embedder_policy.value) == CrossOriginResourcePolicy::Allowed {
         return CrossOriginResourcePolicy::Allowed;
     }
 
-    // Step 3
-    let policy = response
-        .headers
-        .get(HeaderName::from_static("cross-origin-resource-policy"))
-        .map(|h| h.to_str().unwrap_or(""))
-        .unwrap_or("");
+    // Step 6. Queue a cross-origin embedder policy CORP violation report with response,
+    // settingsObject, destination, and false.
+    // TODO: Embedder policy violation reports are not supported yet.
 
-    // Step 4
-    if policy == "same-origin" {
-        return CrossOriginResourcePolicy::Blocked;
+    // Step 7. Return blocked.
+    CrossOriginResourcePolicy::Blocked
+}
+
+/// <https://fetch.spec.whatwg.org/#cross-origin-resource-policy-internal-check>
+fn cross_origin_resource_policy_internal_check(
+    origin: &Origin,
+    embedder_policy_value: EmbedderPolicyValue,
+    response: &Response,
+    request_includes_credentials: bool,
+    for_navigation: bool,
+) -> CrossOriginResourcePolicy {
+    // Step 1. If forNavigation is true and embedderPolicyValue is "unsafe-none", then return allowed.
+    if for_navigation && embedder_policy_value == EmbedderPolicyValue::UnsafeNone {
+        return CrossOriginResourcePolicy::Allowed;
     }
 
-    // Step 5
-    if let Origin::Origin(ref request_origin) = request.origin {
-        let schemeless_same_origin = is_schemelessy_same_site(request_origin, &current_url_origin);
-        if schemeless_same_origin &&
-            (request_origin.scheme() == Some("https") ||
-                response.https_state == HttpsState::None)
-        {
-            return CrossOriginResourcePolicy::Allowed;
+    // Step 2. Let policy be the result of getting `Cross-Origin-Resource-Policy` from
+    // response’s header list.
+    // Step 3. If policy is neither `same-origin`, `same-site`, nor `cross-origin`, then set
+    // policy to null.
+    let mut policy = response
+        .headers
+        .get(HeaderName::from_static("cross-origin-resource-policy"))
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| matches!(*value, "same-origin" | "same-site" | "cross-origin"));
+
+    // Step 4. If policy is null, switch on embedderPolicyValue:
+    if policy.is_none() {
+        match embedder_policy_value {
+            // "unsafe-none": Do nothing.
+            EmbedderPolicyValue::UnsafeNone => {},
+            // "credentialless": Set policy to `same-origin` if:
+            // * response’s request-includes-credentials is true, or
+            // * forNavigation is true.
+            EmbedderPolicyValue::Credentialless => {
+                if request_includes_credentials || for_navigation {
+                    policy = Some("same-origin");
+                }
+            },
+            // "require-corp": Set policy to `same-origin`.
+            EmbedderPolicyValue::RequireCorp => policy = Some("same-origin"),
         }
-    };
+    }
 
-    // Step 6
-    if policy == "same-site" {
-        return CrossOriginResourcePolicy::Blocked;
+    let Some(response_url) = response.actual_response().url() else {
+        return CrossOriginResourcePolicy::Allowed;
+    };
+    let response_origin = response_url.origin();
+
+    // Step 5. Switch on policy:
+    match policy {
+        // `same-origin`: If origin is same origin with response’s URL’s origin, then return
+        // allowed. Otherwise, return blocked.
+        Some("same-origin") => match origin {
+            Origin::Origin(origin) if *origin == response_origin => {
+                CrossOriginResourcePolicy::Allowed
+            },
+            _ => CrossOriginResourcePolicy::Blocked,
+        },
+        // `same-site`: If all of the following are true
+        // * origin is schemelessly same site with response’s URL’s origin
+        // * origin’s scheme is "https" or response’s HTTPS state is "none"
+        // then return allowed. Otherwise, return blocked.
+        Some("same-site") => match origin {
+            Origin::Origin(origin)
+                if is_schemelessy_same_site(origin, &response_origin) &&
+                    (origin.scheme() == Some("https") ||
+                        response.https_state == HttpsState::None) =>
+            {
+                CrossOriginResourcePolicy::Allowed
+            },
+            _ => CrossOriginResourcePolicy::Blocked,
+        },
+        // null or `cross-origin`: Return allowed.
+        _ => CrossOriginResourcePolicy::Allowed,
     }
+}
 
-    CrossOriginResourcePolicy::Allowed
+/// The ALPN protocol ID of the HTTP version of a response.
+/// <https://w3c.github.io/resource-timing/#dom-performanceresourcetiming-nexthopprotocol>
+fn next_hop_protocol(version: Version) -> &'static str {
+    match version {
+        Version::HTTP_09 => "http/0.9",
+        Version::HTTP_10 => "http/1.0",
+        Version::HTTP_11 => "http/1.1",
+        Version::HTTP_2 => "h2",
+        Version::HTTP_3 => "h3",
+        _ => "",
+    }
 }
 
 // Convenience struct that implements Done, for setting responseEnd on function return
@@ -1926,6 +2187,7 @@ async fn http_network_fetch(
 
     // Step 1: Let request be fetchParams’s request.
     let request = &mut fetch_params.request;
+    let cookie_context = CookieSiteContext::for_request(request);
 
     // Step 2
     // TODO be able to create connection using current url's origin and credentials
@@ -1966,6 +2228,14 @@ async fn http_network_fetch(
 
     let browsing_context_id = request.target_webview_id.map(|id| id.0);
 
+    // Requests that did not go through the fetch algorithm, such as CORS preflights, have no
+    // internal priority yet.
+    let priority = request.internal_priority.unwrap_or_else(|| {
+        InternalPriority::for_request(request.priority, request.initiator, request.destination)
+    });
+
+    let preloader = Preloader::new(request, context);
+
     let response_future = obtain_response(
         &context.state.client,
         &url,
@@ -1980,10 +2250,12 @@ async fn http_network_fetch(
         &request.pipeline_id,
         Some(&request_id),
         request.destination,
+        priority,
         is_xhr,
         context,
         fetch_terminated_sender,
         browsing_context_id,
+        preloader.clone(),
     );
 
     // This will only get the headers, the body is read later
@@ -2035,8 +2307,15 @@ async fn http_network_fetch(
         _ => false,
     });
 
-    if !(is_same_origin || req_origin_in_timing_allow || wildcard_present) {
-        context.timing.lock().unwrap().mark_timing_check_failed();
+    {
+        let mut timing = context.timing.lock().unwrap();
+        if !(is_same_origin || req_origin_in_timing_allow || wildcard_present) {
+            timing.mark_timing_check_failed();
+        }
+        // Server timing metrics are only exposed when the timing allow check passed.
+        timing.set_attribute(ResourceAttribute::ServerTiming(parse_server_timing(
+            res.headers(),
+        )));
     }
 
     let timing = context.timing.lock().unwrap().clone();
@@ -2056,6 +2335,10 @@ async fn http_network_fetch(
     response.referrer = request.referrer.to_url().cloned();
     response.referrer_policy = request.referrer_policy;
 
+    if let Some(preloader) = preloader.filter(|_| response.status.in_range(200..=299)) {
+        preloader.preload_from_headers(&response.headers);
+    }
+
     let res_body = response.body.clone();
 
     // We're about to spawn a future to be waited on here
@@ -2090,62 +2373,96 @@ async fn http_network_fetch(
     let headers = response.headers.clone();
     let devtools_chan = context.devtools_chan.clone();
 
-    spawn_task(
-        res.into_body()
-            .map_err(|e| {
-                warn!("Error streaming response body: {:?}", e);
-            })
-            .try_fold(res_body, move |res_body, chunk| {
+    // The body is decoded as it is received, so the size of a body with a content coding
+    // before decoding is only known when the response declares it.
+    let encoded_body_size = response
+        .headers
+        .typed_get::<ContentLength>()
+        .filter(|_| response.headers.contains_key(CONTENT_ENCODING))
+        .map(|content_length| content_length.0);
+
+    let cancellation_listener2 = cancellation_listener.clone();
+    let done_sender4 = done_sender.clone();
+    let res_body3 = res_body.clone();
+    let body_future = res
+        .into_body()
+        .map_err(|e| {
+            warn!("Error streaming response body: {:?}", e);
+        })
+        .try_fold(res_body, move |res_body, chunk| {
+            let cancellation_listener = cancellation_listener.clone();
+            let done_sender = done_sender.clone();
+            async move {
+                // Don't pull more data from the network while the consumer of the
+                // body has asked us to hold off.
+                cancellation_listener.wait_until_resumed().await;
                 if cancellation_listener.cancelled() {
                     *res_body.lock().unwrap() = ResponseBody::Done(vec![]);
                     let _ = done_sender.send(Data::Cancelled);
-                    return future::ready(Err(()));
+                    return Err(());
                 }
                 if let ResponseBody::Receiving(ref mut body) = *res_body.lock().unwrap() {
                     let bytes = chunk;
                     body.extend_from_slice(&bytes);
                     let _ = done_sender.send(Data::Payload(bytes.to_vec()));
                 }
-                future::ready(Ok(res_body))
-            })
-            .and_then(move |res_body| {
-                debug!("successfully finished response for {:?}", url1);
-                let mut body = res_body.lock().unwrap();
-                let completed_body = match *body {
-                    ResponseBody::Receiving(ref mut body) => std::mem::take(body),
-                    _ => vec![],
-                };
-                let devtools_response_body = completed_body.clone();
-                *body = ResponseBody::Done(completed_body);
-                send_response_values_to_devtools(
-                    Some(headers),
-                    status,
-                    Some(devtools_response_body),
-                    &devtools_request,
-                    devtools_chan,
-                );
-                timing_ptr2
-                    .lock()
-                    .unwrap()
-                    .set_attribute(ResourceAttribute::ResponseEnd);
-                let _ = done_sender2.send(Data::Done);
-                future::ready(Ok(()))
-            })
-            .map_err(move |_| {
-                debug!("finished response for {:?}", url2);
-                let mut body = res_body2.lock().unwrap();
-                let completed_body = match *body {
-                    ResponseBody::Receiving(ref mut body) => std::mem::take(body),
-                    _ => vec![],
-                };
-                *body = ResponseBody::Done(completed_body);
-                timing_ptr3
-                    .lock()
-                    .unwrap()
-                    .set_attribute(ResourceAttribute::ResponseEnd);
-                let _ = done_sender3.send(Data::Done);
-            }),
-    );
+                Ok(res_body)
+            }
+        })
+        .and_then(move |res_body| {
+            debug!("successfully finished response for {:?}", url1);
+            let mut body = res_body.lock().unwrap();
+            let completed_body = match *body {
+                ResponseBody::Receiving(ref mut body) => std::mem::take(body),
+                _ => vec![],
+            };
+            let devtools_response_body = completed_body.clone();
+            let decoded_body_size = completed_body.len() as u64;
+            *body = ResponseBody::Done(completed_body);
+            send_response_values_to_devtools(
+                Some(headers),
+                status,
+                Some(devtools_response_body),
+                &devtools_request,
+                devtools_chan,
+            );
+            {
+                let mut timing = timing_ptr2.lock().unwrap();
+                timing.set_attribute(ResourceAttribute::BodySize {
+                    encoded: encoded_body_size.unwrap_or(decoded_body_size),
+                    decoded: decoded_body_size,
+                });
+                timing.set_attribute(ResourceAttribute::ResponseEnd);
+            }
+            let _ = done_sender2.send(Data::Done);
+            future::ready(Ok(()))
+        })
+        .map_err(move |_| {
+            debug!("finished response for {:?}", url2);
+            let mut body = res_body2.lock().unwrap();
+            let completed_body = match *body {
+                ResponseBody::Receiving(ref mut body) => std::mem::take(body),
+                _ => vec![],
+            };
+            *body = ResponseBody::Done(completed_body);
+            timing_ptr3
+                .lock()
+                .unwrap()
+                .set_attribute(ResourceAttribute::ResponseEnd);
+            let _ = done_sender3.send(Data::Done);
+        });
+
+    // Stop waiting for the network as soon as the fetch is cancelled, dropping the body
+    // closes the underlying connection instead of returning it to the pool.
+    spawn_task(async move {
+        tokio::select! {
+            _ = body_future => {},
+            _ = cancellation_listener2.wait_until_cancelled() => {
+                *res_body3.lock().unwrap() = ResponseBody::Done(vec![]);
+                let _ = done_sender4.send(Data::Cancelled);
+            },
+        }
+    });
 
     // TODO these substeps aren't possible yet
     // Substep 1
@@ -2170,7 +2487,12 @@ async fn http_network_fetch(
     // TODO this step isn't possible yet
     // Step 15
     if credentials_flag {
-        set_cookies_from_headers(&url, &response.headers, &context.state.cookie_jar);
+        set_cookies_from_headers(
+            &url,
+            &response.headers,
+            &context.state.cookie_jar,
+            &cookie_context,
+        );
     }
     context
         .state
@@ -2680,10 +3002,7 @@ fn set_the_sec_fetch_user_header(r: &mut Request) {
 fn set_requests_referrer_policy_on_redirect(request: &mut Request, response: &Response) {
     // Step 1: Let policy be the result of executing § 8.1 Parse a referrer policy from a
     // Referrer-Policy header on actualResponse.
-    let referrer_policy: ReferrerPolicy = response
-        .headers
-        .typed_get::<headers::ReferrerPolicy>()
-        .into();
+    let referrer_policy = ReferrerPolicy::parse_header_for_response(Some(&response.headers));
 
     // Step 2: If policy is not the empty string, then set request’s referrer policy to policy.
     if referrer_policy != ReferrerPolicy::EmptyString {
@@ -66,6 +66,10 @@ fn parse_svg_document_in_memory(bytes: &[u8]) -> Result<usvg::Tree, &'static str
             resolve_data: usvg::ImageHrefResolver::default_data_resolver(),
             resolve_string: image_string_href_resolver,
         },
+        // Documents without a `viewBox` whose `width` or `height` is missing or relative don't
+        // specify a size, so the default object size of replaced elements is used instead.
+        // <https://drafts.csswg.org/css-images-3/#default-object-size>
+        default_size: usvg::Size::from_wh(300.0, 150.0).unwrap(),
         ..usvg::Options::default()
     };
 
@@ -78,16 +82,68 @@ fn parse_svg_document_in_memory(bytes: &[u8]) -> Result<usvg::Tree, &'static str
         .map_err(|_| "Not a valid SVG document")
 }
 
+/// Returns the region of an image of the given size that the spatial dimension of a media
+/// fragment, such as `xywh=160,120,320,240` or `xywh=percent:25,25,50,50`, selects. Regions
+/// that extend beyond the image are clipped to it.
+/// <https://www.w3.org/TR/media-frags/#naming-space>
+fn parse_spatial_media_fragment(
+    fragment: &str,
+    image_size: usvg::Size,
+) -> Option<usvg::NonZeroRect> {
+    // If a dimension is given more than once, the last occurrence is used.
+    let value = fragment
+        .split('&')
+        .rev()
+        .find_map(|name_value| name_value.strip_prefix("xywh="))?;
+    let (value, percent) = match value.strip_prefix("percent:") {
+        Some(value) => (value, true),
+        None => (value.strip_prefix("pixel:").unwrap_or(value), false),
+    };
+
+    let components = value
+        .split(',')
+        .map(|component| component.parse::<u32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let &[x, y, width, height] = components.as_slice() else {
+        return None;
+    };
+    let [x, y, width, height] = [x, y, width, height].map(|component| component as f32);
+    let (x, y, width, height) = if percent {
+        let (image_width, image_height) = (image_size.width() / 100., image_size.height() / 100.);
+        (
+            x * image_width,
+            y * image_height,
+            width * image_width,
+            height * image_height,
+        )
+    } else {
+        (x, y, width, height)
+    };
+
+    usvg::NonZeroRect::from_ltrb(
+        x.min(image_size.width()),
+        y.min(image_size.height()),
+        (x + width).min(image_size.width()),
+        (y + height).min(image_size.height()),
+    )
+}
+
 fn decode_bytes_sync(
     key: LoadKey,
     bytes: &[u8],
     cors: CorsStatus,
     content_type: Option<Mime>,
+    fragment: Option<&str>,
 ) -> DecoderMsg {
     let image = if content_type == Some(mime::IMAGE_SVG) {
         parse_svg_document_in_memory(bytes).ok().map(|svg_tree| {
+            let size = svg_tree.size();
+            let view_rect = fragment
+                .and_then(|fragment| parse_spatial_media_fragment(fragment, size))
+                .unwrap_or_else(|| size.to_non_zero_rect(0., 0.));
             DecodedImage::Vector(VectorImageData {
                 svg_tree: Arc::new(svg_tree),
+                view_rect,
                 cors_status: cors,
             })
         })
@@ -271,6 +327,10 @@ impl CompletedLoad {
 struct VectorImageData {
     #[conditional_malloc_size_of]
     svg_tree: Arc<usvg::Tree>,
+    /// The region of the image that is shown, which is the entire image unless the URL
+    /// has a media fragment that selects a part of it.
+    #[ignore_malloc_size_of = "Defined in tiny-skia"]
+    view_rect: usvg::NonZeroRect,
     cors_status: CorsStatus,
 }
 
@@ -614,7 +674,7 @@ impl ImageCacheStore {
             },
             LoadResult::LoadedVectorImage(vector_image) => {
                 self.vector_images.insert(key, vector_image.clone());
-                let natural_dimensions = vector_image.svg_tree.size().to_int_size();
+                let natural_dimensions = vector_image.view_rect.size().to_int_size();
                 let metadata = ImageMetadata {
                     width: natural_dimensions.width(),
                     height: natural_dimensions.height(),
@@ -797,6 +857,7 @@ impl ImageCache for ImageCacheImpl {
                                 pl.bytes.as_slice(),
                                 pl.cors_status,
                                 pl.content_type.clone(),
+                                pl.url.fragment(),
                             ),
                         )
                     },
@@ -905,16 +966,18 @@ impl ImageCache for ImageCacheImpl {
 
         let store = self.store.clone();
         self.thread_pool.spawn(move || {
-            let natural_size = vector_image.svg_tree.size().to_int_size();
+            let view_rect = vector_image.view_rect;
+            let natural_size = view_rect.size().to_int_size();
             let tinyskia_requested_size = {
                 let width = requested_size.width.try_into().unwrap_or(0);
                 let height = requested_size.height.try_into().unwrap_or(0);
                 tiny_skia::IntSize::from_wh(width, height).unwrap_or(natural_size)
             };
-            let transform = tiny_skia::Transform::from_scale(
-                tinyskia_requested_size.width() as f32 / natural_size.width() as f32,
-                tinyskia_requested_size.height() as f32 / natural_size.height() as f32,
-            );
+            let transform = tiny_skia::Transform::from_translate(-view_rect.x(), -view_rect.y())
+                .post_scale(
+                    tinyskia_requested_size.width() as f32 / view_rect.width(),
+                    tinyskia_requested_size.height() as f32 / view_rect.height(),
+                );
             let mut pixmap = tiny_skia::Pixmap::new(
                 tinyskia_requested_size.width(),
                 tinyskia_requested_size.height(),
@@ -1020,7 +1083,7 @@ impl ImageCache for ImageCacheImpl {
                 debug!("Received EOF for {:?}", key);
                 match result {
                     Ok(_) => {
-                        let (bytes, cors_status, content_type) = {
+                        let (bytes, cors_status, content_type, fragment) = {
                             let mut store = self.store.lock().unwrap();
                             let pending_load = store.pending_loads.get_by_key_mut(&id).unwrap();
                             pending_load.result = Some(Ok(()));
@@ -1029,12 +1092,19 @@ impl ImageCache for ImageCacheImpl {
                                 pending_load.bytes.mark_complete(),
                                 pending_load.cors_status,
                                 pending_load.content_type.clone(),
+                                pending_load.url.fragment().map(str::to_owned),
                             )
                         };
 
                         let local_store = self.store.clone();
                         self.thread_pool.spawn(move || {
-                            let msg = decode_bytes_sync(key, &bytes, cors_status, content_type);
+                            let msg = decode_bytes_sync(
+                                key,
+                                &bytes,
+                                cors_status,
+                                content_type,
+                                fragment.as_deref(),
+                            );
                             debug!("Image decoded");
                             local_store.lock().unwrap().handle_decoder(msg);
                         });
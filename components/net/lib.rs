@@ -5,10 +5,13 @@
 #![deny(unsafe_code)]
 
 pub mod async_runtime;
+pub mod connection_scheduler;
 pub mod connector;
 pub mod cookie;
 pub mod cookie_storage;
 mod decoder;
+mod disk_cache;
+pub mod doh;
 pub mod filemanager_thread;
 mod hosts;
 pub mod hsts;
@@ -17,9 +20,12 @@ pub mod http_loader;
 pub mod image_cache;
 pub mod indexeddb;
 pub mod local_directory_listing;
+pub mod preload;
 pub mod protocols;
+pub mod proxy;
 pub mod request_interceptor;
 pub mod resource_thread;
+pub mod server_timing;
 mod storage_thread;
 pub mod subresource_integrity;
 mod websocket_loader;
@@ -30,6 +36,7 @@ pub mod fetch {
     pub mod fetch_params;
     pub mod headers;
     pub mod methods;
+    mod opaque_response_blocking;
 }
 
 /// A module for re-exports of items used in unit tests.
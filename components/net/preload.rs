@@ -0,0 +1,344 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Preloading of resources announced by `Link: rel=preload` headers.
+//!
+//! The headers of a navigation response, and of any [103 Early Hints] responses sent before
+//! it, may list resources that the document will need. These are fetched straight away,
+//! while the document is still being received, and kept in memory until the document
+//! requests a matching resource.
+//!
+//! [103 Early Hints]: https://httpwg.org/specs/rfc8297.html
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use base::id::{PipelineId, WebViewId};
+use content_security_policy as csp;
+use http::header::LINK;
+use http::{HeaderMap, Method};
+use log::debug;
+use net_traits::FetchTaskTarget;
+use net_traits::filemanager_thread::FileTokenCheck;
+use net_traits::request::{
+    CredentialsMode, Destination, Referrer, Request, RequestBuilder, RequestId, RequestMode,
+};
+use net_traits::response::Response;
+use net_traits::{ResourceFetchTiming, ResourceTimingType};
+use servo_arc::Arc as ServoArc;
+use servo_url::ServoUrl;
+use tokio::sync::watch;
+
+use crate::async_runtime::spawn_task;
+use crate::fetch::methods::{FetchContext, fetch};
+
+/// How long a preloaded response is kept for if the document never requests it.
+const UNUSED_PRELOAD_LIFETIME: Duration = Duration::from_secs(60);
+
+/// The responses to preloads that have not been requested by their document yet.
+#[derive(Default)]
+pub struct PreloadedResources {
+    entries: Mutex<Vec<PreloadEntry>>,
+}
+
+struct PreloadEntry {
+    /// The id of the preload's own request, which must not be matched against itself.
+    request_id: RequestId,
+    pipeline_id: PipelineId,
+    url: ServoUrl,
+    destination: Destination,
+    mode: RequestMode,
+    credentials_mode: CredentialsMode,
+    started: Instant,
+    /// Receives the response once the preload has finished.
+    response: watch::Receiver<Option<Response>>,
+}
+
+impl PreloadEntry {
+    fn matches(&self, request: &Request) -> bool {
+        self.request_id != request.id &&
+            Some(self.pipeline_id) == request.pipeline_id &&
+            self.url == request.current_url() &&
+            self.destination == request.destination &&
+            self.mode == request.mode &&
+            self.credentials_mode == request.credentials_mode
+    }
+}
+
+impl PreloadedResources {
+    /// Remove the preload matching `request` and return its response, waiting for the
+    /// preload to finish if it is still in progress. Returns `None` if there is no matching
+    /// preload, or if it failed, in which case the request should go to the network.
+    pub(crate) async fn take_matching(&self, request: &Request) -> Option<Response> {
+        if request.method != Method::GET {
+            return None;
+        }
+
+        let mut receiver = {
+            let mut entries = self.entries.lock().unwrap();
+            let index = entries.iter().position(|entry| entry.matches(request))?;
+            entries.swap_remove(index).response
+        };
+
+        let response = receiver.wait_for(Option::is_some).await.ok()?.clone()?;
+        if response.is_network_error() {
+            return None;
+        }
+        debug!("Using preloaded response for {}", request.current_url());
+        Some(response)
+    }
+
+    /// Add an entry for a new preload, returning `None` if the same resource is already being
+    /// preloaded.
+    fn insert(&self, request: &Request) -> Option<watch::Sender<Option<Response>>> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|entry| entry.started.elapsed() < UNUSED_PRELOAD_LIFETIME);
+        if entries.iter().any(|entry| entry.matches(request)) {
+            return None;
+        }
+
+        let (sender, receiver) = watch::channel(None);
+        entries.push(PreloadEntry {
+            request_id: request.id,
+            pipeline_id: request.pipeline_id?,
+            url: request.current_url(),
+            destination: request.destination,
+            mode: request.mode.clone(),
+            credentials_mode: request.credentials_mode,
+            started: Instant::now(),
+            response: receiver,
+        });
+        Some(sender)
+    }
+}
+
+/// Starts the preloads announced by the responses to a navigation request.
+#[derive(Clone)]
+pub(crate) struct Preloader {
+    /// The context that the contexts of the preloads are created from.
+    context: Arc<FetchContext>,
+    webview_id: Option<WebViewId>,
+    pipeline_id: PipelineId,
+    document_url: ServoUrl,
+}
+
+impl Preloader {
+    /// Create a preloader for the responses to `request`, if it is a navigation request.
+    pub(crate) fn new(request: &Request, context: &FetchContext) -> Option<Preloader> {
+        if !request.is_navigation_request() {
+            return None;
+        }
+        let pipeline_id = request.pipeline_id?;
+        Some(Preloader {
+            context: Arc::new(preload_context(context, ResourceTimingType::None)),
+            webview_id: request.target_webview_id,
+            pipeline_id,
+            document_url: request.current_url(),
+        })
+    }
+
+    /// Start a preload for each `Link: rel=preload` header in `headers`.
+    pub(crate) fn preload_from_headers(&self, headers: &HeaderMap) {
+        let links = headers
+            .get_all(LINK)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(parse_link_header);
+        for link in links {
+            self.preload(link);
+        }
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#process-a-link-header>
+    fn preload(&self, link: Link) {
+        if !link.rel.iter().any(|link_type| link_type == "preload") {
+            return;
+        }
+        let Some(destination) = link.destination() else {
+            return;
+        };
+        let Ok(url) = ServoUrl::parse_with_base(Some(&self.document_url), &link.href) else {
+            return;
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            return;
+        }
+
+        // https://html.spec.whatwg.org/multipage/#create-a-potential-cors-request
+        let (mode, credentials_mode) = match link.crossorigin.as_deref() {
+            None => (RequestMode::NoCors, CredentialsMode::Include),
+            Some(value) if value.eq_ignore_ascii_case("use-credentials") => {
+                (RequestMode::CorsMode, CredentialsMode::Include)
+            },
+            Some(_) => (
+                RequestMode::CorsMode,
+                CredentialsMode::CredentialsSameOrigin,
+            ),
+        };
+        let request = RequestBuilder::new(
+            self.webview_id,
+            url,
+            Referrer::ReferrerUrl(self.document_url.clone()),
+        )
+        .destination(destination)
+        .mode(mode)
+        .credentials_mode(credentials_mode)
+        .origin(self.document_url.origin())
+        .pipeline_id(Some(self.pipeline_id))
+        .build();
+
+        let Some(sender) = self.context.state.preloaded_resources.insert(&request) else {
+            return;
+        };
+        debug!("Preloading {} from a Link header", request.current_url());
+
+        let context = preload_context(&self.context, request.timing_type());
+        spawn_task(async move {
+            let mut target = PreloadTarget { sender };
+            fetch(request, &mut target, &context).await;
+        });
+    }
+}
+
+/// Create a context for a preload, sharing the state of `context` but not its cancellation.
+fn preload_context(context: &FetchContext, timing_type: ResourceTimingType) -> FetchContext {
+    FetchContext {
+        state: context.state.clone(),
+        user_agent: context.user_agent.clone(),
+        devtools_chan: context.devtools_chan.clone(),
+        filemanager: context.filemanager.clone(),
+        file_token: FileTokenCheck::NotRequired,
+        request_interceptor: context.request_interceptor.clone(),
+        cancellation_listener: Default::default(),
+        timing: ServoArc::new(Mutex::new(ResourceFetchTiming::new(timing_type))),
+        protocols: context.protocols.clone(),
+    }
+}
+
+/// Stores the response to a preload once it has been fully received.
+struct PreloadTarget {
+    sender: watch::Sender<Option<Response>>,
+}
+
+impl FetchTaskTarget for PreloadTarget {
+    fn process_request_body(&mut self, _: &Request) {}
+    fn process_request_eof(&mut self, _: &Request) {}
+    fn process_response(&mut self, _: &Request, _: &Response) {}
+    fn process_response_chunk(&mut self, _: &Request, _: Vec<u8>) {}
+    fn process_response_eof(&mut self, _: &Request, response: &Response) {
+        // The matching request will apply its own response filtering.
+        let response = response
+            .internal_response
+            .as_deref()
+            .unwrap_or(response)
+            .clone();
+        let _ = self.sender.send(Some(response));
+    }
+    fn process_csp_violations(&mut self, _: &Request, _: Vec<csp::Violation>) {}
+}
+
+/// A single link from a `Link` header.
+#[derive(Debug, Default, PartialEq)]
+pub struct Link {
+    pub href: String,
+    /// The link types of the `rel` parameter, in ASCII lowercase.
+    pub rel: Vec<String>,
+    pub as_: Option<String>,
+    pub crossorigin: Option<String>,
+}
+
+impl Link {
+    /// <https://html.spec.whatwg.org/multipage/#translate-a-preload-destination>
+    fn destination(&self) -> Option<Destination> {
+        match &*self.as_.as_deref()?.to_ascii_lowercase() {
+            "fetch" => Some(Destination::None),
+            "font" => Some(Destination::Font),
+            "image" => Some(Destination::Image),
+            "script" => Some(Destination::Script),
+            "style" => Some(Destination::Style),
+            "track" => Some(Destination::Track),
+            _ => None,
+        }
+    }
+}
+
+/// Parse the value of a `Link` header, as described in
+/// <https://httpwg.org/specs/rfc8288.html#header>, skipping malformed links.
+pub fn parse_link_header(value: &str) -> Vec<Link> {
+    let mut links = vec![];
+    let mut rest = value;
+    loop {
+        rest = rest.trim_start_matches([' ', '\t', ',']);
+        let Some(after_bracket) = rest.strip_prefix('<') else {
+            break;
+        };
+        let Some(end) = after_bracket.find('>') else {
+            break;
+        };
+        let mut link = Link {
+            href: after_bracket[..end].trim().to_owned(),
+            ..Default::default()
+        };
+        rest = &after_bracket[end + 1..];
+
+        // Parse the `;`-separated parameters, which end at the next `,` outside of quotes.
+        loop {
+            rest = rest.trim_start_matches([' ', '\t']);
+            let Some(after_semicolon) = rest.strip_prefix(';') else {
+                break;
+            };
+            let name_end = after_semicolon
+                .find(['=', ';', ','])
+                .unwrap_or(after_semicolon.len());
+            let name = after_semicolon[..name_end].trim().to_ascii_lowercase();
+            rest = &after_semicolon[name_end..];
+
+            let mut value = String::new();
+            if let Some(after_equals) = rest.strip_prefix('=') {
+                let after_equals = after_equals.trim_start_matches([' ', '\t']);
+                if let Some(quoted) = after_equals.strip_prefix('"') {
+                    let mut chars = quoted.char_indices();
+                    let mut value_end = quoted.len();
+                    while let Some((index, character)) = chars.next() {
+                        match character {
+                            '\\' => {
+                                if let Some((_, escaped)) = chars.next() {
+                                    value.push(escaped);
+                                }
+                            },
+                            '"' => {
+                                value_end = index + 1;
+                                break;
+                            },
+                            _ => value.push(character),
+                        }
+                    }
+                    rest = &quoted[value_end..];
+                } else {
+                    let value_end = after_equals.find([';', ',']).unwrap_or(after_equals.len());
+                    value = after_equals[..value_end].trim().to_owned();
+                    rest = &after_equals[value_end..];
+                }
+            }
+
+            // Only the first occurrence of each parameter is used.
+            match &*name {
+                "rel" if link.rel.is_empty() => {
+                    link.rel = value
+                        .split_ascii_whitespace()
+                        .map(str::to_ascii_lowercase)
+                        .collect();
+                },
+                "as" if link.as_.is_none() => link.as_ = Some(value),
+                "crossorigin" if link.crossorigin.is_none() => link.crossorigin = Some(value),
+                _ => {},
+            }
+        }
+
+        // Skip anything unexpected up to the next link.
+        rest = rest.find(',').map_or("", |next| &rest[next..]);
+        links.push(link);
+    }
+    links
+}
@@ -14,6 +14,7 @@ use std::sync::{Arc, Mutex, RwLock, Weak};
 use std::thread;
 use std::time::Duration;
 
+use base::id::CookieStoreId;
 use cookie::Cookie;
 use crossbeam_channel::Sender;
 use devtools_traits::DevtoolsControlMsg;
@@ -29,9 +30,9 @@ use net_traits::request::{Destination, RequestBuilder, RequestId};
 use net_traits::response::{Response, ResponseInit};
 use net_traits::storage_thread::StorageThreadMsg;
 use net_traits::{
-    AsyncRuntime, CookieSource, CoreResourceMsg, CoreResourceThread, CustomResponseMediator,
-    DiscardFetch, FetchChannels, FetchTaskTarget, ResourceFetchTiming, ResourceThreads,
-    ResourceTimingType, WebSocketDomAction, WebSocketNetworkEvent,
+    AsyncRuntime, CookieData, CookieSource, CoreResourceMsg, CoreResourceThread,
+    CustomResponseMediator, DiscardFetch, FetchChannels, FetchTaskTarget, ResourceFetchTiming,
+    ResourceThreads, ResourceTimingType, WebSocketDomAction, WebSocketNetworkEvent,
 };
 use profile_traits::mem::{
     ProcessReports, ProfilerChan as MemProfilerChan, Report, ReportKind, ReportsChan,
@@ -48,7 +49,7 @@ use crate::async_runtime::{init_async_runtime, spawn_task};
 use crate::connector::{
     CACertificates, CertificateErrorOverrideManager, create_http_client, create_tls_config,
 };
-use crate::cookie::ServoCookie;
+use crate::cookie::{CookieSiteContext, ServoCookie};
 use crate::cookie_storage::CookieStorage;
 use crate::fetch::cors_cache::CorsCache;
 use crate::fetch::fetch_params::FetchParams;
@@ -81,6 +82,7 @@ pub fn new_resource_threads(
     mem_profiler_chan: MemProfilerChan,
     embedder_proxy: EmbedderProxy,
     config_dir: Option<PathBuf>,
+    http_disk_cache_size: Option<u64>,
     certificate_path: Option<String>,
     ignore_certificate_errors: bool,
     protocols: Arc<ProtocolRegistry>,
@@ -105,16 +107,19 @@ pub fn new_resource_threads(
         mem_profiler_chan.clone(),
         embedder_proxy,
         config_dir.clone(),
+        http_disk_cache_size,
         ca_certificates,
         ignore_certificate_errors,
         protocols,
     );
     let idb: IpcSender<IndexedDBThreadMsg> = IndexedDBThreadFactory::new(config_dir.clone());
     let storage: IpcSender<StorageThreadMsg> =
-        StorageThreadFactory::new(config_dir, mem_profiler_chan);
+        StorageThreadFactory::new(config_dir, mem_profiler_chan.clone(), false);
+    let private_storage: IpcSender<StorageThreadMsg> =
+        StorageThreadFactory::new(None, mem_profiler_chan, true);
     (
-        ResourceThreads::new(public_core, storage.clone(), idb.clone()),
-        ResourceThreads::new(private_core, storage, idb),
+        ResourceThreads::new(public_core, storage, idb.clone()),
+        ResourceThreads::new(private_core, private_storage, idb),
         async_runtime,
     )
 }
@@ -127,6 +132,7 @@ pub fn new_core_resource_thread(
     mem_profiler_chan: MemProfilerChan,
     embedder_proxy: EmbedderProxy,
     config_dir: Option<PathBuf>,
+    http_disk_cache_size: Option<u64>,
     ca_certificates: CACertificates,
     ignore_certificate_errors: bool,
     protocols: Arc<ProtocolRegistry>,
@@ -149,6 +155,7 @@ pub fn new_core_resource_thread(
             let mut channel_manager = ResourceChannelManager {
                 resource_manager,
                 config_dir,
+                http_disk_cache_size,
                 ca_certificates,
                 ignore_certificate_errors,
                 cancellation_listeners: Default::default(),
@@ -176,6 +183,7 @@ pub fn new_core_resource_thread(
 struct ResourceChannelManager {
     resource_manager: CoreResourceManager,
     config_dir: Option<PathBuf>,
+    http_disk_cache_size: Option<u64>,
     ca_certificates: CACertificates,
     ignore_certificate_errors: bool,
     cancellation_listeners: HashMap<RequestId, Weak<CancellationListener>>,
@@ -183,17 +191,24 @@ struct ResourceChannelManager {
 
 fn create_http_states(
     config_dir: Option<&Path>,
+    http_disk_cache_size: Option<u64>,
     ca_certificates: CACertificates,
     ignore_certificate_errors: bool,
     embedder_proxy: EmbedderProxy,
 ) -> (Arc<HttpState>, Arc<HttpState>) {
     let mut hsts_list = HstsList::default();
     let mut auth_cache = AuthCache::default();
-    let http_cache = HttpCache::default();
+    let http_cache = match (config_dir, http_disk_cache_size) {
+        (Some(config_dir), Some(max_size)) => {
+            HttpCache::with_disk_cache(config_dir.join("http_cache"), max_size)
+        },
+        _ => HttpCache::default(),
+    };
     let mut cookie_jar = CookieStorage::new(150);
     if let Some(config_dir) = config_dir {
         read_json_from_file(&mut auth_cache, config_dir, "auth_cache.json");
         read_json_from_file(&mut hsts_list, config_dir, "hsts_list.json");
+        hsts_list.remove_expired();
         read_json_from_file(&mut cookie_jar, config_dir, "cookie_jar.json");
     }
 
@@ -210,6 +225,8 @@ fn create_http_states(
             ignore_certificate_errors,
             override_manager.clone(),
         )),
+        connection_scheduler: Default::default(),
+        preloaded_resources: Default::default(),
         override_manager,
         embedder_proxy: Mutex::new(embedder_proxy.clone()),
     };
@@ -227,6 +244,8 @@ fn create_http_states(
             ignore_certificate_errors,
             override_manager.clone(),
         )),
+        connection_scheduler: Default::default(),
+        preloaded_resources: Default::default(),
         override_manager,
         embedder_proxy: Mutex::new(embedder_proxy),
     };
@@ -246,6 +265,7 @@ impl ResourceChannelManager {
     ) {
         let (public_http_state, private_http_state) = create_http_states(
             self.config_dir.as_deref(),
+            self.http_disk_cache_size,
             self.ca_certificates.clone(),
             self.ignore_certificate_errors,
             embedder_proxy,
@@ -382,6 +402,16 @@ impl ResourceChannelManager {
                     cancellation_listener.cancel();
                 }
             },
+            CoreResourceMsg::SuspendResponseBody(request_id) => {
+                if let Some(cancellation_listener) = self.cancellation_listener(request_id) {
+                    cancellation_listener.suspend();
+                }
+            },
+            CoreResourceMsg::ResumeResponseBody(request_id) => {
+                if let Some(cancellation_listener) = self.cancellation_listener(request_id) {
+                    cancellation_listener.resume();
+                }
+            },
             CoreResourceMsg::DeleteCookies(request) => {
                 http_state
                     .cookie_jar
@@ -410,26 +440,67 @@ impl ResourceChannelManager {
                     protocols,
                 )
             },
-            CoreResourceMsg::SetCookieForUrl(request, cookie, source) => self
-                .resource_manager
-                .set_cookie_for_url(&request, cookie.into_inner().to_owned(), source, http_state),
-            CoreResourceMsg::SetCookiesForUrl(request, cookies, source) => {
+            CoreResourceMsg::SetCookieForUrl(request, cookie, source) => {
+                self.resource_manager.set_cookie_for_url(
+                    &request,
+                    cookie.into_inner().to_owned(),
+                    source,
+                    http_state,
+                    &CookieSiteContext::first_party(&request),
+                )
+            },
+            CoreResourceMsg::SetCookiesForUrl(request, cookies, source, ancestor_origins) => {
+                let context = CookieSiteContext::for_document(&request, &ancestor_origins);
                 for cookie in cookies {
                     self.resource_manager.set_cookie_for_url(
                         &request,
                         cookie.into_inner(),
                         source,
                         http_state,
+                        &context,
                     );
                 }
             },
-            CoreResourceMsg::GetCookiesForUrl(url, consumer, source) => {
+            CoreResourceMsg::GetCookiesForUrl(url, consumer, source, ancestor_origins) => {
+                let context = CookieSiteContext::for_document(&url, &ancestor_origins);
                 let mut cookie_jar = http_state.cookie_jar.write().unwrap();
                 cookie_jar.remove_expired_cookies_for_url(&url);
                 consumer
-                    .send(cookie_jar.cookies_for_url(&url, source))
+                    .send(cookie_jar.cookies_for_url_in_context(&url, source, context))
                     .unwrap();
             },
+            CoreResourceMsg::NewCookieListener(id, sender, url, ancestor_origins) => {
+                let context = CookieSiteContext::for_document(&url, &ancestor_origins);
+                http_state
+                    .cookie_jar
+                    .write()
+                    .unwrap()
+                    .add_listener(id, sender, url, context);
+            },
+            CoreResourceMsg::RemoveCookieListener(id) => {
+                http_state.cookie_jar.write().unwrap().remove_listener(id);
+            },
+            CoreResourceMsg::GetCookieDataForUrlAsync(id, url, name) => {
+                let mut cookie_jar = http_state.cookie_jar.write().unwrap();
+                let cookies = cookies_for_listener(&mut cookie_jar, id, &url, name);
+                cookie_jar.send_to_listener(id, CookieData::Get(cookies.into_iter().next()));
+            },
+            CoreResourceMsg::GetAllCookieDataForUrlAsync(id, url, name) => {
+                let mut cookie_jar = http_state.cookie_jar.write().unwrap();
+                let cookies = cookies_for_listener(&mut cookie_jar, id, &url, name);
+                cookie_jar.send_to_listener(id, CookieData::GetAll(cookies));
+            },
+            CoreResourceMsg::SetCookieForUrlAsync(id, url, cookie, source) => {
+                let mut cookie_jar = http_state.cookie_jar.write().unwrap();
+                let Some(context) = cookie_jar.listener_context(id) else {
+                    return true;
+                };
+                let stored = ServoCookie::new_wrapped(cookie.into_inner(), &url, source)
+                    .is_some_and(|cookie| {
+                        cookie_jar.push_in_context(cookie, &url, source, &context)
+                    });
+                cookie_jar.send_to_listener(id, CookieData::Set(stored));
+            },
             CoreResourceMsg::NetworkMediator(mediator_chan, origin) => {
                 self.resource_manager
                     .sw_managers
@@ -463,6 +534,9 @@ impl ResourceChannelManager {
             CoreResourceMsg::ClearCache => {
                 http_state.http_cache.write().unwrap().clear();
             },
+            CoreResourceMsg::UpdateHstsPreloadList(preload_list) => {
+                hsts::replace_hsts_preload_list(preload_list);
+            },
             CoreResourceMsg::ToFileManager(msg) => self.resource_manager.filemanager.handle(msg),
             CoreResourceMsg::Exit(sender) => {
                 if let Some(ref config_dir) = self.config_dir {
@@ -476,8 +550,11 @@ impl ResourceChannelManager {
                         Ok(jar) => write_json_to_file(&*jar, config_dir, "cookie_jar.json"),
                         Err(_) => warn!("Error writing cookie jar to disk"),
                     }
-                    match http_state.hsts_list.read() {
-                        Ok(hsts) => write_json_to_file(&*hsts, config_dir, "hsts_list.json"),
+                    match http_state.hsts_list.write() {
+                        Ok(mut hsts) => {
+                            hsts.remove_expired();
+                            write_json_to_file(&*hsts, config_dir, "hsts_list.json")
+                        },
                         Err(_) => warn!("Error writing hsts list to disk"),
                     }
                 }
@@ -490,6 +567,25 @@ impl ResourceChannelManager {
     }
 }
 
+/// The unexpired cookies visible to `url` in the context of the `CookieStore` identified
+/// by `id`, optionally only those named `name`.
+fn cookies_for_listener(
+    cookie_jar: &mut CookieStorage,
+    id: CookieStoreId,
+    url: &ServoUrl,
+    name: Option<String>,
+) -> Vec<Serde<Cookie<'static>>> {
+    let Some(context) = cookie_jar.listener_context(id) else {
+        return vec![];
+    };
+    cookie_jar.remove_expired_cookies_for_url(url);
+    cookie_jar
+        .cookies_data_for_url_in_context(url, CookieSource::NonHTTP, context)
+        .filter(|cookie| name.as_ref().is_none_or(|name| cookie.name() == name))
+        .map(Serde)
+        .collect()
+}
+
 pub fn read_json_from_file<T>(data: &mut T, config_dir: &Path, filename: &str)
 where
     T: for<'de> Deserialize<'de>,
@@ -735,10 +831,11 @@ impl CoreResourceManager {
         cookie: Cookie<'static>,
         source: CookieSource,
         http_state: &Arc<HttpState>,
+        context: &CookieSiteContext,
     ) {
         if let Some(cookie) = ServoCookie::new_wrapped(cookie, request, source) {
             let mut cookie_jar = http_state.cookie_jar.write().unwrap();
-            cookie_jar.push(cookie, request, source)
+            cookie_jar.push_in_context(cookie, request, source, context);
         }
     }
 
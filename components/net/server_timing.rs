@@ -0,0 +1,114 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Parsing of the [`Server-Timing`] response header, which servers use to report metrics
+//! about the handling of a request, such as the time spent querying a database.
+//!
+//! [`Server-Timing`]: https://w3c.github.io/server-timing/#the-server-timing-header-field
+
+use http::HeaderMap;
+use net_traits::ServerTimingMetric;
+
+/// The `Server-Timing` response header.
+const SERVER_TIMING: &str = "server-timing";
+
+/// Parse the metrics listed in the `Server-Timing` headers of a response, skipping the
+/// ones without a valid name.
+/// <https://w3c.github.io/server-timing/#processing-model>
+pub fn parse_server_timing(headers: &HeaderMap) -> Vec<ServerTimingMetric> {
+    headers
+        .get_all(SERVER_TIMING)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| split_unquoted(value, ','))
+        .filter_map(parse_metric)
+        .collect()
+}
+
+/// Parse a single `server-timing-metric`, where only the first `dur` and `desc`
+/// parameters are taken into account and unknown parameters are ignored.
+fn parse_metric(metric: &str) -> Option<ServerTimingMetric> {
+    let mut parameters = split_unquoted(metric, ';').into_iter();
+    let name = parameters.next()?.trim();
+    if !is_token(name) {
+        return None;
+    }
+
+    let mut duration = None;
+    let mut description = None;
+    for parameter in parameters {
+        let Some((parameter_name, value)) = parameter.split_once('=') else {
+            continue;
+        };
+        let value = unquote(value.trim());
+        match parameter_name.trim().to_ascii_lowercase().as_str() {
+            "dur" if duration.is_none() => {
+                // Durations that are not valid numbers are treated as zero.
+                duration = Some(value.parse().ok().filter(|dur: &f64| dur.is_finite()));
+            },
+            "desc" if description.is_none() => description = Some(value),
+            _ => {},
+        }
+    }
+
+    Some(ServerTimingMetric {
+        name: name.to_owned(),
+        duration: duration.flatten().unwrap_or(0.),
+        description: description.unwrap_or_default(),
+    })
+}
+
+/// Split `value` at each `delimiter` that is not part of a quoted string.
+fn split_unquoted(value: &str, delimiter: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for (index, character) in value.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match character {
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            _ if character == delimiter && !in_quotes => {
+                parts.push(&value[start..index]);
+                start = index + character.len_utf8();
+            },
+            _ => {},
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
+/// The value of a parameter, with the quotes and escapes of a quoted string removed.
+/// <https://httpwg.org/specs/rfc9110.html#quoted.strings>
+fn unquote(value: &str) -> String {
+    let Some(quoted) = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    else {
+        return value.to_owned();
+    };
+    let mut unquoted = String::with_capacity(quoted.len());
+    let mut characters = quoted.chars();
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' => unquoted.extend(characters.next()),
+            _ => unquoted.push(character),
+        }
+    }
+    unquoted
+}
+
+/// Whether `value` is a non-empty `token`.
+/// <https://httpwg.org/specs/rfc9110.html#tokens>
+fn is_token(value: &str) -> bool {
+    !value.is_empty() &&
+        value
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte))
+}
@@ -3,42 +3,55 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 use std::borrow::ToOwned;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 use std::thread;
 
 use base::id::WebViewId;
+use embedder_traits::{AllowOrDeny, PermissionFeature};
 use ipc_channel::ipc::{self, IpcReceiver, IpcSender};
 use malloc_size_of::MallocSizeOf;
-use net_traits::storage_thread::{StorageThreadMsg, StorageType};
+use net_traits::storage_thread::{StorageThreadMsg, StorageType, StoredPasswordCredential};
 use profile_traits::mem::{
     ProcessReports, ProfilerChan as MemProfilerChan, Report, ReportKind, perform_memory_report,
 };
 use profile_traits::path;
-use servo_url::ServoUrl;
+use serde::{Deserialize, Serialize};
+use servo_url::{ImmutableOrigin, ServoUrl};
 
 use crate::resource_thread;
 
 const QUOTA_SIZE_LIMIT: usize = 5 * 1024 * 1024;
 
 pub trait StorageThreadFactory {
-    fn new(config_dir: Option<PathBuf>, mem_profiler_chan: MemProfilerChan) -> Self;
+    fn new(
+        config_dir: Option<PathBuf>,
+        mem_profiler_chan: MemProfilerChan,
+        is_private: bool,
+    ) -> Self;
 }
 
 impl StorageThreadFactory for IpcSender<StorageThreadMsg> {
-    /// Create a storage thread
+    /// Create a storage thread. The storage thread of private webviews keeps everything
+    /// in memory, so that nothing stored or decided in them outlives the private session.
     fn new(
         config_dir: Option<PathBuf>,
         mem_profiler_chan: MemProfilerChan,
+        is_private: bool,
     ) -> IpcSender<StorageThreadMsg> {
         let (chan, port) = ipc::channel().unwrap();
         let chan2 = chan.clone();
+        let (config_dir, reporter_name) = if is_private {
+            (None, "private-storage-reporter")
+        } else {
+            (config_dir, "storage-reporter")
+        };
         thread::Builder::new()
             .name("StorageManager".to_owned())
             .spawn(move || {
                 mem_profiler_chan.run_with_memory_reporting(
                     || StorageManager::new(port, config_dir).start(),
-                    String::from("storage-reporter"),
+                    String::from(reporter_name),
                     chan2,
                     StorageThreadMsg::CollectMemoryReport,
                 );
@@ -50,25 +63,52 @@ impl StorageThreadFactory for IpcSender<StorageThreadMsg> {
 
 type OriginEntry = (usize, BTreeMap<String, String>);
 
+/// <https://w3c.github.io/webappsec-credential-management/#credential-store>
+#[derive(Default, Deserialize, Serialize)]
+struct CredentialStore {
+    /// The password credentials saved for each origin. These are secrets, so they are only
+    /// kept in memory, and never written to disk.
+    #[serde(skip)]
+    passwords: HashMap<String, Vec<StoredPasswordCredential>>,
+    /// The origins whose
+    /// [prevent silent access flag](https://w3c.github.io/webappsec-credential-management/#origin-prevent-silent-access-flag)
+    /// is unset. The flag starts out set, so that credentials are only handed out silently
+    /// once the user has picked one for the origin.
+    silent_access_allowed: HashSet<String>,
+}
+
 struct StorageManager {
     port: IpcReceiver<StorageThreadMsg>,
     session_data: HashMap<WebViewId, HashMap<String, OriginEntry>>,
     local_data: HashMap<String, OriginEntry>,
+    /// The permission decisions made for each origin.
+    permissions: HashMap<String, HashMap<PermissionFeature, AllowOrDeny>>,
+    credentials: CredentialStore,
     config_dir: Option<PathBuf>,
 }
 
 impl StorageManager {
     fn new(port: IpcReceiver<StorageThreadMsg>, config_dir: Option<PathBuf>) -> StorageManager {
         let mut local_data = HashMap::new();
+        let mut permissions = HashMap::new();
+        let mut credentials = CredentialStore::default();
         if let Some(ref config_dir) = config_dir {
             resource_thread::read_json_from_file(&mut local_data, config_dir, "local_data.json");
+            resource_thread::read_json_from_file(&mut permissions, config_dir, "permissions.json");
+            resource_thread::read_json_from_file(&mut credentials, config_dir, "credentials.json");
         }
-        StorageManager {
+        let storage_manager = StorageManager {
             port,
             session_data: HashMap::new(),
             local_data,
+            permissions,
+            credentials,
             config_dir,
-        }
+        };
+        // Rewrite the credential store, dropping any passwords written to it by older
+        // versions.
+        storage_manager.save_credentials();
+        storage_manager
     }
 }
 
@@ -100,6 +140,36 @@ impl StorageManager {
                     self.clear(sender, storage_type, webview_id, url);
                     self.save_state()
                 },
+                StorageThreadMsg::GetPermission(sender, origin, feature) => {
+                    self.get_permission(sender, origin, feature)
+                },
+                StorageThreadMsg::SetPermission(origin, feature, decision) => {
+                    self.set_permission(origin, feature, decision);
+                    self.save_permissions()
+                },
+                StorageThreadMsg::GetPasswordCredentials(sender, origin) => {
+                    self.get_password_credentials(sender, origin)
+                },
+                StorageThreadMsg::StorePasswordCredential(origin, credential) => {
+                    self.store_password_credential(origin, credential)
+                },
+                StorageThreadMsg::GetPreventSilentAccess(sender, origin) => {
+                    let _ = sender.send(
+                        !self
+                            .credentials
+                            .silent_access_allowed
+                            .contains(&origin.ascii_serialization()),
+                    );
+                },
+                StorageThreadMsg::SetPreventSilentAccess(origin, prevent_silent_access) => {
+                    let origin = origin.ascii_serialization();
+                    if prevent_silent_access {
+                        self.credentials.silent_access_allowed.remove(&origin);
+                    } else {
+                        self.credentials.silent_access_allowed.insert(origin);
+                    }
+                    self.save_credentials()
+                },
                 StorageThreadMsg::Clone {
                     sender,
                     src: src_webview_id,
@@ -145,6 +215,90 @@ impl StorageManager {
         }
     }
 
+    fn save_permissions(&self) {
+        if let Some(ref config_dir) = self.config_dir {
+            resource_thread::write_json_to_file(&self.permissions, config_dir, "permissions.json");
+        }
+    }
+
+    fn save_credentials(&self) {
+        if let Some(ref config_dir) = self.config_dir {
+            resource_thread::write_json_to_file(&self.credentials, config_dir, "credentials.json");
+        }
+    }
+
+    fn get_password_credentials(
+        &self,
+        sender: IpcSender<Vec<StoredPasswordCredential>>,
+        origin: ImmutableOrigin,
+    ) {
+        let credentials = self
+            .credentials
+            .passwords
+            .get(&origin.ascii_serialization())
+            .cloned()
+            .unwrap_or_default();
+        let _ = sender.send(credentials);
+    }
+
+    fn store_password_credential(
+        &mut self,
+        origin: ImmutableOrigin,
+        credential: StoredPasswordCredential,
+    ) {
+        let credentials = self
+            .credentials
+            .passwords
+            .entry(origin.ascii_serialization())
+            .or_default();
+        match credentials
+            .iter_mut()
+            .find(|existing| existing.id == credential.id)
+        {
+            Some(existing) => *existing = credential,
+            None => credentials.push(credential),
+        }
+    }
+
+    fn get_permission(
+        &self,
+        sender: IpcSender<Option<AllowOrDeny>>,
+        origin: ImmutableOrigin,
+        feature: PermissionFeature,
+    ) {
+        let decision = self
+            .permissions
+            .get(&origin.ascii_serialization())
+            .and_then(|entry| entry.get(&feature))
+            .copied();
+        let _ = sender.send(decision);
+    }
+
+    fn set_permission(
+        &mut self,
+        origin: ImmutableOrigin,
+        feature: PermissionFeature,
+        decision: Option<AllowOrDeny>,
+    ) {
+        let origin = origin.ascii_serialization();
+        match decision {
+            Some(decision) => {
+                self.permissions
+                    .entry(origin)
+                    .or_default()
+                    .insert(feature, decision);
+            },
+            None => {
+                if let Some(entry) = self.permissions.get_mut(&origin) {
+                    entry.remove(&feature);
+                    if entry.is_empty() {
+                        self.permissions.remove(&origin);
+                    }
+                }
+            },
+        }
+    }
+
     fn select_data(
         &self,
         storage_type: StorageType,
@@ -0,0 +1,65 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::sync::Arc;
+
+use futures::FutureExt;
+use net::connection_scheduler::ConnectionScheduler;
+use net_traits::request::InternalPriority;
+
+#[test]
+fn test_waiting_requests_are_scheduled_by_priority() {
+    let scheduler = Arc::new(ConnectionScheduler::default());
+    let mut permits: Vec<_> = (0..6)
+        .map(|_| {
+            scheduler
+                .acquire("example.com", InternalPriority::Medium)
+                .now_or_never()
+                .expect("Requests below the per-host limit should not wait")
+        })
+        .collect();
+
+    // Other hosts have their own limit.
+    assert!(
+        scheduler
+            .acquire("example.org", InternalPriority::Lowest)
+            .now_or_never()
+            .is_some()
+    );
+
+    let mut low = Box::pin(scheduler.acquire("example.com", InternalPriority::Low));
+    let mut high = Box::pin(scheduler.acquire("example.com", InternalPriority::High));
+    assert!(low.as_mut().now_or_never().is_none());
+    assert!(high.as_mut().now_or_never().is_none());
+
+    // The more urgent request is sent first, even though it was made last.
+    permits.pop();
+    assert!(high.as_mut().now_or_never().is_some());
+    assert!(low.as_mut().now_or_never().is_none());
+
+    permits.pop();
+    assert!(low.as_mut().now_or_never().is_some());
+}
+
+#[test]
+fn test_cancelled_requests_do_not_leak_slots() {
+    let scheduler = Arc::new(ConnectionScheduler::default());
+    let mut permits: Vec<_> = (0..6)
+        .map(|_| {
+            scheduler
+                .acquire("example.com", InternalPriority::Medium)
+                .now_or_never()
+                .unwrap()
+        })
+        .collect();
+
+    let mut cancelled = Box::pin(scheduler.acquire("example.com", InternalPriority::High));
+    let mut waiting = Box::pin(scheduler.acquire("example.com", InternalPriority::Low));
+    assert!(cancelled.as_mut().now_or_never().is_none());
+    assert!(waiting.as_mut().now_or_never().is_none());
+    drop(cancelled);
+
+    permits.pop();
+    assert!(waiting.as_mut().now_or_never().is_some());
+}
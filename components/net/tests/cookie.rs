@@ -4,9 +4,11 @@
 
 use std::time::{Duration, SystemTime};
 
-use net::cookie::ServoCookie;
+use base::id::{CookieStoreId, PipelineNamespace, TEST_NAMESPACE};
+use ipc_channel::ipc;
+use net::cookie::{CookieSiteContext, ServoCookie};
 use net::cookie_storage::CookieStorage;
-use net_traits::CookieSource;
+use net_traits::{CookieData, CookieSource};
 use servo_url::ServoUrl;
 use time::macros::datetime;
 
@@ -508,3 +510,151 @@ fn test_parse_date() {
         Some(datetime!(2024-06-26 15:35:10).assume_utc())
     );
 }
+
+fn cross_site_context(top_level_site: &str) -> CookieSiteContext {
+    CookieSiteContext {
+        partition_key: Some(top_level_site.to_owned()),
+        same_site: false,
+        top_level_navigation: false,
+        safe_method: true,
+    }
+}
+
+fn add_cookie_in_context(
+    storage: &mut CookieStorage,
+    url: &ServoUrl,
+    cookie_str: &str,
+    context: &CookieSiteContext,
+) {
+    let source = CookieSource::HTTP;
+    let cookie = ServoCookie::from_cookie_string(cookie_str.to_owned(), url, source).unwrap();
+    storage.push_in_context(cookie, url, source, context);
+}
+
+#[test]
+fn test_same_site_none_and_partitioned_require_secure() {
+    let url = &ServoUrl::parse("https://example.com/").unwrap();
+    let source = CookieSource::HTTP;
+    assert!(
+        ServoCookie::from_cookie_string("foo=bar; SameSite=None".into(), url, source).is_none()
+    );
+    assert!(
+        ServoCookie::from_cookie_string("foo=bar; SameSite=None; Secure".into(), url, source)
+            .is_some()
+    );
+    assert!(ServoCookie::from_cookie_string("foo=bar; Partitioned".into(), url, source).is_none());
+    assert!(
+        ServoCookie::from_cookie_string("foo=bar; Partitioned; Secure".into(), url, source)
+            .is_some()
+    );
+}
+
+#[test]
+fn test_same_site_cookies_in_cross_site_context() {
+    let mut storage = CookieStorage::new(5);
+    let url = ServoUrl::parse("https://example.com/").unwrap();
+    let source = CookieSource::HTTP;
+    add_cookie_to_storage(&mut storage, &url, "strict=1; SameSite=Strict");
+    add_cookie_to_storage(&mut storage, &url, "lax=1; SameSite=Lax");
+    add_cookie_to_storage(&mut storage, &url, "none=1; SameSite=None; Secure");
+
+    let cross_site = cross_site_context("https://other.org");
+    assert_eq!(
+        storage.cookies_for_url_in_context(&url, source, cross_site.clone()),
+        Some("none=1".to_owned())
+    );
+
+    let cross_site_navigation = CookieSiteContext {
+        partition_key: Some("https://example.com".to_owned()),
+        same_site: false,
+        top_level_navigation: true,
+        safe_method: true,
+    };
+    assert_eq!(
+        storage.cookies_for_url_in_context(&url, source, cross_site_navigation),
+        Some("lax=1; none=1".to_owned())
+    );
+
+    // SameSite cookies received in a cross-site context are ignored.
+    add_cookie_in_context(&mut storage, &url, "strict=2; SameSite=Strict", &cross_site);
+    assert_eq!(
+        storage.cookies_for_url(&url, source),
+        Some("strict=1; lax=1; none=1".to_owned())
+    );
+}
+
+#[test]
+fn test_partitioned_cookies_are_keyed_on_top_level_site() {
+    let mut storage = CookieStorage::new(5);
+    let url = ServoUrl::parse("https://embedded.com/").unwrap();
+    let source = CookieSource::HTTP;
+    let first_top_level = cross_site_context("https://first.org");
+    let second_top_level = cross_site_context("https://second.org");
+    add_cookie_in_context(
+        &mut storage,
+        &url,
+        "foo=first; Partitioned; Secure; SameSite=None",
+        &first_top_level,
+    );
+    add_cookie_in_context(
+        &mut storage,
+        &url,
+        "foo=second; Partitioned; Secure; SameSite=None",
+        &second_top_level,
+    );
+
+    assert_eq!(
+        storage.cookies_for_url_in_context(&url, source, first_top_level),
+        Some("foo=first".to_owned())
+    );
+    assert_eq!(
+        storage.cookies_for_url_in_context(&url, source, second_top_level),
+        Some("foo=second".to_owned())
+    );
+    assert_eq!(storage.cookies_for_url(&url, source), None);
+}
+
+#[test]
+fn test_cookie_listeners_are_notified_of_visible_changes() {
+    PipelineNamespace::install(TEST_NAMESPACE);
+    let mut storage = CookieStorage::new(5);
+    let url = ServoUrl::parse("https://example.com/foo").unwrap();
+    let (sender, receiver) = ipc::channel().unwrap();
+    let id = CookieStoreId::new();
+    storage.add_listener(
+        id,
+        sender,
+        url.clone(),
+        CookieSiteContext::first_party(&url),
+    );
+
+    let next_change = || match receiver.try_recv().map(|response| response.data) {
+        Ok(CookieData::Change(change)) => Some(change),
+        _ => None,
+    };
+
+    add_cookie_to_storage(&mut storage, &url, "foo=bar");
+    let change = next_change().expect("Setting a cookie should be observed");
+    assert_eq!(change.changed.len(), 1);
+    assert_eq!(change.changed[0].value(), "bar");
+    assert!(change.deleted.is_empty());
+
+    // HttpOnly cookies and cookies for other paths are not visible to the listener.
+    add_cookie_to_storage(&mut storage, &url, "secret=1; HttpOnly");
+    add_cookie_to_storage(&mut storage, &url, "other=1; Path=/bar");
+    assert!(next_change().is_none());
+
+    // Expiring a cookie is observed as a deletion.
+    add_cookie_to_storage(
+        &mut storage,
+        &url,
+        "foo=; Expires=Thu, 01 Jan 1970 00:00:00 GMT",
+    );
+    let change = next_change().expect("Deleting a cookie should be observed");
+    assert!(change.changed.is_empty());
+    assert_eq!(change.deleted[0].name(), "foo");
+
+    storage.remove_listener(id);
+    add_cookie_to_storage(&mut storage, &url, "foo=baz");
+    assert!(next_change().is_none());
+}
@@ -0,0 +1,56 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::Duration;
+
+use net::doh::{RECORD_TYPE_A, RECORD_TYPE_AAAA, encode_dns_query, parse_dns_response};
+
+#[test]
+fn test_encode_dns_query() {
+    assert_eq!(
+        encode_dns_query("example.com.", RECORD_TYPE_AAAA).unwrap(),
+        [
+            &[0, 0, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0][..],
+            b"\x07example\x03com\x00",
+            &[0, 28, 0, 1],
+        ]
+        .concat()
+    );
+    assert!(encode_dns_query("a..b", RECORD_TYPE_A).is_none());
+    assert!(encode_dns_query(&"a".repeat(64), RECORD_TYPE_A).is_none());
+}
+
+#[test]
+fn test_parse_dns_response() {
+    let question = encode_dns_query("www.example.com", RECORD_TYPE_A).unwrap();
+    let mut response = question.clone();
+    // Mark the message as a response with two answers.
+    response[2..4].copy_from_slice(&[0x81, 0x80]);
+    response[6..8].copy_from_slice(&[0, 2]);
+    // A CNAME from the question name to example.com, compressed with pointers.
+    response.extend_from_slice(&[0xC0, 12, 0, 5, 0, 1, 0, 0, 1, 0, 0, 2, 0xC0, 16]);
+    // An address of example.com with a time to live of 60 seconds.
+    response.extend_from_slice(&[0xC0, 16, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 93, 184, 216, 34]);
+
+    assert_eq!(
+        parse_dns_response(&response, RECORD_TYPE_A).unwrap(),
+        (
+            vec![IpAddr::from(Ipv4Addr::new(93, 184, 216, 34))],
+            Duration::from_secs(60)
+        )
+    );
+}
+
+#[test]
+fn test_parse_dns_response_errors() {
+    let mut response = encode_dns_query("example.com", RECORD_TYPE_A).unwrap();
+    // NXDOMAIN.
+    response[2..4].copy_from_slice(&[0x81, 0x83]);
+    assert!(parse_dns_response(&response, RECORD_TYPE_A).is_err());
+
+    response[2..4].copy_from_slice(&[0x81, 0x80]);
+    response[6..8].copy_from_slice(&[0, 1]);
+    assert!(parse_dns_response(&response, RECORD_TYPE_A).is_err());
+}
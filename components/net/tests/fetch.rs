@@ -36,13 +36,14 @@ use net::request_interceptor::RequestInterceptor;
 use net::resource_thread::CoreResourceThreadPool;
 use net_traits::filemanager_thread::FileTokenCheck;
 use net_traits::http_status::HttpStatus;
+use net_traits::policy_container::{EmbedderPolicy, EmbedderPolicyValue, PolicyContainer};
 use net_traits::request::{
     Destination, RedirectMode, Referrer, Request, RequestBuilder, RequestMode,
 };
 use net_traits::response::{CacheState, Response, ResponseBody, ResponseType};
 use net_traits::{
     FetchTaskTarget, IncludeSubdomains, NetworkError, ReferrerPolicy, ResourceFetchTiming,
-    ResourceTimingType,
+    ResourceTimingType, ServerTimingMetric,
 };
 use servo_arc::Arc as ServoArc;
 use servo_url::ServoUrl;
@@ -120,6 +121,62 @@ fn test_fetch_response_body_matches_const_message() {
     };
 }
 
+#[test]
+fn test_fetch_response_timing_includes_body_sizes_and_next_hop_protocol() {
+    static MESSAGE: &'static [u8] = b"Hello World!";
+    let handler =
+        move |_: HyperRequest<Incoming>,
+              response: &mut HyperResponse<BoxBody<Bytes, hyper::Error>>| {
+            *response.body_mut() = make_body(MESSAGE.to_vec());
+        };
+    let (server, url) = make_server(handler);
+
+    let request = RequestBuilder::new(Some(TEST_WEBVIEW_ID), url.clone(), Referrer::NoReferrer)
+        .origin(url.origin())
+        .build();
+    let fetch_response = fetch(request, None);
+    let _ = server.close();
+
+    assert!(!fetch_response.is_network_error());
+    let timing = fetch_response.get_resource_timing().lock().unwrap().clone();
+    assert_eq!(timing.next_hop_protocol, "http/1.1");
+    assert_eq!(timing.encoded_body_size, MESSAGE.len() as u64);
+    assert_eq!(timing.decoded_body_size, MESSAGE.len() as u64);
+    assert_eq!(timing.transfer_size, MESSAGE.len() as u64 + 300);
+    assert!(timing.domain_lookup_end >= timing.domain_lookup_start);
+}
+
+#[test]
+fn test_fetch_response_timing_includes_server_timing() {
+    let handler =
+        move |_: HyperRequest<Incoming>,
+              response: &mut HyperResponse<BoxBody<Bytes, hyper::Error>>| {
+            response.headers_mut().insert(
+                HeaderName::from_static("server-timing"),
+                HeaderValue::from_static("db;dur=12.5;desc=\"Query\""),
+            );
+            *response.body_mut() = make_body(b"Yay!".to_vec());
+        };
+    let (server, url) = make_server(handler);
+
+    let request = RequestBuilder::new(Some(TEST_WEBVIEW_ID), url.clone(), Referrer::NoReferrer)
+        .origin(url.origin())
+        .build();
+    let fetch_response = fetch(request, None);
+    let _ = server.close();
+
+    assert!(!fetch_response.is_network_error());
+    let timing = fetch_response.get_resource_timing().lock().unwrap().clone();
+    assert_eq!(
+        timing.server_timing,
+        vec![ServerTimingMetric {
+            name: "db".to_owned(),
+            duration: 12.5,
+            description: "Query".to_owned(),
+        }]
+    );
+}
+
 #[test]
 fn test_fetch_aboutblank() {
     let url = ServoUrl::parse("about:blank").unwrap();
@@ -624,6 +681,111 @@ fn test_fetch_response_is_opaque_filtered() {
     }
 }
 
+fn cross_origin_no_cors_fetch_with_embedder_policy(
+    embedder_policy_value: EmbedderPolicyValue,
+    resource_policy: Option<&'static str>,
+) -> Response {
+    let handler =
+        move |_: HyperRequest<Incoming>,
+              response: &mut HyperResponse<BoxBody<Bytes, hyper::Error>>| {
+            if let Some(resource_policy) = resource_policy {
+                response.headers_mut().insert(
+                    HeaderName::from_static("cross-origin-resource-policy"),
+                    HeaderValue::from_static(resource_policy),
+                );
+            }
+            *response.body_mut() = make_body(b"Yay!".to_vec());
+        };
+    let (server, url) = make_server(handler);
+
+    let mut policy_container = PolicyContainer::default();
+    policy_container.set_embedder_policy(EmbedderPolicy {
+        value: embedder_policy_value,
+        report_only_value: EmbedderPolicyValue::UnsafeNone,
+    });
+    let request = RequestBuilder::new(Some(TEST_WEBVIEW_ID), url, Referrer::NoReferrer)
+        .origin(ServoUrl::parse("http://example.com").unwrap().origin())
+        .mode(RequestMode::NoCors)
+        .policy_container(policy_container)
+        .build();
+    let fetch_response = fetch(request, None);
+    let _ = server.close();
+    fetch_response
+}
+
+#[test]
+fn test_fetch_require_corp_blocks_cross_origin_response_without_resource_policy() {
+    let fetch_response =
+        cross_origin_no_cors_fetch_with_embedder_policy(EmbedderPolicyValue::RequireCorp, None);
+    assert!(fetch_response.is_network_error());
+
+    let fetch_response =
+        cross_origin_no_cors_fetch_with_embedder_policy(EmbedderPolicyValue::UnsafeNone, None);
+    assert!(!fetch_response.is_network_error());
+}
+
+#[test]
+fn test_fetch_require_corp_allows_cross_origin_resource_policy() {
+    let fetch_response = cross_origin_no_cors_fetch_with_embedder_policy(
+        EmbedderPolicyValue::RequireCorp,
+        Some("cross-origin"),
+    );
+    assert!(!fetch_response.is_network_error());
+    assert_eq!(fetch_response.response_type, ResponseType::Opaque);
+}
+
+fn cross_origin_no_cors_fetch(content_type: Option<Mime>, body: &'static [u8]) -> Response {
+    let handler =
+        move |_: HyperRequest<Incoming>,
+              response: &mut HyperResponse<BoxBody<Bytes, hyper::Error>>| {
+            if let Some(ref content_type) = content_type {
+                response
+                    .headers_mut()
+                    .typed_insert(ContentType::from(content_type.clone()));
+            }
+            *response.body_mut() = make_body(body.to_vec());
+        };
+    let (server, url) = make_server(handler);
+
+    let request = RequestBuilder::new(Some(TEST_WEBVIEW_ID), url, Referrer::NoReferrer)
+        .origin(ServoUrl::parse("http://example.com").unwrap().origin())
+        .mode(RequestMode::NoCors)
+        .build();
+    let fetch_response = fetch(request, None);
+    let _ = server.close();
+    fetch_response
+}
+
+#[test]
+fn test_opaque_response_blocking_blocks_json_and_html() {
+    let fetch_response =
+        cross_origin_no_cors_fetch(Some(mime::APPLICATION_JSON), b"{\"secret\": \"value\"}");
+    assert!(fetch_response.is_network_error());
+
+    let fetch_response = cross_origin_no_cors_fetch(
+        Some(mime::TEXT_PLAIN),
+        b"<!DOCTYPE html><html><body>secret</body></html>",
+    );
+    assert!(fetch_response.is_network_error());
+
+    let fetch_response =
+        cross_origin_no_cors_fetch(Some("application/pdf".parse().unwrap()), b"%PDF-");
+    assert!(fetch_response.is_network_error());
+}
+
+#[test]
+fn test_opaque_response_blocking_allows_scripts_and_stylesheets() {
+    let fetch_response =
+        cross_origin_no_cors_fetch(Some(mime::TEXT_JAVASCRIPT), b"console.log(1);");
+    assert!(!fetch_response.is_network_error());
+
+    let fetch_response = cross_origin_no_cors_fetch(Some(mime::TEXT_CSS), b"body { }");
+    assert!(!fetch_response.is_network_error());
+
+    let fetch_response = cross_origin_no_cors_fetch(None, b"console.log(1);");
+    assert!(!fetch_response.is_network_error());
+}
+
 #[test]
 fn test_fetch_response_is_opaque_redirect_filtered() {
     static MESSAGE: &'static [u8] = b"";
@@ -1360,6 +1522,7 @@ fn test_fetch_with_devtools() {
         body: Some(content.as_bytes().to_vec()),
         pipeline_id: TEST_PIPELINE_ID,
         browsing_context_id: TEST_WEBVIEW_ID.0,
+        server_timing: vec![],
     };
 
     assert_eq!(devhttprequests.1, httprequest);
@@ -420,6 +420,45 @@ fn test_hsts_list_with_expired_entry_is_not_is_host_secure() {
     assert!(!hsts_list.is_host_secure("example.com"));
 }
 
+#[test]
+fn test_remove_expired_entries_from_hsts_list() {
+    let mut entries_map = HashMap::new();
+    entries_map.insert(
+        "example.com".to_owned(),
+        vec![
+            HstsEntry {
+                host: "example.com".to_owned(),
+                include_subdomains: true,
+                expires_at: Some(NonZeroU64::new(1).unwrap()),
+            },
+            HstsEntry::new(
+                "servo.example.com".to_owned(),
+                IncludeSubdomains::NotIncluded,
+                Some(StdDuration::from_secs(500000)),
+            )
+            .unwrap(),
+        ],
+    );
+    entries_map.insert(
+        "example.org".to_owned(),
+        vec![HstsEntry {
+            host: "example.org".to_owned(),
+            include_subdomains: false,
+            expires_at: Some(NonZeroU64::new(1).unwrap()),
+        }],
+    );
+    let mut hsts_list = HstsList {
+        entries_map: entries_map,
+    };
+
+    hsts_list.remove_expired();
+
+    assert_eq!(hsts_list.entries_map.len(), 1);
+    assert_eq!(hsts_list.entries_map.get("example.com").unwrap().len(), 1);
+    assert!(hsts_list.is_host_secure("servo.example.com"));
+    assert!(!hsts_list.is_host_secure("other.example.com"));
+}
+
 #[test]
 fn test_preload_hsts_domains_well_formed() {
     let hsts_list = HstsPreloadList::from_servo_preload();
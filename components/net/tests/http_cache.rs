@@ -4,13 +4,14 @@
 
 use base::id::TEST_PIPELINE_ID;
 use http::StatusCode;
-use http::header::{EXPIRES, HeaderValue};
+use http::header::{CACHE_CONTROL, COOKIE, EXPIRES, HeaderValue};
 use net::http_cache::HttpCache;
 use net_traits::request::{Referrer, RequestBuilder};
 use net_traits::response::{Response, ResponseBody};
 use net_traits::{ResourceFetchTiming, ResourceTimingType};
 use servo_url::ServoUrl;
 use tokio::sync::mpsc::unbounded_channel as unbounded;
+use uuid::Uuid;
 
 #[test]
 fn test_refreshing_resource_sets_done_chan_the_appropriate_value() {
@@ -48,3 +49,58 @@ fn test_refreshing_resource_sets_done_chan_the_appropriate_value() {
         }
     })
 }
+
+#[test]
+fn test_complete_responses_are_persisted_to_disk() {
+    let directory = std::env::temp_dir().join(format!("servo-http-cache-{}", Uuid::new_v4()));
+    let url = ServoUrl::parse("https://servo.org").unwrap();
+    let mut request = RequestBuilder::new(None, url.clone(), Referrer::NoReferrer)
+        .pipeline_id(Some(TEST_PIPELINE_ID))
+        .origin(url.origin())
+        .build();
+    request
+        .headers
+        .insert(COOKIE, HeaderValue::from_static("secret=hunter2"));
+    let timing = ResourceFetchTiming::new(ResourceTimingType::Navigation);
+    let mut response = Response::new(url.clone(), timing);
+    response
+        .headers
+        .insert(CACHE_CONTROL, HeaderValue::from_static("max-age=3600"));
+
+    let mut cache = HttpCache::with_disk_cache(directory.clone(), 1024);
+    *response.body.lock().unwrap() = ResponseBody::Receiving(vec![]);
+    cache.store(&request, &response);
+    *response.body.lock().unwrap() = ResponseBody::Done(b"Yay!".to_vec());
+    cache.write_to_disk(&request, &response);
+    drop(cache);
+
+    // Request headers that are not named by `Vary` are not written to disk.
+    for file in std::fs::read_dir(&directory).unwrap() {
+        let contents = std::fs::read(file.unwrap().path()).unwrap();
+        assert!(!String::from_utf8_lossy(&contents).contains("hunter2"));
+    }
+
+    // A new cache, as if Servo had been restarted, reads the response from disk.
+    let mut cache = HttpCache::with_disk_cache(directory.clone(), 1024);
+    cache.load_from_disk(&request);
+    let cached_response = cache
+        .construct_response(&request, &mut None)
+        .expect("The response should have been persisted");
+    assert!(!cached_response.needs_validation);
+    assert_eq!(
+        *cached_response.response.body.lock().unwrap(),
+        ResponseBody::Done(b"Yay!".to_vec())
+    );
+
+    // Responses larger than the disk cache are only kept in memory.
+    let mut cache = HttpCache::with_disk_cache(directory.clone(), 2);
+    cache.clear();
+    cache.store(&request, &response);
+    cache.write_to_disk(&request, &response);
+    drop(cache);
+    let mut cache = HttpCache::with_disk_cache(directory.clone(), 2);
+    cache.load_from_disk(&request);
+    assert!(cache.construct_response(&request, &mut None).is_none());
+
+    let _ = std::fs::remove_dir_all(directory);
+}
@@ -425,6 +425,7 @@ fn test_request_and_response_data_with_network_messages() {
         body: Some(content.as_bytes().to_vec()),
         pipeline_id: TEST_PIPELINE_ID,
         browsing_context_id: TEST_WEBVIEW_ID.0,
+        server_timing: vec![],
     };
 
     assert_eq!(devhttprequests.1, httprequest);
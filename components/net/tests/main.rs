@@ -5,16 +5,21 @@
 #![cfg(test)]
 #![allow(dead_code)]
 
+mod connection_scheduler;
 mod cookie;
 mod cookie_http_state;
 mod data_loader;
+mod doh;
 mod fetch;
 mod file_loader;
 mod filemanager_thread;
 mod hsts;
 mod http_cache;
 mod http_loader;
+mod preload;
+mod proxy;
 mod resource_thread;
+mod server_timing;
 mod subresource_integrity;
 
 use core::convert::Infallible;
@@ -153,6 +158,8 @@ fn create_http_state(fc: Option<EmbedderProxy>) -> HttpState {
             false, /* ignore_certificate_errors */
             override_manager.clone(),
         )),
+        connection_scheduler: Default::default(),
+        preloaded_resources: Default::default(),
         override_manager,
         embedder_proxy: Mutex::new(fc.unwrap_or_else(|| create_embedder_proxy())),
     }
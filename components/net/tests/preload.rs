@@ -0,0 +1,110 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+#![cfg(not(target_os = "windows"))]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use base::id::{TEST_PIPELINE_ID, TEST_WEBVIEW_ID};
+use http::header::{self, HeaderValue};
+use http_body_util::combinators::BoxBody;
+use hyper::body::{Bytes, Incoming};
+use hyper::{Request as HyperRequest, Response as HyperResponse};
+use net::preload::{Link, parse_link_header};
+use net_traits::request::{CredentialsMode, Destination, Referrer, RequestBuilder, RequestMode};
+use net_traits::response::ResponseBody;
+
+use crate::{fetch_with_context, make_body, make_server, new_fetch_context};
+
+#[test]
+fn test_parse_link_header() {
+    let links = parse_link_header(
+        r#"</style.css>; rel=preload; as=style, <https://example.com/a,b.woff2>; REL="Preload font"; as="font"; crossorigin"#,
+    );
+    assert_eq!(
+        links,
+        vec![
+            Link {
+                href: "/style.css".to_owned(),
+                rel: vec!["preload".to_owned()],
+                as_: Some("style".to_owned()),
+                crossorigin: None,
+            },
+            Link {
+                href: "https://example.com/a,b.woff2".to_owned(),
+                rel: vec!["preload".to_owned(), "font".to_owned()],
+                as_: Some("font".to_owned()),
+                crossorigin: Some("".to_owned()),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_parse_link_header_unquoted_and_escaped_values() {
+    let links =
+        parse_link_header(r#"</a.js>; rel=preload garbage; as=script, </b.js>; rel="x\"y""#);
+    assert_eq!(links.len(), 2);
+    assert_eq!(links[0].href, "/a.js");
+    assert_eq!(
+        links[0].rel,
+        vec!["preload".to_owned(), "garbage".to_owned()]
+    );
+    assert_eq!(links[1].rel, vec!["x\"y".to_owned()]);
+}
+
+#[test]
+fn test_link_preload_header_is_used_by_later_request() {
+    static STYLE_REQUESTS: AtomicUsize = AtomicUsize::new(0);
+    let handler =
+        move |request: HyperRequest<Incoming>,
+              response: &mut HyperResponse<BoxBody<Bytes, hyper::Error>>| {
+            if request.uri().path() == "/style.css" {
+                STYLE_REQUESTS.fetch_add(1, Ordering::SeqCst);
+                response
+                    .headers_mut()
+                    .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+                *response.body_mut() = make_body(b"body {}".to_vec());
+            } else {
+                response.headers_mut().insert(
+                    header::LINK,
+                    HeaderValue::from_static("</style.css>; rel=preload; as=style"),
+                );
+                *response.body_mut() = make_body(b"<!DOCTYPE html>".to_vec());
+            }
+        };
+    let (server, url) = make_server(handler);
+    let mut context = new_fetch_context(None, None, None);
+
+    let request = RequestBuilder::new(Some(TEST_WEBVIEW_ID), url.clone(), Referrer::NoReferrer)
+        .destination(Destination::Document)
+        .origin(url.origin())
+        .pipeline_id(Some(TEST_PIPELINE_ID))
+        .build();
+    let response = fetch_with_context(request, &mut context);
+    assert!(!response.is_network_error());
+
+    let style_url = url.join("/style.css").unwrap();
+    let request = RequestBuilder::new(
+        Some(TEST_WEBVIEW_ID),
+        style_url,
+        Referrer::ReferrerUrl(url.clone()),
+    )
+    .destination(Destination::Style)
+    .mode(RequestMode::NoCors)
+    .credentials_mode(CredentialsMode::Include)
+    .origin(url.origin())
+    .pipeline_id(Some(TEST_PIPELINE_ID))
+    .build();
+    let response = fetch_with_context(request, &mut context);
+
+    let _ = server.close();
+
+    let internal_response = response.internal_response.unwrap();
+    assert_eq!(
+        *internal_response.body.lock().unwrap(),
+        ResponseBody::Done(b"body {}".to_vec())
+    );
+    assert_eq!(STYLE_REQUESTS.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,49 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use http::{HeaderMap, HeaderValue};
+use net::server_timing::parse_server_timing;
+use net_traits::ServerTimingMetric;
+
+fn metric(name: &str, duration: f64, description: &str) -> ServerTimingMetric {
+    ServerTimingMetric {
+        name: name.to_owned(),
+        duration,
+        description: description.to_owned(),
+    }
+}
+
+#[test]
+fn test_parse_server_timing() {
+    let mut headers = HeaderMap::new();
+    headers.append(
+        "server-timing",
+        HeaderValue::from_static(r#"db;dur=53.5;desc="Query, \"users\"", cache;desc=hit"#),
+    );
+    headers.append(
+        "server-timing",
+        HeaderValue::from_static("total; DUR = 120 ; dur=1; desc=first; desc=second"),
+    );
+    assert_eq!(
+        parse_server_timing(&headers),
+        vec![
+            metric("db", 53.5, r#"Query, "users""#),
+            metric("cache", 0., "hit"),
+            metric("total", 120., "first"),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_server_timing_skips_invalid_metrics() {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "server-timing",
+        HeaderValue::from_static(r#"app;dur=abc, "quoted";dur=1, ;dur=2, miss"#),
+    );
+    assert_eq!(
+        parse_server_timing(&headers),
+        vec![metric("app", 0., ""), metric("miss", 0., "")]
+    );
+}
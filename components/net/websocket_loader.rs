@@ -41,13 +41,14 @@ use url::Url;
 
 use crate::async_runtime::spawn_task;
 use crate::connector::{CACertificates, TlsConfig, create_tls_config};
-use crate::cookie::ServoCookie;
+use crate::cookie::{CookieSiteContext, ServoCookie};
 use crate::fetch::methods::{
     convert_request_to_csp_request, should_request_be_blocked_by_csp,
     should_request_be_blocked_due_to_a_bad_port,
 };
 use crate::hosts::replace_host;
 use crate::http_loader::HttpState;
+use crate::proxy::{ProxyConfig, ProxyKind, connect_tunnel, socks5_connect};
 /// Create a tungstenite Request object for the initial HTTP request.
 /// This request contains `Origin`, `Sec-WebSocket-Protocol`, `Authorization`,
 /// and `Cookie` headers as appropriate.
@@ -58,6 +59,7 @@ fn create_request(
     origin: &str,
     protocols: &[String],
     http_state: &HttpState,
+    cookie_context: &CookieSiteContext,
 ) -> WebSocketResult<Request> {
     let mut builder = Request::get(resource_url.as_str());
     let headers = builder.headers_mut().unwrap();
@@ -85,7 +87,11 @@ fn create_request(
 
     let mut cookie_jar = http_state.cookie_jar.write().unwrap();
     cookie_jar.remove_expired_cookies_for_url(resource_url);
-    if let Some(cookie_list) = cookie_jar.cookies_for_url(resource_url, CookieSource::HTTP) {
+    if let Some(cookie_list) = cookie_jar.cookies_for_url_in_context(
+        resource_url,
+        CookieSource::HTTP,
+        cookie_context.clone(),
+    ) {
         headers.insert("Cookie", HeaderValue::from_str(&cookie_list)?);
     }
 
@@ -114,6 +120,7 @@ fn process_ws_response(
     response: &Response,
     resource_url: &ServoUrl,
     protocols: &[String],
+    cookie_context: &CookieSiteContext,
 ) -> Result<Option<String>, Error> {
     trace!("processing websocket http response for {}", resource_url);
     let mut protocol_in_use = None;
@@ -134,7 +141,7 @@ fn process_ws_response(
             if let Some(cookie) =
                 ServoCookie::from_cookie_string(s.into(), resource_url, CookieSource::HTTP)
             {
-                jar.push(cookie, resource_url, CookieSource::HTTP);
+                jar.push_in_context(cookie, resource_url, CookieSource::HTTP, cookie_context);
             }
         }
     }
@@ -300,6 +307,7 @@ async fn start_websocket(
     client: Request,
     tls_config: TlsConfig,
     dom_action_receiver: IpcReceiver<WebSocketDomAction>,
+    cookie_context: CookieSiteContext,
 ) -> Result<(), Error> {
     trace!("starting WS connection to {}", url);
 
@@ -324,14 +332,34 @@ async fn start_websocket(
         .port_or_known_default()
         .ok_or_else(|| Error::Url(UrlError::UnableToConnect("Unknown port".into())))?;
 
-    let try_socket = TcpStream::connect((&*domain.to_string(), port)).await;
-    let socket = try_socket.map_err(Error::Io)?;
+    let proxy = ProxyConfig::from_prefs()
+        .proxy_for(net_url.scheme(), host_str)
+        .cloned();
+    let socket = match proxy {
+        // Connections to `ws` URLs also go through a tunnel, since HTTP proxies can't
+        // forward the upgraded connection.
+        Some(proxy) => {
+            let mut socket = TcpStream::connect(proxy.authority())
+                .await
+                .map_err(Error::Io)?;
+            match proxy.kind {
+                ProxyKind::Http => connect_tunnel(&mut socket, &proxy, &host, port).await,
+                ProxyKind::Socks5 => socks5_connect(&mut socket, &proxy, &host, port).await,
+            }
+            .map_err(Error::Io)?;
+            socket
+        },
+        None => TcpStream::connect((&*domain.to_string(), port))
+            .await
+            .map_err(Error::Io)?,
+    };
     let connector = TlsConnector::from(Arc::new(tls_config));
 
     let (stream, response) =
         client_async_tls_with_connector_and_config(client, socket, Some(connector), None).await?;
 
-    let protocol_in_use = process_ws_response(&http_state, &response, &url, &protocols)?;
+    let protocol_in_use =
+        process_ws_response(&http_state, &response, &url, &protocols, &cookie_context)?;
 
     if !initiated_close.load(Ordering::SeqCst) {
         if resource_event_sender
@@ -405,11 +433,13 @@ fn connect(
         }
     }
 
+    let cookie_context = CookieSiteContext::for_request(&request);
     let client = match create_request(
         &req_url,
         &req_origin.ascii_serialization(),
         &protocols,
         &http_state,
+        &cookie_context,
     ) {
         Ok(c) => c,
         Err(e) => return Err(e.to_string()),
@@ -432,6 +462,7 @@ fn connect(
             client,
             tls_config,
             dom_action_receiver,
+            cookie_context,
         )
         .map_err(move |e| {
             warn!("Failed to establish a WebSocket connection: {:?}", e);
@@ -2,7 +2,8 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::time::Duration;
 
 use dom_struct::dom_struct;
 use js::jsapi::{ExceptionStackBehavior, Heap, JS_SetPendingException};
@@ -10,15 +11,20 @@ use js::jsval::{JSVal, UndefinedValue};
 use js::rust::{HandleObject, HandleValue, MutableHandleValue};
 use script_bindings::inheritance::Castable;
 
+use crate::dom::bindings::cell::DomRefCell;
 use crate::dom::bindings::codegen::Bindings::AbortSignalBinding::AbortSignalMethods;
 use crate::dom::bindings::error::{Error, ErrorToJsval};
+use crate::dom::bindings::refcounted::Trusted;
 use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object_with_proto};
-use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::root::{Dom, DomRoot};
 use crate::dom::eventtarget::EventTarget;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::readablestream::PipeTo;
-use crate::realms::InRealm;
+use crate::dom::scheduler::ScheduledTaskAbortSteps;
+use crate::fetch::FetchAbortSteps;
+use crate::realms::{InRealm, enter_realm};
 use crate::script_runtime::{CanGc, JSContext as SafeJSContext};
+use crate::timers::OneshotTimerCallback;
 
 impl js::gc::Rootable for AbortAlgorithm {}
 
@@ -34,7 +40,9 @@ pub(crate) enum AbortAlgorithm {
     /// <https://streams.spec.whatwg.org/#readable-stream-pipe-to>
     StreamPiping(PipeTo),
     /// <https://fetch.spec.whatwg.org/#dom-global-fetch>
-    Fetch,
+    Fetch(FetchAbortSteps),
+    /// <https://wicg.github.io/scheduling-apis/#schedule-a-posttask-task>
+    ScheduledTask(ScheduledTaskAbortSteps),
 }
 
 /// <https://dom.spec.whatwg.org/#abortsignal>
@@ -48,14 +56,26 @@ pub(crate) struct AbortSignal {
 
     /// <https://dom.spec.whatwg.org/#abortsignal-abort-algorithms>
     abort_algorithms: RefCell<Vec<AbortAlgorithm>>,
+
+    /// <https://dom.spec.whatwg.org/#abortsignal-dependent>
+    dependent: Cell<bool>,
+
+    /// <https://dom.spec.whatwg.org/#abortsignal-source-signals>
+    source_signals: DomRefCell<Vec<Dom<AbortSignal>>>,
+
+    /// <https://dom.spec.whatwg.org/#abortsignal-dependent-signals>
+    dependent_signals: DomRefCell<Vec<Dom<AbortSignal>>>,
 }
 
 impl AbortSignal {
-    fn new_inherited() -> AbortSignal {
+    pub(crate) fn new_inherited() -> AbortSignal {
         AbortSignal {
             eventtarget: EventTarget::new_inherited(),
             abort_reason: Default::default(),
             abort_algorithms: Default::default(),
+            dependent: Default::default(),
+            source_signals: Default::default(),
+            dependent_signals: Default::default(),
         }
     }
 
@@ -87,27 +107,122 @@ impl AbortSignal {
             return;
         }
 
-        let abort_reason = reason.get();
-
         // Set signal’s abort reason to reason if it is given;
+        // otherwise to a new "AbortError" DOMException.
+        self.set_abort_reason(cx, &global, reason, can_gc);
+
+        // Let dependentSignalsToAbort be a new list.
+        let mut dependent_signals_to_abort = vec![];
+
+        // For each dependentSignal of signal’s dependent signals:
+        for dependent_signal in self.dependent_signals.borrow().iter() {
+            // If dependentSignal is not aborted, then:
+            if !dependent_signal.aborted() {
+                // Set dependentSignal’s abort reason to signal’s abort reason.
+                dependent_signal.abort_reason.set(self.abort_reason.get());
+                // Append dependentSignal to dependentSignalsToAbort.
+                dependent_signals_to_abort.push(dependent_signal.as_rooted());
+            }
+        }
+
+        // Run the abort steps for signal.
+        self.run_the_abort_steps(cx, &global, realm, can_gc);
+
+        // For each dependentSignal of dependentSignalsToAbort, run the abort steps for dependentSignal.
+        for dependent_signal in dependent_signals_to_abort {
+            dependent_signal.run_the_abort_steps(cx, &global, realm, can_gc);
+        }
+    }
+
+    /// Set the abort reason to `reason` if it is given,
+    /// otherwise to a new "AbortError" DOMException.
+    fn set_abort_reason(
+        &self,
+        cx: SafeJSContext,
+        global: &GlobalScope,
+        reason: HandleValue,
+        can_gc: CanGc,
+    ) {
+        let abort_reason = reason.get();
         if !abort_reason.is_undefined() {
             self.abort_reason.set(abort_reason);
         } else {
-            // otherwise to a new "AbortError" DOMException.
             rooted!(in(*cx) let mut rooted_error = UndefinedValue());
-            Error::Abort.to_jsval(cx, &global, rooted_error.handle_mut(), can_gc);
+            Error::Abort.to_jsval(cx, global, rooted_error.handle_mut(), can_gc);
             self.abort_reason.set(rooted_error.get())
         }
+    }
 
-        // Let dependentSignalsToAbort be a new list.
-        // For each dependentSignal of signal’s dependent signals:
-        // TODO: #36936
+    /// <https://dom.spec.whatwg.org/#create-a-dependent-abort-signal>
+    pub(crate) fn create_dependent_abort_signal(
+        global: &GlobalScope,
+        signals: &[DomRoot<AbortSignal>],
+        can_gc: CanGc,
+    ) -> DomRoot<AbortSignal> {
+        // Let resultSignal be a new object implementing signalInterface using realm.
+        let result_signal = AbortSignal::new_with_proto(global, None, can_gc);
+        result_signal.depend_on(signals);
 
-        // Run the abort steps for signal.
-        self.run_the_abort_steps(cx, &global, realm, can_gc);
+        // Return resultSignal.
+        result_signal
+    }
 
-        // For each dependentSignal of dependentSignalsToAbort, run the abort steps for dependentSignal.
-        // TODO: #36936
+    /// The steps of <https://dom.spec.whatwg.org/#create-a-dependent-abort-signal>
+    /// following the creation of resultSignal, which is `self`.
+    pub(crate) fn depend_on(&self, signals: &[DomRoot<AbortSignal>]) {
+        let result_signal = self;
+
+        // For each signal of signals: if signal is aborted,
+        // then set resultSignal’s abort reason to signal’s abort reason and return resultSignal.
+        if let Some(signal) = signals.iter().find(|signal| signal.aborted()) {
+            result_signal.abort_reason.set(signal.abort_reason.get());
+            return;
+        }
+
+        // Set resultSignal’s dependent to true.
+        result_signal.dependent.set(true);
+
+        // For each signal of signals:
+        for signal in signals {
+            // If signal’s dependent is false, then:
+            if !signal.dependent.get() {
+                // Append signal to resultSignal’s source signals.
+                // Append resultSignal to signal’s dependent signals.
+                result_signal.add_source_signal(signal);
+                continue;
+            }
+
+            // Otherwise, for each sourceSignal of signal’s source signals:
+            for source_signal in signal.source_signals.borrow().iter() {
+                // Assert: sourceSignal is not aborted and not dependent.
+                debug_assert!(!source_signal.aborted() && !source_signal.dependent.get());
+
+                // Append sourceSignal to resultSignal’s source signals.
+                // Append resultSignal to sourceSignal’s dependent signals.
+                result_signal.add_source_signal(source_signal);
+            }
+        }
+    }
+
+    /// Link `source_signal` and `self`, the source and dependent signals being sets.
+    fn add_source_signal(&self, source_signal: &AbortSignal) {
+        let mut source_signals = self.source_signals.borrow_mut();
+        if source_signals
+            .iter()
+            .any(|signal| std::ptr::eq(&**signal, source_signal))
+        {
+            return;
+        }
+        source_signals.push(Dom::from_ref(source_signal));
+        source_signal
+            .dependent_signals
+            .borrow_mut()
+            .push(Dom::from_ref(self));
+    }
+
+    /// The abort reason, for use by algorithms running when the signal is aborted.
+    pub(crate) fn abort_reason(&self) -> JSVal {
+        self.abort_reason.get()
     }
 
     /// <https://dom.spec.whatwg.org/#abortsignal-add>
@@ -136,6 +251,16 @@ impl AbortSignal {
                 reason.set(self.abort_reason.get());
                 pipe.abort_with_reason(cx, global, reason.handle(), realm, can_gc);
             },
+            AbortAlgorithm::Fetch(fetch) => {
+                rooted!(in(*cx) let mut reason = UndefinedValue());
+                reason.set(self.abort_reason.get());
+                fetch.abort(cx, global, reason.handle(), can_gc);
+            },
+            AbortAlgorithm::ScheduledTask(task) => {
+                rooted!(in(*cx) let mut reason = UndefinedValue());
+                reason.set(self.abort_reason.get());
+                task.abort(cx, reason.handle(), can_gc);
+            },
             _ => {
                 // TODO: match on variant and implement algo steps.
                 // See the various items of #34866
@@ -172,6 +297,54 @@ impl AbortSignal {
 }
 
 impl AbortSignalMethods<crate::DomTypeHolder> for AbortSignal {
+    /// <https://dom.spec.whatwg.org/#dom-abortsignal-abort>
+    fn Abort(
+        cx: SafeJSContext,
+        global: &GlobalScope,
+        reason: HandleValue,
+        can_gc: CanGc,
+    ) -> DomRoot<AbortSignal> {
+        // Let signal be a new AbortSignal object.
+        let signal = AbortSignal::new_with_proto(global, None, can_gc);
+
+        // Set signal’s abort reason to reason if it is given;
+        // otherwise to a new "AbortError" DOMException.
+        signal.set_abort_reason(cx, global, reason, can_gc);
+
+        // Return signal.
+        signal
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-abortsignal-timeout>
+    fn Timeout(global: &GlobalScope, milliseconds: u64, can_gc: CanGc) -> DomRoot<AbortSignal> {
+        // Let signal be a new AbortSignal object.
+        let signal = AbortSignal::new_with_proto(global, None, can_gc);
+
+        // Let global be signal’s relevant global object.
+        // Run steps after a timeout given global, "AbortSignal-timeout", milliseconds,
+        // and the following step: queue a global task on the timer task source given global
+        // to signal abort given signal and a new "TimeoutError" DOMException.
+        // Note: the callback keeps the signal alive for the duration of the timeout.
+        let callback = OneshotTimerCallback::AbortSignalTimeout(AbortSignalTimeoutCallback {
+            signal: Trusted::new(&*signal),
+        });
+        global.schedule_callback(callback, Duration::from_millis(milliseconds));
+
+        // Return signal.
+        signal
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-abortsignal-any>
+    fn Any(
+        global: &GlobalScope,
+        signals: Vec<DomRoot<AbortSignal>>,
+        can_gc: CanGc,
+    ) -> DomRoot<AbortSignal> {
+        // The static any(signals) method steps are to return the result
+        // of creating a dependent abort signal from signals using AbortSignal and the current realm.
+        AbortSignal::create_dependent_abort_signal(global, &signals, can_gc)
+    }
+
     /// <https://dom.spec.whatwg.org/#dom-abortsignal-aborted>
     fn Aborted(&self) -> bool {
         // The aborted getter steps are to return true if this is aborted; otherwise false.
@@ -203,3 +376,24 @@ impl AbortSignalMethods<crate::DomTypeHolder> for AbortSignal {
     // <https://dom.spec.whatwg.org/#dom-abortsignal-onabort>
     event_handler!(abort, GetOnabort, SetOnabort);
 }
+
+/// The timer callback of <https://dom.spec.whatwg.org/#dom-abortsignal-timeout>.
+#[derive(JSTraceable, MallocSizeOf)]
+pub(crate) struct AbortSignalTimeoutCallback {
+    #[ignore_malloc_size_of = "Because it is non-owning"]
+    signal: Trusted<AbortSignal>,
+}
+
+impl AbortSignalTimeoutCallback {
+    pub(crate) fn invoke(self, can_gc: CanGc) {
+        let signal = self.signal.root();
+        let global = signal.global();
+        let realm = enter_realm(&*global);
+        let cx = GlobalScope::get_cx();
+
+        // Signal abort given signal and a new "TimeoutError" DOMException.
+        rooted!(in(*cx) let mut reason = UndefinedValue());
+        Error::Timeout.to_jsval(cx, &global, reason.handle_mut(), can_gc);
+        signal.signal_abort(cx, reason.handle(), InRealm::Entered(&realm), can_gc);
+    }
+}
@@ -0,0 +1,71 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use embedder_traits::GetAssertionResponse;
+use js::typedarray::ArrayBuffer;
+
+use crate::dom::authenticatorresponse::{AuthenticatorResponse, CachedArrayBuffer};
+use crate::dom::bindings::codegen::Bindings::AuthenticatorAssertionResponseBinding::AuthenticatorAssertionResponseMethods;
+use crate::dom::bindings::reflector::reflect_dom_object;
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::globalscope::GlobalScope;
+use crate::script_runtime::{CanGc, JSContext};
+
+/// <https://w3c.github.io/webauthn/#authenticatorassertionresponse>
+#[dom_struct]
+pub(crate) struct AuthenticatorAssertionResponse {
+    response: AuthenticatorResponse,
+    /// <https://w3c.github.io/webauthn/#dom-authenticatorassertionresponse-authenticatordata>
+    authenticator_data: CachedArrayBuffer,
+    /// <https://w3c.github.io/webauthn/#dom-authenticatorassertionresponse-signature>
+    signature: CachedArrayBuffer,
+    /// <https://w3c.github.io/webauthn/#dom-authenticatorassertionresponse-userhandle>
+    user_handle: Option<CachedArrayBuffer>,
+}
+
+impl AuthenticatorAssertionResponse {
+    fn new_inherited(client_data_json: Vec<u8>, response: GetAssertionResponse) -> Self {
+        Self {
+            response: AuthenticatorResponse::new_inherited(client_data_json),
+            authenticator_data: CachedArrayBuffer::new(response.authenticator_data),
+            signature: CachedArrayBuffer::new(response.signature),
+            user_handle: response.user_handle.map(CachedArrayBuffer::new),
+        }
+    }
+
+    pub(crate) fn new(
+        global: &GlobalScope,
+        client_data_json: Vec<u8>,
+        response: GetAssertionResponse,
+        can_gc: CanGc,
+    ) -> DomRoot<Self> {
+        reflect_dom_object(
+            Box::new(Self::new_inherited(client_data_json, response)),
+            global,
+            can_gc,
+        )
+    }
+}
+
+impl AuthenticatorAssertionResponseMethods<crate::DomTypeHolder>
+    for AuthenticatorAssertionResponse
+{
+    /// <https://w3c.github.io/webauthn/#dom-authenticatorassertionresponse-authenticatordata>
+    fn AuthenticatorData(&self, cx: JSContext, can_gc: CanGc) -> ArrayBuffer {
+        self.authenticator_data.get(cx, can_gc)
+    }
+
+    /// <https://w3c.github.io/webauthn/#dom-authenticatorassertionresponse-signature>
+    fn Signature(&self, cx: JSContext, can_gc: CanGc) -> ArrayBuffer {
+        self.signature.get(cx, can_gc)
+    }
+
+    /// <https://w3c.github.io/webauthn/#dom-authenticatorassertionresponse-userhandle>
+    fn GetUserHandle(&self, cx: JSContext, can_gc: CanGc) -> Option<ArrayBuffer> {
+        self.user_handle
+            .as_ref()
+            .map(|user_handle| user_handle.get(cx, can_gc))
+    }
+}
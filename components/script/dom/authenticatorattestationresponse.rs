@@ -0,0 +1,93 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use embedder_traits::MakeCredentialResponse;
+use js::typedarray::ArrayBuffer;
+
+use crate::dom::authenticatorresponse::{AuthenticatorResponse, CachedArrayBuffer};
+use crate::dom::bindings::codegen::Bindings::AuthenticatorAttestationResponseBinding::AuthenticatorAttestationResponseMethods;
+use crate::dom::bindings::reflector::reflect_dom_object;
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::str::DOMString;
+use crate::dom::globalscope::GlobalScope;
+use crate::script_runtime::{CanGc, JSContext};
+
+/// <https://w3c.github.io/webauthn/#authenticatorattestationresponse>
+#[dom_struct]
+pub(crate) struct AuthenticatorAttestationResponse {
+    response: AuthenticatorResponse,
+    /// <https://w3c.github.io/webauthn/#dom-authenticatorattestationresponse-attestationobject>
+    attestation_object: CachedArrayBuffer,
+    authenticator_data: CachedArrayBuffer,
+    public_key: Option<CachedArrayBuffer>,
+    public_key_algorithm: i32,
+    transports: Vec<DOMString>,
+}
+
+impl AuthenticatorAttestationResponse {
+    fn new_inherited(client_data_json: Vec<u8>, response: MakeCredentialResponse) -> Self {
+        let mut transports: Vec<DOMString> = response
+            .transports
+            .into_iter()
+            .map(DOMString::from)
+            .collect();
+        // <https://w3c.github.io/webauthn/#dom-authenticatorattestationresponse-transports-slot>
+        // The transports are sorted lexicographically and free of duplicates.
+        transports.sort();
+        transports.dedup();
+        Self {
+            response: AuthenticatorResponse::new_inherited(client_data_json),
+            attestation_object: CachedArrayBuffer::new(response.attestation_object),
+            authenticator_data: CachedArrayBuffer::new(response.authenticator_data),
+            public_key: response.public_key.map(CachedArrayBuffer::new),
+            public_key_algorithm: response.public_key_algorithm,
+            transports,
+        }
+    }
+
+    pub(crate) fn new(
+        global: &GlobalScope,
+        client_data_json: Vec<u8>,
+        response: MakeCredentialResponse,
+        can_gc: CanGc,
+    ) -> DomRoot<Self> {
+        reflect_dom_object(
+            Box::new(Self::new_inherited(client_data_json, response)),
+            global,
+            can_gc,
+        )
+    }
+}
+
+impl AuthenticatorAttestationResponseMethods<crate::DomTypeHolder>
+    for AuthenticatorAttestationResponse
+{
+    /// <https://w3c.github.io/webauthn/#dom-authenticatorattestationresponse-attestationobject>
+    fn AttestationObject(&self, cx: JSContext, can_gc: CanGc) -> ArrayBuffer {
+        self.attestation_object.get(cx, can_gc)
+    }
+
+    /// <https://w3c.github.io/webauthn/#dom-authenticatorattestationresponse-gettransports>
+    fn GetTransports(&self) -> Vec<DOMString> {
+        self.transports.clone()
+    }
+
+    /// <https://w3c.github.io/webauthn/#dom-authenticatorattestationresponse-getauthenticatordata>
+    fn GetAuthenticatorData(&self, cx: JSContext, can_gc: CanGc) -> ArrayBuffer {
+        self.authenticator_data.get(cx, can_gc)
+    }
+
+    /// <https://w3c.github.io/webauthn/#dom-authenticatorattestationresponse-getpublickey>
+    fn GetPublicKey(&self, cx: JSContext, can_gc: CanGc) -> Option<ArrayBuffer> {
+        self.public_key
+            .as_ref()
+            .map(|public_key| public_key.get(cx, can_gc))
+    }
+
+    /// <https://w3c.github.io/webauthn/#dom-authenticatorattestationresponse-getpublickeyalgorithm>
+    fn GetPublicKeyAlgorithm(&self) -> i32 {
+        self.public_key_algorithm
+    }
+}
@@ -0,0 +1,64 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use js::typedarray::{ArrayBuffer, ArrayBufferU8};
+
+use crate::dom::bindings::buffer_source::HeapBufferSource;
+use crate::dom::bindings::codegen::Bindings::AuthenticatorResponseBinding::AuthenticatorResponseMethods;
+use crate::dom::bindings::reflector::Reflector;
+use crate::script_runtime::{CanGc, JSContext};
+
+/// The bytes of an `ArrayBuffer` attribute. The `ArrayBuffer` is created the first time the
+/// attribute is read, and the same object is returned afterwards.
+#[derive(JSTraceable, MallocSizeOf)]
+pub(crate) struct CachedArrayBuffer {
+    bytes: Vec<u8>,
+    #[ignore_malloc_size_of = "mozjs"]
+    buffer: HeapBufferSource<ArrayBufferU8>,
+}
+
+impl CachedArrayBuffer {
+    pub(crate) fn new(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            buffer: HeapBufferSource::default(),
+        }
+    }
+
+    pub(crate) fn get(&self, cx: JSContext, can_gc: CanGc) -> ArrayBuffer {
+        if !self.buffer.is_initialized() {
+            self.buffer
+                .set_data(cx, &self.bytes, can_gc)
+                .expect("Creating an ArrayBuffer should never fail");
+        }
+        self.buffer
+            .get_typed_array()
+            .expect("The ArrayBuffer was initialized")
+    }
+}
+
+/// <https://w3c.github.io/webauthn/#authenticatorresponse>
+#[dom_struct]
+pub(crate) struct AuthenticatorResponse {
+    reflector_: Reflector,
+    /// <https://w3c.github.io/webauthn/#dom-authenticatorresponse-clientdatajson>
+    client_data_json: CachedArrayBuffer,
+}
+
+impl AuthenticatorResponse {
+    pub(crate) fn new_inherited(client_data_json: Vec<u8>) -> Self {
+        Self {
+            reflector_: Reflector::new(),
+            client_data_json: CachedArrayBuffer::new(client_data_json),
+        }
+    }
+}
+
+impl AuthenticatorResponseMethods<crate::DomTypeHolder> for AuthenticatorResponse {
+    /// <https://w3c.github.io/webauthn/#dom-authenticatorresponse-clientdatajson>
+    fn ClientDataJSON(&self, cx: JSContext, can_gc: CanGc) -> ArrayBuffer {
+        self.client_data_json.get(cx, can_gc)
+    }
+}
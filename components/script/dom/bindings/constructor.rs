@@ -68,7 +68,7 @@ fn html_constructor(
     let document = window.Document();
 
     // Step 1. Let registry be current global object's custom element registry.
-    let registry = window.CustomElements();
+    // Definitions in scoped registries are found by constructor as well, see Step 3.
 
     // Step 2 https://html.spec.whatwg.org/multipage/#htmlconstructor
     // The custom element definition cannot use an element interface as its constructor
@@ -100,18 +100,19 @@ fn html_constructor(
     // Step 3. Let definition be the item in registry's custom element definition set with constructor
     // equal to NewTarget. If there is no such item, then throw a TypeError.
     rooted!(in(*cx) let new_target = call_args.new_target().to_object());
-    let definition = match registry.lookup_definition_by_constructor(new_target.handle()) {
-        Some(definition) => definition,
-        None => {
-            throw_dom_exception(
-                cx,
-                global,
-                Error::Type("No custom element definition found for new.target".to_owned()),
-                can_gc,
-            );
-            return Err(());
-        },
-    };
+    let (registry, definition) =
+        match window.lookup_custom_element_definition_by_constructor(new_target.handle()) {
+            Some(found) => found,
+            None => {
+                throw_dom_exception(
+                    cx,
+                    global,
+                    Error::Type("No custom element definition found for new.target".to_owned()),
+                    can_gc,
+                );
+                return Err(());
+            },
+        };
 
     // Step 4. Let isValue be null.
     let mut is_value = None;
@@ -199,6 +200,11 @@ fn html_constructor(
                 element.set_is(is_value);
             }
 
+            // An element constructed directly from a scoped definition uses that scoped registry.
+            if registry.is_scoped() {
+                element.set_scoped_custom_element_registry(&registry);
+            }
+
             if !check_type(&element) {
                 throw_dom_exception(cx, global, Error::InvalidState, can_gc);
                 return Err(());
@@ -15,8 +15,8 @@ use base::id::{
 };
 use constellation_traits::{
     BlobImpl, DomException, DomPoint, MessagePortImpl, Serializable as SerializableInterface,
-    SerializableImageBitmap, StructuredSerializedData, TransferableOffscreenCanvas,
-    Transferrable as TransferrableInterface, TransformStreamData,
+    SerializableImageBitmap, SharedMemoryReferences, StructuredSerializedData,
+    TransferableOffscreenCanvas, Transferrable as TransferrableInterface, TransformStreamData,
 };
 use js::gc::RootedVec;
 use js::glue::{
@@ -34,6 +34,7 @@ use js::rust::{
     CustomAutoRooterGuard, HandleValue, JSAutoStructuredCloneBufferWrapper, MutableHandleValue,
 };
 use script_bindings::conversions::{IDLInterface, SafeToJSValConvertible};
+use servo_config::pref;
 use strum::IntoEnumIterator;
 
 use crate::dom::bindings::conversions::root_from_object;
@@ -602,12 +603,50 @@ pub(crate) struct StructuredDataWriter {
     pub(crate) offscreen_canvases: Option<HashMap<OffscreenCanvasId, TransferableOffscreenCanvas>>,
 }
 
+/// The clone buffer of data written for an agent of the same agent cluster, which
+/// holds the references on the shared memory objects it contains.
+struct SharedMemoryCloneBuffer(JSAutoStructuredCloneBufferWrapper);
+
+// The references held by the buffer are atomically reference counted by SpiderMonkey,
+// so they can be released from any thread.
+#[allow(unsafe_code)]
+unsafe impl Send for SharedMemoryCloneBuffer {}
+
 /// Writes a structured clone. Returns a `DataClone` error if that fails.
 pub(crate) fn write(
     cx: SafeJSContext,
     message: HandleValue,
     transfer: Option<CustomAutoRooterGuard<Vec<*mut JSObject>>>,
 ) -> Fallible<StructuredSerializedData> {
+    write_with_scope(cx, message, transfer, false)
+}
+
+/// Writes a structured clone for an agent of the same agent cluster, which lives in
+/// this process. Shared memory objects, such as `SharedArrayBuffer`s and shared
+/// `WebAssembly.Memory` objects, are then serialized by reference if `global` is
+/// cross-origin isolated.
+/// <https://html.spec.whatwg.org/multipage/#sharedarraybuffer-serialization>
+pub(crate) fn write_for_agent_cluster(
+    global: &GlobalScope,
+    cx: SafeJSContext,
+    message: HandleValue,
+    transfer: Option<CustomAutoRooterGuard<Vec<*mut JSObject>>>,
+) -> Fallible<StructuredSerializedData> {
+    let allow_shared_memory = global.is_cross_origin_isolated() && pref!(js_shared_memory);
+    write_with_scope(cx, message, transfer, allow_shared_memory)
+}
+
+fn write_with_scope(
+    cx: SafeJSContext,
+    message: HandleValue,
+    transfer: Option<CustomAutoRooterGuard<Vec<*mut JSObject>>>,
+    allow_shared_memory: bool,
+) -> Fallible<StructuredSerializedData> {
+    let scope = if allow_shared_memory {
+        StructuredCloneScope::SameProcess
+    } else {
+        StructuredCloneScope::DifferentProcess
+    };
     unsafe {
         rooted!(in(*cx) let mut val = UndefinedValue());
         if let Some(transfer) = transfer {
@@ -616,20 +655,20 @@ pub(crate) fn write(
         let mut sc_writer = StructuredDataWriter::default();
         let sc_writer_ptr = &mut sc_writer as *mut _;
 
-        let scbuf = JSAutoStructuredCloneBufferWrapper::new(
-            StructuredCloneScope::DifferentProcess,
-            &STRUCTURED_CLONE_CALLBACKS,
-        );
+        let scbuf = JSAutoStructuredCloneBufferWrapper::new(scope, &STRUCTURED_CLONE_CALLBACKS);
         let scdata = &mut ((*scbuf.as_raw_ptr()).data_);
+        // Step 13 of <https://html.spec.whatwg.org/multipage/#structuredserializeinternal>:
+        // a SharedArrayBuffer can only be serialized if the agent cluster is cross-origin
+        // isolated, otherwise SpiderMonkey throws a "DataCloneError".
         let policy = CloneDataPolicy {
-            allowIntraClusterClonableSharedObjects_: false,
-            allowSharedMemoryObjects_: false,
+            allowIntraClusterClonableSharedObjects_: allow_shared_memory,
+            allowSharedMemoryObjects_: allow_shared_memory,
         };
         let result = JS_WriteStructuredClone(
             *cx,
             message,
             scdata,
-            StructuredCloneScope::DifferentProcess,
+            scope,
             &policy,
             &STRUCTURED_CLONE_CALLBACKS,
             sc_writer_ptr as *mut raw::c_void,
@@ -660,6 +699,8 @@ pub(crate) fn write(
             image_bitmaps: sc_writer.image_bitmaps.take(),
             transferred_image_bitmaps: sc_writer.transferred_image_bitmaps.take(),
             offscreen_canvases: sc_writer.offscreen_canvases.take(),
+            shared_memory: allow_shared_memory
+                .then(|| SharedMemoryReferences(Box::new(SharedMemoryCloneBuffer(scbuf)))),
         };
 
         Ok(data)
@@ -689,11 +730,19 @@ pub(crate) fn read(
         offscreen_canvases: data.offscreen_canvases.take(),
     };
     let sc_reader_ptr = &mut sc_reader as *mut _;
+    // Shared memory is only ever referenced by data written for this agent cluster,
+    // and the references it holds must outlive the deserialization.
+    let shared_memory = data.shared_memory.take();
+    let scope = if shared_memory.is_some() {
+        StructuredCloneScope::SameProcess
+    } else {
+        StructuredCloneScope::DifferentProcess
+    };
+    // Step 2.2 of the deserialization steps for SharedArrayBuffer: if the target is
+    // not cross-origin isolated, SpiderMonkey throws a "DataCloneError".
+    let allow_shared_memory = shared_memory.is_some() && global.is_cross_origin_isolated();
     unsafe {
-        let scbuf = JSAutoStructuredCloneBufferWrapper::new(
-            StructuredCloneScope::DifferentProcess,
-            &STRUCTURED_CLONE_CALLBACKS,
-        );
+        let scbuf = JSAutoStructuredCloneBufferWrapper::new(scope, &STRUCTURED_CLONE_CALLBACKS);
         let scdata = &mut ((*scbuf.as_raw_ptr()).data_);
 
         WriteBytesToJSStructuredCloneData(
@@ -706,11 +755,11 @@ pub(crate) fn read(
             *cx,
             scdata,
             JS_STRUCTURED_CLONE_VERSION,
-            StructuredCloneScope::DifferentProcess,
+            scope,
             rval,
             &CloneDataPolicy {
-                allowIntraClusterClonableSharedObjects_: false,
-                allowSharedMemoryObjects_: false,
+                allowIntraClusterClonableSharedObjects_: allow_shared_memory,
+                allowSharedMemoryObjects_: allow_shared_memory,
             },
             &STRUCTURED_CLONE_CALLBACKS,
             sc_reader_ptr as *mut raw::c_void,
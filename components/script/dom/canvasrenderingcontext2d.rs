@@ -97,6 +97,12 @@ impl CanvasRenderingContext2D {
     pub(crate) fn send_canvas_2d_msg(&self, msg: Canvas2dMsg) {
         self.canvas_state.send_canvas_2d_msg(msg)
     }
+
+    pub(crate) fn image_key(&self) -> Option<ImageKey> {
+        self.canvas_state
+            .is_paintable()
+            .then(|| self.canvas_state.image_key())
+    }
 }
 
 impl LayoutCanvasRenderingContextHelpers for LayoutDom<'_, CanvasRenderingContext2D> {
@@ -154,6 +160,9 @@ impl CanvasContext for CanvasRenderingContext2D {
             canvas.upcast::<Node>().dirty(NodeDamage::Other);
             canvas.owner_document().add_dirty_2d_canvas(self);
         }
+        if let HTMLCanvasElementOrOffscreenCanvas::OffscreenCanvas(canvas) = &self.canvas {
+            canvas.mark_remote_placeholder_dirty();
+        }
     }
 }
 
@@ -10,6 +10,7 @@ use script_bindings::codegen::InheritTypes::{CharacterDataTypeId, NodeTypeId, Te
 
 use crate::dom::bindings::cell::{DomRefCell, Ref};
 use crate::dom::bindings::codegen::Bindings::CharacterDataBinding::CharacterDataMethods;
+use crate::dom::bindings::codegen::Bindings::DocumentBinding::DocumentMethods;
 use crate::dom::bindings::codegen::Bindings::NodeBinding::Node_Binding::NodeMethods;
 use crate::dom::bindings::codegen::Bindings::ProcessingInstructionBinding::ProcessingInstructionMethods;
 use crate::dom::bindings::codegen::UnionTypes::NodeOrString;
@@ -22,7 +23,7 @@ use crate::dom::comment::Comment;
 use crate::dom::document::Document;
 use crate::dom::element::Element;
 use crate::dom::mutationobserver::{Mutation, MutationObserver};
-use crate::dom::node::{ChildrenMutation, Node, NodeDamage};
+use crate::dom::node::{ChildrenMutation, Node, NodeDamage, NodeTraits};
 use crate::dom::processinginstruction::ProcessingInstruction;
 use crate::dom::text::Text;
 use crate::dom::virtualmethods::vtable_for;
@@ -99,6 +100,21 @@ impl CharacterData {
         }
     }
 
+    /// The boundary points of the document's selection may have moved because the data
+    /// changed, so the selected part of a text node that is painted as selected is updated
+    /// from the live selection range.
+    fn update_painted_selection(&self) {
+        let Some(text) = self.downcast::<Text>() else {
+            return;
+        };
+        if !text.is_painted_as_selected() {
+            return;
+        }
+        if let Some(selection) = self.owner_document().GetSelection(CanGc::note()) {
+            selection.update_painted_selection();
+        }
+    }
+
     // Queue a MutationObserver record before changing the content.
     fn queue_mutation_record(&self) {
         let mutation = LazyCell::new(|| Mutation::CharacterData {
@@ -124,6 +140,7 @@ impl CharacterDataMethods<crate::DomTypeHolder> for CharacterData {
         let node = self.upcast::<Node>();
         node.ranges()
             .replace_code_units(node, 0, old_length, new_length);
+        self.update_painted_selection();
     }
 
     // https://dom.spec.whatwg.org/#dom-characterdata-length
@@ -241,6 +258,7 @@ impl CharacterDataMethods<crate::DomTypeHolder> for CharacterData {
         let node = self.upcast::<Node>();
         node.ranges()
             .replace_code_units(node, offset, count, arg.encode_utf16().count() as u32);
+        self.update_painted_selection();
         Ok(())
     }
 
@@ -294,6 +312,13 @@ impl<'dom> LayoutCharacterDataHelpers<'dom> for LayoutDom<'dom, CharacterData> {
     }
 }
 
+/// Convert an offset in UTF-16 code units into a byte offset into `data`, clamping offsets
+/// that are past the end of the data. An offset in the middle of a surrogate pair is moved
+/// to the start of its code point, so the result is always at a character boundary.
+pub(crate) fn utf8_offset(data: &str, offset: u32) -> usize {
+    split_at_utf16_code_unit_offset(data, offset).map_or(data.len(), |(before, _, _)| before.len())
+}
+
 /// Split the given string at the given position measured in UTF-16 code units from the start.
 ///
 /// * `Err(())` indicates that `offset` if after the end of the string
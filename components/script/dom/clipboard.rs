@@ -14,6 +14,9 @@ use js::rust::HandleValue as SafeHandleValue;
 use crate::dom::bindings::codegen::Bindings::ClipboardBinding::{
     ClipboardMethods, PresentationStyle,
 };
+use crate::dom::bindings::codegen::Bindings::PermissionStatusBinding::{
+    PermissionName, PermissionState,
+};
 use crate::dom::bindings::error::Error;
 use crate::dom::bindings::refcounted::TrustedPromise;
 use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object};
@@ -23,6 +26,7 @@ use crate::dom::blob::Blob;
 use crate::dom::clipboarditem::Representation;
 use crate::dom::eventtarget::EventTarget;
 use crate::dom::globalscope::GlobalScope;
+use crate::dom::permissions::{descriptor_permission_state, request_permission_to_use};
 use crate::dom::promise::Promise;
 use crate::dom::promisenativehandler::{Callback, PromiseNativeHandler};
 use crate::dom::window::Window;
@@ -101,11 +105,19 @@ impl ClipboardMethods<crate::DomTypeHolder> for Clipboard {
 
         // Step 3 Run the following steps in parallel:
 
-        // TODO Step 3.1 Let r be the result of running check clipboard read permission.
+        // Step 3.1 Let r be the result of running check clipboard read permission.
+        let r = request_permission_to_use(PermissionName::Clipboard_read, &global) ==
+            PermissionState::Granted;
+
         // Step 3.2 If r is false, then:
-        // Step 3.2.1 Queue a global task on the permission task source, given realm’s global object,
-        // to reject p with "NotAllowedError" DOMException in realm.
-        // Step 3.2.2 Abort these steps.
+        if !r {
+            // Step 3.2.1 Queue a global task on the permission task source, given realm’s global object,
+            // to reject p with "NotAllowedError" DOMException in realm.
+            // NOTE: We don't have a permission task source, so use the clipboard task source.
+            queue_not_allowed_rejection(&global, &p);
+            // Step 3.2.2 Abort these steps.
+            return p;
+        }
 
         // Step 3.3 Let data be a copy of the system clipboard data.
         let window = global.as_window();
@@ -129,10 +141,18 @@ impl ClipboardMethods<crate::DomTypeHolder> for Clipboard {
 
         // TODO write permission could be removed from spec
         // Step 3.1 Let r be the result of running check clipboard write permission.
+        // Writing doesn't prompt, it is only refused if the user has denied it.
+        let r = descriptor_permission_state(PermissionName::Clipboard_write, Some(&self.global())) !=
+            PermissionState::Denied;
+
         // Step 3.2 If r is false, then:
-        // Step 3.2.1 Queue a global task on the permission task source, given realm’s global object,
-        // to reject p with "NotAllowedError" DOMException in realm.
-        // Step 3.2.2 Abort these steps.
+        if !r {
+            // Step 3.2.1 Queue a global task on the permission task source, given realm’s global object,
+            // to reject p with "NotAllowedError" DOMException in realm.
+            queue_not_allowed_rejection(&self.global(), &p);
+            // Step 3.2.2 Abort these steps.
+            return p;
+        }
 
         let trusted_promise = TrustedPromise::new(p.clone());
         let bytes = Vec::from(data);
@@ -174,6 +194,18 @@ impl ClipboardMethods<crate::DomTypeHolder> for Clipboard {
     }
 }
 
+/// Queues a task to reject `promise` with a "NotAllowedError" `DOMException`, for when the
+/// clipboard permission checks fail.
+fn queue_not_allowed_rejection(global: &GlobalScope, promise: &Rc<Promise>) {
+    let trusted_promise = TrustedPromise::new(promise.clone());
+    global.task_manager().clipboard_task_source().queue(
+        task!(reject_clipboard_permission: move || {
+            let promise = trusted_promise.root();
+            promise.reject_error(Error::NotAllowed, CanGc::note());
+        }),
+    );
+}
+
 impl RoutedPromiseListener<Result<String, String>> for Clipboard {
     fn handle_response(
         &self,
@@ -0,0 +1,384 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::ptr;
+use std::rc::Rc;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use dom_struct::dom_struct;
+use flate2::{Compress, Compression, Crc, FlushCompress, Status};
+use js::conversions::FromJSValConvertible;
+use js::jsapi::JSObject;
+use js::jsval::{ObjectValue, UndefinedValue};
+use js::rust::{HandleObject as SafeHandleObject, HandleValue as SafeHandleValue};
+use js::typedarray::Uint8;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+use crate::DomTypes;
+use crate::dom::bindings::buffer_source::create_buffer_source;
+use crate::dom::bindings::codegen::Bindings::CompressionStreamBinding::{
+    CompressionFormat, CompressionStreamMethods,
+};
+use crate::dom::bindings::codegen::UnionTypes::ArrayBufferViewOrArrayBuffer;
+use crate::dom::bindings::error::{Error, Fallible};
+use crate::dom::bindings::refcounted::{Trusted, TrustedPromise};
+use crate::dom::bindings::reflector::{DomGlobal, Reflector, reflect_dom_object_with_proto};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::promise::Promise;
+use crate::dom::transformstreamdefaultcontroller::TransformerType;
+use crate::dom::types::{TransformStream, TransformStreamDefaultController};
+use crate::realms::enter_realm;
+use crate::script_runtime::{CanGc, JSContext as SafeJSContext};
+
+/// Chunks of at least this many bytes are processed on a background thread,
+/// so that (de)compressing large payloads doesn't block the event loop.
+const OFF_THREAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The threads processing the large chunks of every stream in this process,
+/// or `None` if they could not be started.
+static THREAD_POOL: LazyLock<Option<ThreadPool>> = LazyLock::new(|| {
+    ThreadPoolBuilder::new()
+        .thread_name(|index| format!("CompressionStream#{index}"))
+        .num_threads(2)
+        .build()
+        .ok()
+});
+
+/// The amount of spare output capacity reserved before each call into the codec.
+const OUTPUT_BUFFER_SIZE: usize = 32 * 1024;
+
+/// The fixed ten byte header written at the start of a gzip member:
+/// the magic number, the deflate method, no flags, no modification time,
+/// no extra flags, and an unknown operating system.
+const GZIP_HEADER: [u8; 10] = [0x1f, 0x8b, 0x08, 0, 0, 0, 0, 0, 0, 0xff];
+
+/// The compression or decompression context of a `CompressionStream` or `DecompressionStream`.
+pub trait ChunkCodec: Send {
+    /// Process a chunk of input, returning the output that became available.
+    fn process(&mut self, input: &[u8]) -> Result<Vec<u8>, &'static str>;
+
+    /// Signal the end of the input, returning the remaining output.
+    fn finish(&mut self) -> Result<Vec<u8>, &'static str>;
+}
+
+/// The algorithms of the transform stream backing a `CompressionStream` or `DecompressionStream`.
+#[derive(JSTraceable, MallocSizeOf)]
+pub(crate) struct CompressionTransformer {
+    /// Shared with the background thread processing large chunks.
+    #[ignore_malloc_size_of = "Defined in flate2"]
+    #[no_trace]
+    codec: Arc<Mutex<Box<dyn ChunkCodec>>>,
+}
+
+impl CompressionTransformer {
+    pub(crate) fn new(codec: Box<dyn ChunkCodec>) -> CompressionTransformer {
+        CompressionTransformer {
+            codec: Arc::new(Mutex::new(codec)),
+        }
+    }
+
+    /// <https://compression.spec.whatwg.org/#compress-and-enqueue-a-chunk>
+    /// <https://compression.spec.whatwg.org/#decompress-and-enqueue-a-chunk>
+    #[allow(unsafe_code)]
+    pub(crate) fn transform(
+        &self,
+        cx: SafeJSContext,
+        global: &GlobalScope,
+        chunk: SafeHandleValue,
+        controller: &TransformStreamDefaultController,
+        can_gc: CanGc,
+    ) -> Rc<Promise> {
+        // Step 1. If chunk is not a BufferSource type, then throw a TypeError.
+        let conversion_result = unsafe { ArrayBufferViewOrArrayBuffer::from_jsval(*cx, chunk, ()) };
+        let input = match conversion_result
+            .as_ref()
+            .ok()
+            .and_then(|result| result.get_success_value())
+        {
+            Some(ArrayBufferViewOrArrayBuffer::ArrayBufferView(view)) => unsafe {
+                view.as_slice().to_vec()
+            },
+            Some(ArrayBufferViewOrArrayBuffer::ArrayBuffer(buffer)) => unsafe {
+                buffer.as_slice().to_vec()
+            },
+            None => {
+                let promise = Promise::new(global, can_gc);
+                promise.reject_error(
+                    Error::Type("Chunk is not a BufferSource".to_owned()),
+                    can_gc,
+                );
+                return promise;
+            },
+        };
+
+        // Step 2. Let buffer be the result of (de)compressing chunk
+        // with the stream’s format and context.
+        // Steps 3-4 are done in `enqueue_output`.
+        self.run(cx, global, controller, Some(input), can_gc)
+    }
+
+    /// <https://compression.spec.whatwg.org/#compress-flush-and-enqueue>
+    /// <https://compression.spec.whatwg.org/#decompress-flush-and-enqueue>
+    pub(crate) fn flush(
+        &self,
+        cx: SafeJSContext,
+        global: &GlobalScope,
+        controller: &TransformStreamDefaultController,
+        can_gc: CanGc,
+    ) -> Rc<Promise> {
+        // Step 1. Let buffer be the result of (de)compressing an empty input
+        // with the stream’s format and context, with the finish flag.
+        // Steps 2-3 are done in `enqueue_output`.
+        self.run(cx, global, controller, None, can_gc)
+    }
+
+    /// Feed `input` to the codec, or finish it when there is no input, and enqueue the output.
+    ///
+    /// Note: the transform stream waits for the returned promise before processing the
+    /// next chunk, so the codec is never used by more than one thread at a time.
+    fn run(
+        &self,
+        cx: SafeJSContext,
+        global: &GlobalScope,
+        controller: &TransformStreamDefaultController,
+        input: Option<Vec<u8>>,
+        can_gc: CanGc,
+    ) -> Rc<Promise> {
+        let off_thread = input
+            .as_ref()
+            .is_some_and(|input| input.len() >= OFF_THREAD_CHUNK_SIZE);
+        let codec = self.codec.clone();
+        let process = move || {
+            let mut codec = codec.lock().unwrap();
+            match input {
+                Some(input) => codec.process(&input),
+                None => codec.finish(),
+            }
+        };
+
+        let promise = Promise::new(global, can_gc);
+        if !off_thread {
+            match enqueue_output(cx, global, controller, process(), can_gc) {
+                Ok(()) => promise.resolve_native(&(), can_gc),
+                Err(error) => promise.reject_error(error, can_gc),
+            }
+            return promise;
+        }
+
+        // If the threads can't be started, this errors the stream like a codec error would.
+        let Some(thread_pool) = THREAD_POOL.as_ref() else {
+            promise.reject_error(
+                Error::Type("Failed to start the compression threads".to_owned()),
+                can_gc,
+            );
+            return promise;
+        };
+
+        let trusted_promise = TrustedPromise::new(promise.clone());
+        let trusted_controller = Trusted::new(controller);
+        let task_source = global
+            .task_manager()
+            .dom_manipulation_task_source()
+            .to_sendable();
+        thread_pool.spawn(move || {
+            let output = process();
+            task_source.queue(task!(enqueue_processed_chunk: move || {
+                let controller = trusted_controller.root();
+                let promise = trusted_promise.root();
+                let global = controller.global();
+                let _ac = enter_realm(&*global);
+                let cx = GlobalScope::get_cx();
+                match enqueue_output(cx, &global, &controller, output, CanGc::note()) {
+                    Ok(()) => promise.resolve_native(&(), CanGc::note()),
+                    Err(error) => promise.reject_error(error, CanGc::note()),
+                }
+            }));
+        });
+        promise
+    }
+}
+
+/// The last steps of the (de)compress and enqueue algorithms, shared with the flush algorithms.
+fn enqueue_output(
+    cx: SafeJSContext,
+    global: &GlobalScope,
+    controller: &TransformStreamDefaultController,
+    output: Result<Vec<u8>, &'static str>,
+    can_gc: CanGc,
+) -> Fallible<()> {
+    // If this results in an error, then throw a TypeError.
+    let buffer = output.map_err(|message| Error::Type(message.to_owned()))?;
+
+    // If buffer is empty, return.
+    if buffer.is_empty() {
+        return Ok(());
+    }
+
+    // Let arrays be the result of splitting buffer into one or more non-empty pieces
+    // and converting them into Uint8Arrays.
+    // For each Uint8Array array of arrays, enqueue array in the stream’s transform.
+    // Note: Servo enqueues the whole buffer as a single Uint8Array.
+    rooted!(in(*cx) let mut array = ptr::null_mut::<JSObject>());
+    create_buffer_source::<Uint8>(cx, &buffer, array.handle_mut(), can_gc)
+        .map_err(|_| Error::JSFailed)?;
+    rooted!(in(*cx) let mut chunk = UndefinedValue());
+    chunk.set(ObjectValue(array.get()));
+    controller.enqueue(cx, global, chunk.handle(), can_gc)
+}
+
+/// Run `compress` over `input` until it is consumed, appending the output to `output`.
+/// When `finish` is set, also flush the remaining output and end the deflate stream.
+fn run_compress(
+    compress: &mut Compress,
+    mut input: &[u8],
+    output: &mut Vec<u8>,
+    finish: bool,
+) -> Result<(), &'static str> {
+    let flush = if finish {
+        FlushCompress::Finish
+    } else {
+        FlushCompress::None
+    };
+    loop {
+        output.reserve(OUTPUT_BUFFER_SIZE);
+        let before_in = compress.total_in();
+        let status = compress
+            .compress_vec(input, output, flush)
+            .map_err(|_| "Failed to compress data")?;
+        input = &input[(compress.total_in() - before_in) as usize..];
+        let output_full = output.len() == output.capacity();
+        match status {
+            Status::StreamEnd => return Ok(()),
+            Status::Ok | Status::BufError if !finish && input.is_empty() && !output_full => {
+                return Ok(());
+            },
+            _ => {},
+        }
+    }
+}
+
+/// <https://compression.spec.whatwg.org/#compressionstream-context>
+pub struct Compressor {
+    deflate: Compress,
+    /// The checksum of the uncompressed data, for the "gzip" format only.
+    gzip_crc: Option<Crc>,
+    /// Whether the gzip header still has to be written.
+    gzip_header_pending: bool,
+}
+
+impl Compressor {
+    pub fn new(format: CompressionFormat) -> Compressor {
+        let level = Compression::default();
+        match format {
+            CompressionFormat::Deflate => Compressor {
+                deflate: Compress::new(level, true),
+                gzip_crc: None,
+                gzip_header_pending: false,
+            },
+            CompressionFormat::Deflate_raw => Compressor {
+                deflate: Compress::new(level, false),
+                gzip_crc: None,
+                gzip_header_pending: false,
+            },
+            CompressionFormat::Gzip => Compressor {
+                deflate: Compress::new(level, false),
+                gzip_crc: Some(Crc::new()),
+                gzip_header_pending: true,
+            },
+        }
+    }
+
+    fn write_gzip_header(&mut self, output: &mut Vec<u8>) {
+        if std::mem::take(&mut self.gzip_header_pending) {
+            output.extend_from_slice(&GZIP_HEADER);
+        }
+    }
+}
+
+impl ChunkCodec for Compressor {
+    fn process(&mut self, input: &[u8]) -> Result<Vec<u8>, &'static str> {
+        let mut output = vec![];
+        self.write_gzip_header(&mut output);
+        if let Some(crc) = self.gzip_crc.as_mut() {
+            crc.update(input);
+        }
+        run_compress(&mut self.deflate, input, &mut output, false)?;
+        Ok(output)
+    }
+
+    fn finish(&mut self) -> Result<Vec<u8>, &'static str> {
+        let mut output = vec![];
+        self.write_gzip_header(&mut output);
+        run_compress(&mut self.deflate, &[], &mut output, true)?;
+        if let Some(crc) = self.gzip_crc.as_ref() {
+            output.extend_from_slice(&crc.sum().to_le_bytes());
+            output.extend_from_slice(&crc.amount().to_le_bytes());
+        }
+        Ok(output)
+    }
+}
+
+/// <https://compression.spec.whatwg.org/#compressionstream>
+#[dom_struct]
+pub(crate) struct CompressionStream {
+    reflector_: Reflector,
+
+    /// <https://streams.spec.whatwg.org/#generictransformstream>
+    transform: Dom<TransformStream>,
+}
+
+impl CompressionStream {
+    fn new_inherited(transform: &TransformStream) -> CompressionStream {
+        CompressionStream {
+            reflector_: Reflector::new(),
+            transform: Dom::from_ref(transform),
+        }
+    }
+}
+
+impl CompressionStreamMethods<crate::DomTypeHolder> for CompressionStream {
+    /// <https://compression.spec.whatwg.org/#dom-compressionstream-compressionstream>
+    fn Constructor(
+        global: &GlobalScope,
+        proto: Option<SafeHandleObject>,
+        can_gc: CanGc,
+        format: CompressionFormat,
+    ) -> Fallible<DomRoot<CompressionStream>> {
+        // Step 1. If format is unsupported in CompressionStream, then throw a TypeError.
+        // Note: all formats of the `CompressionFormat` enum are supported.
+
+        // Step 2. Set this’s format to format.
+        // Step 3. Let transformAlgorithm be an algorithm which takes a chunk argument
+        // and runs the compress and enqueue a chunk algorithm with this and chunk.
+        // Step 4. Let flushAlgorithm be an algorithm which takes no argument
+        // and runs the compress flush and enqueue algorithm with this.
+        let transformer_type = TransformerType::Compression(Rc::new(CompressionTransformer::new(
+            Box::new(Compressor::new(format)),
+        )));
+
+        // Step 5. Set this’s transform to a new TransformStream.
+        // Step 6. Set up this’s transform with transformAlgorithm set to transformAlgorithm
+        // and flushAlgorithm set to flushAlgorithm.
+        let transform_stream = TransformStream::new_with_proto(global, None, can_gc);
+        transform_stream.set_up(GlobalScope::get_cx(), global, transformer_type, can_gc)?;
+
+        Ok(reflect_dom_object_with_proto(
+            Box::new(CompressionStream::new_inherited(&transform_stream)),
+            global,
+            proto,
+            can_gc,
+        ))
+    }
+
+    /// <https://streams.spec.whatwg.org/#dom-generictransformstream-readable>
+    fn Readable(&self) -> DomRoot<<crate::DomTypeHolder as DomTypes>::ReadableStream> {
+        self.transform.get_readable()
+    }
+
+    /// <https://streams.spec.whatwg.org/#dom-generictransformstream-writable>
+    fn Writable(&self) -> DomRoot<<crate::DomTypeHolder as DomTypes>::WritableStream> {
+        self.transform.get_writable()
+    }
+}
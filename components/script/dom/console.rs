@@ -103,6 +103,11 @@ impl Console {
     pub(crate) fn internal_warn(global: &GlobalScope, message: DOMString) {
         Console::send_string_message(global, LogLevel::Warn, String::from(message.clone()));
     }
+
+    // Directly logs a DOMString as an error, without processing the message
+    pub(crate) fn internal_error(global: &GlobalScope, message: DOMString) {
+        Console::send_string_message(global, LogLevel::Error, String::from(message));
+    }
 }
 
 // In order to avoid interleaving the stdout output of the Console API methods
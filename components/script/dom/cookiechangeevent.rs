@@ -0,0 +1,133 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use js::rust::{HandleObject, MutableHandleValue};
+use stylo_atoms::Atom;
+
+use crate::dom::bindings::codegen::Bindings::CookieChangeEventBinding::{
+    CookieChangeEventInit, CookieChangeEventMethods,
+};
+use crate::dom::bindings::codegen::Bindings::CookieStoreBinding::CookieListItem;
+use crate::dom::bindings::codegen::Bindings::EventBinding::EventMethods;
+use crate::dom::bindings::error::Fallible;
+use crate::dom::bindings::frozenarray::CachedFrozenArray;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::reflect_dom_object_with_proto;
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::str::DOMString;
+use crate::dom::event::{Event, EventBubbles, EventCancelable};
+use crate::dom::window::Window;
+use crate::script_runtime::{CanGc, JSContext};
+
+/// <https://cookiestore.spec.whatwg.org/#cookiechangeevent>
+#[dom_struct]
+pub(crate) struct CookieChangeEvent {
+    event: Event,
+    /// <https://cookiestore.spec.whatwg.org/#dom-cookiechangeevent-changed>
+    changed: Vec<CookieListItem>,
+    /// <https://cookiestore.spec.whatwg.org/#dom-cookiechangeevent-deleted>
+    deleted: Vec<CookieListItem>,
+    #[ignore_malloc_size_of = "mozjs"]
+    frozen_changed: CachedFrozenArray,
+    #[ignore_malloc_size_of = "mozjs"]
+    frozen_deleted: CachedFrozenArray,
+}
+
+impl CookieChangeEvent {
+    fn new_inherited(
+        changed: Vec<CookieListItem>,
+        deleted: Vec<CookieListItem>,
+    ) -> CookieChangeEvent {
+        CookieChangeEvent {
+            event: Event::new_inherited(),
+            changed,
+            deleted,
+            frozen_changed: CachedFrozenArray::new(),
+            frozen_deleted: CachedFrozenArray::new(),
+        }
+    }
+
+    pub(crate) fn new(
+        window: &Window,
+        type_: Atom,
+        changed: Vec<CookieListItem>,
+        deleted: Vec<CookieListItem>,
+        can_gc: CanGc,
+    ) -> DomRoot<CookieChangeEvent> {
+        Self::new_with_proto(
+            window,
+            None,
+            type_,
+            EventBubbles::DoesNotBubble,
+            EventCancelable::NotCancelable,
+            changed,
+            deleted,
+            can_gc,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn new_with_proto(
+        window: &Window,
+        proto: Option<HandleObject>,
+        type_: Atom,
+        bubbles: EventBubbles,
+        cancelable: EventCancelable,
+        changed: Vec<CookieListItem>,
+        deleted: Vec<CookieListItem>,
+        can_gc: CanGc,
+    ) -> DomRoot<CookieChangeEvent> {
+        let ev = reflect_dom_object_with_proto(
+            Box::new(CookieChangeEvent::new_inherited(changed, deleted)),
+            window,
+            proto,
+            can_gc,
+        );
+        {
+            let event = ev.upcast::<Event>();
+            event.init_event(type_, bool::from(bubbles), bool::from(cancelable));
+        }
+        ev
+    }
+}
+
+impl CookieChangeEventMethods<crate::DomTypeHolder> for CookieChangeEvent {
+    /// <https://cookiestore.spec.whatwg.org/#dom-cookiechangeevent-cookiechangeevent>
+    fn Constructor(
+        window: &Window,
+        proto: Option<HandleObject>,
+        can_gc: CanGc,
+        type_: DOMString,
+        init: &CookieChangeEventInit,
+    ) -> Fallible<DomRoot<CookieChangeEvent>> {
+        Ok(CookieChangeEvent::new_with_proto(
+            window,
+            proto,
+            Atom::from(type_),
+            EventBubbles::from(init.parent.bubbles),
+            EventCancelable::from(init.parent.cancelable),
+            init.changed.clone().unwrap_or_default(),
+            init.deleted.clone().unwrap_or_default(),
+            can_gc,
+        ))
+    }
+
+    /// <https://cookiestore.spec.whatwg.org/#dom-cookiechangeevent-changed>
+    fn Changed(&self, cx: JSContext, can_gc: CanGc, retval: MutableHandleValue) {
+        self.frozen_changed
+            .get_or_init(|| self.changed.clone(), cx, retval, can_gc);
+    }
+
+    /// <https://cookiestore.spec.whatwg.org/#dom-cookiechangeevent-deleted>
+    fn Deleted(&self, cx: JSContext, can_gc: CanGc, retval: MutableHandleValue) {
+        self.frozen_deleted
+            .get_or_init(|| self.deleted.clone(), cx, retval, can_gc);
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-event-istrusted>
+    fn IsTrusted(&self) -> bool {
+        self.event.IsTrusted()
+    }
+}
@@ -0,0 +1,510 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use base::id::CookieStoreId;
+use cookie::{Cookie, CookieBuilder, Expiration, SameSite};
+use dom_struct::dom_struct;
+use hyper_serde::Serde;
+use ipc_channel::ipc;
+use ipc_channel::router::ROUTER;
+use net_traits::{CookieAsyncResponse, CookieChange, CookieData, CookieSource, CoreResourceMsg};
+use servo_url::ServoUrl;
+use stylo_atoms::Atom;
+use time::OffsetDateTime;
+use url::Position;
+
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::CookieStoreBinding::{
+    CookieInit, CookieListItem, CookieSameSite, CookieStoreDeleteOptions, CookieStoreGetOptions,
+    CookieStoreMethods,
+};
+use crate::dom::bindings::error::Error;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::num::Finite;
+use crate::dom::bindings::refcounted::Trusted;
+use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::str::USVString;
+use crate::dom::cookiechangeevent::CookieChangeEvent;
+use crate::dom::event::Event;
+use crate::dom::eventtarget::EventTarget;
+use crate::dom::extendablecookiechangeevent::ExtendableCookieChangeEvent;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::promise::Promise;
+use crate::dom::serviceworkerglobalscope::ServiceWorkerGlobalScope;
+use crate::dom::window::Window;
+use crate::script_runtime::CanGc;
+
+/// <https://cookiestore.spec.whatwg.org/#cookie-maximum-name-value-pair-size>
+const MAX_NAME_VALUE_PAIR_SIZE: usize = 4096;
+/// <https://cookiestore.spec.whatwg.org/#cookie-maximum-attribute-value-size>
+const MAX_ATTRIBUTE_VALUE_SIZE: usize = 1024;
+
+/// <https://cookiestore.spec.whatwg.org/#cookiestore>
+///
+/// Unlike `document.cookie`, all operations are asynchronous: they are sent to the resource
+/// thread, which answers them in order over a channel that also carries notifications of
+/// changes to the cookies visible to this store.
+#[dom_struct]
+pub(crate) struct CookieStore {
+    eventtarget: EventTarget,
+    /// The promises of the operations waiting for a response from the resource thread, in the
+    /// order in which the operations were sent.
+    #[ignore_malloc_size_of = "promises are hard"]
+    in_flight: DomRefCell<VecDeque<Rc<Promise>>>,
+    #[no_trace]
+    store_id: CookieStoreId,
+    /// Whether the resource thread still sends responses and change notifications to this store.
+    listening: Cell<bool>,
+}
+
+impl CookieStore {
+    fn new_inherited() -> CookieStore {
+        CookieStore {
+            eventtarget: EventTarget::new_inherited(),
+            in_flight: Default::default(),
+            store_id: CookieStoreId::new(),
+            listening: Cell::new(false),
+        }
+    }
+
+    pub(crate) fn new(global: &GlobalScope, can_gc: CanGc) -> DomRoot<CookieStore> {
+        let store = reflect_dom_object(Box::new(CookieStore::new_inherited()), global, can_gc);
+        store.setup_listener(global);
+        store
+    }
+
+    /// Registers this store with the resource thread, and routes its responses and change
+    /// notifications to tasks on the networking task source.
+    fn setup_listener(&self, global: &GlobalScope) {
+        let (sender, receiver) = ipc::channel::<CookieAsyncResponse>().unwrap();
+        let task_source = global.task_manager().networking_task_source().to_sendable();
+        let trusted = Trusted::new(self);
+        ROUTER.add_typed_route(
+            receiver,
+            Box::new(move |message| {
+                let Ok(response) = message else {
+                    return;
+                };
+                let trusted = trusted.clone();
+                task_source.queue(task!(cookie_store_response: move || {
+                    trusted.root().handle_response(response.data, CanGc::note());
+                }));
+            }),
+        );
+
+        // Service workers observe the cookies within their registration's scope, while
+        // documents observe those visible to their own URL.
+        let url = match global.downcast::<ServiceWorkerGlobalScope>() {
+            Some(worker) => worker.scope_url().clone(),
+            None => global.creation_url().clone(),
+        };
+        let _ = global
+            .core_resource_thread()
+            .send(CoreResourceMsg::NewCookieListener(
+                self.store_id,
+                sender,
+                url,
+                global.origin_and_ancestor_origins(),
+            ));
+        self.listening.set(true);
+    }
+
+    /// Stops the resource thread from sending responses and change notifications to this
+    /// store, when its global is torn down.
+    pub(crate) fn remove_listener(&self) {
+        if !self.listening.replace(false) {
+            return;
+        }
+        let _ = self
+            .global()
+            .core_resource_thread()
+            .send(CoreResourceMsg::RemoveCookieListener(self.store_id));
+    }
+
+    fn handle_response(&self, data: CookieData, can_gc: CanGc) {
+        if let CookieData::Change(change) = data {
+            self.dispatch_change_event(change, can_gc);
+            return;
+        }
+
+        let Some(promise) = self.in_flight.borrow_mut().pop_front() else {
+            warn!("Received a cookie store response without a pending operation");
+            return;
+        };
+        match data {
+            CookieData::Get(cookie) => {
+                let item = cookie.map(|cookie| create_a_cookie_list_item(&cookie));
+                promise.resolve_native(&item, can_gc);
+            },
+            CookieData::GetAll(cookies) => {
+                let items: Vec<_> = cookies.iter().map(create_a_cookie_list_item).collect();
+                promise.resolve_native(&items, can_gc);
+            },
+            CookieData::Set(true) => promise.resolve_native(&(), can_gc),
+            CookieData::Set(false) => promise.reject_error(
+                Error::Type("The cookie could not be stored".to_owned()),
+                can_gc,
+            ),
+            CookieData::Change(_) => unreachable!(),
+        }
+    }
+
+    /// <https://cookiestore.spec.whatwg.org/#process-cookie-changes>
+    ///
+    /// Service workers are notified of every change within their scope, rather than only of
+    /// those they subscribed to through `CookieStoreManager`, which is not supported yet.
+    fn dispatch_change_event(&self, change: CookieChange, can_gc: CanGc) {
+        let changed: Vec<_> = change
+            .changed
+            .iter()
+            .map(create_a_cookie_list_item)
+            .collect();
+        let deleted: Vec<_> = change
+            .deleted
+            .iter()
+            .map(|cookie| CookieListItem {
+                name: Some(USVString(cookie.name().to_owned())),
+                value: None,
+            })
+            .collect();
+
+        let global = self.global();
+        if let Some(window) = global.downcast::<Window>() {
+            let event = CookieChangeEvent::new(window, atom!("change"), changed, deleted, can_gc);
+            event.upcast::<Event>().fire(self.upcast(), can_gc);
+        } else if let Some(worker) = global.downcast::<ServiceWorkerGlobalScope>() {
+            let event = ExtendableCookieChangeEvent::new(
+                worker,
+                Atom::from("cookiechange"),
+                changed,
+                deleted,
+                can_gc,
+            );
+            event.upcast::<Event>().fire(worker.upcast(), can_gc);
+        }
+    }
+
+    /// Returns a promise that is rejected with `error`.
+    fn reject(&self, error: Error, can_gc: CanGc) -> Rc<Promise> {
+        let promise = Promise::new(&self.global(), can_gc);
+        promise.reject_error(error, can_gc);
+        promise
+    }
+
+    /// Sends `message` to the resource thread, returning a promise settled with its response.
+    fn send(&self, message: CoreResourceMsg, can_gc: CanGc) -> Rc<Promise> {
+        let global = self.global();
+        let promise = Promise::new(&global, can_gc);
+        if global.core_resource_thread().send(message).is_err() {
+            promise.reject_error(Error::Operation, can_gc);
+            return promise;
+        }
+        self.in_flight.borrow_mut().push_back(promise.clone());
+        promise
+    }
+
+    /// Steps shared by `get(options)` and `getAll(options)`, returning the URL to query.
+    /// <https://cookiestore.spec.whatwg.org/#dom-cookiestore-get-options>
+    fn url_for_options(&self, options: &CookieStoreGetOptions) -> Result<ServoUrl, Error> {
+        let global = self.global();
+        // Step 3. If origin is an opaque origin, then return a promise rejected with a
+        // "SecurityError" DOMException.
+        if !global.origin().is_tuple() {
+            return Err(Error::Security);
+        }
+
+        // Step 4. Let url be settings’s creation URL.
+        let url = global.creation_url().clone();
+
+        // Step 6. If options["url"] is present, then run these steps:
+        let Some(options_url) = &options.url else {
+            return Ok(url);
+        };
+        // Step 6.1. Let parsed be the result of parsing options["url"] with settings’s API
+        // base URL.
+        let parsed = global
+            .api_base_url()
+            .join(options_url)
+            .map_err(|_| Error::Type("The url could not be parsed".to_owned()))?;
+        // Step 6.2. If this’s relevant global object is a Window object and parsed does not
+        // equal url with exclude fragments set to true, then return a promise rejected with
+        // a TypeError.
+        if global.is::<Window>() && parsed[..Position::AfterQuery] != url[..Position::AfterQuery] {
+            return Err(Error::Type(
+                "The url does not match the document URL".to_owned(),
+            ));
+        }
+        // Step 6.3. If parsed’s origin and url’s origin are not the same origin, then return a
+        // promise rejected with a TypeError.
+        if parsed.origin() != url.origin() {
+            return Err(Error::Type("The url is not same origin".to_owned()));
+        }
+        // Step 6.4. Set url to parsed.
+        Ok(parsed)
+    }
+
+    /// Sends the result of <https://cookiestore.spec.whatwg.org/#set-a-cookie> to the resource
+    /// thread, or rejects if it is failure.
+    fn set_a_cookie(&self, cookie: Option<Cookie<'static>>, can_gc: CanGc) -> Rc<Promise> {
+        let global = self.global();
+        if !global.origin().is_tuple() {
+            return self.reject(Error::Security, can_gc);
+        }
+        let Some(cookie) = cookie else {
+            return self.reject(Error::Type("The cookie is invalid".to_owned()), can_gc);
+        };
+        self.send(
+            CoreResourceMsg::SetCookieForUrlAsync(
+                self.store_id,
+                global.creation_url().clone(),
+                Serde(cookie),
+                CookieSource::NonHTTP,
+            ),
+            can_gc,
+        )
+    }
+}
+
+/// <https://cookiestore.spec.whatwg.org/#create-a-cookielistitem>
+fn create_a_cookie_list_item(cookie: &Serde<Cookie<'static>>) -> CookieListItem {
+    CookieListItem {
+        name: Some(USVString(cookie.name().to_owned())),
+        value: Some(USVString(cookie.value().to_owned())),
+    }
+}
+
+/// <https://cookiestore.spec.whatwg.org/#set-a-cookie>, returning `None` for failure.
+#[allow(clippy::too_many_arguments)]
+fn create_a_cookie(
+    url: &ServoUrl,
+    name: &str,
+    value: &str,
+    expires: Option<OffsetDateTime>,
+    domain: Option<&str>,
+    path: &str,
+    same_site: CookieSameSite,
+    partitioned: bool,
+) -> Option<Cookie<'static>> {
+    // Step 1. If name or value contain U+003B (;), any C0 control character except U+0009
+    // TAB, or U+007F DELETE, then return failure.
+    let is_invalid = |c: char| c == ';' || c == '\u{7F}' || (c.is_ascii_control() && c != '\t');
+    if name.contains(is_invalid) || value.contains(is_invalid) {
+        return None;
+    }
+    // Step 2. If name contains U+003D (=), then return failure.
+    if name.contains('=') {
+        return None;
+    }
+    // Step 3. If name’s length is 0 and value’s length is 0, then return failure.
+    if name.is_empty() && value.is_empty() {
+        return None;
+    }
+    // Step 4. If name and value together are longer than the cookie maximum name/value pair
+    // size, then return failure.
+    if name.len() + value.len() > MAX_NAME_VALUE_PAIR_SIZE {
+        return None;
+    }
+    // Step 5. If name case-insensitively starts with "__Host-" and either domain is not null
+    // or path is not "/", then return failure.
+    let lowercase_name = name.to_ascii_lowercase();
+    if lowercase_name.starts_with("__host-") && (domain.is_some() || path != "/") {
+        return None;
+    }
+
+    let mut cookie = CookieBuilder::new(name.to_owned(), value.to_owned());
+
+    // Step 7. Let host be url’s host.
+    let host = url.host_str().unwrap_or_default();
+    // Step 8. If domain is not null, then run these steps:
+    if let Some(domain) = domain {
+        // Step 8.1. If domain starts with U+002E (.), then return failure.
+        if domain.starts_with('.') {
+            return None;
+        }
+        // Step 8.2. If host does not equal domain and host does not end with U+002E (.)
+        // followed by domain, then return failure.
+        if host != domain && !host.ends_with(&format!(".{domain}")) {
+            return None;
+        }
+        // Step 8.3. If domain is longer than the cookie maximum attribute value size, then
+        // return failure.
+        if domain.len() > MAX_ATTRIBUTE_VALUE_SIZE {
+            return None;
+        }
+        cookie = cookie.domain(domain.to_owned());
+    }
+
+    // Step 9. If expires is given, then append `Expires` with the serialized date.
+    if let Some(expires) = expires {
+        cookie = cookie.expires(Expiration::DateTime(expires));
+    }
+
+    // Step 10. If path does not start with U+002F (/), then return failure.
+    if !path.starts_with('/') {
+        return None;
+    }
+    // Step 11. If path is longer than the cookie maximum attribute value size, then return
+    // failure.
+    if path.len() > MAX_ATTRIBUTE_VALUE_SIZE {
+        return None;
+    }
+    cookie = cookie.path(path.to_owned());
+
+    // Step 12. Append `Secure`.
+    cookie = cookie.secure(true);
+
+    // Step 13. Append `SameSite` with sameSite.
+    cookie = cookie.same_site(match same_site {
+        CookieSameSite::Strict => SameSite::Strict,
+        CookieSameSite::Lax => SameSite::Lax,
+        CookieSameSite::None => SameSite::None,
+    });
+
+    // Step 14. If partitioned is true, append `Partitioned`.
+    if partitioned {
+        cookie = cookie.partitioned(true);
+    }
+
+    Some(cookie.build())
+}
+
+/// Converts a `DOMHighResTimeStamp` in milliseconds since the epoch to a date.
+fn timestamp_to_date(timestamp: Finite<f64>) -> Option<OffsetDateTime> {
+    OffsetDateTime::from_unix_timestamp_nanos((*timestamp * 1e6) as i128).ok()
+}
+
+impl CookieStoreMethods<crate::DomTypeHolder> for CookieStore {
+    /// <https://cookiestore.spec.whatwg.org/#dom-cookiestore-get>
+    fn Get(&self, name: USVString, can_gc: CanGc) -> Rc<Promise> {
+        let options = CookieStoreGetOptions {
+            name: Some(name),
+            url: None,
+        };
+        self.Get_(&options, can_gc)
+    }
+
+    /// <https://cookiestore.spec.whatwg.org/#dom-cookiestore-get-options>
+    fn Get_(&self, options: &CookieStoreGetOptions, can_gc: CanGc) -> Rc<Promise> {
+        // Step 5. If options is empty, then return a promise rejected with a TypeError.
+        if options.name.is_none() && options.url.is_none() {
+            return self.reject(Error::Type("The options are empty".to_owned()), can_gc);
+        }
+        let url = match self.url_for_options(options) {
+            Ok(url) => url,
+            Err(error) => return self.reject(error, can_gc),
+        };
+        // Step 8. Run the following steps in parallel: query cookies with url and
+        // options["name"], resolving p with the first item of the list or null.
+        self.send(
+            CoreResourceMsg::GetCookieDataForUrlAsync(
+                self.store_id,
+                url,
+                options.name.as_ref().map(|name| name.0.clone()),
+            ),
+            can_gc,
+        )
+    }
+
+    /// <https://cookiestore.spec.whatwg.org/#dom-cookiestore-getall>
+    fn GetAll(&self, name: USVString, can_gc: CanGc) -> Rc<Promise> {
+        let options = CookieStoreGetOptions {
+            name: Some(name),
+            url: None,
+        };
+        self.GetAll_(&options, can_gc)
+    }
+
+    /// <https://cookiestore.spec.whatwg.org/#dom-cookiestore-getall-options>
+    fn GetAll_(&self, options: &CookieStoreGetOptions, can_gc: CanGc) -> Rc<Promise> {
+        let url = match self.url_for_options(options) {
+            Ok(url) => url,
+            Err(error) => return self.reject(error, can_gc),
+        };
+        // Step 7. Run the following steps in parallel: query cookies with url and
+        // options["name"], resolving p with the list.
+        self.send(
+            CoreResourceMsg::GetAllCookieDataForUrlAsync(
+                self.store_id,
+                url,
+                options.name.as_ref().map(|name| name.0.clone()),
+            ),
+            can_gc,
+        )
+    }
+
+    /// <https://cookiestore.spec.whatwg.org/#dom-cookiestore-set>
+    fn Set(&self, name: USVString, value: USVString, can_gc: CanGc) -> Rc<Promise> {
+        let cookie = create_a_cookie(
+            self.global().creation_url(),
+            &name,
+            &value,
+            None,
+            None,
+            "/",
+            CookieSameSite::Strict,
+            false,
+        );
+        self.set_a_cookie(cookie, can_gc)
+    }
+
+    /// <https://cookiestore.spec.whatwg.org/#dom-cookiestore-set-options>
+    fn Set_(&self, options: &CookieInit, can_gc: CanGc) -> Rc<Promise> {
+        let expires = match options.expires {
+            Some(expires) => match timestamp_to_date(expires) {
+                Some(date) => Some(date),
+                None => {
+                    return self
+                        .reject(Error::Type("The expiry date is invalid".to_owned()), can_gc);
+                },
+            },
+            None => None,
+        };
+        let cookie = create_a_cookie(
+            self.global().creation_url(),
+            &options.name,
+            &options.value,
+            expires,
+            options.domain.as_deref(),
+            &options.path,
+            options.sameSite,
+            options.partitioned,
+        );
+        self.set_a_cookie(cookie, can_gc)
+    }
+
+    /// <https://cookiestore.spec.whatwg.org/#dom-cookiestore-delete>
+    fn Delete(&self, name: USVString, can_gc: CanGc) -> Rc<Promise> {
+        let options = CookieStoreDeleteOptions {
+            name,
+            domain: None,
+            path: USVString("/".to_owned()),
+            partitioned: false,
+        };
+        self.Delete_(&options, can_gc)
+    }
+
+    /// <https://cookiestore.spec.whatwg.org/#dom-cookiestore-delete-options>
+    fn Delete_(&self, options: &CookieStoreDeleteOptions, can_gc: CanGc) -> Rc<Promise> {
+        // To delete a cookie, set a cookie with an empty value and an expiry date in the past.
+        let cookie = create_a_cookie(
+            self.global().creation_url(),
+            &options.name,
+            "",
+            Some(OffsetDateTime::UNIX_EPOCH),
+            options.domain.as_deref(),
+            &options.path,
+            CookieSameSite::Strict,
+            options.partitioned,
+        );
+        self.set_a_cookie(cookie, can_gc)
+    }
+
+    // https://cookiestore.spec.whatwg.org/#dom-cookiestore-onchange
+    event_handler!(change, GetOnchange, SetOnchange);
+}
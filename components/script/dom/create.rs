@@ -10,7 +10,7 @@ use crate::dom::bindings::error::{report_pending_exception, throw_dom_exception}
 use crate::dom::bindings::reflector::DomGlobal;
 use crate::dom::bindings::root::DomRoot;
 use crate::dom::customelementregistry::{
-    CustomElementState, is_valid_custom_element_name, upgrade_element,
+    CustomElementRegistry, CustomElementState, is_valid_custom_element_name, upgrade_element,
 };
 use crate::dom::document::Document;
 use crate::dom::element::{CustomElementCreationMode, Element, ElementCreator};
@@ -84,6 +84,7 @@ use crate::dom::htmltrackelement::HTMLTrackElement;
 use crate::dom::htmlulistelement::HTMLUListElement;
 use crate::dom::htmlunknownelement::HTMLUnknownElement;
 use crate::dom::htmlvideoelement::HTMLVideoElement;
+use crate::dom::mathmlelement::MathMLElement;
 use crate::dom::svgelement::SVGElement;
 use crate::dom::svgimageelement::SVGImageElement;
 use crate::dom::svgsvgelement::SVGSVGElement;
@@ -121,6 +122,27 @@ fn create_svg_element(
     }
 }
 
+fn create_mathml_element(
+    name: QualName,
+    prefix: Option<Prefix>,
+    document: &Document,
+    proto: Option<HandleObject>,
+) -> DomRoot<Element> {
+    assert_eq!(name.ns, ns!(mathml));
+
+    if !pref!(dom_mathml_enabled) {
+        return Element::new(name.local, name.ns, prefix, document, proto, CanGc::note());
+    }
+
+    DomRoot::upcast(MathMLElement::new(
+        name.local,
+        prefix,
+        document,
+        proto,
+        CanGc::note(),
+    ))
+}
+
 /// <https://dom.spec.whatwg.org/#concept-create-element>
 #[allow(unsafe_code)]
 #[allow(clippy::too_many_arguments)]
@@ -129,6 +151,7 @@ fn create_html_element(
     prefix: Option<Prefix>,
     is: Option<LocalName>,
     document: &Document,
+    registry: Option<&CustomElementRegistry>,
     creator: ElementCreator,
     mode: CustomElementCreationMode,
     proto: Option<HandleObject>,
@@ -138,7 +161,8 @@ fn create_html_element(
 
     // Step 2. Let definition be the result of looking up a custom element
     // definition given document, namespace, localName, and is.
-    let definition = document.lookup_custom_element_definition(&name.ns, &name.local, is.as_ref());
+    let definition =
+        document.lookup_custom_element_definition(&name.ns, &name.local, is.as_ref(), registry);
 
     // Step 3. If definition is non-null...
     if let Some(definition) = definition {
@@ -158,8 +182,20 @@ fn create_html_element(
                 // Step 3.3. If synchronousCustomElements is true, then run this step while catching any exceptions:
                 CustomElementCreationMode::Synchronous => {
                     // Step 3.3.1. Upgrade result using definition.
-                    upgrade_element(definition, &element, can_gc);
-                    // TODO: "If this step threw an exception exception:" steps.
+                    if let Err(error) = upgrade_element(definition, &element, can_gc) {
+                        // If this step threw an exception exception:
+                        let global = GlobalScope::current().unwrap_or_else(|| document.global());
+                        let cx = GlobalScope::get_cx();
+
+                        // Substep 1. Report exception for definition’s constructor’s corresponding
+                        // JavaScript object’s associated realm’s global object.
+                        let ar = enter_realm(&*global);
+                        throw_dom_exception(cx, &global, error, can_gc);
+                        report_pending_exception(cx, true, InRealm::Entered(&ar), can_gc);
+
+                        // Substep 2. Set result’s custom element state to "failed".
+                        element.set_custom_element_state(CustomElementState::Failed);
+                    }
                 },
                 // Step 3.4. Otherwise, enqueue a custom element upgrade reaction given result and definition.
                 CustomElementCreationMode::Asynchronous => {
@@ -175,8 +211,23 @@ fn create_html_element(
                 CustomElementCreationMode::Synchronous => {
                     let local_name = name.local.clone();
                     //TODO(jdm) Pass proto to create_element?
+                    // The constructor of a scoped definition can only be found while it is running.
+                    if let Some(registry) = registry {
+                        document
+                            .window()
+                            .push_active_scoped_custom_element_constructor(
+                                registry,
+                                definition.clone(),
+                            );
+                    }
                     // Steps 4.1.1-4.1.11
-                    return match definition.create_element(document, prefix.clone(), can_gc) {
+                    let result = definition.create_element(document, prefix.clone(), can_gc);
+                    if registry.is_some() {
+                        document
+                            .window()
+                            .pop_active_scoped_custom_element_constructor();
+                    }
+                    return match result {
                         Ok(element) => {
                             element.set_custom_element_definition(definition.clone());
                             element
@@ -431,19 +482,29 @@ pub(crate) fn create_native_html_element(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn create_element(
     name: QualName,
     is: Option<LocalName>,
     document: &Document,
+    registry: Option<&CustomElementRegistry>,
     creator: ElementCreator,
     mode: CustomElementCreationMode,
     proto: Option<HandleObject>,
     can_gc: CanGc,
 ) -> DomRoot<Element> {
     let prefix = name.prefix.clone();
-    match name.ns {
-        ns!(html) => create_html_element(name, prefix, is, document, creator, mode, proto, can_gc),
+    let result = match name.ns {
+        ns!(html) => create_html_element(
+            name, prefix, is, document, registry, creator, mode, proto, can_gc,
+        ),
         ns!(svg) => create_svg_element(name, prefix, document, proto),
+        ns!(mathml) => create_mathml_element(name, prefix, document, proto),
         _ => Element::new(name.local, name.ns, prefix, document, proto, can_gc),
+    };
+    // Remember the scoped registry so that later upgrades of result look in it.
+    if let Some(registry) = registry {
+        result.set_scoped_custom_element_registry(registry);
     }
+    result
 }
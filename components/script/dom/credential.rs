@@ -0,0 +1,41 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+
+use crate::dom::bindings::codegen::Bindings::CredentialBinding::CredentialMethods;
+use crate::dom::bindings::reflector::Reflector;
+use crate::dom::bindings::str::{DOMString, USVString};
+
+/// <https://w3c.github.io/webappsec-credential-management/#credential>
+#[dom_struct]
+pub(crate) struct Credential {
+    reflector_: Reflector,
+    /// <https://w3c.github.io/webappsec-credential-management/#dom-credential-id>
+    id: USVString,
+    /// <https://w3c.github.io/webappsec-credential-management/#dom-credential-type>
+    type_: DOMString,
+}
+
+impl Credential {
+    pub(crate) fn new_inherited(id: USVString, type_: DOMString) -> Self {
+        Self {
+            reflector_: Reflector::new(),
+            id,
+            type_,
+        }
+    }
+}
+
+impl CredentialMethods<crate::DomTypeHolder> for Credential {
+    /// <https://w3c.github.io/webappsec-credential-management/#dom-credential-id>
+    fn Id(&self) -> USVString {
+        self.id.clone()
+    }
+
+    /// <https://w3c.github.io/webappsec-credential-management/#dom-credential-type>
+    fn Type(&self) -> DOMString {
+        self.type_.clone()
+    }
+}
@@ -0,0 +1,193 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::rc::Rc;
+
+use dom_struct::dom_struct;
+use js::jsval::UndefinedValue;
+use net_traits::IpcSend;
+use net_traits::storage_thread::StorageThreadMsg;
+use servo_config::pref;
+
+use crate::dom::abortsignal::AbortSignal;
+use crate::dom::bindings::codegen::Bindings::CredentialBinding::{
+    CredentialCreationOptions, CredentialRequestOptions,
+};
+use crate::dom::bindings::codegen::Bindings::CredentialsContainerBinding::CredentialsContainerMethods;
+use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::error::Error;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::{DomGlobal, Reflector, reflect_dom_object};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::trace::RootedTraceableBox;
+use crate::dom::credential::Credential;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::passwordcredential::PasswordCredential;
+use crate::dom::promise::Promise;
+use crate::dom::publickeycredential::PublicKeyCredential;
+use crate::script_runtime::CanGc;
+
+/// <https://w3c.github.io/webappsec-credential-management/#credentialscontainer>
+#[dom_struct]
+pub(crate) struct CredentialsContainer {
+    reflector_: Reflector,
+}
+
+impl CredentialsContainer {
+    fn new_inherited() -> Self {
+        Self {
+            reflector_: Reflector::new(),
+        }
+    }
+
+    pub(crate) fn new(global: &GlobalScope, can_gc: CanGc) -> DomRoot<Self> {
+        reflect_dom_object(Box::new(Self::new_inherited()), global, can_gc)
+    }
+
+    /// The steps shared by `create()` and `get()` before the operation of the requested
+    /// credential type is started. Returns false if `promise` has already been rejected.
+    fn check_operation_allowed(
+        &self,
+        signal: Option<&AbortSignal>,
+        promise: &Promise,
+        can_gc: CanGc,
+    ) -> bool {
+        // If this’s relevant global object’s associated Document is not fully active,
+        // return a promise rejected with an "InvalidStateError" DOMException.
+        if !self.global().as_window().Document().is_fully_active() {
+            promise.reject_error(Error::InvalidState, can_gc);
+            return false;
+        }
+
+        // If options.signal is aborted, then return a promise rejected with options.signal’s
+        // abort reason.
+        if let Some(signal) = signal.filter(|signal| signal.aborted()) {
+            let cx = GlobalScope::get_cx();
+            rooted!(in(*cx) let mut reason = UndefinedValue());
+            reason.set(signal.abort_reason());
+            promise.reject(cx, reason.handle(), can_gc);
+            return false;
+        }
+
+        true
+    }
+}
+
+impl CredentialsContainerMethods<crate::DomTypeHolder> for CredentialsContainer {
+    /// <https://w3c.github.io/webappsec-credential-management/#dom-credentialscontainer-get>
+    fn Get(
+        &self,
+        options: RootedTraceableBox<CredentialRequestOptions>,
+        can_gc: CanGc,
+    ) -> Rc<Promise> {
+        let global = self.global();
+        let promise = Promise::new(&global, can_gc);
+        let signal = options.signal.as_deref();
+        if !self.check_operation_allowed(signal, &promise, can_gc) {
+            return promise;
+        }
+
+        if let Some(public_key) = options
+            .publicKey
+            .as_ref()
+            .filter(|_| pref!(dom_webauthn_enabled))
+        {
+            if let Err(error) = PublicKeyCredential::discover_from_external_source(
+                global.as_window(),
+                public_key,
+                signal,
+                &promise,
+            ) {
+                promise.reject_error(error, can_gc);
+            }
+        } else if options.password {
+            PasswordCredential::discover_from_credential_store(
+                global.as_window(),
+                options.mediation,
+                &promise,
+                can_gc,
+            );
+        } else {
+            promise.reject_error(Error::NotSupported, can_gc);
+        }
+        promise
+    }
+
+    /// <https://w3c.github.io/webappsec-credential-management/#dom-credentialscontainer-store>
+    fn Store(&self, credential: &Credential, can_gc: CanGc) -> Rc<Promise> {
+        let promise = Promise::new(&self.global(), can_gc);
+        if !self.global().as_window().Document().is_fully_active() {
+            promise.reject_error(Error::InvalidState, can_gc);
+            return promise;
+        }
+
+        // Only password credentials can be stored.
+        // <https://w3c.github.io/webauthn/#sctn-storeCredential>
+        match credential.downcast::<PasswordCredential>() {
+            Some(password_credential) => {
+                password_credential.store(self.global().as_window(), &promise);
+            },
+            None => promise.reject_error(Error::NotSupported, can_gc),
+        }
+        promise
+    }
+
+    /// <https://w3c.github.io/webappsec-credential-management/#dom-credentialscontainer-create>
+    fn Create(
+        &self,
+        options: RootedTraceableBox<CredentialCreationOptions>,
+        can_gc: CanGc,
+    ) -> Rc<Promise> {
+        let global = self.global();
+        let promise = Promise::new(&global, can_gc);
+        let signal = options.signal.as_deref();
+        if !self.check_operation_allowed(signal, &promise, can_gc) {
+            return promise;
+        }
+
+        // Exactly one type of credential must be requested.
+        let public_key = options
+            .publicKey
+            .as_ref()
+            .filter(|_| pref!(dom_webauthn_enabled));
+        match (public_key, options.password.as_ref()) {
+            (Some(public_key), None) => {
+                if let Err(error) =
+                    PublicKeyCredential::create(global.as_window(), public_key, signal, &promise)
+                {
+                    promise.reject_error(error, can_gc);
+                }
+            },
+            (None, Some(password)) => {
+                match PasswordCredential::new_from_data(&global, None, password, can_gc) {
+                    Ok(credential) => promise.resolve_native(&credential, can_gc),
+                    Err(error) => promise.reject_error(error, can_gc),
+                }
+            },
+            _ => promise.reject_error(Error::NotSupported, can_gc),
+        }
+        promise
+    }
+
+    /// <https://w3c.github.io/webappsec-credential-management/#dom-credentialscontainer-preventsilentaccess>
+    fn PreventSilentAccess(&self, can_gc: CanGc) -> Rc<Promise> {
+        let promise = Promise::new(&self.global(), can_gc);
+        if !self.global().as_window().Document().is_fully_active() {
+            promise.reject_error(Error::InvalidState, can_gc);
+            return promise;
+        }
+
+        // Set the prevent silent access flag of the origin, so that its credentials are only
+        // handed out after the user picked them.
+        let global = self.global();
+        let _ = global
+            .resource_threads()
+            .send(StorageThreadMsg::SetPreventSilentAccess(
+                global.origin().immutable().clone(),
+                true,
+            ));
+        promise.resolve_native(&(), can_gc);
+        promise
+    }
+}
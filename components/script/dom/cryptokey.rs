@@ -29,6 +29,19 @@ pub(crate) enum Handle {
     Pbkdf2(Vec<u8>),
     Hkdf(Vec<u8>),
     Hmac(Vec<u8>),
+    /// An RSA public key, in spki format
+    RsaPublicKey(Vec<u8>),
+    /// An RSA private key, in pkcs8 format
+    RsaPrivateKey(Vec<u8>),
+    /// An elliptic curve public key, as an uncompressed point
+    EcPublicKey(Vec<u8>),
+    /// An elliptic curve private key, in pkcs8 format
+    EcPrivateKey(Vec<u8>),
+    Ed25519PublicKey(Vec<u8>),
+    /// The 32 byte seed of an Ed25519 private key
+    Ed25519PrivateKey(Vec<u8>),
+    X25519PublicKey(Vec<u8>),
+    X25519PrivateKey(Vec<u8>),
 }
 
 /// <https://w3c.github.io/webcrypto/#cryptokey-interface>
@@ -152,6 +165,14 @@ impl Handle {
             Self::Pbkdf2(bytes) => bytes,
             Self::Hkdf(bytes) => bytes,
             Self::Hmac(bytes) => bytes,
+            Self::RsaPublicKey(bytes) => bytes,
+            Self::RsaPrivateKey(bytes) => bytes,
+            Self::EcPublicKey(bytes) => bytes,
+            Self::EcPrivateKey(bytes) => bytes,
+            Self::Ed25519PublicKey(bytes) => bytes,
+            Self::Ed25519PrivateKey(bytes) => bytes,
+            Self::X25519PublicKey(bytes) => bytes,
+            Self::X25519PrivateKey(bytes) => bytes,
         }
     }
 }
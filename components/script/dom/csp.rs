@@ -7,16 +7,19 @@ use std::borrow::Cow;
 use constellation_traits::{LoadData, LoadOrigin};
 /// Used to determine which inline check to run
 pub use content_security_policy::InlineCheckType;
+/// Used to determine whether a navigation is a form submission
+pub use content_security_policy::NavigationCheckType;
 /// Used to report CSP violations in Fetch handlers
 pub use content_security_policy::Violation;
 use content_security_policy::{
-    CheckResult, CspList, Destination, Element as CspElement, Initiator, NavigationCheckType,
-    Origin, ParserMetadata, PolicyDisposition, PolicySource, Request, ViolationResource,
+    CheckResult, CspList, Destination, Element as CspElement, Initiator, Origin, ParserMetadata,
+    PolicyDisposition, PolicySource, Request, ViolationResource,
 };
 use http::header::{HeaderMap, HeaderValue, ValueIter};
 use hyper_serde::Serde;
 use js::rust::describe_scripted_caller;
 use log::warn;
+use servo_url::{ImmutableOrigin, ServoUrl};
 
 use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
 use crate::dom::bindings::inheritance::Castable;
@@ -36,6 +39,7 @@ pub(crate) trait CspReporting {
         global: &GlobalScope,
         load_data: &LoadData,
         element: Option<&Element>,
+        navigation_check_type: NavigationCheckType,
     ) -> bool;
     fn should_elements_inline_type_behavior_be_blocked(
         &self,
@@ -98,6 +102,7 @@ impl CspReporting for Option<CspList> {
         global: &GlobalScope,
         load_data: &LoadData,
         element: Option<&Element>,
+        navigation_check_type: NavigationCheckType,
     ) -> bool {
         let Some(csp_list) = self else {
             return false;
@@ -116,9 +121,8 @@ impl CspReporting for Option<CspList> {
             integrity_metadata: "".to_owned(),
             parser_metadata: ParserMetadata::None,
         };
-        // TODO: set correct navigation check type for form submission if applicable
         let (result, violations) =
-            csp_list.should_navigation_request_be_blocked(&request, NavigationCheckType::Other);
+            csp_list.should_navigation_request_be_blocked(&request, navigation_check_type);
 
         global.report_csp_violations(violations, element, None);
 
@@ -230,7 +234,7 @@ pub(crate) trait GlobalCspReporting {
 }
 
 #[allow(unsafe_code)]
-fn compute_scripted_caller_source_position() -> SourcePosition {
+pub(crate) fn compute_scripted_caller_source_position() -> SourcePosition {
     let scripted_caller =
         unsafe { describe_scripted_caller(*GlobalScope::get_cx()) }.unwrap_or_default();
 
@@ -352,3 +356,170 @@ pub(crate) fn parse_csp_list_from_metadata(headers: &Option<Serde<HeaderMap>>) -
         PolicyDisposition::Report,
     )
 }
+
+/// <https://www.w3.org/TR/CSP/#frame-ancestors-navigation-response>
+///
+/// `ancestor_origins` are the origins of the ancestor navigables of the navigable that the
+/// response is being loaded into, starting with its parent.
+pub(crate) fn is_navigation_response_blocked_by_frame_ancestors(
+    csp_list: &CspList,
+    response_url: &ServoUrl,
+    ancestor_origins: &[ImmutableOrigin],
+) -> bool {
+    // Step 1. If navigation response’s URL is local, return "Allowed".
+    if matches!(response_url.scheme(), "about" | "blob" | "data") {
+        return false;
+    }
+    csp_list
+        .0
+        .iter()
+        .filter(|policy| policy.disposition == PolicyDisposition::Enforce)
+        .flat_map(|policy| policy.directive_set.iter())
+        .filter(|directive| directive.name == "frame-ancestors")
+        .any(|directive| {
+            // Step 2. For each ancestor of target’s ancestor navigables: if the URL of
+            // ancestor’s origin does not match the directive’s source list, return "Blocked".
+            ancestor_origins.iter().any(|origin| {
+                !does_origin_match_source_list(origin, &directive.value, response_url)
+            })
+        })
+}
+
+/// Whether any enforced policy of the list has a `frame-ancestors` directive, in which case the
+/// `X-Frame-Options` header of the response is ignored.
+/// <https://html.spec.whatwg.org/multipage/#the-x-frame-options-header>
+pub(crate) fn has_enforced_frame_ancestors_directive(csp_list: &CspList) -> bool {
+    csp_list
+        .0
+        .iter()
+        .filter(|policy| policy.disposition == PolicyDisposition::Enforce)
+        .flat_map(|policy| policy.directive_set.iter())
+        .any(|directive| directive.name == "frame-ancestors")
+}
+
+/// <https://www.w3.org/TR/CSP/#match-url-to-source-list>, applied to the URL of an origin.
+/// Paths are ignored, since the URL of an origin never has one.
+fn does_origin_match_source_list(
+    origin: &ImmutableOrigin,
+    source_list: &[String],
+    self_url: &ServoUrl,
+) -> bool {
+    // An opaque origin serializes to "null", which does not parse as a URL.
+    let ImmutableOrigin::Tuple(scheme, host, port) = origin else {
+        return false;
+    };
+    let host = host.to_string();
+    // Step 2. If source list is [ "'none'" ], return "Does Not Match". An empty source list
+    // never matches either, since 'none' is not a match for anything.
+    source_list
+        .iter()
+        .any(|expression| does_origin_match_expression(scheme, &host, *port, expression, self_url))
+}
+
+/// <https://www.w3.org/TR/CSP/#match-url-to-source-expression>
+fn does_origin_match_expression(
+    scheme: &str,
+    host: &str,
+    port: u16,
+    expression: &str,
+    self_url: &ServoUrl,
+) -> bool {
+    // Step 1. If expression is the string "*", return "Matches" if url’s scheme is an
+    // HTTP(S) scheme or a WebSocket scheme, or is the same as the protected resource's.
+    if expression == "*" {
+        return matches!(scheme, "http" | "https" | "ws" | "wss") || scheme == self_url.scheme();
+    }
+
+    // Step 4. If expression matches the keyword-source "'self'", return "Matches" if
+    // url’s origin is the same as the protected resource's, allowing for secure upgrades.
+    if expression.eq_ignore_ascii_case("'self'") {
+        let ImmutableOrigin::Tuple(self_scheme, self_host, self_port) = self_url.origin() else {
+            return false;
+        };
+        return self_host.to_string() == host &&
+            does_scheme_part_match(&self_scheme, scheme) &&
+            (self_port == port || (self_scheme == "http" && scheme == "https" && port == 443));
+    }
+
+    // Step 2. If expression matches the scheme-source grammar, return the result of
+    // scheme-part matching its scheme-part against url’s scheme.
+    if let Some(expression_scheme) = expression.strip_suffix(':') {
+        if is_scheme_part(expression_scheme) {
+            return does_scheme_part_match(expression_scheme, scheme);
+        }
+    }
+
+    // Step 3. If expression matches the host-source grammar:
+    let (expression_scheme, rest) = match expression.split_once("://") {
+        Some((expression_scheme, rest)) if is_scheme_part(expression_scheme) => {
+            (Some(expression_scheme), rest)
+        },
+        _ => (None, expression),
+    };
+    // The path-part can be ignored, as the URL of an origin has no path.
+    let rest = rest.split('/').next().unwrap_or_default();
+    let (expression_host, expression_port) = match rest.rsplit_once(':') {
+        Some((expression_host, expression_port)) => (expression_host, Some(expression_port)),
+        None => (rest, None),
+    };
+
+    // Step 3.2. If expression has a scheme-part, and it does not scheme-part match url’s
+    // scheme, return "Does Not Match". If it does not have one, the protected resource’s
+    // scheme has to scheme-part match url’s scheme instead.
+    let scheme_matches = match expression_scheme {
+        Some(expression_scheme) => does_scheme_part_match(expression_scheme, scheme),
+        None => does_scheme_part_match(self_url.scheme(), scheme),
+    };
+    if !scheme_matches {
+        return false;
+    }
+
+    // Step 3.4. If expression’s host-part does not host-part match url’s host, return
+    // "Does Not Match".
+    let host_matches = match expression_host.strip_prefix("*.") {
+        Some(suffix) => host
+            .to_ascii_lowercase()
+            .ends_with(&format!(".{}", suffix.to_ascii_lowercase())),
+        None => expression_host.eq_ignore_ascii_case(host),
+    };
+    if !host_matches {
+        return false;
+    }
+
+    // Step 3.6. If expression does not port-part match url, return "Does Not Match".
+    let default_port = default_port_for_scheme(scheme);
+    match expression_port {
+        Some("*") => true,
+        Some(expression_port) => expression_port.parse::<u16>().is_ok_and(|expression_port| {
+            expression_port == port ||
+                (expression_port == 80 && port == 443 && matches!(scheme, "https" | "wss"))
+        }),
+        None => default_port == Some(port),
+    }
+}
+
+/// <https://www.w3.org/TR/CSP/#grammardef-scheme-part>
+fn is_scheme_part(value: &str) -> bool {
+    let mut chars = value.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic()) &&
+        chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// <https://www.w3.org/TR/CSP/#scheme-part-match>
+fn does_scheme_part_match(expression_scheme: &str, url_scheme: &str) -> bool {
+    let expression_scheme = expression_scheme.to_ascii_lowercase();
+    expression_scheme == url_scheme ||
+        matches!(
+            (expression_scheme.as_str(), url_scheme),
+            ("http", "https") | ("ws", "wss" | "http" | "https") | ("wss", "https")
+        )
+}
+
+fn default_port_for_scheme(scheme: &str) -> Option<u16> {
+    match scheme {
+        "http" | "ws" => Some(80),
+        "https" | "wss" => Some(443),
+        "ftp" => Some(21),
+        _ => None,
+    }
+}
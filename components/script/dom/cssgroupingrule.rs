@@ -16,6 +16,7 @@ use crate::dom::cssconditionrule::CSSConditionRule;
 use crate::dom::csslayerblockrule::CSSLayerBlockRule;
 use crate::dom::cssrule::CSSRule;
 use crate::dom::cssrulelist::{CSSRuleList, RulesSource};
+use crate::dom::cssscoperule::CSSScopeRule;
 use crate::dom::cssstylerule::CSSStyleRule;
 use crate::dom::cssstylesheet::CSSStyleSheet;
 use crate::script_runtime::CanGc;
@@ -41,6 +42,8 @@ impl CSSGroupingRule {
                 rule.clone_rules()
             } else if let Some(rule) = self.downcast::<CSSLayerBlockRule>() {
                 rule.clone_rules()
+            } else if let Some(rule) = self.downcast::<CSSScopeRule>() {
+                rule.clone_rules()
             } else if let Some(rule) = self.downcast::<CSSStyleRule>() {
                 rule.ensure_rules()
             } else {
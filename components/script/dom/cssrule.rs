@@ -22,6 +22,7 @@ use crate::dom::csslayerstatementrule::CSSLayerStatementRule;
 use crate::dom::cssmediarule::CSSMediaRule;
 use crate::dom::cssnamespacerule::CSSNamespaceRule;
 use crate::dom::cssnesteddeclarations::CSSNestedDeclarations;
+use crate::dom::cssscoperule::CSSScopeRule;
 use crate::dom::cssstylerule::CSSStyleRule;
 use crate::dom::cssstylesheet::CSSStyleSheet;
 use crate::dom::csssupportsrule::CSSSupportsRule;
@@ -72,6 +73,8 @@ impl CSSRule {
             rule as &dyn SpecificCSSRule
         } else if let Some(rule) = self.downcast::<CSSNestedDeclarations>() {
             rule as &dyn SpecificCSSRule
+        } else if let Some(rule) = self.downcast::<CSSScopeRule>() {
+            rule as &dyn SpecificCSSRule
         } else {
             unreachable!()
         }
@@ -125,9 +128,11 @@ impl CSSRule {
             StyleCssRule::FontPaletteValues(_) => unimplemented!(), // TODO
             StyleCssRule::Property(_) => unimplemented!(),          // TODO
             StyleCssRule::Margin(_) => unimplemented!(),            // TODO
-            StyleCssRule::Scope(_) => unimplemented!(),             // TODO
-            StyleCssRule::StartingStyle(_) => unimplemented!(),     // TODO
-            StyleCssRule::PositionTry(_) => unimplemented!(),       // TODO
+            StyleCssRule::Scope(s) => {
+                DomRoot::upcast(CSSScopeRule::new(window, parent_stylesheet, s, can_gc))
+            },
+            StyleCssRule::StartingStyle(_) => unimplemented!(), // TODO
+            StyleCssRule::PositionTry(_) => unimplemented!(),   // TODO
             StyleCssRule::NestedDeclarations(s) => DomRoot::upcast(CSSNestedDeclarations::new(
                 window,
                 parent_stylesheet,
@@ -26,18 +26,23 @@ use crate::dom::bindings::codegen::Bindings::CustomElementRegistryBinding::{
 use crate::dom::bindings::codegen::Bindings::ElementBinding::ElementMethods;
 use crate::dom::bindings::codegen::Bindings::FunctionBinding::Function;
 use crate::dom::bindings::codegen::Bindings::WindowBinding::Window_Binding::WindowMethods;
+use crate::dom::bindings::codegen::UnionTypes::DocumentOrShadowRoot as RegistryRoot;
 use crate::dom::bindings::conversions::{ConversionResult, StringificationBehavior};
 use crate::dom::bindings::error::{
     Error, ErrorResult, Fallible, report_pending_exception, throw_dom_exception,
 };
 use crate::dom::bindings::inheritance::Castable;
-use crate::dom::bindings::reflector::{DomGlobal, DomObject, Reflector, reflect_dom_object};
+use crate::dom::bindings::reflector::{
+    DomGlobal, DomObject, Reflector, reflect_dom_object, reflect_dom_object_with_proto,
+};
 use crate::dom::bindings::root::{AsHandleValue, Dom, DomRoot};
 use crate::dom::bindings::settings_stack::is_execution_stack_empty;
 use crate::dom::bindings::str::DOMString;
 use crate::dom::document::Document;
 use crate::dom::domexception::{DOMErrorName, DOMException};
 use crate::dom::element::Element;
+use crate::dom::elementinternals::SubmissionValue;
+use crate::dom::formdata::FormData;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::htmlelement::HTMLElement;
 use crate::dom::htmlformelement::{FormControl, HTMLFormElement};
@@ -74,27 +79,37 @@ pub(crate) struct CustomElementRegistry {
 
     #[ignore_malloc_size_of = "Rc"]
     definitions: DomRefCell<HashMapTracedValues<LocalName, Rc<CustomElementDefinition>>>,
+
+    /// <https://wicg.github.io/webcomponents/proposals/Scoped-Custom-Element-Registries>
+    /// Whether this registry was created by the constructor rather than being
+    /// the global registry of its window.
+    is_scoped: bool,
 }
 
 impl CustomElementRegistry {
-    fn new_inherited(window: &Window) -> CustomElementRegistry {
+    fn new_inherited(window: &Window, is_scoped: bool) -> CustomElementRegistry {
         CustomElementRegistry {
             reflector_: Reflector::new(),
             window: Dom::from_ref(window),
             when_defined: DomRefCell::new(HashMapTracedValues::new()),
             element_definition_is_running: Cell::new(false),
             definitions: DomRefCell::new(HashMapTracedValues::new()),
+            is_scoped,
         }
     }
 
     pub(crate) fn new(window: &Window, can_gc: CanGc) -> DomRoot<CustomElementRegistry> {
         reflect_dom_object(
-            Box::new(CustomElementRegistry::new_inherited(window)),
+            Box::new(CustomElementRegistry::new_inherited(window, false)),
             window,
             can_gc,
         )
     }
 
+    pub(crate) fn is_scoped(&self) -> bool {
+        self.is_scoped
+    }
+
     /// Cleans up any active promises
     /// <https://github.com/servo/servo/issues/15318>
     pub(crate) fn teardown(&self) {
@@ -329,6 +344,20 @@ fn get_callback(
 }
 
 impl CustomElementRegistryMethods<crate::DomTypeHolder> for CustomElementRegistry {
+    /// <https://wicg.github.io/webcomponents/proposals/Scoped-Custom-Element-Registries>
+    fn Constructor(
+        window: &Window,
+        proto: Option<HandleObject>,
+        can_gc: CanGc,
+    ) -> DomRoot<CustomElementRegistry> {
+        reflect_dom_object_with_proto(
+            Box::new(CustomElementRegistry::new_inherited(window, true)),
+            window,
+            proto,
+            can_gc,
+        )
+    }
+
     #[allow(unsafe_code)]
     #[cfg_attr(crown, allow(crown::unrooted_must_root))]
     /// <https://html.spec.whatwg.org/multipage/#dom-customelementregistry-define>
@@ -517,6 +546,7 @@ impl CustomElementRegistryMethods<crate::DomTypeHolder> for CustomElementRegistr
         let document = self.window.Document();
 
         // Steps 18-19: Enqueue custom elements upgrade reaction for upgrade candidates.
+        // Only elements that look up definitions in this registry are candidates.
         for candidate in document
             .upcast::<Node>()
             .traverse_preorder(ShadowIncluding::Yes)
@@ -525,7 +555,8 @@ impl CustomElementRegistryMethods<crate::DomTypeHolder> for CustomElementRegistr
             let is = candidate.get_is();
             if *candidate.local_name() == local_name &&
                 *candidate.namespace() == ns!(html) &&
-                (extends.is_none() || is.as_ref() == Some(&name))
+                (extends.is_none() || is.as_ref() == Some(&name)) &&
+                candidate.custom_element_registry().as_deref() == Some(self)
             {
                 ScriptThread::enqueue_upgrade_reaction(&candidate, definition.clone());
             }
@@ -602,6 +633,58 @@ impl CustomElementRegistryMethods<crate::DomTypeHolder> for CustomElementRegistr
         // Step 6
         promise
     }
+    /// <https://wicg.github.io/webcomponents/proposals/Scoped-Custom-Element-Registries>
+    /// Give this registry to a document or shadow root whose registry is null, such as a
+    /// declarative shadow root with a `shadowrootcustomelementregistry` attribute.
+    fn Initialize(&self, root: RegistryRoot) -> ErrorResult {
+        // A global registry can only be used for the elements of its own document.
+        let document = match &root {
+            RegistryRoot::Document(document) => DomRoot::from_ref(&**document),
+            RegistryRoot::ShadowRoot(shadow_root) => shadow_root.owner_document(),
+        };
+        if !self.is_scoped() && document.custom_element_registry().as_deref() != Some(self) {
+            return Err(Error::NotSupported);
+        }
+
+        let root_node = match &root {
+            RegistryRoot::Document(document) => {
+                if document.custom_element_registry().is_some() {
+                    return Err(Error::NotSupported);
+                }
+                DomRoot::from_ref(document.upcast::<Node>())
+            },
+            RegistryRoot::ShadowRoot(shadow_root) => {
+                if shadow_root.custom_element_registry().is_some() {
+                    return Err(Error::NotSupported);
+                }
+                DomRoot::from_ref(shadow_root.upcast::<Node>())
+            },
+        };
+
+        // Collect the elements without a registry before root gets one.
+        let elements: Vec<_> = root_node
+            .traverse_preorder(ShadowIncluding::No)
+            .filter_map(DomRoot::downcast::<Element>)
+            .filter(|element| element.custom_element_registry().is_none())
+            .collect();
+
+        if let RegistryRoot::ShadowRoot(shadow_root) = &root {
+            shadow_root.set_keep_custom_element_registry_null(false);
+            if self.is_scoped() {
+                shadow_root.set_scoped_custom_element_registry(self);
+            }
+        }
+        for element in elements {
+            if self.is_scoped() && element.scoped_custom_element_registry().is_none() {
+                element.set_scoped_custom_element_registry(self);
+            }
+            if element.is_connected() {
+                try_upgrade_element(&element);
+            }
+        }
+        Ok(())
+    }
+
     /// <https://html.spec.whatwg.org/multipage/#dom-customelementregistry-upgrade>
     fn Upgrade(&self, node: &Node) {
         // Spec says to make a list first and then iterate the list, but
@@ -792,11 +875,11 @@ pub(crate) fn upgrade_element(
     definition: Rc<CustomElementDefinition>,
     element: &Element,
     can_gc: CanGc,
-) {
+) -> ErrorResult {
     // Step 1. If element's custom element state is not "undefined" or "uncustomized", then return.
     let state = element.get_custom_element_state();
     if state != CustomElementState::Undefined && state != CustomElementState::Uncustomized {
-        return;
+        return Ok(());
     }
 
     // Step 2. Set element's custom element definition to definition.
@@ -836,7 +919,18 @@ pub(crate) fn upgrade_element(
         .push(ConstructionStackEntry::Element(DomRoot::from_ref(element)));
 
     // Steps 7-8, successful case
+    // The constructor of a scoped definition can only be found while it is running.
+    let scoped_registry = element
+        .custom_element_registry()
+        .filter(|registry| registry.is_scoped());
+    let window = element.owner_window();
+    if let Some(registry) = scoped_registry.as_deref() {
+        window.push_active_scoped_custom_element_constructor(registry, definition.clone());
+    }
     let result = run_upgrade_constructor(&definition, element, can_gc);
+    if scoped_registry.is_some() {
+        window.pop_active_scoped_custom_element_constructor();
+    }
 
     // "regardless of whether the above steps threw an exception" step
     definition.construction_stack.borrow_mut().pop();
@@ -850,13 +944,7 @@ pub(crate) fn upgrade_element(
         element.clear_reaction_queue();
 
         // Step 8.exception.3
-        let global = GlobalScope::current().expect("No current global");
-        let cx = GlobalScope::get_cx();
-        let ar = enter_realm(&*global);
-        throw_dom_exception(cx, &global, error, can_gc);
-        report_pending_exception(cx, true, InRealm::Entered(&ar), can_gc);
-
-        return;
+        return Err(error);
     }
 
     // Step 9: handle with form-associated custom element
@@ -899,6 +987,7 @@ pub(crate) fn upgrade_element(
 
     // Step 10
     element.set_custom_element_state(CustomElementState::Custom);
+    Ok(())
 }
 
 /// <https://html.spec.whatwg.org/multipage/#concept-upgrade-an-element>
@@ -979,9 +1068,15 @@ pub(crate) fn try_upgrade_element(element: &Element) {
     let namespace = element.namespace();
     let local_name = element.local_name();
     let is = element.get_is();
-    if let Some(definition) =
-        document.lookup_custom_element_definition(namespace, local_name, is.as_ref())
-    {
+    let Some(registry) = element.custom_element_registry() else {
+        return;
+    };
+    if let Some(definition) = document.lookup_custom_element_definition(
+        namespace,
+        local_name,
+        is.as_ref(),
+        Some(&registry),
+    ) {
         // Step 2. If definition is not null, then enqueue a custom element upgrade reaction given
         // element and definition.
         ScriptThread::enqueue_upgrade_reaction(element, definition);
@@ -1004,7 +1099,15 @@ impl CustomElementReaction {
         // Step 2.1
         match *self {
             CustomElementReaction::Upgrade(ref definition) => {
-                upgrade_element(definition.clone(), element, can_gc)
+                // If this throws an exception, catch it, and report it for reaction's definition's
+                // constructor's corresponding JavaScript object's associated realm's global object.
+                if let Err(error) = upgrade_element(definition.clone(), element, can_gc) {
+                    let global = GlobalScope::current().expect("No current global");
+                    let cx = GlobalScope::get_cx();
+                    let ar = enter_realm(&*global);
+                    throw_dom_exception(cx, &global, error, can_gc);
+                    report_pending_exception(cx, true, InRealm::Entered(&ar), can_gc);
+                }
             },
             CustomElementReaction::Callback(ref callback, ref arguments) => {
                 // We're rooted, so it's safe to hand out a handle to objects in Heap
@@ -1030,6 +1133,7 @@ pub(crate) enum CallbackReaction {
     FormAssociated(Option<DomRoot<HTMLFormElement>>),
     FormDisabled(bool),
     FormReset,
+    FormStateRestore(SubmissionValue),
 }
 
 /// <https://html.spec.whatwg.org/multipage/#processing-the-backup-element-queue>
@@ -1208,6 +1312,39 @@ impl CustomElementReactionStack {
             CallbackReaction::FormReset => {
                 (definition.callbacks.form_reset_callback.clone(), Vec::new())
             },
+            CallbackReaction::FormStateRestore(state) => {
+                let cx = GlobalScope::get_cx();
+                let _ac = JSAutoRealm::new(*cx, element.global().reflector().get_jsobject().get());
+
+                rooted!(in(*cx) let mut state_value = NullValue());
+                match state {
+                    SubmissionValue::None => {},
+                    SubmissionValue::USVString(string) => {
+                        string.safe_to_jsval(cx, state_value.handle_mut())
+                    },
+                    SubmissionValue::File(file) => {
+                        state_value.set(ObjectValue(file.reflector().get_jsobject().get()))
+                    },
+                    SubmissionValue::FormData(datums) => {
+                        let form_data =
+                            FormData::new(Some(datums), &element.global(), CanGc::note());
+                        state_value.set(ObjectValue(form_data.reflector().get_jsobject().get()))
+                    },
+                }
+
+                // Servo has no form-filling assist feature, so state is only ever restored
+                // as part of navigation.
+                rooted!(in(*cx) let mut mode_value = UndefinedValue());
+                DOMString::from("restore").safe_to_jsval(cx, mode_value.handle_mut());
+
+                let args = vec![Heap::default(), Heap::default()];
+                args[0].set(state_value.get());
+                args[1].set(mode_value.get());
+                (
+                    definition.callbacks.form_state_restore_callback.clone(),
+                    args,
+                )
+            },
         };
 
         // Step 3
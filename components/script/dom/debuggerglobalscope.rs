@@ -77,6 +77,7 @@ impl DebuggerGlobalScope {
                 gpu_id_hub,
                 None,
                 false,
+                false,
             ),
         });
         let global = unsafe {
@@ -0,0 +1,284 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::rc::Rc;
+
+use dom_struct::dom_struct;
+use flate2::{Crc, Decompress, FlushDecompress, Status};
+use js::rust::HandleObject as SafeHandleObject;
+
+use crate::DomTypes;
+use crate::dom::bindings::codegen::Bindings::CompressionStreamBinding::CompressionFormat;
+use crate::dom::bindings::codegen::Bindings::DecompressionStreamBinding::DecompressionStreamMethods;
+use crate::dom::bindings::error::Fallible;
+use crate::dom::bindings::reflector::{Reflector, reflect_dom_object_with_proto};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::compressionstream::{ChunkCodec, CompressionTransformer};
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::transformstreamdefaultcontroller::TransformerType;
+use crate::dom::types::TransformStream;
+use crate::script_runtime::CanGc;
+
+/// The amount of spare output capacity reserved before each call into the codec.
+const OUTPUT_BUFFER_SIZE: usize = 32 * 1024;
+
+/// The size of the trailer of a gzip member: the CRC-32 and size of the uncompressed data.
+const GZIP_TRAILER_SIZE: usize = 8;
+
+const GZIP_FLAG_HEADER_CRC: u8 = 0x02;
+const GZIP_FLAG_EXTRA: u8 = 0x04;
+const GZIP_FLAG_NAME: u8 = 0x08;
+const GZIP_FLAG_COMMENT: u8 = 0x10;
+
+/// Returns the length of the gzip header at the start of `buffer`,
+/// or `None` if more data is needed to tell.
+///
+/// <https://www.rfc-editor.org/rfc/rfc1952#section-2.3>
+fn gzip_header_length(buffer: &[u8]) -> Result<Option<usize>, &'static str> {
+    if buffer.len() < 10 {
+        return Ok(None);
+    }
+    if buffer[0..3] != [0x1f, 0x8b, 0x08] {
+        return Err("Invalid gzip header");
+    }
+
+    let flags = buffer[3];
+    let mut length = 10;
+    if flags & GZIP_FLAG_EXTRA != 0 {
+        let Some(extra_length) = buffer.get(length..length + 2) else {
+            return Ok(None);
+        };
+        length += 2 + u16::from_le_bytes([extra_length[0], extra_length[1]]) as usize;
+    }
+    for flag in [GZIP_FLAG_NAME, GZIP_FLAG_COMMENT] {
+        if flags & flag == 0 {
+            continue;
+        }
+        // The field is zero-terminated.
+        let Some(field_length) = buffer
+            .get(length..)
+            .and_then(|rest| rest.iter().position(|&byte| byte == 0))
+        else {
+            return Ok(None);
+        };
+        length += field_length + 1;
+    }
+    if flags & GZIP_FLAG_HEADER_CRC != 0 {
+        length += 2;
+    }
+
+    if buffer.len() < length {
+        return Ok(None);
+    }
+    Ok(Some(length))
+}
+
+/// Where the decompressor is in its input.
+enum DecompressorState {
+    /// Buffering the header of a gzip member.
+    GzipHeader(Vec<u8>),
+    /// Inflating the compressed data.
+    Body,
+    /// Buffering the trailer of a gzip member.
+    GzipTrailer(Vec<u8>),
+    /// The end of the compressed input has been reached.
+    Done,
+}
+
+/// <https://compression.spec.whatwg.org/#decompressionstream-context>
+pub struct Decompressor {
+    inflate: Decompress,
+    /// The checksum of the decompressed data, for the "gzip" format only.
+    gzip_crc: Option<Crc>,
+    state: DecompressorState,
+}
+
+impl Decompressor {
+    pub fn new(format: CompressionFormat) -> Decompressor {
+        match format {
+            CompressionFormat::Deflate => Decompressor {
+                inflate: Decompress::new(true),
+                gzip_crc: None,
+                state: DecompressorState::Body,
+            },
+            CompressionFormat::Deflate_raw => Decompressor {
+                inflate: Decompress::new(false),
+                gzip_crc: None,
+                state: DecompressorState::Body,
+            },
+            CompressionFormat::Gzip => Decompressor {
+                inflate: Decompress::new(false),
+                gzip_crc: Some(Crc::new()),
+                state: DecompressorState::GzipHeader(vec![]),
+            },
+        }
+    }
+
+    /// Inflate as much of `input` as possible, appending the output to `output`.
+    /// Returns the number of bytes of input consumed, and whether the end of the
+    /// deflate stream was reached.
+    fn inflate(
+        &mut self,
+        input: &[u8],
+        output: &mut Vec<u8>,
+    ) -> Result<(usize, bool), &'static str> {
+        let start = output.len();
+        let mut consumed = 0;
+        let ended = loop {
+            output.reserve(OUTPUT_BUFFER_SIZE);
+            let before_in = self.inflate.total_in();
+            let status = self
+                .inflate
+                .decompress_vec(&input[consumed..], output, FlushDecompress::None)
+                .map_err(|_| "Invalid compressed data")?;
+            consumed += (self.inflate.total_in() - before_in) as usize;
+            let output_full = output.len() == output.capacity();
+            match status {
+                Status::StreamEnd => break true,
+                // No progress can be made without more input or more output space.
+                Status::Ok | Status::BufError if !output_full => {
+                    if consumed == input.len() || status == Status::BufError {
+                        break false;
+                    }
+                },
+                _ => {},
+            }
+        };
+        if let Some(crc) = self.gzip_crc.as_mut() {
+            crc.update(&output[start..]);
+        }
+        Ok((consumed, ended))
+    }
+
+    fn process_into(&mut self, mut input: &[u8], output: &mut Vec<u8>) -> Result<(), &'static str> {
+        while !input.is_empty() {
+            match &mut self.state {
+                DecompressorState::GzipHeader(header) => {
+                    header.extend_from_slice(input);
+                    let Some(header_length) = gzip_header_length(header)? else {
+                        return Ok(());
+                    };
+                    let rest = header.split_off(header_length);
+                    self.state = DecompressorState::Body;
+                    return self.process_into(&rest, output);
+                },
+                DecompressorState::Body => {
+                    let (consumed, ended) = self.inflate(input, output)?;
+                    input = &input[consumed..];
+                    if !ended {
+                        if !input.is_empty() {
+                            return Err("Invalid compressed data");
+                        }
+                        return Ok(());
+                    }
+                    self.state = match self.gzip_crc {
+                        Some(_) => DecompressorState::GzipTrailer(vec![]),
+                        None => DecompressorState::Done,
+                    };
+                },
+                DecompressorState::GzipTrailer(trailer) => {
+                    let length = input.len().min(GZIP_TRAILER_SIZE - trailer.len());
+                    trailer.extend_from_slice(&input[..length]);
+                    input = &input[length..];
+                    if trailer.len() < GZIP_TRAILER_SIZE {
+                        return Ok(());
+                    }
+                    let crc = self
+                        .gzip_crc
+                        .as_ref()
+                        .expect("gzip decompressor without CRC");
+                    if trailer[0..4] != crc.sum().to_le_bytes() ||
+                        trailer[4..8] != crc.amount().to_le_bytes()
+                    {
+                        return Err("The gzip checksum does not match the decompressed data");
+                    }
+                    self.state = DecompressorState::Done;
+                },
+                DecompressorState::Done => {
+                    return Err("Unexpected data after the end of the compressed stream");
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ChunkCodec for Decompressor {
+    fn process(&mut self, input: &[u8]) -> Result<Vec<u8>, &'static str> {
+        let mut output = vec![];
+        self.process_into(input, &mut output)?;
+        Ok(output)
+    }
+
+    fn finish(&mut self) -> Result<Vec<u8>, &'static str> {
+        // If the end of the compressed input has not been reached, then throw a TypeError.
+        match self.state {
+            DecompressorState::Done => Ok(vec![]),
+            _ => Err("Unexpected end of the compressed stream"),
+        }
+    }
+}
+
+/// <https://compression.spec.whatwg.org/#decompressionstream>
+#[dom_struct]
+pub(crate) struct DecompressionStream {
+    reflector_: Reflector,
+
+    /// <https://streams.spec.whatwg.org/#generictransformstream>
+    transform: Dom<TransformStream>,
+}
+
+impl DecompressionStream {
+    fn new_inherited(transform: &TransformStream) -> DecompressionStream {
+        DecompressionStream {
+            reflector_: Reflector::new(),
+            transform: Dom::from_ref(transform),
+        }
+    }
+}
+
+impl DecompressionStreamMethods<crate::DomTypeHolder> for DecompressionStream {
+    /// <https://compression.spec.whatwg.org/#dom-decompressionstream-decompressionstream>
+    fn Constructor(
+        global: &GlobalScope,
+        proto: Option<SafeHandleObject>,
+        can_gc: CanGc,
+        format: CompressionFormat,
+    ) -> Fallible<DomRoot<DecompressionStream>> {
+        // Step 1. If format is unsupported in DecompressionStream, then throw a TypeError.
+        // Note: all formats of the `CompressionFormat` enum are supported.
+
+        // Step 2. Set this’s format to format.
+        // Step 3. Let transformAlgorithm be an algorithm which takes a chunk argument
+        // and runs the decompress and enqueue a chunk algorithm with this and chunk.
+        // Step 4. Let flushAlgorithm be an algorithm which takes no argument
+        // and runs the decompress flush and enqueue algorithm with this.
+        let transformer_type = TransformerType::Compression(Rc::new(CompressionTransformer::new(
+            Box::new(Decompressor::new(format)),
+        )));
+
+        // Step 5. Set this’s transform to a new TransformStream.
+        // Step 6. Set up this’s transform with transformAlgorithm set to transformAlgorithm
+        // and flushAlgorithm set to flushAlgorithm.
+        let transform_stream = TransformStream::new_with_proto(global, None, can_gc);
+        transform_stream.set_up(GlobalScope::get_cx(), global, transformer_type, can_gc)?;
+
+        Ok(reflect_dom_object_with_proto(
+            Box::new(DecompressionStream::new_inherited(&transform_stream)),
+            global,
+            proto,
+            can_gc,
+        ))
+    }
+
+    /// <https://streams.spec.whatwg.org/#dom-generictransformstream-readable>
+    fn Readable(&self) -> DomRoot<<crate::DomTypeHolder as DomTypes>::ReadableStream> {
+        self.transform.get_readable()
+    }
+
+    /// <https://streams.spec.whatwg.org/#dom-generictransformstream-writable>
+    fn Writable(&self) -> DomRoot<<crate::DomTypeHolder as DomTypes>::WritableStream> {
+        self.transform.get_writable()
+    }
+}
@@ -11,10 +11,9 @@ use constellation_traits::{WorkerGlobalScopeInit, WorkerScriptLoadOrigin};
 use crossbeam_channel::{Receiver, Sender, unbounded};
 use devtools_traits::{DevtoolScriptControlMsg, ScriptToDevtoolsControlMsg, SourceInfo};
 use dom_struct::dom_struct;
-use headers::{HeaderMapExt, ReferrerPolicy as ReferrerPolicyHeader};
 use ipc_channel::ipc::IpcReceiver;
 use ipc_channel::router::ROUTER;
-use js::jsapi::{Heap, JS_AddInterruptCallback, JSContext, JSObject};
+use js::jsapi::{Heap, JS_AddInterruptCallback, JS_SetFutexCanWait, JSContext, JSObject};
 use js::jsval::UndefinedValue;
 use js::rust::{CustomAutoRooter, CustomAutoRooterGuard, HandleValue};
 use net_traits::image_cache::ImageCache;
@@ -23,7 +22,7 @@ use net_traits::request::{
     CredentialsMode, Destination, InsecureRequestsPolicy, ParserMetadata, Referrer, RequestBuilder,
     RequestMode,
 };
-use net_traits::{IpcSend, Metadata};
+use net_traits::{IpcSend, Metadata, ReferrerPolicy};
 use servo_rand::random;
 use servo_url::{ImmutableOrigin, ServoUrl};
 use style::thread_state::{self, ThreadState};
@@ -558,6 +557,10 @@ impl DedicatedWorkerGlobalScope {
                 unsafe {
                     // Handle interrupt requests
                     JS_AddInterruptCallback(*scope.get_cx(), Some(interrupt_callback));
+                    // Dedicated workers are agents whose [[CanBlock]] is true, so unlike
+                    // the main thread they may block in `Atomics.wait`.
+                    // <https://html.spec.whatwg.org/multipage/#obtain-a-worker-agent>
+                    JS_SetFutexCanWait(*scope.get_cx());
                 }
 
                 if scope.is_closing() {
@@ -626,11 +629,8 @@ impl DedicatedWorkerGlobalScope {
         scope.set_csp_list(parse_csp_list_from_metadata(&metadata.headers));
         // Step 5. Set result's referrer policy to the result of parsing the `Referrer-Policy`
         // header given response. [REFERRERPOLICY]
-        let referrer_policy = metadata
-            .headers
-            .as_ref()
-            .and_then(|headers| headers.typed_get::<ReferrerPolicyHeader>())
-            .into();
+        let referrer_policy =
+            ReferrerPolicy::parse_header_for_response(metadata.headers.as_deref());
         scope.set_referrer_policy(referrer_policy);
     }
 
@@ -781,7 +781,8 @@ impl DedicatedWorkerGlobalScope {
         message: HandleValue,
         transfer: CustomAutoRooterGuard<Vec<*mut JSObject>>,
     ) -> ErrorResult {
-        let data = structuredclone::write(cx, message, Some(transfer))?;
+        let data =
+            structuredclone::write_for_agent_cluster(self.upcast(), cx, message, Some(transfer))?;
         let worker = self.worker.borrow().as_ref().unwrap().clone();
         let global_scope = self.upcast::<GlobalScope>();
         let pipeline_id = global_scope.pipeline_id();
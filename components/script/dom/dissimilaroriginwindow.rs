@@ -69,6 +69,7 @@ impl DissimilarOriginWindow {
                 global_to_clone_from.wgpu_id_hub(),
                 Some(global_to_clone_from.is_secure_context()),
                 false,
+                false,
             ),
             window_proxy: Dom::from_ref(window_proxy),
             location: Default::default(),
@@ -20,7 +20,10 @@ use base::id::WebViewId;
 use canvas_traits::canvas::CanvasId;
 use canvas_traits::webgl::{self, WebGLContextId, WebGLMsg};
 use chrono::Local;
-use constellation_traits::{NavigationHistoryBehavior, ScriptToConstellationMessage};
+use constellation_traits::{
+    NavigationHistoryBehavior, PersistedFormControlState, PersistedScrollState, SandboxingFlagSet,
+    ScriptToConstellationMessage, SourceSnapshotParams,
+};
 use content_security_policy::{CspList, PolicyDisposition};
 use cookie::Cookie;
 use cssparser::match_ignore_ascii_case;
@@ -28,28 +31,29 @@ use data_url::mime::Mime;
 use devtools_traits::ScriptToDevtoolsControlMsg;
 use dom_struct::dom_struct;
 use embedder_traits::{
-    AllowOrDeny, AnimationState, ContextMenuResult, Cursor, EditingActionEvent, EmbedderMsg,
-    FocusSequenceNumber, ImeEvent, InputEvent, LoadStatus, MouseButton, MouseButtonAction,
-    MouseButtonEvent, ScrollEvent, TouchEvent, TouchEventType, TouchId, UntrustedNodeAddress,
-    WheelEvent,
+    AllowOrDeny, AnimationState, ContextMenuResult, Cursor, DragDropEvent, EditingActionEvent,
+    EmbedderMsg, FocusSequenceNumber, ImeEvent, InputEvent, LoadStatus, MouseButton,
+    MouseButtonAction, MouseButtonEvent, ScrollEvent, TouchEvent, TouchEventType, TouchId,
+    UntrustedNodeAddress, WheelEvent,
 };
 use encoding_rs::{Encoding, UTF_8};
 use euclid::Point2D;
 use euclid::default::{Rect, Size2D};
 use fnv::FnvHashMap;
-use html5ever::{LocalName, Namespace, QualName, local_name, ns};
+use html5ever::{LocalName, Namespace, QualName, local_name, ns, serialize as html_serialize};
 use hyper_serde::Serde;
 use ipc_channel::ipc;
 use js::rust::{HandleObject, HandleValue, MutableHandleValue};
 use keyboard_types::{Code, Key, KeyState, Modifiers, NamedKey};
 use layout_api::{
     PendingRestyle, ReflowGoal, ReflowPhasesRun, RestyleReason, TrustedNodeAddress,
-    node_id_from_scroll_id,
+    ViewTransitionSnapshot, node_id_from_scroll_id,
 };
 use metrics::{InteractiveFlag, InteractiveWindow, ProgressiveWebMetrics};
 use net_traits::CookieSource::NonHTTP;
 use net_traits::CoreResourceMsg::{GetCookiesForUrl, SetCookiesForUrl};
-use net_traits::policy_container::PolicyContainer;
+use net_traits::permissions_policy::{PermissionsPolicy, PolicyControlledFeature};
+use net_traits::policy_container::{EmbedderPolicy, PolicyContainer};
 use net_traits::pub_domains::is_pub_domain;
 use net_traits::request::{InsecureRequestsPolicy, RequestBuilder};
 use net_traits::response::HttpsState;
@@ -105,9 +109,10 @@ use crate::dom::bindings::codegen::Bindings::NavigatorBinding::Navigator_Binding
 use crate::dom::bindings::codegen::Bindings::NodeBinding::NodeMethods;
 use crate::dom::bindings::codegen::Bindings::NodeFilterBinding::NodeFilter;
 use crate::dom::bindings::codegen::Bindings::PerformanceBinding::PerformanceMethods;
-use crate::dom::bindings::codegen::Bindings::PermissionStatusBinding::PermissionName;
+use crate::dom::bindings::codegen::Bindings::SelectionBinding::SelectionMethods;
 use crate::dom::bindings::codegen::Bindings::ShadowRootBinding::ShadowRootMethods;
 use crate::dom::bindings::codegen::Bindings::TouchBinding::TouchMethods;
+use crate::dom::bindings::codegen::Bindings::ViewTransitionBinding::ViewTransitionUpdateCallback;
 use crate::dom::bindings::codegen::Bindings::WindowBinding::{
     FrameRequestCallback, ScrollBehavior, ScrollOptions, WindowMethods,
 };
@@ -138,7 +143,7 @@ use crate::dom::clipboardevent::{ClipboardEvent, ClipboardEventType};
 use crate::dom::comment::Comment;
 use crate::dom::compositionevent::CompositionEvent;
 use crate::dom::cssstylesheet::CSSStyleSheet;
-use crate::dom::customelementregistry::CustomElementDefinition;
+use crate::dom::customelementregistry::{CustomElementDefinition, CustomElementRegistry};
 use crate::dom::customevent::CustomEvent;
 use crate::dom::datatransfer::DataTransfer;
 use crate::dom::documentfragment::DocumentFragment;
@@ -161,6 +166,7 @@ use crate::dom::htmlanchorelement::HTMLAnchorElement;
 use crate::dom::htmlareaelement::HTMLAreaElement;
 use crate::dom::htmlbaseelement::HTMLBaseElement;
 use crate::dom::htmlcollection::{CollectionFilter, HTMLCollection};
+use crate::dom::htmldialogelement::HTMLDialogElement;
 use crate::dom::htmlelement::HTMLElement;
 use crate::dom::htmlembedelement::HTMLEmbedElement;
 use crate::dom::htmlformelement::{FormControl, FormControlElementHelpers, HTMLFormElement};
@@ -172,7 +178,7 @@ use crate::dom::htmlinputelement::HTMLInputElement;
 use crate::dom::htmlscriptelement::{HTMLScriptElement, ScriptResult};
 use crate::dom::htmltextareaelement::HTMLTextAreaElement;
 use crate::dom::htmltitleelement::HTMLTitleElement;
-use crate::dom::inputevent::HitTestResult;
+use crate::dom::inputevent::{HitTestResult, InputEvent, InputEventType};
 use crate::dom::intersectionobserver::IntersectionObserver;
 use crate::dom::keyboardevent::KeyboardEvent;
 use crate::dom::location::{Location, NavigationType};
@@ -191,6 +197,7 @@ use crate::dom::processinginstruction::ProcessingInstruction;
 use crate::dom::promise::Promise;
 use crate::dom::range::Range;
 use crate::dom::resizeobserver::{ResizeObservationDepth, ResizeObserver};
+use crate::dom::sanitizer::sanitize_with_safe_default;
 use crate::dom::selection::Selection;
 use crate::dom::servoparser::ServoParser;
 use crate::dom::shadowroot::ShadowRoot;
@@ -204,6 +211,7 @@ use crate::dom::treewalker::TreeWalker;
 use crate::dom::trustedhtml::TrustedHTML;
 use crate::dom::types::VisibilityStateEntry;
 use crate::dom::uievent::UIEvent;
+use crate::dom::viewtransition::{ViewTransition, ViewTransitionPhase};
 use crate::dom::virtualmethods::vtable_for;
 use crate::dom::webglrenderingcontext::WebGLRenderingContext;
 #[cfg(feature = "webgpu")]
@@ -212,7 +220,9 @@ use crate::dom::wheelevent::WheelEvent as DomWheelEvent;
 use crate::dom::window::Window;
 use crate::dom::windowproxy::WindowProxy;
 use crate::dom::xpathevaluator::XPathEvaluator;
+use crate::drag_and_drop::DragAndDropState;
 use crate::drag_data_store::{DragDataStore, Kind, Mode};
+use crate::editing;
 use crate::fetch::FetchCanceller;
 use crate::iframe_collection::IFrameCollection;
 use crate::image_animation::ImageAnimationManager;
@@ -222,6 +232,7 @@ use crate::network_listener::{NetworkListener, PreInvoke};
 use crate::realms::{AlreadyInRealm, InRealm, enter_realm};
 use crate::script_runtime::{CanGc, ScriptThreadEventCategory};
 use crate::script_thread::{ScriptThread, with_script_thread};
+use crate::speculation_rules::{PrefetchRecords, SpeculationRuleSet, consider_speculative_loads};
 use crate::stylesheet_set::StylesheetSetRef;
 use crate::task::NonSendTaskBox;
 use crate::task_source::TaskSourceName;
@@ -441,6 +452,44 @@ pub(crate) struct Document {
     #[ignore_malloc_size_of = "Defined in std"]
     #[no_trace]
     last_click_info: DomRefCell<Option<(Instant, Point2D<f32, CSSPixel>)>>,
+    /// <https://html.spec.whatwg.org/multipage/#drag-and-drop-processing-model>
+    drag_and_drop: DragAndDropState,
+    /// The form control state persisted by the previous document of this document's session
+    /// history entry, which is restored once parsing has finished.
+    #[no_trace]
+    persisted_form_state: DomRefCell<Vec<PersistedFormControlState>>,
+    /// Whether the state of the form-associated custom elements of this document has to be
+    /// saved when it is unloaded, because one of them set a state or because state saved by
+    /// an earlier document of its session history entry has to be replaced.
+    form_state_needs_persisting: Cell<bool>,
+    /// The scroll state persisted by the previous document of this document's session
+    /// history entry, which is restored once the document has been loaded.
+    #[no_trace]
+    persisted_scroll_state: Cell<Option<PersistedScrollState>>,
+    /// The origins of the ancestors of this document's navigable, starting with its parent.
+    #[no_trace]
+    ancestor_origins: DomRefCell<Vec<ImmutableOrigin>>,
+    /// <https://html.spec.whatwg.org/multipage/#document-sr-sets>
+    #[no_trace]
+    speculation_rule_sets: DomRefCell<Vec<SpeculationRuleSet>>,
+    /// <https://html.spec.whatwg.org/multipage/#document-prefetch-records>
+    #[no_trace]
+    prefetch_records: DomRefCell<PrefetchRecords>,
+    /// <https://wicg.github.io/nav-speculation/prerendering.html#document-prerendering>
+    prerendering: Cell<bool>,
+    /// Whether this document was frozen by the embedder, which suspends its timers, tasks
+    /// and media until it is resumed.
+    /// <https://wicg.github.io/page-lifecycle/#frozenness>
+    frozen: Cell<bool>,
+    /// <https://wicg.github.io/nav-speculation/prerendering.html#document-post-prerendering-activation-steps-list>
+    #[ignore_malloc_size_of = "Measuring trait objects is hard"]
+    post_prerendering_activation_steps: DomRefCell<Vec<Box<dyn NonSendTaskBox>>>,
+    /// <https://html.spec.whatwg.org/multipage/#active-sandboxing-flag-set>
+    #[no_trace]
+    active_sandboxing_flag_set: Cell<SandboxingFlagSet>,
+    /// <https://html.spec.whatwg.org/multipage/#concept-document-permissions-policy>
+    #[no_trace]
+    permissions_policy: DomRefCell<PermissionsPolicy>,
     /// <https://html.spec.whatwg.org/multipage/#ignore-destructive-writes-counter>
     ignore_destructive_writes_counter: Cell<u32>,
     /// <https://html.spec.whatwg.org/multipage/#ignore-opens-during-unload-counter>
@@ -458,6 +507,10 @@ pub(crate) struct Document {
     dom_count: Cell<u32>,
     /// Entry node for fullscreen.
     fullscreen_element: MutNullableDom<Element>,
+    /// <https://drafts.csswg.org/css-position-4/#document-top-layer>
+    top_layer: DomRefCell<Vec<Dom<Element>>>,
+    /// <https://html.spec.whatwg.org/multipage/#popover-pointerdown-target>
+    popover_pointerdown_target: MutNullableDom<HTMLElement>,
     /// Map from ID to set of form control elements that have that ID as
     /// their 'form' content attribute. Used to reset form controls
     /// whenever any element with the same ID as the form attribute
@@ -491,6 +544,10 @@ pub(crate) struct Document {
     delayed_tasks: DomRefCell<Vec<Box<dyn NonSendTaskBox>>>,
     /// <https://html.spec.whatwg.org/multipage/#completely-loaded>
     completely_loaded: Cell<bool>,
+    /// <https://html.spec.whatwg.org/multipage/#ready-for-post-load-tasks>
+    ready_for_post_load_tasks: Cell<bool>,
+    /// <https://html.spec.whatwg.org/multipage/#print-when-loaded>
+    print_when_loaded: Cell<bool>,
     /// Set of shadow roots connected to the document tree.
     shadow_roots: DomRefCell<HashSet<Dom<ShadowRoot>>>,
     /// Whether any of the shadow roots need the stylesheets flushed.
@@ -568,6 +625,8 @@ pub(crate) struct Document {
     /// The lifetime of an intersection observer is specified at
     /// <https://github.com/w3c/IntersectionObserver/issues/525>.
     intersection_observers: DomRefCell<Vec<Dom<IntersectionObserver>>>,
+    /// <https://html.spec.whatwg.org/multipage/#lazy-load-intersection-observer>
+    lazy_load_intersection_observer: MutNullableDom<IntersectionObserver>,
     /// The active keyboard modifiers for the WebView. This is updated when receiving any input event.
     #[no_trace]
     active_keyboard_modifiers: Cell<Modifiers>,
@@ -581,9 +640,15 @@ pub(crate) struct Document {
     adopted_stylesheets_frozen_types: CachedFrozenArray,
     /// <https://drafts.csswg.org/cssom-view/#document-pending-scroll-event-targets>
     pending_scroll_event_targets: DomRefCell<Vec<Dom<EventTarget>>>,
+    /// <https://drafts.csswg.org/cssom-view/#document-pending-scrollend-event-targets>
+    pending_scrollend_event_targets: DomRefCell<Vec<Dom<EventTarget>>>,
     /// When a `ResizeObserver` starts observing a target, this becomes true, which in turn is a
     /// signal to the [`ScriptThread`] that a rendering update should happen.
     resize_observer_started_observing_target: Cell<bool>,
+    /// <https://drafts.csswg.org/css-view-transitions-1/#document-active-view-transition>
+    active_view_transition: MutNullableDom<ViewTransition>,
+    /// <https://drafts.csswg.org/css-view-transitions-1/#document-rendering-suppression-for-view-transitions>
+    rendering_suppression_for_view_transitions: Cell<bool>,
 }
 
 #[allow(non_snake_case)]
@@ -759,13 +824,14 @@ impl Document {
         // Set the document's activity level, reflow if necessary, and suspend or resume timers.
         self.activity.set(activity);
         let media = ServoMedia::get();
-        let pipeline_id = self.window().pipeline_id();
-        let client_context_id =
-            ClientContextId::build(pipeline_id.namespace_id.0, pipeline_id.index.0.get());
+        let client_context_id = self.media_client_context_id();
 
         if activity != DocumentActivity::FullyActive {
-            self.window().suspend(can_gc);
-            media.suspend(&client_context_id);
+            // A frozen document already had its timers and media suspended.
+            if !self.frozen.replace(false) {
+                self.window().suspend(can_gc);
+                media.suspend(&client_context_id);
+            }
             return;
         }
 
@@ -774,6 +840,11 @@ impl Document {
         self.window().resume(can_gc);
         media.resume(&client_context_id);
 
+        // The session history may have changed while this document was not fully active.
+        if let Some(navigation) = self.window().navigation_if_created() {
+            navigation.invalidate_entries();
+        }
+
         if self.ready_state.get() != DocumentReadyState::Complete {
             return;
         }
@@ -794,8 +865,11 @@ impl Document {
                 }
                 // Step 4.6.2 Set document's page showing flag to true.
                 document.page_showing.set(true);
-                // Step 4.6.3 Update the visibility state of document to "visible".
-                document.update_visibility_state(DocumentVisibilityState::Visible, CanGc::note());
+                // Step 4.6.3 Update the visibility state of document to "visible", unless it is
+                // prerendering, in which case it is hidden until it is activated.
+                if !document.is_prerendering() {
+                    document.update_visibility_state(DocumentVisibilityState::Visible, CanGc::note());
+                }
                 // Step 4.6.4 Fire a page transition event named pageshow at document's relevant
                 // global object with true.
                 let event = PageTransitionEvent::new(
@@ -1357,11 +1431,7 @@ impl Document {
 
                 // Notify the embedder to display an input method.
                 if let Some(kind) = elem.input_method_type() {
-                    let rect = elem.upcast::<Node>().content_box().unwrap_or_default();
-                    let rect = Rect::new(
-                        Point2D::new(rect.origin.x.to_px(), rect.origin.y.to_px()),
-                        Size2D::new(rect.size.width.to_px(), rect.size.height.to_px()),
-                    );
+                    let rect = Self::content_box_for_ime(elem);
                     let (text, multiline) = if let Some(input) = elem.downcast::<HTMLInputElement>()
                     {
                         (
@@ -1387,7 +1457,7 @@ impl Document {
                         kind,
                         text,
                         multiline,
-                        DeviceIntRect::from_untyped(&rect.to_box2d()),
+                        rect,
                     ));
                 }
                 // Scroll operation to happen after element gets focus.
@@ -1570,6 +1640,32 @@ impl Document {
 
         let node = el.upcast::<Node>();
         debug!("{:?} on {:?}", event.action, node.debug_str());
+
+        // The mouse button release that ends a drag-and-drop operation drops the dragged
+        // data instead, and the click that follows it is not dispatched.
+        match (event.action, event.button) {
+            (MouseButtonAction::Click, _) if self.drag_and_drop.take_suppressed_click() => {
+                return;
+            },
+            (MouseButtonAction::Up, MouseButton::Left)
+                if self.drag_and_drop.handle_mouse_up(
+                    self,
+                    &hit_test_result,
+                    input_event,
+                    can_gc,
+                ) =>
+            {
+                return;
+            },
+            _ => {},
+        }
+
+        // Inert nodes are treated as absent for the purposes of user interaction.
+        // <https://html.spec.whatwg.org/multipage/#inert>
+        if node.is_inert() {
+            return;
+        }
+
         // Prevent click event if form control element is disabled.
         if let MouseButtonAction::Click = event.action {
             // The click event is filtered by the disabled state.
@@ -1610,7 +1706,11 @@ impl Document {
                 }
 
                 let target = node.upcast();
-                dom_event.fire(target, can_gc);
+                if dom_event.fire(target, can_gc) && matches!(event.button, MouseButton::Left) {
+                    self.drag_and_drop
+                        .handle_mouse_down(node, hit_test_result.point_in_frame);
+                }
+                self.light_dismiss_open_popovers(event.action, node, can_gc);
             },
             MouseButtonAction::Up => {
                 if let Some(a) = activatable {
@@ -1619,6 +1719,7 @@ impl Document {
 
                 let target = node.upcast();
                 dom_event.fire(target, can_gc);
+                self.light_dismiss_open_popovers(event.action, node, can_gc);
             },
         }
 
@@ -1909,6 +2010,16 @@ impl Document {
                     .map(Result::unwrap_or_default)
                     .unwrap_or_default();
 
+                let (sender, receiver) = ipc::channel().unwrap();
+                self.window
+                    .send_to_constellation(ScriptToConstellationMessage::ForwardToEmbedder(
+                        EmbedderMsg::GetClipboardContents(self.window.webview_id(), sender),
+                    ));
+                let contents = receiver
+                    .recv()
+                    .map(Result::unwrap_or_default)
+                    .unwrap_or_default();
+
                 // Step 7.1.1
                 drag_data_store.set_mode(Mode::ReadOnly);
                 // Step 7.1.2 If trusted or the implementation gives script-generated events access to the clipboard
@@ -1920,8 +2031,21 @@ impl Document {
                     let type_ = DOMString::from("text/plain");
                     let _ = drag_data_store.add(Kind::Text { data, type_ });
 
-                    // Step 7.1.2.1.2 TODO If clipboard-part represents file references, then for each file reference
-                    // Step 7.1.2.1.3 TODO If clipboard-part contains HTML- or XHTML-formatted text then
+                    // Step 7.1.2.1.2 If clipboard-part represents file references, then for each file reference
+                    for file in contents.files {
+                        let _ = drag_data_store.add(Kind::File {
+                            bytes: file.bytes,
+                            name: DOMString::from(file.name),
+                            type_: file.type_,
+                        });
+                    }
+
+                    // Step 7.1.2.1.3 If clipboard-part contains HTML- or XHTML-formatted text then
+                    if let Some(html) = contents.html {
+                        let data = self.sanitize_clipboard_html(html, can_gc);
+                        let type_ = DOMString::from("text/html");
+                        let _ = drag_data_store.add(Kind::Text { data, type_ });
+                    }
 
                     // Step 7.1.3 Update clipboard-event-data’s files to match clipboard-event-data’s items
                     // Step 7.1.4 Update clipboard-event-data’s types to match clipboard-event-data’s items
@@ -1948,6 +2072,39 @@ impl Document {
         event.dispatch(target, false, can_gc);
     }
 
+    /// Parse HTML from the system clipboard into an inert fragment and sanitize it with the
+    /// safe default configuration of the Sanitizer API, before it is exposed to the page by a
+    /// paste event.
+    fn sanitize_clipboard_html(&self, html: String, can_gc: CanGc) -> DOMString {
+        let Some(context) = self.GetBody() else {
+            return DOMString::new();
+        };
+        let nodes: Vec<_> = ServoParser::parse_html_fragment(
+            context.upcast(),
+            DOMString::from(html),
+            false,
+            can_gc,
+        )
+        .collect();
+        let Some(first_node) = nodes.first() else {
+            return DOMString::new();
+        };
+        let fragment = DocumentFragment::new(&first_node.owner_doc(), can_gc);
+        let fragment = fragment.upcast::<Node>();
+        for node in &nodes {
+            let _ = fragment.AppendChild(node, can_gc);
+        }
+
+        sanitize_with_safe_default(fragment, can_gc);
+
+        fragment.html_serialize(
+            html_serialize::TraversalScope::ChildrenOnly(None),
+            false,
+            vec![],
+            can_gc,
+        )
+    }
+
     pub(crate) fn fire_clipboardchange_event(&self, can_gc: CanGc) {
         let clipboardchange_event = ClipboardEvent::new(
             &self.window,
@@ -2012,6 +2169,14 @@ impl Document {
         // Update the cursor when the mouse moves, if it has changed.
         self.set_cursor(hit_test_result.cursor);
 
+        // No mouse events are fired while something is being dragged.
+        if self
+            .drag_and_drop
+            .handle_mouse_move(self, &hit_test_result, input_event, can_gc)
+        {
+            return;
+        }
+
         let Some(new_target) = hit_test_result
             .node
             .inclusive_ancestors(ShadowIncluding::No)
@@ -2144,6 +2309,9 @@ impl Document {
         self.window()
             .send_to_embedder(EmbedderMsg::Status(self.webview_id(), None));
 
+        self.drag_and_drop
+            .handle_mouse_leave(self, &hit_test_result, input_event, can_gc);
+
         for element in hit_test_result
             .node
             .inclusive_ancestors(ShadowIncluding::No)
@@ -2172,6 +2340,26 @@ impl Document {
         );
     }
 
+    pub(crate) fn handle_drag_drop_event(
+        &self,
+        event: DragDropEvent,
+        input_event: &ConstellationInputEvent,
+        can_gc: CanGc,
+    ) {
+        // Ignore all incoming events without a hit test.
+        let Some(hit_test_result) = self.window.hit_test_from_input_event(input_event) else {
+            return;
+        };
+
+        self.drag_and_drop.handle_embedder_drag_event(
+            self,
+            event,
+            &hit_test_result,
+            input_event,
+            can_gc,
+        );
+    }
+
     fn handle_mouse_enter_leave_event(
         &self,
         event_target: DomRoot<Node>,
@@ -2311,17 +2499,33 @@ impl Document {
             TouchEventType::Cancel => "touchcancel",
         };
 
-        let Some(el) = hit_test_result
-            .node
-            .inclusive_ancestors(ShadowIncluding::No)
-            .filter_map(DomRoot::downcast::<Element>)
-            .next()
-        else {
-            self.update_active_touch_points_when_early_return(event);
-            return TouchEventResult::Forwarded;
+        // <https://w3c.github.io/touch-events/#dom-touch-target>
+        // The target of a touch point is the element on which it was first placed on the
+        // surface, even if it has since moved outside of that element.
+        let existing_target = match event.event_type {
+            TouchEventType::Down => None,
+            TouchEventType::Move | TouchEventType::Up | TouchEventType::Cancel => self
+                .active_touch_points
+                .borrow()
+                .iter()
+                .find(|t| t.Identifier() == identifier)
+                .map(|t| t.Target()),
+        };
+        let target = match existing_target {
+            Some(target) => target,
+            None => {
+                let Some(el) = hit_test_result
+                    .node
+                    .inclusive_ancestors(ShadowIncluding::No)
+                    .filter_map(DomRoot::downcast::<Element>)
+                    .next()
+                else {
+                    self.update_active_touch_points_when_early_return(event);
+                    return TouchEventResult::Forwarded;
+                };
+                DomRoot::upcast::<EventTarget>(el)
+            },
         };
-
-        let target = DomRoot::upcast::<EventTarget>(el);
         let window = &*self.window;
 
         let client_x = Finite::wrap(hit_test_result.point_in_frame.x as f64);
@@ -2377,6 +2581,7 @@ impl Document {
             TouchList::new(window, touches.r(), can_gc)
         };
 
+        let modifiers = input_event.active_keyboard_modifiers;
         let event = DomTouchEvent::new(
             window,
             DOMString::from(event_name),
@@ -2387,11 +2592,10 @@ impl Document {
             &touches,
             &TouchList::new(window, from_ref(&&*touch), can_gc),
             &TouchList::new(window, target_touches.r(), can_gc),
-            // FIXME: modifier keys
-            false,
-            false,
-            false,
-            false,
+            modifiers.contains(Modifiers::CONTROL),
+            modifiers.contains(Modifiers::ALT),
+            modifiers.contains(Modifiers::SHIFT),
+            modifiers.contains(Modifiers::META),
             can_gc,
         );
 
@@ -2455,6 +2659,24 @@ impl Document {
         // Step 4.
         // > Run the steps to dispatch pending scrollsnapchange events for doc.
         // TODO(#7673): Implement scroll snapping
+
+        // Step 5.
+        // > For each item target in doc’s pending scrollend event targets, in the order they
+        // > were added to the list, run these substeps:
+        // Step 6.
+        // > Empty doc’s pending scrollend event targets.
+        rooted_vec!(let notify_list <- self.pending_scrollend_event_targets.take().into_iter());
+        for target in notify_list.iter() {
+            if target.downcast::<Document>().is_some() {
+                // Step 5.1
+                // > If target is a Document, fire an event named scrollend that bubbles at target.
+                target.fire_bubbling_event(Atom::from("scrollend"), can_gc);
+            } else if target.downcast::<Element>().is_some() {
+                // Step 5.2
+                // > Otherwise, fire an event named scrollend at target.
+                target.fire_event(Atom::from("scrollend"), can_gc);
+            }
+        }
     }
 
     /// Whenever a viewport gets scrolled (whether in response to user interaction or by an
@@ -2516,10 +2738,45 @@ impl Document {
             .push(Dom::from_ref(target));
     }
 
+    /// Whenever scrolling of a viewport or an element is completed (whether it was scrolled
+    /// in response to user interaction or by an API), the user agent must run these steps:
+    /// <https://drafts.csswg.org/cssom-view/#scrollend>
+    pub(crate) fn handle_scroll_completion(&self, element: Option<&Element>) {
+        // Step 1.
+        // > If scrolling was done by the user, and the user is still interacting with the
+        // > scrolling box, abort these steps.
+        // This is approximated by only considering user scrolling completed once the user
+        // has stopped scrolling for a while, see `Window::note_user_scroll`.
+
+        // Step 2 & 3.
+        // > If target is already in doc’s pending scrollend event targets, abort these steps.
+        let target = match element {
+            Some(element) => element.upcast::<EventTarget>(),
+            None => self.upcast::<EventTarget>(),
+        };
+        if self
+            .pending_scrollend_event_targets
+            .borrow()
+            .iter()
+            .any(|other_target| *other_target == target)
+        {
+            return;
+        }
+
+        // > Append target to doc’s pending scrollend event targets.
+        self.pending_scrollend_event_targets
+            .borrow_mut()
+            .push(Dom::from_ref(target));
+    }
+
     /// Handle scroll event triggered by user interactions from embedder side.
     /// <https://drafts.csswg.org/cssom-view/#scrolling-events>
     #[allow(unsafe_code)]
     pub(crate) fn handle_embedder_scroll_event(&self, event: ScrollEvent) {
+        // A user scroll aborts any ongoing smooth scroll of the same scrolling box.
+        // <https://drafts.csswg.org/cssom-view/#concept-smooth-scroll>
+        self.window.abort_smooth_scroll(event.external_id);
+
         // If it is a viewport scroll.
         if event.external_id.is_root() {
             let Some(document) = self
@@ -2532,6 +2789,7 @@ impl Document {
             };
 
             document.handle_viewport_scroll_event();
+            self.window.note_user_scroll(event.external_id, None);
         } else {
             // Otherwise, check whether it is for a relevant element within the document.
             let Some(node_id) = node_id_from_scroll_id(event.external_id.0 as usize) else {
@@ -2549,6 +2807,8 @@ impl Document {
             };
 
             self.handle_element_scroll_event(&element);
+            self.window
+                .note_user_scroll(event.external_id, Some(&element));
         }
     }
 
@@ -2621,6 +2881,28 @@ impl Document {
         }
 
         if cancel_state == EventDefault::Allowed {
+            // Pressing Escape is a close request for the topmost modal dialog or auto popover.
+            // <https://html.spec.whatwg.org/multipage/#close-requests>
+            if keyboard_event.event.state == KeyState::Down &&
+                keyboard_event.event.key == Key::Named(NamedKey::Escape) &&
+                self.process_close_request(can_gc)
+            {
+                return;
+            }
+
+            // Key presses inside of an editing host edit its content, unless they are part
+            // of an IME composition, which is applied through the composition events.
+            if keyboard_event.event.state == KeyState::Down && !keyboard_event.event.is_composing {
+                if let Some(editing_host) = focused
+                    .as_ref()
+                    .and_then(|focused| focused.upcast::<Node>().editing_host())
+                {
+                    if editing::handle_keydown(self, editing_host.upcast(), &keyevent, can_gc) {
+                        return;
+                    }
+                }
+            }
+
             let msg = EmbedderMsg::Keyboard(self.webview_id(), keyboard_event.clone());
             self.send_to_embedder(msg);
 
@@ -2675,11 +2957,83 @@ impl Document {
             cancelable,
             Some(&self.window),
             0,
-            DOMString::from(composition_event.data),
+            DOMString::from(composition_event.data.clone()),
             can_gc,
         );
         let event = compositionevent.upcast::<Event>();
         event.fire(target, can_gc);
+
+        // Text controls apply the composition while handling the event themselves. For
+        // editing hosts the composed text replaces the current selection, and while the
+        // composition is in progress the inserted text stays selected so that the next
+        // update replaces it.
+        let Some(focused) = focused else {
+            return;
+        };
+        let node = focused.upcast::<Node>();
+        if node.is_editing_host() &&
+            composition_event.state != keyboard_types::CompositionState::Start
+        {
+            let data = DOMString::from(composition_event.data.clone());
+            InputEvent::dispatch_beforeinput(
+                node.upcast(),
+                InputEventType::InsertCompositionText,
+                Some(data.clone()),
+                &[],
+                can_gc,
+            );
+            let select_inserted =
+                composition_event.state == keyboard_types::CompositionState::Update;
+            editing::insert_text(self, node, &composition_event.data, select_inserted, can_gc);
+            InputEvent::dispatch_input(
+                node.upcast(),
+                InputEventType::InsertCompositionText,
+                Some(data),
+                can_gc,
+            );
+        }
+
+        self.send_to_embedder(EmbedderMsg::UpdateIMECaretRect(
+            self.webview_id(),
+            self.ime_caret_rect(&focused, can_gc),
+        ));
+    }
+
+    /// The content box of an element in the units expected by the embedder IME messages.
+    fn content_box_for_ime(element: &Element) -> DeviceIntRect {
+        let rect = element.upcast::<Node>().content_box().unwrap_or_default();
+        let rect = Rect::new(
+            Point2D::new(rect.origin.x.to_px(), rect.origin.y.to_px()),
+            Size2D::new(rect.size.width.to_px(), rect.size.height.to_px()),
+        );
+        DeviceIntRect::from_untyped(&rect.to_box2d())
+    }
+
+    /// The area that the embedder should avoid covering with the IME candidate window. For
+    /// editing hosts this is the area of the selection, while text controls report their
+    /// content box.
+    fn ime_caret_rect(&self, element: &Element, can_gc: CanGc) -> DeviceIntRect {
+        let node = element.upcast::<Node>();
+        if !node.is_editing_host() {
+            return Self::content_box_for_ime(element);
+        }
+        let Some(range) = self
+            .GetSelection(can_gc)
+            .and_then(|selection| selection.GetRangeAt(0).ok())
+        else {
+            return Self::content_box_for_ime(element);
+        };
+        let rect = range
+            .client_rects()
+            .fold(Rect::zero(), |acc, rect| acc.union(&rect));
+        if rect.is_empty() {
+            return Self::content_box_for_ime(element);
+        }
+        let rect = Rect::new(
+            Point2D::new(rect.origin.x.to_px(), rect.origin.y.to_px()),
+            Size2D::new(rect.size.width.to_px(), rect.size.height.to_px()),
+        );
+        DeviceIntRect::from_untyped(&rect.to_box2d())
     }
 
     // https://dom.spec.whatwg.org/#converting-nodes-into-a-node
@@ -2854,7 +3208,8 @@ impl Document {
     ) {
         request = request
             .insecure_requests_policy(self.insecure_requests_policy())
-            .has_trustworthy_ancestor_origin(self.has_trustworthy_ancestor_or_current_origin());
+            .has_trustworthy_ancestor_origin(self.has_trustworthy_ancestor_or_current_origin())
+            .ancestor_origins(self.ancestor_origins_for_nested_navigable());
         let callback = NetworkListener {
             context: std::sync::Arc::new(Mutex::new(listener)),
             task_source: self
@@ -2875,7 +3230,8 @@ impl Document {
     ) {
         request = request
             .insecure_requests_policy(self.insecure_requests_policy())
-            .has_trustworthy_ancestor_origin(self.has_trustworthy_ancestor_or_current_origin());
+            .has_trustworthy_ancestor_origin(self.has_trustworthy_ancestor_or_current_origin())
+            .ancestor_origins(self.ancestor_origins_for_nested_navigable());
         let callback = NetworkListener {
             context: std::sync::Arc::new(Mutex::new(listener)),
             task_source: self
@@ -3006,6 +3362,11 @@ impl Document {
         // TODO: Step 1, increase the event loop's termination nesting level by 1.
         // Step 2
         self.incr_ignore_opens_during_unload_counter();
+        // If the user agent does not intend to keep oldDocument alive in a session history
+        // entry, set oldDocument's salvageable state to false.
+        if !pref!(session_history_bfcache_enabled) {
+            self.salvageable.set(false);
+        }
         // Step 3-6 If oldDocument's page showing is true:
         if self.page_showing.get() {
             // Set oldDocument's page showing to false.
@@ -3064,11 +3425,20 @@ impl Document {
             }
         }
 
+        self.persist_form_state();
+        self.persist_scroll_state();
+
         let global_scope = self.window.as_global_scope();
         // Step 10, 14
         // https://html.spec.whatwg.org/multipage/#unloading-document-cleanup-steps
+        // Step 1 of clean-up steps. For each WebSocket object webSocket whose relevant global
+        // object is window, make disappear webSocket. If this affected any WebSocket objects,
+        // then set document's salvageable state to false.
+        if global_scope.make_websockets_disappear() {
+            self.salvageable.set(false);
+        }
         if !self.salvageable.get() {
-            // Step 1 of clean-up steps.
+            // Step 3 of clean-up steps.
             global_scope.close_event_sources();
             let msg = ScriptToConstellationMessage::DiscardDocument;
             let _ = global_scope.script_to_constellation_chan().send(msg);
@@ -3080,6 +3450,258 @@ impl Document {
         self.decr_ignore_opens_during_unload_counter();
     }
 
+    pub(crate) fn set_persisted_form_state(&self, form_state: Vec<PersistedFormControlState>) {
+        if !form_state.is_empty() {
+            self.form_state_needs_persisting.set(true);
+        }
+        *self.persisted_form_state.borrow_mut() = form_state;
+    }
+
+    /// Note that a form-associated custom element of this document set a state, which is
+    /// saved when the document is unloaded.
+    pub(crate) fn note_form_state_needs_persisting(&self) {
+        self.form_state_needs_persisting.set(true);
+    }
+
+    pub(crate) fn set_persisted_scroll_state(&self, scroll_state: Option<PersistedScrollState>) {
+        self.persisted_scroll_state.set(scroll_state);
+    }
+
+    pub(crate) fn speculation_rule_sets(&self) -> Ref<Vec<SpeculationRuleSet>> {
+        self.speculation_rule_sets.borrow()
+    }
+
+    /// Add a speculation rule set parsed from a `<script type="speculationrules">` element,
+    /// starting the prefetches it asks for.
+    pub(crate) fn add_speculation_rule_set(&self, rule_set: SpeculationRuleSet) {
+        self.speculation_rule_sets.borrow_mut().push(rule_set);
+        consider_speculative_loads(self);
+    }
+
+    pub(crate) fn prefetch_records(&self) -> Ref<PrefetchRecords> {
+        self.prefetch_records.borrow()
+    }
+
+    pub(crate) fn prefetch_records_mut(&self) -> RefMut<PrefetchRecords> {
+        self.prefetch_records.borrow_mut()
+    }
+
+    pub(crate) fn is_prerendering(&self) -> bool {
+        self.prerendering.get()
+    }
+
+    pub(crate) fn set_prerendering(&self, prerendering: bool) {
+        self.prerendering.set(prerendering);
+    }
+
+    pub(crate) fn active_sandboxing_flag_set(&self) -> SandboxingFlagSet {
+        self.active_sandboxing_flag_set.get()
+    }
+
+    pub(crate) fn set_active_sandboxing_flag_set(&self, flags: SandboxingFlagSet) {
+        self.active_sandboxing_flag_set.set(flags);
+    }
+
+    pub(crate) fn permissions_policy(&self) -> Ref<PermissionsPolicy> {
+        self.permissions_policy.borrow()
+    }
+
+    pub(crate) fn set_permissions_policy(&self, permissions_policy: PermissionsPolicy) {
+        *self.permissions_policy.borrow_mut() = permissions_policy;
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#snapshotting-source-snapshot-params>
+    pub(crate) fn snapshot_source_snapshot_params(&self) -> SourceSnapshotParams {
+        SourceSnapshotParams {
+            pipeline_id: self.window.pipeline_id(),
+            // TODO: Servo does not track transient activation yet, so whether the user is
+            // interacting with the page is used instead.
+            has_transient_activation: ScriptThread::is_user_interacting(),
+            sandboxing_flags: self.active_sandboxing_flag_set.get(),
+        }
+    }
+
+    /// Delay the steps of an activation-gated API until this prerendering document is
+    /// activated.
+    pub(crate) fn add_post_prerendering_activation_step<T: 'static + NonSendTaskBox>(
+        &self,
+        task: T,
+    ) {
+        self.post_prerendering_activation_steps
+            .borrow_mut()
+            .push(Box::new(task));
+    }
+
+    /// Activate this prerendering document, as a navigation made it the active document of
+    /// its top-level navigable.
+    /// <https://wicg.github.io/nav-speculation/prerendering.html#prerendering-traversable-activate>
+    pub(crate) fn activate_prerendered_document(&self, can_gc: CanGc) {
+        if !self.prerendering.get() {
+            return;
+        }
+
+        // Set doc's prerendering to false.
+        self.prerendering.set(false);
+
+        // The document stayed hidden while it was prerendered.
+        if self.page_showing.get() {
+            self.update_visibility_state(DocumentVisibilityState::Visible, can_gc);
+        }
+
+        // Fire an event named prerenderingchange at doc.
+        self.upcast::<EventTarget>()
+            .fire_event(Atom::from("prerenderingchange"), can_gc);
+
+        // For each steps in doc's post-prerendering activation steps list, run steps.
+        let steps = mem::take(&mut *self.post_prerendering_activation_steps.borrow_mut());
+        for step in steps {
+            step.run_box();
+        }
+
+        // The speculative loads of the document waited for its activation.
+        consider_speculative_loads(self);
+    }
+
+    pub(crate) fn is_frozen(&self) -> bool {
+        self.frozen.get()
+    }
+
+    fn media_client_context_id(&self) -> ClientContextId {
+        let pipeline_id = self.window().pipeline_id();
+        ClientContextId::build(pipeline_id.namespace_id.0, pipeline_id.index.0.get())
+    }
+
+    /// <https://wicg.github.io/page-lifecycle/#freeze-steps>
+    pub(crate) fn freeze(&self, can_gc: CanGc) {
+        if self.frozen.get() || !self.is_fully_active() {
+            return;
+        }
+
+        // Step 1. Fire an event named freeze at document, with its bubbles attribute
+        // initialized to true.
+        self.upcast::<EventTarget>()
+            .fire_bubbling_event(Atom::from("freeze"), can_gc);
+
+        // Step 2. Pause the media elements of document, and suspend the timers of its
+        // relevant global object. Its tasks are held back by the event loop.
+        ServoMedia::get().suspend(&self.media_client_context_id());
+        self.window().as_global_scope().suspend();
+
+        // Step 3. Set document's frozenness state to true.
+        self.frozen.set(true);
+    }
+
+    /// <https://wicg.github.io/page-lifecycle/#resume-steps>
+    pub(crate) fn resume(&self, can_gc: CanGc) {
+        if !self.frozen.get() {
+            return;
+        }
+
+        // Step 1. Set document's frozenness state to false.
+        self.frozen.set(false);
+        self.window().as_global_scope().resume();
+        ServoMedia::get().resume(&self.media_client_context_id());
+
+        // Step 2. Fire an event named resume at document, with its bubbles attribute
+        // initialized to true.
+        self.upcast::<EventTarget>()
+            .fire_bubbling_event(Atom::from("resume"), can_gc);
+    }
+
+    /// Save the scroll state of this document with its session history entry, so that it can
+    /// be restored if the document is loaded again.
+    /// <https://html.spec.whatwg.org/multipage/#persist-history-entry-state>
+    fn persist_scroll_state(&self) {
+        let scroll_state = self.window.History().current_scroll_state();
+        let _ = self
+            .window
+            .as_global_scope()
+            .script_to_constellation_chan()
+            .send(ScriptToConstellationMessage::PersistScrollState(
+                scroll_state,
+            ));
+    }
+
+    /// Restore the scroll state persisted by the previous document of this document's session
+    /// history entry, returning whether its scroll position was restored.
+    /// <https://html.spec.whatwg.org/multipage/#restore-persisted-state>
+    fn restore_persisted_scroll_state(&self) -> bool {
+        self.persisted_scroll_state
+            .take()
+            .is_some_and(|scroll_state| self.window.History().apply_scroll_state(scroll_state))
+    }
+
+    pub(crate) fn set_ancestor_origins(&self, ancestor_origins: Vec<ImmutableOrigin>) {
+        *self.ancestor_origins.borrow_mut() = ancestor_origins;
+    }
+
+    /// The ancestor origins of a navigable nested in this document, starting with the origin of
+    /// this document itself.
+    pub(crate) fn ancestor_origins_for_nested_navigable(&self) -> Vec<ImmutableOrigin> {
+        std::iter::once(self.origin().immutable().clone())
+            .chain(self.ancestor_origins.borrow().iter().cloned())
+            .collect()
+    }
+
+    fn form_associated_custom_elements(&self) -> impl Iterator<Item = DomRoot<HTMLElement>> + '_ {
+        self.upcast::<Node>()
+            .traverse_preorder(ShadowIncluding::No)
+            .filter_map(DomRoot::downcast::<HTMLElement>)
+            .filter(|element| element.is_form_associated_custom_element())
+    }
+
+    /// Save the state of the form-associated custom elements of this document with its
+    /// session history entry, so that it can be restored if the document is loaded again.
+    fn persist_form_state(&self) {
+        if !self.form_state_needs_persisting.get() {
+            return;
+        }
+        let form_state: Vec<_> = self
+            .form_associated_custom_elements()
+            .enumerate()
+            .filter_map(|(index, element)| {
+                let value = element
+                    .upcast::<Element>()
+                    .get_element_internals()?
+                    .persisted_state()?;
+                Some(PersistedFormControlState {
+                    index,
+                    local_name: element.upcast::<Element>().local_name().to_string(),
+                    value,
+                })
+            })
+            .collect();
+        // Send the state even if it is empty, so that it replaces any state saved by an
+        // earlier unload of the same session history entry.
+        let _ = self
+            .window
+            .as_global_scope()
+            .script_to_constellation_chan()
+            .send(ScriptToConstellationMessage::PersistFormState(form_state));
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#restore-persisted-state>
+    fn restore_persisted_form_state(&self, can_gc: CanGc) {
+        let form_state = self.persisted_form_state.take();
+        if form_state.is_empty() {
+            return;
+        }
+        let elements: Vec<_> = self.form_associated_custom_elements().collect();
+        for state in form_state {
+            // Only restore state if the element at the persisted position is of the same kind.
+            let Some(element) = elements.get(state.index) else {
+                continue;
+            };
+            let element = element.upcast::<Element>();
+            if &**element.local_name() != state.local_name {
+                continue;
+            }
+            element
+                .ensure_element_internals(can_gc)
+                .restore_state(state.value, can_gc);
+        }
+    }
+
     // https://html.spec.whatwg.org/multipage/#the-end
     pub(crate) fn maybe_queue_document_completion(&self) {
         // https://html.spec.whatwg.org/multipage/#delaying-load-events-mode
@@ -3143,8 +3765,10 @@ impl Document {
                 // http://w3c.github.io/navigation-timing/#widl-PerformanceNavigationTiming-loadEventEnd
                 update_with_current_instant(&document.load_event_end);
 
-                if let Some(fragment) = document.url().fragment() {
-                    document.check_and_scroll_fragment(fragment);
+                if !document.restore_persisted_scroll_state() {
+                    if let Some(fragment) = document.url().fragment() {
+                        document.check_and_scroll_fragment(fragment);
+                    }
                 }
             }));
 
@@ -3181,11 +3805,20 @@ impl Document {
         // Step 9.
         // TODO: pending application cache download process tasks.
 
-        // Step 10.
-        // TODO: printing steps.
-
-        // Step 11.
-        // TODO: ready for post-load tasks.
+        // Step 10. If the Document's print when loaded flag is set, then run the printing steps.
+        // Step 11. The Document is now ready for post-load tasks.
+        // Note: These steps are queued after the load and pageshow events, which they follow.
+        let document = Trusted::new(self);
+        self.owner_global()
+            .task_manager()
+            .dom_manipulation_task_source()
+            .queue(task!(ready_for_post_load_tasks: move || {
+                let document = document.root();
+                if document.print_when_loaded.get() && document.window().is_alive() {
+                    document.window().run_the_printing_steps(CanGc::note());
+                }
+                document.ready_for_post_load_tasks.set(true);
+            }));
 
         // The dom.webxr.sessionavailable pref allows webxr
         // content to immediately begin a session without waiting for a user gesture.
@@ -3232,6 +3865,14 @@ impl Document {
         self.completely_loaded.get()
     }
 
+    pub(crate) fn is_ready_for_post_load_tasks(&self) -> bool {
+        self.ready_for_post_load_tasks.get()
+    }
+
+    pub(crate) fn set_print_when_loaded(&self) {
+        self.print_when_loaded.set(true);
+    }
+
     // https://html.spec.whatwg.org/multipage/#pending-parsing-blocking-script
     pub(crate) fn set_pending_parsing_blocking_script(
         &self,
@@ -3381,6 +4022,8 @@ impl Document {
             "Complete before DOMContentLoaded?"
         );
 
+        self.restore_persisted_form_state(CanGc::note());
+
         update_with_current_instant(&self.dom_content_loaded_event_start);
 
         // Step 4.1.
@@ -3582,12 +4225,52 @@ impl Document {
         !self.has_browsing_context || !url_has_network_scheme(&self.url())
     }
 
+    /// The global custom element registry of this document, if it has one.
+    pub(crate) fn custom_element_registry(&self) -> Option<DomRoot<CustomElementRegistry>> {
+        if !pref!(dom_customelements_enabled) || !self.has_browsing_context {
+            return None;
+        }
+        Some(self.window.CustomElements())
+    }
+
+    /// <https://wicg.github.io/webcomponents/proposals/Scoped-Custom-Element-Registries>
+    /// Returns the is value and the scoped custom element registry to create an element with.
+    fn flatten_element_creation_options(
+        &self,
+        options: &StringOrElementCreationOptions,
+    ) -> Fallible<(Option<LocalName>, Option<DomRoot<CustomElementRegistry>>)> {
+        let StringOrElementCreationOptions::ElementCreationOptions(options) = options else {
+            return Ok((None, None));
+        };
+        let is = options.is.as_ref().map(|is| LocalName::from(&**is));
+        let Some(registry) = options.customElementRegistry.as_ref() else {
+            return Ok((is, None));
+        };
+
+        // A registry cannot be combined with an is value, and a global registry can only be
+        // used to create elements in its own document.
+        if is.is_some() {
+            return Err(Error::NotSupported);
+        }
+        if !registry.is_scoped() {
+            if self.custom_element_registry().as_deref() != Some(&**registry) {
+                return Err(Error::NotSupported);
+            }
+            return Ok((None, None));
+        }
+        Ok((None, Some(registry.clone())))
+    }
+
     /// <https://html.spec.whatwg.org/multipage/#look-up-a-custom-element-definition>
+    ///
+    /// `scoped_registry` is the scoped custom element registry to look in instead of the
+    /// document's global registry, if any.
     pub(crate) fn lookup_custom_element_definition(
         &self,
         namespace: &Namespace,
         local_name: &LocalName,
         is: Option<&LocalName>,
+        scoped_registry: Option<&CustomElementRegistry>,
     ) -> Option<Rc<CustomElementDefinition>> {
         if !pref!(dom_customelements_enabled) {
             return None;
@@ -3598,13 +4281,11 @@ impl Document {
             return None;
         }
 
-        // Step 2
-        if !self.has_browsing_context {
-            return None;
-        }
-
-        // Step 3
-        let registry = self.window.CustomElements();
+        // Steps 2-3
+        let registry = match scoped_registry {
+            Some(registry) => DomRoot::from_ref(registry),
+            None => self.custom_element_registry()?,
+        };
 
         registry.lookup_definition(local_name, is)
     }
@@ -3703,6 +4384,14 @@ impl Document {
         if self.window().has_unhandled_resize_event() {
             return true;
         }
+        if self.active_view_transition.get().is_some_and(|transition| {
+            matches!(
+                transition.phase(),
+                ViewTransitionPhase::PendingCapture | ViewTransitionPhase::Animating
+            )
+        }) {
+            return true;
+        }
 
         false
     }
@@ -3890,7 +4579,7 @@ impl Document {
     }
 
     /// <https://html.spec.whatwg.org/multipage/#allowed-to-use>
-    pub(crate) fn allowed_to_use_feature(&self, _feature: PermissionName) -> bool {
+    pub(crate) fn allowed_to_use_feature(&self, feature: PolicyControlledFeature) -> bool {
         // Step 1. If document's browsing context is null, then return false.
         if !self.has_browsing_context {
             return false;
@@ -3904,9 +4593,39 @@ impl Document {
         // Step 3. If the result of running is feature enabled in document for origin on
         // feature, document, and document's origin is "Enabled", then return true.
         // Step 4. Return false.
-        // TODO: All features are currently enabled for `Document`s because we do not
-        // implement the Permissions Policy specification.
-        true
+        let origin = self.origin().immutable();
+        self.permissions_policy
+            .borrow()
+            .is_feature_enabled_for_origin(feature, origin, origin)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#start-intersection-observing-a-lazy-loading-element>
+    pub(crate) fn start_intersection_observing_a_lazy_loading_element(
+        &self,
+        element: &Element,
+        can_gc: CanGc,
+    ) {
+        // Step 1. Let doc be element's node document.
+        // Step 2. If doc's lazy load intersection observer is null, set it to a new
+        // IntersectionObserver instance.
+        let observer = self.lazy_load_intersection_observer.or_init(|| {
+            IntersectionObserver::new_lazy_load_intersection_observer(&self.window, can_gc)
+        });
+
+        // Step 3. Call doc's lazy load intersection observer's observe method with element as
+        // the argument.
+        observer.observe_target_element(element);
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#stop-intersection-observing-a-lazy-loading-element>
+    pub(crate) fn stop_intersection_observing_a_lazy_loading_element(&self, element: &Element) {
+        // Step 1. Let doc be element's node document.
+        // Step 2. Assert: doc's lazy load intersection observer is not null.
+        // Step 3. Call doc's lazy load intersection observer's unobserve method with element
+        // as the argument.
+        if let Some(observer) = self.lazy_load_intersection_observer.get() {
+            observer.unobserve_target_element(element);
+        }
     }
 
     /// Add an [`IntersectionObserver`] to the [`Document`], to be processed in the [`Document`]'s event loop.
@@ -4192,7 +4911,7 @@ impl<'dom> LayoutDocumentHelpers<'dom> for LayoutDom<'dom, Document> {
 // https://html.spec.whatwg.org/multipage/#is-a-registrable-domain-suffix-of-or-is-equal-to
 // The spec says to return a bool, we actually return an Option<Host> containing
 // the parsed host in the successful case, to avoid having to re-parse the host.
-fn get_registrable_domain_suffix_of_or_is_equal_to(
+pub(crate) fn get_registrable_domain_suffix_of_or_is_equal_to(
     host_suffix_string: &str,
     original_host: Host,
 ) -> Option<Host> {
@@ -4391,11 +5110,25 @@ impl Document {
             target_element: MutNullableDom::new(None),
             policy_container: DomRefCell::new(PolicyContainer::default()),
             last_click_info: DomRefCell::new(None),
+            drag_and_drop: DragAndDropState::new(),
+            persisted_form_state: Default::default(),
+            form_state_needs_persisting: Cell::new(false),
+            persisted_scroll_state: Default::default(),
+            ancestor_origins: Default::default(),
+            speculation_rule_sets: Default::default(),
+            prefetch_records: Default::default(),
+            prerendering: Cell::new(false),
+            frozen: Cell::new(false),
+            post_prerendering_activation_steps: Default::default(),
+            active_sandboxing_flag_set: Cell::new(SandboxingFlagSet::empty()),
+            permissions_policy: Default::default(),
             ignore_destructive_writes_counter: Default::default(),
             ignore_opens_during_unload_counter: Default::default(),
             spurious_animation_frames: Cell::new(0),
             dom_count: Cell::new(1),
             fullscreen_element: MutNullableDom::new(None),
+            top_layer: Default::default(),
+            popover_pointerdown_target: Default::default(),
             form_id_listener_map: Default::default(),
             interactive_time: DomRefCell::new(interactive_time),
             tti_window: DomRefCell::new(InteractiveWindow::default()),
@@ -4408,6 +5141,8 @@ impl Document {
             responsive_images: Default::default(),
             redirect_count: Cell::new(0),
             completely_loaded: Cell::new(false),
+            ready_for_post_load_tasks: Cell::new(false),
+            print_when_loaded: Cell::new(false),
             script_and_layout_blockers: Cell::new(0),
             delayed_tasks: Default::default(),
             shadow_roots: DomRefCell::new(HashSet::new()),
@@ -4439,12 +5174,16 @@ impl Document {
             has_trustworthy_ancestor_origin: Cell::new(has_trustworthy_ancestor_origin),
             intersection_observer_task_queued: Cell::new(false),
             intersection_observers: Default::default(),
+            lazy_load_intersection_observer: Default::default(),
             active_keyboard_modifiers: Cell::new(Modifiers::empty()),
             highlighted_dom_node: Default::default(),
             adopted_stylesheets: Default::default(),
             adopted_stylesheets_frozen_types: CachedFrozenArray::new(),
             pending_scroll_event_targets: Default::default(),
+            pending_scrollend_event_targets: Default::default(),
             resize_observer_started_observing_target: Cell::new(false),
+            active_view_transition: Default::default(),
+            rendering_suppression_for_view_transitions: Cell::new(false),
         }
     }
 
@@ -4521,7 +5260,8 @@ impl Document {
     /// Whether or not this [`Document`] has any pending scroll events to be processed during
     /// "update the rendering."
     fn has_pending_scroll_events(&self) -> bool {
-        !self.pending_scroll_event_targets.borrow().is_empty()
+        !self.pending_scroll_event_targets.borrow().is_empty() ||
+            !self.pending_scrollend_event_targets.borrow().is_empty()
     }
 
     pub(crate) fn set_resize_observer_started_observing_target(&self, value: bool) {
@@ -4822,6 +5562,7 @@ impl Document {
     }
 
     pub(crate) fn element_state_will_change(&self, el: &Element) {
+        el.invalidate_relative_selector_anchors(None);
         let mut entry = self.ensure_pending_restyle(el);
         if entry.snapshot.is_none() {
             entry.snapshot = Some(Snapshot::new());
@@ -4838,6 +5579,7 @@ impl Document {
         // I'm getting rid of the whole hashtable soon anyway, since all it does
         // right now is populate the element restyle data in layout, and we
         // could in theory do it in the DOM I think.
+        el.invalidate_relative_selector_anchors(None);
         let mut entry = self.ensure_pending_restyle(el);
         if entry.snapshot.is_none() {
             entry.snapshot = Some(Snapshot::new());
@@ -4888,6 +5630,12 @@ impl Document {
         self.policy_container.borrow().get_referrer_policy()
     }
 
+    pub(crate) fn set_embedder_policy(&self, embedder_policy: EmbedderPolicy) {
+        self.policy_container
+            .borrow_mut()
+            .set_embedder_policy(embedder_policy);
+    }
+
     pub(crate) fn set_target_element(&self, node: Option<&Element>) {
         if let Some(ref element) = self.target_element.get() {
             element.set_target_state(false);
@@ -5032,26 +5780,240 @@ impl Document {
     }
 
     pub(crate) fn set_fullscreen_element(&self, element: Option<&Element>) {
+        // The fullscreen element is rendered in the top layer, together with modal dialogs
+        // and popovers.
+        if let Some(old_element) = self.fullscreen_element.get() {
+            self.remove_from_top_layer(&old_element);
+        }
+        if let Some(element) = element {
+            self.add_to_top_layer(element);
+        }
         self.fullscreen_element.set(element);
     }
 
-    pub(crate) fn get_allow_fullscreen(&self) -> bool {
-        // https://html.spec.whatwg.org/multipage/#allowed-to-use
-        match self.browsing_context() {
-            // Step 1
-            None => false,
-            Some(_) => {
-                // Step 2
-                let window = self.window();
-                if window.is_top_level() {
-                    true
-                } else {
-                    // Step 3
-                    window
-                        .GetFrameElement()
-                        .is_some_and(|el| el.has_attribute(&local_name!("allowfullscreen")))
+    /// <https://drafts.csswg.org/css-position-4/#add-an-element-to-the-top-layer>
+    pub(crate) fn add_to_top_layer(&self, element: &Element) {
+        let mut top_layer = self.top_layer.borrow_mut();
+        top_layer.retain(|other| &**other != element);
+        top_layer.push(Dom::from_ref(element));
+    }
+
+    /// <https://drafts.csswg.org/css-position-4/#remove-an-element-from-the-top-layer-immediately>
+    pub(crate) fn remove_from_top_layer(&self, element: &Element) {
+        self.top_layer
+            .borrow_mut()
+            .retain(|other| &**other != element);
+    }
+
+    pub(crate) fn top_layer_contains(&self, element: &Element) -> bool {
+        self.top_layer
+            .borrow()
+            .iter()
+            .any(|other| &**other == element)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#blocked-by-a-modal-dialog>
+    /// The topmost modal dialog in the top layer, which blocks the rest of this document.
+    pub(crate) fn active_modal_dialog(&self) -> Option<DomRoot<HTMLDialogElement>> {
+        self.top_layer
+            .borrow()
+            .iter()
+            .rev()
+            .filter_map(|element| element.downcast::<HTMLDialogElement>())
+            .find(|dialog| dialog.is_modal())
+            .map(DomRoot::from_ref)
+    }
+
+    /// Close the topmost modal dialog or auto popover in the top layer, which are the only
+    /// kinds of close watchers for now. Returns false if there was nothing to close.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/#process-close-watchers>
+    fn process_close_request(&self, can_gc: CanGc) -> bool {
+        let topmost = self
+            .top_layer
+            .borrow()
+            .iter()
+            .rev()
+            .filter_map(|element| element.downcast::<HTMLElement>())
+            .find(|element| {
+                element
+                    .downcast::<HTMLDialogElement>()
+                    .is_some_and(|dialog| dialog.is_modal()) ||
+                    element.is_showing_auto_popover()
+            })
+            .map(DomRoot::from_ref);
+        let Some(topmost) = topmost else {
+            return false;
+        };
+
+        match topmost.downcast::<HTMLDialogElement>() {
+            Some(dialog) if dialog.is_modal() => dialog.request_close(can_gc),
+            _ => {
+                let _ = topmost.hide_popover(true, true, false, can_gc);
+            },
+        }
+        true
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#showing-auto-popover-list>
+    pub(crate) fn showing_auto_popover_list(&self) -> Vec<DomRoot<HTMLElement>> {
+        self.top_layer
+            .borrow()
+            .iter()
+            .filter_map(|element| element.downcast::<HTMLElement>())
+            .filter(|element| element.is_showing_auto_popover())
+            .map(DomRoot::from_ref)
+            .collect()
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#topmost-auto-popover>
+    pub(crate) fn topmost_auto_popover(&self) -> Option<DomRoot<HTMLElement>> {
+        self.showing_auto_popover_list().pop()
+    }
+
+    /// Run the hide popover algorithm on the last item of the showing auto popover list.
+    /// Returns false if there was no such popover or it could not be hidden, so that callers
+    /// hiding popovers in a loop never spin on the same popover.
+    fn hide_topmost_auto_popover(
+        &self,
+        focus_previous_element: bool,
+        fire_events: bool,
+        can_gc: CanGc,
+    ) -> bool {
+        let Some(popover) = self.topmost_auto_popover() else {
+            return false;
+        };
+        let _ = popover.hide_popover(focus_previous_element, fire_events, false, can_gc);
+        !popover.is_showing_auto_popover()
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#close-entire-popover-list>
+    fn close_entire_popover_list(
+        &self,
+        focus_previous_element: bool,
+        fire_events: bool,
+        can_gc: CanGc,
+    ) {
+        // Step 1. While popoverList is not empty, run the hide popover algorithm given
+        // popoverList's last item, focusPreviousElement, fireEvents, false, and false.
+        while self.hide_topmost_auto_popover(focus_previous_element, fire_events, can_gc) {}
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#hide-all-popovers-until>
+    ///
+    /// An `endpoint` of `None` stands for this document itself.
+    pub(crate) fn hide_all_popovers_until(
+        &self,
+        endpoint: Option<&HTMLElement>,
+        focus_previous_element: bool,
+        mut fire_events: bool,
+        can_gc: CanGc,
+    ) {
+        // Step 5. If endpoint is a Document, then run close entire popover list given
+        // document's showing auto popover list, focusPreviousElement, and fireEvents, and
+        // return.
+        let Some(endpoint) = endpoint else {
+            self.close_entire_popover_list(focus_previous_element, fire_events, can_gc);
+            return;
+        };
+
+        // Step 1. If endpoint is an HTML element and endpoint is not in the popover showing
+        // state, then return.
+        if !endpoint.is_popover_showing() {
+            return;
+        }
+
+        // Step 7. If document's showing auto popover list does not contain endpoint, then
+        // return.
+        if !self
+            .showing_auto_popover_list()
+            .iter()
+            .any(|popover| &**popover == endpoint)
+        {
+            return;
+        }
+
+        // Step 8. Let repeatingHide be false.
+        // Step 9. Perform the following steps at least once:
+        loop {
+            // Step 9.1. Let lastToHide be null.
+            // Step 9.2. For each popover in document's showing auto popover list: if popover is
+            // endpoint, then set foundEndpoint to true; otherwise, if foundEndpoint is true,
+            // then set lastToHide to popover and break.
+            let popover_list = self.showing_auto_popover_list();
+            let Some(endpoint_index) = popover_list
+                .iter()
+                .position(|popover| &**popover == endpoint)
+            else {
+                // Step 9.3. If foundEndpoint is false, then run close entire popover list
+                // given document's showing auto popover list, focusPreviousElement, and
+                // fireEvents, and return.
+                self.close_entire_popover_list(focus_previous_element, fire_events, can_gc);
+                return;
+            };
+
+            // Step 9.4. While lastToHide is not null and lastToHide's popover visibility state
+            // is showing and document's showing auto popover list is not empty, run the hide
+            // popover algorithm given the last item of document's showing auto popover list,
+            // focusPreviousElement, fireEvents, false, and false.
+            if let Some(last_to_hide) = popover_list.get(endpoint_index + 1) {
+                while last_to_hide.is_popover_showing() {
+                    if !self.hide_topmost_auto_popover(focus_previous_element, fire_events, can_gc)
+                    {
+                        return;
+                    }
+                }
+            }
+
+            // Step 9.6. Set repeatingHide to true if document's showing auto popover list
+            // contains endpoint and endpoint is not the last item in it, otherwise false.
+            let popover_list = self.showing_auto_popover_list();
+            let repeating_hide = popover_list.iter().any(|popover| &**popover == endpoint) &&
+                popover_list.last().map(|popover| &**popover) != Some(endpoint);
+            if !repeating_hide {
+                break;
+            }
+
+            // Step 9.7. If repeatingHide is true, then set fireEvents to false.
+            fire_events = false;
+        }
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#light-dismiss-open-popovers>
+    fn light_dismiss_open_popovers(&self, action: MouseButtonAction, target: &Node, can_gc: CanGc) {
+        // Step 4. Let topmostPopover be the result of running topmost auto popover given
+        // document.
+        // Step 5. If topmostPopover is null, then return.
+        if self.topmost_auto_popover().is_none() {
+            return;
+        }
+
+        match action {
+            // Step 6. If event is a pointerdown event, then set document's popover pointerdown
+            // target to the result of running topmost clicked popover given target.
+            MouseButtonAction::Down => {
+                self.popover_pointerdown_target
+                    .set(HTMLElement::topmost_clicked_popover(target).as_deref());
+            },
+            // Step 7. If event is a pointerup event, then:
+            MouseButtonAction::Up => {
+                // Step 7.1. Let ancestor be the result of running topmost clicked popover given
+                // target.
+                // Step 7.2. Let sameTarget be true if ancestor is document's popover pointerdown
+                // target.
+                // Step 7.3. Set document's popover pointerdown target to null.
+                let ancestor = HTMLElement::topmost_clicked_popover(target);
+                let same_target = ancestor == self.popover_pointerdown_target.get();
+                self.popover_pointerdown_target.set(None);
+
+                // Step 7.4. If ancestor is null, then set ancestor to document.
+                // Step 7.5. If sameTarget is true, then run hide all popovers until given
+                // ancestor, false, and true.
+                if same_target {
+                    self.hide_all_popovers_until(ancestor.as_deref(), false, true, can_gc);
                 }
             },
+            MouseButtonAction::Click => {},
         }
     }
 
@@ -5445,7 +6407,7 @@ impl Document {
         // TODO ScreenOrientation hasn't implemented yet
 
         // Step 5 Run the view transition page visibility change steps with document.
-        // TODO ViewTransition hasn't implemented yet
+        self.run_the_view_transition_page_visibility_change_steps();
 
         // Step 6 Run any page visibility change steps which may be defined in other specifications, with visibility
         // state and document. Any other specs' visibility steps will go here.
@@ -5495,6 +6457,69 @@ impl Document {
     pub(crate) fn highlighted_dom_node(&self) -> Option<DomRoot<Node>> {
         self.highlighted_dom_node.get()
     }
+
+    pub(crate) fn active_view_transition(&self) -> Option<DomRoot<ViewTransition>> {
+        self.active_view_transition.get()
+    }
+
+    pub(crate) fn set_active_view_transition(&self, transition: Option<&ViewTransition>) {
+        self.active_view_transition.set(transition);
+    }
+
+    pub(crate) fn rendering_is_suppressed_for_view_transitions(&self) -> bool {
+        self.rendering_suppression_for_view_transitions.get()
+    }
+
+    pub(crate) fn set_rendering_suppression_for_view_transitions(&self, suppressed: bool) {
+        self.rendering_suppression_for_view_transitions
+            .set(suppressed);
+    }
+
+    /// The old state of the document painted over it by its active view transition, if any.
+    pub(crate) fn view_transition_snapshot(&self) -> Option<ViewTransitionSnapshot> {
+        self.active_view_transition
+            .get()
+            .and_then(|transition| transition.old_state_snapshot())
+    }
+
+    /// <https://drafts.csswg.org/css-view-transitions-1/#perform-pending-transition-operations>
+    pub(crate) fn perform_pending_transition_operations(&self, can_gc: CanGc) {
+        // Step 1. If document’s active view transition is not null, then:
+        let Some(transition) = self.active_view_transition.get() else {
+            return;
+        };
+        match transition.phase() {
+            // Step 1.1. If document’s active view transition’s phase is "pending-capture",
+            // then setup view transition for document’s active view transition.
+            ViewTransitionPhase::PendingCapture => transition.setup(can_gc),
+            // Step 1.2. Otherwise, if document’s active view transition’s phase is
+            // "animating", then handle transition frame for document’s active view transition.
+            ViewTransitionPhase::Animating => transition.handle_transition_frame(can_gc),
+            ViewTransitionPhase::UpdateCallbackCalled | ViewTransitionPhase::Done => {},
+        }
+    }
+
+    /// <https://drafts.csswg.org/css-view-transitions-1/#view-transition-page-visibility-change-steps>
+    fn run_the_view_transition_page_visibility_change_steps(&self) {
+        // Step 1. Queue a global task on the DOM manipulation task source, given document’s
+        // relevant global object, to perform the following steps:
+        let document = Trusted::new(self);
+        self.owner_global()
+            .task_manager()
+            .dom_manipulation_task_source()
+            .queue(task!(view_transition_page_visibility_change: move || {
+                let document = document.root();
+                // Step 1.1. If document’s visibility state is "hidden", then:
+                if document.visibility_state.get() != DocumentVisibilityState::Hidden {
+                    return;
+                }
+                // Step 1.1.1. If document’s active view transition is not null, then skip
+                // document’s active view transition with an "InvalidStateError" DOMException.
+                if let Some(transition) = document.active_view_transition.get() {
+                    transition.skip_with_error(Error::InvalidState, CanGc::note());
+                }
+            }));
+    }
 }
 
 #[allow(non_snake_case)]
@@ -5530,11 +6555,49 @@ impl DocumentMethods<crate::DomTypeHolder> for Document {
         ))
     }
 
-    // https://w3c.github.io/editing/ActiveDocuments/execCommand.html#querycommandsupported()
-    fn QueryCommandSupported(&self, _command: DOMString) -> bool {
+    /// <https://w3c.github.io/editing/docs/execCommand/#execcommand()>
+    fn ExecCommand(
+        &self,
+        command_id: DOMString,
+        _show_ui: bool,
+        value: DOMString,
+        can_gc: CanGc,
+    ) -> bool {
+        let Some(command) = editing::EditingCommand::from_command_id(&command_id) else {
+            return false;
+        };
+        editing::execute_command(self, command, value, can_gc)
+    }
+
+    /// <https://w3c.github.io/editing/docs/execCommand/#querycommandenabled()>
+    fn QueryCommandEnabled(&self, command_id: DOMString, can_gc: CanGc) -> bool {
+        editing::EditingCommand::from_command_id(&command_id)
+            .is_some_and(|command| editing::query_command_enabled(self, command, can_gc))
+    }
+
+    /// <https://w3c.github.io/editing/docs/execCommand/#querycommandindeterm()>
+    fn QueryCommandIndeterm(&self, _command_id: DOMString) -> bool {
+        // None of the supported commands have an indeterminacy.
         false
     }
 
+    /// <https://w3c.github.io/editing/docs/execCommand/#querycommandstate()>
+    fn QueryCommandState(&self, command_id: DOMString, can_gc: CanGc) -> bool {
+        editing::EditingCommand::from_command_id(&command_id)
+            .is_some_and(|command| editing::query_command_state(self, command, can_gc))
+    }
+
+    /// <https://w3c.github.io/editing/docs/execCommand/#querycommandsupported()>
+    fn QueryCommandSupported(&self, command_id: DOMString) -> bool {
+        editing::EditingCommand::from_command_id(&command_id).is_some()
+    }
+
+    /// <https://w3c.github.io/editing/docs/execCommand/#querycommandvalue()>
+    fn QueryCommandValue(&self, _command_id: DOMString) -> DOMString {
+        // None of the supported commands have a value.
+        DOMString::new()
+    }
+
     // https://drafts.csswg.org/cssom/#dom-document-stylesheets
     fn StyleSheets(&self, can_gc: CanGc) -> DomRoot<StyleSheetList> {
         self.stylesheet_list.or_init(|| {
@@ -5698,6 +6761,11 @@ impl DocumentMethods<crate::DomTypeHolder> for Document {
         self.upcast::<Node>().child_elements().next()
     }
 
+    /// <https://wicg.github.io/webcomponents/proposals/Scoped-Custom-Element-Registries>
+    fn GetCustomElementRegistry(&self) -> Option<DomRoot<CustomElementRegistry>> {
+        self.custom_element_registry()
+    }
+
     // https://dom.spec.whatwg.org/#dom-document-getelementsbytagname
     fn GetElementsByTagName(
         &self,
@@ -5789,16 +6857,12 @@ impl DocumentMethods<crate::DomTypeHolder> for Document {
         };
 
         let name = QualName::new(None, ns, LocalName::from(local_name));
-        let is = match options {
-            StringOrElementCreationOptions::String(_) => None,
-            StringOrElementCreationOptions::ElementCreationOptions(options) => {
-                options.is.as_ref().map(|is| LocalName::from(&**is))
-            },
-        };
+        let (is, registry) = self.flatten_element_creation_options(&options)?;
         Ok(Element::create(
             name,
             is,
             self,
+            registry.as_deref(),
             ElementCreator::ScriptCreated,
             CustomElementCreationMode::Synchronous,
             None,
@@ -5823,18 +6887,14 @@ impl DocumentMethods<crate::DomTypeHolder> for Document {
         // Step 2. Let is be null.
         // Step 3. If options is a dictionary and options["is"] exists, then set is to it.
         let name = QualName::new(prefix, namespace, local_name);
-        let is = match options {
-            StringOrElementCreationOptions::String(_) => None,
-            StringOrElementCreationOptions::ElementCreationOptions(options) => {
-                options.is.as_ref().map(|is| LocalName::from(&**is))
-            },
-        };
+        let (is, registry) = self.flatten_element_creation_options(&options)?;
 
         // Step 4. Return the result of creating an element given document, localName, namespace, prefix, is, and true.
         Ok(Element::create(
             name,
             is,
             self,
+            registry.as_deref(),
             ElementCreator::ScriptCreated,
             CustomElementCreationMode::Synchronous,
             None,
@@ -6108,6 +7168,7 @@ impl DocumentMethods<crate::DomTypeHolder> for Document {
                         name,
                         None,
                         self,
+                        None,
                         ElementCreator::ScriptCreated,
                         CustomElementCreationMode::Synchronous,
                         None,
@@ -6134,6 +7195,7 @@ impl DocumentMethods<crate::DomTypeHolder> for Document {
                             name,
                             None,
                             self,
+                            None,
                             ElementCreator::ScriptCreated,
                             CustomElementCreationMode::Synchronous,
                             None,
@@ -6412,7 +7474,12 @@ impl DocumentMethods<crate::DomTypeHolder> for Document {
             .window
             .as_global_scope()
             .resource_threads()
-            .send(GetCookiesForUrl(url, tx, NonHTTP));
+            .send(GetCookiesForUrl(
+                url,
+                tx,
+                NonHTTP,
+                self.ancestor_origins_for_nested_navigable(),
+            ));
         let cookies = rx.recv().unwrap();
         Ok(cookies.map_or(DOMString::new(), DOMString::from))
     }
@@ -6437,7 +7504,12 @@ impl DocumentMethods<crate::DomTypeHolder> for Document {
             .window
             .as_global_scope()
             .resource_threads()
-            .send(SetCookiesForUrl(self.url(), cookies, NonHTTP));
+            .send(SetCookiesForUrl(
+                self.url(),
+                cookies,
+                NonHTTP,
+                self.ancestor_origins_for_nested_navigable(),
+            ));
         Ok(())
     }
 
@@ -6613,6 +7685,24 @@ impl DocumentMethods<crate::DomTypeHolder> for Document {
     // https://html.spec.whatwg.org/multipage/#globaleventhandlers
     global_event_handlers!();
 
+    /// <https://wicg.github.io/nav-speculation/prerendering.html#dom-document-prerendering>
+    fn Prerendering(&self) -> bool {
+        self.prerendering.get()
+    }
+
+    // https://wicg.github.io/nav-speculation/prerendering.html#dom-document-onprerenderingchange
+    event_handler!(
+        prerenderingchange,
+        GetOnprerenderingchange,
+        SetOnprerenderingchange
+    );
+
+    // https://wicg.github.io/page-lifecycle/#dom-document-onfreeze
+    event_handler!(freeze, GetOnfreeze, SetOnfreeze);
+
+    // https://wicg.github.io/page-lifecycle/#dom-document-onresume
+    event_handler!(resume, GetOnresume, SetOnresume);
+
     // https://html.spec.whatwg.org/multipage/#handler-onreadystatechange
     event_handler!(
         readystatechange,
@@ -6852,7 +7942,7 @@ impl DocumentMethods<crate::DomTypeHolder> for Document {
 
     // https://fullscreen.spec.whatwg.org/#dom-document-fullscreenenabled
     fn FullscreenEnabled(&self) -> bool {
-        self.get_allow_fullscreen()
+        self.allowed_to_use_feature(PolicyControlledFeature::Fullscreen)
     }
 
     // https://fullscreen.spec.whatwg.org/#dom-document-fullscreen
@@ -6881,6 +7971,40 @@ impl DocumentMethods<crate::DomTypeHolder> for Document {
         }
     }
 
+    /// <https://drafts.csswg.org/css-view-transitions-1/#dom-document-startviewtransition>
+    fn StartViewTransition(
+        &self,
+        update_callback: Option<Rc<ViewTransitionUpdateCallback>>,
+        can_gc: CanGc,
+    ) -> DomRoot<ViewTransition> {
+        // Step 1. Let transition be a new ViewTransition object in this’s relevant Realm.
+        // Step 2. If updateCallback is provided, set transition’s update callback to
+        // updateCallback.
+        let transition = ViewTransition::new(&self.window, update_callback, can_gc);
+
+        // Step 3. Let document be this’s relevant global object’s associated document.
+        let document = self.window.Document();
+
+        // Step 4. If document’s visibility state is "hidden", then skip transition with an
+        // "InvalidStateError" DOMException, and return transition.
+        if document.visibility_state.get() == DocumentVisibilityState::Hidden {
+            transition.skip_with_error(Error::InvalidState, can_gc);
+            return transition;
+        }
+
+        // Step 5. If document’s active view transition is not null, then skip that view
+        // transition with an "AbortError" DOMException in this’s relevant Realm.
+        if let Some(active_view_transition) = document.active_view_transition.get() {
+            active_view_transition.skip_with_error(Error::Abort, can_gc);
+        }
+
+        // Step 6. Set document’s active view transition to transition.
+        document.active_view_transition.set(Some(&transition));
+
+        // Step 7. Return transition.
+        transition
+    }
+
     // https://w3c.github.io/selection-api/#dom-document-getselection
     fn GetSelection(&self, can_gc: CanGc) -> Option<DomRoot<Selection>> {
         if self.has_browsing_context {
@@ -6997,14 +8121,14 @@ fn update_with_current_instant(marker: &Cell<Option<CrossProcessInstant>>) {
 /// <https://w3c.github.io/webappsec-referrer-policy/#determine-policy-for-token>
 pub(crate) fn determine_policy_for_token(token: &str) -> ReferrerPolicy {
     match_ignore_ascii_case! { token,
-        "never" | "no-referrer" => ReferrerPolicy::NoReferrer,
+        "no-referrer" => ReferrerPolicy::NoReferrer,
         "no-referrer-when-downgrade" => ReferrerPolicy::NoReferrerWhenDowngrade,
         "origin" => ReferrerPolicy::Origin,
         "same-origin" => ReferrerPolicy::SameOrigin,
         "strict-origin" => ReferrerPolicy::StrictOrigin,
-        "default" | "strict-origin-when-cross-origin" => ReferrerPolicy::StrictOriginWhenCrossOrigin,
+        "strict-origin-when-cross-origin" => ReferrerPolicy::StrictOriginWhenCrossOrigin,
         "origin-when-cross-origin" => ReferrerPolicy::OriginWhenCrossOrigin,
-        "always" | "unsafe-url" => ReferrerPolicy::UnsafeUrl,
+        "unsafe-url" => ReferrerPolicy::UnsafeUrl,
         _ => ReferrerPolicy::EmptyString,
     }
 }
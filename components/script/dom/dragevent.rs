@@ -0,0 +1,164 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use euclid::Point2D;
+use js::rust::HandleObject;
+use keyboard_types::Modifiers;
+use style_traits::CSSPixel;
+
+use crate::dom::bindings::codegen::Bindings::DragEventBinding::{DragEventInit, DragEventMethods};
+use crate::dom::bindings::codegen::Bindings::MouseEventBinding::MouseEventMethods;
+use crate::dom::bindings::error::Fallible;
+use crate::dom::bindings::reflector::reflect_dom_object_with_proto;
+use crate::dom::bindings::root::{DomRoot, MutNullableDom};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::datatransfer::DataTransfer;
+use crate::dom::event::{EventBubbles, EventCancelable};
+use crate::dom::eventtarget::EventTarget;
+use crate::dom::mouseevent::MouseEvent;
+use crate::dom::window::Window;
+use crate::script_runtime::CanGc;
+
+/// <https://html.spec.whatwg.org/multipage/#dragevent>
+#[dom_struct]
+pub(crate) struct DragEvent {
+    mouseevent: MouseEvent,
+    data_transfer: MutNullableDom<DataTransfer>,
+}
+
+impl DragEvent {
+    fn new_inherited() -> DragEvent {
+        DragEvent {
+            mouseevent: MouseEvent::new_inherited(),
+            data_transfer: MutNullableDom::new(None),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        window: &Window,
+        type_: DOMString,
+        can_bubble: EventBubbles,
+        cancelable: EventCancelable,
+        client_point: Point2D<i32, CSSPixel>,
+        page_point: Point2D<i32, CSSPixel>,
+        modifiers: Modifiers,
+        buttons: u16,
+        related_target: Option<&EventTarget>,
+        data_transfer: Option<&DataTransfer>,
+        can_gc: CanGc,
+    ) -> DomRoot<DragEvent> {
+        Self::new_with_proto(
+            window,
+            None,
+            type_,
+            can_bubble,
+            cancelable,
+            Some(window),
+            0,
+            client_point,
+            client_point,
+            page_point,
+            modifiers,
+            0,
+            buttons,
+            related_target,
+            data_transfer,
+            can_gc,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn new_with_proto(
+        window: &Window,
+        proto: Option<HandleObject>,
+        type_: DOMString,
+        can_bubble: EventBubbles,
+        cancelable: EventCancelable,
+        view: Option<&Window>,
+        detail: i32,
+        screen_point: Point2D<i32, CSSPixel>,
+        client_point: Point2D<i32, CSSPixel>,
+        page_point: Point2D<i32, CSSPixel>,
+        modifiers: Modifiers,
+        button: i16,
+        buttons: u16,
+        related_target: Option<&EventTarget>,
+        data_transfer: Option<&DataTransfer>,
+        can_gc: CanGc,
+    ) -> DomRoot<DragEvent> {
+        let event = reflect_dom_object_with_proto(
+            Box::new(DragEvent::new_inherited()),
+            window,
+            proto,
+            can_gc,
+        );
+        event.mouseevent.initialize_mouse_event(
+            type_,
+            can_bubble,
+            cancelable,
+            view,
+            detail,
+            screen_point,
+            client_point,
+            page_point,
+            modifiers,
+            button,
+            buttons,
+            related_target,
+            None,
+        );
+        event.data_transfer.set(data_transfer);
+        event
+    }
+}
+
+impl DragEventMethods<crate::DomTypeHolder> for DragEvent {
+    /// <https://html.spec.whatwg.org/multipage/#dom-dragevent>
+    fn Constructor(
+        window: &Window,
+        proto: Option<HandleObject>,
+        can_gc: CanGc,
+        type_: DOMString,
+        init: &DragEventInit,
+    ) -> Fallible<DomRoot<DragEvent>> {
+        let mouse_init = &init.parent;
+        let ui_init = &mouse_init.parent.parent;
+        let scroll_offset = window.scroll_offset();
+        let page_point = Point2D::new(
+            scroll_offset.x as i32 + mouse_init.clientX,
+            scroll_offset.y as i32 + mouse_init.clientY,
+        );
+
+        Ok(DragEvent::new_with_proto(
+            window,
+            proto,
+            type_,
+            EventBubbles::from(ui_init.parent.bubbles),
+            EventCancelable::from(ui_init.parent.cancelable),
+            ui_init.view.as_deref(),
+            ui_init.detail,
+            Point2D::new(mouse_init.screenX, mouse_init.screenY),
+            Point2D::new(mouse_init.clientX, mouse_init.clientY),
+            page_point,
+            mouse_init.parent.modifiers(),
+            mouse_init.button,
+            mouse_init.buttons,
+            mouse_init.relatedTarget.as_deref(),
+            init.dataTransfer.as_deref(),
+            can_gc,
+        ))
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-dragevent-datatransfer>
+    fn GetDataTransfer(&self) -> Option<DomRoot<DataTransfer>> {
+        self.data_transfer.get()
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-event-istrusted>
+    fn IsTrusted(&self) -> bool {
+        self.mouseevent.IsTrusted()
+    }
+}
@@ -25,13 +25,15 @@ use js::jsval::JSVal;
 use js::rust::HandleObject;
 use layout_api::LayoutDamage;
 use net_traits::ReferrerPolicy;
-use net_traits::request::CorsSettings;
+use net_traits::permissions_policy::PolicyControlledFeature;
+use net_traits::request::{CorsSettings, RequestPriority};
 use selectors::Element as SelectorsElement;
 use selectors::attr::{AttrSelectorOperation, CaseSensitivity, NamespaceConstraint};
 use selectors::bloom::{BLOOM_HASH_MASK, BloomFilter};
 use selectors::matching::{ElementSelectorFlags, MatchingContext};
 use selectors::sink::Push;
 use servo_arc::Arc;
+use servo_config::pref;
 use style::applicable_declarations::ApplicableDeclarationBlock;
 use style::attr::{AttrValue, LengthOrPercentageOrAuto};
 use style::computed_values::position::T as Position;
@@ -79,6 +81,7 @@ use crate::dom::bindings::codegen::Bindings::ElementBinding::{
 use crate::dom::bindings::codegen::Bindings::FunctionBinding::Function;
 use crate::dom::bindings::codegen::Bindings::HTMLTemplateElementBinding::HTMLTemplateElementMethods;
 use crate::dom::bindings::codegen::Bindings::NodeBinding::NodeMethods;
+use crate::dom::bindings::codegen::Bindings::SanitizerBinding::SetHTMLOptions;
 use crate::dom::bindings::codegen::Bindings::ShadowRootBinding::{
     ShadowRootMethods, ShadowRootMode, SlotAssignmentMode,
 };
@@ -104,8 +107,8 @@ use crate::dom::characterdata::CharacterData;
 use crate::dom::create::create_element;
 use crate::dom::csp::{CspReporting, InlineCheckType, SourcePosition};
 use crate::dom::customelementregistry::{
-    CallbackReaction, CustomElementDefinition, CustomElementReaction, CustomElementState,
-    is_valid_custom_element_name,
+    CallbackReaction, CustomElementDefinition, CustomElementReaction, CustomElementRegistry,
+    CustomElementState, is_valid_custom_element_name,
 };
 use crate::dom::document::{Document, LayoutDocumentHelpers, determine_policy_for_token};
 use crate::dom::documentfragment::DocumentFragment;
@@ -157,6 +160,7 @@ use crate::dom::node::{
 use crate::dom::nodelist::NodeList;
 use crate::dom::promise::Promise;
 use crate::dom::raredata::ElementRareData;
+use crate::dom::sanitizer::set_and_filter_html;
 use crate::dom::servoparser::ServoParser;
 use crate::dom::shadowroot::{IsUserAgentWidget, ShadowRoot};
 use crate::dom::text::Text;
@@ -282,16 +286,18 @@ struct ScrollPosition {
 // Element methods
 //
 impl Element {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn create(
         name: QualName,
         is: Option<LocalName>,
         document: &Document,
+        registry: Option<&CustomElementRegistry>,
         creator: ElementCreator,
         mode: CustomElementCreationMode,
         proto: Option<HandleObject>,
         can_gc: CanGc,
     ) -> DomRoot<Element> {
-        create_element(name, is, document, creator, mode, proto, can_gc)
+        create_element(name, is, document, registry, creator, mode, proto, can_gc)
     }
 
     pub(crate) fn new_inherited(
@@ -368,6 +374,25 @@ impl Element {
         RefMut::map(rare_data, |rare_data| rare_data.as_mut().unwrap())
     }
 
+    /// Note that the text of this element, or its image with the given URL, was painted.
+    /// Returns whether it was painted for the first time, in which case the paint is
+    /// reported to Largest Contentful Paint and Element Timing.
+    /// <https://w3c.github.io/paint-timing/#sec-reporting-paint-timing>
+    pub(crate) fn note_contentful_paint(&self, image_url: Option<&DOMString>) -> bool {
+        let Some(image_url) = image_url else {
+            let node = self.upcast::<Node>();
+            let first_paint = !node.get_flag(NodeFlags::HAS_PAINTED_TEXT);
+            node.set_flag(NodeFlags::HAS_PAINTED_TEXT, true);
+            return first_paint;
+        };
+        let mut rare_data = self.ensure_rare_data();
+        if rare_data.painted_image_url.as_ref() == Some(image_url) {
+            return false;
+        }
+        rare_data.painted_image_url = Some(image_url.clone());
+        true
+    }
+
     pub(crate) fn restyle(&self, damage: NodeDamage) {
         let doc = self.node.owner_doc();
         let mut restyle = doc.ensure_pending_restyle(self);
@@ -391,6 +416,107 @@ impl Element {
         }
     }
 
+    /// Restyle the `:has()` anchors whose relative selectors may match differently after a
+    /// change to this element, or to its children when `mutation` is given.
+    ///
+    /// While matching a relative selector, the style system flags every element that it visits
+    /// with the direction in which the anchor lies. The walks below stop at the first element
+    /// that was not visited in their direction, so that their cost is bounded by the matching
+    /// work that was already done rather than by the size of the document.
+    pub(crate) fn invalidate_relative_selector_anchors(&self, mutation: Option<&ChildrenMutation>) {
+        if !pref!(layout_css_has_selector_enabled) {
+            return;
+        }
+
+        let node = self.upcast::<Node>();
+        let Some(mutation) = mutation else {
+            let flags = self.selector_flags.get();
+            if flags.intersects(ElementSelectorFlags::RELATIVE_SELECTOR_SEARCH_DIRECTION_SIBLING) {
+                if let Some(previous_sibling) = node.GetPreviousSibling() {
+                    Self::restyle_preceding_sibling_anchors(&previous_sibling);
+                }
+            }
+            if flags.intersects(ElementSelectorFlags::RELATIVE_SELECTOR_SEARCH_DIRECTION_ANCESTOR) {
+                if let Some(parent) = node.GetParentElement() {
+                    parent.restyle_ancestor_anchors();
+                }
+            }
+            return;
+        };
+
+        // Inserted children have not been visited yet and removed children are gone, so the
+        // walks start from their neighbours in the tree.
+        if let Some(previous_child) = mutation.prev_child() {
+            Self::restyle_preceding_sibling_anchors(previous_child);
+        }
+        self.restyle_ancestor_anchors();
+    }
+
+    /// Restyle the `:has()` anchors among `node` and its preceding siblings, stopping at the
+    /// first element that was not visited by a search in the sibling direction.
+    fn restyle_preceding_sibling_anchors(node: &Node) {
+        for sibling in node
+            .inclusively_preceding_siblings()
+            .filter_map(DomRoot::downcast::<Element>)
+        {
+            sibling.restyle_if_relative_selector_anchor();
+            if !sibling
+                .selector_flags
+                .get()
+                .intersects(ElementSelectorFlags::RELATIVE_SELECTOR_SEARCH_DIRECTION_SIBLING)
+            {
+                break;
+            }
+        }
+    }
+
+    /// Restyle the `:has()` anchors among this element, its ancestors and the preceding
+    /// siblings of both, stopping at the first ancestor that was not visited by a search in
+    /// the ancestor direction.
+    fn restyle_ancestor_anchors(&self) {
+        for ancestor in self
+            .upcast::<Node>()
+            .inclusive_ancestors(ShadowIncluding::No)
+            .filter_map(DomRoot::downcast::<Element>)
+        {
+            ancestor.restyle_if_relative_selector_anchor();
+            let flags = ancestor.selector_flags.get();
+            if flags
+                .contains(ElementSelectorFlags::RELATIVE_SELECTOR_SEARCH_DIRECTION_ANCESTOR_SIBLING)
+            {
+                if let Some(previous_sibling) = ancestor.upcast::<Node>().GetPreviousSibling() {
+                    Self::restyle_preceding_sibling_anchors(&previous_sibling);
+                }
+            }
+            if !flags.intersects(ElementSelectorFlags::RELATIVE_SELECTOR_SEARCH_DIRECTION_ANCESTOR)
+            {
+                break;
+            }
+        }
+    }
+
+    /// Restyle this element if it anchors a `:has()` relative selector, along with its
+    /// descendants and later siblings when the anchor is not the subject of the selector.
+    fn restyle_if_relative_selector_anchor(&self) {
+        let flags = self.selector_flags.get();
+        if !self.upcast::<Node>().is_connected() ||
+            !flags.intersects(
+                ElementSelectorFlags::ANCHORS_RELATIVE_SELECTOR |
+                    ElementSelectorFlags::ANCHORS_RELATIVE_SELECTOR_NON_SUBJECT,
+            )
+        {
+            return;
+        }
+
+        self.restyle(NodeDamage::Other);
+        if flags.intersects(ElementSelectorFlags::ANCHORS_RELATIVE_SELECTOR_NON_SUBJECT) {
+            let doc = self.node.owner_doc();
+            doc.ensure_pending_restyle(self)
+                .hint
+                .insert(RestyleHint::RESTYLE_DESCENDANTS | RestyleHint::RESTYLE_LATER_SIBLINGS);
+        }
+    }
+
     pub(crate) fn set_is(&self, is: LocalName) {
         *self.is.borrow_mut() = Some(is);
     }
@@ -438,6 +564,34 @@ impl Element {
         self.ensure_rare_data().custom_element_definition = None;
     }
 
+    /// The scoped custom element registry this element was created with, if any.
+    pub(crate) fn scoped_custom_element_registry(&self) -> Option<DomRoot<CustomElementRegistry>> {
+        self.rare_data()
+            .as_ref()?
+            .custom_element_registry
+            .as_ref()
+            .map(|registry| registry.as_rooted())
+    }
+
+    pub(crate) fn set_scoped_custom_element_registry(&self, registry: &CustomElementRegistry) {
+        debug_assert!(registry.is_scoped());
+        self.ensure_rare_data().custom_element_registry = Some(Dom::from_ref(registry));
+    }
+
+    /// <https://wicg.github.io/webcomponents/proposals/Scoped-Custom-Element-Registries>
+    /// The registry used to look up definitions for this element: its scoped registry if it
+    /// has one, otherwise the one of its shadow root or node document. This is null in
+    /// shadow roots that keep their custom element registry null.
+    pub(crate) fn custom_element_registry(&self) -> Option<DomRoot<CustomElementRegistry>> {
+        if let Some(registry) = self.scoped_custom_element_registry() {
+            return Some(registry);
+        }
+        match self.containing_shadow_root() {
+            Some(shadow_root) => shadow_root.custom_element_registry(),
+            None => self.owner_document().custom_element_registry(),
+        }
+    }
+
     #[cfg_attr(crown, allow(crown::unrooted_must_root))]
     pub(crate) fn push_callback_reaction(&self, function: Rc<Function>, args: Box<[Heap<JSVal>]>) {
         self.ensure_rare_data()
@@ -2106,7 +2260,7 @@ impl Element {
 
         if let Some(input) = self.downcast::<HTMLInputElement>() {
             input.input_type().as_ime_type()
-        } else if self.is::<HTMLTextAreaElement>() {
+        } else if self.is::<HTMLTextAreaElement>() || self.upcast::<Node>().is_editing_host() {
             Some(InputMethodType::Text)
         } else {
             // Other focusable elements that are not input fields.
@@ -2137,10 +2291,19 @@ impl Element {
             return false;
         }
         let node = self.upcast::<Node>();
+        if node.is_inert() {
+            return false;
+        }
         if node.get_flag(NodeFlags::SEQUENTIALLY_FOCUSABLE) {
             return true;
         }
 
+        // Editing hosts are focusable areas.
+        // <https://html.spec.whatwg.org/multipage/#focusable-area>
+        if node.is_editing_host() {
+            return true;
+        }
+
         // <a>, <input>, <select>, and <textrea> are inherently focusable.
         matches!(
             node.type_id(),
@@ -2972,7 +3135,8 @@ impl Element {
         if !self.is_connected() {
             return false;
         }
-        self.owner_document().get_allow_fullscreen()
+        self.owner_document()
+            .allowed_to_use_feature(PolicyControlledFeature::Fullscreen)
     }
 
     // https://html.spec.whatwg.org/multipage/#home-subtree
@@ -3154,6 +3318,16 @@ impl ElementMethods<crate::DomTypeHolder> for Element {
     // https://dom.spec.whatwg.org/#dom-element-slot
     make_setter!(SetSlot, "slot");
 
+    // https://w3c.github.io/element-timing/#dom-element-elementtiming
+    fn ElementTiming(&self) -> DOMString {
+        self.get_string_attribute(&LocalName::from("elementtiming"))
+    }
+
+    // https://w3c.github.io/element-timing/#dom-element-elementtiming
+    fn SetElementTiming(&self, value: DOMString, can_gc: CanGc) {
+        self.set_string_attribute(&LocalName::from("elementtiming"), value, can_gc);
+    }
+
     // https://dom.spec.whatwg.org/#dom-element-attributes
     fn Attributes(&self, can_gc: CanGc) -> DomRoot<NamedNodeMap> {
         self.attr_list
@@ -3803,6 +3977,20 @@ impl ElementMethods<crate::DomTypeHolder> for Element {
         Ok(())
     }
 
+    /// <https://wicg.github.io/sanitizer-api/#dom-element-sethtml>
+    fn SetHTML(&self, html: DOMString, options: &SetHTMLOptions, can_gc: CanGc) -> ErrorResult {
+        // Step 1. Let target be this's template contents if this is a template element;
+        // otherwise this.
+        let target = if let Some(template) = self.downcast::<HTMLTemplateElement>() {
+            DomRoot::upcast(template.Content(can_gc))
+        } else {
+            DomRoot::from_ref(self.upcast())
+        };
+
+        // Step 2. Set and filter HTML given target, this, html, options, and true.
+        set_and_filter_html(&target, self, html, options, true, can_gc)
+    }
+
     /// <https://html.spec.whatwg.org/multipage/#dom-element-gethtml>
     fn GetHTML(&self, options: &GetHTMLOptions, can_gc: CanGc) -> DOMString {
         // > Element's getHTML(options) method steps are to return the result of HTML fragment serialization
@@ -3875,6 +4063,12 @@ impl ElementMethods<crate::DomTypeHolder> for Element {
         // with context and compliantString.
         let frag = self.parse_fragment(value, can_gc)?;
 
+        // Parsed elements use the scoped custom element registry of context, if it has one.
+        if let Some(registry) = self.scoped_custom_element_registry() {
+            frag.upcast::<Node>()
+                .set_scoped_custom_element_registry_for_descendants(&registry);
+        }
+
         // Step 5: Replace all with fragment within context.
         Node::replace_all(Some(frag.upcast()), &target, can_gc);
         Ok(())
@@ -3929,6 +4123,7 @@ impl ElementMethods<crate::DomTypeHolder> for Element {
                     QualName::new(None, ns!(html), local_name!("body")),
                     None,
                     &context_document,
+                    None,
                     ElementCreator::ScriptCreated,
                     CustomElementCreationMode::Synchronous,
                     None,
@@ -4193,6 +4388,17 @@ impl ElementMethods<crate::DomTypeHolder> for Element {
 
     // https://dom.spec.whatwg.org/#dom-element-attachshadow
     fn AttachShadow(&self, init: &ShadowRootInit, can_gc: CanGc) -> Fallible<DomRoot<ShadowRoot>> {
+        // <https://wicg.github.io/webcomponents/proposals/Scoped-Custom-Element-Registries>
+        // A global registry can only be given if it is the one of this's node document.
+        let registry = init.customElementRegistry.as_deref();
+        if let Some(registry) = registry {
+            if !registry.is_scoped() &&
+                self.owner_document().custom_element_registry().as_deref() != Some(registry)
+            {
+                return Err(Error::NotSupported);
+            }
+        }
+
         // Step 1. Run attach a shadow root with this, init["mode"], init["clonable"], init["serializable"],
         // init["delegatesFocus"], and init["slotAssignment"].
         let shadow_root = self.attach_shadow(
@@ -4205,10 +4411,19 @@ impl ElementMethods<crate::DomTypeHolder> for Element {
             can_gc,
         )?;
 
+        if let Some(registry) = registry.filter(|registry| registry.is_scoped()) {
+            shadow_root.set_scoped_custom_element_registry(registry);
+        }
+
         // Step 2. Return this’s shadow root.
         Ok(shadow_root)
     }
 
+    /// <https://wicg.github.io/webcomponents/proposals/Scoped-Custom-Element-Registries>
+    fn GetCustomElementRegistry(&self) -> Option<DomRoot<CustomElementRegistry>> {
+        self.custom_element_registry()
+    }
+
     /// <https://dom.spec.whatwg.org/#dom-element-shadowroot>
     fn GetShadowRoot(&self) -> Option<DomRoot<ShadowRoot>> {
         // Step 1. Let shadow be this’s shadow root.
@@ -4831,6 +5046,8 @@ impl VirtualMethods for Element {
             s.children_changed(mutation);
         }
 
+        self.invalidate_relative_selector_anchors(Some(mutation));
+
         let flags = self.selector_flags.get();
         if flags.intersects(ElementSelectorFlags::HAS_SLOW_SELECTOR) {
             // All children of this node need to be restyled when any child changes.
@@ -5757,10 +5974,13 @@ pub(crate) fn reflect_referrer_policy_attribute(element: &Element) -> DOMString
 }
 
 pub(crate) fn referrer_policy_for_element(element: &Element) -> ReferrerPolicy {
+    // An invalid or missing referrerpolicy attribute leaves the policy as the empty string,
+    // in which case the policy of the node document's policy container applies.
     element
         .get_attribute_by_name(DOMString::from_string(String::from("referrerpolicy")))
         .map(|attribute: DomRoot<Attr>| determine_policy_for_token(&attribute.Value()))
-        .unwrap_or(element.owner_document().get_referrer_policy())
+        .filter(|policy| *policy != ReferrerPolicy::EmptyString)
+        .unwrap_or_else(|| element.owner_document().get_referrer_policy())
 }
 
 pub(crate) fn cors_setting_for_element(element: &Element) -> Option<CorsSettings> {
@@ -5770,3 +5990,50 @@ pub(crate) fn cors_setting_for_element(element: &Element) -> Option<CorsSettings
         _ => unreachable!(),
     })
 }
+
+/// The state of the [fetch priority attribute] of `element`.
+///
+/// [fetch priority attribute]: https://html.spec.whatwg.org/multipage/#fetch-priority-attribute
+pub(crate) fn fetch_priority_for_element(element: &Element) -> RequestPriority {
+    let attr = element.get_attribute(&ns!(), &LocalName::from("fetchpriority"));
+    RequestPriority::from_fetch_priority_attribute(attr.as_ref().map(|attr| &**attr.value()))
+}
+
+/// Reflect the [fetch priority attribute] of `element`, limited to known values.
+///
+/// [fetch priority attribute]: https://html.spec.whatwg.org/multipage/#fetch-priority-attribute
+pub(crate) fn reflect_fetch_priority_attribute(element: &Element) -> DOMString {
+    DOMString::from(match fetch_priority_for_element(element) {
+        RequestPriority::High => "high",
+        RequestPriority::Low => "low",
+        RequestPriority::Auto => "auto",
+    })
+}
+
+/// Set the [fetch priority attribute] of `element`.
+///
+/// [fetch priority attribute]: https://html.spec.whatwg.org/multipage/#fetch-priority-attribute
+pub(crate) fn set_fetch_priority_attribute(element: &Element, value: DOMString, can_gc: CanGc) {
+    element.set_string_attribute(&LocalName::from("fetchpriority"), value, can_gc);
+}
+
+/// Whether the [lazy loading attribute] of `element` is in the Lazy state.
+///
+/// [lazy loading attribute]: https://html.spec.whatwg.org/multipage/#lazy-loading-attribute
+pub(crate) fn lazy_loading_attribute_is_lazy(element: &Element) -> bool {
+    element
+        .get_attribute(&ns!(), &local_name!("loading"))
+        .is_some_and(|attr| attr.value().eq_ignore_ascii_case("lazy"))
+}
+
+/// <https://html.spec.whatwg.org/multipage/#will-lazy-load-element-steps>
+pub(crate) fn will_lazy_load_element_steps(element: &Element) -> bool {
+    // Step 1. If scripting is disabled for element, then return false.
+    if !element.owner_document().scripting_enabled() {
+        return false;
+    }
+
+    // Step 2. If element's lazy loading attribute is in the Lazy state, then return true.
+    // Step 3. Return false.
+    lazy_loading_attribute_is_lazy(element)
+}
@@ -4,6 +4,7 @@
 
 use std::cell::Cell;
 
+use constellation_traits::{BlobImpl, PersistedFormValue};
 use dom_struct::dom_struct;
 use html5ever::local_name;
 
@@ -17,6 +18,8 @@ use crate::dom::bindings::inheritance::Castable;
 use crate::dom::bindings::reflector::{Reflector, reflect_dom_object};
 use crate::dom::bindings::root::{Dom, DomRoot, MutNullableDom};
 use crate::dom::bindings::str::{DOMString, USVString};
+use crate::dom::blob::Blob;
+use crate::dom::customelementregistry::CallbackReaction;
 use crate::dom::element::Element;
 use crate::dom::file::File;
 use crate::dom::htmlelement::HTMLElement;
@@ -27,9 +30,10 @@ use crate::dom::shadowroot::ShadowRoot;
 use crate::dom::validation::{Validatable, is_barred_by_datalist_ancestor};
 use crate::dom::validitystate::{ValidationFlags, ValidityState};
 use crate::script_runtime::CanGc;
+use crate::script_thread::ScriptThread;
 
 #[derive(Clone, JSTraceable, MallocSizeOf)]
-enum SubmissionValue {
+pub(crate) enum SubmissionValue {
     File(DomRoot<File>),
     FormData(Vec<FormDatum>),
     USVString(USVString),
@@ -114,6 +118,11 @@ impl ElementInternals {
     }
 
     fn set_state(&self, value: SubmissionValue) {
+        if !matches!(value, SubmissionValue::None) {
+            self.target_element
+                .owner_document()
+                .note_form_state_needs_persisting();
+        }
         *self.state.borrow_mut() = value;
     }
 
@@ -181,6 +190,87 @@ impl ElementInternals {
         }
     }
 
+    /// The state of the target element in a form that can be persisted with the session
+    /// history entry of its document, if it has one.
+    pub(crate) fn persisted_state(&self) -> Option<PersistedFormValue> {
+        let file_value = |file: &File| PersistedFormValue::File {
+            name: file.name().to_string(),
+            type_: file.upcast::<Blob>().type_string(),
+            bytes: file.upcast::<Blob>().get_bytes().unwrap_or_default(),
+        };
+        match &*self.state.borrow() {
+            SubmissionValue::None => None,
+            SubmissionValue::USVString(string) => {
+                Some(PersistedFormValue::String(string.0.clone()))
+            },
+            SubmissionValue::File(file) => Some(file_value(file)),
+            SubmissionValue::FormData(datums) => Some(PersistedFormValue::Entries(
+                datums
+                    .iter()
+                    .map(|datum| {
+                        let value = match &datum.value {
+                            FormDatumValue::String(string) => {
+                                PersistedFormValue::String(string.to_string())
+                            },
+                            FormDatumValue::File(file) => file_value(file),
+                        };
+                        (datum.name.to_string(), value)
+                    })
+                    .collect(),
+            )),
+        }
+    }
+
+    /// Restore the state of the target element that was persisted by an earlier document of
+    /// the same session history entry, by passing it to its `formStateRestoreCallback`.
+    pub(crate) fn restore_state(&self, value: PersistedFormValue, can_gc: CanGc) {
+        let global = self.target_element.owner_global();
+        let file = |name: String, type_: String, bytes: Vec<u8>| {
+            File::new(
+                &global,
+                BlobImpl::new_from_bytes(bytes, type_),
+                DOMString::from(name),
+                None,
+                can_gc,
+            )
+        };
+        let state = match value {
+            PersistedFormValue::String(string) => SubmissionValue::USVString(USVString(string)),
+            PersistedFormValue::File { name, type_, bytes } => {
+                SubmissionValue::File(file(name, type_, bytes))
+            },
+            PersistedFormValue::Entries(entries) => SubmissionValue::FormData(
+                entries
+                    .into_iter()
+                    .filter_map(|(name, value)| {
+                        let (ty, value) = match value {
+                            PersistedFormValue::String(string) => (
+                                DOMString::from("string"),
+                                FormDatumValue::String(DOMString::from(string)),
+                            ),
+                            PersistedFormValue::File { name, type_, bytes } => (
+                                DOMString::from("file"),
+                                FormDatumValue::File(file(name, type_, bytes)),
+                            ),
+                            PersistedFormValue::Entries(_) => return None,
+                        };
+                        Some(FormDatum {
+                            ty,
+                            name: DOMString::from(name),
+                            value,
+                        })
+                    })
+                    .collect(),
+            ),
+        };
+
+        ScriptThread::enqueue_callback_reaction(
+            self.target_element.upcast::<Element>(),
+            CallbackReaction::FormStateRestore(state),
+            None,
+        );
+    }
+
     pub(crate) fn is_invalid(&self) -> bool {
         self.is_target_form_associated() &&
             self.is_instance_validatable() &&
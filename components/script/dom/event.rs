@@ -35,6 +35,7 @@ use crate::dom::htmlinputelement::InputActivationState;
 use crate::dom::htmlslotelement::HTMLSlotElement;
 use crate::dom::mouseevent::MouseEvent;
 use crate::dom::node::{Node, NodeTraits};
+use crate::dom::performanceeventtiming::PerformanceEventTiming;
 use crate::dom::shadowroot::ShadowRoot;
 use crate::dom::virtualmethods::vtable_for;
 use crate::dom::window::Window;
@@ -280,6 +281,14 @@ impl Event {
         can_gc: CanGc,
         // TODO legacy_did_output_listeners_throw_flag for indexeddb
     ) -> bool {
+        // <https://w3c.github.io/event-timing/#sec-modifications-DOM>
+        let timing_entry = PerformanceEventTiming::initialize_event_timing(
+            self,
+            target,
+            CrossProcessInstant::now(),
+            can_gc,
+        );
+
         let mut target = DomRoot::from_ref(target);
 
         // Step 1. Set event’s dispatch flag.
@@ -628,6 +637,11 @@ impl Event {
             }
         }
 
+        // <https://w3c.github.io/event-timing/#sec-modifications-DOM>
+        if let Some(timing_entry) = timing_entry {
+            timing_entry.finalize_event_timing(self, CrossProcessInstant::now());
+        }
+
         // Step 12 Return false if event’s canceled flag is set; otherwise true.
         !self.DefaultPrevented()
     }
@@ -642,6 +656,11 @@ impl Event {
         self.initialized.get()
     }
 
+    #[inline]
+    pub(crate) fn time_stamp(&self) -> CrossProcessInstant {
+        self.time_stamp
+    }
+
     #[inline]
     pub(crate) fn type_(&self) -> Atom {
         self.type_.borrow().clone()
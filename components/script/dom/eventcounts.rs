@@ -0,0 +1,64 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use indexmap::IndexMap;
+
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::PerformanceEventTimingBinding::EventCountsMethods;
+use crate::dom::bindings::like::Maplike;
+use crate::dom::bindings::reflector::{Reflector, reflect_dom_object};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::str::DOMString;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::performanceeventtiming::CONSIDERED_EVENT_TYPES;
+use crate::maplike;
+use crate::script_runtime::CanGc;
+
+/// <https://w3c.github.io/event-timing/#eventcounts>
+#[dom_struct]
+pub(crate) struct EventCounts {
+    reflector_: Reflector,
+    #[custom_trace]
+    map: DomRefCell<IndexMap<DOMString, u64>>,
+}
+
+impl EventCounts {
+    fn new_inherited() -> EventCounts {
+        // The map is initialized with an entry for each of the event types that are
+        // considered for Event Timing, with a count of 0.
+        let map = CONSIDERED_EVENT_TYPES
+            .iter()
+            .map(|event_type| (DOMString::from(*event_type), 0))
+            .collect();
+        EventCounts {
+            reflector_: Reflector::new(),
+            map: DomRefCell::new(map),
+        }
+    }
+
+    pub(crate) fn new(global: &GlobalScope, can_gc: CanGc) -> DomRoot<EventCounts> {
+        reflect_dom_object(Box::new(EventCounts::new_inherited()), global, can_gc)
+    }
+
+    /// Increment the number of dispatched events of the given type.
+    pub(crate) fn increment(&self, event_type: &DOMString) {
+        if let Some(count) = self.map.borrow_mut().get_mut(event_type) {
+            *count += 1;
+        }
+    }
+}
+
+impl EventCountsMethods<crate::DomTypeHolder> for EventCounts {
+    fn Size(&self) -> u32 {
+        self.map.size()
+    }
+}
+
+impl Maplike for EventCounts {
+    type Key = DOMString;
+    type Value = u64;
+
+    maplike!(self, map);
+}
@@ -606,6 +606,7 @@ impl EventSourceMethods<crate::DomTypeHolder> for EventSource {
             global.policy_container(),
         )
         .origin(global.origin().immutable().clone())
+        .ancestor_origins(global.origin_and_ancestor_origins())
         .pipeline_id(Some(global.pipeline_id()));
 
         // Step 10 User agents may set (`Accept`, `text/event-stream`) in request's header list.
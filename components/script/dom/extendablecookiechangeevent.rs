@@ -0,0 +1,125 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use js::rust::{HandleObject, MutableHandleValue};
+use stylo_atoms::Atom;
+
+use crate::dom::bindings::codegen::Bindings::CookieStoreBinding::CookieListItem;
+use crate::dom::bindings::codegen::Bindings::ExtendableCookieChangeEventBinding::{
+    ExtendableCookieChangeEventInit, ExtendableCookieChangeEventMethods,
+};
+use crate::dom::bindings::codegen::Bindings::ExtendableEventBinding::ExtendableEvent_Binding::ExtendableEventMethods;
+use crate::dom::bindings::error::Fallible;
+use crate::dom::bindings::frozenarray::CachedFrozenArray;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::reflect_dom_object_with_proto;
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::str::DOMString;
+use crate::dom::event::Event;
+use crate::dom::extendableevent::ExtendableEvent;
+use crate::dom::serviceworkerglobalscope::ServiceWorkerGlobalScope;
+use crate::script_runtime::{CanGc, JSContext};
+
+/// <https://cookiestore.spec.whatwg.org/#extendablecookiechangeevent>
+#[dom_struct]
+pub(crate) struct ExtendableCookieChangeEvent {
+    event: ExtendableEvent,
+    /// <https://cookiestore.spec.whatwg.org/#dom-extendablecookiechangeevent-changed>
+    changed: Vec<CookieListItem>,
+    /// <https://cookiestore.spec.whatwg.org/#dom-extendablecookiechangeevent-deleted>
+    deleted: Vec<CookieListItem>,
+    #[ignore_malloc_size_of = "mozjs"]
+    frozen_changed: CachedFrozenArray,
+    #[ignore_malloc_size_of = "mozjs"]
+    frozen_deleted: CachedFrozenArray,
+}
+
+impl ExtendableCookieChangeEvent {
+    fn new_inherited(
+        changed: Vec<CookieListItem>,
+        deleted: Vec<CookieListItem>,
+    ) -> ExtendableCookieChangeEvent {
+        ExtendableCookieChangeEvent {
+            event: ExtendableEvent::new_inherited(),
+            changed,
+            deleted,
+            frozen_changed: CachedFrozenArray::new(),
+            frozen_deleted: CachedFrozenArray::new(),
+        }
+    }
+
+    pub(crate) fn new(
+        worker: &ServiceWorkerGlobalScope,
+        type_: Atom,
+        changed: Vec<CookieListItem>,
+        deleted: Vec<CookieListItem>,
+        can_gc: CanGc,
+    ) -> DomRoot<ExtendableCookieChangeEvent> {
+        Self::new_with_proto(worker, None, type_, false, false, changed, deleted, can_gc)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn new_with_proto(
+        worker: &ServiceWorkerGlobalScope,
+        proto: Option<HandleObject>,
+        type_: Atom,
+        bubbles: bool,
+        cancelable: bool,
+        changed: Vec<CookieListItem>,
+        deleted: Vec<CookieListItem>,
+        can_gc: CanGc,
+    ) -> DomRoot<ExtendableCookieChangeEvent> {
+        let ev = reflect_dom_object_with_proto(
+            Box::new(ExtendableCookieChangeEvent::new_inherited(changed, deleted)),
+            worker,
+            proto,
+            can_gc,
+        );
+        {
+            let event = ev.upcast::<Event>();
+            event.init_event(type_, bubbles, cancelable);
+        }
+        ev
+    }
+}
+
+impl ExtendableCookieChangeEventMethods<crate::DomTypeHolder> for ExtendableCookieChangeEvent {
+    /// <https://cookiestore.spec.whatwg.org/#dom-extendablecookiechangeevent-extendablecookiechangeevent>
+    fn Constructor(
+        worker: &ServiceWorkerGlobalScope,
+        proto: Option<HandleObject>,
+        can_gc: CanGc,
+        type_: DOMString,
+        init: &ExtendableCookieChangeEventInit,
+    ) -> Fallible<DomRoot<ExtendableCookieChangeEvent>> {
+        Ok(ExtendableCookieChangeEvent::new_with_proto(
+            worker,
+            proto,
+            Atom::from(type_),
+            init.parent.parent.bubbles,
+            init.parent.parent.cancelable,
+            init.changed.clone().unwrap_or_default(),
+            init.deleted.clone().unwrap_or_default(),
+            can_gc,
+        ))
+    }
+
+    /// <https://cookiestore.spec.whatwg.org/#dom-extendablecookiechangeevent-changed>
+    fn Changed(&self, cx: JSContext, can_gc: CanGc, retval: MutableHandleValue) {
+        self.frozen_changed
+            .get_or_init(|| self.changed.clone(), cx, retval, can_gc);
+    }
+
+    /// <https://cookiestore.spec.whatwg.org/#dom-extendablecookiechangeevent-deleted>
+    fn Deleted(&self, cx: JSContext, can_gc: CanGc, retval: MutableHandleValue) {
+        self.frozen_deleted
+            .get_or_init(|| self.deleted.clone(), cx, retval, can_gc);
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-event-istrusted>
+    fn IsTrusted(&self) -> bool {
+        self.event.IsTrusted()
+    }
+}
@@ -0,0 +1,553 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::cell::{Cell, RefMut};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use dom_struct::dom_struct;
+use embedder_traits::{EmbedderMsg, GeolocationPosition as EmbedderGeolocationPosition};
+use ipc_channel::ipc;
+use ipc_channel::router::ROUTER;
+use net_traits::permissions_policy::PolicyControlledFeature;
+
+use crate::dom::bindings::callback::ExceptionHandling;
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::GeolocationBinding::{
+    GeolocationMethods, PositionCallback, PositionErrorCallback, PositionOptions,
+};
+use crate::dom::bindings::codegen::Bindings::GeolocationPositionErrorBinding::GeolocationPositionErrorConstants;
+use crate::dom::bindings::codegen::Bindings::PermissionStatusBinding::{
+    PermissionName, PermissionState,
+};
+use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::refcounted::Trusted;
+use crate::dom::bindings::reflector::{DomGlobal, Reflector, reflect_dom_object};
+use crate::dom::bindings::root::{DomRoot, MutNullableDom};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::geolocationposition::GeolocationPosition;
+use crate::dom::geolocationpositionerror::GeolocationPositionError;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::permissions::request_permission_to_use;
+use crate::script_runtime::CanGc;
+use crate::timers::{OneshotTimerCallback, OneshotTimerHandle};
+
+/// A request for the position of the device, made by `getCurrentPosition()` or by a watch
+/// registered with `watchPosition()`.
+#[derive(JSTraceable, MallocSizeOf)]
+struct PositionRequest {
+    #[ignore_malloc_size_of = "Rc is hard"]
+    success_callback: Rc<PositionCallback>,
+    #[ignore_malloc_size_of = "Rc is hard"]
+    error_callback: Option<Rc<PositionErrorCallback>>,
+    enable_high_accuracy: bool,
+    timeout: u32,
+    maximum_age: u32,
+    watch_id: Option<i32>,
+    /// The timer that fires when the current acquisition takes longer than the timeout.
+    timeout_handle: Option<OneshotTimerHandle>,
+    /// False once the request failed, while the task invoking its error callback is pending.
+    active: bool,
+}
+
+/// <https://w3c.github.io/geolocation/#geolocation_interface>
+#[dom_struct]
+pub(crate) struct Geolocation {
+    reflector_: Reflector,
+    /// <https://w3c.github.io/geolocation/#dfn-cachedposition>
+    cached_position: MutNullableDom<GeolocationPosition>,
+    /// <https://w3c.github.io/geolocation/#dfn-watchids>
+    watch_ids: DomRefCell<HashSet<i32>>,
+    next_watch_id: Cell<i32>,
+    /// The requests that are waiting for a position, by request id.
+    requests: DomRefCell<HashMap<u32, PositionRequest>>,
+    next_request_id: Cell<u32>,
+}
+
+impl Geolocation {
+    fn new_inherited() -> Self {
+        Self {
+            reflector_: Reflector::new(),
+            cached_position: Default::default(),
+            watch_ids: Default::default(),
+            next_watch_id: Cell::new(1),
+            requests: Default::default(),
+            next_request_id: Cell::new(0),
+        }
+    }
+
+    pub(crate) fn new(global: &GlobalScope, can_gc: CanGc) -> DomRoot<Self> {
+        reflect_dom_object(Box::new(Self::new_inherited()), global, can_gc)
+    }
+
+    fn document_is_fully_active(&self) -> bool {
+        self.global().as_window().Document().is_fully_active()
+    }
+
+    fn add_request(
+        &self,
+        success_callback: Rc<PositionCallback>,
+        error_callback: Option<Rc<PositionErrorCallback>>,
+        options: &PositionOptions,
+        watch_id: Option<i32>,
+    ) -> u32 {
+        let request_id = self.next_request_id.get();
+        self.next_request_id.set(request_id.wrapping_add(1));
+        self.requests.borrow_mut().insert(
+            request_id,
+            PositionRequest {
+                success_callback,
+                error_callback,
+                enable_high_accuracy: options.enableHighAccuracy,
+                timeout: options.timeout,
+                maximum_age: options.maximumAge,
+                watch_id,
+                timeout_handle: None,
+                active: true,
+            },
+        );
+        request_id
+    }
+
+    /// Returns the request with the given id, unless it was dropped or has failed.
+    fn active_request(&self, request_id: u32) -> Option<RefMut<'_, PositionRequest>> {
+        RefMut::filter_map(self.requests.borrow_mut(), |requests| {
+            requests
+                .get_mut(&request_id)
+                .filter(|request| request.active)
+        })
+        .ok()
+    }
+
+    /// <https://w3c.github.io/geolocation/#dfn-request-a-position>
+    fn request_a_position(&self, request_id: u32) {
+        // The position of a prerendering document is requested once it is activated.
+        // <https://wicg.github.io/nav-speculation/prerendering.html#patch-geolocation>
+        let document = self.global().as_window().Document();
+        if document.is_prerendering() {
+            let geolocation = DomRoot::from_ref(self);
+            document.add_post_prerendering_activation_step(
+                task!(RequestAPosition: |geolocation: DomRoot<Geolocation>, request_id: u32| {
+                    geolocation.request_a_position(request_id);
+                }),
+            );
+            return;
+        }
+
+        // Step 3. Let document be the current global object's associated Document.
+        // Step 4. If document is not allowed to use the "geolocation" feature:
+        // Step 5. If geolocation's environment settings object is a non-secure context:
+        //   1. If watchId was passed, remove watchId from watchIDs.
+        //   2. Call back with error passing errorCallback and PERMISSION_DENIED.
+        //   3. Terminate this algorithm.
+        let global = self.global();
+        let document = global.as_window().Document();
+        if !document.allowed_to_use_feature(PolicyControlledFeature::Geolocation) ||
+            !global.is_secure_context()
+        {
+            self.call_back_with_error(
+                request_id,
+                GeolocationPositionErrorConstants::PERMISSION_DENIED,
+                "Geolocation is not allowed in this context",
+            );
+            return;
+        }
+
+        // Step 7. Set permission to request permission to use descriptor.
+        // Step 8. If permission is "denied", then:
+        //   1. If watchId was passed, remove watchId from watchIDs.
+        //   2. Call back with error passing errorCallback and PERMISSION_DENIED.
+        //   3. Terminate this algorithm.
+        if request_permission_to_use(PermissionName::Geolocation, &global) !=
+            PermissionState::Granted
+        {
+            self.call_back_with_error(
+                request_id,
+                GeolocationPositionErrorConstants::PERMISSION_DENIED,
+                "User denied Geolocation",
+            );
+            return;
+        }
+
+        // Step 9. Wait to acquire a position passing successCallback, errorCallback, options,
+        // and watchId.
+        self.acquire_a_position(request_id, true);
+    }
+
+    /// <https://w3c.github.io/geolocation/#dfn-acquire-a-position>
+    ///
+    /// Watches don't consult the cached position once they received a position, because the
+    /// embedder is expected to answer their next request when the position changes.
+    fn acquire_a_position(&self, request_id: u32, use_cached_position: bool) {
+        let (enable_high_accuracy, timeout, maximum_age, watch_id) = {
+            let Some(request) = self.active_request(request_id) else {
+                return;
+            };
+            (
+                request.enable_high_accuracy,
+                request.timeout,
+                request.maximum_age,
+                request.watch_id,
+            )
+        };
+
+        // Step 1. If watchId was passed and this's [[watchIDs]] does not contain watchId,
+        // terminate this algorithm.
+        if watch_id.is_some_and(|watch_id| !self.watch_ids.borrow().contains(&watch_id)) {
+            self.requests.borrow_mut().remove(&request_id);
+            return;
+        }
+
+        // Step 2. Let acquisitionTime be a new EpochTimeStamp that represents now.
+        let acquisition_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        // Step 5.3.2. If cachedPosition is not null, and options.maximumAge is greater than 0:
+        //   1. Let cacheTime be acquisitionTime minus the value of the options.maximumAge member.
+        //   2. If cachedPosition's timestamp's value is greater than cacheTime, and
+        //      cachedPosition.[[isHighAccuracy]] equals options.enableHighAccuracy, set position
+        //      to cachedPosition.
+        if use_cached_position && maximum_age > 0 {
+            if let Some(cached_position) = self.cached_position.get() {
+                let cache_time = acquisition_time.saturating_sub(maximum_age as u64);
+                if cached_position.timestamp() > cache_time &&
+                    cached_position.is_high_accuracy() == enable_high_accuracy
+                {
+                    self.queue_success(request_id, &cached_position);
+                    return;
+                }
+            }
+        }
+
+        // A timeout of 0 means that only a cached position is acceptable.
+        if timeout == 0 {
+            self.call_back_with_error(
+                request_id,
+                GeolocationPositionErrorConstants::TIMEOUT,
+                "Timeout expired",
+            );
+            return;
+        }
+
+        // Step 5. Create an implementation-specific timeout task that elapses at timeoutTime,
+        // during which it tries to acquire the device's position.
+        let global = self.global();
+        if timeout != u32::MAX {
+            let callback = OneshotTimerCallback::GeolocationTimeout(GeolocationTimeoutCallback {
+                geolocation: Trusted::new(self),
+                request_id,
+            });
+            let handle = global.schedule_callback(callback, Duration::from_millis(timeout as u64));
+            if let Some(mut request) = self.active_request(request_id) {
+                request.timeout_handle = Some(handle);
+            }
+        }
+
+        let window = global.as_window();
+        let (sender, receiver) = ipc::channel().expect("Failed to create IPC channel!");
+        let task_source = global
+            .task_manager()
+            .geolocation_task_source()
+            .to_sendable();
+        let trusted = Trusted::new(self);
+        ROUTER.add_typed_route(
+            receiver,
+            Box::new(move |message| {
+                let Ok(result) = message else {
+                    return;
+                };
+                let trusted = trusted.clone();
+                task_source.queue(task!(geolocation_position_acquired: move || {
+                    trusted.root().handle_position(request_id, result, CanGc::note());
+                }));
+            }),
+        );
+        window.send_to_embedder(EmbedderMsg::GetGeolocationPosition(
+            window.webview_id(),
+            enable_high_accuracy,
+            sender,
+        ));
+    }
+
+    /// Handles the embedder's answer to a position request.
+    fn handle_position(
+        &self,
+        request_id: u32,
+        result: Result<EmbedderGeolocationPosition, String>,
+        can_gc: CanGc,
+    ) {
+        let Some((timeout_handle, watch_id, enable_high_accuracy)) =
+            self.active_request(request_id).map(|mut request| {
+                (
+                    request.timeout_handle.take(),
+                    request.watch_id,
+                    request.enable_high_accuracy,
+                )
+            })
+        else {
+            return;
+        };
+
+        // Stop the timeout.
+        if let Some(handle) = timeout_handle {
+            self.global().unschedule_callback(handle);
+        }
+
+        if watch_id.is_some_and(|watch_id| !self.watch_ids.borrow().contains(&watch_id)) {
+            self.requests.borrow_mut().remove(&request_id);
+            return;
+        }
+
+        let position = match result {
+            Ok(position)
+                if position.latitude.is_finite() &&
+                    position.longitude.is_finite() &&
+                    position.accuracy.is_finite() =>
+            {
+                position
+            },
+            Ok(_) => {
+                self.call_back_with_error(
+                    request_id,
+                    GeolocationPositionErrorConstants::POSITION_UNAVAILABLE,
+                    "Invalid position",
+                );
+                return;
+            },
+            // Data acquisition failure: Call back with error passing errorCallback and
+            // POSITION_UNAVAILABLE.
+            Err(message) => {
+                self.call_back_with_error(
+                    request_id,
+                    GeolocationPositionErrorConstants::POSITION_UNAVAILABLE,
+                    &message,
+                );
+                return;
+            },
+        };
+
+        // Step 5.3.3. Otherwise, if position is null, set position to a new GeolocationPosition
+        // passing acquisitionTime and options.enableHighAccuracy.
+        // Step 5.3.4. Set this's [[cachedPosition]] to position.
+        let position =
+            GeolocationPosition::new(&self.global(), &position, enable_high_accuracy, can_gc);
+        self.cached_position.set(Some(&position));
+
+        self.invoke_success_callback(request_id, &position, can_gc);
+    }
+
+    /// Called when the timeout of an acquisition elapsed before a position was acquired.
+    fn handle_timeout(&self, request_id: u32) {
+        let Some(mut request) = self.active_request(request_id) else {
+            return;
+        };
+        request.timeout_handle = None;
+        drop(request);
+        self.call_back_with_error(
+            request_id,
+            GeolocationPositionErrorConstants::TIMEOUT,
+            "Timeout expired",
+        );
+    }
+
+    /// Queues a task to invoke the success callback of a request with a cached `position`.
+    fn queue_success(&self, request_id: u32, position: &GeolocationPosition) {
+        let geolocation = Trusted::new(self);
+        let position = Trusted::new(position);
+        self.global()
+            .task_manager()
+            .geolocation_task_source()
+            .queue(task!(geolocation_cached_position: move || {
+                geolocation.root().invoke_success_callback(
+                    request_id,
+                    &position.root(),
+                    CanGc::note(),
+                );
+            }));
+    }
+
+    /// Invokes the success callback of a request with `position`. A watch then waits for the
+    /// next significant change of geographic position, other requests are done.
+    fn invoke_success_callback(
+        &self,
+        request_id: u32,
+        position: &GeolocationPosition,
+        can_gc: CanGc,
+    ) {
+        let Some((callback, is_watch)) = self
+            .active_request(request_id)
+            .map(|request| (request.success_callback.clone(), request.watch_id.is_some()))
+        else {
+            return;
+        };
+        if !is_watch {
+            self.requests.borrow_mut().remove(&request_id);
+        }
+        let _ = callback.Call__(position, ExceptionHandling::Report, can_gc);
+        if is_watch {
+            self.acquire_a_position(request_id, false);
+        }
+    }
+
+    /// <https://w3c.github.io/geolocation/#dfn-call-back-with-error>
+    ///
+    /// A watch keeps waiting for a position after a timeout, other requests are done after
+    /// an error.
+    fn call_back_with_error(&self, request_id: u32, code: u16, message: &str) {
+        let Some(mut request) = self.active_request(request_id) else {
+            return;
+        };
+
+        // Step 2. If code is PERMISSION_DENIED and watchId was passed, remove watchId from
+        // this's [[watchIDs]].
+        if code == GeolocationPositionErrorConstants::PERMISSION_DENIED {
+            if let Some(watch_id) = request.watch_id {
+                self.watch_ids.borrow_mut().remove(&watch_id);
+            }
+        }
+
+        let keep_request =
+            request.watch_id.is_some() && code == GeolocationPositionErrorConstants::TIMEOUT;
+        let has_error_callback = request.error_callback.is_some();
+        request.active = keep_request;
+        drop(request);
+
+        // Step 1. If callback is null, return.
+        if !has_error_callback {
+            if !keep_request {
+                self.requests.borrow_mut().remove(&request_id);
+            }
+            return;
+        }
+
+        // Step 3. Queue a task on the geolocation task source with a step that invokes callback
+        // with « error » and "report".
+        let geolocation = Trusted::new(self);
+        let message = DOMString::from(message);
+        self.global()
+            .task_manager()
+            .geolocation_task_source()
+            .queue(task!(geolocation_error: move || {
+                let geolocation = geolocation.root();
+                let callback = {
+                    let mut requests = geolocation.requests.borrow_mut();
+                    let callback = requests
+                        .get(&request_id)
+                        .and_then(|request| request.error_callback.clone());
+                    if !keep_request {
+                        requests.remove(&request_id);
+                    }
+                    callback
+                };
+                let Some(callback) = callback else {
+                    return;
+                };
+                let error = GeolocationPositionError::new(
+                    &geolocation.global(),
+                    code,
+                    message,
+                    CanGc::note(),
+                );
+                let _ = callback.Call__(&error, ExceptionHandling::Report, CanGc::note());
+            }));
+    }
+}
+
+impl GeolocationMethods<crate::DomTypeHolder> for Geolocation {
+    /// <https://w3c.github.io/geolocation/#dom-geolocation-getcurrentposition>
+    fn GetCurrentPosition(
+        &self,
+        success_callback: Rc<PositionCallback>,
+        error_callback: Option<Rc<PositionErrorCallback>>,
+        options: &PositionOptions,
+    ) {
+        // Step 1. If this's relevant global object's associated Document is not fully active:
+        //   1. Call back with error errorCallback and POSITION_UNAVAILABLE.
+        //   2. Terminate this algorithm.
+        let request_id = self.add_request(success_callback, error_callback, options, None);
+        if !self.document_is_fully_active() {
+            self.call_back_with_error(
+                request_id,
+                GeolocationPositionErrorConstants::POSITION_UNAVAILABLE,
+                "Document is not fully active",
+            );
+            return;
+        }
+
+        // Step 2. Request a position passing this, successCallback, errorCallback, and options.
+        self.request_a_position(request_id);
+    }
+
+    /// <https://w3c.github.io/geolocation/#dom-geolocation-watchposition>
+    fn WatchPosition(
+        &self,
+        success_callback: Rc<PositionCallback>,
+        error_callback: Option<Rc<PositionErrorCallback>>,
+        options: &PositionOptions,
+    ) -> i32 {
+        // Step 1. If this's relevant global object's associated Document is not fully active:
+        //   1. Call back with error passing errorCallback and POSITION_UNAVAILABLE.
+        //   2. Return 0.
+        if !self.document_is_fully_active() {
+            let request_id = self.add_request(success_callback, error_callback, options, None);
+            self.call_back_with_error(
+                request_id,
+                GeolocationPositionErrorConstants::POSITION_UNAVAILABLE,
+                "Document is not fully active",
+            );
+            return 0;
+        }
+
+        // Step 2. Let watchId be an implementation-defined unsigned long that is greater than
+        // zero.
+        let watch_id = self.next_watch_id.get();
+        self.next_watch_id.set(watch_id.wrapping_add(1).max(1));
+
+        // Step 3. Append watchId to this's [[watchIDs]].
+        self.watch_ids.borrow_mut().insert(watch_id);
+
+        // Step 4. Request a position passing this, successCallback, errorCallback, options,
+        // and watchId.
+        // Step 5. Return watchId.
+        let request_id =
+            self.add_request(success_callback, error_callback, options, Some(watch_id));
+        self.request_a_position(request_id);
+        watch_id
+    }
+
+    /// <https://w3c.github.io/geolocation/#dom-geolocation-clearwatch>
+    fn ClearWatch(&self, watch_id: i32) {
+        // Step 1. Remove watchId from this's [[watchIDs]].
+        self.watch_ids.borrow_mut().remove(&watch_id);
+
+        // Stop the acquisitions of the watch, their pending answers are ignored.
+        let global = self.global();
+        self.requests.borrow_mut().retain(|_, request| {
+            if request.watch_id != Some(watch_id) {
+                return true;
+            }
+            if let Some(handle) = request.timeout_handle.take() {
+                global.unschedule_callback(handle);
+            }
+            false
+        });
+    }
+}
+
+#[derive(JSTraceable, MallocSizeOf)]
+pub(crate) struct GeolocationTimeoutCallback {
+    #[ignore_malloc_size_of = "Because it is non-owning"]
+    geolocation: Trusted<Geolocation>,
+    request_id: u32,
+}
+
+impl GeolocationTimeoutCallback {
+    /// <https://w3c.github.io/geolocation/#dfn-timeout>
+    pub(crate) fn invoke(self) {
+        self.geolocation.root().handle_timeout(self.request_id);
+    }
+}
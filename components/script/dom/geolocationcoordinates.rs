@@ -0,0 +1,92 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use embedder_traits::GeolocationPosition as EmbedderGeolocationPosition;
+
+use crate::dom::bindings::codegen::Bindings::GeolocationCoordinatesBinding::GeolocationCoordinatesMethods;
+use crate::dom::bindings::num::Finite;
+use crate::dom::bindings::reflector::{Reflector, reflect_dom_object};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::globalscope::GlobalScope;
+use crate::script_runtime::CanGc;
+
+/// <https://w3c.github.io/geolocation/#coordinates_interface>
+#[dom_struct]
+pub(crate) struct GeolocationCoordinates {
+    reflector_: Reflector,
+    accuracy: f64,
+    latitude: f64,
+    longitude: f64,
+    altitude: Option<f64>,
+    altitude_accuracy: Option<f64>,
+    heading: Option<f64>,
+    speed: Option<f64>,
+}
+
+impl GeolocationCoordinates {
+    fn new_inherited(position: &EmbedderGeolocationPosition) -> Self {
+        Self {
+            reflector_: Reflector::new(),
+            accuracy: position.accuracy,
+            latitude: position.latitude,
+            longitude: position.longitude,
+            altitude: position.altitude,
+            altitude_accuracy: position.altitude_accuracy,
+            heading: position.heading,
+            speed: position.speed,
+        }
+    }
+
+    pub(crate) fn new(
+        global: &GlobalScope,
+        position: &EmbedderGeolocationPosition,
+        can_gc: CanGc,
+    ) -> DomRoot<Self> {
+        reflect_dom_object(Box::new(Self::new_inherited(position)), global, can_gc)
+    }
+}
+
+/// Values that aren't finite can't be represented in the `double` attributes, so they are
+/// exposed as unknown.
+fn finite_or_none(value: Option<f64>) -> Option<Finite<f64>> {
+    value.and_then(Finite::new)
+}
+
+impl GeolocationCoordinatesMethods<crate::DomTypeHolder> for GeolocationCoordinates {
+    /// <https://w3c.github.io/geolocation/#dom-geolocationcoordinates-accuracy>
+    fn Accuracy(&self) -> Finite<f64> {
+        Finite::wrap(self.accuracy)
+    }
+
+    /// <https://w3c.github.io/geolocation/#dom-geolocationcoordinates-latitude>
+    fn Latitude(&self) -> Finite<f64> {
+        Finite::wrap(self.latitude)
+    }
+
+    /// <https://w3c.github.io/geolocation/#dom-geolocationcoordinates-longitude>
+    fn Longitude(&self) -> Finite<f64> {
+        Finite::wrap(self.longitude)
+    }
+
+    /// <https://w3c.github.io/geolocation/#dom-geolocationcoordinates-altitude>
+    fn GetAltitude(&self) -> Option<Finite<f64>> {
+        finite_or_none(self.altitude)
+    }
+
+    /// <https://w3c.github.io/geolocation/#dom-geolocationcoordinates-altitudeaccuracy>
+    fn GetAltitudeAccuracy(&self) -> Option<Finite<f64>> {
+        finite_or_none(self.altitude_accuracy)
+    }
+
+    /// <https://w3c.github.io/geolocation/#dom-geolocationcoordinates-heading>
+    fn GetHeading(&self) -> Option<Finite<f64>> {
+        finite_or_none(self.heading)
+    }
+
+    /// <https://w3c.github.io/geolocation/#dom-geolocationcoordinates-speed>
+    fn GetSpeed(&self) -> Option<Finite<f64>> {
+        finite_or_none(self.speed)
+    }
+}
@@ -0,0 +1,77 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use embedder_traits::GeolocationPosition as EmbedderGeolocationPosition;
+
+use crate::dom::bindings::codegen::Bindings::GeolocationPositionBinding::GeolocationPositionMethods;
+use crate::dom::bindings::reflector::{Reflector, reflect_dom_object};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::geolocationcoordinates::GeolocationCoordinates;
+use crate::dom::globalscope::GlobalScope;
+use crate::script_runtime::CanGc;
+
+/// <https://w3c.github.io/geolocation/#position_interface>
+#[dom_struct]
+pub(crate) struct GeolocationPosition {
+    reflector_: Reflector,
+    coords: Dom<GeolocationCoordinates>,
+    /// The time at which the position was acquired, in milliseconds since the Unix epoch.
+    timestamp: u64,
+    /// <https://w3c.github.io/geolocation/#dfn-ishighaccuracy>
+    is_high_accuracy: bool,
+}
+
+impl GeolocationPosition {
+    fn new_inherited(
+        coords: &GeolocationCoordinates,
+        timestamp: u64,
+        is_high_accuracy: bool,
+    ) -> Self {
+        Self {
+            reflector_: Reflector::new(),
+            coords: Dom::from_ref(coords),
+            timestamp,
+            is_high_accuracy,
+        }
+    }
+
+    pub(crate) fn new(
+        global: &GlobalScope,
+        position: &EmbedderGeolocationPosition,
+        is_high_accuracy: bool,
+        can_gc: CanGc,
+    ) -> DomRoot<Self> {
+        let coords = GeolocationCoordinates::new(global, position, can_gc);
+        reflect_dom_object(
+            Box::new(Self::new_inherited(
+                &coords,
+                position.timestamp,
+                is_high_accuracy,
+            )),
+            global,
+            can_gc,
+        )
+    }
+
+    pub(crate) fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    pub(crate) fn is_high_accuracy(&self) -> bool {
+        self.is_high_accuracy
+    }
+}
+
+impl GeolocationPositionMethods<crate::DomTypeHolder> for GeolocationPosition {
+    /// <https://w3c.github.io/geolocation/#dom-geolocationposition-coords>
+    fn Coords(&self) -> DomRoot<GeolocationCoordinates> {
+        DomRoot::from_ref(&self.coords)
+    }
+
+    /// <https://w3c.github.io/geolocation/#dom-geolocationposition-timestamp>
+    fn Timestamp(&self) -> u64 {
+        self.timestamp
+    }
+}
@@ -0,0 +1,51 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+
+use crate::dom::bindings::codegen::Bindings::GeolocationPositionErrorBinding::GeolocationPositionErrorMethods;
+use crate::dom::bindings::reflector::{Reflector, reflect_dom_object};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::str::DOMString;
+use crate::dom::globalscope::GlobalScope;
+use crate::script_runtime::CanGc;
+
+/// <https://w3c.github.io/geolocation/#position_error_interface>
+#[dom_struct]
+pub(crate) struct GeolocationPositionError {
+    reflector_: Reflector,
+    code: u16,
+    message: DOMString,
+}
+
+impl GeolocationPositionError {
+    fn new_inherited(code: u16, message: DOMString) -> Self {
+        Self {
+            reflector_: Reflector::new(),
+            code,
+            message,
+        }
+    }
+
+    pub(crate) fn new(
+        global: &GlobalScope,
+        code: u16,
+        message: DOMString,
+        can_gc: CanGc,
+    ) -> DomRoot<Self> {
+        reflect_dom_object(Box::new(Self::new_inherited(code, message)), global, can_gc)
+    }
+}
+
+impl GeolocationPositionErrorMethods<crate::DomTypeHolder> for GeolocationPositionError {
+    /// <https://w3c.github.io/geolocation/#dom-geolocationpositionerror-code>
+    fn Code(&self) -> u16 {
+        self.code
+    }
+
+    /// <https://w3c.github.io/geolocation/#dom-geolocationpositionerror-message>
+    fn Message(&self) -> DOMString {
+        self.message.clone()
+    }
+}
@@ -2,7 +2,7 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
-use std::cell::{Cell, OnceCell, Ref};
+use std::cell::{Cell, OnceCell, Ref, RefMut};
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::ffi::CStr;
@@ -51,8 +51,8 @@ use net_traits::policy_container::PolicyContainer;
 use net_traits::request::{InsecureRequestsPolicy, Referrer, RequestBuilder};
 use net_traits::response::HttpsState;
 use net_traits::{
-    CoreResourceMsg, CoreResourceThread, FetchResponseListener, IpcSend, ReferrerPolicy,
-    ResourceThreads, fetch_async,
+    CoreResourceMsg, CoreResourceThread, FetchResponseListener, IpcSend, NetworkError,
+    ReferrerPolicy, ResourceThreads, fetch_async,
 };
 use profile_traits::{ipc as profile_ipc, mem as profile_mem, time as profile_time};
 use script_bindings::interfaces::GlobalScopeHelpers;
@@ -74,9 +74,7 @@ use crate::dom::bindings::codegen::Bindings::BroadcastChannelBinding::BroadcastC
 use crate::dom::bindings::codegen::Bindings::EventSourceBinding::EventSource_Binding::EventSourceMethods;
 use crate::dom::bindings::codegen::Bindings::FunctionBinding::Function;
 use crate::dom::bindings::codegen::Bindings::NotificationBinding::NotificationPermissionCallback;
-use crate::dom::bindings::codegen::Bindings::PermissionStatusBinding::{
-    PermissionName, PermissionState,
-};
+use crate::dom::bindings::codegen::Bindings::PermissionStatusBinding::PermissionName;
 use crate::dom::bindings::codegen::Bindings::ReportingObserverBinding::Report;
 use crate::dom::bindings::codegen::Bindings::VoidFunctionBinding::VoidFunction;
 use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
@@ -95,6 +93,8 @@ use crate::dom::bindings::trace::CustomTraceable;
 use crate::dom::bindings::weakref::{DOMTracker, WeakRef};
 use crate::dom::blob::Blob;
 use crate::dom::broadcastchannel::BroadcastChannel;
+use crate::dom::console::Console;
+use crate::dom::cookiestore::CookieStore;
 use crate::dom::crypto::Crypto;
 use crate::dom::dedicatedworkerglobalscope::{
     DedicatedWorkerControlMsg, DedicatedWorkerGlobalScope,
@@ -108,10 +108,12 @@ use crate::dom::htmlscriptelement::{ScriptId, SourceCode};
 use crate::dom::messageport::MessagePort;
 use crate::dom::paintworkletglobalscope::PaintWorkletGlobalScope;
 use crate::dom::performance::Performance;
-use crate::dom::performanceobserver::VALID_ENTRY_TYPES;
+use crate::dom::performanceobserver::{VALID_ENTRY_TYPES, is_supported_entry_type};
+use crate::dom::permissionstatus::PermissionStatus;
 use crate::dom::promise::Promise;
 use crate::dom::readablestream::{CrossRealmTransformReadable, ReadableStream};
 use crate::dom::reportingobserver::ReportingObserver;
+use crate::dom::scheduler::Scheduler;
 use crate::dom::serviceworker::ServiceWorker;
 use crate::dom::serviceworkerregistration::ServiceWorkerRegistration;
 use crate::dom::trustedtypepolicyfactory::TrustedTypePolicyFactory;
@@ -121,6 +123,7 @@ use crate::dom::underlyingsourcecontainer::UnderlyingSourceType;
 use crate::dom::webgpu::gpudevice::GPUDevice;
 #[cfg(feature = "webgpu")]
 use crate::dom::webgpu::identityhub::IdentityHub;
+use crate::dom::websocket::WebSocket;
 use crate::dom::window::Window;
 use crate::dom::workerglobalscope::WorkerGlobalScope;
 use crate::dom::workletglobalscope::WorkletGlobalScope;
@@ -191,6 +194,10 @@ impl Drop for AutoCloseWorker {
 pub(crate) struct GlobalScope {
     eventtarget: EventTarget,
     crypto: MutNullableDom<Crypto>,
+    /// <https://cookiestore.spec.whatwg.org/#dom-window-cookiestore>
+    cookie_store: MutNullableDom<CookieStore>,
+    /// <https://wicg.github.io/scheduling-apis/#windoworworkerglobalscope-scheduler>
+    scheduler: MutNullableDom<Scheduler>,
 
     /// A [`TaskManager`] for this [`GlobalScope`].
     task_manager: OnceCell<TaskManager>,
@@ -274,8 +281,9 @@ pub(crate) struct GlobalScope {
     #[no_trace]
     top_level_creation_url: Option<ServoUrl>,
 
-    /// A map for storing the previous permission state read results.
-    permission_state_invocation_results: DomRefCell<HashMap<PermissionName, PermissionState>>,
+    /// Weak references to every `PermissionStatus` created in this global, so that they
+    /// can be notified when the permission store changes.
+    permission_status_tracker: DOMTracker<PermissionStatus>,
 
     /// The microtask queue associated with this global.
     ///
@@ -293,6 +301,10 @@ pub(crate) struct GlobalScope {
     /// Vector storing references of all eventsources.
     event_source_tracker: DOMTracker<EventSource>,
 
+    /// Weak references to every `WebSocket` created in this global, so that their
+    /// connections can be closed when its document is unloaded.
+    websocket_tracker: DOMTracker<WebSocket>,
+
     /// Storage for watching rejected promises waiting for some client to
     /// consume their rejection.
     /// Promises in this list have been rejected in the last turn of the
@@ -350,6 +362,9 @@ pub(crate) struct GlobalScope {
     /// Is considered in a secure context
     inherited_secure_context: Option<bool>,
 
+    /// <https://html.spec.whatwg.org/multipage/#concept-settings-object-cross-origin-isolated-capability>
+    cross_origin_isolated: bool,
+
     /// Directory to store unminified scripts for this window if unminify-js
     /// opt is enabled.
     unminified_js_dir: Option<String>,
@@ -737,6 +752,7 @@ impl GlobalScope {
         microtask_queue: Rc<MicrotaskQueue>,
         #[cfg(feature = "webgpu")] gpu_id_hub: Arc<IdentityHub>,
         inherited_secure_context: Option<bool>,
+        cross_origin_isolated: bool,
         unminify_js: bool,
     ) -> Self {
         Self {
@@ -746,6 +762,8 @@ impl GlobalScope {
             blob_state: Default::default(),
             eventtarget: EventTarget::new_inherited(),
             crypto: Default::default(),
+            cookie_store: Default::default(),
+            scheduler: Default::default(),
             registration_map: DomRefCell::new(HashMapTracedValues::new()),
             worker_map: DomRefCell::new(HashMapTracedValues::new()),
             pipeline_id,
@@ -763,10 +781,11 @@ impl GlobalScope {
             origin,
             creation_url,
             top_level_creation_url,
-            permission_state_invocation_results: Default::default(),
+            permission_status_tracker: DOMTracker::new(),
             microtask_queue,
             list_auto_close_worker: Default::default(),
             event_source_tracker: DOMTracker::new(),
+            websocket_tracker: DOMTracker::new(),
             uncaught_rejections: Default::default(),
             consumed_rejections: Default::default(),
             #[cfg(feature = "webgpu")]
@@ -779,6 +798,7 @@ impl GlobalScope {
             console_count_map: Default::default(),
             dynamic_modules: DomRefCell::new(DynamicModuleList::new()),
             inherited_secure_context,
+            cross_origin_isolated,
             unminified_js_dir: unminify_js.then(|| unminified_path("unminified-js")),
             byte_length_queuing_strategy_size_function: OnceCell::new(),
             count_queuing_strategy_size_function: OnceCell::new(),
@@ -969,6 +989,9 @@ impl GlobalScope {
     pub(crate) fn remove_web_messaging_and_dedicated_workers_infra(&self) {
         self.remove_message_ports_router();
         self.remove_broadcast_channel_router();
+        if let Some(cookie_store) = self.cookie_store.get() {
+            cookie_store.remove_listener();
+        }
 
         // Drop each ref to a worker explicitly now,
         // which will send a shutdown signal,
@@ -2197,10 +2220,26 @@ impl GlobalScope {
         }
     }
 
-    pub(crate) fn permission_state_invocation_results(
-        &self,
-    ) -> &DomRefCell<HashMap<PermissionName, PermissionState>> {
-        &self.permission_state_invocation_results
+    pub(crate) fn track_permission_status(&self, status: &PermissionStatus) {
+        self.permission_status_tracker.track(status);
+    }
+
+    /// Called when the permission store entry for `feature` changed, queues a task to
+    /// update every `PermissionStatus` of this global that is about that feature.
+    /// <https://w3c.github.io/permissions/#dfn-permissionstatus-update-steps>
+    pub(crate) fn permission_state_changed(&self, feature: PermissionName) {
+        self.permission_status_tracker
+            .for_each(|status: DomRoot<PermissionStatus>| {
+                if status.get_query() != feature {
+                    return;
+                }
+                let status = Trusted::new(&*status);
+                self.task_manager().dom_manipulation_task_source().queue(
+                    task!(permission_status_update: move || {
+                        status.root().update(CanGc::note());
+                    }),
+                );
+            });
     }
 
     pub(crate) fn track_worker(
@@ -2239,6 +2278,21 @@ impl GlobalScope {
         canceled_any_fetch
     }
 
+    pub(crate) fn track_websocket(&self, websocket: &WebSocket) {
+        self.websocket_tracker.track(websocket);
+    }
+
+    /// Make every `WebSocket` of this global disappear, returning whether any of them still
+    /// had a connection that was not closed.
+    pub(crate) fn make_websockets_disappear(&self) -> bool {
+        let mut closed_any_connection = false;
+        self.websocket_tracker
+            .for_each(|websocket: DomRoot<WebSocket>| {
+                closed_any_connection |= websocket.make_disappear();
+            });
+        closed_any_connection
+    }
+
     /// Returns the global scope of the realm that the given DOM object's reflector
     /// was created in.
     #[allow(unsafe_code)]
@@ -2362,6 +2416,14 @@ impl GlobalScope {
         self.crypto.or_init(|| Crypto::new(self, can_gc))
     }
 
+    pub(crate) fn cookie_store(&self, can_gc: CanGc) -> DomRoot<CookieStore> {
+        self.cookie_store.or_init(|| CookieStore::new(self, can_gc))
+    }
+
+    pub(crate) fn scheduler(&self, can_gc: CanGc) -> DomRoot<Scheduler> {
+        self.scheduler.or_init(|| Scheduler::new(self, can_gc))
+    }
+
     pub(crate) fn live_devtools_updates(&self) -> bool {
         self.devtools_wants_updates.get()
     }
@@ -2616,6 +2678,25 @@ impl GlobalScope {
         self.downcast::<Window>().expect("expected a Window scope")
     }
 
+    /// Report to the console that a fetch of `url` was blocked because the response did not
+    /// match the integrity metadata of the request, if that is why `error` occurred.
+    pub(crate) fn report_subresource_integrity_failure(
+        &self,
+        url: &ServoUrl,
+        error: &NetworkError,
+    ) {
+        if *error != NetworkError::SubresourceIntegrity {
+            return;
+        }
+        Console::internal_error(
+            self,
+            DOMString::from(format!(
+                "None of the \"integrity\" hashes match the content of the subresource at \"{url}\". \
+                The resource has been blocked."
+            )),
+        );
+    }
+
     /// Returns a policy that should be used for fetches initiated from this global.
     pub(crate) fn insecure_requests_policy(&self) -> InsecureRequestsPolicy {
         if let Some(window) = self.downcast::<Window>() {
@@ -2643,6 +2724,21 @@ impl GlobalScope {
         })
     }
 
+    /// The origin of this global followed by the origins of the ancestors of its navigable, or
+    /// those of the navigable of its owner for workers. Requests made by this global use them
+    /// to determine their site for cookies and cookie partition.
+    pub(crate) fn origin_and_ancestor_origins(&self) -> Vec<ImmutableOrigin> {
+        if let Some(window) = self.downcast::<Window>() {
+            return window.Document().ancestor_origins_for_nested_navigable();
+        }
+        if let Some(worker) = self.downcast::<WorkerGlobalScope>() {
+            if !worker.ancestor_origins().is_empty() {
+                return worker.ancestor_origins().to_vec();
+            }
+        }
+        vec![self.origin().immutable().clone()]
+    }
+
     /// <https://html.spec.whatwg.org/multipage/#report-the-error>
     pub(crate) fn report_an_error(&self, error_info: ErrorInfo, value: HandleValue, can_gc: CanGc) {
         // Step 6. Early return if global is in error reporting mode,
@@ -3070,6 +3166,7 @@ impl GlobalScope {
             || {
                 VALID_ENTRY_TYPES
                     .iter()
+                    .filter(|t| is_supported_entry_type(t))
                     .map(|t| DOMString::from(t.to_string()))
                     .collect()
             },
@@ -3122,6 +3219,14 @@ impl GlobalScope {
         }
     }
 
+    /// <https://html.spec.whatwg.org/multipage/#concept-settings-object-cross-origin-isolated-capability>
+    ///
+    /// This is decided when the global is created, since it determines whether shared memory
+    /// is available in its realm.
+    pub(crate) fn is_cross_origin_isolated(&self) -> bool {
+        self.cross_origin_isolated
+    }
+
     /// <https://www.w3.org/TR/CSP/#get-csp-of-object>
     pub(crate) fn get_csp_list(&self) -> Option<CspList> {
         if self.downcast::<Window>().is_some() || self.downcast::<WorkerGlobalScope>().is_some() {
@@ -3286,7 +3391,7 @@ impl GlobalScope {
     ) {
         fetch_async(
             &self.core_resource_thread(),
-            request_builder,
+            request_builder.ancestor_origins(self.origin_and_ancestor_origins()),
             None,
             network_listener.into_callback(),
         );
@@ -3405,6 +3510,16 @@ impl GlobalScope {
         unreachable!();
     }
 
+    pub(crate) fn report_buffer_mut(&self) -> RefMut<'_, Vec<Report>> {
+        if let Some(window) = self.downcast::<Window>() {
+            return window.report_buffer_mut();
+        }
+        if let Some(worker) = self.downcast::<WorkerGlobalScope>() {
+            return worker.report_buffer_mut();
+        }
+        unreachable!();
+    }
+
     pub(crate) fn import_map(&self) -> Ref<'_, ImportMap> {
         self.import_map.borrow()
     }
@@ -3506,4 +3621,8 @@ impl GlobalScopeHelpers<crate::DomTypeHolder> for GlobalScope {
     fn is_secure_context(&self) -> bool {
         self.is_secure_context()
     }
+
+    fn is_cross_origin_isolated(&self) -> bool {
+        self.is_cross_origin_isolated()
+    }
 }
@@ -4,10 +4,12 @@
 
 use std::cell::Cell;
 use std::cmp::Ordering;
+use std::collections::HashMap;
 
 use base::id::HistoryStateId;
 use constellation_traits::{
-    ScriptToConstellationMessage, StructuredSerializedData, TraversalDirection,
+    PersistedScrollState, ScriptToConstellationMessage, ScrollRestorationMode,
+    StructuredSerializedData, TraversalDirection,
 };
 use dom_struct::dom_struct;
 use js::jsapi::Heap;
@@ -18,9 +20,11 @@ use profile_traits::ipc;
 use profile_traits::ipc::channel;
 use servo_url::ServoUrl;
 
-use crate::dom::bindings::codegen::Bindings::HistoryBinding::HistoryMethods;
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::HistoryBinding::{HistoryMethods, ScrollRestoration};
 use crate::dom::bindings::codegen::Bindings::LocationBinding::Location_Binding::LocationMethods;
-use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::codegen::Bindings::NavigationBinding::NavigationType;
+use crate::dom::bindings::codegen::Bindings::WindowBinding::{ScrollBehavior, WindowMethods};
 use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
 use crate::dom::bindings::inheritance::Castable;
 use crate::dom::bindings::reflector::{DomGlobal, Reflector, reflect_dom_object};
@@ -35,7 +39,7 @@ use crate::dom::popstateevent::PopStateEvent;
 use crate::dom::window::Window;
 use crate::script_runtime::{CanGc, JSContext};
 
-enum PushOrReplace {
+pub(crate) enum PushOrReplace {
     Push,
     Replace,
 }
@@ -49,6 +53,15 @@ pub(crate) struct History {
     state: Heap<JSVal>,
     #[no_trace]
     state_id: Cell<Option<HistoryStateId>>,
+    /// <https://html.spec.whatwg.org/multipage/#she-scroll-restoration-mode> of the current
+    /// session history entry of the document.
+    #[no_trace]
+    scroll_restoration_mode: Cell<ScrollRestorationMode>,
+    /// The scroll state of the session history entries of the document that are not the
+    /// current one, identified by their classic history API state and their URL.
+    #[no_trace]
+    #[ignore_malloc_size_of = "Only contains plain data"]
+    scroll_states: DomRefCell<HashMap<(Option<HistoryStateId>, ServoUrl), PersistedScrollState>>,
 }
 
 impl History {
@@ -58,6 +71,8 @@ impl History {
             window: Dom::from_ref(window),
             state: Heap::default(),
             state_id: Cell::new(None),
+            scroll_restoration_mode: Cell::new(ScrollRestorationMode::Auto),
+            scroll_states: Default::default(),
         }
     }
 
@@ -90,6 +105,15 @@ impl History {
         url: ServoUrl,
         can_gc: CanGc,
     ) {
+        // Fire the navigate event of the navigation API, which cannot cancel the traversal.
+        let navigation = self.window.navigation_if_created();
+        let navigation_traversal = navigation.as_ref().and_then(|navigation| {
+            navigation.start_same_document_traversal(&url, state_id, can_gc)
+        });
+
+        // Save the scroll state of the entry the document is leaving.
+        self.persist_scroll_state();
+
         // Steps 5
         let document = self.window.Document();
         let old_url = document.url().clone();
@@ -98,9 +122,13 @@ impl History {
         // Step 6
         let hash_changed = old_url.fragment() != url.fragment();
 
+        // Restore the persisted scroll position of the entry if its scroll restoration mode
+        // is "auto".
         // Step 8
-        if let Some(fragment) = url.fragment() {
-            document.check_and_scroll_fragment(fragment);
+        if !self.restore_scroll_state(state_id, &url) {
+            if let Some(fragment) = url.fragment() {
+                document.check_and_scroll_fragment(fragment);
+            }
         }
 
         // Step 11
@@ -138,6 +166,10 @@ impl History {
             },
         }
 
+        if let (Some(navigation), Some(navigation_traversal)) = (navigation, navigation_traversal) {
+            navigation.finish_same_document_traversal(navigation_traversal, can_gc);
+        }
+
         // TODO: Queue events on DOM Manipulation task source if non-blocking flag is set.
         // Step 16.1
         if state_changed {
@@ -166,6 +198,66 @@ impl History {
         }
     }
 
+    pub(crate) fn state_id(&self) -> Option<HistoryStateId> {
+        self.state_id.get()
+    }
+
+    /// The scroll state of the current session history entry of the document.
+    pub(crate) fn current_scroll_state(&self) -> PersistedScrollState {
+        PersistedScrollState {
+            scroll_restoration_mode: self.scroll_restoration_mode.get(),
+            scroll_offset: self.window.scroll_offset().to_untyped(),
+        }
+    }
+
+    /// Save the scroll state of the current session history entry of the document, before the
+    /// document moves to another one of its entries.
+    /// <https://html.spec.whatwg.org/multipage/#persist-history-entry-state>
+    pub(crate) fn persist_scroll_state(&self) {
+        let key = (self.state_id.get(), self.window.Document().url());
+        let scroll_state = self.current_scroll_state();
+        self.scroll_states.borrow_mut().insert(key, scroll_state);
+    }
+
+    /// Restore the scroll state of the session history entry of the document with the given
+    /// classic history API state and URL, returning whether its scroll position was restored.
+    /// <https://html.spec.whatwg.org/multipage/#restore-persisted-state>
+    fn restore_scroll_state(&self, state_id: Option<HistoryStateId>, url: &ServoUrl) -> bool {
+        let scroll_state = self
+            .scroll_states
+            .borrow()
+            .get(&(state_id, url.clone()))
+            .copied();
+        match scroll_state {
+            Some(scroll_state) => self.apply_scroll_state(scroll_state),
+            None => {
+                self.scroll_restoration_mode
+                    .set(ScrollRestorationMode::Auto);
+                false
+            },
+        }
+    }
+
+    /// Make a persisted scroll state the one of the current session history entry of the
+    /// document, returning whether its scroll position was restored.
+    pub(crate) fn apply_scroll_state(&self, scroll_state: PersistedScrollState) -> bool {
+        self.scroll_restoration_mode
+            .set(scroll_state.scroll_restoration_mode);
+
+        // Step 2. If entry's scroll restoration mode is "auto", and entry's document's
+        // relevant global object's navigation API's suppress normal scroll restoration during
+        // ongoing navigation is false, then restore scroll position data given entry.
+        if scroll_state.scroll_restoration_mode != ScrollRestorationMode::Auto {
+            return false;
+        }
+        self.window.scroll(
+            scroll_state.scroll_offset.x as f64,
+            scroll_state.scroll_offset.y as f64,
+            ScrollBehavior::Auto,
+        );
+        true
+    }
+
     pub(crate) fn remove_states(&self, states: Vec<HistoryStateId>) {
         let _ = self
             .window
@@ -183,6 +275,7 @@ impl History {
         _title: DOMString,
         url: Option<USVString>,
         push_or_replace: PushOrReplace,
+        can_gc: CanGc,
     ) -> ErrorResult {
         // Step 1
         let document = self.window.Document();
@@ -222,6 +315,58 @@ impl History {
             None => document.url(),
         };
 
+        // Step 7. Run the URL and history update steps given document and newURL, with
+        // serializedData set to serializedData and historyHandling set to historyHandling.
+        // Note: Before that, the shared history push/replace state steps fire a navigate event,
+        // which can cancel the update or perform it on its own when intercepted.
+        if let Some(navigation) = self.window.navigation_if_created() {
+            let navigation_type = match push_or_replace {
+                PushOrReplace::Push => NavigationType::Push,
+                PushOrReplace::Replace => NavigationType::Replace,
+            };
+            if !navigation.fire_a_push_replace_reload_navigate_event(
+                navigation_type,
+                new_url.clone(),
+                true,
+                Some(serialized_data.serialized.clone()),
+                can_gc,
+            ) {
+                return Ok(());
+            }
+        }
+
+        self.url_and_history_update_steps(
+            new_url,
+            Some(serialized_data.serialized),
+            push_or_replace,
+            can_gc,
+        );
+
+        Ok(())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#url-and-history-update-steps>
+    /// Steps 8-13, where no `serialized_data` means that the classic history API state is
+    /// kept on replace, and null on push.
+    pub(crate) fn url_and_history_update_steps(
+        &self,
+        new_url: ServoUrl,
+        serialized_data: Option<Vec<u8>>,
+        push_or_replace: PushOrReplace,
+        can_gc: CanGc,
+    ) {
+        let document = self.window.Document();
+        let navigation_type = match push_or_replace {
+            PushOrReplace::Push => NavigationType::Push,
+            PushOrReplace::Replace => NavigationType::Replace,
+        };
+
+        // The new entry of a push inherits the scroll restoration mode of the current one,
+        // whose scroll state is saved so that it can be restored when traversing back to it.
+        if let PushOrReplace::Push = push_or_replace {
+            self.persist_scroll_state();
+        }
+
         // Step 8
         let state_id = match push_or_replace {
             PushOrReplace::Push => {
@@ -255,40 +400,60 @@ impl History {
             },
         };
 
-        let _ = self.window.as_global_scope().resource_threads().send(
-            CoreResourceMsg::SetHistoryState(state_id, serialized_data.serialized.clone()),
-        );
+        if let Some(serialized_data) = &serialized_data {
+            let _ = self.window.as_global_scope().resource_threads().send(
+                CoreResourceMsg::SetHistoryState(state_id, serialized_data.clone()),
+            );
+        }
 
         // TODO: Step 9 Update current entry to represent a GET request
         // https://github.com/servo/servo/issues/19156
 
         // Step 10
-        document.set_url(new_url);
+        document.set_url(new_url.clone());
 
         // Step 11
-        rooted!(in(*cx) let mut state = UndefinedValue());
-        if structuredclone::read(
-            self.window.as_global_scope(),
-            serialized_data,
-            state.handle_mut(),
-        )
-        .is_err()
-        {
-            warn!("Error reading structuredclone data");
-        }
+        match serialized_data {
+            Some(serialized_data) => {
+                let data = StructuredSerializedData {
+                    serialized: serialized_data,
+                    ..Default::default()
+                };
+                rooted!(in(*GlobalScope::get_cx()) let mut state = UndefinedValue());
+                if structuredclone::read(self.window.as_global_scope(), data, state.handle_mut())
+                    .is_err()
+                {
+                    warn!("Error reading structuredclone data");
+                }
 
-        // Step 12
-        self.state.set(state.get());
+                // Step 12
+                self.state.set(state.get());
+            },
+            None => {
+                if let PushOrReplace::Push = push_or_replace {
+                    self.state.set(NullValue());
+                }
+            },
+        }
 
         // TODO: Step 13 Update Document's latest entry to current entry
         // https://github.com/servo/servo/issues/19158
 
-        Ok(())
+        // Update the navigation API entries for a same-document navigation given the
+        // navigation of the window, the new session history entry and historyHandling.
+        if let Some(navigation) = self.window.navigation_if_created() {
+            navigation.update_entries_for_same_document_navigation(
+                navigation_type,
+                new_url,
+                Some(state_id),
+                can_gc,
+            );
+        }
     }
 
     /// <https://html.spec.whatwg.org/multipage/#can-have-its-url-rewritten>
     /// Step 2-6
-    fn can_have_url_rewritten(document_url: &ServoUrl, target_url: &ServoUrl) -> bool {
+    pub(crate) fn can_have_url_rewritten(document_url: &ServoUrl, target_url: &ServoUrl) -> bool {
         // Step 2. If targetURL and documentURL differ in their scheme, username,
         // password, host, or port components, then return false.
         if target_url.scheme() != document_url.scheme() ||
@@ -324,6 +489,39 @@ impl History {
 }
 
 impl HistoryMethods<crate::DomTypeHolder> for History {
+    /// <https://html.spec.whatwg.org/multipage/#dom-history-scroll-restoration>
+    fn GetScrollRestoration(&self) -> Fallible<ScrollRestoration> {
+        // Step 1. If this's relevant global object's associated Document is not fully active,
+        // then throw a "SecurityError" DOMException.
+        if !self.window.Document().is_fully_active() {
+            return Err(Error::Security);
+        }
+
+        // Step 2. Return this's node navigable's active session history entry's scroll
+        // restoration mode.
+        Ok(match self.scroll_restoration_mode.get() {
+            ScrollRestorationMode::Auto => ScrollRestoration::Auto,
+            ScrollRestorationMode::Manual => ScrollRestoration::Manual,
+        })
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-history-scroll-restoration>
+    fn SetScrollRestoration(&self, value: ScrollRestoration) -> ErrorResult {
+        // Step 1. If this's relevant global object's associated Document is not fully active,
+        // then throw a "SecurityError" DOMException.
+        if !self.window.Document().is_fully_active() {
+            return Err(Error::Security);
+        }
+
+        // Step 2. Set this's node navigable's active session history entry's scroll
+        // restoration mode to the given value.
+        self.scroll_restoration_mode.set(match value {
+            ScrollRestoration::Auto => ScrollRestorationMode::Auto,
+            ScrollRestoration::Manual => ScrollRestorationMode::Manual,
+        });
+        Ok(())
+    }
+
     /// <https://html.spec.whatwg.org/multipage/#dom-history-state>
     fn GetState(&self, _cx: JSContext, mut retval: MutableHandleValue) -> Fallible<()> {
         if !self.window.Document().is_fully_active() {
@@ -377,8 +575,9 @@ impl HistoryMethods<crate::DomTypeHolder> for History {
         data: HandleValue,
         title: DOMString,
         url: Option<USVString>,
+        can_gc: CanGc,
     ) -> ErrorResult {
-        self.push_or_replace_state(cx, data, title, url, PushOrReplace::Push)
+        self.push_or_replace_state(cx, data, title, url, PushOrReplace::Push, can_gc)
     }
 
     /// <https://html.spec.whatwg.org/multipage/#dom-history-replacestate>
@@ -388,7 +587,8 @@ impl HistoryMethods<crate::DomTypeHolder> for History {
         data: HandleValue,
         title: DOMString,
         url: Option<USVString>,
+        can_gc: CanGc,
     ) -> ErrorResult {
-        self.push_or_replace_state(cx, data, title, url, PushOrReplace::Replace)
+        self.push_or_replace_state(cx, data, title, url, PushOrReplace::Replace, can_gc)
     }
 }
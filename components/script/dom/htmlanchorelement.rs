@@ -31,7 +31,7 @@ use crate::dom::htmlelement::HTMLElement;
 use crate::dom::htmlhyperlinkelementutils::{HyperlinkElement, HyperlinkElementTraits};
 use crate::dom::htmlimageelement::HTMLImageElement;
 use crate::dom::mouseevent::MouseEvent;
-use crate::dom::node::{BindContext, Node};
+use crate::dom::node::{BindContext, Node, NodeTraits};
 use crate::dom::virtualmethods::VirtualMethods;
 use crate::links::{LinkRelations, follow_hyperlink};
 use crate::script_runtime::CanGc;
@@ -340,6 +340,17 @@ impl Activatable for HTMLAnchorElement {
 
         // Step 2.
         //TODO: Download the link is `download` attribute is set.
+        // Until then, a link that would be downloaded is not followed either when its document
+        // is not allowed to download.
+        // <https://html.spec.whatwg.org/multipage/#allowed-to-download>
+        if element.has_attribute(&local_name!("download")) &&
+            !self
+                .owner_document()
+                .snapshot_source_snapshot_params()
+                .allows_downloading()
+        {
+            return;
+        }
         follow_hyperlink(element, self.relations.get(), ismap_suffix);
     }
 }
@@ -67,6 +67,7 @@ impl HTMLAudioElementMethods<crate::DomTypeHolder> for HTMLAudioElement {
             QualName::new(None, ns!(html), local_name!("audio")),
             None,
             &window.Document(),
+            None,
             ElementCreator::ScriptCreated,
             CustomElementCreationMode::Synchronous,
             proto,
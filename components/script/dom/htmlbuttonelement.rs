@@ -47,6 +47,9 @@ pub(crate) struct HTMLButtonElement {
     form_owner: MutNullableDom<HTMLFormElement>,
     labels_node_list: MutNullableDom<NodeList>,
     validity_state: MutNullableDom<ValidityState>,
+    /// The explicitly set attr-element of the `popovertarget` attribute.
+    /// <https://html.spec.whatwg.org/multipage/#explicitly-set-attr-element>
+    explicitly_set_popover_target_element: MutNullableDom<Element>,
 }
 
 impl HTMLButtonElement {
@@ -66,6 +69,7 @@ impl HTMLButtonElement {
             form_owner: Default::default(),
             labels_node_list: Default::default(),
             validity_state: Default::default(),
+            explicitly_set_popover_target_element: Default::default(),
         }
     }
 
@@ -91,6 +95,29 @@ impl HTMLButtonElement {
     pub(crate) fn is_submit_button(&self) -> bool {
         self.button_type.get() == ButtonType::Submit
     }
+
+    /// <https://html.spec.whatwg.org/multipage/#popover-target-element>
+    pub(crate) fn popover_target_element(&self) -> Option<DomRoot<HTMLElement>> {
+        // Step 2. If node is disabled, then return null.
+        // Step 3. If node has a form owner and node is a submit button, then return null.
+        if self.upcast::<Element>().disabled_state() ||
+            (self.form_owner().is_some() && self.is_submit_button())
+        {
+            return None;
+        }
+
+        // Step 4. Let popoverElement be the result of running node's get the popovertarget-
+        // associated element.
+        // Step 5. If popoverElement is null, then return null.
+        // Step 6. If popoverElement's popover attribute is in the no popover state, then return
+        // null.
+        self.upcast::<HTMLElement>()
+            .popover_target_attr_associated_element(
+                self.explicitly_set_popover_target_element.get(),
+            )
+            .and_then(DomRoot::downcast::<HTMLElement>)
+            .filter(|popover| popover.popover_state().is_some())
+    }
 }
 
 impl HTMLButtonElementMethods<crate::DomTypeHolder> for HTMLButtonElement {
@@ -202,6 +229,32 @@ impl HTMLButtonElementMethods<crate::DomTypeHolder> for HTMLButtonElement {
     fn SetCustomValidity(&self, error: DOMString) {
         self.validity_state().set_custom_error_message(error);
     }
+
+    // https://html.spec.whatwg.org/multipage/#dom-popovertargetelement
+    fn GetPopoverTargetElement(&self) -> Option<DomRoot<Element>> {
+        self.upcast::<HTMLElement>()
+            .popover_target_attr_associated_element(
+                self.explicitly_set_popover_target_element.get(),
+            )
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-popovertargetelement
+    fn SetPopoverTargetElement(&self, element: Option<&Element>, can_gc: CanGc) {
+        self.upcast::<HTMLElement>()
+            .set_popover_target_attr_associated_element(
+                &self.explicitly_set_popover_target_element,
+                element,
+                can_gc,
+            );
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-popovertargetaction
+    fn PopoverTargetAction(&self) -> DOMString {
+        self.upcast::<HTMLElement>().popover_target_action()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-popovertargetaction
+    make_setter!(SetPopoverTargetAction, "popovertargetaction");
 }
 
 impl HTMLButtonElement {
@@ -285,6 +338,11 @@ impl VirtualMethods for HTMLButtonElement {
                 self.validity_state()
                     .perform_validation_and_update(ValidationFlags::empty(), can_gc);
             },
+            // Changing the content attribute forgets the element set through
+            // popoverTargetElement.
+            local_name!("popovertarget") => {
+                self.explicitly_set_popover_target_element.set(None);
+            },
             _ => {},
         }
     }
@@ -359,7 +417,7 @@ impl Activatable for HTMLButtonElement {
     }
 
     // https://html.spec.whatwg.org/multipage/#run-post-click-activation-steps
-    fn activation_behavior(&self, _event: &Event, _target: &EventTarget, can_gc: CanGc) {
+    fn activation_behavior(&self, _event: &Event, target: &EventTarget, can_gc: CanGc) {
         let ty = self.button_type.get();
         match ty {
             //https://html.spec.whatwg.org/multipage/#attr-button-type-submit-state
@@ -381,5 +439,14 @@ impl Activatable for HTMLButtonElement {
             },
             _ => (),
         }
+
+        // Run the popover target attribute activation behavior given element and event's
+        // target.
+        self.upcast::<HTMLElement>()
+            .run_popover_target_attribute_activation_behavior(
+                self.popover_target_element(),
+                target,
+                can_gc,
+            );
     }
 }
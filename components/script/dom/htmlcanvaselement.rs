@@ -23,6 +23,7 @@ use script_bindings::weakref::WeakRef;
 use servo_media::streams::MediaStreamType;
 use servo_media::streams::registry::MediaStreamId;
 use style::attr::AttrValue;
+use webrender_api::ImageKey;
 
 use super::node::NodeDamage;
 pub(crate) use crate::canvas_context::*;
@@ -79,6 +80,11 @@ pub(crate) struct HTMLCanvasElement {
     callback_id: Cell<u32>,
     #[ignore_malloc_size_of = "not implemented for webidl callbacks"]
     blob_callbacks: RefCell<HashMap<u32, Rc<BlobCallback>>>,
+
+    /// The last frame pushed by the OffscreenCanvas this element is the placeholder
+    /// of, when that canvas was transferred to another event loop.
+    #[no_trace]
+    remote_offscreen_image: Cell<Option<ImageKey>>,
 }
 
 impl HTMLCanvasElement {
@@ -92,6 +98,7 @@ impl HTMLCanvasElement {
             context_mode: DomRefCell::new(None),
             callback_id: Cell::new(0),
             blob_callbacks: RefCell::new(HashMap::new()),
+            remote_offscreen_image: Cell::new(None),
         }
     }
 
@@ -146,6 +153,32 @@ impl HTMLCanvasElement {
         element.set_uint_attribute(&html5ever::local_name!("width"), value, can_gc);
     }
 
+    /// Display a frame of the OffscreenCanvas this element is the placeholder of,
+    /// pushed from the event loop the canvas was transferred to.
+    /// <https://html.spec.whatwg.org/multipage/#offscreencanvas-placeholder>
+    pub(crate) fn update_from_remote_offscreen_canvas(
+        &self,
+        image_key: Option<ImageKey>,
+        width: u64,
+        height: u64,
+        can_gc: CanGc,
+    ) {
+        if !matches!(
+            *self.context_mode.borrow(),
+            Some(RenderingContext::Placeholder(_))
+        ) {
+            return;
+        }
+        if self.Width() as u64 != width {
+            self.set_natural_width(width as _, can_gc);
+        }
+        if self.Height() as u64 != height {
+            self.set_natural_height(height as _, can_gc);
+        }
+        self.remote_offscreen_image.set(image_key);
+        self.upcast::<Node>().dirty(NodeDamage::Other);
+    }
+
     pub(crate) fn set_natural_height(&self, value: u32, can_gc: CanGc) {
         let value = if value > UNSIGNED_LONG_MAX {
             DEFAULT_HEIGHT
@@ -172,7 +205,10 @@ impl LayoutHTMLCanvasElementHelpers for LayoutDom<'_, HTMLCanvasElement> {
                 Some(RenderingContext::WebGL2(context)) => context.to_layout().canvas_data_source(),
                 #[cfg(feature = "webgpu")]
                 Some(RenderingContext::WebGPU(context)) => context.to_layout().canvas_data_source(),
-                Some(RenderingContext::Placeholder(_)) | None => None,
+                Some(RenderingContext::Placeholder(_)) => {
+                    self.unsafe_get().remote_offscreen_image.get()
+                },
+                None => None,
             }
         };
 
@@ -5,23 +5,30 @@
 use dom_struct::dom_struct;
 use html5ever::{LocalName, Prefix, local_name, ns};
 use js::rust::HandleObject;
+use stylo_dom::ElementState;
 
+use crate::dom::attr::Attr;
 use crate::dom::bindings::cell::DomRefCell;
 use crate::dom::bindings::codegen::Bindings::HTMLDialogElementBinding::HTMLDialogElementMethods;
+use crate::dom::bindings::error::{Error, ErrorResult};
 use crate::dom::bindings::inheritance::Castable;
-use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::root::{DomRoot, MutNullableDom};
 use crate::dom::bindings::str::DOMString;
-use crate::dom::document::Document;
-use crate::dom::element::Element;
+use crate::dom::document::{Document, FocusInitiator};
+use crate::dom::element::{AttributeMutation, Element};
+use crate::dom::event::{Event, EventBubbles, EventCancelable};
 use crate::dom::eventtarget::EventTarget;
 use crate::dom::htmlelement::HTMLElement;
-use crate::dom::node::{Node, NodeTraits};
+use crate::dom::node::{Node, NodeTraits, ShadowIncluding, UnbindContext};
+use crate::dom::virtualmethods::VirtualMethods;
 use crate::script_runtime::CanGc;
 
 #[dom_struct]
 pub(crate) struct HTMLDialogElement {
     htmlelement: HTMLElement,
     return_value: DomRefCell<DOMString>,
+    /// <https://html.spec.whatwg.org/multipage/#previously-focused-element>
+    previously_focused_element: MutNullableDom<Element>,
 }
 
 impl HTMLDialogElement {
@@ -33,6 +40,7 @@ impl HTMLDialogElement {
         HTMLDialogElement {
             htmlelement: HTMLElement::new_inherited(local_name, prefix, document),
             return_value: DomRefCell::new(DOMString::new()),
+            previously_focused_element: Default::default(),
         }
     }
 
@@ -53,6 +61,149 @@ impl HTMLDialogElement {
             can_gc,
         )
     }
+
+    /// <https://html.spec.whatwg.org/multipage/#is-modal>
+    ///
+    /// This is reflected in the `:modal` pseudo-class, which the user agent stylesheet
+    /// uses to place the dialog and its `::backdrop` in the top layer.
+    pub(crate) fn is_modal(&self) -> bool {
+        self.upcast::<Element>()
+            .state()
+            .contains(ElementState::MODAL)
+    }
+
+    fn set_is_modal(&self, is_modal: bool) {
+        self.upcast::<Element>()
+            .set_state(ElementState::MODAL, is_modal);
+    }
+
+    /// Stop being modal and leave the top layer, if this dialog was in it.
+    fn remove_from_top_layer(&self) {
+        self.set_is_modal(false);
+        self.owner_document()
+            .remove_from_top_layer(self.upcast::<Element>());
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dialog-focusing-steps>
+    pub(crate) fn run_dialog_focusing_steps(&self, can_gc: CanGc) {
+        let node = self.upcast::<Node>();
+
+        // Step 1. If the allow focus steps given subject's node document return false, then return.
+        if node.is_inert() {
+            return;
+        }
+
+        // Step 2. Let control be null.
+        // Step 3. If subject has the autofocus attribute, then set control to subject.
+        // Step 4. If control is null, then set control to the focus delegate of subject.
+        let control = if self
+            .upcast::<Element>()
+            .has_attribute(&local_name!("autofocus"))
+        {
+            Some(DomRoot::from_ref(self.upcast::<Element>()))
+        } else {
+            self.focus_delegate()
+        };
+
+        // Step 5. If control is null, then set control to subject.
+        // Step 6. Run the focusing steps for control.
+        //
+        // Dialogs themselves are not focusable areas in Servo, so when there is nothing
+        // to focus inside the dialog we focus the document instead, which at least moves
+        // the focus away from the now inert content.
+        let document = self.owner_document();
+        document.request_focus(None, FocusInitiator::Local, can_gc);
+        document.request_focus(control.as_deref(), FocusInitiator::Local, can_gc);
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#focus-delegate>
+    /// The first descendant with the autofocus attribute that is a focusable area, or else the
+    /// first descendant that is a focusable area.
+    fn focus_delegate(&self) -> Option<DomRoot<Element>> {
+        let focusable_descendants = || {
+            self.upcast::<Node>()
+                .traverse_preorder(ShadowIncluding::No)
+                .skip(1)
+                .filter_map(DomRoot::downcast::<Element>)
+                .filter(|element| element.is_focusable_area())
+        };
+        focusable_descendants()
+            .find(|element| element.has_attribute(&local_name!("autofocus")))
+            .or_else(|| focusable_descendants().next())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#close-the-dialog>
+    fn close_the_dialog(&self, result: Option<DOMString>, can_gc: CanGc) {
+        let element = self.upcast::<Element>();
+        let target = self.upcast::<EventTarget>();
+
+        // Step 1. If subject does not have an open attribute, then return.
+        if !element.has_attribute(&local_name!("open")) {
+            return;
+        }
+
+        // Step 4. Let wasModal be the value of subject's is modal flag.
+        let was_modal = self.is_modal();
+
+        // Step 3. Remove subject's open attribute.
+        // Step 5. Set the is modal flag of subject to false.
+        element.remove_attribute(&ns!(), &local_name!("open"), can_gc);
+        self.set_is_modal(false);
+
+        // Step 6. If result is not null, then set the returnValue attribute to result.
+        if let Some(new_value) = result {
+            *self.return_value.borrow_mut() = new_value;
+        }
+
+        // Step 8. If subject's node document's top layer contains subject, then remove an
+        // element from the top layer immediately given subject.
+        self.remove_from_top_layer();
+
+        // Step 10. If subject's previously focused element is not null, then:
+        // Step 10.1. Let element be subject's previously focused element.
+        // Step 10.2. Set subject's previously focused element to null.
+        // Step 10.3. If wasModal is true, run the focusing steps given element.
+        if let Some(previously_focused) = self.previously_focused_element.get() {
+            self.previously_focused_element.set(None);
+            if was_modal {
+                self.owner_document().request_focus(
+                    Some(&previously_focused),
+                    FocusInitiator::Local,
+                    can_gc,
+                );
+            }
+        }
+
+        // Step 11. Queue an element task on the user interaction task source given the subject
+        // element to fire an event named close at subject.
+        self.owner_global()
+            .task_manager()
+            .user_interaction_task_source()
+            .queue_simple_event(target, atom!("close"));
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dialog-request-close>
+    ///
+    /// Run when the user presses Escape while this is the topmost modal dialog: fire a
+    /// cancelable cancel event and close the dialog unless it was canceled.
+    pub(crate) fn request_close(&self, can_gc: CanGc) {
+        if !self.upcast::<Element>().has_attribute(&local_name!("open")) {
+            return;
+        }
+
+        let event = Event::new(
+            &self.owner_global(),
+            atom!("cancel"),
+            EventBubbles::DoesNotBubble,
+            EventCancelable::Cancelable,
+            can_gc,
+        );
+        if !event.fire(self.upcast(), can_gc) {
+            return;
+        }
+
+        self.close_the_dialog(None, can_gc);
+    }
 }
 
 impl HTMLDialogElementMethods<crate::DomTypeHolder> for HTMLDialogElement {
@@ -74,54 +225,131 @@ impl HTMLDialogElementMethods<crate::DomTypeHolder> for HTMLDialogElement {
     }
 
     /// <https://html.spec.whatwg.org/multipage/#dom-dialog-show>
-    fn Show(&self, can_gc: CanGc) {
+    fn Show(&self, can_gc: CanGc) -> ErrorResult {
         let element = self.upcast::<Element>();
 
-        // Step 1 TODO: Check is modal flag is false
+        // Step 1. If this has an open attribute and the is modal flag of this is false, then return.
         if element.has_attribute(&local_name!("open")) {
-            return;
+            if !self.is_modal() {
+                return Ok(());
+            }
+
+            // Step 2. If this has an open attribute, then throw an "InvalidStateError" DOMException.
+            return Err(Error::InvalidState);
+        }
+
+        // Step 3. Add an open attribute to this, whose value is the empty string.
+        element.set_bool_attribute(&local_name!("open"), true, can_gc);
+
+        // Step 4. Set this's previously focused element to the focused element.
+        self.previously_focused_element
+            .set(self.owner_document().get_focused_element().as_deref());
+
+        // Step 5. Let hideUntil be the result of running topmost popover ancestor given this,
+        // null, and false.
+        // Step 6. If hideUntil is null, then set hideUntil to this's node document.
+        // Step 7. Run hide all popovers until given hideUntil, false, and true.
+        let hide_until = HTMLElement::topmost_popover_ancestor(element, None);
+        self.owner_document()
+            .hide_all_popovers_until(hide_until.as_deref(), false, true, can_gc);
+
+        // Step 8. Run the dialog focusing steps given this.
+        self.run_dialog_focusing_steps(can_gc);
+        Ok(())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-dialog-showmodal>
+    fn ShowModal(&self, can_gc: CanGc) -> ErrorResult {
+        let element = self.upcast::<Element>();
+
+        // Step 1. If this has an open attribute and the is modal flag of this is true, then return.
+        if element.has_attribute(&local_name!("open")) {
+            if self.is_modal() {
+                return Ok(());
+            }
+
+            // Step 2. If this has an open attribute, then throw an "InvalidStateError" DOMException.
+            return Err(Error::InvalidState);
         }
 
-        // TODO: Step 2 If this has an open attribute, then throw an "InvalidStateError" DOMException.
+        // Step 4. If this is not connected, then throw an "InvalidStateError" DOMException.
+        if !self.upcast::<Node>().is_connected() {
+            return Err(Error::InvalidState);
+        }
+
+        // Step 5. If this is in the popover showing state, then throw an "InvalidStateError"
+        // DOMException.
+        if self.upcast::<HTMLElement>().is_popover_showing() {
+            return Err(Error::InvalidState);
+        }
 
-        // Step 3
+        // Step 7. Add an open attribute to this, whose value is the empty string.
         element.set_bool_attribute(&local_name!("open"), true, can_gc);
 
-        // TODO: Step 4 Set this's previously focused element to the focused element.
+        // Step 8. Set the is modal flag of this to true.
+        self.set_is_modal(true);
 
-        // TODO: Step 5 Let hideUntil be the result of running topmost popover ancestor given this, null, and false.
+        // Step 9. Let this's node document be blocked by the modal dialog this.
+        // Step 10. If this's node document's top layer does not already contain this, then add
+        // an element to the top layer given this.
+        //
+        // The document is blocked by the topmost modal dialog in its top layer, see
+        // `Document::active_modal_dialog`.
+        let document = self.owner_document();
+        if !document.top_layer_contains(element) {
+            document.add_to_top_layer(element);
+        }
 
-        // TODO: Step 6 If hideUntil is null, then set hideUntil to this's node document.
+        // Step 12. Set this's previously focused element to the focused element.
+        self.previously_focused_element
+            .set(document.get_focused_element().as_deref());
 
-        // TODO: Step 7 Run hide all popovers until given hideUntil, false, and true.
+        // Step 13. Let hideUntil be the result of running topmost popover ancestor given this,
+        // document's showing auto popover list, null, and false.
+        // Step 14. If hideUntil is null, then set hideUntil to this's node document.
+        // Step 15. Run hide all popovers until given hideUntil, false, and true.
+        let hide_until = HTMLElement::topmost_popover_ancestor(element, None);
+        document.hide_all_popovers_until(hide_until.as_deref(), false, true, can_gc);
 
-        // TODO(Issue #32702): Step 8 Run the dialog focusing steps given this.
+        // Step 16. Run the dialog focusing steps given this.
+        self.run_dialog_focusing_steps(can_gc);
+        Ok(())
     }
 
-    // https://html.spec.whatwg.org/multipage/#dom-dialog-close
+    /// <https://html.spec.whatwg.org/multipage/#dom-dialog-close>
     fn Close(&self, return_value: Option<DOMString>, can_gc: CanGc) {
-        let element = self.upcast::<Element>();
-        let target = self.upcast::<EventTarget>();
+        // Step 1. If returnValue is not given, then set it to null.
+        // Step 2. Close the dialog this with returnValue.
+        self.close_the_dialog(return_value, can_gc);
+    }
+}
 
-        // Step 1 & 2
-        if element
-            .remove_attribute(&ns!(), &local_name!("open"), can_gc)
-            .is_none()
-        {
-            return;
-        }
+impl VirtualMethods for HTMLDialogElement {
+    fn super_type(&self) -> Option<&dyn VirtualMethods> {
+        Some(self.upcast::<HTMLElement>() as &dyn VirtualMethods)
+    }
 
-        // Step 3
-        if let Some(new_value) = return_value {
-            *self.return_value.borrow_mut() = new_value;
+    fn attribute_mutated(&self, attr: &Attr, mutation: AttributeMutation, can_gc: CanGc) {
+        self.super_type()
+            .unwrap()
+            .attribute_mutated(attr, mutation, can_gc);
+
+        // A modal dialog whose open attribute is removed directly is no longer modal.
+        if *attr.local_name() == local_name!("open") &&
+            matches!(mutation, AttributeMutation::Removed) &&
+            self.is_modal()
+        {
+            self.remove_from_top_layer();
         }
+    }
 
-        // TODO: Step 4 implement pending dialog stack removal
+    /// <https://html.spec.whatwg.org/multipage/#the-dialog-element:html-element-removing-steps>
+    fn unbind_from_tree(&self, context: &UnbindContext, can_gc: CanGc) {
+        self.super_type().unwrap().unbind_from_tree(context, can_gc);
 
-        // Step 5
-        self.owner_global()
-            .task_manager()
-            .dom_manipulation_task_source()
-            .queue_simple_event(target, atom!("close"));
+        // Step 2. If removedNode's node document's top layer contains removedNode, then remove
+        // an element from the top layer immediately given removedNode.
+        // Step 3. Set is modal of removedNode to false.
+        self.remove_from_top_layer();
     }
 }
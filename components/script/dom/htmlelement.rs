@@ -2,6 +2,7 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use std::cell::Cell;
 use std::collections::HashSet;
 use std::default::Default;
 use std::rc::Rc;
@@ -10,7 +11,9 @@ use dom_struct::dom_struct;
 use html5ever::{LocalName, Prefix, local_name, ns};
 use js::rust::HandleObject;
 use layout_api::QueryMsg;
+use servo_config::pref;
 use style::attr::AttrValue;
+use stylo_atoms::Atom;
 use stylo_dom::ElementState;
 
 use super::customelementregistry::CustomElementState;
@@ -20,13 +23,18 @@ use crate::dom::bindings::codegen::Bindings::CharacterDataBinding::CharacterData
 use crate::dom::bindings::codegen::Bindings::EventHandlerBinding::{
     EventHandlerNonNull, OnErrorEventHandlerNonNull,
 };
-use crate::dom::bindings::codegen::Bindings::HTMLElementBinding::HTMLElementMethods;
+use crate::dom::bindings::codegen::Bindings::HTMLElementBinding::{
+    HTMLElementMethods, ShowPopoverOptions,
+};
 use crate::dom::bindings::codegen::Bindings::HTMLLabelElementBinding::HTMLLabelElementMethods;
+use crate::dom::bindings::codegen::Bindings::NodeBinding::GetRootNodeOptions;
 use crate::dom::bindings::codegen::Bindings::NodeBinding::Node_Binding::NodeMethods;
 use crate::dom::bindings::codegen::Bindings::ShadowRootBinding::ShadowRoot_Binding::ShadowRootMethods;
 use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::codegen::UnionTypes::TogglePopoverOptionsOrBoolean;
 use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
 use crate::dom::bindings::inheritance::{Castable, ElementTypeId, HTMLElementTypeId, NodeTypeId};
+use crate::dom::bindings::refcounted::Trusted;
 use crate::dom::bindings::root::{Dom, DomRoot, MutNullableDom};
 use crate::dom::bindings::str::DOMString;
 use crate::dom::characterdata::CharacterData;
@@ -37,11 +45,13 @@ use crate::dom::documentfragment::DocumentFragment;
 use crate::dom::domstringmap::DOMStringMap;
 use crate::dom::element::{AttributeMutation, Element};
 use crate::dom::elementinternals::ElementInternals;
-use crate::dom::event::Event;
+use crate::dom::event::{Event, EventBubbles, EventCancelable};
 use crate::dom::eventtarget::EventTarget;
 use crate::dom::htmlbodyelement::HTMLBodyElement;
 use crate::dom::htmlbrelement::HTMLBRElement;
+use crate::dom::htmlbuttonelement::HTMLButtonElement;
 use crate::dom::htmldetailselement::HTMLDetailsElement;
+use crate::dom::htmldialogelement::HTMLDialogElement;
 use crate::dom::htmlformelement::{FormControl, HTMLFormElement};
 use crate::dom::htmlframesetelement::HTMLFrameSetElement;
 use crate::dom::htmlhtmlelement::HTMLHtmlElement;
@@ -51,15 +61,56 @@ use crate::dom::htmltextareaelement::HTMLTextAreaElement;
 use crate::dom::node::{BindContext, Node, NodeTraits, ShadowIncluding, UnbindContext};
 use crate::dom::shadowroot::ShadowRoot;
 use crate::dom::text::Text;
+use crate::dom::toggleevent::ToggleEvent;
 use crate::dom::virtualmethods::VirtualMethods;
 use crate::script_runtime::CanGc;
 use crate::script_thread::ScriptThread;
 
+/// The states of the `contenteditable` content attribute.
+///
+/// <https://html.spec.whatwg.org/multipage/#attr-contenteditable>
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ContentEditableState {
+    True,
+    False,
+    PlaintextOnly,
+    Inherit,
+}
+
+/// The states of the `popover` content attribute, other than the no popover state.
+///
+/// <https://html.spec.whatwg.org/multipage/#attr-popover>
+#[derive(Clone, Copy, Debug, JSTraceable, MallocSizeOf, PartialEq)]
+pub(crate) enum PopoverState {
+    Auto,
+    Manual,
+}
+
+impl PopoverState {
+    fn from_value(value: &str) -> PopoverState {
+        if value.is_empty() || value.eq_ignore_ascii_case("auto") {
+            PopoverState::Auto
+        } else {
+            PopoverState::Manual
+        }
+    }
+}
+
 #[dom_struct]
 pub(crate) struct HTMLElement {
     element: Element,
     style_decl: MutNullableDom<CSSStyleDeclaration>,
     dataset: MutNullableDom<DOMStringMap>,
+    /// <https://html.spec.whatwg.org/multipage/#popover-invoker>
+    ///
+    /// This is also the implicit anchor element of the popover.
+    popover_invoker: MutNullableDom<HTMLElement>,
+    /// <https://html.spec.whatwg.org/multipage/#popover-showing-or-hiding>
+    popover_showing_or_hiding: Cell<bool>,
+    /// <https://html.spec.whatwg.org/multipage/#opened-in-popover-mode>
+    opened_in_popover_mode: Cell<Option<PopoverState>>,
+    /// <https://html.spec.whatwg.org/multipage/#popover-previously-focused-element>
+    popover_previously_focused_element: MutNullableDom<Element>,
 }
 
 impl HTMLElement {
@@ -87,6 +138,10 @@ impl HTMLElement {
             ),
             style_decl: Default::default(),
             dataset: Default::default(),
+            popover_invoker: Default::default(),
+            popover_showing_or_hiding: Cell::new(false),
+            opened_in_popover_mode: Cell::new(None),
+            popover_previously_focused_element: Default::default(),
         }
     }
 
@@ -188,6 +243,11 @@ impl HTMLElementMethods<crate::DomTypeHolder> for HTMLElement {
     // https://html.spec.whatwg.org/multipage/#dom-hidden
     make_bool_setter!(SetHidden, "hidden");
 
+    // https://html.spec.whatwg.org/multipage/#dom-inert
+    make_bool_getter!(Inert, "inert");
+    // https://html.spec.whatwg.org/multipage/#dom-inert
+    make_bool_setter!(SetInert, "inert");
+
     // https://html.spec.whatwg.org/multipage/#globaleventhandlers
     global_event_handlers!(NoOnload);
 
@@ -563,6 +623,20 @@ impl HTMLElementMethods<crate::DomTypeHolder> for HTMLElement {
         self.as_element().is_translate_enabled()
     }
 
+    /// <https://html.spec.whatwg.org/multipage/#dom-draggable>
+    fn Draggable(&self) -> bool {
+        self.is_draggable()
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-draggable>
+    fn SetDraggable(&self, value: bool, can_gc: CanGc) {
+        self.as_element().set_string_attribute(
+            &local_name!("draggable"),
+            DOMString::from(if value { "true" } else { "false" }),
+            can_gc,
+        );
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-translate
     fn SetTranslate(&self, yesno: bool, can_gc: CanGc) {
         self.as_element().set_string_attribute(
@@ -577,24 +651,40 @@ impl HTMLElementMethods<crate::DomTypeHolder> for HTMLElement {
 
     // https://html.spec.whatwg.org/multipage/#dom-contenteditable
     fn ContentEditable(&self) -> DOMString {
-        // TODO: https://github.com/servo/servo/issues/12776
-        self.as_element()
-            .get_attribute(&ns!(), &local_name!("contenteditable"))
-            .map(|attr| DOMString::from(&**attr.value()))
-            .unwrap_or_else(|| DOMString::from("inherit"))
+        DOMString::from(match self.content_editable_state() {
+            ContentEditableState::True => "true",
+            ContentEditableState::False => "false",
+            ContentEditableState::PlaintextOnly => "plaintext-only",
+            ContentEditableState::Inherit => "inherit",
+        })
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-contenteditable
-    fn SetContentEditable(&self, _: DOMString) {
-        // TODO: https://github.com/servo/servo/issues/12776
-        warn!("The contentEditable attribute is not implemented yet");
+    fn SetContentEditable(&self, value: DOMString, can_gc: CanGc) -> ErrorResult {
+        let element = self.as_element();
+        let value = value.to_ascii_lowercase();
+        match &*value {
+            "inherit" => {
+                element.remove_attribute(&ns!(), &local_name!("contenteditable"), can_gc);
+            },
+            "true" | "false" | "plaintext-only" => {
+                element.set_string_attribute(
+                    &local_name!("contenteditable"),
+                    DOMString::from(value),
+                    can_gc,
+                );
+            },
+            _ => return Err(Error::Syntax),
+        }
+        Ok(())
     }
 
-    // https://html.spec.whatwg.org/multipage/#dom-contenteditable
+    // https://html.spec.whatwg.org/multipage/#dom-iscontenteditable
     fn IsContentEditable(&self) -> bool {
-        // TODO: https://github.com/servo/servo/issues/12776
-        false
+        let node = self.upcast::<Node>();
+        node.is_editing_host() || node.is_editable()
     }
+
     /// <https://html.spec.whatwg.org/multipage#dom-attachinternals>
     fn AttachInternals(&self, can_gc: CanGc) -> Fallible<DomRoot<ElementInternals>> {
         let element = self.as_element();
@@ -645,6 +735,79 @@ impl HTMLElementMethods<crate::DomTypeHolder> for HTMLElement {
         Ok(internals)
     }
 
+    /// <https://html.spec.whatwg.org/multipage/#dom-showpopover>
+    fn ShowPopover(&self, options: &ShowPopoverOptions, can_gc: CanGc) -> ErrorResult {
+        // Step 1. Let source be options["source"] if it exists; otherwise, null.
+        // Step 2. Run show popover given this, true, and source.
+        self.show_popover(options.source.as_deref(), can_gc)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-hidepopover>
+    fn HidePopover(&self, can_gc: CanGc) -> ErrorResult {
+        // Run the hide popover algorithm given this, true, true, true, and false.
+        self.hide_popover(true, true, false, can_gc)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-togglepopover>
+    fn TogglePopover(
+        &self,
+        options: TogglePopoverOptionsOrBoolean,
+        can_gc: CanGc,
+    ) -> Fallible<bool> {
+        // Step 1. Let force be null.
+        // Step 2. If options is a boolean, set force to options.
+        // Step 3. Otherwise, if options["force"] exists, set force to options["force"].
+        // Step 4. Let source be options["source"] if it exists; otherwise, null.
+        let (force, source) = match options {
+            TogglePopoverOptionsOrBoolean::Boolean(force) => (Some(force), None),
+            TogglePopoverOptionsOrBoolean::TogglePopoverOptions(options) => {
+                (options.force, options.parent.source)
+            },
+        };
+
+        // Step 5. If this's popover visibility state is showing, and force is null or false,
+        // then run the hide popover algorithm given this, true, true, true, and false.
+        if self.is_popover_showing() && force != Some(true) {
+            self.hide_popover(true, true, false, can_gc)?;
+        }
+        // Step 6. Otherwise, if force is null or true, then run show popover given this,
+        // true, and source.
+        else if force != Some(false) {
+            self.show_popover(source.as_deref(), can_gc)?;
+        }
+        // Step 7. Otherwise:
+        // Step 7.1. Let expectedToBeShowing be true if this's popover visibility state is
+        // showing; otherwise false.
+        // Step 7.2. Run check popover validity given expectedToBeShowing, true, and null.
+        else {
+            self.check_popover_validity(self.is_popover_showing(), None, false)?;
+        }
+
+        // Step 8. Return true if this's popover visibility state is showing; otherwise false.
+        Ok(self.is_popover_showing())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-popover>
+    fn GetPopover(&self) -> Option<DOMString> {
+        // The popover IDL attribute reflects the popover content attribute, limited to only
+        // known values.
+        self.popover_state().map(|state| match state {
+            PopoverState::Auto => DOMString::from("auto"),
+            PopoverState::Manual => DOMString::from("manual"),
+        })
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-popover>
+    fn SetPopover(&self, value: Option<DOMString>, can_gc: CanGc) {
+        let element = self.as_element();
+        match value {
+            Some(value) => element.set_string_attribute(&local_name!("popover"), value, can_gc),
+            None => {
+                element.remove_attribute(&ns!(), &local_name!("popover"), can_gc);
+            },
+        }
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-noncedelement-nonce
     fn Nonce(&self) -> DOMString {
         self.as_element().nonce_value().into()
@@ -737,6 +900,52 @@ fn to_camel_case(name: &str) -> Option<DOMString> {
 }
 
 impl HTMLElement {
+    /// Whether this element can be dragged by the user, as determined by its `draggable`
+    /// content attribute. Images and links are draggable by default.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/#the-draggable-attribute>
+    pub(crate) fn is_draggable(&self) -> bool {
+        let element = self.as_element();
+        let draggable = element.get_string_attribute(&local_name!("draggable"));
+        if draggable.eq_ignore_ascii_case("true") {
+            return true;
+        }
+        if draggable.eq_ignore_ascii_case("false") {
+            return false;
+        }
+        match self.upcast::<Node>().type_id() {
+            NodeTypeId::Element(ElementTypeId::HTMLElement(
+                HTMLElementTypeId::HTMLImageElement,
+            )) => true,
+            NodeTypeId::Element(ElementTypeId::HTMLElement(
+                HTMLElementTypeId::HTMLAnchorElement,
+            )) => element.has_attribute(&local_name!("href")),
+            _ => false,
+        }
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#attr-contenteditable>
+    pub(crate) fn content_editable_state(&self) -> ContentEditableState {
+        let Some(attr) = self
+            .as_element()
+            .get_attribute(&ns!(), &local_name!("contenteditable"))
+        else {
+            // The missing value default is the inherit state.
+            return ContentEditableState::Inherit;
+        };
+        let value = attr.value();
+        if value.is_empty() || value.eq_ignore_ascii_case("true") {
+            ContentEditableState::True
+        } else if value.eq_ignore_ascii_case("false") {
+            ContentEditableState::False
+        } else if value.eq_ignore_ascii_case("plaintext-only") {
+            ContentEditableState::PlaintextOnly
+        } else {
+            // The invalid value default is also the inherit state.
+            ContentEditableState::Inherit
+        }
+    }
+
     pub(crate) fn set_custom_attr(
         &self,
         name: DOMString,
@@ -1091,6 +1300,670 @@ impl HTMLElement {
     }
 }
 
+impl HTMLElement {
+    /// The state of the `popover` content attribute, or `None` when it is in the no popover
+    /// state. Invalid values map to the manual state. The attribute has no effect unless
+    /// popovers are enabled.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/#attr-popover>
+    pub(crate) fn popover_state(&self) -> Option<PopoverState> {
+        if !pref!(dom_popover_enabled) {
+            return None;
+        }
+        let attr = self
+            .as_element()
+            .get_attribute(&ns!(), &local_name!("popover"))?;
+        let value = attr.value();
+        Some(PopoverState::from_value(&value))
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#popover-visibility-state>
+    ///
+    /// A popover is in the showing state exactly when it matches `:popover-open`.
+    pub(crate) fn is_popover_showing(&self) -> bool {
+        self.as_element()
+            .state()
+            .contains(ElementState::POPOVER_OPEN)
+    }
+
+    fn set_popover_showing(&self, showing: bool) {
+        self.as_element()
+            .set_state(ElementState::POPOVER_OPEN, showing);
+    }
+
+    /// Whether this element belongs in its document's
+    /// [showing auto popover list](https://html.spec.whatwg.org/multipage/#showing-auto-popover-list).
+    pub(crate) fn is_showing_auto_popover(&self) -> bool {
+        self.is_popover_showing() && self.opened_in_popover_mode.get() == Some(PopoverState::Auto)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#check-popover-validity>
+    ///
+    /// Returns `Ok(false)` when the popover is not in the expected state, and the exception
+    /// to throw when it cannot be shown or hidden at all. Callers that do not throw
+    /// exceptions treat an error like `false`.
+    fn check_popover_validity(
+        &self,
+        expected_to_be_showing: bool,
+        expected_document: Option<&Document>,
+        ignore_dom_state: bool,
+    ) -> Fallible<bool> {
+        // Step 1. If ignoreDomState is false and element's popover attribute is in the no
+        // popover state, then throw a "NotSupportedError" DOMException.
+        if !ignore_dom_state && self.popover_state().is_none() {
+            return Err(Error::NotSupported);
+        }
+
+        // Step 2. If expectedToBeShowing is true and element's popover visibility state is not
+        // showing, or expectedToBeShowing is false and element's popover visibility state is
+        // not hidden, then return false.
+        if expected_to_be_showing != self.is_popover_showing() {
+            return Ok(false);
+        }
+
+        // Step 3. If any of the following are true, then throw an "InvalidStateError"
+        // DOMException:
+        // - ignoreDomState is false and element is not connected;
+        // - ignoreDomState is false and expectedDocument is not null and element's node
+        //   document is not expectedDocument;
+        // - element is a dialog element and its is modal flag is set to true;
+        // - element's fullscreen flag is set.
+        let element = self.as_element();
+        if (!ignore_dom_state &&
+            (!element.is_connected() ||
+                expected_document
+                    .is_some_and(|document| *document != *self.owner_document()))) ||
+            self.downcast::<HTMLDialogElement>()
+                .is_some_and(|dialog| dialog.is_modal()) ||
+            element.state().contains(ElementState::FULLSCREEN)
+        {
+            return Err(Error::InvalidState);
+        }
+
+        // Step 4. Return true.
+        Ok(true)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#show-popover>
+    ///
+    /// The error is only thrown when this is called from script.
+    pub(crate) fn show_popover(&self, source: Option<&HTMLElement>, can_gc: CanGc) -> ErrorResult {
+        // Step 1. If the result of running check popover validity given element, false,
+        // throwExceptions, and null is false, then return.
+        if !self.check_popover_validity(false, None, false)? {
+            return Ok(());
+        }
+
+        // Step 2. Let document be element's node document.
+        let document = self.owner_document();
+
+        // Step 3. Let nestedShow be element's popover showing or hiding.
+        // Step 4. Let fireEvents be the boolean negation of nestedShow.
+        // Step 5. Set element's popover showing or hiding to true.
+        let nested_show = self.popover_showing_or_hiding.get();
+        self.popover_showing_or_hiding.set(true);
+
+        let result = self.show_popover_steps(&document, source, !nested_show, can_gc);
+
+        // Step 6. Let cleanupShowingFlag be the following steps: If nestedShow is false, then
+        // set element's popover showing or hiding to false.
+        if !nested_show {
+            self.popover_showing_or_hiding.set(false);
+        }
+        result
+    }
+
+    /// The steps of <https://html.spec.whatwg.org/multipage/#show-popover> that run before
+    /// cleanupShowingFlag.
+    fn show_popover_steps(
+        &self,
+        document: &Document,
+        source: Option<&HTMLElement>,
+        fire_events: bool,
+        can_gc: CanGc,
+    ) -> ErrorResult {
+        // Step 7. If the result of firing an event named beforetoggle, using ToggleEvent, with
+        // the cancelable attribute initialized to true, the oldState attribute initialized to
+        // "closed", and the newState attribute initialized to "open" at element is false, then
+        // run cleanupShowingFlag and return.
+        if !self.fire_beforetoggle_event(true, can_gc) {
+            return Ok(());
+        }
+
+        // Step 8. If the result of running check popover validity given element, false,
+        // throwExceptions, and document is false, then run cleanupShowingFlag and return.
+        if !self.check_popover_validity(false, Some(document), false)? {
+            return Ok(());
+        }
+
+        // Step 10. Let originalType be the current state of element's popover attribute.
+        let original_type = self.popover_state();
+
+        // Step 12. If originalType is the auto state, then:
+        if original_type == Some(PopoverState::Auto) {
+            // Step 12.2. Let ancestor be the result of running the topmost popover ancestor
+            // algorithm given element, document's showing auto popover list, source, and true.
+            // Step 12.3. If ancestor is null, then set ancestor to document.
+            let ancestor = HTMLElement::topmost_popover_ancestor(self.as_element(), source);
+
+            // Step 12.4. Run hide all popovers until given ancestor, false, and not nestedShow.
+            document.hide_all_popovers_until(ancestor.as_deref(), false, fire_events, can_gc);
+        }
+
+        // Step 15. If originalType is not equal to the value of element's popover attribute,
+        // then throw an "InvalidStateError" DOMException.
+        if original_type != self.popover_state() {
+            return Err(Error::InvalidState);
+        }
+
+        // Step 16. If the result of running check popover validity given element, false,
+        // throwExceptions, and document is false, then run cleanupShowingFlag and return.
+        if !self.check_popover_validity(false, Some(document), false)? {
+            return Ok(());
+        }
+
+        // Step 9. Let shouldRestoreFocus be false.
+        // Step 17. If the result of running topmost auto or hint popover on document is null,
+        // then set shouldRestoreFocus to true.
+        let should_restore_focus = document.topmost_auto_popover().is_none();
+
+        // Step 18. Set element's opened in popover mode to the popover attribute state.
+        self.opened_in_popover_mode.set(original_type);
+
+        // Step 19. Set element's popover invoker to source.
+        self.popover_invoker.set(source);
+
+        // Step 21. Set element's previously focused element to null.
+        // Step 22. Let originallyFocusedElement be document's focused area of the document's
+        // DOM anchor.
+        self.popover_previously_focused_element.set(None);
+        let originally_focused_element = document.get_focused_element();
+
+        // Step 23. Add an element to the top layer given element.
+        document.add_to_top_layer(self.as_element());
+
+        // Step 24. Set element's popover visibility state to showing.
+        self.set_popover_showing(true);
+
+        // Step 25. Run the popover focusing steps given element.
+        self.run_popover_focusing_steps(can_gc);
+
+        // Step 26. If shouldRestoreFocus is true and element's popover attribute is not in the
+        // no popover state, then set element's previously focused element to
+        // originallyFocusedElement.
+        if should_restore_focus && self.popover_state().is_some() {
+            self.popover_previously_focused_element
+                .set(originally_focused_element.as_deref());
+        }
+
+        // Step 27. Queue a popover toggle event task given element, "closed", and "open".
+        self.queue_popover_toggle_event_task("closed", "open");
+        Ok(())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#hide-popover-algorithm>
+    ///
+    /// The error is only thrown when this is called from script.
+    pub(crate) fn hide_popover(
+        &self,
+        focus_previous_element: bool,
+        fire_events: bool,
+        ignore_dom_state: bool,
+        can_gc: CanGc,
+    ) -> ErrorResult {
+        // Step 1. If the result of running check popover validity given element, true,
+        // throwExceptions, null, and ignoreDomState is false, then return.
+        if !self.check_popover_validity(true, None, ignore_dom_state)? {
+            return Ok(());
+        }
+
+        // Step 2. Let document be element's node document.
+        let document = self.owner_document();
+
+        // Step 3. Let nestedHide be element's popover showing or hiding.
+        // Step 4. Set element's popover showing or hiding to true.
+        // Step 5. If nestedHide is true, then set fireEvents to false.
+        let nested_hide = self.popover_showing_or_hiding.get();
+        self.popover_showing_or_hiding.set(true);
+
+        let result = self.hide_popover_steps(
+            &document,
+            focus_previous_element,
+            fire_events && !nested_hide,
+            ignore_dom_state,
+            can_gc,
+        );
+
+        // Step 6. Let cleanupSteps be the following steps: If nestedHide is false, then set
+        // element's popover showing or hiding to false.
+        if !nested_hide {
+            self.popover_showing_or_hiding.set(false);
+        }
+        result
+    }
+
+    /// The steps of <https://html.spec.whatwg.org/multipage/#hide-popover-algorithm> that
+    /// run before cleanupSteps.
+    fn hide_popover_steps(
+        &self,
+        document: &Document,
+        focus_previous_element: bool,
+        fire_events: bool,
+        ignore_dom_state: bool,
+        can_gc: CanGc,
+    ) -> ErrorResult {
+        // Step 7. If element's opened in popover mode is "auto", then:
+        if self.opened_in_popover_mode.get() == Some(PopoverState::Auto) {
+            // Step 7.1. Run hide all popovers until given element, focusPreviousElement, and
+            // fireEvents.
+            document.hide_all_popovers_until(
+                Some(self),
+                focus_previous_element,
+                fire_events,
+                can_gc,
+            );
+
+            // Step 7.2. If the result of running check popover validity given element, true,
+            // throwExceptions, and ignoreDomState is false, then run cleanupSteps and return.
+            if !self.check_popover_validity(true, None, ignore_dom_state)? {
+                return Ok(());
+            }
+        }
+
+        // Step 8. Let autoPopoverListContainsElement be true if document's showing auto
+        // popover list's last item is element, otherwise false.
+        let auto_popover_list_contains_element =
+            document.topmost_auto_popover().as_deref() == Some(self);
+
+        // Step 9. Set element's popover invoker to null.
+        self.popover_invoker.set(None);
+
+        // Step 10. If fireEvents is true, then:
+        if fire_events {
+            // Step 10.1. Fire an event named beforetoggle, using ToggleEvent, with the oldState
+            // attribute initialized to "open" and the newState attribute initialized to
+            // "closed" at element.
+            self.fire_beforetoggle_event(false, can_gc);
+
+            // Step 10.2. If autoPopoverListContainsElement is true and document's showing auto
+            // popover list's last item is not element, then run hide all popovers until given
+            // element, focusPreviousElement, and false.
+            if auto_popover_list_contains_element &&
+                document.topmost_auto_popover().as_deref() != Some(self)
+            {
+                document.hide_all_popovers_until(Some(self), focus_previous_element, false, can_gc);
+            }
+
+            // Step 10.3. If the result of running check popover validity given element, true,
+            // throwExceptions, null, and ignoreDomState is false, then run cleanupSteps and
+            // return.
+            if !self.check_popover_validity(true, None, ignore_dom_state)? {
+                return Ok(());
+            }
+        }
+
+        // Step 10.4. Request an element to be removed from the top layer given element.
+        // Step 11. Otherwise, remove an element from the top layer immediately given element.
+        //
+        // Nothing animates the overlay property, so the element always leaves the top layer
+        // immediately.
+        document.remove_from_top_layer(self.as_element());
+
+        // Step 12. Set element's opened in popover mode to null.
+        // Step 13. Set element's popover visibility state to hidden.
+        self.opened_in_popover_mode.set(None);
+        self.set_popover_showing(false);
+
+        // Step 14. If fireEvents is true, then queue a popover toggle event task given element,
+        // "open", and "closed".
+        if fire_events {
+            self.queue_popover_toggle_event_task("open", "closed");
+        }
+
+        // Step 15. Let previouslyFocusedElement be element's previously focused element.
+        // Step 16. If previouslyFocusedElement is not null, then:
+        if let Some(previously_focused) = self.popover_previously_focused_element.get() {
+            // Step 16.1. Set element's previously focused element to null.
+            self.popover_previously_focused_element.set(None);
+
+            // Step 16.2. If focusPreviousElement is true and document's focused area of the
+            // document's DOM anchor is a shadow-including inclusive descendant of element, then
+            // run the focusing steps for previouslyFocusedElement.
+            if focus_previous_element &&
+                document.get_focused_element().is_some_and(|focused| {
+                    self.upcast::<Node>()
+                        .is_shadow_including_inclusive_ancestor_of(focused.upcast())
+                })
+            {
+                document.request_focus(Some(&previously_focused), FocusInitiator::Local, can_gc);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fire a `beforetoggle` event at this popover, which can only be canceled when the popover
+    /// is about to be shown. Returns false if the event was canceled.
+    fn fire_beforetoggle_event(&self, opening: bool, can_gc: CanGc) -> bool {
+        let (old_state, new_state) = if opening {
+            ("closed", "open")
+        } else {
+            ("open", "closed")
+        };
+        let event = ToggleEvent::new(
+            &self.owner_window(),
+            Atom::from("beforetoggle"),
+            EventBubbles::DoesNotBubble,
+            EventCancelable::from(opening),
+            DOMString::from(old_state),
+            DOMString::from(new_state),
+            can_gc,
+        );
+        event.upcast::<Event>().fire(self.upcast(), can_gc)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#queue-a-popover-toggle-event-task>
+    fn queue_popover_toggle_event_task(&self, old_state: &'static str, new_state: &'static str) {
+        // TODO: Coalesce this with a toggle event task that is still pending, using the popover
+        // toggle task tracker.
+        let this = Trusted::new(self);
+        self.owner_global()
+            .task_manager()
+            .dom_manipulation_task_source()
+            .queue(task!(popover_toggle_event: move || {
+                let this = this.root();
+                let event = ToggleEvent::new(
+                    &this.owner_window(),
+                    atom!("toggle"),
+                    EventBubbles::DoesNotBubble,
+                    EventCancelable::NotCancelable,
+                    DOMString::from(old_state),
+                    DOMString::from(new_state),
+                    CanGc::note(),
+                );
+                event.upcast::<Event>().fire(this.upcast(), CanGc::note());
+            }));
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#popover-focusing-steps>
+    fn run_popover_focusing_steps(&self, can_gc: CanGc) {
+        // Step 1. If subject is a dialog element, then run the dialog focusing steps given
+        // subject and return.
+        if let Some(dialog) = self.downcast::<HTMLDialogElement>() {
+            dialog.run_dialog_focusing_steps(can_gc);
+            return;
+        }
+
+        // Step 2. If subject has the autofocus attribute, then let control be subject.
+        // Step 3. Otherwise, let control be the autofocus delegate for subject.
+        let element = self.as_element();
+        let control = if element.has_attribute(&local_name!("autofocus")) {
+            Some(DomRoot::from_ref(element))
+        } else {
+            self.upcast::<Node>()
+                .traverse_preorder(ShadowIncluding::No)
+                .skip(1)
+                .filter_map(DomRoot::downcast::<Element>)
+                .find(|element| {
+                    element.has_attribute(&local_name!("autofocus")) && element.is_focusable_area()
+                })
+        };
+
+        // Step 4. If control is null, then return.
+        // Step 5. Run the focusing steps given control.
+        if let Some(control) = control {
+            self.owner_document()
+                .request_focus(Some(&control), FocusInitiator::Local, can_gc);
+        }
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#topmost-popover-ancestor>
+    ///
+    /// Returns the topmost showing auto popover that `element`, a popover or modal dialog that
+    /// is about to be shown, is nested in through the flat tree or through its invoker.
+    pub(crate) fn topmost_popover_ancestor(
+        element: &Element,
+        invoker: Option<&HTMLElement>,
+    ) -> Option<DomRoot<HTMLElement>> {
+        // Step 2. Let popoverPositions be an empty ordered map.
+        // Step 3. For each popover of popoverList, set popoverPositions[popover] to its index.
+        let popover_list = element.owner_document().showing_auto_popover_list();
+
+        // Step 4. Let topmostPopoverAncestor be null.
+        let mut topmost_popover_ancestor: Option<(usize, DomRoot<HTMLElement>)> = None;
+
+        // Step 5. Let checkAncestor be an algorithm which performs the following steps given
+        // candidate:
+        let mut check_ancestor = |candidate: Option<&Node>| {
+            // Step 5.1. If candidate is null, then return.
+            let Some(candidate) = candidate else {
+                return;
+            };
+
+            // Step 5.4.1. Set candidateAncestor to the result of running nearest inclusive open
+            // popover given candidate.
+            // Step 5.4.2. If candidateAncestor is null or popoverPositions does not contain
+            // candidateAncestor, then return.
+            let Some(candidate_ancestor) = HTMLElement::nearest_inclusive_open_popover(candidate)
+            else {
+                return;
+            };
+            let Some(candidate_position) = popover_list
+                .iter()
+                .position(|popover| *popover == candidate_ancestor)
+            else {
+                return;
+            };
+
+            // Step 5.6. If topmostPopoverAncestor is null or popoverPositions[topmostPopoverAncestor]
+            // is less than candidatePosition, then set topmostPopoverAncestor to candidateAncestor.
+            if topmost_popover_ancestor
+                .as_ref()
+                .is_none_or(|(position, _)| *position < candidate_position)
+            {
+                topmost_popover_ancestor = Some((candidate_position, candidate_ancestor));
+            }
+        };
+
+        // Step 6. Run checkAncestor given newPopoverOrTopLayerElement's parent node within the
+        // flat tree.
+        check_ancestor(element.upcast::<Node>().GetParentNode().as_deref());
+
+        // Step 7. Run checkAncestor given source.
+        check_ancestor(invoker.map(|invoker| invoker.upcast::<Node>()));
+
+        // Step 8. Return topmostPopoverAncestor.
+        topmost_popover_ancestor.map(|(_, popover)| popover)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#nearest-inclusive-open-popover>
+    fn nearest_inclusive_open_popover(node: &Node) -> Option<DomRoot<HTMLElement>> {
+        node.inclusive_ancestors(ShadowIncluding::Yes)
+            .filter_map(DomRoot::downcast::<HTMLElement>)
+            .find(|element| {
+                element.popover_state() == Some(PopoverState::Auto) && element.is_popover_showing()
+            })
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#nearest-inclusive-target-popover>
+    fn nearest_inclusive_target_popover(node: &Node) -> Option<DomRoot<HTMLElement>> {
+        node.inclusive_ancestors(ShadowIncluding::Yes)
+            .filter_map(|node| {
+                if let Some(button) = node.downcast::<HTMLButtonElement>() {
+                    button.popover_target_element()
+                } else if let Some(input) = node.downcast::<HTMLInputElement>() {
+                    input.popover_target_element()
+                } else {
+                    None
+                }
+            })
+            .find(|popover| {
+                popover.popover_state() == Some(PopoverState::Auto) && popover.is_popover_showing()
+            })
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#topmost-clicked-popover>
+    pub(crate) fn topmost_clicked_popover(node: &Node) -> Option<DomRoot<HTMLElement>> {
+        // Step 1. Let clickedPopover be the result of running nearest inclusive open popover
+        // given node.
+        // Step 2. Let targetPopover be the result of running nearest inclusive target popover
+        // given node.
+        let clicked_popover = HTMLElement::nearest_inclusive_open_popover(node);
+        let target_popover = HTMLElement::nearest_inclusive_target_popover(node);
+
+        // <https://html.spec.whatwg.org/multipage/#popover-stack-position>
+        let popover_list = node.owner_document().showing_auto_popover_list();
+        let stack_position = |popover: &Option<DomRoot<HTMLElement>>| {
+            popover
+                .as_ref()
+                .and_then(|popover| popover_list.iter().position(|other| other == popover))
+                .map_or(0, |index| index + 1)
+        };
+
+        // Step 3. If the result of getting the popover stack position given clickedPopover is
+        // greater than the result of getting the popover stack position given targetPopover,
+        // then return clickedPopover.
+        // Step 4. Return targetPopover.
+        if stack_position(&clicked_popover) > stack_position(&target_popover) {
+            clicked_popover
+        } else {
+            target_popover
+        }
+    }
+
+    /// The element reflected by the `popovertarget` content attribute of a popover invoker,
+    /// given the element explicitly set through `popoverTargetElement`, if any.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/#attr-associated-element>
+    pub(crate) fn popover_target_attr_associated_element(
+        &self,
+        explicitly_set: Option<DomRoot<Element>>,
+    ) -> Option<DomRoot<Element>> {
+        let node = self.upcast::<Node>();
+
+        // Step 1. If element's explicitly set attr-element is not null:
+        // Step 1.1. If attrElement is a descendant of any of element's shadow-including
+        // ancestors, then return attrElement.
+        // Step 1.2. Return null.
+        if let Some(attr_element) = explicitly_set {
+            return attr_element
+                .upcast::<Node>()
+                .GetRootNode(&GetRootNodeOptions::empty())
+                .is_shadow_including_inclusive_ancestor_of(node)
+                .then_some(attr_element);
+        }
+
+        // Step 2. Otherwise, if the content attribute is present in element, then return the
+        // first element candidate, in tree order, that is in element's root and whose ID is
+        // the value of the content attribute.
+        let attr = self
+            .as_element()
+            .get_attribute(&ns!(), &local_name!("popovertarget"))?;
+        let id = Atom::from(&**attr.value());
+        node.GetRootNode(&GetRootNodeOptions::empty())
+            .traverse_preorder(ShadowIncluding::No)
+            .filter_map(DomRoot::downcast::<Element>)
+            .find(|element| element.get_id().as_ref() == Some(&id))
+    }
+
+    /// Set the `popoverTargetElement` of a popover invoker.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/#reflecting-content-attributes-in-idl-attributes:element>
+    pub(crate) fn set_popover_target_attr_associated_element(
+        &self,
+        explicitly_set: &MutNullableDom<Element>,
+        value: Option<&Element>,
+        can_gc: CanGc,
+    ) {
+        let element = self.as_element();
+        match value {
+            // Step 1. If the given value is null, then remove the content attribute.
+            None => {
+                element.remove_attribute(&ns!(), &local_name!("popovertarget"), can_gc);
+            },
+            // Step 2. Set the content attribute's value to the empty string.
+            Some(_) => {
+                element.set_string_attribute(
+                    &local_name!("popovertarget"),
+                    DOMString::new(),
+                    can_gc,
+                );
+            },
+        }
+
+        // Step 3. Set element's explicitly set attr-element to value.
+        explicitly_set.set(value);
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-popovertargetaction>
+    ///
+    /// The `popovertargetaction` attribute, limited to only known values, with the toggle
+    /// state as its missing and invalid value default.
+    pub(crate) fn popover_target_action(&self) -> DOMString {
+        let value = self
+            .as_element()
+            .get_string_attribute(&local_name!("popovertargetaction"))
+            .to_ascii_lowercase();
+        match &*value {
+            "show" | "hide" => DOMString::from(value),
+            _ => DOMString::from("toggle"),
+        }
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#popover-target-attribute-activation-behavior>
+    pub(crate) fn run_popover_target_attribute_activation_behavior(
+        &self,
+        popover: Option<DomRoot<HTMLElement>>,
+        event_target: &EventTarget,
+        can_gc: CanGc,
+    ) {
+        // Step 1. Let popover be node's popover target element.
+        // Step 2. If popover is null, then return.
+        let Some(popover) = popover else {
+            return;
+        };
+
+        // Step 3. If eventTarget is a shadow-including inclusive descendant of popover and
+        // popover is a shadow-including descendant of node, then return.
+        let node = self.upcast::<Node>();
+        let popover_node = popover.upcast::<Node>();
+        if event_target
+            .downcast::<Node>()
+            .is_some_and(|target| popover_node.is_shadow_including_inclusive_ancestor_of(target)) &&
+            node != popover_node &&
+            node.is_shadow_including_inclusive_ancestor_of(popover_node)
+        {
+            return;
+        }
+
+        // Step 4. If node's popovertargetaction attribute is in the show state and popover's
+        // popover visibility state is showing, then return.
+        // Step 5. If node's popovertargetaction attribute is in the hide state and popover's
+        // popover visibility state is hidden, then return.
+        let showing = popover.is_popover_showing();
+        match &*self.popover_target_action() {
+            "show" if showing => return,
+            "hide" if !showing => return,
+            _ => {},
+        }
+
+        // Step 6. If popover's popover visibility state is showing, then run the hide popover
+        // algorithm given popover, true, true, and false.
+        if showing {
+            let _ = popover.hide_popover(true, true, false, can_gc);
+        }
+        // Step 7. Otherwise, if popover's popover visibility state is hidden and the result of
+        // running check popover validity given popover, false, false, and null is true, then
+        // run show popover given popover, false, and node.
+        else if popover
+            .check_popover_validity(false, None, false)
+            .unwrap_or(false)
+        {
+            let _ = popover.show_popover(Some(self), can_gc);
+        }
+    }
+}
+
 impl VirtualMethods for HTMLElement {
     fn super_type(&self) -> Option<&dyn VirtualMethods> {
         Some(self.as_element() as &dyn VirtualMethods)
@@ -1154,6 +2027,34 @@ impl VirtualMethods for HTMLElement {
                     },
                 }
             },
+            // <https://html.spec.whatwg.org/multipage/#the-popover-attribute:concept-element-attributes-change-ext>
+            // If element's popover visibility state is in the showing state and oldValue and
+            // value are in different states, then run the hide popover algorithm given element,
+            // true, true, false, and true.
+            (&local_name!("popover"), mutation) if self.is_popover_showing() => {
+                let old_state = match mutation {
+                    AttributeMutation::Set(old_value) => {
+                        old_value.map(|old_value| PopoverState::from_value(old_value))
+                    },
+                    AttributeMutation::Removed => None,
+                };
+                if mutation.is_removal() || old_state != self.popover_state() {
+                    let _ = self.hide_popover(true, true, true, can_gc);
+                }
+            },
+            // Making the focused element inert runs the focus fixup rule.
+            // <https://html.spec.whatwg.org/multipage/#focus-fixup-rule>
+            (&local_name!("inert"), AttributeMutation::Set(None)) => {
+                let document = self.owner_document();
+                if let Some(focused) = document.get_focused_element() {
+                    if self
+                        .upcast::<Node>()
+                        .is_shadow_including_inclusive_ancestor_of(focused.upcast())
+                    {
+                        document.perform_focus_fixup_rule(&focused, can_gc);
+                    }
+                }
+            },
             (&local_name!("nonce"), mutation) => match mutation {
                 AttributeMutation::Set(_) => {
                     let nonce = &**attr.value();
@@ -1193,6 +2094,13 @@ impl VirtualMethods for HTMLElement {
             super_type.unbind_from_tree(context, can_gc);
         }
 
+        // <https://html.spec.whatwg.org/multipage/#the-popover-attribute:html-element-removing-steps>
+        // If removedNode's popover attribute is not in the no popover state, then run the hide
+        // popover algorithm given removedNode, false, false, false, and true.
+        if self.popover_state().is_some() {
+            let _ = self.hide_popover(false, false, true, can_gc);
+        }
+
         // Unbinding from a tree might enable a form control, if a
         // fieldset ancestor is the only reason it was disabled.
         // (The fact that it's enabled doesn't do much while it's
@@ -47,6 +47,7 @@ use crate::dom::bindings::reflector::{DomGlobal, DomObject};
 use crate::dom::bindings::root::{Dom, DomOnceCell, DomRoot, MutNullableDom};
 use crate::dom::bindings::str::DOMString;
 use crate::dom::blob::Blob;
+use crate::dom::csp::{CspReporting, NavigationCheckType};
 use crate::dom::customelementregistry::CallbackReaction;
 use crate::dom::document::Document;
 use crate::dom::domtokenlist::DOMTokenList;
@@ -844,8 +845,11 @@ impl HTMLFormElement {
 
         // Step 19
         let source = doc.browsing_context().unwrap();
-        let (maybe_chosen, _new) = source
-            .choose_browsing_context(target_attribute_value.unwrap_or(DOMString::new()), noopener);
+        let (maybe_chosen, _new) = source.choose_browsing_context(
+            target_attribute_value.unwrap_or(DOMString::new()),
+            noopener,
+            Default::default(),
+        );
 
         // Step 20
         let chosen = match maybe_chosen {
@@ -868,6 +872,7 @@ impl HTMLFormElement {
             Some(target_document.insecure_requests_policy()),
             target_document.has_trustworthy_ancestor_origin(),
         );
+        load_data.source_snapshot_params = Some(doc.snapshot_source_snapshot_params());
 
         // Step 22
         match (&*scheme, method) {
@@ -1004,6 +1009,19 @@ impl HTMLFormElement {
 
     /// [Planned navigation](https://html.spec.whatwg.org/multipage/#planned-navigation)
     fn plan_to_navigate(&self, mut load_data: LoadData, target: &Window) {
+        // Step 13 of <https://html.spec.whatwg.org/multipage/#navigate>: If the result of should
+        // navigation request of type be blocked by Content Security Policy? given request and
+        // cspNavigationType, which is "form-submission" here, is "Blocked", then return. [CSP]
+        let global = self.owner_global();
+        if global.get_csp_list().should_navigation_request_be_blocked(
+            &global,
+            &load_data,
+            Some(self.upcast()),
+            NavigationCheckType::FormSubmission,
+        ) {
+            return;
+        }
+
         // Step 1
         // Each planned navigation task is tagged with a generation ID, and
         // before the task is handled, it first checks whether the HTMLFormElement's
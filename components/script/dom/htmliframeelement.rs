@@ -5,21 +5,23 @@
 use std::cell::Cell;
 
 use base::id::{BrowsingContextId, PipelineId, WebViewId};
-use bitflags::bitflags;
 use constellation_traits::IFrameSandboxState::{IFrameSandboxed, IFrameUnsandboxed};
 use constellation_traits::{
     IFrameLoadInfo, IFrameLoadInfoWithData, JsEvalResult, LoadData, LoadOrigin,
-    NavigationHistoryBehavior, ScriptToConstellationMessage,
+    NavigationHistoryBehavior, SandboxingFlagSet, ScriptToConstellationMessage,
 };
 use dom_struct::dom_struct;
 use embedder_traits::ViewportDetails;
 use html5ever::{LocalName, Prefix, local_name, ns};
 use js::rust::HandleObject;
 use net_traits::ReferrerPolicy;
+use net_traits::permissions_policy::{
+    Allowlist, PolicyControlledFeature, PolicyDirective, parse_policy_directive,
+};
 use net_traits::request::Destination;
 use profile_traits::ipc as ProfiledIpc;
 use script_traits::{NewLayoutInfo, UpdatePipelineIdReason};
-use servo_url::ServoUrl;
+use servo_url::{ImmutableOrigin, ServoUrl};
 use style::attr::{AttrValue, LengthOrPercentageOrAuto};
 use stylo_atoms::Atom;
 
@@ -34,11 +36,12 @@ use crate::dom::bindings::inheritance::Castable;
 use crate::dom::bindings::reflector::DomGlobal;
 use crate::dom::bindings::root::{DomRoot, LayoutDom, MutNullableDom};
 use crate::dom::bindings::str::{DOMString, USVString};
-use crate::dom::csp::CspReporting;
+use crate::dom::csp::{CspReporting, NavigationCheckType};
 use crate::dom::document::{Document, determine_policy_for_token};
 use crate::dom::domtokenlist::DOMTokenList;
 use crate::dom::element::{
-    AttributeMutation, Element, LayoutElementHelpers, reflect_referrer_policy_attribute,
+    AttributeMutation, Element, LayoutElementHelpers, lazy_loading_attribute_is_lazy,
+    reflect_referrer_policy_attribute, will_lazy_load_element_steps,
 };
 use crate::dom::eventtarget::EventTarget;
 use crate::dom::globalscope::GlobalScope;
@@ -50,21 +53,6 @@ use crate::dom::windowproxy::WindowProxy;
 use crate::script_runtime::CanGc;
 use crate::script_thread::ScriptThread;
 
-#[derive(Clone, Copy, JSTraceable, MallocSizeOf)]
-struct SandboxAllowance(u8);
-
-bitflags! {
-    impl SandboxAllowance: u8 {
-        const ALLOW_NOTHING = 0x00;
-        const ALLOW_SAME_ORIGIN = 0x01;
-        const ALLOW_TOP_NAVIGATION = 0x02;
-        const ALLOW_FORMS = 0x04;
-        const ALLOW_SCRIPTS = 0x08;
-        const ALLOW_POINTER_LOCK = 0x10;
-        const ALLOW_POPUPS = 0x20;
-    }
-}
-
 #[derive(PartialEq)]
 enum PipelineType {
     InitialAboutBlank,
@@ -91,14 +79,51 @@ pub(crate) struct HTMLIFrameElement {
     #[no_trace]
     about_blank_pipeline_id: Cell<Option<PipelineId>>,
     sandbox: MutNullableDom<DOMTokenList>,
-    sandbox_allowance: Cell<Option<SandboxAllowance>>,
+    /// <https://html.spec.whatwg.org/multipage/#iframe-sandboxing-flag-set>
+    #[no_trace]
+    sandboxing_flag_set: Cell<Option<SandboxingFlagSet>>,
     load_blocker: DomRefCell<Option<LoadBlocker>>,
     throttled: Cell<bool>,
+    /// The navigation deferred until the element intersects the viewport.
+    /// <https://html.spec.whatwg.org/multipage/#lazy-load-resumption-steps>
+    #[no_trace]
+    #[ignore_malloc_size_of = "Defined in script_traits"]
+    lazy_load_resumption_steps: DomRefCell<Option<(LoadData, NavigationHistoryBehavior)>>,
 }
 
 impl HTMLIFrameElement {
     pub(crate) fn is_sandboxed(&self) -> bool {
-        self.sandbox_allowance.get().is_some()
+        self.sandboxing_flag_set.get().is_some()
+    }
+
+    /// <https://w3c.github.io/webappsec-permissions-policy/#process-permissions-policy-attributes>
+    fn container_policy(&self, target_origin: &ImmutableOrigin) -> PolicyDirective {
+        let element = self.upcast::<Element>();
+
+        // Step 1. Let policy be the result of parsing the value of element's allow attribute,
+        // with container origin set to the origin of element's node document, and target
+        // origin set to the origin of the URL in element's src attribute.
+        let mut policy = element
+            .get_attribute(&ns!(), &LocalName::from("allow"))
+            .map(|allow| {
+                parse_policy_directive(
+                    &allow.value(),
+                    self.owner_document().origin().immutable(),
+                    target_origin,
+                )
+            })
+            .unwrap_or_default();
+
+        // Step 2. If element is an iframe element with an allowfullscreen attribute, and the
+        // fullscreen feature is not a key in policy, then set policy[fullscreen] to *.
+        if element.has_attribute(&local_name!("allowfullscreen")) {
+            policy
+                .entry(PolicyControlledFeature::Fullscreen)
+                .or_insert(Allowlist::All);
+        }
+
+        // Step 3. Return policy.
+        policy
     }
 
     /// <https://html.spec.whatwg.org/multipage/#otherwise-steps-for-iframe-or-frame-elements>,
@@ -132,6 +157,58 @@ impl HTMLIFrameElement {
         );
     }
 
+    /// Navigate the child browsing context, unless the [will lazy load element steps] return
+    /// true, in which case the navigation is deferred until the element intersects the viewport.
+    ///
+    /// [will lazy load element steps]: https://html.spec.whatwg.org/multipage/#will-lazy-load-element-steps
+    fn navigate_or_defer_lazily(
+        &self,
+        load_data: LoadData,
+        history_handling: NavigationHistoryBehavior,
+        can_gc: CanGc,
+    ) {
+        self.cancel_lazy_load();
+
+        // > If element is an iframe and the will lazy load element steps given element return
+        // > true, then set element's lazy load resumption steps to the rest of this algorithm
+        // > starting with the step labeled navigate, start intersection-observing a lazy
+        // > loading element for element, and return.
+        if will_lazy_load_element_steps(self.upcast()) {
+            *self.lazy_load_resumption_steps.borrow_mut() = Some((load_data, history_handling));
+            self.owner_document()
+                .start_intersection_observing_a_lazy_loading_element(self.upcast(), can_gc);
+            return;
+        }
+
+        self.navigate_or_reload_child_browsing_context(load_data, history_handling, can_gc);
+    }
+
+    /// Run the [lazy load resumption steps] of this iframe, performing the deferred navigation
+    /// if there is one.
+    ///
+    /// [lazy load resumption steps]: https://html.spec.whatwg.org/multipage/#lazy-load-resumption-steps
+    pub(crate) fn resume_lazy_load(&self, can_gc: CanGc) {
+        let Some((load_data, history_handling)) =
+            self.lazy_load_resumption_steps.borrow_mut().take()
+        else {
+            return;
+        };
+        self.navigate_or_reload_child_browsing_context(load_data, history_handling, can_gc);
+    }
+
+    /// Drop any deferred navigation of this iframe.
+    fn cancel_lazy_load(&self) {
+        if self
+            .lazy_load_resumption_steps
+            .borrow_mut()
+            .take()
+            .is_some()
+        {
+            self.owner_document()
+                .stop_intersection_observing_a_lazy_loading_element(self.upcast());
+        }
+    }
+
     fn start_new_pipeline(
         &self,
         mut load_data: LoadData,
@@ -156,6 +233,20 @@ impl HTMLIFrameElement {
         };
 
         let document = self.owner_document();
+        load_data.inherited_cross_origin_isolated = document.global().is_cross_origin_isolated();
+
+        // https://html.spec.whatwg.org/multipage/#determining-the-creation-sandboxing-flags
+        // > The union of the flags that are set in the container's iframe sandboxing flag set
+        // > and in the active sandboxing flag set of the container's node document.
+        load_data.sandboxing_flag_set = self.sandboxing_flag_set.get().unwrap_or_default() |
+            document.active_sandboxing_flag_set();
+
+        let container_policy = self.container_policy(&load_data.url.origin());
+        load_data.inherited_permissions_policy = Some(
+            document
+                .permissions_policy()
+                .inherited_policy_for_child(document.origin().immutable(), &container_policy),
+        );
 
         {
             let load_blocker = &self.load_blocker;
@@ -172,6 +263,7 @@ impl HTMLIFrameElement {
                     global,
                     &load_data,
                     Some(self.upcast()),
+                    NavigationCheckType::Other,
                 ) {
                     return;
                 }
@@ -293,11 +385,7 @@ impl HTMLIFrameElement {
             load_data.policy_container = Some(window.as_global_scope().policy_container());
             let element = self.upcast::<Element>();
             load_data.srcdoc = String::from(element.get_string_attribute(&local_name!("srcdoc")));
-            self.navigate_or_reload_child_browsing_context(
-                load_data,
-                NavigationHistoryBehavior::Push,
-                can_gc,
-            );
+            self.navigate_or_defer_lazily(load_data, NavigationHistoryBehavior::Push, can_gc);
             return;
         }
 
@@ -398,7 +486,7 @@ impl HTMLIFrameElement {
             NavigationHistoryBehavior::Push
         };
 
-        self.navigate_or_reload_child_browsing_context(load_data, history_handling, can_gc);
+        self.navigate_or_defer_lazily(load_data, history_handling, can_gc);
     }
 
     fn create_nested_browsing_context(&self, can_gc: CanGc) {
@@ -433,6 +521,7 @@ impl HTMLIFrameElement {
         );
         load_data.destination = Destination::IFrame;
         load_data.policy_container = Some(window.as_global_scope().policy_container());
+        load_data.ancestor_origins = document.ancestor_origins_for_nested_navigable();
         let browsing_context_id = BrowsingContextId::new();
         let webview_id = window.window_proxy().webview_id();
         self.pipeline_id.set(None);
@@ -455,6 +544,18 @@ impl HTMLIFrameElement {
         self.browsing_context_id.set(None);
     }
 
+    /// Replace the pipeline of the navigation that is pending in this iframe, when its load
+    /// was replaced with the load of an error page.
+    pub(crate) fn replace_pending_pipeline_id(
+        &self,
+        old_pipeline_id: PipelineId,
+        new_pipeline_id: PipelineId,
+    ) {
+        if self.pending_pipeline_id.get() == Some(old_pipeline_id) {
+            self.pending_pipeline_id.set(Some(new_pipeline_id));
+        }
+    }
+
     pub(crate) fn update_pipeline_id(
         &self,
         new_pipeline_id: PipelineId,
@@ -492,9 +593,10 @@ impl HTMLIFrameElement {
             pending_pipeline_id: Cell::new(None),
             about_blank_pipeline_id: Cell::new(None),
             sandbox: Default::default(),
-            sandbox_allowance: Cell::new(None),
+            sandboxing_flag_set: Cell::new(None),
             load_blocker: DomRefCell::new(None),
             throttled: Cell::new(false),
+            lazy_load_resumption_steps: DomRefCell::new(None),
         }
     }
 
@@ -640,12 +742,16 @@ impl HTMLIFrameElementMethods<crate::DomTypeHolder> for HTMLIFrameElement {
                 self.upcast::<Element>(),
                 &local_name!("sandbox"),
                 Some(vec![
+                    Atom::from("allow-downloads"),
                     Atom::from("allow-same-origin"),
                     Atom::from("allow-forms"),
                     Atom::from("allow-pointer-lock"),
                     Atom::from("allow-popups"),
+                    Atom::from("allow-popups-to-escape-sandbox"),
+                    Atom::from("allow-presentation"),
                     Atom::from("allow-scripts"),
                     Atom::from("allow-top-navigation"),
+                    Atom::from("allow-top-navigation-by-user-activation"),
                 ]),
                 can_gc,
             )
@@ -690,6 +796,18 @@ impl HTMLIFrameElementMethods<crate::DomTypeHolder> for HTMLIFrameElement {
     // https://html.spec.whatwg.org/multipage/#attr-iframe-referrerpolicy
     make_setter!(SetReferrerPolicy, "referrerpolicy");
 
+    // https://html.spec.whatwg.org/multipage/#dom-iframe-allow
+    fn Allow(&self) -> DOMString {
+        self.upcast::<Element>()
+            .get_string_attribute(&LocalName::from("allow"))
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-iframe-allow
+    fn SetAllow(&self, value: DOMString, can_gc: CanGc) {
+        self.upcast::<Element>()
+            .set_string_attribute(&LocalName::from("allow"), value, can_gc);
+    }
+
     // https://html.spec.whatwg.org/multipage/#attr-iframe-allowfullscreen
     make_bool_getter!(AllowFullscreen, "allowfullscreen");
     // https://html.spec.whatwg.org/multipage/#attr-iframe-allowfullscreen
@@ -705,6 +823,17 @@ impl HTMLIFrameElementMethods<crate::DomTypeHolder> for HTMLIFrameElement {
     // https://html.spec.whatwg.org/multipage/#dom-dim-height
     make_dimension_setter!(SetHeight, "height");
 
+    // https://html.spec.whatwg.org/multipage/#dom-iframe-loading
+    make_enumerated_getter!(
+        Loading,
+        "loading",
+        "lazy" | "eager",
+        missing => "eager",
+        invalid => "eager"
+    );
+    // https://html.spec.whatwg.org/multipage/#dom-iframe-loading
+    make_setter!(SetLoading, "loading");
+
     // https://html.spec.whatwg.org/multipage/#other-elements,-attributes-and-apis:attr-iframe-frameborder
     make_getter!(FrameBorder, "frameborder");
     // https://html.spec.whatwg.org/multipage/#other-elements,-attributes-and-apis:attr-iframe-frameborder
@@ -732,22 +861,11 @@ impl VirtualMethods for HTMLIFrameElement {
             .attribute_mutated(attr, mutation, can_gc);
         match *attr.local_name() {
             local_name!("sandbox") => {
-                self.sandbox_allowance
-                    .set(mutation.new_value(attr).map(|value| {
-                        let mut modes = SandboxAllowance::ALLOW_NOTHING;
-                        for token in value.as_tokens() {
-                            modes |= match &*token.to_ascii_lowercase() {
-                                "allow-same-origin" => SandboxAllowance::ALLOW_SAME_ORIGIN,
-                                "allow-forms" => SandboxAllowance::ALLOW_FORMS,
-                                "allow-pointer-lock" => SandboxAllowance::ALLOW_POINTER_LOCK,
-                                "allow-popups" => SandboxAllowance::ALLOW_POPUPS,
-                                "allow-scripts" => SandboxAllowance::ALLOW_SCRIPTS,
-                                "allow-top-navigation" => SandboxAllowance::ALLOW_TOP_NAVIGATION,
-                                _ => SandboxAllowance::ALLOW_NOTHING,
-                            };
-                        }
-                        modes
-                    }));
+                self.sandboxing_flag_set.set(
+                    mutation
+                        .new_value(attr)
+                        .map(|value| parse_a_sandboxing_directive(value.as_tokens())),
+                );
             },
             local_name!("srcdoc") => {
                 // https://html.spec.whatwg.org/multipage/#the-iframe-element:the-iframe-element-9
@@ -779,6 +897,16 @@ impl VirtualMethods for HTMLIFrameElement {
                     self.process_the_iframe_attributes(ProcessingMode::NotFirstTime, can_gc);
                 }
             },
+            // https://html.spec.whatwg.org/multipage/#attr-iframe-loading
+            // "When the loading attribute's state is changed to the Eager state, the user agent
+            // must run these steps: ... Invoke resumptionSteps."
+            local_name!("loading") if !lazy_loading_attribute_is_lazy(self.upcast()) => {
+                if self.lazy_load_resumption_steps.borrow().is_some() {
+                    self.owner_document()
+                        .stop_intersection_observing_a_lazy_loading_element(self.upcast());
+                    self.resume_lazy_load(can_gc);
+                }
+            },
             _ => {},
         }
     }
@@ -826,6 +954,8 @@ impl VirtualMethods for HTMLIFrameElement {
         let blocker = &self.load_blocker;
         LoadBlocker::terminate(blocker, CanGc::note());
 
+        self.cancel_lazy_load();
+
         // https://html.spec.whatwg.org/multipage/#a-browsing-context-is-discarded
         let window = self.owner_window();
         let (sender, receiver) =
@@ -876,3 +1006,41 @@ impl VirtualMethods for HTMLIFrameElement {
         self.owner_document().invalidate_iframes_collection();
     }
 }
+
+/// <https://html.spec.whatwg.org/multipage/#parse-a-sandboxing-directive>
+fn parse_a_sandboxing_directive(tokens: &[Atom]) -> SandboxingFlagSet {
+    // Every flag is set, except for those that one of the keywords allows. The sandboxed
+    // navigation, plugins, document.domain, modals and orientation lock flags are always set,
+    // as the keywords allowing the last two are not supported.
+    let mut output = SandboxingFlagSet::all();
+    for token in tokens {
+        output.remove(match &*token.to_ascii_lowercase() {
+            "allow-downloads" => SandboxingFlagSet::SANDBOXED_DOWNLOADS,
+            "allow-forms" => SandboxingFlagSet::SANDBOXED_FORMS,
+            "allow-pointer-lock" => SandboxingFlagSet::SANDBOXED_POINTER_LOCK,
+            "allow-popups" => {
+                SandboxingFlagSet::SANDBOXED_AUXILIARY_NAVIGATION |
+                    SandboxingFlagSet::SANDBOXED_CUSTOM_PROTOCOLS_NAVIGATION
+            },
+            "allow-popups-to-escape-sandbox" => {
+                SandboxingFlagSet::SANDBOX_PROPAGATES_TO_AUXILIARY_BROWSING_CONTEXTS
+            },
+            "allow-presentation" => SandboxingFlagSet::SANDBOXED_PRESENTATION,
+            "allow-same-origin" => SandboxingFlagSet::SANDBOXED_ORIGIN,
+            "allow-scripts" => {
+                SandboxingFlagSet::SANDBOXED_SCRIPTS |
+                    SandboxingFlagSet::SANDBOXED_AUTOMATIC_FEATURES
+            },
+            "allow-top-navigation" => {
+                SandboxingFlagSet::SANDBOXED_TOP_LEVEL_NAVIGATION_WITHOUT_USER_ACTIVATION |
+                    SandboxingFlagSet::SANDBOXED_TOP_LEVEL_NAVIGATION_WITH_USER_ACTIVATION |
+                    SandboxingFlagSet::SANDBOXED_CUSTOM_PROTOCOLS_NAVIGATION
+            },
+            "allow-top-navigation-by-user-activation" => {
+                SandboxingFlagSet::SANDBOXED_TOP_LEVEL_NAVIGATION_WITH_USER_ACTIVATION
+            },
+            _ => SandboxingFlagSet::empty(),
+        });
+    }
+    output
+}
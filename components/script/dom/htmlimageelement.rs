@@ -63,8 +63,10 @@ use crate::dom::csp::{GlobalCspReporting, Violation};
 use crate::dom::document::{Document, determine_policy_for_token};
 use crate::dom::element::{
     AttributeMutation, CustomElementCreationMode, Element, ElementCreator, LayoutElementHelpers,
-    cors_setting_for_element, referrer_policy_for_element, reflect_cross_origin_attribute,
-    reflect_referrer_policy_attribute, set_cross_origin_attribute,
+    cors_setting_for_element, fetch_priority_for_element, lazy_loading_attribute_is_lazy,
+    referrer_policy_for_element, reflect_cross_origin_attribute, reflect_fetch_priority_attribute,
+    reflect_referrer_policy_attribute, set_cross_origin_attribute, set_fetch_priority_attribute,
+    will_lazy_load_element_steps,
 };
 use crate::dom::event::Event;
 use crate::dom::eventtarget::EventTarget;
@@ -166,6 +168,10 @@ pub(crate) struct HTMLImageElement {
     /// Always non-null after construction.
     dimension_attribute_source: MutNullableDom<Element>,
     last_selected_source: DomRefCell<Option<USVString>>,
+    /// The URL whose fetch is deferred until the element intersects the viewport.
+    /// <https://html.spec.whatwg.org/multipage/#lazy-load-resumption-steps>
+    #[no_trace]
+    lazy_load_resumption_url: DomRefCell<Option<ServoUrl>>,
     #[ignore_malloc_size_of = "promises are hard"]
     image_decode_promises: DomRefCell<Vec<Rc<Promise>>>,
     /// Line number this element was created on
@@ -475,7 +481,8 @@ impl HTMLImageElement {
         )
         .origin(document.origin().immutable().clone())
         .pipeline_id(Some(document.global().pipeline_id()))
-        .referrer_policy(referrer_policy_for_element(self.upcast()));
+        .referrer_policy(referrer_policy_for_element(self.upcast()))
+        .priority(fetch_priority_for_element(self.upcast()));
 
         if Self::uses_srcset_or_picture(self.upcast()) {
             request = request.initiator(Initiator::ImageSet);
@@ -870,8 +877,11 @@ impl HTMLImageElement {
         request.metadata = None;
         let document = self.owner_document();
         LoadBlocker::terminate(&request.blocker, can_gc);
-        *request.blocker.borrow_mut() =
-            Some(LoadBlocker::new(&document, LoadType::Image(url.clone())));
+        // Lazily loaded images do not delay the load event of their document.
+        if !will_lazy_load_element_steps(self.upcast()) {
+            *request.blocker.borrow_mut() =
+                Some(LoadBlocker::new(&document, LoadType::Image(url.clone())));
+        }
     }
 
     /// Step 13-17 of html.spec.whatwg.org/multipage/#update-the-image-data
@@ -925,9 +935,40 @@ impl HTMLImageElement {
                 }
             },
         }
+
+        // If the will lazy load element steps given the img return true, then set the img's
+        // lazy load resumption steps to the rest of this algorithm starting with the step
+        // labeled fetch the image, and start intersection-observing a lazy loading element
+        // for the img element.
+        if will_lazy_load_element_steps(self.upcast()) {
+            *self.lazy_load_resumption_url.borrow_mut() = Some(url.clone());
+            self.owner_document()
+                .start_intersection_observing_a_lazy_loading_element(self.upcast(), can_gc);
+            return;
+        }
+
         self.fetch_image(url, can_gc);
     }
 
+    /// Run the [lazy load resumption steps] of this image, fetching the deferred image if
+    /// there is one.
+    ///
+    /// [lazy load resumption steps]: https://html.spec.whatwg.org/multipage/#lazy-load-resumption-steps
+    pub(crate) fn resume_lazy_load(&self, can_gc: CanGc) {
+        let Some(url) = self.lazy_load_resumption_url.borrow_mut().take() else {
+            return;
+        };
+        self.fetch_image(&url, can_gc);
+    }
+
+    /// Drop any deferred fetch of this image, as a new request is about to replace it.
+    fn cancel_lazy_load(&self) {
+        if self.lazy_load_resumption_url.borrow_mut().take().is_some() {
+            self.owner_document()
+                .stop_intersection_observing_a_lazy_loading_element(self.upcast());
+        }
+    }
+
     /// Step 8-12 of html.spec.whatwg.org/multipage/#update-the-image-data
     fn update_the_image_data_sync_steps(&self, can_gc: CanGc) {
         let document = self.owner_document();
@@ -996,6 +1037,8 @@ impl HTMLImageElement {
         let src = elem.get_url_attribute(&local_name!("src"));
         let base_url = document.base_url();
 
+        self.cancel_lazy_load();
+
         // https://html.spec.whatwg.org/multipage/#reacting-to-dom-mutations
         // Always first set the current request to unavailable,
         // ensuring img.complete is false.
@@ -1364,6 +1407,7 @@ impl HTMLImageElement {
             source_set: DomRefCell::new(SourceSet::new()),
             dimension_attribute_source: Default::default(),
             last_selected_source: DomRefCell::new(None),
+            lazy_load_resumption_url: DomRefCell::new(None),
             image_decode_promises: DomRefCell::new(vec![]),
             line_number: creator.return_line_number(),
         }
@@ -1597,6 +1641,7 @@ impl HTMLImageElementMethods<crate::DomTypeHolder> for HTMLImageElement {
             QualName::new(None, ns!(html), local_name!("img")),
             None,
             &window.Document(),
+            None,
             ElementCreator::ScriptCreated,
             CustomElementCreationMode::Synchronous,
             proto,
@@ -1761,6 +1806,28 @@ impl HTMLImageElementMethods<crate::DomTypeHolder> for HTMLImageElement {
         }
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-img-loading
+    make_enumerated_getter!(
+        Loading,
+        "loading",
+        "lazy" | "eager",
+        missing => "eager",
+        invalid => "eager"
+    );
+
+    // https://html.spec.whatwg.org/multipage/#dom-img-loading
+    make_setter!(SetLoading, "loading");
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-img-fetchpriority>
+    fn FetchPriority(&self) -> DOMString {
+        reflect_fetch_priority_attribute(self.upcast::<Element>())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-img-fetchpriority>
+    fn SetFetchPriority(&self, value: DOMString, can_gc: CanGc) {
+        set_fetch_priority_attribute(self.upcast::<Element>(), value, can_gc);
+    }
+
     /// <https://html.spec.whatwg.org/multipage/#dom-img-decode>
     fn Decode(&self, can_gc: CanGc) -> Rc<Promise> {
         // Step 1
@@ -1835,6 +1902,16 @@ impl VirtualMethods for HTMLImageElement {
             &local_name!("crossorigin") |
             &local_name!("sizes") |
             &local_name!("referrerpolicy") => self.update_the_image_data(can_gc),
+            // When the loading attribute's state is changed to the Eager state, resume any
+            // deferred load.
+            // <https://html.spec.whatwg.org/multipage/#attr-img-loading>
+            &local_name!("loading") if !lazy_loading_attribute_is_lazy(self.upcast()) => {
+                if self.lazy_load_resumption_url.borrow().is_some() {
+                    self.owner_document()
+                        .stop_intersection_observing_a_lazy_loading_element(self.upcast());
+                    self.resume_lazy_load(can_gc);
+                }
+            },
             _ => {},
         }
     }
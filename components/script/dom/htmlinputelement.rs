@@ -60,7 +60,7 @@ use crate::dom::clipboardevent::ClipboardEvent;
 use crate::dom::compositionevent::CompositionEvent;
 use crate::dom::document::Document;
 use crate::dom::element::{AttributeMutation, Element, LayoutElementHelpers};
-use crate::dom::event::{Event, EventBubbles, EventCancelable};
+use crate::dom::event::Event;
 use crate::dom::eventtarget::EventTarget;
 use crate::dom::file::File;
 use crate::dom::filelist::{FileList, LayoutFileListHelpers};
@@ -73,6 +73,7 @@ use crate::dom::htmlformelement::{
     FormControl, FormDatum, FormDatumValue, FormSubmitterElement, HTMLFormElement, ResetFrom,
     SubmittedFrom,
 };
+use crate::dom::inputevent::{InputEvent, InputEventType};
 use crate::dom::keyboardevent::KeyboardEvent;
 use crate::dom::mouseevent::MouseEvent;
 use crate::dom::node::{
@@ -377,6 +378,9 @@ pub(crate) struct HTMLInputElement {
     labels_node_list: MutNullableDom<NodeList>,
     validity_state: MutNullableDom<ValidityState>,
     shadow_tree: DomRefCell<Option<ShadowTree>>,
+    /// The explicitly set attr-element of the `popovertarget` attribute.
+    /// <https://html.spec.whatwg.org/multipage/#explicitly-set-attr-element>
+    explicitly_set_popover_target_element: MutNullableDom<Element>,
 }
 
 #[derive(JSTraceable)]
@@ -436,6 +440,7 @@ impl HTMLInputElement {
             labels_node_list: MutNullableDom::new(None),
             validity_state: Default::default(),
             shadow_tree: Default::default(),
+            explicitly_set_popover_target_element: Default::default(),
         }
     }
 
@@ -546,6 +551,37 @@ impl HTMLInputElement {
         input_type == InputType::Submit || input_type == InputType::Image
     }
 
+    /// <https://html.spec.whatwg.org/multipage/#popover-target-element>
+    pub(crate) fn popover_target_element(&self) -> Option<DomRoot<HTMLElement>> {
+        // Step 1. If node is not a button, then return null.
+        if !matches!(
+            self.input_type(),
+            InputType::Submit | InputType::Reset | InputType::Image | InputType::Button
+        ) {
+            return None;
+        }
+
+        // Step 2. If node is disabled, then return null.
+        // Step 3. If node has a form owner and node is a submit button, then return null.
+        if self.upcast::<Element>().disabled_state() ||
+            (self.form_owner().is_some() && self.is_submit_button())
+        {
+            return None;
+        }
+
+        // Step 4. Let popoverElement be the result of running node's get the popovertarget-
+        // associated element.
+        // Step 5. If popoverElement is null, then return null.
+        // Step 6. If popoverElement's popover attribute is in the no popover state, then return
+        // null.
+        self.upcast::<HTMLElement>()
+            .popover_target_attr_associated_element(
+                self.explicitly_set_popover_target_element.get(),
+            )
+            .and_then(DomRoot::downcast::<HTMLElement>)
+            .filter(|popover| popover.popover_state().is_some())
+    }
+
     pub(crate) fn disable_sanitization(&self) {
         self.sanitization_flag.set(false);
     }
@@ -2006,6 +2042,32 @@ impl HTMLInputElementMethods<crate::DomTypeHolder> for HTMLInputElement {
     fn SetCustomValidity(&self, error: DOMString) {
         self.validity_state().set_custom_error_message(error);
     }
+
+    // https://html.spec.whatwg.org/multipage/#dom-popovertargetelement
+    fn GetPopoverTargetElement(&self) -> Option<DomRoot<Element>> {
+        self.upcast::<HTMLElement>()
+            .popover_target_attr_associated_element(
+                self.explicitly_set_popover_target_element.get(),
+            )
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-popovertargetelement
+    fn SetPopoverTargetElement(&self, element: Option<&Element>, can_gc: CanGc) {
+        self.upcast::<HTMLElement>()
+            .set_popover_target_attr_associated_element(
+                &self.explicitly_set_popover_target_element,
+                element,
+                can_gc,
+            );
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-popovertargetaction
+    fn PopoverTargetAction(&self) -> DOMString {
+        self.upcast::<HTMLElement>().popover_target_action()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-popovertargetaction
+    make_setter!(SetPopoverTargetAction, "popovertargetaction");
 }
 
 fn radio_group_iter<'a>(
@@ -2981,6 +3043,11 @@ impl VirtualMethods for HTMLInputElement {
             local_name!("form") => {
                 self.form_attribute_mutated(mutation, can_gc);
             },
+            // Changing the content attribute forgets the element set through
+            // popoverTargetElement.
+            local_name!("popovertarget") => {
+                self.explicitly_set_popover_target_element.set(None);
+            },
             _ => {},
         }
 
@@ -3100,6 +3167,21 @@ impl VirtualMethods for HTMLInputElement {
             self.input_type().is_textual_or_password()
         {
             if let Some(keyevent) = event.downcast::<KeyboardEvent>() {
+                let input_event = self.textinput.borrow().input_event_for_keydown(keyevent);
+                if let Some((input_type, data)) = input_event.clone() {
+                    if event.IsTrusted() &&
+                        !InputEvent::dispatch_beforeinput(
+                            self.upcast(),
+                            input_type,
+                            data,
+                            &[],
+                            can_gc,
+                        )
+                    {
+                        event.PreventDefault();
+                        return;
+                    }
+                }
                 // This can't be inlined, as holding on to textinput.borrow_mut()
                 // during self.implicit_submission will cause a panic.
                 let action = self.textinput.borrow_mut().handle_keydown(keyevent);
@@ -3108,16 +3190,10 @@ impl VirtualMethods for HTMLInputElement {
                         self.implicit_submission(can_gc);
                     },
                     DispatchInput => {
-                        if event.IsTrusted() {
-                            self.owner_global()
-                                .task_manager()
-                                .user_interaction_task_source()
-                                .queue_event(
-                                    self.upcast(),
-                                    atom!("input"),
-                                    EventBubbles::Bubbles,
-                                    EventCancelable::NotCancelable,
-                                );
+                        if let Some((input_type, data)) = input_event {
+                            if event.IsTrusted() {
+                                InputEvent::queue_input(self.upcast(), input_type, data);
+                            }
                         }
                         self.value_dirty.set(true);
                         self.update_placeholder_shown_state();
@@ -3145,16 +3221,40 @@ impl VirtualMethods for HTMLInputElement {
         {
             if let Some(compositionevent) = event.downcast::<CompositionEvent>() {
                 if event.type_() == atom!("compositionend") {
+                    InputEvent::dispatch_beforeinput(
+                        self.upcast(),
+                        InputEventType::InsertCompositionText,
+                        Some(DOMString::from(compositionevent.data())),
+                        &[],
+                        can_gc,
+                    );
                     let _ = self
                         .textinput
                         .borrow_mut()
                         .handle_compositionend(compositionevent);
+                    InputEvent::queue_input(
+                        self.upcast(),
+                        InputEventType::InsertCompositionText,
+                        Some(DOMString::from(compositionevent.data())),
+                    );
                     self.upcast::<Node>().dirty(NodeDamage::Other);
                 } else if event.type_() == atom!("compositionupdate") {
+                    InputEvent::dispatch_beforeinput(
+                        self.upcast(),
+                        InputEventType::InsertCompositionText,
+                        Some(DOMString::from(compositionevent.data())),
+                        &[],
+                        can_gc,
+                    );
                     let _ = self
                         .textinput
                         .borrow_mut()
                         .handle_compositionupdate(compositionevent);
+                    InputEvent::queue_input(
+                        self.upcast(),
+                        InputEventType::InsertCompositionText,
+                        Some(DOMString::from(compositionevent.data())),
+                    );
                     self.upcast::<Node>().dirty(NodeDamage::Other);
                 }
                 event.mark_as_handled();
@@ -3291,9 +3391,12 @@ impl Activatable for HTMLInputElement {
             // https://html.spec.whatwg.org/multipage/#reset-button-state-(type=reset):input-activation-behavior
             // https://html.spec.whatwg.org/multipage/#file-upload-state-(type=file):input-activation-behavior
             // https://html.spec.whatwg.org/multipage/#image-button-state-(type=image):input-activation-behavior
-            InputType::Submit | InputType::Reset | InputType::File | InputType::Image => {
-                self.is_mutable()
-            },
+            // https://html.spec.whatwg.org/multipage/#button-state-(type=button):input-activation-behavior
+            InputType::Submit |
+            InputType::Reset |
+            InputType::File |
+            InputType::Image |
+            InputType::Button => self.is_mutable(),
             // https://html.spec.whatwg.org/multipage/#checkbox-state-(type=checkbox):input-activation-behavior
             // https://html.spec.whatwg.org/multipage/#radio-button-state-(type=radio):input-activation-behavior
             // https://html.spec.whatwg.org/multipage/#color-state-(type=color):input-activation-behavior
@@ -3406,7 +3509,7 @@ impl Activatable for HTMLInputElement {
     }
 
     /// <https://html.spec.whatwg.org/multipage/#input-activation-behavior>
-    fn activation_behavior(&self, _event: &Event, _target: &EventTarget, can_gc: CanGc) {
+    fn activation_behavior(&self, _event: &Event, target: &EventTarget, can_gc: CanGc) {
         match self.input_type() {
             // https://html.spec.whatwg.org/multipage/#submit-button-state-(type=submit):activation-behavior
             // https://html.spec.whatwg.org/multipage/#submit-button-state-(type=image):activation-behavior
@@ -3472,6 +3575,15 @@ impl Activatable for HTMLInputElement {
             },
             _ => (),
         }
+
+        // Run the popover target attribute activation behavior given element and event's
+        // target.
+        self.upcast::<HTMLElement>()
+            .run_popover_target_attribute_activation_behavior(
+                self.popover_target_element(),
+                target,
+                can_gc,
+            );
     }
 }
 
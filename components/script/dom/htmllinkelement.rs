@@ -16,8 +16,8 @@ use mime::Mime;
 use net_traits::mime_classifier::{MediaType, MimeClassifier};
 use net_traits::policy_container::PolicyContainer;
 use net_traits::request::{
-    CorsSettings, Destination, Initiator, InsecureRequestsPolicy, Referrer, RequestBuilder,
-    RequestId,
+    CorsSettings, CredentialsMode, Destination, Initiator, InsecureRequestsPolicy, ParserMetadata,
+    Referrer, RequestBuilder, RequestId, RequestPriority,
 };
 use net_traits::{
     FetchMetadata, FetchResponseListener, NetworkError, ReferrerPolicy, ResourceFetchTiming,
@@ -46,8 +46,9 @@ use crate::dom::documentorshadowroot::StylesheetSource;
 use crate::dom::domtokenlist::DOMTokenList;
 use crate::dom::element::{
     AttributeMutation, Element, ElementCreator, cors_setting_for_element,
-    referrer_policy_for_element, reflect_cross_origin_attribute, reflect_referrer_policy_attribute,
-    set_cross_origin_attribute,
+    fetch_priority_for_element, referrer_policy_for_element, reflect_cross_origin_attribute,
+    reflect_fetch_priority_attribute, reflect_referrer_policy_attribute,
+    set_cross_origin_attribute, set_fetch_priority_attribute,
 };
 use crate::dom::htmlelement::HTMLElement;
 use crate::dom::medialist::MediaList;
@@ -59,6 +60,10 @@ use crate::dom::virtualmethods::VirtualMethods;
 use crate::fetch::create_a_potential_cors_request;
 use crate::links::LinkRelations;
 use crate::network_listener::{PreInvoke, ResourceTimingListener, submit_timing};
+use crate::script_module::{
+    ModuleOwner, ScriptFetchOptions, fetch_external_module_script,
+    resolve_a_module_integrity_metadata,
+};
 use crate::script_runtime::CanGc;
 use crate::stylesheet_loader::{StylesheetContextSource, StylesheetLoader, StylesheetOwner};
 
@@ -80,6 +85,7 @@ struct LinkProcessingOptions {
     cryptographic_nonce_metadata: String,
     cross_origin: Option<CorsSettings>,
     referrer_policy: ReferrerPolicy,
+    fetch_priority: RequestPriority,
     policy_container: PolicyContainer,
     source_set: Option<()>,
     base_url: ServoUrl,
@@ -289,6 +295,13 @@ impl VirtualMethods for HTMLLinkElement {
                 if self.relations.get().contains(LinkRelations::PRELOAD) {
                     self.handle_preload_url();
                 }
+
+                // https://html.spec.whatwg.org/multipage/#link-type-modulepreload
+                // When the href attribute of the link element of an external resource link
+                // that is already browsing-context connected is changed.
+                if self.relations.get().contains(LinkRelations::MODULE_PRELOAD) {
+                    self.handle_modulepreload_url(&attr.value());
+                }
             },
             local_name!("sizes") if self.relations.get().contains(LinkRelations::ICON) => {
                 if let Some(ref href) = get_attr(self.upcast(), &local_name!("href")) {
@@ -407,6 +420,10 @@ impl VirtualMethods for HTMLLinkElement {
                 if relations.contains(LinkRelations::PRELOAD) {
                     self.handle_preload_url();
                 }
+
+                if relations.contains(LinkRelations::MODULE_PRELOAD) {
+                    self.handle_modulepreload_url(&href);
+                }
             }
         }
     }
@@ -451,6 +468,7 @@ impl HTMLLinkElement {
             cryptographic_nonce_metadata: self.upcast::<Element>().nonce_value(),
             cross_origin: cors_setting_for_element(element),
             referrer_policy: referrer_policy_for_element(element),
+            fetch_priority: fetch_priority_for_element(element),
             policy_container: document.policy_container().to_owned(),
             source_set: None, // FIXME
             origin: document.borrow().origin().immutable().to_owned(),
@@ -603,6 +621,89 @@ impl HTMLLinkElement {
         }
     }
 
+    /// <https://html.spec.whatwg.org/multipage/#link-type-modulepreload:fetch-and-process-the-linked-resource>
+    fn handle_modulepreload_url(&self, href: &str) {
+        // Step 1. If el's href attribute's value is the empty string, then return.
+        if href.is_empty() {
+            return;
+        }
+
+        // Step 2. Let destination be the current state of el's as attribute (a destination),
+        // or "script" if it is in no state.
+        let element = self.upcast::<Element>();
+        let destination = element
+            .get_attribute(&ns!(), &local_name!("as"))
+            .map(|attr| translate_a_preload_destination(&attr.value()))
+            .unwrap_or(Destination::Script);
+
+        // Step 3. If destination is not script-like, then queue an element task on the
+        // networking task source given el to fire an event named error at el, and return.
+        let global = self.owner_global();
+        if !destination.is_script_like() {
+            global
+                .task_manager()
+                .networking_task_source()
+                .queue_simple_event(self.upcast(), atom!("error"));
+            return;
+        }
+
+        // Step 4. Let url be the result of encoding-parsing a URL given el's href attribute's
+        // value, relative to el's node document.
+        // Step 5. If url is failure, then return.
+        let Ok(url) = self.owner_document().base_url().join(href) else {
+            return;
+        };
+
+        // Step 6. Let settings object be el's node document's relevant settings object.
+        // This is done by passing a `ModuleOwner` when fetching.
+
+        // Step 7. Let credentials mode be the CORS settings attribute credentials mode for el's
+        // crossorigin attribute.
+        let credentials_mode = match cors_setting_for_element(element) {
+            Some(CorsSettings::UseCredentials) => CredentialsMode::Include,
+            Some(CorsSettings::Anonymous) | None => CredentialsMode::CredentialsSameOrigin,
+        };
+
+        // Step 8. Let cryptographic nonce be el's [[CryptographicNonce]].
+        let cryptographic_nonce = element.nonce_value();
+
+        // Step 9. Let integrity metadata be the value of el's integrity attribute, if it is
+        // specified, or the empty string otherwise.
+        // Step 10. If el does not have an integrity attribute, then set integrity metadata to
+        // the result of resolving a module integrity metadata with url and settings object.
+        let integrity_metadata = match element.get_attribute(&ns!(), &local_name!("integrity")) {
+            Some(attr) => (**attr.value()).to_owned(),
+            None => resolve_a_module_integrity_metadata(&url, &global),
+        };
+
+        // Step 11. Let referrer policy be the current state of el's referrerpolicy attribute.
+        // Step 12. Let fetch priority be the current state of el's fetchpriority attribute.
+        // Step 13. Let options be a script fetch options whose cryptographic nonce is
+        // cryptographic nonce, integrity metadata is integrity metadata, parser metadata is
+        // "not-parser-inserted", credentials mode is credentials mode, referrer policy is
+        // referrer policy, and fetch priority is fetch priority.
+        let options = ScriptFetchOptions {
+            referrer: global.get_referrer(),
+            integrity_metadata,
+            credentials_mode,
+            cryptographic_nonce,
+            parser_metadata: ParserMetadata::NotParserInserted,
+            referrer_policy: referrer_policy_for_element(element),
+            fetch_priority: fetch_priority_for_element(element),
+        };
+
+        // Step 14. Fetch a modulepreload module script graph given url, destination, settings
+        // object, options, and the following steps given result. Firing the load or error event
+        // is done in `ModuleOwner::notify_owner_to_finish`.
+        fetch_external_module_script(
+            ModuleOwner::ModulePreload(Trusted::new(self)),
+            url,
+            destination,
+            options,
+            CanGc::note(),
+        );
+    }
+
     /// <https://html.spec.whatwg.org/multipage/#link-type-preload:fetch-and-process-the-linked-resource-2>
     fn handle_preload_url(&self) {
         // Step 1. Update the source set for el.
@@ -628,11 +729,11 @@ impl HTMLLinkElement {
         // then set options's href to the result of selecting an image source from options's source set.
         // TODO
         // Step 3. Let request be the result of creating a link request given options.
-        let url = options.base_url.clone();
         let Some(request) = options.create_link_request(self.owner_window().webview_id()) else {
             // Step 4. If request is null, then return.
             return;
         };
+        let url = request.url.clone();
         let document = self.upcast::<Node>().owner_doc();
         // Step 5. Let unsafeEndTime be 0.
         // TODO
@@ -896,6 +997,16 @@ impl HTMLLinkElementMethods<crate::DomTypeHolder> for HTMLLinkElement {
     // https://html.spec.whatwg.org/multipage/#dom-link-referrerpolicy
     make_setter!(SetReferrerPolicy, "referrerpolicy");
 
+    /// <https://html.spec.whatwg.org/multipage/#dom-link-fetchpriority>
+    fn FetchPriority(&self) -> DOMString {
+        reflect_fetch_priority_attribute(self.upcast::<Element>())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-link-fetchpriority>
+    fn SetFetchPriority(&self, value: DOMString, can_gc: CanGc) {
+        set_fetch_priority_attribute(self.upcast::<Element>(), value, can_gc);
+    }
+
     // https://drafts.csswg.org/cssom/#dom-linkstyle-sheet
     fn GetSheet(&self, can_gc: CanGc) -> Option<DomRoot<DOMStyleSheet>> {
         self.get_cssom_stylesheet(can_gc).map(DomRoot::upcast)
@@ -943,7 +1054,8 @@ impl LinkProcessingOptions {
         .origin(self.origin)
         .integrity_metadata(self.integrity)
         .cryptographic_nonce_metadata(self.cryptographic_nonce_metadata)
-        .referrer_policy(self.referrer_policy);
+        .referrer_policy(self.referrer_policy)
+        .priority(self.fetch_priority);
 
         // Step 12. Return request.
         Some(builder)
@@ -1072,7 +1184,12 @@ impl FetchResponseListener for PreloadContext {
         _: RequestId,
         fetch_metadata: Result<FetchMetadata, NetworkError>,
     ) {
-        _ = fetch_metadata;
+        if let Err(error) = &fetch_metadata {
+            self.link
+                .root()
+                .owner_global()
+                .report_subresource_integrity_failure(&self.url, error);
+        }
     }
 
     fn process_response_chunk(&mut self, _: RequestId, chunk: Vec<u8>) {
@@ -22,6 +22,7 @@ use ipc_channel::router::ROUTER;
 use js::jsapi::JSAutoRealm;
 use layout_api::MediaFrame;
 use media::{GLPlayerMsg, GLPlayerMsgForward, WindowGLContext};
+use net_traits::permissions_policy::PolicyControlledFeature;
 use net_traits::request::{Destination, RequestId};
 use net_traits::{
     FetchMetadata, FetchResponseListener, FilteredMetadata, Metadata, NetworkError,
@@ -716,7 +717,13 @@ impl HTMLMediaElement {
             // FIXME(nox): I have no idea what this TODO is about.
 
             // FIXME(nox): Review this block.
-            if self.autoplaying.get() && self.Paused() && self.Autoplay() {
+            // https://html.spec.whatwg.org/multipage/#eligible-for-autoplay
+            if self.autoplaying.get() &&
+                self.Paused() &&
+                self.Autoplay() &&
+                self.owner_document()
+                    .allowed_to_use_feature(PolicyControlledFeature::Autoplay)
+            {
                 // Step 1
                 self.paused.set(false);
                 // Step 2
@@ -6,9 +6,11 @@ use std::str::FromStr;
 
 use compositing_traits::CompositorMsg;
 use compositing_traits::viewport_description::ViewportDescription;
+use cssparser::match_ignore_ascii_case;
 use dom_struct::dom_struct;
 use html5ever::{LocalName, Prefix, local_name, ns};
 use js::rust::HandleObject;
+use net_traits::ReferrerPolicy;
 use style::str::HTML_SPACE_CHARACTERS;
 
 use crate::dom::attr::Attr;
@@ -117,7 +119,24 @@ impl HTMLMetaElement {
             let attr = attr.value();
             let attr_val = attr.trim();
             if !attr_val.is_empty() {
-                doc.set_referrer_policy(determine_policy_for_token(attr_val));
+                // 4. Let value be the value of element's content attribute, converted to ASCII
+                // lowercase.
+                // 5. If value is one of the legacy values in the first column, then set value to
+                // the value in the second column.
+                let value = match_ignore_ascii_case! { attr_val,
+                    "never" => "no-referrer",
+                    "default" => "strict-origin-when-cross-origin",
+                    "always" => "unsafe-url",
+                    "origin-when-crossorigin" => "origin-when-cross-origin",
+                    _ => attr_val,
+                };
+
+                // 6. If value is a referrer policy, then set element's node document's policy
+                // container's referrer policy to policy.
+                let policy = determine_policy_for_token(value);
+                if policy != ReferrerPolicy::EmptyString {
+                    doc.set_referrer_policy(policy);
+                }
             }
         }
     }
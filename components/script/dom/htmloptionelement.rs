@@ -167,6 +167,7 @@ impl HTMLOptionElementMethods<crate::DomTypeHolder> for HTMLOptionElement {
             QualName::new(None, ns!(html), local_name!("option")),
             None,
             &window.Document(),
+            None,
             ElementCreator::ScriptCreated,
             CustomElementCreationMode::Synchronous,
             proto,
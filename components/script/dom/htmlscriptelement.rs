@@ -47,12 +47,14 @@ use crate::dom::bindings::root::{Dom, DomRoot, MutNullableDom};
 use crate::dom::bindings::settings_stack::AutoEntryScript;
 use crate::dom::bindings::str::DOMString;
 use crate::dom::bindings::trace::NoTrace;
+use crate::dom::console::Console;
 use crate::dom::csp::{CspReporting, GlobalCspReporting, InlineCheckType, Violation};
 use crate::dom::document::Document;
 use crate::dom::element::{
     AttributeMutation, Element, ElementCreator, cors_setting_for_element,
-    referrer_policy_for_element, reflect_cross_origin_attribute, reflect_referrer_policy_attribute,
-    set_cross_origin_attribute,
+    fetch_priority_for_element, referrer_policy_for_element, reflect_cross_origin_attribute,
+    reflect_fetch_priority_attribute, reflect_referrer_policy_attribute,
+    set_cross_origin_attribute, set_fetch_priority_attribute,
 };
 use crate::dom::event::{Event, EventBubbles, EventCancelable};
 use crate::dom::globalscope::GlobalScope;
@@ -71,6 +73,7 @@ use crate::script_module::{
     fetch_inline_module_script, parse_an_import_map_string, register_import_map,
 };
 use crate::script_runtime::{CanGc, IntroductionType};
+use crate::speculation_rules::parse_a_speculation_rule_set_string;
 use crate::unminify::{ScriptSource, unminify_js};
 
 impl ScriptSource for ScriptOrigin {
@@ -214,6 +217,65 @@ pub(crate) enum ScriptType {
     Classic,
     Module,
     ImportMap,
+    SpeculationRules,
+}
+
+/// Determine the type of a script from the values of its `type` and `language` attributes,
+/// returning `None` if the script should not be run.
+///
+/// Step 7 of <https://html.spec.whatwg.org/multipage/#prepare-a-script>. This is also used by
+/// the prefetch tokenizer in `script::dom::servoparser::prefetch`.
+pub(crate) fn script_type_from_attributes(
+    type_attr: Option<&str>,
+    language_attr: Option<&str>,
+) -> Option<ScriptType> {
+    match (type_attr, language_attr) {
+        (Some(ty), _) if ty.is_empty() => {
+            debug!("script type empty, inferring js");
+            Some(ScriptType::Classic)
+        },
+        (None, Some(lang)) if lang.is_empty() => {
+            debug!("script type empty, inferring js");
+            Some(ScriptType::Classic)
+        },
+        (None, None) => {
+            debug!("script type empty, inferring js");
+            Some(ScriptType::Classic)
+        },
+        (None, Some(lang)) => {
+            debug!("script language={}", lang);
+            let language = format!("text/{}", lang);
+
+            if SCRIPT_JS_MIMES.contains(&language.to_ascii_lowercase().as_str()) {
+                Some(ScriptType::Classic)
+            } else {
+                None
+            }
+        },
+        (Some(ty), _) => {
+            debug!("script type={}", ty);
+
+            let ty = ty.to_ascii_lowercase();
+            let ty = ty.trim_matches(HTML_SPACE_CHARACTERS);
+            if ty == "module" {
+                return Some(ScriptType::Module);
+            }
+
+            if ty == "importmap" {
+                return Some(ScriptType::ImportMap);
+            }
+
+            if ty == "speculationrules" && pref!(dom_speculation_rules_enabled) {
+                return Some(ScriptType::SpeculationRules);
+            }
+
+            if SCRIPT_JS_MIMES.contains(&ty) {
+                Some(ScriptType::Classic)
+            } else {
+                None
+            }
+        },
+    }
 }
 
 #[derive(JSTraceable, MallocSizeOf)]
@@ -355,6 +417,12 @@ impl FetchResponseListener for ClassicContext {
     fn process_request_eof(&mut self, _: RequestId) {}
 
     fn process_response(&mut self, _: RequestId, metadata: Result<FetchMetadata, NetworkError>) {
+        if let Err(error) = &metadata {
+            self.elem
+                .root()
+                .owner_global()
+                .report_subresource_integrity_failure(&self.url, error);
+        }
         self.metadata = metadata.ok().map(|meta| match meta {
             FetchMetadata::Unfiltered(m) => m,
             FetchMetadata::Filtered { unsafe_, .. } => unsafe_,
@@ -548,6 +616,7 @@ pub(crate) fn script_fetch_request(
     .parser_metadata(options.parser_metadata)
     .integrity_metadata(options.integrity_metadata.clone())
     .referrer_policy(options.referrer_policy)
+    .priority(options.fetch_priority)
     .cryptographic_nonce_metadata(options.cryptographic_nonce)
 }
 
@@ -751,7 +820,9 @@ impl HTMLScriptElement {
 
         // Step 23. Module script credentials mode.
         let module_credentials_mode = match script_type {
-            ScriptType::Classic => CredentialsMode::CredentialsSameOrigin,
+            ScriptType::Classic | ScriptType::SpeculationRules => {
+                CredentialsMode::CredentialsSameOrigin
+            },
             ScriptType::Module | ScriptType::ImportMap => reflect_cross_origin_attribute(element)
                 .map_or(
                     CredentialsMode::CredentialsSameOrigin,
@@ -778,7 +849,8 @@ impl HTMLScriptElement {
         // Step 26. Let referrer policy be the current state of el's referrerpolicy content attribute.
         let referrer_policy = referrer_policy_for_element(self.upcast::<Element>());
 
-        // TODO: Step 27. Fetch priority.
+        // Step 27. Let fetch priority be the current state of el's fetchpriority content attribute.
+        let fetch_priority = fetch_priority_for_element(self.upcast::<Element>());
 
         // Step 28. Let parser metadata be "parser-inserted" if el is parser-inserted,
         // and "not-parser-inserted" otherwise.
@@ -796,6 +868,7 @@ impl HTMLScriptElement {
             referrer: self.global().get_referrer(),
             referrer_policy,
             credentials_mode: module_credentials_mode,
+            fetch_priority,
         };
 
         // Step 30. Let settings object be el's node document's relevant settings object.
@@ -806,8 +879,11 @@ impl HTMLScriptElement {
         if let Some(src) = element.get_attribute(&ns!(), &local_name!("src")) {
             // Step 31. If el has a src content attribute, then:
 
-            // Step 31.1. If el's type is "importmap".
-            if script_type == ScriptType::ImportMap {
+            // Step 31.1. If el's type is "importmap" or "speculationrules".
+            if matches!(
+                script_type,
+                ScriptType::ImportMap | ScriptType::SpeculationRules
+            ) {
                 // then queue an element task on the DOM manipulation task source
                 // given el to fire an event named error at el, and return.
                 self.queue_error_event();
@@ -895,7 +971,7 @@ impl HTMLScriptElement {
                         doc.add_asap_script(self);
                     };
                 },
-                ScriptType::ImportMap => (),
+                ScriptType::ImportMap | ScriptType::SpeculationRules => (),
             }
         } else {
             // Step 32. If el does not have a src content attribute:
@@ -970,6 +1046,20 @@ impl HTMLScriptElement {
                     // Step 34.3
                     self.execute(result, can_gc);
                 },
+                ScriptType::SpeculationRules => {
+                    // Let result be the result of parsing a speculation rule set string given
+                    // source text, el's node document, and el's node document's document base
+                    // URL.
+                    match parse_a_speculation_rule_set_string(text_rc.str(), &doc, &base_url) {
+                        // Register the speculation rules, which are never executed as a
+                        // script.
+                        Ok(rule_set) => doc.add_speculation_rule_set(rule_set),
+                        Err(message) => Console::internal_warn(
+                            global,
+                            DOMString::from(format!("Invalid speculation rules: {}", message)),
+                        ),
+                    }
+                },
             }
         }
     }
@@ -1124,6 +1214,7 @@ impl HTMLScriptElement {
                 // Step 6.1 Register an import map given el's relevant global object and el's result.
                 register_import_map(&self.owner_global(), script.import_map, can_gc);
             },
+            ScriptType::SpeculationRules => unreachable!("Speculation rules are never executed"),
         }
 
         // Step 7.
@@ -1266,55 +1357,10 @@ impl HTMLScriptElement {
         let type_attr = element.get_attribute(&ns!(), &local_name!("type"));
         let language_attr = element.get_attribute(&ns!(), &local_name!("language"));
 
-        let script_type = match (
-            type_attr.as_ref().map(|t| t.value()),
-            language_attr.as_ref().map(|l| l.value()),
-        ) {
-            (Some(ref ty), _) if ty.is_empty() => {
-                debug!("script type empty, inferring js");
-                Some(ScriptType::Classic)
-            },
-            (None, Some(ref lang)) if lang.is_empty() => {
-                debug!("script type empty, inferring js");
-                Some(ScriptType::Classic)
-            },
-            (None, None) => {
-                debug!("script type empty, inferring js");
-                Some(ScriptType::Classic)
-            },
-            (None, Some(ref lang)) => {
-                debug!("script language={}", &***lang);
-                let language = format!("text/{}", &***lang);
-
-                if SCRIPT_JS_MIMES.contains(&language.to_ascii_lowercase().as_str()) {
-                    Some(ScriptType::Classic)
-                } else {
-                    None
-                }
-            },
-            (Some(ref ty), _) => {
-                debug!("script type={}", &***ty);
-
-                if ty.to_ascii_lowercase().trim_matches(HTML_SPACE_CHARACTERS) == "module" {
-                    return Some(ScriptType::Module);
-                }
-
-                if ty.to_ascii_lowercase().trim_matches(HTML_SPACE_CHARACTERS) == "importmap" {
-                    return Some(ScriptType::ImportMap);
-                }
-
-                if SCRIPT_JS_MIMES
-                    .contains(&ty.to_ascii_lowercase().trim_matches(HTML_SPACE_CHARACTERS))
-                {
-                    Some(ScriptType::Classic)
-                } else {
-                    None
-                }
-            },
-        };
-
-        // https://github.com/rust-lang/rust/issues/21114
-        script_type
+        script_type_from_attributes(
+            type_attr.as_ref().map(|t| &**t.value()),
+            language_attr.as_ref().map(|l| &**l.value()),
+        )
     }
 
     pub(crate) fn set_parser_inserted(&self, parser_inserted: bool) {
@@ -1516,6 +1562,16 @@ impl HTMLScriptElementMethods<crate::DomTypeHolder> for HTMLScriptElement {
     // https://html.spec.whatwg.org/multipage/#dom-script-referrerpolicy
     make_setter!(SetReferrerPolicy, "referrerpolicy");
 
+    /// <https://html.spec.whatwg.org/multipage/#dom-script-fetchpriority>
+    fn FetchPriority(&self) -> DOMString {
+        reflect_fetch_priority_attribute(self.upcast::<Element>())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-script-fetchpriority>
+    fn SetFetchPriority(&self, value: DOMString, can_gc: CanGc) {
+        set_fetch_priority_attribute(self.upcast::<Element>(), value, can_gc);
+    }
+
     /// <https://w3c.github.io/trusted-types/dist/spec/#dom-htmlscriptelement-innertext>
     fn InnerText(&self) -> TrustedScriptOrString {
         // Step 1: Return the result of running get the text steps with this.
@@ -1592,7 +1648,11 @@ impl HTMLScriptElementMethods<crate::DomTypeHolder> for HTMLScriptElement {
     fn Supports(_window: &Window, type_: DOMString) -> bool {
         // The type argument has to exactly match these values,
         // we do not perform an ASCII case-insensitive match.
-        matches!(type_.str(), "classic" | "module" | "importmap")
+        match type_.str() {
+            "classic" | "module" | "importmap" => true,
+            "speculationrules" => pref!(dom_speculation_rules_enabled),
+            _ => false,
+        }
     }
 }
 
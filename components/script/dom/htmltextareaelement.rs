@@ -27,11 +27,12 @@ use crate::dom::clipboardevent::ClipboardEvent;
 use crate::dom::compositionevent::CompositionEvent;
 use crate::dom::document::Document;
 use crate::dom::element::{AttributeMutation, Element, LayoutElementHelpers};
-use crate::dom::event::{Event, EventBubbles, EventCancelable};
+use crate::dom::event::Event;
 use crate::dom::htmlelement::HTMLElement;
 use crate::dom::htmlfieldsetelement::HTMLFieldSetElement;
 use crate::dom::htmlformelement::{FormControl, HTMLFormElement};
 use crate::dom::htmlinputelement::HTMLInputElement;
+use crate::dom::inputevent::{InputEvent, InputEventType};
 use crate::dom::keyboardevent::KeyboardEvent;
 use crate::dom::node::{
     BindContext, ChildrenMutation, CloneChildrenFlag, Node, NodeDamage, NodeTraits, UnbindContext,
@@ -644,22 +645,31 @@ impl VirtualMethods for HTMLTextAreaElement {
             //TODO: set the editing position for text inputs
         } else if event.type_() == atom!("keydown") && !event.DefaultPrevented() {
             if let Some(kevent) = event.downcast::<KeyboardEvent>() {
+                let input_event = self.textinput.borrow().input_event_for_keydown(kevent);
+                if let Some((input_type, data)) = input_event.clone() {
+                    if event.IsTrusted() &&
+                        !InputEvent::dispatch_beforeinput(
+                            self.upcast(),
+                            input_type,
+                            data,
+                            &[],
+                            can_gc,
+                        )
+                    {
+                        event.PreventDefault();
+                        return;
+                    }
+                }
                 // This can't be inlined, as holding on to textinput.borrow_mut()
                 // during self.implicit_submission will cause a panic.
                 let action = self.textinput.borrow_mut().handle_keydown(kevent);
                 match action {
                     KeyReaction::TriggerDefaultAction => (),
                     KeyReaction::DispatchInput => {
-                        if event.IsTrusted() {
-                            self.owner_global()
-                                .task_manager()
-                                .user_interaction_task_source()
-                                .queue_event(
-                                    self.upcast(),
-                                    atom!("input"),
-                                    EventBubbles::Bubbles,
-                                    EventCancelable::NotCancelable,
-                                );
+                        if let Some((input_type, data)) = input_event {
+                            if event.IsTrusted() {
+                                InputEvent::queue_input(self.upcast(), input_type, data);
+                            }
                         }
                         self.value_dirty.set(true);
                         self.update_placeholder_shown_state();
@@ -683,16 +693,40 @@ impl VirtualMethods for HTMLTextAreaElement {
         {
             if let Some(compositionevent) = event.downcast::<CompositionEvent>() {
                 if event.type_() == atom!("compositionend") {
+                    InputEvent::dispatch_beforeinput(
+                        self.upcast(),
+                        InputEventType::InsertCompositionText,
+                        Some(DOMString::from(compositionevent.data())),
+                        &[],
+                        can_gc,
+                    );
                     let _ = self
                         .textinput
                         .borrow_mut()
                         .handle_compositionend(compositionevent);
+                    InputEvent::queue_input(
+                        self.upcast(),
+                        InputEventType::InsertCompositionText,
+                        Some(DOMString::from(compositionevent.data())),
+                    );
                     self.upcast::<Node>().dirty(NodeDamage::Other);
                 } else if event.type_() == atom!("compositionupdate") {
+                    InputEvent::dispatch_beforeinput(
+                        self.upcast(),
+                        InputEventType::InsertCompositionText,
+                        Some(DOMString::from(compositionevent.data())),
+                        &[],
+                        can_gc,
+                    );
                     let _ = self
                         .textinput
                         .borrow_mut()
                         .handle_compositionupdate(compositionevent);
+                    InputEvent::queue_input(
+                        self.upcast(),
+                        InputEventType::InsertCompositionText,
+                        Some(DOMString::from(compositionevent.data())),
+                    );
                     self.upcast::<Node>().dirty(NodeDamage::Other);
                 }
                 event.mark_as_handled();
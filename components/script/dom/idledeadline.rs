@@ -0,0 +1,68 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::time::Instant;
+
+use dom_struct::dom_struct;
+
+use crate::dom::bindings::codegen::Bindings::IdleDeadlineBinding::IdleDeadlineMethods;
+use crate::dom::bindings::num::Finite;
+use crate::dom::bindings::reflector::{Reflector, reflect_dom_object};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::window::Window;
+use crate::script_runtime::CanGc;
+
+/// <https://w3c.github.io/requestidlecallback/#the-idledeadline-interface>
+#[dom_struct]
+pub(crate) struct IdleDeadline {
+    reflector_: Reflector,
+    /// The end of the idle period the callback runs in, standing in for the
+    /// "get deadline time" algorithm.
+    deadline: Instant,
+    /// <https://w3c.github.io/requestidlecallback/#dfn-timeout>
+    did_timeout: bool,
+}
+
+impl IdleDeadline {
+    fn new_inherited(deadline: Instant, did_timeout: bool) -> IdleDeadline {
+        IdleDeadline {
+            reflector_: Reflector::new(),
+            deadline,
+            did_timeout,
+        }
+    }
+
+    pub(crate) fn new(
+        window: &Window,
+        deadline: Instant,
+        did_timeout: bool,
+        can_gc: CanGc,
+    ) -> DomRoot<IdleDeadline> {
+        reflect_dom_object(
+            Box::new(IdleDeadline::new_inherited(deadline, did_timeout)),
+            window,
+            can_gc,
+        )
+    }
+}
+
+impl IdleDeadlineMethods<crate::DomTypeHolder> for IdleDeadline {
+    /// <https://w3c.github.io/requestidlecallback/#dom-idledeadline-timeremaining>
+    fn TimeRemaining(&self) -> Finite<f64> {
+        // Step 1. Let now be a DOMHighResTimeStamp representing current high resolution time
+        // in milliseconds.
+        // Step 2. Let deadline be the result of calling IdleDeadline's get deadline time algorithm.
+        // Step 3. Let timeRemaining be deadline - now.
+        // Step 4. If timeRemaining is negative, set it to 0.
+        let time_remaining = self.deadline.saturating_duration_since(Instant::now());
+
+        // Step 5. Return timeRemaining.
+        Finite::wrap(time_remaining.as_secs_f64() * 1000.)
+    }
+
+    /// <https://w3c.github.io/requestidlecallback/#dom-idledeadline-didtimeout>
+    fn DidTimeout(&self) -> bool {
+        self.did_timeout
+    }
+}
@@ -8,27 +8,78 @@ use euclid::Point2D;
 use js::rust::HandleObject;
 use style_traits::CSSPixel;
 
+use crate::dom::bindings::codegen::Bindings::EventBinding::Event_Binding::EventMethods;
 use crate::dom::bindings::codegen::Bindings::InputEventBinding::{self, InputEventMethods};
 use crate::dom::bindings::codegen::Bindings::UIEventBinding::UIEvent_Binding::UIEventMethods;
 use crate::dom::bindings::error::Fallible;
-use crate::dom::bindings::reflector::reflect_dom_object_with_proto;
-use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::refcounted::Trusted;
+use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object_with_proto};
+use crate::dom::bindings::root::{Dom, DomRoot};
 use crate::dom::bindings::str::DOMString;
+use crate::dom::event::Event;
+use crate::dom::eventtarget::EventTarget;
 use crate::dom::node::Node;
+use crate::dom::staticrange::StaticRange;
 use crate::dom::uievent::UIEvent;
 use crate::dom::window::Window;
 use crate::script_runtime::CanGc;
 
+/// The kind of edit described by the `inputType` attribute of an [`InputEvent`].
+///
+/// <https://w3c.github.io/input-events/#interface-InputEvent-Attributes>
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum InputEventType {
+    InsertText,
+    InsertLineBreak,
+    InsertParagraph,
+    InsertFromPaste,
+    InsertFromDrop,
+    InsertCompositionText,
+    DeleteContentBackward,
+    DeleteContentForward,
+    DeleteByCut,
+    FormatBold,
+    FormatItalic,
+}
+
+impl InputEventType {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            InputEventType::InsertText => "insertText",
+            InputEventType::InsertLineBreak => "insertLineBreak",
+            InputEventType::InsertParagraph => "insertParagraph",
+            InputEventType::InsertFromPaste => "insertFromPaste",
+            InputEventType::InsertFromDrop => "insertFromDrop",
+            InputEventType::InsertCompositionText => "insertCompositionText",
+            InputEventType::DeleteContentBackward => "deleteContentBackward",
+            InputEventType::DeleteContentForward => "deleteContentForward",
+            InputEventType::DeleteByCut => "deleteByCut",
+            InputEventType::FormatBold => "formatBold",
+            InputEventType::FormatItalic => "formatItalic",
+        }
+    }
+
+    /// Whether a `beforeinput` event for this kind of edit can be canceled.
+    ///
+    /// <https://w3c.github.io/input-events/#event-type-beforeinput>
+    fn is_cancelable(&self) -> bool {
+        *self != InputEventType::InsertCompositionText
+    }
+}
+
 #[dom_struct]
 pub(crate) struct InputEvent {
     uievent: UIEvent,
     data: Option<DOMString>,
     is_composing: bool,
+    input_type: DOMString,
+    target_ranges: Vec<Dom<StaticRange>>,
 }
 
 impl InputEvent {
     #[allow(clippy::too_many_arguments)]
-    fn new(
+    pub(crate) fn new(
         window: &Window,
         proto: Option<HandleObject>,
         type_: DOMString,
@@ -38,6 +89,8 @@ impl InputEvent {
         detail: i32,
         data: Option<DOMString>,
         is_composing: bool,
+        input_type: DOMString,
+        target_ranges: &[DomRoot<StaticRange>],
         can_gc: CanGc,
     ) -> DomRoot<InputEvent> {
         let ev = reflect_dom_object_with_proto(
@@ -45,6 +98,11 @@ impl InputEvent {
                 uievent: UIEvent::new_inherited(),
                 data,
                 is_composing,
+                input_type,
+                target_ranges: target_ranges
+                    .iter()
+                    .map(|range| Dom::from_ref(&**range))
+                    .collect(),
             }),
             window,
             proto,
@@ -54,6 +112,87 @@ impl InputEvent {
             .InitUIEvent(type_, can_bubble, cancelable, view, detail);
         ev
     }
+
+    /// Fire a trusted `beforeinput` event at `target` for an edit that is about to happen.
+    /// Returns false if the edit was canceled by script.
+    ///
+    /// <https://w3c.github.io/input-events/#event-type-beforeinput>
+    pub(crate) fn dispatch_beforeinput(
+        target: &EventTarget,
+        input_type: InputEventType,
+        data: Option<DOMString>,
+        target_ranges: &[DomRoot<StaticRange>],
+        can_gc: CanGc,
+    ) -> bool {
+        let window = target.global();
+        let window = window.as_window();
+        let event = InputEvent::new(
+            window,
+            None,
+            DOMString::from("beforeinput"),
+            true,
+            input_type.is_cancelable(),
+            Some(window),
+            0,
+            data,
+            input_type == InputEventType::InsertCompositionText,
+            DOMString::from(input_type.as_str()),
+            target_ranges,
+            can_gc,
+        );
+        let event = event.upcast::<Event>();
+        event.set_trusted(true);
+        event.fire(target, can_gc);
+        !event.DefaultPrevented()
+    }
+
+    /// Fire a trusted `input` event at `target` after an edit has happened.
+    ///
+    /// <https://w3c.github.io/input-events/#event-type-input>
+    pub(crate) fn dispatch_input(
+        target: &EventTarget,
+        input_type: InputEventType,
+        data: Option<DOMString>,
+        can_gc: CanGc,
+    ) {
+        let window = target.global();
+        let window = window.as_window();
+        let event = InputEvent::new(
+            window,
+            None,
+            DOMString::from("input"),
+            true,
+            false,
+            Some(window),
+            0,
+            data,
+            input_type == InputEventType::InsertCompositionText,
+            DOMString::from(input_type.as_str()),
+            &[],
+            can_gc,
+        );
+        let event = event.upcast::<Event>();
+        event.set_trusted(true);
+        event.fire(target, can_gc);
+    }
+
+    /// Queue a task on the user interaction task source to fire an `input` event at
+    /// `target`, as text controls do after their value was changed by the user.
+    pub(crate) fn queue_input(
+        target: &EventTarget,
+        input_type: InputEventType,
+        data: Option<DOMString>,
+    ) {
+        let trusted_target = Trusted::new(target);
+        target
+            .global()
+            .task_manager()
+            .user_interaction_task_source()
+            .queue(task!(fire_input_event: move || {
+                let target = trusted_target.root();
+                InputEvent::dispatch_input(&target, input_type, data, CanGc::note());
+            }));
+    }
 }
 
 impl InputEventMethods<crate::DomTypeHolder> for InputEvent {
@@ -75,6 +214,8 @@ impl InputEventMethods<crate::DomTypeHolder> for InputEvent {
             init.parent.detail,
             init.data.clone(),
             init.isComposing,
+            init.inputType.clone(),
+            &init.targetRanges,
             can_gc,
         );
         Ok(event)
@@ -90,6 +231,19 @@ impl InputEventMethods<crate::DomTypeHolder> for InputEvent {
         self.is_composing
     }
 
+    /// <https://w3c.github.io/input-events/#dom-inputevent-inputtype>
+    fn InputType(&self) -> DOMString {
+        self.input_type.clone()
+    }
+
+    /// <https://w3c.github.io/input-events/#dom-inputevent-gettargetranges>
+    fn GetTargetRanges(&self) -> Vec<DomRoot<StaticRange>> {
+        self.target_ranges
+            .iter()
+            .map(|range| DomRoot::from_ref(&**range))
+            .collect()
+    }
+
     // https://dom.spec.whatwg.org/#dom-event-istrusted
     fn IsTrusted(&self) -> bool {
         self.uievent.IsTrusted()
@@ -24,6 +24,7 @@ use crate::dom::bindings::cell::DomRefCell;
 use crate::dom::bindings::codegen::Bindings::IntersectionObserverBinding::{
     IntersectionObserverCallback, IntersectionObserverInit, IntersectionObserverMethods,
 };
+use crate::dom::bindings::codegen::Bindings::IntersectionObserverEntryBinding::IntersectionObserverEntryMethods;
 use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
 use crate::dom::bindings::codegen::UnionTypes::{DoubleOrDoubleSequence, ElementOrDocument};
 use crate::dom::bindings::error::{Error, Fallible};
@@ -36,6 +37,8 @@ use crate::dom::bindings::utils::to_frozen_array;
 use crate::dom::document::Document;
 use crate::dom::domrectreadonly::DOMRectReadOnly;
 use crate::dom::element::Element;
+use crate::dom::htmliframeelement::HTMLIFrameElement;
+use crate::dom::htmlimageelement::HTMLImageElement;
 use crate::dom::intersectionobserverentry::IntersectionObserverEntry;
 use crate::dom::node::{Node, NodeTraits};
 use crate::dom::window::Window;
@@ -47,6 +50,25 @@ use crate::script_runtime::{CanGc, JSContext};
 /// <https://w3c.github.io/IntersectionObserver/#intersectionobserver-intersection-root>
 pub type IntersectionRoot = Option<ElementOrDocument>;
 
+/// The implementation-defined margin used by the [lazy load intersection observer], in CSS
+/// pixels. Resources start loading once they are within this distance of the viewport.
+///
+/// [lazy load intersection observer]: https://html.spec.whatwg.org/multipage/#lazy-load-intersection-observer
+const LAZY_LOAD_ROOT_MARGIN: &str = "1250px";
+
+/// The callback of an [`IntersectionObserver`], which is either provided by script or is one
+/// of the internal observers used by the user agent.
+#[derive(JSTraceable)]
+pub(crate) enum IntersectionObserverCallbackType {
+    /// A callback provided to the `IntersectionObserver` constructor.
+    Script(Rc<IntersectionObserverCallback>),
+    /// The callback of the [lazy load intersection observer], which resumes the loads of
+    /// lazily loaded elements once they intersect the viewport.
+    ///
+    /// [lazy load intersection observer]: https://html.spec.whatwg.org/multipage/#lazy-load-intersection-observer
+    LazyLoad,
+}
+
 /// The Intersection Observer interface
 ///
 /// > The IntersectionObserver interface can be used to observe changes in the intersection
@@ -71,7 +93,7 @@ pub(crate) struct IntersectionObserver {
     ///
     /// <https://w3c.github.io/IntersectionObserver/#intersection-observer-callback>
     #[ignore_malloc_size_of = "Rc are hard"]
-    callback: Rc<IntersectionObserverCallback>,
+    callback: IntersectionObserverCallbackType,
 
     /// <https://w3c.github.io/IntersectionObserver/#dom-intersectionobserver-queuedentries-slot>
     queued_entries: DomRefCell<Vec<Dom<IntersectionObserverEntry>>>,
@@ -102,7 +124,7 @@ pub(crate) struct IntersectionObserver {
 impl IntersectionObserver {
     fn new_inherited(
         window: &Window,
-        callback: Rc<IntersectionObserverCallback>,
+        callback: IntersectionObserverCallbackType,
         root: IntersectionRoot,
         root_margin: IntersectionObserverMargin,
         scroll_margin: IntersectionObserverMargin,
@@ -156,7 +178,7 @@ impl IntersectionObserver {
         let observer = reflect_dom_object_with_proto(
             Box::new(Self::new_inherited(
                 window,
-                callback,
+                IntersectionObserverCallbackType::Script(callback),
                 init.root.clone(),
                 root_margin,
                 scroll_margin,
@@ -172,6 +194,34 @@ impl IntersectionObserver {
         Ok(observer)
     }
 
+    /// Create the [lazy load intersection observer] of a [`Document`]. It uses the implicit root,
+    /// a threshold of zero and an implementation-defined root margin.
+    ///
+    /// [lazy load intersection observer]: https://html.spec.whatwg.org/multipage/#lazy-load-intersection-observer
+    pub(crate) fn new_lazy_load_intersection_observer(
+        window: &Window,
+        can_gc: CanGc,
+    ) -> DomRoot<Self> {
+        let root_margin = parse_a_margin(Some(&DOMString::from(LAZY_LOAD_ROOT_MARGIN)))
+            .expect("Lazy load root margin should be a valid margin");
+        let scroll_margin =
+            parse_a_margin(None).expect("Default scroll margin should be a valid margin");
+        let observer = reflect_dom_object_with_proto(
+            Box::new(Self::new_inherited(
+                window,
+                IntersectionObserverCallbackType::LazyLoad,
+                None,
+                root_margin,
+                scroll_margin,
+            )),
+            window,
+            None,
+            can_gc,
+        );
+        *observer.thresholds.borrow_mut() = vec![Finite::wrap(0.)];
+        observer
+    }
+
     /// Step 5-13 of <https://w3c.github.io/IntersectionObserver/#initialize-new-intersection-observer>
     fn init_observer(&self, init: &IntersectionObserverInit) -> Fallible<()> {
         // Step 5
@@ -253,7 +303,7 @@ impl IntersectionObserver {
     }
 
     /// <https://w3c.github.io/IntersectionObserver/#observe-target-element>
-    fn observe_target_element(&self, target: &Element) {
+    pub(crate) fn observe_target_element(&self, target: &Element) {
         // Step 1
         // > If target is in observer’s internal [[ObservationTargets]] slot, return.
         let is_present = self
@@ -285,7 +335,7 @@ impl IntersectionObserver {
     }
 
     /// <https://w3c.github.io/IntersectionObserver/#unobserve-target-element>
-    fn unobserve_target_element(&self, target: &Element) {
+    pub(crate) fn unobserve_target_element(&self, target: &Element) {
         // Step 1
         // > Remove the IntersectionObserverRegistration record whose observer property is equal to
         // > this from target’s internal [[RegisteredIntersectionObservers]] slot, if present.
@@ -374,7 +424,7 @@ impl IntersectionObserver {
 
         // Step 2-3
         // We trivially moved the entries and root them.
-        let queued_entries = self
+        let queued_entries: Vec<DomRoot<IntersectionObserverEntry>> = self
             .queued_entries
             .take()
             .iter_mut()
@@ -382,13 +432,52 @@ impl IntersectionObserver {
             .collect();
 
         // Step 4-5
-        let _ = self.callback.Call_(
-            self,
-            queued_entries,
-            self,
-            ExceptionHandling::Report,
-            can_gc,
-        );
+        match &self.callback {
+            IntersectionObserverCallbackType::Script(callback) => {
+                let _ = callback.Call_(
+                    self,
+                    queued_entries,
+                    self,
+                    ExceptionHandling::Report,
+                    can_gc,
+                );
+            },
+            IntersectionObserverCallbackType::LazyLoad => {
+                self.run_lazy_load_intersection_observer_callback(queued_entries, can_gc)
+            },
+        }
+    }
+
+    /// The callback of the [lazy load intersection observer].
+    ///
+    /// [lazy load intersection observer]: https://html.spec.whatwg.org/multipage/#lazy-load-intersection-observer
+    fn run_lazy_load_intersection_observer_callback(
+        &self,
+        entries: Vec<DomRoot<IntersectionObserverEntry>>,
+        can_gc: CanGc,
+    ) {
+        // > For each entry in entries:
+        for entry in entries {
+            // > 1. Let resumptionSteps be null.
+            // > 2. If entry.isIntersecting is true, then set resumptionSteps to
+            // >    entry.target's lazy load resumption steps.
+            // > 3. If resumptionSteps is null, then return.
+            if !entry.IsIntersecting() {
+                continue;
+            }
+            let target = entry.Target();
+
+            // > 4. Stop intersection-observing a lazy loading element for entry.target.
+            self.unobserve_target_element(&target);
+
+            // > 5. Set entry.target's lazy load resumption steps to null.
+            // > 6. Invoke resumptionSteps.
+            if let Some(image) = target.downcast::<HTMLImageElement>() {
+                image.resume_lazy_load(can_gc);
+            } else if let Some(iframe) = target.downcast::<HTMLIFrameElement>() {
+                iframe.resume_lazy_load(can_gc);
+            }
+        }
     }
 
     /// Connect the observer itself into owner doc if it is unconnected.
@@ -0,0 +1,78 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use script_bindings::str::DOMString;
+
+use crate::dom::bindings::codegen::Bindings::InterventionReportBodyBinding::InterventionReportBodyMethods;
+use crate::dom::bindings::reflector::{Reflector, reflect_dom_object};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::csp::SourcePosition;
+use crate::dom::globalscope::GlobalScope;
+use crate::script_runtime::CanGc;
+
+/// <https://wicg.github.io/intervention-reporting/#intervention-report>
+#[dom_struct]
+pub(crate) struct InterventionReportBody {
+    reflector_: Reflector,
+    id: DOMString,
+    message: DOMString,
+    source_file: Option<DOMString>,
+    line_number: Option<u32>,
+    column_number: Option<u32>,
+}
+
+impl InterventionReportBody {
+    fn new_inherited(id: DOMString, message: DOMString, source_position: SourcePosition) -> Self {
+        Self {
+            reflector_: Reflector::new(),
+            id,
+            message,
+            source_file: Some(source_position.source_file.into()),
+            line_number: Some(source_position.line_number),
+            column_number: Some(source_position.column_number),
+        }
+    }
+
+    pub(crate) fn new(
+        global: &GlobalScope,
+        id: DOMString,
+        message: DOMString,
+        source_position: SourcePosition,
+        can_gc: CanGc,
+    ) -> DomRoot<Self> {
+        reflect_dom_object(
+            Box::new(Self::new_inherited(id, message, source_position)),
+            global,
+            can_gc,
+        )
+    }
+}
+
+impl InterventionReportBodyMethods<crate::DomTypeHolder> for InterventionReportBody {
+    /// <https://wicg.github.io/intervention-reporting/#dom-interventionreportbody-id>
+    fn Id(&self) -> DOMString {
+        self.id.clone()
+    }
+
+    /// <https://wicg.github.io/intervention-reporting/#dom-interventionreportbody-message>
+    fn Message(&self) -> DOMString {
+        self.message.clone()
+    }
+
+    /// <https://wicg.github.io/intervention-reporting/#dom-interventionreportbody-sourcefile>
+    fn GetSourceFile(&self) -> Option<DOMString> {
+        self.source_file.clone()
+    }
+
+    /// <https://wicg.github.io/intervention-reporting/#dom-interventionreportbody-linenumber>
+    fn GetLineNumber(&self) -> Option<u32> {
+        self.line_number
+    }
+
+    /// <https://wicg.github.io/intervention-reporting/#dom-interventionreportbody-columnnumber>
+    fn GetColumnNumber(&self) -> Option<u32> {
+        self.column_number
+    }
+}
@@ -0,0 +1,322 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::cell::Cell;
+
+use app_units::Au;
+use base::Epoch;
+use base::cross_process_instant::CrossProcessInstant;
+use dom_struct::dom_struct;
+use euclid::default::Rect;
+use html5ever::LocalName;
+use layout_api::ContentfulPaint;
+use servo_config::pref;
+use time::Duration;
+
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::ElementBinding::ElementMethods;
+use crate::dom::bindings::codegen::Bindings::HTMLImageElementBinding::HTMLImageElementMethods;
+use crate::dom::bindings::codegen::Bindings::LargestContentfulPaintBinding::LargestContentfulPaintMethods;
+use crate::dom::bindings::codegen::Bindings::NodeBinding::NodeMethods;
+use crate::dom::bindings::codegen::Bindings::PerformanceBinding::DOMHighResTimeStamp;
+use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::element::Element;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::htmlimageelement::HTMLImageElement;
+use crate::dom::node::{Node, from_untrusted_node_address};
+use crate::dom::performanceelementtiming::PerformanceElementTiming;
+use crate::dom::performanceentry::PerformanceEntry;
+use crate::dom::window::Window;
+use crate::script_runtime::CanGc;
+
+/// <https://w3c.github.io/largest-contentful-paint/#sec-largest-contentful-paint-interface>
+#[dom_struct]
+pub(crate) struct LargestContentfulPaint {
+    entry: PerformanceEntry,
+    /// <https://w3c.github.io/largest-contentful-paint/#dom-largestcontentfulpaint-rendertime>
+    #[no_trace]
+    render_time: CrossProcessInstant,
+    /// <https://w3c.github.io/largest-contentful-paint/#dom-largestcontentfulpaint-loadtime>
+    #[no_trace]
+    load_time: Option<CrossProcessInstant>,
+    /// <https://w3c.github.io/largest-contentful-paint/#dom-largestcontentfulpaint-size>
+    size: u32,
+    /// <https://w3c.github.io/largest-contentful-paint/#dom-largestcontentfulpaint-id>
+    id: DOMString,
+    /// <https://w3c.github.io/largest-contentful-paint/#dom-largestcontentfulpaint-url>
+    url: DOMString,
+    /// <https://w3c.github.io/largest-contentful-paint/#dom-largestcontentfulpaint-element>
+    element: Dom<Element>,
+}
+
+impl LargestContentfulPaint {
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    fn new_inherited(
+        content: &PaintedContent,
+        render_time: CrossProcessInstant,
+        size: u32,
+    ) -> LargestContentfulPaint {
+        LargestContentfulPaint {
+            entry: PerformanceEntry::new_inherited(
+                DOMString::new(),
+                DOMString::from("largest-contentful-paint"),
+                Some(render_time),
+                Duration::ZERO,
+            ),
+            render_time,
+            load_time: content.load_time,
+            size,
+            id: content.element.Id(),
+            url: content.url.clone().unwrap_or_default(),
+            element: Dom::from_ref(&*content.element),
+        }
+    }
+
+    fn new(
+        global: &GlobalScope,
+        content: &PaintedContent,
+        render_time: CrossProcessInstant,
+        size: u32,
+        can_gc: CanGc,
+    ) -> DomRoot<LargestContentfulPaint> {
+        let entry = LargestContentfulPaint::new_inherited(content, render_time, size);
+        reflect_dom_object(Box::new(entry), global, can_gc)
+    }
+}
+
+impl LargestContentfulPaintMethods<crate::DomTypeHolder> for LargestContentfulPaint {
+    /// <https://w3c.github.io/largest-contentful-paint/#dom-largestcontentfulpaint-rendertime>
+    fn RenderTime(&self) -> DOMHighResTimeStamp {
+        self.global()
+            .performance()
+            .to_dom_high_res_time_stamp(self.render_time)
+    }
+
+    /// <https://w3c.github.io/largest-contentful-paint/#dom-largestcontentfulpaint-loadtime>
+    fn LoadTime(&self) -> DOMHighResTimeStamp {
+        self.global()
+            .performance()
+            .maybe_to_dom_high_res_time_stamp(self.load_time)
+    }
+
+    /// <https://w3c.github.io/largest-contentful-paint/#dom-largestcontentfulpaint-size>
+    fn Size(&self) -> u32 {
+        self.size
+    }
+
+    /// <https://w3c.github.io/largest-contentful-paint/#dom-largestcontentfulpaint-id>
+    fn Id(&self) -> DOMString {
+        self.id.clone()
+    }
+
+    /// <https://w3c.github.io/largest-contentful-paint/#dom-largestcontentfulpaint-url>
+    fn Url(&self) -> DOMString {
+        self.url.clone()
+    }
+
+    /// <https://w3c.github.io/largest-contentful-paint/#dom-largestcontentfulpaint-element>
+    fn GetElement(&self) -> Option<DomRoot<Element>> {
+        // Elements that have been removed from their document are not exposed.
+        Some(self.element.as_rooted()).filter(|element| element.upcast::<Node>().is_connected())
+    }
+}
+
+/// The content of an element that was painted in a presented frame.
+pub(crate) struct PaintedContent {
+    pub(crate) element: DomRoot<Element>,
+    /// The URL of the painted image, or `None` if the content is text.
+    pub(crate) url: Option<DOMString>,
+    /// The area covered by the painted content.
+    pub(crate) rect: Rect<Au>,
+    /// The time at which the image was loaded, or `None` if the content is text.
+    pub(crate) load_time: Option<CrossProcessInstant>,
+    /// The natural width and height of the painted image, if any.
+    pub(crate) natural_size: (u32, u32),
+}
+
+/// Content of an element that was painted in a display list, waiting for the frame
+/// containing that display list to be presented before being reported.
+#[derive(JSTraceable, MallocSizeOf)]
+#[cfg_attr(crown, crown::unrooted_must_root_lint::must_root)]
+struct PendingContentfulPaint {
+    element: Dom<Element>,
+    url: Option<DOMString>,
+    #[no_trace]
+    rect: Rect<Au>,
+    #[no_trace]
+    load_time: Option<CrossProcessInstant>,
+    #[no_trace]
+    #[ignore_malloc_size_of = "Defined in base"]
+    epoch: Epoch,
+}
+
+/// The state of a [`Window`] used to report the paint of the content of its elements to
+/// Largest Contentful Paint and Element Timing.
+#[derive(Default, JSTraceable, MallocSizeOf)]
+#[cfg_attr(crown, crown::unrooted_must_root_lint::must_root)]
+pub(crate) struct ContentfulPaintState {
+    /// The content painted for the first time in display lists whose frame has not been
+    /// presented yet.
+    pending_paints: DomRefCell<Vec<PendingContentfulPaint>>,
+    /// <https://w3c.github.io/largest-contentful-paint/#largest-contentful-paint-size>
+    largest_contentful_paint_size: Cell<f64>,
+}
+
+impl ContentfulPaintState {
+    /// Note the content painted in the display list with the given epoch, which is reported
+    /// once the frame containing it is presented. Returns whether any content was painted
+    /// for the first time.
+    pub(crate) fn note_contentful_paints(
+        &self,
+        contentful_paints: Vec<ContentfulPaint>,
+        epoch: Epoch,
+    ) -> bool {
+        if !pref!(dom_element_timing_enabled) && !pref!(dom_largest_contentful_paint_enabled) {
+            return false;
+        }
+
+        // Images are painted once they are loaded, so this is the closest we get to the
+        // time at which they finished loading.
+        let load_time = CrossProcessInstant::now();
+        let mut pending_paints = self.pending_paints.borrow_mut();
+        let previously_pending_paints = pending_paints.len();
+        for paint in contentful_paints {
+            let node = unsafe { from_untrusted_node_address(paint.node) };
+            // Text is painted by the element that contains it.
+            let element = match DomRoot::downcast::<Element>(node.clone()) {
+                Some(element) => element,
+                None => match node.GetParentElement() {
+                    Some(element) => element,
+                    None => continue,
+                },
+            };
+
+            let url = paint.is_image.then(|| {
+                element
+                    .downcast::<HTMLImageElement>()
+                    .map(|image| DOMString::from(image.CurrentSrc().0))
+                    .unwrap_or_default()
+            });
+            if !element.note_contentful_paint(url.as_ref()) {
+                continue;
+            }
+            pending_paints.push(PendingContentfulPaint {
+                element: Dom::from_ref(&*element),
+                url,
+                rect: paint.rect,
+                load_time: paint.is_image.then_some(load_time),
+                epoch,
+            });
+        }
+        pending_paints.len() > previously_pending_paints
+    }
+
+    /// Report the content painted in the frame containing the display list with the given
+    /// epoch, which was presented at `render_time`.
+    /// <https://w3c.github.io/paint-timing/#report-largest-contentful-paint>
+    /// <https://w3c.github.io/element-timing/#report-element-timing>
+    pub(crate) fn report_contentful_paints(
+        &self,
+        window: &Window,
+        presented_epoch: Epoch,
+        render_time: CrossProcessInstant,
+        can_gc: CanGc,
+    ) {
+        let painted_contents: Vec<_> = self
+            .pending_paints
+            .borrow()
+            .iter()
+            .filter(|paint| paint.epoch <= presented_epoch)
+            .map(|paint| PaintedContent {
+                element: paint.element.as_rooted(),
+                url: paint.url.clone(),
+                rect: paint.rect,
+                load_time: paint.load_time,
+                natural_size: paint
+                    .element
+                    .downcast::<HTMLImageElement>()
+                    .map_or((0, 0), |image| {
+                        (image.NaturalWidth(), image.NaturalHeight())
+                    }),
+            })
+            .collect();
+        self.pending_paints
+            .borrow_mut()
+            .retain(|paint| paint.epoch > presented_epoch);
+
+        let performance = window.Performance();
+        for content in painted_contents {
+            if !content.element.upcast::<Node>().is_connected() {
+                continue;
+            }
+
+            // Report an Element Timing entry for the elements with an elementtiming attribute.
+            if pref!(dom_element_timing_enabled) &&
+                content
+                    .element
+                    .has_attribute(&LocalName::from("elementtiming"))
+            {
+                let entry = PerformanceElementTiming::new(
+                    window.as_global_scope(),
+                    &content,
+                    render_time,
+                    can_gc,
+                );
+                performance.queue_entry(entry.upcast(), can_gc);
+            }
+
+            self.potentially_add_largest_contentful_paint_entry(
+                window,
+                &content,
+                render_time,
+                can_gc,
+            );
+        }
+    }
+
+    /// <https://w3c.github.io/largest-contentful-paint/#sec-add-lcp-entry>
+    fn potentially_add_largest_contentful_paint_entry(
+        &self,
+        window: &Window,
+        content: &PaintedContent,
+        render_time: CrossProcessInstant,
+        can_gc: CanGc,
+    ) {
+        // Largest Contentful Paint candidates are only reported until the user interacts
+        // with the page.
+        if !pref!(dom_largest_contentful_paint_enabled) ||
+            window.event_timing().has_dispatched_input_event()
+        {
+            return;
+        }
+
+        // The size of the content is its visible area, where images that are scaled up
+        // only count for their natural area.
+        let rect = content.rect;
+        let visible_area = rect.size.width.to_f64_px() * rect.size.height.to_f64_px();
+        let natural_area = content.natural_size.0 as f64 * content.natural_size.1 as f64;
+        let size = if content.url.is_some() && natural_area > 0. {
+            visible_area.min(natural_area)
+        } else {
+            visible_area
+        };
+        if size <= self.largest_contentful_paint_size.get() {
+            return;
+        }
+        self.largest_contentful_paint_size.set(size);
+
+        let entry = LargestContentfulPaint::new(
+            window.as_global_scope(),
+            content,
+            render_time,
+            size as u32,
+            can_gc,
+        );
+        window.Performance().queue_entry(entry.upcast(), can_gc);
+    }
+}
@@ -0,0 +1,357 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::cell::Cell;
+
+use app_units::Au;
+use base::Epoch;
+use base::cross_process_instant::CrossProcessInstant;
+use dom_struct::dom_struct;
+use embedder_traits::{InputEvent, MouseButtonAction, TouchEventType};
+use euclid::default::{Point2D, Rect, Size2D, Vector2D};
+use js::rust::MutableHandleValue;
+use keyboard_types::KeyState;
+use layout_api::LayoutShift as LayoutShiftSource;
+use servo_config::pref;
+use time::Duration;
+
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::LayoutShiftBinding::LayoutShiftMethods;
+use crate::dom::bindings::codegen::Bindings::PerformanceBinding::DOMHighResTimeStamp;
+use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::frozenarray::CachedFrozenArray;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::layoutshiftattribution::LayoutShiftAttribution;
+use crate::dom::node::{Node, from_untrusted_node_address};
+use crate::dom::performanceentry::PerformanceEntry;
+use crate::dom::window::Window;
+use crate::script_runtime::{CanGc, JSContext};
+
+/// The time after an excluding input during which layout shifts are flagged as having
+/// recent input.
+/// <https://wicg.github.io/layout-instability/#dom-layoutshift-hadrecentinput>
+const RECENT_INPUT_WINDOW: Duration = Duration::milliseconds(500);
+
+/// The maximum number of sources reported for a layout shift.
+/// <https://wicg.github.io/layout-instability/#dom-layoutshift-sources>
+const MAX_LAYOUT_SHIFT_SOURCES: usize = 5;
+
+/// <https://wicg.github.io/layout-instability/#sec-layout-shift>
+#[dom_struct]
+pub(crate) struct LayoutShift {
+    entry: PerformanceEntry,
+    /// <https://wicg.github.io/layout-instability/#dom-layoutshift-value>
+    value: f64,
+    /// <https://wicg.github.io/layout-instability/#dom-layoutshift-hadrecentinput>
+    had_recent_input: bool,
+    /// <https://wicg.github.io/layout-instability/#dom-layoutshift-lastinputtime>
+    #[no_trace]
+    last_input_time: Option<CrossProcessInstant>,
+    /// <https://wicg.github.io/layout-instability/#dom-layoutshift-sources>
+    sources: Vec<Dom<LayoutShiftAttribution>>,
+    #[ignore_malloc_size_of = "mozjs"]
+    frozen_sources: CachedFrozenArray,
+}
+
+impl LayoutShift {
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    fn new_inherited(
+        shift: &PendingLayoutShift,
+        render_time: CrossProcessInstant,
+        sources: &[DomRoot<LayoutShiftAttribution>],
+    ) -> LayoutShift {
+        LayoutShift {
+            entry: PerformanceEntry::new_inherited(
+                DOMString::from("layout-shift"),
+                DOMString::from("layout-shift"),
+                Some(render_time),
+                Duration::ZERO,
+            ),
+            value: shift.value,
+            had_recent_input: shift.had_recent_input,
+            last_input_time: shift.last_input_time,
+            sources: sources
+                .iter()
+                .map(|source| Dom::from_ref(&**source))
+                .collect(),
+            frozen_sources: CachedFrozenArray::new(),
+        }
+    }
+
+    fn new(
+        global: &GlobalScope,
+        shift: &PendingLayoutShift,
+        render_time: CrossProcessInstant,
+        can_gc: CanGc,
+    ) -> DomRoot<LayoutShift> {
+        let sources: Vec<_> = shift
+            .sources
+            .iter()
+            .map(|source| {
+                LayoutShiftAttribution::new(
+                    global,
+                    &source.node,
+                    source.previous_rect,
+                    source.current_rect,
+                    can_gc,
+                )
+            })
+            .collect();
+        let entry = LayoutShift::new_inherited(shift, render_time, &sources);
+        reflect_dom_object(Box::new(entry), global, can_gc)
+    }
+}
+
+impl LayoutShiftMethods<crate::DomTypeHolder> for LayoutShift {
+    /// <https://wicg.github.io/layout-instability/#dom-layoutshift-value>
+    fn Value(&self) -> f64 {
+        self.value
+    }
+
+    /// <https://wicg.github.io/layout-instability/#dom-layoutshift-hadrecentinput>
+    fn HadRecentInput(&self) -> bool {
+        self.had_recent_input
+    }
+
+    /// <https://wicg.github.io/layout-instability/#dom-layoutshift-lastinputtime>
+    fn LastInputTime(&self) -> DOMHighResTimeStamp {
+        self.global()
+            .performance()
+            .maybe_to_dom_high_res_time_stamp(self.last_input_time)
+    }
+
+    /// <https://wicg.github.io/layout-instability/#dom-layoutshift-sources>
+    fn Sources(&self, cx: JSContext, can_gc: CanGc, retval: MutableHandleValue) {
+        self.frozen_sources.get_or_init(
+            || {
+                self.sources
+                    .iter()
+                    .map(|source| source.as_rooted())
+                    .collect()
+            },
+            cx,
+            retval,
+            can_gc,
+        );
+    }
+}
+
+/// An element whose box moved in a layout shift, with its boxes in CSS pixels relative to
+/// the viewport.
+#[derive(JSTraceable, MallocSizeOf)]
+#[cfg_attr(crown, crown::unrooted_must_root_lint::must_root)]
+struct PendingLayoutShiftSource {
+    node: Dom<Node>,
+    #[no_trace]
+    previous_rect: Rect<f64>,
+    #[no_trace]
+    current_rect: Rect<f64>,
+}
+
+/// A layout shift in a display list whose frame has not been presented yet.
+#[derive(JSTraceable, MallocSizeOf)]
+#[cfg_attr(crown, crown::unrooted_must_root_lint::must_root)]
+struct PendingLayoutShift {
+    value: f64,
+    had_recent_input: bool,
+    #[no_trace]
+    last_input_time: Option<CrossProcessInstant>,
+    sources: Vec<PendingLayoutShiftSource>,
+    #[no_trace]
+    #[ignore_malloc_size_of = "Defined in base"]
+    epoch: Epoch,
+}
+
+/// The state of a [`Window`] used to report Layout Instability entries.
+#[derive(Default, JSTraceable, MallocSizeOf)]
+#[cfg_attr(crown, crown::unrooted_must_root_lint::must_root)]
+pub(crate) struct LayoutShiftState {
+    /// The time of the most recent excluding input.
+    /// <https://wicg.github.io/layout-instability/#excluding-input>
+    #[no_trace]
+    last_input_time: Cell<Option<CrossProcessInstant>>,
+    /// The layout shifts in display lists whose frame has not been presented yet.
+    pending_layout_shifts: DomRefCell<Vec<PendingLayoutShift>>,
+}
+
+impl LayoutShiftState {
+    /// Note that an excluding input, such as a mouse button or key press, was received.
+    /// <https://wicg.github.io/layout-instability/#excluding-input>
+    pub(crate) fn note_excluding_input(&self) {
+        self.last_input_time.set(Some(CrossProcessInstant::now()));
+    }
+
+    /// Compute the layout shift value of the elements that moved in the display list with the
+    /// given epoch, which is reported once the frame containing it is presented. The boxes of
+    /// the elements are made relative to the viewport using `scroll_offset`. Returns whether
+    /// the display list has a nonzero layout shift value.
+    /// <https://wicg.github.io/layout-instability/#layout-shift-value>
+    pub(crate) fn note_layout_shifts(
+        &self,
+        layout_shifts: Vec<LayoutShiftSource>,
+        viewport_size: Size2D<f64>,
+        scroll_offset: Vector2D<f64>,
+        epoch: Epoch,
+    ) -> bool {
+        let viewport = Rect::from_size(viewport_size);
+        if !pref!(dom_layout_instability_enabled) || layout_shifts.is_empty() || viewport.is_empty()
+        {
+            return false;
+        }
+
+        let to_viewport_rect = |rect: Rect<Au>| {
+            Rect::new(
+                Point2D::new(rect.origin.x.to_f64_px(), rect.origin.y.to_f64_px()),
+                Size2D::new(rect.size.width.to_f64_px(), rect.size.height.to_f64_px()),
+            )
+            .translate(-scroll_offset)
+        };
+        let mut impact_rects = Vec::new();
+        let mut max_move_distance: f64 = 0.;
+        let mut sources = Vec::new();
+        for shift in layout_shifts {
+            let previous_rect = to_viewport_rect(shift.previous_rect);
+            let current_rect = to_viewport_rect(shift.current_rect);
+
+            // Elements that are outside of the viewport both before and after the shift do
+            // not contribute to it.
+            let visible_rects: Vec<_> = [previous_rect, current_rect]
+                .iter()
+                .filter_map(|rect| rect.intersection(&viewport))
+                .collect();
+            if visible_rects.is_empty() {
+                continue;
+            }
+
+            let move_vector = current_rect.origin - previous_rect.origin;
+            max_move_distance = max_move_distance
+                .max(move_vector.x.abs())
+                .max(move_vector.y.abs());
+
+            let node = unsafe { from_untrusted_node_address(shift.node) };
+            sources.push((
+                area_of_union(&visible_rects),
+                node,
+                previous_rect,
+                current_rect,
+            ));
+            impact_rects.extend(visible_rects);
+        }
+
+        // The impact fraction is the part of the viewport covered by the shifting elements,
+        // and the distance fraction is how far they moved relative to the viewport.
+        let viewport_area = viewport.area();
+        let impact_fraction = area_of_union(&impact_rects) / viewport_area;
+        let distance_fraction =
+            (max_move_distance / viewport.width().max(viewport.height())).min(1.);
+        let value = impact_fraction * distance_fraction;
+        if value <= 0. {
+            return false;
+        }
+
+        // The sources are the elements with the largest impact area.
+        sources.sort_by(|(area, ..), (other_area, ..)| other_area.total_cmp(area));
+        sources.truncate(MAX_LAYOUT_SHIFT_SOURCES);
+
+        let last_input_time = self.last_input_time.get();
+        let had_recent_input = last_input_time
+            .is_some_and(|time| CrossProcessInstant::now() - time <= RECENT_INPUT_WINDOW);
+        self.pending_layout_shifts
+            .borrow_mut()
+            .push(PendingLayoutShift {
+                value,
+                had_recent_input,
+                last_input_time,
+                sources: sources
+                    .into_iter()
+                    .map(
+                        |(_, node, previous_rect, current_rect)| PendingLayoutShiftSource {
+                            node: Dom::from_ref(&*node),
+                            previous_rect,
+                            current_rect,
+                        },
+                    )
+                    .collect(),
+                epoch,
+            });
+        true
+    }
+
+    /// Report the layout shifts in the frame containing the display list with the given
+    /// epoch, which was presented at `render_time`.
+    /// <https://wicg.github.io/layout-instability/#report-the-layout-shift>
+    pub(crate) fn report_layout_shifts(
+        &self,
+        window: &Window,
+        presented_epoch: Epoch,
+        render_time: CrossProcessInstant,
+        can_gc: CanGc,
+    ) {
+        let entries: Vec<_> = self
+            .pending_layout_shifts
+            .borrow()
+            .iter()
+            .filter(|shift| shift.epoch <= presented_epoch)
+            .map(|shift| LayoutShift::new(window.as_global_scope(), shift, render_time, can_gc))
+            .collect();
+        self.pending_layout_shifts
+            .borrow_mut()
+            .retain(|shift| shift.epoch > presented_epoch);
+
+        let performance = window.Performance();
+        for entry in entries {
+            performance.queue_entry(entry.upcast(), can_gc);
+        }
+    }
+}
+
+/// Whether `event` is an excluding input, which signals an active interaction of the user
+/// with the document.
+/// <https://wicg.github.io/layout-instability/#excluding-input>
+pub(crate) fn is_excluding_input(event: &InputEvent) -> bool {
+    match event {
+        InputEvent::MouseButton(event) => event.action == MouseButtonAction::Down,
+        InputEvent::Keyboard(event) => event.event.state == KeyState::Down,
+        InputEvent::Touch(event) => matches!(event.event_type, TouchEventType::Down),
+        _ => false,
+    }
+}
+
+/// The area of the union of `rects`, computed by summing the area covered in each of the
+/// vertical slabs delimited by the horizontal edges of the rectangles.
+fn area_of_union(rects: &[Rect<f64>]) -> f64 {
+    let mut edges: Vec<f64> = rects
+        .iter()
+        .flat_map(|rect| [rect.min_x(), rect.max_x()])
+        .collect();
+    edges.sort_by(f64::total_cmp);
+    edges.dedup();
+
+    edges
+        .windows(2)
+        .map(|slab| {
+            let (left, right) = (slab[0], slab[1]);
+            let mut intervals: Vec<(f64, f64)> = rects
+                .iter()
+                .filter(|rect| rect.min_x() <= left && rect.max_x() >= right)
+                .map(|rect| (rect.min_y(), rect.max_y()))
+                .collect();
+            intervals.sort_by(|(top, _), (other_top, _)| top.total_cmp(other_top));
+
+            let mut covered_height = 0.;
+            let mut covered_until = f64::NEG_INFINITY;
+            for (top, bottom) in intervals {
+                if bottom > covered_until {
+                    covered_height += bottom - top.max(covered_until);
+                    covered_until = bottom;
+                }
+            }
+            covered_height * (right - left)
+        })
+        .sum()
+}
@@ -0,0 +1,93 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use euclid::default::Rect;
+
+use crate::dom::bindings::codegen::Bindings::LayoutShiftBinding::LayoutShiftAttributionMethods;
+use crate::dom::bindings::reflector::{Reflector, reflect_dom_object};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::domrectreadonly::DOMRectReadOnly;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::node::Node;
+use crate::script_runtime::CanGc;
+
+/// <https://wicg.github.io/layout-instability/#sec-layout-shift-attribution>
+#[dom_struct]
+pub(crate) struct LayoutShiftAttribution {
+    reflector_: Reflector,
+    /// <https://wicg.github.io/layout-instability/#dom-layoutshiftattribution-node>
+    node: Dom<Node>,
+    /// <https://wicg.github.io/layout-instability/#dom-layoutshiftattribution-previousrect>
+    previous_rect: Dom<DOMRectReadOnly>,
+    /// <https://wicg.github.io/layout-instability/#dom-layoutshiftattribution-currentrect>
+    current_rect: Dom<DOMRectReadOnly>,
+}
+
+impl LayoutShiftAttribution {
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    fn new_inherited(
+        node: &Node,
+        previous_rect: &DOMRectReadOnly,
+        current_rect: &DOMRectReadOnly,
+    ) -> LayoutShiftAttribution {
+        LayoutShiftAttribution {
+            reflector_: Reflector::new(),
+            node: Dom::from_ref(node),
+            previous_rect: Dom::from_ref(previous_rect),
+            current_rect: Dom::from_ref(current_rect),
+        }
+    }
+
+    /// Create the attribution of a layout shift to `node`, whose box moved from
+    /// `previous_rect` to `current_rect`, both in CSS pixels relative to the viewport.
+    pub(crate) fn new(
+        global: &GlobalScope,
+        node: &Node,
+        previous_rect: Rect<f64>,
+        current_rect: Rect<f64>,
+        can_gc: CanGc,
+    ) -> DomRoot<LayoutShiftAttribution> {
+        let new_rect = |rect: Rect<f64>| {
+            DOMRectReadOnly::new(
+                global,
+                None,
+                rect.origin.x,
+                rect.origin.y,
+                rect.size.width,
+                rect.size.height,
+                can_gc,
+            )
+        };
+        let previous_rect = new_rect(previous_rect);
+        let current_rect = new_rect(current_rect);
+        reflect_dom_object(
+            Box::new(LayoutShiftAttribution::new_inherited(
+                node,
+                &previous_rect,
+                &current_rect,
+            )),
+            global,
+            can_gc,
+        )
+    }
+}
+
+impl LayoutShiftAttributionMethods<crate::DomTypeHolder> for LayoutShiftAttribution {
+    /// <https://wicg.github.io/layout-instability/#dom-layoutshiftattribution-node>
+    fn GetNode(&self) -> Option<DomRoot<Node>> {
+        // Nodes that have been removed from their document are not exposed.
+        Some(self.node.as_rooted()).filter(|node| node.is_connected())
+    }
+
+    /// <https://wicg.github.io/layout-instability/#dom-layoutshiftattribution-previousrect>
+    fn PreviousRect(&self) -> DomRoot<DOMRectReadOnly> {
+        self.previous_rect.as_rooted()
+    }
+
+    /// <https://wicg.github.io/layout-instability/#dom-layoutshiftattribution-currentrect>
+    fn CurrentRect(&self) -> DomRoot<DOMRectReadOnly> {
+        self.current_rect.as_rooted()
+    }
+}
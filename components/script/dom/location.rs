@@ -118,7 +118,7 @@ impl Location {
 
         // Initiate navigation
         // TODO: rethrow exceptions, set exceptions enabled flag.
-        let load_data = LoadData::new(
+        let mut load_data = LoadData::new(
             LoadOrigin::Script(load_origin),
             url,
             creator_pipeline_id,
@@ -128,6 +128,7 @@ impl Location {
             Some(source_document.insecure_requests_policy()),
             source_document.has_trustworthy_ancestor_origin(),
         );
+        load_data.source_snapshot_params = Some(source_document.snapshot_source_snapshot_params());
         self.window
             .load_url(history_handling, reload_triggered, load_data, can_gc);
     }
@@ -13,10 +13,14 @@ use crate::conversions::Convert;
 use crate::dom::bindings::codegen::Bindings::MediaDevicesBinding::{
     MediaDevicesMethods, MediaStreamConstraints,
 };
+use crate::dom::bindings::codegen::Bindings::PermissionStatusBinding::{
+    PermissionName, PermissionState,
+};
 use crate::dom::bindings::codegen::UnionTypes::{
     BooleanOrMediaTrackConstraints, ClampedUnsignedLongOrConstrainULongRange as ConstrainULong,
     DoubleOrConstrainDoubleRange as ConstrainDouble,
 };
+use crate::dom::bindings::error::Error;
 use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object};
 use crate::dom::bindings::root::DomRoot;
 use crate::dom::eventtarget::EventTarget;
@@ -24,6 +28,7 @@ use crate::dom::globalscope::GlobalScope;
 use crate::dom::mediadeviceinfo::MediaDeviceInfo;
 use crate::dom::mediastream::MediaStream;
 use crate::dom::mediastreamtrack::MediaStreamTrack;
+use crate::dom::permissions::request_permission_to_use;
 use crate::dom::promise::Promise;
 use crate::realms::{AlreadyInRealm, InRealm};
 use crate::script_runtime::CanGc;
@@ -55,16 +60,34 @@ impl MediaDevicesMethods<crate::DomTypeHolder> for MediaDevices {
         can_gc: CanGc,
     ) -> Rc<Promise> {
         let p = Promise::new_in_current_realm(comp, can_gc);
+        let audio_constraints = convert_constraints(&constraints.audio);
+        let video_constraints = convert_constraints(&constraints.video);
+
+        // <https://w3c.github.io/mediacapture-main/#dfn-request-permission-to-use>
+        // Request "microphone" for audio and "camera" for video, and fail with a
+        // "NotAllowedError" if either of them is denied.
+        let global = self.global();
+        let requested = [
+            (audio_constraints.is_some(), PermissionName::Microphone),
+            (video_constraints.is_some(), PermissionName::Camera),
+        ];
+        for (_, name) in requested.into_iter().filter(|(requested, _)| *requested) {
+            if request_permission_to_use(name, &global) != PermissionState::Granted {
+                p.reject_error(Error::NotAllowed, can_gc);
+                return p;
+            }
+        }
+
         let media = ServoMedia::get();
         let stream = MediaStream::new(&self.global(), can_gc);
-        if let Some(constraints) = convert_constraints(&constraints.audio) {
+        if let Some(constraints) = audio_constraints {
             if let Some(audio) = media.create_audioinput_stream(constraints) {
                 let track =
                     MediaStreamTrack::new(&self.global(), audio, MediaStreamType::Audio, can_gc);
                 stream.add_track(&track);
             }
         }
-        if let Some(constraints) = convert_constraints(&constraints.video) {
+        if let Some(constraints) = video_constraints {
             if let Some(video) = media.create_videoinput_stream(constraints) {
                 let track =
                     MediaStreamTrack::new(&self.global(), video, MediaStreamType::Video, can_gc);
@@ -230,6 +230,9 @@ pub(crate) mod audioparam;
 pub(crate) mod audioscheduledsourcenode;
 pub(crate) mod audiotrack;
 pub(crate) mod audiotracklist;
+pub(crate) mod authenticatorassertionresponse;
+pub(crate) mod authenticatorattestationresponse;
+pub(crate) mod authenticatorresponse;
 pub(crate) mod baseaudiocontext;
 pub(crate) mod beforeunloadevent;
 pub(crate) mod bindings;
@@ -257,10 +260,15 @@ pub(crate) mod clipboarditem;
 pub(crate) mod closeevent;
 pub(crate) mod comment;
 pub(crate) mod compositionevent;
+pub(crate) mod compressionstream;
 pub(crate) mod console;
 pub(crate) mod constantsourcenode;
+pub(crate) mod cookiechangeevent;
+pub(crate) mod cookiestore;
 pub(crate) mod countqueuingstrategy;
 mod create;
+pub(crate) mod credential;
+pub(crate) mod credentialscontainer;
 pub(crate) mod crypto;
 pub(crate) mod cryptokey;
 pub(crate) mod csp;
@@ -279,6 +287,7 @@ pub(crate) mod cssnamespacerule;
 pub(crate) mod cssnesteddeclarations;
 pub(crate) mod cssrule;
 pub(crate) mod cssrulelist;
+pub(crate) mod cssscoperule;
 pub(crate) mod cssstyledeclaration;
 pub(crate) mod cssstylerule;
 pub(crate) mod cssstylesheet;
@@ -291,9 +300,11 @@ pub(crate) mod datatransferitem;
 pub(crate) mod datatransferitemlist;
 pub(crate) mod debuggeradddebuggeeevent;
 pub(crate) mod debuggerglobalscope;
+pub(crate) mod decompressionstream;
 pub(crate) mod dedicatedworkerglobalscope;
 pub(crate) mod defaultteereadrequest;
 pub(crate) mod defaultteeunderlyingsource;
+pub(crate) mod deprecationreportbody;
 pub(crate) mod dissimilaroriginlocation;
 pub(crate) mod dissimilaroriginwindow;
 #[allow(dead_code)]
@@ -315,14 +326,17 @@ pub(crate) mod domrectreadonly;
 pub(crate) mod domstringlist;
 pub(crate) mod domstringmap;
 pub(crate) mod domtokenlist;
+pub(crate) mod dragevent;
 pub(crate) mod dynamicmoduleowner;
 #[allow(dead_code)]
 pub(crate) mod element;
 pub(crate) mod elementinternals;
 pub(crate) mod errorevent;
 pub(crate) mod event;
+pub(crate) mod eventcounts;
 pub(crate) mod eventsource;
 pub(crate) mod eventtarget;
+pub(crate) mod extendablecookiechangeevent;
 pub(crate) mod extendableevent;
 pub(crate) mod extendablemessageevent;
 pub(crate) mod file;
@@ -341,6 +355,10 @@ pub(crate) mod gamepadbuttonlist;
 pub(crate) mod gamepadevent;
 pub(crate) mod gamepadhapticactuator;
 pub(crate) mod gamepadpose;
+pub(crate) mod geolocation;
+pub(crate) mod geolocationcoordinates;
+pub(crate) mod geolocationposition;
+pub(crate) mod geolocationpositionerror;
 #[allow(dead_code)]
 pub(crate) mod globalscope;
 pub(crate) mod hashchangeevent;
@@ -431,6 +449,7 @@ pub(crate) mod idbopendbrequest;
 pub(crate) mod idbrequest;
 pub(crate) mod idbtransaction;
 pub(crate) mod idbversionchangeevent;
+pub(crate) mod idledeadline;
 pub(crate) mod iirfilternode;
 pub(crate) mod imagebitmap;
 pub(crate) mod imagebitmaprenderingcontext;
@@ -438,8 +457,13 @@ pub(crate) mod imagedata;
 pub(crate) mod inputevent;
 pub(crate) mod intersectionobserver;
 pub(crate) mod intersectionobserverentry;
+pub(crate) mod interventionreportbody;
 pub(crate) mod keyboardevent;
+pub(crate) mod largestcontentfulpaint;
+pub(crate) mod layoutshift;
+pub(crate) mod layoutshiftattribution;
 pub(crate) mod location;
+pub(crate) mod mathmlelement;
 pub(crate) mod mediadeviceinfo;
 pub(crate) mod mediadevices;
 pub(crate) mod mediaelementaudiosourcenode;
@@ -465,7 +489,13 @@ pub(crate) mod mouseevent;
 pub(crate) mod mutationobserver;
 pub(crate) mod mutationrecord;
 pub(crate) mod namednodemap;
+pub(crate) mod navigateevent;
+pub(crate) mod navigation;
+pub(crate) mod navigationcurrententrychangeevent;
+pub(crate) mod navigationdestination;
+pub(crate) mod navigationhistoryentry;
 pub(crate) mod navigationpreloadmanager;
+pub(crate) mod navigationtransition;
 pub(crate) mod navigator;
 pub(crate) mod navigatorinfo;
 #[allow(dead_code)]
@@ -484,10 +514,15 @@ pub(crate) mod paintrenderingcontext2d;
 pub(crate) mod paintsize;
 pub(crate) mod paintworkletglobalscope;
 pub(crate) mod pannernode;
+pub(crate) mod passwordcredential;
 pub(crate) mod path2d;
 pub(crate) mod performance;
+pub(crate) mod performanceelementtiming;
 #[allow(dead_code)]
 pub(crate) mod performanceentry;
+pub(crate) mod performanceeventtiming;
+pub(crate) mod performancelonganimationframetiming;
+pub(crate) mod performancelongtasktiming;
 pub(crate) mod performancemark;
 pub(crate) mod performancemeasure;
 pub(crate) mod performancenavigation;
@@ -497,6 +532,7 @@ pub(crate) mod performanceobserver;
 pub(crate) mod performanceobserverentrylist;
 pub(crate) mod performancepainttiming;
 pub(crate) mod performanceresourcetiming;
+pub(crate) mod performanceservertiming;
 pub(crate) mod permissions;
 pub(crate) mod permissionstatus;
 pub(crate) mod pipelineid;
@@ -511,6 +547,7 @@ pub(crate) mod progressevent;
 pub(crate) mod promise;
 pub(crate) mod promisenativehandler;
 pub(crate) mod promiserejectionevent;
+pub(crate) mod publickeycredential;
 pub(crate) mod radionodelist;
 pub(crate) mod range;
 pub(crate) mod raredata;
@@ -540,6 +577,8 @@ pub(crate) mod rtcrtpsender;
 pub(crate) mod rtcrtptransceiver;
 pub(crate) mod rtcsessiondescription;
 pub(crate) mod rtctrackevent;
+pub(crate) mod sanitizer;
+pub(crate) mod scheduler;
 pub(crate) mod screen;
 pub(crate) mod securitypolicyviolationevent;
 pub(crate) mod selection;
@@ -566,6 +605,10 @@ pub(crate) mod svgelement;
 pub(crate) mod svggraphicselement;
 pub(crate) mod svgimageelement;
 pub(crate) mod svgsvgelement;
+pub(crate) mod taskattributiontiming;
+pub(crate) mod taskcontroller;
+pub(crate) mod taskprioritychangeevent;
+pub(crate) mod tasksignal;
 #[cfg(feature = "testbinding")]
 pub(crate) mod testbinding;
 #[cfg(feature = "testbinding")]
@@ -596,6 +639,7 @@ pub(crate) mod textdecoder;
 pub(crate) mod textdecodercommon;
 pub(crate) mod textdecoderstream;
 pub(crate) mod textencoder;
+pub(crate) mod textencoderstream;
 pub(crate) mod textmetrics;
 pub(crate) mod texttrack;
 pub(crate) mod texttrackcue;
@@ -603,6 +647,7 @@ pub(crate) mod texttrackcuelist;
 pub(crate) mod texttracklist;
 #[allow(dead_code)]
 pub(crate) mod timeranges;
+pub(crate) mod toggleevent;
 pub(crate) mod touch;
 pub(crate) mod touchevent;
 pub(crate) mod touchlist;
@@ -627,6 +672,7 @@ pub(crate) mod values;
 pub(crate) mod vertexarrayobject;
 pub(crate) mod videotrack;
 pub(crate) mod videotracklist;
+pub(crate) mod viewtransition;
 pub(crate) mod virtualmethods;
 pub(crate) mod visibilitystateentry;
 pub(crate) mod vttcue;
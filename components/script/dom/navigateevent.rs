@@ -0,0 +1,466 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use dom_struct::dom_struct;
+use js::jsapi::Heap;
+use js::jsval::JSVal;
+use js::rust::{HandleObject, HandleValue, MutableHandleValue};
+use stylo_atoms::Atom;
+
+use crate::dom::abortsignal::AbortSignal;
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::EventBinding::EventMethods;
+use crate::dom::bindings::codegen::Bindings::NavigateEventBinding::{
+    self, NavigateEventMethods, NavigationFocusReset, NavigationInterceptHandler,
+    NavigationInterceptOptions, NavigationScrollBehavior,
+};
+use crate::dom::bindings::codegen::Bindings::NavigationBinding::NavigationType;
+use crate::dom::bindings::codegen::Bindings::WindowBinding::{ScrollBehavior, WindowMethods};
+use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object_with_proto};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::bindings::trace::RootedTraceableBox;
+use crate::dom::console::Console;
+use crate::dom::event::{Event, EventBubbles, EventCancelable};
+use crate::dom::formdata::FormData;
+use crate::dom::navigationdestination::NavigationDestination;
+use crate::dom::window::Window;
+use crate::script_runtime::{CanGc, JSContext};
+
+/// <https://html.spec.whatwg.org/multipage/#concept-navigateevent-interception-state>
+#[derive(Clone, Copy, Debug, JSTraceable, MallocSizeOf, PartialEq)]
+pub(crate) enum InterceptionState {
+    None,
+    Intercepted,
+    Committed,
+    Scrolled,
+    Finished,
+}
+
+/// <https://html.spec.whatwg.org/multipage/#navigateevent>
+#[dom_struct]
+pub(crate) struct NavigateEvent {
+    event: Event,
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-navigationtype>
+    navigation_type: NavigationType,
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-destination>
+    destination: Dom<NavigationDestination>,
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-canintercept>
+    can_intercept: bool,
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-userinitiated>
+    user_initiated: bool,
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-hashchange>
+    hash_change: bool,
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-signal>
+    signal: Dom<AbortSignal>,
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-formdata>
+    form_data: Option<Dom<FormData>>,
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-downloadrequest>
+    download_request: Option<DOMString>,
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-info>
+    #[ignore_malloc_size_of = "mozjs"]
+    info: Heap<JSVal>,
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-hasuavisualtransition>
+    has_ua_visual_transition: bool,
+
+    /// <https://html.spec.whatwg.org/multipage/#concept-navigateevent-interception-state>
+    interception_state: Cell<InterceptionState>,
+
+    /// <https://html.spec.whatwg.org/multipage/#concept-navigateevent-navigation-handler-list>
+    #[ignore_malloc_size_of = "Rc"]
+    navigation_handler_list: DomRefCell<Vec<Rc<NavigationInterceptHandler>>>,
+
+    /// <https://html.spec.whatwg.org/multipage/#concept-navigateevent-focusreset>
+    focus_reset_behavior: Cell<Option<NavigationFocusReset>>,
+
+    /// <https://html.spec.whatwg.org/multipage/#concept-navigateevent-scroll>
+    scroll_behavior: Cell<Option<NavigationScrollBehavior>>,
+}
+
+impl NavigateEvent {
+    #[allow(clippy::too_many_arguments)]
+    fn new_inherited(
+        navigation_type: NavigationType,
+        destination: &NavigationDestination,
+        can_intercept: bool,
+        user_initiated: bool,
+        hash_change: bool,
+        signal: &AbortSignal,
+        form_data: Option<&FormData>,
+        download_request: Option<DOMString>,
+        has_ua_visual_transition: bool,
+    ) -> NavigateEvent {
+        NavigateEvent {
+            event: Event::new_inherited(),
+            navigation_type,
+            destination: Dom::from_ref(destination),
+            can_intercept,
+            user_initiated,
+            hash_change,
+            signal: Dom::from_ref(signal),
+            form_data: form_data.map(Dom::from_ref),
+            download_request,
+            info: Heap::default(),
+            has_ua_visual_transition,
+            interception_state: Cell::new(InterceptionState::None),
+            navigation_handler_list: Default::default(),
+            focus_reset_behavior: Cell::new(None),
+            scroll_behavior: Cell::new(None),
+        }
+    }
+
+    /// Create a trusted `navigate` event, as done by
+    /// <https://html.spec.whatwg.org/multipage/#inner-navigate-event-firing-algorithm>.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        window: &Window,
+        navigation_type: NavigationType,
+        destination: &NavigationDestination,
+        can_intercept: bool,
+        cancelable: bool,
+        user_initiated: bool,
+        hash_change: bool,
+        info: HandleValue,
+        can_gc: CanGc,
+    ) -> DomRoot<NavigateEvent> {
+        let signal = AbortSignal::new_with_proto(window.as_global_scope(), None, can_gc);
+        let event = reflect_dom_object_with_proto(
+            Box::new(NavigateEvent::new_inherited(
+                navigation_type,
+                destination,
+                can_intercept,
+                user_initiated,
+                hash_change,
+                &signal,
+                None,
+                None,
+                false,
+            )),
+            window,
+            None,
+            can_gc,
+        );
+        event.info.set(info.get());
+        event.upcast::<Event>().init_event(
+            Atom::from("navigate"),
+            bool::from(EventBubbles::DoesNotBubble),
+            cancelable,
+        );
+        event
+    }
+
+    pub(crate) fn navigation_type(&self) -> NavigationType {
+        self.navigation_type
+    }
+
+    pub(crate) fn destination(&self) -> &NavigationDestination {
+        &self.destination
+    }
+
+    pub(crate) fn signal(&self) -> &AbortSignal {
+        &self.signal
+    }
+
+    pub(crate) fn interception_state(&self) -> InterceptionState {
+        self.interception_state.get()
+    }
+
+    pub(crate) fn set_interception_state(&self, state: InterceptionState) {
+        self.interception_state.set(state);
+    }
+
+    pub(crate) fn navigation_handlers(&self) -> Vec<Rc<NavigationInterceptHandler>> {
+        self.navigation_handler_list.borrow().clone()
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#navigateevent-perform-shared-checks>
+    fn perform_shared_checks(&self) -> ErrorResult {
+        // Step 1. If event's relevant global object's associated Document is not fully active,
+        // then throw an "InvalidStateError" DOMException.
+        if !self.global().as_window().Document().is_fully_active() {
+            return Err(Error::InvalidState);
+        }
+
+        // Step 2. If event's isTrusted attribute was initialized to false, then throw a
+        // "SecurityError" DOMException.
+        if !self.event.IsTrusted() {
+            return Err(Error::Security);
+        }
+
+        // Step 3. If event's canceled flag is set, then throw an "InvalidStateError"
+        // DOMException.
+        if self.event.DefaultPrevented() {
+            return Err(Error::InvalidState);
+        }
+
+        Ok(())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#process-scroll-behavior>
+    fn process_scroll_behavior(&self) {
+        // Step 1. Set event's interception state to "scrolled".
+        self.interception_state.set(InterceptionState::Scrolled);
+
+        // TODO: Step 2. If event's navigationType was initialized to "traverse", then restore
+        // scroll position data given event's relevant global object's navigable's active
+        // session history entry.
+        if self.navigation_type == NavigationType::Traverse {
+            return;
+        }
+
+        // Step 3. Otherwise:
+        // Step 3.1. Let document be event's relevant global object's associated Document.
+        // Step 3.2. If document's indicated part is null, then scroll to the beginning of the
+        // document given document.
+        // Step 3.3. Otherwise, scroll to the fragment given document.
+        let global = self.global();
+        let window = global.as_window();
+        match self.destination.url().fragment() {
+            Some(fragment) => window.Document().check_and_scroll_fragment(fragment),
+            None => window.scroll(0.0, 0.0, ScrollBehavior::Auto),
+        }
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#navigateevent-finish>
+    pub(crate) fn finish(&self, did_fulfill: bool) {
+        // Step 1. Assert: event's interception state is not "intercepted" or "finished".
+        debug_assert!(!matches!(
+            self.interception_state.get(),
+            InterceptionState::Intercepted | InterceptionState::Finished
+        ));
+
+        // Step 2. If event's interception state is "none", then return.
+        if self.interception_state.get() == InterceptionState::None {
+            return;
+        }
+
+        // TODO: Step 3. Potentially reset the focus given event.
+
+        // Step 4. If didFulfill is true, then potentially process scroll behavior given event.
+        if did_fulfill {
+            self.potentially_process_scroll_behavior();
+        }
+
+        // Step 5. Set event's interception state to "finished".
+        self.interception_state.set(InterceptionState::Finished);
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#potentially-process-scroll-behavior>
+    fn potentially_process_scroll_behavior(&self) {
+        // Step 2. If event's interception state is "scrolled", then return.
+        if self.interception_state.get() == InterceptionState::Scrolled {
+            return;
+        }
+
+        // Step 3. If event's scroll behavior is "manual", then return.
+        if self.scroll_behavior.get() == Some(NavigationScrollBehavior::Manual) {
+            return;
+        }
+
+        // Step 4. Process scroll behavior given event.
+        self.process_scroll_behavior();
+    }
+}
+
+impl NavigateEventMethods<crate::DomTypeHolder> for NavigateEvent {
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-constructor>
+    fn Constructor(
+        window: &Window,
+        proto: Option<HandleObject>,
+        can_gc: CanGc,
+        type_: DOMString,
+        init: RootedTraceableBox<NavigateEventBinding::NavigateEventInit>,
+    ) -> Fallible<DomRoot<NavigateEvent>> {
+        let event = reflect_dom_object_with_proto(
+            Box::new(NavigateEvent::new_inherited(
+                init.navigationType,
+                &init.destination,
+                init.canIntercept,
+                init.userInitiated,
+                init.hashChange,
+                &init.signal,
+                init.formData.as_deref(),
+                init.downloadRequest.clone(),
+                init.hasUAVisualTransition,
+            )),
+            window,
+            proto,
+            can_gc,
+        );
+        event.info.set(init.info.get());
+        event.upcast::<Event>().init_event(
+            Atom::from(type_),
+            init.parent.bubbles,
+            init.parent.cancelable,
+        );
+        Ok(event)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-navigationtype>
+    fn NavigationType(&self) -> NavigationType {
+        self.navigation_type
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-destination>
+    fn Destination(&self) -> DomRoot<NavigationDestination> {
+        DomRoot::from_ref(&*self.destination)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-canintercept>
+    fn CanIntercept(&self) -> bool {
+        self.can_intercept
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-userinitiated>
+    fn UserInitiated(&self) -> bool {
+        self.user_initiated
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-hashchange>
+    fn HashChange(&self) -> bool {
+        self.hash_change
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-signal>
+    fn Signal(&self) -> DomRoot<AbortSignal> {
+        DomRoot::from_ref(&*self.signal)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-formdata>
+    fn GetFormData(&self) -> Option<DomRoot<FormData>> {
+        self.form_data.as_deref().map(DomRoot::from_ref)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-downloadrequest>
+    fn GetDownloadRequest(&self) -> Option<DOMString> {
+        self.download_request.clone()
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-info>
+    fn Info(&self, _cx: JSContext, mut retval: MutableHandleValue) {
+        retval.set(self.info.get())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-hasuavisualtransition>
+    fn HasUAVisualTransition(&self) -> bool {
+        self.has_ua_visual_transition
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-intercept>
+    fn Intercept(&self, options: &NavigationInterceptOptions) -> ErrorResult {
+        // Step 1. Perform shared checks given this.
+        self.perform_shared_checks()?;
+
+        // Step 2. If this's canIntercept attribute was initialized to false, then throw a
+        // "SecurityError" DOMException.
+        if !self.can_intercept {
+            return Err(Error::Security);
+        }
+
+        // Step 3. If this's dispatch flag is unset, then throw an "InvalidStateError"
+        // DOMException.
+        if !self.event.dispatching() {
+            return Err(Error::InvalidState);
+        }
+
+        // Step 4. Assert: this's interception state is either "none" or "intercepted".
+        debug_assert!(matches!(
+            self.interception_state.get(),
+            InterceptionState::None | InterceptionState::Intercepted
+        ));
+
+        // Step 5. Set this's interception state to "intercepted".
+        self.interception_state.set(InterceptionState::Intercepted);
+
+        // Step 6. If options["handler"] exists, then append it to this's navigation handler
+        // list.
+        if let Some(handler) = &options.handler {
+            self.navigation_handler_list
+                .borrow_mut()
+                .push(handler.clone());
+        }
+
+        // Step 7. If options["focusReset"] exists, then:
+        if let Some(focus_reset) = options.focusReset {
+            // Step 7.1. If this's focus reset behavior is not null, and it is not equal to
+            // options["focusReset"], then the user agent may report a warning to the console
+            // indicating that the focusReset option for a previous call to intercept() was
+            // overridden by this new value, and the previous value will be ignored.
+            if self
+                .focus_reset_behavior
+                .get()
+                .is_some_and(|behavior| behavior != focus_reset)
+            {
+                Console::internal_warn(
+                    &self.global(),
+                    DOMString::from(
+                        "The focusReset option of a previous intercept() was overridden.",
+                    ),
+                );
+            }
+
+            // Step 7.2. Set this's focus reset behavior to options["focusReset"].
+            self.focus_reset_behavior.set(Some(focus_reset));
+        }
+
+        // Step 8. If options["scroll"] exists, then:
+        if let Some(scroll) = options.scroll {
+            // Step 8.1. If this's scroll behavior is not null, and it is not equal to
+            // options["scroll"], then the user agent may report a warning to the console
+            // indicating that the scroll option for a previous call to intercept() was
+            // overridden by this new value, and the previous value will be ignored.
+            if self
+                .scroll_behavior
+                .get()
+                .is_some_and(|behavior| behavior != scroll)
+            {
+                Console::internal_warn(
+                    &self.global(),
+                    DOMString::from("The scroll option of a previous intercept() was overridden."),
+                );
+            }
+
+            // Step 8.2. Set this's scroll behavior to options["scroll"].
+            self.scroll_behavior.set(Some(scroll));
+        }
+
+        Ok(())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigateevent-scroll>
+    fn Scroll(&self) -> ErrorResult {
+        // Step 1. Perform shared checks given this.
+        self.perform_shared_checks()?;
+
+        // Step 2. If this's interception state is not "committed", then throw an
+        // "InvalidStateError" DOMException.
+        if self.interception_state.get() != InterceptionState::Committed {
+            return Err(Error::InvalidState);
+        }
+
+        // Step 3. Process scroll behavior given this.
+        self.process_scroll_behavior();
+        Ok(())
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-event-istrusted>
+    fn IsTrusted(&self) -> bool {
+        self.event.IsTrusted()
+    }
+}
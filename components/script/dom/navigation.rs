@@ -0,0 +1,1611 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! The [Navigation API], which exposes the session history entries of the navigable of a
+//! window that belong to its origin and lets script intercept navigations.
+//!
+//! The session history itself lives in the constellation, which sends each document the
+//! entries of its navigable that are same origin with it whenever the session history
+//! changes. Navigation API keys and ids are kept by script, so they are only stable for the
+//! lifetime of a document.
+//!
+//! [Navigation API]: https://html.spec.whatwg.org/multipage/#navigation-api
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use base::id::HistoryStateId;
+use constellation_traits::{
+    NavigableHistoryEntry, NavigationHistoryBehavior, ScriptToConstellationMessage,
+    StructuredSerializedData,
+};
+use dom_struct::dom_struct;
+use js::jsapi::Heap;
+use js::jsval::{JSVal, UndefinedValue};
+use js::rust::{HandleValue, MutableHandleValue};
+use servo_url::ServoUrl;
+use stylo_atoms::Atom;
+use url::Position;
+
+use crate::dom::bindings::callback::ExceptionHandling;
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::EventBinding::EventMethods;
+use crate::dom::bindings::codegen::Bindings::NavigationBinding::{
+    self, NavigationMethods, NavigationNavigateOptions, NavigationOptions, NavigationReloadOptions,
+    NavigationResult, NavigationType, NavigationUpdateCurrentEntryOptions,
+};
+use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::error::{Error, ErrorResult, ErrorToJsval, Fallible};
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object};
+use crate::dom::bindings::root::{Dom, DomRoot, MutNullableDom};
+use crate::dom::bindings::str::{DOMString, USVString};
+use crate::dom::bindings::structuredclone;
+use crate::dom::bindings::trace::RootedTraceableBox;
+use crate::dom::errorevent::ErrorEvent;
+use crate::dom::event::{Event, EventBubbles, EventCancelable};
+use crate::dom::eventtarget::EventTarget;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::history::{History, PushOrReplace};
+use crate::dom::location::NavigationType as LocationNavigationType;
+use crate::dom::navigateevent::{InterceptionState, NavigateEvent};
+use crate::dom::navigationcurrententrychangeevent::NavigationCurrentEntryChangeEvent;
+use crate::dom::navigationdestination::NavigationDestination;
+use crate::dom::navigationhistoryentry::NavigationHistoryEntry;
+use crate::dom::navigationtransition::NavigationTransition;
+use crate::dom::promise::{Promise, wait_for_all_promise};
+use crate::dom::promisenativehandler::{Callback, PromiseNativeHandler};
+use crate::dom::window::Window;
+use crate::realms::{InRealm, enter_realm};
+use crate::script_runtime::{CanGc, JSContext};
+
+/// Deserialize the navigation API state of an entry, which is undefined if there is none.
+pub(crate) fn deserialize_navigation_api_state(
+    global: &GlobalScope,
+    state: Option<&Vec<u8>>,
+    mut retval: MutableHandleValue,
+) -> Fallible<()> {
+    let Some(state) = state else {
+        retval.set(UndefinedValue());
+        return Ok(());
+    };
+    let data = StructuredSerializedData {
+        serialized: state.clone(),
+        ..Default::default()
+    };
+    structuredclone::read(global, data, retval).map(|_| ())
+}
+
+/// Serialize a navigation API state given by script, where undefined means that no state
+/// was given.
+fn serialize_navigation_api_state(cx: JSContext, state: HandleValue) -> Fallible<Option<Vec<u8>>> {
+    if state.is_undefined() {
+        return Ok(None);
+    }
+    structuredclone::write(cx, state, None).map(|data| Some(data.serialized))
+}
+
+/// <https://html.spec.whatwg.org/multipage/#she-navigation-api-key>
+fn new_navigation_api_key() -> DOMString {
+    DOMString::from(uuid::Uuid::new_v4().to_string())
+}
+
+/// <https://html.spec.whatwg.org/multipage/#navigation-api-method-tracker>
+#[derive(JSTraceable, MallocSizeOf)]
+#[cfg_attr(crown, crown::unrooted_must_root_lint::must_root)]
+struct NavigationApiMethodTracker {
+    /// <https://html.spec.whatwg.org/multipage/#navigation-api-method-tracker-key>
+    key: Option<DOMString>,
+
+    /// <https://html.spec.whatwg.org/multipage/#navigation-api-method-tracker-info>
+    #[ignore_malloc_size_of = "mozjs"]
+    info: Box<Heap<JSVal>>,
+
+    /// <https://html.spec.whatwg.org/multipage/#navigation-api-method-tracker-serialized-state>
+    serialized_state: Option<Vec<u8>>,
+
+    /// <https://html.spec.whatwg.org/multipage/#navigation-api-method-tracker-committed-to-entry>
+    committed_to_entry: Option<Dom<NavigationHistoryEntry>>,
+
+    /// <https://html.spec.whatwg.org/multipage/#navigation-api-method-tracker-committed-promise>
+    #[ignore_malloc_size_of = "Rc"]
+    committed_promise: Rc<Promise>,
+
+    /// <https://html.spec.whatwg.org/multipage/#navigation-api-method-tracker-finished-promise>
+    #[ignore_malloc_size_of = "Rc"]
+    finished_promise: Rc<Promise>,
+}
+
+impl NavigationApiMethodTracker {
+    fn new(
+        global: &GlobalScope,
+        key: Option<DOMString>,
+        info: HandleValue,
+        serialized_state: Option<Vec<u8>>,
+        can_gc: CanGc,
+    ) -> NavigationApiMethodTracker {
+        let finished_promise = Promise::new(global, can_gc);
+        // The finished promise is rejected along with the committed one, so avoid reporting
+        // unhandled rejections for pages that only look at the latter.
+        finished_promise.set_promise_is_handled();
+        NavigationApiMethodTracker {
+            key,
+            info: Heap::boxed(info.get()),
+            serialized_state,
+            committed_to_entry: None,
+            committed_promise: Promise::new(global, can_gc),
+            finished_promise,
+        }
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#navigation-api-method-tracker-derived-result>
+    fn derived_result(&self) -> NavigationResult {
+        NavigationResult {
+            committed: Some(self.committed_promise.clone()),
+            finished: Some(self.finished_promise.clone()),
+        }
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#notify-about-the-committed-to-entry>
+    fn notify_about_the_committed_to_entry(
+        &mut self,
+        entry: &NavigationHistoryEntry,
+        can_gc: CanGc,
+    ) {
+        // Step 1. Set apiMethodTracker's committed-to entry to nhe.
+        self.committed_to_entry = Some(Dom::from_ref(entry));
+
+        // Step 2. If apiMethodTracker's serialized state is not null, then set nhe's session
+        // history entry's navigation API state to apiMethodTracker's serialized state.
+        if let Some(state) = self.serialized_state.take() {
+            entry.set_state(Some(state));
+        }
+
+        // Step 3. Resolve apiMethodTracker's committed promise with nhe.
+        self.committed_promise
+            .resolve_native(&DomRoot::from_ref(entry), can_gc);
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#resolve-the-finished-promise>
+    fn resolve_the_finished_promise(&self, can_gc: CanGc) {
+        // Step 1. Assert: apiMethodTracker's committed-to entry is not null.
+        let Some(entry) = &self.committed_to_entry else {
+            return;
+        };
+
+        // Step 2. Resolve apiMethodTracker's finished promise with its committed-to entry.
+        self.finished_promise
+            .resolve_native(&DomRoot::from_ref(&**entry), can_gc);
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#reject-the-finished-promise>
+    fn reject_the_finished_promise(&self, cx: JSContext, exception: HandleValue, can_gc: CanGc) {
+        // Step 1. Reject apiMethodTracker's committed promise with exception.
+        // Note: This does nothing if it was already resolved.
+        if self.committed_promise.is_pending() {
+            self.committed_promise.reject(cx, exception, can_gc);
+        }
+
+        // Step 2. Reject apiMethodTracker's finished promise with exception.
+        self.finished_promise.reject(cx, exception, can_gc);
+    }
+}
+
+/// The entries of a navigable as they are once a traversal to another entry of the same
+/// document has been applied, computed before firing its `navigate` event.
+pub(crate) struct SameDocumentTraversal {
+    entries: Vec<DomRoot<NavigationHistoryEntry>>,
+    current_index: usize,
+}
+
+/// <https://html.spec.whatwg.org/multipage/#navigation-interface>
+#[dom_struct]
+pub(crate) struct Navigation {
+    eventtarget: EventTarget,
+
+    window: Dom<Window>,
+
+    /// <https://html.spec.whatwg.org/multipage/#navigation-entry-list>
+    entry_list: DomRefCell<Vec<Dom<NavigationHistoryEntry>>>,
+
+    /// <https://html.spec.whatwg.org/multipage/#navigation-current-entry-index>
+    current_entry_index: Cell<Option<usize>>,
+
+    /// Whether the entry list reflects the session history of the constellation. It is
+    /// populated the first time it is needed, and again when the document is reactivated.
+    has_entries: Cell<bool>,
+
+    /// Whether the entry list was populated before the constellation sent a session history
+    /// that knows of the current entry, so that it only holds the entries known to script.
+    has_provisional_entries: Cell<bool>,
+
+    /// <https://html.spec.whatwg.org/multipage/#concept-navigation-transition>
+    transition: MutNullableDom<NavigationTransition>,
+
+    /// <https://html.spec.whatwg.org/multipage/#ongoing-navigate-event>
+    ongoing_navigate_event: MutNullableDom<NavigateEvent>,
+
+    /// <https://html.spec.whatwg.org/multipage/#ongoing-api-method-tracker>
+    ongoing_api_method_tracker: DomRefCell<Option<NavigationApiMethodTracker>>,
+
+    /// <https://html.spec.whatwg.org/multipage/#upcoming-non-traverse-api-method-tracker>
+    upcoming_non_traverse_api_method_tracker: DomRefCell<Option<NavigationApiMethodTracker>>,
+
+    /// <https://html.spec.whatwg.org/multipage/#upcoming-traverse-api-method-trackers>
+    upcoming_traverse_api_method_trackers: DomRefCell<Vec<NavigationApiMethodTracker>>,
+}
+
+impl Navigation {
+    fn new_inherited(window: &Window) -> Navigation {
+        Navigation {
+            eventtarget: EventTarget::new_inherited(),
+            window: Dom::from_ref(window),
+            entry_list: Default::default(),
+            current_entry_index: Cell::new(None),
+            has_entries: Cell::new(false),
+            has_provisional_entries: Cell::new(false),
+            transition: Default::default(),
+            ongoing_navigate_event: Default::default(),
+            ongoing_api_method_tracker: Default::default(),
+            upcoming_non_traverse_api_method_tracker: Default::default(),
+            upcoming_traverse_api_method_trackers: Default::default(),
+        }
+    }
+
+    pub(crate) fn new(window: &Window, can_gc: CanGc) -> DomRoot<Navigation> {
+        reflect_dom_object(Box::new(Navigation::new_inherited(window)), window, can_gc)
+    }
+
+    pub(crate) fn window(&self) -> &Window {
+        &self.window
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#has-entries-and-events-disabled>
+    fn has_entries_and_events_disabled(&self) -> bool {
+        let document = self.window.Document();
+        !document.is_fully_active() ||
+            document.is_initial_about_blank() ||
+            !document.origin().is_tuple()
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#navigation-current-entry>
+    fn current_entry(&self) -> Option<DomRoot<NavigationHistoryEntry>> {
+        // Step 1. If navigation has entries and events disabled, then return null.
+        if self.has_entries_and_events_disabled() {
+            return None;
+        }
+
+        // Step 2. Assert: navigation's current entry index is not −1.
+        // Step 3. Return navigation's entry list[navigation's current entry index].
+        let index = self.current_entry_index.get()?;
+        self.entry_list
+            .borrow()
+            .get(index)
+            .map(|entry| DomRoot::from_ref(&**entry))
+    }
+
+    pub(crate) fn index_of_entry(&self, entry: &NavigationHistoryEntry) -> Option<usize> {
+        self.entry_list
+            .borrow()
+            .iter()
+            .position(|candidate| &**candidate == entry)
+    }
+
+    /// Populate the entry list from the session history of the constellation if it is not
+    /// up to date.
+    /// <https://html.spec.whatwg.org/multipage/#initialize-the-navigation-api-entries-for-a-new-document>
+    fn ensure_entries(&self, can_gc: CanGc) {
+        if self.has_entries.get() || self.has_entries_and_events_disabled() {
+            return;
+        }
+        self.has_entries.set(true);
+
+        let current_entry = NavigableHistoryEntry {
+            pipeline_id: self.window.pipeline_id(),
+            url: self.window.Document().url(),
+            history_state_id: self.window.History().state_id(),
+        };
+        let (entries, current_index) = self
+            .entries_from_navigable_history(&current_entry, can_gc)
+            .unwrap_or_else(|| {
+                let entry = NavigationHistoryEntry::new(
+                    self,
+                    current_entry.url,
+                    new_navigation_api_key(),
+                    current_entry.pipeline_id,
+                    current_entry.history_state_id,
+                    None,
+                    can_gc,
+                );
+                self.has_provisional_entries.set(true);
+                (vec![entry], 0)
+            });
+        self.set_entries(entries, Some(current_index), can_gc);
+    }
+
+    /// Throw away the entry list, so that it is populated again the next time it is needed.
+    /// <https://html.spec.whatwg.org/multipage/#update-the-navigation-api-entries-for-reactivation>
+    pub(crate) fn invalidate_entries(&self) {
+        self.has_entries.set(false);
+        self.has_provisional_entries.set(false);
+    }
+
+    /// Called when the constellation sent a new session history for the navigable of this
+    /// navigation. A provisional entry list is replaced with the entries it was missing.
+    pub(crate) fn navigable_history_changed(&self, can_gc: CanGc) {
+        if !self.has_entries.get() ||
+            !self.has_provisional_entries.get() ||
+            self.has_entries_and_events_disabled()
+        {
+            return;
+        }
+        let Some(current_entry) = self.current_entry() else {
+            return;
+        };
+        let current_entry = current_entry.session_history_entry();
+        if let Some((entries, current_index)) =
+            self.entries_from_navigable_history(&current_entry, can_gc)
+        {
+            self.has_provisional_entries.set(false);
+            self.set_entries(entries, Some(current_index), can_gc);
+        }
+    }
+
+    /// Build the entry list from the session history the constellation last sent, which only
+    /// holds the entries contiguous with the current one that are same origin with it, reusing
+    /// the [`NavigationHistoryEntry`] of entries that are already known. Returns `None` if that
+    /// session history does not have the given entry as its current entry yet.
+    fn entries_from_navigable_history(
+        &self,
+        current_entry: &NavigableHistoryEntry,
+        can_gc: CanGc,
+    ) -> Option<(Vec<DomRoot<NavigationHistoryEntry>>, usize)> {
+        let navigable_history = self.window.navigable_history();
+        let (history, current_index) = navigable_history.as_ref()?;
+        if history.get(*current_index) != Some(current_entry) {
+            return None;
+        }
+
+        let old_entries: Vec<_> = self
+            .entry_list
+            .borrow()
+            .iter()
+            .map(|entry| DomRoot::from_ref(&**entry))
+            .collect();
+        let mut old_entries = old_entries.into_iter();
+        let entries = history
+            .iter()
+            .map(|entry| {
+                // Both lists are in session history order, so only look for a known entry
+                // after the last one that was reused.
+                old_entries
+                    .by_ref()
+                    .find(|old_entry| old_entry.represents(entry))
+                    .unwrap_or_else(|| {
+                        NavigationHistoryEntry::new(
+                            self,
+                            entry.url.clone(),
+                            new_navigation_api_key(),
+                            entry.pipeline_id,
+                            entry.history_state_id,
+                            None,
+                            can_gc,
+                        )
+                    })
+            })
+            .collect();
+        Some((entries, *current_index))
+    }
+
+    /// Replace the entry list, firing `dispose` at the entries that are not part of it anymore.
+    fn set_entries(
+        &self,
+        entries: Vec<DomRoot<NavigationHistoryEntry>>,
+        current_index: Option<usize>,
+        can_gc: CanGc,
+    ) {
+        let new_entries = entries
+            .iter()
+            .map(|entry| Dom::from_ref(&**entry))
+            .collect();
+        let old_entries = std::mem::replace(&mut *self.entry_list.borrow_mut(), new_entries);
+        self.current_entry_index.set(current_index);
+
+        let disposed_entries: Vec<_> = old_entries
+            .iter()
+            .filter(|old_entry| !entries.iter().any(|entry| **entry == ***old_entry))
+            .map(|entry| DomRoot::from_ref(&**entry))
+            .collect();
+        for entry in disposed_entries {
+            entry
+                .upcast::<EventTarget>()
+                .fire_event(Atom::from("dispose"), can_gc);
+        }
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#update-the-navigation-api-entries-for-a-same-document-navigation>
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    pub(crate) fn update_entries_for_same_document_navigation(
+        &self,
+        navigation_type: NavigationType,
+        url: ServoUrl,
+        history_state_id: Option<HistoryStateId>,
+        can_gc: CanGc,
+    ) {
+        // Step 1. If navigation has entries and events disabled, then return.
+        if !self.has_entries.get() || self.has_entries_and_events_disabled() {
+            return;
+        }
+
+        // Step 2. Let oldCurrentNHE be the current entry of navigation.
+        let Some(old_current_entry) = self.current_entry() else {
+            return;
+        };
+        let Some(current_index) = self.current_entry_index.get() else {
+            return;
+        };
+
+        // Step 3. Let disposedNHEs be a new empty list.
+        let mut disposed_entries = vec![];
+        match navigation_type {
+            // Step 4. If navigationType is "traverse", then the entries are updated by
+            // `finish_same_document_traversal` instead.
+            NavigationType::Traverse => return,
+            // Step 5. Otherwise, if navigationType is "push", then:
+            NavigationType::Push => {
+                let entry = NavigationHistoryEntry::new(
+                    self,
+                    url,
+                    new_navigation_api_key(),
+                    self.window.pipeline_id(),
+                    history_state_id,
+                    None,
+                    can_gc,
+                );
+                let mut entry_list = self.entry_list.borrow_mut();
+
+                // Step 5.1. Set navigation's current entry index to navigation's current
+                // entry index + 1.
+                self.current_entry_index.set(Some(current_index + 1));
+
+                // Step 5.2. Let i be navigation's current entry index.
+                // Step 5.3. While i < navigation's entry list's size: append navigation's
+                // entry list[i] to disposedNHEs.
+                // Step 5.4. Remove all items in disposedNHEs from navigation's entry list.
+                disposed_entries.extend(
+                    entry_list
+                        .drain(current_index + 1..)
+                        .map(|entry| DomRoot::from_ref(&*entry)),
+                );
+
+                // Step 5.5. Append a new NavigationHistoryEntry for destinationSHE to
+                // navigation's entry list.
+                entry_list.push(Dom::from_ref(&*entry));
+            },
+            // Step 6. Otherwise, if navigationType is "replace", then:
+            NavigationType::Replace => {
+                // Step 6.1. Append oldCurrentNHE to disposedNHEs.
+                // Step 6.2. Set navigation's entry list[navigation's current entry index] to a
+                // new NavigationHistoryEntry for destinationSHE.
+                // Note: The replacement keeps the key of the entry it replaces, as it
+                // represents the same slot in the session history.
+                let entry = NavigationHistoryEntry::new(
+                    self,
+                    url,
+                    old_current_entry.key().clone(),
+                    self.window.pipeline_id(),
+                    history_state_id,
+                    old_current_entry.state(),
+                    can_gc,
+                );
+                self.entry_list.borrow_mut()[current_index] = Dom::from_ref(&*entry);
+                disposed_entries.push(old_current_entry.clone());
+            },
+            // Note: A reload that was intercepted keeps the current entry.
+            NavigationType::Reload => {},
+        }
+
+        // Step 7. If navigation's ongoing API method tracker is non-null, then notify about
+        // the committed-to entry given navigation's ongoing API method tracker and the current
+        // entry of navigation.
+        if let Some(current_entry) = self.current_entry() {
+            if let Some(tracker) = self.ongoing_api_method_tracker.borrow_mut().as_mut() {
+                tracker.notify_about_the_committed_to_entry(&current_entry, can_gc);
+            }
+        }
+
+        // Step 8. Prepare to run script given navigation's relevant settings object.
+        // Step 9. Fire an event named currententrychange at navigation using
+        // NavigationCurrentEntryChangeEvent, with its navigationType attribute initialized to
+        // navigationType and its from initialized to oldCurrentNHE.
+        self.fire_current_entry_change_event(Some(navigation_type), &old_current_entry, can_gc);
+
+        // Step 10. For each disposedNHE of disposedNHEs: fire an event named dispose at
+        // disposedNHE.
+        for entry in disposed_entries {
+            entry
+                .upcast::<EventTarget>()
+                .fire_event(Atom::from("dispose"), can_gc);
+        }
+    }
+
+    /// Fire the `navigate` event of a traversal of the session history to another entry of
+    /// the document of this navigation, which the constellation has already applied.
+    /// <https://html.spec.whatwg.org/multipage/#fire-a-traverse-navigate-event>
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    pub(crate) fn start_same_document_traversal(
+        &self,
+        url: &ServoUrl,
+        history_state_id: Option<HistoryStateId>,
+        can_gc: CanGc,
+    ) -> Option<SameDocumentTraversal> {
+        if !self.has_entries.get() || self.has_entries_and_events_disabled() {
+            return None;
+        }
+
+        // Note: The constellation sends the session history of the traversal before asking
+        // the document to apply it.
+        let destination = NavigableHistoryEntry {
+            pipeline_id: self.window.pipeline_id(),
+            url: url.clone(),
+            history_state_id,
+        };
+        let (entries, current_index) = self.entries_from_navigable_history(&destination, can_gc)?;
+        let destination_entry = &entries[current_index];
+        if self
+            .current_entry()
+            .is_some_and(|current_entry| current_entry == *destination_entry)
+        {
+            return None;
+        }
+
+        // Step 2. Let destinationNHE be the NavigationHistoryEntry in navigation's entry list
+        // whose session history entry is destinationSHE, or null if no such
+        // NavigationHistoryEntry exists.
+        // Step 3. If destinationNHE is non-null, then:
+        // Step 3.1. Set destination's entry to destinationNHE.
+        // Step 3.2. Set destination's state to destinationNHE's session history entry's
+        // navigation API state.
+        // Step 5. Set destination's URL to destinationSHE's URL.
+        // Step 6. Let destinationDocument be destinationSHE's document.
+        // Step 7. Set destination's is same document to true if destinationDocument is
+        // navigation's relevant global object's associated Document; otherwise false.
+        let destination = NavigationDestination::new(
+            &self.window,
+            destination_entry.url().clone(),
+            Some(&**destination_entry),
+            destination_entry.state(),
+            true,
+            can_gc,
+        );
+
+        // Note: From the traverse steps of
+        // <https://html.spec.whatwg.org/multipage/#inner-navigate-event-firing-algorithm>:
+        // If navigation's upcoming traverse API method trackers[destination key] exists, then
+        // it becomes the API method tracker of this navigation.
+        let api_method_tracker = {
+            let mut trackers = self.upcoming_traverse_api_method_trackers.borrow_mut();
+            trackers
+                .iter()
+                .position(|tracker| tracker.key.as_ref() == Some(destination_entry.key()))
+                .map(|index| trackers.remove(index))
+        };
+
+        // Step 8. Return the result of performing the inner navigate event firing algorithm
+        // given navigation, "traverse", event, destination, userInvolvement, null, and null.
+        self.inner_navigate_event_firing_algorithm(
+            NavigationType::Traverse,
+            &destination,
+            api_method_tracker,
+            None,
+            can_gc,
+        );
+
+        Some(SameDocumentTraversal {
+            entries,
+            current_index,
+        })
+    }
+
+    /// Update the entry list once a same-document traversal is applied, and run the handlers
+    /// of its `navigate` event if it was intercepted.
+    /// <https://html.spec.whatwg.org/multipage/#update-the-navigation-api-entries-for-a-same-document-navigation>
+    pub(crate) fn finish_same_document_traversal(
+        &self,
+        traversal: SameDocumentTraversal,
+        can_gc: CanGc,
+    ) {
+        let old_current_entry = self.current_entry();
+
+        // Step 4. If navigationType is "traverse", then:
+        // Step 4.1. Set navigation's current entry index to the index within navigation's
+        // entry list of the NavigationHistoryEntry whose session history entry is
+        // destinationSHE.
+        self.has_provisional_entries.set(false);
+        self.set_entries(traversal.entries, Some(traversal.current_index), can_gc);
+
+        // Step 7. If navigation's ongoing API method tracker is non-null, then notify about
+        // the committed-to entry given navigation's ongoing API method tracker and the current
+        // entry of navigation.
+        if let Some(current_entry) = self.current_entry() {
+            if let Some(tracker) = self.ongoing_api_method_tracker.borrow_mut().as_mut() {
+                tracker.notify_about_the_committed_to_entry(&current_entry, can_gc);
+            }
+        }
+
+        // Step 9. Fire an event named currententrychange at navigation.
+        if let Some(old_current_entry) = old_current_entry {
+            self.fire_current_entry_change_event(
+                Some(NavigationType::Traverse),
+                &old_current_entry,
+                can_gc,
+            );
+        }
+
+        // Note: The handlers of a traversal that was intercepted only run once the traversal
+        // has been committed.
+        if let Some(event) = self.ongoing_navigate_event.get() {
+            if event.navigation_type() == NavigationType::Traverse {
+                self.run_the_navigation_handlers(&event, can_gc);
+            }
+        }
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#fire-a-push/replace/reload-navigate-event>
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    pub(crate) fn fire_a_push_replace_reload_navigate_event(
+        &self,
+        navigation_type: NavigationType,
+        destination_url: ServoUrl,
+        is_same_document: bool,
+        classic_history_api_state: Option<Vec<u8>>,
+        can_gc: CanGc,
+    ) -> bool {
+        self.ensure_entries(can_gc);
+
+        // Step 3. Let apiMethodTracker be navigation's upcoming non-traverse API method
+        // tracker.
+        // Step 4. Set navigation's upcoming non-traverse API method tracker to null.
+        let api_method_tracker = self
+            .upcoming_non_traverse_api_method_tracker
+            .borrow_mut()
+            .take();
+
+        // Step 5. Let destination be a new NavigationDestination created in navigation's
+        // relevant realm.
+        // Step 6. Set destination's URL to destinationURL.
+        // Step 7. Set destination's entry to null.
+        // Step 8. Set destination's state to navigationAPIState.
+        // Step 9. Set destination's is same document to isSameDocument.
+        let state = match &api_method_tracker {
+            Some(tracker) => tracker.serialized_state.clone(),
+            None if navigation_type == NavigationType::Reload => {
+                self.current_entry().and_then(|entry| entry.state())
+            },
+            None => None,
+        };
+        let destination = NavigationDestination::new(
+            &self.window,
+            destination_url,
+            None,
+            state,
+            is_same_document,
+            can_gc,
+        );
+
+        // Step 10. Return the result of performing the inner navigate event firing algorithm
+        // given navigation, navigationType, event, destination, userInvolvement,
+        // sourceElement, formDataEntryList, and null.
+        self.inner_navigate_event_firing_algorithm(
+            navigation_type,
+            &destination,
+            api_method_tracker,
+            classic_history_api_state,
+            can_gc,
+        )
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#inner-navigate-event-firing-algorithm>
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    fn inner_navigate_event_firing_algorithm(
+        &self,
+        navigation_type: NavigationType,
+        destination: &NavigationDestination,
+        api_method_tracker: Option<NavigationApiMethodTracker>,
+        classic_history_api_state: Option<Vec<u8>>,
+        can_gc: CanGc,
+    ) -> bool {
+        // Step 1. If navigation has entries and events disabled, then:
+        if self.has_entries_and_events_disabled() {
+            // Step 1.1. Assert: apiMethodTracker is null.
+            // Step 1.2. Return true.
+            return true;
+        }
+
+        // Note: A new navigation aborts the one that is ongoing.
+        self.inform_the_navigation_api_about_aborting_navigation(can_gc);
+
+        // Step 4. Let document be navigation's relevant global object's associated Document.
+        let document = self.window.Document();
+        let document_url = document.url();
+
+        // Step 5. If document can have its URL rewritten to destination's URL, and either
+        // destination's is same document is true or navigationType is not "traverse", then
+        // initialize event's canIntercept to true. Otherwise, initialize it to false.
+        let can_intercept = History::can_have_url_rewritten(&document_url, destination.url()) &&
+            (destination.is_same_document() || navigation_type != NavigationType::Traverse);
+
+        // Step 6. Let traverseCanBeCanceled be true if navigationType is "traverse" and ...
+        // Note: Traversals have already been applied by the constellation by the time their
+        // `navigate` event is fired, so they cannot be canceled.
+        let cancelable = navigation_type != NavigationType::Traverse;
+
+        // Step 13. If all of the following are true: destination's is same document is true;
+        // destination's URL equals navigation's relevant global object's associated
+        // Document's URL with exclude fragments set to true; and destination's URL's fragment
+        // is not identical to navigation's relevant global object's associated Document's
+        // URL's fragment, then initialize event's hashChange to true. Otherwise, initialize
+        // it to false.
+        let hash_change = destination.is_same_document() &&
+            destination.url().as_url()[..Position::AfterQuery] ==
+                document_url.as_url()[..Position::AfterQuery] &&
+            destination.url().fragment() != document_url.fragment();
+
+        // Step 15. If apiMethodTracker is non-null, then initialize event's info to
+        // apiMethodTracker's info. Otherwise, initialize it to undefined.
+        let cx = GlobalScope::get_cx();
+        rooted!(in(*cx) let mut info = UndefinedValue());
+        if let Some(tracker) = &api_method_tracker {
+            info.set(tracker.info.get());
+        }
+
+        // Step 2. Let event be the result of creating an event given NavigateEvent, in
+        // navigation's relevant realm.
+        let event = NavigateEvent::new(
+            &self.window,
+            navigation_type,
+            destination,
+            can_intercept,
+            cancelable,
+            false,
+            hash_change,
+            info.handle(),
+            can_gc,
+        );
+
+        // Step 25. Set navigation's ongoing navigate event to event.
+        self.ongoing_navigate_event.set(Some(&event));
+
+        // Note: From <https://html.spec.whatwg.org/multipage/#fire-a-push/replace/reload-navigate-event>
+        // and <https://html.spec.whatwg.org/multipage/#fire-a-traverse-navigate-event>:
+        // Set navigation's ongoing API method tracker to apiMethodTracker.
+        *self.ongoing_api_method_tracker.borrow_mut() = api_method_tracker;
+
+        // Step 28. Let dispatchResult be the result of dispatching event at navigation.
+        let dispatch_result = event.upcast::<Event>().fire(self.upcast(), can_gc);
+
+        // Step 29. If dispatchResult is false:
+        if !dispatch_result {
+            // Step 29.2. If event's abort controller's signal is not aborted, then abort the
+            // ongoing navigation given navigation.
+            if !event.signal().aborted() {
+                self.abort_the_ongoing_navigation(Error::Abort, can_gc);
+            }
+
+            // Step 29.3. Return false.
+            return false;
+        }
+
+        // Step 30. Let endResultIsSameDocument be true if event's interception state is not
+        // "none" or event's destination's is same document is true.
+        let end_result_is_same_document =
+            event.interception_state() != InterceptionState::None || destination.is_same_document();
+
+        // Step 32. If event's interception state is not "none":
+        if event.interception_state() != InterceptionState::None {
+            // Step 32.1. Set event's interception state to "committed".
+            event.set_interception_state(InterceptionState::Committed);
+
+            // Step 32.2. Let fromNHE be the current entry of navigation.
+            // Step 32.4. Set navigation's transition to a new NavigationTransition created in
+            // navigation's relevant realm, whose navigation type is navigationType, from entry
+            // is fromNHE, and whose finished promise is a new promise created in navigation's
+            // relevant realm.
+            if let Some(from) = self.current_entry() {
+                self.transition.set(Some(&NavigationTransition::new(
+                    &self.window,
+                    navigation_type,
+                    &from,
+                    can_gc,
+                )));
+            }
+
+            match navigation_type {
+                // Step 32.6. If navigationType is "traverse", then set navigation's suppress
+                // normal scroll restoration during ongoing navigation to true.
+                NavigationType::Traverse => {},
+                // Step 32.7. If navigationType is "push" or "replace", then run the URL and
+                // history update steps given document and event's destination's URL, with
+                // serializedData set to event's classic history API state and historyHandling
+                // set to navigationType.
+                NavigationType::Push | NavigationType::Replace => {
+                    let push_or_replace = if navigation_type == NavigationType::Push {
+                        PushOrReplace::Push
+                    } else {
+                        PushOrReplace::Replace
+                    };
+                    self.window.History().url_and_history_update_steps(
+                        destination.url().clone(),
+                        classic_history_api_state,
+                        push_or_replace,
+                        can_gc,
+                    );
+                },
+                // Step 32.8. Otherwise, if navigationType is "reload", then update the
+                // navigation API entries for a same-document navigation given navigation,
+                // navigable's active session history entry, and "reload".
+                NavigationType::Reload => self.update_entries_for_same_document_navigation(
+                    NavigationType::Reload,
+                    document_url,
+                    None,
+                    can_gc,
+                ),
+            }
+        }
+
+        // Step 33. If endResultIsSameDocument is true:
+        if end_result_is_same_document {
+            // Note: The handlers of a traversal only run once it has been committed, see
+            // `finish_same_document_traversal`.
+            if navigation_type != NavigationType::Traverse {
+                self.run_the_navigation_handlers(&event, can_gc);
+            }
+        }
+        // Step 34. Otherwise, if apiMethodTracker is non-null, then clean up apiMethodTracker.
+        else {
+            self.ongoing_navigate_event.set(None);
+            self.ongoing_api_method_tracker.borrow_mut().take();
+        }
+
+        // Step 35. If event's interception state is "none", then return true.
+        // Step 36. Return false.
+        event.interception_state() == InterceptionState::None
+    }
+
+    /// Steps 33.1 to 33.5 of <https://html.spec.whatwg.org/multipage/#inner-navigate-event-firing-algorithm>
+    fn run_the_navigation_handlers(&self, event: &NavigateEvent, can_gc: CanGc) {
+        let global = self.global();
+
+        // Step 33.1. Let promisesList be an empty list.
+        // Step 33.2. For each handler of event's navigation handler list: append the result
+        // of invoking handler with an empty arguments list to promisesList.
+        let promises = event
+            .navigation_handlers()
+            .iter()
+            .map(|handler| {
+                handler
+                    .Call__(ExceptionHandling::Rethrow, can_gc)
+                    .unwrap_or_else(|error| {
+                        let promise = Promise::new(&global, can_gc);
+                        promise.reject_error(error, can_gc);
+                        promise
+                    })
+            })
+            .collect();
+
+        // Step 33.3. If promisesList's size is 0, then set promisesList to « a promise
+        // resolved with undefined ».
+        // Step 33.5. Wait for all of promisesList, with the following success steps and
+        // failure steps.
+        let realm = enter_realm(&*global);
+        let comp = InRealm::Entered(&realm);
+        let promise = wait_for_all_promise(GlobalScope::get_cx(), &global, promises, comp, can_gc);
+        let handler = PromiseNativeHandler::new(
+            &global,
+            Some(Box::new(NavigationHandlersFulfillmentHandler {
+                navigation: Dom::from_ref(self),
+                event: Dom::from_ref(event),
+            })),
+            Some(Box::new(NavigationHandlersRejectionHandler {
+                navigation: Dom::from_ref(self),
+                event: Dom::from_ref(event),
+            })),
+            can_gc,
+        );
+        promise.append_native_handler(&handler, comp, can_gc);
+    }
+
+    /// The success and failure steps of step 33.5 of
+    /// <https://html.spec.whatwg.org/multipage/#inner-navigate-event-firing-algorithm>, where
+    /// `rejection` is the reason of the failure.
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    fn finish_navigation_handlers(
+        &self,
+        event: &NavigateEvent,
+        rejection: Option<HandleValue>,
+        can_gc: CanGc,
+    ) {
+        // Step 1. If event's relevant global object is not fully active, then abort these
+        // steps.
+        if !self.window.Document().is_fully_active() {
+            return;
+        }
+
+        // Step 2. If event's abort controller's signal is aborted, then abort these steps.
+        if event.signal().aborted() {
+            return;
+        }
+
+        // Step 3. Assert: event equals navigation's ongoing navigate event.
+        // Step 4. Set navigation's ongoing navigate event to null.
+        self.ongoing_navigate_event.set(None);
+
+        // Step 5. Finish event given whether the handlers were fulfilled.
+        event.finish(rejection.is_none());
+
+        let api_method_tracker = self.ongoing_api_method_tracker.borrow_mut().take();
+        let transition = self.transition.take();
+        match rejection {
+            None => {
+                // Step 6. Fire an event named navigatesuccess at navigation.
+                self.upcast::<EventTarget>()
+                    .fire_event(Atom::from("navigatesuccess"), can_gc);
+
+                // Step 7. If navigation's transition is not null, then resolve navigation's
+                // transition's finished promise with undefined.
+                // Step 8. Set navigation's transition to null.
+                if let Some(transition) = transition {
+                    transition.finished().resolve_native(&(), can_gc);
+                }
+
+                // Step 9. If apiMethodTracker is non-null, then resolve the finished promise
+                // for apiMethodTracker.
+                if let Some(api_method_tracker) = api_method_tracker {
+                    api_method_tracker.resolve_the_finished_promise(can_gc);
+                }
+            },
+            Some(reason) => {
+                // Step 6. Fire an event named navigateerror at navigation using ErrorEvent,
+                // with error initialized to rejectionReason.
+                self.fire_navigate_error_event(reason, can_gc);
+
+                // Step 7. If navigation's transition is not null, then reject navigation's
+                // transition's finished promise with rejectionReason.
+                // Step 8. Set navigation's transition to null.
+                let cx = GlobalScope::get_cx();
+                if let Some(transition) = transition {
+                    transition.finished().reject(cx, reason, can_gc);
+                }
+
+                // Step 9. If apiMethodTracker is non-null, then reject the finished promise
+                // for apiMethodTracker with rejectionReason.
+                if let Some(api_method_tracker) = api_method_tracker {
+                    api_method_tracker.reject_the_finished_promise(cx, reason, can_gc);
+                }
+            },
+        }
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#inform-the-navigation-api-about-aborting-navigation>
+    fn inform_the_navigation_api_about_aborting_navigation(&self, can_gc: CanGc) {
+        // Step 1. If navigation's ongoing navigate event is null, then return.
+        if self.ongoing_navigate_event.get().is_none() {
+            return;
+        }
+
+        // Step 2. Abort the ongoing navigation given navigation.
+        self.abort_the_ongoing_navigation(Error::Abort, can_gc);
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#abort-the-ongoing-navigation>
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    fn abort_the_ongoing_navigation(&self, error: Error, can_gc: CanGc) {
+        // Step 1. Let event be navigation's ongoing navigate event.
+        // Step 2. Assert: event is not null.
+        let Some(event) = self.ongoing_navigate_event.get() else {
+            return;
+        };
+
+        // Step 5. If error was not given, then let error be a new "AbortError" DOMException
+        // created in navigation's relevant realm.
+        let global = self.global();
+        let cx = GlobalScope::get_cx();
+        rooted!(in(*cx) let mut reason = UndefinedValue());
+        error.to_jsval(cx, &global, reason.handle_mut(), can_gc);
+
+        // Step 6. If event's dispatch flag is set, then set event's canceled flag to true.
+        if event.upcast::<Event>().dispatching() {
+            event.upcast::<Event>().PreventDefault();
+        }
+
+        // Step 7. Signal abort on event's abort controller given error.
+        let realm = enter_realm(&*global);
+        event
+            .signal()
+            .signal_abort(cx, reason.handle(), InRealm::Entered(&realm), can_gc);
+
+        // Step 8. Set navigation's ongoing navigate event to null.
+        self.ongoing_navigate_event.set(None);
+
+        // Step 9. Fire an event named navigateerror at navigation using ErrorEvent, with
+        // error initialized to error.
+        self.fire_navigate_error_event(reason.handle(), can_gc);
+
+        // Step 10. If navigation's ongoing API method tracker is non-null, then reject the
+        // finished promise for apiMethodTracker with error.
+        if let Some(api_method_tracker) = self.ongoing_api_method_tracker.borrow_mut().take() {
+            api_method_tracker.reject_the_finished_promise(cx, reason.handle(), can_gc);
+        }
+
+        // Step 11. If navigation's transition is not null, then:
+        // Step 11.1. Reject navigation's transition's finished promise with error.
+        // Step 11.2. Set navigation's transition to null.
+        if let Some(transition) = self.transition.take() {
+            transition.finished().reject(cx, reason.handle(), can_gc);
+        }
+    }
+
+    fn fire_navigate_error_event(&self, error: HandleValue, can_gc: CanGc) {
+        let event = ErrorEvent::new(
+            &self.global(),
+            Atom::from("navigateerror"),
+            EventBubbles::DoesNotBubble,
+            EventCancelable::NotCancelable,
+            DOMString::new(),
+            DOMString::new(),
+            0,
+            0,
+            error,
+            can_gc,
+        );
+        event.upcast::<Event>().fire(self.upcast(), can_gc);
+    }
+
+    fn fire_current_entry_change_event(
+        &self,
+        navigation_type: Option<NavigationType>,
+        from: &NavigationHistoryEntry,
+        can_gc: CanGc,
+    ) {
+        let event = NavigationCurrentEntryChangeEvent::new(
+            &self.window,
+            Atom::from("currententrychange"),
+            EventBubbles::DoesNotBubble,
+            EventCancelable::NotCancelable,
+            navigation_type,
+            from,
+            can_gc,
+        );
+        event.upcast::<Event>().fire(self.upcast(), can_gc);
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#navigation-api-early-error-result>
+    fn early_error_result(&self, error: Error, can_gc: CanGc) -> NavigationResult {
+        let global = self.global();
+        let committed = Promise::new(&global, can_gc);
+        committed.reject_error(error.clone(), can_gc);
+        let finished = Promise::new(&global, can_gc);
+        finished.reject_error(error, can_gc);
+        NavigationResult {
+            committed: Some(committed),
+            finished: Some(finished),
+        }
+    }
+
+    /// Check that the document of this navigation can start a navigation, as done by the
+    /// navigation methods.
+    fn check_document_can_navigate(&self) -> ErrorResult {
+        // If document is not fully active, then return an early error result for an
+        // "InvalidStateError" DOMException.
+        // If document's unload counter is greater than 0, then return an early error result
+        // for an "InvalidStateError" DOMException.
+        let document = self.window.Document();
+        if !document.is_fully_active() || document.is_prompting_or_unloading() {
+            return Err(Error::InvalidState);
+        }
+        Ok(())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#maybe-set-the-upcoming-non-traverse-api-method-tracker>
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    fn maybe_set_the_upcoming_non_traverse_api_method_tracker(
+        &self,
+        info: HandleValue,
+        serialized_state: Option<Vec<u8>>,
+        can_gc: CanGc,
+    ) -> NavigationResult {
+        // Step 1. Let committedPromise and finishedPromise be new promises created in
+        // navigation's relevant realm.
+        // Step 3. Let apiMethodTracker be a new navigation API method tracker.
+        let api_method_tracker =
+            NavigationApiMethodTracker::new(&self.global(), None, info, serialized_state, can_gc);
+        let result = api_method_tracker.derived_result();
+
+        // Step 4. Assert: navigation's upcoming non-traverse API method tracker is null.
+        // Step 5. If navigation does not have entries and events disabled, then set
+        // navigation's upcoming non-traverse API method tracker to apiMethodTracker.
+        // Note: If it does, the promises never settle, as no navigate event is fired.
+        if self.has_entries_and_events_disabled() {
+            api_method_tracker
+                .committed_promise
+                .set_promise_is_handled();
+        } else {
+            *self.upcoming_non_traverse_api_method_tracker.borrow_mut() = Some(api_method_tracker);
+        }
+
+        // Step 6. Return apiMethodTracker.
+        result
+    }
+
+    /// Steps 12 and 13 of <https://html.spec.whatwg.org/multipage/#dom-navigation-navigate>
+    /// and <https://html.spec.whatwg.org/multipage/#dom-navigation-reload>
+    fn navigation_result_after_navigating(
+        &self,
+        result: NavigationResult,
+        can_gc: CanGc,
+    ) -> NavigationResult {
+        // Step 12. If this's upcoming non-traverse API method tracker is apiMethodTracker,
+        // then:
+        // Note: This means the navigate algorithm bailed out before ever getting to the inner
+        // navigate event firing algorithm which would promote that upcoming API method tracker
+        // to ongoing.
+        // Step 12.1. Set this's upcoming non-traverse API method tracker to null.
+        // Step 12.2. Return an early error result for an "AbortError" DOMException.
+        if self
+            .upcoming_non_traverse_api_method_tracker
+            .borrow_mut()
+            .take()
+            .is_some()
+        {
+            return self.early_error_result(Error::Abort, can_gc);
+        }
+
+        // Step 13. Return a navigation API method tracker-derived result for apiMethodTracker.
+        result
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#performing-a-navigation-api-traversal>
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    fn perform_a_navigation_api_traversal(
+        &self,
+        key: DOMString,
+        options: &NavigationOptions,
+        can_gc: CanGc,
+    ) -> NavigationResult {
+        // Step 1. Let document be navigation's relevant global object's associated Document.
+        // Step 2. If document is not fully active, then return an early error result for an
+        // "InvalidStateError" DOMException.
+        // Step 3. If document's unload counter is greater than 0, then return an early error
+        // result for an "InvalidStateError" DOMException.
+        if let Err(error) = self.check_document_can_navigate() {
+            return self.early_error_result(error, can_gc);
+        }
+
+        // Step 4. Let current be the current entry of navigation.
+        let Some(current) = self.current_entry() else {
+            return self.early_error_result(Error::InvalidState, can_gc);
+        };
+
+        // Step 5. If key equals current's session history entry's navigation API key, then
+        // return «[ "committed" → a promise resolved with current, "finished" → a promise
+        // resolved with current ]».
+        if *current.key() == key {
+            let global = self.global();
+            let committed = Promise::new(&global, can_gc);
+            committed.resolve_native(&current, can_gc);
+            let finished = Promise::new(&global, can_gc);
+            finished.resolve_native(&current, can_gc);
+            return NavigationResult {
+                committed: Some(committed),
+                finished: Some(finished),
+            };
+        }
+
+        // Step 6. If navigation's upcoming traverse API method trackers[key] exists, then
+        // return a navigation API method tracker-derived result for navigation's upcoming
+        // traverse API method trackers[key].
+        if let Some(tracker) = self
+            .upcoming_traverse_api_method_trackers
+            .borrow()
+            .iter()
+            .find(|tracker| tracker.key.as_ref() == Some(&key))
+        {
+            return tracker.derived_result();
+        }
+
+        let entry_index = self
+            .entry_list
+            .borrow()
+            .iter()
+            .position(|entry| *entry.key() == key);
+        let (Some(entry_index), Some(current_index)) =
+            (entry_index, self.current_entry_index.get())
+        else {
+            return self.early_error_result(Error::InvalidState, can_gc);
+        };
+
+        // Step 7. Let info be options["info"], if it exists; otherwise, undefined.
+        // Step 8. Let apiMethodTracker be the result of adding an upcoming traverse API method
+        // tracker for navigation given key and info.
+        let api_method_tracker = NavigationApiMethodTracker::new(
+            &self.global(),
+            Some(key),
+            options.info.handle(),
+            None,
+            can_gc,
+        );
+        let result = api_method_tracker.derived_result();
+        self.upcoming_traverse_api_method_trackers
+            .borrow_mut()
+            .push(api_method_tracker);
+
+        // Step 9. Let navigable be document's node navigable.
+        // Step 10. Let traversable be navigable's traversable navigable.
+        // Step 12. Append the following session history traversal steps to traversable.
+        // Note: The session history is kept by the constellation, which traverses it by the
+        // number of entries of this navigable between the current entry and the target one.
+        let delta = entry_index as isize - current_index as isize;
+        self.window
+            .send_to_constellation(ScriptToConstellationMessage::TraverseNavigableHistory(
+                delta,
+            ));
+
+        // Step 13. Return a navigation API method tracker-derived result for apiMethodTracker.
+        result
+    }
+}
+
+impl NavigationMethods<crate::DomTypeHolder> for Navigation {
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigation-entries>
+    fn Entries(&self) -> Vec<DomRoot<NavigationHistoryEntry>> {
+        self.ensure_entries(CanGc::note());
+
+        // Step 1. If this has entries and events disabled, then return the empty list.
+        if self.has_entries_and_events_disabled() {
+            return vec![];
+        }
+
+        // Step 2. Return this's entry list.
+        self.entry_list
+            .borrow()
+            .iter()
+            .map(|entry| DomRoot::from_ref(&**entry))
+            .collect()
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigation-currententry>
+    fn GetCurrentEntry(&self) -> Option<DomRoot<NavigationHistoryEntry>> {
+        self.ensure_entries(CanGc::note());
+        self.current_entry()
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigation-updatecurrententry>
+    fn UpdateCurrentEntry(
+        &self,
+        options: RootedTraceableBox<NavigationUpdateCurrentEntryOptions>,
+        can_gc: CanGc,
+    ) -> ErrorResult {
+        self.ensure_entries(can_gc);
+
+        // Step 1. Let current be the current entry of this.
+        // Step 2. If current is null, then throw an "InvalidStateError" DOMException.
+        let Some(current) = self.current_entry() else {
+            return Err(Error::InvalidState);
+        };
+
+        // Step 3. Let serializedState be StructuredSerializeForStorage(options["state"]),
+        // rethrowing any exceptions.
+        let serialized_state =
+            structuredclone::write(GlobalScope::get_cx(), options.state.handle(), None)?;
+
+        // Step 4. Set current's session history entry's navigation API state to
+        // serializedState.
+        current.set_state(Some(serialized_state.serialized));
+
+        // Step 5. Fire an event named currententrychange at this using
+        // NavigationCurrentEntryChangeEvent, with its navigationType attribute initialized to
+        // null and its from initialized to current.
+        self.fire_current_entry_change_event(None, &current, can_gc);
+        Ok(())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigation-transition>
+    fn GetTransition(&self) -> Option<DomRoot<NavigationTransition>> {
+        self.transition.get()
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigation-cangoback>
+    fn CanGoBack(&self) -> bool {
+        self.ensure_entries(CanGc::note());
+
+        // Step 1. If this has entries and events disabled, then return false.
+        if self.has_entries_and_events_disabled() {
+            return false;
+        }
+
+        // Step 2. Assert: this's current entry index is not −1.
+        // Step 3. If this's current entry index is 0, then return false.
+        // Step 4. Return true.
+        self.current_entry_index
+            .get()
+            .is_some_and(|index| index != 0)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigation-cangoforward>
+    fn CanGoForward(&self) -> bool {
+        self.ensure_entries(CanGc::note());
+
+        // Step 1. If this has entries and events disabled, then return false.
+        if self.has_entries_and_events_disabled() {
+            return false;
+        }
+
+        // Step 2. Assert: this's current entry index is not −1.
+        // Step 3. If this's current entry index is equal to this's entry list's size − 1,
+        // then return false.
+        // Step 4. Return true.
+        self.current_entry_index
+            .get()
+            .is_some_and(|index| index + 1 < self.entry_list.borrow().len())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigation-navigate>
+    fn Navigate(
+        &self,
+        url: USVString,
+        options: RootedTraceableBox<NavigationNavigateOptions>,
+        can_gc: CanGc,
+    ) -> NavigationResult {
+        self.ensure_entries(can_gc);
+        let document = self.window.Document();
+
+        // Step 1. Let urlRecord be the result of parsing a URL given url, relative to this's
+        // relevant settings object.
+        // Step 2. If urlRecord is failure, then return an early error result for a
+        // "SyntaxError" DOMException.
+        let Ok(url) = document.base_url().join(&url.0) else {
+            return self.early_error_result(Error::Syntax, can_gc);
+        };
+
+        // Step 4. If options["history"] is "push", and the navigation must be a replace given
+        // urlRecord and document, then return an early error result for a "NotSupportedError"
+        // DOMException.
+        let history_handling = match options.history {
+            NavigationBinding::NavigationHistoryBehavior::Auto => NavigationHistoryBehavior::Auto,
+            NavigationBinding::NavigationHistoryBehavior::Push => NavigationHistoryBehavior::Push,
+            NavigationBinding::NavigationHistoryBehavior::Replace => {
+                NavigationHistoryBehavior::Replace
+            },
+        };
+        if history_handling == NavigationHistoryBehavior::Push &&
+            (url.scheme() == "javascript" || document.is_initial_about_blank())
+        {
+            return self.early_error_result(Error::NotSupported, can_gc);
+        }
+
+        // Step 5. Let state be options["state"], if it exists; otherwise, undefined.
+        // Step 6. Let serializedState be StructuredSerializeForStorage(state). If this throws
+        // an exception, then return an early error result for that exception.
+        let serialized_state =
+            match serialize_navigation_api_state(GlobalScope::get_cx(), options.state.handle()) {
+                Ok(serialized_state) => serialized_state,
+                Err(error) => return self.early_error_result(error, can_gc),
+            };
+
+        // Step 7. If document is not fully active, then return an early error result for an
+        // "InvalidStateError" DOMException.
+        // Step 8. If document's unload counter is greater than 0, then return an early error
+        // result for an "InvalidStateError" DOMException.
+        if let Err(error) = self.check_document_can_navigate() {
+            return self.early_error_result(error, can_gc);
+        }
+
+        // Step 9. Let info be options["info"], if it exists; otherwise, undefined.
+        // Step 10. Let apiMethodTracker be the result of maybe setting the upcoming
+        // non-traverse API method tracker for this given info and serializedState.
+        let result = self.maybe_set_the_upcoming_non_traverse_api_method_tracker(
+            options.parent.info.handle(),
+            serialized_state,
+            can_gc,
+        );
+
+        // Step 11. Navigate document's node navigable to urlRecord using document, with
+        // historyHandling set to options["history"] and navigationAPIState set to
+        // serializedState.
+        self.window.Location().navigate(
+            url,
+            history_handling,
+            LocationNavigationType::Normal,
+            can_gc,
+        );
+
+        self.navigation_result_after_navigating(result, can_gc)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigation-reload>
+    fn Reload(
+        &self,
+        options: RootedTraceableBox<NavigationReloadOptions>,
+        can_gc: CanGc,
+    ) -> NavigationResult {
+        self.ensure_entries(can_gc);
+
+        // Step 2. Let serializedState be StructuredSerializeForStorage(undefined).
+        // Step 3. If options["state"] exists, then set serializedState to
+        // StructuredSerializeForStorage(options["state"]). If this throws an exception, then
+        // return an early error result for that exception.
+        // Step 4. Otherwise: if current is non-null, then set serializedState to current's
+        // session history entry's navigation API state.
+        let serialized_state =
+            match serialize_navigation_api_state(GlobalScope::get_cx(), options.state.handle()) {
+                Ok(Some(serialized_state)) => Some(serialized_state),
+                Ok(None) => self.current_entry().and_then(|entry| entry.state()),
+                Err(error) => return self.early_error_result(error, can_gc),
+            };
+
+        // Step 5. If document is not fully active, then return an early error result for an
+        // "InvalidStateError" DOMException.
+        // Step 6. If document's unload counter is greater than 0, then return an early error
+        // result for an "InvalidStateError" DOMException.
+        if let Err(error) = self.check_document_can_navigate() {
+            return self.early_error_result(error, can_gc);
+        }
+
+        // Step 7. Let info be options["info"], if it exists; otherwise, undefined.
+        // Step 8. Let apiMethodTracker be the result of maybe setting the upcoming
+        // non-traverse API method tracker for this given info and serializedState.
+        let result = self.maybe_set_the_upcoming_non_traverse_api_method_tracker(
+            options.parent.info.handle(),
+            serialized_state,
+            can_gc,
+        );
+
+        // Step 9. Reload document's node navigable with navigationAPIState set to
+        // serializedState.
+        self.window.Location().navigate(
+            self.window.get_url(),
+            NavigationHistoryBehavior::Replace,
+            LocationNavigationType::ReloadByScript,
+            can_gc,
+        );
+
+        self.navigation_result_after_navigating(result, can_gc)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigation-traverseto>
+    fn TraverseTo(
+        &self,
+        key: DOMString,
+        options: RootedTraceableBox<NavigationOptions>,
+        can_gc: CanGc,
+    ) -> NavigationResult {
+        self.ensure_entries(can_gc);
+
+        // Step 1. If this's current entry index is −1, then return an early error result for
+        // an "InvalidStateError" DOMException.
+        // Step 2. If this's entry list does not contain a NavigationHistoryEntry whose session
+        // history entry's navigation API key equals key, then return an early error result
+        // for an "InvalidStateError" DOMException.
+        if self.current_entry_index.get().is_none() ||
+            !self
+                .entry_list
+                .borrow()
+                .iter()
+                .any(|entry| *entry.key() == key)
+        {
+            return self.early_error_result(Error::InvalidState, can_gc);
+        }
+
+        // Step 3. Return the result of performing a navigation API traversal given this, key,
+        // and options.
+        self.perform_a_navigation_api_traversal(key, &options, can_gc)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigation-back>
+    fn Back(
+        &self,
+        options: RootedTraceableBox<NavigationOptions>,
+        can_gc: CanGc,
+    ) -> NavigationResult {
+        self.ensure_entries(can_gc);
+
+        // Step 1. If this's current entry index is −1 or 0, then return an early error result
+        // for an "InvalidStateError" DOMException.
+        // Step 2. Let key be this's entry list[this's current entry index − 1]'s session
+        // history entry's navigation API key.
+        let key = self
+            .current_entry_index
+            .get()
+            .and_then(|index| index.checked_sub(1))
+            .and_then(|index| {
+                self.entry_list
+                    .borrow()
+                    .get(index)
+                    .map(|entry| entry.key().clone())
+            });
+        let Some(key) = key else {
+            return self.early_error_result(Error::InvalidState, can_gc);
+        };
+
+        // Step 3. Return the result of performing a navigation API traversal given this, key,
+        // and options.
+        self.perform_a_navigation_api_traversal(key, &options, can_gc)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigation-forward>
+    fn Forward(
+        &self,
+        options: RootedTraceableBox<NavigationOptions>,
+        can_gc: CanGc,
+    ) -> NavigationResult {
+        self.ensure_entries(can_gc);
+
+        // Step 1. If this's current entry index is −1 or is equal to this's entry list's
+        // size − 1, then return an early error result for an "InvalidStateError"
+        // DOMException.
+        // Step 2. Let key be this's entry list[this's current entry index + 1]'s session
+        // history entry's navigation API key.
+        let key = self.current_entry_index.get().and_then(|index| {
+            self.entry_list
+                .borrow()
+                .get(index + 1)
+                .map(|entry| entry.key().clone())
+        });
+        let Some(key) = key else {
+            return self.early_error_result(Error::InvalidState, can_gc);
+        };
+
+        // Step 3. Return the result of performing a navigation API traversal given this, key,
+        // and options.
+        self.perform_a_navigation_api_traversal(key, &options, can_gc)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#handler-navigation-onnavigate
+    event_handler!(navigate, GetOnnavigate, SetOnnavigate);
+
+    // https://html.spec.whatwg.org/multipage/#handler-navigation-onnavigatesuccess
+    event_handler!(navigatesuccess, GetOnnavigatesuccess, SetOnnavigatesuccess);
+
+    // https://html.spec.whatwg.org/multipage/#handler-navigation-onnavigateerror
+    event_handler!(navigateerror, GetOnnavigateerror, SetOnnavigateerror);
+
+    // https://html.spec.whatwg.org/multipage/#handler-navigation-oncurrententrychange
+    event_handler!(
+        currententrychange,
+        GetOncurrententrychange,
+        SetOncurrententrychange
+    );
+}
+
+/// The success steps of step 33.5 of
+/// <https://html.spec.whatwg.org/multipage/#inner-navigate-event-firing-algorithm>
+#[derive(JSTraceable, MallocSizeOf)]
+#[cfg_attr(crown, crown::unrooted_must_root_lint::must_root)]
+struct NavigationHandlersFulfillmentHandler {
+    navigation: Dom<Navigation>,
+    event: Dom<NavigateEvent>,
+}
+
+impl Callback for NavigationHandlersFulfillmentHandler {
+    fn callback(&self, _cx: JSContext, _v: HandleValue, _realm: InRealm, can_gc: CanGc) {
+        self.navigation
+            .finish_navigation_handlers(&self.event, None, can_gc);
+    }
+}
+
+/// The failure steps of step 33.5 of
+/// <https://html.spec.whatwg.org/multipage/#inner-navigate-event-firing-algorithm>
+#[derive(JSTraceable, MallocSizeOf)]
+#[cfg_attr(crown, crown::unrooted_must_root_lint::must_root)]
+struct NavigationHandlersRejectionHandler {
+    navigation: Dom<Navigation>,
+    event: Dom<NavigateEvent>,
+}
+
+impl Callback for NavigationHandlersRejectionHandler {
+    fn callback(&self, _cx: JSContext, v: HandleValue, _realm: InRealm, can_gc: CanGc) {
+        self.navigation
+            .finish_navigation_handlers(&self.event, Some(v), can_gc);
+    }
+}
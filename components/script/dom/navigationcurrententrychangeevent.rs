@@ -0,0 +1,120 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use js::rust::HandleObject;
+use stylo_atoms::Atom;
+
+use crate::dom::bindings::codegen::Bindings::EventBinding::EventMethods;
+use crate::dom::bindings::codegen::Bindings::NavigationBinding::NavigationType;
+use crate::dom::bindings::codegen::Bindings::NavigationCurrentEntryChangeEventBinding;
+use crate::dom::bindings::codegen::Bindings::NavigationCurrentEntryChangeEventBinding::NavigationCurrentEntryChangeEventMethods;
+use crate::dom::bindings::error::Fallible;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::reflect_dom_object_with_proto;
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::event::{Event, EventBubbles, EventCancelable};
+use crate::dom::navigationhistoryentry::NavigationHistoryEntry;
+use crate::dom::window::Window;
+use crate::script_runtime::CanGc;
+
+/// <https://html.spec.whatwg.org/multipage/#navigationcurrententrychangeevent>
+#[dom_struct]
+pub(crate) struct NavigationCurrentEntryChangeEvent {
+    event: Event,
+    navigation_type: Option<NavigationType>,
+    from: Dom<NavigationHistoryEntry>,
+}
+
+impl NavigationCurrentEntryChangeEvent {
+    pub(crate) fn new(
+        window: &Window,
+        type_: Atom,
+        can_bubble: EventBubbles,
+        cancelable: EventCancelable,
+        navigation_type: Option<NavigationType>,
+        from: &NavigationHistoryEntry,
+        can_gc: CanGc,
+    ) -> DomRoot<NavigationCurrentEntryChangeEvent> {
+        Self::new_with_proto(
+            window,
+            None,
+            type_,
+            can_bubble,
+            cancelable,
+            navigation_type,
+            from,
+            can_gc,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn new_with_proto(
+        window: &Window,
+        proto: Option<HandleObject>,
+        type_: Atom,
+        can_bubble: EventBubbles,
+        cancelable: EventCancelable,
+        navigation_type: Option<NavigationType>,
+        from: &NavigationHistoryEntry,
+        can_gc: CanGc,
+    ) -> DomRoot<NavigationCurrentEntryChangeEvent> {
+        let ev = reflect_dom_object_with_proto(
+            Box::new(NavigationCurrentEntryChangeEvent {
+                event: Event::new_inherited(),
+                navigation_type,
+                from: Dom::from_ref(from),
+            }),
+            window,
+            proto,
+            can_gc,
+        );
+
+        {
+            let event = ev.upcast::<Event>();
+            event.init_event(type_, bool::from(can_bubble), bool::from(cancelable));
+        }
+        ev
+    }
+}
+
+impl NavigationCurrentEntryChangeEventMethods<crate::DomTypeHolder>
+    for NavigationCurrentEntryChangeEvent
+{
+    /// <https://html.spec.whatwg.org/multipage/#navigationcurrententrychangeevent>
+    fn Constructor(
+        window: &Window,
+        proto: Option<HandleObject>,
+        can_gc: CanGc,
+        type_: DOMString,
+        init: &NavigationCurrentEntryChangeEventBinding::NavigationCurrentEntryChangeEventInit,
+    ) -> Fallible<DomRoot<NavigationCurrentEntryChangeEvent>> {
+        Ok(NavigationCurrentEntryChangeEvent::new_with_proto(
+            window,
+            proto,
+            Atom::from(type_),
+            EventBubbles::from(init.parent.bubbles),
+            EventCancelable::from(init.parent.cancelable),
+            init.navigationType,
+            &init.from,
+            can_gc,
+        ))
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigationcurrententrychangeevent-navigationtype>
+    fn GetNavigationType(&self) -> Option<NavigationType> {
+        self.navigation_type
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigationcurrententrychangeevent-from>
+    fn From(&self) -> DomRoot<NavigationHistoryEntry> {
+        DomRoot::from_ref(&*self.from)
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-event-istrusted>
+    fn IsTrusted(&self) -> bool {
+        self.event.IsTrusted()
+    }
+}
@@ -0,0 +1,118 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use js::rust::MutableHandleValue;
+use servo_url::ServoUrl;
+
+use crate::dom::bindings::codegen::Bindings::NavigationDestinationBinding::NavigationDestinationMethods;
+use crate::dom::bindings::codegen::Bindings::NavigationHistoryEntryBinding::NavigationHistoryEntryMethods;
+use crate::dom::bindings::error::Fallible;
+use crate::dom::bindings::reflector::{DomGlobal, Reflector, reflect_dom_object};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::{DOMString, USVString};
+use crate::dom::navigation::deserialize_navigation_api_state;
+use crate::dom::navigationhistoryentry::NavigationHistoryEntry;
+use crate::dom::window::Window;
+use crate::script_runtime::{CanGc, JSContext};
+
+/// <https://html.spec.whatwg.org/multipage/#navigationdestination>
+#[dom_struct]
+pub(crate) struct NavigationDestination {
+    reflector_: Reflector,
+
+    /// <https://html.spec.whatwg.org/multipage/#concept-navigationdestination-url>
+    #[no_trace]
+    url: ServoUrl,
+
+    /// <https://html.spec.whatwg.org/multipage/#concept-navigationdestination-entry>
+    entry: Option<Dom<NavigationHistoryEntry>>,
+
+    /// <https://html.spec.whatwg.org/multipage/#concept-navigationdestination-state>
+    state: Option<Vec<u8>>,
+
+    /// <https://html.spec.whatwg.org/multipage/#concept-navigationdestination-samedocument>
+    is_same_document: bool,
+}
+
+impl NavigationDestination {
+    fn new_inherited(
+        url: ServoUrl,
+        entry: Option<&NavigationHistoryEntry>,
+        state: Option<Vec<u8>>,
+        is_same_document: bool,
+    ) -> NavigationDestination {
+        NavigationDestination {
+            reflector_: Reflector::new(),
+            url,
+            entry: entry.map(Dom::from_ref),
+            state,
+            is_same_document,
+        }
+    }
+
+    pub(crate) fn new(
+        window: &Window,
+        url: ServoUrl,
+        entry: Option<&NavigationHistoryEntry>,
+        state: Option<Vec<u8>>,
+        is_same_document: bool,
+        can_gc: CanGc,
+    ) -> DomRoot<NavigationDestination> {
+        reflect_dom_object(
+            Box::new(NavigationDestination::new_inherited(
+                url,
+                entry,
+                state,
+                is_same_document,
+            )),
+            window,
+            can_gc,
+        )
+    }
+
+    pub(crate) fn url(&self) -> &ServoUrl {
+        &self.url
+    }
+
+    pub(crate) fn is_same_document(&self) -> bool {
+        self.is_same_document
+    }
+}
+
+impl NavigationDestinationMethods<crate::DomTypeHolder> for NavigationDestination {
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigationdestination-url>
+    fn Url(&self) -> USVString {
+        USVString(self.url.to_string())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigationdestination-key>
+    fn Key(&self) -> DOMString {
+        self.entry
+            .as_ref()
+            .map_or_else(DOMString::new, |entry| entry.key().clone())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigationdestination-id>
+    fn Id(&self) -> DOMString {
+        self.entry
+            .as_ref()
+            .map_or_else(DOMString::new, |entry| entry.id().clone())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigationdestination-index>
+    fn Index(&self) -> i64 {
+        self.entry.as_ref().map_or(-1, |entry| entry.Index())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigationdestination-samedocument>
+    fn SameDocument(&self) -> bool {
+        self.is_same_document
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigationdestination-getstate>
+    fn GetState(&self, _cx: JSContext, retval: MutableHandleValue) -> Fallible<()> {
+        deserialize_navigation_api_state(&self.global(), self.state.as_ref(), retval)
+    }
+}
@@ -0,0 +1,208 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use base::id::{HistoryStateId, PipelineId};
+use constellation_traits::NavigableHistoryEntry;
+use dom_struct::dom_struct;
+use js::jsval::UndefinedValue;
+use js::rust::MutableHandleValue;
+use servo_url::ServoUrl;
+
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::NavigationHistoryEntryBinding::NavigationHistoryEntryMethods;
+use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::error::Fallible;
+use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::{DOMString, USVString};
+use crate::dom::eventtarget::EventTarget;
+use crate::dom::navigation::{Navigation, deserialize_navigation_api_state};
+use crate::script_runtime::{CanGc, JSContext};
+
+/// A session history entry of the navigable of a [`Navigation`], as exposed to script.
+/// <https://html.spec.whatwg.org/multipage/#navigationhistoryentry>
+#[dom_struct]
+pub(crate) struct NavigationHistoryEntry {
+    eventtarget: EventTarget,
+
+    /// The [`Navigation`] this entry belongs to.
+    navigation: Dom<Navigation>,
+
+    /// <https://html.spec.whatwg.org/multipage/#she-url>
+    #[no_trace]
+    url: ServoUrl,
+
+    /// <https://html.spec.whatwg.org/multipage/#she-navigation-api-key>
+    key: DOMString,
+
+    /// <https://html.spec.whatwg.org/multipage/#she-navigation-api-id>
+    id: DOMString,
+
+    /// The pipeline of the document this entry belongs to.
+    #[no_trace]
+    pipeline_id: PipelineId,
+
+    /// The classic history API state of this entry, used to recognize it in the session
+    /// history of the constellation.
+    #[no_trace]
+    history_state_id: Option<HistoryStateId>,
+
+    /// <https://html.spec.whatwg.org/multipage/#she-navigation-api-state>
+    state: DomRefCell<Option<Vec<u8>>>,
+}
+
+impl NavigationHistoryEntry {
+    fn new_inherited(
+        navigation: &Navigation,
+        url: ServoUrl,
+        key: DOMString,
+        pipeline_id: PipelineId,
+        history_state_id: Option<HistoryStateId>,
+        state: Option<Vec<u8>>,
+    ) -> NavigationHistoryEntry {
+        NavigationHistoryEntry {
+            eventtarget: EventTarget::new_inherited(),
+            navigation: Dom::from_ref(navigation),
+            url,
+            key,
+            id: DOMString::from(uuid::Uuid::new_v4().to_string()),
+            pipeline_id,
+            history_state_id,
+            state: DomRefCell::new(state),
+        }
+    }
+
+    pub(crate) fn new(
+        navigation: &Navigation,
+        url: ServoUrl,
+        key: DOMString,
+        pipeline_id: PipelineId,
+        history_state_id: Option<HistoryStateId>,
+        state: Option<Vec<u8>>,
+        can_gc: CanGc,
+    ) -> DomRoot<NavigationHistoryEntry> {
+        reflect_dom_object(
+            Box::new(NavigationHistoryEntry::new_inherited(
+                navigation,
+                url,
+                key,
+                pipeline_id,
+                history_state_id,
+                state,
+            )),
+            navigation.window(),
+            can_gc,
+        )
+    }
+
+    pub(crate) fn url(&self) -> &ServoUrl {
+        &self.url
+    }
+
+    pub(crate) fn key(&self) -> &DOMString {
+        &self.key
+    }
+
+    pub(crate) fn id(&self) -> &DOMString {
+        &self.id
+    }
+
+    pub(crate) fn state(&self) -> Option<Vec<u8>> {
+        self.state.borrow().clone()
+    }
+
+    pub(crate) fn set_state(&self, state: Option<Vec<u8>>) {
+        *self.state.borrow_mut() = state;
+    }
+
+    /// Whether this entry stands for the given entry of the session history of the
+    /// constellation.
+    pub(crate) fn represents(&self, entry: &NavigableHistoryEntry) -> bool {
+        self.pipeline_id == entry.pipeline_id &&
+            self.history_state_id == entry.history_state_id &&
+            self.url == entry.url
+    }
+
+    /// The entry of the session history of the constellation this entry stands for.
+    pub(crate) fn session_history_entry(&self) -> NavigableHistoryEntry {
+        NavigableHistoryEntry {
+            pipeline_id: self.pipeline_id,
+            url: self.url.clone(),
+            history_state_id: self.history_state_id,
+        }
+    }
+
+    /// Whether this entry belongs to the document of the [`Navigation`] it is exposed by.
+    pub(crate) fn is_same_document(&self) -> bool {
+        self.pipeline_id == self.navigation.window().pipeline_id()
+    }
+
+    fn document_is_fully_active(&self) -> bool {
+        self.navigation.window().Document().is_fully_active()
+    }
+}
+
+impl NavigationHistoryEntryMethods<crate::DomTypeHolder> for NavigationHistoryEntry {
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigationhistoryentry-url>
+    fn GetUrl(&self) -> Option<USVString> {
+        // Step 1. Let document be this's relevant global object's associated Document.
+        // Step 2. If document is not fully active, then return the empty string.
+        if !self.document_is_fully_active() {
+            return Some(USVString::default());
+        }
+
+        // TODO: Step 4. If she's document does not equal document, and she's document state's
+        // request referrer policy is "no-referrer" or "origin", then return null.
+        // Step 5. Return she's URL, serialized.
+        Some(USVString(self.url.to_string()))
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigationhistoryentry-key>
+    fn Key(&self) -> DOMString {
+        if !self.document_is_fully_active() {
+            return DOMString::new();
+        }
+        self.key.clone()
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigationhistoryentry-id>
+    fn Id(&self) -> DOMString {
+        if !self.document_is_fully_active() {
+            return DOMString::new();
+        }
+        self.id.clone()
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigationhistoryentry-index>
+    fn Index(&self) -> i64 {
+        if !self.document_is_fully_active() {
+            return -1;
+        }
+        self.navigation
+            .index_of_entry(self)
+            .map_or(-1, |index| index as i64)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigationhistoryentry-samedocument>
+    fn SameDocument(&self) -> bool {
+        self.document_is_fully_active() && self.is_same_document()
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigationhistoryentry-getstate>
+    fn GetState(&self, _cx: JSContext, mut retval: MutableHandleValue) -> Fallible<()> {
+        // Step 1. If this's relevant global object's associated Document is not fully active,
+        // then return undefined.
+        if !self.document_is_fully_active() {
+            retval.set(UndefinedValue());
+            return Ok(());
+        }
+
+        // Step 2. Return StructuredDeserialize(this's session history entry's navigation API
+        // state). Rethrow any exceptions.
+        deserialize_navigation_api_state(&self.global(), self.state.borrow().as_ref(), retval)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#handler-navigationhistoryentry-ondispose
+    event_handler!(dispose, GetOndispose, SetOndispose);
+}
@@ -0,0 +1,78 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::rc::Rc;
+
+use dom_struct::dom_struct;
+
+use crate::dom::bindings::codegen::Bindings::NavigationBinding::NavigationType;
+use crate::dom::bindings::codegen::Bindings::NavigationTransitionBinding::NavigationTransitionMethods;
+use crate::dom::bindings::reflector::{Reflector, reflect_dom_object};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::navigationhistoryentry::NavigationHistoryEntry;
+use crate::dom::promise::Promise;
+use crate::dom::window::Window;
+use crate::script_runtime::CanGc;
+
+/// An ongoing navigation that was intercepted by a `navigate` event handler.
+/// <https://html.spec.whatwg.org/multipage/#navigationtransition>
+#[dom_struct]
+pub(crate) struct NavigationTransition {
+    reflector_: Reflector,
+
+    /// <https://html.spec.whatwg.org/multipage/#concept-navigationtransition-navigationtype>
+    navigation_type: NavigationType,
+
+    /// <https://html.spec.whatwg.org/multipage/#concept-navigationtransition-from>
+    from: Dom<NavigationHistoryEntry>,
+
+    /// <https://html.spec.whatwg.org/multipage/#concept-navigationtransition-finished>
+    #[ignore_malloc_size_of = "Rc"]
+    finished: Rc<Promise>,
+}
+
+impl NavigationTransition {
+    pub(crate) fn new(
+        window: &Window,
+        navigation_type: NavigationType,
+        from: &NavigationHistoryEntry,
+        can_gc: CanGc,
+    ) -> DomRoot<NavigationTransition> {
+        let finished = Promise::new(window.as_global_scope(), can_gc);
+        // The finished promise is only there for convenience, so rejecting it must not cause
+        // unhandled rejections.
+        finished.set_promise_is_handled();
+        reflect_dom_object(
+            Box::new(NavigationTransition {
+                reflector_: Reflector::new(),
+                navigation_type,
+                from: Dom::from_ref(from),
+                finished,
+            }),
+            window,
+            can_gc,
+        )
+    }
+
+    pub(crate) fn finished(&self) -> &Rc<Promise> {
+        &self.finished
+    }
+}
+
+impl NavigationTransitionMethods<crate::DomTypeHolder> for NavigationTransition {
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigationtransition-navigationtype>
+    fn NavigationType(&self) -> NavigationType {
+        self.navigation_type
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigationtransition-from>
+    fn From(&self) -> DomRoot<NavigationHistoryEntry> {
+        DomRoot::from_ref(&*self.from)
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-navigationtransition-finished>
+    fn Finished(&self) -> Rc<Promise> {
+        self.finished.clone()
+    }
+}
@@ -34,9 +34,11 @@ use crate::dom::bindings::utils::to_frozen_array;
 #[cfg(feature = "bluetooth")]
 use crate::dom::bluetooth::Bluetooth;
 use crate::dom::clipboard::Clipboard;
+use crate::dom::credentialscontainer::CredentialsContainer;
 use crate::dom::csp::{GlobalCspReporting, Violation};
 use crate::dom::gamepad::Gamepad;
 use crate::dom::gamepadevent::GamepadEventType;
+use crate::dom::geolocation::Geolocation;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::mediadevices::MediaDevices;
 use crate::dom::mediasession::MediaSession;
@@ -77,6 +79,8 @@ pub(crate) struct Navigator {
     permissions: MutNullableDom<Permissions>,
     mediasession: MutNullableDom<MediaSession>,
     clipboard: MutNullableDom<Clipboard>,
+    geolocation: MutNullableDom<Geolocation>,
+    credentials: MutNullableDom<CredentialsContainer>,
     #[cfg(feature = "webgpu")]
     gpu: MutNullableDom<GPU>,
     /// <https://www.w3.org/TR/gamepad/#dfn-hasgamepadgesture>
@@ -100,6 +104,8 @@ impl Navigator {
             permissions: Default::default(),
             mediasession: Default::default(),
             clipboard: Default::default(),
+            geolocation: Default::default(),
+            credentials: Default::default(),
             #[cfg(feature = "webgpu")]
             gpu: Default::default(),
             has_gamepad_gesture: Cell::new(false),
@@ -338,6 +344,18 @@ impl NavigatorMethods<crate::DomTypeHolder> for Navigator {
             .or_init(|| Clipboard::new(&self.global(), CanGc::note()))
     }
 
+    /// <https://w3c.github.io/geolocation/#dom-navigator-geolocation>
+    fn Geolocation(&self) -> DomRoot<Geolocation> {
+        self.geolocation
+            .or_init(|| Geolocation::new(&self.global(), CanGc::note()))
+    }
+
+    /// <https://w3c.github.io/webappsec-credential-management/#dom-navigator-credentials>
+    fn Credentials(&self) -> DomRoot<CredentialsContainer> {
+        self.credentials
+            .or_init(|| CredentialsContainer::new(&self.global(), CanGc::note()))
+    }
+
     /// <https://w3c.github.io/beacon/#sec-processing-model>
     fn SendBeacon(&self, url: USVString, data: Option<BodyInit>, can_gc: CanGc) -> Fallible<bool> {
         let global = self.global();
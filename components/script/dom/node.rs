@@ -87,7 +87,9 @@ use crate::dom::bindings::root::{Dom, DomRoot, DomSlice, LayoutDom, MutNullableD
 use crate::dom::bindings::str::{DOMString, USVString};
 use crate::dom::characterdata::{CharacterData, LayoutCharacterDataHelpers};
 use crate::dom::cssstylesheet::CSSStyleSheet;
-use crate::dom::customelementregistry::{CallbackReaction, try_upgrade_element};
+use crate::dom::customelementregistry::{
+    CallbackReaction, CustomElementRegistry, try_upgrade_element,
+};
 use crate::dom::document::{Document, DocumentSource, HasBrowsingContext, IsHTMLDocument};
 use crate::dom::documentfragment::DocumentFragment;
 use crate::dom::documenttype::DocumentType;
@@ -97,7 +99,8 @@ use crate::dom::eventtarget::EventTarget;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::htmlcanvaselement::{HTMLCanvasElement, LayoutHTMLCanvasElementHelpers};
 use crate::dom::htmlcollection::HTMLCollection;
-use crate::dom::htmlelement::HTMLElement;
+use crate::dom::htmldialogelement::HTMLDialogElement;
+use crate::dom::htmlelement::{ContentEditableState, HTMLElement};
 use crate::dom::htmliframeelement::{HTMLIFrameElement, HTMLIFrameElementLayoutMethods};
 use crate::dom::htmlimageelement::{HTMLImageElement, LayoutHTMLImageElementHelpers};
 use crate::dom::htmlinputelement::{HTMLInputElement, InputType, LayoutHTMLInputElementHelpers};
@@ -236,6 +239,10 @@ bitflags! {
         /// Whether this node resides in UA shadow DOM. Element within UA Shadow DOM
         /// will have a different style computation behavior
         const IS_IN_UA_WIDGET = 1 << 12;
+
+        /// Whether the text of this element has been painted, as reported to Largest
+        /// Contentful Paint and Element Timing.
+        const HAS_PAINTED_TEXT = 1 << 13;
     }
 }
 
@@ -332,6 +339,20 @@ impl Node {
         html: DOMString,
         can_gc: CanGc,
     ) {
+        let fragment = Node::parse_html_into_fragment(target, context_element, html, can_gc);
+
+        // Step 4. Replace all with fragment within target.
+        Node::replace_all(Some(fragment.upcast()), target, can_gc);
+    }
+
+    /// Steps 1-3 of <https://html.spec.whatwg.org/multipage/#concept-unsafely-set-html>, shared with
+    /// <https://wicg.github.io/sanitizer-api/#set-and-filter-html>.
+    pub(crate) fn parse_html_into_fragment(
+        target: &Node,
+        context_element: &Element,
+        html: DOMString,
+        can_gc: CanGc,
+    ) -> DomRoot<DocumentFragment> {
         // Step 1. Let newChildren be the result of the HTML fragment parsing algorithm.
         let new_children = ServoParser::parse_html_fragment(context_element, html, true, can_gc);
 
@@ -348,8 +369,37 @@ impl Node {
                 .unwrap();
         }
 
-        // Step 4. Replace all with fragment within target.
-        Node::replace_all(Some(fragment.upcast()), target, can_gc);
+        // Parsed elements use the scoped custom element registry of target, if it has one.
+        let registry = match target.downcast::<ShadowRoot>() {
+            Some(shadow_root) => shadow_root.scoped_custom_element_registry(),
+            None => target
+                .downcast::<Element>()
+                .and_then(Element::scoped_custom_element_registry),
+        };
+        if let Some(registry) = registry {
+            fragment
+                .upcast::<Node>()
+                .set_scoped_custom_element_registry_for_descendants(&registry);
+        }
+
+        fragment
+    }
+
+    /// <https://wicg.github.io/webcomponents/proposals/Scoped-Custom-Element-Registries>
+    /// Give every element in this subtree that doesn't have a scoped custom element registry
+    /// yet the given one, so that they are upgraded with its definitions once inserted.
+    pub(crate) fn set_scoped_custom_element_registry_for_descendants(
+        &self,
+        registry: &CustomElementRegistry,
+    ) {
+        for element in self
+            .traverse_preorder(ShadowIncluding::No)
+            .filter_map(DomRoot::downcast::<Element>)
+        {
+            if element.scoped_custom_element_registry().is_none() {
+                element.set_scoped_custom_element_registry(registry);
+            }
+        }
     }
 
     pub(crate) fn clean_up_style_and_layout_data(&self) {
@@ -883,6 +933,44 @@ impl Node {
         })
     }
 
+    /// <https://html.spec.whatwg.org/multipage/#editing-host>
+    pub(crate) fn is_editing_host(&self) -> bool {
+        self.downcast::<HTMLElement>().is_some_and(|element| {
+            matches!(
+                element.content_editable_state(),
+                ContentEditableState::True | ContentEditableState::PlaintextOnly
+            )
+        })
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#editable>
+    pub(crate) fn is_editable(&self) -> bool {
+        if self.is_editing_host() {
+            return false;
+        }
+        if self
+            .downcast::<HTMLElement>()
+            .is_some_and(|element| element.content_editable_state() == ContentEditableState::False)
+        {
+            return false;
+        }
+        self.GetParentNode()
+            .is_some_and(|parent| parent.is_editing_host() || parent.is_editable())
+    }
+
+    /// <https://w3c.github.io/editing/docs/execCommand/#editing-host-of>
+    pub(crate) fn editing_host(&self) -> Option<DomRoot<HTMLElement>> {
+        if self.is_editing_host() {
+            return self.downcast::<HTMLElement>().map(DomRoot::from_ref);
+        }
+        if !self.is_editable() {
+            return None;
+        }
+        self.ancestors()
+            .find(|ancestor| ancestor.is_editing_host())
+            .and_then(DomRoot::downcast::<HTMLElement>)
+    }
+
     pub(crate) fn is_inclusive_ancestor_of(&self, child: &Node) -> bool {
         self == child || self.is_ancestor_of(child)
     }
@@ -896,6 +984,37 @@ impl Node {
             .any(|ancestor| &*ancestor == self)
     }
 
+    /// <https://html.spec.whatwg.org/multipage/#inert>
+    /// A node is inert when it is in the subtree of an element with the `inert` attribute,
+    /// unless a modal dialog in between escapes that inertness. While a document is blocked by
+    /// a modal dialog, every node outside of that dialog is inert as well.
+    pub(crate) fn is_inert(&self) -> bool {
+        for ancestor in self.inclusive_ancestors(ShadowIncluding::Yes) {
+            let Some(element) = ancestor.downcast::<HTMLElement>() else {
+                continue;
+            };
+            if element
+                .upcast::<Element>()
+                .has_attribute(&local_name!("inert"))
+            {
+                return true;
+            }
+            if element
+                .downcast::<HTMLDialogElement>()
+                .is_some_and(|dialog| dialog.is_modal())
+            {
+                break;
+            }
+        }
+
+        let Some(dialog) = self.owner_doc().active_modal_dialog() else {
+            return false;
+        };
+        !dialog
+            .upcast::<Node>()
+            .is_shadow_including_inclusive_ancestor_of(self)
+    }
+
     pub(crate) fn following_siblings(&self) -> impl Iterator<Item = DomRoot<Node>> + use<> {
         SimpleNodeIterator {
             current: self.GetNextSibling(),
@@ -1929,6 +2048,10 @@ impl<'dom> LayoutNodeHelpers<'dom> for LayoutDom<'dom, Node> {
             return area.selection_for_layout();
         }
 
+        if let Some(text) = self.downcast::<Text>() {
+            return text.selection_for_layout();
+        }
+
         if let Some(input) = self.downcast::<HTMLInputElement>() {
             return input.selection_for_layout();
         }
@@ -2934,6 +3057,7 @@ impl Node {
                     name,
                     element.get_is(),
                     &document,
+                    element.scoped_custom_element_registry().as_deref(),
                     ElementCreator::ScriptCreated,
                     CustomElementCreationMode::Asynchronous,
                     None,
@@ -3020,6 +3144,10 @@ impl Node {
                 // Step 7.3 Set copy’s shadow root’s declarative to node’s shadow root’s declarative.
                 copy_shadow_root.set_declarative(shadow_root.is_declarative());
 
+                if let Some(registry) = shadow_root.scoped_custom_element_registry() {
+                    copy_shadow_root.set_scoped_custom_element_registry(&registry);
+                }
+
                 // Step 7.4 For each child child of node’s shadow root, in tree order: append the result of
                 // cloning child with document and the clone children flag set, to copy’s shadow root.
                 for child in shadow_root.upcast::<Node>().children() {
@@ -4127,6 +4255,18 @@ impl<'a> ChildrenMutation<'a> {
         ChildrenMutation::ReplaceAll { removed, added }
     }
 
+    /// Get the child that precedes the added or removed children.
+    pub(crate) fn prev_child(&self) -> Option<&Node> {
+        match *self {
+            ChildrenMutation::Append { prev, .. } => Some(prev),
+            ChildrenMutation::Insert { prev, .. } => Some(prev),
+            ChildrenMutation::Prepend { .. } => None,
+            ChildrenMutation::Replace { prev, .. } => prev,
+            ChildrenMutation::ReplaceAll { .. } => None,
+            ChildrenMutation::ChangeText => None,
+        }
+    }
+
     /// Get the child that follows the added or removed children.
     /// Currently only used when this mutation might force us to
     /// restyle later children (see HAS_SLOW_SELECTOR_LATER_SIBLINGS and
@@ -35,15 +35,13 @@ use super::bindings::cell::DomRefCell;
 use super::bindings::refcounted::{Trusted, TrustedPromise};
 use super::bindings::reflector::DomGlobal;
 use super::performanceresourcetiming::InitiatorType;
-use super::permissionstatus::PermissionStatus;
 use crate::dom::bindings::callback::ExceptionHandling;
 use crate::dom::bindings::codegen::Bindings::NotificationBinding::{
     NotificationAction, NotificationDirection, NotificationMethods, NotificationOptions,
     NotificationPermission, NotificationPermissionCallback,
 };
-use crate::dom::bindings::codegen::Bindings::PermissionStatusBinding::PermissionStatus_Binding::PermissionStatusMethods;
 use crate::dom::bindings::codegen::Bindings::PermissionStatusBinding::{
-    PermissionDescriptor, PermissionName, PermissionState,
+    PermissionName, PermissionState,
 };
 use crate::dom::bindings::codegen::UnionTypes::UnsignedLongOrUnsignedLongSequence;
 use crate::dom::bindings::error::{Error, Fallible};
@@ -56,7 +54,7 @@ use crate::dom::bindings::utils::to_frozen_array;
 use crate::dom::csp::{GlobalCspReporting, Violation};
 use crate::dom::eventtarget::EventTarget;
 use crate::dom::globalscope::GlobalScope;
-use crate::dom::permissions::{PermissionAlgorithm, Permissions, descriptor_permission_state};
+use crate::dom::permissions::{descriptor_permission_state, request_permission_to_use};
 use crate::dom::promise::Promise;
 use crate::dom::serviceworkerglobalscope::ServiceWorkerGlobalScope;
 use crate::dom::serviceworkerregistration::ServiceWorkerRegistration;
@@ -401,7 +399,7 @@ impl NotificationMethods<crate::DomTypeHolder> for Notification {
 
         // TODO: Step 3: Run these steps in parallel:
         // Step 3.1: Let permissionState be the result of requesting permission to use "notifications".
-        let notification_permission = request_notification_permission(global, can_gc);
+        let notification_permission = request_notification_permission(global);
 
         // Step 3.2: Queue a global task on the DOM manipulation task source given global to run these steps:
         let trusted_promise = TrustedPromise::new(promise.clone());
@@ -677,18 +675,8 @@ fn get_notifications_permission_state(global: &GlobalScope) -> NotificationPermi
     }
 }
 
-fn request_notification_permission(global: &GlobalScope, can_gc: CanGc) -> NotificationPermission {
-    let cx = GlobalScope::get_cx();
-    let promise = &Promise::new(global, can_gc);
-    let descriptor = PermissionDescriptor {
-        name: PermissionName::Notifications,
-    };
-    let status = PermissionStatus::new(global, &descriptor, can_gc);
-
-    // The implementation of `request_notification_permission` seemed to be synchronous
-    Permissions::permission_request(cx, promise, &descriptor, &status);
-
-    match status.State() {
+fn request_notification_permission(global: &GlobalScope) -> NotificationPermission {
+    match request_permission_to_use(PermissionName::Notifications, global) {
         PermissionState::Granted => NotificationPermission::Granted,
         PermissionState::Denied => NotificationPermission::Denied,
         // Should only receive "Granted" or "Denied" from the permission request
@@ -7,7 +7,7 @@ use std::collections::HashMap;
 use std::rc::Rc;
 
 use base::id::{OffscreenCanvasId, OffscreenCanvasIndex};
-use constellation_traits::{BlobImpl, TransferableOffscreenCanvas};
+use constellation_traits::{BlobImpl, PlaceholderCanvas, TransferableOffscreenCanvas};
 use dom_struct::dom_struct;
 use euclid::default::Size2D;
 use js::rust::{HandleObject, HandleValue};
@@ -38,6 +38,16 @@ use crate::dom::offscreencanvasrenderingcontext2d::OffscreenCanvasRenderingConte
 use crate::dom::promise::Promise;
 use crate::realms::{AlreadyInRealm, InRealm};
 use crate::script_runtime::{CanGc, JSContext};
+use crate::task_source::SendableTaskSource;
+
+/// The placeholder canvas element of an OffscreenCanvas that was transferred to
+/// another event loop, such as a dedicated worker. The element stays in the event
+/// loop of its document and is updated through tasks queued on it.
+#[derive(Clone, JSTraceable, MallocSizeOf)]
+pub(crate) struct RemotePlaceholder {
+    canvas: Trusted<HTMLCanvasElement>,
+    task_source: SendableTaskSource,
+}
 
 /// <https://html.spec.whatwg.org/multipage/#offscreencanvas>
 #[dom_struct]
@@ -54,6 +64,12 @@ pub(crate) struct OffscreenCanvas {
 
     /// <https://html.spec.whatwg.org/multipage/#offscreencanvas-placeholder>
     placeholder: Option<WeakRef<HTMLCanvasElement>>,
+
+    /// The placeholder canvas element, when it lives in another event loop.
+    remote_placeholder: DomRefCell<Option<RemotePlaceholder>>,
+
+    /// Whether a frame was drawn that was not yet pushed to `remote_placeholder`.
+    remote_placeholder_dirty: Cell<bool>,
 }
 
 impl OffscreenCanvas {
@@ -68,6 +84,8 @@ impl OffscreenCanvas {
             height: Cell::new(height),
             context: DomRefCell::new(None),
             placeholder,
+            remote_placeholder: DomRefCell::new(None),
+            remote_placeholder_dirty: Cell::new(false),
         }
     }
 
@@ -179,6 +197,48 @@ impl OffscreenCanvas {
             .as_ref()
             .and_then(|placeholder| placeholder.root())
     }
+
+    /// Push the current frame to the placeholder canvas element living in another
+    /// event loop, once the current task is done drawing.
+    pub(crate) fn mark_remote_placeholder_dirty(&self) {
+        if self.remote_placeholder.borrow().is_none() {
+            return;
+        }
+        if self.remote_placeholder_dirty.replace(true) {
+            return;
+        }
+        let this = Trusted::new(self);
+        self.global()
+            .task_manager()
+            .dom_manipulation_task_source()
+            .queue(task!(commit_to_placeholder: move || {
+                this.root().commit_to_remote_placeholder();
+            }));
+    }
+
+    fn commit_to_remote_placeholder(&self) {
+        self.remote_placeholder_dirty.set(false);
+        let Some(placeholder) = self.remote_placeholder.borrow().clone() else {
+            return;
+        };
+        let image_key = match self.context().as_deref() {
+            Some(OffscreenRenderingContext::Context2d(context)) => context.update_image(),
+            _ => None,
+        };
+        let width = self.Width();
+        let height = self.Height();
+        let canvas = placeholder.canvas;
+        placeholder
+            .task_source
+            .queue(task!(update_placeholder: move || {
+                canvas.root().update_from_remote_offscreen_canvas(
+                    image_key,
+                    width,
+                    height,
+                    CanGc::note(),
+                );
+            }));
+    }
 }
 
 impl Transferable for OffscreenCanvas {
@@ -201,11 +261,6 @@ impl Transferable for OffscreenCanvas {
             return Err(Error::InvalidState);
         }
 
-        // TODO(#37882): Allow to transfer with a placeholder canvas element.
-        if self.placeholder.is_some() {
-            return Err(Error::InvalidState);
-        }
-
         // Step 2. Set value's context mode to detached.
         *self.context.borrow_mut() = Some(OffscreenRenderingContext::Detached);
 
@@ -223,10 +278,27 @@ impl Transferable for OffscreenCanvas {
         // TODO(#37918) Step 7. Set dataHolder.[[Language]] to language and
         // dataHolder.[[Direction]] to direction.
 
-        // TODO(#37882) Step 8. Set dataHolder.[[PlaceholderCanvas]] to be a
-        // weak reference to value's placeholder canvas element, if value has
-        // one, or null if it does not.
-        let transferred = TransferableOffscreenCanvas { width, height };
+        // Step 8. Set dataHolder.[[PlaceholderCanvas]] to be a weak reference
+        // to value's placeholder canvas element, if value has one, or null if
+        // it does not.
+        // Note: the receiving event loop can only reach the element through a
+        // `Trusted` reference, which keeps it alive as long as the canvas.
+        let placeholder = match self.placeholder() {
+            Some(canvas) => Some(RemotePlaceholder {
+                canvas: Trusted::new(&*canvas),
+                task_source: self
+                    .global()
+                    .task_manager()
+                    .dom_manipulation_task_source()
+                    .to_sendable(),
+            }),
+            None => self.remote_placeholder.borrow_mut().take(),
+        };
+        let transferred = TransferableOffscreenCanvas {
+            width,
+            height,
+            placeholder: placeholder.map(|placeholder| PlaceholderCanvas(Box::new(placeholder))),
+        };
 
         Ok((OffscreenCanvasId::new(), transferred))
     }
@@ -245,18 +317,25 @@ impl Transferable for OffscreenCanvas {
         // dataHolder.[[Language]] and its inherited direction to
         // dataHolder.[[Direction]].
 
-        // TODO(#37882) Step 3. If dataHolder.[[PlaceholderCanvas]] is not null,
-        // set value's placeholder canvas element to
-        // dataHolder.[[PlaceholderCanvas]] (while maintaining the weak
-        // reference semantics).
-        Ok(OffscreenCanvas::new(
+        let value = OffscreenCanvas::new(
             owner,
             None,
             transferred.width,
             transferred.height,
             None,
             CanGc::note(),
-        ))
+        );
+
+        // Step 3. If dataHolder.[[PlaceholderCanvas]] is not null, set value's
+        // placeholder canvas element to dataHolder.[[PlaceholderCanvas]] (while
+        // maintaining the weak reference semantics).
+        if let Some(placeholder) = transferred
+            .placeholder
+            .and_then(|placeholder| placeholder.0.downcast::<RemotePlaceholder>().ok())
+        {
+            *value.remote_placeholder.borrow_mut() = Some(*placeholder);
+        }
+        Ok(value)
     }
 
     fn serialized_storage<'a>(
@@ -332,6 +411,7 @@ impl OffscreenCanvasMethods<crate::DomTypeHolder> for OffscreenCanvas {
         if let Some(canvas) = self.placeholder() {
             canvas.set_natural_width(value as _, can_gc)
         }
+        self.mark_remote_placeholder_dirty();
     }
 
     /// <https://html.spec.whatwg.org/multipage/#dom-offscreencanvas-height>
@@ -350,6 +430,7 @@ impl OffscreenCanvasMethods<crate::DomTypeHolder> for OffscreenCanvas {
         if let Some(canvas) = self.placeholder() {
             canvas.set_natural_height(value as _, can_gc)
         }
+        self.mark_remote_placeholder_dirty();
     }
 
     /// <https://html.spec.whatwg.org/multipage/#dom-offscreencanvas-transfertoimagebitmap>
@@ -8,6 +8,7 @@ use crate::dom::bindings::codegen::UnionTypes::HTMLCanvasElementOrOffscreenCanva
 use canvas_traits::canvas::Canvas2dMsg;
 use dom_struct::dom_struct;
 use pixels::Snapshot;
+use webrender_api::ImageKey;
 
 use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding::{
     CanvasDirection, CanvasFillRule, CanvasImageSource, CanvasLineCap, CanvasLineJoin,
@@ -69,6 +70,12 @@ impl OffscreenCanvasRenderingContext2D {
     pub(crate) fn send_canvas_2d_msg(&self, msg: Canvas2dMsg) {
         self.context.send_canvas_2d_msg(msg)
     }
+
+    /// Flush the pending drawing commands, and return the image displaying them.
+    pub(crate) fn update_image(&self) -> Option<ImageKey> {
+        self.context.update_rendering();
+        self.context.image_key()
+    }
 }
 
 impl CanvasContext for OffscreenCanvasRenderingContext2D {
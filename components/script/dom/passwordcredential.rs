@@ -0,0 +1,290 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::rc::Rc;
+
+use dom_struct::dom_struct;
+use embedder_traits::{AllowOrDeny, EmbedderMsg, PasswordCredentialInfo};
+use ipc_channel::ipc;
+use ipc_channel::router::ROUTER;
+use js::rust::HandleObject;
+use net_traits::IpcSend;
+use net_traits::storage_thread::{StorageThreadMsg, StoredPasswordCredential};
+use servo_url::ServoUrl;
+
+use crate::dom::bindings::codegen::Bindings::CredentialBinding::{
+    CredentialMediationRequirement, CredentialMethods,
+};
+use crate::dom::bindings::codegen::Bindings::PasswordCredentialBinding::{
+    PasswordCredentialData, PasswordCredentialMethods,
+};
+use crate::dom::bindings::error::{Error, Fallible};
+use crate::dom::bindings::refcounted::TrustedPromise;
+use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object_with_proto};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::str::{DOMString, USVString};
+use crate::dom::credential::Credential;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::promise::Promise;
+use crate::dom::window::Window;
+use crate::script_runtime::CanGc;
+
+/// <https://w3c.github.io/webappsec-credential-management/#passwordcredential>
+#[dom_struct]
+pub(crate) struct PasswordCredential {
+    credential: Credential,
+    /// <https://w3c.github.io/webappsec-credential-management/#dom-passwordcredential-password>
+    password: USVString,
+    /// <https://w3c.github.io/webappsec-credential-management/#dom-credentialuserdata-name>
+    name: USVString,
+    /// <https://w3c.github.io/webappsec-credential-management/#dom-credentialuserdata-iconurl>
+    icon_url: USVString,
+}
+
+impl PasswordCredential {
+    fn new_inherited(stored: StoredPasswordCredential) -> Self {
+        Self {
+            credential: Credential::new_inherited(
+                USVString(stored.id),
+                DOMString::from("password"),
+            ),
+            password: USVString(stored.password),
+            name: USVString(stored.name),
+            icon_url: USVString(stored.icon_url),
+        }
+    }
+
+    fn new(
+        global: &GlobalScope,
+        proto: Option<HandleObject>,
+        stored: StoredPasswordCredential,
+        can_gc: CanGc,
+    ) -> DomRoot<Self> {
+        reflect_dom_object_with_proto(Box::new(Self::new_inherited(stored)), global, proto, can_gc)
+    }
+
+    /// <https://w3c.github.io/webappsec-credential-management/#abstract-opdef-create-a-passwordcredential-from-passwordcredentialdata>
+    pub(crate) fn new_from_data(
+        global: &GlobalScope,
+        proto: Option<HandleObject>,
+        data: &PasswordCredentialData,
+        can_gc: CanGc,
+    ) -> Fallible<DomRoot<Self>> {
+        // Step 2. If any of the following are the empty string, throw a TypeError exception:
+        // data’s id member’s value, data’s origin member’s value, data’s password member’s
+        // value.
+        if data.parent.id.is_empty() || data.origin.is_empty() || data.password.is_empty() {
+            return Err(Error::Type(
+                "The id, origin and password of a PasswordCredential must not be empty".to_owned(),
+            ));
+        }
+
+        // A page may only create credentials for its own origin, as those are the only ones
+        // it can store.
+        let same_origin = ServoUrl::parse(&data.origin)
+            .is_ok_and(|origin| origin.origin() == *global.origin().immutable());
+        if !same_origin {
+            return Err(Error::Security);
+        }
+
+        // Step 3. Set c’s properties as follows.
+        let stored = StoredPasswordCredential {
+            id: data.parent.id.to_string(),
+            password: data.password.to_string(),
+            name: data.name.as_deref().unwrap_or_default().to_owned(),
+            icon_url: data.iconURL.as_deref().unwrap_or_default().to_owned(),
+        };
+        Ok(Self::new(global, proto, stored, can_gc))
+    }
+
+    /// <https://w3c.github.io/webappsec-credential-management/#collectfromcredentialstore-passwordcredential>
+    pub(crate) fn discover_from_credential_store(
+        window: &Window,
+        mediation: CredentialMediationRequirement,
+        promise: &Rc<Promise>,
+        can_gc: CanGc,
+    ) {
+        let global = window.as_global_scope();
+        let origin = global.origin().immutable().clone();
+        let mut credentials = read_from_store(global, |sender| {
+            StorageThreadMsg::GetPasswordCredentials(sender, origin.clone())
+        })
+        .unwrap_or_default();
+
+        if credentials.is_empty() {
+            promise.resolve_native(&None::<DomRoot<Credential>>, can_gc);
+            return;
+        }
+
+        // A single credential may be handed out without asking the user, but only once the
+        // user has picked a credential for the origin, and the origin hasn't called
+        // `preventSilentAccess()` since.
+        // <https://w3c.github.io/webappsec-credential-management/#abstract-opdef-request-a-credential>
+        if mediation != CredentialMediationRequirement::Required && credentials.len() == 1 {
+            let prevent_silent_access = read_from_store(global, |sender| {
+                StorageThreadMsg::GetPreventSilentAccess(sender, origin.clone())
+            })
+            .unwrap_or(true);
+            if !prevent_silent_access {
+                let credential = Self::new(global, None, credentials.remove(0), can_gc);
+                promise.resolve_native(&credential, can_gc);
+                return;
+            }
+        }
+
+        // The user can't be asked in silent mediation.
+        if mediation == CredentialMediationRequirement::Silent {
+            promise.resolve_native(&None::<DomRoot<Credential>>, can_gc);
+            return;
+        }
+
+        let choices = credentials
+            .iter()
+            .map(|credential| PasswordCredentialInfo {
+                id: credential.id.clone(),
+                name: credential.name.clone(),
+                icon_url: credential.icon_url.clone(),
+            })
+            .collect();
+        let (sender, receiver) = ipc::channel().expect("Failed to create IPC channel!");
+        let task_source = global
+            .task_manager()
+            .dom_manipulation_task_source()
+            .to_sendable();
+        let trusted_promise = TrustedPromise::new(promise.clone());
+        let mut credentials = Some(credentials);
+        ROUTER.add_typed_route(
+            receiver,
+            Box::new(move |message| {
+                let Some(mut credentials) = credentials.take() else {
+                    return;
+                };
+                let chosen = message
+                    .ok()
+                    .flatten()
+                    .filter(|index| *index < credentials.len())
+                    .map(|index| credentials.swap_remove(index));
+                let trusted_promise = trusted_promise.clone();
+                task_source.queue(task!(resolve_chosen_password_credential: move || {
+                    let promise = trusted_promise.root();
+                    let global = promise.global();
+                    let can_gc = CanGc::note();
+                    let Some(chosen) = chosen else {
+                        promise.resolve_native(&None::<DomRoot<Credential>>, can_gc);
+                        return;
+                    };
+                    // The user picked a credential, so the origin may use it silently again.
+                    let _ = global.resource_threads().send(
+                        StorageThreadMsg::SetPreventSilentAccess(
+                            global.origin().immutable().clone(),
+                            false,
+                        ),
+                    );
+                    let credential = PasswordCredential::new(&global, None, chosen, can_gc);
+                    promise.resolve_native(&credential, can_gc);
+                }));
+            }),
+        );
+        window.send_to_embedder(EmbedderMsg::SelectPasswordCredential(
+            window.webview_id(),
+            window.get_url(),
+            choices,
+            sender,
+        ));
+    }
+
+    /// Ask the user whether to save this credential, saving it if they agree, and resolve
+    /// `promise` once they answered.
+    /// <https://w3c.github.io/webappsec-credential-management/#store-passwordcredential>
+    pub(crate) fn store(&self, window: &Window, promise: &Rc<Promise>) {
+        let global = window.as_global_scope();
+        let origin = global.origin().immutable().clone();
+        let stored = StoredPasswordCredential {
+            id: self.credential.Id().to_string(),
+            password: self.password.to_string(),
+            name: self.name.to_string(),
+            icon_url: self.icon_url.to_string(),
+        };
+        let info = PasswordCredentialInfo {
+            id: stored.id.clone(),
+            name: stored.name.clone(),
+            icon_url: stored.icon_url.clone(),
+        };
+
+        let (sender, receiver) = ipc::channel().expect("Failed to create IPC channel!");
+        let task_source = global
+            .task_manager()
+            .dom_manipulation_task_source()
+            .to_sendable();
+        let trusted_promise = TrustedPromise::new(promise.clone());
+        let mut stored = Some(stored);
+        ROUTER.add_typed_route(
+            receiver,
+            Box::new(move |message| {
+                let Some(stored) = stored.take() else {
+                    return;
+                };
+                let allowed = matches!(message, Ok(AllowOrDeny::Allow));
+                let origin = origin.clone();
+                let trusted_promise = trusted_promise.clone();
+                task_source.queue(task!(store_password_credential: move || {
+                    let promise = trusted_promise.root();
+                    // The page is not told whether the user agreed to save the credential.
+                    if allowed {
+                        let _ = promise.global().resource_threads().send(
+                            StorageThreadMsg::StorePasswordCredential(origin, stored),
+                        );
+                    }
+                    promise.resolve_native(&(), CanGc::note());
+                }));
+            }),
+        );
+        window.send_to_embedder(EmbedderMsg::SavePasswordCredential(
+            window.webview_id(),
+            window.get_url(),
+            info,
+            sender,
+        ));
+    }
+}
+
+impl PasswordCredentialMethods<crate::DomTypeHolder> for PasswordCredential {
+    /// <https://w3c.github.io/webappsec-credential-management/#dom-passwordcredential-passwordcredential-data>
+    fn Constructor(
+        window: &Window,
+        proto: Option<HandleObject>,
+        can_gc: CanGc,
+        data: &PasswordCredentialData,
+    ) -> Fallible<DomRoot<PasswordCredential>> {
+        Self::new_from_data(window.as_global_scope(), proto, data, can_gc)
+    }
+
+    /// <https://w3c.github.io/webappsec-credential-management/#dom-passwordcredential-password>
+    fn Password(&self) -> USVString {
+        self.password.clone()
+    }
+
+    /// <https://w3c.github.io/webappsec-credential-management/#dom-credentialuserdata-name>
+    fn Name(&self) -> USVString {
+        self.name.clone()
+    }
+
+    /// <https://w3c.github.io/webappsec-credential-management/#dom-credentialuserdata-iconurl>
+    fn IconURL(&self) -> USVString {
+        self.icon_url.clone()
+    }
+}
+
+/// Sends a request built by `message` to the credential store and waits for the answer.
+fn read_from_store<T>(
+    global: &GlobalScope,
+    message: impl FnOnce(ipc::IpcSender<T>) -> StorageThreadMsg,
+) -> Option<T>
+where
+    T: for<'de> serde::Deserialize<'de> + serde::Serialize,
+{
+    let (sender, receiver) = ipc::channel().ok()?;
+    global.resource_threads().send(message(sender)).ok()?;
+    receiver.recv().ok()
+}
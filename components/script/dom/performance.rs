@@ -19,8 +19,9 @@ use crate::dom::bindings::error::{Error, Fallible};
 use crate::dom::bindings::inheritance::Castable;
 use crate::dom::bindings::num::Finite;
 use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object};
-use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::root::{DomRoot, MutNullableDom};
 use crate::dom::bindings::str::DOMString;
+use crate::dom::eventcounts::EventCounts;
 use crate::dom::eventtarget::EventTarget;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::performanceentry::PerformanceEntry;
@@ -56,6 +57,31 @@ const INVALID_ENTRY_NAMES: &[&str] = &[
     "loadEventEnd",
 ];
 
+/// Entry types that are only delivered to performance observers.
+const NOT_AVAILABLE_FROM_TIMELINE: &[&str] = &[
+    "element",
+    "event",
+    "largest-contentful-paint",
+    "layout-shift",
+    "long-animation-frame",
+    "longtask",
+];
+
+/// The maximum number of buffered entries of a type that is not available from the
+/// performance timeline, which are only delivered to observers with the buffered flag.
+/// <https://w3c.github.io/timing-entrytypes-registry/#registry>
+fn max_observer_buffer_size(entry_type: &str) -> usize {
+    match entry_type {
+        "long-animation-frame" | "longtask" => 200,
+        _ => 150,
+    }
+}
+
+/// The default and minimum durationThreshold of the observers of "event" entries.
+/// <https://w3c.github.io/event-timing/#sec-modifications-perf-timeline>
+const DEFAULT_DURATION_THRESHOLD: Duration = Duration::milliseconds(104);
+const MINIMUM_DURATION_THRESHOLD: Duration = Duration::milliseconds(16);
+
 /// Implementation of a list of PerformanceEntry items shared by the
 /// Performance and PerformanceObserverEntryList interfaces implementations.
 #[derive(JSTraceable, MallocSizeOf)]
@@ -129,12 +155,20 @@ impl IntoIterator for PerformanceEntryList {
 struct PerformanceObserver {
     observer: DomRoot<DOMPerformanceObserver>,
     entry_types: Vec<DOMString>,
+    /// The minimum duration of the "event" entries delivered to this observer.
+    #[no_trace]
+    #[ignore_malloc_size_of = "No MallocSizeOf support for `time` crate"]
+    duration_threshold: Duration,
 }
 
 #[dom_struct]
 pub(crate) struct Performance {
     eventtarget: EventTarget,
     buffer: DomRefCell<PerformanceEntryList>,
+    /// The buffered entries of the types that are not available from the performance
+    /// timeline.
+    /// <https://w3c.github.io/performance-timeline/#performance-entry-buffer-map>
+    observer_buffer: DomRefCell<PerformanceEntryList>,
     observers: DomRefCell<Vec<PerformanceObserver>>,
     pending_notification_observers_task: Cell<bool>,
     #[no_trace]
@@ -148,6 +182,8 @@ pub(crate) struct Performance {
     resource_timing_buffer_current_size: Cell<usize>,
     resource_timing_buffer_pending_full_event: Cell<bool>,
     resource_timing_secondary_entries: DomRefCell<VecDeque<DomRoot<PerformanceEntry>>>,
+    /// <https://w3c.github.io/event-timing/#dom-performance-eventcounts>
+    event_counts: MutNullableDom<EventCounts>,
 }
 
 impl Performance {
@@ -155,6 +191,7 @@ impl Performance {
         Performance {
             eventtarget: EventTarget::new_inherited(),
             buffer: DomRefCell::new(PerformanceEntryList::new(Vec::new())),
+            observer_buffer: DomRefCell::new(PerformanceEntryList::new(Vec::new())),
             observers: DomRefCell::new(Vec::new()),
             pending_notification_observers_task: Cell::new(false),
             time_origin,
@@ -162,6 +199,7 @@ impl Performance {
             resource_timing_buffer_current_size: Cell::new(0),
             resource_timing_buffer_pending_full_event: Cell::new(false),
             resource_timing_secondary_entries: DomRefCell::new(VecDeque::new()),
+            event_counts: Default::default(),
         }
     }
 
@@ -197,6 +235,7 @@ impl Performance {
     pub(crate) fn clear_and_disable_performance_entry_buffer(&self) {
         let mut buffer = self.buffer.borrow_mut();
         buffer.entries.clear();
+        self.observer_buffer.borrow_mut().entries.clear();
         self.resource_timing_buffer_size_limit.set(0);
     }
 
@@ -217,6 +256,7 @@ impl Performance {
             None => observers.push(PerformanceObserver {
                 observer: DomRoot::from_ref(observer),
                 entry_types,
+                duration_threshold: DEFAULT_DURATION_THRESHOLD,
             }),
         };
     }
@@ -226,9 +266,14 @@ impl Performance {
         observer: &DOMPerformanceObserver,
         entry_type: &DOMString,
         buffered: bool,
+        duration_threshold: Option<f64>,
     ) {
         if buffered {
-            let buffer = self.buffer.borrow();
+            let buffer = if NOT_AVAILABLE_FROM_TIMELINE.contains(&&**entry_type) {
+                self.observer_buffer.borrow()
+            } else {
+                self.buffer.borrow()
+            };
             let mut new_entries =
                 buffer.get_entries_by_name_and_type(None, Some(entry_type.clone()));
             if !new_entries.is_empty() {
@@ -249,6 +294,14 @@ impl Performance {
                     }));
             }
         }
+        // The durationThreshold only applies to "event" entries, and can not be lower
+        // than 16ms.
+        // <https://w3c.github.io/event-timing/#sec-modifications-perf-timeline>
+        let duration_threshold = duration_threshold
+            .filter(|_| entry_type == "event")
+            .map_or(DEFAULT_DURATION_THRESHOLD, |threshold| {
+                Duration::seconds_f64(threshold / 1000.).max(MINIMUM_DURATION_THRESHOLD)
+            });
         let mut observers = self.observers.borrow_mut();
         match observers.iter().position(|o| *o.observer == *observer) {
             // If the observer is already in the list, we only update
@@ -258,15 +311,27 @@ impl Performance {
                 if !observers[p].entry_types.contains(entry_type) {
                     observers[p].entry_types.push(entry_type.clone())
                 }
+                if entry_type == "event" {
+                    observers[p].duration_threshold = duration_threshold;
+                }
             },
             // Otherwise, we create and insert the new PerformanceObserver.
             None => observers.push(PerformanceObserver {
                 observer: DomRoot::from_ref(observer),
                 entry_types: vec![entry_type.clone()],
+                duration_threshold,
             }),
         };
     }
 
+    /// Whether any PerformanceObserver observes the given type of entries.
+    pub(crate) fn has_observers_for(&self, entry_type: &str) -> bool {
+        self.observers
+            .borrow()
+            .iter()
+            .any(|o| o.entry_types.iter().any(|type_| type_ == entry_type))
+    }
+
     /// Remove a PerformanceObserver from the list of observers.
     pub(crate) fn remove_observer(&self, observer: &DOMPerformanceObserver) {
         let mut observers = self.observers.borrow_mut();
@@ -301,18 +366,35 @@ impl Performance {
             .borrow()
             .iter()
             .filter(|o| o.entry_types.contains(entry.entry_type()))
+            .filter(|o| entry.entry_type() != "event" || entry.duration() >= o.duration_threshold)
         {
             o.observer.queue_entry(entry);
         }
 
         // Step 4.
-        //add the new entry to the buffer.
-        self.buffer
-            .borrow_mut()
-            .entries
-            .push(DomRoot::from_ref(entry));
-
-        let entry_last_index = self.buffer.borrow_mut().entries.len() - 1;
+        // Add the new entry to the buffer. Entries whose type is not available from the
+        // performance timeline are kept in a separate buffer of limited size, from which
+        // they are only delivered to observers with the buffered flag.
+        // <https://w3c.github.io/timing-entrytypes-registry/#registry>
+        let available_from_timeline = !NOT_AVAILABLE_FROM_TIMELINE
+            .iter()
+            .any(|entry_type| entry.entry_type() == *entry_type);
+        let entry_last_index = if available_from_timeline {
+            let mut buffer = self.buffer.borrow_mut();
+            buffer.entries.push(DomRoot::from_ref(entry));
+            Some(buffer.entries.len() - 1)
+        } else {
+            let mut observer_buffer = self.observer_buffer.borrow_mut();
+            let buffered_entries = observer_buffer
+                .entries
+                .iter()
+                .filter(|buffered_entry| buffered_entry.entry_type() == entry.entry_type())
+                .count();
+            if buffered_entries < max_observer_buffer_size(entry.entry_type()) {
+                observer_buffer.entries.push(DomRoot::from_ref(entry));
+            }
+            None
+        };
 
         // Step 5.
         // If there is already a queued notification task, we just bail out.
@@ -333,7 +415,7 @@ impl Performance {
                 owner.root().notify_observers();
             }));
 
-        Some(entry_last_index)
+        entry_last_index
     }
 
     /// Observers notifications task.
@@ -460,6 +542,19 @@ impl PerformanceMethods<crate::DomTypeHolder> for Performance {
         (self.time_origin - CrossProcessInstant::epoch()).to_dom_high_res_time_stamp()
     }
 
+    /// <https://w3c.github.io/event-timing/#dom-performance-eventcounts>
+    fn EventCounts(&self, can_gc: CanGc) -> DomRoot<EventCounts> {
+        self.event_counts
+            .or_init(|| EventCounts::new(&self.global(), can_gc))
+    }
+
+    /// <https://w3c.github.io/event-timing/#dom-performance-interactioncount>
+    fn InteractionCount(&self) -> u64 {
+        self.global()
+            .downcast::<Window>()
+            .map_or(0, |window| window.event_timing().interaction_count())
+    }
+
     // https://www.w3.org/TR/performance-timeline-2/#dom-performance-getentries
     fn GetEntries(&self) -> Vec<DomRoot<PerformanceEntry>> {
         self.buffer
@@ -0,0 +1,155 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use base::cross_process_instant::CrossProcessInstant;
+use dom_struct::dom_struct;
+use time::Duration;
+
+use crate::dom::bindings::codegen::Bindings::ElementBinding::ElementMethods;
+use crate::dom::bindings::codegen::Bindings::PerformanceBinding::DOMHighResTimeStamp;
+use crate::dom::bindings::codegen::Bindings::PerformanceElementTimingBinding::PerformanceElementTimingMethods;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::domrectreadonly::DOMRectReadOnly;
+use crate::dom::element::Element;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::largestcontentfulpaint::PaintedContent;
+use crate::dom::node::Node;
+use crate::dom::performanceentry::PerformanceEntry;
+use crate::script_runtime::CanGc;
+
+/// <https://w3c.github.io/element-timing/#sec-performance-element-timing>
+#[dom_struct]
+pub(crate) struct PerformanceElementTiming {
+    entry: PerformanceEntry,
+    /// <https://w3c.github.io/element-timing/#dom-performanceelementtiming-rendertime>
+    #[no_trace]
+    render_time: CrossProcessInstant,
+    /// <https://w3c.github.io/element-timing/#dom-performanceelementtiming-loadtime>
+    #[no_trace]
+    load_time: Option<CrossProcessInstant>,
+    /// <https://w3c.github.io/element-timing/#dom-performanceelementtiming-intersectionrect>
+    intersection_rect: Dom<DOMRectReadOnly>,
+    /// <https://w3c.github.io/element-timing/#dom-performanceelementtiming-identifier>
+    identifier: DOMString,
+    /// <https://w3c.github.io/element-timing/#dom-performanceelementtiming-naturalwidth>
+    natural_width: u32,
+    /// <https://w3c.github.io/element-timing/#dom-performanceelementtiming-naturalheight>
+    natural_height: u32,
+    /// <https://w3c.github.io/element-timing/#dom-performanceelementtiming-id>
+    id: DOMString,
+    /// <https://w3c.github.io/element-timing/#dom-performanceelementtiming-element>
+    element: Dom<Element>,
+    /// <https://w3c.github.io/element-timing/#dom-performanceelementtiming-url>
+    url: DOMString,
+}
+
+impl PerformanceElementTiming {
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    fn new_inherited(
+        content: &PaintedContent,
+        render_time: CrossProcessInstant,
+        intersection_rect: &DOMRectReadOnly,
+    ) -> PerformanceElementTiming {
+        let name = if content.url.is_some() {
+            "image-paint"
+        } else {
+            "text-paint"
+        };
+        PerformanceElementTiming {
+            entry: PerformanceEntry::new_inherited(
+                DOMString::from(name),
+                DOMString::from("element"),
+                Some(render_time),
+                Duration::ZERO,
+            ),
+            render_time,
+            load_time: content.load_time,
+            intersection_rect: Dom::from_ref(intersection_rect),
+            identifier: content.element.ElementTiming(),
+            natural_width: content.natural_size.0,
+            natural_height: content.natural_size.1,
+            id: content.element.Id(),
+            element: Dom::from_ref(&*content.element),
+            url: content.url.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Create the entry reporting the paint of `content` at `render_time`.
+    /// <https://w3c.github.io/element-timing/#report-element-timing>
+    pub(crate) fn new(
+        global: &GlobalScope,
+        content: &PaintedContent,
+        render_time: CrossProcessInstant,
+        can_gc: CanGc,
+    ) -> DomRoot<PerformanceElementTiming> {
+        let rect = content.rect;
+        let intersection_rect = DOMRectReadOnly::new(
+            global,
+            None,
+            rect.origin.x.to_f64_px(),
+            rect.origin.y.to_f64_px(),
+            rect.size.width.to_f64_px(),
+            rect.size.height.to_f64_px(),
+            can_gc,
+        );
+        let entry =
+            PerformanceElementTiming::new_inherited(content, render_time, &intersection_rect);
+        reflect_dom_object(Box::new(entry), global, can_gc)
+    }
+}
+
+impl PerformanceElementTimingMethods<crate::DomTypeHolder> for PerformanceElementTiming {
+    /// <https://w3c.github.io/element-timing/#dom-performanceelementtiming-rendertime>
+    fn RenderTime(&self) -> DOMHighResTimeStamp {
+        self.global()
+            .performance()
+            .to_dom_high_res_time_stamp(self.render_time)
+    }
+
+    /// <https://w3c.github.io/element-timing/#dom-performanceelementtiming-loadtime>
+    fn LoadTime(&self) -> DOMHighResTimeStamp {
+        self.global()
+            .performance()
+            .maybe_to_dom_high_res_time_stamp(self.load_time)
+    }
+
+    /// <https://w3c.github.io/element-timing/#dom-performanceelementtiming-intersectionrect>
+    fn IntersectionRect(&self) -> DomRoot<DOMRectReadOnly> {
+        self.intersection_rect.as_rooted()
+    }
+
+    /// <https://w3c.github.io/element-timing/#dom-performanceelementtiming-identifier>
+    fn Identifier(&self) -> DOMString {
+        self.identifier.clone()
+    }
+
+    /// <https://w3c.github.io/element-timing/#dom-performanceelementtiming-naturalwidth>
+    fn NaturalWidth(&self) -> u32 {
+        self.natural_width
+    }
+
+    /// <https://w3c.github.io/element-timing/#dom-performanceelementtiming-naturalheight>
+    fn NaturalHeight(&self) -> u32 {
+        self.natural_height
+    }
+
+    /// <https://w3c.github.io/element-timing/#dom-performanceelementtiming-id>
+    fn Id(&self) -> DOMString {
+        self.id.clone()
+    }
+
+    /// <https://w3c.github.io/element-timing/#dom-performanceelementtiming-element>
+    fn GetElement(&self) -> Option<DomRoot<Element>> {
+        // Elements that have been removed from their document are not exposed.
+        Some(self.element.as_rooted()).filter(|element| element.upcast::<Node>().is_connected())
+    }
+
+    /// <https://w3c.github.io/element-timing/#dom-performanceelementtiming-url>
+    fn Url(&self) -> DOMString {
+        self.url.clone()
+    }
+}
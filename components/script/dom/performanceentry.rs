@@ -2,6 +2,8 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use std::cell::Cell;
+
 use base::cross_process_instant::CrossProcessInstant;
 use dom_struct::dom_struct;
 use time::Duration;
@@ -26,7 +28,7 @@ pub(crate) struct PerformanceEntry {
     /// because it can be negative and `std::time::Duration` cannot be.
     #[no_trace]
     #[ignore_malloc_size_of = "No MallocSizeOf support for `time` crate"]
-    duration: Duration,
+    duration: Cell<Duration>,
 }
 
 impl PerformanceEntry {
@@ -41,7 +43,7 @@ impl PerformanceEntry {
             name,
             entry_type,
             start_time,
-            duration,
+            duration: Cell::new(duration),
         }
     }
 
@@ -71,7 +73,12 @@ impl PerformanceEntry {
     }
 
     pub(crate) fn duration(&self) -> Duration {
-        self.duration
+        self.duration.get()
+    }
+
+    /// Set the duration of an entry that was created before its end was known.
+    pub(crate) fn set_duration(&self, duration: Duration) {
+        self.duration.set(duration);
     }
 }
 
@@ -95,6 +102,6 @@ impl PerformanceEntryMethods<crate::DomTypeHolder> for PerformanceEntry {
 
     // https://w3c.github.io/performance-timeline/#dom-performanceentry-duration
     fn Duration(&self) -> DOMHighResTimeStamp {
-        self.duration.to_dom_high_res_time_stamp()
+        self.duration.get().to_dom_high_res_time_stamp()
     }
 }
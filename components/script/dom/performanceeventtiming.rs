@@ -0,0 +1,600 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::cell::Cell;
+use std::collections::HashMap;
+
+use base::Epoch;
+use base::cross_process_instant::CrossProcessInstant;
+use dom_struct::dom_struct;
+use servo_config::pref;
+use time::Duration;
+
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::EventBinding::EventMethods;
+use crate::dom::bindings::codegen::Bindings::InputEventBinding::InputEventMethods;
+use crate::dom::bindings::codegen::Bindings::KeyboardEventBinding::KeyboardEventMethods;
+use crate::dom::bindings::codegen::Bindings::PerformanceBinding::{
+    DOMHighResTimeStamp, PerformanceMethods,
+};
+use crate::dom::bindings::codegen::Bindings::PerformanceEventTimingBinding::PerformanceEventTimingMethods;
+use crate::dom::bindings::codegen::Bindings::PointerEventBinding::PointerEventMethods;
+use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object};
+use crate::dom::bindings::root::{Dom, DomRoot, MutNullableDom};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::bindings::trace::HashMapTracedValues;
+use crate::dom::event::Event;
+use crate::dom::eventtarget::EventTarget;
+use crate::dom::inputevent::InputEvent;
+use crate::dom::keyboardevent::KeyboardEvent;
+use crate::dom::node::Node;
+use crate::dom::performanceentry::PerformanceEntry;
+use crate::dom::pointerevent::{PointerEvent, PointerId};
+use crate::dom::window::Window;
+use crate::script_runtime::CanGc;
+
+/// The types of events whose timing is measured.
+/// <https://w3c.github.io/event-timing/#sec-events-exposed>
+pub(crate) const CONSIDERED_EVENT_TYPES: &[&str] = &[
+    "auxclick",
+    "beforeinput",
+    "click",
+    "compositionend",
+    "compositionstart",
+    "compositionupdate",
+    "contextmenu",
+    "dblclick",
+    "dragend",
+    "dragenter",
+    "dragleave",
+    "dragover",
+    "dragstart",
+    "drop",
+    "gotpointercapture",
+    "input",
+    "keydown",
+    "keypress",
+    "keyup",
+    "lostpointercapture",
+    "mousedown",
+    "mouseenter",
+    "mouseleave",
+    "mouseout",
+    "mouseover",
+    "mouseup",
+    "pointercancel",
+    "pointerdown",
+    "pointerenter",
+    "pointerleave",
+    "pointerout",
+    "pointerover",
+    "pointerup",
+    "touchcancel",
+    "touchend",
+    "touchstart",
+];
+
+/// Event timing entries shorter than this are never reported to observers.
+/// <https://w3c.github.io/event-timing/#dispatch-pending-event-timing-entries>
+const MINIMUM_DURATION: Duration = Duration::milliseconds(16);
+
+/// The granularity of the duration of event timing entries, which are rounded to
+/// mitigate timing attacks.
+/// <https://w3c.github.io/event-timing/#set-event-timing-entry-duration>
+const DURATION_GRANULARITY: Duration = Duration::milliseconds(8);
+
+/// The amount by which the user interaction value increases with each interaction.
+/// <https://w3c.github.io/event-timing/#increase-interaction-count>
+const USER_INTERACTION_VALUE_INCREMENT: u64 = 7;
+
+/// <https://w3c.github.io/event-timing/#sec-performance-event-timing>
+#[dom_struct]
+pub(crate) struct PerformanceEventTiming {
+    entry: PerformanceEntry,
+    /// <https://w3c.github.io/event-timing/#dom-performanceeventtiming-processingstart>
+    #[no_trace]
+    processing_start: CrossProcessInstant,
+    /// <https://w3c.github.io/event-timing/#dom-performanceeventtiming-processingend>
+    #[no_trace]
+    processing_end: Cell<Option<CrossProcessInstant>>,
+    /// <https://w3c.github.io/event-timing/#dom-performanceeventtiming-cancelable>
+    cancelable: bool,
+    /// <https://w3c.github.io/event-timing/#performanceeventtiming-eventtarget>
+    event_target: MutNullableDom<Node>,
+    /// <https://w3c.github.io/event-timing/#dom-performanceeventtiming-interactionid>
+    interaction_id: Cell<u64>,
+    /// The epoch of the display list produced by the rendering update that followed the
+    /// event, while waiting for the frame containing it to be presented.
+    #[no_trace]
+    #[ignore_malloc_size_of = "Defined in base"]
+    presentation_epoch: Cell<Option<Epoch>>,
+}
+
+impl PerformanceEventTiming {
+    fn new_inherited(
+        entry_type: DOMString,
+        name: DOMString,
+        start_time: Option<CrossProcessInstant>,
+        processing_start: CrossProcessInstant,
+        cancelable: bool,
+    ) -> PerformanceEventTiming {
+        PerformanceEventTiming {
+            entry: PerformanceEntry::new_inherited(name, entry_type, start_time, Duration::ZERO),
+            processing_start,
+            processing_end: Default::default(),
+            cancelable,
+            event_target: Default::default(),
+            interaction_id: Default::default(),
+            presentation_epoch: Default::default(),
+        }
+    }
+
+    /// <https://w3c.github.io/event-timing/#initialize-event-timing>
+    pub(crate) fn initialize_event_timing(
+        event: &Event,
+        target: &EventTarget,
+        processing_start: CrossProcessInstant,
+        can_gc: CanGc,
+    ) -> Option<DomRoot<PerformanceEventTiming>> {
+        // Only the trusted events of the considered types that target a node in a
+        // Window are measured, and only when the Event Timing API is enabled.
+        if !pref!(dom_event_timing_enabled) ||
+            !event.IsTrusted() ||
+            !CONSIDERED_EVENT_TYPES.contains(&&*event.type_())
+        {
+            return None;
+        }
+        let node = target.downcast::<Node>()?;
+        let global = target.global();
+        global.downcast::<Window>()?;
+
+        // Let timingEntry be a new PerformanceEventTiming object, whose name is event's
+        // type, whose entryType is "event" and whose startTime is event's timeStamp.
+        let timing_entry = PerformanceEventTiming::new_inherited(
+            DOMString::from("event"),
+            DOMString::from(&*event.type_()),
+            Some(event.time_stamp()),
+            processing_start,
+            event.Cancelable(),
+        );
+        timing_entry.event_target.set(Some(node));
+        Some(reflect_dom_object(Box::new(timing_entry), &*global, can_gc))
+    }
+
+    /// <https://w3c.github.io/event-timing/#finalize-event-timing>
+    pub(crate) fn finalize_event_timing(&self, event: &Event, processing_end: CrossProcessInstant) {
+        let global = self.global();
+        let Some(window) = global.downcast::<Window>() else {
+            return;
+        };
+
+        // Set timingEntry's processingEnd to processingEnd.
+        self.processing_end.set(Some(processing_end));
+        window.event_timing().finalize(event, self);
+    }
+
+    /// Whether the event of this entry was rendered in the frame containing the display
+    /// list with `presented_epoch`, or, if it is `None`, by a rendering update that did not
+    /// produce a new frame.
+    fn is_rendered(&self, presented_epoch: Option<Epoch>) -> bool {
+        match (self.presentation_epoch.get(), presented_epoch) {
+            (Some(epoch), Some(presented_epoch)) => epoch <= presented_epoch,
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    /// Create a copy of this entry whose entryType is "first-input".
+    fn copy_as_first_input(&self, can_gc: CanGc) -> DomRoot<PerformanceEventTiming> {
+        let copy = PerformanceEventTiming::new_inherited(
+            DOMString::from("first-input"),
+            self.entry.name().clone(),
+            self.entry.start_time(),
+            self.processing_start,
+            self.cancelable,
+        );
+        copy.entry.set_duration(self.entry.duration());
+        copy.processing_end.set(self.processing_end.get());
+        copy.event_target.set(self.event_target.get().as_deref());
+        copy.interaction_id.set(self.interaction_id.get());
+        reflect_dom_object(Box::new(copy), &*self.global(), can_gc)
+    }
+}
+
+impl PerformanceEventTimingMethods<crate::DomTypeHolder> for PerformanceEventTiming {
+    /// <https://w3c.github.io/event-timing/#dom-performanceeventtiming-processingstart>
+    fn ProcessingStart(&self) -> DOMHighResTimeStamp {
+        self.global()
+            .performance()
+            .to_dom_high_res_time_stamp(self.processing_start)
+    }
+
+    /// <https://w3c.github.io/event-timing/#dom-performanceeventtiming-processingend>
+    fn ProcessingEnd(&self) -> DOMHighResTimeStamp {
+        self.global()
+            .performance()
+            .maybe_to_dom_high_res_time_stamp(self.processing_end.get())
+    }
+
+    /// <https://w3c.github.io/event-timing/#dom-performanceeventtiming-cancelable>
+    fn Cancelable(&self) -> bool {
+        self.cancelable
+    }
+
+    /// <https://w3c.github.io/event-timing/#dom-performanceeventtiming-target>
+    fn GetTarget(&self) -> Option<DomRoot<Node>> {
+        // Targets that have been removed from their document are not exposed.
+        self.event_target.get().filter(|node| node.is_connected())
+    }
+
+    /// <https://w3c.github.io/event-timing/#dom-performanceeventtiming-interactionid>
+    fn InteractionId(&self) -> u64 {
+        self.interaction_id.get()
+    }
+}
+
+/// The Event Timing state of a [`Window`].
+/// <https://w3c.github.io/event-timing/#sec-modifications-HTML>
+#[derive(JSTraceable, MallocSizeOf)]
+#[cfg_attr(crown, crown::unrooted_must_root_lint::must_root)]
+pub(crate) struct EventTimingState {
+    /// <https://w3c.github.io/event-timing/#window-entries-to-be-queued>
+    entries_to_be_queued: DomRefCell<Vec<Dom<PerformanceEventTiming>>>,
+    /// <https://w3c.github.io/event-timing/#window-pending-first-pointer-down>
+    pending_first_pointer_down: MutNullableDom<PerformanceEventTiming>,
+    /// <https://w3c.github.io/event-timing/#window-has-dispatched-input-event>
+    has_dispatched_input_event: Cell<bool>,
+    /// <https://w3c.github.io/event-timing/#window-user-interaction-value>
+    user_interaction_value: Cell<u64>,
+    /// <https://w3c.github.io/event-timing/#window-interactioncount>
+    interaction_count: Cell<u64>,
+    /// <https://w3c.github.io/event-timing/#window-pending-key-downs>
+    pending_key_downs: DomRefCell<HashMapTracedValues<u32, Dom<PerformanceEventTiming>>>,
+    /// <https://w3c.github.io/event-timing/#window-pointer-interaction-value-map>
+    pointer_interaction_values: DomRefCell<HashMap<i32, u64>>,
+    /// <https://w3c.github.io/event-timing/#window-pending-pointer-downs>
+    pending_pointer_downs: DomRefCell<HashMapTracedValues<i32, Dom<PerformanceEventTiming>>>,
+}
+
+impl Default for EventTimingState {
+    fn default() -> EventTimingState {
+        EventTimingState {
+            entries_to_be_queued: Default::default(),
+            pending_first_pointer_down: Default::default(),
+            has_dispatched_input_event: Default::default(),
+            // The user interaction value starts at a random integer between 100 and 10000.
+            user_interaction_value: Cell::new(100 + servo_rand::random::<u64>() % 9901),
+            interaction_count: Default::default(),
+            pending_key_downs: Default::default(),
+            pointer_interaction_values: Default::default(),
+            pending_pointer_downs: Default::default(),
+        }
+    }
+}
+
+impl EventTimingState {
+    /// <https://w3c.github.io/event-timing/#dom-performance-interactioncount>
+    pub(crate) fn interaction_count(&self) -> u64 {
+        self.interaction_count.get()
+    }
+
+    /// <https://w3c.github.io/event-timing/#window-has-dispatched-input-event>
+    pub(crate) fn has_dispatched_input_event(&self) -> bool {
+        self.has_dispatched_input_event.get()
+    }
+
+    /// The steps of <https://w3c.github.io/event-timing/#finalize-event-timing> that depend
+    /// on the type of the event.
+    fn finalize(&self, event: &Event, timing_entry: &PerformanceEventTiming) {
+        let event_type = interaction_event_type(event);
+        match &*event_type {
+            // A pointer down is queued once the pointer up or cancel that follows it
+            // determines whether it is part of an interaction.
+            "pointerdown" => {
+                let previous_pointer_down = self
+                    .pending_pointer_downs
+                    .borrow_mut()
+                    .insert(pointer_id(event), Dom::from_ref(timing_entry))
+                    .map(|entry| entry.as_rooted());
+                if let Some(previous_pointer_down) = previous_pointer_down {
+                    self.queue_later(&previous_pointer_down);
+                }
+                return;
+            },
+            // Likewise, a key down is queued once the matching key up is known.
+            "keydown" if !is_composing(event) => {
+                let previous_key_down = self
+                    .pending_key_downs
+                    .borrow_mut()
+                    .insert(key_code(event), Dom::from_ref(timing_entry))
+                    .map(|entry| entry.as_rooted());
+                // A key that is held down repeats, each repetition being an interaction.
+                if let Some(previous_key_down) = previous_key_down {
+                    self.increase_interaction_count();
+                    previous_key_down
+                        .interaction_id
+                        .set(self.user_interaction_value.get());
+                    self.queue_later(&previous_key_down);
+                }
+                return;
+            },
+            _ => {},
+        }
+
+        // Set timingEntry's interactionId to the result of computing interactionId given
+        // event, and append timingEntry to the window's entries to be queued.
+        let interaction_id = self.compute_interaction_id(event, &event_type);
+        timing_entry.interaction_id.set(interaction_id);
+        self.queue_later(timing_entry);
+    }
+
+    /// Append an entry to the window's entries to be queued, which are queued at the next
+    /// dispatch of the pending Event Timing entries.
+    fn queue_later(&self, timing_entry: &PerformanceEventTiming) {
+        self.entries_to_be_queued
+            .borrow_mut()
+            .push(Dom::from_ref(timing_entry));
+    }
+
+    /// <https://w3c.github.io/event-timing/#compute-interactionid>
+    fn compute_interaction_id(&self, event: &Event, event_type: &str) -> u64 {
+        match event_type {
+            "keyup" => {
+                if is_composing(event) {
+                    return 0;
+                }
+                let Some(key_down) = self
+                    .pending_key_downs
+                    .borrow_mut()
+                    .remove(&key_code(event))
+                    .map(|entry| entry.as_rooted())
+                else {
+                    return 0;
+                };
+                self.increase_interaction_count();
+                let interaction_id = self.user_interaction_value.get();
+                key_down.interaction_id.set(interaction_id);
+                self.queue_later(&key_down);
+                interaction_id
+            },
+            "compositionstart" => {
+                let mut pending_key_downs = self.pending_key_downs.borrow_mut();
+                self.entries_to_be_queued
+                    .borrow_mut()
+                    .extend(pending_key_downs.drain().map(|(_, key_down)| key_down));
+                0
+            },
+            "input" => {
+                let is_composing = event
+                    .downcast::<InputEvent>()
+                    .is_some_and(|event| event.IsComposing());
+                if !is_composing {
+                    return 0;
+                }
+                self.increase_interaction_count();
+                self.user_interaction_value.get()
+            },
+            "click" => self
+                .pointer_interaction_values
+                .borrow_mut()
+                .remove(&pointer_id(event))
+                .unwrap_or_default(),
+            "pointerup" | "pointercancel" | "contextmenu" => {
+                let pointer_id = pointer_id(event);
+                let Some(pointer_down) = self
+                    .pending_pointer_downs
+                    .borrow_mut()
+                    .remove(&pointer_id)
+                    .map(|entry| entry.as_rooted())
+                else {
+                    if event_type == "pointercancel" {
+                        return 0;
+                    }
+                    return self
+                        .pointer_interaction_values
+                        .borrow()
+                        .get(&pointer_id)
+                        .copied()
+                        .unwrap_or_default();
+                };
+                self.queue_later(&pointer_down);
+                if event_type == "pointercancel" {
+                    return 0;
+                }
+
+                self.increase_interaction_count();
+                let interaction_id = self.user_interaction_value.get();
+                self.pointer_interaction_values
+                    .borrow_mut()
+                    .insert(pointer_id, interaction_id);
+                pointer_down.interaction_id.set(interaction_id);
+                interaction_id
+            },
+            _ => 0,
+        }
+    }
+
+    /// <https://w3c.github.io/event-timing/#increase-interaction-count>
+    fn increase_interaction_count(&self) {
+        self.user_interaction_value
+            .set(self.user_interaction_value.get() + USER_INTERACTION_VALUE_INCREMENT);
+        self.interaction_count.set(self.interaction_count.get() + 1);
+    }
+
+    /// All the entries whose duration is not known yet.
+    fn entries_without_duration(&self) -> Vec<DomRoot<PerformanceEventTiming>> {
+        let entries_to_be_queued = self.entries_to_be_queued.borrow();
+        let pending_pointer_downs = self.pending_pointer_downs.borrow();
+        let pending_key_downs = self.pending_key_downs.borrow();
+        entries_to_be_queued
+            .iter()
+            .chain(pending_pointer_downs.iter().map(|(_, entry)| entry))
+            .chain(pending_key_downs.iter().map(|(_, entry)| entry))
+            .filter(|entry| entry.entry.duration() == Duration::ZERO)
+            .map(|entry| entry.as_rooted())
+            .collect()
+    }
+
+    /// Note that a rendering update produced the display list with the given epoch,
+    /// so that the entries of the events handled before it are dispatched once the
+    /// frame containing it is presented. Returns whether any entry is waiting for it.
+    pub(crate) fn await_presentation(&self, epoch: Epoch) -> bool {
+        let mut awaiting_presentation = false;
+        for entry in self.entries_without_duration() {
+            if entry.presentation_epoch.get().is_none() {
+                entry.presentation_epoch.set(Some(epoch));
+                awaiting_presentation = true;
+            }
+        }
+        awaiting_presentation
+    }
+
+    /// <https://w3c.github.io/event-timing/#dispatch-pending-event-timing-entries>
+    ///
+    /// The rendering timestamp is the presentation time of the frame containing the display
+    /// list with `presented_epoch`, or the time of a rendering update that did not produce
+    /// a new frame if it is `None`. Only the entries of events that were rendered in that
+    /// frame, or handled before that rendering update, are dispatched.
+    pub(crate) fn dispatch_pending_entries(
+        &self,
+        window: &Window,
+        presented_epoch: Option<Epoch>,
+        rendering_timestamp: CrossProcessInstant,
+        can_gc: CanGc,
+    ) {
+        // Entries whose duration is already known, such as pointer downs that were
+        // rendered before the matching pointer up, do not wait for a presentation.
+        let is_ready = |entry: &PerformanceEventTiming| {
+            entry.entry.duration() != Duration::ZERO || entry.is_rendered(presented_epoch)
+        };
+
+        // For each timingEntry in window's entries to be queued, set event timing entry
+        // duration and queue timingEntry if its duration is at least 16ms.
+        let entries_to_dispatch: Vec<_> = self
+            .entries_to_be_queued
+            .borrow()
+            .iter()
+            .filter(|entry| is_ready(entry))
+            .map(|entry| entry.as_rooted())
+            .collect();
+        self.entries_to_be_queued
+            .borrow_mut()
+            .retain(|entry| !is_ready(entry));
+        let performance = window.Performance();
+        for timing_entry in entries_to_dispatch {
+            self.set_event_timing_entry_duration(
+                window,
+                &timing_entry,
+                rendering_timestamp,
+                can_gc,
+            );
+            if timing_entry.entry.duration() >= MINIMUM_DURATION {
+                performance.queue_entry(timing_entry.upcast(), can_gc);
+            }
+        }
+
+        // The pending pointer downs and key downs are not queued yet, but their duration
+        // is set as well.
+        for timing_entry in self.entries_without_duration() {
+            if timing_entry.is_rendered(presented_epoch) {
+                self.set_event_timing_entry_duration(
+                    window,
+                    &timing_entry,
+                    rendering_timestamp,
+                    can_gc,
+                );
+            }
+        }
+    }
+
+    /// <https://w3c.github.io/event-timing/#set-event-timing-entry-duration>
+    fn set_event_timing_entry_duration(
+        &self,
+        window: &Window,
+        timing_entry: &PerformanceEventTiming,
+        rendering_timestamp: CrossProcessInstant,
+        can_gc: CanGc,
+    ) {
+        // Step 1. If timingEntry's duration attribute value is nonzero, return.
+        if timing_entry.entry.duration() != Duration::ZERO {
+            return;
+        }
+        timing_entry.presentation_epoch.set(None);
+
+        // Step 2. Let start be timingEntry's startTime attribute value.
+        // Step 3. Set timingEntry's duration to a DOMHighResTimeStamp resulting from
+        // renderingTimestamp - start, with granularity of 8ms or less.
+        let start = timing_entry
+            .entry
+            .start_time()
+            .unwrap_or(timing_entry.processing_start);
+        let duration = rendering_timestamp - start;
+        let duration = DURATION_GRANULARITY * (duration / DURATION_GRANULARITY).round() as i32;
+        timing_entry.entry.set_duration(duration);
+
+        // Step 4. Let name be timingEntry's name attribute value.
+        // Step 5. Increase the count of name in the eventCounts of the window's Performance.
+        let name = timing_entry.entry.name();
+        let performance = window.Performance();
+        performance.EventCounts(can_gc).increment(name);
+
+        // Step 6. If window's has dispatched input event is false, report the first input.
+        if self.has_dispatched_input_event.get() {
+            return;
+        }
+        match &**name {
+            "pointerdown" => {
+                self.pending_first_pointer_down
+                    .set(Some(&timing_entry.copy_as_first_input(can_gc)));
+            },
+            "pointerup" => {
+                if let Some(first_pointer_down) = self.pending_first_pointer_down.take() {
+                    self.has_dispatched_input_event.set(true);
+                    performance.queue_entry(first_pointer_down.upcast(), can_gc);
+                }
+            },
+            "click" | "keydown" | "mousedown" => {
+                self.has_dispatched_input_event.set(true);
+                let first_input = timing_entry.copy_as_first_input(can_gc);
+                performance.queue_entry(first_input.upcast(), can_gc);
+            },
+            _ => {},
+        }
+    }
+}
+
+/// The type of `event` as far as interactions are concerned. Servo does not fire
+/// `pointerdown` and `pointerup` for mouse buttons yet, so `mousedown` and `mouseup`
+/// take their place.
+fn interaction_event_type(event: &Event) -> DOMString {
+    let event_type = event.type_();
+    DOMString::from(match &*event_type {
+        "mousedown" => "pointerdown",
+        "mouseup" => "pointerup",
+        event_type => event_type,
+    })
+}
+
+/// The pointerId of `event`, where events that are not pointer events come from the mouse.
+fn pointer_id(event: &Event) -> i32 {
+    event
+        .downcast::<PointerEvent>()
+        .map_or(PointerId::Mouse as i32, |event| event.PointerId())
+}
+
+/// The keyCode of `event`, if it is a keyboard event.
+fn key_code(event: &Event) -> u32 {
+    event
+        .downcast::<KeyboardEvent>()
+        .map_or(0, |event| event.KeyCode())
+}
+
+/// Whether `event` is a keyboard event fired during a composition session.
+fn is_composing(event: &Event) -> bool {
+    event
+        .downcast::<KeyboardEvent>()
+        .is_some_and(|event| event.IsComposing())
+}
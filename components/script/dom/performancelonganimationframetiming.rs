@@ -0,0 +1,122 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use base::cross_process_instant::CrossProcessInstant;
+use dom_struct::dom_struct;
+use time::Duration;
+
+use super::performance::ToDOMHighResTimeStamp;
+use crate::dom::bindings::codegen::Bindings::PerformanceBinding::DOMHighResTimeStamp;
+use crate::dom::bindings::codegen::Bindings::PerformanceLongAnimationFrameTimingBinding::PerformanceLongAnimationFrameTimingMethods;
+use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::str::DOMString;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::performanceentry::PerformanceEntry;
+use crate::script_runtime::CanGc;
+
+/// The timing of a frame, as measured by the `ScriptThread`.
+/// <https://w3c.github.io/long-animation-frames/#frame-timing-info>
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct FrameTimingInfo {
+    /// The start of the first task of the frame.
+    pub(crate) start_time: CrossProcessInstant,
+    /// The end of the frame, after its rendering update if there was one.
+    pub(crate) end_time: CrossProcessInstant,
+    /// The start of "update the rendering", if the frame was rendered. Times that were
+    /// not recorded are reported as 0.
+    pub(crate) render_start: Option<CrossProcessInstant>,
+    /// The start of style and layout for the document this entry is reported to.
+    pub(crate) style_and_layout_start: Option<CrossProcessInstant>,
+    /// The time by which the long tasks of the frame exceeded the long task threshold.
+    pub(crate) blocking_duration: Duration,
+    /// The time at which the first UI event of the frame was received.
+    pub(crate) first_ui_event_timestamp: Option<CrossProcessInstant>,
+}
+
+impl FrameTimingInfo {
+    /// The timing of a frame starting at `start_time`, which has not recorded anything yet.
+    pub(crate) fn new(start_time: CrossProcessInstant) -> FrameTimingInfo {
+        FrameTimingInfo {
+            start_time,
+            end_time: start_time,
+            render_start: None,
+            style_and_layout_start: None,
+            blocking_duration: Duration::ZERO,
+            first_ui_event_timestamp: None,
+        }
+    }
+}
+
+/// <https://w3c.github.io/long-animation-frames/#sec-PerformanceLongAnimationFrameTiming>
+#[dom_struct]
+pub(crate) struct PerformanceLongAnimationFrameTiming {
+    entry: PerformanceEntry,
+    #[no_trace]
+    render_start: Option<CrossProcessInstant>,
+    #[no_trace]
+    style_and_layout_start: Option<CrossProcessInstant>,
+    #[no_trace]
+    #[ignore_malloc_size_of = "No MallocSizeOf support for `time` crate"]
+    blocking_duration: Duration,
+    #[no_trace]
+    first_ui_event_timestamp: Option<CrossProcessInstant>,
+}
+
+impl PerformanceLongAnimationFrameTiming {
+    fn new_inherited(timing: FrameTimingInfo) -> PerformanceLongAnimationFrameTiming {
+        PerformanceLongAnimationFrameTiming {
+            entry: PerformanceEntry::new_inherited(
+                DOMString::from("long-animation-frame"),
+                DOMString::from("long-animation-frame"),
+                Some(timing.start_time),
+                timing.end_time - timing.start_time,
+            ),
+            render_start: timing.render_start,
+            style_and_layout_start: timing.style_and_layout_start,
+            blocking_duration: timing.blocking_duration,
+            first_ui_event_timestamp: timing.first_ui_event_timestamp,
+        }
+    }
+
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    pub(crate) fn new(
+        global: &GlobalScope,
+        timing: FrameTimingInfo,
+        can_gc: CanGc,
+    ) -> DomRoot<PerformanceLongAnimationFrameTiming> {
+        let entry = PerformanceLongAnimationFrameTiming::new_inherited(timing);
+        reflect_dom_object(Box::new(entry), global, can_gc)
+    }
+}
+
+impl PerformanceLongAnimationFrameTimingMethods<crate::DomTypeHolder>
+    for PerformanceLongAnimationFrameTiming
+{
+    /// <https://w3c.github.io/long-animation-frames/#dom-performancelonganimationframetiming-renderstart>
+    fn RenderStart(&self) -> DOMHighResTimeStamp {
+        self.global()
+            .performance()
+            .maybe_to_dom_high_res_time_stamp(self.render_start)
+    }
+
+    /// <https://w3c.github.io/long-animation-frames/#dom-performancelonganimationframetiming-styleandlayoutstart>
+    fn StyleAndLayoutStart(&self) -> DOMHighResTimeStamp {
+        self.global()
+            .performance()
+            .maybe_to_dom_high_res_time_stamp(self.style_and_layout_start)
+    }
+
+    /// <https://w3c.github.io/long-animation-frames/#dom-performancelonganimationframetiming-blockingduration>
+    fn BlockingDuration(&self) -> DOMHighResTimeStamp {
+        self.blocking_duration.to_dom_high_res_time_stamp()
+    }
+
+    /// <https://w3c.github.io/long-animation-frames/#dom-performancelonganimationframetiming-firstuieventtimestamp>
+    fn FirstUIEventTimestamp(&self) -> DOMHighResTimeStamp {
+        self.global()
+            .performance()
+            .maybe_to_dom_high_res_time_stamp(self.first_ui_event_timestamp)
+    }
+}
@@ -0,0 +1,168 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use base::cross_process_instant::CrossProcessInstant;
+use dom_struct::dom_struct;
+use html5ever::local_name;
+use js::rust::MutableHandleValue;
+use time::Duration;
+
+use crate::dom::bindings::codegen::Bindings::PerformanceLongTaskTimingBinding::PerformanceLongTaskTimingMethods;
+use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::frozenarray::CachedFrozenArray;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::reflect_dom_object;
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::element::Element;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::htmlobjectelement::HTMLObjectElement;
+use crate::dom::performanceentry::PerformanceEntry;
+use crate::dom::taskattributiontiming::{TaskAttributionTiming, TaskContainer};
+use crate::dom::window::Window;
+use crate::script_runtime::{CanGc, JSContext};
+
+/// <https://w3c.github.io/longtasks/#sec-PerformanceLongTaskTiming>
+#[dom_struct]
+pub(crate) struct PerformanceLongTaskTiming {
+    entry: PerformanceEntry,
+    attribution: Vec<Dom<TaskAttributionTiming>>,
+    #[ignore_malloc_size_of = "mozjs"]
+    frozen_attribution: CachedFrozenArray,
+}
+
+impl PerformanceLongTaskTiming {
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    fn new_inherited(
+        name: DOMString,
+        start_time: CrossProcessInstant,
+        duration: Duration,
+        attribution: &TaskAttributionTiming,
+    ) -> PerformanceLongTaskTiming {
+        PerformanceLongTaskTiming {
+            entry: PerformanceEntry::new_inherited(
+                name,
+                DOMString::from("longtask"),
+                Some(start_time),
+                duration,
+            ),
+            attribution: vec![Dom::from_ref(attribution)],
+            frozen_attribution: CachedFrozenArray::new(),
+        }
+    }
+
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    pub(crate) fn new(
+        global: &GlobalScope,
+        name: DOMString,
+        start_time: CrossProcessInstant,
+        duration: Duration,
+        attribution: &TaskAttributionTiming,
+        can_gc: CanGc,
+    ) -> DomRoot<PerformanceLongTaskTiming> {
+        let entry =
+            PerformanceLongTaskTiming::new_inherited(name, start_time, duration, attribution);
+        reflect_dom_object(Box::new(entry), global, can_gc)
+    }
+
+    /// Determine the name of a long task entry reported to `destination`, along with the
+    /// container of the frame that caused the long task, as seen from `destination`.
+    /// <https://w3c.github.io/longtasks/#report-long-tasks>
+    pub(crate) fn attribution(
+        destination: &Window,
+        culprit: Option<&Window>,
+    ) -> (DOMString, TaskContainer) {
+        let window_container = || TaskContainer {
+            type_: DOMString::from("window"),
+            src: DOMString::new(),
+            id: DOMString::new(),
+            name: DOMString::new(),
+        };
+
+        let Some(culprit) = culprit else {
+            return (DOMString::from("unknown"), window_container());
+        };
+        if culprit == destination {
+            return (DOMString::from("self"), window_container());
+        }
+
+        let destination_proxy = destination.window_proxy();
+        let culprit_proxy = culprit.window_proxy();
+        let same_origin = destination
+            .Document()
+            .origin()
+            .same_origin(culprit.Document().origin());
+
+        // If the culprit is a descendant of the destination, find the frame element of the
+        // destination's document that contains it.
+        let mut child = &*culprit_proxy;
+        while let Some(parent) = child.parent() {
+            if *parent == *destination_proxy {
+                let container = child
+                    .frame_element()
+                    .map(Self::frame_container)
+                    .unwrap_or_else(window_container);
+                let name = if same_origin {
+                    "same-origin-descendant"
+                } else {
+                    "cross-origin-descendant"
+                };
+                return (DOMString::from(name), container);
+            }
+            child = parent;
+        }
+
+        let mut ancestor = destination_proxy.parent();
+        while let Some(proxy) = ancestor {
+            if *proxy == *culprit_proxy {
+                let name = if same_origin {
+                    "same-origin-ancestor"
+                } else {
+                    "cross-origin-ancestor"
+                };
+                return (DOMString::from(name), window_container());
+            }
+            ancestor = proxy.parent();
+        }
+
+        let name = if same_origin {
+            "same-origin"
+        } else {
+            "cross-origin-unreachable"
+        };
+        (DOMString::from(name), window_container())
+    }
+
+    /// <https://w3c.github.io/longtasks/#dom-taskattributiontiming-containertype>
+    fn frame_container(element: &Element) -> TaskContainer {
+        let src = if element.is::<HTMLObjectElement>() {
+            local_name!("data")
+        } else {
+            local_name!("src")
+        };
+        TaskContainer {
+            type_: DOMString::from(&**element.local_name()),
+            src: element.get_string_attribute(&src),
+            id: element.get_string_attribute(&local_name!("id")),
+            name: element.get_string_attribute(&local_name!("name")),
+        }
+    }
+}
+
+impl PerformanceLongTaskTimingMethods<crate::DomTypeHolder> for PerformanceLongTaskTiming {
+    /// <https://w3c.github.io/longtasks/#dom-performancelongtasktiming-attribution>
+    fn Attribution(&self, cx: JSContext, can_gc: CanGc, retval: MutableHandleValue) {
+        self.frozen_attribution.get_or_init(
+            || {
+                self.attribution
+                    .iter()
+                    .map(|attribution| attribution.as_rooted())
+                    .collect()
+            },
+            cx,
+            retval,
+            can_gc,
+        );
+    }
+}
@@ -2,8 +2,8 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
-use base::cross_process_instant::CrossProcessInstant;
 use dom_struct::dom_struct;
+use net_traits::ResourceFetchTiming;
 
 use crate::dom::bindings::codegen::Bindings::PerformanceBinding::DOMHighResTimeStamp;
 use crate::dom::bindings::codegen::Bindings::PerformanceNavigationTimingBinding::{
@@ -30,15 +30,14 @@ pub(crate) struct PerformanceNavigationTiming {
 
 impl PerformanceNavigationTiming {
     fn new_inherited(
-        navigation_start: CrossProcessInstant,
+        resource_timing: &ResourceFetchTiming,
         document: &Document,
     ) -> PerformanceNavigationTiming {
         PerformanceNavigationTiming {
             performanceresourcetiming: PerformanceResourceTiming::new_inherited(
                 document.url(),
                 InitiatorType::Navigation,
-                None,
-                Some(navigation_start),
+                resource_timing,
             ),
             document: Dom::from_ref(document),
             nav_type: NavigationTimingType::Navigate,
@@ -47,13 +46,13 @@ impl PerformanceNavigationTiming {
 
     pub(crate) fn new(
         global: &GlobalScope,
-        fetch_start: CrossProcessInstant,
+        resource_timing: &ResourceFetchTiming,
         document: &Document,
         can_gc: CanGc,
     ) -> DomRoot<PerformanceNavigationTiming> {
         reflect_dom_object(
             Box::new(PerformanceNavigationTiming::new_inherited(
-                fetch_start,
+                resource_timing,
                 document,
             )),
             global,
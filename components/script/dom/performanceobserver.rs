@@ -7,6 +7,7 @@ use std::rc::Rc;
 
 use dom_struct::dom_struct;
 use js::rust::{HandleObject, MutableHandleValue};
+use servo_config::pref;
 
 use crate::dom::bindings::callback::ExceptionHandling;
 use crate::dom::bindings::cell::DomRefCell;
@@ -27,15 +28,35 @@ use crate::script_runtime::{CanGc, JSContext};
 
 /// List of allowed performance entry types, in alphabetical order.
 pub(crate) const VALID_ENTRY_TYPES: &[&str] = &[
+    "element",     // Element Timing API
+    "event",       // Event Timing API
+    "first-input", // Event Timing API
     // "frame", //TODO Frame Timing API
-    "mark",       // User Timing API
-    "measure",    // User Timing API
-    "navigation", // Navigation Timing API
-    "paint",      // Paint Timing API
-    "resource",   // Resource Timing API
-                  // "server", XXX Server Timing API
+    "largest-contentful-paint", // Largest Contentful Paint API
+    "layout-shift",             // Layout Instability API
+    "long-animation-frame",     // Long Animation Frames API
+    "longtask",                 // Long Tasks API
+    "mark",                     // User Timing API
+    "measure",                  // User Timing API
+    "navigation",               // Navigation Timing API
+    "paint",                    // Paint Timing API
+    "resource",                 // Resource Timing API
+                                // "server", XXX Server Timing API
 ];
 
+/// Whether `entry_type` is one of the [`VALID_ENTRY_TYPES`] whose API is enabled.
+pub(crate) fn is_supported_entry_type(entry_type: &str) -> bool {
+    match entry_type {
+        "element" => pref!(dom_element_timing_enabled),
+        "event" | "first-input" => pref!(dom_event_timing_enabled),
+        "largest-contentful-paint" => pref!(dom_largest_contentful_paint_enabled),
+        "layout-shift" => pref!(dom_layout_instability_enabled),
+        "long-animation-frame" => pref!(dom_long_animation_frames_enabled),
+        "longtask" => pref!(dom_long_tasks_enabled),
+        _ => VALID_ENTRY_TYPES.contains(&entry_type),
+    }
+}
+
 #[derive(Clone, Copy, JSTraceable, MallocSizeOf, PartialEq)]
 enum ObserverType {
     Undefined,
@@ -197,7 +218,7 @@ impl PerformanceObserverMethods<crate::DomTypeHolder> for PerformanceObserver {
             // Steps 6.1 - 6.2
             let entry_types = entry_types
                 .iter()
-                .filter(|e| VALID_ENTRY_TYPES.contains(&e.as_ref()))
+                .filter(|e| is_supported_entry_type(e))
                 .cloned()
                 .collect::<Vec<DOMString>>();
 
@@ -219,7 +240,7 @@ impl PerformanceObserverMethods<crate::DomTypeHolder> for PerformanceObserver {
             Ok(())
         } else if let Some(entry_type) = &options.type_ {
             // Step 7.2
-            if !VALID_ENTRY_TYPES.contains(&entry_type.as_ref()) {
+            if !is_supported_entry_type(entry_type) {
                 Console::internal_warn(
                     &self.global(),
                     DOMString::from("No valid entry type provided to observe()."),
@@ -234,6 +255,7 @@ impl PerformanceObserverMethods<crate::DomTypeHolder> for PerformanceObserver {
                 self,
                 entry_type,
                 options.buffered.unwrap_or(false),
+                options.durationThreshold.map(|threshold| *threshold),
             );
             Ok(())
         } else {
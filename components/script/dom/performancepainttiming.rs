@@ -2,16 +2,21 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use std::cell::Cell;
+
+use base::Epoch;
 use base::cross_process_instant::CrossProcessInstant;
 use dom_struct::dom_struct;
 use script_traits::ProgressiveWebMetricType;
 use time::Duration;
 
+use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
 use crate::dom::bindings::reflector::reflect_dom_object;
 use crate::dom::bindings::root::DomRoot;
 use crate::dom::bindings::str::DOMString;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::performanceentry::PerformanceEntry;
+use crate::dom::window::Window;
 use crate::script_runtime::CanGc;
 
 #[dom_struct]
@@ -52,3 +57,81 @@ impl PerformancePaintTiming {
         reflect_dom_object(Box::new(entry), global, can_gc)
     }
 }
+
+/// The progress of reporting one of the paint timing metrics of a document.
+#[derive(Clone, Copy, Default, PartialEq)]
+enum PaintTimingStatus {
+    /// No display list triggering the metric has been built yet.
+    #[default]
+    Waiting,
+    /// The display list with the given epoch triggers the metric and is waiting to be
+    /// presented. The flag says whether it was the first display list of the document.
+    Painted(Epoch, bool),
+    /// The metric has been reported.
+    Reported,
+}
+
+/// The state of a [`Window`] used to report the first paint and the first contentful paint
+/// of its document, using the time at which the frames containing them were presented.
+/// <https://w3c.github.io/paint-timing/#sec-reporting-paint-timing>
+#[derive(Default, JSTraceable, MallocSizeOf)]
+pub(crate) struct PaintTimingState {
+    #[no_trace]
+    #[ignore_malloc_size_of = "Defined in base"]
+    first_paint: Cell<PaintTimingStatus>,
+    #[no_trace]
+    #[ignore_malloc_size_of = "Defined in base"]
+    first_contentful_paint: Cell<PaintTimingStatus>,
+}
+
+impl PaintTimingState {
+    /// Note the display list built with the given epoch. Returns whether it triggers one of
+    /// the paint timing metrics, which are reported once the frame containing it is presented.
+    pub(crate) fn note_display_list(&self, epoch: Epoch, is_contentful: bool) -> bool {
+        let first_reflow = self.first_paint.get() == PaintTimingStatus::Waiting;
+        if first_reflow {
+            self.first_paint
+                .set(PaintTimingStatus::Painted(epoch, first_reflow));
+        }
+        if !is_contentful || self.first_contentful_paint.get() != PaintTimingStatus::Waiting {
+            return first_reflow;
+        }
+        self.first_contentful_paint
+            .set(PaintTimingStatus::Painted(epoch, first_reflow));
+        true
+    }
+
+    /// Report the paint timing metrics triggered by the display lists up to the given epoch,
+    /// now that the frame containing it was presented at `presentation_time`.
+    /// <https://w3c.github.io/paint-timing/#report-paint-timing>
+    pub(crate) fn report_paint_timing(
+        &self,
+        window: &Window,
+        presented_epoch: Epoch,
+        presentation_time: CrossProcessInstant,
+        can_gc: CanGc,
+    ) {
+        let metrics = [
+            (&self.first_paint, ProgressiveWebMetricType::FirstPaint),
+            (
+                &self.first_contentful_paint,
+                ProgressiveWebMetricType::FirstContentfulPaint,
+            ),
+        ];
+        for (status, metric_type) in metrics {
+            let PaintTimingStatus::Painted(epoch, first_reflow) = status.get() else {
+                continue;
+            };
+            if epoch > presented_epoch {
+                continue;
+            }
+            status.set(PaintTimingStatus::Reported);
+            window.Document().handle_paint_metric(
+                metric_type,
+                presentation_time,
+                first_reflow,
+                can_gc,
+            );
+        }
+    }
+}
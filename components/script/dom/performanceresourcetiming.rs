@@ -4,18 +4,21 @@
 
 use base::cross_process_instant::CrossProcessInstant;
 use dom_struct::dom_struct;
-use net_traits::ResourceFetchTiming;
+use js::rust::MutableHandleValue;
+use net_traits::{ResourceFetchTiming, ServerTimingMetric};
 use servo_url::ServoUrl;
 use time::Duration;
 
 use crate::dom::bindings::codegen::Bindings::PerformanceBinding::DOMHighResTimeStamp;
 use crate::dom::bindings::codegen::Bindings::PerformanceResourceTimingBinding::PerformanceResourceTimingMethods;
+use crate::dom::bindings::frozenarray::CachedFrozenArray;
 use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object};
 use crate::dom::bindings::root::DomRoot;
 use crate::dom::bindings::str::DOMString;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::performanceentry::PerformanceEntry;
-use crate::script_runtime::CanGc;
+use crate::dom::performanceservertiming::PerformanceServerTiming;
+use crate::script_runtime::{CanGc, JSContext};
 // TODO UA may choose to limit how many resources are included as PerformanceResourceTiming objects
 // recommended minimum is 150, can be changed by setResourceTimingBufferSize in performance
 // https://w3c.github.io/resource-timing/#sec-extensions-performance-interface
@@ -38,7 +41,7 @@ pub(crate) enum InitiatorType {
 pub(crate) struct PerformanceResourceTiming {
     entry: PerformanceEntry,
     initiator_type: InitiatorType,
-    next_hop: Option<DOMString>,
+    next_hop: DOMString,
     #[no_trace]
     worker_start: Option<CrossProcessInstant>,
     #[no_trace]
@@ -66,91 +69,62 @@ pub(crate) struct PerformanceResourceTiming {
     transfer_size: u64,     //size in octets
     encoded_body_size: u64, //size in octets
     decoded_body_size: u64, //size in octets
+    #[no_trace]
+    server_timing: Vec<ServerTimingMetric>,
+    #[ignore_malloc_size_of = "mozjs"]
+    frozen_server_timing: CachedFrozenArray,
 }
 
-// TODO(#21269): next_hop
-// TODO(#21264): worker_start
-// TODO(#21258): fetch_start
-// TODO(#21259): domain_lookup_start
-// TODO(#21260): domain_lookup_end
-// TODO(#21261): connect_start
-// TODO(#21262): connect_end
 impl PerformanceResourceTiming {
+    /// Create the entry for a resource fetched with the given timing. The entry of a
+    /// navigation starts at the time origin, while other entries start when the fetch
+    /// started.
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
     pub(crate) fn new_inherited(
         url: ServoUrl,
         initiator_type: InitiatorType,
-        next_hop: Option<DOMString>,
-        fetch_start: Option<CrossProcessInstant>,
+        resource_timing: &ResourceFetchTiming,
     ) -> PerformanceResourceTiming {
-        let entry_type = if initiator_type == InitiatorType::Navigation {
-            DOMString::from("navigation")
+        let (entry_type, start_time, duration) = if initiator_type == InitiatorType::Navigation {
+            (DOMString::from("navigation"), None, Duration::ZERO)
         } else {
-            DOMString::from("resource")
+            let duration = match (resource_timing.start_time, resource_timing.response_end) {
+                (Some(start_time), Some(end_time)) => end_time - start_time,
+                _ => Duration::ZERO,
+            };
+            (
+                DOMString::from("resource"),
+                resource_timing.start_time,
+                duration,
+            )
         };
         PerformanceResourceTiming {
             entry: PerformanceEntry::new_inherited(
                 DOMString::from(url.into_string()),
                 entry_type,
-                None,
-                Duration::ZERO,
-            ),
-            initiator_type,
-            next_hop,
-            worker_start: None,
-            redirect_start: None,
-            redirect_end: None,
-            fetch_start,
-            domain_lookup_end: None,
-            domain_lookup_start: None,
-            connect_start: None,
-            connect_end: None,
-            secure_connection_start: None,
-            request_start: None,
-            response_start: None,
-            response_end: None,
-            transfer_size: 0,
-            encoded_body_size: 0,
-            decoded_body_size: 0,
-        }
-    }
-
-    //TODO fetch start should be in RFT
-    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
-    fn from_resource_timing(
-        url: ServoUrl,
-        initiator_type: InitiatorType,
-        next_hop: Option<DOMString>,
-        resource_timing: &ResourceFetchTiming,
-    ) -> PerformanceResourceTiming {
-        let duration = match (resource_timing.start_time, resource_timing.response_end) {
-            (Some(start_time), Some(end_time)) => end_time - start_time,
-            _ => Duration::ZERO,
-        };
-        PerformanceResourceTiming {
-            entry: PerformanceEntry::new_inherited(
-                DOMString::from(url.into_string()),
-                DOMString::from("resource"),
-                resource_timing.start_time,
+                start_time,
                 duration,
             ),
             initiator_type,
-            next_hop,
+            next_hop: DOMString::from(resource_timing.next_hop_protocol.clone()),
+            // Service workers do not intercept fetches yet.
             worker_start: None,
             redirect_start: resource_timing.redirect_start,
             redirect_end: resource_timing.redirect_end,
             fetch_start: resource_timing.fetch_start,
             domain_lookup_start: resource_timing.domain_lookup_start,
-            //TODO (#21260)
-            domain_lookup_end: None,
+            domain_lookup_end: resource_timing.domain_lookup_end,
             connect_start: resource_timing.connect_start,
             connect_end: resource_timing.connect_end,
             secure_connection_start: resource_timing.secure_connection_start,
             request_start: resource_timing.request_start,
             response_start: resource_timing.response_start,
             response_end: resource_timing.response_end,
-            transfer_size: 0,
-            encoded_body_size: 0,
-            decoded_body_size: 0,
+            transfer_size: resource_timing.transfer_size,
+            encoded_body_size: resource_timing.encoded_body_size,
+            decoded_body_size: resource_timing.decoded_body_size,
+            server_timing: resource_timing.server_timing.clone(),
+            frozen_server_timing: CachedFrozenArray::new(),
         }
     }
 
@@ -158,15 +132,13 @@ impl PerformanceResourceTiming {
         global: &GlobalScope,
         url: ServoUrl,
         initiator_type: InitiatorType,
-        next_hop: Option<DOMString>,
         resource_timing: &ResourceFetchTiming,
         can_gc: CanGc,
     ) -> DomRoot<PerformanceResourceTiming> {
         reflect_dom_object(
-            Box::new(PerformanceResourceTiming::from_resource_timing(
+            Box::new(PerformanceResourceTiming::new_inherited(
                 url,
                 initiator_type,
-                next_hop,
                 resource_timing,
             )),
             global,
@@ -205,10 +177,12 @@ impl PerformanceResourceTimingMethods<crate::DomTypeHolder> for PerformanceResou
     // returns the ALPN protocol ID of the network protocol used to fetch the resource
     // when a proxy is configured
     fn NextHopProtocol(&self) -> DOMString {
-        match self.next_hop {
-            Some(ref protocol) => protocol.clone(),
-            None => DOMString::from(""),
-        }
+        self.next_hop.clone()
+    }
+
+    // https://w3c.github.io/resource-timing/#dom-performanceresourcetiming-workerstart
+    fn WorkerStart(&self) -> DOMHighResTimeStamp {
+        self.to_dom_high_res_time_stamp(self.worker_start)
     }
 
     // https://w3c.github.io/resource-timing/#dom-performanceresourcetiming-domainlookupstart
@@ -280,4 +254,20 @@ impl PerformanceResourceTimingMethods<crate::DomTypeHolder> for PerformanceResou
     fn ResponseEnd(&self) -> DOMHighResTimeStamp {
         self.to_dom_high_res_time_stamp(self.response_end)
     }
+
+    // https://w3c.github.io/server-timing/#dom-performanceresourcetiming-servertiming
+    fn ServerTiming(&self, cx: JSContext, can_gc: CanGc, retval: MutableHandleValue) {
+        self.frozen_server_timing.get_or_init(
+            || {
+                let global = self.global();
+                self.server_timing
+                    .iter()
+                    .map(|metric| PerformanceServerTiming::new(&global, metric.clone(), can_gc))
+                    .collect()
+            },
+            cx,
+            retval,
+            can_gc,
+        );
+    }
 }
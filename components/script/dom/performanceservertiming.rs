@@ -0,0 +1,61 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use net_traits::ServerTimingMetric;
+
+use crate::dom::bindings::codegen::Bindings::PerformanceBinding::DOMHighResTimeStamp;
+use crate::dom::bindings::codegen::Bindings::PerformanceServerTimingBinding::PerformanceServerTimingMethods;
+use crate::dom::bindings::num::Finite;
+use crate::dom::bindings::reflector::{Reflector, reflect_dom_object};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::str::DOMString;
+use crate::dom::globalscope::GlobalScope;
+use crate::script_runtime::CanGc;
+
+/// <https://w3c.github.io/server-timing/#the-performanceservertiming-interface>
+#[dom_struct]
+pub(crate) struct PerformanceServerTiming {
+    reflector_: Reflector,
+    #[no_trace]
+    metric: ServerTimingMetric,
+}
+
+impl PerformanceServerTiming {
+    fn new_inherited(metric: ServerTimingMetric) -> PerformanceServerTiming {
+        PerformanceServerTiming {
+            reflector_: Reflector::new(),
+            metric,
+        }
+    }
+
+    pub(crate) fn new(
+        global: &GlobalScope,
+        metric: ServerTimingMetric,
+        can_gc: CanGc,
+    ) -> DomRoot<PerformanceServerTiming> {
+        reflect_dom_object(
+            Box::new(PerformanceServerTiming::new_inherited(metric)),
+            global,
+            can_gc,
+        )
+    }
+}
+
+impl PerformanceServerTimingMethods<crate::DomTypeHolder> for PerformanceServerTiming {
+    /// <https://w3c.github.io/server-timing/#dom-performanceservertiming-name>
+    fn Name(&self) -> DOMString {
+        DOMString::from(self.metric.name.clone())
+    }
+
+    /// <https://w3c.github.io/server-timing/#dom-performanceservertiming-duration>
+    fn Duration(&self) -> DOMHighResTimeStamp {
+        Finite::wrap(self.metric.duration)
+    }
+
+    /// <https://w3c.github.io/server-timing/#dom-performanceservertiming-description>
+    fn Description(&self) -> DOMString {
+        DOMString::from(self.metric.description.clone())
+    }
+}
@@ -10,6 +10,9 @@ use ipc_channel::ipc;
 use js::conversions::ConversionResult;
 use js::jsapi::JSObject;
 use js::jsval::{ObjectValue, UndefinedValue};
+use net_traits::IpcSend;
+use net_traits::permissions_policy::PolicyControlledFeature;
+use net_traits::storage_thread::StorageThreadMsg;
 use script_bindings::inheritance::Castable;
 use servo_config::pref;
 
@@ -140,11 +143,7 @@ impl Permissions {
 
                     Operation::Revoke => {
                         // (Revoke) Step 3.
-                        let globalscope = self.global();
-                        globalscope
-                            .permission_state_invocation_results()
-                            .borrow_mut()
-                            .remove(&root_desc.name);
+                        set_permission_store_entry(&self.global(), root_desc.name, None);
 
                         // (Revoke) Step 4.
                         Bluetooth::permission_revoke(&bluetooth_desc, &result, can_gc)
@@ -172,11 +171,7 @@ impl Permissions {
 
                     Operation::Revoke => {
                         // (Revoke) Step 3.
-                        let globalscope = self.global();
-                        globalscope
-                            .permission_state_invocation_results()
-                            .borrow_mut()
-                            .remove(&root_desc.name);
+                        set_permission_store_entry(&self.global(), root_desc.name, None);
 
                         // (Revoke) Step 4.
                         Permissions::permission_revoke(&root_desc, &status, can_gc);
@@ -267,14 +262,8 @@ impl PermissionAlgorithm for Permissions {
         match status.State() {
             // Step 3.
             PermissionState::Prompt => {
-                // https://w3c.github.io/permissions/#request-permission-to-use (Step 3 - 4)
-                let permission_name = status.get_query();
                 let globalscope = GlobalScope::current().expect("No current global object");
-                let state = prompt_user_from_embedder(permission_name, &globalscope);
-                globalscope
-                    .permission_state_invocation_results()
-                    .borrow_mut()
-                    .insert(permission_name, state);
+                request_permission_to_use(status.get_query(), &globalscope);
             },
 
             // Step 2.
@@ -319,8 +308,14 @@ pub(crate) fn descriptor_permission_state(
     // relevant global object has an associated Document run the following step:
     //   1. Let document be settings' relevant global object's associated Document.
     //   2. If document is not allowed to use feature, return "denied".
-    if let Some(window) = global_scope.downcast::<Window>() {
-        if !window.Document().allowed_to_use_feature(feature) {
+    let policy_controlled_feature: Option<PolicyControlledFeature> = feature.convert();
+    if let (Some(window), Some(policy_controlled_feature)) =
+        (global_scope.downcast::<Window>(), policy_controlled_feature)
+    {
+        if !window
+            .Document()
+            .allowed_to_use_feature(policy_controlled_feature)
+        {
             return PermissionState::Denied;
         }
     }
@@ -331,12 +326,8 @@ pub(crate) fn descriptor_permission_state(
     //
     // TODO: We aren't making a key based on the descriptor, but on the descriptor's name. This really
     // only matters for WebBluetooth, which adds more fields to the descriptor beyond the name.
-    if let Some(entry) = global_scope
-        .permission_state_invocation_results()
-        .borrow()
-        .get(&feature)
-    {
-        return *entry;
+    if let Some(entry) = get_permission_store_entry(&global_scope, feature) {
+        return entry;
     }
 
     // Step 8. Return the PermissionState enum value that represents the permission state
@@ -345,6 +336,81 @@ pub(crate) fn descriptor_permission_state(
     PermissionState::Prompt
 }
 
+/// <https://w3c.github.io/permissions/#dfn-request-permission-to-use>
+pub(crate) fn request_permission_to_use(
+    feature: PermissionName,
+    global_scope: &GlobalScope,
+) -> PermissionState {
+    // Step 1. Let current state be the descriptor's permission state given settings.
+    let current_state = descriptor_permission_state(feature, Some(global_scope));
+    // Step 2. If current state is not "prompt", return current state and abort these steps.
+    if current_state != PermissionState::Prompt {
+        return current_state;
+    }
+    // Step 3. Ask the user for express permission for the calling algorithm to use the
+    // powerful feature described by descriptor.
+    let state = prompt_user_from_embedder(feature, global_scope);
+    // Step 4. If the user grants permission, set a permission store entry with descriptor,
+    // key, and "granted"; otherwise set it with "denied".
+    set_permission_store_entry(global_scope, feature, Some(state));
+    // Step 5. Return the current permission state.
+    state
+}
+
+/// <https://w3c.github.io/permissions/#dfn-get-a-permission-store-entry>
+///
+/// The permission store is shared by every global with the same origin, and is persisted
+/// with the user's profile. Opaque origins don't have entries.
+fn get_permission_store_entry(
+    global_scope: &GlobalScope,
+    feature: PermissionName,
+) -> Option<PermissionState> {
+    if !global_scope.origin().is_tuple() {
+        return None;
+    }
+    let (sender, receiver) = ipc::channel().expect("Failed to create IPC channel!");
+    global_scope
+        .resource_threads()
+        .send(StorageThreadMsg::GetPermission(
+            sender,
+            global_scope.origin().immutable().clone(),
+            feature.convert(),
+        ))
+        .ok()?;
+    match receiver.recv().ok()? {
+        Some(AllowOrDeny::Allow) => Some(PermissionState::Granted),
+        Some(AllowOrDeny::Deny) => Some(PermissionState::Denied),
+        None => None,
+    }
+}
+
+/// <https://w3c.github.io/permissions/#dfn-set-a-permission-store-entry>
+/// and <https://w3c.github.io/permissions/#dfn-remove-a-permission-store-entry>
+///
+/// Removes the entry if `state` is `None`. Afterwards, runs the `PermissionStatus` update steps
+/// for the statuses of this global that observe `feature`.
+pub(crate) fn set_permission_store_entry(
+    global_scope: &GlobalScope,
+    feature: PermissionName,
+    state: Option<PermissionState>,
+) {
+    if global_scope.origin().is_tuple() {
+        let decision = match state {
+            Some(PermissionState::Granted) => Some(AllowOrDeny::Allow),
+            Some(PermissionState::Denied) => Some(AllowOrDeny::Deny),
+            Some(PermissionState::Prompt) | None => None,
+        };
+        let _ = global_scope
+            .resource_threads()
+            .send(StorageThreadMsg::SetPermission(
+                global_scope.origin().immutable().clone(),
+                feature.convert(),
+                decision,
+            ));
+    }
+    global_scope.permission_state_changed(feature);
+}
+
 fn prompt_user_from_embedder(name: PermissionName, global_scope: &GlobalScope) -> PermissionState {
     let Some(webview_id) = global_scope.webview_id() else {
         warn!("Requesting permissions from non-webview-associated global scope");
@@ -353,6 +419,7 @@ fn prompt_user_from_embedder(name: PermissionName, global_scope: &GlobalScope) -
     let (sender, receiver) = ipc::channel().expect("Failed to create IPC channel!");
     global_scope.send_to_embedder(EmbedderMsg::PromptPermission(
         webview_id,
+        global_scope.get_url(),
         name.convert(),
         sender,
     ));
@@ -384,6 +451,19 @@ impl Convert<PermissionFeature> for PermissionName {
             PermissionName::Background_sync => PermissionFeature::BackgroundSync,
             PermissionName::Bluetooth => PermissionFeature::Bluetooth,
             PermissionName::Persistent_storage => PermissionFeature::PersistentStorage,
+            PermissionName::Clipboard_read => PermissionFeature::ClipboardRead,
+            PermissionName::Clipboard_write => PermissionFeature::ClipboardWrite,
+        }
+    }
+}
+
+impl Convert<Option<PolicyControlledFeature>> for PermissionName {
+    fn convert(self) -> Option<PolicyControlledFeature> {
+        match self {
+            PermissionName::Geolocation => Some(PolicyControlledFeature::Geolocation),
+            PermissionName::Camera => Some(PolicyControlledFeature::Camera),
+            PermissionName::Microphone => Some(PolicyControlledFeature::Microphone),
+            _ => None,
         }
     }
 }
@@ -9,10 +9,12 @@ use dom_struct::dom_struct;
 use crate::dom::bindings::codegen::Bindings::PermissionStatusBinding::{
     PermissionDescriptor, PermissionName, PermissionState, PermissionStatusMethods,
 };
-use crate::dom::bindings::reflector::reflect_dom_object;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object};
 use crate::dom::bindings::root::DomRoot;
 use crate::dom::eventtarget::EventTarget;
 use crate::dom::globalscope::GlobalScope;
+use crate::dom::permissions::descriptor_permission_state;
 use crate::script_runtime::CanGc;
 
 // https://w3c.github.io/permissions/#permissionstatus
@@ -37,11 +39,13 @@ impl PermissionStatus {
         query: &PermissionDescriptor,
         can_gc: CanGc,
     ) -> DomRoot<PermissionStatus> {
-        reflect_dom_object(
+        let status = reflect_dom_object(
             Box::new(PermissionStatus::new_inherited(query.name)),
             global,
             can_gc,
-        )
+        );
+        global.track_permission_status(&status);
+        status
     }
 
     pub(crate) fn set_state(&self, state: PermissionState) {
@@ -51,6 +55,17 @@ impl PermissionStatus {
     pub(crate) fn get_query(&self) -> PermissionName {
         self.query.get()
     }
+
+    /// <https://w3c.github.io/permissions/#dfn-permissionstatus-update-steps>
+    pub(crate) fn update(&self, can_gc: CanGc) {
+        let state = descriptor_permission_state(self.get_query(), Some(&self.global()));
+        if state == self.state.get() {
+            return;
+        }
+        self.state.set(state);
+        self.upcast::<EventTarget>()
+            .fire_event(atom!("change"), can_gc);
+    }
 }
 
 impl PermissionStatusMethods<crate::DomTypeHolder> for PermissionStatus {
@@ -0,0 +1,534 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::rc::Rc;
+
+use aws_lc_rs::digest;
+use base64::prelude::*;
+use dom_struct::dom_struct;
+use embedder_traits::{
+    AttestationConveyancePreference, AuthenticatorAttachment, AuthenticatorError,
+    CredentialDescriptor, CredentialUser, EmbedderMsg, GetAssertionParameters,
+    GetAssertionResponse, MakeCredentialParameters, MakeCredentialResponse, RelyingParty,
+    UserVerificationRequirement,
+};
+use ipc_channel::ipc;
+use ipc_channel::router::ROUTER;
+use js::jsval::UndefinedValue;
+use js::typedarray::ArrayBuffer;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use servo_url::MutableOrigin;
+use url::Host;
+
+use crate::dom::abortsignal::AbortSignal;
+use crate::dom::authenticatorassertionresponse::AuthenticatorAssertionResponse;
+use crate::dom::authenticatorattestationresponse::AuthenticatorAttestationResponse;
+use crate::dom::authenticatorresponse::{AuthenticatorResponse, CachedArrayBuffer};
+use crate::dom::bindings::codegen::Bindings::PublicKeyCredentialBinding::{
+    AuthenticationExtensionsClientOutputs, PublicKeyCredentialCreationOptions,
+    PublicKeyCredentialDescriptor, PublicKeyCredentialMethods, PublicKeyCredentialRequestOptions,
+};
+use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::codegen::UnionTypes::ArrayBufferViewOrArrayBuffer;
+use crate::dom::bindings::error::{Error, Fallible};
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::refcounted::{Trusted, TrustedPromise};
+use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::{DOMString, USVString};
+use crate::dom::credential::Credential;
+use crate::dom::document::get_registrable_domain_suffix_of_or_is_equal_to;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::promise::Promise;
+use crate::dom::window::Window;
+use crate::script_runtime::{CanGc, JSContext};
+
+/// The timeout used when the relying party doesn't specify one, in milliseconds.
+const DEFAULT_TIMEOUT_MS: u32 = 300_000;
+
+/// The range that timeouts specified by the relying party are clamped to, in milliseconds.
+const MIN_TIMEOUT_MS: u32 = 15_000;
+const MAX_TIMEOUT_MS: u32 = 600_000;
+
+/// The algorithms used when the relying party doesn't list any, ES256 and RS256.
+/// <https://w3c.github.io/webauthn/#dom-publickeycredentialcreationoptions-pubkeycredparams>
+const DEFAULT_ALGORITHMS: [i32; 2] = [-7, -257];
+
+/// <https://w3c.github.io/webauthn/#publickeycredential>
+#[dom_struct]
+pub(crate) struct PublicKeyCredential {
+    credential: Credential,
+    /// <https://w3c.github.io/webauthn/#dom-publickeycredential-rawid>
+    raw_id: CachedArrayBuffer,
+    /// <https://w3c.github.io/webauthn/#dom-publickeycredential-response>
+    response: Dom<AuthenticatorResponse>,
+    /// <https://w3c.github.io/webauthn/#dom-publickeycredential-authenticatorattachment>
+    authenticator_attachment: Option<DOMString>,
+}
+
+impl PublicKeyCredential {
+    fn new_inherited(
+        raw_id: Vec<u8>,
+        response: &AuthenticatorResponse,
+        authenticator_attachment: Option<AuthenticatorAttachment>,
+    ) -> Self {
+        // <https://w3c.github.io/webauthn/#dom-publickeycredential-identifier-slot>
+        // The id is the base64url encoding of the credential ID.
+        let id = USVString(BASE64_URL_SAFE_NO_PAD.encode(&raw_id));
+        Self {
+            credential: Credential::new_inherited(id, DOMString::from("public-key")),
+            raw_id: CachedArrayBuffer::new(raw_id),
+            response: Dom::from_ref(response),
+            authenticator_attachment: authenticator_attachment.map(|attachment| {
+                DOMString::from(match attachment {
+                    AuthenticatorAttachment::Platform => "platform",
+                    AuthenticatorAttachment::CrossPlatform => "cross-platform",
+                })
+            }),
+        }
+    }
+
+    fn new(
+        global: &GlobalScope,
+        raw_id: Vec<u8>,
+        response: &AuthenticatorResponse,
+        authenticator_attachment: Option<AuthenticatorAttachment>,
+        can_gc: CanGc,
+    ) -> DomRoot<Self> {
+        reflect_dom_object(
+            Box::new(Self::new_inherited(
+                raw_id,
+                response,
+                authenticator_attachment,
+            )),
+            global,
+            can_gc,
+        )
+    }
+
+    /// <https://w3c.github.io/webauthn/#sctn-createCredential>
+    pub(crate) fn create(
+        window: &Window,
+        options: &PublicKeyCredentialCreationOptions,
+        signal: Option<&AbortSignal>,
+        promise: &Rc<Promise>,
+    ) -> Fallible<()> {
+        // Step 2. If sameOriginWithAncestors is false, return a "NotAllowedError" DOMException.
+        if !is_same_origin_with_its_ancestors(window) {
+            return Err(Error::NotAllowed);
+        }
+
+        // Step 4. Let timeout be the value of options.timeout, clamped to a reasonable range,
+        // or a client specific default if it is not present.
+        let timeout = clamp_timeout(options.timeout);
+
+        // Step 5. If the length of options.user.id is not between 1 and 64 bytes (inclusive)
+        // then throw a TypeError.
+        let user_id = buffer_source_to_vec(&options.user.id);
+        if user_id.is_empty() || user_id.len() > 64 {
+            return Err(Error::Type(
+                "The user id must be between 1 and 64 bytes long".to_owned(),
+            ));
+        }
+
+        // Steps 6-8. Determine the RP ID from the origin of the caller and options.rp.id.
+        let document = window.Document();
+        let caller_origin = document.origin();
+        let rp_id = relying_party_id(caller_origin, options.rp.id.as_deref())?;
+
+        // Steps 9-10. Collect the algorithms of the "public-key" credential types that are
+        // requested, falling back to the defaults if none are listed at all.
+        let algorithms = if options.pubKeyCredParams.is_empty() {
+            DEFAULT_ALGORITHMS.to_vec()
+        } else {
+            let algorithms: Vec<i32> = options
+                .pubKeyCredParams
+                .iter()
+                .filter(|parameters| &*parameters.type_ == "public-key")
+                .map(|parameters| parameters.alg)
+                .collect();
+            if algorithms.is_empty() {
+                return Err(Error::NotSupported);
+            }
+            algorithms
+        };
+
+        // Steps 13-16. Let collectedClientData be the client data of the caller and let
+        // clientDataHash be the hash of its serialization.
+        let client_data_json = client_data_json(
+            "webauthn.create",
+            &buffer_source_to_vec(&options.challenge),
+            caller_origin,
+        );
+        let client_data_hash = digest::digest(&digest::SHA256, &client_data_json)
+            .as_ref()
+            .to_vec();
+
+        // Step 20. Let the authenticators that match options.authenticatorSelection create
+        // a credential.
+        let selection = options.authenticatorSelection.as_ref();
+        let authenticator_attachment = selection
+            .and_then(|selection| selection.authenticatorAttachment.as_deref())
+            .and_then(|attachment| match attachment {
+                "platform" => Some(AuthenticatorAttachment::Platform),
+                "cross-platform" => Some(AuthenticatorAttachment::CrossPlatform),
+                _ => None,
+            });
+        // <https://w3c.github.io/webauthn/#dom-authenticatorselectioncriteria-residentkey>
+        // If residentKey is not present, requireResidentKey decides whether a discoverable
+        // credential is required.
+        let require_resident_key =
+            selection.is_some_and(|selection| match selection.residentKey.as_deref() {
+                Some(resident_key) => resident_key == "required",
+                None => selection.requireResidentKey,
+            });
+        let user_verification = selection
+            .map(|selection| user_verification_requirement(&selection.userVerification))
+            .unwrap_or_default();
+        let attestation = match &*options.attestation {
+            "indirect" => AttestationConveyancePreference::Indirect,
+            "direct" => AttestationConveyancePreference::Direct,
+            "enterprise" => AttestationConveyancePreference::Enterprise,
+            _ => AttestationConveyancePreference::None,
+        };
+
+        let parameters = MakeCredentialParameters {
+            client_data_hash,
+            relying_party: RelyingParty {
+                id: rp_id,
+                name: options.rp.parent.name.to_string(),
+            },
+            user: CredentialUser {
+                id: user_id,
+                name: options.user.parent.name.to_string(),
+                display_name: options.user.displayName.to_string(),
+            },
+            algorithms,
+            exclude_credentials: credential_descriptors(&options.excludeCredentials),
+            authenticator_attachment,
+            require_resident_key,
+            user_verification,
+            attestation,
+            timeout,
+        };
+
+        let sender = route_authenticator_result(
+            window,
+            signal,
+            promise,
+            move |global, response: MakeCredentialResponse, can_gc| {
+                let credential_id = response.credential_id.clone();
+                let authenticator_attachment = response.authenticator_attachment;
+                let response = AuthenticatorAttestationResponse::new(
+                    global,
+                    client_data_json,
+                    response,
+                    can_gc,
+                );
+                PublicKeyCredential::new(
+                    global,
+                    credential_id,
+                    response.upcast(),
+                    authenticator_attachment,
+                    can_gc,
+                )
+            },
+        );
+        window.send_to_embedder(EmbedderMsg::MakeCredential(
+            window.webview_id(),
+            window.get_url(),
+            parameters,
+            sender,
+        ));
+        Ok(())
+    }
+
+    /// <https://w3c.github.io/webauthn/#sctn-discover-from-external-source>
+    pub(crate) fn discover_from_external_source(
+        window: &Window,
+        options: &PublicKeyCredentialRequestOptions,
+        signal: Option<&AbortSignal>,
+        promise: &Rc<Promise>,
+    ) -> Fallible<()> {
+        // Step 3. Let timeout be the value of options.timeout, clamped to a reasonable range,
+        // or a client specific default if it is not present.
+        let timeout = clamp_timeout(options.timeout);
+
+        // Steps 4-7. Determine the RP ID from the origin of the caller and options.rpId.
+        let document = window.Document();
+        let caller_origin = document.origin();
+        let rp_id = relying_party_id(caller_origin, options.rpId.as_deref())?;
+
+        // Steps 10-13. Let collectedClientData be the client data of the caller and let
+        // clientDataHash be the hash of its serialization.
+        let client_data_json = client_data_json(
+            "webauthn.get",
+            &buffer_source_to_vec(&options.challenge),
+            caller_origin,
+        );
+        let client_data_hash = digest::digest(&digest::SHA256, &client_data_json)
+            .as_ref()
+            .to_vec();
+
+        let parameters = GetAssertionParameters {
+            client_data_hash,
+            relying_party_id: rp_id,
+            allow_credentials: credential_descriptors(&options.allowCredentials),
+            user_verification: user_verification_requirement(&options.userVerification),
+            timeout,
+        };
+
+        let sender = route_authenticator_result(
+            window,
+            signal,
+            promise,
+            move |global, response: GetAssertionResponse, can_gc| {
+                let credential_id = response.credential_id.clone();
+                let authenticator_attachment = response.authenticator_attachment;
+                let response =
+                    AuthenticatorAssertionResponse::new(global, client_data_json, response, can_gc);
+                PublicKeyCredential::new(
+                    global,
+                    credential_id,
+                    response.upcast(),
+                    authenticator_attachment,
+                    can_gc,
+                )
+            },
+        );
+        window.send_to_embedder(EmbedderMsg::GetAssertion(
+            window.webview_id(),
+            window.get_url(),
+            parameters,
+            sender,
+        ));
+        Ok(())
+    }
+}
+
+impl PublicKeyCredentialMethods<crate::DomTypeHolder> for PublicKeyCredential {
+    /// <https://w3c.github.io/webauthn/#dom-publickeycredential-rawid>
+    fn RawId(&self, cx: JSContext, can_gc: CanGc) -> ArrayBuffer {
+        self.raw_id.get(cx, can_gc)
+    }
+
+    /// <https://w3c.github.io/webauthn/#dom-publickeycredential-response>
+    fn Response(&self) -> DomRoot<AuthenticatorResponse> {
+        DomRoot::from_ref(&self.response)
+    }
+
+    /// <https://w3c.github.io/webauthn/#dom-publickeycredential-authenticatorattachment>
+    fn GetAuthenticatorAttachment(&self) -> Option<DOMString> {
+        self.authenticator_attachment.clone()
+    }
+
+    /// <https://w3c.github.io/webauthn/#dom-publickeycredential-getclientextensionresults>
+    fn GetClientExtensionResults(&self) -> AuthenticationExtensionsClientOutputs {
+        // No extensions are supported, so there are never any results.
+        AuthenticationExtensionsClientOutputs::empty()
+    }
+
+    /// <https://w3c.github.io/webauthn/#dom-publickeycredential-isuserverifyingplatformauthenticatoravailable>
+    fn IsUserVerifyingPlatformAuthenticatorAvailable(
+        global: &GlobalScope,
+        can_gc: CanGc,
+    ) -> Rc<Promise> {
+        let promise = Promise::new(global, can_gc);
+        let window = global.as_window();
+        let (sender, receiver) = ipc::channel().expect("Failed to create IPC channel!");
+        let task_source = global
+            .task_manager()
+            .dom_manipulation_task_source()
+            .to_sendable();
+        let trusted_promise = TrustedPromise::new(promise.clone());
+        ROUTER.add_typed_route(
+            receiver,
+            Box::new(move |message| {
+                let available = message.unwrap_or(false);
+                let trusted_promise = trusted_promise.clone();
+                task_source.queue(task!(resolve_platform_authenticator_available: move || {
+                    trusted_promise.root().resolve_native(&available, CanGc::note());
+                }));
+            }),
+        );
+        window.send_to_embedder(EmbedderMsg::IsUserVerifyingPlatformAuthenticatorAvailable(
+            window.webview_id(),
+            sender,
+        ));
+        promise
+    }
+}
+
+/// Routes the result of an authenticator operation back to the script thread, where it
+/// settles `promise`. A successful result is turned into a credential by `on_success`.
+/// If `signal` is aborted while the operation is in progress, the promise is rejected with
+/// the abort reason instead.
+fn route_authenticator_result<Response, OnSuccess>(
+    window: &Window,
+    signal: Option<&AbortSignal>,
+    promise: &Rc<Promise>,
+    on_success: OnSuccess,
+) -> ipc::IpcSender<Result<Response, AuthenticatorError>>
+where
+    Response: Serialize + DeserializeOwned + Send + 'static,
+    OnSuccess:
+        FnOnce(&GlobalScope, Response, CanGc) -> DomRoot<PublicKeyCredential> + Send + 'static,
+{
+    let (sender, receiver) = ipc::channel().expect("Failed to create IPC channel!");
+    let task_source = window
+        .as_global_scope()
+        .task_manager()
+        .dom_manipulation_task_source()
+        .to_sendable();
+    let trusted_promise = TrustedPromise::new(promise.clone());
+    let trusted_signal = signal.map(Trusted::new);
+    let mut on_success = Some(on_success);
+    ROUTER.add_typed_route(
+        receiver,
+        Box::new(move |message| {
+            let Some(on_success) = on_success.take() else {
+                return;
+            };
+            let result = message.unwrap_or(Err(AuthenticatorError::NotAllowed));
+            let trusted_promise = trusted_promise.clone();
+            let trusted_signal = trusted_signal.clone();
+            task_source.queue(task!(settle_authenticator_operation: move || {
+                let promise = trusted_promise.root();
+                let can_gc = CanGc::note();
+                if let Some(signal) = trusted_signal.map(|signal| signal.root()) {
+                    if signal.aborted() {
+                        let cx = GlobalScope::get_cx();
+                        rooted!(in(*cx) let mut reason = UndefinedValue());
+                        reason.set(signal.abort_reason());
+                        promise.reject(cx, reason.handle(), can_gc);
+                        return;
+                    }
+                }
+                match result {
+                    Ok(response) => {
+                        let credential = on_success(&promise.global(), response, can_gc);
+                        promise.resolve_native(&credential, can_gc);
+                    },
+                    Err(error) => promise.reject_error(authenticator_error_to_dom(error), can_gc),
+                }
+            }));
+        }),
+    );
+    sender
+}
+
+fn authenticator_error_to_dom(error: AuthenticatorError) -> Error {
+    match error {
+        AuthenticatorError::NotAllowed => Error::NotAllowed,
+        AuthenticatorError::InvalidState => Error::InvalidState,
+        AuthenticatorError::NotSupported => Error::NotSupported,
+        AuthenticatorError::Unknown(message) => {
+            warn!("Authenticator operation failed: {message}");
+            Error::Operation
+        },
+    }
+}
+
+/// <https://w3c.github.io/webappsec-credential-management/#same-origin-with-its-ancestors>
+fn is_same_origin_with_its_ancestors(window: &Window) -> bool {
+    let document = window.Document();
+    let origin = document.origin();
+    let mut window_proxy = window.window_proxy();
+    while let Some(parent) = window_proxy.parent().map(DomRoot::from_ref) {
+        // A parent in another script thread is never same origin.
+        match parent.document() {
+            Some(parent_document) if parent_document.origin().same_origin(origin) => {},
+            _ => return false,
+        }
+        window_proxy = parent;
+    }
+    true
+}
+
+/// Determines the RP ID of an operation from the origin of the caller and the RP ID that
+/// the relying party asked for, if any.
+/// <https://w3c.github.io/webauthn/#rp-id>
+fn relying_party_id(caller_origin: &MutableOrigin, requested_id: Option<&str>) -> Fallible<String> {
+    // If callerOrigin is an opaque origin, return a "NotAllowedError" DOMException.
+    if !caller_origin.is_tuple() {
+        return Err(Error::NotAllowed);
+    }
+
+    // Let effectiveDomain be the callerOrigin’s effective domain. If effectiveDomain is not
+    // a valid domain, then return a "SecurityError" DOMException.
+    let Some(effective_domain @ Host::Domain(_)) = caller_origin.effective_domain() else {
+        return Err(Error::Security);
+    };
+
+    // If the RP ID is present, it must be a registrable domain suffix of or equal to
+    // effectiveDomain. Otherwise the RP ID is effectiveDomain.
+    match requested_id {
+        Some(requested_id) => {
+            get_registrable_domain_suffix_of_or_is_equal_to(requested_id, effective_domain)
+                .map(|host| host.to_string())
+                .ok_or(Error::Security)
+        },
+        None => Ok(effective_domain.to_string()),
+    }
+}
+
+/// Serializes the client data of an operation as its JSON-compatible serialization.
+/// The members are written in the order required by
+/// <https://w3c.github.io/webauthn/#clientdatajson-serialization>.
+fn client_data_json(type_: &str, challenge: &[u8], caller_origin: &MutableOrigin) -> Vec<u8> {
+    let challenge = BASE64_URL_SAFE_NO_PAD.encode(challenge);
+    let origin = caller_origin.immutable().ascii_serialization();
+    format!(
+        "{{\"type\":{},\"challenge\":{},\"origin\":{},\"crossOrigin\":false}}",
+        serde_json::Value::from(type_),
+        serde_json::Value::from(challenge),
+        serde_json::Value::from(origin),
+    )
+    .into_bytes()
+}
+
+fn clamp_timeout(timeout: Option<u32>) -> u32 {
+    timeout.map_or(DEFAULT_TIMEOUT_MS, |timeout| {
+        timeout.clamp(MIN_TIMEOUT_MS, MAX_TIMEOUT_MS)
+    })
+}
+
+/// <https://w3c.github.io/webauthn/#enumdef-userverificationrequirement>
+/// Unknown values are ignored, leaving the default of "preferred".
+fn user_verification_requirement(user_verification: &str) -> UserVerificationRequirement {
+    match user_verification {
+        "required" => UserVerificationRequirement::Required,
+        "discouraged" => UserVerificationRequirement::Discouraged,
+        _ => UserVerificationRequirement::Preferred,
+    }
+}
+
+/// Converts the descriptors of the "public-key" credentials in `descriptors`, ignoring
+/// those of any other type.
+fn credential_descriptors<Descriptor>(descriptors: &[Descriptor]) -> Vec<CredentialDescriptor>
+where
+    Descriptor: std::ops::Deref<Target = PublicKeyCredentialDescriptor>,
+{
+    descriptors
+        .iter()
+        .filter(|descriptor| &*descriptor.type_ == "public-key")
+        .map(|descriptor| CredentialDescriptor {
+            id: buffer_source_to_vec(&descriptor.id),
+            transports: descriptor
+                .transports
+                .iter()
+                .flatten()
+                .map(|transport| transport.to_string())
+                .collect(),
+        })
+        .collect()
+}
+
+fn buffer_source_to_vec(source: &ArrayBufferViewOrArrayBuffer) -> Vec<u8> {
+    match source {
+        ArrayBufferViewOrArrayBuffer::ArrayBufferView(view) => view.to_vec(),
+        ArrayBufferViewOrArrayBuffer::ArrayBuffer(buffer) => buffer.to_vec(),
+    }
+}
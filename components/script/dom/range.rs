@@ -327,7 +327,7 @@ impl Range {
         self.abstract_range().Collapsed()
     }
 
-    fn client_rects(
+    pub(crate) fn client_rects(
         &self,
     ) -> impl Iterator<Item = euclid::Rect<app_units::Au, euclid::UnknownUnit>> {
         // FIXME: For text nodes that are only partially selected, this should return the client
@@ -9,8 +9,9 @@ use style::selector_parser::PseudoElement;
 use stylo_atoms::Atom;
 
 use crate::dom::bindings::root::{Dom, MutNullableDom};
+use crate::dom::bindings::str::DOMString;
 use crate::dom::customelementregistry::{
-    CustomElementDefinition, CustomElementReaction, CustomElementState,
+    CustomElementDefinition, CustomElementReaction, CustomElementRegistry, CustomElementState,
 };
 use crate::dom::domtokenlist::DOMTokenList;
 use crate::dom::elementinternals::ElementInternals;
@@ -70,6 +71,10 @@ pub(crate) struct ElementRareData {
     pub(crate) custom_element_definition: Option<Rc<CustomElementDefinition>>,
     /// <https://dom.spec.whatwg.org/#concept-element-custom-element-state>
     pub(crate) custom_element_state: CustomElementState,
+    /// <https://wicg.github.io/webcomponents/proposals/Scoped-Custom-Element-Registries>
+    /// The scoped custom element registry this element was created with, if any.
+    /// Elements without one use their document's global registry.
+    pub(crate) custom_element_registry: Option<Dom<CustomElementRegistry>>,
     /// The "name" content attribute; not used as frequently as id, but used
     /// in named getter loops so it's worth looking up quickly when present
     #[no_trace]
@@ -91,4 +96,8 @@ pub(crate) struct ElementRareData {
 
     /// <https://drafts.csswg.org/css-shadow-parts/#dom-element-part>
     pub(crate) part: MutNullableDom<DOMTokenList>,
+
+    /// The URL of the last image of this element that was painted, as reported to Largest
+    /// Contentful Paint and Element Timing.
+    pub(crate) painted_image_url: Option<DOMString>,
 }
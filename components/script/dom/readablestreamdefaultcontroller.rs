@@ -773,6 +773,34 @@ impl ReadableStreamDefaultController {
         None
     }
 
+    /// Remove the native chunks queued so far, for a consumer that reads the body
+    /// outside of the streams machinery, like WebAssembly streaming compilation.
+    /// Also returns whether the source has already been closed, in which case the
+    /// stream is closed now that its queue is empty.
+    pub(crate) fn take_native_chunks(&self, can_gc: CanGc) -> (Vec<Box<[u8]>>, bool) {
+        let chunks = {
+            let mut queue = self.queue.borrow_mut();
+            let chunks = queue
+                .queue
+                .drain(..)
+                .filter_map(|value| match value {
+                    EnqueuedValue::Native(chunk) => Some(chunk),
+                    _ => None,
+                })
+                .collect();
+            queue.total_size = Default::default();
+            chunks
+        };
+        let close_requested = self.close_requested.get();
+        if close_requested {
+            self.clear_algorithms();
+            if let Some(stream) = self.stream.get().filter(|stream| stream.is_readable()) {
+                stream.close(can_gc);
+            }
+        }
+        (chunks, close_requested)
+    }
+
     /// <https://streams.spec.whatwg.org/#readable-stream-default-controller-clear-algorithms>
     fn clear_algorithms(&self) {
         // Set controller.[[pullAlgorithm]] to undefined.
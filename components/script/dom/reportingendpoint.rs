@@ -4,6 +4,7 @@
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use headers::{ContentType, HeaderMapExt};
 use http::HeaderMap;
@@ -18,17 +19,24 @@ use net_traits::{
 };
 use script_bindings::str::DOMString;
 use serde::Serialize;
+use servo_config::pref;
 use servo_url::{ImmutableOrigin, ServoUrl};
 
 use crate::dom::bindings::codegen::Bindings::CSPViolationReportBodyBinding::CSPViolationReportBody;
+use crate::dom::bindings::codegen::Bindings::DeprecationReportBodyBinding::DeprecationReportBodyMethods;
+use crate::dom::bindings::codegen::Bindings::InterventionReportBodyBinding::InterventionReportBodyMethods;
 use crate::dom::bindings::codegen::Bindings::ReportingObserverBinding::Report;
 use crate::dom::bindings::codegen::Bindings::SecurityPolicyViolationEventBinding::SecurityPolicyViolationEventDisposition;
+use crate::dom::bindings::codegen::UnionTypes::CSPViolationReportBodyOrDeprecationReportBodyOrInterventionReportBody as ReportBodyType;
+use crate::dom::bindings::inheritance::Castable;
 use crate::dom::bindings::refcounted::Trusted;
 use crate::dom::bindings::root::DomRoot;
 use crate::dom::csp::Violation;
 use crate::dom::csppolicyviolationreport::serialize_disposition;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::performanceresourcetiming::InitiatorType;
+use crate::dom::window::Window;
+use crate::dom::workerglobalscope::WorkerGlobalScope;
 use crate::fetch::create_a_potential_cors_request;
 use crate::network_listener::{PreInvoke, ResourceTimingListener, submit_timing};
 use crate::script_runtime::CanGc;
@@ -170,16 +178,10 @@ impl SendReportsToEndpoints for GlobalScope {
                     origin.clone(),
                     origin_report_list,
                 );
-                // Step 3.3.2. If result is "Failure":
-                // TODO(37238)
-                // Step 3.3.2.1. Increment endpoint’s failures.
-                // TODO(37238)
-                // Step 3.3.3. If result is "Remove Endpoint":
-                // TODO(37238)
-                // Step 3.3.3.1 Remove endpoint from context’s endpoints list.
-                // TODO(37238)
+                // Steps 3.3.2-3.3.3 are handled once the response has been received, in
+                // `CSPReportEndpointFetchListener::process_response`.
                 // Step 3.3.4. Remove each report from reports.
-                // TODO(37238)
+                // Note: The reports were already taken from the report buffer by the caller.
             }
         }
     }
@@ -225,28 +227,24 @@ impl SendReportsToEndpoints for GlobalScope {
             })),
             self.task_manager().networking_task_source().into(),
         );
-        // Step 4. Wait for a response (response).
-        // TODO(37238)
-        // Step 5. If response’s status is an OK status (200-299), return "Success".
-        // TODO(37238)
-        // Step 6. If response’s status is 410 Gone [RFC9110], return "Remove Endpoint".
-        // TODO(37238)
-        // Step 7. Return "Failure".
-        // TODO(37238)
+        // Steps 4-7 are handled in `CSPReportEndpointFetchListener::process_response`.
     }
 
     fn serialize_list_of_reports(reports: &[&Report]) -> Option<RequestBody> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as f64;
         // Step 1. Let collection be an empty list.
         // Step 2. For each report in reports:
         let report_body: Vec<SerializedReport> = reports
             .iter()
             // Step 2.1. Let data be a map with the following key/value pairs:
             .map(|r| SerializedReport {
-                // TODO(37238)
-                age: 0,
+                age: (now - *r.timestamp).max(0.) as u64,
                 type_: r.type_.to_string(),
                 url: r.url.to_string(),
-                user_agent: "".to_owned(),
+                user_agent: pref!(user_agent),
                 body: r.body.clone().map(|b| b.into()),
             })
             // Step 2.2. Increment report’s attempts.
@@ -268,7 +266,51 @@ struct SerializedReport {
     type_: String,
     url: String,
     user_agent: String,
-    body: Option<CSPReportingEndpointBody>,
+    body: Option<SerializedReportBody>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum SerializedReportBody {
+    Csp(CSPReportingEndpointBody),
+    Feature(FeatureReportingEndpointBody),
+}
+
+impl From<ReportBodyType> for SerializedReportBody {
+    fn from(value: ReportBodyType) -> Self {
+        match value {
+            ReportBodyType::CSPViolationReportBody(body) => SerializedReportBody::Csp(body.into()),
+            ReportBodyType::DeprecationReportBody(body) => {
+                SerializedReportBody::Feature(FeatureReportingEndpointBody {
+                    id: body.Id().into(),
+                    message: body.Message().into(),
+                    source_file: body.GetSourceFile().map(Into::into),
+                    line_number: body.GetLineNumber(),
+                    column_number: body.GetColumnNumber(),
+                })
+            },
+            ReportBodyType::InterventionReportBody(body) => {
+                SerializedReportBody::Feature(FeatureReportingEndpointBody {
+                    id: body.Id().into(),
+                    message: body.Message().into(),
+                    source_file: body.GetSourceFile().map(Into::into),
+                    line_number: body.GetLineNumber(),
+                    column_number: body.GetColumnNumber(),
+                })
+            },
+        }
+    }
+}
+
+/// The body of a "deprecation" or "intervention" report.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FeatureReportingEndpointBody {
+    id: String,
+    message: String,
+    source_file: Option<String>,
+    line_number: Option<u32>,
+    column_number: Option<u32>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -322,12 +364,45 @@ impl FetchResponseListener for CSPReportEndpointFetchListener {
 
     fn process_request_eof(&mut self, _: RequestId) {}
 
+    /// Steps 4-7 of <https://w3c.github.io/reporting/#try-delivery>, followed by steps 3.3.2-3.3.3
+    /// of <https://w3c.github.io/reporting/#send-reports>.
     fn process_response(
         &mut self,
         _: RequestId,
         fetch_metadata: Result<FetchMetadata, NetworkError>,
     ) {
-        _ = fetch_metadata;
+        // Step 4. Wait for a response (response).
+        let status = fetch_metadata.ok().map(|metadata| match metadata {
+            FetchMetadata::Unfiltered(m) => m.status,
+            FetchMetadata::Filtered { unsafe_, .. } => unsafe_.status,
+        });
+        let global = self.global.root();
+        let mut endpoints = if let Some(window) = global.downcast::<Window>() {
+            window.endpoints_list_mut()
+        } else if let Some(worker) = global.downcast::<WorkerGlobalScope>() {
+            worker.endpoints_list_mut()
+        } else {
+            return;
+        };
+        match status.map(|status| status.raw_code()) {
+            // Step 5. If response’s status is an OK status (200-299), return "Success".
+            Some(200..=299) => {},
+            // Step 6. If response’s status is 410 Gone [RFC9110], return "Remove Endpoint".
+            Some(410) => {
+                // Step 3.3.3.1 Remove endpoint from context’s endpoints list.
+                endpoints.retain(|endpoint| endpoint.url != self.endpoint);
+            },
+            // Step 7. Return "Failure".
+            _ => {
+                // Step 3.3.2.1. Increment endpoint’s failures.
+                for endpoint in endpoints
+                    .iter_mut()
+                    .filter(|endpoint| endpoint.url == self.endpoint)
+                {
+                    endpoint.failures += 1;
+                }
+            },
+        }
     }
 
     fn process_response_chunk(&mut self, _: RequestId, chunk: Vec<u8>) {
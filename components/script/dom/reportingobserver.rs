@@ -9,22 +9,29 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use dom_struct::dom_struct;
 use js::rust::HandleObject;
 use script_bindings::str::DOMString;
+use servo_config::pref;
 use servo_url::ServoUrl;
 
 use crate::dom::bindings::callback::ExceptionHandling;
 use crate::dom::bindings::cell::DomRefCell;
-use crate::dom::bindings::codegen::Bindings::CSPViolationReportBodyBinding::CSPViolationReportBody;
 use crate::dom::bindings::codegen::Bindings::ReportingObserverBinding::{
     Report, ReportList, ReportingObserverCallback, ReportingObserverMethods,
     ReportingObserverOptions,
 };
+use crate::dom::bindings::codegen::UnionTypes::CSPViolationReportBodyOrDeprecationReportBodyOrInterventionReportBody as ReportBodyType;
 use crate::dom::bindings::num::Finite;
 use crate::dom::bindings::refcounted::Trusted;
 use crate::dom::bindings::reflector::{DomGlobal, Reflector, reflect_dom_object_with_proto};
 use crate::dom::bindings::root::DomRoot;
+use crate::dom::csp::compute_scripted_caller_source_position;
+use crate::dom::deprecationreportbody::DeprecationReportBody;
 use crate::dom::globalscope::GlobalScope;
+use crate::dom::interventionreportbody::InterventionReportBody;
 use crate::script_runtime::CanGc;
 
+/// The maximum number of reports of a single type kept in a global's report buffer.
+const MAX_BUFFERED_REPORTS_PER_TYPE: usize = 100;
+
 #[dom_struct]
 pub(crate) struct ReportingObserver {
     reflector_: Reflector,
@@ -70,6 +77,10 @@ impl ReportingObserver {
         match report.type_.str() {
             // https://w3c.github.io/webappsec-csp/#reporting
             "csp-violation" => true,
+            // https://wicg.github.io/deprecation-reporting/#deprecation-report
+            "deprecation" => true,
+            // https://wicg.github.io/intervention-reporting/#intervention-report
+            "intervention" => true,
             _ => false,
         }
     }
@@ -121,13 +132,26 @@ impl ReportingObserver {
         for observer in global.registered_reporting_observers().iter() {
             observer.add_report_to_observer(report);
         }
+        let mut report_buffer = global.report_buffer_mut();
         // Step 2. Append report to scope’s report buffer.
-        global.append_report(report.clone());
+        report_buffer.push(report.clone());
         // Step 3. Let type be report’s type.
-        // TODO(37328)
+        let type_ = &report.type_;
         // Step 4. If scope’s report buffer now contains more than 100 reports with
         // type equal to type, remove the earliest item with type equal to type in the report buffer.
-        // TODO(37328)
+        if report_buffer
+            .iter()
+            .filter(|report| report.type_ == *type_)
+            .count() >
+            MAX_BUFFERED_REPORTS_PER_TYPE
+        {
+            if let Some(index) = report_buffer
+                .iter()
+                .position(|report| report.type_ == *type_)
+            {
+                report_buffer.remove(index);
+            }
+        }
     }
 
     /// <https://w3c.github.io/reporting/#invoke-observers>
@@ -158,7 +182,7 @@ impl ReportingObserver {
         global: &GlobalScope,
         type_: DOMString,
         url: Option<ServoUrl>,
-        body: Option<CSPViolationReportBody>,
+        body: Option<ReportBodyType>,
         destination: DOMString,
     ) -> Report {
         // Step 2. If url was not provided by the caller, let url be settings’s creation URL.
@@ -188,7 +212,7 @@ impl ReportingObserver {
     pub(crate) fn generate_and_queue_a_report(
         global: &GlobalScope,
         type_: DOMString,
-        body: Option<CSPViolationReportBody>,
+        body: Option<ReportBodyType>,
         destination: DOMString,
     ) {
         // Step 1. Let settings be context’s relevant settings object.
@@ -203,6 +227,55 @@ impl ReportingObserver {
         global.append_report(report);
     }
 
+    /// <https://wicg.github.io/deprecation-reporting/#deprecation-report>
+    ///
+    /// Queues a report for the use of the deprecated feature identified by `id`.
+    pub(crate) fn report_deprecation(global: &GlobalScope, id: &str, message: &str, can_gc: CanGc) {
+        if !pref!(dom_deprecation_reporting_enabled) {
+            return;
+        }
+        let body = DeprecationReportBody::new(
+            global,
+            id.into(),
+            message.into(),
+            compute_scripted_caller_source_position(),
+            can_gc,
+        );
+        Self::generate_and_queue_a_report(
+            global,
+            "deprecation".into(),
+            Some(ReportBodyType::DeprecationReportBody(body)),
+            "default".into(),
+        );
+    }
+
+    /// <https://wicg.github.io/intervention-reporting/#intervention-report>
+    ///
+    /// Queues a report for the intervention identified by `id`.
+    pub(crate) fn report_intervention(
+        global: &GlobalScope,
+        id: &str,
+        message: &str,
+        can_gc: CanGc,
+    ) {
+        if !pref!(dom_intervention_reporting_enabled) {
+            return;
+        }
+        let body = InterventionReportBody::new(
+            global,
+            id.into(),
+            message.into(),
+            compute_scripted_caller_source_position(),
+            can_gc,
+        );
+        Self::generate_and_queue_a_report(
+            global,
+            "intervention".into(),
+            Some(ReportBodyType::InterventionReportBody(body)),
+            "default".into(),
+        );
+    }
+
     /// <https://w3c.github.io/webappsec-csp/#strip-url-for-use-in-reports>
     pub(crate) fn strip_url_for_reports(mut url: ServoUrl) -> String {
         let scheme = url.scheme();
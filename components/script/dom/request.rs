@@ -15,23 +15,25 @@ use net_traits::ReferrerPolicy as MsgReferrerPolicy;
 use net_traits::fetch::headers::is_forbidden_method;
 use net_traits::request::{
     CacheMode as NetTraitsRequestCache, CredentialsMode as NetTraitsRequestCredentials,
-    Destination as NetTraitsRequestDestination, Origin, RedirectMode as NetTraitsRequestRedirect,
-    Referrer as NetTraitsRequestReferrer, Request as NetTraitsRequest, RequestBuilder,
-    RequestMode as NetTraitsRequestMode, Window,
+    Destination as NetTraitsRequestDestination, InternalPriority, Origin,
+    RedirectMode as NetTraitsRequestRedirect, Referrer as NetTraitsRequestReferrer,
+    Request as NetTraitsRequest, RequestBuilder, RequestMode as NetTraitsRequestMode,
+    RequestPriority as NetTraitsRequestPriority, Window,
 };
 use servo_url::ServoUrl;
 
 use crate::body::{BodyMixin, BodyType, Extractable, consume_body};
 use crate::conversions::Convert;
+use crate::dom::abortsignal::AbortSignal;
 use crate::dom::bindings::cell::DomRefCell;
 use crate::dom::bindings::codegen::Bindings::HeadersBinding::{HeadersInit, HeadersMethods};
 use crate::dom::bindings::codegen::Bindings::RequestBinding::{
-    ReferrerPolicy, RequestCache, RequestCredentials, RequestDestination, RequestInfo, RequestInit,
-    RequestMethods, RequestMode, RequestRedirect,
+    ReferrerPolicy, RequestCache, RequestCredentials, RequestDestination, RequestDuplex,
+    RequestInfo, RequestInit, RequestMethods, RequestMode, RequestPriority, RequestRedirect,
 };
 use crate::dom::bindings::error::{Error, Fallible};
 use crate::dom::bindings::reflector::{DomGlobal, Reflector, reflect_dom_object_with_proto};
-use crate::dom::bindings::root::{DomRoot, MutNullableDom};
+use crate::dom::bindings::root::{DomRoot, MutDom, MutNullableDom};
 use crate::dom::bindings::str::{ByteString, DOMString, USVString};
 use crate::dom::bindings::trace::RootedTraceableBox;
 use crate::dom::globalscope::GlobalScope;
@@ -47,15 +49,18 @@ pub(crate) struct Request {
     request: DomRefCell<NetTraitsRequest>,
     body_stream: MutNullableDom<ReadableStream>,
     headers: MutNullableDom<Headers>,
+    /// <https://fetch.spec.whatwg.org/#request-signal>
+    signal: MutDom<AbortSignal>,
 }
 
 impl Request {
-    fn new_inherited(global: &GlobalScope, url: ServoUrl) -> Request {
+    fn new_inherited(global: &GlobalScope, url: ServoUrl, signal: &AbortSignal) -> Request {
         Request {
             reflector_: Reflector::new(),
             request: DomRefCell::new(net_request_from_global(global, url)),
             body_stream: MutNullableDom::new(None),
             headers: Default::default(),
+            signal: MutDom::new(signal),
         }
     }
 
@@ -65,8 +70,9 @@ impl Request {
         url: ServoUrl,
         can_gc: CanGc,
     ) -> DomRoot<Request> {
+        let signal = AbortSignal::new_with_proto(global, None, can_gc);
         reflect_dom_object_with_proto(
-            Box::new(Request::new_inherited(global, url)),
+            Box::new(Request::new_inherited(global, url, &signal)),
             global,
             proto,
             can_gc,
@@ -84,6 +90,11 @@ impl Request {
         r
     }
 
+    /// <https://fetch.spec.whatwg.org/#request-signal>
+    pub(crate) fn signal(&self) -> DomRoot<AbortSignal> {
+        self.signal.get()
+    }
+
     fn clone_from(r: &Request, can_gc: CanGc) -> Fallible<DomRoot<Request>> {
         let req = r.request.borrow();
         let url = req.url();
@@ -175,7 +186,8 @@ impl RequestMethods<crate::DomTypeHolder> for Request {
         // Step 3
         let base_url = global.api_base_url();
 
-        // Step 4 TODO: "Let signal be null."
+        // Step 4. Let signal be null.
+        let mut signal = None;
 
         match input {
             // Step 5
@@ -205,7 +217,8 @@ impl RequestMethods<crate::DomTypeHolder> for Request {
                 }
                 // Step 6.1
                 temporary_request = input_request.request.borrow().clone();
-                // Step 6.2 TODO: "Set signal to input's signal."
+                // Step 6.2. Set signal to input’s signal.
+                signal = Some(input_request.signal());
             },
         }
 
@@ -245,6 +258,8 @@ impl RequestMethods<crate::DomTypeHolder> for Request {
         request.cache_mode = temporary_request.cache_mode;
         request.redirect_mode = temporary_request.redirect_mode;
         request.integrity_metadata = temporary_request.integrity_metadata;
+        request.priority = temporary_request.priority;
+        request.internal_priority = temporary_request.internal_priority;
 
         // Step 13
         if init.body.is_some() ||
@@ -254,6 +269,7 @@ impl RequestMethods<crate::DomTypeHolder> for Request {
             init.headers.is_some() ||
             init.method.is_some() ||
             init.mode.is_some() ||
+            init.priority.is_some() ||
             init.redirect.is_some() ||
             init.referrer.is_some() ||
             init.referrerPolicy.is_some() ||
@@ -374,14 +390,44 @@ impl RequestMethods<crate::DomTypeHolder> for Request {
             request.method = method;
         }
 
-        // Step 26 TODO: "If init["signal"] exists..."
-        // Step 27 TODO: "If init["priority"] exists..."
+        // Step 26. If init["signal"] exists, then set signal to it.
+        if let Some(init_signal) = init.signal.as_ref() {
+            signal = init_signal.clone();
+        }
+
+        // Step 27. If init["priority"] exists, then:
+        if let Some(init_priority) = init.priority {
+            let priority = init_priority.convert();
+            match request.internal_priority {
+                // Step 27.1. If request's internal priority is not null, then update request's
+                // internal priority in an implementation-defined manner.
+                Some(_) => {
+                    request.internal_priority = Some(InternalPriority::for_request(
+                        priority,
+                        request.initiator,
+                        request.destination,
+                    ))
+                },
+                // Step 27.2. Otherwise, set request's priority to init["priority"].
+                None => request.priority = priority,
+            }
+        }
 
         // Step 28
         let r = Request::from_net_request(global, proto, request, can_gc);
 
-        // Step 29 TODO: "Set this's signal to new AbortSignal object..."
-        // Step 30 TODO: "If signal is not null..."
+        // Step 29. Set this’s signal to a new AbortSignal object with this’s relevant realm.
+        // Note: done as part of creating the request object.
+
+        // Step 30. If signal is not null, then make this’s signal follow signal:
+        if let Some(signal) = signal {
+            // Step 30.1. Let dependentSignal be the result of creating a dependent abort signal
+            // from « signal », using AbortSignal and this’s relevant realm.
+            let dependent_signal =
+                AbortSignal::create_dependent_abort_signal(global, &[signal], can_gc);
+            // Step 30.2. Set this’s signal to dependentSignal.
+            r.signal.set(&dependent_signal);
+        }
 
         // Step 31
         // "or_init" looks unclear here, but it always enters the block since r
@@ -514,9 +560,36 @@ impl RequestMethods<crate::DomTypeHolder> for Request {
 
         // Step 38 is done earlier
 
-        // Step 39 "TODO if body is non-null and body's source is null..."
-        // This looks like where we need to set the use-preflight flag
-        // if the request has a body and nothing else has set the flag.
+        // Step 39. If inputOrInitBody is non-null and inputOrInitBody’s source is null, then:
+        if input_body
+            .as_ref()
+            .is_some_and(|body| body.source_is_null())
+        {
+            // Step 39.1. If initBody is non-null and init["duplex"] does not exist,
+            // then throw a TypeError.
+            if matches!(init.body, Some(Some(_))) && init.duplex.is_none() {
+                return Err(Error::Type(
+                    "RequestInit's duplex member is required when sending a ReadableStream body"
+                        .to_string(),
+                ));
+            }
+
+            // Step 39.2. If this’s request’s mode is neither "same-origin" nor "cors",
+            // then throw a TypeError.
+            let mut request = r.request.borrow_mut();
+            if !matches!(
+                request.mode,
+                NetTraitsRequestMode::SameOrigin | NetTraitsRequestMode::CorsMode
+            ) {
+                return Err(Error::Type(
+                    "Streaming request bodies require a same-origin or cors request mode"
+                        .to_string(),
+                ));
+            }
+
+            // Step 39.3. Set this’s request’s use-CORS-preflight flag.
+            request.use_cors_preflight = true;
+        }
 
         // Step 40 is done earlier
 
@@ -597,6 +670,16 @@ impl RequestMethods<crate::DomTypeHolder> for Request {
         DOMString::from_string(r.integrity_metadata.clone())
     }
 
+    /// <https://fetch.spec.whatwg.org/#dom-request-signal>
+    fn Signal(&self) -> DomRoot<AbortSignal> {
+        self.signal()
+    }
+
+    /// <https://fetch.spec.whatwg.org/#dom-request-duplex>
+    fn Duplex(&self) -> RequestDuplex {
+        RequestDuplex::Half
+    }
+
     /// <https://fetch.spec.whatwg.org/#dom-body-body>
     fn GetBody(&self) -> Option<DomRoot<ReadableStream>> {
         self.body()
@@ -618,7 +701,19 @@ impl RequestMethods<crate::DomTypeHolder> for Request {
         }
 
         // Step 2
-        Request::clone_from(self, can_gc)
+        let cloned_request = Request::clone_from(self, can_gc)?;
+
+        // Step 3. Let clonedSignal be the result of creating a dependent abort signal
+        // from « this’s signal », using AbortSignal and this’s relevant realm.
+        let cloned_signal =
+            AbortSignal::create_dependent_abort_signal(&self.global(), &[self.signal()], can_gc);
+
+        // Step 4. Let clonedRequestObject be the result of creating a Request object,
+        // given clonedRequest, this’s headers’s guard, clonedSignal and this’s relevant realm.
+        cloned_request.signal.set(&cloned_signal);
+
+        // Step 5. Return clonedRequestObject.
+        Ok(cloned_request)
     }
 
     // https://fetch.spec.whatwg.org/#dom-body-text
@@ -779,6 +874,16 @@ impl Convert<RequestDestination> for NetTraitsRequestDestination {
     }
 }
 
+impl Convert<NetTraitsRequestPriority> for RequestPriority {
+    fn convert(self) -> NetTraitsRequestPriority {
+        match self {
+            RequestPriority::High => NetTraitsRequestPriority::High,
+            RequestPriority::Low => NetTraitsRequestPriority::Low,
+            RequestPriority::Auto => NetTraitsRequestPriority::Auto,
+        }
+    }
+}
+
 impl Convert<NetTraitsRequestMode> for RequestMode {
     fn convert(self) -> NetTraitsRequestMode {
         match self {
@@ -2,6 +2,7 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use std::cell::Cell;
 use std::rc::Rc;
 use std::str::FromStr;
 
@@ -10,6 +11,8 @@ use http::header::HeaderMap as HyperHeaders;
 use hyper_serde::Serde;
 use js::rust::{HandleObject, HandleValue};
 use net_traits::http_status::HttpStatus;
+use net_traits::request::RequestId;
+use net_traits::{resume_async_fetch_body, suspend_async_fetch_body};
 use servo_url::ServoUrl;
 use url::Position;
 
@@ -32,6 +35,10 @@ use crate::dom::readablestream::ReadableStream;
 use crate::dom::underlyingsourcecontainer::UnderlyingSourceType;
 use crate::script_runtime::{CanGc, JSContext, StreamConsumer};
 
+/// The error code reported to a WebAssembly compilation whose body could not be
+/// read, it ends up in the `report_stream_error` callback of the runtime.
+const STREAM_CONSUMER_ERROR: usize = 0;
+
 #[dom_struct]
 pub(crate) struct Response {
     reflector_: Reflector,
@@ -48,14 +55,52 @@ pub(crate) struct Response {
     #[ignore_malloc_size_of = "StreamConsumer"]
     stream_consumer: DomRefCell<Option<StreamConsumer>>,
     redirected: DomRefCell<bool>,
+    /// Shared with the underlying source of `body_stream`, to pause the network
+    /// while the stream's queue is full.
+    #[conditional_malloc_size_of]
+    body_flow_control: Rc<ResponseBodyFlowControl>,
+}
+
+/// Propagates backpressure from the body stream of a response obtained through
+/// `fetch()` to the networking task that receives the body.
+#[derive(Default, JSTraceable, MallocSizeOf)]
+pub(crate) struct ResponseBodyFlowControl {
+    /// The fetch feeding the body, while it is still ongoing.
+    #[no_trace]
+    request_id: Cell<Option<RequestId>>,
+    /// Whether the network was asked to stop reading the body.
+    suspended: Cell<bool>,
+}
+
+impl ResponseBodyFlowControl {
+    /// The stream's queue is full: stop reading the body from the network.
+    fn suspend(&self) {
+        let Some(request_id) = self.request_id.get() else {
+            return;
+        };
+        if !self.suspended.replace(true) {
+            suspend_async_fetch_body(request_id);
+        }
+    }
+
+    /// The stream was pulled: continue reading the body from the network.
+    pub(crate) fn resume(&self) {
+        let Some(request_id) = self.request_id.get() else {
+            return;
+        };
+        if self.suspended.replace(false) {
+            resume_async_fetch_body(request_id);
+        }
+    }
 }
 
 #[allow(non_snake_case)]
 impl Response {
     pub(crate) fn new_inherited(global: &GlobalScope, can_gc: CanGc) -> Response {
+        let body_flow_control = Rc::new(ResponseBodyFlowControl::default());
         let stream = ReadableStream::new_with_external_underlying_source(
             global,
-            UnderlyingSourceType::FetchResponse,
+            UnderlyingSourceType::FetchResponse(body_flow_control.clone()),
             can_gc,
         )
         .expect("Failed to create ReadableStream with external underlying source");
@@ -69,6 +114,7 @@ impl Response {
             body_stream: MutNullableDom::new(Some(&*stream)),
             stream_consumer: DomRefCell::new(None),
             redirected: DomRefCell::new(false),
+            body_flow_control,
         }
     }
 
@@ -91,7 +137,10 @@ impl Response {
     }
 
     pub(crate) fn error_stream(&self, error: Error, can_gc: CanGc) {
-        if let Some(body) = self.body_stream.get() {
+        if let Some(stream_consumer) = self.stream_consumer.borrow_mut().take() {
+            stream_consumer.stream_error(STREAM_CONSUMER_ERROR);
+        }
+        if let Some(body) = self.body_stream.get().filter(|body| body.is_readable()) {
             body.error_native(error, can_gc);
         }
     }
@@ -504,21 +553,71 @@ impl Response {
         }
     }
 
-    pub(crate) fn set_stream_consumer(&self, sc: Option<StreamConsumer>) {
-        *self.stream_consumer.borrow_mut() = sc;
+    /// Start feeding the body to a WebAssembly compilation, as part of
+    /// <https://webassembly.github.io/spec/web-api/#compile-a-potential-webassembly-response>.
+    /// Whatever part of the body already arrived is handed over at once, the rest
+    /// follows chunk by chunk as it is received from the network.
+    pub(crate) fn set_stream_consumer(&self, stream_consumer: StreamConsumer, can_gc: CanGc) {
+        stream_consumer
+            .note_response_urls(self.url.borrow().as_ref().map(ServoUrl::to_string), None);
+
+        let Some(body) = self.body_stream.get() else {
+            stream_consumer.stream_end();
+            return;
+        };
+        if body.has_byte_controller() {
+            stream_consumer.stream_error(STREAM_CONSUMER_ERROR);
+            return;
+        }
+        body.set_is_disturbed(true);
+        if body.is_errored() {
+            stream_consumer.stream_error(STREAM_CONSUMER_ERROR);
+            return;
+        }
+
+        let (chunks, body_complete) = body.get_default_controller().take_native_chunks(can_gc);
+        for chunk in chunks {
+            if !stream_consumer.consume_chunk(&chunk) {
+                return;
+            }
+        }
+        if body_complete || body.is_closed() {
+            stream_consumer.stream_end();
+            return;
+        }
+
+        // The queue was drained, so the network no longer needs to be held off.
+        self.body_flow_control.resume();
+        *self.stream_consumer.borrow_mut() = Some(stream_consumer);
+    }
+
+    /// Associate the body of this response with the ongoing fetch identified by
+    /// `request_id`, so that backpressure from the body stream reaches the network.
+    pub(crate) fn set_fetch_request_id(&self, request_id: RequestId) {
+        self.body_flow_control.request_id.set(Some(request_id));
     }
 
     pub(crate) fn stream_chunk(&self, chunk: Vec<u8>, can_gc: CanGc) {
         // Note, are these two actually mutually exclusive?
         if let Some(stream_consumer) = self.stream_consumer.borrow().as_ref() {
-            stream_consumer.consume_chunk(chunk.as_slice());
+            if !stream_consumer.consume_chunk(chunk.as_slice()) {
+                // Compilation failed, there is no point in receiving the rest of the body.
+                self.body_flow_control.suspend();
+            }
         } else if let Some(body) = self.body_stream.get() {
             body.enqueue_native(chunk, can_gc);
+            // Once the chunk is queued rather than handed to a pending read request,
+            // hold off the network until the stream is pulled again.
+            let desired_size = body.get_default_controller().get_desired_size();
+            if desired_size.is_some_and(|size| size <= 0.) {
+                self.body_flow_control.suspend();
+            }
         }
     }
 
     #[cfg_attr(crown, allow(crown::unrooted_must_root))]
     pub(crate) fn finish(&self, can_gc: CanGc) {
+        self.body_flow_control.request_id.set(None);
         if let Some(body) = self.body_stream.get() {
             body.controller_close_native(can_gc);
         }
@@ -0,0 +1,1432 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use html5ever::{Namespace, local_name, ns};
+use js::rust::HandleObject;
+use servo_url::ServoUrl;
+
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::HTMLTemplateElementBinding::HTMLTemplateElementMethods;
+use crate::dom::bindings::codegen::Bindings::NodeBinding::NodeMethods;
+use crate::dom::bindings::codegen::Bindings::SanitizerBinding::{
+    SanitizerAttributeNamespace, SanitizerConfig, SanitizerElementNamespace,
+    SanitizerElementNamespaceWithAttributes, SanitizerMethods, SanitizerPresets, SetHTMLOptions,
+};
+use crate::dom::bindings::codegen::UnionTypes::{
+    SanitizerConfigOrSanitizerPresets, SanitizerOrSanitizerConfigOrSanitizerPresets,
+    StringOrSanitizerAttributeNamespace, StringOrSanitizerElementNamespace,
+    StringOrSanitizerElementNamespaceWithAttributes,
+};
+use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::{Reflector, reflect_dom_object_with_proto};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::str::DOMString;
+use crate::dom::comment::Comment;
+use crate::dom::element::Element;
+use crate::dom::htmltemplateelement::HTMLTemplateElement;
+use crate::dom::node::Node;
+use crate::dom::window::Window;
+use crate::script_runtime::CanGc;
+
+/// <https://wicg.github.io/sanitizer-api/#built-in-safe-default-configuration>
+const DEFAULT_HTML_ELEMENTS: &[(&str, &[&str])] = &[
+    ("a", &["href", "hreflang", "type"]),
+    ("abbr", &[]),
+    ("address", &[]),
+    ("article", &[]),
+    ("aside", &[]),
+    ("b", &[]),
+    ("bdi", &[]),
+    ("bdo", &[]),
+    ("blockquote", &["cite"]),
+    ("body", &[]),
+    ("br", &[]),
+    ("caption", &[]),
+    ("cite", &[]),
+    ("code", &[]),
+    ("col", &["span"]),
+    ("colgroup", &["span"]),
+    ("data", &["value"]),
+    ("dd", &[]),
+    ("del", &["cite", "datetime"]),
+    ("dfn", &[]),
+    ("div", &[]),
+    ("dl", &[]),
+    ("dt", &[]),
+    ("em", &[]),
+    ("figcaption", &[]),
+    ("figure", &[]),
+    ("footer", &[]),
+    ("h1", &[]),
+    ("h2", &[]),
+    ("h3", &[]),
+    ("h4", &[]),
+    ("h5", &[]),
+    ("h6", &[]),
+    ("head", &[]),
+    ("header", &[]),
+    ("hgroup", &[]),
+    ("hr", &[]),
+    ("html", &[]),
+    ("i", &[]),
+    ("ins", &["cite", "datetime"]),
+    ("kbd", &[]),
+    ("li", &["value"]),
+    ("main", &[]),
+    ("mark", &[]),
+    ("menu", &[]),
+    ("nav", &[]),
+    ("ol", &["reversed", "start", "type"]),
+    ("p", &[]),
+    ("pre", &[]),
+    ("q", &[]),
+    ("rp", &[]),
+    ("rt", &[]),
+    ("ruby", &[]),
+    ("s", &[]),
+    ("samp", &[]),
+    ("search", &[]),
+    ("section", &[]),
+    ("small", &[]),
+    ("span", &[]),
+    ("strong", &[]),
+    ("sub", &[]),
+    ("sup", &[]),
+    ("table", &[]),
+    ("tbody", &[]),
+    ("td", &["colspan", "headers", "rowspan"]),
+    ("tfoot", &[]),
+    ("th", &["abbr", "colspan", "headers", "rowspan", "scope"]),
+    ("thead", &[]),
+    ("time", &["datetime"]),
+    ("title", &[]),
+    ("tr", &[]),
+    ("u", &[]),
+    ("ul", &[]),
+    ("var", &[]),
+    ("wbr", &[]),
+];
+
+/// <https://wicg.github.io/sanitizer-api/#built-in-safe-default-configuration>
+const DEFAULT_MATHML_ELEMENTS: &[(&str, &[&str])] = &[
+    ("annotation", &["encoding"]),
+    ("annotation-xml", &["encoding"]),
+    ("maction", &["actiontype", "selection"]),
+    ("math", &["display"]),
+    ("merror", &[]),
+    ("mfrac", &["linethickness"]),
+    ("mi", &["mathvariant"]),
+    ("mmultiscripts", &[]),
+    ("mn", &[]),
+    (
+        "mo",
+        &[
+            "fence",
+            "form",
+            "largeop",
+            "lspace",
+            "maxsize",
+            "minsize",
+            "movablelimits",
+            "rspace",
+            "separator",
+            "stretchy",
+            "symmetric",
+        ],
+    ),
+    ("mover", &["accent"]),
+    (
+        "mpadded",
+        &["depth", "height", "lspace", "voffset", "width"],
+    ),
+    ("mphantom", &[]),
+    ("mprescripts", &[]),
+    ("mroot", &[]),
+    ("mrow", &[]),
+    ("ms", &[]),
+    ("mspace", &["depth", "height", "width"]),
+    ("msqrt", &[]),
+    ("mstyle", &[]),
+    ("msub", &[]),
+    ("msubsup", &[]),
+    ("msup", &[]),
+    ("mtable", &[]),
+    ("mtd", &["columnspan", "rowspan"]),
+    ("mtext", &[]),
+    ("mtr", &[]),
+    ("munder", &["accentunder"]),
+    ("munderover", &["accent", "accentunder"]),
+    ("semantics", &[]),
+];
+
+/// <https://wicg.github.io/sanitizer-api/#built-in-safe-default-configuration>
+const DEFAULT_SVG_ELEMENTS: &[(&str, &[&str])] = &[
+    ("a", &["href", "hreflang", "type"]),
+    ("circle", &["cx", "cy", "pathLength", "r"]),
+    ("defs", &[]),
+    ("desc", &[]),
+    ("ellipse", &["cx", "cy", "pathLength", "rx", "ry"]),
+    ("foreignObject", &["height", "width", "x", "y"]),
+    ("g", &[]),
+    ("line", &["pathLength", "x1", "x2", "y1", "y2"]),
+    (
+        "marker",
+        &[
+            "markerHeight",
+            "markerUnits",
+            "markerWidth",
+            "orient",
+            "preserveAspectRatio",
+            "refX",
+            "refY",
+            "viewBox",
+        ],
+    ),
+    ("metadata", &[]),
+    ("path", &["d", "pathLength"]),
+    ("polygon", &["pathLength", "points"]),
+    ("polyline", &["pathLength", "points"]),
+    (
+        "rect",
+        &["height", "pathLength", "rx", "ry", "width", "x", "y"],
+    ),
+    (
+        "svg",
+        &[
+            "height",
+            "preserveAspectRatio",
+            "viewBox",
+            "width",
+            "x",
+            "y",
+        ],
+    ),
+    (
+        "text",
+        &["dx", "dy", "lengthAdjust", "rotate", "textLength", "x", "y"],
+    ),
+    (
+        "textPath",
+        &[
+            "lengthAdjust",
+            "method",
+            "path",
+            "side",
+            "spacing",
+            "startOffset",
+            "textLength",
+        ],
+    ),
+    ("title", &[]),
+    (
+        "tspan",
+        &["dx", "dy", "lengthAdjust", "rotate", "textLength", "x", "y"],
+    ),
+];
+
+/// <https://wicg.github.io/sanitizer-api/#built-in-safe-default-configuration>
+const DEFAULT_ATTRIBUTES: &[&str] = &[
+    "alignment-baseline",
+    "baseline-shift",
+    "clip-path",
+    "clip-rule",
+    "color",
+    "color-interpolation",
+    "cursor",
+    "dir",
+    "direction",
+    "display",
+    "displaystyle",
+    "dominant-baseline",
+    "fill",
+    "fill-opacity",
+    "fill-rule",
+    "font-family",
+    "font-size",
+    "font-size-adjust",
+    "font-stretch",
+    "font-style",
+    "font-variant",
+    "font-weight",
+    "lang",
+    "letter-spacing",
+    "marker-end",
+    "marker-mid",
+    "marker-start",
+    "mathbackground",
+    "mathcolor",
+    "mathsize",
+    "opacity",
+    "paint-order",
+    "pointer-events",
+    "scriptlevel",
+    "shape-rendering",
+    "stop-color",
+    "stop-opacity",
+    "stroke",
+    "stroke-dasharray",
+    "stroke-dashoffset",
+    "stroke-linecap",
+    "stroke-linejoin",
+    "stroke-miterlimit",
+    "stroke-opacity",
+    "stroke-width",
+    "text-anchor",
+    "text-decoration",
+    "text-overflow",
+    "text-rendering",
+    "title",
+    "transform",
+    "transform-origin",
+    "unicode-bidi",
+    "vector-effect",
+    "visibility",
+    "white-space",
+    "word-spacing",
+    "writing-mode",
+];
+
+/// The `removeElements` of the
+/// <https://wicg.github.io/sanitizer-api/#built-in-safe-baseline-configuration>
+const BASELINE_REMOVE_ELEMENTS: &[(&str, Namespace)] = &[
+    ("embed", ns!(html)),
+    ("frame", ns!(html)),
+    ("iframe", ns!(html)),
+    ("object", ns!(html)),
+    ("script", ns!(html)),
+    ("script", ns!(svg)),
+    ("use", ns!(svg)),
+];
+
+/// <https://html.spec.whatwg.org/multipage/#event-handler-content-attributes>
+const EVENT_HANDLER_CONTENT_ATTRIBUTES: &[&str] = &[
+    "onabort",
+    "onafterprint",
+    "onanimationcancel",
+    "onanimationend",
+    "onanimationiteration",
+    "onanimationstart",
+    "onauxclick",
+    "onbeforeinput",
+    "onbeforematch",
+    "onbeforeprint",
+    "onbeforetoggle",
+    "onbeforeunload",
+    "onblur",
+    "oncancel",
+    "oncanplay",
+    "oncanplaythrough",
+    "onchange",
+    "onclick",
+    "onclose",
+    "oncommand",
+    "oncontextlost",
+    "oncontextmenu",
+    "oncontextrestored",
+    "oncopy",
+    "oncuechange",
+    "oncut",
+    "ondblclick",
+    "ondrag",
+    "ondragend",
+    "ondragenter",
+    "ondragleave",
+    "ondragover",
+    "ondragstart",
+    "ondrop",
+    "ondurationchange",
+    "onemptied",
+    "onended",
+    "onerror",
+    "onfocus",
+    "onformdata",
+    "ongotpointercapture",
+    "onhashchange",
+    "oninput",
+    "oninvalid",
+    "onkeydown",
+    "onkeypress",
+    "onkeyup",
+    "onlanguagechange",
+    "onload",
+    "onloadeddata",
+    "onloadedmetadata",
+    "onloadstart",
+    "onlostpointercapture",
+    "onmessage",
+    "onmessageerror",
+    "onmousedown",
+    "onmouseenter",
+    "onmouseleave",
+    "onmousemove",
+    "onmouseout",
+    "onmouseover",
+    "onmouseup",
+    "onoffline",
+    "ononline",
+    "onpagehide",
+    "onpagereveal",
+    "onpageshow",
+    "onpageswap",
+    "onpaste",
+    "onpause",
+    "onplay",
+    "onplaying",
+    "onpointercancel",
+    "onpointerdown",
+    "onpointerenter",
+    "onpointerleave",
+    "onpointermove",
+    "onpointerout",
+    "onpointerover",
+    "onpointerup",
+    "onpopstate",
+    "onprogress",
+    "onratechange",
+    "onrejectionhandled",
+    "onreset",
+    "onresize",
+    "onscroll",
+    "onscrollend",
+    "onsecuritypolicyviolation",
+    "onseeked",
+    "onseeking",
+    "onselect",
+    "onselectionchange",
+    "onselectstart",
+    "onslotchange",
+    "onstalled",
+    "onstorage",
+    "onsubmit",
+    "onsuspend",
+    "ontimeupdate",
+    "ontoggle",
+    "ontouchcancel",
+    "ontouchend",
+    "ontouchmove",
+    "ontouchstart",
+    "ontransitioncancel",
+    "ontransitionend",
+    "ontransitionrun",
+    "ontransitionstart",
+    "onunhandledrejection",
+    "onunload",
+    "onvolumechange",
+    "onwaiting",
+    "onwheel",
+];
+
+/// A canonicalized `SanitizerElementNamespace` or `SanitizerAttributeNamespace`.
+#[derive(Clone, Debug, Eq, JSTraceable, MallocSizeOf, Ord, PartialEq, PartialOrd)]
+pub(crate) struct SanitizerName {
+    name: String,
+    namespace: Option<String>,
+}
+
+impl SanitizerName {
+    fn new(name: &str, namespace: &Namespace) -> SanitizerName {
+        SanitizerName {
+            name: name.to_owned(),
+            namespace: (*namespace != ns!()).then(|| namespace.to_string()),
+        }
+    }
+
+    /// Steps 3-5 of <https://wicg.github.io/sanitizer-api/#canonicalize-a-sanitizer-name>
+    fn canonicalize(name: DOMString, namespace: Option<DOMString>) -> SanitizerName {
+        // Step 4. If name["namespace"] is the empty string, then set it to null.
+        SanitizerName {
+            name: name.into(),
+            namespace: namespace.map(String::from).filter(|ns| !ns.is_empty()),
+        }
+    }
+
+    fn matches(&self, name: &str, namespace: &Namespace) -> bool {
+        self.name == name && self.namespace.as_deref().unwrap_or_default() == &**namespace
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#custom-data-attribute>
+    fn is_custom_data_attribute(&self) -> bool {
+        self.namespace.is_none() && self.name.starts_with("data-")
+    }
+
+    fn to_element_dictionary(&self) -> SanitizerElementNamespace {
+        SanitizerElementNamespace {
+            name: DOMString::from(self.name.clone()),
+            namespace: self.namespace.clone().map(DOMString::from),
+        }
+    }
+
+    fn to_attribute_dictionary(&self) -> StringOrSanitizerAttributeNamespace {
+        StringOrSanitizerAttributeNamespace::SanitizerAttributeNamespace(
+            SanitizerAttributeNamespace {
+                name: DOMString::from(self.name.clone()),
+                namespace: self.namespace.clone().map(DOMString::from),
+            },
+        )
+    }
+}
+
+/// <https://wicg.github.io/sanitizer-api/#canonicalize-a-sanitizer-element>
+fn canonicalize_element(element: StringOrSanitizerElementNamespace) -> SanitizerName {
+    match element {
+        StringOrSanitizerElementNamespace::String(name) => SanitizerName::new(&name, &ns!(html)),
+        StringOrSanitizerElementNamespace::SanitizerElementNamespace(element) => {
+            SanitizerName::canonicalize(element.name, element.namespace)
+        },
+    }
+}
+
+/// <https://wicg.github.io/sanitizer-api/#canonicalize-a-sanitizer-attribute>
+fn canonicalize_attribute(attribute: StringOrSanitizerAttributeNamespace) -> SanitizerName {
+    match attribute {
+        StringOrSanitizerAttributeNamespace::String(name) => SanitizerName::new(&name, &ns!()),
+        StringOrSanitizerAttributeNamespace::SanitizerAttributeNamespace(attribute) => {
+            SanitizerName::canonicalize(attribute.name, attribute.namespace)
+        },
+    }
+}
+
+fn canonicalize_attributes(
+    attributes: Option<Vec<StringOrSanitizerAttributeNamespace>>,
+) -> Option<Vec<SanitizerName>> {
+    attributes.map(|attributes| attributes.into_iter().map(canonicalize_attribute).collect())
+}
+
+fn canonicalize_elements(
+    elements: Option<Vec<StringOrSanitizerElementNamespace>>,
+) -> Option<Vec<SanitizerName>> {
+    elements.map(|elements| elements.into_iter().map(canonicalize_element).collect())
+}
+
+fn contains(list: &Option<Vec<SanitizerName>>, name: &SanitizerName) -> bool {
+    list.as_ref().is_some_and(|list| list.contains(name))
+}
+
+/// Removes `name` from `list`, returning whether it was present.
+fn remove(list: &mut Option<Vec<SanitizerName>>, name: &SanitizerName) -> bool {
+    let Some(list) = list else {
+        return false;
+    };
+    let length = list.len();
+    list.retain(|item| item != name);
+    list.len() != length
+}
+
+fn has_duplicates(list: &Option<Vec<SanitizerName>>) -> bool {
+    list.as_ref().is_some_and(|list| {
+        list.iter()
+            .enumerate()
+            .any(|(i, item)| list[..i].contains(item))
+    })
+}
+
+fn remove_duplicates(list: &mut Vec<SanitizerName>) {
+    let mut seen = Vec::with_capacity(list.len());
+    list.retain(|item| {
+        if seen.contains(item) {
+            return false;
+        }
+        seen.push(item.clone());
+        true
+    });
+}
+
+fn intersects(first: &Option<Vec<SanitizerName>>, second: &Option<Vec<SanitizerName>>) -> bool {
+    first.iter().flatten().any(|item| contains(second, item))
+}
+
+/// A canonicalized `SanitizerElementNamespaceWithAttributes`.
+#[derive(Clone, Debug, Eq, JSTraceable, MallocSizeOf, PartialEq)]
+struct SanitizerElementWithAttributes {
+    name: SanitizerName,
+    attributes: Option<Vec<SanitizerName>>,
+    remove_attributes: Option<Vec<SanitizerName>>,
+}
+
+impl SanitizerElementWithAttributes {
+    /// <https://wicg.github.io/sanitizer-api/#canonicalize-a-sanitizer-element-with-attributes>
+    fn canonicalize(element: StringOrSanitizerElementNamespaceWithAttributes) -> Self {
+        match element {
+            StringOrSanitizerElementNamespaceWithAttributes::String(name) => Self {
+                name: SanitizerName::new(&name, &ns!(html)),
+                attributes: None,
+                remove_attributes: None,
+            },
+            StringOrSanitizerElementNamespaceWithAttributes::SanitizerElementNamespaceWithAttributes(
+                element,
+            ) => Self {
+                name: SanitizerName::canonicalize(element.parent.name, element.parent.namespace),
+                attributes: canonicalize_attributes(element.attributes),
+                remove_attributes: canonicalize_attributes(element.removeAttributes),
+            },
+        }
+    }
+
+    fn from_defaults(name: &str, namespace: &Namespace, attributes: &[&str]) -> Self {
+        Self {
+            name: SanitizerName::new(name, namespace),
+            attributes: Some(
+                attributes
+                    .iter()
+                    .map(|attribute| SanitizerName::new(attribute, &ns!()))
+                    .collect(),
+            ),
+            remove_attributes: None,
+        }
+    }
+
+    fn to_dictionary(&self) -> StringOrSanitizerElementNamespaceWithAttributes {
+        let sorted = |list: &Option<Vec<SanitizerName>>| {
+            list.as_ref().map(|list| {
+                let mut list = list.clone();
+                list.sort();
+                list.iter()
+                    .map(SanitizerName::to_attribute_dictionary)
+                    .collect()
+            })
+        };
+        StringOrSanitizerElementNamespaceWithAttributes::SanitizerElementNamespaceWithAttributes(
+            SanitizerElementNamespaceWithAttributes {
+                parent: self.name.to_element_dictionary(),
+                attributes: sorted(&self.attributes),
+                removeAttributes: sorted(&self.remove_attributes),
+            },
+        )
+    }
+}
+
+/// A canonicalized `SanitizerConfig`.
+///
+/// <https://wicg.github.io/sanitizer-api/#sanitizer-configuration>
+#[derive(Clone, Debug, Default, JSTraceable, MallocSizeOf)]
+pub(crate) struct SanitizerConfiguration {
+    elements: Option<Vec<SanitizerElementWithAttributes>>,
+    remove_elements: Option<Vec<SanitizerName>>,
+    replace_with_children_elements: Option<Vec<SanitizerName>>,
+    attributes: Option<Vec<SanitizerName>>,
+    remove_attributes: Option<Vec<SanitizerName>>,
+    comments: bool,
+    data_attributes: Option<bool>,
+}
+
+impl SanitizerConfiguration {
+    /// <https://wicg.github.io/sanitizer-api/#built-in-safe-default-configuration>
+    fn built_in_safe_default() -> SanitizerConfiguration {
+        let elements = [
+            (ns!(html), DEFAULT_HTML_ELEMENTS),
+            (ns!(mathml), DEFAULT_MATHML_ELEMENTS),
+            (ns!(svg), DEFAULT_SVG_ELEMENTS),
+        ]
+        .iter()
+        .flat_map(|(namespace, elements)| {
+            elements.iter().map(move |(name, attributes)| {
+                SanitizerElementWithAttributes::from_defaults(name, namespace, attributes)
+            })
+        })
+        .collect();
+        SanitizerConfiguration {
+            elements: Some(elements),
+            remove_elements: None,
+            replace_with_children_elements: None,
+            attributes: Some(
+                DEFAULT_ATTRIBUTES
+                    .iter()
+                    .map(|attribute| SanitizerName::new(attribute, &ns!()))
+                    .collect(),
+            ),
+            remove_attributes: None,
+            comments: false,
+            data_attributes: Some(false),
+        }
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#sanitizer-canonicalize-the-configuration>
+    fn canonicalize(
+        configuration: &SanitizerConfig,
+        allow_comments_and_data_attributes: bool,
+    ) -> SanitizerConfiguration {
+        let configuration = configuration.clone();
+        let mut result = SanitizerConfiguration {
+            elements: configuration.elements.map(|elements| {
+                elements
+                    .into_iter()
+                    .map(SanitizerElementWithAttributes::canonicalize)
+                    .collect()
+            }),
+            remove_elements: canonicalize_elements(configuration.removeElements),
+            replace_with_children_elements: canonicalize_elements(
+                configuration.replaceWithChildrenElements,
+            ),
+            attributes: canonicalize_attributes(configuration.attributes),
+            remove_attributes: canonicalize_attributes(configuration.removeAttributes),
+            // Step 4. If configuration["comments"] does not exist, then set
+            // configuration["comments"] to allowCommentsAndDataAttributes.
+            comments: configuration
+                .comments
+                .unwrap_or(allow_comments_and_data_attributes),
+            data_attributes: configuration.dataAttributes,
+        };
+        // Step 1. If neither configuration["elements"] nor configuration["removeElements"] exist,
+        // then set configuration["removeElements"] to « ».
+        if result.elements.is_none() && result.remove_elements.is_none() {
+            result.remove_elements = Some(vec![]);
+        }
+        // Step 2. If neither configuration["attributes"] nor configuration["removeAttributes"]
+        // exist, then set configuration["removeAttributes"] to « ».
+        if result.attributes.is_none() && result.remove_attributes.is_none() {
+            result.remove_attributes = Some(vec![]);
+        }
+        // Step 5. If configuration["attributes"] exists and configuration["dataAttributes"]
+        // does not exist, then set configuration["dataAttributes"] to
+        // allowCommentsAndDataAttributes.
+        if result.attributes.is_some() && result.data_attributes.is_none() {
+            result.data_attributes = Some(allow_comments_and_data_attributes);
+        }
+        result
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#sanitizerconfig-valid>
+    fn is_valid(&self) -> bool {
+        // The configuration has either an elements or a removeElements key, but not both.
+        if self.elements.is_some() == self.remove_elements.is_some() {
+            return false;
+        }
+        // The configuration has either an attributes or a removeAttributes key, but not both.
+        if self.attributes.is_some() == self.remove_attributes.is_some() {
+            return false;
+        }
+        // None of the lists contain duplicates.
+        let element_names = self.elements.as_ref().map(|elements| {
+            elements
+                .iter()
+                .map(|element| element.name.clone())
+                .collect()
+        });
+        if [
+            &element_names,
+            &self.remove_elements,
+            &self.replace_with_children_elements,
+            &self.attributes,
+            &self.remove_attributes,
+        ]
+        .into_iter()
+        .any(has_duplicates)
+        {
+            return false;
+        }
+        // The intersection of replaceWithChildrenElements with elements or removeElements
+        // is empty.
+        if intersects(&self.replace_with_children_elements, &element_names) ||
+            intersects(&self.replace_with_children_elements, &self.remove_elements)
+        {
+            return false;
+        }
+        // replaceWithChildrenElements does not contain the html element.
+        if self
+            .replace_with_children_elements
+            .iter()
+            .flatten()
+            .any(|name| name.matches("html", &ns!(html)))
+        {
+            return false;
+        }
+        for element in self.elements.iter().flatten() {
+            if has_duplicates(&element.attributes) || has_duplicates(&element.remove_attributes) {
+                return false;
+            }
+            if self.attributes.is_some() {
+                // Per-element attributes do not overlap with the global allow-list, and
+                // per-element removeAttributes are a subset of it.
+                if intersects(&element.attributes, &self.attributes) ||
+                    element
+                        .remove_attributes
+                        .iter()
+                        .flatten()
+                        .any(|attribute| !contains(&self.attributes, attribute))
+                {
+                    return false;
+                }
+                if self.data_attributes == Some(true) &&
+                    element
+                        .attributes
+                        .iter()
+                        .flatten()
+                        .any(SanitizerName::is_custom_data_attribute)
+                {
+                    return false;
+                }
+            } else if element.attributes.is_some() && element.remove_attributes.is_some() ||
+                intersects(&element.attributes, &self.remove_attributes) ||
+                intersects(&element.remove_attributes, &self.remove_attributes)
+            {
+                return false;
+            }
+        }
+        if self.attributes.is_some() {
+            // If dataAttributes is true, attributes does not contain custom data attributes.
+            if self.data_attributes == Some(true) &&
+                self.attributes
+                    .iter()
+                    .flatten()
+                    .any(SanitizerName::is_custom_data_attribute)
+            {
+                return false;
+            }
+        } else if self.data_attributes.is_some() {
+            // dataAttributes only applies to a global allow-list.
+            return false;
+        }
+        true
+    }
+
+    fn element_with_attributes(
+        &self,
+        name: &SanitizerName,
+    ) -> Option<&SanitizerElementWithAttributes> {
+        self.elements
+            .as_ref()?
+            .iter()
+            .find(|element| element.name == *name)
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#sanitizer-allow-an-element>
+    fn allow_element(&mut self, mut element: SanitizerElementWithAttributes) -> bool {
+        // Step 4. If configuration["elements"] exists:
+        if self.elements.is_some() {
+            // Step 4.1. Let modified be the result of remove element from
+            // configuration["replaceWithChildrenElements"].
+            let modified = remove(&mut self.replace_with_children_elements, &element.name);
+            // Step 4.3. If configuration["attributes"] exists:
+            if let Some(global_attributes) = &self.attributes {
+                if let Some(attributes) = &mut element.attributes {
+                    remove_duplicates(attributes);
+                    attributes.retain(|attribute| !global_attributes.contains(attribute));
+                    if self.data_attributes == Some(true) {
+                        attributes.retain(|attribute| !attribute.is_custom_data_attribute());
+                    }
+                }
+                if let Some(remove_attributes) = &mut element.remove_attributes {
+                    remove_duplicates(remove_attributes);
+                    remove_attributes.retain(|attribute| global_attributes.contains(attribute));
+                }
+            } else {
+                // Step 4.4. Otherwise:
+                if let Some(attributes) = &mut element.attributes {
+                    remove_duplicates(attributes);
+                    let remove_attributes = element.remove_attributes.take().unwrap_or_default();
+                    attributes.retain(|attribute| {
+                        !remove_attributes.contains(attribute) &&
+                            !contains(&self.remove_attributes, attribute)
+                    });
+                }
+                if let Some(remove_attributes) = &mut element.remove_attributes {
+                    remove_duplicates(remove_attributes);
+                    remove_attributes
+                        .retain(|attribute| !contains(&self.remove_attributes, attribute));
+                }
+            }
+            let elements = self.elements.as_mut().expect("checked above");
+            // Step 4.5. If configuration["elements"] does not contain element, append it.
+            let Some(index) = elements
+                .iter()
+                .position(|current| current.name == element.name)
+            else {
+                elements.push(element);
+                return true;
+            };
+            // Step 4.8. If element is equal to current element, then return modified.
+            if elements[index] == element {
+                return modified;
+            }
+            // Step 4.9. Remove element from configuration["elements"].
+            // Step 4.10. Append element to configuration["elements"].
+            elements.remove(index);
+            elements.push(element);
+            return true;
+        }
+        // Step 5.1. If element["attributes"] exists or element["removeAttributes"] is not
+        // empty, then return false.
+        if element.attributes.is_some() ||
+            element
+                .remove_attributes
+                .as_ref()
+                .is_some_and(|remove_attributes| !remove_attributes.is_empty())
+        {
+            return false;
+        }
+        // Step 5.2. Let modified be the result of remove element from
+        // configuration["replaceWithChildrenElements"].
+        let modified = remove(&mut self.replace_with_children_elements, &element.name);
+        // Step 5.3. If configuration["removeElements"] does not contain element, return modified.
+        // Step 5.5. Remove element from configuration["removeElements"].
+        remove(&mut self.remove_elements, &element.name) || modified
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#sanitizer-remove-an-element>
+    fn remove_element(&mut self, element: &SanitizerName) -> bool {
+        // Step 2. Let modified be the result of remove element from
+        // configuration["replaceWithChildrenElements"].
+        let modified = remove(&mut self.replace_with_children_elements, element);
+        // Step 3. If configuration["elements"] exists:
+        if let Some(elements) = &mut self.elements {
+            let length = elements.len();
+            elements.retain(|item| item.name != *element);
+            return elements.len() != length || modified;
+        }
+        // Step 4. Otherwise:
+        let remove_elements = self.remove_elements.get_or_insert_default();
+        if remove_elements.contains(element) {
+            return modified;
+        }
+        remove_elements.push(element.clone());
+        true
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#sanitizer-replace-an-element-with-its-children>
+    fn replace_element_with_children(&mut self, element: SanitizerName) -> bool {
+        // Step 2. If the built-in non-replaceable elements list contains element, return false.
+        if element.matches("html", &ns!(html)) {
+            return false;
+        }
+        // Step 3. If configuration["replaceWithChildrenElements"] contains element, return false.
+        if contains(&self.replace_with_children_elements, &element) {
+            return false;
+        }
+        // Step 4. Remove element from configuration["removeElements"].
+        remove(&mut self.remove_elements, &element);
+        // Step 5. Remove element from configuration["elements"] list.
+        if let Some(elements) = &mut self.elements {
+            elements.retain(|item| item.name != element);
+        }
+        // Step 6. Add element to configuration["replaceWithChildrenElements"].
+        self.replace_with_children_elements
+            .get_or_insert_default()
+            .push(element);
+        true
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#sanitizer-allow-an-attribute>
+    fn allow_attribute(&mut self, attribute: SanitizerName) -> bool {
+        // Step 2. If configuration["attributes"] exists:
+        if let Some(attributes) = &mut self.attributes {
+            // Step 2.1. If configuration["dataAttributes"] is true and attribute is a custom
+            // data attribute, then return false.
+            if self.data_attributes == Some(true) && attribute.is_custom_data_attribute() {
+                return false;
+            }
+            // Step 2.2. If configuration["attributes"] contains attribute return false.
+            if attributes.contains(&attribute) {
+                return false;
+            }
+            // Step 2.4. Remove attribute from each element's attributes.
+            for element in self.elements.iter_mut().flatten() {
+                remove(&mut element.attributes, &attribute);
+            }
+            // Step 2.5. Append attribute to configuration["attributes"].
+            attributes.push(attribute);
+            return true;
+        }
+        // Step 3. Otherwise, remove attribute from configuration["removeAttributes"].
+        remove(&mut self.remove_attributes, &attribute)
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#sanitizer-remove-an-attribute>
+    fn remove_attribute(&mut self, attribute: &SanitizerName) -> bool {
+        // Step 2. If configuration["attributes"] exists:
+        if self.attributes.is_some() {
+            // Step 2.1. Let modified be the result of remove attribute from
+            // configuration["attributes"].
+            let mut modified = remove(&mut self.attributes, attribute);
+            // Step 2.2. Remove attribute from each element's attributes and removeAttributes.
+            for element in self.elements.iter_mut().flatten() {
+                modified |= remove(&mut element.attributes, attribute);
+                modified |= remove(&mut element.remove_attributes, attribute);
+            }
+            return modified;
+        }
+        // Step 3.1. If configuration["removeAttributes"] contains attribute, return false.
+        if contains(&self.remove_attributes, attribute) {
+            return false;
+        }
+        // Step 3.2. Remove attribute from each element's attributes and removeAttributes.
+        for element in self.elements.iter_mut().flatten() {
+            remove(&mut element.attributes, attribute);
+            remove(&mut element.remove_attributes, attribute);
+        }
+        // Step 3.3. Add attribute to configuration["removeAttributes"].
+        self.remove_attributes
+            .get_or_insert_default()
+            .push(attribute.clone());
+        true
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#sanitizer-remove-unsafe>
+    fn remove_unsafe(&mut self) -> bool {
+        // Step 2. Let result be false.
+        let mut result = false;
+        // Step 3. For each element in built-in safe baseline configuration[removeElements],
+        // call remove an element element from configuration.
+        for (name, namespace) in BASELINE_REMOVE_ELEMENTS {
+            result |= self.remove_element(&SanitizerName::new(name, namespace));
+        }
+        // Step 5. For each attribute listed in event handler content attributes, call remove
+        // an attribute attribute from configuration.
+        for name in EVENT_HANDLER_CONTENT_ATTRIBUTES {
+            result |= self.remove_attribute(&SanitizerName::new(name, &ns!()));
+        }
+        result
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#dom-sanitizer-get>
+    fn to_dictionary(&self) -> SanitizerConfig {
+        let sorted_elements = |list: &Option<Vec<SanitizerName>>| {
+            list.as_ref().map(|list| {
+                let mut list = list.clone();
+                list.sort();
+                list.iter()
+                    .map(|name| {
+                        StringOrSanitizerElementNamespace::SanitizerElementNamespace(
+                            name.to_element_dictionary(),
+                        )
+                    })
+                    .collect()
+            })
+        };
+        let sorted_attributes = |list: &Option<Vec<SanitizerName>>| {
+            list.as_ref().map(|list| {
+                let mut list = list.clone();
+                list.sort();
+                list.iter()
+                    .map(SanitizerName::to_attribute_dictionary)
+                    .collect()
+            })
+        };
+        SanitizerConfig {
+            elements: self.elements.as_ref().map(|elements| {
+                let mut elements = elements.clone();
+                elements.sort_by(|a, b| a.name.cmp(&b.name));
+                elements
+                    .iter()
+                    .map(SanitizerElementWithAttributes::to_dictionary)
+                    .collect()
+            }),
+            removeElements: sorted_elements(&self.remove_elements),
+            replaceWithChildrenElements: sorted_elements(&self.replace_with_children_elements),
+            attributes: sorted_attributes(&self.attributes),
+            removeAttributes: sorted_attributes(&self.remove_attributes),
+            comments: Some(self.comments),
+            dataAttributes: self.data_attributes,
+        }
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#sanitize>
+    fn sanitize(&self, node: &Node, safe: bool, can_gc: CanGc) {
+        // Step 2. If safe is true, then set configuration to the result of calling
+        // remove unsafe on configuration.
+        if safe {
+            let mut configuration = self.clone();
+            configuration.remove_unsafe();
+            return configuration.sanitize_core(node, true, can_gc);
+        }
+        // Step 3. Call sanitize core on node, configuration, and safe.
+        self.sanitize_core(node, false, can_gc)
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#sanitize-core>
+    fn sanitize_core(&self, node: &Node, handle_javascript_navigation_urls: bool, can_gc: CanGc) {
+        // Step 2. For each child in current’s children:
+        for child in node.children().collect::<Vec<_>>() {
+            // Step 2.2. If child implements Comment: if configuration["comments"] is not true,
+            // then remove child.
+            if child.is::<Comment>() {
+                if !self.comments {
+                    child.remove_self(can_gc);
+                }
+                continue;
+            }
+            // Step 2.1. If child implements DocumentType or Text, continue.
+            let Some(element) = child.downcast::<Element>() else {
+                continue;
+            };
+            // Step 2.4.1. Let elementName be a SanitizerElementNamespace with child’s local
+            // name and namespace.
+            let element_name = SanitizerName::new(element.local_name(), element.namespace());
+            // Step 2.4.2. If configuration["replaceWithChildrenElements"] contains elementName:
+            if contains(&self.replace_with_children_elements, &element_name) {
+                // Step 2.4.2.1. Call sanitize core on child with configuration and
+                // handleJavascriptNavigationUrls.
+                self.sanitize_core(&child, handle_javascript_navigation_urls, can_gc);
+                // Step 2.4.2.2. Call replace all with child’s children within child.
+                for grandchild in child.children().collect::<Vec<_>>() {
+                    node.InsertBefore(&grandchild, Some(&child), can_gc)
+                        .expect("Moving sanitized children should never fail");
+                }
+                child.remove_self(can_gc);
+                continue;
+            }
+            // Step 2.4.3. If configuration["removeElements"] contains elementName, or if
+            // configuration["elements"] exists and does not contain elementName, then remove
+            // child and continue.
+            let element_with_attributes = self.element_with_attributes(&element_name);
+            if contains(&self.remove_elements, &element_name) ||
+                (self.elements.is_some() && element_with_attributes.is_none())
+            {
+                child.remove_self(can_gc);
+                continue;
+            }
+            // Step 2.4.5. If elementName equals «[ "name" → "template", "namespace" → HTML
+            // namespace ]», then call sanitize core on child’s template contents.
+            if let Some(template) = element.downcast::<HTMLTemplateElement>() {
+                self.sanitize_core(
+                    template.Content(can_gc).upcast(),
+                    handle_javascript_navigation_urls,
+                    can_gc,
+                );
+            }
+            // Step 2.4.6. If child is a shadow host, then call sanitize core on child’s
+            // shadow root.
+            if let Some(shadow_root) = element.shadow_root() {
+                self.sanitize_core(
+                    shadow_root.upcast(),
+                    handle_javascript_navigation_urls,
+                    can_gc,
+                );
+            }
+            // Step 2.4.7. For each attribute in child’s attribute list:
+            let attributes: Vec<_> = element
+                .attrs()
+                .iter()
+                .map(|attribute| DomRoot::from_ref(&**attribute))
+                .collect();
+            for attribute in attributes {
+                let attribute_name =
+                    SanitizerName::new(attribute.local_name(), attribute.namespace());
+                if !self.is_attribute_allowed(
+                    element,
+                    &element_name,
+                    element_with_attributes,
+                    &attribute_name,
+                    &attribute.value(),
+                    handle_javascript_navigation_urls,
+                ) {
+                    element.remove_attribute(attribute.namespace(), attribute.local_name(), can_gc);
+                }
+            }
+            // Step 2.4.8. Call sanitize core on child with configuration and
+            // handleJavascriptNavigationUrls.
+            self.sanitize_core(&child, handle_javascript_navigation_urls, can_gc);
+        }
+    }
+
+    /// Step 2.4.7 of <https://wicg.github.io/sanitizer-api/#sanitize-core>
+    fn is_attribute_allowed(
+        &self,
+        element: &Element,
+        element_name: &SanitizerName,
+        element_with_attributes: Option<&SanitizerElementWithAttributes>,
+        attribute_name: &SanitizerName,
+        value: &str,
+        handle_javascript_navigation_urls: bool,
+    ) -> bool {
+        let local_attributes =
+            element_with_attributes.and_then(|element| element.attributes.as_ref());
+        let local_remove_attributes =
+            element_with_attributes.and_then(|element| element.remove_attributes.as_ref());
+        // Step 2.4.7.2. If elementWithLocalAttributes["removeAttributes"] contains attrName,
+        // then remove attribute.
+        if local_remove_attributes.is_some_and(|list| list.contains(attribute_name)) {
+            return false;
+        }
+        if self.attributes.is_some() {
+            // Step 2.4.7.3. Otherwise, if configuration["attributes"] exists: remove attribute
+            // unless either list allows it, or it is an allowed custom data attribute.
+            if !contains(&self.attributes, attribute_name) &&
+                !local_attributes.is_some_and(|list| list.contains(attribute_name)) &&
+                !(attribute_name.is_custom_data_attribute() &&
+                    self.data_attributes == Some(true))
+            {
+                return false;
+            }
+        } else if local_attributes.is_some_and(|list| !list.contains(attribute_name)) ||
+            contains(&self.remove_attributes, attribute_name)
+        {
+            // Step 2.4.7.4. Otherwise, remove attribute if it is not in the per-element
+            // allow-list, or if configuration["removeAttributes"] contains it.
+            return false;
+        }
+        // Step 2.4.7.5. If handleJavascriptNavigationUrls:
+        if !handle_javascript_navigation_urls {
+            return true;
+        }
+        let namespace = element.namespace();
+        let local_name = element.local_name();
+        let is_href = |attribute: &SanitizerName| {
+            attribute.name == "href" &&
+                (attribute.namespace.is_none() ||
+                    attribute.namespace.as_deref() == Some(&*ns!(xlink)))
+        };
+        // Step 2.4.7.5.1. If «[elementName, attrName]» matches an entry in the built-in
+        // navigating URL attributes list, and if attribute contains a javascript: URL,
+        // then remove attribute.
+        let is_navigating_url_attribute = match (namespace, local_name) {
+            (&ns!(html), &local_name!("a") | &local_name!("area") | &local_name!("base")) => {
+                attribute_name.matches("href", &ns!())
+            },
+            (&ns!(html), &local_name!("button") | &local_name!("input")) => {
+                attribute_name.matches("formaction", &ns!())
+            },
+            (&ns!(html), &local_name!("form")) => attribute_name.matches("action", &ns!()),
+            (&ns!(html), &local_name!("iframe")) => attribute_name.matches("src", &ns!()),
+            (&ns!(svg), &local_name!("a")) => is_href(attribute_name),
+            // Step 2.4.7.5.2. If child’s namespace is the MathML Namespace and attr’s local
+            // name is "href" and attr’s namespace is null or the XLink namespace and attr
+            // contains a javascript: URL, then remove attribute.
+            (&ns!(mathml), _) => is_href(attribute_name),
+            _ => false,
+        };
+        if is_navigating_url_attribute && contains_javascript_url(value) {
+            return false;
+        }
+        // Step 2.4.7.5.3. If the built-in animating URL attributes list contains
+        // «[elementName, attrName]» and attr’s value is "href" or "xlink:href", then remove
+        // attribute.
+        let is_animating_url_attribute = element_name.namespace.as_deref() == Some(&*ns!(svg)) &&
+            matches!(
+                &*element_name.name,
+                "animate" | "animateMotion" | "animateTransform" | "set"
+            ) &&
+            attribute_name.matches("attributeName", &ns!());
+        !(is_animating_url_attribute && matches!(value, "href" | "xlink:href"))
+    }
+}
+
+/// <https://wicg.github.io/sanitizer-api/#contains-a-javascript-url>
+fn contains_javascript_url(value: &str) -> bool {
+    // Step 1. Let url be the result of running the basic URL parser on attribute’s value.
+    // Step 2. If url is failure, then return false.
+    // Step 3. Return whether url’s scheme is "javascript".
+    ServoUrl::parse(value).is_ok_and(|url| url.scheme() == "javascript")
+}
+
+/// <https://wicg.github.io/sanitizer-api/#get-a-sanitizer-instance-from-options>
+fn configuration_from_options(
+    options: &SetHTMLOptions,
+    safe: bool,
+) -> Fallible<SanitizerConfiguration> {
+    // Step 1. Let sanitizerSpec be "default".
+    // Step 2. If options["sanitizer"] exists, then set sanitizerSpec to options["sanitizer"].
+    match &options.sanitizer {
+        // Step 4. If sanitizerSpec is a string: set sanitizerSpec to the built-in safe
+        // default configuration.
+        None | Some(SanitizerOrSanitizerConfigOrSanitizerPresets::SanitizerPresets(_)) => {
+            Ok(SanitizerConfiguration::built_in_safe_default())
+        },
+        // Step 5. If sanitizerSpec is a dictionary: set a configuration with sanitizerSpec,
+        // not safe, and a new Sanitizer. If this returns false, throw a TypeError.
+        Some(SanitizerOrSanitizerConfigOrSanitizerPresets::SanitizerConfig(configuration)) => {
+            let configuration = SanitizerConfiguration::canonicalize(configuration, !safe);
+            if !configuration.is_valid() {
+                return Err(Error::Type("Invalid sanitizer configuration".to_owned()));
+            }
+            Ok(configuration)
+        },
+        // Step 6. Assert: sanitizerSpec is a Sanitizer instance.
+        Some(SanitizerOrSanitizerConfigOrSanitizerPresets::Sanitizer(sanitizer)) => {
+            Ok(sanitizer.configuration.borrow().clone())
+        },
+    }
+}
+
+/// Remove everything from `node` that the built-in safe default configuration does not allow,
+/// as `setHTML()` does without a sanitizer. This is also used for HTML pasted into a page.
+pub(crate) fn sanitize_with_safe_default(node: &Node, can_gc: CanGc) {
+    SanitizerConfiguration::built_in_safe_default().sanitize(node, true, can_gc);
+}
+
+/// <https://wicg.github.io/sanitizer-api/#set-and-filter-html>
+pub(crate) fn set_and_filter_html(
+    target: &Node,
+    context_element: &Element,
+    html: DOMString,
+    options: &SetHTMLOptions,
+    safe: bool,
+    can_gc: CanGc,
+) -> ErrorResult {
+    // Step 1. If safe and contextElement’s local name is "script" and contextElement’s namespace
+    // is the HTML namespace or the SVG namespace, then return.
+    if safe &&
+        *context_element.local_name() == local_name!("script") &&
+        matches!(*context_element.namespace(), ns!(html) | ns!(svg))
+    {
+        return Ok(());
+    }
+    // Step 2. Let sanitizer be the result of calling get a sanitizer instance from options
+    // with options and safe.
+    let configuration = configuration_from_options(options, safe)?;
+    // Step 3. Let newChildren be the result of the HTML fragment parsing algorithm steps
+    // given contextElement, html, and true.
+    // Step 4. Let fragment be a new DocumentFragment whose node document is contextElement’s
+    // node document.
+    // Step 5. For each node in newChildren, append node to fragment.
+    let fragment = Node::parse_html_into_fragment(target, context_element, html, can_gc);
+    // Step 6. Run sanitize on fragment using sanitizer and safe.
+    configuration.sanitize(fragment.upcast(), safe, can_gc);
+    // Step 7. Replace all with fragment within target.
+    Node::replace_all(Some(fragment.upcast()), target, can_gc);
+    Ok(())
+}
+
+/// <https://wicg.github.io/sanitizer-api/#sanitizer>
+#[dom_struct]
+pub(crate) struct Sanitizer {
+    reflector_: Reflector,
+
+    /// <https://wicg.github.io/sanitizer-api/#sanitizer-configuration>
+    configuration: DomRefCell<SanitizerConfiguration>,
+}
+
+impl Sanitizer {
+    fn new_inherited(configuration: SanitizerConfiguration) -> Sanitizer {
+        Sanitizer {
+            reflector_: Reflector::new(),
+            configuration: DomRefCell::new(configuration),
+        }
+    }
+
+    fn new_with_proto(
+        window: &Window,
+        proto: Option<HandleObject>,
+        configuration: SanitizerConfiguration,
+        can_gc: CanGc,
+    ) -> DomRoot<Sanitizer> {
+        reflect_dom_object_with_proto(
+            Box::new(Sanitizer::new_inherited(configuration)),
+            window,
+            proto,
+            can_gc,
+        )
+    }
+}
+
+impl SanitizerMethods<crate::DomTypeHolder> for Sanitizer {
+    /// <https://wicg.github.io/sanitizer-api/#dom-sanitizer-constructor>
+    fn Constructor(
+        window: &Window,
+        proto: Option<HandleObject>,
+        can_gc: CanGc,
+        configuration: SanitizerConfigOrSanitizerPresets,
+    ) -> Fallible<DomRoot<Sanitizer>> {
+        let configuration = match configuration {
+            // Step 1. If configuration is a SanitizerPresets string, then set configuration
+            // to the built-in safe default configuration.
+            SanitizerConfigOrSanitizerPresets::SanitizerPresets(SanitizerPresets::Default) => {
+                SanitizerConfiguration::built_in_safe_default()
+            },
+            // Step 2. Let valid be the return value of set a configuration with configuration
+            // and true on this.
+            SanitizerConfigOrSanitizerPresets::SanitizerConfig(configuration) => {
+                let configuration = SanitizerConfiguration::canonicalize(&configuration, true);
+                // Step 3. If valid is false, then throw a TypeError.
+                if !configuration.is_valid() {
+                    return Err(Error::Type("Invalid sanitizer configuration".to_owned()));
+                }
+                configuration
+            },
+        };
+        Ok(Sanitizer::new_with_proto(
+            window,
+            proto,
+            configuration,
+            can_gc,
+        ))
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#dom-sanitizer-get>
+    fn Get(&self) -> SanitizerConfig {
+        self.configuration.borrow().to_dictionary()
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#dom-sanitizer-allowelement>
+    fn AllowElement(&self, element: StringOrSanitizerElementNamespaceWithAttributes) -> bool {
+        self.configuration
+            .borrow_mut()
+            .allow_element(SanitizerElementWithAttributes::canonicalize(element))
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#dom-sanitizer-removeelement>
+    fn RemoveElement(&self, element: StringOrSanitizerElementNamespace) -> bool {
+        self.configuration
+            .borrow_mut()
+            .remove_element(&canonicalize_element(element))
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#dom-sanitizer-replaceelementwithchildren>
+    fn ReplaceElementWithChildren(&self, element: StringOrSanitizerElementNamespace) -> bool {
+        self.configuration
+            .borrow_mut()
+            .replace_element_with_children(canonicalize_element(element))
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#dom-sanitizer-allowattribute>
+    fn AllowAttribute(&self, attribute: StringOrSanitizerAttributeNamespace) -> bool {
+        self.configuration
+            .borrow_mut()
+            .allow_attribute(canonicalize_attribute(attribute))
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#dom-sanitizer-removeattribute>
+    fn RemoveAttribute(&self, attribute: StringOrSanitizerAttributeNamespace) -> bool {
+        self.configuration
+            .borrow_mut()
+            .remove_attribute(&canonicalize_attribute(attribute))
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#dom-sanitizer-setcomments>
+    fn SetComments(&self, allow: bool) -> bool {
+        let mut configuration = self.configuration.borrow_mut();
+        // Step 1. If configuration["comments"] exists and equals allow, then return false.
+        if configuration.comments == allow {
+            return false;
+        }
+        // Step 2. Set configuration["comments"] to allow.
+        configuration.comments = allow;
+        true
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#dom-sanitizer-setdataattributes>
+    fn SetDataAttributes(&self, allow: bool) -> bool {
+        let mut configuration = self.configuration.borrow_mut();
+        // Step 1. If configuration["attributes"] does not exist, then return false.
+        if configuration.attributes.is_none() {
+            return false;
+        }
+        // Step 2. If configuration["dataAttributes"] equals allow, then return false.
+        if configuration.data_attributes == Some(allow) {
+            return false;
+        }
+        // Step 3. If allow is true, remove any custom data attributes from
+        // configuration["attributes"] and from each element's attributes.
+        if allow {
+            let configuration = &mut *configuration;
+            if let Some(attributes) = &mut configuration.attributes {
+                attributes.retain(|attribute| !attribute.is_custom_data_attribute());
+            }
+            for element in configuration.elements.iter_mut().flatten() {
+                if let Some(attributes) = &mut element.attributes {
+                    attributes.retain(|attribute| !attribute.is_custom_data_attribute());
+                }
+            }
+        }
+        // Step 4. Set configuration["dataAttributes"] to allow.
+        configuration.data_attributes = Some(allow);
+        true
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#dom-sanitizer-removeunsafe>
+    fn RemoveUnsafe(&self) -> bool {
+        self.configuration.borrow_mut().remove_unsafe()
+    }
+}
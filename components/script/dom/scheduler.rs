@@ -0,0 +1,399 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use dom_struct::dom_struct;
+use js::jsval::UndefinedValue;
+use js::rust::HandleValue;
+use script_bindings::inheritance::Castable;
+
+use crate::dom::abortsignal::{AbortAlgorithm, AbortSignal};
+use crate::dom::bindings::callback::ExceptionHandling;
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::SchedulerBinding::{
+    SchedulerMethods, SchedulerPostTaskCallback, SchedulerPostTaskOptions, TaskPriority,
+};
+use crate::dom::bindings::refcounted::Trusted;
+use crate::dom::bindings::reflector::{DomGlobal, Reflector, reflect_dom_object};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::promise::Promise;
+use crate::dom::tasksignal::TaskSignal;
+use crate::realms::enter_realm;
+use crate::script_runtime::{CanGc, JSContext};
+use crate::timers::OneshotTimerCallback;
+
+/// What a scheduled task does when it runs.
+#[derive(JSTraceable, MallocSizeOf)]
+enum ScheduledTaskSteps {
+    /// Invoke the callback passed to `postTask()`, settling the promise with its result.
+    Callback {
+        #[ignore_malloc_size_of = "Rc"]
+        callback: Rc<SchedulerPostTaskCallback>,
+        #[ignore_malloc_size_of = "Rc"]
+        promise: Rc<Promise>,
+    },
+    /// Resolve the promise returned by `yield()`.
+    Continuation {
+        #[ignore_malloc_size_of = "Rc"]
+        promise: Rc<Promise>,
+    },
+}
+
+/// The priority and abort sources of a scheduled task.
+/// <https://wicg.github.io/scheduling-apis/#scheduling-state>
+#[derive(JSTraceable, MallocSizeOf)]
+#[cfg_attr(crown, crown::unrooted_must_root_lint::must_root)]
+struct SchedulingState {
+    /// A fixed priority, which takes precedence over the priority of `signal`.
+    priority: Option<TaskPriority>,
+    /// The signal the task was posted with, whose priority is used if it is a `TaskSignal`.
+    signal: Option<Dom<AbortSignal>>,
+}
+
+impl SchedulingState {
+    /// <https://wicg.github.io/scheduling-apis/#scheduler-task-effective-priority>
+    fn effective_priority(&self) -> TaskPriority {
+        self.priority
+            .or_else(|| {
+                self.signal
+                    .as_ref()
+                    .and_then(|signal| signal.downcast::<TaskSignal>())
+                    .map(TaskSignal::priority)
+            })
+            .unwrap_or(TaskPriority::User_visible)
+    }
+}
+
+impl Clone for SchedulingState {
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    fn clone(&self) -> Self {
+        SchedulingState {
+            priority: self.priority,
+            signal: self.signal.as_ref().map(|signal| Dom::from_ref(&**signal)),
+        }
+    }
+}
+
+/// <https://wicg.github.io/scheduling-apis/#scheduler-task>
+#[derive(JSTraceable, MallocSizeOf)]
+#[cfg_attr(crown, crown::unrooted_must_root_lint::must_root)]
+struct ScheduledTask {
+    /// Identifies the task and orders it relative to other tasks of the same priority.
+    /// <https://wicg.github.io/scheduling-apis/#scheduler-task-enqueue-order>
+    id: u64,
+    state: SchedulingState,
+    steps: ScheduledTaskSteps,
+    /// Whether the task has been queued, which is delayed by the `delay` option of `postTask()`.
+    ready: bool,
+}
+
+impl ScheduledTask {
+    /// Tasks of a higher priority run first, and continuations run before other tasks of
+    /// the same priority.
+    /// <https://wicg.github.io/scheduling-apis/#scheduler-select-the-next-scheduler-task-queue-from-all-priorities>
+    fn rank(&self) -> (u8, bool) {
+        let priority = match self.state.effective_priority() {
+            TaskPriority::User_blocking => 2,
+            TaskPriority::User_visible => 1,
+            TaskPriority::Background => 0,
+        };
+        (
+            priority,
+            matches!(self.steps, ScheduledTaskSteps::Continuation { .. }),
+        )
+    }
+}
+
+/// <https://wicg.github.io/scheduling-apis/#scheduler>
+#[dom_struct]
+pub(crate) struct Scheduler {
+    reflector_: Reflector,
+
+    /// The tasks posted to this scheduler that have not yet run.
+    /// <https://wicg.github.io/scheduling-apis/#scheduler-static-priority-task-queue-map>
+    tasks: DomRefCell<Vec<ScheduledTask>>,
+
+    /// <https://wicg.github.io/scheduling-apis/#scheduler-next-enqueue-order>
+    next_enqueue_order: Cell<u64>,
+
+    /// The scheduling state of the `postTask()` callback that is currently running,
+    /// inherited by continuations created through `yield()`.
+    /// <https://wicg.github.io/scheduling-apis/#event-loop-current-scheduling-state>
+    current_scheduling_state: DomRefCell<Option<SchedulingState>>,
+}
+
+impl Scheduler {
+    fn new_inherited() -> Scheduler {
+        Scheduler {
+            reflector_: Reflector::new(),
+            tasks: Default::default(),
+            next_enqueue_order: Default::default(),
+            current_scheduling_state: Default::default(),
+        }
+    }
+
+    pub(crate) fn new(global: &GlobalScope, can_gc: CanGc) -> DomRoot<Scheduler> {
+        reflect_dom_object(Box::new(Scheduler::new_inherited()), global, can_gc)
+    }
+
+    /// <https://wicg.github.io/scheduling-apis/#schedule-a-task-to-invoke-an-algorithm>
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    fn schedule_task(&self, state: SchedulingState, steps: ScheduledTaskSteps, delay: u64) {
+        let id = self.next_enqueue_order.get();
+        self.next_enqueue_order.set(id + 1);
+
+        // If the task has an abort source, add an algorithm that removes the task and
+        // rejects its promise with the abort reason.
+        if let Some(signal) = &state.signal {
+            signal.add(&AbortAlgorithm::ScheduledTask(ScheduledTaskAbortSteps {
+                scheduler: Dom::from_ref(self),
+                id,
+            }));
+        }
+
+        let ready = delay == 0;
+        let priority = state.effective_priority();
+        self.tasks.borrow_mut().push(ScheduledTask {
+            id,
+            state,
+            steps,
+            ready,
+        });
+
+        if ready {
+            self.queue_run_task(priority);
+            return;
+        }
+
+        // Run steps after a timeout, which then queue the task.
+        let callback =
+            OneshotTimerCallback::SchedulerPostTaskDelay(SchedulerPostTaskDelayCallback {
+                scheduler: Trusted::new(self),
+                id,
+            });
+        self.global()
+            .schedule_callback(callback, Duration::from_millis(delay));
+    }
+
+    /// Queue a task on the event loop that runs the next scheduled task. Background tasks use
+    /// a task source that yields to other work while the event loop is busy.
+    fn queue_run_task(&self, priority: TaskPriority) {
+        let global = self.global();
+        let task_manager = global.task_manager();
+        let task_source = match priority {
+            TaskPriority::Background => task_manager.background_posted_task_task_source(),
+            _ => task_manager.posted_task_task_source(),
+        };
+        let scheduler = Trusted::new(self);
+        task_source.queue(task!(run_scheduled_task: move || {
+            scheduler.root().run_next_task(CanGc::note());
+        }));
+    }
+
+    /// Queue a task posted with a delay, once the delay has elapsed.
+    fn delay_elapsed(&self, id: u64) {
+        let priority = {
+            let mut tasks = self.tasks.borrow_mut();
+            let Some(task) = tasks.iter_mut().find(|task| task.id == id) else {
+                // The task was aborted while it was waiting.
+                return;
+            };
+            task.ready = true;
+            task.state.effective_priority()
+        };
+        self.queue_run_task(priority);
+    }
+
+    /// Run the highest-priority ready task, oldest first.
+    /// <https://wicg.github.io/scheduling-apis/#scheduler-run-a-scheduler-task>
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    fn run_next_task(&self, can_gc: CanGc) {
+        let task = {
+            let mut tasks = self.tasks.borrow_mut();
+            let next = tasks
+                .iter()
+                .enumerate()
+                .filter(|(_, task)| task.ready)
+                .max_by(|(_, a), (_, b)| a.rank().cmp(&b.rank()).then(b.id.cmp(&a.id)))
+                .map(|(index, _)| index);
+            match next {
+                Some(index) => tasks.remove(index),
+                None => return,
+            }
+        };
+
+        match task.steps {
+            ScheduledTaskSteps::Callback { callback, promise } => {
+                self.run_callback(task.state, &callback, &promise, can_gc);
+            },
+            ScheduledTaskSteps::Continuation { promise } => {
+                promise.resolve_native(&(), can_gc);
+            },
+        }
+    }
+
+    /// <https://wicg.github.io/scheduling-apis/#schedule-a-posttask-task>
+    fn run_callback(
+        &self,
+        state: SchedulingState,
+        callback: &SchedulerPostTaskCallback,
+        promise: &Promise,
+        can_gc: CanGc,
+    ) {
+        let global = self.global();
+        let _ac = enter_realm(&*global);
+        let cx = GlobalScope::get_cx();
+
+        // Set the event loop’s current scheduling state to state while the callback runs.
+        let previous_state = self.current_scheduling_state.replace(Some(state));
+
+        // Let callbackResult be the result of invoking callback.
+        rooted!(in(*cx) let mut rval = UndefinedValue());
+        let result = callback.Call__(rval.handle_mut(), ExceptionHandling::Rethrow, can_gc);
+
+        *self.current_scheduling_state.borrow_mut() = previous_state;
+
+        match result {
+            // If callbackResult is an abrupt completion, reject result with it.
+            Err(error) => promise.reject_error(error, can_gc),
+            // Otherwise, resolve result with callbackResult.
+            Ok(()) => promise.resolve(cx, rval.handle(), can_gc),
+        }
+    }
+
+    /// Remove an aborted task, rejecting its promise with the abort reason.
+    fn abort_task(&self, cx: JSContext, id: u64, reason: HandleValue, can_gc: CanGc) {
+        let task = {
+            let mut tasks = self.tasks.borrow_mut();
+            let Some(index) = tasks.iter().position(|task| task.id == id) else {
+                return;
+            };
+            tasks.remove(index)
+        };
+        let promise = match &task.steps {
+            ScheduledTaskSteps::Callback { promise, .. } => promise,
+            ScheduledTaskSteps::Continuation { promise } => promise,
+        };
+        promise.reject(cx, reason, can_gc);
+    }
+}
+
+impl SchedulerMethods<crate::DomTypeHolder> for Scheduler {
+    /// <https://wicg.github.io/scheduling-apis/#dom-scheduler-posttask>
+    fn PostTask(
+        &self,
+        callback: Rc<SchedulerPostTaskCallback>,
+        options: &SchedulerPostTaskOptions,
+        can_gc: CanGc,
+    ) -> Rc<Promise> {
+        // Step 1. Let result be a new promise.
+        let result = Promise::new(&self.global(), can_gc);
+
+        // Step 2. If options["signal"] exists, then:
+        if let Some(signal) = &options.signal {
+            // Step 2.1. Let signal be options["signal"].
+            // Step 2.2. If signal is aborted, then reject result with signal’s abort reason
+            // and return result.
+            if signal.aborted() {
+                let cx = GlobalScope::get_cx();
+                rooted!(in(*cx) let mut reason = UndefinedValue());
+                reason.set(signal.abort_reason());
+                result.reject(cx, reason.handle(), can_gc);
+                return result;
+            }
+        }
+
+        // Step 3. Let state be a new scheduling state, whose priority source is
+        // options["priority"] if it exists, and otherwise the signal if it is a TaskSignal.
+        let state = SchedulingState {
+            priority: options.priority,
+            signal: options.signal.as_deref().map(Dom::from_ref),
+        };
+
+        // Step 4. Schedule a postTask task with callback, state and options["delay"].
+        self.schedule_task(
+            state,
+            ScheduledTaskSteps::Callback {
+                callback,
+                promise: result.clone(),
+            },
+            options.delay,
+        );
+
+        // Step 5. Return result.
+        result
+    }
+
+    /// <https://wicg.github.io/scheduling-apis/#dom-scheduler-yield>
+    fn Yield(&self, can_gc: CanGc) -> Rc<Promise> {
+        // Step 1. Let result be a new promise.
+        let result = Promise::new(&self.global(), can_gc);
+
+        // Step 2. Let inheritedState be the scheduler’s relevant agent’s event loop’s
+        // current scheduling state. A continuation outside of a postTask() callback
+        // runs at the default priority.
+        let state = self
+            .current_scheduling_state
+            .borrow()
+            .clone()
+            .unwrap_or(SchedulingState {
+                priority: None,
+                signal: None,
+            });
+
+        // Step 3. If the inherited abort source is aborted, then reject result with its
+        // abort reason and return result.
+        if let Some(signal) = state.signal.as_ref().filter(|signal| signal.aborted()) {
+            let cx = GlobalScope::get_cx();
+            rooted!(in(*cx) let mut reason = UndefinedValue());
+            reason.set(signal.abort_reason());
+            result.reject(cx, reason.handle(), can_gc);
+            return result;
+        }
+
+        // Step 4. Schedule a yield continuation with the inherited state, which resolves result.
+        self.schedule_task(
+            state,
+            ScheduledTaskSteps::Continuation {
+                promise: result.clone(),
+            },
+            0,
+        );
+
+        // Step 5. Return result.
+        result
+    }
+}
+
+/// The abort algorithm added to the signal of a task posted to a [`Scheduler`].
+#[derive(Clone, JSTraceable, MallocSizeOf)]
+#[cfg_attr(crown, crown::unrooted_must_root_lint::must_root)]
+pub(crate) struct ScheduledTaskAbortSteps {
+    scheduler: Dom<Scheduler>,
+    id: u64,
+}
+
+impl ScheduledTaskAbortSteps {
+    pub(crate) fn abort(&self, cx: JSContext, reason: HandleValue, can_gc: CanGc) {
+        self.scheduler.abort_task(cx, self.id, reason, can_gc);
+    }
+}
+
+/// The timer callback of a task posted with a non-zero `delay`.
+#[derive(JSTraceable, MallocSizeOf)]
+pub(crate) struct SchedulerPostTaskDelayCallback {
+    #[ignore_malloc_size_of = "Because it is non-owning"]
+    scheduler: Trusted<Scheduler>,
+    id: u64,
+}
+
+impl SchedulerPostTaskDelayCallback {
+    pub(crate) fn invoke(self) {
+        self.scheduler.root().delay_elapsed(self.id);
+    }
+}
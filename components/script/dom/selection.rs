@@ -6,6 +6,7 @@ use std::cell::Cell;
 
 use dom_struct::dom_struct;
 
+use crate::dom::bindings::cell::DomRefCell;
 use crate::dom::bindings::codegen::Bindings::NodeBinding::{GetRootNodeOptions, NodeMethods};
 use crate::dom::bindings::codegen::Bindings::RangeBinding::RangeMethods;
 use crate::dom::bindings::codegen::Bindings::SelectionBinding::SelectionMethods;
@@ -17,8 +18,9 @@ use crate::dom::bindings::root::{Dom, DomRoot, MutNullableDom};
 use crate::dom::bindings::str::DOMString;
 use crate::dom::document::Document;
 use crate::dom::eventtarget::EventTarget;
-use crate::dom::node::{Node, NodeTraits};
+use crate::dom::node::{Node, NodeTraits, ShadowIncluding};
 use crate::dom::range::Range;
+use crate::dom::text::Text;
 use crate::script_runtime::CanGc;
 
 #[derive(Clone, Copy, JSTraceable, MallocSizeOf)]
@@ -35,6 +37,8 @@ pub(crate) struct Selection {
     range: MutNullableDom<Range>,
     direction: Cell<Direction>,
     task_queued: Cell<bool>,
+    /// The text nodes that layout currently paints as (partially) selected.
+    painted_text: DomRefCell<Vec<Dom<Text>>>,
 }
 
 impl Selection {
@@ -45,6 +49,7 @@ impl Selection {
             range: MutNullableDom::new(None),
             direction: Cell::new(Direction::Directionless),
             task_queued: Cell::new(false),
+            painted_text: DomRefCell::new(vec![]),
         }
     }
 
@@ -96,12 +101,87 @@ impl Selection {
                 task!(selectionchange_task_steps: move || {
                     let this = this.root();
                     this.task_queued.set(false);
+                    this.update_painted_selection();
                     this.document.upcast::<EventTarget>().fire_event(atom!("selectionchange"), CanGc::note());
                 })
             );
         self.task_queued.set(true);
     }
 
+    /// Mark the text nodes covered by this selection's range, so that layout paints them
+    /// as selected, and unmark the ones that are no longer covered. A collapsed range is
+    /// only painted, as a caret, when it is inside of editable content.
+    pub(crate) fn update_painted_selection(&self) {
+        let mut selected = vec![];
+        if let Some(range) = self.range.get() {
+            let start = range.start_container();
+            let end = range.end_container();
+            if range.collapsed() {
+                if let Some((text, offset)) = start
+                    .is_editable()
+                    .then(|| Self::caret_position(&start, range.start_offset()))
+                    .flatten()
+                {
+                    text.set_selected_range(Some(offset..offset));
+                    selected.push(text);
+                }
+            } else {
+                for node in range
+                    .CommonAncestorContainer()
+                    .traverse_preorder(ShadowIncluding::No)
+                {
+                    let Some(text) = node.downcast::<Text>() else {
+                        continue;
+                    };
+                    let start_offset = if node == start {
+                        range.start_offset()
+                    } else if range.IntersectsNode(&node) {
+                        0
+                    } else {
+                        continue;
+                    };
+                    let end_offset = if node == end {
+                        range.end_offset()
+                    } else {
+                        node.len()
+                    };
+                    text.set_selected_range(Some(start_offset..end_offset));
+                    selected.push(DomRoot::from_ref(text));
+                }
+            }
+        }
+
+        let mut painted_text = self.painted_text.borrow_mut();
+        for text in painted_text.iter() {
+            if !selected.iter().any(|selected| **selected == **text) {
+                text.set_selected_range(None);
+            }
+        }
+        *painted_text = selected.iter().map(|text| Dom::from_ref(&**text)).collect();
+    }
+
+    /// The text node and offset at which a caret for a collapsed selection at the given
+    /// boundary point is painted, which is in an adjacent text node if the boundary
+    /// point is between two children.
+    fn caret_position(container: &Node, offset: u32) -> Option<(DomRoot<Text>, u32)> {
+        if let Some(text) = container.downcast::<Text>() {
+            return Some((DomRoot::from_ref(text), offset));
+        }
+        if let Some(text) = offset
+            .checked_sub(1)
+            .and_then(|index| container.children().nth(index as usize))
+            .and_then(DomRoot::downcast::<Text>)
+        {
+            let length = text.upcast::<Node>().len();
+            return Some((text, length));
+        }
+        container
+            .children()
+            .nth(offset as usize)
+            .and_then(DomRoot::downcast::<Text>)
+            .map(|text| (text, 0))
+    }
+
     fn is_same_root(&self, node: &Node) -> bool {
         &*node.GetRootNode(&GetRootNodeOptions::empty()) == self.document.upcast::<Node>()
     }
@@ -39,6 +39,7 @@ use crate::dom::bindings::str::DOMString;
 use crate::dom::bindings::structuredclone;
 use crate::dom::bindings::trace::CustomTraceable;
 use crate::dom::bindings::utils::define_all_exposed_interfaces;
+use crate::dom::cookiestore::CookieStore;
 use crate::dom::csp::Violation;
 use crate::dom::dedicatedworkerglobalscope::AutoWorkerReset;
 use crate::dom::event::Event;
@@ -488,6 +489,11 @@ impl ServiceWorkerGlobalScope {
         }
     }
 
+    /// The scope URL of the registration this service worker belongs to.
+    pub(crate) fn scope_url(&self) -> &ServoUrl {
+        &self.scope_url
+    }
+
     pub(crate) fn event_loop_sender(&self) -> ScriptEventLoopSender {
         ScriptEventLoopSender::ServiceWorker(self.own_sender.clone())
     }
@@ -517,4 +523,12 @@ impl ServiceWorkerGlobalScopeMethods<crate::DomTypeHolder> for ServiceWorkerGlob
 
     // https://w3c.github.io/ServiceWorker/#dom-serviceworkerglobalscope-onmessageerror
     event_handler!(messageerror, GetOnmessageerror, SetOnmessageerror);
+
+    // https://cookiestore.spec.whatwg.org/#dom-serviceworkerglobalscope-cookiestore
+    fn CookieStore(&self) -> DomRoot<CookieStore> {
+        self.upcast::<GlobalScope>().cookie_store(CanGc::note())
+    }
+
+    // https://cookiestore.spec.whatwg.org/#dom-serviceworkerglobalscope-oncookiechange
+    event_handler!(cookiechange, GetOncookiechange, SetOncookiechange);
 }
@@ -5,7 +5,6 @@
 use std::borrow::Cow;
 use std::cell::Cell;
 
-use base::cross_process_instant::CrossProcessInstant;
 use base::id::PipelineId;
 use base64::Engine as _;
 use base64::engine::general_purpose;
@@ -1056,8 +1055,7 @@ impl FetchResponseListener for ParserContext {
         let _realm = enter_realm(&*parser);
 
         match status {
-            // are we throwing this away or can we use it?
-            Ok(_) => (),
+            Ok(resource_timing) => self.resource_timing = resource_timing,
             // TODO(Savago): we should send a notification to callers #5463.
             Err(err) => debug!("Failed to load page URL {}, error: {:?}", self.url, err),
         }
@@ -1072,12 +1070,11 @@ impl FetchResponseListener for ParserContext {
         }
 
         // TODO: Only update if this is the current document resource.
-        // TODO(mrobinson): Pass a proper fetch_start parameter here instead of `CrossProcessInstant::now()`.
         if let Some(pushed_index) = self.pushed_entry_index {
             let document = &parser.document;
             let performance_entry = PerformanceNavigationTiming::new(
                 &document.global(),
-                CrossProcessInstant::now(),
+                &self.resource_timing,
                 document,
                 CanGc::note(),
             );
@@ -1108,10 +1105,11 @@ impl FetchResponseListener for ParserContext {
 
         let document = &parser.document;
 
-        // TODO: Pass a proper fetch start time here.
+        // The timing of the fetch is only known once the response is complete, at which
+        // point this entry is replaced, see `process_response_eof`.
         let performance_entry = PerformanceNavigationTiming::new(
             &document.global(),
-            CrossProcessInstant::now(),
+            &self.resource_timing,
             document,
             CanGc::note(),
         );
@@ -1479,7 +1477,9 @@ fn create_element_for_token(
         .map(|attr| LocalName::from(&*attr.value));
 
     // Step 4.
-    let definition = document.lookup_custom_element_definition(&name.ns, &name.local, is.as_ref());
+    // The tree sink doesn't know the intended parent, so this uses the global registry.
+    let definition =
+        document.lookup_custom_element_definition(&name.ns, &name.local, is.as_ref(), None);
 
     // Step 5.
     let will_execute_script =
@@ -1507,7 +1507,16 @@ fn create_element_for_token(
         CustomElementCreationMode::Asynchronous
     };
 
-    let element = Element::create(name, is, document, creator, creation_mode, None, can_gc);
+    let element = Element::create(
+        name,
+        is,
+        document,
+        None,
+        creator,
+        creation_mode,
+        None,
+        can_gc,
+    );
 
     // https://html.spec.whatwg.org/multipage#the-input-element:value-sanitization-algorithm-3
     // says to invoke sanitization "when an input element is first created";
@@ -1616,6 +1625,7 @@ fn attach_declarative_shadow_inner(host: &Node, template: &Node, attributes: &[A
     let mut clonable = false;
     let mut delegatesfocus = false;
     let mut serializable = false;
+    let mut keep_custom_element_registry_null = false;
 
     let attributes: Vec<ElementAttribute> = attributes
         .iter()
@@ -1648,6 +1658,9 @@ fn attach_declarative_shadow_inner(host: &Node, template: &Node, attributes: &[A
             local_name!("shadowrootserializable") => {
                 serializable = true;
             },
+            ref name if &**name == "shadowrootcustomelementregistry" => {
+                keep_custom_element_registry_null = true;
+            },
             _ => {},
         });
 
@@ -1666,6 +1679,10 @@ fn attach_declarative_shadow_inner(host: &Node, template: &Node, attributes: &[A
             // Step 8.3. Set shadow's declarative to true.
             shadow_root.set_declarative(true);
 
+            // The elements of the shadow root are not upgraded until a registry is given to
+            // `CustomElementRegistry.initialize()`.
+            shadow_root.set_keep_custom_element_registry_null(keep_custom_element_registry_null);
+
             // Set 8.4. Set template's template contents property to shadow.
             let shadow = shadow_root.upcast::<DocumentFragment>();
             template_element.set_contents(Some(shadow));
@@ -3,6 +3,7 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
 use std::ops::Deref;
 
 use base::id::{PipelineId, WebViewId};
@@ -17,14 +18,18 @@ use markup5ever::TokenizerResult;
 use net_traits::policy_container::PolicyContainer;
 use net_traits::request::{
     CorsSettings, CredentialsMode, Destination, InsecureRequestsPolicy, ParserMetadata, Referrer,
+    RequestPriority,
 };
 use net_traits::{CoreResourceMsg, FetchChannels, IpcSend, ReferrerPolicy, ResourceThreads};
 use servo_url::{ImmutableOrigin, ServoUrl};
+use style::str::HTML_SPACE_CHARACTERS;
 
 use crate::dom::bindings::reflector::DomGlobal;
 use crate::dom::bindings::trace::{CustomTraceable, JSTraceable};
 use crate::dom::document::{Document, determine_policy_for_token};
-use crate::dom::htmlscriptelement::script_fetch_request;
+use crate::dom::htmlscriptelement::{
+    ScriptType, script_fetch_request, script_type_from_attributes,
+};
 use crate::fetch::create_a_potential_cors_request;
 use crate::script_module::ScriptFetchOptions;
 
@@ -74,8 +79,11 @@ impl Tokenizer {
             // true after the first script tag, since that is what will
             // block the main parser.
             prefetching: Cell::new(false),
+            template_depth: Cell::new(0),
+            prefetched_urls: Default::default(),
             insecure_requests_policy: document.insecure_requests_policy(),
             has_trustworthy_ancestor_origin: document.has_trustworthy_ancestor_or_current_origin(),
+            ancestor_origins: document.ancestor_origins_for_nested_navigable(),
             policy_container: global.policy_container(),
         };
         let options = Default::default();
@@ -107,10 +115,18 @@ struct PrefetchSink {
     #[no_trace]
     resource_threads: ResourceThreads,
     prefetching: Cell<bool>,
+    /// The number of `<template>` elements the tokenizer is currently inside of.
+    template_depth: Cell<usize>,
+    /// URLs that have already been prefetched, so that repeated references to the same
+    /// resource are only fetched once.
+    #[no_trace]
+    prefetched_urls: RefCell<HashSet<ServoUrl>>,
     #[no_trace]
     insecure_requests_policy: InsecureRequestsPolicy,
     has_trustworthy_ancestor_origin: bool,
     #[no_trace]
+    ancestor_origins: Vec<ImmutableOrigin>,
+    #[no_trace]
     policy_container: PolicyContainer,
 }
 
@@ -126,113 +142,37 @@ impl TokenSink for PrefetchSink {
             _ => return TokenSinkResult::Continue,
         };
         match (tag.kind, &tag.name) {
-            (TagKind::StartTag, &local_name!("script")) if self.prefetching.get() => {
-                if let Some(url) = self.get_url(tag, local_name!("src")) {
-                    debug!("Prefetch script {}", url);
-                    let cors_setting = self.get_cors_settings(tag, local_name!("crossorigin"));
-                    let integrity_metadata = self
-                        .get_attr(tag, local_name!("integrity"))
-                        .map(|attr| String::from(&attr.value))
-                        .unwrap_or_default();
-                    let cryptographic_nonce = self
-                        .get_attr(tag, local_name!("nonce"))
-                        .map(|attr| String::from(&attr.value))
-                        .unwrap_or_default();
-                    let request = script_fetch_request(
-                        self.webview_id,
-                        url,
-                        cors_setting,
-                        self.origin.clone(),
-                        self.pipeline_id,
-                        ScriptFetchOptions {
-                            referrer: self.referrer.clone(),
-                            referrer_policy: self.referrer_policy,
-                            integrity_metadata,
-                            cryptographic_nonce,
-                            credentials_mode: CredentialsMode::CredentialsSameOrigin,
-                            parser_metadata: ParserMetadata::ParserInserted,
-                        },
-                        self.insecure_requests_policy,
-                        self.has_trustworthy_ancestor_origin,
-                        self.policy_container.clone(),
-                    );
-                    let _ = self
-                        .resource_threads
-                        .send(CoreResourceMsg::Fetch(request, FetchChannels::Prefetch));
-                }
-                TokenSinkResult::RawData(RawKind::ScriptData)
-            },
-            (TagKind::StartTag, &local_name!("img")) if self.prefetching.get() => {
-                if let Some(url) = self.get_url(tag, local_name!("src")) {
-                    debug!("Prefetch {} {}", tag.name, url);
-                    let request = create_a_potential_cors_request(
-                        Some(self.webview_id),
-                        url,
-                        Destination::Image,
-                        self.get_cors_settings(tag, local_name!("crossorigin")),
-                        None,
-                        self.referrer.clone(),
-                        self.insecure_requests_policy,
-                        self.has_trustworthy_ancestor_origin,
-                        self.policy_container.clone(),
-                    )
-                    .origin(self.origin.clone())
-                    .pipeline_id(Some(self.pipeline_id))
-                    .referrer_policy(self.get_referrer_policy(tag, local_name!("referrerpolicy")));
-
-                    let _ = self
-                        .resource_threads
-                        .send(CoreResourceMsg::Fetch(request, FetchChannels::Prefetch));
-                }
+            // The contents of a template are inert, so nothing inside one is fetched.
+            (TagKind::StartTag, &local_name!("template")) => {
+                self.template_depth.set(self.template_depth.get() + 1);
                 TokenSinkResult::Continue
             },
-            (TagKind::StartTag, &local_name!("link")) if self.prefetching.get() => {
-                if let Some(rel) = self.get_attr(tag, local_name!("rel")) {
-                    if rel.value.eq_ignore_ascii_case("stylesheet") {
-                        if let Some(url) = self.get_url(tag, local_name!("href")) {
-                            debug!("Prefetch {} {}", tag.name, url);
-                            let cors_setting =
-                                self.get_cors_settings(tag, local_name!("crossorigin"));
-                            let referrer_policy =
-                                self.get_referrer_policy(tag, local_name!("referrerpolicy"));
-                            let integrity_metadata = self
-                                .get_attr(tag, local_name!("integrity"))
-                                .map(|attr| String::from(&attr.value))
-                                .unwrap_or_default();
-
-                            // https://html.spec.whatwg.org/multipage/#default-fetch-and-process-the-linked-resource
-                            let request = create_a_potential_cors_request(
-                                Some(self.webview_id),
-                                url,
-                                Destination::Style,
-                                cors_setting,
-                                None,
-                                self.referrer.clone(),
-                                self.insecure_requests_policy,
-                                self.has_trustworthy_ancestor_origin,
-                                self.policy_container.clone(),
-                            )
-                            .origin(self.origin.clone())
-                            .pipeline_id(Some(self.pipeline_id))
-                            .referrer_policy(referrer_policy)
-                            .integrity_metadata(integrity_metadata);
-
-                            let _ = self
-                                .resource_threads
-                                .send(CoreResourceMsg::Fetch(request, FetchChannels::Prefetch));
-                        }
-                    }
-                }
+            (TagKind::EndTag, &local_name!("template")) => {
+                self.template_depth
+                    .set(self.template_depth.get().saturating_sub(1));
                 TokenSinkResult::Continue
             },
             (TagKind::StartTag, &local_name!("script")) => {
+                if self.should_prefetch() {
+                    self.prefetch_script(tag);
+                }
                 TokenSinkResult::RawData(RawKind::ScriptData)
             },
             (TagKind::EndTag, &local_name!("script")) => {
                 // After the first script tag, the main parser is blocked, so it's worth prefetching.
-                self.prefetching.set(true);
+                if self.template_depth.get() == 0 {
+                    self.prefetching.set(true);
+                }
                 TokenSinkResult::Script(PrefetchHandle)
             },
+            (TagKind::StartTag, &local_name!("img")) if self.should_prefetch() => {
+                self.prefetch_image(tag);
+                TokenSinkResult::Continue
+            },
+            (TagKind::StartTag, &local_name!("link")) if self.should_prefetch() => {
+                self.prefetch_link(tag);
+                TokenSinkResult::Continue
+            },
             (TagKind::StartTag, &local_name!("base")) => {
                 if let Some(url) = self.get_url(tag, local_name!("href")) {
                     if self.base_url.borrow().is_none() {
@@ -248,6 +188,183 @@ impl TokenSink for PrefetchSink {
 }
 
 impl PrefetchSink {
+    fn should_prefetch(&self) -> bool {
+        self.prefetching.get() && self.template_depth.get() == 0
+    }
+
+    /// Record that `url` is being prefetched, returning false if it already was.
+    fn mark_as_prefetched(&self, url: &ServoUrl) -> bool {
+        self.prefetched_urls.borrow_mut().insert(url.clone())
+    }
+
+    fn prefetch_script(&self, tag: &Tag) {
+        let Some(url) = self.get_url(tag, local_name!("src")) else {
+            return;
+        };
+        let script_type = script_type_from_attributes(
+            self.get_attr(tag, local_name!("type"))
+                .map(|attr| &*attr.value),
+            self.get_attr(tag, local_name!("language"))
+                .map(|attr| &*attr.value),
+        );
+        let cors_setting = self.get_cors_settings(tag, local_name!("crossorigin"));
+        let cors_setting = match script_type {
+            // Module scripts are supported, so classic scripts marked nomodule never run.
+            Some(ScriptType::Classic) if self.get_attr(tag, local_name!("nomodule")).is_some() => {
+                return;
+            },
+            Some(ScriptType::Classic) => cors_setting,
+            // Module scripts are always fetched in CORS mode.
+            Some(ScriptType::Module) => Some(cors_setting.unwrap_or(CorsSettings::Anonymous)),
+            Some(ScriptType::ImportMap) | Some(ScriptType::SpeculationRules) | None => return,
+        };
+        if !self.mark_as_prefetched(&url) {
+            return;
+        }
+
+        debug!("Prefetch script {}", url);
+        let integrity_metadata = self
+            .get_attr(tag, local_name!("integrity"))
+            .map(|attr| String::from(&attr.value))
+            .unwrap_or_default();
+        let cryptographic_nonce = self
+            .get_attr(tag, local_name!("nonce"))
+            .map(|attr| String::from(&attr.value))
+            .unwrap_or_default();
+        let request = script_fetch_request(
+            self.webview_id,
+            url,
+            cors_setting,
+            self.origin.clone(),
+            self.pipeline_id,
+            ScriptFetchOptions {
+                referrer: self.referrer.clone(),
+                referrer_policy: self.get_referrer_policy(tag, local_name!("referrerpolicy")),
+                integrity_metadata,
+                cryptographic_nonce,
+                credentials_mode: CredentialsMode::CredentialsSameOrigin,
+                parser_metadata: ParserMetadata::ParserInserted,
+                fetch_priority: self.get_fetch_priority(tag),
+            },
+            self.insecure_requests_policy,
+            self.has_trustworthy_ancestor_origin,
+            self.policy_container.clone(),
+        )
+        .ancestor_origins(self.ancestor_origins.clone());
+        let _ = self
+            .resource_threads
+            .send(CoreResourceMsg::Fetch(request, FetchChannels::Prefetch));
+    }
+
+    fn prefetch_image(&self, tag: &Tag) {
+        // Lazily loaded images are only fetched once they approach the viewport.
+        let is_lazy = self
+            .get_attr(tag, local_name!("loading"))
+            .is_some_and(|attr| attr.value.eq_ignore_ascii_case("lazy"));
+        if is_lazy {
+            return;
+        }
+        let Some(url) = self.get_url(tag, local_name!("src")) else {
+            return;
+        };
+        if !self.mark_as_prefetched(&url) {
+            return;
+        }
+
+        debug!("Prefetch {} {}", tag.name, url);
+        let request = create_a_potential_cors_request(
+            Some(self.webview_id),
+            url,
+            Destination::Image,
+            self.get_cors_settings(tag, local_name!("crossorigin")),
+            None,
+            self.referrer.clone(),
+            self.insecure_requests_policy,
+            self.has_trustworthy_ancestor_origin,
+            self.policy_container.clone(),
+        )
+        .origin(self.origin.clone())
+        .pipeline_id(Some(self.pipeline_id))
+        .referrer_policy(self.get_referrer_policy(tag, local_name!("referrerpolicy")))
+        .priority(self.get_fetch_priority(tag))
+        .ancestor_origins(self.ancestor_origins.clone());
+
+        let _ = self
+            .resource_threads
+            .send(CoreResourceMsg::Fetch(request, FetchChannels::Prefetch));
+    }
+
+    fn prefetch_link(&self, tag: &Tag) {
+        let Some(rel) = self.get_attr(tag, local_name!("rel")) else {
+            return;
+        };
+        let rel = rel.value.to_ascii_lowercase();
+        let link_types: Vec<&str> = rel.split(HTML_SPACE_CHARACTERS).collect();
+        let cors_setting = self.get_cors_settings(tag, local_name!("crossorigin"));
+
+        let (destination, cors_setting) =
+            if link_types.contains(&"stylesheet") && !link_types.contains(&"alternate") {
+                (Destination::Style, cors_setting)
+            } else if link_types.contains(&"modulepreload") {
+                // Module scripts are always fetched in CORS mode.
+                (
+                    Destination::Script,
+                    Some(cors_setting.unwrap_or(CorsSettings::Anonymous)),
+                )
+            } else if link_types.contains(&"preload") {
+                let Some(as_attr) = self.get_attr(tag, local_name!("as")) else {
+                    return;
+                };
+                let destination = match &*as_attr.value.to_ascii_lowercase() {
+                    "fetch" => Destination::None,
+                    "font" => Destination::Font,
+                    "image" => Destination::Image,
+                    "script" => Destination::Script,
+                    "style" => Destination::Style,
+                    _ => return,
+                };
+                (destination, cors_setting)
+            } else {
+                return;
+            };
+
+        let Some(url) = self.get_url(tag, local_name!("href")) else {
+            return;
+        };
+        if !self.mark_as_prefetched(&url) {
+            return;
+        }
+
+        debug!("Prefetch {} {}", tag.name, url);
+        let integrity_metadata = self
+            .get_attr(tag, local_name!("integrity"))
+            .map(|attr| String::from(&attr.value))
+            .unwrap_or_default();
+
+        // https://html.spec.whatwg.org/multipage/#default-fetch-and-process-the-linked-resource
+        let request = create_a_potential_cors_request(
+            Some(self.webview_id),
+            url,
+            destination,
+            cors_setting,
+            None,
+            self.referrer.clone(),
+            self.insecure_requests_policy,
+            self.has_trustworthy_ancestor_origin,
+            self.policy_container.clone(),
+        )
+        .origin(self.origin.clone())
+        .pipeline_id(Some(self.pipeline_id))
+        .referrer_policy(self.get_referrer_policy(tag, local_name!("referrerpolicy")))
+        .integrity_metadata(integrity_metadata)
+        .priority(self.get_fetch_priority(tag))
+        .ancestor_origins(self.ancestor_origins.clone());
+
+        let _ = self
+            .resource_threads
+            .send(CoreResourceMsg::Fetch(request, FetchChannels::Prefetch));
+    }
+
     fn get_attr<'a>(&'a self, tag: &'a Tag, name: LocalName) -> Option<&'a Attribute> {
         tag.attrs.iter().find(|attr| attr.name.local == name)
     }
@@ -265,6 +382,11 @@ impl PrefetchSink {
             .unwrap_or(self.referrer_policy)
     }
 
+    fn get_fetch_priority(&self, tag: &Tag) -> RequestPriority {
+        let fetch_priority = self.get_attr(tag, LocalName::from("fetchpriority"));
+        RequestPriority::from_fetch_priority_attribute(fetch_priority.map(|attr| &*attr.value))
+    }
+
     fn get_cors_settings(&self, tag: &Tag, name: LocalName) -> Option<CorsSettings> {
         let crossorigin = self.get_attr(tag, name)?;
         if crossorigin.value.eq_ignore_ascii_case("anonymous") {
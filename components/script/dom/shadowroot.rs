@@ -23,10 +23,14 @@ use crate::conversions::Convert;
 use crate::dom::bindings::cell::DomRefCell;
 use crate::dom::bindings::codegen::Bindings::ElementBinding::GetHTMLOptions;
 use crate::dom::bindings::codegen::Bindings::HTMLSlotElementBinding::HTMLSlotElement_Binding::HTMLSlotElementMethods;
+use crate::dom::bindings::codegen::Bindings::SanitizerBinding::SetHTMLOptions;
 use crate::dom::bindings::codegen::Bindings::ShadowRootBinding::ShadowRoot_Binding::ShadowRootMethods;
 use crate::dom::bindings::codegen::Bindings::ShadowRootBinding::{
     ShadowRootMode, SlotAssignmentMode,
 };
+use crate::dom::bindings::codegen::UnionTypes::{
+    TrustedHTMLOrNullIsEmptyString, TrustedHTMLOrString,
+};
 use crate::dom::bindings::frozenarray::CachedFrozenArray;
 use crate::dom::bindings::inheritance::Castable;
 use crate::dom::bindings::num::Finite;
@@ -34,6 +38,7 @@ use crate::dom::bindings::reflector::reflect_dom_object;
 use crate::dom::bindings::root::{Dom, DomRoot, LayoutDom, MutNullableDom};
 use crate::dom::bindings::str::DOMString;
 use crate::dom::cssstylesheet::CSSStyleSheet;
+use crate::dom::customelementregistry::CustomElementRegistry;
 use crate::dom::document::Document;
 use crate::dom::documentfragment::DocumentFragment;
 use crate::dom::documentorshadowroot::{
@@ -45,7 +50,9 @@ use crate::dom::node::{
     BindContext, Node, NodeDamage, NodeFlags, NodeTraits, ShadowIncluding, UnbindContext,
     VecPreOrderInsertionHelper,
 };
+use crate::dom::sanitizer::set_and_filter_html;
 use crate::dom::stylesheetlist::{StyleSheetList, StyleSheetListOwner};
+use crate::dom::trustedhtml::TrustedHTML;
 use crate::dom::types::EventTarget;
 use crate::dom::virtualmethods::{VirtualMethods, vtable_for};
 use crate::dom::window::Window;
@@ -104,6 +111,15 @@ pub(crate) struct ShadowRoot {
     /// Cached frozen array of [`Self::adopted_stylesheets`]
     #[ignore_malloc_size_of = "mozjs"]
     adopted_stylesheets_frozen_types: CachedFrozenArray,
+
+    /// <https://wicg.github.io/webcomponents/proposals/Scoped-Custom-Element-Registries>
+    /// The scoped custom element registry passed to `attachShadow`, if any.
+    custom_element_registry: MutNullableDom<CustomElementRegistry>,
+
+    /// <https://dom.spec.whatwg.org/#shadowroot-keep-custom-element-registry-null>
+    /// Set for declarative shadow roots with a `shadowrootcustomelementregistry` attribute,
+    /// whose elements are not upgraded until a registry is given to `initialize()`.
+    keep_custom_element_registry_null: Cell<bool>,
 }
 
 impl ShadowRoot {
@@ -143,6 +159,8 @@ impl ShadowRoot {
             delegates_focus: Cell::new(false),
             adopted_stylesheets: Default::default(),
             adopted_stylesheets_frozen_types: CachedFrozenArray::new(),
+            custom_element_registry: Default::default(),
+            keep_custom_element_registry_null: Cell::new(false),
         }
     }
 
@@ -360,6 +378,33 @@ impl ShadowRoot {
     pub(crate) fn set_delegates_focus(&self, delegates_focus: bool) {
         self.delegates_focus.set(delegates_focus);
     }
+
+    pub(crate) fn scoped_custom_element_registry(&self) -> Option<DomRoot<CustomElementRegistry>> {
+        self.custom_element_registry.get()
+    }
+
+    pub(crate) fn set_scoped_custom_element_registry(&self, registry: &CustomElementRegistry) {
+        debug_assert!(registry.is_scoped());
+        self.custom_element_registry.set(Some(registry));
+    }
+
+    pub(crate) fn keeps_custom_element_registry_null(&self) -> bool {
+        self.keep_custom_element_registry_null.get()
+    }
+
+    pub(crate) fn set_keep_custom_element_registry_null(&self, keep: bool) {
+        self.keep_custom_element_registry_null.set(keep);
+    }
+
+    /// <https://wicg.github.io/webcomponents/proposals/Scoped-Custom-Element-Registries>
+    /// The registry used to look up definitions for elements in this shadow root, if any.
+    pub(crate) fn custom_element_registry(&self) -> Option<DomRoot<CustomElementRegistry>> {
+        if self.keeps_custom_element_registry_null() {
+            return None;
+        }
+        self.scoped_custom_element_registry()
+            .or_else(|| self.document.custom_element_registry())
+    }
 }
 
 impl ShadowRootMethods<crate::DomTypeHolder> for ShadowRoot {
@@ -435,6 +480,11 @@ impl ShadowRootMethods<crate::DomTypeHolder> for ShadowRoot {
         host.expect("Trying to get host from a detached shadow root")
     }
 
+    /// <https://wicg.github.io/webcomponents/proposals/Scoped-Custom-Element-Registries>
+    fn GetCustomElementRegistry(&self) -> Option<DomRoot<CustomElementRegistry>> {
+        self.custom_element_registry()
+    }
+
     // https://drafts.csswg.org/cssom/#dom-document-stylesheets
     fn StyleSheets(&self) -> DomRoot<StyleSheetList> {
         self.stylesheet_list.or_init(|| {
@@ -459,18 +509,25 @@ impl ShadowRootMethods<crate::DomTypeHolder> for ShadowRoot {
     }
 
     /// <https://html.spec.whatwg.org/multipage/#dom-shadowroot-innerhtml>
-    fn GetInnerHTML(&self, can_gc: CanGc) -> Fallible<DOMString> {
+    fn GetInnerHTML(&self, can_gc: CanGc) -> Fallible<TrustedHTMLOrNullIsEmptyString> {
         // ShadowRoot's innerHTML getter steps are to return the result of running fragment serializing
         // algorithm steps with this and true.
         self.upcast::<Node>()
             .fragment_serialization_algorithm(true, can_gc)
+            .map(TrustedHTMLOrNullIsEmptyString::NullIsEmptyString)
     }
 
     /// <https://html.spec.whatwg.org/multipage/#dom-shadowroot-innerhtml>
-    fn SetInnerHTML(&self, value: DOMString, can_gc: CanGc) -> ErrorResult {
-        // TODO Step 1. Let compliantString be the result of invoking the Get Trusted Type compliant string algorithm
+    fn SetInnerHTML(&self, value: TrustedHTMLOrNullIsEmptyString, can_gc: CanGc) -> ErrorResult {
+        // Step 1. Let compliantString be the result of invoking the Get Trusted Type compliant string algorithm
         // with TrustedHTML, this's relevant global object, the given value, "ShadowRoot innerHTML", and "script".
-        let compliant_string = value;
+        let compliant_string = TrustedHTML::get_trusted_script_compliant_string(
+            &self.owner_global(),
+            value.convert(),
+            "ShadowRoot",
+            "innerHTML",
+            can_gc,
+        )?;
 
         // Step 2. Let context be this's host.
         let context = self.Host();
@@ -482,6 +539,12 @@ impl ShadowRootMethods<crate::DomTypeHolder> for ShadowRoot {
         // we can't continue if parsing failed
         let frag = context.parse_fragment(compliant_string, can_gc)?;
 
+        // Elements parsed into a shadow root use its scoped registry, not the one of its host.
+        if let Some(registry) = self.scoped_custom_element_registry() {
+            frag.upcast::<Node>()
+                .set_scoped_custom_element_registry_for_descendants(&registry);
+        }
+
         // Step 4. Replace all with fragment within this.
         Node::replace_all(Some(frag.upcast()), self.upcast(), can_gc);
         Ok(())
@@ -493,12 +556,30 @@ impl ShadowRootMethods<crate::DomTypeHolder> for ShadowRoot {
     }
 
     /// <https://html.spec.whatwg.org/multipage/#dom-shadowroot-sethtmlunsafe>
-    fn SetHTMLUnsafe(&self, html: DOMString, can_gc: CanGc) {
+    fn SetHTMLUnsafe(&self, html: TrustedHTMLOrString, can_gc: CanGc) -> ErrorResult {
+        // Step 1. Let compliantHTML be the result of invoking the
+        // Get Trusted Type compliant string algorithm with TrustedHTML,
+        // this's relevant global object, html, "ShadowRoot setHTMLUnsafe", and "script".
+        let html = TrustedHTML::get_trusted_script_compliant_string(
+            &self.owner_global(),
+            html,
+            "ShadowRoot",
+            "setHTMLUnsafe",
+            can_gc,
+        )?;
         // Step 2. Unsafely set HTMl given this, this's shadow host, and complaintHTML
         let target = self.upcast::<Node>();
         let context_element = self.Host();
 
         Node::unsafely_set_html(target, &context_element, html, can_gc);
+        Ok(())
+    }
+
+    /// <https://wicg.github.io/sanitizer-api/#dom-shadowroot-sethtml>
+    fn SetHTML(&self, html: DOMString, options: &SetHTMLOptions, can_gc: CanGc) -> ErrorResult {
+        // Step 1. Set and filter HTML using this (as target), this's shadow host (as context
+        // element), html, options, and true.
+        set_and_filter_html(self.upcast(), &self.Host(), html, options, true, can_gc)
     }
 
     // https://dom.spec.whatwg.org/#dom-shadowroot-onslotchange
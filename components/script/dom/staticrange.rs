@@ -40,6 +40,28 @@ impl StaticRange {
             ),
         }
     }
+
+    pub(crate) fn new(
+        document: &Document,
+        start_container: &Node,
+        start_offset: u32,
+        end_container: &Node,
+        end_offset: u32,
+        can_gc: CanGc,
+    ) -> DomRoot<StaticRange> {
+        reflect_dom_object_with_proto(
+            Box::new(StaticRange::new_inherited(
+                start_container,
+                start_offset,
+                end_container,
+                end_offset,
+            )),
+            document.window(),
+            None,
+            can_gc,
+        )
+    }
+
     pub(crate) fn new_with_doc(
         document: &Document,
         proto: Option<HandleObject>,
@@ -12,27 +12,37 @@ use aes::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit, StreamCipher};
 use aes::{Aes128, Aes192, Aes256};
 use aes_gcm::{AeadInPlace, AesGcm, KeyInit};
 use aes_kw::{KekAes128, KekAes192, KekAes256};
-use aws_lc_rs::{digest, hkdf, hmac, pbkdf2};
+use aws_lc_rs::encoding::AsDer;
+use aws_lc_rs::rand::SystemRandom;
+use aws_lc_rs::rsa::{
+    KeySize, OAEP_SHA1_MGF1SHA1, OAEP_SHA256_MGF1SHA256, OAEP_SHA384_MGF1SHA384,
+    OAEP_SHA512_MGF1SHA512, OaepAlgorithm, OaepPrivateDecryptingKey, OaepPublicEncryptingKey,
+    PrivateDecryptingKey, PublicEncryptingKey,
+};
+use aws_lc_rs::signature::{EcdsaKeyPair, Ed25519KeyPair, KeyPair, RsaKeyPair};
+use aws_lc_rs::{agreement, digest, hkdf, hmac, pbkdf2, signature};
 use base64::prelude::*;
 use cipher::consts::{U12, U16, U32};
 use dom_struct::dom_struct;
 use js::conversions::ConversionResult;
 use js::jsapi::{JS_NewObject, JSObject};
 use js::jsval::ObjectValue;
-use js::rust::MutableHandleObject;
-use js::typedarray::ArrayBufferU8;
+use js::rust::{HandleObject, MutableHandleObject};
+use js::typedarray::{ArrayBufferU8, Uint8};
 use servo_rand::{RngCore, ServoRng};
 
 use crate::dom::bindings::buffer_source::create_buffer_source;
 use crate::dom::bindings::cell::DomRefCell;
 use crate::dom::bindings::codegen::Bindings::CryptoKeyBinding::{
-    CryptoKeyMethods, KeyType, KeyUsage,
+    CryptoKeyMethods, CryptoKeyPair, KeyType, KeyUsage,
 };
 use crate::dom::bindings::codegen::Bindings::SubtleCryptoBinding::{
     AesCbcParams, AesCtrParams, AesDerivedKeyParams, AesGcmParams, AesKeyAlgorithm,
-    AesKeyGenParams, Algorithm, AlgorithmIdentifier, HkdfParams, HmacImportParams,
+    AesKeyGenParams, Algorithm, AlgorithmIdentifier, EcKeyAlgorithm, EcKeyGenParams,
+    EcKeyImportParams, EcdhKeyDeriveParams, EcdsaParams, HkdfParams, HmacImportParams,
     HmacKeyAlgorithm, HmacKeyGenParams, JsonWebKey, KeyAlgorithm, KeyFormat, Pbkdf2Params,
-    SubtleCryptoMethods,
+    RsaHashedImportParams, RsaHashedKeyAlgorithm, RsaHashedKeyGenParams, RsaKeyAlgorithm,
+    RsaOaepParams, RsaPssParams, SubtleCryptoMethods,
 };
 use crate::dom::bindings::codegen::UnionTypes::{
     ArrayBufferViewOrArrayBuffer, ArrayBufferViewOrArrayBufferOrJsonWebKey,
@@ -66,8 +76,9 @@ const ALG_RSA_OAEP: &str = "RSA-OAEP";
 const ALG_RSA_PSS: &str = "RSA-PSS";
 const ALG_ECDH: &str = "ECDH";
 const ALG_ECDSA: &str = "ECDSA";
+const ALG_ED25519: &str = "Ed25519";
+const ALG_X25519: &str = "X25519";
 
-#[allow(dead_code)]
 static SUPPORTED_ALGORITHMS: &[&str] = &[
     ALG_AES_CBC,
     ALG_AES_CTR,
@@ -85,6 +96,8 @@ static SUPPORTED_ALGORITHMS: &[&str] = &[
     ALG_RSA_PSS,
     ALG_ECDH,
     ALG_ECDSA,
+    ALG_ED25519,
+    ALG_X25519,
 ];
 
 const NAMED_CURVE_P256: &str = "P-256";
@@ -521,7 +534,24 @@ impl SubtleCryptoMethods<crate::DomTypeHolder> for SubtleCrypto {
                 let key = normalized_algorithm.generate_key(&subtle, key_usages, extractable, CanGc::note());
 
                 match key {
-                    Ok(key) => promise.resolve_native(&key, CanGc::note()),
+                    Ok(GeneratedKey::Key(key)) => {
+                        // If the [[type]] internal slot of result is "secret" or "private" and usages is empty,
+                        // then throw a SyntaxError.
+                        if matches!(key.Type(), KeyType::Secret | KeyType::Private) && key.usages().is_empty() {
+                            promise.reject_error(Error::Syntax, CanGc::note());
+                            return;
+                        }
+                        promise.resolve_native(&key, CanGc::note())
+                    },
+                    Ok(GeneratedKey::KeyPair(key_pair)) => {
+                        // If the [[usages]] internal slot of the privateKey attribute of result is the empty
+                        // sequence, then throw a SyntaxError.
+                        if key_pair.privateKey.as_ref().is_some_and(|key| key.usages().is_empty()) {
+                            promise.reject_error(Error::Syntax, CanGc::note());
+                            return;
+                        }
+                        promise.resolve_native(&key_pair, CanGc::note())
+                    },
                     Err(e) => promise.reject_error(e, CanGc::note()),
                 }
             }));
@@ -592,12 +622,17 @@ impl SubtleCryptoMethods<crate::DomTypeHolder> for SubtleCrypto {
                 // Step 10. If the following steps or referenced procedures say to throw an error, reject promise
                 // with the returned error and then terminate the algorithm.
 
-                // TODO Step 11. If the name member of normalizedAlgorithm is not equal to the name attribute of the #
-                // [[algorithm]] internal slot of baseKey then throw an InvalidAccessError.
                 let promise = trusted_promise.root();
                 let base_key = trusted_base_key.root();
                 let subtle = this.root();
 
+                // Step 11. If the name member of normalizedAlgorithm is not equal to the name attribute of the
+                // [[algorithm]] internal slot of baseKey then throw an InvalidAccessError.
+                if normalized_algorithm.name() != base_key.algorithm() {
+                    promise.reject_error(Error::InvalidAccess, CanGc::note());
+                    return;
+                }
+
                 // Step 12. If the [[usages]] internal slot of baseKey does not contain an entry that is
                 // "deriveKey", then throw an InvalidAccessError.
                 if !base_key.usages().contains(&KeyUsage::DeriveKey) {
@@ -698,11 +733,16 @@ impl SubtleCryptoMethods<crate::DomTypeHolder> for SubtleCrypto {
                 // Step 6. If the following steps or referenced procedures say to throw an error,
                 // reject promise with the returned error and then terminate the algorithm.
 
-                // TODO Step 7. If the name member of normalizedAlgorithm is not equal to the name attribute
-                // of the [[algorithm]] internal slot of baseKey then throw an InvalidAccessError.
                 let promise = trusted_promise.root();
                 let base_key = trusted_base_key.root();
 
+                // Step 7. If the name member of normalizedAlgorithm is not equal to the name attribute
+                // of the [[algorithm]] internal slot of baseKey then throw an InvalidAccessError.
+                if normalized_algorithm.name() != base_key.algorithm() {
+                    promise.reject_error(Error::InvalidAccess, CanGc::note());
+                    return;
+                }
+
                 // Step 8. If the [[usages]] internal slot of baseKey does not contain an entry that
                 // is "deriveBits", then throw an InvalidAccessError.
                 if !base_key.usages().contains(&KeyUsage::DeriveBits) {
@@ -753,28 +793,22 @@ impl SubtleCryptoMethods<crate::DomTypeHolder> for SubtleCrypto {
             },
         };
 
-        let data = match key_data {
-            ArrayBufferViewOrArrayBufferOrJsonWebKey::ArrayBufferView(view) => view.to_vec(),
-            ArrayBufferViewOrArrayBufferOrJsonWebKey::JsonWebKey(json_web_key) => {
-                let data_string = match json_web_key.k {
-                    Some(s) => s.to_string(),
-                    None => {
-                        promise.reject_error(Error::Syntax, can_gc);
-                        return promise;
-                    },
-                };
-
-                match base64::engine::general_purpose::STANDARD_NO_PAD
-                    .decode(data_string.as_bytes())
-                {
-                    Ok(data) => data,
-                    Err(_) => {
-                        promise.reject_error(Error::Syntax, can_gc);
-                        return promise;
-                    },
-                }
+        // If format is equal to the string "jwk", keyData must be a JsonWebKey dictionary, and it must not
+        // be one otherwise.
+        let data = match (format, key_data) {
+            (
+                KeyFormat::Jwk,
+                ArrayBufferViewOrArrayBufferOrJsonWebKey::JsonWebKey(json_web_key),
+            ) => jwk_to_json(&json_web_key),
+            (KeyFormat::Jwk, _) | (_, ArrayBufferViewOrArrayBufferOrJsonWebKey::JsonWebKey(_)) => {
+                promise.reject_error(
+                    Error::Type("The key data does not match the key format".into()),
+                    can_gc,
+                );
+                return promise;
             },
-            ArrayBufferViewOrArrayBufferOrJsonWebKey::ArrayBuffer(array_buffer) => {
+            (_, ArrayBufferViewOrArrayBufferOrJsonWebKey::ArrayBufferView(view)) => view.to_vec(),
+            (_, ArrayBufferViewOrArrayBufferOrJsonWebKey::ArrayBuffer(array_buffer)) => {
                 array_buffer.to_vec()
             },
         };
@@ -787,10 +821,29 @@ impl SubtleCryptoMethods<crate::DomTypeHolder> for SubtleCrypto {
             .queue(task!(import_key: move || {
                 let subtle = this.root();
                 let promise = trusted_promise.root();
+                let (format, data) = if format == KeyFormat::Jwk {
+                    match parse_jwk(&data, &normalized_algorithm, extractable, &key_usages) {
+                        Ok(key_material) => key_material,
+                        Err(e) => {
+                            promise.reject_error(e, CanGc::note());
+                            return;
+                        }
+                    }
+                } else {
+                    (format, data)
+                };
                 let imported_key = normalized_algorithm.import_key(&subtle,
                     format, &data, extractable, key_usages, CanGc::note());
                 match imported_key {
-                    Ok(k) => promise.resolve_native(&k, CanGc::note()),
+                    Ok(k) => {
+                        // If the [[type]] internal slot of result is "secret" or "private" and usages is empty,
+                        // then throw a SyntaxError.
+                        if matches!(k.Type(), KeyType::Secret | KeyType::Private) && k.usages().is_empty() {
+                            promise.reject_error(Error::Syntax, CanGc::note());
+                            return;
+                        }
+                        promise.resolve_native(&k, CanGc::note())
+                    },
                     Err(e) => promise.reject_error(e, CanGc::note()),
                 };
             }));
@@ -827,14 +880,10 @@ impl SubtleCryptoMethods<crate::DomTypeHolder> for SubtleCrypto {
                     promise.reject_error(Error::InvalidAccess, CanGc::note());
                     return;
                 }
-                let exported_key = match alg_name.as_str() {
-                    ALG_AES_CBC | ALG_AES_CTR | ALG_AES_KW | ALG_AES_GCM => subtle.export_key_aes(format, &key),
-                    _ => Err(Error::NotSupported),
-                };
-                match exported_key {
+                match subtle.export_key(format, &key) {
                     Ok(k) => {
                         match k {
-                            AesExportedKey::Raw(k) => {
+                            ExportedKey::Raw(k) => {
                                 let cx = GlobalScope::get_cx();
                                 rooted!(in(*cx) let mut array_buffer_ptr = ptr::null_mut::<JSObject>());
                                 create_buffer_source::<ArrayBufferU8>(cx, &k, array_buffer_ptr.handle_mut(),
@@ -842,7 +891,7 @@ impl SubtleCryptoMethods<crate::DomTypeHolder> for SubtleCrypto {
                                     .expect("failed to create buffer source for exported key.");
                                 promise.resolve_native(&array_buffer_ptr.get(), CanGc::note())
                             },
-                            AesExportedKey::Jwk(k) => {
+                            ExportedKey::Jwk(k) => {
                                 promise.resolve_native(&k, CanGc::note())
                             },
                         }
@@ -902,7 +951,7 @@ impl SubtleCryptoMethods<crate::DomTypeHolder> for SubtleCrypto {
                     return;
                 }
 
-                let exported_key = match subtle.export_key_aes(format, &key) {
+                let exported_key = match subtle.export_key(format, &key) {
                     Ok(k) => k,
                     Err(e) => {
                         promise.reject_error(e, CanGc::note());
@@ -911,38 +960,10 @@ impl SubtleCryptoMethods<crate::DomTypeHolder> for SubtleCrypto {
                 };
 
                 let bytes = match exported_key {
-                    AesExportedKey::Raw(k) => k,
-                    AesExportedKey::Jwk(key) => {
-                        // The spec states to convert this to an ECMAscript object and stringify it, but since we know
-                        // that the output will be a string of JSON we can just construct it manually
-                        // TODO: Support more than just a subset of the JWK dict, or find a way to
-                        // stringify via SM internals
-                        let Some(k) = key.k else {
-                            promise.reject_error(Error::Syntax, CanGc::note());
-                            return;
-                        };
-                        let Some(alg) = key.alg else {
-                            promise.reject_error(Error::Syntax, CanGc::note());
-                            return;
-                        };
-                        let Some(ext) = key.ext else {
-                            promise.reject_error(Error::Syntax, CanGc::note());
-                            return;
-                        };
-                        let Some(key_ops) = key.key_ops else {
-                            promise.reject_error(Error::Syntax, CanGc::note());
-                            return;
-                        };
-                        let key_ops_str = key_ops.iter().map(|op| op.to_string()).collect::<Vec<String>>();
-                        format!("{{
-                            \"kty\": \"oct\",
-                            \"k\": \"{}\",
-                            \"alg\": \"{}\",
-                            \"ext\": {},
-                            \"key_ops\": {:?}
-                        }}", k, alg, ext, key_ops_str)
-                        .into_bytes()
-                    },
+                    ExportedKey::Raw(k) => k,
+                    // The spec states to convert this to an ECMAscript object and stringify it, but since we know
+                    // that the output will be a string of JSON we can just construct it directly
+                    ExportedKey::Jwk(key) => jwk_to_json(&key),
                 };
 
                 let cx = GlobalScope::get_cx();
@@ -966,6 +987,11 @@ impl SubtleCryptoMethods<crate::DomTypeHolder> for SubtleCrypto {
                             &params, &wrapping_key, &bytes, cx, array_buffer_ptr.handle_mut(), CanGc::note()
                         )
                     },
+                    KeyWrapAlgorithm::RsaOaep(params) => {
+                        subtle.encrypt_rsa_oaep(
+                            &params, &wrapping_key, &bytes, cx, array_buffer_ptr.handle_mut(), CanGc::note()
+                        )
+                    },
                 };
 
                 match result {
@@ -1055,6 +1081,12 @@ impl SubtleCryptoMethods<crate::DomTypeHolder> for SubtleCrypto {
                             CanGc::note()
                         )
                     },
+                    KeyWrapAlgorithm::RsaOaep(params) => {
+                        subtle.decrypt_rsa_oaep(
+                            &params, &unwrapping_key, &wrapped_key_bytes, cx, array_buffer_ptr.handle_mut(),
+                            CanGc::note()
+                        )
+                    },
                 };
 
                 let bytes = match result {
@@ -1065,11 +1097,11 @@ impl SubtleCryptoMethods<crate::DomTypeHolder> for SubtleCrypto {
                     },
                 };
 
-                let import_key_bytes = match format {
-                    KeyFormat::Raw | KeyFormat::Spki | KeyFormat::Pkcs8 => bytes,
+                let (format, import_key_bytes) = match format {
+                    KeyFormat::Raw | KeyFormat::Spki | KeyFormat::Pkcs8 => (format, bytes),
                     KeyFormat::Jwk => {
-                        match parse_jwk(&bytes, normalized_key_algorithm.clone(), extractable, &key_usages) {
-                            Ok(bytes) => bytes,
+                        match parse_jwk(&bytes, &normalized_key_algorithm, extractable, &key_usages) {
+                            Ok(key_material) => key_material,
                             Err(e) => {
                                 promise.reject_error(e, CanGc::note());
                                 return;
@@ -1321,6 +1353,77 @@ impl SubtlePbkdf2Params {
     }
 }
 
+/// <https://w3c.github.io/webcrypto/#dfn-RsaHashedKeyGenParams>
+struct SubtleRsaHashedKeyGenParams {
+    /// <https://w3c.github.io/webcrypto/#dfn-RsaKeyGenParams-modulusLength>
+    modulus_length: u32,
+
+    /// <https://w3c.github.io/webcrypto/#dfn-RsaKeyGenParams-publicExponent>
+    public_exponent: Vec<u8>,
+
+    /// <https://w3c.github.io/webcrypto/#dfn-RsaHashedKeyGenParams-hash>
+    hash: DigestAlgorithm,
+}
+
+impl SubtleRsaHashedKeyGenParams {
+    fn new(cx: JSContext, params: RootedTraceableBox<RsaHashedKeyGenParams>) -> Fallible<Self> {
+        let params = Self {
+            modulus_length: params.parent.modulusLength,
+            public_exponent: params.parent.publicExponent.to_vec(),
+            hash: normalize_algorithm_for_digest(cx, &params.hash)?,
+        };
+        Ok(params)
+    }
+}
+
+/// <https://w3c.github.io/webcrypto/#dfn-RsaOaepParams>
+#[derive(Clone, Debug)]
+pub(crate) struct SubtleRsaOaepParams {
+    /// <https://w3c.github.io/webcrypto/#dfn-RsaOaepParams-label>
+    label: Option<Vec<u8>>,
+}
+
+impl From<RootedTraceableBox<RsaOaepParams>> for SubtleRsaOaepParams {
+    fn from(params: RootedTraceableBox<RsaOaepParams>) -> Self {
+        let label = params.label.as_ref().map(|label| match label {
+            ArrayBufferViewOrArrayBuffer::ArrayBufferView(view) => view.to_vec(),
+            ArrayBufferViewOrArrayBuffer::ArrayBuffer(buffer) => buffer.to_vec(),
+        });
+        SubtleRsaOaepParams { label }
+    }
+}
+
+/// The RSA algorithms, which share their key formats.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RsaAlgorithm {
+    /// <https://w3c.github.io/webcrypto/#rsassa-pkcs1>
+    RsassaPkcs1v15,
+
+    /// <https://w3c.github.io/webcrypto/#rsa-pss>
+    RsaPss,
+
+    /// <https://w3c.github.io/webcrypto/#rsa-oaep>
+    RsaOaep,
+}
+
+/// The elliptic curve algorithms, which share their key formats.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum EcAlgorithm {
+    /// <https://w3c.github.io/webcrypto/#ecdsa>
+    Ecdsa,
+
+    /// <https://w3c.github.io/webcrypto/#ecdh>
+    Ecdh,
+}
+
+/// <https://w3c.github.io/webcrypto/#dfn-NamedCurve>
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum NamedCurve {
+    P256,
+    P384,
+    P521,
+}
+
 enum GetKeyLengthAlgorithm {
     Aes(u16),
     Hmac(SubtleHmacImportParams),
@@ -1353,6 +1456,10 @@ enum ImportKeyAlgorithm {
     Hmac(SubtleHmacImportParams),
     Pbkdf2,
     Hkdf,
+    Rsa(RsaAlgorithm, DigestAlgorithm),
+    Ec(EcAlgorithm, NamedCurve),
+    Ed25519,
+    X25519,
 }
 
 /// A normalized algorithm returned by [`normalize_algorithm`] with operation `"deriveBits"`
@@ -1361,6 +1468,10 @@ enum ImportKeyAlgorithm {
 enum DeriveBitsAlgorithm {
     Pbkdf2(SubtlePbkdf2Params),
     Hkdf(SubtleHkdfParams),
+    /// The public key of the other party
+    Ecdh(Trusted<CryptoKey>),
+    /// The public key of the other party
+    X25519(Trusted<CryptoKey>),
 }
 
 /// A normalized algorithm returned by [`normalize_algorithm`] with operation `"encrypt"` or `"decrypt"`
@@ -1371,6 +1482,7 @@ enum EncryptionAlgorithm {
     AesCbc(SubtleAesCbcParams),
     AesCtr(SubtleAesCtrParams),
     AesGcm(SubtleAesGcmParams),
+    RsaOaep(SubtleRsaOaepParams),
 }
 
 /// A normalized algorithm returned by [`normalize_algorithm`] with operation `"sign"` or `"verify"`
@@ -1378,6 +1490,12 @@ enum EncryptionAlgorithm {
 /// [`normalize_algorithm`]: https://w3c.github.io/webcrypto/#algorithm-normalization-normalize-an-algorithm
 enum SignatureAlgorithm {
     Hmac,
+    RsassaPkcs1v15,
+    /// The salt length
+    RsaPss(u32),
+    /// The hash function
+    Ecdsa(DigestAlgorithm),
+    Ed25519,
 }
 
 /// A normalized algorithm returned by [`normalize_algorithm`] with operation `"generateKey"`
@@ -1386,6 +1504,16 @@ enum SignatureAlgorithm {
 enum KeyGenerationAlgorithm {
     Aes(SubtleAesKeyGenParams),
     Hmac(SubtleHmacKeyGenParams),
+    Rsa(RsaAlgorithm, SubtleRsaHashedKeyGenParams),
+    Ec(EcAlgorithm, NamedCurve),
+    Ed25519,
+    X25519,
+}
+
+/// The result of generating a key, which is a key pair for asymmetric algorithms.
+enum GeneratedKey {
+    Key(DomRoot<CryptoKey>),
+    KeyPair(CryptoKeyPair),
 }
 
 /// A normalized algorithm returned by [`normalize_algorithm`] with operation `"wrapKey"` or `"unwrapKey"`
@@ -1397,6 +1525,7 @@ enum KeyWrapAlgorithm {
     AesCbc(SubtleAesCbcParams),
     AesCtr(SubtleAesCtrParams),
     AesGcm(SubtleAesGcmParams),
+    RsaOaep(SubtleRsaOaepParams),
 }
 
 macro_rules! value_from_js_object {
@@ -1409,6 +1538,15 @@ macro_rules! value_from_js_object {
     }};
 }
 
+/// Returns the registered name of the algorithm that matches `name` in an ASCII case-insensitive manner.
+fn registered_algorithm_name(name: &str) -> Result<&'static str, Error> {
+    SUPPORTED_ALGORITHMS
+        .iter()
+        .copied()
+        .find(|algorithm| algorithm.eq_ignore_ascii_case(name))
+        .ok_or(Error::NotSupported)
+}
+
 /// <https://w3c.github.io/webcrypto/#algorithm-normalization-normalize-an-algorithm> with operation `"get key length"`
 fn normalize_algorithm_for_get_key_length(
     cx: JSContext,
@@ -1479,25 +1617,37 @@ fn normalize_algorithm_for_import_key(
             rooted!(in(*cx) let value = ObjectValue(obj.get()));
             let algorithm = value_from_js_object!(Algorithm, cx, value);
 
-            let name = algorithm.name.str().to_uppercase();
+            let name = registered_algorithm_name(algorithm.name.str())?;
             if name == ALG_HMAC {
                 let params = value_from_js_object!(HmacImportParams, cx, value);
                 let subtle_params = SubtleHmacImportParams::new(cx, params)?;
                 return Ok(ImportKeyAlgorithm::Hmac(subtle_params));
             }
+            if let Some(rsa_algorithm) = RsaAlgorithm::from_name(name) {
+                let params = value_from_js_object!(RsaHashedImportParams, cx, value);
+                let hash = normalize_algorithm_for_digest(cx, &params.hash)?;
+                return Ok(ImportKeyAlgorithm::Rsa(rsa_algorithm, hash));
+            }
+            if let Some(ec_algorithm) = EcAlgorithm::from_name(name) {
+                let params = value_from_js_object!(EcKeyImportParams, cx, value);
+                let named_curve = NamedCurve::from_name(params.namedCurve.str())?;
+                return Ok(ImportKeyAlgorithm::Ec(ec_algorithm, named_curve));
+            }
 
             name
         },
-        AlgorithmIdentifier::String(name) => name.str().to_uppercase(),
+        AlgorithmIdentifier::String(name) => registered_algorithm_name(name.str())?,
     };
 
-    let normalized_algorithm = match name.as_str() {
+    let normalized_algorithm = match name {
         ALG_AES_CBC => ImportKeyAlgorithm::AesCbc,
         ALG_AES_CTR => ImportKeyAlgorithm::AesCtr,
         ALG_AES_KW => ImportKeyAlgorithm::AesKw,
         ALG_AES_GCM => ImportKeyAlgorithm::AesGcm,
         ALG_PBKDF2 => ImportKeyAlgorithm::Pbkdf2,
         ALG_HKDF => ImportKeyAlgorithm::Hkdf,
+        ALG_ED25519 => ImportKeyAlgorithm::Ed25519,
+        ALG_X25519 => ImportKeyAlgorithm::X25519,
         _ => return Err(Error::NotSupported),
     };
 
@@ -1525,6 +1675,12 @@ fn normalize_algorithm_for_derive_bits(
         let params = value_from_js_object!(HkdfParams, cx, value);
         let subtle_params = SubtleHkdfParams::new(cx, params)?;
         DeriveBitsAlgorithm::Hkdf(subtle_params)
+    } else if algorithm.name.str().eq_ignore_ascii_case(ALG_ECDH) {
+        let params = value_from_js_object!(EcdhKeyDeriveParams, cx, value);
+        DeriveBitsAlgorithm::Ecdh(Trusted::new(&*params.public))
+    } else if algorithm.name.str().eq_ignore_ascii_case(ALG_X25519) {
+        let params = value_from_js_object!(EcdhKeyDeriveParams, cx, value);
+        DeriveBitsAlgorithm::X25519(Trusted::new(&*params.public))
     } else {
         return Err(Error::NotSupported);
     };
@@ -1555,6 +1711,9 @@ fn normalize_algorithm_for_encrypt_or_decrypt(
     } else if name.eq_ignore_ascii_case(ALG_AES_GCM) {
         let params = value_from_js_object!(AesGcmParams, cx, value);
         EncryptionAlgorithm::AesGcm(params.into())
+    } else if name.eq_ignore_ascii_case(ALG_RSA_OAEP) {
+        let params = value_from_js_object!(RsaOaepParams, cx, value);
+        EncryptionAlgorithm::RsaOaep(params.into())
     } else {
         return Err(Error::NotSupported);
     };
@@ -1573,13 +1732,26 @@ fn normalize_algorithm_for_sign_or_verify(
             rooted!(in(*cx) let value = ObjectValue(obj.get()));
             let algorithm = value_from_js_object!(Algorithm, cx, value);
 
-            algorithm.name.str().to_uppercase()
+            let name = registered_algorithm_name(algorithm.name.str())?;
+            if name == ALG_RSA_PSS {
+                let params = value_from_js_object!(RsaPssParams, cx, value);
+                return Ok(SignatureAlgorithm::RsaPss(params.saltLength));
+            }
+            if name == ALG_ECDSA {
+                let params = value_from_js_object!(EcdsaParams, cx, value);
+                let hash = normalize_algorithm_for_digest(cx, &params.hash)?;
+                return Ok(SignatureAlgorithm::Ecdsa(hash));
+            }
+
+            name
         },
-        AlgorithmIdentifier::String(name) => name.str().to_uppercase(),
+        AlgorithmIdentifier::String(name) => registered_algorithm_name(name.str())?,
     };
 
-    let normalized_algorithm = match name.as_str() {
+    let normalized_algorithm = match name {
         ALG_HMAC => SignatureAlgorithm::Hmac,
+        ALG_RSASSA_PKCS1 => SignatureAlgorithm::RsassaPkcs1v15,
+        ALG_ED25519 => SignatureAlgorithm::Ed25519,
         _ => return Err(Error::NotSupported),
     };
 
@@ -1591,9 +1763,16 @@ fn normalize_algorithm_for_generate_key(
     cx: JSContext,
     algorithm: &AlgorithmIdentifier,
 ) -> Result<KeyGenerationAlgorithm, Error> {
-    let AlgorithmIdentifier::Object(obj) = algorithm else {
-        // All algorithms that support "generateKey" require additional parameters
-        return Err(Error::NotSupported);
+    let obj = match algorithm {
+        AlgorithmIdentifier::Object(obj) => obj,
+        AlgorithmIdentifier::String(name) => {
+            // Only the algorithms without additional parameters can be given by name
+            return match registered_algorithm_name(name.str())? {
+                ALG_ED25519 => Ok(KeyGenerationAlgorithm::Ed25519),
+                ALG_X25519 => Ok(KeyGenerationAlgorithm::X25519),
+                _ => Err(Error::NotSupported),
+            };
+        },
     };
 
     rooted!(in(*cx) let value = ObjectValue(obj.get()));
@@ -1611,6 +1790,18 @@ fn normalize_algorithm_for_generate_key(
         let params = value_from_js_object!(HmacKeyGenParams, cx, value);
         let subtle_params = SubtleHmacKeyGenParams::new(cx, params)?;
         KeyGenerationAlgorithm::Hmac(subtle_params)
+    } else if let Some(rsa_algorithm) = RsaAlgorithm::from_name(name) {
+        let params = value_from_js_object!(RsaHashedKeyGenParams, cx, value);
+        let subtle_params = SubtleRsaHashedKeyGenParams::new(cx, params)?;
+        KeyGenerationAlgorithm::Rsa(rsa_algorithm, subtle_params)
+    } else if let Some(ec_algorithm) = EcAlgorithm::from_name(name) {
+        let params = value_from_js_object!(EcKeyGenParams, cx, value);
+        let named_curve = NamedCurve::from_name(params.namedCurve.str())?;
+        KeyGenerationAlgorithm::Ec(ec_algorithm, named_curve)
+    } else if name.eq_ignore_ascii_case(ALG_ED25519) {
+        KeyGenerationAlgorithm::Ed25519
+    } else if name.eq_ignore_ascii_case(ALG_X25519) {
+        KeyGenerationAlgorithm::X25519
     } else {
         return Err(Error::NotSupported);
     };
@@ -1656,6 +1847,13 @@ fn normalize_algorithm_for_key_wrap(
             rooted!(in(*cx) let value = ObjectValue(obj.get()));
             KeyWrapAlgorithm::AesGcm(value_from_js_object!(AesGcmParams, cx, value).into())
         },
+        ALG_RSA_OAEP => {
+            let AlgorithmIdentifier::Object(obj) = algorithm else {
+                return Err(Error::Syntax);
+            };
+            rooted!(in(*cx) let value = ObjectValue(obj.get()));
+            KeyWrapAlgorithm::RsaOaep(value_from_js_object!(RsaOaepParams, cx, value).into())
+        },
         _ => return Err(Error::NotSupported),
     };
 
@@ -2253,12 +2451,12 @@ impl SubtleCrypto {
 
     /// <https://w3c.github.io/webcrypto/#aes-cbc-operations>
     /// <https://w3c.github.io/webcrypto/#aes-ctr-operations>
-    fn export_key_aes(&self, format: KeyFormat, key: &CryptoKey) -> Result<AesExportedKey, Error> {
+    fn export_key_aes(&self, format: KeyFormat, key: &CryptoKey) -> Result<ExportedKey, Error> {
         match format {
             KeyFormat::Raw => match key.handle() {
-                Handle::Aes128(key_data) => Ok(AesExportedKey::Raw(key_data.as_slice().to_vec())),
-                Handle::Aes192(key_data) => Ok(AesExportedKey::Raw(key_data.as_slice().to_vec())),
-                Handle::Aes256(key_data) => Ok(AesExportedKey::Raw(key_data.as_slice().to_vec())),
+                Handle::Aes128(key_data) => Ok(ExportedKey::Raw(key_data.as_slice().to_vec())),
+                Handle::Aes192(key_data) => Ok(ExportedKey::Raw(key_data.as_slice().to_vec())),
+                Handle::Aes256(key_data) => Ok(ExportedKey::Raw(key_data.as_slice().to_vec())),
                 _ => Err(Error::Data),
             },
             KeyFormat::Jwk => {
@@ -2274,32 +2472,12 @@ impl SubtleCrypto {
                     },
                     _ => return Err(Error::Data),
                 };
-                let key_ops = key
-                    .usages()
-                    .iter()
-                    .map(|usage| DOMString::from(usage.as_str()))
-                    .collect::<Vec<DOMString>>();
                 let jwk = JsonWebKey {
                     alg: Some(alg),
-                    crv: None,
-                    d: None,
-                    dp: None,
-                    dq: None,
-                    e: None,
-                    ext: Some(key.Extractable()),
                     k: Some(k),
-                    key_ops: Some(key_ops),
-                    kty: Some(DOMString::from("oct")),
-                    n: None,
-                    oth: None,
-                    p: None,
-                    q: None,
-                    qi: None,
-                    use_: None,
-                    x: None,
-                    y: None,
+                    ..new_exported_jwk(key, "oct")
                 };
-                Ok(AesExportedKey::Jwk(Box::new(jwk)))
+                Ok(ExportedKey::Jwk(Box::new(jwk)))
             },
             _ => Err(Error::NotSupported),
         }
@@ -2611,103 +2789,875 @@ impl SubtleCrypto {
         // Step 9. Return key.
         Ok(key)
     }
-}
-
-pub(crate) enum AesExportedKey {
-    Raw(Vec<u8>),
-    Jwk(Box<JsonWebKey>),
-}
+    /// <https://w3c.github.io/webcrypto/#hmac-operations>
+    fn export_key_hmac(&self, format: KeyFormat, key: &CryptoKey) -> Result<ExportedKey, Error> {
+        match format {
+            KeyFormat::Raw => Ok(ExportedKey::Raw(key.handle().as_bytes().to_vec())),
+            KeyFormat::Jwk => {
+                let cx = GlobalScope::get_cx();
+                rooted!(in(*cx) let mut algorithm_slot = ObjectValue(key.Algorithm(cx).as_ptr()));
+                let params = value_from_js_object!(HmacKeyAlgorithm, cx, algorithm_slot);
+                let alg = match params.hash.name.str() {
+                    ALG_SHA1 => "HS1",
+                    ALG_SHA256 => "HS256",
+                    ALG_SHA384 => "HS384",
+                    ALG_SHA512 => "HS512",
+                    _ => return Err(Error::NotSupported),
+                };
+                let jwk = JsonWebKey {
+                    alg: Some(DOMString::from(alg)),
+                    k: Some(base64_url_encode(key.handle().as_bytes())),
+                    ..new_exported_jwk(key, "oct")
+                };
+                Ok(ExportedKey::Jwk(Box::new(jwk)))
+            },
+            _ => Err(Error::NotSupported),
+        }
+    }
 
-fn data_to_jwk_params(alg: &str, size: &str, key: &[u8]) -> (DOMString, DOMString) {
-    let jwk_alg = match alg {
-        ALG_AES_CBC => DOMString::from(format!("A{}CBC", size)),
-        ALG_AES_CTR => DOMString::from(format!("A{}CTR", size)),
-        ALG_AES_KW => DOMString::from(format!("A{}KW", size)),
-        ALG_AES_GCM => DOMString::from(format!("A{}GCM", size)),
-        _ => unreachable!(),
-    };
-    let data = base64::engine::general_purpose::STANDARD_NO_PAD.encode(key);
-    (jwk_alg, DOMString::from(data))
-}
+    /// <https://w3c.github.io/webcrypto/#dfn-SubtleCrypto-method-exportKey>
+    fn export_key(&self, format: KeyFormat, key: &CryptoKey) -> Result<ExportedKey, Error> {
+        match key.handle() {
+            Handle::Aes128(_) | Handle::Aes192(_) | Handle::Aes256(_) => {
+                self.export_key_aes(format, key)
+            },
+            Handle::Hmac(_) => self.export_key_hmac(format, key),
+            Handle::RsaPublicKey(_) | Handle::RsaPrivateKey(_) => self.export_key_rsa(format, key),
+            Handle::EcPublicKey(_) | Handle::EcPrivateKey(_) => self.export_key_ec(format, key),
+            Handle::Ed25519PublicKey(_) |
+            Handle::Ed25519PrivateKey(_) |
+            Handle::X25519PublicKey(_) |
+            Handle::X25519PrivateKey(_) => self.export_key_okp(format, key),
+            Handle::Pbkdf2(_) | Handle::Hkdf(_) => Err(Error::NotSupported),
+        }
+    }
 
-trait AlgorithmFromName {
-    fn from_name(name: DOMString, out: MutableHandleObject, cx: JSContext);
-}
+    /// Creates the public and the private key of a newly generated key pair.
+    #[allow(clippy::too_many_arguments)]
+    fn new_key_pair(
+        &self,
+        name: &str,
+        algorithm_object: HandleObject,
+        public_handle: Handle,
+        private_handle: Handle,
+        extractable: bool,
+        usages: &[KeyUsage],
+        can_gc: CanGc,
+    ) -> CryptoKeyPair {
+        let (public_usages, private_usages) = asymmetric_key_usages(name);
 
-impl AlgorithmFromName for KeyAlgorithm {
-    /// Fill the object referenced by `out` with an [KeyAlgorithm]
-    /// of the specified name and size.
-    #[allow(unsafe_code)]
-    fn from_name(name: DOMString, out: MutableHandleObject, cx: JSContext) {
-        let key_algorithm = Self { name };
+        // The public key is always extractable, and each key only gets the usages that apply to it.
+        let public_key = CryptoKey::new(
+            &self.global(),
+            KeyType::Public,
+            true,
+            DOMString::from(name),
+            algorithm_object,
+            usages
+                .iter()
+                .copied()
+                .filter(|usage| public_usages.contains(usage))
+                .collect(),
+            public_handle,
+            can_gc,
+        );
+        let private_key = CryptoKey::new(
+            &self.global(),
+            KeyType::Private,
+            extractable,
+            DOMString::from(name),
+            algorithm_object,
+            usages
+                .iter()
+                .copied()
+                .filter(|usage| private_usages.contains(usage))
+                .collect(),
+            private_handle,
+            can_gc,
+        );
 
-        unsafe {
-            key_algorithm.to_jsobject(*cx, out);
+        CryptoKeyPair {
+            publicKey: Some(public_key),
+            privateKey: Some(private_key),
         }
     }
-}
-
-trait AlgorithmFromLengthAndHash {
-    fn from_length_and_hash(
-        length: u32,
-        hash: DigestAlgorithm,
-        out: MutableHandleObject,
-        cx: JSContext,
-    );
-}
 
-impl AlgorithmFromLengthAndHash for HmacKeyAlgorithm {
+    /// <https://w3c.github.io/webcrypto/#rsassa-pkcs1-operations>
+    /// <https://w3c.github.io/webcrypto/#rsa-pss-operations>
+    /// <https://w3c.github.io/webcrypto/#rsa-oaep-operations>
     #[allow(unsafe_code)]
-    fn from_length_and_hash(
-        length: u32,
-        hash: DigestAlgorithm,
-        out: MutableHandleObject,
-        cx: JSContext,
-    ) {
-        let hmac_key_algorithm = Self {
-            parent: KeyAlgorithm {
-                name: ALG_HMAC.into(),
-            },
-            length,
-            hash: KeyAlgorithm { name: hash.name() },
+    fn generate_key_rsa(
+        &self,
+        usages: Vec<KeyUsage>,
+        algorithm: RsaAlgorithm,
+        params: &SubtleRsaHashedKeyGenParams,
+        extractable: bool,
+        can_gc: CanGc,
+    ) -> Result<CryptoKeyPair, Error> {
+        // Step 1. If usages contains an entry which is not allowed for the algorithm, then throw a SyntaxError.
+        check_key_pair_usages(algorithm.name(), &usages)?;
+
+        // Step 2. Generate an RSA key pair, as defined in [RFC3447], with RSA modulus length equal to the
+        // modulusLength member of normalizedAlgorithm and RSA public exponent equal to the publicExponent
+        // member of normalizedAlgorithm.
+        // Step 3. If performing the operation results in an error, then throw an OperationError.
+        // NOTE: Our backend only generates keys with the public exponent 65537 and common modulus lengths.
+        let key_size = match params.modulus_length {
+            2048 => KeySize::Rsa2048,
+            3072 => KeySize::Rsa3072,
+            4096 => KeySize::Rsa4096,
+            8192 => KeySize::Rsa8192,
+            _ => return Err(Error::Operation),
         };
-
-        unsafe {
-            hmac_key_algorithm.to_jsobject(*cx, out);
+        if strip_leading_zeros(&params.public_exponent) != [0x01, 0x00, 0x01] {
+            return Err(Error::Operation);
         }
-    }
-}
+        let key_pair = RsaKeyPair::generate(key_size).map_err(|_| Error::Operation)?;
+        let private_key = key_pair
+            .as_der()
+            .map_err(|_| Error::Operation)?
+            .as_ref()
+            .to_vec();
+        let components = RsaKeyComponents::from_pkcs8(&private_key)?;
+
+        // Step 4. Let algorithm be a new RsaHashedKeyAlgorithm dictionary.
+        // Step 5. Set the name attribute of algorithm to the name of the algorithm.
+        // Step 6. Set the modulusLength attribute of algorithm to equal the modulusLength member of
+        // normalizedAlgorithm.
+        // Step 7. Set the publicExponent attribute of algorithm to equal the publicExponent member of
+        // normalizedAlgorithm.
+        // Step 8. Set the hash attribute of algorithm to the hash member of normalizedAlgorithm.
+        let cx = GlobalScope::get_cx();
+        rooted!(in(*cx) let mut algorithm_object = unsafe { JS_NewObject(*cx, ptr::null()) });
+        assert!(!algorithm_object.is_null());
+        RsaHashedKeyAlgorithm::from_rsa_parameters(
+            DOMString::from(algorithm.name()),
+            components.modulus_length(),
+            &components.public_exponent,
+            params.hash,
+            algorithm_object.handle_mut(),
+            cx,
+            can_gc,
+        );
 
-trait AlgorithmFromNameAndSize {
-    fn from_name_and_size(name: DOMString, size: u16, out: MutableHandleObject, cx: JSContext);
-}
+        // Step 9-18. Create the public and the private key, and return them as a CryptoKeyPair.
+        Ok(self.new_key_pair(
+            algorithm.name(),
+            algorithm_object.handle(),
+            Handle::RsaPublicKey(components.to_spki()),
+            Handle::RsaPrivateKey(private_key),
+            extractable,
+            &usages,
+            can_gc,
+        ))
+    }
 
-impl AlgorithmFromNameAndSize for AesKeyAlgorithm {
-    /// Fill the object referenced by `out` with an [AesKeyAlgorithm]
-    /// of the specified name and size.
+    /// <https://w3c.github.io/webcrypto/#ecdsa-operations>
+    /// <https://w3c.github.io/webcrypto/#ecdh-operations>
     #[allow(unsafe_code)]
-    fn from_name_and_size(name: DOMString, size: u16, out: MutableHandleObject, cx: JSContext) {
-        let key_algorithm = Self {
-            parent: KeyAlgorithm { name },
-            length: size,
-        };
+    fn generate_key_ec(
+        &self,
+        usages: Vec<KeyUsage>,
+        algorithm: EcAlgorithm,
+        named_curve: NamedCurve,
+        extractable: bool,
+        can_gc: CanGc,
+    ) -> Result<CryptoKeyPair, Error> {
+        // Step 1. If usages contains an entry which is not allowed for the algorithm, then throw a SyntaxError.
+        check_key_pair_usages(algorithm.name(), &usages)?;
+
+        // Step 2. Generate an Elliptic Curve key pair on the curve given by the namedCurve member of
+        // normalizedAlgorithm.
+        // Step 3. If performing the key generation operation results in an error, then throw an OperationError.
+        let private_key =
+            EcdsaKeyPair::generate_pkcs8(named_curve.signing_algorithm(), &SystemRandom::new())
+                .map_err(|_| Error::Operation)?;
+        let key_pair =
+            EcdsaKeyPair::from_pkcs8(named_curve.signing_algorithm(), private_key.as_ref())
+                .map_err(|_| Error::Operation)?;
+
+        // Step 4. Let algorithm be a new EcKeyAlgorithm object.
+        // Step 5. Set the name attribute of algorithm to the name of the algorithm.
+        // Step 6. Set the namedCurve attribute of algorithm to equal the namedCurve member of normalizedAlgorithm.
+        let cx = GlobalScope::get_cx();
+        rooted!(in(*cx) let mut algorithm_object = unsafe { JS_NewObject(*cx, ptr::null()) });
+        assert!(!algorithm_object.is_null());
+        EcKeyAlgorithm::from_name_and_curve(
+            DOMString::from(algorithm.name()),
+            named_curve,
+            algorithm_object.handle_mut(),
+            cx,
+        );
 
-        unsafe {
-            key_algorithm.to_jsobject(*cx, out);
-        }
+        // Step 7-16. Create the public and the private key, and return them as a CryptoKeyPair.
+        Ok(self.new_key_pair(
+            algorithm.name(),
+            algorithm_object.handle(),
+            Handle::EcPublicKey(key_pair.public_key().as_ref().to_vec()),
+            Handle::EcPrivateKey(private_key.as_ref().to_vec()),
+            extractable,
+            &usages,
+            can_gc,
+        ))
     }
-}
 
-impl SubtleHkdfParams {
-    /// <https://w3c.github.io/webcrypto/#hkdf-operations>
-    fn derive_bits(&self, key: &CryptoKey, length: Option<u32>) -> Result<Vec<u8>, Error> {
-        // Step 1. If length is null or zero, or is not a multiple of 8, then throw an OperationError.
-        let Some(length) = length else {
-            return Err(Error::Operation);
-        };
-        if length == 0 || length % 8 != 0 {
-            return Err(Error::Operation);
-        };
+    /// <https://w3c.github.io/webcrypto/#ed25519-operations>
+    /// <https://w3c.github.io/webcrypto/#x25519-operations>
+    #[allow(unsafe_code)]
+    fn generate_key_okp(
+        &self,
+        usages: Vec<KeyUsage>,
+        name: &str,
+        extractable: bool,
+        can_gc: CanGc,
+    ) -> Result<CryptoKeyPair, Error> {
+        // Step 1. If usages contains an entry which is not allowed for the algorithm, then throw a SyntaxError.
+        check_key_pair_usages(name, &usages)?;
+
+        // Step 2. Generate an Ed25519 or X25519 key pair.
+        // Step 3. If the key generation step fails, then throw an OperationError.
+        let mut private_key = vec![0; 32];
+        self.rng.borrow_mut().fill_bytes(&mut private_key);
+        let public_key = okp_public_key(name, &private_key).map_err(|_| Error::Operation)?;
+
+        // Step 4. Let algorithm be a new KeyAlgorithm object.
+        // Step 5. Set the name attribute of algorithm to the name of the algorithm.
+        let cx = GlobalScope::get_cx();
+        rooted!(in(*cx) let mut algorithm_object = unsafe { JS_NewObject(*cx, ptr::null()) });
+        assert!(!algorithm_object.is_null());
+        KeyAlgorithm::from_name(DOMString::from(name), algorithm_object.handle_mut(), cx);
+
+        // Step 6-15. Create the public and the private key, and return them as a CryptoKeyPair.
+        Ok(self.new_key_pair(
+            name,
+            algorithm_object.handle(),
+            okp_key_handle(name, KeyType::Public, public_key),
+            okp_key_handle(name, KeyType::Private, private_key),
+            extractable,
+            &usages,
+            can_gc,
+        ))
+    }
+
+    /// <https://w3c.github.io/webcrypto/#rsassa-pkcs1-operations>
+    /// <https://w3c.github.io/webcrypto/#rsa-pss-operations>
+    /// <https://w3c.github.io/webcrypto/#rsa-oaep-operations>
+    #[allow(unsafe_code, clippy::too_many_arguments)]
+    fn import_key_rsa(
+        &self,
+        algorithm: RsaAlgorithm,
+        hash: DigestAlgorithm,
+        format: KeyFormat,
+        key_data: &[u8],
+        extractable: bool,
+        usages: Vec<KeyUsage>,
+        can_gc: CanGc,
+    ) -> Result<DomRoot<CryptoKey>, Error> {
+        // NOTE: JWKs have already been converted to spki or pkcs8 by the caller.
+        let (key_type, handle) = match format {
+            KeyFormat::Spki => {
+                // If usages contains an entry which is not allowed for a public key, then throw a SyntaxError.
+                check_imported_key_usages(algorithm.name(), KeyType::Public, &usages)?;
+                (KeyType::Public, Handle::RsaPublicKey(key_data.to_vec()))
+            },
+            KeyFormat::Pkcs8 => {
+                // If usages contains an entry which is not allowed for a private key, then throw a SyntaxError.
+                check_imported_key_usages(algorithm.name(), KeyType::Private, &usages)?;
+
+                // If the private key cannot be used by our backend, then throw a DataError.
+                RsaKeyPair::from_pkcs8(key_data).map_err(|_| Error::Data)?;
+                (KeyType::Private, Handle::RsaPrivateKey(key_data.to_vec()))
+            },
+            _ => return Err(Error::NotSupported),
+        };
+
+        // If parsing the key fails, then throw a DataError.
+        let components = RsaKeyComponents::from_handle(&handle)?;
+
+        let cx = GlobalScope::get_cx();
+        rooted!(in(*cx) let mut algorithm_object = unsafe { JS_NewObject(*cx, ptr::null()) });
+        assert!(!algorithm_object.is_null());
+        RsaHashedKeyAlgorithm::from_rsa_parameters(
+            DOMString::from(algorithm.name()),
+            components.modulus_length(),
+            &components.public_exponent,
+            hash,
+            algorithm_object.handle_mut(),
+            cx,
+            can_gc,
+        );
+
+        let key = CryptoKey::new(
+            &self.global(),
+            key_type,
+            extractable,
+            DOMString::from(algorithm.name()),
+            algorithm_object.handle(),
+            usages,
+            handle,
+            can_gc,
+        );
+
+        Ok(key)
+    }
+
+    /// <https://w3c.github.io/webcrypto/#ecdsa-operations>
+    /// <https://w3c.github.io/webcrypto/#ecdh-operations>
+    #[allow(unsafe_code, clippy::too_many_arguments)]
+    fn import_key_ec(
+        &self,
+        algorithm: EcAlgorithm,
+        named_curve: NamedCurve,
+        format: KeyFormat,
+        key_data: &[u8],
+        extractable: bool,
+        usages: Vec<KeyUsage>,
+        can_gc: CanGc,
+    ) -> Result<DomRoot<CryptoKey>, Error> {
+        // NOTE: JWKs have already been converted to raw or pkcs8 by the caller.
+        let (key_type, handle) = match format {
+            KeyFormat::Spki | KeyFormat::Raw => {
+                // If usages contains an entry which is not allowed for a public key, then throw a SyntaxError.
+                check_imported_key_usages(algorithm.name(), KeyType::Public, &usages)?;
+
+                let point = if format == KeyFormat::Spki {
+                    decode_spki(key_data, &named_curve.algorithm_identifier())?
+                } else {
+                    key_data
+                };
+                (
+                    KeyType::Public,
+                    Handle::EcPublicKey(named_curve.validate_point(point)?.to_vec()),
+                )
+            },
+            KeyFormat::Pkcs8 => {
+                // If usages contains an entry which is not allowed for a private key, then throw a SyntaxError.
+                check_imported_key_usages(algorithm.name(), KeyType::Private, &usages)?;
+
+                // If the key is not a valid key on the curve given by the namedCurve member of
+                // normalizedAlgorithm, then throw a DataError.
+                EcdsaKeyPair::from_pkcs8(named_curve.signing_algorithm(), key_data)
+                    .map_err(|_| Error::Data)?;
+                decode_ec_private_key(named_curve, key_data)?;
+                (KeyType::Private, Handle::EcPrivateKey(key_data.to_vec()))
+            },
+            _ => return Err(Error::NotSupported),
+        };
+
+        let cx = GlobalScope::get_cx();
+        rooted!(in(*cx) let mut algorithm_object = unsafe { JS_NewObject(*cx, ptr::null()) });
+        assert!(!algorithm_object.is_null());
+        EcKeyAlgorithm::from_name_and_curve(
+            DOMString::from(algorithm.name()),
+            named_curve,
+            algorithm_object.handle_mut(),
+            cx,
+        );
+
+        let key = CryptoKey::new(
+            &self.global(),
+            key_type,
+            extractable,
+            DOMString::from(algorithm.name()),
+            algorithm_object.handle(),
+            usages,
+            handle,
+            can_gc,
+        );
+
+        Ok(key)
+    }
+
+    /// <https://w3c.github.io/webcrypto/#ed25519-operations>
+    /// <https://w3c.github.io/webcrypto/#x25519-operations>
+    #[allow(unsafe_code)]
+    fn import_key_okp(
+        &self,
+        name: &str,
+        format: KeyFormat,
+        key_data: &[u8],
+        extractable: bool,
+        usages: Vec<KeyUsage>,
+        can_gc: CanGc,
+    ) -> Result<DomRoot<CryptoKey>, Error> {
+        // NOTE: JWKs have already been converted to raw or pkcs8 by the caller.
+        let algorithm_identifier = okp_algorithm_identifier(name);
+        let (key_type, key) = match format {
+            KeyFormat::Spki | KeyFormat::Raw => {
+                // If usages contains an entry which is not allowed for a public key, then throw a SyntaxError.
+                check_imported_key_usages(name, KeyType::Public, &usages)?;
+
+                let public_key = if format == KeyFormat::Spki {
+                    decode_spki(key_data, algorithm_identifier)?
+                } else {
+                    key_data
+                };
+                if public_key.len() != 32 {
+                    return Err(Error::Data);
+                }
+                (KeyType::Public, public_key.to_vec())
+            },
+            KeyFormat::Pkcs8 => {
+                // If usages contains an entry which is not allowed for a private key, then throw a SyntaxError.
+                check_imported_key_usages(name, KeyType::Private, &usages)?;
+
+                // The private key is a CurvePrivateKey, as defined in [RFC8410]
+                let mut reader = DerReader::new(decode_pkcs8(key_data, algorithm_identifier)?);
+                let private_key = reader.read(DER_OCTET_STRING)?;
+                reader.finish()?;
+                if private_key.len() != 32 {
+                    return Err(Error::Data);
+                }
+                (KeyType::Private, private_key.to_vec())
+            },
+            _ => return Err(Error::NotSupported),
+        };
+
+        let cx = GlobalScope::get_cx();
+        rooted!(in(*cx) let mut algorithm_object = unsafe { JS_NewObject(*cx, ptr::null()) });
+        assert!(!algorithm_object.is_null());
+        KeyAlgorithm::from_name(DOMString::from(name), algorithm_object.handle_mut(), cx);
+
+        let key = CryptoKey::new(
+            &self.global(),
+            key_type,
+            extractable,
+            DOMString::from(name),
+            algorithm_object.handle(),
+            usages,
+            okp_key_handle(name, key_type, key),
+            can_gc,
+        );
+
+        Ok(key)
+    }
+
+    /// <https://w3c.github.io/webcrypto/#rsassa-pkcs1-operations>
+    /// <https://w3c.github.io/webcrypto/#rsa-pss-operations>
+    /// <https://w3c.github.io/webcrypto/#rsa-oaep-operations>
+    fn export_key_rsa(&self, format: KeyFormat, key: &CryptoKey) -> Result<ExportedKey, Error> {
+        match (format, key.handle()) {
+            (KeyFormat::Spki, Handle::RsaPublicKey(spki)) => Ok(ExportedKey::Raw(spki.clone())),
+            (KeyFormat::Pkcs8, Handle::RsaPrivateKey(pkcs8)) => Ok(ExportedKey::Raw(pkcs8.clone())),
+            // If the [[type]] internal slot of key does not match the format, then throw an InvalidAccessError.
+            (KeyFormat::Spki | KeyFormat::Pkcs8, _) => Err(Error::InvalidAccess),
+            (KeyFormat::Jwk, handle) => {
+                let algorithm =
+                    RsaAlgorithm::from_name(&key.algorithm()).ok_or(Error::NotSupported)?;
+                let hash = rsa_key_hash(GlobalScope::get_cx(), key)?;
+                let components = RsaKeyComponents::from_handle(handle)?;
+                let mut jwk = JsonWebKey {
+                    alg: Some(DOMString::from(algorithm.jwk_alg(hash))),
+                    n: Some(base64_url_encode(&components.modulus)),
+                    e: Some(base64_url_encode(&components.public_exponent)),
+                    ..new_exported_jwk(key, "RSA")
+                };
+                if let Some([d, p, q, dp, dq, qi]) = &components.private {
+                    jwk.d = Some(base64_url_encode(d));
+                    jwk.p = Some(base64_url_encode(p));
+                    jwk.q = Some(base64_url_encode(q));
+                    jwk.dp = Some(base64_url_encode(dp));
+                    jwk.dq = Some(base64_url_encode(dq));
+                    jwk.qi = Some(base64_url_encode(qi));
+                }
+                Ok(ExportedKey::Jwk(Box::new(jwk)))
+            },
+            (KeyFormat::Raw, _) => Err(Error::NotSupported),
+        }
+    }
+
+    /// <https://w3c.github.io/webcrypto/#ecdsa-operations>
+    /// <https://w3c.github.io/webcrypto/#ecdh-operations>
+    fn export_key_ec(&self, format: KeyFormat, key: &CryptoKey) -> Result<ExportedKey, Error> {
+        let named_curve = ec_key_named_curve(GlobalScope::get_cx(), key)?;
+        match (format, key.handle()) {
+            (KeyFormat::Spki, Handle::EcPublicKey(point)) => Ok(ExportedKey::Raw(encode_spki(
+                &named_curve.algorithm_identifier(),
+                point,
+            ))),
+            (KeyFormat::Raw, Handle::EcPublicKey(point)) => Ok(ExportedKey::Raw(point.clone())),
+            (KeyFormat::Pkcs8, Handle::EcPrivateKey(pkcs8)) => Ok(ExportedKey::Raw(pkcs8.clone())),
+            // If the [[type]] internal slot of key does not match the format, then throw an InvalidAccessError.
+            (KeyFormat::Spki | KeyFormat::Raw | KeyFormat::Pkcs8, _) => Err(Error::InvalidAccess),
+            (KeyFormat::Jwk, handle) => {
+                let (point, private_key) = match handle {
+                    Handle::EcPublicKey(point) => (point.clone(), None),
+                    Handle::EcPrivateKey(pkcs8) => {
+                        let key_pair =
+                            EcdsaKeyPair::from_pkcs8(named_curve.signing_algorithm(), pkcs8)
+                                .map_err(|_| Error::Operation)?;
+                        (
+                            key_pair.public_key().as_ref().to_vec(),
+                            Some(decode_ec_private_key(named_curve, pkcs8)?),
+                        )
+                    },
+                    _ => return Err(Error::Operation),
+                };
+                let (x, y) = named_curve.validate_point(&point)?[1..]
+                    .split_at(named_curve.coordinate_length());
+                let jwk = JsonWebKey {
+                    crv: Some(DOMString::from(named_curve.name())),
+                    x: Some(base64_url_encode(x)),
+                    y: Some(base64_url_encode(y)),
+                    d: private_key.map(|private_key| base64_url_encode(&private_key)),
+                    ..new_exported_jwk(key, "EC")
+                };
+                Ok(ExportedKey::Jwk(Box::new(jwk)))
+            },
+        }
+    }
+
+    /// <https://w3c.github.io/webcrypto/#ed25519-operations>
+    /// <https://w3c.github.io/webcrypto/#x25519-operations>
+    fn export_key_okp(&self, format: KeyFormat, key: &CryptoKey) -> Result<ExportedKey, Error> {
+        let name = key.algorithm();
+        let algorithm_identifier = okp_algorithm_identifier(&name);
+        match (format, key.handle()) {
+            (
+                KeyFormat::Spki,
+                Handle::Ed25519PublicKey(public_key) | Handle::X25519PublicKey(public_key),
+            ) => Ok(ExportedKey::Raw(encode_spki(
+                algorithm_identifier,
+                public_key,
+            ))),
+            (
+                KeyFormat::Raw,
+                Handle::Ed25519PublicKey(public_key) | Handle::X25519PublicKey(public_key),
+            ) => Ok(ExportedKey::Raw(public_key.clone())),
+            (
+                KeyFormat::Pkcs8,
+                Handle::Ed25519PrivateKey(private_key) | Handle::X25519PrivateKey(private_key),
+            ) => Ok(ExportedKey::Raw(encode_pkcs8(
+                algorithm_identifier,
+                &der_encode(DER_OCTET_STRING, private_key),
+            ))),
+            // If the [[type]] internal slot of key does not match the format, then throw an InvalidAccessError.
+            (KeyFormat::Spki | KeyFormat::Raw | KeyFormat::Pkcs8, _) => Err(Error::InvalidAccess),
+            (KeyFormat::Jwk, handle) => {
+                let (public_key, private_key) = match handle {
+                    Handle::Ed25519PublicKey(public_key) | Handle::X25519PublicKey(public_key) => {
+                        (public_key.clone(), None)
+                    },
+                    Handle::Ed25519PrivateKey(private_key) |
+                    Handle::X25519PrivateKey(private_key) => (
+                        okp_public_key(&name, private_key).map_err(|_| Error::Operation)?,
+                        Some(base64_url_encode(private_key)),
+                    ),
+                    _ => return Err(Error::Operation),
+                };
+                let jwk = JsonWebKey {
+                    alg: (name == ALG_ED25519).then(|| DOMString::from(ALG_ED25519)),
+                    crv: Some(DOMString::from(name.as_str())),
+                    x: Some(base64_url_encode(&public_key)),
+                    d: private_key,
+                    ..new_exported_jwk(key, "OKP")
+                };
+                Ok(ExportedKey::Jwk(Box::new(jwk)))
+            },
+        }
+    }
+
+    /// <https://w3c.github.io/webcrypto/#rsa-oaep-operations>
+    fn encrypt_rsa_oaep(
+        &self,
+        params: &SubtleRsaOaepParams,
+        key: &CryptoKey,
+        data: &[u8],
+        cx: JSContext,
+        handle: MutableHandleObject,
+        can_gc: CanGc,
+    ) -> Result<Vec<u8>, Error> {
+        // Step 1. If the [[type]] internal slot of key is not "public", then throw an InvalidAccessError.
+        let Handle::RsaPublicKey(spki) = key.handle() else {
+            return Err(Error::InvalidAccess);
+        };
+
+        // Step 2. Let label be the contents of the label member of normalizedAlgorithm or the empty octet
+        // string if the label member of normalizedAlgorithm is not present.
+        // Step 3. Perform the encryption operation defined in Section 7.1 of [RFC3447] with the key represented
+        // by key as the recipient's RSA public key, the contents of plaintext as the message to be encrypted, M
+        // and label as the label, L, and with the hash function specified by the hash attribute of the
+        // [[algorithm]] internal slot of key as the Hash option and MGF1 (defined in Section B.2.1 of [RFC3447])
+        // as the MGF option.
+        // Step 4. If performing the operation results in an error, then throw an OperationError.
+        let hash = rsa_key_hash(cx, key)?;
+        let public_key = PublicEncryptingKey::from_der(spki).map_err(|_| Error::Operation)?;
+        let public_key = OaepPublicEncryptingKey::new(public_key).map_err(|_| Error::Operation)?;
+        let mut ciphertext = vec![0; public_key.ciphertext_size()];
+        let ciphertext = public_key
+            .encrypt(
+                hash.oaep_algorithm(),
+                data,
+                &mut ciphertext,
+                params.label.as_deref(),
+            )
+            .map_err(|_| Error::Operation)?
+            .to_vec();
+
+        // Step 5. Let ciphertext be the value C that results from performing the operation.
+        // Step 6. Return the result of creating an ArrayBuffer containing ciphertext.
+        create_buffer_source::<ArrayBufferU8>(cx, &ciphertext, handle, can_gc)
+            .expect("failed to create buffer source for encrypted data.");
+
+        Ok(ciphertext)
+    }
+
+    /// <https://w3c.github.io/webcrypto/#rsa-oaep-operations>
+    fn decrypt_rsa_oaep(
+        &self,
+        params: &SubtleRsaOaepParams,
+        key: &CryptoKey,
+        data: &[u8],
+        cx: JSContext,
+        handle: MutableHandleObject,
+        can_gc: CanGc,
+    ) -> Result<Vec<u8>, Error> {
+        // Step 1. If the [[type]] internal slot of key is not "private", then throw an InvalidAccessError.
+        let Handle::RsaPrivateKey(pkcs8) = key.handle() else {
+            return Err(Error::InvalidAccess);
+        };
+
+        // Step 2. Let label be the contents of the label member of normalizedAlgorithm or the empty octet
+        // string if the label member of normalizedAlgorithm is not present.
+        // Step 3. Perform the decryption operation defined in Section 7.1 of [RFC3447] with the key represented
+        // by key as the recipient's RSA private key, the contents of ciphertext as the ciphertext to be
+        // decrypted, C, and label as the label, L, and with the hash function specified by the hash attribute
+        // of the [[algorithm]] internal slot of key as the Hash option and MGF1 (defined in Section B.2.1 of
+        // [RFC3447]) as the MGF option.
+        // Step 4. If performing the operation results in an error, then throw an OperationError.
+        let hash = rsa_key_hash(cx, key)?;
+        let private_key = PrivateDecryptingKey::from_pkcs8(pkcs8).map_err(|_| Error::Operation)?;
+        let private_key =
+            OaepPrivateDecryptingKey::new(private_key).map_err(|_| Error::Operation)?;
+        let mut plaintext = vec![0; private_key.min_output_size()];
+        let plaintext = private_key
+            .decrypt(
+                hash.oaep_algorithm(),
+                data,
+                &mut plaintext,
+                params.label.as_deref(),
+            )
+            .map_err(|_| Error::Operation)?
+            .to_vec();
+
+        // Step 5. Let plaintext the value M that results from performing the operation.
+        // Step 6. Return the result of creating an ArrayBuffer containing plaintext.
+        create_buffer_source::<ArrayBufferU8>(cx, &plaintext, handle, can_gc)
+            .expect("failed to create buffer source for decrypted data.");
+
+        Ok(plaintext)
+    }
+}
+
+pub(crate) enum ExportedKey {
+    Raw(Vec<u8>),
+    Jwk(Box<JsonWebKey>),
+}
+
+fn data_to_jwk_params(alg: &str, size: &str, key: &[u8]) -> (DOMString, DOMString) {
+    let jwk_alg = match alg {
+        ALG_AES_CBC => DOMString::from(format!("A{}CBC", size)),
+        ALG_AES_CTR => DOMString::from(format!("A{}CTR", size)),
+        ALG_AES_KW => DOMString::from(format!("A{}KW", size)),
+        ALG_AES_GCM => DOMString::from(format!("A{}GCM", size)),
+        _ => unreachable!(),
+    };
+    (jwk_alg, base64_url_encode(key))
+}
+
+/// Encodes a JWK member with the base64url encoding, as required by [RFC7518].
+fn base64_url_encode(data: &[u8]) -> DOMString {
+    DOMString::from(base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(data))
+}
+
+/// Returns a JWK of the given key type, with the "key_ops" and "ext" members set from `key`.
+fn new_exported_jwk(key: &CryptoKey, kty: &str) -> JsonWebKey {
+    JsonWebKey {
+        kty: Some(DOMString::from(kty)),
+        key_ops: Some(
+            key.usages()
+                .iter()
+                .map(|usage| DOMString::from(usage.as_str()))
+                .collect(),
+        ),
+        ext: Some(key.Extractable()),
+        ..Default::default()
+    }
+}
+
+trait AlgorithmFromName {
+    fn from_name(name: DOMString, out: MutableHandleObject, cx: JSContext);
+}
+
+impl AlgorithmFromName for KeyAlgorithm {
+    /// Fill the object referenced by `out` with an [KeyAlgorithm]
+    /// of the specified name and size.
+    #[allow(unsafe_code)]
+    fn from_name(name: DOMString, out: MutableHandleObject, cx: JSContext) {
+        let key_algorithm = Self { name };
+
+        unsafe {
+            key_algorithm.to_jsobject(*cx, out);
+        }
+    }
+}
+
+trait AlgorithmFromLengthAndHash {
+    fn from_length_and_hash(
+        length: u32,
+        hash: DigestAlgorithm,
+        out: MutableHandleObject,
+        cx: JSContext,
+    );
+}
+
+impl AlgorithmFromLengthAndHash for HmacKeyAlgorithm {
+    #[allow(unsafe_code)]
+    fn from_length_and_hash(
+        length: u32,
+        hash: DigestAlgorithm,
+        out: MutableHandleObject,
+        cx: JSContext,
+    ) {
+        let hmac_key_algorithm = Self {
+            parent: KeyAlgorithm {
+                name: ALG_HMAC.into(),
+            },
+            length,
+            hash: KeyAlgorithm { name: hash.name() },
+        };
+
+        unsafe {
+            hmac_key_algorithm.to_jsobject(*cx, out);
+        }
+    }
+}
+
+trait AlgorithmFromNameAndSize {
+    fn from_name_and_size(name: DOMString, size: u16, out: MutableHandleObject, cx: JSContext);
+}
+
+impl AlgorithmFromNameAndSize for AesKeyAlgorithm {
+    /// Fill the object referenced by `out` with an [AesKeyAlgorithm]
+    /// of the specified name and size.
+    #[allow(unsafe_code)]
+    fn from_name_and_size(name: DOMString, size: u16, out: MutableHandleObject, cx: JSContext) {
+        let key_algorithm = Self {
+            parent: KeyAlgorithm { name },
+            length: size,
+        };
+
+        unsafe {
+            key_algorithm.to_jsobject(*cx, out);
+        }
+    }
+}
+
+trait AlgorithmFromRsaParameters {
+    fn from_rsa_parameters(
+        name: DOMString,
+        modulus_length: u32,
+        public_exponent: &[u8],
+        hash: DigestAlgorithm,
+        out: MutableHandleObject,
+        cx: JSContext,
+        can_gc: CanGc,
+    );
+}
+
+impl AlgorithmFromRsaParameters for RsaHashedKeyAlgorithm {
+    /// Fill the object referenced by `out` with an [RsaHashedKeyAlgorithm]
+    /// of the specified name, modulus length, public exponent and hash.
+    #[allow(unsafe_code)]
+    fn from_rsa_parameters(
+        name: DOMString,
+        modulus_length: u32,
+        public_exponent: &[u8],
+        hash: DigestAlgorithm,
+        out: MutableHandleObject,
+        cx: JSContext,
+        can_gc: CanGc,
+    ) {
+        rooted!(in(*cx) let mut public_exponent_object = ptr::null_mut::<JSObject>());
+        let public_exponent = create_buffer_source::<Uint8>(
+            cx,
+            public_exponent,
+            public_exponent_object.handle_mut(),
+            can_gc,
+        )
+        .expect("failed to create buffer source for public exponent.");
+        let key_algorithm = RootedTraceableBox::new(Self {
+            parent: RootedTraceableBox::new(RsaKeyAlgorithm {
+                parent: KeyAlgorithm { name },
+                modulusLength: modulus_length,
+                publicExponent: public_exponent,
+            }),
+            hash: KeyAlgorithm { name: hash.name() },
+        });
+
+        unsafe {
+            key_algorithm.to_jsobject(*cx, out);
+        }
+    }
+}
+
+trait AlgorithmFromNameAndCurve {
+    fn from_name_and_curve(
+        name: DOMString,
+        named_curve: NamedCurve,
+        out: MutableHandleObject,
+        cx: JSContext,
+    );
+}
+
+impl AlgorithmFromNameAndCurve for EcKeyAlgorithm {
+    /// Fill the object referenced by `out` with an [EcKeyAlgorithm]
+    /// of the specified name and curve.
+    #[allow(unsafe_code)]
+    fn from_name_and_curve(
+        name: DOMString,
+        named_curve: NamedCurve,
+        out: MutableHandleObject,
+        cx: JSContext,
+    ) {
+        let key_algorithm = Self {
+            parent: KeyAlgorithm { name },
+            namedCurve: DOMString::from(named_curve.name()),
+        };
+
+        unsafe {
+            key_algorithm.to_jsobject(*cx, out);
+        }
+    }
+}
+
+/// Returns the hash function of the [[algorithm]] internal slot of an RSA key.
+fn rsa_key_hash(cx: JSContext, key: &CryptoKey) -> Result<DigestAlgorithm, Error> {
+    rooted!(in(*cx) let mut algorithm_slot = ObjectValue(key.Algorithm(cx).as_ptr()));
+    let algorithm = value_from_js_object!(RsaHashedKeyAlgorithm, cx, algorithm_slot);
+    normalize_algorithm_for_digest(
+        cx,
+        &AlgorithmIdentifier::String(algorithm.hash.name.clone()),
+    )
+}
+
+/// Returns the named curve of the [[algorithm]] internal slot of an elliptic curve key.
+fn ec_key_named_curve(cx: JSContext, key: &CryptoKey) -> Result<NamedCurve, Error> {
+    rooted!(in(*cx) let mut algorithm_slot = ObjectValue(key.Algorithm(cx).as_ptr()));
+    let algorithm = value_from_js_object!(EcKeyAlgorithm, cx, algorithm_slot);
+    NamedCurve::from_name(algorithm.namedCurve.str())
+}
+
+impl SubtleHkdfParams {
+    /// <https://w3c.github.io/webcrypto/#hkdf-operations>
+    fn derive_bits(&self, key: &CryptoKey, length: Option<u32>) -> Result<Vec<u8>, Error> {
+        // Step 1. If length is null or zero, or is not a multiple of 8, then throw an OperationError.
+        let Some(length) = length else {
+            return Err(Error::Operation);
+        };
+        if length == 0 || length % 8 != 0 {
+            return Err(Error::Operation);
+        };
 
         // Step 3. Let keyDerivationKey be the secret represented by [[handle]] internal slot of key.
         let key_derivation_key = key.handle().as_bytes();
@@ -2846,6 +3796,140 @@ impl DigestAlgorithm {
             Self::Sha512 => 512,
         }
     }
+
+    /// The RSA-OAEP padding that uses this hash function for both the digest and MGF1.
+    fn oaep_algorithm(&self) -> &'static OaepAlgorithm {
+        match self {
+            Self::Sha1 => &OAEP_SHA1_MGF1SHA1,
+            Self::Sha256 => &OAEP_SHA256_MGF1SHA256,
+            Self::Sha384 => &OAEP_SHA384_MGF1SHA384,
+            Self::Sha512 => &OAEP_SHA512_MGF1SHA512,
+        }
+    }
+}
+
+impl RsaAlgorithm {
+    fn from_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case(ALG_RSASSA_PKCS1) {
+            Some(Self::RsassaPkcs1v15)
+        } else if name.eq_ignore_ascii_case(ALG_RSA_PSS) {
+            Some(Self::RsaPss)
+        } else if name.eq_ignore_ascii_case(ALG_RSA_OAEP) {
+            Some(Self::RsaOaep)
+        } else {
+            None
+        }
+    }
+
+    /// <https://w3c.github.io/webcrypto/#dom-algorithm-name>
+    fn name(&self) -> &'static str {
+        match self {
+            Self::RsassaPkcs1v15 => ALG_RSASSA_PKCS1,
+            Self::RsaPss => ALG_RSA_PSS,
+            Self::RsaOaep => ALG_RSA_OAEP,
+        }
+    }
+
+    /// The "alg" member of a JWK for this algorithm with the given hash function, as defined in [RFC7518].
+    fn jwk_alg(&self, hash: DigestAlgorithm) -> &'static str {
+        match (self, hash) {
+            (Self::RsassaPkcs1v15, DigestAlgorithm::Sha1) => "RS1",
+            (Self::RsassaPkcs1v15, DigestAlgorithm::Sha256) => "RS256",
+            (Self::RsassaPkcs1v15, DigestAlgorithm::Sha384) => "RS384",
+            (Self::RsassaPkcs1v15, DigestAlgorithm::Sha512) => "RS512",
+            (Self::RsaPss, DigestAlgorithm::Sha1) => "PS1",
+            (Self::RsaPss, DigestAlgorithm::Sha256) => "PS256",
+            (Self::RsaPss, DigestAlgorithm::Sha384) => "PS384",
+            (Self::RsaPss, DigestAlgorithm::Sha512) => "PS512",
+            (Self::RsaOaep, DigestAlgorithm::Sha1) => "RSA-OAEP",
+            (Self::RsaOaep, DigestAlgorithm::Sha256) => "RSA-OAEP-256",
+            (Self::RsaOaep, DigestAlgorithm::Sha384) => "RSA-OAEP-384",
+            (Self::RsaOaep, DigestAlgorithm::Sha512) => "RSA-OAEP-512",
+        }
+    }
+}
+
+impl EcAlgorithm {
+    fn from_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case(ALG_ECDSA) {
+            Some(Self::Ecdsa)
+        } else if name.eq_ignore_ascii_case(ALG_ECDH) {
+            Some(Self::Ecdh)
+        } else {
+            None
+        }
+    }
+
+    /// <https://w3c.github.io/webcrypto/#dom-algorithm-name>
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Ecdsa => ALG_ECDSA,
+            Self::Ecdh => ALG_ECDH,
+        }
+    }
+}
+
+impl NamedCurve {
+    fn from_name(name: &str) -> Result<Self, Error> {
+        match name {
+            NAMED_CURVE_P256 => Ok(Self::P256),
+            NAMED_CURVE_P384 => Ok(Self::P384),
+            NAMED_CURVE_P521 => Ok(Self::P521),
+            _ => Err(Error::NotSupported),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::P256 => NAMED_CURVE_P256,
+            Self::P384 => NAMED_CURVE_P384,
+            Self::P521 => NAMED_CURVE_P521,
+        }
+    }
+
+    /// The length of a coordinate or a private scalar on this curve, in bytes.
+    fn coordinate_length(&self) -> usize {
+        match self {
+            Self::P256 => 32,
+            Self::P384 => 48,
+            Self::P521 => 66,
+        }
+    }
+
+    /// The contents of the AlgorithmIdentifier of keys on this curve, as defined in [RFC5480].
+    fn algorithm_identifier(&self) -> Vec<u8> {
+        let curve_oid: &[u8] = match self {
+            Self::P256 => &[0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07],
+            Self::P384 => &[0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x22],
+            Self::P521 => &[0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x23],
+        };
+        [EC_PUBLIC_KEY_OID, curve_oid].concat()
+    }
+
+    /// The signing algorithm that uses the hash function matching this curve.
+    fn signing_algorithm(&self) -> &'static signature::EcdsaSigningAlgorithm {
+        match self {
+            Self::P256 => &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+            Self::P384 => &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+            Self::P521 => &signature::ECDSA_P521_SHA512_FIXED_SIGNING,
+        }
+    }
+
+    fn agreement_algorithm(&self) -> &'static agreement::Algorithm {
+        match self {
+            Self::P256 => &agreement::ECDH_P256,
+            Self::P384 => &agreement::ECDH_P384,
+            Self::P521 => &agreement::ECDH_P521,
+        }
+    }
+
+    /// Throws a DataError unless `point` is an uncompressed point of the size used by this curve.
+    fn validate_point<'a>(&self, point: &'a [u8]) -> Result<&'a [u8], Error> {
+        if point.first() != Some(&0x04) || point.len() != 1 + 2 * self.coordinate_length() {
+            return Err(Error::Data);
+        }
+        Ok(point)
+    }
 }
 
 impl ImportKeyAlgorithm {
@@ -2878,15 +3962,53 @@ impl ImportKeyAlgorithm {
                 subtle.import_key_pbkdf2(format, secret, extractable, key_usages, can_gc)
             },
             Self::Hkdf => subtle.import_key_hkdf(format, secret, extractable, key_usages, can_gc),
+            Self::Rsa(algorithm, hash) => subtle.import_key_rsa(
+                *algorithm,
+                *hash,
+                format,
+                secret,
+                extractable,
+                key_usages,
+                can_gc,
+            ),
+            Self::Ec(algorithm, named_curve) => subtle.import_key_ec(
+                *algorithm,
+                *named_curve,
+                format,
+                secret,
+                extractable,
+                key_usages,
+                can_gc,
+            ),
+            Self::Ed25519 => {
+                subtle.import_key_okp(ALG_ED25519, format, secret, extractable, key_usages, can_gc)
+            },
+            Self::X25519 => {
+                subtle.import_key_okp(ALG_X25519, format, secret, extractable, key_usages, can_gc)
+            },
         }
     }
 }
 
 impl DeriveBitsAlgorithm {
+    /// <https://w3c.github.io/webcrypto/#dom-algorithm-name>
+    fn name(&self) -> &str {
+        match self {
+            Self::Pbkdf2(_) => ALG_PBKDF2,
+            Self::Hkdf(_) => ALG_HKDF,
+            Self::Ecdh(_) => ALG_ECDH,
+            Self::X25519(_) => ALG_X25519,
+        }
+    }
+
     fn derive_bits(&self, key: &CryptoKey, length: Option<u32>) -> Result<Vec<u8>, Error> {
         match self {
             Self::Pbkdf2(pbkdf2_params) => pbkdf2_params.derive_bits(key, length),
             Self::Hkdf(hkdf_params) => hkdf_params.derive_bits(key, length),
+            Self::Ecdh(public_key) => {
+                derive_bits_ecdh(GlobalScope::get_cx(), key, &public_key.root(), length)
+            },
+            Self::X25519(public_key) => derive_bits_x25519(key, &public_key.root(), length),
         }
     }
 }
@@ -2898,6 +4020,7 @@ impl EncryptionAlgorithm {
             Self::AesCbc(params) => &params.name,
             Self::AesCtr(params) => &params.name,
             Self::AesGcm(params) => &params.name,
+            Self::RsaOaep(_) => ALG_RSA_OAEP,
         }
     }
 
@@ -2917,6 +4040,7 @@ impl EncryptionAlgorithm {
                 subtle.encrypt_decrypt_aes_ctr(params, key, data, cx, result, can_gc)
             },
             Self::AesGcm(params) => subtle.encrypt_aes_gcm(params, key, data, cx, result, can_gc),
+            Self::RsaOaep(params) => subtle.encrypt_rsa_oaep(params, key, data, cx, result, can_gc),
         }
     }
 
@@ -2936,91 +4060,557 @@ impl EncryptionAlgorithm {
                 subtle.encrypt_decrypt_aes_ctr(params, key, data, cx, result, can_gc)
             },
             Self::AesGcm(params) => subtle.decrypt_aes_gcm(params, key, data, cx, result, can_gc),
+            Self::RsaOaep(params) => subtle.decrypt_rsa_oaep(params, key, data, cx, result, can_gc),
+        }
+    }
+}
+
+impl SignatureAlgorithm {
+    fn name(&self) -> &str {
+        match self {
+            Self::Hmac => ALG_HMAC,
+            Self::RsassaPkcs1v15 => ALG_RSASSA_PKCS1,
+            Self::RsaPss(_) => ALG_RSA_PSS,
+            Self::Ecdsa(_) => ALG_ECDSA,
+            Self::Ed25519 => ALG_ED25519,
+        }
+    }
+
+    fn sign(&self, cx: JSContext, key: &CryptoKey, data: &[u8]) -> Result<Vec<u8>, Error> {
+        match self {
+            Self::Hmac => sign_hmac(cx, key, data).map(|s| s.as_ref().to_vec()),
+            Self::RsassaPkcs1v15 => sign_rsa(cx, key, None, data),
+            Self::RsaPss(salt_length) => sign_rsa(cx, key, Some(*salt_length), data),
+            Self::Ecdsa(hash) => sign_ecdsa(cx, key, *hash, data),
+            Self::Ed25519 => sign_ed25519(key, data),
+        }
+    }
+
+    fn verify(
+        &self,
+        cx: JSContext,
+        key: &CryptoKey,
+        data: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, Error> {
+        match self {
+            Self::Hmac => verify_hmac(cx, key, data, signature),
+            Self::RsassaPkcs1v15 => verify_rsa(cx, key, None, data, signature),
+            Self::RsaPss(salt_length) => verify_rsa(cx, key, Some(*salt_length), data, signature),
+            Self::Ecdsa(hash) => verify_ecdsa(cx, key, *hash, data, signature),
+            Self::Ed25519 => verify_ed25519(key, data, signature),
+        }
+    }
+}
+
+impl KeyGenerationAlgorithm {
+    // FIXME: This doesn't really need the "SubtleCrypto" argument
+    fn generate_key(
+        &self,
+        subtle: &SubtleCrypto,
+        usages: Vec<KeyUsage>,
+        extractable: bool,
+        can_gc: CanGc,
+    ) -> Result<GeneratedKey, Error> {
+        match self {
+            Self::Aes(params) => subtle
+                .generate_key_aes(usages, params, extractable, can_gc)
+                .map(GeneratedKey::Key),
+            Self::Hmac(params) => subtle
+                .generate_key_hmac(usages, params, extractable, can_gc)
+                .map(GeneratedKey::Key),
+            Self::Rsa(algorithm, params) => subtle
+                .generate_key_rsa(usages, *algorithm, params, extractable, can_gc)
+                .map(GeneratedKey::KeyPair),
+            Self::Ec(algorithm, named_curve) => subtle
+                .generate_key_ec(usages, *algorithm, *named_curve, extractable, can_gc)
+                .map(GeneratedKey::KeyPair),
+            Self::Ed25519 => subtle
+                .generate_key_okp(usages, ALG_ED25519, extractable, can_gc)
+                .map(GeneratedKey::KeyPair),
+            Self::X25519 => subtle
+                .generate_key_okp(usages, ALG_X25519, extractable, can_gc)
+                .map(GeneratedKey::KeyPair),
         }
     }
 }
 
-impl SignatureAlgorithm {
-    fn name(&self) -> &str {
-        match self {
-            Self::Hmac => ALG_HMAC,
-        }
+/// <https://w3c.github.io/webcrypto/#hmac-operations>
+fn sign_hmac(cx: JSContext, key: &CryptoKey, data: &[u8]) -> Result<impl AsRef<[u8]>, Error> {
+    // Step 1. Let mac be the result of performing the MAC Generation operation described in Section 4 of [FIPS-198-1]
+    // using the key represented by [[handle]] internal slot of key, the hash function identified by the hash attribute
+    // of the [[algorithm]] internal slot of key and message as the input data text.
+    rooted!(in(*cx) let mut algorithm_slot = ObjectValue(key.Algorithm(cx).as_ptr()));
+    let params = value_from_js_object!(HmacKeyAlgorithm, cx, algorithm_slot);
+
+    let hash_algorithm = match params.hash.name.str() {
+        ALG_SHA1 => hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+        ALG_SHA256 => hmac::HMAC_SHA256,
+        ALG_SHA384 => hmac::HMAC_SHA384,
+        ALG_SHA512 => hmac::HMAC_SHA512,
+        _ => return Err(Error::NotSupported),
+    };
+
+    let sign_key = hmac::Key::new(hash_algorithm, key.handle().as_bytes());
+    let mac = hmac::sign(&sign_key, data);
+
+    // Step 2. Return the result of creating an ArrayBuffer containing mac.
+    // NOTE: This is done by the caller
+    Ok(mac)
+}
+
+/// <https://w3c.github.io/webcrypto/#hmac-operations>
+fn verify_hmac(
+    cx: JSContext,
+    key: &CryptoKey,
+    data: &[u8],
+    signature: &[u8],
+) -> Result<bool, Error> {
+    // Step 1. Let mac be the result of performing the MAC Generation operation described in Section 4 of [FIPS-198-1]
+    // using the key represented by [[handle]] internal slot of key, the hash function identified by the hash attribute
+    // of the [[algorithm]] internal slot of key and message as the input data text.
+    let mac = sign_hmac(cx, key, data)?;
+
+    // Step 2. Return true if mac is equal to signature and false otherwise.
+    let is_valid = mac.as_ref() == signature;
+    Ok(is_valid)
+}
+
+/// <https://w3c.github.io/webcrypto/#rsassa-pkcs1-operations>
+/// <https://w3c.github.io/webcrypto/#rsa-pss-operations>
+///
+/// Uses RSA-PSS with the given salt length, or RSASSA-PKCS1-v1_5 if there is none.
+fn sign_rsa(
+    cx: JSContext,
+    key: &CryptoKey,
+    salt_length: Option<u32>,
+    data: &[u8],
+) -> Result<Vec<u8>, Error> {
+    // Step 1. If the [[type]] internal slot of key is not "private", then throw an InvalidAccessError.
+    let Handle::RsaPrivateKey(pkcs8) = key.handle() else {
+        return Err(Error::InvalidAccess);
+    };
+
+    // Step 2. Perform the signature generation operation defined in [RFC3447] with the key represented by
+    // the [[handle]] internal slot of key as the signer's private key, the contents of message as M, the hash
+    // function identified by the hash attribute of the [[algorithm]] internal slot of key and, for RSA-PSS,
+    // the saltLength member of normalizedAlgorithm as the length of the salt.
+    // NOTE: Our backend does not sign with SHA-1, and always uses a salt as long as the digest.
+    let hash = rsa_key_hash(cx, key)?;
+    let encoding: &'static dyn signature::RsaEncoding = match (salt_length, hash) {
+        (None, DigestAlgorithm::Sha256) => &signature::RSA_PKCS1_SHA256,
+        (None, DigestAlgorithm::Sha384) => &signature::RSA_PKCS1_SHA384,
+        (None, DigestAlgorithm::Sha512) => &signature::RSA_PKCS1_SHA512,
+        (Some(_), DigestAlgorithm::Sha256) => &signature::RSA_PSS_SHA256,
+        (Some(_), DigestAlgorithm::Sha384) => &signature::RSA_PSS_SHA384,
+        (Some(_), DigestAlgorithm::Sha512) => &signature::RSA_PSS_SHA512,
+        (_, DigestAlgorithm::Sha1) => return Err(Error::NotSupported),
+    };
+    if salt_length.is_some_and(|salt_length| salt_length as usize != hash.block_size_in_bits() / 8)
+    {
+        return Err(Error::NotSupported);
+    }
+
+    // Step 3. If performing the operation results in an error, then throw an OperationError.
+    let key_pair = RsaKeyPair::from_pkcs8(pkcs8).map_err(|_| Error::Operation)?;
+    let mut signature = vec![0; key_pair.public_modulus_len()];
+    key_pair
+        .sign(encoding, &SystemRandom::new(), data, &mut signature)
+        .map_err(|_| Error::Operation)?;
+
+    // Step 4. Return the signature S that results from performing the operation.
+    Ok(signature)
+}
+
+/// <https://w3c.github.io/webcrypto/#rsassa-pkcs1-operations>
+/// <https://w3c.github.io/webcrypto/#rsa-pss-operations>
+///
+/// Uses RSA-PSS with the given salt length, or RSASSA-PKCS1-v1_5 if there is none.
+fn verify_rsa(
+    cx: JSContext,
+    key: &CryptoKey,
+    salt_length: Option<u32>,
+    data: &[u8],
+    signature: &[u8],
+) -> Result<bool, Error> {
+    // Step 1. If the [[type]] internal slot of key is not "public", then throw an InvalidAccessError.
+    if !matches!(key.handle(), Handle::RsaPublicKey(_)) {
+        return Err(Error::InvalidAccess);
+    }
+
+    // Step 2. Perform the signature verification operation defined in [RFC3447] with the key represented by
+    // the [[handle]] internal slot of key as the signer's RSA public key, the contents of message as M, the
+    // contents of signature as S, the hash function identified by the hash attribute of the [[algorithm]]
+    // internal slot of key and, for RSA-PSS, the saltLength member of normalizedAlgorithm as the length of
+    // the salt.
+    let hash = rsa_key_hash(cx, key)?;
+    let algorithm: &'static dyn signature::VerificationAlgorithm = match (salt_length, hash) {
+        (None, DigestAlgorithm::Sha1) => &signature::RSA_PKCS1_2048_8192_SHA1_FOR_LEGACY_USE_ONLY,
+        (None, DigestAlgorithm::Sha256) => &signature::RSA_PKCS1_2048_8192_SHA256,
+        (None, DigestAlgorithm::Sha384) => &signature::RSA_PKCS1_2048_8192_SHA384,
+        (None, DigestAlgorithm::Sha512) => &signature::RSA_PKCS1_2048_8192_SHA512,
+        (Some(_), DigestAlgorithm::Sha256) => &signature::RSA_PSS_2048_8192_SHA256,
+        (Some(_), DigestAlgorithm::Sha384) => &signature::RSA_PSS_2048_8192_SHA384,
+        (Some(_), DigestAlgorithm::Sha512) => &signature::RSA_PSS_2048_8192_SHA512,
+        (Some(_), DigestAlgorithm::Sha1) => return Err(Error::NotSupported),
+    };
+    if salt_length.is_some_and(|salt_length| salt_length as usize != hash.block_size_in_bits() / 8)
+    {
+        return Err(Error::NotSupported);
+    }
+    let public_key = RsaKeyComponents::from_handle(key.handle())?.public_key();
+
+    // Step 3. Let result be a boolean with the value true if the result of the operation was "valid
+    // signature" and the value false otherwise.
+    let result = signature::UnparsedPublicKey::new(algorithm, public_key)
+        .verify(data, signature)
+        .is_ok();
+
+    // Step 4. Return result.
+    Ok(result)
+}
+
+/// <https://w3c.github.io/webcrypto/#ecdsa-operations>
+fn sign_ecdsa(
+    cx: JSContext,
+    key: &CryptoKey,
+    hash: DigestAlgorithm,
+    data: &[u8],
+) -> Result<Vec<u8>, Error> {
+    // Step 1. If the [[type]] internal slot of key is not "private", then throw an InvalidAccessError.
+    let Handle::EcPrivateKey(pkcs8) = key.handle() else {
+        return Err(Error::InvalidAccess);
+    };
+
+    // Step 2. Let hashAlgorithm be the hash member of normalizedAlgorithm.
+    // Step 3. Let M be the result of performing the digest operation specified by hashAlgorithm using message.
+    // Step 4. Let d be the ECDSA private key associated with key.
+    // Step 5. Let params be the EC domain parameters associated with key.
+    // Step 6. Perform the ECDSA signing process, as specified in [RFC6090], Section 5.4, with M as the message,
+    // using params as the EC domain parameters, and with d as the private key.
+    // NOTE: Our backend hashes the message itself and cannot sign a digest, so it only signs with
+    // the hash functions it pairs with each curve: SHA-256 on P-256, SHA-384 on P-384 and
+    // SHA-256, SHA-384 or SHA-512 on P-521. Other pairs throw a NotSupportedError.
+    let algorithm: &'static signature::EcdsaSigningAlgorithm =
+        match (ec_key_named_curve(cx, key)?, hash) {
+            (NamedCurve::P256, DigestAlgorithm::Sha256) => {
+                &signature::ECDSA_P256_SHA256_FIXED_SIGNING
+            },
+            (NamedCurve::P384, DigestAlgorithm::Sha384) => {
+                &signature::ECDSA_P384_SHA384_FIXED_SIGNING
+            },
+            (NamedCurve::P521, DigestAlgorithm::Sha256) => {
+                &signature::ECDSA_P521_SHA256_FIXED_SIGNING
+            },
+            (NamedCurve::P521, DigestAlgorithm::Sha384) => {
+                &signature::ECDSA_P521_SHA384_FIXED_SIGNING
+            },
+            (NamedCurve::P521, DigestAlgorithm::Sha512) => {
+                &signature::ECDSA_P521_SHA512_FIXED_SIGNING
+            },
+            _ => return Err(Error::NotSupported),
+        };
+
+    // Step 7. Let r and s be the pair of integers resulting from performing the ECDSA signing process.
+    // Step 8. Let result be an empty byte sequence.
+    // Step 9. Let n be the smallest integer such that n * 8 is greater than the logarithm to base 2 of the
+    // order of the base point of the elliptic curve identified by params.
+    // Step 10. Convert r to an octet string of length n and append it to result.
+    // Step 11. Convert s to an octet string of length n and append it to result.
+    // Step 12. If performing the operation results in an error, then throw an OperationError.
+    let key_pair = EcdsaKeyPair::from_pkcs8(algorithm, pkcs8).map_err(|_| Error::Operation)?;
+    let signature = key_pair
+        .sign(&SystemRandom::new(), data)
+        .map_err(|_| Error::Operation)?;
+
+    // Step 13. Return result.
+    Ok(signature.as_ref().to_vec())
+}
+
+/// <https://w3c.github.io/webcrypto/#ecdsa-operations>
+fn verify_ecdsa(
+    cx: JSContext,
+    key: &CryptoKey,
+    hash: DigestAlgorithm,
+    data: &[u8],
+    signature: &[u8],
+) -> Result<bool, Error> {
+    // Step 1. If the [[type]] internal slot of key is not "public", then throw an InvalidAccessError.
+    let Handle::EcPublicKey(point) = key.handle() else {
+        return Err(Error::InvalidAccess);
+    };
+
+    // Step 2. Let hashAlgorithm be the hash member of normalizedAlgorithm.
+    // Step 3. Let M be the result of performing the digest operation specified by hashAlgorithm using message.
+    // Step 4. Let Q be the ECDSA public key associated with key.
+    // Step 5. Let params be the EC domain parameters associated with key.
+    // Step 6. Perform the ECDSA verifying process, as specified in [RFC6090], Section 5.3, with M as the
+    // received message, signature as the received signature and using params as the EC domain parameters,
+    // and Q as the public key.
+    // NOTE: Our backend only verifies r || s signatures for the hash functions it signs with on each
+    // curve, so the signatures of the remaining pairs it supports are converted to DER first.
+    // SHA-1 and SHA-512 on P-256 and P-384 throw a NotSupportedError.
+    let named_curve = ec_key_named_curve(cx, key)?;
+    let (algorithm, signature): (&'static signature::EcdsaVerificationAlgorithm, _) =
+        match (named_curve, hash) {
+            (NamedCurve::P256, DigestAlgorithm::Sha256) => {
+                (&signature::ECDSA_P256_SHA256_FIXED, signature.to_vec())
+            },
+            (NamedCurve::P256, DigestAlgorithm::Sha384) => (
+                &signature::ECDSA_P256_SHA384_ASN1,
+                ecdsa_signature_to_der(named_curve, signature),
+            ),
+            (NamedCurve::P384, DigestAlgorithm::Sha256) => (
+                &signature::ECDSA_P384_SHA256_ASN1,
+                ecdsa_signature_to_der(named_curve, signature),
+            ),
+            (NamedCurve::P384, DigestAlgorithm::Sha384) => {
+                (&signature::ECDSA_P384_SHA384_FIXED, signature.to_vec())
+            },
+            (NamedCurve::P521, DigestAlgorithm::Sha1) => {
+                (&signature::ECDSA_P521_SHA1_FIXED, signature.to_vec())
+            },
+            (NamedCurve::P521, DigestAlgorithm::Sha256) => {
+                (&signature::ECDSA_P521_SHA256_FIXED, signature.to_vec())
+            },
+            (NamedCurve::P521, DigestAlgorithm::Sha384) => {
+                (&signature::ECDSA_P521_SHA384_FIXED, signature.to_vec())
+            },
+            (NamedCurve::P521, DigestAlgorithm::Sha512) => {
+                (&signature::ECDSA_P521_SHA512_FIXED, signature.to_vec())
+            },
+            _ => return Err(Error::NotSupported),
+        };
+
+    // Step 7. Let result be a boolean with the value true if the signature is valid and the value false
+    // otherwise.
+    let result = signature::UnparsedPublicKey::new(algorithm, point)
+        .verify(data, &signature)
+        .is_ok();
+
+    // Step 8. Return result.
+    Ok(result)
+}
+
+/// <https://w3c.github.io/webcrypto/#ed25519-operations>
+fn sign_ed25519(key: &CryptoKey, data: &[u8]) -> Result<Vec<u8>, Error> {
+    // Step 1. If the [[type]] internal slot of key is not "private", then throw an InvalidAccessError.
+    let Handle::Ed25519PrivateKey(private_key) = key.handle() else {
+        return Err(Error::InvalidAccess);
+    };
+
+    // Step 2. Let result be the result of performing the Ed25519 signing process, as specified in
+    // [RFC8032], Section 5.1.6, with message as M, using the Ed25519 private key associated with key.
+    // Step 3. If performing the operation results in an error, then throw an OperationError.
+    let key_pair =
+        Ed25519KeyPair::from_seed_unchecked(private_key).map_err(|_| Error::Operation)?;
+
+    // Step 4. Return result.
+    Ok(key_pair.sign(data).as_ref().to_vec())
+}
+
+/// <https://w3c.github.io/webcrypto/#ed25519-operations>
+fn verify_ed25519(key: &CryptoKey, data: &[u8], signature: &[u8]) -> Result<bool, Error> {
+    // Step 1. If the [[type]] internal slot of key is not "public", then throw an InvalidAccessError.
+    let Handle::Ed25519PublicKey(public_key) = key.handle() else {
+        return Err(Error::InvalidAccess);
+    };
+
+    // Step 2. Let result be a boolean with the value true if the signature is valid, as specified in
+    // [RFC8032], Section 5.1.7, and the value false otherwise.
+    let result = signature::UnparsedPublicKey::new(&signature::ED25519, public_key)
+        .verify(data, signature)
+        .is_ok();
+
+    // Step 3. Return result.
+    Ok(result)
+}
+
+/// <https://w3c.github.io/webcrypto/#ecdh-operations>
+fn derive_bits_ecdh(
+    cx: JSContext,
+    key: &CryptoKey,
+    public_key: &CryptoKey,
+    length: Option<u32>,
+) -> Result<Vec<u8>, Error> {
+    // Step 1. If the [[type]] internal slot of key is not "private", then throw an InvalidAccessError.
+    let Handle::EcPrivateKey(pkcs8) = key.handle() else {
+        return Err(Error::InvalidAccess);
+    };
+
+    // Step 2. Let publicKey be the public member of normalizedAlgorithm.
+    // Step 3. If the [[type]] internal slot of publicKey is not "public", then throw an InvalidAccessError.
+    let Handle::EcPublicKey(point) = public_key.handle() else {
+        return Err(Error::InvalidAccess);
+    };
+
+    // Step 4. If the name attribute of the [[algorithm]] internal slot of publicKey is not equal to the name
+    // property of the [[algorithm]] internal slot of key, then throw an InvalidAccessError.
+    if public_key.algorithm() != key.algorithm() {
+        return Err(Error::InvalidAccess);
+    }
+
+    // Step 5. If the namedCurve attribute of the [[algorithm]] internal slot of publicKey is not equal to the
+    // namedCurve property of the [[algorithm]] internal slot of key, then throw an InvalidAccessError.
+    let named_curve = ec_key_named_curve(cx, key)?;
+    if ec_key_named_curve(cx, public_key)? != named_curve {
+        return Err(Error::InvalidAccess);
+    }
+
+    // Step 6. Perform the ECDH primitive specified in [RFC6090] Section 4 with key as the EC private key d and
+    // the EC public key represented by the [[handle]] internal slot of publicKey as the EC public key.
+    // Step 7. Let secret be the byte sequence containing the result of applying the field element to octet
+    // string conversion defined in Section 6.2 of [RFC6090] to the output of the ECDH primitive.
+    // NOTE: If performing the operation results in an error, then throw an OperationError.
+    let private_key = agreement::PrivateKey::from_private_key(
+        named_curve.agreement_algorithm(),
+        &decode_ec_private_key(named_curve, pkcs8)?,
+    )
+    .map_err(|_| Error::Operation)?;
+    let secret = agreement::agree(
+        &private_key,
+        &agreement::UnparsedPublicKey::new(named_curve.agreement_algorithm(), point),
+        Error::Operation,
+        |secret| Ok(secret.to_vec()),
+    )?;
+
+    // Step 8. Return secret, truncated to length bits.
+    truncate_derived_bits(secret, length)
+}
+
+/// <https://w3c.github.io/webcrypto/#x25519-operations>
+fn derive_bits_x25519(
+    key: &CryptoKey,
+    public_key: &CryptoKey,
+    length: Option<u32>,
+) -> Result<Vec<u8>, Error> {
+    // Step 1. If the [[type]] internal slot of key is not "private", then throw an InvalidAccessError.
+    let Handle::X25519PrivateKey(private_key) = key.handle() else {
+        return Err(Error::InvalidAccess);
+    };
+
+    // Step 2. Let publicKey be the public member of normalizedAlgorithm.
+    // Step 3. If the [[type]] internal slot of publicKey is not "public", then throw an InvalidAccessError.
+    // Step 4. If the name attribute of the [[algorithm]] internal slot of publicKey is not equal to the name
+    // property of the [[algorithm]] internal slot of key, then throw an InvalidAccessError.
+    let Handle::X25519PublicKey(public_key) = public_key.handle() else {
+        return Err(Error::InvalidAccess);
+    };
+
+    // Step 5. Let secret be the result of performing the X25519 function specified in [RFC7748] Section 5
+    // with key as the X25519 private key k and the X25519 public key represented by the [[public key]]
+    // internal slot of publicKey as the X25519 public key u.
+    let private_key = agreement::PrivateKey::from_private_key(&agreement::X25519, private_key)
+        .map_err(|_| Error::Operation)?;
+    let secret = agreement::agree(
+        &private_key,
+        &agreement::UnparsedPublicKey::new(&agreement::X25519, public_key),
+        Error::Operation,
+        |secret| Ok(secret.to_vec()),
+    )?;
+
+    // Step 6. If secret is the all-zero value, then throw a OperationError.
+    if secret.iter().all(|byte| *byte == 0) {
+        return Err(Error::Operation);
     }
 
-    fn sign(&self, cx: JSContext, key: &CryptoKey, data: &[u8]) -> Result<Vec<u8>, Error> {
-        match self {
-            Self::Hmac => sign_hmac(cx, key, data).map(|s| s.as_ref().to_vec()),
-        }
+    // Step 7. Return secret, truncated to length bits.
+    truncate_derived_bits(secret, length)
+}
+
+/// Returns the first `length` bits of a shared secret, or the whole secret if `length` is null.
+fn truncate_derived_bits(mut secret: Vec<u8>, length: Option<u32>) -> Result<Vec<u8>, Error> {
+    // If length is null: Return secret
+    let Some(length) = length else {
+        return Ok(secret);
+    };
+
+    // Otherwise: If the length of secret in bits is less than length: throw an OperationError.
+    if secret.len() * 8 < length as usize {
+        return Err(Error::Operation);
     }
 
-    fn verify(
-        &self,
-        cx: JSContext,
-        key: &CryptoKey,
-        data: &[u8],
-        signature: &[u8],
-    ) -> Result<bool, Error> {
-        match self {
-            Self::Hmac => verify_hmac(cx, key, data, signature),
+    // Otherwise: Return an octet string containing the first length bits of secret.
+    secret.truncate((length as usize).div_ceil(8));
+    if length % 8 != 0 {
+        if let Some(last_byte) = secret.last_mut() {
+            *last_byte &= 0xff << (8 - length % 8);
         }
     }
+    Ok(secret)
 }
 
-impl KeyGenerationAlgorithm {
-    // FIXME: This doesn't really need the "SubtleCrypto" argument
-    fn generate_key(
-        &self,
-        subtle: &SubtleCrypto,
-        usages: Vec<KeyUsage>,
-        extractable: bool,
-        can_gc: CanGc,
-    ) -> Result<DomRoot<CryptoKey>, Error> {
-        match self {
-            Self::Aes(params) => subtle.generate_key_aes(usages, params, extractable, can_gc),
-            Self::Hmac(params) => subtle.generate_key_hmac(usages, params, extractable, can_gc),
-        }
+/// The usages that are allowed for the public and the private key of an asymmetric algorithm.
+fn asymmetric_key_usages(name: &str) -> (&'static [KeyUsage], &'static [KeyUsage]) {
+    match name {
+        ALG_RSA_OAEP => (
+            &[KeyUsage::Encrypt, KeyUsage::WrapKey],
+            &[KeyUsage::Decrypt, KeyUsage::UnwrapKey],
+        ),
+        ALG_ECDH | ALG_X25519 => (&[], &[KeyUsage::DeriveKey, KeyUsage::DeriveBits]),
+        _ => (&[KeyUsage::Verify], &[KeyUsage::Sign]),
     }
 }
 
-/// <https://w3c.github.io/webcrypto/#hmac-operations>
-fn sign_hmac(cx: JSContext, key: &CryptoKey, data: &[u8]) -> Result<impl AsRef<[u8]>, Error> {
-    // Step 1. Let mac be the result of performing the MAC Generation operation described in Section 4 of [FIPS-198-1]
-    // using the key represented by [[handle]] internal slot of key, the hash function identified by the hash attribute
-    // of the [[algorithm]] internal slot of key and message as the input data text.
-    rooted!(in(*cx) let mut algorithm_slot = ObjectValue(key.Algorithm(cx).as_ptr()));
-    let params = value_from_js_object!(HmacKeyAlgorithm, cx, algorithm_slot);
+/// Throws a SyntaxError if `usages` contains an entry which cannot be used by either key of a key pair.
+fn check_key_pair_usages(name: &str, usages: &[KeyUsage]) -> Result<(), Error> {
+    let (public_usages, private_usages) = asymmetric_key_usages(name);
+    if usages
+        .iter()
+        .any(|usage| !public_usages.contains(usage) && !private_usages.contains(usage))
+    {
+        return Err(Error::Syntax);
+    }
+    Ok(())
+}
 
-    let hash_algorithm = match params.hash.name.str() {
-        ALG_SHA1 => hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
-        ALG_SHA256 => hmac::HMAC_SHA256,
-        ALG_SHA384 => hmac::HMAC_SHA384,
-        ALG_SHA512 => hmac::HMAC_SHA512,
-        _ => return Err(Error::NotSupported),
+/// Throws a SyntaxError if `usages` contains an entry which cannot be used by an imported key of the given type.
+fn check_imported_key_usages(
+    name: &str,
+    key_type: KeyType,
+    usages: &[KeyUsage],
+) -> Result<(), Error> {
+    let (public_usages, private_usages) = asymmetric_key_usages(name);
+    let allowed_usages = match key_type {
+        KeyType::Public => public_usages,
+        _ => private_usages,
     };
+    if usages.iter().any(|usage| !allowed_usages.contains(usage)) {
+        return Err(Error::Syntax);
+    }
+    Ok(())
+}
 
-    let sign_key = hmac::Key::new(hash_algorithm, key.handle().as_bytes());
-    let mac = hmac::sign(&sign_key, data);
-
-    // Step 2. Return the result of creating an ArrayBuffer containing mac.
-    // NOTE: This is done by the caller
-    Ok(mac)
+/// Computes the public key of an Ed25519 or X25519 private key.
+fn okp_public_key(name: &str, private_key: &[u8]) -> Result<Vec<u8>, Error> {
+    if name == ALG_ED25519 {
+        let key_pair = Ed25519KeyPair::from_seed_unchecked(private_key).map_err(|_| Error::Data)?;
+        Ok(key_pair.public_key().as_ref().to_vec())
+    } else {
+        let private_key = agreement::PrivateKey::from_private_key(&agreement::X25519, private_key)
+            .map_err(|_| Error::Data)?;
+        let public_key = private_key.compute_public_key().map_err(|_| Error::Data)?;
+        Ok(public_key.as_ref().to_vec())
+    }
 }
 
-/// <https://w3c.github.io/webcrypto/#hmac-operations>
-fn verify_hmac(
-    cx: JSContext,
-    key: &CryptoKey,
-    data: &[u8],
-    signature: &[u8],
-) -> Result<bool, Error> {
-    // Step 1. Let mac be the result of performing the MAC Generation operation described in Section 4 of [FIPS-198-1]
-    // using the key represented by [[handle]] internal slot of key, the hash function identified by the hash attribute
-    // of the [[algorithm]] internal slot of key and message as the input data text.
-    let mac = sign_hmac(cx, key, data)?;
+/// Wraps the key data of an Ed25519 or X25519 key in the matching handle.
+fn okp_key_handle(name: &str, key_type: KeyType, key: Vec<u8>) -> Handle {
+    match (name == ALG_ED25519, key_type) {
+        (true, KeyType::Public) => Handle::Ed25519PublicKey(key),
+        (true, _) => Handle::Ed25519PrivateKey(key),
+        (false, KeyType::Public) => Handle::X25519PublicKey(key),
+        (false, _) => Handle::X25519PrivateKey(key),
+    }
+}
 
-    // Step 2. Return true if mac is equal to signature and false otherwise.
-    let is_valid = mac.as_ref() == signature;
-    Ok(is_valid)
+/// The contents of the AlgorithmIdentifier of Ed25519 or X25519 keys, as defined in [RFC8410].
+fn okp_algorithm_identifier(name: &str) -> &'static [u8] {
+    if name == ALG_ED25519 {
+        ED25519_ALGORITHM_IDENTIFIER
+    } else {
+        X25519_ALGORITHM_IDENTIFIER
+    }
 }
 
 impl KeyWrapAlgorithm {
@@ -3031,17 +4621,62 @@ impl KeyWrapAlgorithm {
             Self::AesCbc(key_gen_params) => &key_gen_params.name,
             Self::AesCtr(key_gen_params) => &key_gen_params.name,
             Self::AesGcm(_) => ALG_AES_GCM,
+            Self::RsaOaep(_) => ALG_RSA_OAEP,
+        }
+    }
+}
+
+/// Serializes a JWK dictionary, which is what the spec does by converting it to an ECMAScript object
+/// and stringifying it.
+fn jwk_to_json(jwk: &JsonWebKey) -> Vec<u8> {
+    let mut object = serde_json::Map::new();
+    let string_members = [
+        ("kty", &jwk.kty),
+        ("use", &jwk.use_),
+        ("alg", &jwk.alg),
+        ("crv", &jwk.crv),
+        ("x", &jwk.x),
+        ("y", &jwk.y),
+        ("d", &jwk.d),
+        ("n", &jwk.n),
+        ("e", &jwk.e),
+        ("p", &jwk.p),
+        ("q", &jwk.q),
+        ("dp", &jwk.dp),
+        ("dq", &jwk.dq),
+        ("qi", &jwk.qi),
+        ("k", &jwk.k),
+    ];
+    for (name, value) in string_members {
+        if let Some(value) = value {
+            object.insert(
+                name.to_owned(),
+                serde_json::Value::String(value.to_string()),
+            );
         }
     }
+    if let Some(ext) = jwk.ext {
+        object.insert("ext".to_owned(), serde_json::Value::Bool(ext));
+    }
+    if let Some(key_ops) = &jwk.key_ops {
+        let key_ops = key_ops
+            .iter()
+            .map(|op| serde_json::Value::String(op.to_string()))
+            .collect();
+        object.insert("key_ops".to_owned(), serde_json::Value::Array(key_ops));
+    }
+    serde_json::Value::Object(object).to_string().into_bytes()
 }
 
 /// <https://w3c.github.io/webcrypto/#concept-parse-a-jwk>
+///
+/// Returns the format and the key data which the key described by the JWK should be imported from.
 fn parse_jwk(
     bytes: &[u8],
-    import_alg: ImportKeyAlgorithm,
+    import_alg: &ImportKeyAlgorithm,
     extractable: bool,
     key_usages: &[KeyUsage],
-) -> Result<Vec<u8>, Error> {
+) -> Result<(KeyFormat, Vec<u8>), Error> {
     let value = serde_json::from_slice(bytes)
         .map_err(|_| Error::Type("Failed to parse JWK string".into()))?;
     let serde_json::Value::Object(obj) = value else {
@@ -3049,27 +4684,25 @@ fn parse_jwk(
     };
 
     let kty = get_jwk_string(&obj, "kty")?;
-    let ext = get_jwk_bool(&obj, "ext")?;
-    if !ext && extractable {
-        return Err(Error::Data);
+
+    // If the ext field of jwk is present, has the value false and extractable is true, then throw a DataError.
+    if let Some(ext) = obj.get("ext") {
+        if !ext.as_bool().ok_or(Error::Data)? && extractable {
+            return Err(Error::Data);
+        }
     }
 
     // If the key_ops field of jwk is present, and is invalid according to the requirements of JSON Web Key [JWK]
     // or does not contain all of the specified usages values, then throw a DataError.
-    if let Some(serde_json::Value::Array(key_ops)) = obj.get("key_ops") {
-        if key_ops.iter().any(|op| {
-            let op_string = match op {
-                serde_json::Value::String(op_string) => op_string,
-                _ => return true,
-            };
-            let usage = match usage_from_str(op_string) {
-                Ok(usage) => usage,
-                Err(_) => {
-                    return true;
-                },
-            };
-            !key_usages.contains(&usage)
-        }) {
+    if let Some(key_ops) = obj.get("key_ops") {
+        let serde_json::Value::Array(key_ops) = key_ops else {
+            return Err(Error::Data);
+        };
+        let key_ops = key_ops
+            .iter()
+            .map(|op| op.as_str().ok_or(Error::Data).and_then(usage_from_str))
+            .collect::<Result<Vec<KeyUsage>, Error>>()?;
+        if key_usages.iter().any(|usage| !key_ops.contains(usage)) {
             return Err(Error::Data);
         }
     }
@@ -3082,14 +4715,9 @@ fn parse_jwk(
             if kty != "oct" {
                 return Err(Error::Data);
             }
-            let k = get_jwk_string(&obj, "k")?;
-            let alg = get_jwk_string(&obj, "alg")?;
+            let data = get_jwk_bytes(&obj, "k")?;
 
-            let data = base64::engine::general_purpose::STANDARD_NO_PAD
-                .decode(k.as_bytes())
-                .map_err(|_| Error::Data)?;
-
-            let expected_alg = match (data.len() * 8, &import_alg) {
+            let expected_alg = match (data.len() * 8, import_alg) {
                 (128, ImportKeyAlgorithm::AesCbc) => "A128CBC",
                 (128, ImportKeyAlgorithm::AesCtr) => "A128CTR",
                 (128, ImportKeyAlgorithm::AesKw) => "A128KW",
@@ -3104,25 +4732,16 @@ fn parse_jwk(
                 (256, ImportKeyAlgorithm::AesGcm) => "A256GCM",
                 _ => return Err(Error::Data),
             };
+            check_jwk_member(&obj, "alg", expected_alg)?;
+            check_jwk_member(&obj, "use", "enc")?;
 
-            if alg != expected_alg {
-                return Err(Error::Data);
-            }
-
-            if let Some(serde_json::Value::String(use_)) = obj.get("use") {
-                if use_ != "enc" {
-                    return Err(Error::Data);
-                }
-            }
-
-            Ok(data)
+            Ok((KeyFormat::Raw, data))
         },
         ImportKeyAlgorithm::Hmac(params) => {
             if kty != "oct" {
                 return Err(Error::Data);
             }
-            let k = get_jwk_string(&obj, "k")?;
-            let alg = get_jwk_string(&obj, "alg")?;
+            let data = get_jwk_bytes(&obj, "k")?;
 
             let expected_alg = match params.hash {
                 DigestAlgorithm::Sha1 => "HS1",
@@ -3130,22 +4749,122 @@ fn parse_jwk(
                 DigestAlgorithm::Sha384 => "HS384",
                 DigestAlgorithm::Sha512 => "HS512",
             };
+            check_jwk_member(&obj, "alg", expected_alg)?;
+            check_jwk_member(&obj, "use", "sig")?;
 
-            if alg != expected_alg {
+            Ok((KeyFormat::Raw, data))
+        },
+        ImportKeyAlgorithm::Rsa(algorithm, hash) => {
+            if kty != "RSA" {
+                return Err(Error::Data);
+            }
+            check_jwk_member(&obj, "alg", algorithm.jwk_alg(*hash))?;
+            let expected_use = match algorithm {
+                RsaAlgorithm::RsaOaep => "enc",
+                _ => "sig",
+            };
+            check_jwk_member(&obj, "use", expected_use)?;
+
+            let mut components = RsaKeyComponents {
+                modulus: get_jwk_bytes(&obj, "n")?,
+                public_exponent: get_jwk_bytes(&obj, "e")?,
+                private: None,
+            };
+            if !obj.contains_key("d") {
+                return Ok((KeyFormat::Spki, components.to_spki()));
+            }
+
+            // NOTE: Keys with more than two prime factors are not supported.
+            if obj.contains_key("oth") {
+                return Err(Error::NotSupported);
+            }
+            let private = [
+                get_jwk_bytes(&obj, "d")?,
+                get_jwk_bytes(&obj, "p")?,
+                get_jwk_bytes(&obj, "q")?,
+                get_jwk_bytes(&obj, "dp")?,
+                get_jwk_bytes(&obj, "dq")?,
+                get_jwk_bytes(&obj, "qi")?,
+            ];
+            components.private = Some(private);
+            Ok((KeyFormat::Pkcs8, components.to_pkcs8().ok_or(Error::Data)?))
+        },
+        ImportKeyAlgorithm::Ec(algorithm, named_curve) => {
+            if kty != "EC" || get_jwk_string(&obj, "crv")? != named_curve.name() {
+                return Err(Error::Data);
+            }
+            match algorithm {
+                EcAlgorithm::Ecdsa => {
+                    let expected_alg = match named_curve {
+                        NamedCurve::P256 => "ES256",
+                        NamedCurve::P384 => "ES384",
+                        NamedCurve::P521 => "ES512",
+                    };
+                    check_jwk_member(&obj, "alg", expected_alg)?;
+                    check_jwk_member(&obj, "use", "sig")?;
+                },
+                EcAlgorithm::Ecdh => check_jwk_member(&obj, "use", "enc")?,
+            }
+
+            let x = get_jwk_bytes(&obj, "x")?;
+            let y = get_jwk_bytes(&obj, "y")?;
+            let coordinate_length = named_curve.coordinate_length();
+            if x.len() != coordinate_length || y.len() != coordinate_length {
                 return Err(Error::Data);
             }
+            let point = [&[0x04][..], x.as_slice(), y.as_slice()].concat();
+            if !obj.contains_key("d") {
+                return Ok((KeyFormat::Raw, point));
+            }
 
-            if let Some(serde_json::Value::String(use_)) = obj.get("use") {
-                if use_ != "sign" {
+            let private_key = get_jwk_bytes(&obj, "d")?;
+            if private_key.len() != coordinate_length {
+                return Err(Error::Data);
+            }
+            Ok((
+                KeyFormat::Pkcs8,
+                encode_ec_private_key(*named_curve, &private_key, &point),
+            ))
+        },
+        ImportKeyAlgorithm::Ed25519 | ImportKeyAlgorithm::X25519 => {
+            let name = match import_alg {
+                ImportKeyAlgorithm::Ed25519 => ALG_ED25519,
+                _ => ALG_X25519,
+            };
+            if kty != "OKP" || get_jwk_string(&obj, "crv")? != name {
+                return Err(Error::Data);
+            }
+            if name == ALG_ED25519 {
+                if obj
+                    .get("alg")
+                    .is_some_and(|alg| !matches!(alg.as_str(), Some("Ed25519" | "EdDSA")))
+                {
                     return Err(Error::Data);
                 }
+                check_jwk_member(&obj, "use", "sig")?;
+            } else {
+                check_jwk_member(&obj, "use", "enc")?;
+            }
+
+            let public_key = get_jwk_bytes(&obj, "x")?;
+            if !obj.contains_key("d") {
+                return Ok((KeyFormat::Raw, public_key));
             }
 
-            base64::engine::general_purpose::STANDARD_NO_PAD
-                .decode(k.as_bytes())
-                .map_err(|_| Error::Data)
+            // If the private key does not belong to the public key, then throw a DataError.
+            let private_key = get_jwk_bytes(&obj, "d")?;
+            if okp_public_key(name, &private_key)? != public_key {
+                return Err(Error::Data);
+            }
+            Ok((
+                KeyFormat::Pkcs8,
+                encode_pkcs8(
+                    okp_algorithm_identifier(name),
+                    &der_encode(DER_OCTET_STRING, &private_key),
+                ),
+            ))
         },
-        _ => Err(Error::NotSupported),
+        ImportKeyAlgorithm::Pbkdf2 | ImportKeyAlgorithm::Hkdf => Err(Error::NotSupported),
     }
 }
 
@@ -3161,16 +4880,27 @@ fn get_jwk_string(
     Ok(s.to_string())
 }
 
-fn get_jwk_bool(
+/// Returns the base64url decoded value of a JWK member.
+fn get_jwk_bytes(
     value: &serde_json::Map<String, serde_json::Value>,
     key: &str,
-) -> Result<bool, Error> {
-    let b = value
-        .get(key)
-        .ok_or(Error::Data)?
-        .as_bool()
-        .ok_or(Error::Data)?;
-    Ok(b)
+) -> Result<Vec<u8>, Error> {
+    let s = get_jwk_string(value, key)?;
+    base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(s.as_bytes())
+        .map_err(|_| Error::Data)
+}
+
+/// Throws a DataError if the member `key` of the JWK is present and not equal to `expected`.
+fn check_jwk_member(
+    value: &serde_json::Map<String, serde_json::Value>,
+    key: &str,
+    expected: &str,
+) -> Result<(), Error> {
+    match value.get(key) {
+        Some(member) if member.as_str() != Some(expected) => Err(Error::Data),
+        _ => Ok(()),
+    }
 }
 
 fn usage_from_str(op: &str) -> Result<KeyUsage, Error> {
@@ -3189,3 +4919,312 @@ fn usage_from_str(op: &str) -> Result<KeyUsage, Error> {
     };
     Ok(usage)
 }
+
+const DER_INTEGER: u8 = 0x02;
+const DER_BIT_STRING: u8 = 0x03;
+const DER_OCTET_STRING: u8 = 0x04;
+const DER_SEQUENCE: u8 = 0x30;
+/// The tag of the `publicKey` field of an ECPrivateKey, as defined in [RFC5915].
+const DER_EC_PUBLIC_KEY: u8 = 0xa1;
+
+/// The contents of the AlgorithmIdentifier of RSA keys, which is `rsaEncryption` with NULL parameters.
+const RSA_ALGORITHM_IDENTIFIER: &[u8] = &[
+    0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01, 0x05, 0x00,
+];
+/// The `id-ecPublicKey` object identifier, as defined in [RFC5480].
+const EC_PUBLIC_KEY_OID: &[u8] = &[0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+const ED25519_ALGORITHM_IDENTIFIER: &[u8] = &[0x06, 0x03, 0x2b, 0x65, 0x70];
+const X25519_ALGORITHM_IDENTIFIER: &[u8] = &[0x06, 0x03, 0x2b, 0x65, 0x6e];
+
+/// Encodes a DER element with the given tag.
+fn der_encode(tag: u8, contents: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![tag];
+    if contents.len() < 0x80 {
+        encoded.push(contents.len() as u8);
+    } else {
+        let length = contents.len().to_be_bytes();
+        let length = strip_leading_zeros(&length);
+        encoded.push(0x80 | length.len() as u8);
+        encoded.extend_from_slice(length);
+    }
+    encoded.extend_from_slice(contents);
+    encoded
+}
+
+/// Encodes an unsigned big-endian integer as a DER INTEGER.
+fn der_encode_integer(value: &[u8]) -> Vec<u8> {
+    let value = strip_leading_zeros(value);
+
+    // A leading zero keeps the integer positive.
+    if value.first().is_none_or(|byte| byte & 0x80 != 0) {
+        der_encode(DER_INTEGER, &[&[0][..], value].concat())
+    } else {
+        der_encode(DER_INTEGER, value)
+    }
+}
+
+/// Converts an ECDSA signature from the concatenation of r and s used by Web Crypto to the
+/// Ecdsa-Sig-Value structure of [RFC3279]. Signatures of the wrong length stay empty, so that they
+/// fail to verify.
+fn ecdsa_signature_to_der(named_curve: NamedCurve, signature: &[u8]) -> Vec<u8> {
+    if signature.len() != 2 * named_curve.coordinate_length() {
+        return Vec::new();
+    }
+    let (r, s) = signature.split_at(named_curve.coordinate_length());
+    der_encode(
+        DER_SEQUENCE,
+        &[der_encode_integer(r), der_encode_integer(s)].concat(),
+    )
+}
+
+fn strip_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|byte| *byte != 0)
+        .unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+fn left_pad(bytes: &[u8], length: usize) -> Vec<u8> {
+    let mut padded = vec![0; length.saturating_sub(bytes.len())];
+    padded.extend_from_slice(bytes);
+    padded
+}
+
+/// Reads the DER encoded elements of a byte sequence, throwing a DataError if they are malformed.
+struct DerReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> DerReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Reads the contents of the next element, which must have the given tag.
+    fn read(&mut self, tag: u8) -> Result<&'a [u8], Error> {
+        let (&actual_tag, rest) = self.data.split_first().ok_or(Error::Data)?;
+        if actual_tag != tag {
+            return Err(Error::Data);
+        }
+        let (&first_length_byte, mut rest) = rest.split_first().ok_or(Error::Data)?;
+        let length = if first_length_byte < 0x80 {
+            first_length_byte as usize
+        } else {
+            let length_size = (first_length_byte & 0x7f) as usize;
+            if length_size == 0 || length_size > size_of::<usize>() || rest.len() < length_size {
+                return Err(Error::Data);
+            }
+            let (length_bytes, remaining) = rest.split_at(length_size);
+            rest = remaining;
+            length_bytes
+                .iter()
+                .fold(0, |length, byte| (length << 8) | *byte as usize)
+        };
+        if rest.len() < length {
+            return Err(Error::Data);
+        }
+        let (contents, remaining) = rest.split_at(length);
+        self.data = remaining;
+        Ok(contents)
+    }
+
+    /// Reads an unsigned INTEGER, without its leading zeros.
+    fn read_integer(&mut self) -> Result<&'a [u8], Error> {
+        self.read(DER_INTEGER).map(strip_leading_zeros)
+    }
+
+    /// Throws a DataError unless all elements have been read.
+    fn finish(&self) -> Result<(), Error> {
+        if !self.data.is_empty() {
+            return Err(Error::Data);
+        }
+        Ok(())
+    }
+}
+
+/// Encodes a SubjectPublicKeyInfo, as defined in [RFC5280].
+fn encode_spki(algorithm_identifier: &[u8], public_key: &[u8]) -> Vec<u8> {
+    let contents = [
+        der_encode(DER_SEQUENCE, algorithm_identifier),
+        der_encode(DER_BIT_STRING, &[&[0][..], public_key].concat()),
+    ]
+    .concat();
+    der_encode(DER_SEQUENCE, &contents)
+}
+
+/// Returns the public key of a SubjectPublicKeyInfo, which must have the given AlgorithmIdentifier.
+fn decode_spki<'a>(spki: &'a [u8], algorithm_identifier: &[u8]) -> Result<&'a [u8], Error> {
+    let mut reader = DerReader::new(spki);
+    let mut info = DerReader::new(reader.read(DER_SEQUENCE)?);
+    reader.finish()?;
+    if info.read(DER_SEQUENCE)? != algorithm_identifier {
+        return Err(Error::Data);
+    }
+    let public_key = info.read(DER_BIT_STRING)?;
+    info.finish()?;
+
+    // The key must not have any unused bits.
+    match public_key.split_first() {
+        Some((0, public_key)) => Ok(public_key),
+        _ => Err(Error::Data),
+    }
+}
+
+/// Encodes a PrivateKeyInfo, as defined in [RFC5208].
+fn encode_pkcs8(algorithm_identifier: &[u8], private_key: &[u8]) -> Vec<u8> {
+    let contents = [
+        der_encode_integer(&[0]),
+        der_encode(DER_SEQUENCE, algorithm_identifier),
+        der_encode(DER_OCTET_STRING, private_key),
+    ]
+    .concat();
+    der_encode(DER_SEQUENCE, &contents)
+}
+
+/// Returns the private key of a PrivateKeyInfo, which must have the given AlgorithmIdentifier.
+fn decode_pkcs8<'a>(pkcs8: &'a [u8], algorithm_identifier: &[u8]) -> Result<&'a [u8], Error> {
+    let mut reader = DerReader::new(pkcs8);
+    let mut info = DerReader::new(reader.read(DER_SEQUENCE)?);
+    reader.finish()?;
+
+    // Version 2 (encoded as 1) is a OneAsymmetricKey, as defined in [RFC5958], whose
+    // additional fields are ignored.
+    if !matches!(info.read_integer()?, [] | [1]) {
+        return Err(Error::Data);
+    }
+    if info.read(DER_SEQUENCE)? != algorithm_identifier {
+        return Err(Error::Data);
+    }
+    info.read(DER_OCTET_STRING)
+}
+
+/// Encodes an elliptic curve private key with its public point in pkcs8 format.
+fn encode_ec_private_key(named_curve: NamedCurve, private_key: &[u8], point: &[u8]) -> Vec<u8> {
+    // ECPrivateKey, as defined in [RFC5915]
+    let contents = [
+        der_encode_integer(&[1]),
+        der_encode(DER_OCTET_STRING, private_key),
+        der_encode(
+            DER_EC_PUBLIC_KEY,
+            &der_encode(DER_BIT_STRING, &[&[0][..], point].concat()),
+        ),
+    ]
+    .concat();
+    encode_pkcs8(
+        &named_curve.algorithm_identifier(),
+        &der_encode(DER_SEQUENCE, &contents),
+    )
+}
+
+/// Returns the private scalar of an elliptic curve private key in pkcs8 format.
+fn decode_ec_private_key(named_curve: NamedCurve, pkcs8: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut reader = DerReader::new(decode_pkcs8(pkcs8, &named_curve.algorithm_identifier())?);
+
+    // ECPrivateKey, as defined in [RFC5915]
+    let mut key = DerReader::new(reader.read(DER_SEQUENCE)?);
+    if key.read_integer()? != [1] {
+        return Err(Error::Data);
+    }
+    let private_key = key.read(DER_OCTET_STRING)?;
+    if private_key.len() > named_curve.coordinate_length() {
+        return Err(Error::Data);
+    }
+    Ok(left_pad(private_key, named_curve.coordinate_length()))
+}
+
+/// The integers of an RSA key, big-endian and without leading zeros.
+struct RsaKeyComponents {
+    modulus: Vec<u8>,
+    public_exponent: Vec<u8>,
+    /// The private exponent, the two primes and the CRT coefficients of a private key, in the
+    /// order of an RSAPrivateKey, as defined in [RFC3447].
+    private: Option<[Vec<u8>; 6]>,
+}
+
+impl RsaKeyComponents {
+    fn from_handle(handle: &Handle) -> Result<Self, Error> {
+        match handle {
+            Handle::RsaPublicKey(spki) => Self::from_spki(spki),
+            Handle::RsaPrivateKey(pkcs8) => Self::from_pkcs8(pkcs8),
+            _ => Err(Error::InvalidAccess),
+        }
+    }
+
+    fn from_spki(spki: &[u8]) -> Result<Self, Error> {
+        // RSAPublicKey, as defined in [RFC3447]
+        let mut reader = DerReader::new(decode_spki(spki, RSA_ALGORITHM_IDENTIFIER)?);
+        let mut key = DerReader::new(reader.read(DER_SEQUENCE)?);
+        reader.finish()?;
+        let components = Self {
+            modulus: key.read_integer()?.to_vec(),
+            public_exponent: key.read_integer()?.to_vec(),
+            private: None,
+        };
+        key.finish()?;
+        Ok(components)
+    }
+
+    fn from_pkcs8(pkcs8: &[u8]) -> Result<Self, Error> {
+        // RSAPrivateKey, as defined in [RFC3447]
+        let mut reader = DerReader::new(decode_pkcs8(pkcs8, RSA_ALGORITHM_IDENTIFIER)?);
+        let mut key = DerReader::new(reader.read(DER_SEQUENCE)?);
+        reader.finish()?;
+        if !key.read_integer()?.is_empty() {
+            // Keys with more than two prime factors are not supported.
+            return Err(Error::Data);
+        }
+        Ok(Self {
+            modulus: key.read_integer()?.to_vec(),
+            public_exponent: key.read_integer()?.to_vec(),
+            private: Some([
+                key.read_integer()?.to_vec(),
+                key.read_integer()?.to_vec(),
+                key.read_integer()?.to_vec(),
+                key.read_integer()?.to_vec(),
+                key.read_integer()?.to_vec(),
+                key.read_integer()?.to_vec(),
+            ]),
+        })
+    }
+
+    /// The length of the modulus, in bits.
+    fn modulus_length(&self) -> u32 {
+        match self.modulus.first() {
+            Some(first_byte) => self.modulus.len() as u32 * 8 - first_byte.leading_zeros(),
+            None => 0,
+        }
+    }
+
+    /// Encodes the public key as an RSAPublicKey, as defined in [RFC3447].
+    fn public_key(&self) -> Vec<u8> {
+        let contents = [
+            der_encode_integer(&self.modulus),
+            der_encode_integer(&self.public_exponent),
+        ]
+        .concat();
+        der_encode(DER_SEQUENCE, &contents)
+    }
+
+    fn to_spki(&self) -> Vec<u8> {
+        encode_spki(RSA_ALGORITHM_IDENTIFIER, &self.public_key())
+    }
+
+    /// Encodes the private key in pkcs8 format, if this is a private key.
+    fn to_pkcs8(&self) -> Option<Vec<u8>> {
+        let private = self.private.as_ref()?;
+        let mut contents = [
+            der_encode_integer(&[0]),
+            der_encode_integer(&self.modulus),
+            der_encode_integer(&self.public_exponent),
+        ]
+        .concat();
+        for integer in private {
+            contents.extend(der_encode_integer(integer));
+        }
+        Some(encode_pkcs8(
+            RSA_ALGORITHM_IDENTIFIER,
+            &der_encode(DER_SEQUENCE, &contents),
+        ))
+    }
+}
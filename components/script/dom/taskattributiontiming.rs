@@ -0,0 +1,78 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use time::Duration;
+
+use crate::dom::bindings::codegen::Bindings::PerformanceLongTaskTimingBinding::TaskAttributionTimingMethods;
+use crate::dom::bindings::reflector::reflect_dom_object;
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::str::DOMString;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::performanceentry::PerformanceEntry;
+use crate::script_runtime::CanGc;
+
+/// The container of the frame a long task is attributed to.
+#[derive(JSTraceable, MallocSizeOf)]
+pub(crate) struct TaskContainer {
+    /// "iframe", "embed", "object" or "window".
+    pub(crate) type_: DOMString,
+    pub(crate) src: DOMString,
+    pub(crate) id: DOMString,
+    pub(crate) name: DOMString,
+}
+
+/// <https://w3c.github.io/longtasks/#sec-TaskAttributionTiming>
+#[dom_struct]
+pub(crate) struct TaskAttributionTiming {
+    entry: PerformanceEntry,
+    container: TaskContainer,
+}
+
+impl TaskAttributionTiming {
+    fn new_inherited(container: TaskContainer) -> TaskAttributionTiming {
+        TaskAttributionTiming {
+            // The name is "unknown", and the start time and duration are 0.
+            entry: PerformanceEntry::new_inherited(
+                DOMString::from("unknown"),
+                DOMString::from("taskattribution"),
+                None,
+                Duration::ZERO,
+            ),
+            container,
+        }
+    }
+
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    pub(crate) fn new(
+        global: &GlobalScope,
+        container: TaskContainer,
+        can_gc: CanGc,
+    ) -> DomRoot<TaskAttributionTiming> {
+        let entry = TaskAttributionTiming::new_inherited(container);
+        reflect_dom_object(Box::new(entry), global, can_gc)
+    }
+}
+
+impl TaskAttributionTimingMethods<crate::DomTypeHolder> for TaskAttributionTiming {
+    /// <https://w3c.github.io/longtasks/#dom-taskattributiontiming-containertype>
+    fn ContainerType(&self) -> DOMString {
+        self.container.type_.clone()
+    }
+
+    /// <https://w3c.github.io/longtasks/#dom-taskattributiontiming-containersrc>
+    fn ContainerSrc(&self) -> DOMString {
+        self.container.src.clone()
+    }
+
+    /// <https://w3c.github.io/longtasks/#dom-taskattributiontiming-containerid>
+    fn ContainerId(&self) -> DOMString {
+        self.container.id.clone()
+    }
+
+    /// <https://w3c.github.io/longtasks/#dom-taskattributiontiming-containername>
+    fn ContainerName(&self) -> DOMString {
+        self.container.name.clone()
+    }
+}
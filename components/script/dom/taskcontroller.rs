@@ -0,0 +1,66 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use js::rust::HandleObject;
+use script_bindings::inheritance::Castable;
+
+use crate::dom::abortcontroller::AbortController;
+use crate::dom::bindings::codegen::Bindings::SchedulerBinding::TaskPriority;
+use crate::dom::bindings::codegen::Bindings::TaskControllerBinding::{
+    TaskControllerInit, TaskControllerMethods,
+};
+use crate::dom::bindings::error::ErrorResult;
+use crate::dom::bindings::reflector::reflect_dom_object_with_proto;
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::tasksignal::TaskSignal;
+use crate::script_runtime::CanGc;
+
+/// <https://wicg.github.io/scheduling-apis/#taskcontroller>
+#[dom_struct]
+pub(crate) struct TaskController {
+    abortcontroller: AbortController,
+}
+
+impl TaskController {
+    fn new_inherited(signal: &TaskSignal) -> TaskController {
+        TaskController {
+            abortcontroller: AbortController::new_inherited(signal.upcast()),
+        }
+    }
+}
+
+impl TaskControllerMethods<crate::DomTypeHolder> for TaskController {
+    /// <https://wicg.github.io/scheduling-apis/#dom-taskcontroller-taskcontroller>
+    fn Constructor(
+        global: &GlobalScope,
+        proto: Option<HandleObject>,
+        can_gc: CanGc,
+        init: &TaskControllerInit,
+    ) -> DomRoot<TaskController> {
+        // Step 1. Let signal be a new TaskSignal object.
+        // Step 2. Set signal’s priority to init["priority"].
+        let signal = TaskSignal::new(global, init.priority, can_gc);
+
+        // Step 3. Set this’s signal to signal.
+        reflect_dom_object_with_proto(
+            Box::new(TaskController::new_inherited(&signal)),
+            global,
+            proto,
+            can_gc,
+        )
+    }
+
+    /// <https://wicg.github.io/scheduling-apis/#dom-taskcontroller-setpriority>
+    fn SetPriority(&self, priority: TaskPriority, can_gc: CanGc) -> ErrorResult {
+        // The setPriority(priority) method steps are to signal priority change on
+        // this’s signal given priority.
+        let signal = self.abortcontroller.signal();
+        signal
+            .downcast::<TaskSignal>()
+            .expect("A TaskController's signal is always a TaskSignal")
+            .signal_priority_change(priority, can_gc)
+    }
+}
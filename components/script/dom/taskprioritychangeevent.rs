@@ -0,0 +1,106 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use js::rust::HandleObject;
+use stylo_atoms::Atom;
+
+use crate::dom::bindings::codegen::Bindings::EventBinding::EventMethods;
+use crate::dom::bindings::codegen::Bindings::SchedulerBinding::TaskPriority;
+use crate::dom::bindings::codegen::Bindings::TaskPriorityChangeEventBinding;
+use crate::dom::bindings::codegen::Bindings::TaskPriorityChangeEventBinding::TaskPriorityChangeEventMethods;
+use crate::dom::bindings::error::Fallible;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::reflect_dom_object_with_proto;
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::str::DOMString;
+use crate::dom::event::{Event, EventBubbles, EventCancelable};
+use crate::dom::globalscope::GlobalScope;
+use crate::script_runtime::CanGc;
+
+// https://wicg.github.io/scheduling-apis/#taskprioritychangeevent
+#[dom_struct]
+pub(crate) struct TaskPriorityChangeEvent {
+    event: Event,
+    previous_priority: TaskPriority,
+}
+
+impl TaskPriorityChangeEvent {
+    fn new_inherited(previous_priority: TaskPriority) -> TaskPriorityChangeEvent {
+        TaskPriorityChangeEvent {
+            event: Event::new_inherited(),
+            previous_priority,
+        }
+    }
+
+    pub(crate) fn new(
+        global: &GlobalScope,
+        type_: Atom,
+        bubbles: EventBubbles,
+        cancelable: EventCancelable,
+        previous_priority: TaskPriority,
+        can_gc: CanGc,
+    ) -> DomRoot<TaskPriorityChangeEvent> {
+        Self::new_with_proto(
+            global,
+            None,
+            type_,
+            bubbles,
+            cancelable,
+            previous_priority,
+            can_gc,
+        )
+    }
+
+    fn new_with_proto(
+        global: &GlobalScope,
+        proto: Option<HandleObject>,
+        type_: Atom,
+        bubbles: EventBubbles,
+        cancelable: EventCancelable,
+        previous_priority: TaskPriority,
+        can_gc: CanGc,
+    ) -> DomRoot<TaskPriorityChangeEvent> {
+        let ev = reflect_dom_object_with_proto(
+            Box::new(TaskPriorityChangeEvent::new_inherited(previous_priority)),
+            global,
+            proto,
+            can_gc,
+        );
+        ev.upcast::<Event>()
+            .init_event(type_, bool::from(bubbles), bool::from(cancelable));
+        ev
+    }
+}
+
+impl TaskPriorityChangeEventMethods<crate::DomTypeHolder> for TaskPriorityChangeEvent {
+    // https://wicg.github.io/scheduling-apis/#dom-taskprioritychangeevent-taskprioritychangeevent
+    fn Constructor(
+        global: &GlobalScope,
+        proto: Option<HandleObject>,
+        can_gc: CanGc,
+        type_: DOMString,
+        init: &TaskPriorityChangeEventBinding::TaskPriorityChangeEventInit,
+    ) -> Fallible<DomRoot<TaskPriorityChangeEvent>> {
+        Ok(TaskPriorityChangeEvent::new_with_proto(
+            global,
+            proto,
+            Atom::from(type_),
+            EventBubbles::from(init.parent.bubbles),
+            EventCancelable::from(init.parent.cancelable),
+            init.previousPriority,
+            can_gc,
+        ))
+    }
+
+    // https://wicg.github.io/scheduling-apis/#dom-taskprioritychangeevent-previouspriority
+    fn PreviousPriority(&self) -> TaskPriority {
+        self.previous_priority
+    }
+
+    // https://dom.spec.whatwg.org/#dom-event-istrusted
+    fn IsTrusted(&self) -> bool {
+        self.event.IsTrusted()
+    }
+}
@@ -0,0 +1,175 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::cell::Cell;
+
+use dom_struct::dom_struct;
+use script_bindings::inheritance::Castable;
+use stylo_atoms::Atom;
+
+use crate::dom::abortsignal::AbortSignal;
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::SchedulerBinding::TaskPriority;
+use crate::dom::bindings::codegen::Bindings::TaskSignalBinding::{
+    TaskSignalAnyInit, TaskSignalMethods,
+};
+use crate::dom::bindings::codegen::UnionTypes::TaskPriorityOrTaskSignal;
+use crate::dom::bindings::error::{Error, ErrorResult};
+use crate::dom::bindings::reflector::{DomGlobal, reflect_dom_object_with_proto};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::event::{Event, EventBubbles, EventCancelable};
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::taskprioritychangeevent::TaskPriorityChangeEvent;
+use crate::script_runtime::CanGc;
+
+/// <https://wicg.github.io/scheduling-apis/#tasksignal>
+#[dom_struct]
+pub(crate) struct TaskSignal {
+    abortsignal: AbortSignal,
+
+    /// <https://wicg.github.io/scheduling-apis/#tasksignal-priority>
+    priority: Cell<TaskPriority>,
+
+    /// <https://wicg.github.io/scheduling-apis/#tasksignal-priority-changing>
+    priority_changing: Cell<bool>,
+
+    /// Whether this is a signal created by `TaskSignal.any()` with a fixed priority,
+    /// whose priority can never change.
+    /// <https://wicg.github.io/scheduling-apis/#tasksignal-has-fixed-priority>
+    fixed_priority: Cell<bool>,
+
+    /// The signals created by `TaskSignal.any()` that follow the priority of this one.
+    /// <https://wicg.github.io/scheduling-apis/#tasksignal-dependent-signals>
+    dependent_signals: DomRefCell<Vec<Dom<TaskSignal>>>,
+}
+
+impl TaskSignal {
+    fn new_inherited(priority: TaskPriority) -> TaskSignal {
+        TaskSignal {
+            abortsignal: AbortSignal::new_inherited(),
+            priority: Cell::new(priority),
+            priority_changing: Default::default(),
+            fixed_priority: Default::default(),
+            dependent_signals: Default::default(),
+        }
+    }
+
+    pub(crate) fn new(
+        global: &GlobalScope,
+        priority: TaskPriority,
+        can_gc: CanGc,
+    ) -> DomRoot<TaskSignal> {
+        reflect_dom_object_with_proto(
+            Box::new(TaskSignal::new_inherited(priority)),
+            global,
+            None,
+            can_gc,
+        )
+    }
+
+    pub(crate) fn priority(&self) -> TaskPriority {
+        self.priority.get()
+    }
+
+    /// <https://wicg.github.io/scheduling-apis/#tasksignal-signal-priority-change>
+    pub(crate) fn signal_priority_change(
+        &self,
+        priority: TaskPriority,
+        can_gc: CanGc,
+    ) -> ErrorResult {
+        // Step 1. If signal’s priority changing is true, then throw a "NotAllowedError" DOMException.
+        if self.priority_changing.get() {
+            return Err(Error::NotAllowed);
+        }
+
+        // Step 2. If signal’s priority equals priority, then return.
+        if self.priority.get() == priority {
+            return Ok(());
+        }
+
+        // Step 3. Set signal’s priority changing to true.
+        self.priority_changing.set(true);
+
+        // Step 4. Let previousPriority be signal’s priority.
+        // Step 5. Set signal’s priority to priority.
+        let previous_priority = self.priority.replace(priority);
+
+        // Step 6. Fire an event named prioritychange at signal using TaskPriorityChangeEvent,
+        // with its previousPriority attribute initialized to previousPriority.
+        let event = TaskPriorityChangeEvent::new(
+            &self.global(),
+            Atom::from("prioritychange"),
+            EventBubbles::DoesNotBubble,
+            EventCancelable::NotCancelable,
+            previous_priority,
+            can_gc,
+        );
+        event.upcast::<Event>().fire(self.upcast(), can_gc);
+
+        // Step 7. For each dependentSignal of signal’s dependent signals,
+        // signal priority change on dependentSignal with priority.
+        let dependent_signals: Vec<_> = self
+            .dependent_signals
+            .borrow()
+            .iter()
+            .map(|signal| signal.as_rooted())
+            .collect();
+        for dependent_signal in dependent_signals {
+            let _ = dependent_signal.signal_priority_change(priority, can_gc);
+        }
+
+        // Step 8. Set signal’s priority changing to false.
+        self.priority_changing.set(false);
+        Ok(())
+    }
+}
+
+impl TaskSignalMethods<crate::DomTypeHolder> for TaskSignal {
+    /// <https://wicg.github.io/scheduling-apis/#dom-tasksignal-any>
+    fn Any(
+        global: &GlobalScope,
+        signals: Vec<DomRoot<AbortSignal>>,
+        init: &TaskSignalAnyInit,
+        can_gc: CanGc,
+    ) -> DomRoot<TaskSignal> {
+        // The steps to create a dependent task signal:
+        // <https://wicg.github.io/scheduling-apis/#create-a-dependent-task-signal>
+        // Let resultSignal be the result of creating a dependent signal from signals
+        // using the TaskSignal interface and realm.
+        let result_signal = TaskSignal::new(global, TaskPriority::User_visible, can_gc);
+        result_signal.upcast::<AbortSignal>().depend_on(&signals);
+
+        match &init.priority {
+            // If priority is a TaskSignal object, resultSignal follows its priority,
+            // unless that priority is fixed.
+            TaskPriorityOrTaskSignal::TaskSignal(source_signal) => {
+                result_signal.priority.set(source_signal.priority.get());
+                if source_signal.fixed_priority.get() {
+                    result_signal.fixed_priority.set(true);
+                } else {
+                    source_signal
+                        .dependent_signals
+                        .borrow_mut()
+                        .push(Dom::from_ref(&*result_signal));
+                }
+            },
+            // Otherwise, set resultSignal’s priority to priority, which is then fixed.
+            TaskPriorityOrTaskSignal::TaskPriority(priority) => {
+                result_signal.priority.set(*priority);
+                result_signal.fixed_priority.set(true);
+            },
+        }
+
+        // Return resultSignal.
+        result_signal
+    }
+
+    /// <https://wicg.github.io/scheduling-apis/#dom-tasksignal-priority>
+    fn Priority(&self) -> TaskPriority {
+        self.priority.get()
+    }
+
+    // <https://wicg.github.io/scheduling-apis/#dom-tasksignal-onprioritychange>
+    event_handler!(prioritychange, GetOnprioritychange, SetOnprioritychange);
+}
@@ -2,6 +2,9 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use std::cell::Cell;
+use std::ops::Range;
+
 use dom_struct::dom_struct;
 use js::rust::HandleObject;
 
@@ -12,13 +15,13 @@ use crate::dom::bindings::codegen::Bindings::TextBinding::TextMethods;
 use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
 use crate::dom::bindings::error::{Error, Fallible};
 use crate::dom::bindings::inheritance::Castable;
-use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::root::{Dom, DomRoot, LayoutDom};
 use crate::dom::bindings::str::DOMString;
-use crate::dom::characterdata::CharacterData;
+use crate::dom::characterdata::{CharacterData, LayoutCharacterDataHelpers, utf8_offset};
 use crate::dom::document::Document;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::htmlslotelement::{HTMLSlotElement, Slottable};
-use crate::dom::node::Node;
+use crate::dom::node::{Node, NodeDamage};
 use crate::dom::window::Window;
 use crate::script_runtime::CanGc;
 
@@ -26,12 +29,16 @@ use crate::script_runtime::CanGc;
 #[dom_struct]
 pub(crate) struct Text {
     characterdata: CharacterData,
+    /// The range of this node's data that is covered by the document's selection, in UTF-16
+    /// code units, as it should be painted by layout. A collapsed range is painted as a caret.
+    selected_range: Cell<Option<(u32, u32)>>,
 }
 
 impl Text {
     pub(crate) fn new_inherited(text: DOMString, document: &Document) -> Text {
         Text {
             characterdata: CharacterData::new_inherited(text, document),
+            selected_range: Cell::new(None),
         }
     }
 
@@ -52,6 +59,32 @@ impl Text {
             can_gc,
         )
     }
+
+    /// Update the part of this node that is painted as selected, given as offsets in
+    /// UTF-16 code units.
+    pub(crate) fn set_selected_range(&self, range: Option<Range<u32>>) {
+        let range = range.map(|range| (range.start, range.end));
+        if self.selected_range.get() != range {
+            self.selected_range.set(range);
+            self.upcast::<Node>().dirty(NodeDamage::Other);
+        }
+    }
+
+    /// Whether any part of this node is painted as selected, or contains the caret.
+    pub(crate) fn is_painted_as_selected(&self) -> bool {
+        self.selected_range.get().is_some()
+    }
+}
+
+#[allow(unsafe_code)]
+impl LayoutDom<'_, Text> {
+    /// The byte range of this node's data that is painted as selected. This is computed from
+    /// the current data, so that it is always at character boundaries.
+    pub(crate) fn selection_for_layout(self) -> Option<Range<usize>> {
+        let (start, end) = unsafe { self.unsafe_get().selected_range.get() }?;
+        let data = self.upcast::<CharacterData>().data_for_layout();
+        Some(utf8_offset(data, start)..utf8_offset(data, end))
+    }
 }
 
 impl TextMethods<crate::DomTypeHolder> for Text {
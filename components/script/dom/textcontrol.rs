@@ -314,6 +314,17 @@ impl<'a, E: TextControlElement> TextControlSelection<'a, E> {
                     EventBubbles::Bubbles,
                     EventCancelable::NotCancelable,
                 );
+            // <https://w3c.github.io/selection-api/#scheduling-selectionhange-event>
+            self.element
+                .owner_global()
+                .task_manager()
+                .user_interaction_task_source()
+                .queue_event(
+                    self.element.upcast::<EventTarget>(),
+                    atom!("selectionchange"),
+                    EventBubbles::Bubbles,
+                    EventCancelable::NotCancelable,
+                );
         }
 
         self.element.upcast::<Node>().dirty(NodeDamage::Other);
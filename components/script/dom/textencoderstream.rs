@@ -0,0 +1,225 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::{ptr, slice};
+
+use dom_struct::dom_struct;
+use js::jsapi::{
+    JS_DeprecatedStringHasLatin1Chars, JS_GetLatin1StringCharsAndLength,
+    JS_GetTwoByteStringCharsAndLength, JSObject,
+};
+use js::jsval::{ObjectValue, UndefinedValue};
+use js::rust::{HandleObject as SafeHandleObject, HandleValue as SafeHandleValue, ToString};
+use js::typedarray::Uint8;
+
+use crate::DomTypes;
+use crate::dom::bindings::buffer_source::create_buffer_source;
+use crate::dom::bindings::codegen::Bindings::TextEncoderStreamBinding::TextEncoderStreamMethods;
+use crate::dom::bindings::error::{Error, Fallible};
+use crate::dom::bindings::reflector::{Reflector, reflect_dom_object_with_proto};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::transformstreamdefaultcontroller::TransformerType;
+use crate::dom::types::{TransformStream, TransformStreamDefaultController};
+use crate::script_runtime::{CanGc, JSContext as SafeJSContext};
+
+/// The UTF-8 encoding of U+FFFD REPLACEMENT CHARACTER.
+const REPLACEMENT_CHARACTER_BYTES: [u8; 3] = [0xEF, 0xBF, 0xBD];
+
+/// The state of a `TextEncoderStream`, shared with its transform algorithms.
+#[derive(Default, JSTraceable, MallocSizeOf)]
+pub(crate) struct TextEncoderStreamEncoder {
+    /// <https://encoding.spec.whatwg.org/#textencoderstream-pending-high-surrogate>
+    leading_surrogate: Cell<Option<u16>>,
+}
+
+impl TextEncoderStreamEncoder {
+    /// <https://encoding.spec.whatwg.org/#convert-code-unit-to-scalar-value>
+    ///
+    /// Converts `code_units` to UTF-8, carrying a trailing leading surrogate over
+    /// to the next chunk.
+    fn encode(&self, code_units: &[u16]) -> Vec<u8> {
+        let mut output = Vec::with_capacity(code_units.len());
+        let mut buffer = [0; 4];
+        let mut push = |c: char| output.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+
+        for &item in code_units {
+            // Step 1. If encoder’s leading surrogate is non-null:
+            if let Some(leading) = self.leading_surrogate.take() {
+                // Step 1.3. If item is a trailing surrogate, then return a scalar value
+                // from surrogates given leadingSurrogate and item.
+                if (0xDC00..=0xDFFF).contains(&item) {
+                    let scalar =
+                        0x10000 + ((leading as u32 - 0xD800) << 10) + (item as u32 - 0xDC00);
+                    push(char::from_u32(scalar).expect("surrogate pair is a scalar value"));
+                    continue;
+                }
+                // Step 1.4. Restore item to input.
+                // Step 1.5. Return U+FFFD.
+                push(char::REPLACEMENT_CHARACTER);
+            }
+            // Step 2. If item is a leading surrogate, then set encoder’s leading
+            // surrogate to item and return continue.
+            if (0xD800..=0xDBFF).contains(&item) {
+                self.leading_surrogate.set(Some(item));
+                continue;
+            }
+            // Step 3. If item is a trailing surrogate, then return U+FFFD.
+            // Step 4. Return item.
+            push(char::from_u32(item as u32).unwrap_or(char::REPLACEMENT_CHARACTER));
+        }
+        output
+    }
+}
+
+/// Enqueues `bytes` as a `Uint8Array` in the transform of `controller`.
+fn enqueue_bytes(
+    cx: SafeJSContext,
+    global: &GlobalScope,
+    bytes: &[u8],
+    controller: &TransformStreamDefaultController,
+    can_gc: CanGc,
+) -> Fallible<()> {
+    rooted!(in(*cx) let mut array = ptr::null_mut::<JSObject>());
+    create_buffer_source::<Uint8>(cx, bytes, array.handle_mut(), can_gc)
+        .map_err(|_| Error::JSFailed)?;
+    rooted!(in(*cx) let mut chunk = UndefinedValue());
+    chunk.set(ObjectValue(array.get()));
+    controller.enqueue(cx, global, chunk.handle(), can_gc)
+}
+
+/// <https://encoding.spec.whatwg.org/#encode-and-enqueue-a-chunk>
+#[allow(unsafe_code)]
+pub(crate) fn encode_and_enqueue_a_chunk(
+    cx: SafeJSContext,
+    global: &GlobalScope,
+    chunk: SafeHandleValue,
+    encoder: &TextEncoderStreamEncoder,
+    controller: &TransformStreamDefaultController,
+    can_gc: CanGc,
+) -> Fallible<()> {
+    // Step 1. Let input be the result of converting chunk to a DOMString.
+    // Step 2. Convert input to an I/O queue of code units.
+    // Note: the code units are read directly from the JS string, as lone surrogates
+    // must be preserved until they are converted below.
+    let output = unsafe {
+        let jsstr = ToString(*cx, chunk);
+        if jsstr.is_null() {
+            return Err(Error::JSFailed);
+        }
+        let mut length = 0;
+        if JS_DeprecatedStringHasLatin1Chars(jsstr) {
+            let chars = JS_GetLatin1StringCharsAndLength(*cx, ptr::null(), jsstr, &mut length);
+            assert!(!chars.is_null());
+            let code_units: Vec<u16> = slice::from_raw_parts(chars, length)
+                .iter()
+                .map(|&c| c as u16)
+                .collect();
+            encoder.encode(&code_units)
+        } else {
+            let chars = JS_GetTwoByteStringCharsAndLength(*cx, ptr::null(), jsstr, &mut length);
+            assert!(!chars.is_null());
+            encoder.encode(slice::from_raw_parts(chars, length))
+        }
+    };
+
+    // Step 3. Let output be the I/O queue of bytes « end-of-queue ».
+    // Step 4. While true:
+    // Step 4.1. Let item be the result of reading from input.
+    // Step 4.2. If item is end-of-queue:
+    // Step 4.2.1. Convert output into a byte sequence.
+    // Step 4.2.2. If output is not empty:
+    if output.is_empty() {
+        return Ok(());
+    }
+    // Step 4.2.2.1. Let chunk be the result of creating a Uint8Array object given
+    //      output and encoder’s relevant realm.
+    // Step 4.2.2.2. Enqueue chunk into encoder’s transform.
+    enqueue_bytes(cx, global, &output, controller, can_gc)
+}
+
+/// <https://encoding.spec.whatwg.org/#encode-and-flush>
+pub(crate) fn encode_and_flush(
+    cx: SafeJSContext,
+    global: &GlobalScope,
+    encoder: &TextEncoderStreamEncoder,
+    controller: &TransformStreamDefaultController,
+    can_gc: CanGc,
+) -> Fallible<()> {
+    // Step 1. If encoder’s leading surrogate is non-null:
+    if encoder.leading_surrogate.take().is_none() {
+        return Ok(());
+    }
+    // Step 1.1. Let chunk be the result of creating a Uint8Array object given
+    //      « 0xEF, 0xBF, 0xBD » and encoder’s relevant realm.
+    // Step 1.2. Enqueue chunk into encoder’s transform.
+    enqueue_bytes(cx, global, &REPLACEMENT_CHARACTER_BYTES, controller, can_gc)
+}
+
+/// <https://encoding.spec.whatwg.org/#textencoderstream>
+#[dom_struct]
+pub(crate) struct TextEncoderStream {
+    reflector_: Reflector,
+
+    /// <https://streams.spec.whatwg.org/#generictransformstream>
+    transform: Dom<TransformStream>,
+}
+
+impl TextEncoderStream {
+    fn new_inherited(transform: &TransformStream) -> TextEncoderStream {
+        TextEncoderStream {
+            reflector_: Reflector::new(),
+            transform: Dom::from_ref(transform),
+        }
+    }
+
+    fn new_with_proto(
+        cx: SafeJSContext,
+        global: &GlobalScope,
+        proto: Option<SafeHandleObject>,
+        can_gc: CanGc,
+    ) -> Fallible<DomRoot<Self>> {
+        let encoder = Rc::new(TextEncoderStreamEncoder::default());
+        let transformer_type = TransformerType::Encoder(encoder);
+
+        let transform_stream = TransformStream::new_with_proto(global, None, can_gc);
+        transform_stream.set_up(cx, global, transformer_type, can_gc)?;
+
+        Ok(reflect_dom_object_with_proto(
+            Box::new(TextEncoderStream::new_inherited(&transform_stream)),
+            global,
+            proto,
+            can_gc,
+        ))
+    }
+}
+
+impl TextEncoderStreamMethods<crate::DomTypeHolder> for TextEncoderStream {
+    /// <https://encoding.spec.whatwg.org/#dom-textencoderstream>
+    fn Constructor(
+        global: &GlobalScope,
+        proto: Option<SafeHandleObject>,
+        can_gc: CanGc,
+    ) -> Fallible<DomRoot<TextEncoderStream>> {
+        Self::new_with_proto(GlobalScope::get_cx(), global, proto, can_gc)
+    }
+
+    /// <https://encoding.spec.whatwg.org/#dom-textencoder-encoding>
+    fn Encoding(&self) -> DOMString {
+        DOMString::from("utf-8")
+    }
+
+    /// <https://streams.spec.whatwg.org/#dom-generictransformstream-readable>
+    fn Readable(&self) -> DomRoot<<crate::DomTypeHolder as DomTypes>::ReadableStream> {
+        self.transform.get_readable()
+    }
+
+    /// <https://streams.spec.whatwg.org/#dom-generictransformstream-writable>
+    fn Writable(&self) -> DomRoot<<crate::DomTypeHolder as DomTypes>::WritableStream> {
+        self.transform.get_writable()
+    }
+}
@@ -0,0 +1,110 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use dom_struct::dom_struct;
+use js::rust::HandleObject;
+use stylo_atoms::Atom;
+
+use crate::dom::bindings::codegen::Bindings::EventBinding::EventMethods;
+use crate::dom::bindings::codegen::Bindings::ToggleEventBinding;
+use crate::dom::bindings::codegen::Bindings::ToggleEventBinding::ToggleEventMethods;
+use crate::dom::bindings::error::Fallible;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::reflect_dom_object_with_proto;
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::str::DOMString;
+use crate::dom::event::{Event, EventBubbles, EventCancelable};
+use crate::dom::window::Window;
+use crate::script_runtime::CanGc;
+
+// https://html.spec.whatwg.org/multipage/#the-toggleevent-interface
+#[dom_struct]
+pub(crate) struct ToggleEvent {
+    event: Event,
+    old_state: DOMString,
+    new_state: DOMString,
+}
+
+impl ToggleEvent {
+    fn new_inherited(old_state: DOMString, new_state: DOMString) -> ToggleEvent {
+        ToggleEvent {
+            event: Event::new_inherited(),
+            old_state,
+            new_state,
+        }
+    }
+
+    pub(crate) fn new(
+        window: &Window,
+        type_: Atom,
+        bubbles: EventBubbles,
+        cancelable: EventCancelable,
+        old_state: DOMString,
+        new_state: DOMString,
+        can_gc: CanGc,
+    ) -> DomRoot<ToggleEvent> {
+        Self::new_with_proto(
+            window, None, type_, bubbles, cancelable, old_state, new_state, can_gc,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn new_with_proto(
+        window: &Window,
+        proto: Option<HandleObject>,
+        type_: Atom,
+        bubbles: EventBubbles,
+        cancelable: EventCancelable,
+        old_state: DOMString,
+        new_state: DOMString,
+        can_gc: CanGc,
+    ) -> DomRoot<ToggleEvent> {
+        let ev = reflect_dom_object_with_proto(
+            Box::new(ToggleEvent::new_inherited(old_state, new_state)),
+            window,
+            proto,
+            can_gc,
+        );
+        ev.upcast::<Event>()
+            .init_event(type_, bool::from(bubbles), bool::from(cancelable));
+        ev
+    }
+}
+
+impl ToggleEventMethods<crate::DomTypeHolder> for ToggleEvent {
+    // https://html.spec.whatwg.org/multipage/#toggleevent
+    fn Constructor(
+        window: &Window,
+        proto: Option<HandleObject>,
+        can_gc: CanGc,
+        type_: DOMString,
+        init: &ToggleEventBinding::ToggleEventInit,
+    ) -> Fallible<DomRoot<ToggleEvent>> {
+        Ok(ToggleEvent::new_with_proto(
+            window,
+            proto,
+            Atom::from(type_),
+            EventBubbles::from(init.parent.bubbles),
+            EventCancelable::from(init.parent.cancelable),
+            init.oldState.clone(),
+            init.newState.clone(),
+            can_gc,
+        ))
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-toggleevent-oldstate
+    fn OldState(&self) -> DOMString {
+        self.old_state.clone()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-toggleevent-newstate
+    fn NewState(&self) -> DOMString {
+        self.new_state.clone()
+    }
+
+    // https://dom.spec.whatwg.org/#dom-event-istrusted
+    fn IsTrusted(&self) -> bool {
+        self.event.IsTrusted()
+    }
+}
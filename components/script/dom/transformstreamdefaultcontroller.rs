@@ -23,11 +23,15 @@ use crate::dom::bindings::codegen::Bindings::TransformerBinding::Transformer;
 use crate::dom::bindings::error::{Error, ErrorToJsval, Fallible};
 use crate::dom::bindings::reflector::{DomGlobal, Reflector, reflect_dom_object};
 use crate::dom::bindings::root::{Dom, DomRoot, MutNullableDom};
+use crate::dom::compressionstream::CompressionTransformer;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::promise::Promise;
 use crate::dom::promisenativehandler::{Callback, PromiseNativeHandler};
 use crate::dom::textdecodercommon::TextDecoderCommon;
 use crate::dom::textdecoderstream::{decode_and_enqueue_a_chunk, flush_and_enqueue};
+use crate::dom::textencoderstream::{
+    TextEncoderStreamEncoder, encode_and_enqueue_a_chunk, encode_and_flush,
+};
 use crate::realms::{InRealm, enter_realm};
 use crate::script_runtime::{CanGc, JSContext as SafeJSContext};
 
@@ -75,6 +79,14 @@ pub(crate) enum TransformerType {
     ///
     /// <https://encoding.spec.whatwg.org/#textdecodercommon>
     Decoder(Rc<TextDecoderCommon>),
+    /// Algorithms supporting `TextEncoderStream` are implemented in Rust
+    ///
+    /// <https://encoding.spec.whatwg.org/#textencoderstream-encoder>
+    Encoder(Rc<TextEncoderStreamEncoder>),
+    /// Algorithms supporting `CompressionStream` and `DecompressionStream` are implemented in Rust
+    ///
+    /// <https://compression.spec.whatwg.org/#generic-transform-stream>
+    Compression(Rc<CompressionTransformer>),
 }
 
 impl TransformerType {
@@ -256,6 +268,30 @@ impl TransformStreamDefaultController {
                         p
                     })
             },
+            TransformerType::Encoder(encoder) => {
+                // <https://encoding.spec.whatwg.org/#dom-textencoderstream>
+                // Step 2. Let transformAlgorithm be an algorithm which takes a
+                // chunk argument and runs the encode and enqueue a chunk
+                // algorithm with this and chunk.
+                encode_and_enqueue_a_chunk(cx, global, chunk, encoder, self, can_gc)
+                    // <https://streams.spec.whatwg.org/#transformstream-set-up>
+                    // Step 5.3 Return a promise resolved with undefined.
+                    .map(|_| Promise::new_resolved(global, cx, (), can_gc))
+                    .unwrap_or_else(|e| {
+                        let realm = enter_realm(self);
+                        let p = Promise::new_in_current_realm((&realm).into(), can_gc);
+                        p.reject_error(e, can_gc);
+                        p
+                    })
+            },
+            TransformerType::Compression(transformer) => {
+                // <https://compression.spec.whatwg.org/#dom-compressionstream-compressionstream>
+                // Step 3. Let transformAlgorithm be an algorithm which takes a chunk argument
+                // and runs the compress and enqueue a chunk algorithm with this and chunk.
+                // Note: the algorithm returns a promise, as large chunks are processed off
+                // the main thread.
+                transformer.transform(cx, global, chunk, self, can_gc)
+            },
         };
 
         Ok(result)
@@ -311,6 +347,18 @@ impl TransformStreamDefaultController {
                 // Step 7.3 Return a promise resolved with undefined.
                 Promise::new_resolved(global, cx, (), can_gc)
             },
+            TransformerType::Encoder(_) => {
+                // <https://streams.spec.whatwg.org/#transformstream-set-up>
+                // Step 7.3 Return a promise resolved with undefined.
+                // Note: `TextEncoderStream` does NOT specify a cancel algorithm.
+                Promise::new_resolved(global, cx, (), can_gc)
+            },
+            TransformerType::Compression(_) => {
+                // <https://streams.spec.whatwg.org/#transformstream-set-up>
+                // Step 7.3 Return a promise resolved with undefined.
+                // Note: compression streams do NOT specify a cancel algorithm.
+                Promise::new_resolved(global, cx, (), can_gc)
+            },
         };
 
         Ok(result)
@@ -376,6 +424,27 @@ impl TransformStreamDefaultController {
                         p
                     })
             },
+            TransformerType::Encoder(encoder) => {
+                // <https://encoding.spec.whatwg.org/#dom-textencoderstream>
+                // Step 3. Let flushAlgorithm be an algorithm which runs the
+                // encode and flush algorithm with this.
+                encode_and_flush(cx, global, encoder, self, can_gc)
+                    // <https://streams.spec.whatwg.org/#transformstream-set-up>
+                    // Step 6.3 Return a promise resolved with undefined.
+                    .map(|_| Promise::new_resolved(global, cx, (), can_gc))
+                    .unwrap_or_else(|e| {
+                        let realm = enter_realm(self);
+                        let p = Promise::new_in_current_realm((&realm).into(), can_gc);
+                        p.reject_error(e, can_gc);
+                        p
+                    })
+            },
+            TransformerType::Compression(transformer) => {
+                // <https://compression.spec.whatwg.org/#dom-compressionstream-compressionstream>
+                // Step 4. Let flushAlgorithm be an algorithm which takes no argument
+                // and runs the compress flush and enqueue algorithm with this.
+                transformer.flush(cx, global, self, can_gc)
+            },
         };
 
         Ok(result)
@@ -4,9 +4,12 @@
 use std::fmt;
 
 use dom_struct::dom_struct;
+use js::jsapi::CompilationType;
+use js::rust::HandleValue;
 
 use crate::dom::bindings::codegen::Bindings::TrustedScriptBinding::TrustedScriptMethods;
 use crate::dom::bindings::codegen::UnionTypes::TrustedScriptOrString;
+use crate::dom::bindings::conversions::root_from_handlevalue;
 use crate::dom::bindings::error::Fallible;
 use crate::dom::bindings::reflector::{Reflector, reflect_dom_object};
 use crate::dom::bindings::root::DomRoot;
@@ -14,7 +17,7 @@ use crate::dom::bindings::str::DOMString;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::trustedtypepolicy::TrustedType;
 use crate::dom::trustedtypepolicyfactory::TrustedTypePolicyFactory;
-use crate::script_runtime::CanGc;
+use crate::script_runtime::{CanGc, JSContext};
 
 #[dom_struct]
 pub struct TrustedScript {
@@ -59,6 +62,59 @@ impl TrustedScript {
             TrustedScriptOrString::TrustedScript(trusted_script) => Ok(trusted_script.data.clone()),
         }
     }
+
+    /// Step 2 of <https://w3c.github.io/webappsec-csp/#can-compile-strings>
+    ///
+    /// Returns false when compilation must be blocked, in which case the caller
+    /// throws an `EvalError`.
+    #[allow(unsafe_code)]
+    pub(crate) fn can_compile_string_with_trusted_type(
+        cx: JSContext,
+        global: &GlobalScope,
+        code_string: DOMString,
+        compilation_type: CompilationType,
+        body_string: DOMString,
+        body_arg: HandleValue,
+        can_gc: CanGc,
+    ) -> bool {
+        // Step 2.1. Let compilationSink be "Function" if compilationType is "FUNCTION",
+        // and "eval" otherwise.
+        let compilation_sink = if compilation_type == CompilationType::Function {
+            "Function"
+        } else {
+            "eval"
+        };
+        // Step 2.2. Let isTrusted be true if bodyArg implements TrustedScript, and false otherwise.
+        // Step 2.3. If isTrusted is true then:
+        // Step 2.3.1. If bodyString is not equal to bodyArg’s data, set isTrusted to false.
+        // TODO Step 2.4. If isTrusted is true, then check each of parameterArgs, which
+        // are not yet exposed to the CSP callback by the bindings.
+        let is_trusted = unsafe { root_from_handlevalue::<TrustedScript>(body_arg, *cx) }
+            .is_ok_and(|trusted_script| trusted_script.data == body_string);
+        // Step 2.5. If isTrusted is true, let sourceToValidate be a new TrustedScript
+        // object created in realm whose data is set to codeString.
+        // Note: such an object is always compliant, so validation can be skipped.
+        if is_trusted {
+            return true;
+        }
+        // Step 2.6. Let sourceString be the result of executing the Get Trusted Type
+        // compliant string algorithm, with TrustedScript, realm, sourceToValidate,
+        // compilationSink, and 'script'.
+        let source_string = TrustedTypePolicyFactory::get_trusted_type_compliant_string(
+            TrustedType::TrustedScript,
+            global,
+            code_string.clone(),
+            compilation_sink,
+            "'script'",
+            can_gc,
+        );
+        match source_string {
+            // Step 2.7. If the algorithm throws an error, throw an EvalError.
+            Err(_) => false,
+            // Step 2.8. If sourceString is not equal to codeString, throw an EvalError.
+            Ok(source_string) => source_string == code_string,
+        }
+    }
 }
 
 impl fmt::Display for TrustedScript {
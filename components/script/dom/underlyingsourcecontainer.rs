@@ -20,6 +20,7 @@ use crate::dom::defaultteeunderlyingsource::DefaultTeeUnderlyingSource;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::messageport::MessagePort;
 use crate::dom::promise::Promise;
+use crate::dom::response::ResponseBodyFlowControl;
 use crate::dom::transformstream::TransformStream;
 use crate::script_runtime::{CanGc, JSContext as SafeJSContext};
 
@@ -35,8 +36,9 @@ pub(crate) enum UnderlyingSourceType {
     Memory(usize),
     /// A blob as underlying source, with a known total size.
     Blob(usize),
-    /// A fetch response as underlying source.
-    FetchResponse,
+    /// A fetch response as underlying source,
+    /// with the means to resume the network when the stream is pulled.
+    FetchResponse(Rc<ResponseBodyFlowControl>),
     /// A struct representing a JS object as underlying source,
     /// and the actual JS object for use as `thisArg` in callbacks.
     Js(JsUnderlyingSource, Heap<*mut JSObject>),
@@ -57,7 +59,7 @@ impl UnderlyingSourceType {
             self,
             UnderlyingSourceType::Memory(_) |
                 UnderlyingSourceType::Blob(_) |
-                UnderlyingSourceType::FetchResponse |
+                UnderlyingSourceType::FetchResponse(_) |
                 UnderlyingSourceType::Transfer(_)
         )
     }
@@ -217,6 +219,11 @@ impl UnderlyingSourceContainer {
                 // Return ! TransformStreamDefaultSourcePullAlgorithm(stream).
                 Some(stream.transform_stream_default_source_pull(&self.global(), can_gc))
             },
+            UnderlyingSourceType::FetchResponse(flow_control) => {
+                // The stream wants more data, let the network deliver it.
+                flow_control.resume();
+                None
+            },
             _ => None,
         }
     }
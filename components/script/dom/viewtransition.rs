@@ -0,0 +1,576 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use dom_struct::dom_struct;
+use euclid::default::Size2D;
+use js::jsval::UndefinedValue;
+use js::rust::HandleValue as SafeHandleValue;
+use layout_api::{RestyleReason, ViewTransitionSnapshot};
+use style::bezier::Bezier;
+use webrender_api::ImageKey;
+
+use crate::dom::bindings::callback::ExceptionHandling;
+use crate::dom::bindings::codegen::Bindings::ViewTransitionBinding::{
+    ViewTransitionMethods, ViewTransitionUpdateCallback,
+};
+use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::error::{Error, ErrorToJsval};
+use crate::dom::bindings::refcounted::Trusted;
+use crate::dom::bindings::reflector::{DomGlobal, Reflector, reflect_dom_object};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::document::Document;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::promise::Promise;
+use crate::dom::promisenativehandler::{Callback, PromiseNativeHandler};
+use crate::dom::window::Window;
+use crate::realms::{InRealm, enter_realm};
+use crate::script_runtime::{CanGc, JSContext as SafeJSContext};
+use crate::timers::OneshotTimerCallback;
+
+/// The duration of the default cross-fade animation of the old state of a document.
+/// <https://drafts.csswg.org/css-view-transitions-1/#ua-styles>
+const CROSS_FADE_DURATION: f64 = 0.25;
+
+/// The time after which a transition whose update callback did not settle is skipped.
+/// <https://drafts.csswg.org/css-view-transitions-1/#call-the-update-callback>
+const UPDATE_CALLBACK_TIMEOUT: Duration = Duration::from_secs(4);
+
+/// <https://drafts.csswg.org/css-view-transitions-1/#viewtransition-phase>
+#[derive(Clone, Copy, Debug, JSTraceable, MallocSizeOf, PartialEq, PartialOrd)]
+pub(crate) enum ViewTransitionPhase {
+    PendingCapture,
+    UpdateCallbackCalled,
+    Animating,
+    Done,
+}
+
+/// A same-document view transition, which captures the old state of a document, lets the
+/// update callback change the DOM and then cross-fades from the old state to the new one.
+///
+/// Only the root of the document is captured: the old state is painted over the viewport
+/// and faded out on top of the live rendering of the new state.
+/// <https://drafts.csswg.org/css-view-transitions-1/#viewtransition>
+#[dom_struct]
+pub(crate) struct ViewTransition {
+    reflector_: Reflector,
+
+    /// <https://drafts.csswg.org/css-view-transitions-1/#viewtransition-update-callback>
+    #[ignore_malloc_size_of = "Rc"]
+    update_callback: Option<Rc<ViewTransitionUpdateCallback>>,
+
+    /// <https://drafts.csswg.org/css-view-transitions-1/#viewtransition-phase>
+    phase: Cell<ViewTransitionPhase>,
+
+    /// <https://drafts.csswg.org/css-view-transitions-1/#viewtransition-update-callback-done-promise>
+    #[ignore_malloc_size_of = "Rc"]
+    update_callback_done_promise: Rc<Promise>,
+
+    /// <https://drafts.csswg.org/css-view-transitions-1/#viewtransition-ready-promise>
+    #[ignore_malloc_size_of = "Rc"]
+    ready_promise: Rc<Promise>,
+
+    /// <https://drafts.csswg.org/css-view-transitions-1/#viewtransition-finished-promise>
+    #[ignore_malloc_size_of = "Rc"]
+    finished_promise: Rc<Promise>,
+
+    /// <https://drafts.csswg.org/css-view-transitions-1/#viewtransition-initial-snapshot-containing-block-size>
+    #[no_trace]
+    initial_snapshot_containing_block_size: Cell<Option<Size2D<f32>>>,
+
+    /// The image holding the rendering of the old state of the document, captured by the
+    /// compositor.
+    #[no_trace]
+    old_state_image: Cell<Option<ImageKey>>,
+
+    /// The opacity with which the old state is currently painted.
+    old_state_opacity: Cell<f32>,
+
+    /// The value of the animation timeline of the document when the cross-fade started.
+    animation_start_time: Cell<f64>,
+}
+
+impl ViewTransition {
+    fn new_inherited(
+        window: &Window,
+        update_callback: Option<Rc<ViewTransitionUpdateCallback>>,
+        can_gc: CanGc,
+    ) -> ViewTransition {
+        let global = window.as_global_scope();
+        ViewTransition {
+            reflector_: Reflector::new(),
+            update_callback,
+            phase: Cell::new(ViewTransitionPhase::PendingCapture),
+            update_callback_done_promise: Promise::new(global, can_gc),
+            ready_promise: Promise::new(global, can_gc),
+            finished_promise: Promise::new(global, can_gc),
+            initial_snapshot_containing_block_size: Cell::new(None),
+            old_state_image: Cell::new(None),
+            old_state_opacity: Cell::new(1.),
+            animation_start_time: Cell::new(0.),
+        }
+    }
+
+    pub(crate) fn new(
+        window: &Window,
+        update_callback: Option<Rc<ViewTransitionUpdateCallback>>,
+        can_gc: CanGc,
+    ) -> DomRoot<ViewTransition> {
+        reflect_dom_object(
+            Box::new(ViewTransition::new_inherited(
+                window,
+                update_callback,
+                can_gc,
+            )),
+            window,
+            can_gc,
+        )
+    }
+
+    pub(crate) fn phase(&self) -> ViewTransitionPhase {
+        self.phase.get()
+    }
+
+    /// The old state of the document to paint over the new one, if it is currently shown.
+    pub(crate) fn old_state_snapshot(&self) -> Option<ViewTransitionSnapshot> {
+        if self.phase.get() == ViewTransitionPhase::PendingCapture {
+            return None;
+        }
+        Some(ViewTransitionSnapshot {
+            image_key: self.old_state_image.get()?,
+            opacity: self.old_state_opacity.get(),
+        })
+    }
+
+    fn document(&self) -> DomRoot<Document> {
+        self.global().as_window().Document()
+    }
+
+    /// The size of the snapshot containing block, which for the root is the viewport.
+    /// <https://drafts.csswg.org/css-view-transitions-1/#snapshot-containing-block-size>
+    fn snapshot_containing_block_size(&self) -> Size2D<f32> {
+        self.global()
+            .as_window()
+            .viewport_details()
+            .size
+            .to_untyped()
+    }
+
+    /// <https://drafts.csswg.org/css-view-transitions-1/#setup-view-transition>
+    pub(crate) fn setup(&self, can_gc: CanGc) {
+        // Step 1. Let document be transition’s relevant global object’s associated document.
+        let document = self.document();
+
+        // Step 2. Flush the update callback queue.
+        // Note: The update callback is called directly when it is scheduled.
+
+        // Step 3. Capture the old state for transition.
+        // If failure is returned, then skip the view transition for transition with an
+        // "InvalidStateError" DOMException in transition’s relevant Realm, and return.
+        if !self.capture_the_old_state() {
+            self.skip_with_error(Error::InvalidState, can_gc);
+            return;
+        }
+
+        // Step 4. Set document’s rendering suppression for view transitions to true.
+        document.set_rendering_suppression_for_view_transitions(true);
+
+        // Step 5. Queue a global task on the DOM manipulation task source, given transition’s
+        // relevant global object, to perform the following steps:
+        let transition = Trusted::new(self);
+        self.global()
+            .task_manager()
+            .dom_manipulation_task_source()
+            .queue(task!(schedule_view_transition_update_callback: move || {
+                let transition = transition.root();
+                // Step 5.1. If transition’s phase is "done", then abort these steps.
+                if transition.phase.get() == ViewTransitionPhase::Done {
+                    return;
+                }
+                // Step 5.2. Schedule the update callback for transition.
+                // Step 5.3. Flush the update callback queue.
+                transition.call_the_update_callback(CanGc::note());
+            }));
+    }
+
+    /// <https://drafts.csswg.org/css-view-transitions-1/#capture-the-old-state>
+    ///
+    /// Only the root of the document is captured, by asking the compositor to store its
+    /// current rendering as an image. This fails for documents that are not displayed at
+    /// the root of their `WebView`.
+    fn capture_the_old_state(&self) -> bool {
+        let global = self.global();
+        let window = global.as_window();
+
+        // Step 3. Set transition’s initial snapshot containing block size to the snapshot
+        // containing block size.
+        self.initial_snapshot_containing_block_size
+            .set(Some(self.snapshot_containing_block_size()));
+
+        let Some(image_key) = window
+            .compositor_api()
+            .capture_view_transition_snapshot_blocking(window.webview_id(), window.pipeline_id())
+        else {
+            return false;
+        };
+        self.old_state_image.set(Some(image_key));
+        true
+    }
+
+    /// <https://drafts.csswg.org/css-view-transitions-1/#call-the-update-callback>
+    fn call_the_update_callback(&self, can_gc: CanGc) {
+        // Step 1. Assert: transition’s phase is "done", or before "update-callback-called".
+        assert!(
+            self.phase.get() == ViewTransitionPhase::Done ||
+                self.phase.get() < ViewTransitionPhase::UpdateCallbackCalled
+        );
+
+        // Step 2. If transition’s phase is not "done", then set transition’s phase to
+        // "update-callback-called".
+        if self.phase.get() != ViewTransitionPhase::Done {
+            self.phase.set(ViewTransitionPhase::UpdateCallbackCalled);
+        }
+
+        // Step 3. Let callbackPromise be null.
+        let global = self.global();
+        let cx = GlobalScope::get_cx();
+        let callback_promise = match &self.update_callback {
+            // Step 4. If transition’s update callback is null, then set callbackPromise to a
+            // promise resolved with undefined, in transition’s relevant Realm.
+            None => Promise::new_resolved(&global, cx, (), can_gc),
+            // Step 5. Otherwise, set callbackPromise to the result of invoking transition’s
+            // update callback.
+            Some(callback) => callback
+                .Call__(ExceptionHandling::Rethrow, can_gc)
+                .unwrap_or_else(|error| {
+                    let promise = Promise::new(&global, can_gc);
+                    promise.reject_error(error, can_gc);
+                    promise
+                }),
+        };
+
+        // Step 6. Let fulfillSteps be to following steps:
+        // Step 7. Let rejectSteps be the following steps given reason:
+        // Step 8. React to callbackPromise with fulfillSteps and rejectSteps.
+        let handler = PromiseNativeHandler::new(
+            &global,
+            Some(Box::new(UpdateCallbackFulfillmentHandler {
+                transition: Dom::from_ref(self),
+            })),
+            Some(Box::new(UpdateCallbackRejectionHandler {
+                transition: Dom::from_ref(self),
+            })),
+            can_gc,
+        );
+        let realm = enter_realm(&*global);
+        let comp = InRealm::Entered(&realm);
+        callback_promise.append_native_handler(&handler, comp, can_gc);
+
+        // Step 9. To skip a transition after a timeout, the user agent may perform the
+        // following steps in parallel:
+        // Step 9.1. Wait for an implementation-defined duration.
+        // Step 9.2. Queue a global task on the DOM manipulation task source, given
+        // transition’s relevant global object, to perform the following steps:
+        let callback = OneshotTimerCallback::ViewTransitionTimeout(ViewTransitionTimeoutCallback {
+            transition: Trusted::new(self),
+        });
+        global.schedule_callback(callback, UPDATE_CALLBACK_TIMEOUT);
+    }
+
+    /// <https://drafts.csswg.org/css-view-transitions-1/#activate-view-transition>
+    fn activate(&self, can_gc: CanGc) {
+        // Step 1. If transition’s phase is "done", then return.
+        if self.phase.get() == ViewTransitionPhase::Done {
+            return;
+        }
+
+        // Step 2. Set transition’s relevant global object’s associated document’s rendering
+        // suppression for view transitions to false.
+        let document = self.document();
+        document.set_rendering_suppression_for_view_transitions(false);
+
+        // Step 3. If transition’s initial snapshot containing block size is not equal to the
+        // snapshot containing block size, then skip transition with an "InvalidStateError"
+        // DOMException in transition’s relevant Realm, and return.
+        if self.initial_snapshot_containing_block_size.get() !=
+            Some(self.snapshot_containing_block_size())
+        {
+            self.skip_with_error(Error::InvalidState, can_gc);
+            return;
+        }
+
+        // Step 4. Capture the new state for transition.
+        // Note: The new state of the root is its live rendering, so nothing needs to be
+        // captured.
+
+        // Step 5. For each capturedElement of transition’s named elements' values: ...
+        // Note: The old state starts out fully opaque and is faded out with the default
+        // animation in `handle_transition_frame`.
+        self.animation_start_time
+            .set(document.current_animation_timeline_value());
+        self.old_state_opacity.set(1.);
+        document.add_restyle_reason(RestyleReason::ViewTransitionSnapshotChanged);
+
+        // Step 6. Set transition’s phase to "animating".
+        self.phase.set(ViewTransitionPhase::Animating);
+
+        // Step 7. Resolve transition’s ready promise.
+        self.ready_promise.resolve_native(&(), can_gc);
+    }
+
+    /// <https://drafts.csswg.org/css-view-transitions-1/#handle-transition-frame>
+    pub(crate) fn handle_transition_frame(&self, can_gc: CanGc) {
+        // Step 1. Let document be transition’s relevant global object’s associated document.
+        let document = self.document();
+
+        // Step 2. Let hasActiveAnimations be a boolean, initially false.
+        // Step 3. For each element of transition’s transition root pseudo-element’s inclusive
+        // descendants: ...
+        let elapsed = document.current_animation_timeline_value() - self.animation_start_time.get();
+        let progress = (elapsed / CROSS_FADE_DURATION).clamp(0., 1.);
+        let has_active_animations = progress < 1.;
+
+        // Step 4. If hasActiveAnimations is false:
+        if !has_active_animations {
+            // Step 4.1. Set transition’s phase to "done".
+            self.phase.set(ViewTransitionPhase::Done);
+            // Step 4.2. Clear view transition transition.
+            self.clear(&document);
+            // Step 4.3. Resolve transition’s finished promise.
+            self.finished_promise.resolve_native(&(), can_gc);
+            // Step 4.4. Return.
+            return;
+        }
+
+        // Step 5. If transition’s initial snapshot containing block size is not equal to the
+        // snapshot containing block size, then skip the view transition for transition with
+        // an "InvalidStateError" DOMException in transition’s relevant Realm, and return.
+        if self.initial_snapshot_containing_block_size.get() !=
+            Some(self.snapshot_containing_block_size())
+        {
+            self.skip_with_error(Error::InvalidState, can_gc);
+            return;
+        }
+
+        // Step 6. Update pseudo-element styles for transition.
+        // The old state fades out with the `ease` timing function of the default animation.
+        let epsilon = 1. / (200. * CROSS_FADE_DURATION);
+        let eased_progress =
+            Bezier::calculate_bezier_output(progress, epsilon, 0.25, 0.1, 0.25, 1.);
+        self.old_state_opacity.set(1. - eased_progress as f32);
+        document.add_restyle_reason(RestyleReason::ViewTransitionSnapshotChanged);
+    }
+
+    pub(crate) fn skip_with_error(&self, error: Error, can_gc: CanGc) {
+        let global = self.global();
+        let cx = GlobalScope::get_cx();
+        let _ac = enter_realm(&*global);
+        rooted!(in(*cx) let mut reason = UndefinedValue());
+        error.to_jsval(cx, &global, reason.handle_mut(), can_gc);
+        self.skip(reason.handle(), can_gc);
+    }
+
+    /// <https://drafts.csswg.org/css-view-transitions-1/#skip-the-view-transition>
+    pub(crate) fn skip(&self, reason: SafeHandleValue, can_gc: CanGc) {
+        // Step 1. Let document be transition’s relevant global object’s associated document.
+        let document = self.document();
+
+        // Step 2. Assert: transition’s phase is not "done".
+        assert_ne!(self.phase.get(), ViewTransitionPhase::Done);
+
+        // Step 3. If transition’s phase is before "update-callback-called", then schedule the
+        // update callback for transition.
+        if self.phase.get() < ViewTransitionPhase::UpdateCallbackCalled {
+            self.call_the_update_callback(can_gc);
+        }
+
+        // Step 4. Set rendering suppression for view transitions to false.
+        document.set_rendering_suppression_for_view_transitions(false);
+
+        // Step 5. If document’s active view transition is transition, Clear view transition
+        // transition.
+        if document
+            .active_view_transition()
+            .is_some_and(|transition| &*transition == self)
+        {
+            self.clear(&document);
+        }
+
+        // Step 6. Set transition’s phase to "done".
+        self.phase.set(ViewTransitionPhase::Done);
+
+        // Step 7. Reject transition’s ready promise with reason.
+        let cx = GlobalScope::get_cx();
+        self.ready_promise.reject(cx, reason, can_gc);
+
+        // Step 8. Resolve transition’s finished promise with the result of reacting to
+        // transition’s update callback done promise:
+        let global = self.global();
+        let handler = PromiseNativeHandler::new(
+            &global,
+            Some(Box::new(FinishedPromiseFulfillmentHandler {
+                promise: self.finished_promise.clone(),
+            })),
+            Some(Box::new(FinishedPromiseRejectionHandler {
+                promise: self.finished_promise.clone(),
+            })),
+            can_gc,
+        );
+        let realm = enter_realm(&*global);
+        let comp = InRealm::Entered(&realm);
+        self.update_callback_done_promise
+            .append_native_handler(&handler, comp, can_gc);
+    }
+
+    /// <https://drafts.csswg.org/css-view-transitions-1/#clear-view-transition>
+    fn clear(&self, document: &Document) {
+        // Step 1. Let document be transition’s relevant global object’s associated document.
+        // Step 2. Assert: document’s active view transition is transition.
+        debug_assert!(
+            document
+                .active_view_transition()
+                .is_some_and(|transition| &*transition == self)
+        );
+
+        // Step 3. For each capturedElement of transition’s named elements' values: ...
+        if let Some(image_key) = self.old_state_image.take() {
+            self.global()
+                .as_window()
+                .compositor_api()
+                .delete_image(image_key);
+        }
+        document.add_restyle_reason(RestyleReason::ViewTransitionSnapshotChanged);
+
+        // Step 4. Set document’s show view transition tree to false.
+        // Step 5. Set document’s active view transition to null.
+        document.set_active_view_transition(None);
+    }
+}
+
+impl ViewTransitionMethods<crate::DomTypeHolder> for ViewTransition {
+    /// <https://drafts.csswg.org/css-view-transitions-1/#dom-viewtransition-updatecallbackdone>
+    fn UpdateCallbackDone(&self) -> Rc<Promise> {
+        self.update_callback_done_promise.clone()
+    }
+
+    /// <https://drafts.csswg.org/css-view-transitions-1/#dom-viewtransition-ready>
+    fn Ready(&self) -> Rc<Promise> {
+        self.ready_promise.clone()
+    }
+
+    /// <https://drafts.csswg.org/css-view-transitions-1/#dom-viewtransition-finished>
+    fn Finished(&self) -> Rc<Promise> {
+        self.finished_promise.clone()
+    }
+
+    /// <https://drafts.csswg.org/css-view-transitions-1/#dom-viewtransition-skiptransition>
+    fn SkipTransition(&self, can_gc: CanGc) {
+        // Step 1. If this's phase is not "done", then skip the view transition for this with
+        // an "AbortError" DOMException.
+        if self.phase.get() != ViewTransitionPhase::Done {
+            self.skip_with_error(Error::Abort, can_gc);
+        }
+    }
+}
+
+/// The fulfillment handler for the callback promise in
+/// <https://drafts.csswg.org/css-view-transitions-1/#call-the-update-callback>
+#[derive(JSTraceable, MallocSizeOf)]
+#[cfg_attr(crown, crown::unrooted_must_root_lint::must_root)]
+struct UpdateCallbackFulfillmentHandler {
+    transition: Dom<ViewTransition>,
+}
+
+impl Callback for UpdateCallbackFulfillmentHandler {
+    /// Step 6 of <https://drafts.csswg.org/css-view-transitions-1/#call-the-update-callback>
+    fn callback(&self, _cx: SafeJSContext, _v: SafeHandleValue, _realm: InRealm, can_gc: CanGc) {
+        // Step 6.1. Resolve transition’s update callback done promise with undefined.
+        self.transition
+            .update_callback_done_promise
+            .resolve_native(&(), can_gc);
+
+        // Step 6.2. Activate transition.
+        self.transition.activate(can_gc);
+    }
+}
+
+/// The rejection handler for the callback promise in
+/// <https://drafts.csswg.org/css-view-transitions-1/#call-the-update-callback>
+#[derive(JSTraceable, MallocSizeOf)]
+#[cfg_attr(crown, crown::unrooted_must_root_lint::must_root)]
+struct UpdateCallbackRejectionHandler {
+    transition: Dom<ViewTransition>,
+}
+
+impl Callback for UpdateCallbackRejectionHandler {
+    /// Step 7 of <https://drafts.csswg.org/css-view-transitions-1/#call-the-update-callback>
+    fn callback(&self, cx: SafeJSContext, v: SafeHandleValue, _realm: InRealm, can_gc: CanGc) {
+        // Step 7.1. Reject transition’s update callback done promise with reason.
+        self.transition
+            .update_callback_done_promise
+            .reject(cx, v, can_gc);
+
+        // Step 7.2. If transition’s phase is "done", then return.
+        if self.transition.phase.get() == ViewTransitionPhase::Done {
+            return;
+        }
+
+        // Step 7.3. Mark as handled transition’s ready promise.
+        self.transition.ready_promise.set_promise_is_handled();
+
+        // Step 7.4. Skip the view transition transition with reason.
+        self.transition.skip(v, can_gc);
+    }
+}
+
+/// The fulfillment handler for the update callback done promise in
+/// <https://drafts.csswg.org/css-view-transitions-1/#skip-the-view-transition>
+#[derive(JSTraceable, MallocSizeOf)]
+struct FinishedPromiseFulfillmentHandler {
+    #[ignore_malloc_size_of = "Rc"]
+    promise: Rc<Promise>,
+}
+
+impl Callback for FinishedPromiseFulfillmentHandler {
+    fn callback(&self, _cx: SafeJSContext, _v: SafeHandleValue, _realm: InRealm, can_gc: CanGc) {
+        self.promise.resolve_native(&(), can_gc);
+    }
+}
+
+/// The rejection handler for the update callback done promise in
+/// <https://drafts.csswg.org/css-view-transitions-1/#skip-the-view-transition>
+#[derive(JSTraceable, MallocSizeOf)]
+struct FinishedPromiseRejectionHandler {
+    #[ignore_malloc_size_of = "Rc"]
+    promise: Rc<Promise>,
+}
+
+impl Callback for FinishedPromiseRejectionHandler {
+    fn callback(&self, cx: SafeJSContext, v: SafeHandleValue, _realm: InRealm, can_gc: CanGc) {
+        self.promise.reject(cx, v, can_gc);
+    }
+}
+
+/// The timer callback that skips a view transition whose update callback takes too long,
+/// from step 9 of <https://drafts.csswg.org/css-view-transitions-1/#call-the-update-callback>.
+#[derive(JSTraceable, MallocSizeOf)]
+pub(crate) struct ViewTransitionTimeoutCallback {
+    #[ignore_malloc_size_of = "Because it is non-owning"]
+    transition: Trusted<ViewTransition>,
+}
+
+impl ViewTransitionTimeoutCallback {
+    pub(crate) fn invoke(self, can_gc: CanGc) {
+        // Step 9.2.1. If transition’s phase is "done", then return.
+        // Step 9.2.2. Note: This happens if transition was skipped before this point.
+        // Step 9.2.3. Skip transition with a "TimeoutError" DOMException.
+        let transition = self.transition.root();
+        if transition.phase.get() == ViewTransitionPhase::UpdateCallbackCalled {
+            transition.skip_with_error(Error::Timeout, can_gc);
+        }
+    }
+}
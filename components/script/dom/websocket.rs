@@ -139,6 +139,28 @@ impl WebSocket {
         )
     }
 
+    /// <https://websockets.spec.whatwg.org/#make-disappear>
+    ///
+    /// Returns whether the connection of this `WebSocket` was still in use.
+    pub(crate) fn make_disappear(&self) -> bool {
+        match self.ready_state.get() {
+            WebSocketRequestState::Closing | WebSocketRequestState::Closed => false,
+            // Step 1. If webSocket's ready state is CONNECTING or OPEN, then fail the WebSocket
+            // connection with status code 1001.
+            WebSocketRequestState::Connecting | WebSocketRequestState::Open => {
+                let _ = self.sender.send(WebSocketDomAction::Close(
+                    Some(close_code::GOING_AWAY),
+                    None,
+                ));
+
+                // Step 2. Set webSocket's ready state to CLOSED (3).
+                // Note: This also makes sure that no events are fired at it anymore.
+                self.ready_state.set(WebSocketRequestState::Closed);
+                true
+            },
+        }
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-websocket-send
     fn send_impl(&self, data_byte_len: u64) -> Fallible<bool> {
         let return_after_buffer = match self.ready_state.get() {
@@ -257,12 +279,14 @@ impl WebSocketMethods<crate::DomTypeHolder> for WebSocket {
 
         // Step 12. Establish a WebSocket connection given urlRecord, protocols, and client.
         let ws = WebSocket::new(global, proto, url_record.clone(), dom_action_sender, can_gc);
+        global.track_websocket(&ws);
         let address = Trusted::new(&*ws);
 
         let request = RequestBuilder::new(global.webview_id(), url_record, Referrer::NoReferrer)
             .origin(global.origin().immutable().clone())
             .insecure_requests_policy(global.insecure_requests_policy())
             .has_trustworthy_ancestor_origin(global.has_trustworthy_ancestor_or_current_origin())
+            .ancestor_origins(global.origin_and_ancestor_origins())
             .mode(RequestMode::WebSocket { protocols })
             .service_workers_mode(ServiceWorkersMode::None)
             .credentials_mode(CredentialsMode::Include)
@@ -6,7 +6,7 @@ use std::borrow::ToOwned;
 use std::cell::{Cell, RefCell, RefMut};
 use std::cmp;
 use std::collections::hash_map::Entry;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::default::Default;
 use std::ffi::c_void;
 use std::io::{Write, stderr, stdout};
@@ -16,16 +16,18 @@ use std::time::{Duration, Instant};
 
 use app_units::Au;
 use backtrace::Backtrace;
+use base::Epoch;
 use base::cross_process_instant::CrossProcessInstant;
 use base::id::{BrowsingContextId, PipelineId, WebViewId};
 use base64::Engine;
 #[cfg(feature = "bluetooth")]
 use bluetooth_traits::BluetoothRequest;
 use canvas_traits::webgl::WebGLChan;
-use compositing_traits::CrossProcessCompositorApi;
+use compositing_traits::{CompositorMsg, CrossProcessCompositorApi};
 use constellation_traits::{
-    DocumentState, LoadData, LoadOrigin, NavigationHistoryBehavior, ScriptToConstellationChan,
-    ScriptToConstellationMessage, StructuredSerializedData, WindowSizeType,
+    DocumentState, LoadData, LoadOrigin, NavigableHistoryEntry, NavigationHistoryBehavior,
+    ScriptToConstellationChan, ScriptToConstellationMessage, StructuredSerializedData,
+    WindowSizeType,
 };
 use crossbeam_channel::{Sender, unbounded};
 use cssparser::SourceLocation;
@@ -94,16 +96,21 @@ use webrender_api::units::{DeviceIntSize, DevicePixel, LayoutPixel, LayoutPoint}
 
 use super::bindings::codegen::Bindings::MessagePortBinding::StructuredSerializeOptions;
 use super::bindings::trace::HashMapTracedValues;
+use crate::dom::bindings::callback::ExceptionHandling;
 use crate::dom::bindings::cell::{DomRefCell, Ref};
 use crate::dom::bindings::codegen::Bindings::DocumentBinding::{
     DocumentMethods, DocumentReadyState, NamedPropertyValue,
 };
 use crate::dom::bindings::codegen::Bindings::HTMLIFrameElementBinding::HTMLIFrameElementMethods;
 use crate::dom::bindings::codegen::Bindings::HistoryBinding::History_Binding::HistoryMethods;
+use crate::dom::bindings::codegen::Bindings::IdleDeadlineBinding::{
+    IdleRequestCallback, IdleRequestOptions,
+};
 use crate::dom::bindings::codegen::Bindings::ImageBitmapBinding::{
     ImageBitmapOptions, ImageBitmapSource,
 };
 use crate::dom::bindings::codegen::Bindings::MediaQueryListBinding::MediaQueryList_Binding::MediaQueryListMethods;
+use crate::dom::bindings::codegen::Bindings::NavigationBinding::NavigationType;
 use crate::dom::bindings::codegen::Bindings::ReportingObserverBinding::Report;
 use crate::dom::bindings::codegen::Bindings::RequestBinding::RequestInit;
 use crate::dom::bindings::codegen::Bindings::VoidFunctionBinding::VoidFunction;
@@ -111,7 +118,9 @@ use crate::dom::bindings::codegen::Bindings::WindowBinding::{
     self, FrameRequestCallback, ScrollBehavior, ScrollToOptions, WindowMethods,
     WindowPostMessageOptions,
 };
-use crate::dom::bindings::codegen::UnionTypes::{RequestOrUSVString, StringOrFunction};
+use crate::dom::bindings::codegen::UnionTypes::{
+    RequestOrUSVString, TrustedScriptOrStringOrFunction,
+};
 use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
 use crate::dom::bindings::inheritance::{Castable, ElementTypeId, HTMLElementTypeId, NodeTypeId};
 use crate::dom::bindings::num::Finite;
@@ -125,9 +134,10 @@ use crate::dom::bindings::utils::GlobalStaticData;
 use crate::dom::bindings::weakref::DOMTracker;
 #[cfg(feature = "bluetooth")]
 use crate::dom::bluetooth::BluetoothExtraPermissionData;
+use crate::dom::cookiestore::CookieStore;
 use crate::dom::crypto::Crypto;
 use crate::dom::cssstyledeclaration::{CSSModificationAccess, CSSStyleDeclaration, CSSStyleOwner};
-use crate::dom::customelementregistry::CustomElementRegistry;
+use crate::dom::customelementregistry::{CustomElementDefinition, CustomElementRegistry};
 use crate::dom::document::{AnimationFrameCallback, Document};
 use crate::dom::element::Element;
 use crate::dom::event::{Event, EventBubbles, EventCancelable};
@@ -140,18 +150,25 @@ use crate::dom::history::History;
 use crate::dom::htmlcollection::{CollectionFilter, HTMLCollection};
 use crate::dom::htmliframeelement::HTMLIFrameElement;
 use crate::dom::idbfactory::IDBFactory;
+use crate::dom::idledeadline::IdleDeadline;
 use crate::dom::inputevent::HitTestResult;
+use crate::dom::largestcontentfulpaint::ContentfulPaintState;
+use crate::dom::layoutshift::LayoutShiftState;
 use crate::dom::location::Location;
 use crate::dom::medialist::MediaList;
 use crate::dom::mediaquerylist::{MediaQueryList, MediaQueryListMatchState};
 use crate::dom::mediaquerylistevent::MediaQueryListEvent;
 use crate::dom::messageevent::MessageEvent;
+use crate::dom::navigation::Navigation;
 use crate::dom::navigator::Navigator;
 use crate::dom::node::{Node, NodeDamage, NodeTraits, from_untrusted_node_address};
 use crate::dom::performance::Performance;
+use crate::dom::performanceeventtiming::EventTimingState;
+use crate::dom::performancepainttiming::PaintTimingState;
 use crate::dom::promise::Promise;
 use crate::dom::reportingendpoint::{ReportingEndpoint, SendReportsToEndpoints};
 use crate::dom::reportingobserver::ReportingObserver;
+use crate::dom::scheduler::Scheduler;
 use crate::dom::screen::Screen;
 use crate::dom::selection::Selection;
 use crate::dom::shadowroot::ShadowRoot;
@@ -172,7 +189,7 @@ use crate::microtask::MicrotaskQueue;
 use crate::realms::{InRealm, enter_realm};
 use crate::script_runtime::{CanGc, JSContext, Runtime};
 use crate::script_thread::ScriptThread;
-use crate::timers::{IsInterval, TimerCallback};
+use crate::timers::{IsInterval, OneshotTimerCallback, TimerCallback};
 use crate::unminify::unminified_path;
 use crate::webdriver_handlers::{find_node_by_unique_id_in_document, jsval_to_webdriver};
 use crate::{fetch, window_named_properties};
@@ -228,6 +245,54 @@ impl LayoutBlocker {
 
 type PendingImageRasterizationKey = (PendingImageId, DeviceIntSize);
 
+/// How long a smooth scroll takes to reach its destination.
+const SMOOTH_SCROLL_DURATION: Duration = Duration::from_millis(300);
+
+/// How often an ongoing smooth scroll moves the scroll position towards its destination.
+const SMOOTH_SCROLL_FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// How long the user must have stopped scrolling a scrolling box before its scrolling is
+/// considered completed.
+const USER_SCROLL_END_DELAY: Duration = Duration::from_millis(100);
+
+/// A smooth scroll of a scrolling box that is currently in progress.
+/// <https://drafts.csswg.org/cssom-view/#concept-smooth-scroll>
+#[derive(JSTraceable, MallocSizeOf)]
+#[cfg_attr(crown, crown::unrooted_must_root_lint::must_root)]
+struct SmoothScroll {
+    /// The scroll node of the scrolling box that is being scrolled.
+    #[no_trace]
+    scroll_id: ExternalScrollId,
+    /// The element associated with the scrolling box, or `None` for the viewport.
+    element: Option<Dom<Element>>,
+    /// The scroll position of the scrolling box when the smooth scroll started.
+    #[no_trace]
+    start: Vector2D<f32, LayoutPixel>,
+    /// The scroll position that the smooth scroll is moving towards.
+    #[no_trace]
+    target: Vector2D<f32, LayoutPixel>,
+    /// The time at which the smooth scroll started.
+    #[no_trace]
+    #[ignore_malloc_size_of = "Defined in std"]
+    start_time: Instant,
+}
+
+/// A scrolling box that the user has recently scrolled, but whose scrolling has not yet
+/// completed.
+#[derive(JSTraceable, MallocSizeOf)]
+#[cfg_attr(crown, crown::unrooted_must_root_lint::must_root)]
+struct UserScroll {
+    /// The scroll node of the scrolling box that is being scrolled.
+    #[no_trace]
+    scroll_id: ExternalScrollId,
+    /// The element associated with the scrolling box, or `None` for the viewport.
+    element: Option<Dom<Element>>,
+    /// The time at which the user last scrolled the scrolling box.
+    #[no_trace]
+    #[ignore_malloc_size_of = "Defined in std"]
+    last_scroll_time: Instant,
+}
+
 #[dom_struct]
 pub(crate) struct Window {
     globalscope: GlobalScope,
@@ -255,8 +320,21 @@ pub(crate) struct Window {
     document: MutNullableDom<Document>,
     location: MutNullableDom<Location>,
     history: MutNullableDom<History>,
+    /// <https://html.spec.whatwg.org/multipage/#window-navigation-api>
+    navigation: MutNullableDom<Navigation>,
+    /// The session history entries of the navigable of this window that are same origin with
+    /// it, along with the index of the current one, as last sent by the constellation.
+    #[no_trace]
+    #[ignore_malloc_size_of = "Defined in constellation_traits"]
+    navigable_history: DomRefCell<Option<(Vec<NavigableHistoryEntry>, usize)>>,
     indexeddb: MutNullableDom<IDBFactory>,
     custom_element_registry: MutNullableDom<CustomElementRegistry>,
+    /// <https://html.spec.whatwg.org/multipage/#active-custom-element-constructor-map>
+    /// The definitions of scoped custom element registries whose constructors are currently
+    /// running, so that the HTML element constructor can find them.
+    #[ignore_malloc_size_of = "Rc"]
+    active_scoped_custom_element_constructors:
+        DomRefCell<Vec<(Dom<CustomElementRegistry>, Rc<CustomElementDefinition>)>>,
     performance: MutNullableDom<Performance>,
     #[no_trace]
     navigation_start: Cell<CrossProcessInstant>,
@@ -415,9 +493,48 @@ pub(crate) struct Window {
     /// <https://w3c.github.io/reporting/#windoworworkerglobalscope-reports>
     report_list: DomRefCell<Vec<Report>>,
 
+    /// <https://w3c.github.io/reporting/#windoworworkerglobalscope-report-buffer>
+    report_buffer: DomRefCell<Vec<Report>>,
+
     /// <https://w3c.github.io/reporting/#windoworworkerglobalscope-endpoints>
     #[no_trace]
     endpoints_list: DomRefCell<Vec<ReportingEndpoint>>,
+
+    /// <https://w3c.github.io/requestidlecallback/#dfn-idle-callback-identifier>
+    idle_callback_identifier: Cell<u32>,
+
+    /// <https://w3c.github.io/requestidlecallback/#dfn-list-of-idle-request-callbacks>
+    #[ignore_malloc_size_of = "Rc is hard"]
+    idle_request_callbacks: DomRefCell<VecDeque<(u32, Rc<IdleRequestCallback>)>>,
+
+    /// <https://w3c.github.io/requestidlecallback/#dfn-list-of-runnable-idle-callbacks>
+    #[ignore_malloc_size_of = "Rc is hard"]
+    runnable_idle_callbacks: DomRefCell<VecDeque<(u32, Rc<IdleRequestCallback>)>>,
+
+    /// The smooth scrolls of the scrolling boxes of this window that are in progress.
+    smooth_scrolls: DomRefCell<Vec<SmoothScroll>>,
+
+    /// The scrolling boxes of this window that the user is scrolling.
+    user_scrolls: DomRefCell<Vec<UserScroll>>,
+
+    /// The Event Timing state of this window.
+    /// <https://w3c.github.io/event-timing/#sec-modifications-HTML>
+    event_timing: EventTimingState,
+
+    /// The Paint Timing state of this window.
+    paint_timing: PaintTimingState,
+
+    /// The Largest Contentful Paint and Element Timing state of this window.
+    contentful_paint: ContentfulPaintState,
+
+    /// The Layout Instability state of this window.
+    layout_shift: LayoutShiftState,
+
+    /// The epoch of the last display list for which presentation feedback was requested
+    /// from the renderer, used to avoid requesting it more than once per frame.
+    #[no_trace]
+    #[ignore_malloc_size_of = "Defined in base"]
+    last_presentation_feedback_epoch: Cell<Option<Epoch>>,
 }
 
 impl Window {
@@ -429,6 +546,43 @@ impl Window {
         self.upcast::<GlobalScope>()
     }
 
+    /// Note that the constructor of `definition`, from the scoped `registry`, is about to run.
+    /// Must be balanced by a call to [`Self::pop_active_scoped_custom_element_constructor`].
+    pub(crate) fn push_active_scoped_custom_element_constructor(
+        &self,
+        registry: &CustomElementRegistry,
+        definition: Rc<CustomElementDefinition>,
+    ) {
+        debug_assert!(registry.is_scoped());
+        self.active_scoped_custom_element_constructors
+            .borrow_mut()
+            .push((Dom::from_ref(registry), definition));
+    }
+
+    pub(crate) fn pop_active_scoped_custom_element_constructor(&self) {
+        self.active_scoped_custom_element_constructors
+            .borrow_mut()
+            .pop();
+    }
+
+    /// Find the custom element definition whose constructor is `constructor`, looking in the
+    /// global registry first and then in the scoped registries whose constructors are running.
+    pub(crate) fn lookup_custom_element_definition_by_constructor(
+        &self,
+        constructor: HandleObject,
+    ) -> Option<(DomRoot<CustomElementRegistry>, Rc<CustomElementDefinition>)> {
+        let global = self.CustomElements();
+        if let Some(definition) = global.lookup_definition_by_constructor(constructor) {
+            return Some((global, definition));
+        }
+        self.active_scoped_custom_element_constructors
+            .borrow()
+            .iter()
+            .rev()
+            .find(|(_, definition)| definition.constructor.callback() == constructor.get())
+            .map(|(registry, definition)| (registry.as_rooted(), definition.clone()))
+    }
+
     pub(crate) fn layout(&self) -> Ref<Box<dyn Layout>> {
         self.layout.borrow()
     }
@@ -544,7 +698,13 @@ impl Window {
     }
 
     pub(crate) fn append_report(&self, report: Report) {
-        self.report_list.borrow_mut().push(report);
+        let mut report_list = self.report_list.borrow_mut();
+        report_list.push(report);
+        // Reports are delivered in batches: if a delivery task is already pending,
+        // it will pick up this report as well.
+        if report_list.len() > 1 {
+            return;
+        }
         let trusted_window = Trusted::new(self);
         self.upcast::<GlobalScope>()
             .task_manager()
@@ -559,14 +719,79 @@ impl Window {
             }));
     }
 
+    /// <https://html.spec.whatwg.org/multipage/#cannot-show-simple-dialogs>
+    fn cannot_show_simple_dialogs(&self, method_name: &str) -> bool {
+        // Step 3. If window's relevant agent's event loop's termination nesting level is nonzero,
+        // then optionally return true.
+        if self.Document().is_prompting_or_unloading() {
+            ReportingObserver::report_intervention(
+                self.upcast(),
+                "DialogDuringUnload",
+                &format!("Blocked {method_name}() during unload."),
+                CanGc::note(),
+            );
+            return true;
+        }
+        // A prerendering document is not shown to the user, who therefore can not answer
+        // its dialogs.
+        // <https://wicg.github.io/nav-speculation/prerendering.html#patch-modals>
+        if self.Document().is_prerendering() {
+            return true;
+        }
+        // TODO: Steps 1, 2 and 4.
+        // Step 5. Return false.
+        false
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#printing-steps>
+    pub(crate) fn run_the_printing_steps(&self, can_gc: CanGc) {
+        // TODO: Step 2. If the active sandboxing flag set of document has the sandboxed modals
+        // flag set, then return.
+        // A prerendering document is not shown to the user, who therefore can not print it.
+        // <https://wicg.github.io/nav-speculation/prerendering.html#patch-modals>
+        if self.Document().is_prerendering() {
+            return;
+        }
+
+        // Step 3. Fire an event named beforeprint at the relevant global object of document,
+        // as well as any child navigable in it.
+        // TODO: Fire the event at child navigables.
+        self.upcast::<EventTarget>()
+            .fire_event(Atom::from("beforeprint"), can_gc);
+
+        // Step 4. The user agent should offer the user the opportunity to obtain a physical
+        // form (or the representation of a physical form) of document.
+        // Note: The page is blocked until the embedder is done printing, like it is by the
+        // simple dialogs.
+        let (sender, receiver) =
+            ProfiledIpc::channel(self.global().time_profiler_chan().clone()).unwrap();
+        self.send_to_embedder(EmbedderMsg::RequestPrint(self.webview_id(), sender));
+        if receiver.recv().is_err() {
+            debug!("Print request was dropped without a response.");
+        }
+
+        // Step 5. Fire an event named afterprint at the relevant global object of document, as
+        // well as any child navigables in it.
+        self.upcast::<EventTarget>()
+            .fire_event(Atom::from("afterprint"), can_gc);
+    }
+
     pub(crate) fn buffered_reports(&self) -> Vec<Report> {
-        self.report_list.borrow().clone()
+        self.report_buffer.borrow().clone()
+    }
+
+    pub(crate) fn report_buffer_mut(&self) -> RefMut<'_, Vec<Report>> {
+        self.report_buffer.borrow_mut()
     }
 
     pub(crate) fn set_endpoints_list(&self, endpoints: Vec<ReportingEndpoint>) {
         *self.endpoints_list.borrow_mut() = endpoints;
     }
 
+    pub(crate) fn endpoints_list_mut(&self) -> RefMut<'_, Vec<ReportingEndpoint>> {
+        self.endpoints_list.borrow_mut()
+    }
+
     /// Returns the window proxy if it has not been discarded.
     /// <https://html.spec.whatwg.org/multipage/#a-browsing-context-is-discarded>
     pub(crate) fn undiscarded_window_proxy(&self) -> Option<DomRoot<WindowProxy>> {
@@ -917,6 +1142,9 @@ impl WindowMethods<crate::DomTypeHolder> for Window {
 
     // https://html.spec.whatwg.org/multipage/#dom-alert
     fn Alert(&self, s: DOMString) {
+        if self.cannot_show_simple_dialogs("alert") {
+            return;
+        }
         // Print to the console.
         // Ensure that stderr doesn't trample through the alert() we use to
         // communicate test results (see executorservo.py in wptrunner).
@@ -946,6 +1174,9 @@ impl WindowMethods<crate::DomTypeHolder> for Window {
 
     // https://html.spec.whatwg.org/multipage/#dom-confirm
     fn Confirm(&self, s: DOMString) -> bool {
+        if self.cannot_show_simple_dialogs("confirm") {
+            return false;
+        }
         let (sender, receiver) =
             ProfiledIpc::channel(self.global().time_profiler_chan().clone()).unwrap();
         let dialog = SimpleDialog::Confirm {
@@ -966,6 +1197,9 @@ impl WindowMethods<crate::DomTypeHolder> for Window {
 
     // https://html.spec.whatwg.org/multipage/#dom-prompt
     fn Prompt(&self, message: DOMString, default: DOMString) -> Option<DOMString> {
+        if self.cannot_show_simple_dialogs("prompt") {
+            return None;
+        }
         let (sender, receiver) =
             ProfiledIpc::channel(self.global().time_profiler_chan().clone()).unwrap();
         let dialog = SimpleDialog::Prompt {
@@ -985,6 +1219,28 @@ impl WindowMethods<crate::DomTypeHolder> for Window {
         }
     }
 
+    /// <https://html.spec.whatwg.org/multipage/#dom-print>
+    fn Print(&self, can_gc: CanGc) {
+        // Step 1. Let document be this's associated Document.
+        let document = self.Document();
+
+        // Step 2. If document is not fully active, then return.
+        // Step 3. If document's unload counter is greater than 0, then return.
+        if !document.is_fully_active() || document.is_prompting_or_unloading() {
+            return;
+        }
+
+        // Step 4. If document is ready for post-load tasks, then run the printing steps for
+        // document.
+        if document.is_ready_for_post_load_tasks() {
+            self.run_the_printing_steps(can_gc);
+            return;
+        }
+
+        // Step 5. Otherwise, set document's print when loaded flag.
+        document.set_print_when_loaded();
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-window-stop
     fn Stop(&self, can_gc: CanGc) {
         // TODO: Cancel ongoing navigation.
@@ -1191,6 +1447,16 @@ impl WindowMethods<crate::DomTypeHolder> for Window {
         self.as_global_scope().crypto(CanGc::note())
     }
 
+    // https://cookiestore.spec.whatwg.org/#dom-window-cookiestore
+    fn CookieStore(&self) -> DomRoot<CookieStore> {
+        self.as_global_scope().cookie_store(CanGc::note())
+    }
+
+    // https://wicg.github.io/scheduling-apis/#dom-windoworworkerglobalscope-scheduler
+    fn Scheduler(&self) -> DomRoot<Scheduler> {
+        self.as_global_scope().scheduler(CanGc::note())
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-frameelement
     fn GetFrameElement(&self) -> Option<DomRoot<Element>> {
         // Steps 1-3.
@@ -1215,6 +1481,12 @@ impl WindowMethods<crate::DomTypeHolder> for Window {
         Some(DomRoot::from_ref(container))
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-navigation
+    fn Navigation(&self) -> DomRoot<Navigation> {
+        self.navigation
+            .or_init(|| Navigation::new(self, CanGc::note()))
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-navigator
     fn Navigator(&self) -> DomRoot<Navigator> {
         self.navigator
@@ -1225,20 +1497,19 @@ impl WindowMethods<crate::DomTypeHolder> for Window {
     fn SetTimeout(
         &self,
         _cx: JSContext,
-        callback: StringOrFunction,
+        callback: TrustedScriptOrStringOrFunction,
         timeout: i32,
         args: Vec<HandleValue>,
-    ) -> i32 {
-        let callback = match callback {
-            StringOrFunction::String(i) => TimerCallback::StringTimerCallback(i),
-            StringOrFunction::Function(i) => TimerCallback::FunctionTimerCallback(i),
-        };
-        self.as_global_scope().set_timeout_or_interval(
+        can_gc: CanGc,
+    ) -> Fallible<i32> {
+        let callback =
+            TimerCallback::from_handler(self.as_global_scope(), callback, "setTimeout", can_gc)?;
+        Ok(self.as_global_scope().set_timeout_or_interval(
             callback,
             args,
             Duration::from_millis(timeout.max(0) as u64),
             IsInterval::NonInterval,
-        )
+        ))
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-windowtimers-cleartimeout
@@ -1250,20 +1521,19 @@ impl WindowMethods<crate::DomTypeHolder> for Window {
     fn SetInterval(
         &self,
         _cx: JSContext,
-        callback: StringOrFunction,
+        callback: TrustedScriptOrStringOrFunction,
         timeout: i32,
         args: Vec<HandleValue>,
-    ) -> i32 {
-        let callback = match callback {
-            StringOrFunction::String(i) => TimerCallback::StringTimerCallback(i),
-            StringOrFunction::Function(i) => TimerCallback::FunctionTimerCallback(i),
-        };
-        self.as_global_scope().set_timeout_or_interval(
+        can_gc: CanGc,
+    ) -> Fallible<i32> {
+        let callback =
+            TimerCallback::from_handler(self.as_global_scope(), callback, "setInterval", can_gc)?;
+        Ok(self.as_global_scope().set_timeout_or_interval(
             callback,
             args,
             Duration::from_millis(timeout.max(0) as u64),
             IsInterval::Interval,
-        )
+        ))
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-windowtimers-clearinterval
@@ -1407,6 +1677,54 @@ impl WindowMethods<crate::DomTypeHolder> for Window {
         doc.cancel_animation_frame(ident);
     }
 
+    /// <https://w3c.github.io/requestidlecallback/#the-requestidlecallback-method>
+    fn RequestIdleCallback(
+        &self,
+        callback: Rc<IdleRequestCallback>,
+        options: &IdleRequestOptions,
+    ) -> u32 {
+        // Step 1. Let window be this Window object.
+        // Step 2. Increment the window's idle callback identifier by one.
+        // Step 3. Let handle be the current value of window's idle callback identifier.
+        let handle = self.idle_callback_identifier.get().wrapping_add(1);
+        self.idle_callback_identifier.set(handle);
+
+        // Step 4. Push callback to the end of window's list of idle request callbacks,
+        // associated with handle.
+        self.idle_request_callbacks
+            .borrow_mut()
+            .push_back((handle, callback));
+
+        // Step 5. Return handle and then continue running this algorithm asynchronously.
+        // Step 6. If the timeout property is present in options and has a positive value:
+        if let Some(timeout) = options.timeout.filter(|timeout| *timeout > 0) {
+            // Step 6.1. Wait for timeout milliseconds.
+            // Step 6.2. Wait until all invocations of this algorithm, whose timeout added to
+            // their posted time occurred before this one's, have completed.
+            // Step 6.3. Optionally, wait a further implementation-defined length of time.
+            // Step 6.4. Queue a task on the idle-task task source which performs the invoke
+            // idle callback timeout algorithm, passing handle and window as arguments.
+            let callback = OneshotTimerCallback::IdleCallbackTimeout(IdleCallbackTimeoutCallback {
+                window: Trusted::new(self),
+                handle,
+            });
+            self.as_global_scope()
+                .schedule_callback(callback, Duration::from_millis(timeout.into()));
+        }
+
+        handle
+    }
+
+    /// <https://w3c.github.io/requestidlecallback/#the-cancelidlecallback-method>
+    fn CancelIdleCallback(&self, handle: u32) {
+        // Step 1. Let window be this Window object.
+        // Step 2. Find the entry in either the window's list of idle request callbacks or list
+        // of runnable idle callbacks that is associated with the value handle.
+        // Step 3. If there is such an entry, remove it from both window's list of idle request
+        // callbacks and the list of runnable idle callbacks.
+        self.remove_idle_callback(handle);
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-window-postmessage
     fn PostMessage(
         &self,
@@ -1824,6 +2142,11 @@ impl WindowMethods<crate::DomTypeHolder> for Window {
         self.as_global_scope().is_secure_context()
     }
 
+    /// <https://html.spec.whatwg.org/multipage/#dom-crossoriginisolated>
+    fn CrossOriginIsolated(&self) -> bool {
+        self.as_global_scope().is_cross_origin_isolated()
+    }
+
     /// <https://html.spec.whatwg.org/multipage/#dom-window-nameditem>
     fn NamedGetter(&self, name: DOMString) -> Option<NamedPropertyValue> {
         if name.is_empty() {
@@ -2112,10 +2435,13 @@ impl Window {
             .min(scrolling_area.height() as f64 - viewport.height as f64)
             .max(0.0f64);
 
-        // Step 10
-        //TODO handling ongoing smooth scrolling
+        // Step 10. If position is the same as the viewport’s current scroll position, and the
+        // viewport does not have an ongoing smooth scroll, abort these steps.
         let scroll_offset = self.scroll_offset();
-        if x == scroll_offset.x as f64 && y == scroll_offset.y as f64 {
+        if x == scroll_offset.x as f64 &&
+            y == scroll_offset.y as f64 &&
+            !self.has_ongoing_smooth_scroll(self.pipeline_id().root_scroll_id())
+        {
             return;
         }
 
@@ -2139,12 +2465,129 @@ impl Window {
         x: f32,
         y: f32,
         scroll_id: ExternalScrollId,
-        _behavior: ScrollBehavior,
+        behavior: ScrollBehavior,
+        element: Option<&Element>,
+    ) {
+        // Step 1. Abort any ongoing smooth scroll for box.
+        self.abort_smooth_scroll(scroll_id);
+
+        // Step 2. If the user agent honors the scroll-behavior property and one of the
+        // following are true, perform a smooth scroll of box to position:
+        //  - behavior is "auto" and element is not null and its computed value of the
+        //    scroll-behavior property is smooth
+        //  - behavior is smooth
+        // Otherwise, perform an instant scroll of box to position.
+        // TODO: Honor the computed value of the `scroll-behavior` property for "auto".
+        if behavior == ScrollBehavior::Smooth {
+            self.perform_a_smooth_scroll(x, y, scroll_id, element);
+        } else if self.perform_an_instant_scroll(x, y, scroll_id, element) {
+            self.Document().handle_scroll_completion(element);
+        }
+    }
+
+    /// <https://drafts.csswg.org/cssom-view/#concept-smooth-scroll>
+    fn perform_a_smooth_scroll(
+        &self,
+        x: f32,
+        y: f32,
+        scroll_id: ExternalScrollId,
         element: Option<&Element>,
     ) {
-        // TODO Step 1
-        // TODO(mrobinson, #18709): Add smooth scrolling support to WebRender so that we can
-        // properly process ScrollBehavior here.
+        let start = self.scroll_offset_query_with_external_scroll_id(scroll_id);
+        let target = Vector2D::new(x, y);
+        if start == target {
+            return;
+        }
+
+        let mut smooth_scrolls = self.smooth_scrolls.borrow_mut();
+        let needs_update_scheduled = smooth_scrolls.is_empty();
+        smooth_scrolls.push(SmoothScroll {
+            scroll_id,
+            element: element.map(Dom::from_ref),
+            start,
+            target,
+            start_time: Instant::now(),
+        });
+        drop(smooth_scrolls);
+
+        if needs_update_scheduled {
+            self.schedule_smooth_scroll_update();
+        }
+    }
+
+    fn has_ongoing_smooth_scroll(&self, scroll_id: ExternalScrollId) -> bool {
+        self.smooth_scrolls
+            .borrow()
+            .iter()
+            .any(|smooth_scroll| smooth_scroll.scroll_id == scroll_id)
+    }
+
+    /// Abort the ongoing smooth scroll of the scrolling box with the given scroll id, if
+    /// there is one, leaving the box at its current scroll position.
+    pub(crate) fn abort_smooth_scroll(&self, scroll_id: ExternalScrollId) {
+        self.smooth_scrolls
+            .borrow_mut()
+            .retain(|smooth_scroll| smooth_scroll.scroll_id != scroll_id);
+    }
+
+    fn schedule_smooth_scroll_update(&self) {
+        // TODO: Smooth scrolls should be driven by the compositor, or at least by the
+        // rendering update, instead of by a timer in script.
+        let callback = OneshotTimerCallback::SmoothScrollUpdate(SmoothScrollUpdateCallback {
+            window: Trusted::new(self),
+        });
+        self.as_global_scope()
+            .schedule_callback(callback, SMOOTH_SCROLL_FRAME_INTERVAL);
+    }
+
+    /// Move every ongoing smooth scroll one step towards its destination, finishing the
+    /// ones that have taken [`SMOOTH_SCROLL_DURATION`].
+    fn update_smooth_scrolls(&self) {
+        let now = Instant::now();
+        let mut steps = vec![];
+        self.smooth_scrolls.borrow_mut().retain(|smooth_scroll| {
+            let progress = (now.duration_since(smooth_scroll.start_time).as_secs_f32() /
+                SMOOTH_SCROLL_DURATION.as_secs_f32())
+            .min(1.0);
+            // Ease in and out, so that the scroll neither starts nor stops abruptly.
+            let eased_progress = progress * progress * (3.0 - 2.0 * progress);
+            let position = smooth_scroll
+                .start
+                .lerp(smooth_scroll.target, eased_progress);
+            steps.push((
+                smooth_scroll.scroll_id,
+                position,
+                smooth_scroll
+                    .element
+                    .as_ref()
+                    .map(|element| element.as_rooted()),
+                progress >= 1.0,
+            ));
+            progress < 1.0
+        });
+
+        for (scroll_id, position, element, finished) in steps {
+            self.perform_an_instant_scroll(position.x, position.y, scroll_id, element.as_deref());
+            if finished {
+                self.Document().handle_scroll_completion(element.as_deref());
+            }
+        }
+
+        if !self.smooth_scrolls.borrow().is_empty() {
+            self.schedule_smooth_scroll_update();
+        }
+    }
+
+    /// <https://drafts.csswg.org/cssom-view/#concept-instant-scroll>
+    ///
+    /// Returns whether the scroll position of the scrolling box changed.
+    fn perform_an_instant_scroll(
+        &self,
+        x: f32,
+        y: f32,
+        scroll_id: ExternalScrollId,
+        element: Option<&Element>,
+    ) -> bool {
         let reflow_phases_run =
             self.reflow(ReflowGoal::UpdateScrollNode(scroll_id, Vector2D::new(x, y)));
 
@@ -2152,11 +2595,77 @@ impl Window {
         // > invocation, where no translations were applied as a result, then no scrollend event fires
         // > because no scrolling occurred.
         // Even though the note mention the scrollend, it is relevant to the scroll as well.
-        if reflow_phases_run.contains(ReflowPhasesRun::UpdatedScrollNodeOffset) {
-            match element {
-                Some(el) => self.Document().handle_element_scroll_event(el),
-                None => self.Document().handle_viewport_scroll_event(),
-            };
+        if !reflow_phases_run.contains(ReflowPhasesRun::UpdatedScrollNodeOffset) {
+            return false;
+        }
+        match element {
+            Some(el) => self.Document().handle_element_scroll_event(el),
+            None => self.Document().handle_viewport_scroll_event(),
+        };
+        true
+    }
+
+    /// Note that the user scrolled the scrolling box with the given scroll id. Its scrolling
+    /// is considered completed once the user has not scrolled it for [`USER_SCROLL_END_DELAY`].
+    pub(crate) fn note_user_scroll(&self, scroll_id: ExternalScrollId, element: Option<&Element>) {
+        let now = Instant::now();
+        let mut user_scrolls = self.user_scrolls.borrow_mut();
+        if let Some(user_scroll) = user_scrolls
+            .iter_mut()
+            .find(|user_scroll| user_scroll.scroll_id == scroll_id)
+        {
+            user_scroll.last_scroll_time = now;
+            return;
+        }
+
+        let needs_check_scheduled = user_scrolls.is_empty();
+        user_scrolls.push(UserScroll {
+            scroll_id,
+            element: element.map(Dom::from_ref),
+            last_scroll_time: now,
+        });
+        drop(user_scrolls);
+
+        if needs_check_scheduled {
+            self.schedule_user_scroll_end_check(USER_SCROLL_END_DELAY);
+        }
+    }
+
+    fn schedule_user_scroll_end_check(&self, delay: Duration) {
+        let callback = OneshotTimerCallback::UserScrollEndCheck(UserScrollEndCheckCallback {
+            window: Trusted::new(self),
+        });
+        self.as_global_scope().schedule_callback(callback, delay);
+    }
+
+    /// Complete the scrolling of every scrolling box that the user has stopped scrolling
+    /// for at least [`USER_SCROLL_END_DELAY`].
+    fn check_user_scroll_ends(&self) {
+        let now = Instant::now();
+        let mut completed = vec![];
+        let mut next_check = None;
+        self.user_scrolls.borrow_mut().retain(|user_scroll| {
+            let elapsed = now.duration_since(user_scroll.last_scroll_time);
+            if elapsed >= USER_SCROLL_END_DELAY {
+                completed.push(
+                    user_scroll
+                        .element
+                        .as_ref()
+                        .map(|element| element.as_rooted()),
+                );
+                return false;
+            }
+            let remaining = USER_SCROLL_END_DELAY - elapsed;
+            next_check = Some(next_check.map_or(remaining, |next: Duration| next.min(remaining)));
+            true
+        });
+
+        for element in completed {
+            self.Document().handle_scroll_completion(element.as_deref());
+        }
+
+        if let Some(delay) = next_check {
+            self.schedule_user_scroll_end_check(delay);
         }
     }
 
@@ -2255,6 +2764,7 @@ impl Window {
             node_to_animating_image_map: document.image_animation_manager().node_to_image_map(),
             theme: self.theme.get(),
             highlighted_dom_node: document.highlighted_dom_node().map(|node| node.to_opaque()),
+            view_transition_snapshot: document.view_transition_snapshot(),
         };
 
         let Some(reflow_result) = self.layout.borrow_mut().reflow(reflow) else {
@@ -2271,6 +2781,32 @@ impl Window {
             reflow_result.pending_rasterization_images,
         );
 
+        if reflow_result
+            .reflow_phases_run
+            .contains(ReflowPhasesRun::BuiltDisplayList)
+        {
+            let epoch = self.layout.borrow().current_epoch();
+            let noted_paint_timing = self
+                .paint_timing
+                .note_display_list(epoch, reflow_result.is_contentful);
+            let noted_contentful_paints = self
+                .contentful_paint
+                .note_contentful_paints(reflow_result.contentful_paints, epoch);
+            let noted_layout_shifts = self.layout_shift.note_layout_shifts(
+                reflow_result.layout_shifts,
+                self.viewport_details.get().size.to_f64().to_untyped(),
+                self.scroll_offset_query_with_external_scroll_id_no_reflow(
+                    self.pipeline_id().root_scroll_id(),
+                )
+                .to_f64()
+                .to_untyped(),
+                epoch,
+            );
+            if noted_paint_timing || noted_contentful_paints || noted_layout_shifts {
+                self.request_presentation_feedback(epoch);
+            }
+        }
+
         if let Some(iframe_sizes) = reflow_result.iframe_sizes {
             document
                 .iframes_mut()
@@ -2283,6 +2819,170 @@ impl Window {
         reflow_result.reflow_phases_run
     }
 
+    pub(crate) fn event_timing(&self) -> &EventTimingState {
+        &self.event_timing
+    }
+
+    pub(crate) fn layout_shift(&self) -> &LayoutShiftState {
+        &self.layout_shift
+    }
+
+    /// <https://w3c.github.io/event-timing/#dispatch-pending-event-timing-entries>
+    ///
+    /// When the rendering update produced a new display list, the entries are only
+    /// dispatched once the renderer reports the presentation time of the frame containing it.
+    pub(crate) fn dispatch_pending_event_timing_entries(
+        &self,
+        built_display_list: bool,
+        can_gc: CanGc,
+    ) {
+        if !built_display_list {
+            self.event_timing.dispatch_pending_entries(
+                self,
+                None,
+                CrossProcessInstant::now(),
+                can_gc,
+            );
+            return;
+        }
+
+        let epoch = self.layout.borrow().current_epoch();
+        if self.event_timing.await_presentation(epoch) {
+            self.request_presentation_feedback(epoch);
+        }
+    }
+
+    /// Ask the renderer to report when the frame containing the display list with the given
+    /// epoch is presented, see [`Self::handle_frame_presented`].
+    fn request_presentation_feedback(&self, epoch: Epoch) {
+        if self.last_presentation_feedback_epoch.replace(Some(epoch)) == Some(epoch) {
+            return;
+        }
+        let _ = self
+            .compositor_api
+            .sender()
+            .send(CompositorMsg::RequestPresentationFeedback(
+                self.webview_id(),
+                self.pipeline_id(),
+                epoch,
+            ));
+    }
+
+    /// Dispatch the Event Timing entries of the events that were rendered, and report the
+    /// paint timing, the content that was painted and the layout shifts, in the frame
+    /// containing the display list with the given epoch, now that it has been presented.
+    pub(crate) fn handle_frame_presented(
+        &self,
+        epoch: Epoch,
+        presentation_time: CrossProcessInstant,
+        can_gc: CanGc,
+    ) {
+        self.event_timing
+            .dispatch_pending_entries(self, Some(epoch), presentation_time, can_gc);
+        self.paint_timing
+            .report_paint_timing(self, epoch, presentation_time, can_gc);
+        self.contentful_paint
+            .report_contentful_paints(self, epoch, presentation_time, can_gc);
+        self.layout_shift
+            .report_layout_shifts(self, epoch, presentation_time, can_gc);
+    }
+
+    /// Whether there are idle callbacks waiting for an idle period.
+    pub(crate) fn has_idle_callbacks(&self) -> bool {
+        !self.idle_request_callbacks.borrow().is_empty() ||
+            !self.runnable_idle_callbacks.borrow().is_empty()
+    }
+
+    /// <https://w3c.github.io/requestidlecallback/#start-an-idle-period-algorithm>
+    pub(crate) fn start_an_idle_period(&self, deadline: Instant) {
+        // Step 1. Optionally, if the user agent determines the idle period should be delayed,
+        // return from this algorithm.
+        // Note: the ScriptThread only starts an idle period once it has run out of tasks.
+
+        // Step 2-5. Append all entries from the list of idle request callbacks to the list of
+        // runnable idle callbacks, preserving order, and clear the former.
+        let pending: Vec<_> = self.idle_request_callbacks.borrow_mut().drain(..).collect();
+        self.runnable_idle_callbacks.borrow_mut().extend(pending);
+
+        // Step 6. Queue a task on the idle-task task source, which performs the steps defined
+        // in the invoke idle callbacks algorithm with window and getDeadline as parameters.
+        self.queue_invoke_idle_callbacks(deadline);
+    }
+
+    fn queue_invoke_idle_callbacks(&self, deadline: Instant) {
+        let window = Trusted::new(self);
+        self.as_global_scope()
+            .task_manager()
+            .idle_task_source()
+            .queue(task!(invoke_idle_callbacks: move || {
+                window.root().invoke_idle_callbacks(deadline, CanGc::note());
+            }));
+    }
+
+    /// <https://w3c.github.io/requestidlecallback/#invoke-idle-callbacks-algorithm>
+    fn invoke_idle_callbacks(&self, deadline: Instant, can_gc: CanGc) {
+        // Step 1. If the user-agent believes it should end the idle period early due to newly
+        // scheduled high-priority work, return from the algorithm.
+        // Step 2. Let now be the current time.
+        // Step 3. If now is less than the result of calling getDeadline and the window's list of
+        // runnable idle callbacks is not empty:
+        // Note: otherwise the remaining callbacks run in the next idle period.
+        if Instant::now() >= deadline {
+            return;
+        }
+
+        // Step 3.1. Pop the top callback from window's list of runnable idle callbacks.
+        let Some((_, callback)) = self.runnable_idle_callbacks.borrow_mut().pop_front() else {
+            return;
+        };
+
+        // Step 3.2. Let deadlineArg be a new IdleDeadline whose get deadline time algorithm
+        // is getDeadline.
+        let deadline_arg = IdleDeadline::new(self, deadline, false, can_gc);
+
+        // Step 3.3. Call callback with deadlineArg as its argument. If an uncaught runtime
+        // script error occurs, then report the exception.
+        let _ = callback.Call__(&deadline_arg, ExceptionHandling::Report, can_gc);
+
+        // Step 3.4. If window's list of runnable idle callbacks is not empty, queue a task which
+        // performs the steps in the invoke idle callbacks algorithm with getDeadline and window
+        // as a parameters and return from this algorithm.
+        if !self.runnable_idle_callbacks.borrow().is_empty() {
+            self.queue_invoke_idle_callbacks(deadline);
+        }
+    }
+
+    /// <https://w3c.github.io/requestidlecallback/#invoke-idle-callback-timeout-algorithm>
+    pub(crate) fn invoke_idle_callback_timeout(&self, handle: u32, can_gc: CanGc) {
+        // Step 1. Let callback be the result of finding the entry in window's list of idle
+        // request callbacks or the list of runnable idle callbacks that is associated with
+        // the value given by the handle argument.
+        // Step 2. If callback is not undefined:
+        // Step 2.1. Remove callback from whichever list it was found in.
+        let Some(callback) = self.remove_idle_callback(handle) else {
+            return;
+        };
+
+        // Step 2.2. Let now be the current time.
+        // Step 2.3. Let deadlineArg be a new IdleDeadline whose get deadline time algorithm
+        // returns now and whose timeout is true.
+        let deadline_arg = IdleDeadline::new(self, Instant::now(), true, can_gc);
+
+        // Step 2.4. Call callback with deadlineArg as its argument. If an uncaught runtime
+        // script error occurs, then report the exception.
+        let _ = callback.Call__(&deadline_arg, ExceptionHandling::Report, can_gc);
+    }
+
+    fn remove_idle_callback(&self, handle: u32) -> Option<Rc<IdleRequestCallback>> {
+        for list in [&self.idle_request_callbacks, &self.runnable_idle_callbacks] {
+            let mut list = list.borrow_mut();
+            if let Some(index) = list.iter().position(|(entry, _)| *entry == handle) {
+                return list.remove(index).map(|(_, callback)| callback);
+            }
+        }
+        None
+    }
+
     pub(crate) fn maybe_send_idle_document_state_to_constellation(&self) {
         if !opts::get().wait_for_stable_image {
             return;
@@ -2648,7 +3348,7 @@ impl Window {
         &self,
         history_handling: NavigationHistoryBehavior,
         force_reload: bool,
-        load_data: LoadData,
+        mut load_data: LoadData,
         can_gc: CanGc,
     ) {
         let doc = self.Document();
@@ -2665,6 +3365,30 @@ impl Window {
             // Step 6
             // TODO: Fragment handling appears to have moved to step 13
             if let Some(fragment) = load_data.url.fragment() {
+                // Step 13.3. Let continue be the result of firing a push/replace/reload
+                // navigate event at navigation with navigationType set to historyHandling,
+                // isSameDocument set to true and destinationURL set to url.
+                let navigation_type = if history_handling == NavigationHistoryBehavior::Replace {
+                    NavigationType::Replace
+                } else {
+                    NavigationType::Push
+                };
+                if let Some(navigation) = self.navigation_if_created() {
+                    if !navigation.fire_a_push_replace_reload_navigate_event(
+                        navigation_type,
+                        load_data.url.clone(),
+                        true,
+                        None,
+                        can_gc,
+                    ) {
+                        return;
+                    }
+                }
+
+                if navigation_type == NavigationType::Push {
+                    self.History().persist_scroll_state();
+                }
+
                 let webdriver_sender = self.webdriver_load_status_sender.borrow().clone();
                 if let Some(ref sender) = webdriver_sender {
                     let _ = sender.send(WebDriverLoadStatus::NavigationStart);
@@ -2698,6 +3422,14 @@ impl Window {
                     .dom_manipulation_task_source()
                     .queue(task);
                 doc.set_url(load_data.url.clone());
+                if let Some(navigation) = self.navigation_if_created() {
+                    navigation.update_entries_for_same_document_navigation(
+                        navigation_type,
+                        load_data.url,
+                        self.History().state_id(),
+                        can_gc,
+                    );
+                }
                 return;
             }
         }
@@ -2711,6 +3443,55 @@ impl Window {
             }
         }
 
+        // Step 11. If historyHandling is "auto", then:
+        let resolved_history_handling = if history_handling == NavigationHistoryBehavior::Auto {
+            // Step 11.1. If url equals navigable's active document's URL, and
+            // initiatorOriginSnapshot is same origin with targetNavigable's active document's
+            // origin, then set historyHandling to "replace".
+            // Note: `targetNavigable` is not actually defined in the spec, "active document" is
+            // assumed to be the correct reference based on WPT results
+            if let LoadOrigin::Script(initiator_origin) = initiator_origin_snapshot {
+                if load_data.url == doc.url() && initiator_origin.same_origin(doc.origin()) {
+                    NavigationHistoryBehavior::Replace
+                } else {
+                    NavigationHistoryBehavior::Push
+                }
+            } else {
+                // Step 11.2. Otherwise, set historyHandling to "push".
+                NavigationHistoryBehavior::Push
+            }
+        // Step 12. If the navigation must be a replace given url and navigable's active
+        // document, then set historyHandling to "replace".
+        } else if load_data.url.scheme() == "javascript" || doc.is_initial_about_blank() {
+            NavigationHistoryBehavior::Replace
+        } else {
+            NavigationHistoryBehavior::Push
+        };
+
+        // Let continue be the result of firing a push/replace/reload navigate event at
+        // navigation with navigationType set to historyHandling, isSameDocument set to false
+        // and destinationURL set to url.
+        if load_data.url.scheme() != "javascript" {
+            if let Some(navigation) = self.navigation_if_created() {
+                let navigation_type = if force_reload {
+                    NavigationType::Reload
+                } else if resolved_history_handling == NavigationHistoryBehavior::Replace {
+                    NavigationType::Replace
+                } else {
+                    NavigationType::Push
+                };
+                if !navigation.fire_a_push_replace_reload_navigate_event(
+                    navigation_type,
+                    load_data.url.clone(),
+                    false,
+                    None,
+                    can_gc,
+                ) {
+                    return;
+                }
+            }
+        }
+
         // Step 8
         if doc.prompt_to_unload(false, can_gc) {
             let window_proxy = self.window_proxy();
@@ -2721,35 +3502,25 @@ impl Window {
                 window_proxy.start_delaying_load_events_mode();
             }
 
-            // Step 11. If historyHandling is "auto", then:
-            let resolved_history_handling = if history_handling == NavigationHistoryBehavior::Auto {
-                // Step 11.1. If url equals navigable's active document's URL, and
-                // initiatorOriginSnapshot is same origin with targetNavigable's active document's
-                // origin, then set historyHandling to "replace".
-                // Note: `targetNavigable` is not actually defined in the spec, "active document" is
-                // assumed to be the correct reference based on WPT results
-                if let LoadOrigin::Script(initiator_origin) = initiator_origin_snapshot {
-                    if load_data.url == doc.url() && initiator_origin.same_origin(doc.origin()) {
-                        NavigationHistoryBehavior::Replace
-                    } else {
-                        NavigationHistoryBehavior::Push
-                    }
-                } else {
-                    // Step 11.2. Otherwise, set historyHandling to "push".
-                    NavigationHistoryBehavior::Push
-                }
-            // Step 12. If the navigation must be a replace given url and navigable's active
-            // document, then set historyHandling to "replace".
-            } else if load_data.url.scheme() == "javascript" || doc.is_initial_about_blank() {
-                NavigationHistoryBehavior::Replace
-            } else {
-                NavigationHistoryBehavior::Push
-            };
-
             if let Some(sender) = self.webdriver_load_status_sender.borrow().as_ref() {
                 let _ = sender.send(WebDriverLoadStatus::NavigationStart);
             }
 
+            // Use the response of a prefetch started by the speculation rules of this document,
+            // if one matches the URL.
+            // <https://html.spec.whatwg.org/multipage/#find-a-matching-prefetch-record>
+            let initiated_by_document = matches!(
+                initiator_origin_snapshot,
+                LoadOrigin::Script(origin) if origin.same_origin(doc.origin())
+            );
+            if initiated_by_document &&
+                load_data.data.is_none() &&
+                doc.prefetch_records_mut()
+                    .take_matching_record(&load_data.url)
+            {
+                load_data.prefetched = true;
+            }
+
             // Step 13
             ScriptThread::navigate(pipeline_id, load_data, resolved_history_handling);
         };
@@ -2778,6 +3549,28 @@ impl Window {
             .add_restyle_reason(RestyleReason::ThemeChanged);
     }
 
+    /// The [`Navigation`] of this window, if script has accessed it.
+    pub(crate) fn navigation_if_created(&self) -> Option<DomRoot<Navigation>> {
+        self.navigation.get()
+    }
+
+    pub(crate) fn navigable_history(&self) -> Ref<'_, Option<(Vec<NavigableHistoryEntry>, usize)>> {
+        self.navigable_history.borrow()
+    }
+
+    /// Store the session history of the navigable of this window sent by the constellation.
+    pub(crate) fn set_navigable_history(
+        &self,
+        entries: Vec<NavigableHistoryEntry>,
+        current_index: usize,
+        can_gc: CanGc,
+    ) {
+        *self.navigable_history.borrow_mut() = Some((entries, current_index));
+        if let Some(navigation) = self.navigation_if_created() {
+            navigation.navigable_history_changed(can_gc);
+        }
+    }
+
     pub(crate) fn get_url(&self) -> ServoUrl {
         self.Document().url()
     }
@@ -3138,6 +3931,7 @@ impl Window {
         player_context: WindowGLContext,
         #[cfg(feature = "webgpu")] gpu_id_hub: Arc<IdentityHub>,
         inherited_secure_context: Option<bool>,
+        cross_origin_isolated: bool,
         theme: Theme,
     ) -> DomRoot<Self> {
         let error_reporter = CSSErrorReporter {
@@ -3166,6 +3960,7 @@ impl Window {
                 #[cfg(feature = "webgpu")]
                 gpu_id_hub,
                 inherited_secure_context,
+                cross_origin_isolated,
                 unminify_js,
             ),
             script_chan,
@@ -3176,8 +3971,11 @@ impl Window {
             navigator: Default::default(),
             location: Default::default(),
             history: Default::default(),
+            navigation: Default::default(),
+            navigable_history: Default::default(),
             indexeddb: Default::default(),
             custom_element_registry: Default::default(),
+            active_scoped_custom_element_constructors: Default::default(),
             window_proxy: Default::default(),
             document: Default::default(),
             performance: Default::default(),
@@ -3229,7 +4027,18 @@ impl Window {
             trusted_types: Default::default(),
             reporting_observer_list: Default::default(),
             report_list: Default::default(),
+            report_buffer: Default::default(),
             endpoints_list: Default::default(),
+            idle_callback_identifier: Default::default(),
+            idle_request_callbacks: Default::default(),
+            runnable_idle_callbacks: Default::default(),
+            smooth_scrolls: Default::default(),
+            user_scrolls: Default::default(),
+            event_timing: Default::default(),
+            paint_timing: Default::default(),
+            contentful_paint: Default::default(),
+            layout_shift: Default::default(),
+            last_presentation_feedback_epoch: Default::default(),
         });
 
         unsafe {
@@ -3444,3 +4253,48 @@ impl WindowHelpers for Window {
         Self::create_named_properties_object(cx, proto, object)
     }
 }
+
+/// The timer callback of the `timeout` option of
+/// <https://w3c.github.io/requestidlecallback/#the-requestidlecallback-method>.
+#[derive(JSTraceable, MallocSizeOf)]
+pub(crate) struct IdleCallbackTimeoutCallback {
+    #[ignore_malloc_size_of = "Because it is non-owning"]
+    window: Trusted<Window>,
+    handle: u32,
+}
+
+impl IdleCallbackTimeoutCallback {
+    pub(crate) fn invoke(self, can_gc: CanGc) {
+        self.window
+            .root()
+            .invoke_idle_callback_timeout(self.handle, can_gc);
+    }
+}
+
+/// The timer callback that moves the ongoing smooth scrolls of a [`Window`] one step
+/// towards their destination.
+#[derive(JSTraceable, MallocSizeOf)]
+pub(crate) struct SmoothScrollUpdateCallback {
+    #[ignore_malloc_size_of = "Because it is non-owning"]
+    window: Trusted<Window>,
+}
+
+impl SmoothScrollUpdateCallback {
+    pub(crate) fn invoke(self) {
+        self.window.root().update_smooth_scrolls();
+    }
+}
+
+/// The timer callback that completes the scrolling of the scrolling boxes of a [`Window`]
+/// that the user has stopped scrolling.
+#[derive(JSTraceable, MallocSizeOf)]
+pub(crate) struct UserScrollEndCheckCallback {
+    #[ignore_malloc_size_of = "Because it is non-owning"]
+    window: Trusted<Window>,
+}
+
+impl UserScrollEndCheckCallback {
+    pub(crate) fn invoke(self) {
+        self.window.root().check_user_scroll_ends();
+    }
+}
@@ -11,6 +11,7 @@ use constellation_traits::{
     ScriptToConstellationMessage,
 };
 use dom_struct::dom_struct;
+use embedder_traits::AuxiliaryWebViewFeatures;
 use html5ever::local_name;
 use indexmap::map::IndexMap;
 use ipc_channel::ipc;
@@ -32,9 +33,9 @@ use js::jsval::{NullValue, PrivateValue, UndefinedValue};
 use js::rust::wrappers::{JS_TransplantObject, NewWindowProxy, SetWindowProxy};
 use js::rust::{Handle, MutableHandle, MutableHandleValue, get_object_class};
 use malloc_size_of::{MallocSizeOf, MallocSizeOfOps};
-use net_traits::IpcSend;
 use net_traits::request::Referrer;
 use net_traits::storage_thread::StorageThreadMsg;
+use net_traits::{IpcSend, ReferrerPolicy};
 use script_traits::NewLayoutInfo;
 use serde::{Deserialize, Serialize};
 use servo_url::{ImmutableOrigin, ServoUrl};
@@ -287,6 +288,7 @@ impl WindowProxy {
         &self,
         name: DOMString,
         noopener: bool,
+        features: AuxiliaryWebViewFeatures,
     ) -> Option<DomRoot<WindowProxy>> {
         let (response_sender, response_receiver) = ipc::channel().unwrap();
         let window = self
@@ -302,7 +304,7 @@ impl WindowProxy {
             .and_then(ScriptThread::find_document)
             .expect("A WindowProxy creating an auxiliary to have an active document");
         let blank_url = ServoUrl::parse("about:blank").ok().unwrap();
-        let load_data = LoadData::new(
+        let mut load_data = LoadData::new(
             LoadOrigin::Script(document.origin().immutable().clone()),
             blank_url,
             None,
@@ -316,12 +318,14 @@ impl WindowProxy {
             load_data: load_data.clone(),
             opener_webview_id: window.webview_id(),
             opener_pipeline_id: self.currently_active.get().unwrap(),
+            features,
             response_sender,
         };
         let constellation_msg = ScriptToConstellationMessage::CreateAuxiliaryWebView(load_info);
         window.send_to_constellation(constellation_msg);
 
         let response = response_receiver.recv().unwrap()?;
+        load_data.sandboxing_flag_set = response.sandboxing_flag_set;
         let new_browsing_context_id = BrowsingContextId::from(response.new_webview_id);
         let new_layout_info = NewLayoutInfo {
             parent_info: None,
@@ -492,7 +496,7 @@ impl WindowProxy {
             _ => target,
         };
         // Step 6. Let tokenizedFeatures be the result of tokenizing features.
-        let tokenized_features = tokenize_open_features(features);
+        let mut tokenized_features = tokenize_open_features(features);
         // Step 7 - 8.
         // If tokenizedFeatures["noreferrer"] exists, then set noreferrer to
         // the result of parsing tokenizedFeatures["noreferrer"] as a boolean feature.
@@ -505,21 +509,37 @@ impl WindowProxy {
         } else {
             parse_open_feature_boolean(&tokenized_features, "noopener")
         };
-        // (TODO) Step 10. Remove tokenizedFeatures["noopener"] and tokenizedFeatures["noreferrer"].
+        // Step 10. Remove tokenizedFeatures["noopener"] and tokenizedFeatures["noreferrer"].
+        tokenized_features.shift_remove("noopener");
+        tokenized_features.shift_remove("noreferrer");
+
+        // Step 11. Let referrerPolicy be the empty string.
+        // Step 12. If noreferrer is true, then set referrerPolicy to "no-referrer".
+        let referrer_policy = if noreferrer {
+            ReferrerPolicy::NoReferrer
+        } else {
+            ReferrerPolicy::EmptyString
+        };
 
-        // (TODO) Step 11. Let referrerPolicy be the empty string.
-        // (TODO) Step 12. If noreferrer is true, then set referrerPolicy to "no-referrer".
+        // Step 15.2. Set up browsing context features for targetNavigable's active browsing
+        // context given tokenizedFeatures. This happens when the embedder is asked to create
+        // it, so the features are gathered up front.
+        let device_pixel_ratio = self
+            .currently_active
+            .get()
+            .and_then(ScriptThread::find_document)
+            .map_or(1.0, |document| document.window().device_pixel_ratio().get());
+        let features = set_up_browsing_context_features(&tokenized_features, device_pixel_ratio);
 
         // Step 13 - 14
         // Let targetNavigable and windowType be the result of applying the rules for
         // choosing a navigable given target, sourceDocument's node navigable, and noopener.
         // If targetNavigable is null, then return null.
-        let (chosen, new) = match self.choose_browsing_context(non_empty_target, noopener) {
+        let (chosen, new) = match self.choose_browsing_context(non_empty_target, noopener, features)
+        {
             (Some(chosen), new) => (chosen, new),
             (None, _) => return Ok(None),
         };
-        // TODO Step 15.2, Set up browsing context features for targetNavigable's
-        // active browsing context given tokenizedFeatures.
         let target_document = match chosen.document() {
             Some(target_document) => target_document,
             None => return Ok(None),
@@ -545,15 +565,20 @@ impl WindowProxy {
             let referrer = if noreferrer {
                 Referrer::NoReferrer
             } else {
-                target_window.as_global_scope().get_referrer()
+                existing_document.window().as_global_scope().get_referrer()
             };
             // Step 15.5 Otherwise, navigate targetNavigable to urlRecord using sourceDocument,
             // with referrerPolicy set to referrerPolicy and exceptionsEnabled set to true.
-            // FIXME: referrerPolicy may not be used properly here. exceptionsEnabled not used.
-            let referrer_policy = target_document.get_referrer_policy();
+            // An empty referrerPolicy falls back to the policy of sourceDocument.
+            // FIXME: exceptionsEnabled not used.
+            let referrer_policy = if referrer_policy == ReferrerPolicy::EmptyString {
+                existing_document.get_referrer_policy()
+            } else {
+                referrer_policy
+            };
             let pipeline_id = target_window.pipeline_id();
             let secure = target_window.as_global_scope().is_secure_context();
-            let load_data = LoadData::new(
+            let mut load_data = LoadData::new(
                 LoadOrigin::Script(existing_document.origin().immutable().clone()),
                 url,
                 Some(pipeline_id),
@@ -563,6 +588,8 @@ impl WindowProxy {
                 Some(target_document.insecure_requests_policy()),
                 has_trustworthy_ancestor_origin,
             );
+            load_data.source_snapshot_params =
+                Some(existing_document.snapshot_source_snapshot_params());
             let history_handling = if new {
                 NavigationHistoryBehavior::Replace
             } else {
@@ -583,6 +610,7 @@ impl WindowProxy {
         &self,
         name: DOMString,
         noopener: bool,
+        features: AuxiliaryWebViewFeatures,
     ) -> (Option<DomRoot<WindowProxy>>, bool) {
         match name.to_lowercase().as_ref() {
             "" | "_self" => {
@@ -600,7 +628,10 @@ impl WindowProxy {
                 // Step 5
                 (Some(DomRoot::from_ref(self.top())), false)
             },
-            "_blank" => (self.create_auxiliary_browsing_context(name, noopener), true),
+            "_blank" => (
+                self.create_auxiliary_browsing_context(name, noopener, features),
+                true,
+            ),
             _ => {
                 // Step 6.
                 // TODO: expand the search to all 'familiar' bc,
@@ -608,7 +639,10 @@ impl WindowProxy {
                 // See https://html.spec.whatwg.org/multipage/#familiar-with
                 match ScriptThread::find_window_proxy_by_name(&name) {
                     Some(proxy) => (Some(proxy), false),
-                    None => (self.create_auxiliary_browsing_context(name, noopener), true),
+                    None => (
+                        self.create_auxiliary_browsing_context(name, noopener, features),
+                        true,
+                    ),
                 }
             },
         }
@@ -905,6 +939,62 @@ fn parse_open_feature_boolean(tokenized_features: &IndexMap<String, String>, nam
     false
 }
 
+// https://html.spec.whatwg.org/multipage/#popup-window-is-requested
+fn is_popup_window_requested(tokenized_features: &IndexMap<String, String>) -> bool {
+    let parse_with_default = |name: &str, default: bool| {
+        if tokenized_features.contains_key(name) {
+            parse_open_feature_boolean(tokenized_features, name)
+        } else {
+            default
+        }
+    };
+    // Step 1
+    if tokenized_features.is_empty() {
+        return false;
+    }
+    // Step 2
+    if tokenized_features.contains_key("popup") {
+        return parse_open_feature_boolean(tokenized_features, "popup");
+    }
+    // Step 3 - 5
+    if !parse_with_default("location", false) && !parse_with_default("toolbar", false) {
+        return true;
+    }
+    // Step 6 - 9
+    !parse_with_default("menubar", false) ||
+        !parse_with_default("resizable", true) ||
+        !parse_with_default("scrollbars", false) ||
+        !parse_with_default("status", false)
+}
+
+// https://html.spec.whatwg.org/multipage/#set-up-browsing-context-features
+fn set_up_browsing_context_features(
+    tokenized_features: &IndexMap<String, String>,
+    device_pixel_ratio: f32,
+) -> AuxiliaryWebViewFeatures {
+    // Features that fail to parse as an integer are treated as if they were 0.
+    let parse_in_device_pixels = |name: &str| {
+        tokenized_features.get(name).map(|value| {
+            let css_pixels = parse_integer(value.chars()).unwrap_or(0);
+            (css_pixels as f32 * device_pixel_ratio) as i32
+        })
+    };
+    // A requested size of 0 is ignored, and any other is at least 100 CSS pixels.
+    let minimum_size = (100.0 * device_pixel_ratio) as i32;
+    let parse_size = |name: &str| {
+        parse_in_device_pixels(name)
+            .filter(|size| *size != 0)
+            .map(|size| size.max(minimum_size))
+    };
+    AuxiliaryWebViewFeatures {
+        is_popup: is_popup_window_requested(tokenized_features),
+        left: parse_in_device_pixels("left"),
+        top: parse_in_device_pixels("top"),
+        width: parse_size("width"),
+        height: parse_size("height"),
+    }
+}
+
 // This is only called from extern functions,
 // there's no use using the lifetimed handles here.
 // https://html.spec.whatwg.org/multipage/#accessing-other-browsing-contexts
@@ -27,7 +27,7 @@ use net_traits::request::{
 };
 use net_traits::{IpcSend, ReferrerPolicy};
 use profile_traits::mem::{ProcessReports, perform_memory_report};
-use servo_url::{MutableOrigin, ServoUrl};
+use servo_url::{ImmutableOrigin, MutableOrigin, ServoUrl};
 use timers::TimerScheduler;
 use uuid::Uuid;
 
@@ -42,7 +42,7 @@ use crate::dom::bindings::codegen::Bindings::VoidFunctionBinding::VoidFunction;
 use crate::dom::bindings::codegen::Bindings::WorkerBinding::WorkerType;
 use crate::dom::bindings::codegen::Bindings::WorkerGlobalScopeBinding::WorkerGlobalScopeMethods;
 use crate::dom::bindings::codegen::UnionTypes::{
-    RequestOrUSVString, StringOrFunction, TrustedScriptURLOrUSVString,
+    RequestOrUSVString, TrustedScriptOrStringOrFunction, TrustedScriptURLOrUSVString,
 };
 use crate::dom::bindings::error::{Error, ErrorResult, Fallible, report_pending_exception};
 use crate::dom::bindings::inheritance::Castable;
@@ -61,6 +61,7 @@ use crate::dom::performance::Performance;
 use crate::dom::promise::Promise;
 use crate::dom::reportingendpoint::{ReportingEndpoint, SendReportsToEndpoints};
 use crate::dom::reportingobserver::ReportingObserver;
+use crate::dom::scheduler::Scheduler;
 use crate::dom::trustedscripturl::TrustedScriptURL;
 use crate::dom::trustedtypepolicyfactory::TrustedTypePolicyFactory;
 use crate::dom::types::ImageBitmap;
@@ -93,6 +94,8 @@ pub(crate) fn prepare_workerscope_init(
         origin: global.origin().immutable().clone(),
         creation_url: global.creation_url().clone(),
         inherited_secure_context: Some(global.is_secure_context()),
+        inherited_cross_origin_isolated: global.is_cross_origin_isolated(),
+        ancestor_origins: global.origin_and_ancestor_origins(),
     };
 
     init
@@ -145,12 +148,20 @@ pub(crate) struct WorkerGlobalScope {
     #[no_trace]
     insecure_requests_policy: InsecureRequestsPolicy,
 
+    /// The origin of the owner of this worker followed by the origins of the ancestors of its
+    /// navigable.
+    #[no_trace]
+    ancestor_origins: Vec<ImmutableOrigin>,
+
     /// <https://w3c.github.io/reporting/#windoworworkerglobalscope-registered-reporting-observer-list>
     reporting_observer_list: DomRefCell<Vec<DomRoot<ReportingObserver>>>,
 
     /// <https://w3c.github.io/reporting/#windoworworkerglobalscope-reports>
     report_list: DomRefCell<Vec<Report>>,
 
+    /// <https://w3c.github.io/reporting/#windoworworkerglobalscope-report-buffer>
+    report_buffer: DomRefCell<Vec<Report>>,
+
     /// <https://w3c.github.io/reporting/#windoworworkerglobalscope-endpoints>
     #[no_trace]
     endpoints_list: DomRefCell<Vec<ReportingEndpoint>>,
@@ -192,6 +203,7 @@ impl WorkerGlobalScope {
                 #[cfg(feature = "webgpu")]
                 gpu_id_hub,
                 init.inherited_secure_context,
+                init.inherited_cross_origin_isolated,
                 false,
             ),
             worker_id: init.worker_id,
@@ -210,9 +222,11 @@ impl WorkerGlobalScope {
             indexeddb: Default::default(),
             timer_scheduler: RefCell::default(),
             insecure_requests_policy,
+            ancestor_origins: init.ancestor_origins,
             trusted_types: Default::default(),
             reporting_observer_list: Default::default(),
             report_list: Default::default(),
+            report_buffer: Default::default(),
             endpoints_list: Default::default(),
         }
     }
@@ -222,6 +236,12 @@ impl WorkerGlobalScope {
         self.insecure_requests_policy
     }
 
+    /// The origin of the owner of this worker followed by the origins of the ancestors of its
+    /// navigable.
+    pub(crate) fn ancestor_origins(&self) -> &[ImmutableOrigin] {
+        &self.ancestor_origins
+    }
+
     /// Clear various items when the worker event-loop shuts-down.
     pub(crate) fn clear_js_runtime(&self) {
         self.upcast::<GlobalScope>()
@@ -305,7 +325,13 @@ impl WorkerGlobalScope {
     }
 
     pub(crate) fn append_report(&self, report: Report) {
-        self.report_list.borrow_mut().push(report);
+        let mut report_list = self.report_list.borrow_mut();
+        report_list.push(report);
+        // Reports are delivered in batches: if a delivery task is already pending,
+        // it will pick up this report as well.
+        if report_list.len() > 1 {
+            return;
+        }
         let trusted_worker = Trusted::new(self);
         self.upcast::<GlobalScope>()
             .task_manager()
@@ -321,7 +347,11 @@ impl WorkerGlobalScope {
     }
 
     pub(crate) fn buffered_reports(&self) -> Vec<Report> {
-        self.report_list.borrow().clone()
+        self.report_buffer.borrow().clone()
+    }
+
+    pub(crate) fn report_buffer_mut(&self) -> RefMut<'_, Vec<Report>> {
+        self.report_buffer.borrow_mut()
     }
 
     pub(crate) fn set_endpoints_list(&self, endpoints: Option<Vec<ReportingEndpoint>>) {
@@ -330,6 +360,10 @@ impl WorkerGlobalScope {
         }
     }
 
+    pub(crate) fn endpoints_list_mut(&self) -> RefMut<'_, Vec<ReportingEndpoint>> {
+        self.endpoints_list.borrow_mut()
+    }
+
     /// Get a mutable reference to the [`TimerScheduler`] for this [`ServiceWorkerGlobalScope`].
     pub(crate) fn timer_scheduler(&self) -> RefMut<TimerScheduler> {
         self.timer_scheduler.borrow_mut()
@@ -471,6 +505,11 @@ impl WorkerGlobalScopeMethods<crate::DomTypeHolder> for WorkerGlobalScope {
         self.upcast::<GlobalScope>().crypto(CanGc::note())
     }
 
+    // https://wicg.github.io/scheduling-apis/#dom-windoworworkerglobalscope-scheduler
+    fn Scheduler(&self) -> DomRoot<Scheduler> {
+        self.upcast::<GlobalScope>().scheduler(CanGc::note())
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-windowbase64-btoa
     fn Btoa(&self, btoa: DOMString) -> Fallible<DOMString> {
         base64_btoa(btoa)
@@ -485,20 +524,23 @@ impl WorkerGlobalScopeMethods<crate::DomTypeHolder> for WorkerGlobalScope {
     fn SetTimeout(
         &self,
         _cx: JSContext,
-        callback: StringOrFunction,
+        callback: TrustedScriptOrStringOrFunction,
         timeout: i32,
         args: Vec<HandleValue>,
-    ) -> i32 {
-        let callback = match callback {
-            StringOrFunction::String(i) => TimerCallback::StringTimerCallback(i),
-            StringOrFunction::Function(i) => TimerCallback::FunctionTimerCallback(i),
-        };
-        self.upcast::<GlobalScope>().set_timeout_or_interval(
+        can_gc: CanGc,
+    ) -> Fallible<i32> {
+        let callback = TimerCallback::from_handler(
+            self.upcast::<GlobalScope>(),
+            callback,
+            "setTimeout",
+            can_gc,
+        )?;
+        Ok(self.upcast::<GlobalScope>().set_timeout_or_interval(
             callback,
             args,
             Duration::from_millis(timeout.max(0) as u64),
             IsInterval::NonInterval,
-        )
+        ))
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-windowtimers-cleartimeout
@@ -511,20 +553,23 @@ impl WorkerGlobalScopeMethods<crate::DomTypeHolder> for WorkerGlobalScope {
     fn SetInterval(
         &self,
         _cx: JSContext,
-        callback: StringOrFunction,
+        callback: TrustedScriptOrStringOrFunction,
         timeout: i32,
         args: Vec<HandleValue>,
-    ) -> i32 {
-        let callback = match callback {
-            StringOrFunction::String(i) => TimerCallback::StringTimerCallback(i),
-            StringOrFunction::Function(i) => TimerCallback::FunctionTimerCallback(i),
-        };
-        self.upcast::<GlobalScope>().set_timeout_or_interval(
+        can_gc: CanGc,
+    ) -> Fallible<i32> {
+        let callback = TimerCallback::from_handler(
+            self.upcast::<GlobalScope>(),
+            callback,
+            "setInterval",
+            can_gc,
+        )?;
+        Ok(self.upcast::<GlobalScope>().set_timeout_or_interval(
             callback,
             args,
             Duration::from_millis(timeout.max(0) as u64),
             IsInterval::Interval,
-        )
+        ))
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-windowtimers-clearinterval
@@ -617,6 +662,11 @@ impl WorkerGlobalScopeMethods<crate::DomTypeHolder> for WorkerGlobalScope {
         self.upcast::<GlobalScope>().is_secure_context()
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-crossoriginisolated
+    fn CrossOriginIsolated(&self) -> bool {
+        self.upcast::<GlobalScope>().is_cross_origin_isolated()
+    }
+
     /// <https://html.spec.whatwg.org/multipage/#dom-structuredclone>
     fn StructuredClone(
         &self,
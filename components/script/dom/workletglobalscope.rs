@@ -112,6 +112,7 @@ impl WorkletGlobalScope {
                 init.gpu_id_hub.clone(),
                 init.inherited_secure_context,
                 false,
+                false,
             ),
             base_url,
             to_script_thread_sender: init.to_script_thread_sender.clone(),
@@ -65,6 +65,7 @@ use crate::dom::node::Node;
 use crate::dom::performanceresourcetiming::InitiatorType;
 use crate::dom::progressevent::ProgressEvent;
 use crate::dom::readablestream::ReadableStream;
+use crate::dom::reportingobserver::ReportingObserver;
 use crate::dom::servoparser::ServoParser;
 use crate::dom::window::Window;
 use crate::dom::workerglobalscope::WorkerGlobalScope;
@@ -396,6 +397,16 @@ impl XMLHttpRequestMethods<crate::DomTypeHolder> for XMLHttpRequest {
                     {
                         return Err(Error::InvalidAccess);
                     }
+                    let global = self.global();
+                    if global.is::<Window>() {
+                        ReportingObserver::report_deprecation(
+                            &global,
+                            "XMLHttpRequestSynchronousInNonWorkerOutsideBeforeUnload",
+                            "Synchronous XMLHttpRequest on the main thread is deprecated because of \
+                             its detrimental effects to the end user's experience.",
+                            CanGc::note(),
+                        );
+                    }
                 }
                 // Step 11 - abort existing requests
                 self.terminate_ongoing_fetch();
@@ -0,0 +1,557 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Drag and drop of content within a page, of content out of a page into the embedder
+//! and of files from the embedder into a page.
+//!
+//! <https://html.spec.whatwg.org/multipage/#dnd>
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use embedder_traits::{DragDropAction, DragDropEvent, EmbedderMsg};
+use euclid::Point2D;
+use net_traits::image_cache::Image;
+use script_traits::ConstellationInputEvent;
+use servo_config::pref;
+use style_traits::CSSPixel;
+
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::HTMLAnchorElementBinding::HTMLAnchorElementMethods;
+use crate::dom::bindings::codegen::Bindings::HTMLImageElementBinding::HTMLImageElementMethods;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::root::{DomRoot, MutNullableDom};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::datatransfer::DataTransfer;
+use crate::dom::document::Document;
+use crate::dom::dragevent::DragEvent;
+use crate::dom::element::Element;
+use crate::dom::event::{Event, EventBubbles, EventCancelable};
+use crate::dom::eventtarget::EventTarget;
+use crate::dom::htmlanchorelement::HTMLAnchorElement;
+use crate::dom::htmlelement::HTMLElement;
+use crate::dom::htmlimageelement::HTMLImageElement;
+use crate::dom::inputevent::{HitTestResult, InputEvent, InputEventType};
+use crate::dom::node::{Node, ShadowIncluding};
+use crate::drag_data_store::{DragDataStore, Kind, Mode};
+use crate::editing;
+use crate::script_runtime::CanGc;
+
+/// The distance in CSS pixels that the mouse has to move with the primary button pressed
+/// on a draggable element before a drag-and-drop operation starts.
+const DRAG_THRESHOLD: f32 = 4.0;
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum DragEventType {
+    DragStart,
+    Drag,
+    DragEnter,
+    DragLeave,
+    DragOver,
+    Drop,
+    DragEnd,
+}
+
+impl DragEventType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DragEventType::DragStart => "dragstart",
+            DragEventType::Drag => "drag",
+            DragEventType::DragEnter => "dragenter",
+            DragEventType::DragLeave => "dragleave",
+            DragEventType::DragOver => "dragover",
+            DragEventType::Drop => "drop",
+            DragEventType::DragEnd => "dragend",
+        }
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dndevents>
+    fn cancelable(&self) -> EventCancelable {
+        match self {
+            DragEventType::DragLeave | DragEventType::DragEnd => EventCancelable::NotCancelable,
+            _ => EventCancelable::Cancelable,
+        }
+    }
+
+    /// The mode of the drag data store while this event is dispatched.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/#dndevents>
+    fn mode(&self) -> Mode {
+        match self {
+            DragEventType::DragStart => Mode::ReadWrite,
+            DragEventType::Drop => Mode::ReadOnly,
+            _ => Mode::Protected,
+        }
+    }
+}
+
+/// The state of the drag-and-drop operation of a [`Document`], if there is one.
+#[derive(JSTraceable, MallocSizeOf)]
+#[cfg_attr(crown, crown::unrooted_must_root_lint::must_root)]
+pub(crate) struct DragAndDropState {
+    /// A draggable element that the primary mouse button was pressed on. It becomes the
+    /// source node once the mouse has moved far enough from `candidate_point`.
+    candidate: MutNullableDom<Element>,
+    #[no_trace]
+    candidate_point: Cell<Option<Point2D<f32, CSSPixel>>>,
+    /// <https://html.spec.whatwg.org/multipage/#source-node>
+    source_node: MutNullableDom<Element>,
+    /// <https://html.spec.whatwg.org/multipage/#current-target-element>
+    current_target: MutNullableDom<Element>,
+    /// Whether the current target element accepted a drop during the last `dragover`.
+    drop_allowed: Cell<bool>,
+    /// The drag data store of the drag-and-drop operation in progress, if any.
+    #[ignore_malloc_size_of = "Rc"]
+    #[no_trace]
+    data_store: DomRefCell<Option<Rc<RefCell<Option<DragDataStore>>>>>,
+    /// Whether the `click` that follows the `mouseup` which ended a drag should be ignored.
+    suppress_click: Cell<bool>,
+}
+
+impl DragAndDropState {
+    pub(crate) fn new() -> Self {
+        Self {
+            candidate: Default::default(),
+            candidate_point: Cell::new(None),
+            source_node: Default::default(),
+            current_target: Default::default(),
+            drop_allowed: Cell::new(false),
+            data_store: DomRefCell::new(None),
+            suppress_click: Cell::new(false),
+        }
+    }
+
+    /// Remember the draggable element under the mouse after a `mousedown` of the primary
+    /// button was dispatched and not canceled.
+    pub(crate) fn handle_mouse_down(&self, target: &Node, point: Point2D<f32, CSSPixel>) {
+        self.suppress_click.set(false);
+        if !pref!(dom_drag_and_drop_enabled) {
+            return;
+        }
+        let candidate = target
+            .inclusive_ancestors(ShadowIncluding::No)
+            .filter_map(DomRoot::downcast::<HTMLElement>)
+            .find(|element| element.is_draggable());
+        self.candidate
+            .set(candidate.as_ref().map(|element| element.upcast()));
+        self.candidate_point.set(candidate.map(|_| point));
+    }
+
+    /// Handle a mouse move, starting or continuing a drag-and-drop operation. Returns true
+    /// if the move belongs to a drag, in which case no mouse events should be fired for it.
+    pub(crate) fn handle_mouse_move(
+        &self,
+        document: &Document,
+        hit_test_result: &HitTestResult,
+        input_event: &ConstellationInputEvent,
+        can_gc: CanGc,
+    ) -> bool {
+        if let Some(source) = self.source_node.get() {
+            // Step 1 of the drag-and-drop processing model: fire drag at the source node. If
+            // it is canceled, the drag-and-drop operation is aborted.
+            if !self.fire_drag_event(
+                document,
+                &source,
+                DragEventType::Drag,
+                None,
+                hit_test_result,
+                input_event,
+                can_gc,
+            ) {
+                self.drop_allowed.set(false);
+                self.end_drag(document, hit_test_result, input_event, can_gc);
+                return true;
+            }
+            self.update_current_target(document, hit_test_result, input_event, can_gc);
+            return true;
+        }
+
+        let (Some(candidate), Some(start_point)) =
+            (self.candidate.get(), self.candidate_point.get())
+        else {
+            return false;
+        };
+        if input_event.pressed_mouse_buttons & 1 == 0 {
+            self.candidate.set(None);
+            self.candidate_point.set(None);
+            return false;
+        }
+        if (hit_test_result.point_in_frame - start_point).length() < DRAG_THRESHOLD {
+            return false;
+        }
+
+        self.candidate.set(None);
+        self.candidate_point.set(None);
+        self.start_drag(document, &candidate, hit_test_result, input_event, can_gc)
+    }
+
+    /// Handle the release of the primary mouse button, dropping the dragged data on the
+    /// current target element. Returns true if a drag-and-drop operation ended.
+    pub(crate) fn handle_mouse_up(
+        &self,
+        document: &Document,
+        hit_test_result: &HitTestResult,
+        input_event: &ConstellationInputEvent,
+        can_gc: CanGc,
+    ) -> bool {
+        self.candidate.set(None);
+        self.candidate_point.set(None);
+        if self.source_node.get().is_none() {
+            return false;
+        }
+
+        self.end_drag(document, hit_test_result, input_event, can_gc);
+        self.suppress_click.set(true);
+        true
+    }
+
+    /// Whether the `click` that is being processed ends a drag and should be ignored.
+    pub(crate) fn take_suppressed_click(&self) -> bool {
+        self.suppress_click.replace(false)
+    }
+
+    /// Handle the mouse leaving the page. A drag that started in this document continues in
+    /// the embedder, which was handed the dragged data when the drag started.
+    pub(crate) fn handle_mouse_leave(
+        &self,
+        document: &Document,
+        hit_test_result: &HitTestResult,
+        input_event: &ConstellationInputEvent,
+        can_gc: CanGc,
+    ) {
+        self.candidate.set(None);
+        self.candidate_point.set(None);
+        if self.source_node.get().is_none() {
+            return;
+        }
+
+        self.drop_allowed.set(false);
+        if let Some(current_target) = self.current_target.take() {
+            self.fire_drag_event(
+                document,
+                &current_target,
+                DragEventType::DragLeave,
+                None,
+                hit_test_result,
+                input_event,
+                can_gc,
+            );
+        }
+        if let Some(source) = self.source_node.take() {
+            self.fire_drag_event(
+                document,
+                &source,
+                DragEventType::DragEnd,
+                None,
+                hit_test_result,
+                input_event,
+                can_gc,
+            );
+        }
+        *self.data_store.borrow_mut() = None;
+    }
+
+    /// Handle a drag from outside of the page, which the embedder reports with
+    /// [`DragDropEvent`]s. The dragged files are only exposed to the page on `drop`.
+    pub(crate) fn handle_embedder_drag_event(
+        &self,
+        document: &Document,
+        event: DragDropEvent,
+        hit_test_result: &HitTestResult,
+        input_event: &ConstellationInputEvent,
+        can_gc: CanGc,
+    ) {
+        // Drags that started in this document are driven by mouse events.
+        if !pref!(dom_drag_and_drop_enabled) || self.source_node.get().is_some() {
+            return;
+        }
+
+        match event.action {
+            DragDropAction::Enter | DragDropAction::Over => {
+                if self.data_store.borrow().is_none() {
+                    let mut data_store = DragDataStore::new();
+                    for file in event.files {
+                        let _ = data_store.add(Kind::File {
+                            bytes: file.bytes,
+                            name: DOMString::from(file.name),
+                            type_: file.type_,
+                        });
+                    }
+                    *self.data_store.borrow_mut() = Some(Rc::new(RefCell::new(Some(data_store))));
+                }
+                self.update_current_target(document, hit_test_result, input_event, can_gc);
+            },
+            DragDropAction::Leave => {
+                self.drop_allowed.set(false);
+                if let Some(current_target) = self.current_target.take() {
+                    self.fire_drag_event(
+                        document,
+                        &current_target,
+                        DragEventType::DragLeave,
+                        None,
+                        hit_test_result,
+                        input_event,
+                        can_gc,
+                    );
+                }
+                *self.data_store.borrow_mut() = None;
+            },
+            DragDropAction::Drop => {
+                self.end_drag(document, hit_test_result, input_event, can_gc);
+            },
+        }
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#drag-and-drop-processing-model>
+    fn start_drag(
+        &self,
+        document: &Document,
+        source: &Element,
+        hit_test_result: &HitTestResult,
+        input_event: &ConstellationInputEvent,
+        can_gc: CanGc,
+    ) -> bool {
+        // Create a drag data store and populate it with the default data for links and images.
+        let mut data_store = DragDataStore::new();
+        if let Some(anchor) = source.downcast::<HTMLAnchorElement>() {
+            let href = DOMString::from(anchor.Href().0);
+            data_store.set_data(DOMString::from("text/uri-list"), href.clone());
+            data_store.set_data(DOMString::from("text/plain"), href);
+        } else if let Some(image) = source.downcast::<HTMLImageElement>() {
+            let src = DOMString::from(image.CurrentSrc().0);
+            data_store.set_data(DOMString::from("text/uri-list"), src.clone());
+            data_store.set_data(DOMString::from("text/plain"), src);
+
+            // The default feedback for dragging an image is the image itself.
+            if let Some(raster_image) = image.image_data().as_ref().and_then(Image::as_raster_image)
+            {
+                let point_in_node = hit_test_result.point_in_node.to_i32();
+                data_store.set_bitmap(Some(raster_image), point_in_node.x, point_in_node.y);
+            }
+        }
+        *self.data_store.borrow_mut() = Some(Rc::new(RefCell::new(Some(data_store))));
+
+        // Fire dragstart at the source node. If it is canceled, the drag-and-drop
+        // operation does not occur.
+        if !self.fire_drag_event(
+            document,
+            source,
+            DragEventType::DragStart,
+            None,
+            hit_test_result,
+            input_event,
+            can_gc,
+        ) {
+            *self.data_store.borrow_mut() = None;
+            return false;
+        }
+        self.source_node.set(Some(source));
+
+        // Hand the dragged data to the embedder, which renders the drag image and takes
+        // over the drag if it leaves the page.
+        let drag_data = self.data_store.borrow().as_ref().and_then(|data_store| {
+            data_store
+                .borrow()
+                .as_ref()
+                .map(|data_store| data_store.to_drag_data())
+        });
+        if let Some(drag_data) = drag_data {
+            document.send_to_embedder(EmbedderMsg::StartDrag(document.webview_id(), drag_data));
+        }
+
+        self.update_current_target(document, hit_test_result, input_event, can_gc);
+        true
+    }
+
+    /// Update the current target element to the element under the pointer, firing
+    /// `dragenter`, `dragleave` and `dragover` as needed.
+    fn update_current_target(
+        &self,
+        document: &Document,
+        hit_test_result: &HitTestResult,
+        input_event: &ConstellationInputEvent,
+        can_gc: CanGc,
+    ) {
+        let new_target = hit_test_result
+            .node
+            .inclusive_ancestors(ShadowIncluding::No)
+            .filter_map(DomRoot::downcast::<Element>)
+            .next();
+        let previous_target = self.current_target.get();
+
+        if new_target != previous_target {
+            if let Some(new_target) = new_target.as_ref() {
+                self.fire_drag_event(
+                    document,
+                    new_target,
+                    DragEventType::DragEnter,
+                    previous_target.as_deref(),
+                    hit_test_result,
+                    input_event,
+                    can_gc,
+                );
+            }
+            if let Some(previous_target) = previous_target.as_ref() {
+                self.fire_drag_event(
+                    document,
+                    previous_target,
+                    DragEventType::DragLeave,
+                    new_target.as_deref(),
+                    hit_test_result,
+                    input_event,
+                    can_gc,
+                );
+            }
+            self.current_target.set(new_target.as_deref());
+        }
+
+        let Some(target) = new_target else {
+            self.drop_allowed.set(false);
+            return;
+        };
+
+        // Canceling dragover accepts the drop. Editable content accepts drops by default.
+        let canceled = !self.fire_drag_event(
+            document,
+            &target,
+            DragEventType::DragOver,
+            None,
+            hit_test_result,
+            input_event,
+            can_gc,
+        );
+        self.drop_allowed
+            .set(canceled || target.upcast::<Node>().editing_host().is_some());
+    }
+
+    /// Drop the dragged data on the current target element if it accepted the drop, then
+    /// fire `dragend` at the source node and reset the drag-and-drop state.
+    fn end_drag(
+        &self,
+        document: &Document,
+        hit_test_result: &HitTestResult,
+        input_event: &ConstellationInputEvent,
+        can_gc: CanGc,
+    ) {
+        if let Some(current_target) = self.current_target.take() {
+            if !self.drop_allowed.get() {
+                self.fire_drag_event(
+                    document,
+                    &current_target,
+                    DragEventType::DragLeave,
+                    None,
+                    hit_test_result,
+                    input_event,
+                    can_gc,
+                );
+            } else if self.fire_drag_event(
+                document,
+                &current_target,
+                DragEventType::Drop,
+                None,
+                hit_test_result,
+                input_event,
+                can_gc,
+            ) {
+                self.insert_dropped_text(document, &current_target, can_gc);
+            }
+        }
+
+        if let Some(source) = self.source_node.take() {
+            self.fire_drag_event(
+                document,
+                &source,
+                DragEventType::DragEnd,
+                None,
+                hit_test_result,
+                input_event,
+                can_gc,
+            );
+            document.send_to_embedder(EmbedderMsg::EndDrag(document.webview_id()));
+        }
+
+        self.drop_allowed.set(false);
+        *self.data_store.borrow_mut() = None;
+    }
+
+    /// The default action of `drop` on editable content: insert the dragged text.
+    fn insert_dropped_text(&self, document: &Document, target: &Element, can_gc: CanGc) {
+        let Some(editing_host) = target.upcast::<Node>().editing_host() else {
+            return;
+        };
+        let text = self.data_store.borrow().as_ref().and_then(|data_store| {
+            data_store
+                .borrow()
+                .as_ref()
+                .and_then(|data_store| data_store.find_matching_text("text/plain"))
+        });
+        let Some(text) = text.filter(|text| !text.is_empty()) else {
+            return;
+        };
+
+        let event_target = editing_host.upcast::<EventTarget>();
+        if !InputEvent::dispatch_beforeinput(
+            event_target,
+            InputEventType::InsertFromDrop,
+            Some(text.clone()),
+            &[],
+            can_gc,
+        ) {
+            return;
+        }
+        editing::insert_text(document, editing_host.upcast(), &text, false, can_gc);
+        InputEvent::dispatch_input(
+            event_target,
+            InputEventType::InsertFromDrop,
+            Some(text),
+            can_gc,
+        );
+    }
+
+    /// Fire a [`DragEvent`] whose `dataTransfer` is associated with the drag data store of
+    /// the current operation. Returns false if the event was canceled.
+    #[allow(clippy::too_many_arguments)]
+    fn fire_drag_event(
+        &self,
+        document: &Document,
+        target: &Element,
+        event_type: DragEventType,
+        related_target: Option<&Element>,
+        hit_test_result: &HitTestResult,
+        input_event: &ConstellationInputEvent,
+        can_gc: CanGc,
+    ) -> bool {
+        let Some(data_store) = self.data_store.borrow().clone() else {
+            return true;
+        };
+        if let Some(data_store) = data_store.borrow_mut().as_mut() {
+            data_store.set_mode(event_type.mode());
+        }
+
+        let window = document.window();
+        let data_transfer = DataTransfer::new(window, Rc::clone(&data_store), can_gc);
+        let event = DragEvent::new(
+            window,
+            DOMString::from(event_type.as_str()),
+            EventBubbles::Bubbles,
+            event_type.cancelable(),
+            hit_test_result.point_in_frame.to_i32(),
+            hit_test_result
+                .point_relative_to_initial_containing_block
+                .to_i32(),
+            input_event.active_keyboard_modifiers,
+            input_event.pressed_mouse_buttons,
+            related_target.map(|element| element.upcast()),
+            Some(&data_transfer),
+            can_gc,
+        );
+        let result = event.upcast::<Event>().fire(target.upcast(), can_gc);
+
+        // Once the event has been dispatched the page can no longer read or change the data.
+        if let Some(data_store) = data_store.borrow_mut().as_mut() {
+            data_store.set_mode(Mode::Protected);
+        }
+        result
+    }
+}
@@ -5,6 +5,8 @@
 use std::sync::Arc;
 
 use constellation_traits::BlobImpl;
+use embedder_traits::{DataTransferFile, DragData};
+use euclid::Point2D;
 use indexmap::IndexMap;
 use pixels::RasterImage;
 
@@ -68,7 +70,6 @@ impl Kind {
 }
 
 /// <https://html.spec.whatwg.org/multipage/#drag-data-store-bitmap>
-#[allow(dead_code)] // TODO this used by DragEvent.
 struct Bitmap {
     image: Option<Arc<RasterImage>>,
     x: i32,
@@ -271,6 +272,29 @@ impl DragDataStore {
         self.item_list.clear();
         self.clear_was_called = true;
     }
+
+    /// The contents of this drag data store in the form that is handed to the embedder
+    /// when a drag operation leaves the page.
+    pub(crate) fn to_drag_data(&self) -> DragData {
+        let mut drag_data = DragData::default();
+        for item in self.item_list.values() {
+            match item {
+                Kind::Text { data, type_ } => {
+                    drag_data.items.push((type_.to_string(), data.to_string()))
+                },
+                Kind::File { bytes, name, type_ } => drag_data.files.push(DataTransferFile {
+                    name: name.to_string(),
+                    type_: type_.clone(),
+                    bytes: bytes.clone(),
+                }),
+            }
+        }
+        if let Some(bitmap) = &self.bitmap {
+            drag_data.image = bitmap.image.clone();
+            drag_data.image_offset = Point2D::new(bitmap.x, bitmap.y);
+        }
+        drag_data
+    }
 }
 
 fn normalize_mime(mut format: DOMString) -> DOMString {
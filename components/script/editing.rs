@@ -0,0 +1,926 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Editing of content inside of editing hosts (elements with `contenteditable`), which
+//! implements caret movement, keyboard editing and the commands of `document.execCommand`.
+//!
+//! <https://w3c.github.io/editing/docs/execCommand/>
+
+use html5ever::{LocalName, QualName, local_name, ns};
+use keyboard_types::{Key, KeyState, Modifiers, NamedKey, ShortcutMatcher};
+
+use crate::dom::bindings::codegen::Bindings::CharacterDataBinding::CharacterData_Binding::CharacterDataMethods;
+use crate::dom::bindings::codegen::Bindings::DocumentBinding::DocumentMethods;
+use crate::dom::bindings::codegen::Bindings::NodeBinding::NodeMethods;
+use crate::dom::bindings::codegen::Bindings::RangeBinding::RangeMethods;
+use crate::dom::bindings::codegen::Bindings::SelectionBinding::SelectionMethods;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::str::DOMString;
+use crate::dom::characterdata::CharacterData;
+use crate::dom::document::Document;
+use crate::dom::element::{CustomElementCreationMode, Element, ElementCreator};
+use crate::dom::htmlelement::HTMLElement;
+use crate::dom::inputevent::{InputEvent, InputEventType};
+use crate::dom::keyboardevent::KeyboardEvent;
+use crate::dom::node::{Node, ShadowIncluding};
+use crate::dom::range::Range;
+use crate::dom::selection::Selection;
+use crate::dom::staticrange::StaticRange;
+use crate::dom::text::Text;
+use crate::script_runtime::CanGc;
+use crate::textinput::{CMD_OR_CONTROL, Direction, is_shortcut_chord};
+
+/// The editing commands supported by `execCommand` and friends.
+///
+/// <https://w3c.github.io/editing/docs/execCommand/#commands>
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum EditingCommand {
+    Bold,
+    Delete,
+    ForwardDelete,
+    InsertLineBreak,
+    InsertParagraph,
+    InsertText,
+    Italic,
+    SelectAll,
+}
+
+impl EditingCommand {
+    /// Look up a command by its (ASCII case-insensitive) command name.
+    pub(crate) fn from_command_id(command_id: &str) -> Option<EditingCommand> {
+        [
+            ("bold", EditingCommand::Bold),
+            ("delete", EditingCommand::Delete),
+            ("forwarddelete", EditingCommand::ForwardDelete),
+            ("insertlinebreak", EditingCommand::InsertLineBreak),
+            ("insertparagraph", EditingCommand::InsertParagraph),
+            ("inserttext", EditingCommand::InsertText),
+            ("italic", EditingCommand::Italic),
+            ("selectall", EditingCommand::SelectAll),
+        ]
+        .into_iter()
+        .find(|(name, _)| command_id.eq_ignore_ascii_case(name))
+        .map(|(_, command)| command)
+    }
+
+    /// The `inputType` of the `beforeinput` and `input` events fired for this command, if
+    /// it modifies content.
+    ///
+    /// <https://w3c.github.io/input-events/#interface-InputEvent-Attributes>
+    pub(crate) fn input_type(&self) -> Option<InputEventType> {
+        Some(match self {
+            EditingCommand::Bold => InputEventType::FormatBold,
+            EditingCommand::Delete => InputEventType::DeleteContentBackward,
+            EditingCommand::ForwardDelete => InputEventType::DeleteContentForward,
+            EditingCommand::InsertLineBreak => InputEventType::InsertLineBreak,
+            EditingCommand::InsertParagraph => InputEventType::InsertParagraph,
+            EditingCommand::InsertText => InputEventType::InsertText,
+            EditingCommand::Italic => InputEventType::FormatItalic,
+            EditingCommand::SelectAll => return None,
+        })
+    }
+
+    /// Whether elements with the given local name apply the inline style toggled by this
+    /// command.
+    fn is_style_element(&self, name: &LocalName) -> bool {
+        match self {
+            EditingCommand::Bold => matches!(*name, local_name!("b") | local_name!("strong")),
+            EditingCommand::Italic => matches!(*name, local_name!("i") | local_name!("em")),
+            _ => false,
+        }
+    }
+}
+
+/// Returns the editing host of the active range, which is the range of the document's
+/// selection, if the selection is inside of editable content.
+///
+/// <https://w3c.github.io/editing/docs/execCommand/#active-range>
+pub(crate) fn active_editing_host(
+    document: &Document,
+    can_gc: CanGc,
+) -> Option<DomRoot<HTMLElement>> {
+    let range = document.GetSelection(can_gc)?.GetRangeAt(0).ok()?;
+    let host = range.start_container().editing_host()?;
+    host.upcast::<Node>()
+        .is_inclusive_ancestor_of(&range.end_container())
+        .then_some(host)
+}
+
+/// <https://w3c.github.io/editing/docs/execCommand/#enabled>
+pub(crate) fn query_command_enabled(
+    document: &Document,
+    command: EditingCommand,
+    can_gc: CanGc,
+) -> bool {
+    // The selectAll command is always enabled, all other commands require an active
+    // range inside of an editing host.
+    command == EditingCommand::SelectAll || active_editing_host(document, can_gc).is_some()
+}
+
+/// <https://w3c.github.io/editing/docs/execCommand/#state>
+pub(crate) fn query_command_state(
+    document: &Document,
+    command: EditingCommand,
+    can_gc: CanGc,
+) -> bool {
+    let Some(host) = active_editing_host(document, can_gc) else {
+        return false;
+    };
+    styled_ancestor(document, &host, command, can_gc).is_some()
+}
+
+/// Run the given command. Returns false if the command was not enabled.
+///
+/// <https://w3c.github.io/editing/docs/execCommand/#execcommand()>
+pub(crate) fn execute_command(
+    document: &Document,
+    command: EditingCommand,
+    value: DOMString,
+    can_gc: CanGc,
+) -> bool {
+    if command == EditingCommand::SelectAll {
+        select_all(document, None, can_gc);
+        return true;
+    }
+    let Some(host) = active_editing_host(document, can_gc) else {
+        return false;
+    };
+    let host = host.upcast::<Node>();
+    match command {
+        EditingCommand::Bold | EditingCommand::Italic => {
+            toggle_inline_style(document, host, command, can_gc)
+        },
+        EditingCommand::Delete => delete(document, host, Direction::Backward, can_gc),
+        EditingCommand::ForwardDelete => delete(document, host, Direction::Forward, can_gc),
+        EditingCommand::InsertLineBreak => insert_line_break(document, host, can_gc),
+        EditingCommand::InsertParagraph => insert_paragraph(document, host, can_gc),
+        EditingCommand::InsertText => insert_text(document, host, &value, false, can_gc),
+        EditingCommand::SelectAll => unreachable!("Handled above"),
+    }
+
+    // Step 8: Fire an event named "input" at affected editing host.
+    if let Some(input_type) = command.input_type() {
+        let data = (command == EditingCommand::InsertText).then_some(value);
+        InputEvent::dispatch_input(host.upcast(), input_type, data, can_gc);
+    }
+    true
+}
+
+/// The ranges that running `command` would affect, as exposed to script through
+/// `InputEvent.getTargetRanges()`.
+///
+/// <https://w3c.github.io/input-events/#dom-inputevent-gettargetranges>
+fn target_ranges(
+    document: &Document,
+    editing_host: &Node,
+    command: EditingCommand,
+    can_gc: CanGc,
+) -> Vec<DomRoot<StaticRange>> {
+    let range = match command {
+        EditingCommand::Delete => {
+            deletion_range(document, editing_host, Direction::Backward, can_gc)
+        },
+        EditingCommand::ForwardDelete => {
+            deletion_range(document, editing_host, Direction::Forward, can_gc)
+        },
+        _ => editing_selection(document, editing_host, can_gc)
+            .and_then(|selection| selection.GetRangeAt(0).ok()),
+    };
+    range
+        .map(|range| {
+            StaticRange::new(
+                document,
+                &range.start_container(),
+                range.start_offset(),
+                &range.end_container(),
+                range.end_offset(),
+                can_gc,
+            )
+        })
+        .into_iter()
+        .collect()
+}
+
+/// The default action to take in response to a key press inside of an editing host.
+enum KeyAction {
+    /// Run an editing command.
+    Command(EditingCommand),
+    /// The caret was moved, which does not modify content.
+    MovedCaret,
+    /// The key press does not have an editing action.
+    Ignored,
+}
+
+/// Perform the default action of a `keydown` event targeting an editing host. Returns
+/// true if the event was handled.
+pub(crate) fn handle_keydown(
+    document: &Document,
+    editing_host: &Node,
+    event: &KeyboardEvent,
+    can_gc: CanGc,
+) -> bool {
+    let mut modifiers = event.modifiers();
+    let extend = modifiers.contains(Modifiers::SHIFT);
+    modifiers.remove(Modifiers::SHIFT);
+    let key = event.key();
+
+    // Shift+Enter inserts a line break rather than a new paragraph.
+    let enter_command = if extend {
+        EditingCommand::InsertLineBreak
+    } else {
+        EditingCommand::InsertParagraph
+    };
+
+    let action = ShortcutMatcher::new(KeyState::Down, key.clone(), modifiers)
+        .shortcut(CMD_OR_CONTROL, 'A', || {
+            KeyAction::Command(EditingCommand::SelectAll)
+        })
+        .shortcut(CMD_OR_CONTROL, 'B', || {
+            KeyAction::Command(EditingCommand::Bold)
+        })
+        .shortcut(CMD_OR_CONTROL, 'I', || {
+            KeyAction::Command(EditingCommand::Italic)
+        })
+        .shortcut(Modifiers::empty(), Key::Named(NamedKey::Backspace), || {
+            KeyAction::Command(EditingCommand::Delete)
+        })
+        .shortcut(Modifiers::empty(), Key::Named(NamedKey::Delete), || {
+            KeyAction::Command(EditingCommand::ForwardDelete)
+        })
+        .shortcut(Modifiers::empty(), Key::Named(NamedKey::Enter), || {
+            KeyAction::Command(enter_command)
+        })
+        .shortcut(Modifiers::empty(), Key::Named(NamedKey::ArrowLeft), || {
+            move_caret(document, editing_host, Direction::Backward, extend, can_gc);
+            KeyAction::MovedCaret
+        })
+        .shortcut(Modifiers::empty(), Key::Named(NamedKey::ArrowRight), || {
+            move_caret(document, editing_host, Direction::Forward, extend, can_gc);
+            KeyAction::MovedCaret
+        })
+        .shortcut(Modifiers::empty(), Key::Named(NamedKey::Home), || {
+            move_caret_to_limit(document, editing_host, Direction::Backward, extend, can_gc);
+            KeyAction::MovedCaret
+        })
+        .shortcut(Modifiers::empty(), Key::Named(NamedKey::End), || {
+            move_caret_to_limit(document, editing_host, Direction::Forward, extend, can_gc);
+            KeyAction::MovedCaret
+        })
+        .otherwise(|| match &key {
+            Key::Character(_) if !is_shortcut_chord(modifiers) => {
+                KeyAction::Command(EditingCommand::InsertText)
+            },
+            _ => KeyAction::Ignored,
+        })
+        .unwrap_or(KeyAction::Ignored);
+
+    match action {
+        KeyAction::Ignored => false,
+        KeyAction::MovedCaret => true,
+        KeyAction::Command(EditingCommand::SelectAll) => {
+            select_all(document, Some(editing_host), can_gc);
+            true
+        },
+        KeyAction::Command(command) => {
+            let value = match &key {
+                Key::Character(text) => DOMString::from(text.as_str()),
+                _ => DOMString::new(),
+            };
+
+            // User initiated edits give script a chance to cancel them before they happen.
+            if let Some(input_type) = command.input_type() {
+                let data = (command == EditingCommand::InsertText).then(|| value.clone());
+                let target_ranges = target_ranges(document, editing_host, command, can_gc);
+                if !InputEvent::dispatch_beforeinput(
+                    editing_host.upcast(),
+                    input_type,
+                    data,
+                    &target_ranges,
+                    can_gc,
+                ) {
+                    return true;
+                }
+            }
+            execute_command(document, command, value, can_gc)
+        },
+    }
+}
+
+/// Replace the current selection inside of `editing_host` with `text`. If there is no
+/// selection, the text is appended to the end of the editing host. When `select_inserted`
+/// is true the inserted text is left selected, otherwise the selection is collapsed to
+/// the end of the inserted text.
+pub(crate) fn insert_text(
+    document: &Document,
+    editing_host: &Node,
+    text: &str,
+    select_inserted: bool,
+    can_gc: CanGc,
+) {
+    let Some(selection) = editing_selection(document, editing_host, can_gc) else {
+        return;
+    };
+    let Ok(range) = selection.GetRangeAt(0) else {
+        return;
+    };
+    if range.DeleteContents().is_err() {
+        return;
+    }
+
+    let container = range.start_container();
+    let offset = range.start_offset();
+    let (text_node, start) = match container.downcast::<Text>() {
+        Some(text_node) => {
+            if text_node
+                .upcast::<CharacterData>()
+                .InsertData(offset, DOMString::from(text))
+                .is_err()
+            {
+                return;
+            }
+            (DomRoot::from_ref(text_node), offset)
+        },
+        None => {
+            let text_node = document.CreateTextNode(DOMString::from(text), can_gc);
+            if range.InsertNode(text_node.upcast(), can_gc).is_err() {
+                return;
+            }
+            (text_node, 0)
+        },
+    };
+
+    let end = start + text.encode_utf16().count() as u32;
+    let anchor = if select_inserted { start } else { end };
+    let text_node = text_node.upcast::<Node>();
+    let _ = selection.SetBaseAndExtent(text_node, anchor, text_node, end, can_gc);
+}
+
+/// The document's selection, if it is contained in `editing_host`. If the document has no
+/// selection, the selection is collapsed to the end of the editing host first.
+fn editing_selection(
+    document: &Document,
+    editing_host: &Node,
+    can_gc: CanGc,
+) -> Option<DomRoot<Selection>> {
+    let selection = document.GetSelection(can_gc)?;
+    if selection.RangeCount() == 0 {
+        selection
+            .Collapse(Some(editing_host), editing_host.len(), can_gc)
+            .ok()?;
+    }
+    let range = selection.GetRangeAt(0).ok()?;
+    if !editing_host.is_inclusive_ancestor_of(&range.start_container()) ||
+        !editing_host.is_inclusive_ancestor_of(&range.end_container())
+    {
+        return None;
+    }
+    Some(selection)
+}
+
+/// Select the contents of the given editing host, or of the active editing host if there
+/// is one, or of the document body otherwise.
+fn select_all(document: &Document, editing_host: Option<&Node>, can_gc: CanGc) {
+    let Some(selection) = document.GetSelection(can_gc) else {
+        return;
+    };
+    let root = match editing_host {
+        Some(host) => DomRoot::from_ref(host),
+        None => match active_editing_host(document, can_gc) {
+            Some(host) => DomRoot::upcast(host),
+            None => match document.GetBody() {
+                Some(body) => DomRoot::upcast(body),
+                None => return,
+            },
+        },
+    };
+    let _ = selection.SelectAllChildren(&root, can_gc);
+}
+
+/// The length in UTF-16 code units of the character before or after `offset` in `text`.
+fn adjacent_character_length(text: &str, offset: u32, direction: Direction) -> Option<u32> {
+    let mut position = 0;
+    for character in text.chars() {
+        let length = character.len_utf16() as u32;
+        match direction {
+            Direction::Forward if position == offset => return Some(length),
+            Direction::Backward if position + length == offset => return Some(length),
+            _ => {},
+        }
+        position += length;
+    }
+    None
+}
+
+/// The text nodes inside of `editing_host`, in tree order.
+fn text_nodes(editing_host: &Node) -> Vec<DomRoot<Text>> {
+    editing_host
+        .traverse_preorder(ShadowIncluding::No)
+        .filter_map(DomRoot::downcast::<Text>)
+        .collect()
+}
+
+/// Resolve a boundary point to a position in a text node, preferring the text node
+/// that precedes or follows the boundary point depending on `direction`.
+fn text_position(
+    editing_host: &Node,
+    node: &Node,
+    offset: u32,
+    direction: Direction,
+) -> Option<(DomRoot<Text>, u32)> {
+    if let Some(text) = node.downcast::<Text>() {
+        return Some((DomRoot::from_ref(text), offset));
+    }
+    let texts = text_nodes(editing_host);
+    let child = node.children().nth(offset as usize);
+    match direction {
+        // The last text node that comes before the boundary point.
+        Direction::Backward => texts
+            .into_iter()
+            .rev()
+            .find(|text| match &child {
+                Some(child) => text.upcast::<Node>().is_before(child),
+                None => {
+                    node.is_inclusive_ancestor_of(text.upcast()) ||
+                        text.upcast::<Node>().is_before(node)
+                },
+            })
+            .map(|text| {
+                let length = text.upcast::<Node>().len();
+                (text, length)
+            }),
+        // The first text node that comes after the boundary point.
+        Direction::Forward => texts
+            .into_iter()
+            .find(|text| match &child {
+                Some(child) => {
+                    child.is_inclusive_ancestor_of(text.upcast()) || child.is_before(text.upcast())
+                },
+                None => {
+                    node.is_before(text.upcast()) && !node.is_inclusive_ancestor_of(text.upcast())
+                },
+            })
+            .map(|text| (text, 0)),
+    }
+}
+
+/// Move the caret by one character, extending the selection instead if `extend` is true.
+/// A non-collapsed selection is collapsed in the direction of movement.
+fn move_caret(
+    document: &Document,
+    editing_host: &Node,
+    direction: Direction,
+    extend: bool,
+    can_gc: CanGc,
+) {
+    let Some(selection) = editing_selection(document, editing_host, can_gc) else {
+        return;
+    };
+    if !extend && !selection.IsCollapsed() {
+        let _ = match direction {
+            Direction::Backward => selection.CollapseToStart(can_gc),
+            Direction::Forward => selection.CollapseToEnd(can_gc),
+        };
+        return;
+    }
+    let Some(focus) = selection.GetFocusNode() else {
+        return;
+    };
+    let Some((text, offset)) =
+        text_position(editing_host, &focus, selection.FocusOffset(), direction)
+    else {
+        return;
+    };
+
+    let new_position = match adjacent_character_length(
+        &text.upcast::<CharacterData>().data(),
+        offset,
+        direction,
+    ) {
+        Some(length) => match direction {
+            Direction::Backward => Some((text.clone(), offset - length)),
+            Direction::Forward => Some((text.clone(), offset + length)),
+        },
+        // We are at the edge of the text node, continue in the adjacent one.
+        None => {
+            let texts = text_nodes(editing_host);
+            let index = texts.iter().position(|candidate| *candidate == text);
+            let adjacent = index.and_then(|index| match direction {
+                Direction::Backward => index.checked_sub(1).and_then(|index| texts.get(index)),
+                Direction::Forward => texts.get(index + 1),
+            });
+            adjacent.map(|adjacent| {
+                let data = adjacent.upcast::<CharacterData>().data();
+                match direction {
+                    Direction::Backward => {
+                        let length = adjacent.upcast::<Node>().len();
+                        let step = adjacent_character_length(&data, length, direction).unwrap_or(0);
+                        (adjacent.clone(), length - step)
+                    },
+                    Direction::Forward => {
+                        let step = adjacent_character_length(&data, 0, direction).unwrap_or(0);
+                        (adjacent.clone(), step)
+                    },
+                }
+            })
+        },
+    };
+
+    let Some((node, offset)) = new_position else {
+        return;
+    };
+    let node = node.upcast::<Node>();
+    let _ = if extend {
+        selection.Extend(node, offset, can_gc)
+    } else {
+        selection.Collapse(Some(node), offset, can_gc)
+    };
+}
+
+/// Move the caret to the start or end of the editing host.
+fn move_caret_to_limit(
+    document: &Document,
+    editing_host: &Node,
+    direction: Direction,
+    extend: bool,
+    can_gc: CanGc,
+) {
+    let Some(selection) = editing_selection(document, editing_host, can_gc) else {
+        return;
+    };
+    let offset = match direction {
+        Direction::Backward => 0,
+        Direction::Forward => editing_host.len(),
+    };
+    let _ = if extend {
+        selection.Extend(editing_host, offset, can_gc)
+    } else {
+        selection.Collapse(Some(editing_host), offset, can_gc)
+    };
+}
+
+/// The content that deleting in the given direction would remove: the selected content or,
+/// if the selection is collapsed, the character or line break before or after the caret.
+fn deletion_range(
+    document: &Document,
+    editing_host: &Node,
+    direction: Direction,
+    can_gc: CanGc,
+) -> Option<DomRoot<Range>> {
+    let selection = editing_selection(document, editing_host, can_gc)?;
+    let range = selection.GetRangeAt(0).ok()?;
+    if !range.collapsed() {
+        return Some(range);
+    }
+
+    let container = range.start_container();
+    let offset = range.start_offset();
+
+    // A line break directly adjacent to the caret is removed as a whole.
+    let adjacent_child = match direction {
+        Direction::Backward => offset
+            .checked_sub(1)
+            .and_then(|index| container.children().nth(index as usize)),
+        Direction::Forward => container.children().nth(offset as usize),
+    };
+    if let Some(child) = adjacent_child {
+        if child
+            .downcast::<Element>()
+            .is_some_and(|element| *element.local_name() == local_name!("br"))
+        {
+            let index = child.index();
+            return Some(Range::new(
+                document,
+                &container,
+                index,
+                &container,
+                index + 1,
+                can_gc,
+            ));
+        }
+    }
+
+    let (text, offset) = text_position(editing_host, &container, offset, direction)?;
+    let data = text.upcast::<CharacterData>().data().clone();
+    let (text, offset, length) = match adjacent_character_length(&data, offset, direction) {
+        Some(length) => (text, offset, length),
+        // Nothing to delete in this text node, continue in the adjacent one.
+        None => {
+            let texts = text_nodes(editing_host);
+            let index = texts.iter().position(|candidate| *candidate == text)?;
+            let adjacent = match direction {
+                Direction::Backward => index.checked_sub(1).and_then(|index| texts.get(index)),
+                Direction::Forward => texts.get(index + 1),
+            }?;
+            let data = adjacent.upcast::<CharacterData>().data().clone();
+            let offset = match direction {
+                Direction::Backward => adjacent.upcast::<Node>().len(),
+                Direction::Forward => 0,
+            };
+            let length = adjacent_character_length(&data, offset, direction)?;
+            (adjacent.clone(), offset, length)
+        },
+    };
+
+    let start = match direction {
+        Direction::Backward => offset - length,
+        Direction::Forward => offset,
+    };
+    let text = text.upcast::<Node>();
+    Some(Range::new(
+        document,
+        text,
+        start,
+        text,
+        start + length,
+        can_gc,
+    ))
+}
+
+/// Delete the content returned by [`deletion_range`] and collapse the selection to where
+/// it used to be.
+fn delete(document: &Document, editing_host: &Node, direction: Direction, can_gc: CanGc) {
+    let Some(selection) = editing_selection(document, editing_host, can_gc) else {
+        return;
+    };
+    let Some(range) = deletion_range(document, editing_host, direction, can_gc) else {
+        return;
+    };
+    if range.DeleteContents().is_ok() {
+        let _ = selection.Collapse(Some(&range.start_container()), range.start_offset(), can_gc);
+    }
+}
+
+/// Create an HTML element with the given local name in `document`.
+fn create_html_element(document: &Document, name: LocalName, can_gc: CanGc) -> DomRoot<Element> {
+    Element::create(
+        QualName::new(None, ns!(html), name),
+        None,
+        document,
+        None,
+        ElementCreator::ScriptCreated,
+        CustomElementCreationMode::Synchronous,
+        None,
+        can_gc,
+    )
+}
+
+/// <https://w3c.github.io/editing/docs/execCommand/#the-insertlinebreak-command>
+fn insert_line_break(document: &Document, editing_host: &Node, can_gc: CanGc) {
+    let Some(selection) = editing_selection(document, editing_host, can_gc) else {
+        return;
+    };
+    let Ok(range) = selection.GetRangeAt(0) else {
+        return;
+    };
+    if range.DeleteContents().is_err() {
+        return;
+    }
+
+    let br = create_html_element(document, local_name!("br"), can_gc);
+    if range.InsertNode(br.upcast(), can_gc).is_err() {
+        return;
+    }
+    let br = br.upcast::<Node>();
+
+    // A line break at the end of a block does not create a new line by itself, so add
+    // another one for the caret to be placed on.
+    if br.GetNextSibling().is_none() {
+        if let Some(parent) = br.GetParentNode() {
+            let extra_br = create_html_element(document, local_name!("br"), can_gc);
+            let _ = parent.AppendChild(extra_br.upcast(), can_gc);
+        }
+    }
+
+    if let Some(parent) = br.GetParentNode() {
+        let _ = selection.Collapse(Some(&parent), br.index() + 1, can_gc);
+    }
+}
+
+/// <https://w3c.github.io/editing/docs/execCommand/#the-insertparagraph-command>
+///
+/// This splits the closest block ancestor of the caret inside the editing host. If there
+/// is no such block, a line break is inserted instead.
+fn insert_paragraph(document: &Document, editing_host: &Node, can_gc: CanGc) {
+    let Some(selection) = editing_selection(document, editing_host, can_gc) else {
+        return;
+    };
+    let Ok(range) = selection.GetRangeAt(0) else {
+        return;
+    };
+    if range.DeleteContents().is_err() {
+        return;
+    }
+
+    let container = range.start_container();
+    let block = container
+        .inclusive_ancestors(ShadowIncluding::No)
+        .take_while(|node| &**node != editing_host)
+        .filter_map(DomRoot::downcast::<Element>)
+        .find(|element| {
+            matches!(
+                *element.local_name(),
+                local_name!("div") | local_name!("p") | local_name!("li")
+            )
+        });
+    let Some(block) = block else {
+        insert_line_break(document, editing_host, can_gc);
+        return;
+    };
+    let block = block.upcast::<Node>();
+    let Some(parent) = block.GetParentNode() else {
+        return;
+    };
+
+    // Move everything after the caret into a shallow clone of the block.
+    if range.SetEnd(block, block.len()).is_err() {
+        return;
+    }
+    let Ok(contents) = range.ExtractContents(can_gc) else {
+        return;
+    };
+    let Ok(new_block) = block.CloneNode(false, can_gc) else {
+        return;
+    };
+    let _ = new_block.AppendChild(contents.upcast(), can_gc);
+    if parent
+        .InsertBefore(&new_block, block.GetNextSibling().as_deref(), can_gc)
+        .is_err()
+    {
+        return;
+    }
+
+    // Empty blocks collapse to nothing, so give them a line break to keep their height.
+    for node in [block, &*new_block] {
+        if node.children_count() == 0 {
+            let br = create_html_element(document, local_name!("br"), can_gc);
+            let _ = node.AppendChild(br.upcast(), can_gc);
+        }
+    }
+
+    let _ = selection.Collapse(Some(&new_block), 0, can_gc);
+}
+
+/// The closest inclusive ancestor of the active range's start that applies the inline
+/// style of `command`, inside of `editing_host`.
+fn styled_ancestor(
+    document: &Document,
+    editing_host: &HTMLElement,
+    command: EditingCommand,
+    can_gc: CanGc,
+) -> Option<DomRoot<Element>> {
+    let range = document.GetSelection(can_gc)?.GetRangeAt(0).ok()?;
+    range
+        .start_container()
+        .inclusive_ancestors(ShadowIncluding::No)
+        .take_while(|node| &**node != editing_host.upcast::<Node>())
+        .filter_map(DomRoot::downcast::<Element>)
+        .find(|element| command.is_style_element(element.local_name()))
+}
+
+/// Toggle the inline style of a bold or italic command on the current selection. If the
+/// selection is already styled, the selected part of the styling element is taken out of it.
+/// Otherwise the selected content is wrapped in a new styling element.
+fn toggle_inline_style(
+    document: &Document,
+    editing_host: &Node,
+    command: EditingCommand,
+    can_gc: CanGc,
+) {
+    let Some(selection) = editing_selection(document, editing_host, can_gc) else {
+        return;
+    };
+    let Some(host) = editing_host.downcast::<HTMLElement>() else {
+        return;
+    };
+
+    if let Some(styled) = styled_ancestor(document, host, command, can_gc) {
+        remove_inline_style(document, &selection, &styled, can_gc);
+        return;
+    }
+
+    let Ok(range) = selection.GetRangeAt(0) else {
+        return;
+    };
+    if range.collapsed() {
+        return;
+    }
+    let Ok(contents) = range.ExtractContents(can_gc) else {
+        return;
+    };
+    let name = match command {
+        EditingCommand::Bold => local_name!("b"),
+        _ => local_name!("i"),
+    };
+    let wrapper = create_html_element(document, name, can_gc);
+    let wrapper = wrapper.upcast::<Node>();
+    if wrapper.AppendChild(contents.upcast(), can_gc).is_err() ||
+        range.InsertNode(wrapper, can_gc).is_err()
+    {
+        return;
+    }
+    let _ = selection.SelectAllChildren(wrapper, can_gc);
+}
+
+/// Take the part of the selection that is inside of `styled` out of it, by splitting `styled`
+/// at the boundaries of the selection. The parts before and after the selection stay styled.
+fn remove_inline_style(
+    document: &Document,
+    selection: &Selection,
+    styled: &Element,
+    can_gc: CanGc,
+) {
+    let Ok(range) = selection.GetRangeAt(0) else {
+        return;
+    };
+    if range.collapsed() {
+        return;
+    }
+    let styled = styled.upcast::<Node>();
+    let Some(parent) = styled.GetParentNode() else {
+        return;
+    };
+
+    // Only the part of the selection inside of the styling element is unstyled.
+    let (end_container, end_offset) = if styled.is_inclusive_ancestor_of(&range.end_container()) {
+        (range.end_container(), range.end_offset())
+    } else {
+        (DomRoot::from_ref(styled), styled.children_count())
+    };
+    let selected = Range::new(
+        document,
+        &range.start_container(),
+        range.start_offset(),
+        &end_container,
+        end_offset,
+        can_gc,
+    );
+    let Ok(selected_contents) = selected.ExtractContents(can_gc) else {
+        return;
+    };
+
+    // The extracted range collapsed where the selection was, so everything after it is the
+    // part that stays styled after the selection.
+    let after = Range::new(
+        document,
+        &selected.start_container(),
+        selected.start_offset(),
+        styled,
+        styled.children_count(),
+        can_gc,
+    );
+    let Ok(after_contents) = after.ExtractContents(can_gc) else {
+        return;
+    };
+
+    let reference = styled.GetNextSibling();
+    let first_selected = selected_contents.upcast::<Node>().GetFirstChild();
+    let last_selected = selected_contents.upcast::<Node>().GetLastChild();
+    if parent
+        .InsertBefore(selected_contents.upcast(), reference.as_deref(), can_gc)
+        .is_err()
+    {
+        return;
+    }
+    if has_content(after_contents.upcast()) {
+        let Ok(styled_after) = styled.CloneNode(false, can_gc) else {
+            return;
+        };
+        if styled_after
+            .AppendChild(after_contents.upcast(), can_gc)
+            .is_err() ||
+            parent
+                .InsertBefore(&styled_after, reference.as_deref(), can_gc)
+                .is_err()
+        {
+            return;
+        }
+    }
+    if !has_content(styled) {
+        styled.remove_self(can_gc);
+    }
+
+    if let (Some(first_selected), Some(last_selected)) = (first_selected, last_selected) {
+        let _ = selection.SetBaseAndExtent(
+            &parent,
+            first_selected.index(),
+            &parent,
+            last_selected.index() + 1,
+            can_gc,
+        );
+    }
+}
+
+/// Whether `node` has descendants that render something: text that is not empty, or elements
+/// without children such as images and line breaks. Splitting an element at the boundaries of
+/// a range can leave it with nothing but empty text and empty copies of its descendants.
+fn has_content(node: &Node) -> bool {
+    node.traverse_preorder(ShadowIncluding::No)
+        .skip(1)
+        .any(|descendant| match descendant.downcast::<CharacterData>() {
+            Some(text) if descendant.is::<Text>() => !text.data().is_empty(),
+            Some(_) => false,
+            None => descendant.is::<Element>() && descendant.children_count() == 0,
+        })
+}
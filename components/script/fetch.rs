@@ -7,6 +7,8 @@ use std::sync::{Arc, Mutex};
 
 use base::id::WebViewId;
 use ipc_channel::ipc;
+use js::jsval::UndefinedValue;
+use js::rust::HandleValue;
 use net_traits::policy_container::{PolicyContainer, RequestPolicyContainer};
 use net_traits::request::{
     CorsSettings, CredentialsMode, Destination, InsecureRequestsPolicy, Referrer,
@@ -19,6 +21,8 @@ use net_traits::{
 };
 use servo_url::ServoUrl;
 
+use crate::body::BodyMixin;
+use crate::dom::abortsignal::AbortAlgorithm;
 use crate::dom::bindings::codegen::Bindings::RequestBinding::{
     RequestInfo, RequestInit, RequestMethods,
 };
@@ -40,12 +44,102 @@ use crate::dom::response::Response;
 use crate::dom::serviceworkerglobalscope::ServiceWorkerGlobalScope;
 use crate::network_listener::{self, PreInvoke, ResourceTimingListener, submit_timing_data};
 use crate::realms::{InRealm, enter_realm};
-use crate::script_runtime::CanGc;
+use crate::script_runtime::{CanGc, JSContext};
 
 struct FetchContext {
     fetch_promise: Option<TrustedPromise>,
+    request_object: Trusted<Request>,
     response_object: Trusted<Response>,
     resource_timing: ResourceFetchTiming,
+    /// The `locallyAborted` flag of <https://fetch.spec.whatwg.org/#dom-global-fetch>.
+    locally_aborted: bool,
+}
+
+/// The abort steps that `fetch()` adds to its request object's signal,
+/// see step 11 of <https://fetch.spec.whatwg.org/#dom-global-fetch>.
+#[derive(Clone, JSTraceable, MallocSizeOf)]
+pub(crate) struct FetchAbortSteps {
+    #[ignore_malloc_size_of = "Arc"]
+    #[no_trace]
+    fetch_context: Arc<Mutex<FetchContext>>,
+    /// Identifies the ongoing fetch, standing in for the fetch controller.
+    #[no_trace]
+    request_id: RequestId,
+}
+
+impl FetchAbortSteps {
+    #[cfg_attr(crown, allow(crown::unrooted_must_root))]
+    pub(crate) fn abort(
+        &self,
+        cx: JSContext,
+        global: &GlobalScope,
+        reason: HandleValue,
+        can_gc: CanGc,
+    ) {
+        let mut fetch_context = self.fetch_context.lock().unwrap();
+
+        // Step 11.1. Set locallyAborted to true.
+        fetch_context.locally_aborted = true;
+
+        // Step 11.2. Assert: controller is non-null.
+        // Step 11.3. Abort controller with requestObject’s signal’s abort reason.
+        // Note: this closes the connection if the response is still being received.
+        cancel_async_fetch(vec![self.request_id]);
+
+        // Step 11.4. Abort the fetch() call with p, request, responseObject,
+        // and requestObject’s signal’s abort reason.
+        let Some(promise) = fetch_context.fetch_promise.take() else {
+            return;
+        };
+        let promise = promise.root();
+        abort_fetch_call(
+            cx,
+            global,
+            &promise,
+            &fetch_context.request_object.root(),
+            Some(&fetch_context.response_object.root()),
+            reason,
+            can_gc,
+        );
+        fetch_context.fetch_promise = Some(TrustedPromise::new(promise));
+    }
+}
+
+/// <https://fetch.spec.whatwg.org/#abort-fetch>
+fn abort_fetch_call(
+    cx: JSContext,
+    global: &GlobalScope,
+    promise: &Promise,
+    request: &Request,
+    response_object: Option<&Response>,
+    error: HandleValue,
+    can_gc: CanGc,
+) {
+    let _ac = enter_realm(promise);
+
+    // Step 1. Reject promise with error.
+    // Note: this is a no-op if the promise was already resolved.
+    promise.reject_native(&error, can_gc);
+
+    // Step 2. If request’s body is non-null and is readable, then cancel request’s body with error.
+    if let Some(body) = request.body() {
+        if body.is_readable() {
+            body.cancel(cx, global, error, can_gc);
+        }
+    }
+
+    // Step 3. If responseObject is null, then return.
+    let Some(response_object) = response_object else {
+        return;
+    };
+
+    // Step 4. Let response be responseObject’s response.
+    // Step 5. If response’s body is non-null and is readable, then error response’s body with error.
+    if let Some(body) = response_object.body() {
+        if body.is_readable() {
+            body.error(error, can_gc);
+        }
+    }
 }
 
 /// RAII fetch canceller object. By default initialized to not having a canceller
@@ -101,6 +195,8 @@ fn request_init_from_request(request: NetTraitsRequest) -> RequestBuilder {
         body: request.body.clone(),
         service_workers_mode: ServiceWorkersMode::All,
         destination: request.destination,
+        priority: request.priority,
+        internal_priority: request.internal_priority,
         synchronous: request.synchronous,
         mode: request.mode.clone(),
         cache_mode: request.cache_mode,
@@ -125,6 +221,7 @@ fn request_init_from_request(request: NetTraitsRequest) -> RequestBuilder {
         policy_container: request.policy_container,
         insecure_requests_policy: request.insecure_requests_policy,
         has_trustworthy_ancestor_origin: request.has_trustworthy_ancestor_origin,
+        ancestor_origins: request.ancestor_origins,
         https_state: request.https_state,
         response_tainting: request.response_tainting,
         crash: None,
@@ -151,24 +248,44 @@ pub(crate) fn Fetch(
 
     // Step 2. Let requestObject be the result of invoking the initial value of Request as constructor
     //         with input and init as arguments. If this throws an exception, reject p with it and return p.
-    let request = match Request::Constructor(global, None, can_gc, input, init) {
+    let request_object = match Request::Constructor(global, None, can_gc, input, init) {
         Err(e) => {
             response.error_stream(e.clone(), can_gc);
             promise.reject_error(e, can_gc);
             return promise;
         },
-        Ok(r) => {
-            // Step 3. Let request be requestObject’s request.
-            r.get_request()
-        },
+        Ok(r) => r,
     };
+    // Step 3. Let request be requestObject’s request.
+    let request = request_object.get_request();
     let timing_type = request.timing_type();
 
     let mut request_init = request_init_from_request(request);
     request_init.policy_container =
         RequestPolicyContainer::PolicyContainer(global.policy_container());
-
-    // TODO: Step 4. If requestObject’s signal is aborted, then: [..]
+    response.set_fetch_request_id(request_init.id);
+
+    // Step 4. If requestObject’s signal is aborted, then:
+    let signal = request_object.signal();
+    if signal.aborted() {
+        // Step 4.1. Abort the fetch() call with p, request, null,
+        // and requestObject’s signal’s abort reason.
+        let cx = GlobalScope::get_cx();
+        rooted!(in(*cx) let mut reason = UndefinedValue());
+        reason.set(signal.abort_reason());
+        abort_fetch_call(
+            cx,
+            global,
+            &promise,
+            &request_object,
+            None,
+            reason.handle(),
+            can_gc,
+        );
+
+        // Step 4.2. Return p.
+        return promise;
+    }
 
     // Step 5. Let globalObject be request’s client’s global object.
     // NOTE:   We already get the global object as an argument
@@ -179,16 +296,31 @@ pub(crate) fn Fetch(
         request_init.service_workers_mode = ServiceWorkersMode::None;
     }
 
-    // TODO: Steps 8-11, abortcontroller stuff
-
-    // Step 12. Set controller to the result of calling fetch given request and
-    //           processResponse given response being these steps: [..]
+    // Step 8. Let relevantRealm be this’s relevant realm.
+    // Step 9. Let locallyAborted be false.
+    // Step 10. Let controller be null.
+    // Note: locallyAborted is kept in the fetch context, and the request id is used to
+    // reach the ongoing fetch in place of its controller.
+    let request_id = request_init.id;
     let fetch_context = Arc::new(Mutex::new(FetchContext {
         fetch_promise: Some(TrustedPromise::new(promise.clone())),
+        request_object: Trusted::new(&*request_object),
         response_object: Trusted::new(&*response),
         resource_timing: ResourceFetchTiming::new(timing_type),
+        locally_aborted: false,
     }));
 
+    // Step 11. Add the following abort steps to requestObject’s signal:
+    // Note: steps are implemented in `FetchAbortSteps::abort`.
+    let cx = GlobalScope::get_cx();
+    rooted!(in(*cx) let abort_algorithm = AbortAlgorithm::Fetch(FetchAbortSteps {
+        fetch_context: fetch_context.clone(),
+        request_id,
+    }));
+    signal.add(&abort_algorithm);
+
+    // Step 12. Set controller to the result of calling fetch given request and
+    //           processResponse given response being these steps: [..]
     global.fetch(
         request_init,
         fetch_context,
@@ -216,6 +348,11 @@ impl FetchResponseListener for FetchContext {
         _: RequestId,
         fetch_metadata: Result<FetchMetadata, NetworkError>,
     ) {
+        // Step 12.1. If locallyAborted is true, then abort these steps.
+        if self.locally_aborted {
+            return;
+        }
+
         let promise = self
             .fetch_promise
             .take()
@@ -280,6 +417,9 @@ impl FetchResponseListener for FetchContext {
     }
 
     fn process_response_chunk(&mut self, _: RequestId, chunk: Vec<u8>) {
+        if self.locally_aborted {
+            return;
+        }
         let response = self.response_object.root();
         response.stream_chunk(chunk, CanGc::note());
     }
@@ -287,11 +427,21 @@ impl FetchResponseListener for FetchContext {
     fn process_response_eof(
         &mut self,
         _: RequestId,
-        _response: Result<ResourceFetchTiming, NetworkError>,
+        response: Result<ResourceFetchTiming, NetworkError>,
     ) {
-        let response = self.response_object.root();
-        let _ac = enter_realm(&*response);
-        response.finish(CanGc::note());
+        if self.locally_aborted {
+            return;
+        }
+        let response_object = self.response_object.root();
+        let _ac = enter_realm(&*response_object);
+        if response.is_err() {
+            response_object.error_stream(
+                Error::Type("Network error occurred".to_string()),
+                CanGc::note(),
+            );
+            return;
+        }
+        response_object.finish(CanGc::note());
         // TODO
         // ... trailerObject is not supported in Servo yet.
     }
@@ -349,7 +499,9 @@ pub(crate) fn load_whole_resource(
     csp_violations_processor: &dyn CspViolationsProcessor,
     can_gc: CanGc,
 ) -> Result<(Metadata, Vec<u8>), NetworkError> {
-    let request = request.https_state(global.get_https_state());
+    let request = request
+        .https_state(global.get_https_state())
+        .ancestor_origins(global.origin_and_ancestor_origins());
     let (action_sender, action_receiver) = ipc::channel().unwrap();
     let url = request.url.clone();
     core_resource_thread
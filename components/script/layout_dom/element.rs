@@ -543,9 +543,17 @@ impl<'dom> style::dom::TElement for ServoLayoutElement<'dom> {
 
     fn query_container_size(
         &self,
-        _display: &Display,
+        display: &Display,
     ) -> euclid::default::Size2D<Option<app_units::Au>> {
-        todo!();
+        // Elements that do not generate a box are never query containers.
+        if display.is_none() || display.is_contents() {
+            return euclid::default::Size2D::new(None, None);
+        }
+        let size = self
+            .as_node()
+            .layout_data()
+            .and_then(|layout_data| layout_data.query_container_size());
+        euclid::default::Size2D::new(size.map(|size| size.width), size.map(|size| size.height))
     }
 
     fn has_selector_flags(&self, flags: ElementSelectorFlags) -> bool {
@@ -5,6 +5,7 @@
 #![allow(unsafe_code)]
 
 use std::borrow::Cow;
+use std::ffi::c_void;
 use std::fmt;
 
 use base::id::{BrowsingContextId, PipelineId};
@@ -82,6 +83,12 @@ impl<'dom> ServoLayoutNode<'dom> {
         ServoLayoutNode::from_layout_js(node)
     }
 
+    /// Returns the [`TrustedNodeAddress`] of this node, which can be used to recreate it
+    /// with [`ServoLayoutNode::new`].
+    pub fn to_trusted_node_address(&self) -> TrustedNodeAddress {
+        TrustedNodeAddress(self.node.unsafe_get() as *const Node as *const c_void)
+    }
+
     pub(super) fn script_type_id(&self) -> NodeTypeId {
         self.node.type_id_for_layout()
     }
@@ -320,6 +327,17 @@ impl<'dom> ThreadSafeLayoutNode<'dom> for ServoThreadSafeLayoutNode<'dom> {
         parent_data.styles.primary().clone()
     }
 
+    fn parent_selected_style(&self) -> Arc<ComputedValues> {
+        let parent_element = self.node.traversal_parent().unwrap();
+        let parent_data = parent_element.borrow_data().unwrap();
+        parent_data
+            .styles
+            .pseudos
+            .get(&PseudoElement::Selection)
+            .unwrap_or(parent_data.styles.primary())
+            .clone()
+    }
+
     fn debug_id(self) -> usize {
         self.node.debug_id()
     }
@@ -29,6 +29,7 @@ pub(crate) mod clipboard_provider;
 pub(crate) mod conversions;
 mod devtools;
 pub(crate) mod document_loader;
+mod editing;
 #[macro_use]
 mod dom;
 mod canvas_context;
@@ -57,6 +58,7 @@ pub(crate) mod script_runtime;
 pub(crate) mod script_thread;
 pub(crate) mod security_manager;
 pub(crate) mod serviceworker_manager;
+mod speculation_rules;
 mod stylesheet_loader;
 mod stylesheet_set;
 mod task_manager;
@@ -71,6 +73,7 @@ mod window_named_properties;
 
 mod unminify;
 
+mod drag_and_drop;
 mod drag_data_store;
 mod links;
 mod xpath;
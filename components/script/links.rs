@@ -7,6 +7,7 @@
 use constellation_traits::{LoadData, LoadOrigin, NavigationHistoryBehavior};
 use html5ever::{local_name, ns};
 use malloc_size_of::malloc_size_of_is_0;
+use net_traits::ReferrerPolicy;
 use net_traits::request::Referrer;
 use style::str::HTML_SPACE_CHARACTERS;
 
@@ -383,7 +384,8 @@ pub(crate) fn follow_hyperlink(
     let source = document.browsing_context().unwrap();
     let (maybe_chosen, history_handling) = match target_attribute_value {
         Some(name) => {
-            let (maybe_chosen, new) = source.choose_browsing_context(name, noopener);
+            let (maybe_chosen, new) =
+                source.choose_browsing_context(name, noopener, Default::default());
             let history_handling = if new {
                 NavigationHistoryBehavior::Replace
             } else {
@@ -417,14 +419,18 @@ pub(crate) fn follow_hyperlink(
         };
 
         // Step 11: Let referrerPolicy be the current state of subject's referrerpolicy content attribute.
-        let referrer_policy = referrer_policy_for_element(subject);
+        let mut referrer_policy = referrer_policy_for_element(subject);
 
         // Step 12: If subject's link types includes the noreferrer keyword, then set
         //          referrerPolicy to "no-referrer".
+        //
+        // The referrer is that of subject's node document, which is the source of the
+        // navigation, not that of the navigated document.
         let referrer = if relations.contains(LinkRelations::NO_REFERRER) {
+            referrer_policy = ReferrerPolicy::NoReferrer;
             Referrer::NoReferrer
         } else {
-            target_window.as_global_scope().get_referrer()
+            window.as_global_scope().get_referrer()
         };
 
         // Step 13: Navigate targetNavigable to urlString using subject's node document,
@@ -432,7 +438,7 @@ pub(crate) fn follow_hyperlink(
         //          userInvolvement, and sourceElement set to subject.
         let pipeline_id = target_window.as_global_scope().pipeline_id();
         let secure = target_window.as_global_scope().is_secure_context();
-        let load_data = LoadData::new(
+        let mut load_data = LoadData::new(
             LoadOrigin::Script(document.origin().immutable().clone()),
             url,
             Some(pipeline_id),
@@ -442,6 +448,7 @@ pub(crate) fn follow_hyperlink(
             Some(document.insecure_requests_policy()),
             document.has_trustworthy_ancestor_origin(),
         );
+        load_data.source_snapshot_params = Some(document.snapshot_source_snapshot_params());
         let target = Trusted::new(target_window);
         let task = task!(navigate_follow_hyperlink: move || {
             debug!("following hyperlink to {}", load_data.url);
@@ -67,11 +67,16 @@ impl MixedMessage {
                 ScriptThreadMessage::GetTitle(id) => Some(*id),
                 ScriptThreadMessage::SetDocumentActivity(id, ..) => Some(*id),
                 ScriptThreadMessage::SetThrottled(id, ..) => Some(*id),
+                ScriptThreadMessage::SetFrozen(id, ..) => Some(*id),
+                ScriptThreadMessage::ActivatePrerenderedDocument(id) => Some(*id),
+                ScriptThreadMessage::DisownOpener(id) => Some(*id),
                 ScriptThreadMessage::SetThrottledInContainingIframe(id, ..) => Some(*id),
                 ScriptThreadMessage::NavigateIframe(id, ..) => Some(*id),
                 ScriptThreadMessage::PostMessage { target: id, .. } => Some(*id),
                 ScriptThreadMessage::UpdatePipelineId(_, _, _, id, _) => Some(*id),
+                ScriptThreadMessage::ReplacePendingPipelineId(id, ..) => Some(*id),
                 ScriptThreadMessage::UpdateHistoryState(id, ..) => Some(*id),
+                ScriptThreadMessage::UpdateNavigableHistory(id, ..) => Some(*id),
                 ScriptThreadMessage::RemoveHistoryStates(id, ..) => Some(*id),
                 ScriptThreadMessage::FocusIFrame(id, ..) => Some(*id),
                 ScriptThreadMessage::FocusDocument(id, ..) => Some(*id),
@@ -87,7 +92,7 @@ impl MixedMessage {
                 ScriptThreadMessage::DispatchStorageEvent(id, ..) => Some(*id),
                 ScriptThreadMessage::ReportCSSError(id, ..) => Some(*id),
                 ScriptThreadMessage::Reload(id, ..) => Some(*id),
-                ScriptThreadMessage::PaintMetric(id, ..) => Some(*id),
+                ScriptThreadMessage::FramePresented(id, ..) => Some(*id),
                 ScriptThreadMessage::ExitFullScreen(id, ..) => Some(*id),
                 ScriptThreadMessage::MediaSessionAction(..) => None,
                 #[cfg(feature = "webgpu")]
@@ -10,12 +10,12 @@ use std::cell::Cell;
 
 use base::cross_process_instant::CrossProcessInstant;
 use base::id::{BrowsingContextId, PipelineId, WebViewId};
-use constellation_traits::LoadData;
+use constellation_traits::{LoadData, LoadOrigin};
 use crossbeam_channel::Sender;
 use embedder_traits::{Theme, ViewportDetails};
 use http::header;
 use net_traits::request::{
-    CredentialsMode, InsecureRequestsPolicy, RedirectMode, RequestBuilder, RequestMode,
+    CacheMode, CredentialsMode, InsecureRequestsPolicy, RedirectMode, RequestBuilder, RequestMode,
 };
 use net_traits::response::ResponseInit;
 use net_traits::{
@@ -23,8 +23,12 @@ use net_traits::{
     Metadata, fetch_async, set_default_accept_language,
 };
 use script_traits::DocumentActivity;
-use servo_url::{MutableOrigin, ServoUrl};
+use servo_url::{ImmutableOrigin, MutableOrigin, ServoUrl};
 
+use crate::dom::csp::{
+    has_enforced_frame_ancestors_directive, is_navigation_response_blocked_by_frame_ancestors,
+    parse_csp_list_from_metadata,
+};
 use crate::fetch::FetchCanceller;
 use crate::messaging::MainThreadScriptMsg;
 
@@ -200,6 +204,14 @@ impl InProgressLoad {
     pub(crate) fn request_builder(&mut self) -> RequestBuilder {
         let id = self.pipeline_id;
         let webview_id = self.webview_id;
+
+        // The site for cookies of a nested navigation depends on the ancestors of the navigable,
+        // while that of a top-level navigation depends on the document that started it.
+        let ancestor_origins = match self.load_data.load_origin {
+            _ if self.parent_info.is_some() => self.load_data.ancestor_origins.clone(),
+            LoadOrigin::Script(ref origin) => vec![origin.clone()],
+            LoadOrigin::Constellation | LoadOrigin::WebDriver => vec![],
+        };
         let mut request_builder = RequestBuilder::new(
             Some(webview_id),
             self.load_data.url.clone(),
@@ -219,6 +231,7 @@ impl InProgressLoad {
                 .unwrap_or(InsecureRequestsPolicy::DoNotUpgrade),
         )
         .has_trustworthy_ancestor_origin(self.load_data.has_trustworthy_ancestor_origin)
+        .ancestor_origins(ancestor_origins)
         .headers(self.load_data.headers.clone())
         .body(self.load_data.data.clone())
         .redirect_mode(RedirectMode::Manual)
@@ -226,6 +239,11 @@ impl InProgressLoad {
         .crash(self.load_data.crash.clone());
         request_builder.url_list = self.url_list.clone();
 
+        // A prefetched response is served from the HTTP cache, where the prefetch left it.
+        if self.load_data.prefetched {
+            request_builder = request_builder.cache_mode(CacheMode::ForceCache);
+        }
+
         if !request_builder.headers.contains_key(header::ACCEPT) {
             request_builder
                 .headers
@@ -235,4 +253,85 @@ impl InProgressLoad {
 
         request_builder
     }
+
+    /// Whether the response to this load may not be rendered, because it is being loaded
+    /// into a child navigable and it does not allow being embedded in the ancestors of that
+    /// navigable. Returns the reason to display on the error page that replaces it.
+    /// <https://html.spec.whatwg.org/multipage/#process-a-navigate-response>
+    pub(crate) fn embedding_blocked_reason(&self, metadata: &Metadata) -> Option<String> {
+        if self.parent_info.is_none() {
+            return None;
+        }
+        let url = &metadata.final_url;
+        let csp_list = parse_csp_list_from_metadata(&metadata.headers);
+        if let Some(ref csp_list) = csp_list {
+            if is_navigation_response_blocked_by_frame_ancestors(
+                csp_list,
+                url,
+                &self.load_data.ancestor_origins,
+            ) {
+                return Some(format!(
+                    "Refused to display {url} in a frame because of its frame-ancestors directive"
+                ));
+            }
+
+            // An enforced frame-ancestors directive takes precedence over X-Frame-Options.
+            if has_enforced_frame_ancestors_directive(csp_list) {
+                return None;
+            }
+        }
+
+        let x_frame_options = get_x_frame_options(metadata);
+        if adheres_to_x_frame_options(&x_frame_options, &url.origin(), &self.load_data) {
+            return None;
+        }
+        Some(format!(
+            "Refused to display {url} in a frame because it set 'X-Frame-Options' to '{}'",
+            x_frame_options.join(", ")
+        ))
+    }
+}
+
+/// Get, decode, and split the `X-Frame-Options` header of a response, lowercasing its values.
+fn get_x_frame_options(metadata: &Metadata) -> Vec<String> {
+    let Some(ref headers) = metadata.headers else {
+        return vec![];
+    };
+    let mut values: Vec<String> = vec![];
+    for value in headers.get_all(header::X_FRAME_OPTIONS) {
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        for value in value.split(',') {
+            let value = value.trim().to_ascii_lowercase();
+            if !values.contains(&value) {
+                values.push(value);
+            }
+        }
+    }
+    values
+}
+
+/// <https://html.spec.whatwg.org/multipage/#check-a-navigation-response's-adherence-to-x-frame-options>
+fn adheres_to_x_frame_options(
+    x_frame_options: &[String],
+    destination_origin: &ImmutableOrigin,
+    load_data: &LoadData,
+) -> bool {
+    // Conflicting values are treated as if the header was set to "deny", and unknown ones as
+    // if the header was not set at all.
+    if x_frame_options.len() > 1 {
+        return !x_frame_options
+            .iter()
+            .any(|value| matches!(value.as_str(), "deny" | "allowall" | "sameorigin"));
+    }
+    match x_frame_options.first().map(String::as_str) {
+        Some("deny") => false,
+        // Every container document must be same origin with the destination.
+        Some("sameorigin") => load_data
+            .ancestor_origins
+            .iter()
+            .all(|origin| origin == destination_origin),
+        _ => true,
+    }
 }
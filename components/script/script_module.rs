@@ -13,7 +13,6 @@ use std::sync::{Arc, Mutex};
 use std::{mem, ptr};
 
 use encoding_rs::UTF_8;
-use headers::{HeaderMapExt, ReferrerPolicy as ReferrerPolicyHeader};
 use html5ever::local_name;
 use hyper_serde::Serde;
 use indexmap::{IndexMap, IndexSet};
@@ -38,6 +37,7 @@ use mime::Mime;
 use net_traits::http_status::HttpStatus;
 use net_traits::request::{
     CredentialsMode, Destination, ParserMetadata, Referrer, RequestBuilder, RequestId, RequestMode,
+    RequestPriority,
 };
 use net_traits::{
     FetchMetadata, FetchResponseListener, Metadata, NetworkError, ReferrerPolicy,
@@ -65,7 +65,9 @@ use crate::dom::csp::{GlobalCspReporting, Violation};
 use crate::dom::document::Document;
 use crate::dom::dynamicmoduleowner::{DynamicModuleId, DynamicModuleOwner};
 use crate::dom::element::Element;
+use crate::dom::eventtarget::EventTarget;
 use crate::dom::globalscope::GlobalScope;
+use crate::dom::htmllinkelement::HTMLLinkElement;
 use crate::dom::htmlscriptelement::{
     HTMLScriptElement, SCRIPT_JS_MIMES, ScriptId, ScriptOrigin, ScriptType,
 };
@@ -912,9 +914,10 @@ impl ModuleTree {
                 // Step 8.
 
                 let visited_urls = self.visited_urls.borrow().clone();
-                let options = options.descendant_fetch_options();
 
                 for url in urls_to_fetch {
+                    let options = options.descendant_fetch_options(&url, &global);
+
                     // https://html.spec.whatwg.org/multipage/#internal-module-script-graph-fetching-procedure
                     // Step 1.
                     assert!(self.visited_urls.borrow().contains(&url));
@@ -925,7 +928,7 @@ impl ModuleTree {
                         url,
                         visited_urls.clone(),
                         destination,
-                        options.clone(),
+                        options,
                         Some(parent_identity.clone()),
                         false,
                         None,
@@ -1028,13 +1031,14 @@ impl Callback for ModuleHandler {
 }
 
 /// The owner of the module
-/// It can be `worker` or `script` element
+/// It can be `worker`, `script` element or a `link` element for `modulepreload`
 #[derive(Clone)]
 pub(crate) enum ModuleOwner {
     #[allow(dead_code)]
     Worker(TrustedWorkerAddress),
     Window(Trusted<HTMLScriptElement>),
     DynamicModule(Trusted<DynamicModuleOwner>),
+    ModulePreload(Trusted<HTMLLinkElement>),
 }
 
 impl ModuleOwner {
@@ -1043,6 +1047,7 @@ impl ModuleOwner {
             ModuleOwner::Worker(worker) => (*worker.root().clone()).global(),
             ModuleOwner::Window(script) => (*script.root()).global(),
             ModuleOwner::DynamicModule(dynamic_module) => (*dynamic_module.root()).global(),
+            ModuleOwner::ModulePreload(link) => (*link.root()).global(),
         }
     }
 
@@ -1055,6 +1060,20 @@ impl ModuleOwner {
         match &self {
             ModuleOwner::Worker(_) => unimplemented!(),
             ModuleOwner::DynamicModule(_) => unimplemented!(),
+            ModuleOwner::ModulePreload(link) => {
+                // <https://html.spec.whatwg.org/multipage/#link-type-modulepreload>
+                // Step 14. If result is null, fire an event named error at el, and return.
+                // Otherwise, fire an event named load at el.
+                let module_tree = module_identity.get_module_tree(&self.global());
+                let event = if module_tree.get_network_error().borrow().is_some() {
+                    atom!("error")
+                } else {
+                    atom!("load")
+                };
+                link.root()
+                    .upcast::<EventTarget>()
+                    .fire_event(event, can_gc);
+            },
             ModuleOwner::Window(script) => {
                 let global = self.global();
 
@@ -1214,6 +1233,11 @@ impl FetchResponseListener for ModuleContext {
     fn process_request_eof(&mut self, _: RequestId) {}
 
     fn process_response(&mut self, _: RequestId, metadata: Result<FetchMetadata, NetworkError>) {
+        if let Err(error) = &metadata {
+            self.owner
+                .global()
+                .report_subresource_integrity_failure(&self.url, error);
+        }
         self.metadata = metadata.ok().map(|meta| match meta {
             FetchMetadata::Unfiltered(m) => m,
             FetchMetadata::Filtered { unsafe_, .. } => unsafe_,
@@ -1290,10 +1314,8 @@ impl FetchResponseListener for ModuleContext {
 
             // Step 13.4: Let referrerPolicy be the result of parsing the `Referrer-Policy` header
             // given response.
-            let referrer_policy = meta
-                .headers
-                .and_then(|headers| headers.typed_get::<ReferrerPolicyHeader>())
-                .into();
+            let referrer_policy =
+                ReferrerPolicy::parse_header_for_response(meta.headers.as_deref());
 
             // Step 13.5: If referrerPolicy is not the empty string, set options's referrer policy
             // to referrerPolicy.
@@ -1468,6 +1490,8 @@ pub(crate) struct ScriptFetchOptions {
     pub(crate) parser_metadata: ParserMetadata,
     #[no_trace]
     pub(crate) referrer_policy: ReferrerPolicy,
+    #[no_trace]
+    pub(crate) fetch_priority: RequestPriority,
 }
 
 impl ScriptFetchOptions {
@@ -1480,18 +1504,23 @@ impl ScriptFetchOptions {
             parser_metadata: ParserMetadata::NotParserInserted,
             credentials_mode: CredentialsMode::CredentialsSameOrigin,
             referrer_policy: ReferrerPolicy::EmptyString,
+            fetch_priority: RequestPriority::Auto,
         }
     }
 
     /// <https://html.spec.whatwg.org/multipage/#descendant-script-fetch-options>
-    fn descendant_fetch_options(&self) -> ScriptFetchOptions {
+    fn descendant_fetch_options(&self, url: &ServoUrl, global: &GlobalScope) -> ScriptFetchOptions {
+        // Step 2. Let integrity be the result of resolving a module integrity metadata with url and
+        // settingsObject.
+        let integrity_metadata = resolve_a_module_integrity_metadata(url, global);
         Self {
             referrer: self.referrer.clone(),
-            integrity_metadata: String::new(),
+            integrity_metadata,
             cryptographic_nonce: self.cryptographic_nonce.clone(),
             credentials_mode: self.credentials_mode,
             parser_metadata: self.parser_metadata,
             referrer_policy: self.referrer_policy,
+            fetch_priority: RequestPriority::Auto,
         }
     }
 }
@@ -1521,18 +1550,19 @@ fn fetch_an_import_module_script_graph(
         let jsstr = std::ptr::NonNull::new(GetModuleRequestSpecifier(*cx, module_request)).unwrap();
         DOMString::from_string(jsstr_to_string(*cx, jsstr))
     };
-    let mut options = ScriptFetchOptions::default_classic_script(global);
     let module_data = unsafe { module_script_from_reference_private(&reference_private) };
-    if let Some(data) = module_data {
-        options = data.options.descendant_fetch_options();
-    }
     let url = ModuleTree::resolve_module_specifier(global, module_data, specifier, can_gc);
 
     // Step 2.
-    if url.is_err() {
+    let Ok(url) = url else {
         let specifier_error = gen_type_error(global, "Wrong module specifier".to_owned(), can_gc);
         return Err(specifier_error);
-    }
+    };
+
+    let options = match module_data {
+        Some(data) => data.options.descendant_fetch_options(&url, global),
+        None => ScriptFetchOptions::default_classic_script(global),
+    };
 
     let dynamic_module_id = DynamicModuleId(Uuid::new_v4());
 
@@ -1558,8 +1588,6 @@ fn fetch_an_import_module_script_graph(
         .referencing_private
         .set(reference_private.get());
 
-    let url = url.unwrap();
-
     let mut visited_urls = HashSet::new();
     visited_urls.insert(url.clone());
 
@@ -1834,6 +1862,7 @@ fn fetch_single_module_script(
     let document: Option<DomRoot<Document>> = match &owner {
         ModuleOwner::Worker(_) | ModuleOwner::DynamicModule(_) => None,
         ModuleOwner::Window(script) => Some(script.root().owner_document()),
+        ModuleOwner::ModulePreload(link) => Some(link.root().owner_document()),
     };
     let webview_id = document.as_ref().map(|document| document.webview_id());
 
@@ -1845,9 +1874,11 @@ fn fetch_single_module_script(
         .integrity_metadata(options.integrity_metadata.clone())
         .credentials_mode(options.credentials_mode)
         .referrer_policy(options.referrer_policy)
+        .priority(options.fetch_priority)
         .mode(mode)
         .insecure_requests_policy(global.insecure_requests_policy())
         .has_trustworthy_ancestor_origin(global.has_trustworthy_ancestor_origin())
+        .ancestor_origins(global.origin_and_ancestor_origins())
         .policy_container(global.policy_container().to_owned())
         .cryptographic_nonce_metadata(options.cryptographic_nonce.clone());
 
@@ -1986,6 +2017,16 @@ pub(crate) struct ImportMap {
     integrity: ModuleIntegrityMap,
 }
 
+/// <https://html.spec.whatwg.org/multipage/#resolving-a-module-integrity-metadata>
+pub(crate) fn resolve_a_module_integrity_metadata(url: &ServoUrl, global: &GlobalScope) -> String {
+    // Step 1. Let map be settingsObject's global object's import map.
+    let import_map = global.import_map();
+
+    // Step 2. If map's integrity[url] does not exist, then return the empty string.
+    // Step 3. Return map's integrity[url].
+    import_map.integrity.get(url).cloned().unwrap_or_default()
+}
+
 /// <https://html.spec.whatwg.org/multipage/#register-an-import-map>
 pub(crate) fn register_import_map(
     global: &GlobalScope,
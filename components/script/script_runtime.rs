@@ -45,8 +45,8 @@ use js::panic::wrap_panic;
 pub(crate) use js::rust::ThreadSafeJSContext;
 use js::rust::wrappers::{GetPromiseIsHandled, JS_GetPromiseResult};
 use js::rust::{
-    Handle, HandleObject as RustHandleObject, IntoHandle, JSEngine, JSEngineHandle, ParentRuntime,
-    Runtime as RustRuntime,
+    Handle, HandleObject as RustHandleObject, HandleValue as RustHandleValue, IntoHandle, JSEngine,
+    JSEngineHandle, ParentRuntime, Runtime as RustRuntime,
 };
 use malloc_size_of::MallocSizeOfOps;
 use malloc_size_of_derive::MallocSizeOf;
@@ -71,6 +71,7 @@ use crate::dom::bindings::refcounted::{
 };
 use crate::dom::bindings::reflector::{DomGlobal, DomObject};
 use crate::dom::bindings::root::trace_roots;
+use crate::dom::bindings::str::DOMString;
 use crate::dom::bindings::utils::DOM_CALLBACKS;
 use crate::dom::bindings::{principals, settings_stack};
 use crate::dom::csp::CspReporting;
@@ -80,6 +81,7 @@ use crate::dom::globalscope::GlobalScope;
 use crate::dom::promise::Promise;
 use crate::dom::promiserejectionevent::PromiseRejectionEvent;
 use crate::dom::response::Response;
+use crate::dom::trustedscript::TrustedScript;
 use crate::microtask::{EnqueuedPromiseCallback, Microtask, MicrotaskQueue};
 use crate::realms::{AlreadyInRealm, InRealm, enter_realm};
 use crate::script_module::EnsureModuleHooksInitialized;
@@ -472,12 +474,12 @@ unsafe extern "C" fn promise_rejection_tracker(
 unsafe extern "C" fn content_security_policy_allows(
     cx: *mut RawJSContext,
     runtime_code: RuntimeCode,
-    sample: HandleString,
-    _compilation_type: CompilationType,
+    code_string: HandleString,
+    compilation_type: CompilationType,
     _parameter_strings: u8, //FIXME in bindings generation
-    _body_string: HandleString,
+    body_string: HandleString,
     _parameter_args: u8, //FIXME in bindings generation
-    _body_arg: HandleValue,
+    body_arg: HandleValue,
     can_compile_strings: *mut bool,
 ) -> bool {
     let mut allowed = false;
@@ -489,9 +491,19 @@ unsafe extern "C" fn content_security_policy_allows(
 
         allowed = match runtime_code {
             RuntimeCode::JS => {
-                let source = std::ptr::NonNull::new(*sample)
+                let source = std::ptr::NonNull::new(*code_string)
                     .map_or_else(String::new, |jsstr| jsstr_to_string(*cx, jsstr));
-                global
+                let body = std::ptr::NonNull::new(*body_string)
+                    .map_or_else(String::new, |jsstr| jsstr_to_string(*cx, jsstr));
+                TrustedScript::can_compile_string_with_trusted_type(
+                    cx,
+                    global,
+                    DOMString::from(source.clone()),
+                    compilation_type,
+                    DOMString::from(body),
+                    RustHandleValue::from_raw(body_arg),
+                    CanGc::note(),
+                ) && global
                     .get_csp_list()
                     .is_js_evaluation_allowed(global, &source)
             },
@@ -767,7 +779,6 @@ impl Runtime {
         // TODO: handle js.asyncstack.enabled (needs new Spidermonkey)
         // TODO: handle js.throw_on_debugee_would_run (needs new Spidermonkey)
         // TODO: handle js.dump_stack_on_debugee_would_run (needs new Spidermonkey)
-        // TODO: handle js.shared_memory.enabled
         JS_SetGCParameter(
             cx,
             JSGCParamKey::JSGC_MAX_BYTES,
@@ -919,6 +930,18 @@ unsafe extern "C" fn get_size(obj: *mut JSObject) -> usize {
     }
 }
 
+/// Measure the memory usage of the most-derived DOM struct of the given DOM object.
+#[allow(unsafe_code)]
+pub(crate) fn dom_object_size_of<T: DomObject>(object: &T, ops: &mut MallocSizeOfOps) -> usize {
+    let obj = object.reflector().get_jsobject().get();
+    unsafe {
+        match get_dom_class(obj) {
+            Ok(class) => (class.malloc_size_of)(ops, private_from_object(obj) as *const c_void),
+            Err(()) => 0,
+        }
+    }
+}
+
 thread_local!(static GC_CYCLE_START: Cell<Option<Instant>> = const { Cell::new(None) });
 thread_local!(static GC_SLICE_START: Cell<Option<Instant>> = const { Cell::new(None) });
 
@@ -1207,7 +1230,7 @@ unsafe extern "C" fn consume_stream(
             );
             return false;
         }
-        unwrapped_source.set_stream_consumer(Some(StreamConsumer(_consumer)));
+        unwrapped_source.set_stream_consumer(StreamConsumer(_consumer), CanGc::note());
     } else {
         //Step 3 Upon rejection of source, return with reason.
         throw_dom_exception(
@@ -32,14 +32,16 @@ use background_hang_monitor_api::{
     BackgroundHangMonitor, BackgroundHangMonitorExitSignal, HangAnnotation, MonitoredComponentId,
     MonitoredComponentType,
 };
+use base::Epoch;
 use base::cross_process_instant::CrossProcessInstant;
 use base::id::{BrowsingContextId, HistoryStateId, PipelineId, PipelineNamespace, WebViewId};
 use canvas_traits::webgl::WebGLPipeline;
 use chrono::{DateTime, Local};
 use compositing_traits::{CompositorMsg, CrossProcessCompositorApi, PipelineExitSource};
 use constellation_traits::{
-    JsEvalResult, LoadData, LoadOrigin, NavigationHistoryBehavior, ScriptToConstellationChan,
-    ScriptToConstellationMessage, StructuredSerializedData, WindowSizeType,
+    JsEvalResult, LoadData, LoadOrigin, NavigableHistoryEntry, NavigationHistoryBehavior,
+    ScriptToConstellationChan, ScriptToConstellationMessage, StructuredSerializedData,
+    WindowSizeType,
 };
 use crossbeam_channel::unbounded;
 use data_url::mime::Mime;
@@ -56,8 +58,9 @@ use embedder_traits::{
 use euclid::Point2D;
 use euclid::default::Rect;
 use fonts::{FontContext, SystemFontServiceProxy};
-use headers::{HeaderMapExt, LastModified, ReferrerPolicy as ReferrerPolicyHeader};
+use headers::{HeaderMapExt, LastModified};
 use html5ever::{local_name, ns};
+use http::Method;
 use http::header::REFRESH;
 use hyper_serde::Serde;
 use ipc_channel::ipc;
@@ -74,20 +77,24 @@ use layout_api::{
 use media::WindowGLContext;
 use metrics::MAX_TASK_NS;
 use net_traits::image_cache::{ImageCache, ImageCacheResponseMessage};
+use net_traits::permissions_policy::PermissionsPolicy;
+use net_traits::policy_container::{EmbedderPolicy, OpenerPolicyValue};
+use net_traits::pub_domains::reg_site;
 use net_traits::request::{Referrer, RequestId};
 use net_traits::response::ResponseInit;
 use net_traits::storage_thread::StorageType;
 use net_traits::{
-    FetchMetadata, FetchResponseListener, FetchResponseMsg, Metadata, NetworkError,
+    FetchMetadata, FetchResponseListener, FetchResponseMsg, Metadata, NetworkError, ReferrerPolicy,
     ResourceFetchTiming, ResourceThreads, ResourceTimingType,
 };
 use percent_encoding::percent_decode;
-use profile_traits::mem::{ProcessReports, ReportsChan, perform_memory_report};
+use profile_traits::mem::{ProcessReports, Report, ReportKind, ReportsChan, perform_memory_report};
+use profile_traits::path;
 use profile_traits::time::ProfilerCategory;
 use profile_traits::time_profile;
 use script_traits::{
     ConstellationInputEvent, DiscardBrowsingContext, DocumentActivity, InitialScriptState,
-    NewLayoutInfo, Painter, ProgressiveWebMetricType, ScriptThreadMessage, UpdatePipelineIdReason,
+    NewLayoutInfo, Painter, ScriptThreadMessage, UpdatePipelineIdReason,
 };
 use servo_config::opts;
 use servo_url::{ImmutableOrigin, MutableOrigin, ServoUrl};
@@ -121,7 +128,7 @@ use crate::dom::bindings::root::{
 use crate::dom::bindings::settings_stack::AutoEntryScript;
 use crate::dom::bindings::str::DOMString;
 use crate::dom::bindings::trace::{HashMapTracedValues, JSTraceable};
-use crate::dom::csp::{CspReporting, GlobalCspReporting, Violation};
+use crate::dom::csp::{CspReporting, GlobalCspReporting, NavigationCheckType, Violation};
 use crate::dom::customelementregistry::{
     CallbackReaction, CustomElementDefinition, CustomElementReactionStack,
 };
@@ -133,9 +140,15 @@ use crate::dom::globalscope::GlobalScope;
 use crate::dom::htmlanchorelement::HTMLAnchorElement;
 use crate::dom::htmliframeelement::HTMLIFrameElement;
 use crate::dom::htmlslotelement::HTMLSlotElement;
+use crate::dom::layoutshift::is_excluding_input;
 use crate::dom::mutationobserver::MutationObserver;
 use crate::dom::node::{Node, NodeTraits, ShadowIncluding};
+use crate::dom::performancelonganimationframetiming::{
+    FrameTimingInfo, PerformanceLongAnimationFrameTiming,
+};
+use crate::dom::performancelongtasktiming::PerformanceLongTaskTiming;
 use crate::dom::servoparser::{ParserContext, ServoParser};
+use crate::dom::taskattributiontiming::TaskAttributionTiming;
 use crate::dom::types::DebuggerGlobalScope;
 #[cfg(feature = "webgpu")]
 use crate::dom::webgpu::identityhub::IdentityHub;
@@ -155,7 +168,7 @@ use crate::realms::enter_realm;
 use crate::script_module::ScriptFetchOptions;
 use crate::script_runtime::{
     CanGc, IntroductionType, JSContext, JSContextHelper, Runtime, ScriptThreadEventCategory,
-    ThreadSafeJSContext,
+    ThreadSafeJSContext, dom_object_size_of,
 };
 use crate::task_queue::TaskQueue;
 use crate::task_source::{SendableTaskSource, TaskSourceName};
@@ -199,6 +212,18 @@ unsafe_no_jsmanaged_fields!(TaskQueue<MainThreadScriptMsg>);
 
 type NodeIdSet = HashSet<String>;
 
+/// The maximum length of an idle period, see
+/// <https://w3c.github.io/requestidlecallback/#why50>.
+const MAXIMUM_IDLE_PERIOD_DURATION: Duration = Duration::from_millis(50);
+
+/// The expected interval between renderer-driven rendering opportunities, at 60Hz.
+const FRAME_INTERVAL: Duration = Duration::from_micros(16_667);
+
+/// The duration above which a task or an animation frame is considered long, see
+/// <https://w3c.github.io/longtasks/#long-task> and
+/// <https://w3c.github.io/long-animation-frames/#long-animation-frame>.
+const LONG_TASK_THRESHOLD: time::Duration = time::Duration::milliseconds(50);
+
 #[derive(JSTraceable)]
 // ScriptThread instances are rooted on creation, so this is okay
 #[cfg_attr(crown, allow(crown::unrooted_must_root))]
@@ -347,6 +372,21 @@ pub struct ScriptThread {
     #[no_trace]
     scheduled_update_the_rendering: RefCell<Option<TimerId>>,
 
+    /// The end of the current idle period, before which no new idle period is started.
+    /// <https://w3c.github.io/requestidlecallback/#dfn-idle-period>
+    idle_period_deadline: Cell<Option<Instant>>,
+
+    /// The [`TimerId`] of a timer that wakes up the ScriptThread once the current idle
+    /// period has ended, so that the next one can start.
+    #[no_trace]
+    scheduled_idle_period: RefCell<Option<TimerId>>,
+
+    /// The timing of the animation frame currently being run, which starts with the first
+    /// task after the previous frame ended and ends after the next rendering update.
+    /// <https://w3c.github.io/long-animation-frames/#current-frame-timing-info>
+    #[no_trace]
+    current_frame_timing: Cell<Option<FrameTimingInfo>>,
+
     /// Whether an animation tick or ScriptThread-triggered rendering update is pending. This might
     /// either be because the Servo renderer is managing animations and the [`ScriptThread`] has
     /// received a [`ScriptThreadMessage::TickAllAnimations`] message, because the [`ScriptThread`]
@@ -638,7 +678,7 @@ impl ScriptThread {
                         let global = &trusted_global.root();
                         // Step 5: If the result of should navigation request of type be blocked by
                         // Content Security Policy? given request and cspNavigationType is "Blocked", then return. [CSP]
-                        if global.get_csp_list().should_navigation_request_be_blocked(global, &load_data, None) {
+                        if global.get_csp_list().should_navigation_request_be_blocked(global, &load_data, None, NavigationCheckType::Other) {
                             return;
                         }
                         if ScriptThread::check_load_origin(&load_data.load_origin, &window.get_url().origin()) {
@@ -710,7 +750,8 @@ impl ScriptThread {
                 .borrow()
                 .iter()
                 .filter_map(|(id, document)| {
-                    if document.is_fully_active() {
+                    // The tasks of frozen documents are held back until they are resumed.
+                    if document.is_fully_active() && !document.is_frozen() {
                         Some(id)
                     } else {
                         None
@@ -1006,6 +1047,9 @@ impl ScriptThread {
             layout_factory,
             relative_mouse_down_point: Cell::new(Point2D::zero()),
             scheduled_update_the_rendering: Default::default(),
+            idle_period_deadline: Default::default(),
+            current_frame_timing: Default::default(),
+            scheduled_idle_period: Default::default(),
             needs_rendering_update: Arc::new(AtomicBool::new(false)),
             debugger_global: debugger_global.as_traced(),
         }
@@ -1125,6 +1169,9 @@ impl ScriptThread {
         let _realm = enter_realm(document.window());
         for event in document.take_pending_input_events().into_iter() {
             document.update_active_keyboard_modifiers(event.active_keyboard_modifiers);
+            if is_excluding_input(&event.event) {
+                window.layout_shift().note_excluding_input();
+            }
 
             match event.event.clone() {
                 InputEvent::MouseButton(mouse_button_event) => {
@@ -1180,6 +1227,9 @@ impl ScriptThread {
                 InputEvent::Scroll(scroll_event) => {
                     document.handle_embedder_scroll_event(scroll_event);
                 },
+                InputEvent::DragDrop(drag_drop_event) => {
+                    document.handle_drag_drop_event(drag_drop_event, &event, can_gc);
+                },
             }
 
             self.notify_webdriver_input_event_completed(pipeline_id, event.event, &document);
@@ -1252,6 +1302,9 @@ impl ScriptThread {
             return false;
         }
 
+        let render_start = CrossProcessInstant::now();
+        let mut style_and_layout_starts = HashMap::new();
+
         // TODO: The specification says to filter out non-renderable documents,
         // as well as those for which a rendering update would be unnecessary,
         // but this isn't happening here.
@@ -1288,7 +1341,7 @@ impl ScriptThread {
                 .find_document(*pipeline_id)
                 .expect("Got pipeline for Document not managed by this ScriptThread.");
 
-            if !document.is_fully_active() {
+            if !document.is_fully_active() || document.is_frozen() {
                 continue;
             }
 
@@ -1332,7 +1385,12 @@ impl ScriptThread {
             // > 14. For each doc of docs, run the animation frame callbacks for doc, passing
             // > in the relative high resolution time given frameTimestamp and doc's
             // > relevant global object as the timestamp.
-            document.run_the_animation_frame_callbacks(can_gc);
+            //
+            // Throttled documents are not displayed, so their animation frame callbacks are
+            // not run until they are displayed again.
+            if !document.window().throttled() {
+                document.run_the_animation_frame_callbacks(can_gc);
+            }
 
             // Run the resize observer steps.
             let _realm = enter_realm(&*document);
@@ -1350,8 +1408,9 @@ impl ScriptThread {
             // TODO(#31870): Implement step 17: if the focused area of doc is not a focusable area,
             // then run the focusing steps for document's viewport.
 
-            // TODO: Perform pending transition operations from
-            // https://drafts.csswg.org/css-view-transitions/#perform-pending-transition-operations.
+            // > 18. For each doc of docs, perform pending transition operations for doc.
+            // > [CSSVIEWTRANSITIONS]
+            document.perform_pending_transition_operations(can_gc);
 
             // > 19. For each doc of docs, run the update intersection observations steps for doc,
             // > passing in the relative high resolution time given now and
@@ -1363,10 +1422,23 @@ impl ScriptThread {
 
             // > Step 22: For each doc of docs, update the rendering or user interface of
             // > doc and its node navigable to reflect the current state.
-            built_any_display_lists = document
-                .update_the_rendering()
-                .contains(ReflowPhasesRun::BuiltDisplayList) ||
-                built_any_display_lists;
+            //
+            // While a view transition waits for its update callback, the rendering of the
+            // document is suppressed so that the old state stays on screen.
+            // <https://drafts.csswg.org/css-view-transitions-1/#document-rendering-suppression-for-view-transitions>
+            style_and_layout_starts.insert(*pipeline_id, CrossProcessInstant::now());
+            let built_display_list = !document.rendering_is_suppressed_for_view_transitions() &&
+                document
+                    .update_the_rendering()
+                    .contains(ReflowPhasesRun::BuiltDisplayList);
+            built_any_display_lists = built_display_list || built_any_display_lists;
+
+            // Dispatch the pending Event Timing entries of doc, once the frame reflecting the
+            // events is presented if this update produced a new display list.
+            // <https://w3c.github.io/event-timing/#dispatch-pending-event-timing-entries>
+            document
+                .window()
+                .dispatch_pending_event_timing_entries(built_display_list, can_gc);
 
             // TODO: Process top layer removals according to
             // https://drafts.csswg.org/css-position-4/#process-top-layer-removals.
@@ -1375,6 +1447,8 @@ impl ScriptThread {
         // Perform a microtask checkpoint as the specifications says that *update the rendering*
         // should be run in a task and a microtask checkpoint is always done when running tasks.
         self.perform_a_microtask_checkpoint(can_gc);
+
+        self.end_animation_frame(Some(render_start), &style_and_layout_starts, can_gc);
         built_any_display_lists
     }
 
@@ -1393,12 +1467,7 @@ impl ScriptThread {
             .borrow()
             .iter()
             .any(|(_, document)| document.needs_rendering_update());
-        let running_animations = self.documents.borrow().iter().any(|(_, document)| {
-            document.is_fully_active() &&
-                !document.window().throttled() &&
-                (document.animations().running_animation_count() != 0 ||
-                    document.has_active_request_animation_frame_callbacks())
-        });
+        let running_animations = self.has_running_animations();
 
         // If we are not running animations and no rendering update is
         // necessary, just exit early and schedule the next rendering update
@@ -1443,6 +1512,82 @@ impl ScriptThread {
         );
     }
 
+    /// Whether any fully active document is running animations or has animation frame
+    /// callbacks, meaning that rendering opportunities will keep occurring.
+    fn has_running_animations(&self) -> bool {
+        self.documents.borrow().iter().any(|(_, document)| {
+            document.is_fully_active() &&
+                !document.window().throttled() &&
+                (document.animations().running_animation_count() != 0 ||
+                    document.has_active_request_animation_frame_callbacks())
+        })
+    }
+
+    /// <https://w3c.github.io/requestidlecallback/#start-an-idle-period-algorithm>
+    ///
+    /// Start an idle period for all windows with idle callbacks once the event loop has run
+    /// out of tasks. The idle period ends at the next rendering opportunity or timer, and
+    /// lasts at most [`MAXIMUM_IDLE_PERIOD_DURATION`].
+    fn maybe_start_an_idle_period(&self) {
+        if let Some(timer_id) = self.scheduled_idle_period.borrow_mut().take() {
+            self.timer_scheduler.borrow_mut().cancel_timer(timer_id);
+        }
+
+        let windows: Vec<_> = self
+            .documents
+            .borrow()
+            .iter()
+            .filter(|(_, document)| {
+                document.is_fully_active() && document.window().has_idle_callbacks()
+            })
+            .map(|(_, document)| DomRoot::from_ref(document.window()))
+            .collect();
+        if windows.is_empty() || !self.task_queue.is_empty() {
+            return;
+        }
+
+        // Idle callbacks requested during an idle period wait for the next one.
+        let now = Instant::now();
+        if let Some(previous_deadline) = self.idle_period_deadline.get() {
+            if previous_deadline > now {
+                let timer_id = self.schedule_timer(TimerEventRequest {
+                    callback: Box::new(|| {}),
+                    duration: previous_deadline - now,
+                });
+                *self.scheduled_idle_period.borrow_mut() = Some(timer_id);
+                return;
+            }
+        }
+
+        let mut deadline = now + MAXIMUM_IDLE_PERIOD_DURATION;
+        if let Some(next_timer) = self.timer_scheduler.borrow().next_timer_time() {
+            deadline = deadline.min(next_timer);
+        }
+
+        // While animations are running the renderer triggers rendering opportunities at the
+        // display's refresh rate, so end the idle period before the next frame is due.
+        if self.has_running_animations() {
+            let next_frame = self
+                .last_render_opportunity_time
+                .get()
+                .map_or(now, |last_render_opportunity_time| {
+                    last_render_opportunity_time + FRAME_INTERVAL
+                });
+            deadline = deadline.min(next_frame);
+        }
+
+        // There is no idle time before the next rendering opportunity or timer, which will
+        // wake up the event loop again.
+        if deadline <= now {
+            return;
+        }
+
+        self.idle_period_deadline.set(Some(deadline));
+        for window in windows {
+            window.start_an_idle_period(deadline);
+        }
+    }
+
     /// Fulfill the possibly-pending pending `document.fonts.ready` promise if
     /// all web fonts have loaded.
     fn maybe_fulfill_font_ready_promises(&self, can_gc: CanGc) {
@@ -1557,6 +1702,7 @@ impl ScriptThread {
                     self.set_needs_rendering_update();
                 },
                 MixedMessage::FromConstellation(ScriptThreadMessage::SendInputEvent(id, event)) => {
+                    self.note_ui_event();
                     self.handle_input_event(id, event)
                 },
                 MixedMessage::FromScript(MainThreadScriptMsg::Common(CommonScriptMsg::Task(
@@ -1626,6 +1772,7 @@ impl ScriptThread {
                 continue;
             }
 
+            let task_start = CrossProcessInstant::now();
             let exiting = self.profile_event(category, pipeline_id, move || {
                 match msg {
                     MixedMessage::FromConstellation(ScriptThreadMessage::ExitScriptThread) => {
@@ -1660,6 +1807,8 @@ impl ScriptThread {
             // https://html.spec.whatwg.org/multipage/#event-loop-processing-model step 6
             // TODO(#32003): A microtask checkpoint is only supposed to be performed after running a task.
             self.perform_a_microtask_checkpoint(can_gc);
+
+            self.record_task_timing(pipeline_id, task_start, can_gc);
         }
 
         for (_, doc) in self.documents.borrow().iter() {
@@ -1688,9 +1837,133 @@ impl ScriptThread {
         // This must happen last to detect if any change above makes a rendering update necessary.
         self.maybe_schedule_rendering_opportunity_after_ipc_message(built_any_display_lists);
 
+        // If no rendering update is coming, the current animation frame ends without one.
+        if !self.needs_rendering_update.load(Ordering::Relaxed) &&
+            self.scheduled_update_the_rendering.borrow().is_none() &&
+            !self.has_running_animations()
+        {
+            self.end_animation_frame(None, &HashMap::new(), can_gc);
+        }
+
+        // Any remaining time before the next rendering opportunity can be used for idle callbacks.
+        self.maybe_start_an_idle_period();
+
         true
     }
 
+    /// Record the timing of a task that has just run as part of the current animation
+    /// frame, and report it as a long task if it took longer than [`LONG_TASK_THRESHOLD`].
+    /// <https://w3c.github.io/longtasks/#report-long-tasks>
+    fn record_task_timing(
+        &self,
+        pipeline_id: Option<PipelineId>,
+        start: CrossProcessInstant,
+        can_gc: CanGc,
+    ) {
+        let end = CrossProcessInstant::now();
+        let duration = end - start;
+
+        // <https://w3c.github.io/long-animation-frames/#record-task-end-time>
+        let mut frame_timing = self
+            .current_frame_timing
+            .get()
+            .unwrap_or_else(|| FrameTimingInfo::new(start));
+        frame_timing.end_time = end;
+        if duration > LONG_TASK_THRESHOLD {
+            frame_timing.blocking_duration += duration - LONG_TASK_THRESHOLD;
+        }
+        self.current_frame_timing.set(Some(frame_timing));
+
+        if duration <= LONG_TASK_THRESHOLD {
+            return;
+        }
+
+        // The task is attributed to the browsing context of the document it ran for, if any.
+        let culprit = pipeline_id.and_then(|id| self.documents.borrow().find_window(id));
+        let documents: Vec<_> = self
+            .documents
+            .borrow()
+            .iter()
+            .map(|(_, document)| document)
+            .filter(|document| document.is_fully_active())
+            .collect();
+        for document in documents {
+            let window = document.window();
+            let performance = window.Performance();
+            if !performance.has_observers_for("longtask") {
+                continue;
+            }
+
+            let (name, container) =
+                PerformanceLongTaskTiming::attribution(window, culprit.as_deref());
+            let global = window.as_global_scope();
+            let attribution = TaskAttributionTiming::new(global, container, can_gc);
+            let entry =
+                PerformanceLongTaskTiming::new(global, name, start, duration, &attribution, can_gc);
+            performance.queue_entry(entry.upcast(), can_gc);
+        }
+    }
+
+    /// Note that a UI event was received during the current animation frame.
+    fn note_ui_event(&self) {
+        let now = CrossProcessInstant::now();
+        let mut frame_timing = self
+            .current_frame_timing
+            .get()
+            .unwrap_or_else(|| FrameTimingInfo::new(now));
+        if frame_timing.first_ui_event_timestamp.is_none() {
+            frame_timing.first_ui_event_timestamp = Some(now);
+        }
+        self.current_frame_timing.set(Some(frame_timing));
+    }
+
+    /// End the current animation frame, reporting it as a long animation frame to the
+    /// documents observing them if it took longer than [`LONG_TASK_THRESHOLD`].
+    /// <https://w3c.github.io/long-animation-frames/#finalize-timing-info>
+    fn end_animation_frame(
+        &self,
+        render_start: Option<CrossProcessInstant>,
+        style_and_layout_starts: &HashMap<PipelineId, CrossProcessInstant>,
+        can_gc: CanGc,
+    ) {
+        let end = CrossProcessInstant::now();
+        let mut frame_timing = match (self.current_frame_timing.take(), render_start) {
+            (Some(frame_timing), _) => frame_timing,
+            // A rendering update that was not preceded by any task starts its own frame.
+            (None, Some(render_start)) => FrameTimingInfo::new(render_start),
+            (None, None) => return,
+        };
+        frame_timing.end_time = end;
+        frame_timing.render_start = render_start;
+
+        if frame_timing.end_time - frame_timing.start_time <= LONG_TASK_THRESHOLD {
+            return;
+        }
+
+        let documents: Vec<_> = self
+            .documents
+            .borrow()
+            .iter()
+            .filter(|(_, document)| document.is_fully_active())
+            .collect();
+        for (pipeline_id, document) in documents {
+            let window = document.window();
+            let performance = window.Performance();
+            if !performance.has_observers_for("long-animation-frame") {
+                continue;
+            }
+
+            frame_timing.style_and_layout_start =
+                style_and_layout_starts.get(&pipeline_id).copied();
+            let entry = PerformanceLongAnimationFrameTiming::new(
+                window.as_global_scope(),
+                frame_timing,
+                can_gc,
+            );
+            performance.queue_entry(entry.upcast(), can_gc);
+        }
+    }
+
     fn categorize_msg(&self, msg: &MixedMessage) -> ScriptThreadEventCategory {
         match *msg {
             MixedMessage::FromConstellation(ref inner_msg) => match *inner_msg {
@@ -1911,6 +2184,15 @@ impl ScriptThread {
             ScriptThreadMessage::SetThrottled(pipeline_id, throttled) => {
                 self.handle_set_throttled_msg(pipeline_id, throttled)
             },
+            ScriptThreadMessage::SetFrozen(pipeline_id, frozen) => {
+                self.handle_set_frozen_msg(pipeline_id, frozen, can_gc)
+            },
+            ScriptThreadMessage::ActivatePrerenderedDocument(pipeline_id) => {
+                self.handle_activate_prerendered_document_msg(pipeline_id, can_gc)
+            },
+            ScriptThreadMessage::DisownOpener(pipeline_id) => {
+                self.handle_disown_opener_msg(pipeline_id)
+            },
             ScriptThreadMessage::SetThrottledInContainingIframe(
                 parent_pipeline_id,
                 browsing_context_id,
@@ -1949,9 +2231,31 @@ impl ScriptThread {
                 reason,
                 can_gc,
             ),
+            ScriptThreadMessage::ReplacePendingPipelineId(
+                parent_pipeline_id,
+                browsing_context_id,
+                old_pipeline_id,
+                new_pipeline_id,
+            ) => {
+                let frame_element = self
+                    .documents
+                    .borrow()
+                    .find_iframe(parent_pipeline_id, browsing_context_id);
+                if let Some(frame_element) = frame_element {
+                    frame_element.replace_pending_pipeline_id(old_pipeline_id, new_pipeline_id);
+                }
+            },
             ScriptThreadMessage::UpdateHistoryState(pipeline_id, history_state_id, url) => {
                 self.handle_update_history_state_msg(pipeline_id, history_state_id, url, can_gc)
             },
+            ScriptThreadMessage::UpdateNavigableHistory(pipeline_id, entries, current_index) => {
+                self.handle_update_navigable_history_msg(
+                    pipeline_id,
+                    entries,
+                    current_index,
+                    can_gc,
+                )
+            },
             ScriptThreadMessage::RemoveHistoryStates(pipeline_id, history_states) => {
                 self.handle_remove_history_states(pipeline_id, history_states)
             },
@@ -1997,18 +2301,9 @@ impl ScriptThread {
                 discard_browsing_context,
                 can_gc,
             ),
-            ScriptThreadMessage::PaintMetric(
-                pipeline_id,
-                metric_type,
-                metric_value,
-                first_reflow,
-            ) => self.handle_paint_metric(
-                pipeline_id,
-                metric_type,
-                metric_value,
-                first_reflow,
-                can_gc,
-            ),
+            ScriptThreadMessage::FramePresented(pipeline_id, epoch, presentation_time) => {
+                self.handle_frame_presented(pipeline_id, epoch, presentation_time, can_gc)
+            },
             ScriptThreadMessage::MediaSessionAction(pipeline_id, action) => {
                 self.handle_media_session_action(pipeline_id, action, can_gc)
             },
@@ -2660,14 +2955,32 @@ impl ScriptThread {
 
         let mut reports = vec![];
         perform_memory_report(|ops| {
-            for (_, document) in documents.iter() {
+            for (pipeline_id, document) in documents.iter() {
+                let prefix = format!("pipeline({pipeline_id}) url({})", document.url());
+
+                // DOM objects are owned by the JS heap, so this cross-cuts the malloc heap
+                // measurement of the JS runtime below.
+                let dom_tree_size = document
+                    .upcast::<Node>()
+                    .traverse_preorder(ShadowIncluding::Yes)
+                    .map(|node| dom_object_size_of(&*node, ops))
+                    .sum();
+                reports.push(Report {
+                    path: path![prefix, "dom-tree"],
+                    kind: ReportKind::NonExplicitSize,
+                    size: dom_tree_size,
+                });
+
                 document
                     .window()
                     .layout()
-                    .collect_reports(&mut reports, ops);
+                    .collect_reports(&prefix, &mut reports, ops);
             }
 
+            // The image cache and the JS runtime are shared by all pipelines of this
+            // script thread.
             let prefix = format!("url({urls})");
+            reports.push(self.image_cache.memory_report(&prefix, ops));
             reports.extend(self.get_cx().get_reports(prefix.clone(), ops));
         });
 
@@ -2719,6 +3032,46 @@ impl ScriptThread {
         warn!("SetThrottled sent to nonexistent pipeline");
     }
 
+    /// Activate the prerendered document of a pipeline, or make sure that it will not be
+    /// prerendering if it has not been created yet.
+    fn handle_activate_prerendered_document_msg(&self, id: PipelineId, can_gc: CanGc) {
+        let document = self.documents.borrow().find_document(id);
+        if let Some(document) = document {
+            document.activate_prerendered_document(can_gc);
+            return;
+        }
+        let mut loads = self.incomplete_loads.borrow_mut();
+        if let Some(load) = loads.iter_mut().find(|load| load.pipeline_id == id) {
+            load.load_data.prerendering = false;
+            return;
+        }
+        warn!("ActivatePrerenderedDocument sent to nonexistent pipeline");
+    }
+
+    /// Freeze or resume the document of a pipeline, as its webview was frozen or resumed.
+    fn handle_set_frozen_msg(&self, id: PipelineId, frozen: bool, can_gc: CanGc) {
+        let Some(document) = self.documents.borrow().find_document(id) else {
+            return warn!("SetFrozen sent to nonexistent pipeline");
+        };
+        if frozen {
+            document.freeze(can_gc);
+        } else {
+            document.resume(can_gc);
+        }
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#disowned-its-opener>
+    fn handle_disown_opener_msg(&self, id: PipelineId) {
+        match self.documents.borrow().find_window(id) {
+            Some(window) => {
+                if let Some(window_proxy) = window.undiscarded_window_proxy() {
+                    window_proxy.disown();
+                }
+            },
+            None => warn!("DisownOpener sent to nonexistent pipeline"),
+        }
+    }
+
     /// Handles activity change message
     fn handle_set_document_activity_msg(
         &self,
@@ -2939,6 +3292,22 @@ impl ScriptThread {
         }
     }
 
+    fn handle_update_navigable_history_msg(
+        &self,
+        pipeline_id: PipelineId,
+        entries: Vec<NavigableHistoryEntry>,
+        current_index: usize,
+        can_gc: CanGc,
+    ) {
+        let Some(window) = self.documents.borrow().find_window(pipeline_id) else {
+            return warn!(
+                "update navigable history after pipeline {} closed.",
+                pipeline_id
+            );
+        };
+        window.set_navigable_history(entries, current_index, can_gc);
+    }
+
     fn handle_remove_history_states(
         &self,
         pipeline_id: PipelineId,
@@ -3009,6 +3378,25 @@ impl ScriptThread {
                     return None;
                 };
 
+                // A response that may not be embedded in the ancestors of the navigable it is
+                // loaded into is replaced by an error page. Dropping the load cancels its fetch,
+                // and the constellation replaces this pipeline with one for the error page.
+                let blocked_reason = metadata.as_ref().and_then(|metadata| {
+                    self.incomplete_loads.borrow()[idx].embedding_blocked_reason(metadata)
+                });
+                if let Some(reason) = blocked_reason {
+                    warn!("{reason}");
+                    self.incomplete_loads.borrow_mut().remove(idx);
+                    self.senders
+                        .pipeline_to_constellation_sender
+                        .send((
+                            *id,
+                            ScriptToConstellationMessage::NavigationResponseBlocked(reason),
+                        ))
+                        .unwrap();
+                    return None;
+                }
+
                 let load = self.incomplete_loads.borrow_mut().remove(idx);
                 metadata.map(|meta| self.load(meta, load, can_gc))
             },
@@ -3370,6 +3758,9 @@ impl ScriptThread {
         let origin = if final_url.as_str() == "about:blank" || final_url.as_str() == "about:srcdoc"
         {
             incomplete.origin.clone()
+        } else if incomplete.load_data.navigation_error.is_some() {
+            // The error page that replaces a blocked response has an opaque origin.
+            MutableOrigin::new(ImmutableOrigin::new_opaque())
         } else {
             MutableOrigin::new(final_url.origin())
         };
@@ -3403,6 +3794,47 @@ impl ScriptThread {
             theme: incomplete.theme,
         };
 
+        // https://html.spec.whatwg.org/multipage/#obtain-coop
+        let opener_policy = OpenerPolicyValue::parse_from_headers(metadata.headers.as_deref());
+        // https://html.spec.whatwg.org/multipage/#obtain-an-embedder-policy
+        // The embedder policy only applies in secure contexts.
+        let embedder_policy = if final_url.is_potentially_trustworthy() {
+            EmbedderPolicy::parse_from_headers(metadata.headers.as_deref())
+        } else {
+            EmbedderPolicy::default()
+        };
+
+        // https://html.spec.whatwg.org/multipage/#initialise-the-document-object
+        // A top-level document is cross-origin isolated when it opts in with a `same-origin`
+        // opener policy and an embedder policy compatible with cross-origin isolation, which
+        // puts it in its own browsing context group. Nested documents can only be isolated when
+        // their parent is, and when they opt in to an embedder policy themselves.
+        //
+        // The event loop of the document was chosen before its opener policy was known, so a
+        // top-level document is only isolated if the constellation placed it in a content
+        // process that no other browsing context group can use.
+        let cross_origin_isolated = if incomplete.parent_info.is_some() {
+            incomplete.load_data.inherited_cross_origin_isolated &&
+                embedder_policy
+                    .value
+                    .is_compatible_with_cross_origin_isolation()
+        } else {
+            incomplete.load_data.in_isolated_content_process &&
+                opener_policy == OpenerPolicyValue::SameOrigin &&
+                embedder_policy
+                    .value
+                    .is_compatible_with_cross_origin_isolation()
+        };
+        if incomplete.parent_info.is_none() {
+            self.senders
+                .pipeline_to_constellation_sender
+                .send((
+                    incomplete.pipeline_id,
+                    ScriptToConstellationMessage::SetCrossOriginOpenerPolicy(opener_policy),
+                ))
+                .unwrap();
+        }
+
         // Create the window and document objects.
         let window = Window::new(
             incomplete.webview_id,
@@ -3444,6 +3876,7 @@ impl ScriptThread {
             #[cfg(feature = "webgpu")]
             self.gpu_id_hub.clone(),
             incomplete.load_data.inherited_secure_context,
+            cross_origin_isolated,
             incomplete.theme,
         );
         self.debugger_global.fire_add_debuggee(
@@ -3529,12 +3962,20 @@ impl ScriptThread {
             can_gc,
         );
 
-        let referrer_policy = metadata
-            .headers
-            .as_deref()
-            .and_then(|h| h.typed_get::<ReferrerPolicyHeader>())
-            .into();
+        let referrer_policy =
+            ReferrerPolicy::parse_header_for_response(metadata.headers.as_deref());
         document.set_referrer_policy(referrer_policy);
+        document.set_embedder_policy(embedder_policy);
+        document.set_persisted_form_state(incomplete.load_data.persisted_form_state.clone());
+        document.set_persisted_scroll_state(incomplete.load_data.persisted_scroll_state);
+        document.set_prerendering(incomplete.load_data.prerendering);
+        document.set_active_sandboxing_flag_set(incomplete.load_data.sandboxing_flag_set);
+        document.set_permissions_policy(PermissionsPolicy::create_from_response(
+            incomplete.load_data.inherited_permissions_policy.as_ref(),
+            document.origin().immutable(),
+            metadata.headers.as_deref(),
+        ));
+        document.set_ancestor_origins(incomplete.load_data.ancestor_origins.clone());
 
         let refresh_header = metadata.headers.as_deref().and_then(|h| h.get(REFRESH));
         if let Some(refresh_val) = refresh_header {
@@ -3769,6 +4210,10 @@ impl ScriptThread {
     /// Instructs the constellation to fetch the document that will be loaded. Stores the InProgressLoad
     /// argument until a notification is received that the fetch is complete.
     fn pre_page_load(&self, mut incomplete: InProgressLoad) {
+        if let Some(reason) = incomplete.load_data.navigation_error.clone() {
+            return self.page_load_navigation_error(incomplete, reason);
+        }
+
         let context = ParserContext::new(incomplete.pipeline_id, incomplete.load_data.url.clone());
         self.incomplete_parser_contexts
             .0
@@ -3872,12 +4317,44 @@ impl ScriptThread {
         assert!(metadata.location_url.is_some());
 
         let mut incomplete_loads = self.incomplete_loads.borrow_mut();
-        let Some(incomplete_load) = incomplete_loads
-            .iter_mut()
-            .find(|incomplete_load| incomplete_load.pipeline_id == id)
+        let Some(index) = incomplete_loads
+            .iter()
+            .position(|incomplete_load| incomplete_load.pipeline_id == id)
         else {
             return;
         };
+        let incomplete_load = &mut incomplete_loads[index];
+
+        // Documents of different sites never share an event loop, so in multiprocess mode a
+        // navigation that is redirected to another site is restarted from the redirect target
+        // by the constellation, in a pipeline of the event loop of that site. Dropping the load
+        // cancels its fetch. Only GET navigations are restarted, as the body of other requests
+        // can't be sent again.
+        if let Some(Ok(location_url)) = metadata.location_url.as_ref() {
+            if opts::get().multiprocess &&
+                incomplete_load.load_data.method == Method::GET &&
+                reg_site(location_url) != reg_site(&incomplete_load.load_data.url)
+            {
+                incomplete_loads.remove(index);
+                let referrer = metadata
+                    .referrer
+                    .clone()
+                    .map(Referrer::ReferrerUrl)
+                    .unwrap_or(Referrer::NoReferrer);
+                self.senders
+                    .pipeline_to_constellation_sender
+                    .send((
+                        id,
+                        ScriptToConstellationMessage::NavigationRedirectedToOtherSite {
+                            url: location_url.clone(),
+                            referrer,
+                            referrer_policy: metadata.referrer_policy,
+                        },
+                    ))
+                    .unwrap();
+                return;
+            }
+        }
 
         // Update the `url_list` of the incomplete load to track all redirects. This will be reflected
         // in the new `RequestBuilder` as well.
@@ -3950,6 +4427,22 @@ impl ScriptThread {
         );
     }
 
+    /// Synchronously load the error page that replaces a response that was blocked from
+    /// being rendered in its navigable.
+    fn page_load_navigation_error(&self, incomplete: InProgressLoad, reason: String) {
+        let id = incomplete.pipeline_id;
+        let url = incomplete.load_data.url.clone();
+        self.incomplete_loads.borrow_mut().push(incomplete);
+
+        let mut context = ParserContext::new(id, url);
+        let dummy_request_id = RequestId::default();
+        context.process_response(dummy_request_id, Err(NetworkError::Internal(reason)));
+        context.process_response_eof(
+            dummy_request_id,
+            Ok(ResourceFetchTiming::new(ResourceTimingType::None)),
+        );
+    }
+
     /// Synchronously parse a srcdoc document from a giving HTML string.
     fn page_load_about_srcdoc(&self, mut incomplete: InProgressLoad) {
         let id = incomplete.pipeline_id;
@@ -4011,22 +4504,17 @@ impl ScriptThread {
         }
     }
 
-    fn handle_paint_metric(
+    fn handle_frame_presented(
         &self,
         pipeline_id: PipelineId,
-        metric_type: ProgressiveWebMetricType,
-        metric_value: CrossProcessInstant,
-        first_reflow: bool,
+        epoch: Epoch,
+        presentation_time: CrossProcessInstant,
         can_gc: CanGc,
     ) {
-        match self.documents.borrow().find_document(pipeline_id) {
-            Some(document) => {
-                document.handle_paint_metric(metric_type, metric_value, first_reflow, can_gc)
-            },
-            None => warn!(
-                "Received paint metric ({metric_type:?}) for unknown document: {pipeline_id:?}"
-            ),
-        }
+        let Some(window) = self.documents.borrow().find_window(pipeline_id) else {
+            return warn!("Received frame presentation for unknown pipeline: {pipeline_id:?}");
+        };
+        window.handle_frame_presented(epoch, presentation_time, can_gc);
     }
 
     fn handle_media_session_action(
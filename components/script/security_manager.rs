@@ -16,6 +16,7 @@ use servo_url::ServoUrl;
 use stylo_atoms::Atom;
 
 use crate::conversions::Convert;
+use crate::dom::bindings::codegen::UnionTypes::CSPViolationReportBodyOrDeprecationReportBodyOrInterventionReportBody as ReportBodyType;
 use crate::dom::bindings::inheritance::Castable;
 use crate::dom::bindings::refcounted::Trusted;
 use crate::dom::bindings::root::DomRoot;
@@ -174,7 +175,7 @@ impl TaskOnce for CSPViolationReportTask {
             ReportingObserver::generate_and_queue_a_report(
                 &self.global.root(),
                 "csp-violation".into(),
-                Some(body),
+                Some(ReportBodyType::CSPViolationReportBody(body)),
                 report_to_directive.value.join(" ").into(),
             )
         }
@@ -0,0 +1,575 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Parsing of speculation rule sets and the prefetches they trigger.
+//!
+//! <https://html.spec.whatwg.org/multipage/#speculative-loading>
+
+use std::time::{Duration, Instant};
+
+use constellation_traits::{LoadData, LoadOrigin, ScriptToConstellationMessage};
+use http::header;
+use malloc_size_of_derive::MallocSizeOf;
+use net_traits::request::{
+    CredentialsMode, Destination, Initiator, RedirectMode, RequestBuilder, RequestId, RequestMode,
+    RequestPriority,
+};
+use net_traits::{
+    DOCUMENT_ACCEPT_HEADER_VALUE, FetchMetadata, FetchResponseListener, NetworkError,
+    ReferrerPolicy, ResourceFetchTiming, ResourceTimingType,
+};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use servo_url::ServoUrl;
+
+use crate::dom::bindings::refcounted::Trusted;
+use crate::dom::bindings::reflector::DomGlobal;
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::csp::{GlobalCspReporting, Violation};
+use crate::dom::document::{Document, determine_policy_for_token};
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::performanceresourcetiming::InitiatorType;
+use crate::network_listener::{PreInvoke, ResourceTimingListener, submit_timing};
+use crate::script_runtime::CanGc;
+
+/// How long a completed prefetch can be used by a navigation, regardless of the
+/// freshness of the response in the HTTP cache.
+const PREFETCH_RECORD_LIFETIME: Duration = Duration::from_secs(5 * 60);
+
+/// The keys a speculation rule is allowed to have.
+const SPECULATION_RULE_KEYS: [&str; 9] = [
+    "eagerness",
+    "expects_no_vary_search",
+    "referrer_policy",
+    "relative_to",
+    "requires",
+    "source",
+    "tag",
+    "urls",
+    "where",
+];
+
+/// <https://html.spec.whatwg.org/multipage/#speculation-rule-eagerness>
+#[derive(Clone, Copy, Debug, MallocSizeOf, PartialEq)]
+pub(crate) enum SpeculationRuleEagerness {
+    Immediate,
+    Eager,
+    Moderate,
+    Conservative,
+}
+
+/// <https://html.spec.whatwg.org/multipage/#speculation-rule>
+#[derive(Debug, MallocSizeOf)]
+pub(crate) struct SpeculationRule {
+    /// <https://html.spec.whatwg.org/multipage/#sr-urls>
+    urls: Vec<ServoUrl>,
+    /// <https://html.spec.whatwg.org/multipage/#sr-referrer-policy>
+    referrer_policy: Option<ReferrerPolicy>,
+    /// <https://html.spec.whatwg.org/multipage/#sr-eagerness>
+    eagerness: SpeculationRuleEagerness,
+}
+
+/// <https://html.spec.whatwg.org/multipage/#speculation-rule-set>
+#[derive(Debug, Default, MallocSizeOf)]
+pub(crate) struct SpeculationRuleSet {
+    /// <https://html.spec.whatwg.org/multipage/#sr-set-prefetch>
+    prefetch: Vec<SpeculationRule>,
+    /// <https://wicg.github.io/nav-speculation/prerendering.html#sr-set-prerender>
+    prerender: Vec<SpeculationRule>,
+}
+
+/// <https://html.spec.whatwg.org/multipage/#parse-a-speculation-rule-set-string>
+pub(crate) fn parse_a_speculation_rule_set_string(
+    input: &str,
+    document: &Document,
+    base_url: &ServoUrl,
+) -> Result<SpeculationRuleSet, String> {
+    // Step 1. Let parsed be the result of parsing a JSON string to an Infra value given input.
+    let parsed: JsonValue =
+        serde_json::from_str(input).map_err(|_| "The value needs to be valid JSON.".to_owned())?;
+
+    // Step 2. If parsed is not a map, then throw a TypeError indicating that the top-level
+    // value needs to be a JSON object.
+    let JsonValue::Object(parsed) = parsed else {
+        return Err("The top-level value needs to be a JSON object.".to_owned());
+    };
+
+    // Step 3. Let result be a new speculation rule set.
+    let mut result = SpeculationRuleSet::default();
+
+    // Step 4. Let tag be null.
+    // Step 5. If parsed["tag"] exists, then set tag to parsed["tag"], throwing a TypeError
+    // if it is not a speculation rule tag.
+    if let Some(tag) = parsed.get("tag") {
+        if !tag.as_str().is_some_and(is_a_speculation_rule_tag) {
+            return Err("The \"tag\" value needs to be an ASCII string.".to_owned());
+        }
+    }
+
+    // Step 6. Let typesToTreatAsPrefetch be « "prefetch" ».
+    // Step 7. For each type of typesToTreatAsPrefetch, and for "prerender": if parsed[type]
+    // exists, then:
+    for (type_, type_rules) in [
+        ("prefetch", &mut result.prefetch),
+        ("prerender", &mut result.prerender),
+    ] {
+        let Some(rules) = parsed.get(type_) else {
+            continue;
+        };
+        // Step 7.1. If parsed[type] is a list, then for each rule of parsed[type]:
+        let JsonValue::Array(rules) = rules else {
+            // Step 7.2. Otherwise, the user agent may report a warning to the console
+            // indicating that the rules list for type needs to be a JSON array.
+            warn!("The \"{}\" rules need to be a JSON array", type_);
+            continue;
+        };
+        for rule in rules {
+            // Step 7.1.1. Let rule be the result of parsing a speculation rule given rule,
+            // tag, document, and baseURL.
+            // Step 7.1.2. If rule is null, then continue.
+            // Step 7.1.3. Append rule to result's rules of that type.
+            match parse_a_speculation_rule(rule, document, base_url) {
+                Ok(rule) => type_rules.push(rule),
+                Err(message) => warn!("Ignoring speculation rule: {}", message),
+            }
+        }
+    }
+
+    // Step 8. Return result.
+    Ok(result)
+}
+
+/// <https://html.spec.whatwg.org/multipage/#parse-a-speculation-rule>
+fn parse_a_speculation_rule(
+    input: &JsonValue,
+    document: &Document,
+    base_url: &ServoUrl,
+) -> Result<SpeculationRule, String> {
+    // Step 1. If input is not a map, then return null.
+    let JsonValue::Object(input) = input else {
+        return Err("The rule needs to be a JSON object.".to_owned());
+    };
+
+    // Step 2. If input has any key other than the allowed ones, then return null.
+    if let Some(key) = input
+        .keys()
+        .find(|key| !SPECULATION_RULE_KEYS.contains(&key.as_str()))
+    {
+        return Err(format!("The rule has an unknown key \"{}\".", key));
+    }
+
+    // Step 3. If input["source"] exists, then set source to input["source"].
+    // Step 4. Otherwise, if input["urls"] exists and input["where"] does not exist, then set
+    // source to "list".
+    // Step 5. Otherwise, if input["where"] exists and input["urls"] does not exist, then set
+    // source to "document".
+    let source = match (input.get("source"), input.get("urls"), input.get("where")) {
+        (Some(source), _, _) => source.as_str(),
+        (None, Some(_), None) => Some("list"),
+        (None, None, Some(_)) => Some("document"),
+        _ => None,
+    };
+
+    // Step 6. If source is neither "list" nor "document", then return null.
+    let urls = match source {
+        Some("list") => parse_list_rule_urls(input, document, base_url)?,
+        // TODO: Support document rules, which select the links of the document that match
+        // a predicate.
+        Some("document") => return Err("Document rules are not supported.".to_owned()),
+        _ => return Err("The rule has an invalid source.".to_owned()),
+    };
+
+    // Step 13. Let requirements be an empty ordered set.
+    // Step 14. If input["requires"] exists, then for each requirement of input["requires"]:
+    if let Some(requires) = input.get("requires") {
+        let JsonValue::Array(requires) = requires else {
+            return Err("The \"requires\" value needs to be a JSON array.".to_owned());
+        };
+        // Step 14.2.1. If requirement is not a speculation rule requirement, then return
+        // null.
+        // Note: "anonymous-client-ip-when-cross-origin" can not be honoured, so rules that
+        // require it are dropped like rules with an unknown requirement.
+        if let Some(requirement) = requires.first() {
+            return Err(format!("The requirement {} is not supported.", requirement));
+        }
+    }
+
+    // Step 15. Let referrerPolicy be the empty string.
+    // Step 16. If input["referrer_policy"] exists, then:
+    let referrer_policy = match input.get("referrer_policy") {
+        None => None,
+        Some(JsonValue::String(policy)) if policy.is_empty() => None,
+        Some(JsonValue::String(policy)) => match determine_policy_for_token(policy) {
+            // Step 16.2. If input["referrer_policy"] is not a referrer policy, then return
+            // null.
+            ReferrerPolicy::EmptyString => {
+                return Err(format!("The referrer policy \"{}\" is invalid.", policy));
+            },
+            policy => Some(policy),
+        },
+        Some(_) => return Err("The \"referrer_policy\" value needs to be a string.".to_owned()),
+    };
+
+    // Step 17. Let eagerness be "immediate" if source is "list"; otherwise, "conservative".
+    // Step 18. If input["eagerness"] exists, then set eagerness to input["eagerness"], returning
+    // null if it is not a speculation rule eagerness.
+    let eagerness = match input.get("eagerness") {
+        None => SpeculationRuleEagerness::Immediate,
+        Some(eagerness) => match eagerness.as_str() {
+            Some("immediate") => SpeculationRuleEagerness::Immediate,
+            Some("eager") => SpeculationRuleEagerness::Eager,
+            Some("moderate") => SpeculationRuleEagerness::Moderate,
+            Some("conservative") => SpeculationRuleEagerness::Conservative,
+            _ => return Err(format!("The eagerness {} is invalid.", eagerness)),
+        },
+    };
+
+    // Step 19. If input["expects_no_vary_search"] exists, then it needs to be a string.
+    if input
+        .get("expects_no_vary_search")
+        .is_some_and(|value| !value.is_string())
+    {
+        return Err("The \"expects_no_vary_search\" value needs to be a string.".to_owned());
+    }
+
+    // Step 20. If input["tag"] exists, then it needs to be a speculation rule tag.
+    if input
+        .get("tag")
+        .is_some_and(|tag| !tag.as_str().is_some_and(is_a_speculation_rule_tag))
+    {
+        return Err("The \"tag\" value needs to be an ASCII string.".to_owned());
+    }
+
+    // Step 21. Return a speculation rule.
+    Ok(SpeculationRule {
+        urls,
+        referrer_policy,
+        eagerness,
+    })
+}
+
+/// Steps 7 to 11 of <https://html.spec.whatwg.org/multipage/#parse-a-speculation-rule>, for
+/// rules whose source is "list".
+fn parse_list_rule_urls(
+    input: &JsonMap<String, JsonValue>,
+    document: &Document,
+    base_url: &ServoUrl,
+) -> Result<Vec<ServoUrl>, String> {
+    // Step 7.1. If input["where"] exists, then return null.
+    if input.contains_key("where") {
+        return Err("A list rule can not have a \"where\" key.".to_owned());
+    }
+
+    // Step 7.2. If input["relative_to"] exists, then set baseURL to document's document base
+    // URL if it is "document", returning null if it is neither "ruleset" nor "document".
+    let base_url = match input.get("relative_to") {
+        None => base_url.clone(),
+        Some(relative_to) => match relative_to.as_str() {
+            Some("ruleset") => base_url.clone(),
+            Some("document") => document.base_url(),
+            _ => {
+                return Err(format!(
+                    "The \"relative_to\" value {} is invalid.",
+                    relative_to
+                ));
+            },
+        },
+    };
+
+    // Step 7.3. If input["urls"] does not exist or is not a list, then return null.
+    let Some(JsonValue::Array(urls)) = input.get("urls") else {
+        return Err("The \"urls\" value needs to be a JSON array.".to_owned());
+    };
+
+    // Step 7.4. For each urlString of input["urls"]:
+    let mut result = Vec::with_capacity(urls.len());
+    for url in urls {
+        // Step 7.4.1. If urlString is not a string, then return null.
+        let Some(url) = url.as_str() else {
+            return Err("The \"urls\" value needs to contain strings.".to_owned());
+        };
+
+        // Step 7.4.2. Let parsedURL be the result of URL parsing urlString with baseURL.
+        // Step 7.4.3. If parsedURL is failure, or its scheme is not an HTTP(S) scheme, then
+        // continue.
+        let Ok(url) = base_url.join(url) else {
+            continue;
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            continue;
+        }
+
+        // Step 7.4.4. Append parsedURL to urls.
+        result.push(url);
+    }
+    Ok(result)
+}
+
+/// <https://html.spec.whatwg.org/multipage/#speculation-rule-tag>
+fn is_a_speculation_rule_tag(tag: &str) -> bool {
+    tag.chars().all(|c| c.is_ascii_graphic() || c == ' ')
+}
+
+/// The state of a [`PrefetchRecord`].
+#[derive(Clone, Copy, Debug, MallocSizeOf, PartialEq)]
+enum PrefetchRecordState {
+    Ongoing,
+    Completed,
+}
+
+/// <https://html.spec.whatwg.org/multipage/#prefetch-record>
+#[derive(Debug, MallocSizeOf)]
+pub(crate) struct PrefetchRecord {
+    /// <https://html.spec.whatwg.org/multipage/#prefetch-record-url>
+    url: ServoUrl,
+    /// <https://html.spec.whatwg.org/multipage/#prefetch-record-state>
+    state: PrefetchRecordState,
+    /// <https://html.spec.whatwg.org/multipage/#prefetch-record-start-time>
+    start_time: Instant,
+}
+
+impl PrefetchRecord {
+    /// Whether this record can be used by a navigation to `url`.
+    /// <https://html.spec.whatwg.org/multipage/#prefetch-record-matches-a-url>
+    fn matches(&self, url: &ServoUrl) -> bool {
+        self.state == PrefetchRecordState::Completed &&
+            self.start_time.elapsed() < PREFETCH_RECORD_LIFETIME &&
+            self.url.as_url()[..url::Position::AfterQuery] ==
+                url.as_url()[..url::Position::AfterQuery]
+    }
+}
+
+/// The prefetch records of a [`Document`], keyed by the URL they were started for.
+#[derive(Default, MallocSizeOf)]
+pub(crate) struct PrefetchRecords(Vec<PrefetchRecord>);
+
+impl PrefetchRecords {
+    fn contains(&self, url: &ServoUrl) -> bool {
+        self.0.iter().any(|record| record.url == *url)
+    }
+
+    fn complete(&mut self, url: &ServoUrl) {
+        if let Some(record) = self.0.iter_mut().find(|record| record.url == *url) {
+            record.state = PrefetchRecordState::Completed;
+        }
+    }
+
+    fn remove(&mut self, url: &ServoUrl) {
+        self.0.retain(|record| record.url != *url);
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#find-a-matching-prefetch-record>
+    ///
+    /// A record that is found is removed, as a prefetched response is only used once.
+    pub(crate) fn take_matching_record(&mut self, url: &ServoUrl) -> bool {
+        // Drop the records that can no longer be used.
+        self.0.retain(|record| {
+            record.state == PrefetchRecordState::Ongoing ||
+                record.start_time.elapsed() < PREFETCH_RECORD_LIFETIME
+        });
+        let Some(index) = self.0.iter().position(|record| record.matches(url)) else {
+            return false;
+        };
+        self.0.remove(index);
+        true
+    }
+}
+
+/// <https://html.spec.whatwg.org/multipage/#consider-speculative-loads>
+pub(crate) fn consider_speculative_loads(document: &Document) {
+    // Step 1. If document's node navigable is not a top-level traversable, then return.
+    if !document.window().is_top_level() || !document.is_fully_active() {
+        return;
+    }
+
+    // A prerendering document waits for its activation to start speculative loads.
+    if document.is_prerendering() {
+        return;
+    }
+
+    for (url, referrer_policy) in candidates(document, |rule_set| &rule_set.prefetch) {
+        if document.prefetch_records().contains(&url) {
+            continue;
+        }
+        prefetch(document, url, referrer_policy);
+    }
+
+    // Only one navigation is prerendered at a time, so the first candidate is prerendered.
+    if let Some((url, referrer_policy)) = candidates(document, |rule_set| &rule_set.prerender)
+        .into_iter()
+        .next()
+    {
+        prerender(document, url, referrer_policy);
+    }
+}
+
+/// The URLs of the given rules of the speculation rule sets of a [`Document`] that should be
+/// loaded speculatively, along with the referrer policy to use for them.
+fn candidates(
+    document: &Document,
+    rules: impl Fn(&SpeculationRuleSet) -> &Vec<SpeculationRule>,
+) -> Vec<(ServoUrl, Option<ReferrerPolicy>)> {
+    // TODO: Only "immediate" and "eager" rules are considered, as there is no heuristic to
+    // trigger the speculative loads of more conservative rules yet.
+    document
+        .speculation_rule_sets()
+        .iter()
+        .flat_map(|rule_set| rules(rule_set).iter())
+        .filter(|rule| {
+            matches!(
+                rule.eagerness,
+                SpeculationRuleEagerness::Immediate | SpeculationRuleEagerness::Eager
+            )
+        })
+        .flat_map(|rule| {
+            rule.urls
+                .iter()
+                .map(move |url| (url.clone(), rule.referrer_policy))
+        })
+        // Cross-origin speculative loads would need to be isolated from the cookies and the
+        // network state of the user, which is not supported, so only same-origin URLs are
+        // loaded.
+        .filter(|(url, _)| url.origin().same_origin(document.origin()))
+        .collect()
+}
+
+/// Ask the constellation to prerender a navigation of the top-level navigable of a
+/// [`Document`] to `url`, in a hidden document that the navigation activates instantly.
+/// <https://wicg.github.io/nav-speculation/prerendering.html#start-user-agent-initiated-prerendering>
+fn prerender(document: &Document, url: ServoUrl, referrer_policy: Option<ReferrerPolicy>) {
+    let window = document.window();
+    let global = window.as_global_scope();
+    let load_data = LoadData::new(
+        LoadOrigin::Script(document.origin().immutable().clone()),
+        url,
+        Some(global.pipeline_id()),
+        global.get_referrer(),
+        referrer_policy.unwrap_or_else(|| document.get_referrer_policy()),
+        Some(global.is_secure_context()),
+        Some(document.insecure_requests_policy()),
+        document.has_trustworthy_ancestor_origin(),
+    );
+    window.send_to_constellation(ScriptToConstellationMessage::Prerender(load_data));
+}
+
+/// <https://html.spec.whatwg.org/multipage/#prefetch>
+fn prefetch(document: &Document, url: ServoUrl, referrer_policy: Option<ReferrerPolicy>) {
+    // Step 1. Let prefetchRecord be a new prefetch record whose URL is url, and start time is
+    // the current high resolution time.
+    // Step 2. Append prefetchRecord to document's prefetch records.
+    document.prefetch_records_mut().0.push(PrefetchRecord {
+        url: url.clone(),
+        state: PrefetchRecordState::Ongoing,
+        start_time: Instant::now(),
+    });
+
+    // Step 3. Let request be a navigation request for url, whose initiator is "prefetch",
+    // which causes a `Sec-Purpose: prefetch` header to be sent, and whose priority is low
+    // so that it does not compete with the resources of the current document.
+    let window = document.window();
+    let mut request = RequestBuilder::new(
+        Some(window.webview_id()),
+        url.clone(),
+        window.as_global_scope().get_referrer(),
+    )
+    .initiator(Initiator::Prefetch)
+    .destination(Destination::Document)
+    .mode(RequestMode::Navigate)
+    .credentials_mode(CredentialsMode::Include)
+    .redirect_mode(RedirectMode::Follow)
+    .priority(RequestPriority::Low)
+    .origin(document.origin().immutable().clone())
+    .pipeline_id(Some(window.pipeline_id()))
+    .policy_container(document.policy_container().to_owned())
+    .referrer_policy(referrer_policy.unwrap_or_else(|| document.get_referrer_policy()));
+    request
+        .headers
+        .insert(header::ACCEPT, DOCUMENT_ACCEPT_HEADER_VALUE);
+
+    // Step 4. Fetch request. The response ends up in the HTTP cache, where the navigation
+    // that matches prefetchRecord will find it.
+    let context = SpeculationRulesPrefetchContext {
+        document: Trusted::new(document),
+        url,
+        succeeded: false,
+        resource_timing: ResourceFetchTiming::new(ResourceTimingType::Resource),
+    };
+    document.fetch_background(request, context);
+}
+
+struct SpeculationRulesPrefetchContext {
+    /// The [`Document`] whose speculation rules caused this prefetch.
+    document: Trusted<Document>,
+    /// The url being prefetched.
+    url: ServoUrl,
+    /// Whether the response of the prefetch was successful.
+    succeeded: bool,
+    resource_timing: ResourceFetchTiming,
+}
+
+impl FetchResponseListener for SpeculationRulesPrefetchContext {
+    fn process_request_body(&mut self, _: RequestId) {}
+
+    fn process_request_eof(&mut self, _: RequestId) {}
+
+    fn process_response(&mut self, _: RequestId, metadata: Result<FetchMetadata, NetworkError>) {
+        self.succeeded = metadata.is_ok_and(|metadata| {
+            let metadata = match metadata {
+                FetchMetadata::Unfiltered(metadata) => metadata,
+                FetchMetadata::Filtered { unsafe_, .. } => unsafe_,
+            };
+            metadata.status.is_success()
+        });
+    }
+
+    fn process_response_chunk(&mut self, _: RequestId, _: Vec<u8>) {}
+
+    fn process_response_eof(
+        &mut self,
+        _: RequestId,
+        response: Result<ResourceFetchTiming, NetworkError>,
+    ) {
+        let document = self.document.root();
+        let mut records = document.prefetch_records_mut();
+        if self.succeeded && response.is_ok() {
+            records.complete(&self.url);
+        } else {
+            // A failed prefetch is forgotten, so that navigations go to the network.
+            records.remove(&self.url);
+        }
+    }
+
+    fn resource_timing_mut(&mut self) -> &mut ResourceFetchTiming {
+        &mut self.resource_timing
+    }
+
+    fn resource_timing(&self) -> &ResourceFetchTiming {
+        &self.resource_timing
+    }
+
+    fn submit_resource_timing(&mut self) {
+        submit_timing(self, CanGc::note())
+    }
+
+    fn process_csp_violations(&mut self, _request_id: RequestId, violations: Vec<Violation>) {
+        let global = &self.resource_timing_global();
+        global.report_csp_violations(violations, None, None);
+    }
+}
+
+impl ResourceTimingListener for SpeculationRulesPrefetchContext {
+    fn resource_timing_information(&self) -> (InitiatorType, ServoUrl) {
+        (InitiatorType::Other, self.url.clone())
+    }
+
+    fn resource_timing_global(&self) -> DomRoot<GlobalScope> {
+        self.document.root().global()
+    }
+}
+
+impl PreInvoke for SpeculationRulesPrefetchContext {
+    fn should_invoke(&self) -> bool {
+        // Prefetch requests are never aborted.
+        true
+    }
+}
@@ -8,7 +8,7 @@ use std::sync::atomic::AtomicBool;
 use cssparser::SourceLocation;
 use encoding_rs::UTF_8;
 use mime::{self, Mime};
-use net_traits::request::{CorsSettings, Destination, RequestId};
+use net_traits::request::{CorsSettings, Destination, RequestId, RequestPriority};
 use net_traits::{
     FetchMetadata, FetchResponseListener, FilteredMetadata, Metadata, NetworkError, ReferrerPolicy,
     ResourceFetchTiming, ResourceTimingType,
@@ -33,7 +33,7 @@ use crate::dom::bindings::reflector::DomGlobal;
 use crate::dom::bindings::root::DomRoot;
 use crate::dom::csp::{GlobalCspReporting, Violation};
 use crate::dom::document::Document;
-use crate::dom::element::Element;
+use crate::dom::element::{Element, fetch_priority_for_element};
 use crate::dom::eventtarget::EventTarget;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::htmlelement::HTMLElement;
@@ -131,6 +131,13 @@ impl FetchResponseListener for StylesheetContext {
     fn process_request_eof(&mut self, _: RequestId) {}
 
     fn process_response(&mut self, _: RequestId, metadata: Result<FetchMetadata, NetworkError>) {
+        if let Err(error) = &metadata {
+            self.document
+                .root()
+                .window()
+                .as_global_scope()
+                .report_subresource_integrity_failure(&self.url, error);
+        }
         if let Ok(FetchMetadata::Filtered {
             filtered: FilteredMetadata::Opaque | FilteredMetadata::OpaqueRedirect(_),
             ..
@@ -365,6 +372,11 @@ impl StylesheetLoader<'_> {
             .as_stylesheet_owner()
             .expect("Stylesheet not loaded by <style> or <link> element!");
         let referrer_policy = owner.referrer_policy();
+        let fetch_priority = if self.elem.is::<HTMLLinkElement>() {
+            fetch_priority_for_element(self.elem.upcast())
+        } else {
+            RequestPriority::Auto
+        };
         owner.increment_pending_loads_count();
         if owner.parser_inserted() {
             document.increment_script_blocking_stylesheet_count();
@@ -386,7 +398,8 @@ impl StylesheetLoader<'_> {
         .origin(document.origin().immutable().clone())
         .pipeline_id(Some(self.elem.global().pipeline_id()))
         .referrer_policy(referrer_policy)
-        .integrity_metadata(integrity_metadata);
+        .integrity_metadata(integrity_metadata)
+        .priority(fetch_priority);
 
         document.fetch(LoadType::Stylesheet(url), request, context);
     }
@@ -140,10 +140,18 @@ impl TaskManager {
     task_source_functions!(self, file_reading_task_source, FileReading);
     task_source_functions!(self, font_loading_task_source, FontLoading);
     task_source_functions!(self, gamepad_task_source, Gamepad);
+    task_source_functions!(self, geolocation_task_source, Geolocation);
+    task_source_functions!(self, idle_task_source, IdleTask);
     task_source_functions!(self, media_element_task_source, MediaElement);
     task_source_functions!(self, networking_task_source, Networking);
     task_source_functions!(self, performance_timeline_task_source, PerformanceTimeline);
     task_source_functions!(self, port_message_queue, PortMessage);
+    task_source_functions!(self, posted_task_task_source, PostedTask);
+    task_source_functions!(
+        self,
+        background_posted_task_task_source,
+        BackgroundPostedTask
+    );
     task_source_functions!(self, remote_event_task_source, RemoteEvent);
     task_source_functions!(self, timer_task_source, Timer);
     task_source_functions!(self, user_interaction_task_source, UserInteraction);
@@ -133,7 +133,9 @@ impl<T: QueuedTaskConversion> TaskQueue<T> {
             };
 
             match task_source {
-                TaskSourceName::PerformanceTimeline => {
+                TaskSourceName::PerformanceTimeline |
+                TaskSourceName::BackgroundPostedTask |
+                TaskSourceName::IdleTask => {
                     to_be_throttled.push(incoming.remove(index - 1));
                     index -= 1; // We've removed an element, so the next has the same index.
                 },
@@ -191,6 +193,13 @@ impl<T: QueuedTaskConversion> TaskQueue<T> {
         &self.port
     }
 
+    /// Whether there are no tasks left to run, including throttled ones.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.msg_queue.borrow().is_empty() &&
+            self.port.is_empty() &&
+            self.throttled.borrow().values().all(VecDeque::is_empty)
+    }
+
     /// Take a message from the front of the queue, without waiting if empty.
     pub(crate) fn recv(&self) -> Result<T, ()> {
         self.msg_queue.borrow_mut().pop_front().ok_or(())
@@ -32,9 +32,16 @@ pub(crate) enum TaskSourceName {
     /// <https://drafts.csswg.org/css-font-loading/#task-source>
     FontLoading,
     HistoryTraversal,
+    /// <https://w3c.github.io/requestidlecallback/#the-idle-task-source>
+    IdleTask,
     Networking,
     PerformanceTimeline,
     PortMessage,
+    /// <https://wicg.github.io/scheduling-apis/#posted-task-task-source>
+    PostedTask,
+    /// The posted task task source for tasks of "background" priority, which are throttled
+    /// while the event loop is busy.
+    BackgroundPostedTask,
     UserInteraction,
     RemoteEvent,
     /// <https://html.spec.whatwg.org/multipage/#rendering-task-source>
@@ -44,6 +51,8 @@ pub(crate) enum TaskSourceName {
     Timer,
     /// <https://www.w3.org/TR/gamepad/#dfn-gamepad-task-source>
     Gamepad,
+    /// <https://w3c.github.io/geolocation/#dfn-geolocation-task-source>
+    Geolocation,
     /// <https://w3c.github.io/IntersectionObserver/#intersectionobserver-task-source>
     IntersectionObserver,
 }
@@ -59,11 +68,14 @@ impl From<TaskSourceName> for ScriptThreadEventCategory {
             TaskSourceName::FileReading => ScriptThreadEventCategory::FileRead,
             TaskSourceName::FontLoading => ScriptThreadEventCategory::FontLoading,
             TaskSourceName::HistoryTraversal => ScriptThreadEventCategory::HistoryEvent,
+            TaskSourceName::IdleTask => ScriptThreadEventCategory::ScriptEvent,
             TaskSourceName::Networking => ScriptThreadEventCategory::NetworkEvent,
             TaskSourceName::PerformanceTimeline => {
                 ScriptThreadEventCategory::PerformanceTimelineTask
             },
             TaskSourceName::PortMessage => ScriptThreadEventCategory::PortMessage,
+            TaskSourceName::PostedTask => ScriptThreadEventCategory::ScriptEvent,
+            TaskSourceName::BackgroundPostedTask => ScriptThreadEventCategory::ScriptEvent,
             TaskSourceName::UserInteraction => ScriptThreadEventCategory::InputEvent,
             TaskSourceName::RemoteEvent => ScriptThreadEventCategory::NetworkEvent,
             TaskSourceName::Rendering => ScriptThreadEventCategory::Rendering,
@@ -71,6 +83,7 @@ impl From<TaskSourceName> for ScriptThreadEventCategory {
             TaskSourceName::WebSocket => ScriptThreadEventCategory::WebSocketEvent,
             TaskSourceName::Timer => ScriptThreadEventCategory::TimerEvent,
             TaskSourceName::Gamepad => ScriptThreadEventCategory::InputEvent,
+            TaskSourceName::Geolocation => ScriptThreadEventCategory::ScriptEvent,
             TaskSourceName::IntersectionObserver => ScriptThreadEventCategory::ScriptEvent,
         }
     }
@@ -59,6 +59,12 @@ pub mod size_of {
     }
 }
 
+pub mod compression {
+    pub use crate::dom::bindings::codegen::Bindings::CompressionStreamBinding::CompressionFormat;
+    pub use crate::dom::compressionstream::{ChunkCodec, Compressor};
+    pub use crate::dom::decompressionstream::Decompressor;
+}
+
 pub mod srcset {
     pub use crate::dom::htmlimageelement::{Descriptor, ImageSource, parse_a_srcset_attribute};
 }
@@ -15,10 +15,13 @@ use unicode_segmentation::UnicodeSegmentation;
 use crate::clipboard_provider::{ClipboardProvider, EmbedderClipboardProvider};
 use crate::dom::bindings::cell::DomRefCell;
 use crate::dom::bindings::codegen::Bindings::EventBinding::Event_Binding::EventMethods;
+use crate::dom::bindings::conversions::DerivedFrom;
 use crate::dom::bindings::inheritance::Castable;
 use crate::dom::bindings::str::DOMString;
 use crate::dom::compositionevent::CompositionEvent;
 use crate::dom::event::Event;
+use crate::dom::eventtarget::EventTarget;
+use crate::dom::inputevent::{InputEvent, InputEventType};
 use crate::dom::keyboardevent::KeyboardEvent;
 use crate::dom::node::NodeTraits;
 use crate::dom::types::ClipboardEvent;
@@ -235,6 +238,16 @@ pub(crate) const CMD_OR_CONTROL: Modifiers = Modifiers::META;
 #[cfg(not(target_os = "macos"))]
 pub(crate) const CMD_OR_CONTROL: Modifiers = Modifiers::CONTROL;
 
+/// Whether a key pressed with `mods` is a keyboard shortcut, rather than a character that is
+/// typed. Characters typed with Alt (Option on macOS) or AltGr are text. Windows reports AltGr
+/// as Control and Alt pressed together, so that combination is treated as AltGr too.
+pub(crate) fn is_shortcut_chord(mods: Modifiers) -> bool {
+    if mods.contains(Modifiers::ALT_GRAPH) || mods.contains(Modifiers::CONTROL | Modifiers::ALT) {
+        return false;
+    }
+    mods.intersects(Modifiers::CONTROL | Modifiers::META)
+}
+
 /// The length in bytes of the first n characters in a UTF-8 string.
 ///
 /// If the string has fewer than n characters, returns the length of the whole string.
@@ -262,6 +275,17 @@ fn len_of_first_n_code_units(text: &str, n: UTF16CodeUnits) -> UTF8Bytes {
     utf8_len
 }
 
+/// The text that typing `key` with `mods` inserts, if it is not a shortcut. This is shared
+/// by [`TextInput::handle_keydown_aux`], which inserts the text, and
+/// [`TextInput::input_event_for_keydown`], which describes the edit to `beforeinput` and
+/// `input` listeners, so that the two agree.
+fn inserted_text(key: &Key, mods: Modifiers) -> Option<&str> {
+    match key {
+        Key::Character(text) if !is_shortcut_chord(mods) => Some(text.as_str()),
+        _ => None,
+    }
+}
+
 impl<T: ClipboardProvider> TextInput<T> {
     /// Instantiate a new text input control
     pub fn new(
@@ -858,6 +882,41 @@ impl<T: ClipboardProvider> TextInput<T> {
         self.handle_keydown_aux(key, mods, cfg!(target_os = "macos"))
     }
 
+    /// The kind of edit, and the data it inserts, that [`Self::handle_keydown`] would
+    /// perform for the given `KeyboardEvent`, if any. This is used to fire `beforeinput`
+    /// before the edit happens and `input` after it.
+    pub(crate) fn input_event_for_keydown(
+        &self,
+        event: &KeyboardEvent,
+    ) -> Option<(InputEventType, Option<DOMString>)> {
+        let key = event.key();
+        let mut mods = event.modifiers();
+        mods.remove(Modifiers::SHIFT);
+        ShortcutMatcher::new(KeyState::Down, key.clone(), mods)
+            .shortcut(CMD_OR_CONTROL, 'X', || {
+                self.get_selection_text()
+                    .map(|_| (InputEventType::DeleteByCut, None))
+            })
+            .shortcut(CMD_OR_CONTROL, 'V', || {
+                Some((InputEventType::InsertFromPaste, None))
+            })
+            .shortcut(Modifiers::empty(), Key::Named(NamedKey::Delete), || {
+                Some((InputEventType::DeleteContentForward, None))
+            })
+            .shortcut(Modifiers::empty(), Key::Named(NamedKey::Backspace), || {
+                Some((InputEventType::DeleteContentBackward, None))
+            })
+            .shortcut(Modifiers::empty(), Key::Named(NamedKey::Enter), || {
+                self.multiline
+                    .then_some((InputEventType::InsertLineBreak, None))
+            })
+            .otherwise(|| {
+                inserted_text(&key, mods)
+                    .map(|text| (InputEventType::InsertText, Some(DOMString::from(text))))
+            })
+            .flatten()
+    }
+
     // This function exists for easy unit testing.
     // To test Mac OS shortcuts on other systems a flag is passed.
     pub fn handle_keydown_aux(
@@ -1021,10 +1080,12 @@ impl<T: ClipboardProvider> TextInput<T> {
                 KeyReaction::RedrawSelection
             })
             .otherwise(|| {
-                if let Key::Character(ref c) = key {
-                    self.insert_string(c.as_str());
+                if let Some(text) = inserted_text(&key, mods) {
+                    self.insert_string(text);
                     return KeyReaction::DispatchInput;
                 }
+                // The text typed with an input method is inserted by the composition events
+                // that follow, so no edit happens here.
                 if matches!(key, Key::Named(NamedKey::Process)) {
                     return KeyReaction::DispatchInput;
                 }
@@ -1201,8 +1262,10 @@ impl<T: ClipboardProvider> TextInput<T> {
 
     fn paste_contents(&mut self, drag_data_store: &DragDataStore) {
         for item in drag_data_store.iter_item_list() {
-            if let Kind::Text { data, .. } = item {
-                self.insert_string(data.to_string());
+            if let Kind::Text { data, type_ } = item {
+                if type_.eq("text/plain") {
+                    self.insert_string(data.to_string());
+                }
             }
         }
     }
@@ -1210,7 +1273,7 @@ impl<T: ClipboardProvider> TextInput<T> {
 
 /// <https://www.w3.org/TR/clipboard-apis/#clipboard-actions> step 3
 pub(crate) fn handle_text_clipboard_action(
-    owning_node: &impl NodeTraits,
+    owning_node: &(impl NodeTraits + Castable + DerivedFrom<EventTarget>),
     textinput: &DomRefCell<TextInput<EmbedderClipboardProvider>>,
     event: &ClipboardEvent,
     can_gc: CanGc,
@@ -1241,6 +1304,16 @@ pub(crate) fn handle_text_clipboard_action(
 
             // Step 3.1 If there is a selection in an editable context where cutting is enabled, then
             if let Some(text) = selection {
+                if !InputEvent::dispatch_beforeinput(
+                    owning_node.upcast(),
+                    InputEventType::DeleteByCut,
+                    None,
+                    &[],
+                    can_gc,
+                ) {
+                    return false;
+                }
+
                 // Step 3.1.1 Copy the selected contents, if any, to the clipboard
                 textinput.borrow_mut().clipboard_provider.set_text(text);
 
@@ -1253,6 +1326,7 @@ pub(crate) fn handle_text_clipboard_action(
                     .fire_clipboardchange_event(can_gc);
 
                 // Step 3.1.4 Queue tasks to fire any events that should fire due to the modification.
+                InputEvent::queue_input(owning_node.upcast(), InputEventType::DeleteByCut, None);
             } else {
                 // Step 3.2 Else, if there is no selection or the context is not editable, then
                 return false;
@@ -1261,11 +1335,26 @@ pub(crate) fn handle_text_clipboard_action(
         "paste" => {
             // Step 3.1 If there is a selection or cursor in an editable context where pasting is enabled, then
             if let Some(data) = event.get_clipboard_data() {
+                if !InputEvent::dispatch_beforeinput(
+                    owning_node.upcast(),
+                    InputEventType::InsertFromPaste,
+                    None,
+                    &[],
+                    can_gc,
+                ) {
+                    return false;
+                }
+
                 // Step 3.1.1 Insert the most suitable content found on the clipboard, if any, into the context.
                 let drag_data_store = data.data_store().expect("This shouldn't fail");
                 textinput.borrow_mut().paste_contents(&drag_data_store);
 
                 // Step 3.1.2 Queue tasks to fire any events that should fire due to the modification.
+                InputEvent::queue_input(
+                    owning_node.upcast(),
+                    InputEventType::InsertFromPaste,
+                    None,
+                );
             } else {
                 // Step 3.2 Else return false.
                 return false;
@@ -18,9 +18,14 @@ use serde::{Deserialize, Serialize};
 use servo_config::pref;
 use timers::{BoxedTimerCallback, TimerEventRequest};
 
+use crate::dom::abortsignal::AbortSignalTimeoutCallback;
 use crate::dom::bindings::callback::ExceptionHandling::Report;
 use crate::dom::bindings::cell::DomRefCell;
 use crate::dom::bindings::codegen::Bindings::FunctionBinding::Function;
+use crate::dom::bindings::codegen::UnionTypes::{
+    TrustedScriptOrString, TrustedScriptOrStringOrFunction,
+};
+use crate::dom::bindings::error::Fallible;
 use crate::dom::bindings::inheritance::Castable;
 use crate::dom::bindings::refcounted::Trusted;
 use crate::dom::bindings::reflector::{DomGlobal, DomObject};
@@ -29,10 +34,17 @@ use crate::dom::bindings::str::DOMString;
 use crate::dom::csp::CspReporting;
 use crate::dom::document::{ImageAnimationUpdateCallback, RefreshRedirectDue};
 use crate::dom::eventsource::EventSourceTimeoutCallback;
+use crate::dom::geolocation::GeolocationTimeoutCallback;
 use crate::dom::globalscope::GlobalScope;
+use crate::dom::scheduler::SchedulerPostTaskDelayCallback;
 #[cfg(feature = "testbinding")]
 use crate::dom::testbinding::TestBindingCallback;
+use crate::dom::trustedscript::TrustedScript;
 use crate::dom::types::{Window, WorkerGlobalScope};
+use crate::dom::viewtransition::ViewTransitionTimeoutCallback;
+use crate::dom::window::{
+    IdleCallbackTimeoutCallback, SmoothScrollUpdateCallback, UserScrollEndCheckCallback,
+};
 use crate::dom::xmlhttprequest::XHRTimeoutCallback;
 use crate::script_module::ScriptFetchOptions;
 use crate::script_runtime::{CanGc, IntroductionType};
@@ -79,25 +91,39 @@ struct OneshotTimer {
 //     `invoke<T: DomObject>(self: Box<Self>, this: &T, js_timers: &JsTimers);`.
 #[derive(JSTraceable, MallocSizeOf)]
 pub(crate) enum OneshotTimerCallback {
+    AbortSignalTimeout(AbortSignalTimeoutCallback),
     XhrTimeout(XHRTimeoutCallback),
     EventSourceTimeout(EventSourceTimeoutCallback),
+    GeolocationTimeout(GeolocationTimeoutCallback),
     JsTimer(JsTimerTask),
     #[cfg(feature = "testbinding")]
     TestBindingCallback(TestBindingCallback),
     RefreshRedirectDue(RefreshRedirectDue),
     ImageAnimationUpdate(ImageAnimationUpdateCallback),
+    SchedulerPostTaskDelay(SchedulerPostTaskDelayCallback),
+    IdleCallbackTimeout(IdleCallbackTimeoutCallback),
+    ViewTransitionTimeout(ViewTransitionTimeoutCallback),
+    SmoothScrollUpdate(SmoothScrollUpdateCallback),
+    UserScrollEndCheck(UserScrollEndCheckCallback),
 }
 
 impl OneshotTimerCallback {
     fn invoke<T: DomObject>(self, this: &T, js_timers: &JsTimers, can_gc: CanGc) {
         match self {
+            OneshotTimerCallback::AbortSignalTimeout(callback) => callback.invoke(can_gc),
             OneshotTimerCallback::XhrTimeout(callback) => callback.invoke(can_gc),
             OneshotTimerCallback::EventSourceTimeout(callback) => callback.invoke(),
+            OneshotTimerCallback::GeolocationTimeout(callback) => callback.invoke(),
             OneshotTimerCallback::JsTimer(task) => task.invoke(this, js_timers, can_gc),
             #[cfg(feature = "testbinding")]
             OneshotTimerCallback::TestBindingCallback(callback) => callback.invoke(),
             OneshotTimerCallback::RefreshRedirectDue(callback) => callback.invoke(can_gc),
             OneshotTimerCallback::ImageAnimationUpdate(callback) => callback.invoke(can_gc),
+            OneshotTimerCallback::SchedulerPostTaskDelay(callback) => callback.invoke(),
+            OneshotTimerCallback::IdleCallbackTimeout(callback) => callback.invoke(can_gc),
+            OneshotTimerCallback::ViewTransitionTimeout(callback) => callback.invoke(can_gc),
+            OneshotTimerCallback::SmoothScrollUpdate(callback) => callback.invoke(),
+            OneshotTimerCallback::UserScrollEndCheck(callback) => callback.invoke(),
         }
     }
 }
@@ -394,6 +420,50 @@ pub(crate) enum TimerCallback {
     FunctionTimerCallback(Rc<Function>),
 }
 
+impl TimerCallback {
+    /// Converts the handler given to `setTimeout()` or `setInterval()`, running the
+    /// Trusted Types checks for string handlers.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/#timer-initialisation-steps>
+    pub(crate) fn from_handler(
+        global: &GlobalScope,
+        handler: TrustedScriptOrStringOrFunction,
+        method_name: &str,
+        can_gc: CanGc,
+    ) -> Fallible<TimerCallback> {
+        let handler = match handler {
+            TrustedScriptOrStringOrFunction::Function(function) => {
+                return Ok(TimerCallback::FunctionTimerCallback(function));
+            },
+            TrustedScriptOrStringOrFunction::TrustedScript(script) => {
+                TrustedScriptOrString::TrustedScript(script)
+            },
+            TrustedScriptOrStringOrFunction::String(string) => {
+                TrustedScriptOrString::String(string)
+            },
+        };
+        // Step 10.1.1. Let globalName be "Window" if global is a Window object;
+        // "WorkerGlobalScope" otherwise.
+        let global_name = if global.is::<Window>() {
+            "Window"
+        } else {
+            "WorkerGlobalScope"
+        };
+        // Step 10.1.2. Let methodName be "setInterval" if repeat is true; "setTimeout" otherwise.
+        // Step 10.1.3. Let sink be a concatenation of globalName, U+0020 SPACE, and methodName.
+        // Step 10.1.4. Set handler to the result of invoking the Get Trusted Type compliant
+        // string algorithm with TrustedScript, global, handler, sink, and "script".
+        let code = TrustedScript::get_trusted_script_compliant_string(
+            global,
+            handler,
+            global_name,
+            method_name,
+            can_gc,
+        )?;
+        Ok(TimerCallback::StringTimerCallback(code))
+    }
+}
+
 #[derive(Clone, JSTraceable, MallocSizeOf)]
 #[cfg_attr(crown, allow(crown::unrooted_must_root))]
 enum InternalTimerCallback {
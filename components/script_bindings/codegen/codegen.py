@@ -3254,6 +3254,7 @@ class CGWrapGlobalMethod(CGAbstractMethod):
         return CGGeneric(f"""
 let raw = Root::new(MaybeUnreflectedDom::from_box(object));
 let origin = (*raw.as_ptr()).upcast::<D::GlobalScope>().origin();
+let cross_origin_isolated = (*raw.as_ptr()).upcast::<D::GlobalScope>().is_cross_origin_isolated();
 
 rooted!(in(*cx) let mut obj = ptr::null_mut::<JSObject>());
 create_global_object::<D>(
@@ -3263,7 +3264,8 @@ create_global_object::<D>(
     {TRACE_HOOK_NAME}::<D>,
     obj.handle_mut(),
     origin,
-    {"true" if self.descriptor.useSystemCompartment else "false"});
+    {"true" if self.descriptor.useSystemCompartment else "false"},
+    cross_origin_isolated);
 assert!(!obj.is_null());
 
 let root = raw.reflect_with(obj.get());
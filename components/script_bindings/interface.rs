@@ -32,6 +32,7 @@ use js::rust::{
     HandleObject, HandleValue, MutableHandleObject, RealmOptions, define_methods,
     define_properties, get_object_class, is_dom_class, maybe_wrap_object,
 };
+use servo_config::pref;
 use servo_url::MutableOrigin;
 
 use crate::DomTypes;
@@ -135,6 +136,10 @@ impl InterfaceConstructorBehavior {
 pub(crate) type TraceHook = unsafe extern "C" fn(trc: *mut JSTracer, obj: *mut JSObject);
 
 /// Create a global object with the given class.
+///
+/// Shared memory is only made available when `cross_origin_isolated` is true, see
+/// <https://html.spec.whatwg.org/multipage/#sharedarraybuffer-serialization>.
+#[allow(clippy::too_many_arguments)]
 pub(crate) unsafe fn create_global_object<D: DomTypes>(
     cx: SafeJSContext,
     class: &'static JSClass,
@@ -143,12 +148,14 @@ pub(crate) unsafe fn create_global_object<D: DomTypes>(
     mut rval: MutableHandleObject,
     origin: &MutableOrigin,
     use_system_compartment: bool,
+    cross_origin_isolated: bool,
 ) {
     assert!(rval.is_null());
 
     let mut options = RealmOptions::default();
     options.creationOptions_.traceGlobal_ = Some(trace);
-    options.creationOptions_.sharedMemoryAndAtomics_ = false;
+    options.creationOptions_.sharedMemoryAndAtomics_ =
+        cross_origin_isolated && pref!(js_shared_memory);
     if use_system_compartment {
         options.creationOptions_.compSpec_ = CompartmentSpecifier::NewCompartmentAndZone;
         options.creationOptions_.__bindgen_anon_1.comp_ = std::ptr::null_mut();
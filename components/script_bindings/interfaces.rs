@@ -85,6 +85,8 @@ pub trait GlobalScopeHelpers<D: DomTypes> {
     fn get_url(&self) -> ServoUrl;
 
     fn is_secure_context(&self) -> bool;
+
+    fn is_cross_origin_isolated(&self) -> bool;
 }
 
 pub trait DocumentHelpers {
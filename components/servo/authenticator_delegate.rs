@@ -0,0 +1,102 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use embedder_traits::{
+    AuthenticatorError, GetAssertionParameters, GetAssertionResponse, MakeCredentialParameters,
+    MakeCredentialResponse,
+};
+use ipc_channel::ipc::IpcSender;
+use serde::Serialize;
+use url::Url;
+
+use crate::WebView;
+
+/// A request from a page for an operation of an authenticator. Once the operation is done,
+/// the embedder should call [`AuthenticatorRequest::success`] with its result or
+/// [`AuthenticatorRequest::failure`] with an error. Dropping the request without responding
+/// fails it with [`AuthenticatorError::NotAllowed`], which is also what the page sees when
+/// the user cancels the operation.
+pub struct AuthenticatorRequest<Parameters, Response: Serialize> {
+    origin: Url,
+    parameters: Parameters,
+    result_sender: IpcSender<Result<Response, AuthenticatorError>>,
+    response_sent: bool,
+}
+
+/// A request to create a new public key credential, from `navigator.credentials.create()`.
+pub type MakeCredentialRequest =
+    AuthenticatorRequest<MakeCredentialParameters, MakeCredentialResponse>;
+
+/// A request to sign a challenge with an existing public key credential, from
+/// `navigator.credentials.get()`.
+pub type GetAssertionRequest = AuthenticatorRequest<GetAssertionParameters, GetAssertionResponse>;
+
+impl<Parameters, Response: Serialize> AuthenticatorRequest<Parameters, Response> {
+    pub(crate) fn new(
+        origin: Url,
+        parameters: Parameters,
+        result_sender: IpcSender<Result<Response, AuthenticatorError>>,
+    ) -> Self {
+        Self {
+            origin,
+            parameters,
+            result_sender,
+            response_sent: false,
+        }
+    }
+
+    /// The URL of the page making the request. Its origin should be shown to the user when
+    /// asking for consent.
+    pub fn origin(&self) -> &Url {
+        &self.origin
+    }
+
+    pub fn parameters(&self) -> &Parameters {
+        &self.parameters
+    }
+
+    pub fn success(mut self, response: Response) {
+        let _ = self.result_sender.send(Ok(response));
+        self.response_sent = true;
+    }
+
+    pub fn failure(mut self, error: AuthenticatorError) {
+        let _ = self.result_sender.send(Err(error));
+        self.response_sent = true;
+    }
+}
+
+impl<Parameters, Response: Serialize> Drop for AuthenticatorRequest<Parameters, Response> {
+    fn drop(&mut self) {
+        if !self.response_sent {
+            let _ = self.result_sender.send(Err(AuthenticatorError::NotAllowed));
+        }
+    }
+}
+
+/// A delegate that performs the operations of the Web Authentication API with an
+/// authenticator, such as a CTAP2 security key or the passkeys of the platform. Servo
+/// validates the options of the page and computes the client data before calling the
+/// delegate, which is responsible for asking the user for consent and talking to the
+/// authenticator. By default no authenticator is available and every request fails.
+pub trait AuthenticatorDelegate {
+    /// Create a new credential with the
+    /// [authenticatorMakeCredential](https://w3c.github.io/webauthn/#sctn-op-make-cred)
+    /// operation.
+    fn make_credential(&self, _webview: WebView, _request: MakeCredentialRequest) {}
+
+    /// Sign the client data with an existing credential with the
+    /// [authenticatorGetAssertion](https://w3c.github.io/webauthn/#sctn-op-get-assertion)
+    /// operation.
+    fn get_assertion(&self, _webview: WebView, _request: GetAssertionRequest) {}
+
+    /// Whether a platform authenticator that is able to verify the user is available.
+    fn is_user_verifying_platform_authenticator_available(&self, _webview: WebView) -> bool {
+        false
+    }
+}
+
+pub(crate) struct DefaultAuthenticatorDelegate;
+
+impl AuthenticatorDelegate for DefaultAuthenticatorDelegate {}
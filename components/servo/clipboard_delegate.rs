@@ -2,6 +2,7 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use embedder_traits::ClipboardContents;
 use ipc_channel::ipc::IpcSender;
 
 use crate::WebView;
@@ -42,6 +43,42 @@ impl Drop for StringRequest {
     }
 }
 
+pub struct ClipboardContentsRequest {
+    pub(crate) result_sender: IpcSender<Result<ClipboardContents, String>>,
+    response_sent: bool,
+}
+
+impl ClipboardContentsRequest {
+    pub fn success(mut self, contents: ClipboardContents) {
+        let _ = self.result_sender.send(Ok(contents));
+        self.response_sent = true;
+    }
+
+    pub fn failure(mut self, message: String) {
+        let _ = self.result_sender.send(Err(message));
+        self.response_sent = true;
+    }
+}
+
+impl From<IpcSender<Result<ClipboardContents, String>>> for ClipboardContentsRequest {
+    fn from(result_sender: IpcSender<Result<ClipboardContents, String>>) -> Self {
+        Self {
+            result_sender,
+            response_sent: false,
+        }
+    }
+}
+
+impl Drop for ClipboardContentsRequest {
+    fn drop(&mut self) {
+        if !self.response_sent {
+            let _ = self
+                .result_sender
+                .send(Err("No response sent to request.".into()));
+        }
+    }
+}
+
 /// A delegate that is responsible for accessing the system clipboard. On Mac, Windows, and
 /// Linux if the `clipboard` feature is enabled, a default delegate is automatically used
 /// that implements clipboard support. An embedding application can override this delegate
@@ -55,6 +92,13 @@ pub trait ClipboardDelegate {
     /// [`StringRequest::failure`] with a failure message.
     fn get_text(&self, _webview: WebView, _request: StringRequest) {}
 
+    /// A request to get the HTML and file contents of the system clipboard, which are
+    /// exposed to the page when the user pastes. Once the contents are retrieved the
+    /// embedder should call [`ClipboardContentsRequest::success`] with them or
+    /// [`ClipboardContentsRequest::failure`] with a failure message. HTML is sanitized
+    /// by Servo before it is exposed to the page.
+    fn get_contents(&self, _webview: WebView, _request: ClipboardContentsRequest) {}
+
     /// A request to set the text contents of the system clipboard to `new_contents`.
     fn set_text(&self, _webview: WebView, _new_contents: String) {}
 }
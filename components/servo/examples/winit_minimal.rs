@@ -8,7 +8,8 @@ use std::rc::Rc;
 
 use euclid::{Scale, Size2D};
 use servo::{
-    RenderingContext, Servo, ServoBuilder, WebView, WebViewBuilder, WindowRenderingContext,
+    AuxiliaryWebViewFeatures, RenderingContext, Servo, ServoBuilder, WebView, WebViewBuilder,
+    WindowRenderingContext,
 };
 use tracing::warn;
 use url::Url;
@@ -53,7 +54,11 @@ impl ::servo::WebViewDelegate for AppState {
         self.window.request_redraw();
     }
 
-    fn request_open_auxiliary_webview(&self, parent_webview: WebView) -> Option<WebView> {
+    fn request_open_auxiliary_webview(
+        &self,
+        parent_webview: WebView,
+        _features: AuxiliaryWebViewFeatures,
+    ) -> Option<WebView> {
         let webview = WebViewBuilder::new_auxiliary(&self.servo)
             .hidpi_scale_factor(Scale::new(self.window.scale_factor() as f32))
             .delegate(parent_webview.delegate())
@@ -0,0 +1,74 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use embedder_traits::GeolocationPosition;
+use ipc_channel::ipc::IpcSender;
+
+use crate::WebView;
+
+/// A request from a page for the current position of the device. Once a position is
+/// acquired the embedder should call [`GeolocationRequest::success`] with it or
+/// [`GeolocationRequest::failure`] with a failure message. Dropping the request without
+/// responding is treated as a failure.
+pub struct GeolocationRequest {
+    pub(crate) result_sender: IpcSender<Result<GeolocationPosition, String>>,
+    enable_high_accuracy: bool,
+    response_sent: bool,
+}
+
+impl GeolocationRequest {
+    pub(crate) fn new(
+        enable_high_accuracy: bool,
+        result_sender: IpcSender<Result<GeolocationPosition, String>>,
+    ) -> Self {
+        Self {
+            result_sender,
+            enable_high_accuracy,
+            response_sent: false,
+        }
+    }
+
+    /// Whether the page asked for the most accurate position available, even if it is
+    /// slower to acquire or uses more power.
+    pub fn enable_high_accuracy(&self) -> bool {
+        self.enable_high_accuracy
+    }
+
+    pub fn success(mut self, position: GeolocationPosition) {
+        let _ = self.result_sender.send(Ok(position));
+        self.response_sent = true;
+    }
+
+    pub fn failure(mut self, message: String) {
+        let _ = self.result_sender.send(Err(message));
+        self.response_sent = true;
+    }
+}
+
+impl Drop for GeolocationRequest {
+    fn drop(&mut self) {
+        if !self.response_sent {
+            let _ = self
+                .result_sender
+                .send(Err("No response sent to request.".into()));
+        }
+    }
+}
+
+/// A delegate that is responsible for providing the position of the device to the
+/// Geolocation API. Permission to use the position is requested from the
+/// [`crate::WebViewDelegate`] before this delegate is asked for a position, so an
+/// implementation doesn't need to prompt the user.
+///
+/// Pages watching the position issue a new request as soon as the previous one is answered,
+/// so an implementation may hold on to a request until the position changes. By default no
+/// position source is available and every request fails.
+pub trait GeolocationDelegate {
+    /// A request to acquire the current position of the device.
+    fn get_position(&self, _webview: WebView, _request: GeolocationRequest) {}
+}
+
+pub(crate) struct DefaultGeolocationDelegate;
+
+impl GeolocationDelegate for DefaultGeolocationDelegate {}
@@ -17,7 +17,9 @@
 //! `Servo` is fed events from a generic type that implements the
 //! `WindowMethods` trait.
 
+mod authenticator_delegate;
 mod clipboard_delegate;
+mod geolocation_delegate;
 mod javascript_evaluator;
 mod proxies;
 mod responders;
@@ -40,7 +42,7 @@ use bluetooth::BluetoothThreadFactory;
 #[cfg(feature = "bluetooth")]
 use bluetooth_traits::BluetoothRequest;
 use canvas_traits::webgl::{GlType, WebGLThreads};
-use clipboard_delegate::StringRequest;
+use clipboard_delegate::{ClipboardContentsRequest, StringRequest};
 pub use compositing::WebRenderDebugOption;
 use compositing::{IOCompositor, InitialCompositorState};
 pub use compositing_traits::rendering_context::{
@@ -126,6 +128,10 @@ pub use {
 #[cfg(feature = "bluetooth")]
 pub use {bluetooth, bluetooth_traits};
 
+pub use crate::authenticator_delegate::{
+    AuthenticatorDelegate, AuthenticatorRequest, GetAssertionRequest, MakeCredentialRequest,
+};
+pub use crate::geolocation_delegate::{GeolocationDelegate, GeolocationRequest};
 use crate::proxies::ConstellationProxy;
 use crate::responders::ServoErrorChannel;
 pub use crate::servo_delegate::{ServoDelegate, ServoError};
@@ -133,7 +139,8 @@ use crate::webrender_api::FrameReadyParams;
 pub use crate::webview::{WebView, WebViewBuilder};
 pub use crate::webview_delegate::{
     AllowOrDenyRequest, AuthenticationRequest, ColorPicker, FormControl, NavigationRequest,
-    PermissionRequest, SelectElement, WebResourceLoad, WebViewDelegate,
+    PasswordCredentialChooser, PasswordCredentialSaveRequest, PermissionRequest, PrintRequest,
+    SelectElement, WebResourceLoad, WebViewDelegate,
 };
 
 #[cfg(feature = "media-gstreamer")]
@@ -637,6 +644,15 @@ impl Servo {
             .send(EmbedderToConstellationMessage::CreateMemoryReport(snd));
     }
 
+    /// Replace the built-in HSTS preload list with a newer version, in the format produced by
+    /// `./mach update-hsts-preload`.
+    pub fn update_hsts_preload_list(&self, preload_list: Vec<u8>) {
+        self.constellation_proxy
+            .send(EmbedderToConstellationMessage::UpdateHstsPreloadList(
+                preload_list,
+            ));
+    }
+
     pub fn start_shutting_down(&self) {
         if self.shutdown_state.get() != ShutdownState::NotShuttingDown {
             warn!("Requested shutdown while already shutting down");
@@ -714,11 +730,11 @@ impl Servo {
                     webview.delegate().request_navigation(webview, request);
                 }
             },
-            EmbedderMsg::AllowOpeningWebView(webview_id, response_sender) => {
+            EmbedderMsg::AllowOpeningWebView(webview_id, features, response_sender) => {
                 if let Some(webview) = self.get_webview_handle(webview_id) {
                     let webview_id_and_viewport_details = webview
                         .delegate()
-                        .request_open_auxiliary_webview(webview)
+                        .request_open_auxiliary_webview(webview, features)
                         .map(|webview| (webview.id(), webview.viewport_details()));
                     let _ = response_sender.send(webview_id_and_viewport_details);
                 }
@@ -782,11 +798,91 @@ impl Servo {
                         .get_text(webview, StringRequest::from(result_sender));
                 }
             },
+            EmbedderMsg::GetClipboardContents(webview_id, result_sender) => {
+                if let Some(webview) = self.get_webview_handle(webview_id) {
+                    webview
+                        .clipboard_delegate()
+                        .get_contents(webview, ClipboardContentsRequest::from(result_sender));
+                }
+            },
             EmbedderMsg::SetClipboardText(webview_id, string) => {
                 if let Some(webview) = self.get_webview_handle(webview_id) {
                     webview.clipboard_delegate().set_text(webview, string);
                 }
             },
+            EmbedderMsg::GetGeolocationPosition(
+                webview_id,
+                enable_high_accuracy,
+                result_sender,
+            ) => {
+                if let Some(webview) = self.get_webview_handle(webview_id) {
+                    webview.geolocation_delegate().get_position(
+                        webview,
+                        GeolocationRequest::new(enable_high_accuracy, result_sender),
+                    );
+                }
+            },
+            EmbedderMsg::MakeCredential(webview_id, url, parameters, result_sender) => {
+                if let Some(webview) = self.get_webview_handle(webview_id) {
+                    webview.authenticator_delegate().make_credential(
+                        webview,
+                        MakeCredentialRequest::new(url.into_url(), parameters, result_sender),
+                    );
+                }
+            },
+            EmbedderMsg::GetAssertion(webview_id, url, parameters, result_sender) => {
+                if let Some(webview) = self.get_webview_handle(webview_id) {
+                    webview.authenticator_delegate().get_assertion(
+                        webview,
+                        GetAssertionRequest::new(url.into_url(), parameters, result_sender),
+                    );
+                }
+            },
+            EmbedderMsg::IsUserVerifyingPlatformAuthenticatorAvailable(
+                webview_id,
+                result_sender,
+            ) => {
+                let available = self.get_webview_handle(webview_id).is_some_and(|webview| {
+                    webview
+                        .authenticator_delegate()
+                        .is_user_verifying_platform_authenticator_available(webview)
+                });
+                let _ = result_sender.send(available);
+            },
+            EmbedderMsg::SelectPasswordCredential(
+                webview_id,
+                url,
+                credentials,
+                response_sender,
+            ) => {
+                if let Some(webview) = self.get_webview_handle(webview_id) {
+                    let credential_chooser = PasswordCredentialChooser::new(
+                        url.into_url(),
+                        credentials,
+                        response_sender,
+                        self.servo_errors.sender(),
+                    );
+                    webview
+                        .delegate()
+                        .show_password_credential_chooser(webview, credential_chooser);
+                }
+            },
+            EmbedderMsg::SavePasswordCredential(webview_id, url, credential, response_sender) => {
+                if let Some(webview) = self.get_webview_handle(webview_id) {
+                    let save_request = PasswordCredentialSaveRequest {
+                        url: url.into_url(),
+                        credential,
+                        allow_deny_request: AllowOrDenyRequest::new(
+                            response_sender,
+                            AllowOrDeny::Deny,
+                            self.servo_errors.sender(),
+                        ),
+                    };
+                    webview
+                        .delegate()
+                        .request_save_password_credential(webview, save_request);
+                }
+            },
             EmbedderMsg::SetCursor(webview_id, cursor) => {
                 if let Some(webview) = self.get_webview_handle(webview_id) {
                     webview.set_cursor(cursor);
@@ -896,9 +992,10 @@ impl Servo {
                         .request_authentication(webview, authentication_request);
                 }
             },
-            EmbedderMsg::PromptPermission(webview_id, requested_feature, response_sender) => {
+            EmbedderMsg::PromptPermission(webview_id, url, requested_feature, response_sender) => {
                 if let Some(webview) = self.get_webview_handle(webview_id) {
                     let permission_request = PermissionRequest {
+                        url: url.into_url(),
                         requested_feature,
                         allow_deny_request: AllowOrDenyRequest::new(
                             response_sender,
@@ -927,6 +1024,23 @@ impl Servo {
                     webview.delegate().hide_ime(webview);
                 }
             },
+            EmbedderMsg::UpdateIMECaretRect(webview_id, caret_rect) => {
+                if let Some(webview) = self.get_webview_handle(webview_id) {
+                    webview
+                        .delegate()
+                        .update_ime_caret_rect(webview, caret_rect);
+                }
+            },
+            EmbedderMsg::StartDrag(webview_id, data) => {
+                if let Some(webview) = self.get_webview_handle(webview_id) {
+                    webview.delegate().notify_drag_started(webview, data);
+                }
+            },
+            EmbedderMsg::EndDrag(webview_id) => {
+                if let Some(webview) = self.get_webview_handle(webview_id) {
+                    webview.delegate().notify_drag_ended(webview);
+                }
+            },
             EmbedderMsg::ReportProfile(_items) => {},
             EmbedderMsg::MediaSessionEvent(webview_id, media_session_event) => {
                 if let Some(webview) = self.get_webview_handle(webview_id) {
@@ -983,6 +1097,12 @@ impl Servo {
                     None => self.delegate().show_notification(notification),
                 }
             },
+            EmbedderMsg::RequestPrint(webview_id, response_sender) => {
+                if let Some(webview) = self.get_webview_handle(webview_id) {
+                    let request = PrintRequest::new(response_sender, self.servo_errors.sender());
+                    webview.delegate().request_print(webview, request);
+                }
+            },
             EmbedderMsg::ShowFormControl(webview_id, position, form_control) => {
                 if let Some(webview) = self.get_webview_handle(webview_id) {
                     let form_control = match form_control {
@@ -1148,6 +1268,7 @@ fn create_constellation(
         mem_profiler_chan.clone(),
         embedder_proxy.clone(),
         config_dir,
+        opts.http_disk_cache_size,
         opts.certificate_path.clone(),
         opts.ignore_certificate_errors,
         Arc::new(protocols),
@@ -13,12 +13,16 @@ mod common;
 
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 use anyhow::ensure;
 use common::{ServoTest, run_api_tests};
+use servo::ipc_channel::ipc;
 use servo::{
-    JSValue, JavaScriptEvaluationError, LoadStatus, Theme, WebView, WebViewBuilder, WebViewDelegate,
+    JSValue, JavaScriptEvaluationError, LoadStatus, PrintRequest, Theme, WebView, WebViewBuilder,
+    WebViewDelegate,
 };
+use servo_config::prefs;
 use url::Url;
 
 #[derive(Default)]
@@ -164,11 +168,433 @@ fn test_theme_change(servo_test: &ServoTest) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+fn test_ecdsa_sign_and_verify(servo_test: &ServoTest) -> Result<(), anyhow::Error> {
+    let webview = WebViewBuilder::new(servo_test.servo())
+        .url(Url::parse("data:text/html,").unwrap())
+        .build();
+
+    // Every pair of curve and hash that can be signed with must verify its own signatures, the
+    // other pairs must be rejected, and signatures of pairs that are only verified as DER must
+    // still be accepted as r || s.
+    let _ = evaluate_javascript(
+        servo_test,
+        webview.clone(),
+        "window.ecdsaResults = undefined;
+        (async () => {
+            const subtle = crypto.subtle;
+            const message = new TextEncoder().encode('servo');
+            const hex = string => Uint8Array.from(string.match(/../g), byte => parseInt(byte, 16));
+            const results = [];
+            for (const [namedCurve, hash, signs] of [
+                ['P-256', 'SHA-256', true], ['P-256', 'SHA-384', false], ['P-256', 'SHA-1', false],
+                ['P-384', 'SHA-384', true], ['P-384', 'SHA-256', false], ['P-384', 'SHA-512', false],
+                ['P-521', 'SHA-256', true], ['P-521', 'SHA-384', true], ['P-521', 'SHA-512', true],
+            ]) {
+                const { privateKey, publicKey } = await subtle.generateKey(
+                    { name: 'ECDSA', namedCurve }, false, ['sign', 'verify']);
+                const algorithm = { name: 'ECDSA', hash };
+                try {
+                    const signature = await subtle.sign(algorithm, privateKey, message);
+                    results.push(signs &&
+                        await subtle.verify(algorithm, publicKey, signature, message) &&
+                        !(await subtle.verify(algorithm, publicKey, signature, hex('00'))));
+                } catch (error) {
+                    results.push(!signs && error.name == 'NotSupportedError');
+                }
+            }
+            for (const [namedCurve, hash, point, signature] of [
+                ['P-256', 'SHA-384',
+                 '0411ee9313d928649d24d55d5534ef3190b041482d6b41c30af8364911c484a177' +
+                 '9be9ab6786ad185c89dbaa46bfb216f296ff41ab978538231b4e0984a59bb5a3',
+                 'a75d979cbe8bc1cd6f62846c5d600072f2b7c96b8935d6d0a4c22ff847ced091' +
+                 '748adcf0382897802275e488c97b1e7ae2f135157f766df358747b37504e0340'],
+                ['P-384', 'SHA-256',
+                 '044e7536ddbbb7cf2574905e615b6d18c3ce560b13defc8048f969342711df82bb' +
+                 '1f61c947e1c1066a7d72f96aba640461ea09964b881d92ac4e5d01f46cffcdbc9e' +
+                 'c958c657a8eb544a9ef50a0297505ace13d6c363d2a8a5cde61e6ac7bece28',
+                 'b9c3ffac10dd5013213712a59ad1f54e25b226346f37ca982e0fd0864302f6c1' +
+                 '8c93fd05140f3b7af5bd065d001a5c201403849d420e464435ef24375048139d' +
+                 '7542ad0d34c820e26023f571415e974ddb5d15ec0bef39ccfd3e1fca79c89725'],
+            ]) {
+                const publicKey = await subtle.importKey(
+                    'raw', hex(point), { name: 'ECDSA', namedCurve }, false, ['verify']);
+                const algorithm = { name: 'ECDSA', hash };
+                results.push(
+                    await subtle.verify(algorithm, publicKey, hex(signature), message) &&
+                    !(await subtle.verify(algorithm, publicKey, hex(signature).slice(1), message)));
+            }
+            return results.every(result => result);
+        })().then(result => ecdsaResults = result, () => ecdsaResults = false);",
+    );
+
+    let mut result = Ok(JSValue::Undefined);
+    for _ in 0..100 {
+        result = evaluate_javascript(servo_test, webview.clone(), "ecdsaResults");
+        if result != Ok(JSValue::Undefined) {
+            break;
+        }
+    }
+    ensure!(result == Ok(JSValue::Boolean(true)));
+
+    Ok(())
+}
+
+fn test_transfer_offscreen_canvas_with_placeholder(
+    servo_test: &ServoTest,
+) -> Result<(), anyhow::Error> {
+    // A canvas transferred to a worker after `transferControlToOffscreen()` detaches the
+    // original `OffscreenCanvas`, and the worker's changes reach the placeholder element.
+    let webview = WebViewBuilder::new(servo_test.servo())
+        .url(
+            Url::parse(
+                "data:text/html,<canvas id=placeholder width=10 height=10></canvas><script>
+                    const worker = new Worker(URL.createObjectURL(new Blob([`
+                        onmessage = ({ data }) => {
+                            data.width = 37;
+                            data.height = 23;
+                            const context = data.getContext('2d');
+                            context.fillStyle = 'green';
+                            context.fillRect(0, 0, 37, 23);
+                        };
+                    `])));
+                    const offscreen = document.getElementById('placeholder')
+                        .transferControlToOffscreen();
+                    worker.postMessage(offscreen, [offscreen]);
+                    window.detached = (() => {
+                        try {
+                            offscreen.getContext('2d');
+                            return false;
+                        } catch (error) {
+                            return error.name == 'InvalidStateError';
+                        }
+                    })();
+fn test_frozen_webview_runs_no_timers_or_animation_frames(
+    servo_test: &ServoTest,
+) -> Result<(), anyhow::Error> {
+    let webview = WebViewBuilder::new(servo_test.servo())
+        .url(
+            Url::parse(
+                "data:text/html,<script>
+                    window.callbacks = [];
+                    window.frozenAt = -1;
+                    window.resumedAt = -1;
+                    document.addEventListener('freeze', () => frozenAt = performance.now());
+                    document.addEventListener('resume', () => resumedAt = performance.now());
+                    setInterval(() => callbacks.push(performance.now()), 5);
+                    function tick() {
+                        callbacks.push(performance.now());
+                        requestAnimationFrame(tick);
+                    }
+                    requestAnimationFrame(tick);
+#[derive(Default)]
+struct PrintDelegate {
+    print_request: RefCell<Option<PrintRequest>>,
+}
+
+impl WebViewDelegate for PrintDelegate {
+    fn request_print(&self, _webview: WebView, request: PrintRequest) {
+        *self.print_request.borrow_mut() = Some(request);
+    }
+}
+
+fn test_print_waits_for_load_and_embedder(servo_test: &ServoTest) -> Result<(), anyhow::Error> {
+    let delegate = Rc::new(PrintDelegate::default());
+    let webview = WebViewBuilder::new(servo_test.servo())
+        .delegate(delegate.clone())
+        .url(
+            Url::parse(
+                "data:text/html,<script>
+                    window.printEvents = [];
+                    window.beforePrintAt = -1;
+                    window.afterPrintAt = -1;
+                    addEventListener('load', () => printEvents.push('load'));
+                    addEventListener('beforeprint', () => {
+                        printEvents.push('beforeprint');
+                        beforePrintAt = performance.now();
+                    });
+                    addEventListener('afterprint', () => {
+                        printEvents.push('afterprint');
+                        afterPrintAt = performance.now();
+                    });
+                    print();
+                </script>",
+            )
+            .unwrap(),
+        )
+        .build();
+
+    let mut result = Ok(JSValue::Undefined);
+    for _ in 0..100 {
+        result = evaluate_javascript(
+            servo_test,
+            webview.clone(),
+            "[detached, placeholder.width, placeholder.height].join(',')",
+        );
+        if result == Ok(JSValue::String("true,37,23".into())) {
+            break;
+        }
+    }
+    ensure!(result == Ok(JSValue::String("true,37,23".into())));
+
+    Ok(())
+}
+
+fn test_memory_report_per_pipeline(servo_test: &ServoTest) -> Result<(), anyhow::Error> {
+    let webview = WebViewBuilder::new(servo_test.servo())
+        .url(Url::parse("data:text/html,<p>servo</p><div><span></span></div>").unwrap())
+        .build();
+
+    let load_webview = webview.clone();
+    servo_test.spin(move || Ok(load_webview.load_status() != LoadStatus::Complete))?;
+
+    let (sender, receiver) = ipc::channel()?;
+    servo_test.servo().create_memory_report(sender);
+    let result = Rc::new(RefCell::new(None));
+    let received = result.clone();
+    servo_test.spin(move || {
+        if let Ok(report) = receiver.try_recv() {
+            *received.borrow_mut() = Some(report);
+        }
+        Ok(received.borrow().is_none())
+    })?;
+
+    // The DOM tree and the layout of the pipeline are reported under the same prefix,
+    // which identifies the pipeline and its URL.
+    let result = result.borrow_mut().take().unwrap();
+    let reports: Vec<_> = result
+        .results
+        .iter()
+        .flat_map(|process| &process.reports)
+        .filter(|report| report.path[0].starts_with("pipeline("))
+        .collect();
+    let dom_tree = reports
+        .iter()
+        .find(|report| report.path[1..] == ["dom-tree"]);
+    ensure!(dom_tree.is_some_and(|report| report.size > 0));
+    let prefix = &dom_tree.unwrap().path[0];
+    ensure!(prefix.contains("url(data:text/html,"));
+    ensure!(reports.iter().any(|report| {
+        &report.path[0] == prefix && report.path[1..] == ["layout-thread", "stylist"]
+    }));
+    webview.set_frozen(true);
+    let frozen_at = Instant::now();
+    servo_test.spin(move || Ok(frozen_at.elapsed() < Duration::from_millis(200)))?;
+    webview.set_frozen(false);
+    // The freeze and resume events are fired before the timers and animation frames are
+    // suspended and after they are resumed, so no callback may have run in between.
+    let result = evaluate_javascript(
+        servo_test,
+        webview.clone(),
+        "frozenAt >= 0 && resumedAt > frozenAt && \
+            !callbacks.some(time => time > frozenAt && time < resumedAt)",
+    // Printing is deferred until the document is ready for post-load tasks.
+    let request_delegate = delegate.clone();
+    servo_test.spin(move || Ok(request_delegate.print_request.borrow().is_none()))?;
+    // The page stays blocked, so afterprint can not fire, until the embedder is done.
+    let requested_at = Instant::now();
+    servo_test.spin(move || Ok(requested_at.elapsed() < Duration::from_millis(100)))?;
+    let request = delegate.print_request.borrow_mut().take();
+    ensure!(request.is_some());
+    request.unwrap().finish();
+        "printEvents.join(',') == 'load,beforeprint,afterprint' && \
+            afterPrintAt - beforePrintAt >= 100",
+    );
+    ensure!(result == Ok(JSValue::Boolean(true)));
+
+    Ok(())
+}
+
+fn test_cascade_layers(servo_test: &ServoTest) -> Result<(), anyhow::Error> {
+    // Layers are ordered by their first declaration, unlayered rules win over layered ones
+    // whatever their specificity, important declarations cascade in the reverse layer order,
+    // and imported style sheets can be put into a layer.
+    let webview = WebViewBuilder::new(servo_test.servo())
+        .url(
+            Url::parse(
+                "data:text/html,<style>
+                    @import url('data:text/css,.imported.x { color: rgb(0, 0, 9) }') layer(base);
+                    @layer base, theme;
+                    @layer theme { p { color: rgb(0, 0, 1) } }
+                    @layer base { p { color: rgb(0, 0, 2) } .unlayered { color: rgb(0, 0, 3) } }
+                    .unlayered { color: rgb(0, 0, 4) }
+                    @layer base { .important { color: rgb(0, 0, 5) !important } }
+                    @layer theme { .important { color: rgb(0, 0, 6) !important } }
+                    .imported { color: rgb(0, 0, 7) }
+                </style>
+                <p id=layered></p>
+                <p id=unlayered class=unlayered></p>
+                <p id=important class=important></p>
+                <p id=imported class='imported x'></p>",
+            )
+            .unwrap(),
+        )
+        .build();
+
+    let load_webview = webview.clone();
+    servo_test.spin(move || Ok(load_webview.load_status() != LoadStatus::Complete))?;
+
+    let result = evaluate_javascript(
+        servo_test,
+        webview.clone(),
+        "const rules = document.styleSheets[0].cssRules;
+        ['layered', 'unlayered', 'important', 'imported']
+            .map(id => getComputedStyle(document.getElementById(id)).color)
+            .concat([
+                rules[0].layerName,
+                rules[1] instanceof CSSLayerStatementRule && rules[1].nameList.join(' '),
+                rules[2] instanceof CSSLayerBlockRule && rules[2].name,
+            ]).join(';')",
+    );
+
+    ensure!(
+        result ==
+            Ok(JSValue::String(
+                "rgb(0, 0, 1);rgb(0, 0, 4);rgb(0, 0, 5);rgb(0, 0, 7);base;base theme;theme".into()
+            ))
+    );
+
+    Ok(())
+}
+
+fn test_has_selector_invalidation(servo_test: &ServoTest) -> Result<(), anyhow::Error> {
+    let original_preferences = prefs::get().clone();
+    let mut preferences = original_preferences.clone();
+    preferences.layout_css_has_selector_enabled = true;
+    prefs::set(preferences);
+
+    // The anchors cover the ancestor direction (`.parent` and `.outer`), the sibling
+    // direction (`.anchor`) and the sibling of an ancestor (`.a`).
+    let webview = WebViewBuilder::new(servo_test.servo())
+        .url(
+            Url::parse(
+                "data:text/html,<style>
+                    * { color: rgb(0, 0, 0) }
+                    .parent:has(> .child) { color: rgb(0, 0, 1) }
+                    .anchor:has(+ .next) { color: rgb(0, 0, 2) }
+                    .outer:has(.deep) { color: rgb(0, 0, 3) }
+                    .a:has(+ .b .c) { color: rgb(0, 0, 4) }
+                </style>
+                <div id=parent class=parent></div>
+                <div id=parent2 class=parent><span id=existing></span></div>
+                <span id=anchor class=anchor></span><span></span>
+                <div id=outer class=outer><div><p id=outer-inner></p></div></div>
+                <div id=a class=a></div><div id=b class=b></div>",
+            )
+            .unwrap(),
+        )
+        .build();
+
+    let result = evaluate_javascript(
+        servo_test,
+        webview.clone(),
+        "const element = id => document.getElementById(id);
+        const create = (tag, className) => {
+            const created = document.createElement(tag);
+            created.className = className;
+            return created;
+        };
+        const colors = () => ['parent', 'parent2', 'anchor', 'outer', 'a']
+            .map(id => getComputedStyle(element(id)).color).join(',');
+        const results = [colors()];
+
+        const child = create('span', 'child');
+        const next = create('span', 'next');
+        const deep = create('i', 'deep');
+        const c = create('i', 'c');
+        element('parent').appendChild(child);
+        element('anchor').after(next);
+        element('outer-inner').appendChild(deep);
+        element('b').appendChild(c);
+        element('existing').classList.add('child');
+        results.push(colors());
+
+        for (const inserted of [child, next, deep, c]) {
+            inserted.remove();
+        }
+        element('existing').classList.remove('child');
+        results.push(colors());
+        results.join(';')",
+    );
+    prefs::set(original_preferences);
+
+    ensure!(
+        result ==
+            Ok(JSValue::String(
+                "rgb(0, 0, 0),rgb(0, 0, 0),rgb(0, 0, 0),rgb(0, 0, 0),rgb(0, 0, 0);\
+                rgb(0, 0, 1),rgb(0, 0, 1),rgb(0, 0, 2),rgb(0, 0, 3),rgb(0, 0, 4);\
+                rgb(0, 0, 0),rgb(0, 0, 0),rgb(0, 0, 0),rgb(0, 0, 0),rgb(0, 0, 0)"
+                    .into()
+            ))
+    );
+
+    Ok(())
+}
+
+fn test_scope_rule_cascade(servo_test: &ServoTest) -> Result<(), anyhow::Error> {
+    let original_preferences = prefs::get().clone();
+    let mut preferences = original_preferences.clone();
+    preferences.layout_css_at_scope_enabled = true;
+    prefs::set(preferences);
+
+    // Scoped rules only match within their scope root and above their scope limit, and the
+    // nearest scope root wins over both the order of appearance and unscoped rules.
+    let webview = WebViewBuilder::new(servo_test.servo())
+        .url(
+            Url::parse(
+                "data:text/html,<style>
+                    @scope (.dark) { p { color: rgb(0, 0, 1) } }
+                    @scope (.light) { p { color: rgb(0, 0, 2) } }
+                    @scope (.card) to (.content) { span { color: rgb(0, 0, 3) } }
+                    p, span { color: rgb(0, 0, 4) }
+                </style>
+                <p id=unscoped></p>
+                <div class=light><div class=dark><p id=nearest-dark></p></div></div>
+                <div class=dark><div class=light><p id=nearest-light></p></div></div>
+                <div class=card>
+                    <span id=in-scope></span>
+                    <div class=content><span id=below-limit></span></div>
+                </div>",
+            )
+            .unwrap(),
+        )
+        .build();
+
+    let result = evaluate_javascript(
+        servo_test,
+        webview.clone(),
+        "const rule = document.styleSheets[0].cssRules[2];
+        [
+            'unscoped', 'nearest-dark', 'nearest-light', 'in-scope', 'below-limit'
+        ].map(id => getComputedStyle(document.getElementById(id)).color)
+            .concat([rule instanceof CSSScopeRule, rule.start, rule.end]).join(';')",
+    );
+    prefs::set(original_preferences);
+
+    ensure!(
+        result ==
+            Ok(JSValue::String(
+                "rgb(0, 0, 4);rgb(0, 0, 1);rgb(0, 0, 2);rgb(0, 0, 3);rgb(0, 0, 4);\
+                true;.card;.content"
+                    .into()
+            ))
+    );
+
+    Ok(())
+}
+
 fn main() {
     run_api_tests!(
         test_create_webview,
         test_evaluate_javascript_basic,
         test_theme_change,
+        test_ecdsa_sign_and_verify,
+        test_transfer_offscreen_canvas_with_placeholder,
+        test_memory_report_per_pipeline,
+        test_frozen_webview_runs_no_timers_or_animation_frames,
+        test_cascade_layers,
+        test_has_selector_invalidation,
+        test_scope_rule_cascade,
+        test_print_waits_for_load_and_embedder,
         // This test needs to be last, as it tests creating and dropping
         // a WebView right before shutdown.
         test_create_webview_and_immediately_drop_webview_before_shutdown
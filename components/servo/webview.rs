@@ -22,7 +22,9 @@ use url::Url;
 use webrender_api::ScrollLocation;
 use webrender_api::units::{DeviceIntPoint, DevicePixel, DeviceRect};
 
+use crate::authenticator_delegate::{AuthenticatorDelegate, DefaultAuthenticatorDelegate};
 use crate::clipboard_delegate::{ClipboardDelegate, DefaultClipboardDelegate};
+use crate::geolocation_delegate::{DefaultGeolocationDelegate, GeolocationDelegate};
 use crate::javascript_evaluator::JavaScriptEvaluator;
 use crate::webview_delegate::{DefaultWebViewDelegate, WebViewDelegate};
 use crate::{ConstellationProxy, Servo, WebRenderDebugOption};
@@ -76,6 +78,8 @@ pub(crate) struct WebViewInner {
     pub(crate) compositor: Rc<RefCell<IOCompositor>>,
     pub(crate) delegate: Rc<dyn WebViewDelegate>,
     pub(crate) clipboard_delegate: Rc<dyn ClipboardDelegate>,
+    pub(crate) geolocation_delegate: Rc<dyn GeolocationDelegate>,
+    pub(crate) authenticator_delegate: Rc<dyn AuthenticatorDelegate>,
     javascript_evaluator: Rc<RefCell<JavaScriptEvaluator>>,
     /// The rectangle of the [`WebView`] in device pixels, which is the viewport.
     rect: DeviceRect,
@@ -119,6 +123,8 @@ impl WebView {
             compositor: servo.compositor.clone(),
             delegate: builder.delegate,
             clipboard_delegate: Rc::new(DefaultClipboardDelegate),
+            geolocation_delegate: Rc::new(DefaultGeolocationDelegate),
+            authenticator_delegate: Rc::new(DefaultAuthenticatorDelegate),
             javascript_evaluator: servo.javascript_evaluator.clone(),
             rect: DeviceRect::from_origin_and_size(Point2D::origin(), size),
             hidpi_scale_factor: builder.hidpi_scale_factor,
@@ -207,6 +213,22 @@ impl WebView {
         self.inner_mut().clipboard_delegate = delegate;
     }
 
+    pub fn geolocation_delegate(&self) -> Rc<dyn GeolocationDelegate> {
+        self.inner().geolocation_delegate.clone()
+    }
+
+    pub fn set_geolocation_delegate(&self, delegate: Rc<dyn GeolocationDelegate>) {
+        self.inner_mut().geolocation_delegate = delegate;
+    }
+
+    pub fn authenticator_delegate(&self) -> Rc<dyn AuthenticatorDelegate> {
+        self.inner().authenticator_delegate.clone()
+    }
+
+    pub fn set_authenticator_delegate(&self, delegate: Rc<dyn AuthenticatorDelegate>) {
+        self.inner_mut().authenticator_delegate = delegate;
+    }
+
     pub fn id(&self) -> WebViewId {
         self.inner().id
     }
@@ -530,6 +552,18 @@ impl WebView {
             ));
     }
 
+    /// Freeze or resume this [`WebView`]. The timers, tasks and media of the documents of a
+    /// frozen [`WebView`] are suspended until it is resumed, which lets embedders keep
+    /// background [`WebView`]s alive cheaply.
+    pub fn set_frozen(&self, frozen: bool) {
+        self.inner()
+            .constellation_proxy
+            .send(EmbedderToConstellationMessage::SetWebViewFrozen(
+                self.id(),
+                frozen,
+            ));
+    }
+
     pub fn toggle_webrender_debugging(&self, debugging: WebRenderDebugOption) {
         self.inner()
             .compositor
@@ -7,10 +7,11 @@ use std::path::PathBuf;
 use base::id::PipelineId;
 use constellation_traits::EmbedderToConstellationMessage;
 use embedder_traits::{
-    AllowOrDeny, AuthenticationResponse, ContextMenuResult, Cursor, FilterPattern, FocusId,
-    GamepadHapticEffectType, InputMethodType, KeyboardEvent, LoadStatus, MediaSessionEvent,
-    Notification, PermissionFeature, RgbColor, ScreenGeometry, SelectElementOptionOrOptgroup,
-    SimpleDialog, TraversalId, WebResourceRequest, WebResourceResponse, WebResourceResponseMsg,
+    AllowOrDeny, AuthenticationResponse, AuxiliaryWebViewFeatures, ContextMenuResult, Cursor,
+    DragData, FilterPattern, FocusId, GamepadHapticEffectType, InputMethodType, KeyboardEvent,
+    LoadStatus, MediaSessionEvent, Notification, PasswordCredentialInfo, PermissionFeature,
+    RgbColor, ScreenGeometry, SelectElementOptionOrOptgroup, SimpleDialog, TraversalId,
+    WebResourceRequest, WebResourceResponse, WebResourceResponseMsg,
 };
 use ipc_channel::ipc::IpcSender;
 use serde::Serialize;
@@ -107,11 +108,18 @@ impl<T: Serialize> Drop for IpcResponder<T> {
 /// either by reading a cached value or querying the user for permission via the user
 /// interface.
 pub struct PermissionRequest {
+    pub(crate) url: Url,
     pub(crate) requested_feature: PermissionFeature,
     pub(crate) allow_deny_request: AllowOrDenyRequest,
 }
 
 impl PermissionRequest {
+    /// The URL of the document or worker requesting the permission. The decision
+    /// applies to every page with the same origin.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
     pub fn feature(&self) -> PermissionFeature {
         self.requested_feature
     }
@@ -196,6 +204,110 @@ impl AuthenticationRequest {
     }
 }
 
+/// A request from a page for one of its saved password credentials, made by
+/// `navigator.credentials.get()`. The embedder should let the user choose one of the
+/// [`PasswordCredentialChooser::credentials`] or decline. Dropping the chooser without
+/// responding declines the request.
+pub struct PasswordCredentialChooser {
+    url: Url,
+    credentials: Vec<PasswordCredentialInfo>,
+    responder: IpcResponder<Option<usize>>,
+    error_sender: ServoErrorSender,
+}
+
+impl PasswordCredentialChooser {
+    pub(crate) fn new(
+        url: Url,
+        credentials: Vec<PasswordCredentialInfo>,
+        response_sender: IpcSender<Option<usize>>,
+        error_sender: ServoErrorSender,
+    ) -> Self {
+        Self {
+            url,
+            credentials,
+            responder: IpcResponder::new(response_sender, None),
+            error_sender,
+        }
+    }
+
+    /// The URL of the page requesting a credential.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// The credentials saved for the origin of the page.
+    pub fn credentials(&self) -> &[PasswordCredentialInfo] {
+        &self.credentials
+    }
+
+    /// Hand the credential at `index` in [`PasswordCredentialChooser::credentials`] to the page.
+    pub fn select(mut self, index: usize) {
+        if let Err(error) = self.responder.send(Some(index)) {
+            self.error_sender.raise_response_send_error(error);
+        }
+    }
+
+    /// Decline to hand any credential to the page.
+    pub fn dismiss(mut self) {
+        if let Err(error) = self.responder.send(None) {
+            self.error_sender.raise_response_send_error(error);
+        }
+    }
+}
+
+/// A request from a page to save a password credential, made by
+/// `navigator.credentials.store()`. The credential is only saved if the embedder allows it.
+/// Dropping the request without responding denies it.
+pub struct PasswordCredentialSaveRequest {
+    pub(crate) url: Url,
+    pub(crate) credential: PasswordCredentialInfo,
+    pub(crate) allow_deny_request: AllowOrDenyRequest,
+}
+
+impl PasswordCredentialSaveRequest {
+    /// The URL of the page storing the credential.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// The credential to save. Its password is not exposed to the embedder.
+    pub fn credential(&self) -> &PasswordCredentialInfo {
+        &self.credential
+    }
+
+    pub fn allow(self) {
+        self.allow_deny_request.allow();
+    }
+
+    pub fn deny(self) {
+        self.allow_deny_request.deny();
+    }
+}
+
+/// A request from a page to be printed, made by `window.print()`. The page is blocked, and
+/// only fires its `afterprint` event, once the request is finished, which happens when it
+/// is dropped.
+pub struct PrintRequest {
+    responder: IpcResponder<()>,
+    error_sender: ServoErrorSender,
+}
+
+impl PrintRequest {
+    pub(crate) fn new(response_sender: IpcSender<()>, error_sender: ServoErrorSender) -> Self {
+        Self {
+            responder: IpcResponder::new(response_sender, ()),
+            error_sender,
+        }
+    }
+
+    /// Let the page continue, once the user is done printing.
+    pub fn finish(mut self) {
+        if let Err(error) = self.responder.send(()) {
+            self.error_sender.raise_response_send_error(error);
+        }
+    }
+}
+
 /// Information related to the loading of a web resource. These are created for all HTTP requests.
 /// The client may choose to intercept the load of web resources and send an alternate response
 /// by calling [`WebResourceLoad::intercept`].
@@ -476,8 +588,14 @@ pub trait WebViewDelegate {
     /// Try to resize the window that contains this [`WebView`] to the provided outer size.
     fn request_resize_to(&self, _webview: WebView, _requested_outer_size: DeviceIntSize) {}
     /// Whether or not to allow script to open a new `WebView`. If not handled by the
-    /// embedder, these requests are automatically denied.
-    fn request_open_auxiliary_webview(&self, _parent_webview: WebView) -> Option<WebView> {
+    /// embedder, these requests are automatically denied. The [`AuxiliaryWebViewFeatures`]
+    /// describe whether script asked for a popup window and where it should be placed, which
+    /// the embedder may honor when presenting the new `WebView`.
+    fn request_open_auxiliary_webview(
+        &self,
+        _parent_webview: WebView,
+        _features: AuxiliaryWebViewFeatures,
+    ) -> Option<WebView> {
         None
     }
 
@@ -493,6 +611,24 @@ pub trait WebViewDelegate {
     ) {
     }
 
+    /// Let the user choose which of the saved password credentials of a page is handed to
+    /// it. If not handled, no credential is handed out.
+    fn show_password_credential_chooser(
+        &self,
+        _webview: WebView,
+        _credential_chooser: PasswordCredentialChooser,
+    ) {
+    }
+
+    /// Ask the user whether a page may save a password credential. If not handled, the
+    /// credential is not saved.
+    fn request_save_password_credential(
+        &self,
+        _webview: WebView,
+        _save_request: PasswordCredentialSaveRequest,
+    ) {
+    }
+
     /// Show the user a [simple dialog](https://html.spec.whatwg.org/multipage/#simple-dialogs) (`alert()`, `confirm()`,
     /// or `prompt()`). Since their messages are controlled by web content, they should be presented to the user in a
     /// way that makes them impossible to mistake for browser UI.
@@ -562,6 +698,18 @@ pub trait WebViewDelegate {
     /// Request to hide the IME when the editable element is blurred.
     fn hide_ime(&self, _webview: WebView) {}
 
+    /// Notify the embedder that the caret of the element receiving IME input has moved
+    /// during a composition. The rectangle can be used to position the IME candidate window.
+    fn update_ime_caret_rect(&self, _webview: WebView, _caret_rect: DeviceIntRect) {}
+
+    /// A drag and drop operation started in the page. The embedder may render the drag
+    /// image under the cursor and continue the operation natively, using the provided data,
+    /// when the cursor leaves the [`WebView`].
+    fn notify_drag_started(&self, _webview: WebView, _data: DragData) {}
+
+    /// The drag and drop operation that started in the page has ended.
+    fn notify_drag_ended(&self, _webview: WebView) {}
+
     /// Request that the embedder show UI elements for form controls that are not integrated
     /// into page content, such as dropdowns for `<select>` elements.
     fn show_form_control(&self, _webview: WebView, _form_control: FormControl) {}
@@ -589,6 +737,12 @@ pub trait WebViewDelegate {
 
     /// Request to display a notification.
     fn show_notification(&self, _webview: WebView, _notification: Notification) {}
+
+    /// The page requested to be printed with `window.print()`. This is the opportunity for
+    /// the embedder to offer the user to print the [`WebView`], after which it should finish
+    /// the [`PrintRequest`]. Servo can't lay out pages for paged media yet, so this can only
+    /// print what is currently rendered.
+    fn request_print(&self, _webview: WebView, _request: PrintRequest) {}
 }
 
 pub(crate) struct DefaultWebViewDelegate;
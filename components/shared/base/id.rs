@@ -375,6 +375,8 @@ namespace_id! {ImageBitmapId, ImageBitmapIndex, "ImageBitmap"}
 
 namespace_id! {OffscreenCanvasId, OffscreenCanvasIndex, "OffscreenCanvas"}
 
+namespace_id! {CookieStoreId, CookieStoreIndex, "CookieStore"}
+
 // We provide ids just for unit testing.
 pub const TEST_NAMESPACE: PipelineNamespaceId = PipelineNamespaceId(1234);
 #[allow(unsafe_code)]
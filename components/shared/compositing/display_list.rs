@@ -492,6 +492,8 @@ impl ScrollTree {
             if result.is_some() {
                 return result;
             }
+            // TODO: Do not chain the scroll to ancestors of nodes whose `overscroll-behavior`
+            // is `contain` or `none`. Stylo does not support this property for Servo yet.
             node.parent
         };
 
@@ -816,15 +818,6 @@ pub struct CompositorDisplayListInfo {
     /// The `ScrollTreeNodeId` of the topmost scrolling frame of this info's scroll
     /// tree.
     pub root_scroll_node_id: ScrollTreeNodeId,
-
-    /// Contentful paint i.e. whether the display list contains items of type
-    /// text, image, non-white canvas or SVG). Used by metrics.
-    /// See <https://w3c.github.io/paint-timing/#first-contentful-paint>.
-    pub is_contentful: bool,
-
-    /// Whether the first layout or a subsequent (incremental) layout triggered this
-    /// display list creation.
-    pub first_reflow: bool,
 }
 
 impl CompositorDisplayListInfo {
@@ -836,7 +829,6 @@ impl CompositorDisplayListInfo {
         pipeline_id: PipelineId,
         epoch: Epoch,
         viewport_scroll_sensitivity: AxesScrollSensitivity,
-        first_reflow: bool,
     ) -> Self {
         let mut scroll_tree = ScrollTree::default();
         let root_reference_frame_id = scroll_tree.add_scroll_tree_node(
@@ -872,8 +864,6 @@ impl CompositorDisplayListInfo {
             scroll_tree,
             root_reference_frame_id,
             root_scroll_node_id,
-            is_contentful: false,
-            first_reflow,
         }
     }
 
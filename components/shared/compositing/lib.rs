@@ -6,6 +6,7 @@
 
 use std::fmt::{Debug, Error, Formatter};
 
+use base::Epoch;
 use base::id::{PipelineId, WebViewId};
 use crossbeam_channel::Sender;
 use embedder_traits::{AnimationState, EventLoopWaker, TouchEventResult};
@@ -116,6 +117,11 @@ pub enum CompositorMsg {
     /// The same as the above but it will be forwarded to the pipeline instead
     /// of send via a channel.
     GenerateImageKeysForPipeline(PipelineId),
+    /// Render the current contents of the given pipeline's `WebView` and store them as
+    /// a new image, whose key is returned via the provided channel sender. This is used
+    /// to capture the old state of a document for a view transition, which is only
+    /// possible for the root pipeline of a `WebView`.
+    CaptureViewTransitionSnapshot(WebViewId, PipelineId, IpcSender<Option<ImageKey>>),
     /// Perform a resource update operation.
     UpdateImages(SmallVec<[ImageUpdate; 1]>),
 
@@ -139,6 +145,9 @@ pub enum CompositorMsg {
     CollectMemoryReport(ReportsChan),
     /// A top-level frame has parsed a viewport metatag and is sending the new constraints.
     Viewport(WebViewId, ViewportDescription),
+    /// Script wants to know when the display list with the given [`Epoch`] is presented for
+    /// the given pipeline. The presentation time is sent back via the constellation.
+    RequestPresentationFeedback(WebViewId, PipelineId, Epoch),
 }
 
 impl Debug for CompositorMsg {
@@ -244,6 +253,24 @@ impl CrossProcessCompositorApi {
         receiver.recv().ok()
     }
 
+    /// Capture the current rendering of the given pipeline as an image for a view
+    /// transition, blocking until the compositor has done so.
+    pub fn capture_view_transition_snapshot_blocking(
+        &self,
+        webview_id: WebViewId,
+        pipeline_id: PipelineId,
+    ) -> Option<ImageKey> {
+        let (sender, receiver) = ipc::channel().unwrap();
+        self.0
+            .send(CompositorMsg::CaptureViewTransitionSnapshot(
+                webview_id,
+                pipeline_id,
+                sender,
+            ))
+            .ok()?;
+        receiver.recv().ok().flatten()
+    }
+
     /// Sends a message to the compositor for creating new image keys.
     /// The compositor will then send a batch of keys over the constellation to the script_thread
     /// and the appropriate pipeline.
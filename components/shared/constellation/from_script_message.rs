@@ -12,18 +12,21 @@ use base::id::{
     BroadcastChannelRouterId, BrowsingContextId, HistoryStateId, MessagePortId,
     MessagePortRouterId, PipelineId, ServiceWorkerId, ServiceWorkerRegistrationId, WebViewId,
 };
+use bitflags::bitflags;
 use canvas_traits::canvas::{CanvasId, CanvasMsg};
 use devtools_traits::{DevtoolScriptControlMsg, ScriptToDevtoolsControlMsg, WorkerId};
 use embedder_traits::{
-    AnimationState, EmbedderMsg, FocusSequenceNumber, JSValue, JavaScriptEvaluationError,
-    JavaScriptEvaluationId, MediaSessionEvent, Theme, TouchEventResult, ViewportDetails,
-    WebDriverMessageId,
+    AnimationState, AuxiliaryWebViewFeatures, EmbedderMsg, FocusSequenceNumber, JSValue,
+    JavaScriptEvaluationError, JavaScriptEvaluationId, MediaSessionEvent, Theme, TouchEventResult,
+    ViewportDetails, WebDriverMessageId,
 };
-use euclid::default::Size2D as UntypedSize2D;
+use euclid::default::{Size2D as UntypedSize2D, Vector2D as UntypedVector2D};
 use http::{HeaderMap, Method};
 use ipc_channel::Error as IpcError;
 use ipc_channel::ipc::{IpcReceiver, IpcSender};
-use net_traits::policy_container::PolicyContainer;
+use malloc_size_of_derive::MallocSizeOf;
+use net_traits::permissions_policy::InheritedPolicy;
+use net_traits::policy_container::{OpenerPolicyValue, PolicyContainer};
 use net_traits::request::{Destination, InsecureRequestsPolicy, Referrer, RequestBody};
 use net_traits::storage_thread::StorageType;
 use net_traits::{CoreResourceMsg, ReferrerPolicy, ResourceThreads};
@@ -69,6 +72,71 @@ pub enum LoadOrigin {
     Script(ImmutableOrigin),
 }
 
+/// <https://html.spec.whatwg.org/multipage/#sandboxing-flag-set>
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, MallocSizeOf, PartialEq, Serialize)]
+pub struct SandboxingFlagSet(u32);
+
+bitflags! {
+    impl SandboxingFlagSet: u32 {
+        /// <https://html.spec.whatwg.org/multipage/#sandboxed-navigation-browsing-context-flag>
+        const SANDBOXED_NAVIGATION = 1 << 0;
+        /// <https://html.spec.whatwg.org/multipage/#sandboxed-auxiliary-navigation-browsing-context-flag>
+        const SANDBOXED_AUXILIARY_NAVIGATION = 1 << 1;
+        /// <https://html.spec.whatwg.org/multipage/#sandboxed-top-level-navigation-without-user-activation-browsing-context-flag>
+        const SANDBOXED_TOP_LEVEL_NAVIGATION_WITHOUT_USER_ACTIVATION = 1 << 2;
+        /// <https://html.spec.whatwg.org/multipage/#sandboxed-top-level-navigation-with-user-activation-browsing-context-flag>
+        const SANDBOXED_TOP_LEVEL_NAVIGATION_WITH_USER_ACTIVATION = 1 << 3;
+        /// <https://html.spec.whatwg.org/multipage/#sandboxed-plugins-browsing-context-flag>
+        const SANDBOXED_PLUGINS = 1 << 4;
+        /// <https://html.spec.whatwg.org/multipage/#sandboxed-origin-browsing-context-flag>
+        const SANDBOXED_ORIGIN = 1 << 5;
+        /// <https://html.spec.whatwg.org/multipage/#sandboxed-forms-browsing-context-flag>
+        const SANDBOXED_FORMS = 1 << 6;
+        /// <https://html.spec.whatwg.org/multipage/#sandboxed-pointer-lock-browsing-context-flag>
+        const SANDBOXED_POINTER_LOCK = 1 << 7;
+        /// <https://html.spec.whatwg.org/multipage/#sandboxed-scripts-browsing-context-flag>
+        const SANDBOXED_SCRIPTS = 1 << 8;
+        /// <https://html.spec.whatwg.org/multipage/#sandboxed-automatic-features-browsing-context-flag>
+        const SANDBOXED_AUTOMATIC_FEATURES = 1 << 9;
+        /// <https://html.spec.whatwg.org/multipage/#sandboxed-document.domain-browsing-context-flag>
+        const SANDBOXED_DOCUMENT_DOMAIN = 1 << 10;
+        /// <https://html.spec.whatwg.org/multipage/#sandbox-propagates-to-auxiliary-browsing-contexts-flag>
+        const SANDBOX_PROPAGATES_TO_AUXILIARY_BROWSING_CONTEXTS = 1 << 11;
+        /// <https://html.spec.whatwg.org/multipage/#sandboxed-modals-flag>
+        const SANDBOXED_MODALS = 1 << 12;
+        /// <https://html.spec.whatwg.org/multipage/#sandboxed-orientation-lock-browsing-context-flag>
+        const SANDBOXED_ORIENTATION_LOCK = 1 << 13;
+        /// <https://html.spec.whatwg.org/multipage/#sandboxed-presentation-browsing-context-flag>
+        const SANDBOXED_PRESENTATION = 1 << 14;
+        /// <https://html.spec.whatwg.org/multipage/#sandboxed-downloads-browsing-context-flag>
+        const SANDBOXED_DOWNLOADS = 1 << 15;
+        /// <https://html.spec.whatwg.org/multipage/#sandboxed-custom-protocols-navigation-browsing-context-flag>
+        const SANDBOXED_CUSTOM_PROTOCOLS_NAVIGATION = 1 << 16;
+    }
+}
+
+/// The state of the document that initiated a navigation, captured when the navigation
+/// starts.
+/// <https://html.spec.whatwg.org/multipage/#source-snapshot-params>
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct SourceSnapshotParams {
+    /// The pipeline of the document that initiated the navigation.
+    pub pipeline_id: PipelineId,
+    /// <https://html.spec.whatwg.org/multipage/#source-snapshot-params-activation>
+    pub has_transient_activation: bool,
+    /// <https://html.spec.whatwg.org/multipage/#source-snapshot-params-sandbox>
+    pub sandboxing_flags: SandboxingFlagSet,
+}
+
+impl SourceSnapshotParams {
+    /// <https://html.spec.whatwg.org/multipage/#source-snapshot-params-download>
+    pub fn allows_downloading(&self) -> bool {
+        !self
+            .sandboxing_flags
+            .contains(SandboxingFlagSet::SANDBOXED_DOWNLOADS)
+    }
+}
+
 /// can be passed to `LoadUrl` to load a page with GET/POST
 /// parameters or headers
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -112,8 +180,94 @@ pub struct LoadData {
     pub has_trustworthy_ancestor_origin: bool,
     /// Servo internal: if crash details are present, trigger a crash error page with these details.
     pub crash: Option<String>,
+    /// Servo internal: if present, the response to this load was blocked from being rendered in
+    /// its navigable and an error page with this reason is displayed instead.
+    pub navigation_error: Option<String>,
     /// Destination, used for CSP checks
     pub destination: Destination,
+    /// The state of the form controls of the document that was previously loaded for this
+    /// session history entry, which is restored once the document has been parsed.
+    pub persisted_form_state: Vec<PersistedFormControlState>,
+    /// The scroll state of the document that was previously loaded for this session history
+    /// entry, which is restored once the document has been loaded.
+    pub persisted_scroll_state: Option<PersistedScrollState>,
+    /// The origins of the ancestors of the navigable being loaded, starting with its parent,
+    /// used for the CSP `frame-ancestors` check. These are set by the constellation when it
+    /// creates the pipeline of the load, and any value set by script is ignored.
+    pub ancestor_origins: Vec<ImmutableOrigin>,
+    /// Whether the parent document of the navigable being loaded is cross-origin isolated.
+    /// Always false for top-level navigables.
+    pub inherited_cross_origin_isolated: bool,
+    /// Whether the pipeline of the document being loaded runs in a content process that no
+    /// document of another browsing context group can use, which top-level documents need in
+    /// order to be cross-origin isolated. This is set by the constellation.
+    pub in_isolated_content_process: bool,
+    /// Whether the response to this load was prefetched because of the speculation rules of
+    /// the document that started it, in which case it is taken from the HTTP cache even if
+    /// it is stale.
+    pub prefetched: bool,
+    /// Whether this load prerenders a likely next navigation, in a document that is hidden
+    /// until that navigation activates it.
+    /// <https://wicg.github.io/nav-speculation/prerendering.html#document-prerendering>
+    pub prerendering: bool,
+    /// The sandboxing flags that the navigable being loaded imposes on its new document:
+    /// those of its container and parent document for a child navigable, or its popup
+    /// sandboxing flags for a top-level one.
+    /// <https://html.spec.whatwg.org/multipage/#determining-the-creation-sandboxing-flags>
+    pub sandboxing_flag_set: SandboxingFlagSet,
+    /// The state of the document that initiated this navigation, if it was initiated by a
+    /// document, used to check that the navigation is allowed by sandboxing.
+    pub source_snapshot_params: Option<SourceSnapshotParams>,
+    /// The inherited permissions policy of the navigable being loaded, as defined by its
+    /// container. Always `None` for top-level navigables.
+    pub inherited_permissions_policy: Option<InheritedPolicy>,
+}
+
+/// The state of a form-associated custom element that is persisted with the session
+/// history entry of its document.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PersistedFormControlState {
+    /// The position of the element among the form-associated custom elements of its
+    /// document, in tree order.
+    pub index: usize,
+    /// The local name of the element, used to check that the element at `index` in the
+    /// new document is the same kind of element.
+    pub local_name: String,
+    /// The state of the element, as set with `ElementInternals.setFormValue()`.
+    pub value: PersistedFormValue,
+}
+
+/// <https://html.spec.whatwg.org/multipage/#scroll-restoration-mode>
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub enum ScrollRestorationMode {
+    /// The user agent is responsible for restoring the scroll position upon navigation.
+    #[default]
+    Auto,
+    /// The page is responsible for restoring the scroll position.
+    Manual,
+}
+
+/// The scroll state of a document that is persisted with its session history entry.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PersistedScrollState {
+    /// <https://html.spec.whatwg.org/multipage/#she-scroll-restoration-mode>
+    pub scroll_restoration_mode: ScrollRestorationMode,
+    /// The scroll offset of the viewport of the document, in CSS pixels.
+    /// <https://html.spec.whatwg.org/multipage/#she-scroll-position-data>
+    pub scroll_offset: UntypedVector2D<f32>,
+}
+
+/// A value that was passed to `ElementInternals.setFormValue()` as the state of an element.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum PersistedFormValue {
+    String(String),
+    File {
+        name: String,
+        type_: String,
+        bytes: Vec<u8>,
+    },
+    /// The entries of a `FormData`, as pairs of their name and their value.
+    Entries(Vec<(String, PersistedFormValue)>),
 }
 
 /// The result of evaluating a javascript scheme url.
@@ -153,9 +307,20 @@ impl LoadData {
             srcdoc: "".to_string(),
             inherited_secure_context,
             crash: None,
+            navigation_error: None,
             inherited_insecure_requests_policy,
             has_trustworthy_ancestor_origin,
             destination: Destination::Document,
+            persisted_form_state: Vec::new(),
+            persisted_scroll_state: None,
+            ancestor_origins: Vec::new(),
+            inherited_cross_origin_isolated: false,
+            in_isolated_content_process: false,
+            prefetched: false,
+            prerendering: false,
+            sandboxing_flag_set: SandboxingFlagSet::empty(),
+            source_snapshot_params: None,
+            inherited_permissions_policy: None,
         }
     }
 }
@@ -175,6 +340,18 @@ pub enum NavigationHistoryBehavior {
     Replace,
 }
 
+/// A session history entry of a single navigable, as exposed to script by the Navigation API.
+/// <https://html.spec.whatwg.org/multipage/#session-history-entry>
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct NavigableHistoryEntry {
+    /// The pipeline of the document of this entry.
+    pub pipeline_id: PipelineId,
+    /// The URL of this entry.
+    pub url: ServoUrl,
+    /// The classic history API state of this entry, if any.
+    pub history_state_id: Option<HistoryStateId>,
+}
+
 /// Entities required to spawn service workers
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ScopeThings {
@@ -372,6 +549,8 @@ pub struct AuxiliaryWebViewCreationRequest {
     pub opener_webview_id: WebViewId,
     /// The pipeline opener browsing context.
     pub opener_pipeline_id: PipelineId,
+    /// The features requested for the new webview.
+    pub features: AuxiliaryWebViewFeatures,
     /// Sender for the constellation’s response to our request.
     pub response_sender: IpcSender<Option<AuxiliaryWebViewCreationResponse>>,
 }
@@ -383,6 +562,9 @@ pub struct AuxiliaryWebViewCreationResponse {
     pub new_webview_id: WebViewId,
     /// The new pipeline ID.
     pub new_pipeline_id: PipelineId,
+    /// The popup sandboxing flags of the new browsing context, which its document inherits
+    /// from the opener if the opener's sandbox propagates to auxiliary browsing contexts.
+    pub sandboxing_flag_set: SandboxingFlagSet,
 }
 
 /// Specifies the information required to load an iframe.
@@ -447,6 +629,11 @@ pub struct WorkerGlobalScopeInit {
     pub creation_url: ServoUrl,
     /// True if secure context
     pub inherited_secure_context: Option<bool>,
+    /// True if the owner of the worker is cross-origin isolated
+    pub inherited_cross_origin_isolated: bool,
+    /// The origin of the owner of the worker followed by the origins of the ancestors of its
+    /// navigable, used to determine the site for cookies of requests made by the worker
+    pub ancestor_origins: Vec<ImmutableOrigin>,
 }
 
 /// Common entities representing a network load origin
@@ -574,6 +761,20 @@ pub enum ScriptToConstellationMessage {
     LoadUrl(LoadData, NavigationHistoryBehavior),
     /// Abort loading after sending a LoadUrl message.
     AbortLoadUrl,
+    /// The response to the load of this pipeline may not be embedded in the ancestors of its
+    /// navigable, so the load should be replaced with an error page with the given reason.
+    NavigationResponseBlocked(String),
+    /// The navigation of this pending pipeline was redirected to a document of another site,
+    /// which must be loaded in the event loop of that site, so the load should be restarted
+    /// from the redirect target in a new pipeline.
+    NavigationRedirectedToOtherSite {
+        /// The target of the redirect.
+        url: ServoUrl,
+        /// The referrer of the request to the redirect target.
+        referrer: Referrer,
+        /// The referrer policy of the request to the redirect target.
+        referrer_policy: ReferrerPolicy,
+    },
     /// Post a message to the currently active window of a given browsing context.
     PostMessage {
         /// The target of the posted message.
@@ -598,6 +799,9 @@ pub enum ScriptToConstellationMessage {
     ReplaceHistoryState(HistoryStateId, ServoUrl),
     /// Gets the length of the joint session history from the constellation.
     JointSessionHistoryLength(IpcSender<u32>),
+    /// Traverse the session history of the browsing context of this pipeline by the given
+    /// number of its own entries, which may span multiple joint session history entries.
+    TraverseNavigableHistory(isize),
     /// Notification that this iframe should be removed.
     /// Returns a list of pipelines which were closed.
     RemoveIFrame(BrowsingContextId, IpcSender<Vec<PipelineId>>),
@@ -617,10 +821,20 @@ pub enum ScriptToConstellationMessage {
     SetLayoutEpoch(Epoch, IpcSender<bool>),
     /// Update the pipeline Url, which can change after redirections.
     SetFinalUrl(ServoUrl),
+    /// Notify the constellation of the cross-origin opener policy of a newly loaded
+    /// top-level document, so that it can be moved to a new browsing context group if needed.
+    SetCrossOriginOpenerPolicy(OpenerPolicyValue),
     /// Script has handled a touch event, and either prevented or allowed default actions.
     TouchEventProcessed(TouchEventResult),
     /// A log entry, with the top-level browsing context id and thread name
     LogEntry(Option<String>, LogEntry),
+    /// Persist the state of the form controls of the document with its session history entry.
+    PersistFormState(Vec<PersistedFormControlState>),
+    /// Persist the scroll state of the document with its session history entry.
+    PersistScrollState(PersistedScrollState),
+    /// Prerender a likely next navigation of the top-level browsing context of this pipeline,
+    /// so that it can be activated instantly when that navigation happens.
+    Prerender(LoadData),
     /// Discard the document.
     DiscardDocument,
     /// Discard the browsing context.
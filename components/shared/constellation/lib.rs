@@ -49,6 +49,8 @@ pub enum EmbedderToConstellationMessage {
     LoadUrl(WebViewId, ServoUrl),
     /// Clear the network cache.
     ClearCache,
+    /// Replace the HSTS preload list with the given FST map.
+    UpdateHstsPreloadList(Vec<u8>),
     /// Request to traverse the joint session history of the provided browsing context.
     TraverseHistory(WebViewId, TraversalDirection, TraversalId),
     /// Inform the Constellation that a `WebView`'s [`ViewportDetails`] have changed.
@@ -88,6 +90,12 @@ pub enum EmbedderToConstellationMessage {
     MediaSessionAction(MediaSessionActionType),
     /// Set whether to use less resources, by stopping animations and running timers at a heavily limited rate.
     SetWebViewThrottled(WebViewId, bool),
+    /// Set whether a webview is hidden, in which case it is throttled unless it is playing
+    /// media.
+    SetWebViewHidden(WebViewId, bool),
+    /// Freeze or resume all documents of a webview, suspending or resuming their timers,
+    /// tasks and media.
+    SetWebViewFrozen(WebViewId, bool),
     /// The Servo renderer scrolled and is updating the scroll states of the nodes in the
     /// given pipeline via the constellation.
     SetScrollStates(PipelineId, HashMap<ExternalScrollId, LayoutVector2D>),
@@ -107,8 +115,9 @@ pub enum EmbedderToConstellationMessage {
 /// A description of a paint metric that is sent from the Servo renderer to the
 /// constellation.
 pub enum PaintMetricEvent {
-    FirstPaint(CrossProcessInstant, bool /* first_reflow */),
-    FirstContentfulPaint(CrossProcessInstant, bool /* first_reflow */),
+    /// The display list with the given [`Epoch`] was presented, for which script requested
+    /// presentation feedback.
+    FramePresented(Epoch, CrossProcessInstant),
 }
 
 impl fmt::Debug for EmbedderToConstellationMessage {
@@ -8,7 +8,9 @@
 mod serializable;
 mod transferable;
 
+use std::any::Any;
 use std::collections::HashMap;
+use std::fmt;
 
 use base::id::{
     BlobId, DomExceptionId, DomPointId, ImageBitmapId, MessagePortId, OffscreenCanvasId,
@@ -42,6 +44,22 @@ pub struct StructuredSerializedData {
     pub transferred_image_bitmaps: Option<HashMap<ImageBitmapId, SerializableImageBitmap>>,
     /// Transferred offscreen canvas objects.
     pub offscreen_canvases: Option<HashMap<OffscreenCanvasId, TransferableOffscreenCanvas>>,
+    /// References to the shared memory objects found in `serialized`, set when the data
+    /// was written for an agent of the same agent cluster. These never leave the process.
+    #[serde(skip)]
+    #[ignore_malloc_size_of = "Owned by SpiderMonkey"]
+    pub shared_memory: Option<SharedMemoryReferences>,
+}
+
+/// Keeps the shared memory serialized as part of a structured clone, such as the
+/// buffer of a `SharedArrayBuffer`, alive until the data is deserialized.
+/// <https://html.spec.whatwg.org/multipage/#sharedarraybuffer-serialization>
+pub struct SharedMemoryReferences(pub Box<dyn Any + Send>);
+
+impl fmt::Debug for SharedMemoryReferences {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("SharedMemoryReferences")
+    }
 }
 
 impl StructuredSerializedData {
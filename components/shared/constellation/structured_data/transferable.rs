@@ -6,7 +6,9 @@
 //! <https://html.spec.whatwg.org/multipage/#transferable-objects>. The implementations are here
 //! instead of in script as they need to be passed through the Constellation.
 
+use std::any::Any;
 use std::collections::VecDeque;
+use std::fmt;
 
 use base::id::MessagePortId;
 use malloc_size_of_derive::MallocSizeOf;
@@ -195,4 +197,20 @@ impl MessagePortImpl {
 pub struct TransferableOffscreenCanvas {
     pub width: u64,
     pub height: u64,
+    /// The placeholder canvas element of the transferred canvas, if it has one.
+    /// It stays in the event loop of its document, so a canvas with a placeholder
+    /// is only ever transferred within the process.
+    #[serde(skip)]
+    #[ignore_malloc_size_of = "Opaque to this crate"]
+    pub placeholder: Option<PlaceholderCanvas>,
+}
+
+/// A placeholder canvas element living in another event loop of this process.
+/// <https://html.spec.whatwg.org/multipage/#offscreencanvas-placeholder>
+pub struct PlaceholderCanvas(pub Box<dyn Any + Send>);
+
+impl fmt::Debug for PlaceholderCanvas {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("PlaceholderCanvas")
+    }
 }
@@ -9,11 +9,12 @@ use serde::{Deserialize, Serialize};
 use webrender_api::ExternalScrollId;
 use webrender_api::units::DevicePoint;
 
-use crate::WebDriverMessageId;
+use crate::{DataTransferFile, WebDriverMessageId};
 
 /// An input event that is sent from the embedder to Servo.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum InputEvent {
+    DragDrop(DragDropEvent),
     EditingAction(EditingActionEvent),
     Gamepad(GamepadEvent),
     Ime(ImeEvent),
@@ -26,6 +27,39 @@ pub enum InputEvent {
     Scroll(ScrollEvent),
 }
 
+/// A drag and drop operation that started outside of the `WebView`, such as files dragged
+/// from the system file manager, and that is now over the `WebView`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DragDropEvent {
+    pub action: DragDropAction,
+    pub point: DevicePoint,
+    /// The files that are being dragged. Their contents are only exposed to the page when
+    /// they are dropped.
+    pub files: Vec<DataTransferFile>,
+}
+
+impl DragDropEvent {
+    pub fn new(action: DragDropAction, point: DevicePoint, files: Vec<DataTransferFile>) -> Self {
+        Self {
+            action,
+            point,
+            files,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum DragDropAction {
+    /// The drag entered the `WebView`.
+    Enter,
+    /// The drag moved over the `WebView`.
+    Over,
+    /// The drag left the `WebView` or was canceled.
+    Leave,
+    /// The dragged data was dropped onto the `WebView`.
+    Drop,
+}
+
 /// An editing action that should be performed on a `WebView`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum EditingActionEvent {
@@ -37,6 +71,7 @@ pub enum EditingActionEvent {
 impl InputEvent {
     pub fn point(&self) -> Option<DevicePoint> {
         match self {
+            InputEvent::DragDrop(event) => Some(event.point),
             InputEvent::EditingAction(..) => None,
             InputEvent::Gamepad(..) => None,
             InputEvent::Ime(..) => None,
@@ -52,6 +87,7 @@ impl InputEvent {
 
     pub fn webdriver_message_id(&self) -> Option<WebDriverMessageId> {
         match self {
+            InputEvent::DragDrop(..) => None,
             InputEvent::EditingAction(..) => None,
             InputEvent::Gamepad(..) => None,
             InputEvent::Ime(..) => None,
@@ -67,6 +103,7 @@ impl InputEvent {
 
     pub fn with_webdriver_message_id(mut self, webdriver_id: Option<WebDriverMessageId>) -> Self {
         match self {
+            InputEvent::DragDrop(..) => {},
             InputEvent::EditingAction(..) => {},
             InputEvent::Gamepad(..) => {},
             InputEvent::Ime(..) => {},
@@ -11,6 +11,7 @@
 pub mod input_events;
 pub mod resources;
 pub mod user_content_manager;
+mod webauthn;
 mod webdriver;
 
 use std::collections::HashMap;
@@ -41,6 +42,7 @@ use webrender_api::ExternalScrollId;
 use webrender_api::units::{DeviceIntPoint, DeviceIntRect, DeviceIntSize, DevicePixel, LayoutSize};
 
 pub use crate::input_events::*;
+pub use crate::webauthn::*;
 pub use crate::webdriver::*;
 
 /// Tracks whether Servo isn't shutting down, is in the process of shutting down,
@@ -307,6 +309,76 @@ pub enum SelectElementOptionOrOptgroup {
     },
 }
 
+/// Contents of the system clipboard, other than plain text, that are exposed to a page
+/// when the user pastes into it.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ClipboardContents {
+    /// HTML-formatted text on the clipboard, if any. It is sanitized before being exposed
+    /// to the page.
+    pub html: Option<String>,
+    /// Files referenced by the clipboard.
+    pub files: Vec<DataTransferFile>,
+}
+
+/// A geographic position provided by the embedder in response to a
+/// [`EmbedderMsg::GetGeolocationPosition`] request. Coordinates use the WGS84 datum.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct GeolocationPosition {
+    /// The latitude, in decimal degrees.
+    pub latitude: f64,
+    /// The longitude, in decimal degrees.
+    pub longitude: f64,
+    /// The accuracy of the latitude and longitude, in meters.
+    pub accuracy: f64,
+    /// The height above the WGS84 ellipsoid, in meters, if known.
+    pub altitude: Option<f64>,
+    /// The accuracy of the altitude, in meters, if known.
+    pub altitude_accuracy: Option<f64>,
+    /// The direction of travel in degrees clockwise from true north, if known.
+    pub heading: Option<f64>,
+    /// The horizontal speed, in meters per second, if known.
+    pub speed: Option<f64>,
+    /// The time at which the position was acquired, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+/// A password credential of a page, offered to the user in response to a
+/// [`EmbedderMsg::SelectPasswordCredential`] or [`EmbedderMsg::SavePasswordCredential`]
+/// request.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PasswordCredentialInfo {
+    /// The username of the account.
+    pub id: String,
+    /// A human readable name for the account. May be empty.
+    pub name: String,
+    /// The URL of an image representing the account. May be empty.
+    pub icon_url: String,
+}
+
+/// The data of a drag and drop operation that started in a page.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct DragData {
+    /// The text items that are being dragged, as pairs of their type and their data.
+    pub items: Vec<(String, String)>,
+    /// The files that are being dragged.
+    pub files: Vec<DataTransferFile>,
+    /// The image to render under the cursor while dragging, if the page set one.
+    pub image: Option<Arc<RasterImage>>,
+    /// The position of the cursor relative to the top left corner of the drag image.
+    pub image_offset: Point2D<i32, CSSPixel>,
+}
+
+/// A file that is transferred into a page from the system clipboard or by drag and drop.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DataTransferFile {
+    /// The name of the file, without its path.
+    pub name: String,
+    /// The MIME type of the file.
+    pub type_: String,
+    /// The contents of the file.
+    pub bytes: Vec<u8>,
+}
+
 /// Data about a `WebView` or `<iframe>` viewport: its size and also the
 /// HiDPI scale factor to use when rendering the contents.
 #[derive(Clone, Copy, Debug, Default, Deserialize, MallocSizeOf, PartialEq, Serialize)]
@@ -327,6 +399,25 @@ impl ViewportDetails {
     }
 }
 
+/// The features of a new auxiliary `WebView` requested by script through the `features`
+/// argument of `window.open()`. The requested geometry is in device pixels, and any part of
+/// it that was not requested is left to the embedder.
+/// <https://html.spec.whatwg.org/multipage/#set-up-browsing-context-features>
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct AuxiliaryWebViewFeatures {
+    /// Whether script asked for a popup window, rather than for a new tab.
+    /// <https://html.spec.whatwg.org/multipage/#popup-window-is-requested>
+    pub is_popup: bool,
+    /// The requested distance of the window from the left edge of the screen.
+    pub left: Option<i32>,
+    /// The requested distance of the window from the top edge of the screen.
+    pub top: Option<i32>,
+    /// The requested width of the viewport of the window.
+    pub width: Option<i32>,
+    /// The requested height of the viewport of the window.
+    pub height: Option<i32>,
+}
+
 /// Unlike [`ScreenGeometry`], the data is in device-independent pixels
 /// to be used by DOM APIs
 #[derive(Default, Deserialize, Serialize)]
@@ -387,8 +478,12 @@ pub enum EmbedderMsg {
     ),
     /// Whether or not to allow a pipeline to load a url.
     AllowNavigationRequest(WebViewId, PipelineId, ServoUrl),
-    /// Whether or not to allow script to open a new tab/browser
-    AllowOpeningWebView(WebViewId, IpcSender<Option<(WebViewId, ViewportDetails)>>),
+    /// Whether or not to allow script to open a new tab/browser with the given features
+    AllowOpeningWebView(
+        WebViewId,
+        AuxiliaryWebViewFeatures,
+        IpcSender<Option<(WebViewId, ViewportDetails)>>,
+    ),
     /// A webview was destroyed.
     WebViewClosed(WebViewId),
     /// A webview potentially gained focus for keyboard events, as initiated
@@ -405,8 +500,58 @@ pub enum EmbedderMsg {
     ClearClipboard(WebViewId),
     /// Gets system clipboard contents
     GetClipboardText(WebViewId, IpcSender<Result<String, String>>),
+    /// Gets the contents of the system clipboard that are not plain text, such as HTML
+    /// and files, for a paste operation.
+    GetClipboardContents(WebViewId, IpcSender<Result<ClipboardContents, String>>),
     /// Sets system clipboard contents
     SetClipboardText(WebViewId, String),
+    /// Acquire the current geographic position of the device. The boolean is true if the page
+    /// requested a high accuracy position.
+    GetGeolocationPosition(
+        WebViewId,
+        bool,
+        IpcSender<Result<GeolocationPosition, String>>,
+    ),
+    /// Create a new public key credential with an authenticator, on behalf of the page
+    /// with the given origin.
+    MakeCredential(
+        WebViewId,
+        ServoUrl,
+        MakeCredentialParameters,
+        IpcSender<Result<MakeCredentialResponse, AuthenticatorError>>,
+    ),
+    /// Sign a challenge with an existing public key credential, on behalf of the page with
+    /// the given origin.
+    GetAssertion(
+        WebViewId,
+        ServoUrl,
+        GetAssertionParameters,
+        IpcSender<Result<GetAssertionResponse, AuthenticatorError>>,
+    ),
+    /// Whether a platform authenticator that verifies the user, such as a passkey manager
+    /// protected by biometrics, is available.
+    IsUserVerifyingPlatformAuthenticatorAvailable(WebViewId, IpcSender<bool>),
+    /// Let the user choose one of the saved password credentials of the page with the given
+    /// URL. The index of the chosen credential is sent back, or `None` if the user declined.
+    SelectPasswordCredential(
+        WebViewId,
+        ServoUrl,
+        Vec<PasswordCredentialInfo>,
+        IpcSender<Option<usize>>,
+    ),
+    /// Ask the user whether to save a password credential for the page with the given URL,
+    /// as requested by `navigator.credentials.store()`.
+    SavePasswordCredential(
+        WebViewId,
+        ServoUrl,
+        PasswordCredentialInfo,
+        IpcSender<AllowOrDeny>,
+    ),
+    /// A drag and drop operation started in the page. The embedder may render the drag image
+    /// under the cursor and continue the operation natively if the cursor leaves the `WebView`.
+    StartDrag(WebViewId, DragData),
+    /// The drag and drop operation that started in the page has ended.
+    EndDrag(WebViewId),
     /// Changes the cursor.
     SetCursor(WebViewId, Cursor),
     /// A favicon was detected
@@ -439,8 +584,14 @@ pub enum EmbedderMsg {
         bool,
         IpcSender<Option<Vec<PathBuf>>>,
     ),
-    /// Open interface to request permission specified by prompt.
-    PromptPermission(WebViewId, PermissionFeature, IpcSender<AllowOrDeny>),
+    /// Open interface to request permission specified by prompt, on behalf of the
+    /// document or worker with the given URL.
+    PromptPermission(
+        WebViewId,
+        ServoUrl,
+        PermissionFeature,
+        IpcSender<AllowOrDeny>,
+    ),
     /// Request to present an IME to the user when an editable element is focused.
     /// If the input is text, the second parameter defines the pre-existing string
     /// text content and the zero-based index into the string locating the insertion point.
@@ -454,6 +605,9 @@ pub enum EmbedderMsg {
     ),
     /// Request to hide the IME when the editable element is blurred.
     HideIME(WebViewId),
+    /// Report the position of the caret in the focused editable element while an IME
+    /// composition is in progress, so that the candidate window can be placed next to it.
+    UpdateIMECaretRect(WebViewId, DeviceIntRect),
     /// Report a complete sampled profile
     ReportProfile(Vec<u8>),
     /// Notifies the embedder about media session events
@@ -473,6 +627,9 @@ pub enum EmbedderMsg {
     ShutdownComplete,
     /// Request to display a notification.
     ShowNotification(Option<WebViewId>, Notification),
+    /// The page requested to be printed with `window.print()`. The page is blocked until a
+    /// response is sent, once the user is done printing.
+    RequestPrint(WebViewId, IpcSender<()>),
     /// Request to display a form control to the embedder.
     ShowFormControl(WebViewId, DeviceIntRect, FormControl),
     /// Inform the embedding layer that a JavaScript evaluation has
@@ -570,7 +727,7 @@ pub enum MediaSessionEvent {
 }
 
 /// Enum with variants that match the DOM PermissionName enum
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub enum PermissionFeature {
     Geolocation,
     Notifications,
@@ -583,6 +740,8 @@ pub enum PermissionFeature {
     BackgroundSync,
     Bluetooth,
     PersistentStorage,
+    ClipboardRead,
+    ClipboardWrite,
 }
 
 /// Used to specify the kind of input method editor appropriate to edit a field.
@@ -0,0 +1,140 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Types used to delegate the operations of the
+//! [Web Authentication API](https://w3c.github.io/webauthn/) to an authenticator
+//! provided by the embedder, such as a CTAP2 security key or the platform's passkeys.
+
+use serde::{Deserialize, Serialize};
+
+/// <https://w3c.github.io/webauthn/#enumdef-userverificationrequirement>
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum UserVerificationRequirement {
+    Required,
+    #[default]
+    Preferred,
+    Discouraged,
+}
+
+/// <https://w3c.github.io/webauthn/#enumdef-authenticatorattachment>
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum AuthenticatorAttachment {
+    Platform,
+    CrossPlatform,
+}
+
+/// <https://w3c.github.io/webauthn/#enumdef-attestationconveyancepreference>
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum AttestationConveyancePreference {
+    #[default]
+    None,
+    Indirect,
+    Direct,
+    Enterprise,
+}
+
+/// <https://w3c.github.io/webauthn/#dictdef-publickeycredentialrpentity>
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RelyingParty {
+    /// The RP ID, a valid domain string that the credential is scoped to.
+    pub id: String,
+    pub name: String,
+}
+
+/// <https://w3c.github.io/webauthn/#dictdef-publickeycredentialuserentity>
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CredentialUser {
+    /// The user handle of the account, at most 64 bytes.
+    pub id: Vec<u8>,
+    pub name: String,
+    pub display_name: String,
+}
+
+/// <https://w3c.github.io/webauthn/#dictdef-publickeycredentialdescriptor>
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CredentialDescriptor {
+    pub id: Vec<u8>,
+    /// Hints about how the authenticator holding the credential may be reached, such as
+    /// "usb", "nfc", "ble" or "internal".
+    pub transports: Vec<String>,
+}
+
+/// The parameters of the
+/// [authenticatorMakeCredential](https://w3c.github.io/webauthn/#sctn-op-make-cred) operation.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MakeCredentialParameters {
+    /// The SHA-256 hash of the serialized client data.
+    pub client_data_hash: Vec<u8>,
+    pub relying_party: RelyingParty,
+    pub user: CredentialUser,
+    /// The COSE algorithm identifiers that the relying party supports, from most to least
+    /// preferred.
+    pub algorithms: Vec<i32>,
+    /// Credentials that must not already exist on the authenticator.
+    pub exclude_credentials: Vec<CredentialDescriptor>,
+    pub authenticator_attachment: Option<AuthenticatorAttachment>,
+    /// Whether a client-side discoverable credential (a passkey) must be created.
+    pub require_resident_key: bool,
+    pub user_verification: UserVerificationRequirement,
+    pub attestation: AttestationConveyancePreference,
+    /// How long the relying party is willing to wait, in milliseconds.
+    pub timeout: u32,
+}
+
+/// The result of a successful authenticatorMakeCredential operation.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MakeCredentialResponse {
+    pub credential_id: Vec<u8>,
+    /// The CBOR encoded [attestation object](https://w3c.github.io/webauthn/#attestation-object).
+    pub attestation_object: Vec<u8>,
+    /// The [authenticator data](https://w3c.github.io/webauthn/#authenticator-data) contained
+    /// in the attestation object.
+    pub authenticator_data: Vec<u8>,
+    /// The DER encoded SubjectPublicKeyInfo of the new credential, if the algorithm has one.
+    pub public_key: Option<Vec<u8>>,
+    /// The COSE algorithm identifier of the new credential.
+    pub public_key_algorithm: i32,
+    pub transports: Vec<String>,
+    pub authenticator_attachment: Option<AuthenticatorAttachment>,
+}
+
+/// The parameters of the
+/// [authenticatorGetAssertion](https://w3c.github.io/webauthn/#sctn-op-get-assertion) operation.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetAssertionParameters {
+    /// The SHA-256 hash of the serialized client data.
+    pub client_data_hash: Vec<u8>,
+    pub relying_party_id: String,
+    /// The credentials that may be used. If empty, any discoverable credential for the
+    /// relying party may be used.
+    pub allow_credentials: Vec<CredentialDescriptor>,
+    pub user_verification: UserVerificationRequirement,
+    /// How long the relying party is willing to wait, in milliseconds.
+    pub timeout: u32,
+}
+
+/// The result of a successful authenticatorGetAssertion operation.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetAssertionResponse {
+    pub credential_id: Vec<u8>,
+    pub authenticator_data: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub user_handle: Option<Vec<u8>>,
+    pub authenticator_attachment: Option<AuthenticatorAttachment>,
+}
+
+/// The reasons an authenticator operation can fail. These are reported to the page as
+/// `DOMException`s with the matching name.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum AuthenticatorError {
+    /// The user cancelled the operation, no authenticator was available, or the operation
+    /// timed out.
+    NotAllowed,
+    /// The authenticator already holds one of the excluded credentials.
+    InvalidState,
+    /// None of the requested algorithms or options are supported by the authenticator.
+    NotSupported,
+    /// Any other failure, with a description.
+    Unknown(String),
+}
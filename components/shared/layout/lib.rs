@@ -26,7 +26,7 @@ use compositing_traits::CrossProcessCompositorApi;
 use constellation_traits::LoadData;
 use embedder_traits::{Cursor, Theme, UntrustedNodeAddress, ViewportDetails};
 use euclid::Point2D;
-use euclid::default::{Point2D as UntypedPoint2D, Rect};
+use euclid::default::{Point2D as UntypedPoint2D, Rect, Size2D as UntypedSize2D};
 use fnv::FnvHashMap;
 use fonts::{FontContext, SystemFontServiceProxy};
 use fxhash::FxHashMap;
@@ -61,6 +61,10 @@ use webrender_api::{ExternalScrollId, ImageKey};
 
 pub trait GenericLayoutDataTrait: Any + MallocSizeOfTrait {
     fn as_any(&self) -> &dyn Any;
+
+    /// The size of the content box of this element in the last layout, if it is a query
+    /// container. Container queries are evaluated against this size.
+    fn query_container_size(&self) -> Option<UntypedSize2D<Au>>;
 }
 
 pub type GenericLayoutData = dyn GenericLayoutDataTrait + Send + Sync;
@@ -162,6 +166,32 @@ pub struct PendingImage {
     pub origin: ImmutableOrigin,
 }
 
+/// The content of an element that was painted in a display list, which the script thread
+/// uses to report Largest Contentful Paint and Element Timing entries.
+/// <https://w3c.github.io/paint-timing/#sec-reporting-paint-timing>
+#[derive(Debug)]
+pub struct ContentfulPaint {
+    /// The element whose content was painted.
+    pub node: UntrustedNodeAddress,
+    /// Whether the painted content is an image, as opposed to text.
+    pub is_image: bool,
+    /// The area covered by the painted content, without any scrolling or transforms applied.
+    pub rect: Rect<Au>,
+}
+
+/// An element whose box moved between two consecutive display lists, which the script
+/// thread uses to report Layout Instability entries.
+/// <https://wicg.github.io/layout-instability/#unstable-node>
+#[derive(Debug)]
+pub struct LayoutShift {
+    /// The element whose box moved.
+    pub node: UntrustedNodeAddress,
+    /// The border box of the element in the previous display list.
+    pub previous_rect: Rect<Au>,
+    /// The border box of the element in the new display list.
+    pub current_rect: Rect<Au>,
+}
+
 /// A data structure to tarck vector image that are fully loaded (i.e has a parsed SVG
 /// tree) but not yet rasterized to the size needed by layout. The rasterization is
 /// happening in the image cache.
@@ -231,9 +261,10 @@ pub trait Layout {
     /// Inform the layout that its ScriptThread is about to exit.
     fn exit_now(&mut self);
 
-    /// Requests that layout measure its memory usage. The resulting reports are sent back
-    /// via the supplied channel.
-    fn collect_reports(&self, reports: &mut Vec<Report>, ops: &mut MallocSizeOfOps);
+    /// Requests that layout measure its memory usage. The resulting reports are added to
+    /// `reports`, with paths starting with `prefix`, which identifies the pipeline of this
+    /// layout.
+    fn collect_reports(&self, prefix: &str, reports: &mut Vec<Report>, ops: &mut MallocSizeOfOps);
 
     /// Sets quirks mode for the document, causing the quirks mode stylesheet to be used.
     fn set_quirks_mode(&mut self, quirks_mode: QuirksMode);
@@ -375,6 +406,7 @@ bitflags! {
         const ThemeChanged = 1 << 4;
         const ViewportSizeChanged = 1 << 5;
         const PaintWorkletLoaded = 1 << 6;
+        const ViewTransitionSnapshotChanged = 1 << 7;
     }
 }
 
@@ -395,6 +427,13 @@ pub struct ReflowResult {
     pub pending_images: Vec<PendingImage>,
     /// The list of vector images that were encountered that still need to be rasterized.
     pub pending_rasterization_images: Vec<PendingRasterizationImage>,
+    /// The content painted in the display list built during this reflow, if any.
+    pub contentful_paints: Vec<ContentfulPaint>,
+    /// The elements that moved in the display list built during this reflow, if any.
+    pub layout_shifts: Vec<LayoutShift>,
+    /// Whether the display list built during this reflow, if any, paints any text, image
+    /// or iframe, which makes its presentation a First Contentful Paint.
+    pub is_contentful: bool,
     /// The list of iframes in this layout and their sizes, used in order
     /// to communicate them with the Constellation and also the `Window`
     /// element of their content pages. Returning None if incremental reflow
@@ -454,6 +493,20 @@ pub struct ReflowRequest {
     pub theme: Theme,
     /// The node highlighted by the devtools, if any
     pub highlighted_dom_node: Option<OpaqueNode>,
+    /// The snapshot of the old state of the document painted over it during a view
+    /// transition, if any.
+    pub view_transition_snapshot: Option<ViewTransitionSnapshot>,
+}
+
+/// The captured old state of a document which is painted over the viewport while a
+/// view transition cross-fades to the new state.
+/// <https://drafts.csswg.org/css-view-transitions-1/#capture-the-old-state>
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewTransitionSnapshot {
+    /// The image holding the rendering of the old state of the document.
+    pub image_key: ImageKey,
+    /// The opacity with which to paint the old state.
+    pub opacity: f32,
 }
 
 impl ReflowRequest {
@@ -157,6 +157,11 @@ pub trait ThreadSafeLayoutNode<'dom>: Clone + Copy + Debug + NodeInfo + PartialE
     /// the parent until all the children have been processed.
     fn parent_style(&self) -> Arc<ComputedValues>;
 
+    /// Returns the style of the `::selection` pseudo-element of this node's parent, falling
+    /// back to the parent's primary style. Like [`Self::parent_style`], this is only safe to
+    /// call while processing this node's subtree.
+    fn parent_selected_style(&self) -> Arc<ComputedValues>;
+
     fn debug_id(self) -> usize;
 
     /// Returns an iterator over this node's children.
@@ -191,8 +196,7 @@ pub trait ThreadSafeLayoutNode<'dom>: Clone + Copy + Debug + NodeInfo + PartialE
             el.selected_style()
         } else {
             debug_assert!(self.is_text_node());
-            // TODO(stshine): What should the selected style be for text?
-            self.parent_style()
+            self.parent_selected_style()
         }
     }
 
@@ -10,7 +10,7 @@ use std::sync::{LazyLock, OnceLock};
 use std::thread::{self, JoinHandle};
 
 use base::cross_process_instant::CrossProcessInstant;
-use base::id::HistoryStateId;
+use base::id::{CookieStoreId, HistoryStateId};
 use content_security_policy::{self as csp};
 use cookie::Cookie;
 use crossbeam_channel::{Receiver, Sender, unbounded};
@@ -43,6 +43,7 @@ pub mod http_status;
 pub mod image_cache;
 pub mod indexeddb_thread;
 pub mod mime_classifier;
+pub mod permissions_policy;
 pub mod policy_container;
 pub mod pub_domains;
 pub mod quality;
@@ -153,6 +154,55 @@ impl Display for ReferrerPolicy {
     }
 }
 
+impl ReferrerPolicy {
+    /// Returns the policy named by `token`, if it is a non-empty referrer policy.
+    fn from_token(token: &str) -> Option<Self> {
+        Some(match token {
+            "no-referrer" => ReferrerPolicy::NoReferrer,
+            "no-referrer-when-downgrade" => ReferrerPolicy::NoReferrerWhenDowngrade,
+            "origin" => ReferrerPolicy::Origin,
+            "same-origin" => ReferrerPolicy::SameOrigin,
+            "origin-when-cross-origin" => ReferrerPolicy::OriginWhenCrossOrigin,
+            "unsafe-url" => ReferrerPolicy::UnsafeUrl,
+            "strict-origin" => ReferrerPolicy::StrictOrigin,
+            "strict-origin-when-cross-origin" => ReferrerPolicy::StrictOriginWhenCrossOrigin,
+            _ => return None,
+        })
+    }
+
+    /// <https://w3c.github.io/webappsec-referrer-policy/#parse-referrer-policy-from-header>
+    pub fn parse_header_for_response(headers: Option<&HeaderMap>) -> Self {
+        // Step 2. Let policy be the empty string.
+        let mut policy = ReferrerPolicy::EmptyString;
+        let Some(headers) = headers else {
+            return policy;
+        };
+
+        // Step 1. Let policy-tokens be the result of extracting header list values given
+        // `Referrer-Policy` and response’s header list.
+        let policy_tokens = headers
+            .get_all(header::REFERRER_POLICY)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|token| token.trim_matches(|c| c == ' ' || c == '\t'));
+
+        // Step 3. For each token in policy-tokens, if token is a referrer policy and token is
+        // not the empty string, then set policy to token.
+        //
+        // Unknown tokens are skipped, which allows a list of policies to fall back to older
+        // ones that the user agent does understand.
+        for token in policy_tokens {
+            if let Some(token_policy) = ReferrerPolicy::from_token(token) {
+                policy = token_policy;
+            }
+        }
+
+        // Step 4. Return policy.
+        policy
+    }
+}
+
 impl From<Option<ReferrerPolicyHeader>> for ReferrerPolicy {
     fn from(header: Option<ReferrerPolicyHeader>) -> Self {
         header.map_or(ReferrerPolicy::EmptyString, |policy| match policy {
@@ -441,6 +491,12 @@ impl ResourceThreads {
     pub fn clear_cache(&self) {
         let _ = self.core_thread.send(CoreResourceMsg::ClearCache);
     }
+
+    pub fn update_hsts_preload_list(&self, preload_list: Vec<u8>) {
+        let _ = self
+            .core_thread
+            .send(CoreResourceMsg::UpdateHstsPreloadList(preload_list));
+    }
 }
 
 impl IpcSend<CoreResourceMsg> for ResourceThreads {
@@ -520,14 +576,31 @@ pub enum FetchChannels {
 pub enum CoreResourceMsg {
     Fetch(RequestBuilder, FetchChannels),
     Cancel(Vec<RequestId>),
+    /// Stop reading the response body of an ongoing fetch from the network, because its
+    /// consumer isn't ready for more data.
+    SuspendResponseBody(RequestId),
+    /// Continue reading the response body of a fetch suspended by `SuspendResponseBody`.
+    ResumeResponseBody(RequestId),
     /// Initiate a fetch in response to processing a redirection
     FetchRedirect(RequestBuilder, ResponseInit, IpcSender<FetchResponseMsg>),
     /// Store a cookie for a given originating URL
     SetCookieForUrl(ServoUrl, Serde<Cookie<'static>>, CookieSource),
-    /// Store a set of cookies for a given originating URL
-    SetCookiesForUrl(ServoUrl, Vec<Serde<Cookie<'static>>>, CookieSource),
-    /// Retrieve the stored cookies for a given URL
-    GetCookiesForUrl(ServoUrl, IpcSender<Option<String>>, CookieSource),
+    /// Store a set of cookies for a given originating URL, from a document whose origin and
+    /// ancestor origins are given to determine the site for cookies and the cookie partition
+    SetCookiesForUrl(
+        ServoUrl,
+        Vec<Serde<Cookie<'static>>>,
+        CookieSource,
+        Vec<ImmutableOrigin>,
+    ),
+    /// Retrieve the stored cookies for a given URL, for a document whose origin and ancestor
+    /// origins are given to determine the site for cookies and the cookie partition
+    GetCookiesForUrl(
+        ServoUrl,
+        IpcSender<Option<String>>,
+        CookieSource,
+        Vec<ImmutableOrigin>,
+    ),
     /// Get a cookie by name for a given originating URL
     GetCookiesDataForUrl(
         ServoUrl,
@@ -536,6 +609,30 @@ pub enum CoreResourceMsg {
     ),
     DeleteCookies(ServoUrl),
     DeleteCookie(ServoUrl, String),
+    /// Register a `CookieStore` that receives the results of its asynchronous cookie
+    /// operations, and notifications of changes to the cookies visible to the given URL,
+    /// over the given channel. The origin and ancestor origins are used to determine the
+    /// site for cookies and the cookie partition of all operations of this store.
+    NewCookieListener(
+        CookieStoreId,
+        IpcSender<CookieAsyncResponse>,
+        ServoUrl,
+        Vec<ImmutableOrigin>,
+    ),
+    /// Stop sending responses and change notifications to a `CookieStore`
+    RemoveCookieListener(CookieStoreId),
+    /// Asynchronously get the first cookie visible to a URL, optionally filtered by name
+    GetCookieDataForUrlAsync(CookieStoreId, ServoUrl, Option<String>),
+    /// Asynchronously get all cookies visible to a URL, optionally filtered by name
+    GetAllCookieDataForUrlAsync(CookieStoreId, ServoUrl, Option<String>),
+    /// Asynchronously store a cookie for a given originating URL. Deletions are expressed as
+    /// storing a cookie whose expiry date is in the past.
+    SetCookieForUrlAsync(
+        CookieStoreId,
+        ServoUrl,
+        Serde<Cookie<'static>>,
+        CookieSource,
+    ),
     /// Get a history state by a given history state id
     GetHistoryState(HistoryStateId, IpcSender<Option<Vec<u8>>>),
     /// Set a history state for a given history state id
@@ -544,6 +641,8 @@ pub enum CoreResourceMsg {
     RemoveHistoryStates(Vec<HistoryStateId>),
     /// Clear the network cache.
     ClearCache,
+    /// Replace the HSTS preload list with the given FST map.
+    UpdateHstsPreloadList(Vec<u8>),
     /// Send the service worker network mediator for an origin to CoreResourceThread
     NetworkMediator(IpcSender<CustomResponseMediator>, ImmutableOrigin),
     /// Message forwarded to file manager's handler
@@ -557,6 +656,8 @@ pub enum CoreResourceMsg {
 #[expect(clippy::large_enum_variant)]
 enum ToFetchThreadMessage {
     Cancel(Vec<RequestId>),
+    SuspendResponseBody(RequestId),
+    ResumeResponseBody(RequestId),
     StartFetch(
         /* request_builder */ RequestBuilder,
         /* response_init */ Option<ResponseInit>,
@@ -663,6 +764,16 @@ impl FetchThread {
                         .core_resource_thread
                         .send(CoreResourceMsg::Cancel(request_ids));
                 },
+                ToFetchThreadMessage::SuspendResponseBody(request_id) => {
+                    let _ = self
+                        .core_resource_thread
+                        .send(CoreResourceMsg::SuspendResponseBody(request_id));
+                },
+                ToFetchThreadMessage::ResumeResponseBody(request_id) => {
+                    let _ = self
+                        .core_resource_thread
+                        .send(CoreResourceMsg::ResumeResponseBody(request_id));
+                },
                 ToFetchThreadMessage::Exit => break,
             }
         }
@@ -718,6 +829,25 @@ pub fn cancel_async_fetch(request_ids: Vec<RequestId>) {
         .send(ToFetchThreadMessage::Cancel(request_ids));
 }
 
+/// Ask the resource thread to stop reading the response body of an ongoing request from
+/// the network until [`resume_async_fetch_body`] is called. Does nothing if the request
+/// has already completed.
+pub fn suspend_async_fetch_body(request_id: RequestId) {
+    let _ = FETCH_THREAD
+        .get()
+        .expect("Fetch thread should always be initialized on start-up")
+        .send(ToFetchThreadMessage::SuspendResponseBody(request_id));
+}
+
+/// Ask the resource thread to continue reading the response body of a request that was
+/// suspended with [`suspend_async_fetch_body`].
+pub fn resume_async_fetch_body(request_id: RequestId) {
+    let _ = FETCH_THREAD
+        .get()
+        .expect("Fetch thread should always be initialized on start-up")
+        .send(ToFetchThreadMessage::ResumeResponseBody(request_id));
+}
+
 #[derive(Clone, Debug, Deserialize, MallocSizeOf, Serialize)]
 pub struct ResourceCorsData {
     /// CORS Preflight flag
@@ -729,6 +859,7 @@ pub struct ResourceCorsData {
 #[derive(Clone, Debug, Deserialize, MallocSizeOf, Serialize)]
 pub struct ResourceFetchTiming {
     pub domain_lookup_start: Option<CrossProcessInstant>,
+    pub domain_lookup_end: Option<CrossProcessInstant>,
     pub timing_check_passed: bool,
     pub timing_type: ResourceTimingType,
     /// Number of redirects until final resource (currently limited to 20)
@@ -743,8 +874,35 @@ pub struct ResourceFetchTiming {
     pub connect_start: Option<CrossProcessInstant>,
     pub connect_end: Option<CrossProcessInstant>,
     pub start_time: Option<CrossProcessInstant>,
+    /// The ALPN protocol ID of the network protocol used to fetch the resource, or an empty
+    /// string if it is unknown.
+    pub next_hop_protocol: String,
+    /// The size in octets of the response received from the network, including its headers.
+    pub transfer_size: u64,
+    /// The size in octets of the response body as received from the network, before
+    /// removing any content coding.
+    pub encoded_body_size: u64,
+    /// The size in octets of the response body after removing any content coding.
+    pub decoded_body_size: u64,
+    /// The metrics sent by the server in the `Server-Timing` headers of the response.
+    pub server_timing: Vec<ServerTimingMetric>,
 }
 
+/// A metric sent by the server in a `Server-Timing` header.
+/// <https://w3c.github.io/server-timing/#the-server-timing-header-field>
+#[derive(Clone, Debug, Deserialize, MallocSizeOf, PartialEq, Serialize)]
+pub struct ServerTimingMetric {
+    pub name: String,
+    /// The duration of the metric in milliseconds.
+    pub duration: f64,
+    pub description: String,
+}
+
+/// The size of the headers of a response, which is included in the transfer size of the
+/// responses fetched from the network.
+/// <https://w3c.github.io/resource-timing/#dom-performanceresourcetiming-transfersize>
+const RESPONSE_HEADERS_TRANSFER_SIZE: u64 = 300;
+
 pub enum RedirectStartValue {
     #[allow(dead_code)]
     Zero,
@@ -768,6 +926,7 @@ pub enum ResourceTimeValue {
 pub enum ResourceAttribute {
     RedirectCount(u16),
     DomainLookupStart,
+    DomainLookupEnd,
     RequestStart,
     ResponseStart,
     RedirectStart(RedirectStartValue),
@@ -778,6 +937,14 @@ pub enum ResourceAttribute {
     SecureConnectionStart,
     ResponseEnd,
     StartTime(ResourceTimeValue),
+    NextHopProtocol(String),
+    /// The size of the body of a response fetched from the network, before and after
+    /// removing any content coding.
+    BodySize {
+        encoded: u64,
+        decoded: u64,
+    },
+    ServerTiming(Vec<ServerTimingMetric>),
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, MallocSizeOf, PartialEq, Serialize)]
@@ -794,6 +961,7 @@ impl ResourceFetchTiming {
             timing_type,
             timing_check_passed: true,
             domain_lookup_start: None,
+            domain_lookup_end: None,
             redirect_count: 0,
             secure_connection_start: None,
             request_start: None,
@@ -805,6 +973,11 @@ impl ResourceFetchTiming {
             connect_end: None,
             response_end: None,
             start_time: None,
+            next_hop_protocol: String::new(),
+            transfer_size: 0,
+            encoded_body_size: 0,
+            decoded_body_size: 0,
+            server_timing: Vec::new(),
         }
     }
 
@@ -822,7 +995,11 @@ impl ResourceFetchTiming {
         }
         let now = Some(CrossProcessInstant::now());
         match attribute {
-            ResourceAttribute::DomainLookupStart => self.domain_lookup_start = now,
+            // Host names are resolved by the HTTP client while it connects, where the lookup
+            // can't be timed, so it is reported at the start of the fetch, as for persistent
+            // connections.
+            ResourceAttribute::DomainLookupStart => self.domain_lookup_start = self.fetch_start,
+            ResourceAttribute::DomainLookupEnd => self.domain_lookup_end = self.fetch_start,
             ResourceAttribute::RedirectCount(count) => self.redirect_count = count,
             ResourceAttribute::RequestStart => self.request_start = now,
             ResourceAttribute::ResponseStart => self.response_start = now,
@@ -848,6 +1025,13 @@ impl ResourceFetchTiming {
                     if self.redirect_start.is_none() || !self.timing_check_passed => {},
                 _ => self.start_time = self.get_time_value(val),
             },
+            ResourceAttribute::NextHopProtocol(protocol) => self.next_hop_protocol = protocol,
+            ResourceAttribute::BodySize { encoded, decoded } => {
+                self.transfer_size = encoded + RESPONSE_HEADERS_TRANSFER_SIZE;
+                self.encoded_body_size = encoded;
+                self.decoded_body_size = decoded;
+            },
+            ResourceAttribute::ServerTiming(metrics) => self.server_timing = metrics,
         }
     }
 
@@ -863,12 +1047,15 @@ impl ResourceFetchTiming {
     pub fn mark_timing_check_failed(&mut self) {
         self.timing_check_passed = false;
         self.domain_lookup_start = None;
+        self.domain_lookup_end = None;
         self.redirect_count = 0;
         self.request_start = None;
         self.response_start = None;
         self.redirect_start = None;
         self.connect_start = None;
         self.connect_end = None;
+        self.next_hop_protocol.clear();
+        self.server_timing.clear();
     }
 }
 
@@ -973,6 +1160,34 @@ pub enum CookieSource {
     NonHTTP,
 }
 
+/// A message sent from the resource thread to a `CookieStore`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CookieAsyncResponse {
+    pub data: CookieData,
+}
+
+/// The result of an asynchronous cookie operation, or a change notification.
+///
+/// Results are sent in the order in which the operations were requested.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum CookieData {
+    /// Cookies visible to the store were changed or deleted.
+    Change(CookieChange),
+    /// The result of `CoreResourceMsg::GetCookieDataForUrlAsync`.
+    Get(Option<Serde<Cookie<'static>>>),
+    /// The result of `CoreResourceMsg::GetAllCookieDataForUrlAsync`.
+    GetAll(Vec<Serde<Cookie<'static>>>),
+    /// The result of `CoreResourceMsg::SetCookieForUrlAsync`: whether the cookie was stored.
+    Set(bool),
+}
+
+/// A set of cookie changes observed by a `CookieStore`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct CookieChange {
+    pub changed: Vec<Serde<Cookie<'static>>>,
+    pub deleted: Vec<Serde<Cookie<'static>>>,
+}
+
 /// Network errors that have to be exported out of the loaders
 #[derive(Clone, Debug, Deserialize, Eq, MallocSizeOf, PartialEq, Serialize)]
 pub enum NetworkError {
@@ -983,6 +1198,9 @@ pub enum NetworkError {
     SslValidation(String, Vec<u8>),
     /// Crash error, to be converted to Resource::Crash in the HTML parser.
     Crash(String),
+    /// The response did not match the integrity metadata of the request.
+    /// <https://w3c.github.io/webappsec-subresource-integrity/#does-response-match-metadatalist>
+    SubresourceIntegrity,
 }
 
 impl NetworkError {
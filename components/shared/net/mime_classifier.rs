@@ -188,6 +188,20 @@ impl MimeClassifier {
         }
     }
 
+    /// Sniffs `data` with the rules for sniffing images and audio or video, returning the
+    /// computed MIME type if it is one of those.
+    pub fn sniff_media_type(&self, data: &[u8]) -> Option<Mime> {
+        self.image_classifier
+            .classify(data)
+            .or_else(|| self.audio_video_classifier.classify(data))
+    }
+
+    /// Whether `data` is identified as an HTML, XML or PDF document by the rules for
+    /// identifying an unknown MIME type.
+    pub fn sniffs_as_markup_document(&self, data: &[u8]) -> bool {
+        self.scriptable_classifier.classify(data).is_some()
+    }
+
     pub fn validate(&self) -> Result<(), String> {
         self.image_classifier.validate()?;
         self.audio_video_classifier.validate()?;
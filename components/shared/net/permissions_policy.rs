@@ -0,0 +1,377 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! An implementation of the [Permissions Policy] specification, which lets documents and
+//! their embedders control which powerful features are available to them.
+//!
+//! [Permissions Policy]: https://w3c.github.io/webappsec-permissions-policy/
+
+use std::collections::HashMap;
+
+use http::HeaderMap;
+use malloc_size_of_derive::MallocSizeOf;
+use serde::{Deserialize, Serialize};
+use servo_url::{ImmutableOrigin, ServoUrl};
+
+/// The features supported by Servo whose use is controlled by a permissions policy. The
+/// default allowlist of all of them is `'self'`.
+/// <https://w3c.github.io/webappsec-permissions-policy/#policy-controlled-feature>
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, MallocSizeOf, PartialEq, Serialize)]
+pub enum PolicyControlledFeature {
+    /// <https://html.spec.whatwg.org/multipage/#autoplay-feature>
+    Autoplay,
+    /// <https://w3c.github.io/mediacapture-main/#dfn-camera>
+    Camera,
+    /// <https://fullscreen.spec.whatwg.org/#permissions-policy-integration>
+    Fullscreen,
+    /// <https://w3c.github.io/geolocation/#permissions-policy>
+    Geolocation,
+    /// <https://w3c.github.io/mediacapture-main/#dfn-microphone>
+    Microphone,
+}
+
+impl PolicyControlledFeature {
+    const ALL: [Self; 5] = [
+        Self::Autoplay,
+        Self::Camera,
+        Self::Fullscreen,
+        Self::Geolocation,
+        Self::Microphone,
+    ];
+
+    fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "autoplay" => Some(Self::Autoplay),
+            "camera" => Some(Self::Camera),
+            "fullscreen" => Some(Self::Fullscreen),
+            "geolocation" => Some(Self::Geolocation),
+            "microphone" => Some(Self::Microphone),
+            _ => None,
+        }
+    }
+}
+
+/// <https://w3c.github.io/webappsec-permissions-policy/#allowlists>
+#[derive(Clone, Debug, Deserialize, MallocSizeOf, PartialEq, Serialize)]
+pub enum Allowlist {
+    /// The special value `*`, which matches every origin.
+    All,
+    /// The origins matched by this allowlist.
+    Origins(Vec<ImmutableOrigin>),
+}
+
+impl Allowlist {
+    /// <https://w3c.github.io/webappsec-permissions-policy/#matches>
+    pub fn matches(&self, origin: &ImmutableOrigin) -> bool {
+        match self {
+            Allowlist::All => true,
+            Allowlist::Origins(origins) => origins.contains(origin),
+        }
+    }
+
+    /// The allowlist matching the origins that are matched by both this allowlist and `other`.
+    fn intersection(&self, other: &Allowlist) -> Allowlist {
+        match (self, other) {
+            (Allowlist::All, allowlist) | (allowlist, Allowlist::All) => allowlist.clone(),
+            (Allowlist::Origins(origins), allowlist) => Allowlist::Origins(
+                origins
+                    .iter()
+                    .filter(|origin| allowlist.matches(origin))
+                    .cloned()
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// <https://w3c.github.io/webappsec-permissions-policy/#policy-directive>
+pub type PolicyDirective = HashMap<PolicyControlledFeature, Allowlist>;
+
+/// <https://w3c.github.io/webappsec-permissions-policy/#algo-parse-policy-directive>
+///
+/// This parses the value of the `allow` attribute of a container, where `'self'` stands for
+/// `container_origin` and `'src'` for `target_origin`.
+pub fn parse_policy_directive(
+    value: &str,
+    container_origin: &ImmutableOrigin,
+    target_origin: &ImmutableOrigin,
+) -> PolicyDirective {
+    // Let directive be an empty ordered map.
+    let mut directive = PolicyDirective::new();
+
+    // For each serialized-declaration of value, strictly split on ";":
+    for declaration in value.split(';') {
+        // Let tokens be the result of splitting serialized-declaration on ASCII
+        // whitespace.
+        let mut tokens = declaration.split_ascii_whitespace();
+
+        // If tokens is an empty list, then continue.
+        // Let feature-name be the first element of tokens.
+        // If feature-name does not identify any recognized policy-controlled
+        // feature, then continue.
+        let Some(feature) = tokens.next().and_then(PolicyControlledFeature::from_token) else {
+            continue;
+        };
+
+        // Let targetlist be the remaining elements, if any, of tokens.
+        // If targetlist is empty and target origin is given, append the string
+        // "'src'" to targetlist.
+        let targetlist: Vec<&str> = tokens.collect();
+        let targetlist = if targetlist.is_empty() {
+            vec!["'src'"]
+        } else {
+            targetlist
+        };
+
+        // Let allowlist be a new allowlist.
+        let mut origins = Vec::new();
+        let mut allowlist = None;
+
+        // For each element in targetlist:
+        for element in targetlist {
+            // If element is an ASCII case-insensitive match for "*", set allowlist
+            // to the special value * and break.
+            if element == "*" {
+                allowlist = Some(Allowlist::All);
+                break;
+            }
+            if element.eq_ignore_ascii_case("'self'") {
+                // If element is an ASCII case-insensitive match for "'self'", let
+                // result be container origin.
+                origins.push(container_origin.clone());
+            } else if element.eq_ignore_ascii_case("'src'") {
+                // If element is an ASCII case-insensitive match for "'src'", let
+                // result be target origin.
+                origins.push(target_origin.clone());
+            } else if let Ok(url) = ServoUrl::parse(element) {
+                // Otherwise, let result be the result of executing the URL parser on
+                // element. If result is not failure, set result to its origin.
+                // If result is an opaque origin, continue.
+                let origin = url.origin();
+                if origin.is_tuple() {
+                    origins.push(origin);
+                }
+            }
+        }
+
+        // Set directive[feature] to allowlist.
+        directive
+            .entry(feature)
+            .or_insert_with(|| allowlist.unwrap_or(Allowlist::Origins(origins)));
+    }
+
+    // Return directive.
+    directive
+}
+
+/// <https://w3c.github.io/webappsec-permissions-policy/#process-response-policy>
+///
+/// The `Permissions-Policy` header is a structured field dictionary, whose members map a
+/// feature to an allowlist: `*`, `self`, a quoted URL, or an inner list of those.
+fn process_response_policy(
+    headers: Option<&HeaderMap>,
+    origin: &ImmutableOrigin,
+) -> PolicyDirective {
+    let mut directive = PolicyDirective::new();
+    let Some(headers) = headers else {
+        return directive;
+    };
+
+    let members = headers
+        .get_all("permissions-policy")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','));
+    for member in members {
+        let Some((key, value)) = member.split_once('=') else {
+            continue;
+        };
+        let Some(feature) = PolicyControlledFeature::from_token(key.trim()) else {
+            continue;
+        };
+
+        // Any parameters of the member are ignored.
+        let value = value.trim();
+        let items: Vec<&str> = match value.strip_prefix('(') {
+            Some(inner_list) => match inner_list.split_once(')') {
+                Some((items, _)) => items.split_ascii_whitespace().collect(),
+                None => continue,
+            },
+            None => value.split(';').take(1).map(str::trim).collect(),
+        };
+
+        let mut origins = Vec::new();
+        let mut allowlist = None;
+        for item in items {
+            match item {
+                "*" => {
+                    allowlist = Some(Allowlist::All);
+                    break;
+                },
+                "self" => origins.push(origin.clone()),
+                _ => {
+                    let Some(url) = item
+                        .strip_prefix('"')
+                        .and_then(|item| item.strip_suffix('"'))
+                        .and_then(|url| ServoUrl::parse(url).ok())
+                    else {
+                        continue;
+                    };
+                    let url_origin = url.origin();
+                    if url_origin.is_tuple() {
+                        origins.push(url_origin);
+                    }
+                },
+            }
+        }
+        directive.insert(feature, allowlist.unwrap_or(Allowlist::Origins(origins)));
+    }
+    directive
+}
+
+/// The origins for which the inherited policy of a child navigable enables each feature,
+/// as defined by its container and the document of its parent before the origin of the
+/// document of the child navigable is known.
+/// <https://w3c.github.io/webappsec-permissions-policy/#define-inherited-policy-in-container>
+#[derive(Clone, Debug, Default, Deserialize, MallocSizeOf, Serialize)]
+pub struct InheritedPolicy(HashMap<PolicyControlledFeature, Allowlist>);
+
+impl InheritedPolicy {
+    fn enables(&self, feature: PolicyControlledFeature, origin: &ImmutableOrigin) -> bool {
+        self.0
+            .get(&feature)
+            .is_none_or(|allowlist| allowlist.matches(origin))
+    }
+}
+
+/// <https://w3c.github.io/webappsec-permissions-policy/#permissions-policy>
+#[derive(Clone, Debug, Default, Deserialize, MallocSizeOf, Serialize)]
+pub struct PermissionsPolicy {
+    /// The features that are disabled by the inherited policy of this policy.
+    /// <https://w3c.github.io/webappsec-permissions-policy/#inherited-policy>
+    disabled_features: Vec<PolicyControlledFeature>,
+    /// <https://w3c.github.io/webappsec-permissions-policy/#declared-policy>
+    declared_policy: PolicyDirective,
+}
+
+impl PermissionsPolicy {
+    /// <https://w3c.github.io/webappsec-permissions-policy/#create-from-response>
+    ///
+    /// `inherited_policy` is that of the navigable being loaded, which is `None` for a
+    /// top-level navigable.
+    pub fn create_from_response(
+        inherited_policy: Option<&InheritedPolicy>,
+        origin: &ImmutableOrigin,
+        headers: Option<&HeaderMap>,
+    ) -> Self {
+        // Let policy be a new policy, with an empty declared policy.
+        // For each feature supported, set the inherited policy of feature to the
+        // result of defining an inherited policy for feature in navigable at origin.
+        let disabled_features = PolicyControlledFeature::ALL
+            .into_iter()
+            .filter(|feature| {
+                inherited_policy.is_some_and(|inherited| !inherited.enables(*feature, origin))
+            })
+            .collect::<Vec<_>>();
+
+        // Let d be the result of processing response policy given response and
+        // origin.
+        // For each feature → allowlist of d, if the inherited policy of feature is
+        // "Enabled", set the declared policy of feature to allowlist.
+        let declared_policy = process_response_policy(headers, origin)
+            .into_iter()
+            .filter(|(feature, _)| !disabled_features.contains(feature))
+            .collect();
+
+        // Return policy.
+        PermissionsPolicy {
+            disabled_features,
+            declared_policy,
+        }
+    }
+
+    /// <https://w3c.github.io/webappsec-permissions-policy/#is-feature-enabled>
+    ///
+    /// `document_origin` is the origin of the document that has this policy.
+    pub fn is_feature_enabled_for_origin(
+        &self,
+        feature: PolicyControlledFeature,
+        document_origin: &ImmutableOrigin,
+        origin: &ImmutableOrigin,
+    ) -> bool {
+        self.allowlist_for_feature(feature, document_origin)
+            .matches(origin)
+    }
+
+    /// The allowlist matching the origins for which `feature` is enabled in the document that
+    /// has this policy.
+    fn allowlist_for_feature(
+        &self,
+        feature: PolicyControlledFeature,
+        document_origin: &ImmutableOrigin,
+    ) -> Allowlist {
+        // If policy's inherited policy for feature is "Disabled", return "Disabled".
+        if self.disabled_features.contains(&feature) {
+            return Allowlist::Origins(Vec::new());
+        }
+
+        // If feature is present in policy's declared policy: if the allowlist for
+        // feature in policy's declared policy matches origin, then return "Enabled", and
+        // otherwise return "Disabled".
+        if let Some(allowlist) = self.declared_policy.get(&feature) {
+            return allowlist.clone();
+        }
+
+        // If feature's default allowlist is 'self', and origin is same origin with
+        // document's origin, return "Enabled".
+        // Return "Disabled".
+        Allowlist::Origins(vec![document_origin.clone()])
+    }
+
+    /// <https://w3c.github.io/webappsec-permissions-policy/#define-inherited-policy-in-container>
+    ///
+    /// Define the inherited policy of a child navigable whose container has the given
+    /// container policy, and whose node document has this policy and `document_origin`.
+    pub fn inherited_policy_for_child(
+        &self,
+        document_origin: &ImmutableOrigin,
+        container_policy: &PolicyDirective,
+    ) -> InheritedPolicy {
+        InheritedPolicy(
+            PolicyControlledFeature::ALL
+                .into_iter()
+                .map(|feature| {
+                    // The feature is disabled in the child navigable if it is disabled in
+                    // the parent document for its own origin.
+                    if !self.is_feature_enabled_for_origin(
+                        feature,
+                        document_origin,
+                        document_origin,
+                    ) {
+                        return (feature, Allowlist::Origins(Vec::new()));
+                    }
+
+                    // Otherwise, it is only enabled for the origins that both the declared
+                    // policy of the parent document, if it declares the feature, and the
+                    // container policy match, falling back to the default allowlist
+                    // (`'self'`, that is the origin of the parent document) if the container
+                    // policy does not declare the feature.
+                    let declared_allowlist = self
+                        .declared_policy
+                        .get(&feature)
+                        .cloned()
+                        .unwrap_or(Allowlist::All);
+                    let container_allowlist = container_policy
+                        .get(&feature)
+                        .cloned()
+                        .unwrap_or_else(|| Allowlist::Origins(vec![document_origin.clone()]));
+                    (
+                        feature,
+                        declared_allowlist.intersection(&container_allowlist),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
@@ -3,6 +3,7 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 use content_security_policy::CspList;
+use http::HeaderMap;
 use malloc_size_of_derive::MallocSizeOf;
 use serde::{Deserialize, Serialize};
 
@@ -33,8 +34,8 @@ pub struct PolicyContainer {
     pub csp_list: Option<CspList>,
     /// <https://html.spec.whatwg.org/multipage/#policy-container-referrer-policy>
     referrer_policy: ReferrerPolicy,
-    // https://html.spec.whatwg.org/multipage/#policy-container-embedder-policy
-    // TODO: Embedder Policy
+    /// <https://html.spec.whatwg.org/multipage/#policy-container-embedder-policy>
+    embedder_policy: EmbedderPolicy,
 }
 
 impl PolicyContainer {
@@ -46,6 +47,10 @@ impl PolicyContainer {
         self.referrer_policy = referrer_policy;
     }
 
+    pub fn set_embedder_policy(&mut self, embedder_policy: EmbedderPolicy) {
+        self.embedder_policy = embedder_policy;
+    }
+
     pub fn get_referrer_policy(&self) -> ReferrerPolicy {
         // https://w3c.github.io/webappsec-referrer-policy/#referrer-policy-empty-string
         if self.referrer_policy == ReferrerPolicy::EmptyString {
@@ -54,4 +59,109 @@ impl PolicyContainer {
 
         self.referrer_policy
     }
+
+    pub fn get_embedder_policy(&self) -> EmbedderPolicy {
+        self.embedder_policy
+    }
+}
+
+/// <https://html.spec.whatwg.org/multipage/#embedder-policy-value-2>
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, MallocSizeOf, PartialEq, Serialize)]
+pub enum EmbedderPolicyValue {
+    #[default]
+    UnsafeNone,
+    RequireCorp,
+    Credentialless,
+}
+
+impl EmbedderPolicyValue {
+    /// <https://html.spec.whatwg.org/multipage/#compatible-with-cross-origin-isolation>
+    pub fn is_compatible_with_cross_origin_isolation(&self) -> bool {
+        matches!(self, Self::RequireCorp | Self::Credentialless)
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        // The header is a structured field item whose bare token is the policy value; any
+        // parameters (such as `report-to`) are ignored here.
+        let token = value.split(';').next()?.trim();
+        match token {
+            "unsafe-none" => Some(Self::UnsafeNone),
+            "require-corp" => Some(Self::RequireCorp),
+            "credentialless" => Some(Self::Credentialless),
+            _ => None,
+        }
+    }
+}
+
+/// <https://html.spec.whatwg.org/multipage/#embedder-policy>
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, MallocSizeOf, PartialEq, Serialize)]
+pub struct EmbedderPolicy {
+    /// <https://html.spec.whatwg.org/multipage/#embedder-policy-value>
+    pub value: EmbedderPolicyValue,
+    /// <https://html.spec.whatwg.org/multipage/#embedder-policy-report-only-value>
+    pub report_only_value: EmbedderPolicyValue,
+}
+
+impl EmbedderPolicy {
+    /// <https://html.spec.whatwg.org/multipage/#obtain-an-embedder-policy>
+    pub fn parse_from_headers(headers: Option<&HeaderMap>) -> Self {
+        // Step 1. Let policy be a new embedder policy.
+        let mut policy = EmbedderPolicy::default();
+        let Some(headers) = headers else {
+            return policy;
+        };
+
+        let parse_header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(EmbedderPolicyValue::parse)
+        };
+
+        // Step 3. Let parsedItem be the result of getting a structured field value with
+        // `Cross-Origin-Embedder-Policy` and "item" from response's header list.
+        if let Some(value) = parse_header("cross-origin-embedder-policy") {
+            policy.value = value;
+        }
+
+        // Step 4. Set parsedItem to the result of getting a structured field value with
+        // `Cross-Origin-Embedder-Policy-Report-Only` and "item" from response's header list.
+        if let Some(value) = parse_header("cross-origin-embedder-policy-report-only") {
+            policy.report_only_value = value;
+        }
+
+        // Step 5. Return policy.
+        policy
+    }
+}
+
+/// <https://html.spec.whatwg.org/multipage/#cross-origin-opener-policy-value>
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, MallocSizeOf, PartialEq, Serialize)]
+pub enum OpenerPolicyValue {
+    #[default]
+    UnsafeNone,
+    SameOriginAllowPopups,
+    SameOrigin,
+    NoopenerAllowPopups,
+}
+
+impl OpenerPolicyValue {
+    /// <https://html.spec.whatwg.org/multipage/#obtain-coop>
+    ///
+    /// Only the enforced `Cross-Origin-Opener-Policy` header is considered; reporting is not
+    /// supported yet.
+    pub fn parse_from_headers(headers: Option<&HeaderMap>) -> Self {
+        let Some(value) = headers
+            .and_then(|headers| headers.get("cross-origin-opener-policy"))
+            .and_then(|value| value.to_str().ok())
+        else {
+            return Self::UnsafeNone;
+        };
+        match value.split(';').next().map(str::trim) {
+            Some("same-origin-allow-popups") => Self::SameOriginAllowPopups,
+            Some("same-origin") => Self::SameOrigin,
+            Some("noopener-allow-popups") => Self::NoopenerAllowPopups,
+            _ => Self::UnsafeNone,
+        }
+    }
 }
@@ -15,6 +15,7 @@
 //! those cases are not present.
 
 use std::collections::HashSet;
+use std::fmt;
 use std::iter::FromIterator;
 use std::sync::LazyLock;
 
@@ -152,3 +153,41 @@ pub fn reg_host(url: &ServoUrl) -> Option<Host> {
         ImmutableOrigin::Opaque(_) => None,
     }
 }
+
+/// A [site](https://html.spec.whatwg.org/multipage/#site): the scheme of an origin along with
+/// the registered domain name of its host, or the host itself if it is an IP address.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Site {
+    scheme: String,
+    host: Host,
+}
+
+impl fmt::Display for Site {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}://{}", self.scheme, self.host)
+    }
+}
+
+/// The site of the origin of a URL.
+/// Returns None if the origin of the URL is opaque.
+/// <https://html.spec.whatwg.org/multipage/#obtain-a-site>
+pub fn reg_site(url: &ServoUrl) -> Option<Site> {
+    origin_site(&url.origin())
+}
+
+/// The site of an origin.
+/// Returns None if the origin is opaque.
+/// <https://html.spec.whatwg.org/multipage/#obtain-a-site>
+pub fn origin_site(origin: &ImmutableOrigin) -> Option<Site> {
+    match origin {
+        ImmutableOrigin::Tuple(scheme, Host::Domain(domain), _) => Some(Site {
+            scheme: scheme.clone(),
+            host: Host::Domain(String::from(reg_suffix(domain))),
+        }),
+        ImmutableOrigin::Tuple(scheme, ip, _) => Some(Site {
+            scheme: scheme.clone(),
+            host: ip.clone(),
+        }),
+        ImmutableOrigin::Opaque(_) => None,
+    }
+}
@@ -115,6 +115,91 @@ pub enum RedirectMode {
     Manual,
 }
 
+/// Request [priority](https://fetch.spec.whatwg.org/#request-priority)
+#[derive(Clone, Copy, Debug, Default, Deserialize, MallocSizeOf, PartialEq, Serialize)]
+pub enum RequestPriority {
+    High,
+    Low,
+    #[default]
+    Auto,
+}
+
+impl RequestPriority {
+    /// The state of a [fetch priority attribute](https://html.spec.whatwg.org/multipage/#fetch-priority-attribute)
+    /// with the given value. Both the missing and the invalid value defaults are "auto".
+    pub fn from_fetch_priority_attribute(value: Option<&str>) -> RequestPriority {
+        match value {
+            Some(value) if value.eq_ignore_ascii_case("high") => RequestPriority::High,
+            Some(value) if value.eq_ignore_ascii_case("low") => RequestPriority::Low,
+            _ => RequestPriority::Auto,
+        }
+    }
+}
+
+/// Request [internal priority](https://fetch.spec.whatwg.org/#request-internal-priority).
+/// Servo uses a small set of ordered buckets, where more urgent requests compare greater.
+#[derive(
+    Clone, Copy, Debug, Deserialize, Eq, MallocSizeOf, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub enum InternalPriority {
+    Lowest,
+    Low,
+    Medium,
+    High,
+    Highest,
+}
+
+impl InternalPriority {
+    /// Derive an internal priority from a request's priority, initiator and destination,
+    /// as allowed by step 15 of <https://fetch.spec.whatwg.org/#concept-fetch>.
+    pub fn for_request(
+        priority: RequestPriority,
+        initiator: Initiator,
+        destination: Destination,
+    ) -> InternalPriority {
+        if initiator == Initiator::Prefetch {
+            return InternalPriority::Lowest;
+        }
+
+        let default = match destination {
+            Destination::Document |
+            Destination::IFrame |
+            Destination::Frame |
+            Destination::Style |
+            Destination::Font => InternalPriority::Highest,
+            Destination::Script | Destination::Json | Destination::None => InternalPriority::High,
+            Destination::Image | Destination::Audio | Destination::Video | Destination::Track => {
+                InternalPriority::Low
+            },
+            _ => InternalPriority::Medium,
+        };
+
+        match priority {
+            RequestPriority::High => default.raised(),
+            RequestPriority::Low => default.lowered(),
+            RequestPriority::Auto => default,
+        }
+    }
+
+    fn raised(self) -> InternalPriority {
+        match self {
+            InternalPriority::Lowest => InternalPriority::Low,
+            InternalPriority::Low => InternalPriority::Medium,
+            InternalPriority::Medium => InternalPriority::High,
+            InternalPriority::High | InternalPriority::Highest => InternalPriority::Highest,
+        }
+    }
+
+    fn lowered(self) -> InternalPriority {
+        match self {
+            InternalPriority::Lowest | InternalPriority::Low => InternalPriority::Lowest,
+            InternalPriority::Medium => InternalPriority::Low,
+            InternalPriority::High => InternalPriority::Medium,
+            InternalPriority::Highest => InternalPriority::High,
+        }
+    }
+}
+
 /// [Response tainting](https://fetch.spec.whatwg.org/#concept-request-response-tainting)
 #[derive(Clone, Copy, Debug, Deserialize, MallocSizeOf, PartialEq, Serialize)]
 pub enum ResponseTainting {
@@ -274,6 +359,11 @@ pub struct RequestBuilder {
     // TODO: client object
     /// <https://fetch.spec.whatwg.org/#concept-request-destination>
     pub destination: Destination,
+
+    /// <https://fetch.spec.whatwg.org/#request-priority>
+    pub priority: RequestPriority,
+    /// <https://fetch.spec.whatwg.org/#request-internal-priority>
+    pub internal_priority: Option<InternalPriority>,
     pub synchronous: bool,
     pub mode: RequestMode,
 
@@ -294,6 +384,11 @@ pub struct RequestBuilder {
     pub policy_container: RequestPolicyContainer,
     pub insecure_requests_policy: InsecureRequestsPolicy,
     pub has_trustworthy_ancestor_origin: bool,
+    /// The origin of the request's client followed by the origins of the ancestors of its
+    /// navigable, or for a nested navigation request the origins of the ancestors of the
+    /// navigable being navigated. Used to determine the site for cookies and the partition of
+    /// partitioned cookies.
+    pub ancestor_origins: Vec<ImmutableOrigin>,
 
     /// <https://fetch.spec.whatwg.org/#concept-request-referrer>
     pub referrer: Referrer,
@@ -337,6 +432,8 @@ impl RequestBuilder {
             body: None,
             service_workers_mode: ServiceWorkersMode::All,
             destination: Destination::None,
+            priority: RequestPriority::Auto,
+            internal_priority: None,
             synchronous: false,
             mode: RequestMode::NoCors,
             cache_mode: CacheMode::Default,
@@ -347,6 +444,7 @@ impl RequestBuilder {
             policy_container: RequestPolicyContainer::default(),
             insecure_requests_policy: InsecureRequestsPolicy::DoNotUpgrade,
             has_trustworthy_ancestor_origin: false,
+            ancestor_origins: Vec::new(),
             referrer,
             referrer_policy: ReferrerPolicy::EmptyString,
             pipeline_id: None,
@@ -399,6 +497,12 @@ impl RequestBuilder {
         self
     }
 
+    /// <https://fetch.spec.whatwg.org/#request-priority>
+    pub fn priority(mut self, priority: RequestPriority) -> RequestBuilder {
+        self.priority = priority;
+        self
+    }
+
     pub fn synchronous(mut self, synchronous: bool) -> RequestBuilder {
         self.synchronous = synchronous;
         self
@@ -504,6 +608,11 @@ impl RequestBuilder {
         self
     }
 
+    pub fn ancestor_origins(mut self, ancestor_origins: Vec<ImmutableOrigin>) -> RequestBuilder {
+        self.ancestor_origins = ancestor_origins;
+        self
+    }
+
     /// <https://fetch.spec.whatwg.org/#request-service-workers-mode>
     pub fn service_workers_mode(
         mut self,
@@ -536,6 +645,8 @@ impl RequestBuilder {
         request.body = self.body;
         request.service_workers_mode = self.service_workers_mode;
         request.destination = self.destination;
+        request.priority = self.priority;
+        request.internal_priority = self.internal_priority;
         request.synchronous = self.synchronous;
         request.mode = self.mode;
         request.use_cors_preflight = self.use_cors_preflight;
@@ -558,6 +669,7 @@ impl RequestBuilder {
         request.policy_container = self.policy_container;
         request.insecure_requests_policy = self.insecure_requests_policy;
         request.has_trustworthy_ancestor_origin = self.has_trustworthy_ancestor_origin;
+        request.ancestor_origins = self.ancestor_origins;
         request
     }
 }
@@ -593,7 +705,10 @@ pub struct Request {
     pub initiator: Initiator,
     /// <https://fetch.spec.whatwg.org/#concept-request-destination>
     pub destination: Destination,
-    // TODO: priority object
+    /// <https://fetch.spec.whatwg.org/#request-priority>
+    pub priority: RequestPriority,
+    /// <https://fetch.spec.whatwg.org/#request-internal-priority>
+    pub internal_priority: Option<InternalPriority>,
     /// <https://fetch.spec.whatwg.org/#concept-request-origin>
     pub origin: Origin,
     /// <https://fetch.spec.whatwg.org/#concept-request-referrer>
@@ -634,6 +749,11 @@ pub struct Request {
     /// <https://w3c.github.io/webappsec-upgrade-insecure-requests/#insecure-requests-policy>
     pub insecure_requests_policy: InsecureRequestsPolicy,
     pub has_trustworthy_ancestor_origin: bool,
+    /// The origin of the request's client followed by the origins of the ancestors of its
+    /// navigable, or for a nested navigation request the origins of the ancestors of the
+    /// navigable being navigated. Used to determine the site for cookies and the partition of
+    /// partitioned cookies.
+    pub ancestor_origins: Vec<ImmutableOrigin>,
     pub https_state: HttpsState,
     /// Servo internal: if crash details are present, trigger a crash error page with these details.
     pub crash: Option<String>,
@@ -661,6 +781,8 @@ impl Request {
             service_workers_mode: ServiceWorkersMode::All,
             initiator: Initiator::None,
             destination: Destination::None,
+            priority: RequestPriority::Auto,
+            internal_priority: None,
             origin: origin.unwrap_or(Origin::Client),
             referrer,
             referrer_policy: ReferrerPolicy::EmptyString,
@@ -682,6 +804,7 @@ impl Request {
             policy_container: RequestPolicyContainer::Client,
             insecure_requests_policy: InsecureRequestsPolicy::DoNotUpgrade,
             has_trustworthy_ancestor_origin: false,
+            ancestor_origins: Vec::new(),
             https_state,
             crash: None,
         }
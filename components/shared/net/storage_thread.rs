@@ -3,11 +3,12 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 use base::id::WebViewId;
+use embedder_traits::{AllowOrDeny, PermissionFeature};
 use ipc_channel::ipc::IpcSender;
 use malloc_size_of_derive::MallocSizeOf;
 use profile_traits::mem::ReportsChan;
 use serde::{Deserialize, Serialize};
-use servo_url::ServoUrl;
+use servo_url::{ImmutableOrigin, ServoUrl};
 
 #[derive(Clone, Copy, Debug, Deserialize, MallocSizeOf, Serialize)]
 pub enum StorageType {
@@ -15,6 +16,18 @@ pub enum StorageType {
     Local,
 }
 
+/// A password credential saved in the credential store of an origin.
+/// <https://w3c.github.io/webappsec-credential-management/#passwordcredential>
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StoredPasswordCredential {
+    /// The username of the account.
+    pub id: String,
+    pub password: String,
+    /// A human readable name for the account, shown when choosing a credential.
+    pub name: String,
+    pub icon_url: String,
+}
+
 /// Request operations on the storage data associated with a particular url
 #[derive(Debug, Deserialize, Serialize)]
 pub enum StorageThreadMsg {
@@ -64,6 +77,32 @@ pub enum StorageThreadMsg {
     /// clears the associated storage data by removing all the key/value pairs
     Clear(IpcSender<bool>, StorageType, WebViewId, ServoUrl),
 
+    /// gets the permission decision stored for the given feature at the given origin
+    GetPermission(
+        IpcSender<Option<AllowOrDeny>>,
+        ImmutableOrigin,
+        PermissionFeature,
+    ),
+
+    /// stores the permission decision for the given feature at the given origin,
+    /// or forgets the stored decision if none is given
+    SetPermission(ImmutableOrigin, PermissionFeature, Option<AllowOrDeny>),
+
+    /// gets the password credentials saved for the given origin
+    GetPasswordCredentials(IpcSender<Vec<StoredPasswordCredential>>, ImmutableOrigin),
+
+    /// saves a password credential for the given origin, replacing any saved credential
+    /// with the same id
+    StorePasswordCredential(ImmutableOrigin, StoredPasswordCredential),
+
+    /// gets whether credentials of the given origin may only be handed out after the user
+    /// picked them
+    GetPreventSilentAccess(IpcSender<bool>, ImmutableOrigin),
+
+    /// sets whether credentials of the given origin may only be handed out after the user
+    /// picked them
+    SetPreventSilentAccess(ImmutableOrigin, bool),
+
     /// clones all storage data of the given top-level browsing context for a new browsing context.
     /// should only be used for sessionStorage.
     Clone {
@@ -0,0 +1,49 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use http::header::REFERRER_POLICY;
+use http::{HeaderMap, HeaderValue};
+use net_traits::ReferrerPolicy;
+
+fn parse(values: &[&'static str]) -> ReferrerPolicy {
+    let mut headers = HeaderMap::new();
+    for value in values {
+        headers.append(REFERRER_POLICY, HeaderValue::from_static(value));
+    }
+    ReferrerPolicy::parse_header_for_response(Some(&headers))
+}
+
+#[test]
+fn test_parse_referrer_policy_header_missing() {
+    assert_eq!(
+        ReferrerPolicy::parse_header_for_response(None),
+        ReferrerPolicy::EmptyString
+    );
+    assert_eq!(parse(&[]), ReferrerPolicy::EmptyString);
+}
+
+#[test]
+fn test_parse_referrer_policy_header_single_value() {
+    assert_eq!(parse(&["no-referrer"]), ReferrerPolicy::NoReferrer);
+    assert_eq!(parse(&["unsafe-url"]), ReferrerPolicy::UnsafeUrl);
+    assert_eq!(
+        parse(&["strict-origin-when-cross-origin"]),
+        ReferrerPolicy::StrictOriginWhenCrossOrigin
+    );
+}
+
+#[test]
+fn test_parse_referrer_policy_header_fallback_list() {
+    assert_eq!(
+        parse(&["no-referrer, strict-origin"]),
+        ReferrerPolicy::StrictOrigin
+    );
+    assert_eq!(parse(&["origin", "unsafe-url"]), ReferrerPolicy::UnsafeUrl);
+    // Unknown and legacy tokens do not override an earlier valid token.
+    assert_eq!(
+        parse(&["same-origin, some-future-policy, always"]),
+        ReferrerPolicy::SameOrigin
+    );
+    assert_eq!(parse(&["never, default"]), ReferrerPolicy::EmptyString);
+}
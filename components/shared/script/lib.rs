@@ -14,6 +14,7 @@ use std::fmt;
 use std::sync::Arc;
 
 use background_hang_monitor_api::BackgroundHangMonitorRegister;
+use base::Epoch;
 use base::cross_process_instant::CrossProcessInstant;
 use base::id::{BrowsingContextId, HistoryStateId, PipelineId, PipelineNamespaceId, WebViewId};
 #[cfg(feature = "bluetooth")]
@@ -21,8 +22,8 @@ use bluetooth_traits::BluetoothRequest;
 use canvas_traits::webgl::WebGLPipeline;
 use compositing_traits::CrossProcessCompositorApi;
 use constellation_traits::{
-    LoadData, NavigationHistoryBehavior, ScriptToConstellationChan, StructuredSerializedData,
-    WindowSizeType,
+    LoadData, NavigableHistoryEntry, NavigationHistoryBehavior, ScriptToConstellationChan,
+    StructuredSerializedData, WindowSizeType,
 };
 use crossbeam_channel::{RecvTimeoutError, Sender};
 use devtools_traits::ScriptToDevtoolsControlMsg;
@@ -156,6 +157,15 @@ pub enum ScriptThreadMessage {
     SetDocumentActivity(PipelineId, DocumentActivity),
     /// Set whether to use less resources by running timers at a heavily limited rate.
     SetThrottled(PipelineId, bool),
+    /// Freeze or resume the document of the given pipeline, as its webview is frozen or
+    /// resumed by the embedder.
+    SetFrozen(PipelineId, bool),
+    /// Activate the prerendered document of the given pipeline, which a navigation of its
+    /// top-level browsing context is about to make active.
+    ActivatePrerenderedDocument(PipelineId),
+    /// Make the browsing context of the given top-level pipeline disown its opener, because
+    /// its cross-origin opener policy moved it to a new browsing context group.
+    DisownOpener(PipelineId),
     /// Notify the containing iframe (in PipelineId) that the nested browsing context (BrowsingContextId) is throttled.
     SetThrottledInContainingIframe(PipelineId, BrowsingContextId, bool),
     /// Notifies script thread that a url should be loaded in this iframe.
@@ -191,8 +201,16 @@ pub enum ScriptThreadMessage {
         PipelineId,
         UpdatePipelineIdReason,
     ),
+    /// Replaces the PipelineId of the navigation that is pending in a given iframe, when the
+    /// constellation replaced its load with the load of an error page.
+    /// First PipelineId is for the parent, then the replaced and the new PipelineId for the frame.
+    ReplacePendingPipelineId(PipelineId, BrowsingContextId, PipelineId, PipelineId),
     /// Updates the history state and url of a given pipeline.
     UpdateHistoryState(PipelineId, Option<HistoryStateId>, ServoUrl),
+    /// Updates the session history entries of the browsing context of a given pipeline that
+    /// are contiguous with its current entry and same origin with it, along with the index
+    /// of the current entry.
+    UpdateNavigableHistory(PipelineId, Vec<NavigableHistoryEntry>, usize),
     /// Removes inaccesible history states.
     RemoveHistoryStates(PipelineId, Vec<HistoryStateId>),
     /// Set an iframe to be focused. Used when an element in an iframe gains focus.
@@ -236,13 +254,9 @@ pub enum ScriptThreadMessage {
     ReportCSSError(PipelineId, String, u32, u32, String),
     /// Reload the given page.
     Reload(PipelineId),
-    /// Notifies the script thread about a new recorded paint metric.
-    PaintMetric(
-        PipelineId,
-        ProgressiveWebMetricType,
-        CrossProcessInstant,
-        bool, /* first_reflow */
-    ),
+    /// Notifies the script thread that the display list with the given [`Epoch`] was
+    /// presented at the given time.
+    FramePresented(PipelineId, Epoch, CrossProcessInstant),
     /// Notifies the media session about a user requested media session action.
     MediaSessionAction(PipelineId, MediaSessionActionType),
     /// Notifies script thread that WebGPU server has started
@@ -93,6 +93,11 @@ impl TimerScheduler {
         self.queue.retain(|event| event.id != id);
     }
 
+    /// The time at which the next timer of this [`TimerScheduler`] is due, if any.
+    pub fn next_timer_time(&self) -> Option<Instant> {
+        self.queue.peek().map(|event| event.for_time)
+    }
+
     /// Get a [`Receiver<Instant>`] that receives a message after waiting for the next timer
     /// to fire. If there are no timers, the channel will *never* send a message.
     pub fn wait_channel(&self) -> Receiver<Instant> {
@@ -18,9 +18,9 @@ use servo::ipc_channel::ipc::IpcSender;
 use servo::webrender_api::ScrollLocation;
 use servo::webrender_api::units::{DeviceIntPoint, DeviceIntSize};
 use servo::{
-    AllowOrDenyRequest, AuthenticationRequest, FilterPattern, FocusId, FormControl,
-    GamepadHapticEffectType, KeyboardEvent, LoadStatus, PermissionRequest, Servo, ServoDelegate,
-    ServoError, SimpleDialog, TraversalId, WebDriverCommandMsg, WebDriverJSResult,
+    AllowOrDenyRequest, AuthenticationRequest, AuxiliaryWebViewFeatures, FilterPattern, FocusId,
+    FormControl, GamepadHapticEffectType, KeyboardEvent, LoadStatus, PermissionRequest, Servo,
+    ServoDelegate, ServoError, SimpleDialog, TraversalId, WebDriverCommandMsg, WebDriverJSResult,
     WebDriverJSValue, WebDriverLoadStatus, WebDriverUserPrompt, WebView, WebViewBuilder,
     WebViewDelegate,
 };
@@ -630,7 +630,10 @@ impl WebViewDelegate for RunningAppState {
     fn request_open_auxiliary_webview(
         &self,
         parent_webview: servo::WebView,
+        _features: AuxiliaryWebViewFeatures,
     ) -> Option<servo::WebView> {
+        // servoshell opens every auxiliary `WebView` as a new tab of the same window, so
+        // requests for popup windows and their geometry are not honored.
         let webview = WebViewBuilder::new_auxiliary(&self.servo)
             .hidpi_scale_factor(self.inner().window.hidpi_scale_factor())
             .delegate(parent_webview.delegate())
@@ -94,7 +94,8 @@ impl Dialog {
 
     pub fn new_permission_request_dialog(permission_request: PermissionRequest) -> Self {
         let message = format!(
-            "Do you want to grant permission for {:?}?",
+            "Do you want to grant {} permission for {:?}?",
+            permission_request.url().origin().ascii_serialization(),
             permission_request.feature()
         );
         Dialog::Permission {
@@ -15,12 +15,12 @@ use servo::servo_geometry::DeviceIndependentPixel;
 use servo::webrender_api::ScrollLocation;
 use servo::webrender_api::units::{DeviceIntRect, DeviceIntSize, DevicePixel};
 use servo::{
-    AllowOrDenyRequest, CompositionEvent, CompositionState, ContextMenuResult, ImeEvent,
-    InputEvent, InputMethodType, Key, KeyState, KeyboardEvent, LoadStatus, MediaSessionActionType,
-    MediaSessionEvent, MouseButton, MouseButtonAction, MouseButtonEvent, MouseMoveEvent, NamedKey,
-    NavigationRequest, PermissionRequest, RenderingContext, ScreenGeometry, Servo, ServoDelegate,
-    ServoError, SimpleDialog, TouchEvent, TouchEventType, TouchId, WebView, WebViewBuilder,
-    WebViewDelegate, WindowRenderingContext,
+    AllowOrDenyRequest, AuxiliaryWebViewFeatures, CompositionEvent, CompositionState,
+    ContextMenuResult, ImeEvent, InputEvent, InputMethodType, Key, KeyState, KeyboardEvent,
+    LoadStatus, MediaSessionActionType, MediaSessionEvent, MouseButton, MouseButtonAction,
+    MouseButtonEvent, MouseMoveEvent, NamedKey, NavigationRequest, PermissionRequest,
+    RenderingContext, ScreenGeometry, Servo, ServoDelegate, ServoError, SimpleDialog, TouchEvent,
+    TouchEventType, TouchId, WebView, WebViewBuilder, WebViewDelegate, WindowRenderingContext,
 };
 use url::Url;
 
@@ -237,7 +237,11 @@ impl WebViewDelegate for RunningAppState {
         }
     }
 
-    fn request_open_auxiliary_webview(&self, parent_webview: WebView) -> Option<WebView> {
+    fn request_open_auxiliary_webview(
+        &self,
+        parent_webview: WebView,
+        _features: AuxiliaryWebViewFeatures,
+    ) -> Option<WebView> {
         let webview = WebViewBuilder::new_auxiliary(&self.servo)
             .delegate(parent_webview.delegate())
             .hidpi_scale_factor(self.inner().hidpi_scale_factor)
@@ -334,6 +334,12 @@ pub(crate) fn parse_command_line_arguments(args: Vec<String>) -> ArgumentParsing
         "config directory following xdg spec on linux platform",
         "",
     );
+    opts.optopt(
+        "",
+        "http-disk-cache-size",
+        "Persist the HTTP cache in the config directory, using at most this many megabytes",
+        "256",
+    );
     opts.optflag("v", "version", "Display servo version information");
     opts.optflag("", "unminify-js", "Unminify Javascript");
     opts.optflag("", "print-pwm", "Print Progressive Web Metrics");
@@ -358,6 +364,18 @@ pub(crate) fn parse_command_line_arguments(args: Vec<String>) -> ArgumentParsing
         "Set custom user agent string (or ios / android / desktop for platform default)",
         "NCSA Mosaic/1.0 (X11;SunOS 4.1.4 sun4m)",
     );
+    opts.optopt(
+        "",
+        "proxy-server",
+        "Fetch http and https URLs through this HTTP or SOCKS5 proxy",
+        "socks5://127.0.0.1:1080",
+    );
+    opts.optopt(
+        "",
+        "proxy-bypass-list",
+        "Comma-separated list of hosts to reach without the proxy",
+        "*.example.com,10.0.0.0/8",
+    );
     opts.optmulti(
         "",
         "tracing-filter",
@@ -526,6 +544,16 @@ pub(crate) fn parse_command_line_arguments(args: Vec<String>) -> ArgumentParsing
                 })
             });
 
+    let http_disk_cache_size = opt_match.opt_str("http-disk-cache-size").map(|size| {
+        let megabytes: u64 = size.parse().unwrap_or_else(|err| {
+            args_fail(&format!(
+                "Error parsing option: --http-disk-cache-size ({})",
+                err
+            ))
+        });
+        megabytes * 1024 * 1024
+    });
+
     if opt_match.opt_present("devtools") {
         let port = opt_match
             .opt_str("devtools")
@@ -588,22 +616,49 @@ pub(crate) fn parse_command_line_arguments(args: Vec<String>) -> ArgumentParsing
     if opt_match.opt_present("enable-experimental-web-platform-features") {
         vec![
             "dom_async_clipboard_enabled",
+            "dom_compression_stream_enabled",
+            "dom_credential_management_enabled",
+            "dom_deprecation_reporting_enabled",
+            "dom_drag_and_drop_enabled",
+            "dom_element_timing_enabled",
+            "dom_event_timing_enabled",
             "dom_fontface_enabled",
+            "dom_geolocation_enabled",
             "dom_intersection_observer_enabled",
+            "dom_intervention_reporting_enabled",
+            "dom_largest_contentful_paint_enabled",
+            "dom_layout_instability_enabled",
+            "dom_long_animation_frames_enabled",
+            "dom_long_tasks_enabled",
+            "dom_mathml_enabled",
             "dom_mouse_event_which_enabled",
+            "dom_navigation_api_enabled",
             "dom_navigator_sendbeacon_enabled",
             "dom_notification_enabled",
             "dom_offscreen_canvas_enabled",
+            "dom_page_lifecycle_enabled",
             "dom_permissions_enabled",
+            "dom_popover_enabled",
+            "dom_request_idle_callback_enabled",
             "dom_resize_observer_enabled",
+            "dom_sanitizer_enabled",
+            "dom_scheduler_enabled",
+            "dom_server_timing_enabled",
+            "dom_speculation_rules_enabled",
             "dom_svg_enabled",
+            "dom_text_encoder_stream_enabled",
             "dom_trusted_types_enabled",
+            "dom_view_transitions_enabled",
+            "dom_webauthn_enabled",
             "dom_webgl2_enabled",
             "dom_webgpu_enabled",
             "dom_xpath_enabled",
             "layout_columns_enabled",
             "layout_container_queries_enabled",
+            "layout_css_at_scope_enabled",
+            "layout_css_has_selector_enabled",
             "layout_grid_enabled",
+            "layout_grid_masonry_enabled",
         ]
         .iter()
         .for_each(|pref| preferences.set_value(pref, PrefValue::Bool(true)));
@@ -687,6 +742,14 @@ pub(crate) fn parse_command_line_arguments(args: Vec<String>) -> ArgumentParsing
         preferences.user_agent = user_agent;
     }
 
+    if let Some(proxy) = opt_match.opt_str("proxy-server") {
+        preferences.network_http_proxy_uri = proxy.clone();
+        preferences.network_https_proxy_uri = proxy;
+    }
+    if let Some(bypass_list) = opt_match.opt_str("proxy-bypass-list") {
+        preferences.network_proxy_bypass_list = bypass_list;
+    }
+
     let opts = Opts {
         debug: debug_options.clone(),
         wait_for_stable_image,
@@ -701,6 +764,7 @@ pub(crate) fn parse_command_line_arguments(args: Vec<String>) -> ArgumentParsing
         random_pipeline_closure_probability,
         random_pipeline_closure_seed,
         config_dir,
+        http_disk_cache_size,
         shaders_dir: opt_match.opt_str("shaders").map(Into::into),
         certificate_path: opt_match.opt_str("certificate-path"),
         ignore_certificate_errors: opt_match.opt_present("ignore-certificate-errors"),
@@ -0,0 +1,112 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use script::test::compression::{ChunkCodec, CompressionFormat, Compressor, Decompressor};
+
+/// "hello" compressed by gzip with the file name "a.txt" in its header.
+const HELLO_GZIP_WITH_NAME: [u8; 31] = [
+    0x1f, 0x8b, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0x61, 0x2e, 0x74, 0x78, 0x74, 0x00,
+    0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x07, 0x00, 0x86, 0xa6, 0x10, 0x36, 0x05, 0x00, 0x00, 0x00,
+];
+
+fn compress(format: CompressionFormat, chunks: &[&[u8]]) -> Vec<u8> {
+    let mut compressor = Compressor::new(format);
+    let mut output = vec![];
+    for chunk in chunks {
+        output.extend(compressor.process(chunk).unwrap());
+    }
+    output.extend(compressor.finish().unwrap());
+    output
+}
+
+fn decompress(format: CompressionFormat, chunks: &[&[u8]]) -> Result<Vec<u8>, &'static str> {
+    let mut decompressor = Decompressor::new(format);
+    let mut output = vec![];
+    for chunk in chunks {
+        output.extend(decompressor.process(chunk)?);
+    }
+    output.extend(decompressor.finish()?);
+    Ok(output)
+}
+
+#[test]
+fn round_trip() {
+    let input: Vec<u8> = (0..200_000).map(|index| (index % 251) as u8).collect();
+    for format in [
+        CompressionFormat::Deflate,
+        CompressionFormat::Deflate_raw,
+        CompressionFormat::Gzip,
+    ] {
+        let compressed = compress(format, &[&input[..1000], &input[1000..], &[]]);
+        assert!(compressed.len() < input.len());
+        let chunks: Vec<&[u8]> = compressed.chunks(7).collect();
+        assert_eq!(decompress(format, &chunks), Ok(input.clone()));
+    }
+}
+
+#[test]
+fn gzip_header_and_trailer() {
+    let compressed = compress(CompressionFormat::Gzip, &[b"hello ", b"world"]);
+    assert_eq!(compressed[..10], [0x1f, 0x8b, 0x08, 0, 0, 0, 0, 0, 0, 0xff]);
+    let trailer = &compressed[compressed.len() - 8..];
+    assert_eq!(trailer[..4], 0x0d4a1185_u32.to_le_bytes());
+    assert_eq!(trailer[4..], 11_u32.to_le_bytes());
+}
+
+#[test]
+fn gzip_header_with_optional_fields() {
+    assert_eq!(
+        decompress(CompressionFormat::Gzip, &[&HELLO_GZIP_WITH_NAME]),
+        Ok(b"hello".to_vec())
+    );
+
+    // The header may be split anywhere between chunks.
+    let chunks: Vec<&[u8]> = HELLO_GZIP_WITH_NAME.chunks(1).collect();
+    assert_eq!(
+        decompress(CompressionFormat::Gzip, &chunks),
+        Ok(b"hello".to_vec())
+    );
+}
+
+#[test]
+fn gzip_invalid_header() {
+    let mut input = HELLO_GZIP_WITH_NAME;
+    input[0] = 0;
+    assert!(decompress(CompressionFormat::Gzip, &[&input]).is_err());
+}
+
+#[test]
+fn gzip_checksum_mismatch() {
+    let mut input = HELLO_GZIP_WITH_NAME;
+    input[23] ^= 1;
+    assert!(decompress(CompressionFormat::Gzip, &[&input]).is_err());
+
+    let mut input = HELLO_GZIP_WITH_NAME;
+    input[27] = 6;
+    assert!(decompress(CompressionFormat::Gzip, &[&input]).is_err());
+}
+
+#[test]
+fn gzip_multiple_members() {
+    // Only the first member is decompressed, so anything after it is an error.
+    let input = [HELLO_GZIP_WITH_NAME, HELLO_GZIP_WITH_NAME].concat();
+    assert!(decompress(CompressionFormat::Gzip, &[&input]).is_err());
+    assert!(
+        decompress(
+            CompressionFormat::Gzip,
+            &[&HELLO_GZIP_WITH_NAME, &HELLO_GZIP_WITH_NAME]
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn gzip_truncated() {
+    for length in [5, 15, 20, 23, 30] {
+        assert!(
+            decompress(CompressionFormat::Gzip, &[&HELLO_GZIP_WITH_NAME[..length]]).is_err(),
+            "Truncated to {length} bytes"
+        );
+    }
+}
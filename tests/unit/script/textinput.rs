@@ -612,6 +612,33 @@ fn test_clipboard_paste() {
     assert_eq!(textinput.get_content(), "abcdefg");
 }
 
+#[test]
+fn test_textinput_does_not_insert_characters_typed_with_shortcut_modifiers() {
+    let mut textinput = text_input(Lines::Single, "abc");
+    textinput.handle_keydown_aux(Key::Character("q".to_owned()), Modifiers::CONTROL, false);
+    textinput.handle_keydown_aux(Key::Character("q".to_owned()), Modifiers::META, false);
+    assert_eq!(textinput.get_content(), "abc");
+    textinput.handle_keydown_aux(Key::Character("Q".to_owned()), Modifiers::SHIFT, false);
+    assert_eq!(textinput.get_content(), "Qabc");
+}
+
+#[test]
+fn test_textinput_inserts_characters_typed_with_alt_or_altgr() {
+    let mut textinput = text_input(Lines::Single, "");
+    // Option on macOS and Alt on other systems.
+    textinput.handle_keydown_aux(Key::Character("å".to_owned()), Modifiers::ALT, false);
+    assert_eq!(textinput.get_content(), "å");
+    textinput.handle_keydown_aux(Key::Character("€".to_owned()), Modifiers::ALT_GRAPH, false);
+    assert_eq!(textinput.get_content(), "å€");
+    // Windows reports AltGr as Control and Alt pressed together.
+    textinput.handle_keydown_aux(
+        Key::Character("@".to_owned()),
+        Modifiers::CONTROL | Modifiers::ALT,
+        false,
+    );
+    assert_eq!(textinput.get_content(), "å€@");
+}
+
 #[test]
 fn test_textinput_cursor_position_correct_after_clearing_selection() {
     let mut textinput = text_input(Lines::Single, "abcdef");